@@ -0,0 +1,513 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The `information_schema` of each catalog, whose `tables` and `columns` tables are
+//! built from the live catalog on each scan, for tools introspecting the database.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use common_catalog::consts::INFORMATION_SCHEMA_NAME;
+use common_error::ext::BoxedError;
+use common_query::logical_plan::Expr;
+use common_query::physical_plan::PhysicalPlanRef;
+use common_recordbatch::RecordBatches;
+use common_time::timestamp::TimeUnit;
+use common_time::Timestamp;
+use datafusion::logical_expr::{BinaryExpr, Expr as DfExpr, Operator};
+use datafusion::scalar::ScalarValue;
+use datatypes::prelude::{ConcreteDataType, DataType, MutableVector};
+use datatypes::schema::{ColumnSchema, Schema, SchemaRef};
+use datatypes::value::ValueRef;
+use datatypes::vectors::VectorRef;
+use snafu::ResultExt;
+use table::error::{Result as TableResult, TablesRecordBatchSnafu};
+use table::metadata::{
+    FilterPushDownType, TableInfoBuilder, TableInfoRef, TableMetaBuilder, TableType,
+};
+use table::table::scan::SimpleTableScan;
+use table::{Table, TableRef};
+
+use crate::error::Result;
+use crate::CatalogListRef;
+
+/// Name of the table listing all tables.
+pub const TABLES: &str = "tables";
+/// Name of the table listing columns of all tables.
+pub const COLUMNS: &str = "columns";
+
+const TABLE_CATALOG: &str = "table_catalog";
+const TABLE_SCHEMA: &str = "table_schema";
+const TABLE_NAME: &str = "table_name";
+
+/// Provides the `information_schema` tables of a catalog.
+pub struct InformationSchemaProvider {
+    catalog_name: String,
+    catalog_list: CatalogListRef,
+}
+
+impl InformationSchemaProvider {
+    pub fn new(catalog_name: String, catalog_list: CatalogListRef) -> Self {
+        Self {
+            catalog_name,
+            catalog_list,
+        }
+    }
+
+    pub fn table_names(&self) -> Vec<String> {
+        vec![TABLES.to_string(), COLUMNS.to_string()]
+    }
+
+    /// Returns the table with given name, or `None` if there is no such table.
+    pub fn table(&self, name: &str) -> Option<TableRef> {
+        let kind = if name.eq_ignore_ascii_case(TABLES) {
+            InformationTableKind::Tables
+        } else if name.eq_ignore_ascii_case(COLUMNS) {
+            InformationTableKind::Columns
+        } else {
+            return None;
+        };
+        Some(Arc::new(InformationTable::new(
+            kind,
+            self.catalog_name.clone(),
+            self.catalog_list.clone(),
+        )))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum InformationTableKind {
+    Tables,
+    Columns,
+}
+
+impl InformationTableKind {
+    fn name(&self) -> &'static str {
+        match self {
+            InformationTableKind::Tables => TABLES,
+            InformationTableKind::Columns => COLUMNS,
+        }
+    }
+
+    fn schema(&self) -> Schema {
+        let string_column =
+            |name: &str| ColumnSchema::new(name, ConcreteDataType::string_datatype(), false);
+        let mut columns = vec![
+            string_column(TABLE_CATALOG),
+            string_column(TABLE_SCHEMA),
+            string_column(TABLE_NAME),
+        ];
+        match self {
+            InformationTableKind::Tables => columns.extend([
+                string_column("table_type"),
+                ColumnSchema::new("table_id", ConcreteDataType::uint32_datatype(), false),
+                string_column("engine"),
+                ColumnSchema::new(
+                    "create_time",
+                    ConcreteDataType::timestamp_millisecond_datatype(),
+                    false,
+                ),
+                // Approximate data stats, null if the table doesn't report region stats.
+                ColumnSchema::new("table_rows", ConcreteDataType::uint64_datatype(), true),
+                ColumnSchema::new(
+                    "max_timestamp",
+                    ConcreteDataType::timestamp_millisecond_datatype(),
+                    true,
+                ),
+            ]),
+            InformationTableKind::Columns => columns.extend([
+                string_column("column_name"),
+                ColumnSchema::new(
+                    "ordinal_position",
+                    ConcreteDataType::uint64_datatype(),
+                    false,
+                ),
+                string_column("data_type"),
+                string_column("is_nullable"),
+                string_column("column_key"),
+                string_column("semantic_type"),
+            ]),
+        }
+        Schema::new(columns)
+    }
+}
+
+/// A table of `information_schema`.
+struct InformationTable {
+    kind: InformationTableKind,
+    schema: SchemaRef,
+    catalog_name: String,
+    catalog_list: CatalogListRef,
+}
+
+impl InformationTable {
+    fn new(kind: InformationTableKind, catalog_name: String, catalog_list: CatalogListRef) -> Self {
+        Self {
+            kind,
+            schema: Arc::new(kind.schema()),
+            catalog_name,
+            catalog_list,
+        }
+    }
+
+    /// Builds the rows of tables in schemas and tables matching the equality `filters`.
+    async fn build_columns(&self, filters: &[Expr]) -> Result<Vec<VectorRef>> {
+        let mut builder = RowsBuilder::new(self.kind, &self.schema);
+
+        let Some(catalog) = self.catalog_list.catalog(&self.catalog_name)? else {
+            return Ok(builder.finish());
+        };
+        let schema_names = match find_eq_value(filters, TABLE_SCHEMA) {
+            Some(schema_name) => vec![schema_name.to_string()],
+            None => catalog.schema_names()?,
+        };
+        for schema_name in schema_names {
+            let Some(schema) = catalog.schema(&schema_name)? else {
+                continue;
+            };
+            let table_names = match find_eq_value(filters, TABLE_NAME) {
+                Some(table_name) => vec![table_name.to_string()],
+                None => schema.table_names()?,
+            };
+            for table_name in table_names {
+                if let Some(table) = schema.table(&table_name).await? {
+                    builder.add_table(&self.catalog_name, &schema_name, &table_name, &table);
+                }
+            }
+        }
+        Ok(builder.finish())
+    }
+}
+
+#[async_trait]
+impl Table for InformationTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn table_info(&self) -> TableInfoRef {
+        Arc::new(
+            TableInfoBuilder::default()
+                .name(self.kind.name())
+                .catalog_name(&self.catalog_name)
+                .schema_name(INFORMATION_SCHEMA_NAME)
+                .table_type(TableType::View)
+                .meta(
+                    TableMetaBuilder::default()
+                        .schema(self.schema.clone())
+                        .primary_key_indices(vec![])
+                        .next_column_id(self.schema.num_columns() as u32)
+                        .build()
+                        .unwrap(),
+                )
+                .build()
+                .unwrap(),
+        )
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::View
+    }
+
+    async fn scan(
+        &self,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> TableResult<PhysicalPlanRef> {
+        let columns = self
+            .build_columns(filters)
+            .await
+            .map_err(BoxedError::new)
+            .context(TablesRecordBatchSnafu)?;
+
+        let (schema, columns) = match projection {
+            Some(projection) => {
+                let column_schemas = projection
+                    .iter()
+                    .map(|i| self.schema.column_schemas()[*i].clone())
+                    .collect();
+                let columns = projection.iter().map(|i| columns[*i].clone()).collect();
+                (Arc::new(Schema::new(column_schemas)), columns)
+            }
+            None => (self.schema.clone(), columns),
+        };
+        let batches = RecordBatches::try_from_columns(schema, columns)
+            .map_err(BoxedError::new)
+            .context(TablesRecordBatchSnafu)?;
+        Ok(Arc::new(SimpleTableScan::new(batches.as_stream())))
+    }
+
+    fn supports_filters_pushdown(&self, filters: &[&Expr]) -> TableResult<Vec<FilterPushDownType>> {
+        // The filters are only used to prune schemas and tables to visit, so they
+        // still need to be evaluated on the output.
+        Ok(vec![FilterPushDownType::Inexact; filters.len()])
+    }
+}
+
+/// Returns the string `column` must be equal to according to the `filters`.
+fn find_eq_value<'a>(filters: &'a [Expr], column: &str) -> Option<&'a str> {
+    filters.iter().find_map(|filter| {
+        let DfExpr::BinaryExpr(BinaryExpr { left, op: Operator::Eq, right }) = filter.df_expr() else {
+            return None;
+        };
+        match (left.as_ref(), right.as_ref()) {
+            (DfExpr::Column(c), DfExpr::Literal(ScalarValue::Utf8(Some(value))))
+            | (DfExpr::Literal(ScalarValue::Utf8(Some(value))), DfExpr::Column(c))
+                if c.name.eq_ignore_ascii_case(column) =>
+            {
+                Some(value.as_str())
+            }
+            _ => None,
+        }
+    })
+}
+
+/// Builds the columns of an [InformationTable] row by row.
+struct RowsBuilder {
+    kind: InformationTableKind,
+    columns: Vec<Box<dyn MutableVector>>,
+}
+
+impl RowsBuilder {
+    fn new(kind: InformationTableKind, schema: &Schema) -> Self {
+        let columns = schema
+            .column_schemas()
+            .iter()
+            .map(|column| column.data_type.create_mutable_vector(0))
+            .collect();
+        Self { kind, columns }
+    }
+
+    fn add_table(
+        &mut self,
+        catalog_name: &str,
+        schema_name: &str,
+        table_name: &str,
+        table: &TableRef,
+    ) {
+        let table_info = table.table_info();
+        match self.kind {
+            InformationTableKind::Tables => {
+                let create_time = table_info.meta.created_on.timestamp_millis();
+                let stat = table.table_stat().ok();
+                let table_rows = stat
+                    .as_ref()
+                    .map_or(ValueRef::Null, |stat| ValueRef::UInt64(stat.approximate_rows));
+                let max_timestamp = stat
+                    .and_then(|stat| stat.max_timestamp)
+                    .and_then(|ts| ts.convert_to(TimeUnit::Millisecond))
+                    .map_or(ValueRef::Null, ValueRef::Timestamp);
+                self.push_row(&[
+                    ValueRef::String(catalog_name),
+                    ValueRef::String(schema_name),
+                    ValueRef::String(table_name),
+                    ValueRef::String(table_type_name(table_info.table_type)),
+                    ValueRef::UInt32(table_info.ident.table_id),
+                    ValueRef::String(&table_info.meta.engine),
+                    ValueRef::Timestamp(Timestamp::new_millisecond(create_time)),
+                    table_rows,
+                    max_timestamp,
+                ]);
+            }
+            InformationTableKind::Columns => {
+                let meta = &table_info.meta;
+                for (i, column) in meta.schema.column_schemas().iter().enumerate() {
+                    let semantic_type = if column.is_time_index() {
+                        "TIME INDEX"
+                    } else if meta.primary_key_indices.contains(&i) {
+                        "PRIMARY KEY"
+                    } else {
+                        "VALUE"
+                    };
+                    let column_key = if semantic_type == "VALUE" { "" } else { "PRI" };
+                    let data_type = mysql_type_name(&column.data_type);
+                    self.push_row(&[
+                        ValueRef::String(catalog_name),
+                        ValueRef::String(schema_name),
+                        ValueRef::String(table_name),
+                        ValueRef::String(&column.name),
+                        // Ordinal positions start from 1 as in MySQL.
+                        ValueRef::UInt64(i as u64 + 1),
+                        ValueRef::String(&data_type),
+                        ValueRef::String(if column.is_nullable() { "YES" } else { "NO" }),
+                        ValueRef::String(column_key),
+                        ValueRef::String(semantic_type),
+                    ]);
+                }
+            }
+        }
+    }
+
+    fn push_row(&mut self, row: &[ValueRef]) {
+        for (column, value) in self.columns.iter_mut().zip(row) {
+            // Safety: the values are of the types of the columns in the schema.
+            column.push_value_ref(*value);
+        }
+    }
+
+    fn finish(mut self) -> Vec<VectorRef> {
+        self.columns
+            .iter_mut()
+            .map(|column| column.to_vector())
+            .collect()
+    }
+}
+
+fn table_type_name(table_type: TableType) -> &'static str {
+    match table_type {
+        TableType::Base => "BASE TABLE",
+        TableType::View => "VIEW",
+        TableType::Temporary => "LOCAL TEMPORARY",
+    }
+}
+
+/// Returns the name of the MySQL type that `data_type` is close to.
+fn mysql_type_name(data_type: &ConcreteDataType) -> String {
+    match data_type {
+        ConcreteDataType::Null(_) => "null",
+        ConcreteDataType::Boolean(_) => "boolean",
+        ConcreteDataType::Int8(_) => "tinyint",
+        ConcreteDataType::Int16(_) => "smallint",
+        ConcreteDataType::Int32(_) => "int",
+        ConcreteDataType::Int64(_) => "bigint",
+        ConcreteDataType::UInt8(_) => "tinyint unsigned",
+        ConcreteDataType::UInt16(_) => "smallint unsigned",
+        ConcreteDataType::UInt32(_) => "int unsigned",
+        ConcreteDataType::UInt64(_) => "bigint unsigned",
+        ConcreteDataType::Float32(_) => "float",
+        ConcreteDataType::Float64(_) => "double",
+        ConcreteDataType::Binary(_) => "varbinary",
+        ConcreteDataType::String(_) => "varchar",
+        ConcreteDataType::Date(_) => "date",
+        ConcreteDataType::DateTime(_) => "datetime",
+        ConcreteDataType::Timestamp(_) => "timestamp",
+        ConcreteDataType::List(_) | ConcreteDataType::Dictionary(_) => {
+            return data_type.name().to_lowercase();
+        }
+    }
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
+    use common_query::physical_plan::SessionContext;
+    use datafusion::logical_expr::{col, lit};
+    use table::table::numbers::NumbersTable;
+
+    use super::*;
+    use crate::local::memory::new_memory_catalog_list;
+    use crate::CatalogList;
+
+    async fn scan(table: &TableRef, projection: Option<&Vec<usize>>, filters: &[Expr]) -> String {
+        let plan = table.scan(projection, filters, None).await.unwrap();
+        let stream = plan.execute(0, SessionContext::new().task_ctx()).unwrap();
+        RecordBatches::try_collect(stream)
+            .await
+            .unwrap()
+            .pretty_print()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_information_schema() {
+        let catalog_list = new_memory_catalog_list().unwrap();
+        let schema = catalog_list
+            .catalog(DEFAULT_CATALOG_NAME)
+            .unwrap()
+            .unwrap()
+            .schema(DEFAULT_SCHEMA_NAME)
+            .unwrap()
+            .unwrap();
+        schema
+            .register_table("numbers".to_string(), Arc::new(NumbersTable::new(1)))
+            .unwrap();
+
+        let provider =
+            InformationSchemaProvider::new(DEFAULT_CATALOG_NAME.to_string(), catalog_list);
+        assert!(provider.table("no_such_table").is_none());
+        let tables = provider.table("TABLES").unwrap();
+        let columns = provider.table(COLUMNS).unwrap();
+
+        // Skips the create time.
+        let projection = vec![0, 1, 2, 3, 4, 5];
+        let expected = "\
++---------------+--------------+------------+------------+----------+--------+
+| table_catalog | table_schema | table_name | table_type | table_id | engine |
++---------------+--------------+------------+------------+----------+--------+
+| greptime      | public       | numbers    | BASE TABLE | 1        |        |
++---------------+--------------+------------+------------+----------+--------+";
+        assert_eq!(expected, scan(&tables, Some(&projection), &[]).await);
+
+        // Tables created later are listed too.
+        schema
+            .register_table("numbers2".to_string(), Arc::new(NumbersTable::new(2)))
+            .unwrap();
+        let filters = vec![col(TABLE_NAME).eq(lit("numbers2")).into()];
+        let expected = "\
++---------------+--------------+------------+------------+----------+--------+
+| table_catalog | table_schema | table_name | table_type | table_id | engine |
++---------------+--------------+------------+------------+----------+--------+
+| greptime      | public       | numbers2   | BASE TABLE | 2        |        |
++---------------+--------------+------------+------------+----------+--------+";
+        assert_eq!(expected, scan(&tables, Some(&projection), &filters).await);
+
+        // The numbers table has no region stats.
+        let projection = vec![2, 7, 8];
+        let expected = "\
++------------+------------+---------------+
+| table_name | table_rows | max_timestamp |
++------------+------------+---------------+
+| numbers2   |            |               |
++------------+------------+---------------+";
+        assert_eq!(expected, scan(&tables, Some(&projection), &filters).await);
+
+        let filters = vec![
+            col(TABLE_SCHEMA).eq(lit(DEFAULT_SCHEMA_NAME)).into(),
+            lit("numbers").eq(col(TABLE_NAME)).into(),
+        ];
+        let expected = "\
++---------------+--------------+------------+-------------+------------------+--------------+-------------+------------+---------------+
+| table_catalog | table_schema | table_name | column_name | ordinal_position | data_type    | is_nullable | column_key | semantic_type |
++---------------+--------------+------------+-------------+------------------+--------------+-------------+------------+---------------+
+| greptime      | public       | numbers    | number      | 1                | int unsigned | NO          | PRI        | PRIMARY KEY   |
++---------------+--------------+------------+-------------+------------------+--------------+-------------+------------+---------------+";
+        assert_eq!(expected, scan(&columns, None, &filters).await);
+
+        let filters = vec![col(TABLE_SCHEMA).eq(lit("no_such_schema")).into()];
+        let expected = "\
++---------------+--------------+------------+-------------+------------------+-----------+-------------+------------+---------------+
+| table_catalog | table_schema | table_name | column_name | ordinal_position | data_type | is_nullable | column_key | semantic_type |
++---------------+--------------+------------+-------------+------------------+-----------+-------------+------------+---------------+
++---------------+--------------+------------+-------------+------------------+-----------+-------------+------------+---------------+";
+        assert_eq!(expected, scan(&columns, None, &filters).await);
+    }
+
+    #[test]
+    fn test_find_eq_value() {
+        let filters: Vec<Expr> = vec![
+            col("a").gt(lit("x")).into(),
+            col("b").eq(col("c")).into(),
+            lit("y").eq(col("a")).into(),
+        ];
+        assert_eq!(Some("y"), find_eq_value(&filters, "a"));
+        assert_eq!(None, find_eq_value(&filters, "b"));
+    }
+}
@@ -17,9 +17,11 @@
 use std::any::Any;
 use std::fmt::{Debug, Formatter};
 use std::sync::Arc;
+use std::time::Instant;
 
 use api::v1::meta::{RegionStat, TableName};
 use common_telemetry::{info, warn};
+use common_time::Timestamp;
 use snafu::{OptionExt, ResultExt};
 use table::engine::{EngineContext, TableEngineRef};
 use table::metadata::TableId;
@@ -31,6 +33,7 @@ pub use crate::schema::{SchemaProvider, SchemaProviderRef};
 
 pub mod error;
 pub mod helper;
+pub mod information_schema;
 pub mod local;
 pub mod remote;
 pub mod schema;
@@ -226,9 +229,20 @@ pub(crate) async fn handle_system_table_request<'a, M: CatalogManager>(
     Ok(())
 }
 
+/// The stat of a region in the datanode, with the number of rows written to the region
+/// since it's opened and the max timestamp of rows in the region.
+#[derive(Debug)]
+pub struct DatanodeRegionStat {
+    pub stat: RegionStat,
+    pub written_rows: u64,
+    pub max_timestamp: Option<Timestamp>,
+}
+
 /// The stat of regions in the datanode node.
 /// The number of regions can be got from len of vec.
-pub async fn datanode_stat(catalog_manager: &CatalogManagerRef) -> Result<(u64, Vec<RegionStat>)> {
+pub async fn datanode_stat(
+    catalog_manager: &CatalogManagerRef,
+) -> Result<(u64, Vec<DatanodeRegionStat>)> {
     let mut region_number: u64 = 0;
     let mut region_stats = Vec::new();
 
@@ -262,15 +276,20 @@ pub async fn datanode_stat(catalog_manager: &CatalogManagerRef) -> Result<(u64,
 
                 match table.region_stats() {
                     Ok(stats) => {
-                        let stats = stats.into_iter().map(|stat| RegionStat {
-                            region_id: stat.region_id,
-                            table_name: Some(TableName {
-                                catalog_name: catalog_name.clone(),
-                                schema_name: schema_name.clone(),
-                                table_name: table_name.clone(),
-                            }),
-                            approximate_bytes: stat.disk_usage_bytes as i64,
-                            ..Default::default()
+                        let stats = stats.into_iter().map(|stat| DatanodeRegionStat {
+                            stat: RegionStat {
+                                region_id: stat.region_id,
+                                table_name: Some(TableName {
+                                    catalog_name: catalog_name.clone(),
+                                    schema_name: schema_name.clone(),
+                                    table_name: table_name.clone(),
+                                }),
+                                approximate_bytes: stat.disk_usage_bytes as i64,
+                                approximate_rows: stat.approximate_rows as i64,
+                                ..Default::default()
+                            },
+                            written_rows: stat.written_rows,
+                            max_timestamp: stat.max_timestamp,
                         });
 
                         region_stats.extend(stats);
@@ -285,3 +304,55 @@ pub async fn datanode_stat(catalog_manager: &CatalogManagerRef) -> Result<(u64,
 
     Ok((region_number, region_stats))
 }
+
+/// Max number of tables opened concurrently while starting the catalog manager, the
+/// number of regions opened concurrently is further limited by the table engine.
+pub(crate) const TABLE_OPEN_CONCURRENCY: usize = 64;
+
+/// Logs progress every time this number of regions are opened.
+const PROGRESS_LOG_REGIONS: usize = 100;
+
+/// Progress of opening tables while starting the catalog manager.
+pub(crate) struct OpenTableProgress {
+    scope: String,
+    total_tables: usize,
+    opened_tables: usize,
+    opened_regions: usize,
+    start: Instant,
+}
+
+impl OpenTableProgress {
+    pub(crate) fn new(scope: impl Into<String>, total_tables: usize) -> Self {
+        let scope = scope.into();
+        info!("Opening {} tables in {}", total_tables, scope);
+        Self {
+            scope,
+            total_tables,
+            opened_tables: 0,
+            opened_regions: 0,
+            start: Instant::now(),
+        }
+    }
+
+    /// Records an opened table, logs the progress with an estimated remaining time every
+    /// [PROGRESS_LOG_REGIONS] regions and after all tables are opened.
+    pub(crate) fn table_opened(&mut self, table: &TableRef) {
+        let regions = table.table_info().meta.region_numbers.len();
+        let logged = self.opened_regions / PROGRESS_LOG_REGIONS;
+        self.opened_tables += 1;
+        self.opened_regions += regions;
+        if self.opened_regions / PROGRESS_LOG_REGIONS == logged
+            && self.opened_tables < self.total_tables
+        {
+            return;
+        }
+
+        let elapsed = self.start.elapsed();
+        let remaining = self.total_tables.saturating_sub(self.opened_tables);
+        let eta = elapsed.mul_f64(remaining as f64 / self.opened_tables as f64);
+        info!(
+            "Opened {}/{} tables ({} regions) in {}, elapsed: {:?}, eta: {:?}",
+            self.opened_tables, self.total_tables, self.opened_regions, self.scope, elapsed, eta
+        );
+    }
+}
@@ -26,6 +26,7 @@ use common_telemetry::{error, info};
 use datatypes::prelude::ScalarVector;
 use datatypes::vectors::{BinaryVector, UInt8Vector};
 use futures_util::lock::Mutex;
+use futures_util::StreamExt;
 use snafu::{ensure, OptionExt, ResultExt};
 use table::engine::{EngineContext, TableEngineRef};
 use table::metadata::TableId;
@@ -47,8 +48,9 @@ use crate::system::{
 use crate::tables::SystemCatalog;
 use crate::{
     handle_system_table_request, CatalogList, CatalogManager, CatalogProvider, CatalogProviderRef,
-    DeregisterTableRequest, RegisterSchemaRequest, RegisterSystemTableRequest,
+    DeregisterTableRequest, OpenTableProgress, RegisterSchemaRequest, RegisterSystemTableRequest,
     RegisterTableRequest, RenameTableRequest, SchemaProvider, SchemaProviderRef,
+    TABLE_OPEN_CONCURRENCY,
 };
 
 /// A `CatalogManager` consists of a system catalog and a bunch of user catalogs.
@@ -193,6 +195,7 @@ impl LocalCatalogManager {
     async fn handle_system_catalog_entries(&self, entries: Vec<Entry>) -> Result<TableId> {
         let entries = Self::sort_entries(entries);
         let mut max_table_id = 0;
+        let mut tables = Vec::new();
         for entry in entries {
             match entry {
                 Entry::Catalog(c) => {
@@ -216,15 +219,32 @@ impl LocalCatalogManager {
                     info!("Registered schema: {:?}", s);
                 }
                 Entry::Table(t) => {
-                    self.open_and_register_table(&t).await?;
-                    info!("Registered table: {:?}", t);
                     max_table_id = max_table_id.max(t.table_id);
+                    tables.push(t);
                 }
             }
         }
+        self.open_and_register_tables(&tables).await?;
         Ok(max_table_id)
     }
 
+    /// Opens and registers tables concurrently, catalogs and schemas of the tables
+    /// should be registered before.
+    async fn open_and_register_tables(&self, tables: &[TableEntry]) -> Result<()> {
+        let mut progress = OpenTableProgress::new("local catalog", tables.len());
+        let mut opened = futures::stream::iter(tables.iter().map(|t| async move {
+            let table = self.open_and_register_table(t).await?;
+            info!("Registered table: {:?}", t);
+            Ok::<_, error::Error>(table)
+        }))
+        .buffer_unordered(TABLE_OPEN_CONCURRENCY);
+
+        while let Some(table) = opened.next().await {
+            progress.table_opened(&table?);
+        }
+        Ok(())
+    }
+
     /// Sort catalog entries to ensure catalog entries comes first, then schema entries,
     /// and table entries is the last.
     fn sort_entries(mut entries: Vec<Entry>) -> Vec<Entry> {
@@ -232,7 +252,7 @@ impl LocalCatalogManager {
         entries
     }
 
-    async fn open_and_register_table(&self, t: &TableEntry) -> Result<()> {
+    async fn open_and_register_table(&self, t: &TableEntry) -> Result<TableRef> {
         let catalog = self
             .catalogs
             .catalog(&t.catalog_name)?
@@ -271,8 +291,8 @@ impl LocalCatalogManager {
                 ),
             })?;
 
-        schema.register_table(t.table_name.clone(), option)?;
-        Ok(())
+        schema.register_table(t.table_name.clone(), option.clone())?;
+        Ok(option)
     }
 }
 
@@ -415,6 +435,7 @@ impl CatalogManager for LocalCatalogManager {
             .await?;
         Ok(schema
             .rename_table(&request.table_name, request.new_table_name)
+            .await
             .is_ok())
     }
 
@@ -94,8 +94,10 @@ impl CatalogManager for MemoryCatalogManager {
     }
 
     async fn rename_table(&self, request: RenameTableRequest) -> Result<bool> {
-        let catalogs = self.catalogs.write().unwrap();
-        let catalog = catalogs
+        let catalog = self
+            .catalogs
+            .read()
+            .unwrap()
             .get(&request.catalog)
             .context(CatalogNotFoundSnafu {
                 catalog_name: &request.catalog,
@@ -109,6 +111,7 @@ impl CatalogManager for MemoryCatalogManager {
             })?;
         Ok(schema
             .rename_table(&request.table_name, request.new_table_name)
+            .await
             .is_ok())
     }
 
@@ -322,7 +325,7 @@ impl SchemaProvider for MemorySchemaProvider {
         }
     }
 
-    fn rename_table(&self, name: &str, new_name: String) -> Result<TableRef> {
+    async fn rename_table(&self, name: &str, new_name: String) -> Result<TableRef> {
         let mut tables = self.tables.write().unwrap();
         if tables.get(name).is_some() {
             let table = tables.remove(name).unwrap();
@@ -417,6 +420,7 @@ mod tests {
         let new_table_name = "numbers";
         provider
             .rename_table(table_name, new_table_name.to_string())
+            .await
             .unwrap();
 
         // test old table name not exist
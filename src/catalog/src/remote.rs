@@ -59,6 +59,17 @@ pub trait KvBackend: Send + Sync {
         self.delete_range(key, &[]).await
     }
 
+    /// Moves the value of `from_key` to `to_key`, overwriting the value of `to_key` if it
+    /// exists. Does nothing if `from_key` doesn't exist.
+    ///
+    /// The default implementation is not atomic, backends should override it if they
+    /// can move values atomically.
+    async fn move_value(&self, from_key: &[u8], to_key: &[u8]) -> Result<(), Error> {
+        let Some(Kv(_, value)) = self.get(from_key).await? else { return Ok(()) };
+        self.set(to_key, &value).await?;
+        self.delete(from_key).await
+    }
+
     /// Default get is implemented based on `range` method.
     async fn get(&self, key: &[u8]) -> Result<Option<Kv>, Error> {
         let mut iter = self.range(key);
@@ -18,7 +18,9 @@ use std::sync::Arc;
 use async_stream::stream;
 use common_telemetry::info;
 use meta_client::client::MetaClient;
-use meta_client::rpc::{CompareAndPutRequest, DeleteRangeRequest, PutRequest, RangeRequest};
+use meta_client::rpc::{
+    CompareAndPutRequest, DeleteRangeRequest, MoveValueRequest, PutRequest, RangeRequest,
+};
 use snafu::ResultExt;
 
 use crate::error::{Error, MetaSrvSnafu};
@@ -84,6 +86,12 @@ impl KvBackend for MetaKvBackend {
         Ok(())
     }
 
+    async fn move_value(&self, from_key: &[u8], to_key: &[u8]) -> Result<(), Error> {
+        let req = MoveValueRequest::new(from_key, to_key);
+        let _ = self.client.move_value(req).await.context(MetaSrvSnafu)?;
+        Ok(())
+    }
+
     async fn compare_and_set(
         &self,
         key: &[u8],
@@ -29,14 +29,14 @@ use parking_lot::RwLock;
 use snafu::{OptionExt, ResultExt};
 use table::engine::{EngineContext, TableEngineRef};
 use table::metadata::TableId;
-use table::requests::{CreateTableRequest, OpenTableRequest};
+use table::requests::{CreateTableRequest, OpenRegionsRequest, OpenTableRequest};
 use table::table::numbers::NumbersTable;
 use table::TableRef;
 use tokio::sync::Mutex;
 
 use crate::error::{
-    CatalogNotFoundSnafu, CreateTableSnafu, InvalidCatalogValueSnafu, OpenTableSnafu, Result,
-    SchemaNotFoundSnafu, TableExistsSnafu, UnimplementedSnafu,
+    CatalogNotFoundSnafu, CreateTableSnafu, Error, InvalidCatalogValueSnafu, OpenTableSnafu,
+    Result, SchemaNotFoundSnafu, TableExistsSnafu, TableNotFoundSnafu,
 };
 use crate::helper::{
     build_catalog_prefix, build_schema_prefix, build_table_global_prefix, CatalogKey, CatalogValue,
@@ -46,8 +46,9 @@ use crate::helper::{
 use crate::remote::{Kv, KvBackendRef};
 use crate::{
     handle_system_table_request, CatalogList, CatalogManager, CatalogProvider, CatalogProviderRef,
-    DeregisterTableRequest, RegisterSchemaRequest, RegisterSystemTableRequest,
+    DeregisterTableRequest, OpenTableProgress, RegisterSchemaRequest, RegisterSystemTableRequest,
     RegisterTableRequest, RenameTableRequest, SchemaProvider, SchemaProviderRef,
+    TABLE_OPEN_CONCURRENCY,
 };
 
 /// Catalog manager based on metasrv.
@@ -249,19 +250,38 @@ impl RemoteCatalogManager {
         mut max_table_id: TableId,
     ) -> Result<()> {
         info!("initializing tables in {}.{}", catalog_name, schema_name);
-        let mut table_num = 0;
-        let mut tables = self.iter_remote_tables(catalog_name, schema_name).await;
-        while let Some(r) = tables.next().await {
-            let (table_key, table_value) = r?;
-            let table_ref = self.open_or_create_table(&table_key, &table_value).await?;
-            schema.register_table(table_key.table_name.to_string(), table_ref)?;
-            info!("Registered table {}", &table_key.table_name);
+        let tables = self
+            .iter_remote_tables(catalog_name, schema_name)
+            .await
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut progress =
+            OpenTableProgress::new(format!("{catalog_name}.{schema_name}"), tables.len());
+        let mut opened = futures::stream::iter(tables.iter().map(|(table_key, table_value)| {
+            let schema = schema.clone();
+            async move {
+                let table_ref = self.open_or_create_table(table_key, table_value).await?;
+                schema.register_table(table_key.table_name.to_string(), table_ref.clone())?;
+                info!("Registered table {}", &table_key.table_name);
+                Ok::<_, Error>(table_ref)
+            }
+        }))
+        .buffer_unordered(TABLE_OPEN_CONCURRENCY);
+
+        while let Some(table_ref) = opened.next().await {
+            progress.table_opened(&table_ref?);
+        }
+        for (_, table_value) in &tables {
             max_table_id = max_table_id.max(table_value.table_id());
-            table_num += 1;
         }
         info!(
             "initialized tables in {}.{}, total: {}",
-            catalog_name, schema_name, table_num
+            catalog_name,
+            schema_name,
+            tables.len()
         );
         Ok(())
     }
@@ -343,6 +363,35 @@ impl RemoteCatalogManager {
                     "Table opened: {}.{}.{}",
                     catalog_name, schema_name, table_name
                 );
+
+                // Regions moved to this datanode, e.g. by decommissioning another datanode,
+                // are not recorded in the table's own metadata.
+                let opened = &table.table_info().meta.region_numbers;
+                let moved_in = region_numbers
+                    .iter()
+                    .filter(|n| !opened.contains(n))
+                    .copied()
+                    .collect::<Vec<_>>();
+                if moved_in.is_empty() {
+                    return Ok(table);
+                }
+                let request = OpenRegionsRequest {
+                    catalog_name: catalog_name.clone(),
+                    schema_name: schema_name.clone(),
+                    table_name: table_name.clone(),
+                    table_id,
+                    region_numbers: moved_in,
+                };
+                let table = self
+                    .engine
+                    .open_regions(&context, request)
+                    .await
+                    .with_context(|_| OpenTableSnafu {
+                        table_info: format!(
+                            "{catalog_name}.{schema_name}.{table_name}, id:{table_id}"
+                        ),
+                    })?
+                    .unwrap_or(table);
                 Ok(table)
             }
             None => {
@@ -460,11 +509,29 @@ impl CatalogManager for RemoteCatalogManager {
         Ok(true)
     }
 
-    async fn rename_table(&self, _request: RenameTableRequest) -> Result<bool> {
-        UnimplementedSnafu {
-            operation: "rename table",
+    async fn rename_table(&self, request: RenameTableRequest) -> Result<bool> {
+        let catalog_name = &request.catalog;
+        let schema_name = &request.schema;
+        let schema = self
+            .schema(catalog_name, schema_name)?
+            .context(SchemaNotFoundSnafu {
+                catalog: catalog_name,
+                schema: schema_name,
+            })?;
+        if schema.table_exist(&request.new_table_name)? {
+            return TableExistsSnafu {
+                table: format!(
+                    "{}.{}.{}",
+                    catalog_name, schema_name, &request.new_table_name
+                ),
+            }
+            .fail();
         }
-        .fail()
+
+        schema
+            .rename_table(&request.table_name, request.new_table_name)
+            .await?;
+        Ok(true)
     }
 
     async fn register_system_table(&self, request: RegisterSystemTableRequest) -> Result<()> {
@@ -808,11 +875,31 @@ impl SchemaProvider for RemoteSchemaProvider {
         prev
     }
 
-    fn rename_table(&self, _name: &str, _new_name: String) -> Result<TableRef> {
-        UnimplementedSnafu {
-            operation: "rename table",
-        }
-        .fail()
+    async fn rename_table(&self, name: &str, new_name: String) -> Result<TableRef> {
+        let table_key = self.build_regional_table_key(name).to_string();
+        let new_table_key = self.build_regional_table_key(&new_name).to_string();
+        let _guard = self.mutex.lock().await;
+        let prev_tables = self.tables.load();
+        let table = prev_tables
+            .get(name)
+            .cloned()
+            .with_context(|| TableNotFoundSnafu {
+                table_info: name.to_string(),
+            })?;
+        self.backend
+            .move_value(table_key.as_bytes(), new_table_key.as_bytes())
+            .await?;
+        debug!(
+            "Successfully renamed catalog table entry, key: {}, new key: {}",
+            table_key, new_table_key
+        );
+
+        let mut new_tables = HashMap::with_capacity(prev_tables.len());
+        new_tables.clone_from(&prev_tables);
+        new_tables.remove(name);
+        new_tables.insert(new_name, table.clone());
+        self.tables.store(Arc::new(new_tables));
+        Ok(table)
     }
 
     fn deregister_table(&self, name: &str) -> Result<Option<TableRef>> {
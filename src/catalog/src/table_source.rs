@@ -15,6 +15,7 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
+use common_catalog::consts::INFORMATION_SCHEMA_NAME;
 use common_catalog::format_full_table_name;
 use datafusion::common::{OwnedTableReference, ResolvedTableReference, TableReference};
 use datafusion::datasource::provider_as_source;
@@ -26,6 +27,7 @@ use table::table::adapter::DfTableProviderAdapter;
 use crate::error::{
     CatalogNotFoundSnafu, QueryAccessDeniedSnafu, Result, SchemaNotFoundSnafu, TableNotExistSnafu,
 };
+use crate::information_schema::InformationSchemaProvider;
 use crate::CatalogListRef;
 
 pub struct DfTableSourceProvider {
@@ -60,7 +62,8 @@ impl DfTableSourceProvider {
                 TableReference::Bare { .. } => (),
                 TableReference::Partial { schema, .. } => {
                     ensure!(
-                        schema.as_ref() == self.default_schema,
+                        schema.as_ref() == self.default_schema
+                            || schema.eq_ignore_ascii_case(INFORMATION_SCHEMA_NAME),
                         QueryAccessDeniedSnafu {
                             catalog: &self.default_catalog,
                             schema: schema.as_ref(),
@@ -72,7 +75,8 @@ impl DfTableSourceProvider {
                 } => {
                     ensure!(
                         catalog.as_ref() == self.default_catalog
-                            && schema.as_ref() == self.default_schema,
+                            && (schema.as_ref() == self.default_schema
+                                || schema.eq_ignore_ascii_case(INFORMATION_SCHEMA_NAME)),
                         QueryAccessDeniedSnafu {
                             catalog: catalog.as_ref(),
                             schema: schema.as_ref()
@@ -105,16 +109,24 @@ impl DfTableSourceProvider {
             .catalog_list
             .catalog(catalog_name)?
             .context(CatalogNotFoundSnafu { catalog_name })?;
-        let schema = catalog.schema(schema_name)?.context(SchemaNotFoundSnafu {
-            catalog: catalog_name,
-            schema: schema_name,
+        let table = match catalog.schema(schema_name)? {
+            Some(schema) => schema.table(table_name).await?,
+            // Catalogs don't have a real `information_schema`, except the system catalog.
+            None if schema_name.eq_ignore_ascii_case(INFORMATION_SCHEMA_NAME) => {
+                InformationSchemaProvider::new(catalog_name.to_string(), self.catalog_list.clone())
+                    .table(table_name)
+            }
+            None => {
+                return SchemaNotFoundSnafu {
+                    catalog: catalog_name,
+                    schema: schema_name,
+                }
+                .fail()
+            }
+        }
+        .with_context(|| TableNotExistSnafu {
+            table: format_full_table_name(catalog_name, schema_name, table_name),
         })?;
-        let table = schema
-            .table(table_name)
-            .await?
-            .with_context(|| TableNotExistSnafu {
-                table: format_full_table_name(catalog_name, schema_name, table_name),
-            })?;
 
         let table = DfTableProviderAdapter::new(table);
         let table = provider_as_source(Arc::new(table));
@@ -152,6 +164,13 @@ mod tests {
         let result = table_provider.resolve_table_ref(table_ref);
         assert!(result.is_ok());
 
+        let table_ref = TableReference::Partial {
+            schema: Cow::Borrowed("information_schema"),
+            table: Cow::Borrowed("tables"),
+        };
+        let result = table_provider.resolve_table_ref(table_ref);
+        assert!(result.is_ok());
+
         let table_ref = TableReference::Partial {
             schema: Cow::Borrowed("wrong_schema"),
             table: Cow::Borrowed("table_name"),
@@ -175,4 +194,23 @@ mod tests {
         let result = table_provider.resolve_table_ref(table_ref);
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn test_resolve_information_schema() {
+        let query_ctx = &QueryContext::with("greptime", "public");
+        let mut table_provider =
+            DfTableSourceProvider::new(Arc::new(MemoryCatalogManager::default()), true, query_ctx);
+
+        let table_ref = OwnedTableReference::Partial {
+            schema: "information_schema".to_string(),
+            table: "columns".to_string(),
+        };
+        assert!(table_provider.resolve_table(table_ref).await.is_ok());
+
+        let table_ref = OwnedTableReference::Partial {
+            schema: "information_schema".to_string(),
+            table: "no_such_table".to_string(),
+        };
+        assert!(table_provider.resolve_table(table_ref).await.is_err());
+    }
 }
@@ -232,7 +232,7 @@ impl SchemaProvider for InformationSchema {
         panic!("System catalog & schema does not support register table")
     }
 
-    fn rename_table(&self, _name: &str, _new_name: String) -> crate::error::Result<TableRef> {
+    async fn rename_table(&self, _name: &str, _new_name: String) -> crate::error::Result<TableRef> {
         unimplemented!("System catalog & schema does not support rename table")
     }
 
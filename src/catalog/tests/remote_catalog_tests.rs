@@ -22,11 +22,13 @@ mod tests {
     use std::collections::HashSet;
     use std::sync::Arc;
 
-    use catalog::helper::{CatalogKey, CatalogValue, SchemaKey, SchemaValue};
+    use catalog::helper::{
+        build_table_regional_prefix, CatalogKey, CatalogValue, SchemaKey, SchemaValue,
+    };
     use catalog::remote::{
         KvBackend, KvBackendRef, RemoteCatalogManager, RemoteCatalogProvider, RemoteSchemaProvider,
     };
-    use catalog::{CatalogList, CatalogManager, RegisterTableRequest};
+    use catalog::{CatalogList, CatalogManager, RegisterTableRequest, RenameTableRequest};
     use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
     use datatypes::schema::RawSchema;
     use futures_util::StreamExt;
@@ -213,6 +215,88 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_rename_table() {
+        let node_id = 42;
+        let (backend, table_engine, catalog_manager) = prepare_components(node_id).await;
+        let catalog_name = DEFAULT_CATALOG_NAME.to_string();
+        let schema_name = DEFAULT_SCHEMA_NAME.to_string();
+        let table_id = 1;
+        let table = table_engine
+            .create_table(
+                &EngineContext {},
+                CreateTableRequest {
+                    id: table_id,
+                    catalog_name: catalog_name.clone(),
+                    schema_name: schema_name.clone(),
+                    table_name: "test_table".to_string(),
+                    desc: None,
+                    schema: RawSchema::new(vec![]),
+                    region_numbers: vec![0],
+                    primary_key_indices: vec![],
+                    create_if_not_exists: false,
+                    table_options: Default::default(),
+                },
+            )
+            .await
+            .unwrap();
+        let reg_req = RegisterTableRequest {
+            catalog: catalog_name.clone(),
+            schema: schema_name.clone(),
+            table_name: "test_table".to_string(),
+            table_id,
+            table,
+        };
+        assert!(catalog_manager.register_table(reg_req).await.unwrap());
+
+        let rename_req = RenameTableRequest {
+            catalog: catalog_name.clone(),
+            schema: schema_name.clone(),
+            table_name: "test_table".to_string(),
+            new_table_name: "new_table".to_string(),
+            table_id,
+        };
+        assert!(catalog_manager
+            .rename_table(rename_req.clone())
+            .await
+            .unwrap());
+
+        let schema = catalog_manager
+            .schema(&catalog_name, &schema_name)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            HashSet::from(["new_table".to_string(), "numbers".to_string()]),
+            schema
+                .table_names()
+                .unwrap()
+                .into_iter()
+                .collect::<HashSet<_>>()
+        );
+        let prefix = build_table_regional_prefix(&catalog_name, &schema_name);
+        let keys = backend
+            .range(prefix.as_bytes())
+            .map(|kv| String::from_utf8(kv.unwrap().0).unwrap())
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(
+            vec![
+                format!("{prefix}new_table-{node_id}"),
+                format!("{prefix}numbers-{node_id}"),
+            ],
+            keys
+        );
+
+        // Renames to an existing table.
+        let rename_req = RenameTableRequest {
+            table_name: "new_table".to_string(),
+            new_table_name: "numbers".to_string(),
+            ..rename_req
+        };
+        let err = catalog_manager.rename_table(rename_req).await.unwrap_err();
+        assert_matches!(err, catalog::error::Error::TableExists { .. });
+    }
+
     #[tokio::test]
     async fn test_register_catalog_schema_table() {
         let node_id = 42;
@@ -17,6 +17,7 @@ use std::sync::Arc;
 use api::v1::greptime_database_client::GreptimeDatabaseClient;
 use arrow_flight::flight_service_client::FlightServiceClient;
 use common_grpc::channel_manager::ChannelManager;
+use common_grpc::compression::{GrpcCompression, ACCEPTED_ENCODINGS};
 use parking_lot::RwLock;
 use snafu::{OptionExt, ResultExt};
 use tonic::transport::Channel;
@@ -141,17 +142,30 @@ impl Client {
 
     pub(crate) fn make_flight_client(&self) -> Result<FlightClient> {
         let (addr, channel) = self.find_channel()?;
-        Ok(FlightClient {
-            addr,
-            client: FlightServiceClient::new(channel),
-        })
+        let mut client = FlightServiceClient::new(channel);
+        for encoding in ACCEPTED_ENCODINGS {
+            client = client.accept_compressed(encoding);
+        }
+        if let Some(encoding) = self.compression().encoding() {
+            client = client.send_compressed(encoding);
+        }
+        Ok(FlightClient { addr, client })
     }
 
     pub(crate) fn make_database_client(&self) -> Result<DatabaseClient> {
         let (_, channel) = self.find_channel()?;
-        Ok(DatabaseClient {
-            inner: GreptimeDatabaseClient::new(channel),
-        })
+        let mut client = GreptimeDatabaseClient::new(channel);
+        for encoding in ACCEPTED_ENCODINGS {
+            client = client.accept_compressed(encoding);
+        }
+        if let Some(encoding) = self.compression().encoding() {
+            client = client.send_compressed(encoding);
+        }
+        Ok(DatabaseClient { inner: client })
+    }
+
+    fn compression(&self) -> GrpcCompression {
+        self.inner.channel_manager.config().compression
     }
 }
 
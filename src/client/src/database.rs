@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::str::FromStr;
+
 use api::v1::auth_header::AuthScheme;
 use api::v1::ddl_request::Expr as DdlExpr;
 use api::v1::greptime_request::Request;
@@ -21,20 +23,46 @@ use api::v1::{
     DropTableExpr, FlushTableExpr, GreptimeRequest, InsertRequest, PromRangeQuery, QueryRequest,
     RequestHeader,
 };
-use arrow_flight::{FlightData, Ticket};
+use arrow_flight::flight_descriptor::DescriptorType;
+use arrow_flight::{Action, FlightData, FlightDescriptor, PutResult, Ticket};
 use common_error::prelude::*;
-use common_grpc::flight::{flight_messages_to_recordbatches, FlightDecoder, FlightMessage};
+use common_grpc::flight::{
+    flight_messages_to_recordbatches, BulkLoadRequest, BulkLoadResult, FlightDecoder,
+    FlightEncoder, FlightMessage, PutBatchResult, ABORT_BULK_LOAD, BEGIN_BULK_LOAD,
+    BULK_LOAD_HEADER, COMMIT_BULK_LOAD, DROP_TABLE_SYNC_HEADER, MULTI_STATEMENTS_HEADER,
+    ON_ERROR_CONTINUE, ON_ERROR_STOP, SCAN_STATS_HEADER,
+};
 use common_query::Output;
+use common_recordbatch::scan_stats::ScanStats;
+use common_recordbatch::RecordBatch;
 use common_telemetry::logging;
 use futures_util::{TryFutureExt, TryStreamExt};
 use prost::Message;
-use snafu::{ensure, ResultExt};
+use snafu::{ensure, OptionExt, ResultExt};
+use tonic::metadata::MetadataValue;
 
 use crate::error::{
     ConvertFlightDataSnafu, IllegalDatabaseResponseSnafu, IllegalFlightMessagesSnafu,
 };
 use crate::{error, Client, Result};
 
+/// What to do with the following statements of a multi-statement query once a statement
+/// fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnError {
+    Stop,
+    Continue,
+}
+
+impl OnError {
+    fn header_value(&self) -> &'static str {
+        match self {
+            OnError::Stop => ON_ERROR_STOP,
+            OnError::Continue => ON_ERROR_CONTINUE,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Database {
     // The "catalog" and "schema" to be used in processing the requests at the server side.
@@ -108,6 +136,50 @@ impl Database {
         .await
     }
 
+    /// Executes the statements of `sql` in order, and returns the result of each executed
+    /// statement. Statements after a failed one are skipped unless `on_error` is
+    /// [OnError::Continue].
+    pub async fn sql_statements(
+        &self,
+        sql: &str,
+        on_error: OnError,
+    ) -> Result<Vec<Result<Output>>> {
+        let request = Request::Query(QueryRequest {
+            query: Some(Query::Sql(sql.to_string())),
+        });
+        let flight_messages = self
+            .do_get_messages(request, &[(MULTI_STATEMENTS_HEADER, on_error.header_value())])
+            .await?;
+
+        let mut results = Vec::new();
+        let mut outputs = Vec::new();
+        for message in flight_messages {
+            let FlightMessage::StatementEnd(end) = message else {
+                outputs.push(message);
+                continue;
+            };
+            let statement_messages = std::mem::take(&mut outputs);
+            let result = if end.err_code.is_empty() {
+                flight_messages_to_output(statement_messages)
+            } else {
+                let code = StatusCode::from_str(&end.err_code).unwrap_or(StatusCode::Unknown);
+                error::ServerSnafu {
+                    code,
+                    msg: end.err_msg,
+                }
+                .fail()
+            };
+            results.push(result);
+        }
+        ensure!(
+            outputs.is_empty(),
+            IllegalFlightMessagesSnafu {
+                reason: "Expect the outputs of each statement to end with 'StatementEnd'!"
+            }
+        );
+        Ok(results)
+    }
+
     pub async fn logical_plan(&self, logical_plan: Vec<u8>) -> Result<Output> {
         self.do_get(Request::Query(QueryRequest {
             query: Some(Query::LogicalPlan(logical_plan)),
@@ -115,6 +187,23 @@ impl Database {
         .await
     }
 
+    /// Executes the logical plan like [Database::logical_plan], and also returns the stats of
+    /// the scans executed by it.
+    pub async fn logical_plan_with_scan_stats(
+        &self,
+        logical_plan: Vec<u8>,
+    ) -> Result<(Output, ScanStats)> {
+        let (output, scan_stats) = self
+            .do_get_with_scan_stats(
+                Request::Query(QueryRequest {
+                    query: Some(Query::LogicalPlan(logical_plan)),
+                }),
+                true,
+            )
+            .await?;
+        Ok((output, scan_stats.unwrap_or_default()))
+    }
+
     pub async fn prom_range_query(
         &self,
         promql: &str,
@@ -154,6 +243,18 @@ impl Database {
         .await
     }
 
+    /// Drops the table like `DROP TABLE ... SYNC`, returns after the data of the table is
+    /// deleted.
+    pub async fn drop_table_sync(&self, expr: DropTableExpr) -> Result<Output> {
+        let request = Request::Ddl(DdlRequest {
+            expr: Some(DdlExpr::DropTable(expr)),
+        });
+        let flight_messages = self
+            .do_get_messages(request, &[(DROP_TABLE_SYNC_HEADER, "true")])
+            .await?;
+        flight_messages_to_output(flight_messages)
+    }
+
     pub async fn flush_table(&self, expr: FlushTableExpr) -> Result<Output> {
         self.do_get(Request::Ddl(DdlRequest {
             expr: Some(DdlExpr::FlushTable(expr)),
@@ -161,19 +262,238 @@ impl Database {
         .await
     }
 
-    async fn do_get(&self, request: Request) -> Result<Output> {
-        let request = GreptimeRequest {
-            header: Some(RequestHeader {
-                catalog: self.catalog.clone(),
-                schema: self.schema.clone(),
-                authorization: self.ctx.auth_header.clone(),
-            }),
+    /// Writes record `batches` to the region `region_number` of table `table_name` through
+    /// Flight `DoPut`. Columns are sent as Arrow IPC, so no rows are built on either side.
+    ///
+    /// Returns the number of affected rows of each batch. A batch that fails, e.g. due to
+    /// schema mismatch, doesn't fail other batches.
+    pub async fn write_record_batch(
+        &self,
+        table_name: &str,
+        region_number: u32,
+        batches: Vec<RecordBatch>,
+    ) -> Result<Vec<Result<usize>>> {
+        self.do_put(table_name, region_number, batches, None).await
+    }
+
+    /// Begins a bulk load of table `table_name`. Batches written to the bulk load bypass the
+    /// WAL, and are invisible until [BulkLoad::commit()] is called.
+    pub async fn begin_bulk_load(&self, table_name: &str) -> Result<BulkLoad> {
+        let load_id = self
+            .do_bulk_load_action(BEGIN_BULK_LOAD, table_name, "")
+            .await?;
+        Ok(BulkLoad {
+            database: self.clone(),
+            load_id,
+        })
+    }
+
+    /// Sends the bulk load action `action_type` by Flight `DoAction`, returns the load id in
+    /// the result.
+    async fn do_bulk_load_action(
+        &self,
+        action_type: &str,
+        table_name: &str,
+        load_id: &str,
+    ) -> Result<String> {
+        let request = BulkLoadRequest {
+            header: self.to_rpc_request_header(),
+            table_name: table_name.to_string(),
+            load_id: load_id.to_string(),
+        };
+        let action = Action {
+            r#type: action_type.to_string(),
+            body: request.encode_to_vec().into(),
+        };
+
+        let mut client = self.client.make_flight_client()?;
+        let results: Vec<arrow_flight::Result> = match client
+            .mut_inner()
+            .do_action(action)
+            .and_then(|response| response.into_inner().try_collect())
+            .await
+        {
+            Ok(results) => results,
+            Err(e) => {
+                let tonic_code = e.code();
+                let e: error::Error = e.into();
+                logging::error!(
+                    "Failed to do Flight action {}, addr: {}, code: {}, source: {}",
+                    action_type,
+                    client.addr(),
+                    tonic_code,
+                    e
+                );
+                return Err(e)
+                    .map_err(BoxedError::new)
+                    .context(error::FlightActionSnafu {
+                        tonic_code,
+                        addr: client.addr(),
+                    });
+            }
+        };
+
+        let result = results
+            .into_iter()
+            .next()
+            .context(IllegalFlightMessagesSnafu {
+                reason: format!("Expecting a result of Flight action {action_type}"),
+            })?;
+        let result = BulkLoadResult::try_from(result).context(ConvertFlightDataSnafu)?;
+        Ok(result.load_id)
+    }
+
+    /// Writes record `batches` through Flight `DoPut`, to the bulk load `bulk_load` if it's
+    /// set, otherwise to the table directly.
+    async fn do_put(
+        &self,
+        table_name: &str,
+        region_number: u32,
+        batches: Vec<RecordBatch>,
+        bulk_load: Option<&str>,
+    ) -> Result<Vec<Result<usize>>> {
+        if batches.is_empty() {
+            return Ok(vec![]);
+        }
+
+        // Only the table and the region are needed, columns are carried by the batches.
+        let target = self.to_rpc_request(Request::Insert(InsertRequest {
+            table_name: table_name.to_string(),
+            region_number,
+            ..Default::default()
+        }));
+        let mut encoder = FlightEncoder::default();
+        let mut flight_data = Vec::with_capacity(batches.len() + 1);
+        let mut schema = None;
+        for batch in batches {
+            if schema.as_ref() != Some(&batch.schema) {
+                schema = Some(batch.schema.clone());
+                flight_data.push(encoder.encode(FlightMessage::Schema(batch.schema.clone())));
+            }
+            flight_data.push(encoder.encode(FlightMessage::Recordbatch(batch)));
+        }
+        flight_data[0].flight_descriptor = Some(FlightDescriptor {
+            r#type: DescriptorType::Cmd as i32,
+            cmd: target.encode_to_vec().into(),
+            path: vec![],
+        });
+
+        let mut request = tonic::Request::new(futures_util::stream::iter(flight_data));
+        if let Some(load_id) = bulk_load {
+            let value = MetadataValue::try_from(load_id).map_err(|e| {
+                error::IllegalGrpcClientStateSnafu {
+                    err_msg: format!("Invalid bulk load id {load_id}: {e}"),
+                }
+                .build()
+            })?;
+            let _ = request.metadata_mut().insert(BULK_LOAD_HEADER, value);
+        }
+
+        let mut client = self.client.make_flight_client()?;
+        let put_results: Vec<PutResult> = match client
+            .mut_inner()
+            .do_put(request)
+            .and_then(|response| response.into_inner().try_collect())
+            .await
+        {
+            Ok(put_results) => put_results,
+            Err(e) => {
+                let tonic_code = e.code();
+                let e: error::Error = e.into();
+                logging::error!(
+                    "Failed to do Flight put, addr: {}, code: {}, source: {}",
+                    client.addr(),
+                    tonic_code,
+                    e
+                );
+                return Err(e)
+                    .map_err(BoxedError::new)
+                    .context(error::FlightPutSnafu {
+                        tonic_code,
+                        addr: client.addr(),
+                    });
+            }
+        };
+
+        put_results
+            .into_iter()
+            .map(|put_result| {
+                let result =
+                    PutBatchResult::try_from(put_result).context(ConvertFlightDataSnafu)?;
+                if result.err_code.is_empty() {
+                    return Ok(Ok(result.affected_rows as _));
+                }
+                let code = StatusCode::from_str(&result.err_code).unwrap_or(StatusCode::Unknown);
+                Ok(error::ServerSnafu {
+                    code,
+                    msg: result.err_msg,
+                }
+                .fail())
+            })
+            .collect()
+    }
+
+    fn to_rpc_request(&self, request: Request) -> GreptimeRequest {
+        GreptimeRequest {
+            header: self.to_rpc_request_header(),
             request: Some(request),
+        }
+    }
+
+    fn to_rpc_request_header(&self) -> Option<RequestHeader> {
+        Some(RequestHeader {
+            catalog: self.catalog.clone(),
+            schema: self.schema.clone(),
+            authorization: self.ctx.auth_header.clone(),
+        })
+    }
+
+    async fn do_get(&self, request: Request) -> Result<Output> {
+        let (output, _) = self.do_get_with_scan_stats(request, false).await?;
+        Ok(output)
+    }
+
+    /// Sends the request by Flight `DoGet`, and asks the server for the scan stats of the
+    /// request if `scan_stats` is true.
+    async fn do_get_with_scan_stats(
+        &self,
+        request: Request,
+        scan_stats: bool,
+    ) -> Result<(Output, Option<ScanStats>)> {
+        let metadata: &[_] = if scan_stats {
+            &[(SCAN_STATS_HEADER, "true")]
+        } else {
+            &[]
         };
-        let request = Ticket {
-            ticket: request.encode_to_vec().into(),
+        let mut flight_messages = self.do_get_messages(request, metadata).await?;
+        let scan_stats = match flight_messages.last() {
+            Some(FlightMessage::ScanStats(stats)) => {
+                let stats = *stats;
+                let _ = flight_messages.pop();
+                Some(stats)
+            }
+            _ => None,
         };
 
+        let output = flight_messages_to_output(flight_messages)?;
+        Ok((output, scan_stats))
+    }
+
+    /// Sends the request by Flight `DoGet` with the gRPC request `metadata`, and returns the
+    /// decoded messages of the response.
+    async fn do_get_messages(
+        &self,
+        request: Request,
+        metadata: &[(&'static str, &'static str)],
+    ) -> Result<Vec<FlightMessage>> {
+        let request = self.to_rpc_request(request);
+        let mut request = tonic::Request::new(Ticket {
+            ticket: request.encode_to_vec().into(),
+        });
+        for &(key, value) in metadata {
+            let _ = request.metadata_mut().insert(key, MetadataValue::from_static(value));
+        }
+
         let mut client = self.client.make_flight_client()?;
 
         // TODO(LFC): Streaming get flight data.
@@ -207,25 +527,70 @@ impl Database {
             })?;
 
         let decoder = &mut FlightDecoder::default();
-        let flight_messages = flight_data
+        flight_data
             .into_iter()
             .map(|x| decoder.try_decode(x).context(ConvertFlightDataSnafu))
-            .collect::<Result<Vec<_>>>()?;
+            .collect()
+    }
+}
 
-        let output = if let Some(FlightMessage::AffectedRows(rows)) = flight_messages.get(0) {
-            ensure!(
-                flight_messages.len() == 1,
-                IllegalFlightMessagesSnafu {
-                    reason: "Expect 'AffectedRows' Flight messages to be one and only!"
-                }
-            );
-            Output::AffectedRows(*rows)
-        } else {
-            let recordbatches = flight_messages_to_recordbatches(flight_messages)
-                .context(ConvertFlightDataSnafu)?;
-            Output::RecordBatches(recordbatches)
-        };
-        Ok(output)
+/// An uncommitted bulk load of a table, begun by [Database::begin_bulk_load()].
+#[derive(Clone, Debug)]
+pub struct BulkLoad {
+    database: Database,
+    load_id: String,
+}
+
+impl BulkLoad {
+    pub fn load_id(&self) -> &str {
+        &self.load_id
+    }
+
+    /// Writes record `batches` to the region `region_number` of the bulk load, like
+    /// [Database::write_record_batch()]. Written batches are invisible until committed.
+    pub async fn write_batch(
+        &self,
+        region_number: u32,
+        batches: Vec<RecordBatch>,
+    ) -> Result<Vec<Result<usize>>> {
+        self.database
+            .do_put("", region_number, batches, Some(&self.load_id))
+            .await
+    }
+
+    /// Commits the bulk load, all written batches are visible at once.
+    pub async fn commit(self) -> Result<()> {
+        let _ = self
+            .database
+            .do_bulk_load_action(COMMIT_BULK_LOAD, "", &self.load_id)
+            .await?;
+        Ok(())
+    }
+
+    /// Discards the bulk load and all written batches.
+    pub async fn abort(self) -> Result<()> {
+        let _ = self
+            .database
+            .do_bulk_load_action(ABORT_BULK_LOAD, "", &self.load_id)
+            .await?;
+        Ok(())
+    }
+}
+
+/// Converts the messages of a single output to [Output].
+fn flight_messages_to_output(flight_messages: Vec<FlightMessage>) -> Result<Output> {
+    if let Some(FlightMessage::AffectedRows(rows)) = flight_messages.get(0) {
+        ensure!(
+            flight_messages.len() == 1,
+            IllegalFlightMessagesSnafu {
+                reason: "Expect 'AffectedRows' Flight messages to be one and only!"
+            }
+        );
+        Ok(Output::AffectedRows(*rows))
+    } else {
+        let recordbatches =
+            flight_messages_to_recordbatches(flight_messages).context(ConvertFlightDataSnafu)?;
+        Ok(Output::RecordBatches(recordbatches))
     }
 }
 
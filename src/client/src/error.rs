@@ -34,6 +34,20 @@ pub enum Error {
         source: BoxedError,
     },
 
+    #[snafu(display("Failed to do Flight put, code: {}, source: {}", tonic_code, source))]
+    FlightPut {
+        addr: String,
+        tonic_code: Code,
+        source: BoxedError,
+    },
+
+    #[snafu(display("Failed to do Flight action, code: {}, source: {}", tonic_code, source))]
+    FlightAction {
+        addr: String,
+        tonic_code: Code,
+        source: BoxedError,
+    },
+
     #[snafu(display("Failed to convert FlightData, source: {}", source))]
     ConvertFlightData {
         #[snafu(backtrace)]
@@ -72,6 +86,28 @@ pub enum Error {
 
     #[snafu(display("Illegal Database response: {err_msg}"))]
     IllegalDatabaseResponse { err_msg: String },
+
+    #[snafu(display("Failed to collect record batches, source: {}", source))]
+    CollectRecordBatches {
+        #[snafu(backtrace)]
+        source: common_recordbatch::error::Error,
+    },
+
+    #[snafu(display("Column {} not found in query results", column))]
+    ColumnNotFound { column: String, backtrace: Backtrace },
+
+    #[snafu(display(
+        "Failed to read column {} of type {} as {}",
+        column,
+        actual,
+        expected
+    ))]
+    ColumnTypeMismatch {
+        column: String,
+        expected: String,
+        actual: String,
+        backtrace: Backtrace,
+    },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -85,11 +121,16 @@ impl ErrorExt for Error {
             | Error::IllegalDatabaseResponse { .. } => StatusCode::Internal,
 
             Error::Server { code, .. } => *code,
-            Error::FlightGet { source, .. } => source.status_code(),
+            Error::FlightGet { source, .. }
+            | Error::FlightPut { source, .. }
+            | Error::FlightAction { source, .. } => source.status_code(),
             Error::CreateChannel { source, .. } | Error::ConvertFlightData { source } => {
                 source.status_code()
             }
             Error::IllegalGrpcClientState { .. } => StatusCode::Unexpected,
+            Error::CollectRecordBatches { source } => source.status_code(),
+            Error::ColumnNotFound { .. } => StatusCode::TableColumnNotFound,
+            Error::ColumnTypeMismatch { .. } => StatusCode::InvalidArguments,
         }
     }
 
@@ -16,10 +16,13 @@ mod client;
 mod database;
 mod error;
 pub mod load_balance;
+mod row;
 
 pub use api;
 pub use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
+pub use common_function_macro::FromRow;
 
 pub use self::client::Client;
-pub use self::database::Database;
+pub use self::database::{BulkLoad, Database, OnError};
 pub use self::error::{Error, Result};
+pub use self::row::{batches_as, rows_as, FromRow, FromValue, Row};
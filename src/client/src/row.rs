@@ -0,0 +1,201 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Typed access to rows of query results.
+
+use std::any::type_name;
+
+use chrono::{NaiveDate, NaiveDateTime, Utc};
+use common_query::Output;
+use common_recordbatch::RecordBatches;
+use common_time::{Date, DateTime, Timestamp};
+use datatypes::data_type::DataType;
+use datatypes::schema::Schema;
+use datatypes::value::Value;
+use snafu::{OptionExt, ResultExt};
+
+use crate::error::{self, Result};
+
+/// Converts a value of a column to a Rust type.
+pub trait FromValue: Sized {
+    /// Returns [None] if the value can't be converted to `Self`.
+    fn from_value(value: &Value) -> Option<Self>;
+}
+
+/// Types that can be built from a row of query results, usually implemented by
+/// `#[derive(FromRow)]`.
+pub trait FromRow: Sized {
+    fn from_row(row: &Row) -> Result<Self>;
+}
+
+/// A row of query results, whose values are accessed by column names.
+pub struct Row<'a> {
+    schema: &'a Schema,
+    values: Vec<Value>,
+}
+
+impl<'a> Row<'a> {
+    pub fn new(schema: &'a Schema, values: Vec<Value>) -> Self {
+        Self { schema, values }
+    }
+
+    /// Returns the value of `column` as `T`, fails if there's no such column or the value
+    /// can't be converted to `T`.
+    pub fn get<T: FromValue>(&self, column: &str) -> Result<T> {
+        let index = self
+            .schema
+            .column_index_by_name(column)
+            .context(error::ColumnNotFoundSnafu { column })?;
+        let value = &self.values[index];
+        T::from_value(value).with_context(|| error::ColumnTypeMismatchSnafu {
+            column,
+            expected: type_name::<T>(),
+            actual: if value.is_null() {
+                "null".to_string()
+            } else {
+                self.schema.column_schemas()[index]
+                    .data_type
+                    .name()
+                    .to_string()
+            },
+        })
+    }
+}
+
+/// Collects rows of `output` as `T`, the output should be results of a query.
+pub async fn rows_as<T: FromRow>(output: Output) -> Result<Vec<T>> {
+    let batches = match output {
+        Output::RecordBatches(batches) => batches,
+        Output::Stream(stream) => RecordBatches::try_collect(stream)
+            .await
+            .context(error::CollectRecordBatchesSnafu)?,
+        Output::AffectedRows(rows) => {
+            return error::IllegalDatabaseResponseSnafu {
+                err_msg: format!("expect query results, actual affected rows: {rows}"),
+            }
+            .fail()
+        }
+    };
+    batches_as(&batches)
+}
+
+/// Converts rows of `batches` to `T`.
+pub fn batches_as<T: FromRow>(batches: &RecordBatches) -> Result<Vec<T>> {
+    let mut rows = Vec::new();
+    for batch in batches.iter() {
+        for values in batch.rows() {
+            rows.push(T::from_row(&Row::new(&batch.schema, values))?);
+        }
+    }
+    Ok(rows)
+}
+
+impl<T: FromValue> FromValue for Option<T> {
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Null => Some(None),
+            value => T::from_value(value).map(Some),
+        }
+    }
+}
+
+macro_rules! impl_from_value {
+    ($Type: ty, $Variant: ident) => {
+        impl FromValue for $Type {
+            fn from_value(value: &Value) -> Option<Self> {
+                match value {
+                    Value::$Variant(v) => Some(*v),
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+impl_from_value!(bool, Boolean);
+impl_from_value!(u8, UInt8);
+impl_from_value!(u16, UInt16);
+impl_from_value!(u32, UInt32);
+impl_from_value!(u64, UInt64);
+impl_from_value!(i8, Int8);
+impl_from_value!(i16, Int16);
+impl_from_value!(i32, Int32);
+impl_from_value!(i64, Int64);
+impl_from_value!(Date, Date);
+impl_from_value!(DateTime, DateTime);
+impl_from_value!(Timestamp, Timestamp);
+
+impl FromValue for f32 {
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Float32(v) => Some(v.0),
+            _ => None,
+        }
+    }
+}
+
+impl FromValue for f64 {
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Float64(v) => Some(v.0),
+            _ => None,
+        }
+    }
+}
+
+impl FromValue for String {
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::String(v) => Some(v.as_utf8().to_string()),
+            _ => None,
+        }
+    }
+}
+
+impl FromValue for Vec<u8> {
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Binary(v) => Some(v.to_vec()),
+            _ => None,
+        }
+    }
+}
+
+impl FromValue for NaiveDate {
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Date(v) => v.to_chrono_date(),
+            _ => None,
+        }
+    }
+}
+
+impl FromValue for NaiveDateTime {
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::DateTime(v) => v.to_chrono_datetime(),
+            Value::Timestamp(v) => v.to_chrono_datetime().single().map(|v| v.naive_utc()),
+            _ => None,
+        }
+    }
+}
+
+impl FromValue for chrono::DateTime<Utc> {
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Timestamp(v) => v.to_chrono_datetime().single(),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,176 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use chrono::{NaiveDate, NaiveDateTime, Utc};
+use client::{batches_as, rows_as, Error, FromRow};
+use common_query::Output;
+use common_recordbatch::{RecordBatch, RecordBatches};
+use common_time::{Date, DateTime, Timestamp};
+use datatypes::data_type::{ConcreteDataType, DataType};
+use datatypes::schema::{ColumnSchema, Schema};
+use datatypes::value::Value;
+
+#[derive(Debug, PartialEq, FromRow)]
+struct AllTypes {
+    boolean: bool,
+    uint8: u8,
+    uint16: u16,
+    uint32: u32,
+    uint64: u64,
+    int8: i8,
+    int16: i16,
+    int32: i32,
+    int64: i64,
+    float32: f32,
+    float64: f64,
+    string: String,
+    binary: Vec<u8>,
+    date: Date,
+    datetime: DateTime,
+    ts: Timestamp,
+    chrono_date: NaiveDate,
+    chrono_datetime: NaiveDateTime,
+    chrono_ts: chrono::DateTime<Utc>,
+    nullable: Option<i64>,
+}
+
+const TS_MILLIS: i64 = 1672201025000;
+
+fn new_batches(nullable: Value) -> RecordBatches {
+    let columns = vec![
+        ("boolean", ConcreteDataType::boolean_datatype(), Value::from(true)),
+        ("uint8", ConcreteDataType::uint8_datatype(), Value::from(u8::MAX)),
+        ("uint16", ConcreteDataType::uint16_datatype(), Value::from(u16::MAX)),
+        ("uint32", ConcreteDataType::uint32_datatype(), Value::from(u32::MAX)),
+        ("uint64", ConcreteDataType::uint64_datatype(), Value::from(u64::MAX)),
+        ("int8", ConcreteDataType::int8_datatype(), Value::from(i8::MIN)),
+        ("int16", ConcreteDataType::int16_datatype(), Value::from(i16::MIN)),
+        ("int32", ConcreteDataType::int32_datatype(), Value::from(i32::MIN)),
+        ("int64", ConcreteDataType::int64_datatype(), Value::from(i64::MIN)),
+        ("float32", ConcreteDataType::float32_datatype(), Value::from(1.5f32)),
+        ("float64", ConcreteDataType::float64_datatype(), Value::from(2.5f64)),
+        ("string", ConcreteDataType::string_datatype(), Value::from("host1")),
+        ("binary", ConcreteDataType::binary_datatype(), Value::from(vec![1u8, 2])),
+        ("date", ConcreteDataType::date_datatype(), Value::Date(Date::new(19354))),
+        (
+            "datetime",
+            ConcreteDataType::datetime_datatype(),
+            Value::DateTime(DateTime::new(1672201025)),
+        ),
+        (
+            "ts",
+            ConcreteDataType::timestamp_millisecond_datatype(),
+            Value::Timestamp(Timestamp::new_millisecond(TS_MILLIS)),
+        ),
+        ("chrono_date", ConcreteDataType::date_datatype(), Value::Date(Date::new(19354))),
+        (
+            "chrono_datetime",
+            ConcreteDataType::datetime_datatype(),
+            Value::DateTime(DateTime::new(1672201025)),
+        ),
+        (
+            "chrono_ts",
+            ConcreteDataType::timestamp_millisecond_datatype(),
+            Value::Timestamp(Timestamp::new_millisecond(TS_MILLIS)),
+        ),
+        ("nullable", ConcreteDataType::int64_datatype(), nullable),
+    ];
+
+    let schema = Arc::new(Schema::new(
+        columns
+            .iter()
+            .map(|(name, data_type, _)| ColumnSchema::new(*name, data_type.clone(), true))
+            .collect(),
+    ));
+    let vectors = columns.iter().map(|(_, data_type, value)| {
+        let mut vector = data_type.create_mutable_vector(1);
+        vector.push_value_ref(value.as_value_ref());
+        vector.to_vector()
+    });
+    let batch = RecordBatch::new(schema.clone(), vectors).unwrap();
+    RecordBatches::try_new(schema, vec![batch]).unwrap()
+}
+
+#[tokio::test]
+async fn test_rows_as_all_types() {
+    let output = Output::RecordBatches(new_batches(Value::from(42i64)));
+    let rows: Vec<AllTypes> = rows_as(output).await.unwrap();
+
+    let date = NaiveDate::from_ymd_opt(2022, 12, 28).unwrap();
+    let datetime = date.and_hms_opt(4, 17, 5).unwrap();
+    let expected = AllTypes {
+        boolean: true,
+        uint8: u8::MAX,
+        uint16: u16::MAX,
+        uint32: u32::MAX,
+        uint64: u64::MAX,
+        int8: i8::MIN,
+        int16: i16::MIN,
+        int32: i32::MIN,
+        int64: i64::MIN,
+        float32: 1.5,
+        float64: 2.5,
+        string: "host1".to_string(),
+        binary: vec![1, 2],
+        date: Date::new(19354),
+        datetime: DateTime::new(1672201025),
+        ts: Timestamp::new_millisecond(TS_MILLIS),
+        chrono_date: date,
+        chrono_datetime: datetime,
+        chrono_ts: chrono::DateTime::from_utc(datetime, Utc),
+        nullable: Some(42),
+    };
+    assert_eq!(vec![expected], rows);
+
+    let rows: Vec<AllTypes> = batches_as(&new_batches(Value::Null)).unwrap();
+    assert_eq!(None, rows[0].nullable);
+
+    let result = rows_as::<AllTypes>(Output::AffectedRows(1)).await;
+    assert!(matches!(result, Err(Error::IllegalDatabaseResponse { .. })));
+}
+
+#[derive(Debug, FromRow)]
+struct MissingColumn {
+    #[allow(dead_code)]
+    host: String,
+}
+
+#[derive(Debug, FromRow)]
+struct WrongType {
+    #[allow(dead_code)]
+    int64: i32,
+}
+
+#[derive(Debug, FromRow)]
+struct NotNullable {
+    #[allow(dead_code)]
+    nullable: i64,
+}
+
+#[test]
+fn test_rows_as_errors() {
+    let batches = new_batches(Value::Null);
+
+    let err = batches_as::<MissingColumn>(&batches).unwrap_err();
+    assert!(matches!(err, Error::ColumnNotFound { .. }), "{err:?}");
+    assert_eq!("Column host not found in query results", err.to_string());
+
+    let err = batches_as::<WrongType>(&batches).unwrap_err();
+    assert_eq!("Failed to read column int64 of type Int64 as i32", err.to_string());
+
+    let err = batches_as::<NotNullable>(&batches).unwrap_err();
+    assert_eq!("Failed to read column nullable of type null as i64", err.to_string());
+}
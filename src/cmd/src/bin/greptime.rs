@@ -157,6 +157,8 @@ async fn main() -> Result<()> {
         result = app.run() => {
             if let Err(err) = result {
                 error!(err; "Fatal error occurs!");
+                // Exits with a non-zero code, e.g. when `cli verify-storage` finds problems.
+                return Err(err);
             }
         }
         _ = tokio::signal::ctrl_c() => {
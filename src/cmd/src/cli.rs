@@ -15,19 +15,25 @@
 mod cmd;
 mod helper;
 mod repl;
+mod verify;
 
 use clap::Parser;
 pub use repl::Repl;
 
+use crate::cli::verify::VerifyStorageCommand;
 use crate::error::Result;
 
-pub struct Instance {
-    repl: Repl,
+pub enum Instance {
+    Repl(Repl),
+    VerifyStorage(VerifyStorageCommand),
 }
 
 impl Instance {
     pub async fn run(&mut self) -> Result<()> {
-        self.repl.run().await
+        match self {
+            Instance::Repl(repl) => repl.run().await,
+            Instance::VerifyStorage(cmd) => cmd.run().await,
+        }
     }
 
     pub async fn stop(&self) -> Result<()> {
@@ -50,12 +56,14 @@ impl Command {
 #[derive(Parser)]
 enum SubCommand {
     Attach(AttachCommand),
+    VerifyStorage(VerifyStorageCommand),
 }
 
 impl SubCommand {
     async fn build(self) -> Result<Instance> {
         match self {
             SubCommand::Attach(cmd) => cmd.build().await,
+            SubCommand::VerifyStorage(cmd) => Ok(Instance::VerifyStorage(cmd)),
         }
     }
 }
@@ -73,6 +81,6 @@ pub(crate) struct AttachCommand {
 impl AttachCommand {
     async fn build(self) -> Result<Instance> {
         let repl = Repl::try_new(&self).await?;
-        Ok(Instance { repl })
+        Ok(Instance::Repl(repl))
     }
 }
@@ -0,0 +1,113 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap::Parser;
+use client::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
+use hyper::{Client, Uri};
+use servers::query_handler::StorageVerifyResult;
+use snafu::{ensure, ResultExt};
+
+use crate::error::{
+    InvalidUriSnafu, ParseVerifyReportSnafu, RequestHttpSnafu, Result, StorageProblemsFoundSnafu,
+    VerifyStorageSnafu, WriteFileSnafu,
+};
+
+const VERIFY_PATH: &str = "/v1/admin/verify";
+
+/// Checks that the SST files referenced by the manifests of the regions of a table exist in
+/// the object store with the expected sizes, through the HTTP API of a standalone server.
+/// Nothing is modified. Fails if any problem is found.
+#[derive(Debug, Parser)]
+pub(crate) struct VerifyStorageCommand {
+    #[clap(long, default_value = "127.0.0.1:4000")]
+    pub(crate) http_addr: String,
+    #[clap(long, default_value = DEFAULT_CATALOG_NAME)]
+    pub(crate) catalog: String,
+    #[clap(long, default_value = DEFAULT_SCHEMA_NAME)]
+    pub(crate) schema: String,
+    #[clap(long)]
+    pub(crate) table: String,
+    /// File to write the report in JSON to.
+    #[clap(long)]
+    pub(crate) report: Option<String>,
+}
+
+impl VerifyStorageCommand {
+    pub(crate) async fn run(&self) -> Result<()> {
+        let url = format!(
+            "http://{}{VERIFY_PATH}?catalog_name={}&schema_name={}&table_name={}",
+            self.http_addr, self.catalog, self.schema, self.table
+        );
+        let uri = url.parse::<Uri>().context(InvalidUriSnafu { url: &url })?;
+        let response = Client::new()
+            .get(uri)
+            .await
+            .context(RequestHttpSnafu { url: &url })?;
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body())
+            .await
+            .context(RequestHttpSnafu { url: &url })?;
+        ensure!(
+            status.is_success(),
+            VerifyStorageSnafu {
+                reason: format!("{status}, {}", String::from_utf8_lossy(&body)),
+            }
+        );
+
+        if let Some(path) = &self.report {
+            std::fs::write(path, &body).context(WriteFileSnafu { path })?;
+        }
+        let result =
+            serde_json::from_slice::<StorageVerifyResult>(&body).context(ParseVerifyReportSnafu)?;
+        print_summary(&result);
+
+        ensure!(
+            !result.has_problems,
+            StorageProblemsFoundSnafu {
+                table: format!("{}.{}.{}", self.catalog, self.schema, self.table),
+            }
+        );
+        Ok(())
+    }
+}
+
+#[allow(clippy::print_stdout)]
+fn print_summary(result: &StorageVerifyResult) {
+    for region in &result.regions {
+        if let Some(reason) = &region.skipped {
+            println!("Region {}: skipped, {reason}", region.region_name);
+            continue;
+        }
+        println!(
+            "Region {}: {} files checked, {} missing, {} size mismatches, {} orphans",
+            region.region_name,
+            region.checked_files,
+            region.missing_files.len(),
+            region.size_mismatches.len(),
+            region.orphan_files.len()
+        );
+        let files = region
+            .missing_files
+            .iter()
+            .map(|file| ("missing", file))
+            .chain(region.size_mismatches.iter().map(|file| ("size mismatch", file)))
+            .chain(region.orphan_files.iter().map(|file| ("orphan", file)));
+        for (problem, file) in files {
+            println!(
+                "  {problem}: {}, expected size: {:?}, actual size: {:?}",
+                file.file_name, file.expected_size, file.actual_size
+            );
+        }
+    }
+}
@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::sync::Arc;
+
 use clap::Parser;
 use common_telemetry::logging;
 use datanode::datanode::{
@@ -21,18 +23,66 @@ use meta_client::MetaClientOptions;
 use servers::Mode;
 use snafu::ResultExt;
 
-use crate::error::{Error, MissingConfigSnafu, Result, ShutdownDatanodeSnafu, StartDatanodeSnafu};
+use crate::error::{
+    Error, MissingConfigSnafu, ReloadDatanodeSnafu, Result, ShutdownDatanodeSnafu,
+    StartDatanodeSnafu,
+};
+use crate::frontend::load_frontend_plugins;
 use crate::toml_loader;
 
 pub struct Instance {
     datanode: Datanode,
+    /// Command to build options again on reload.
+    cmd: StartCommand,
 }
 
 impl Instance {
     pub async fn run(&mut self) -> Result<()> {
+        self.reload_on_sighup();
         self.datanode.start().await.context(StartDatanodeSnafu)
     }
 
+    /// Builds options from the command and config file again and applies the ones that
+    /// could change at runtime.
+    pub fn reload(&self) -> Result<()> {
+        let opts = DatanodeOptions::try_from(self.cmd.clone())?;
+        self.datanode
+            .config_reloader()
+            .reload(opts)
+            .context(ReloadDatanodeSnafu)?;
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    fn reload_on_sighup(&self) {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut hangup = match signal(SignalKind::hangup()) {
+            Ok(hangup) => hangup,
+            Err(e) => {
+                logging::error!("Failed to listen to SIGHUP, reload is disabled: {}", e);
+                return;
+            }
+        };
+        let cmd = self.cmd.clone();
+        let reloader = self.datanode.config_reloader();
+        tokio::spawn(async move {
+            while hangup.recv().await.is_some() {
+                logging::info!("Received SIGHUP, reloading datanode options");
+                let result = DatanodeOptions::try_from(cmd.clone()).and_then(|opts| {
+                    reloader.reload(opts).context(ReloadDatanodeSnafu)?;
+                    Ok(())
+                });
+                if let Err(e) = result {
+                    logging::error!(e; "Failed to reload datanode options, keep the running ones");
+                }
+            }
+        });
+    }
+
+    #[cfg(not(unix))]
+    fn reload_on_sighup(&self) {}
+
     pub async fn stop(&self) -> Result<()> {
         self.datanode
             .shutdown()
@@ -66,7 +116,7 @@ impl SubCommand {
     }
 }
 
-#[derive(Debug, Parser, Default)]
+#[derive(Debug, Parser, Default, Clone)]
 struct StartCommand {
     #[clap(long)]
     node_id: Option<u64>,
@@ -77,6 +127,10 @@ struct StartCommand {
     #[clap(long)]
     mysql_addr: Option<String>,
     #[clap(long)]
+    influxdb_addr: Option<String>,
+    #[clap(long)]
+    opentsdb_http_addr: Option<String>,
+    #[clap(long)]
     metasrv_addr: Option<String>,
     #[clap(short, long)]
     config_file: Option<String>,
@@ -86,19 +140,27 @@ struct StartCommand {
     wal_dir: Option<String>,
     #[clap(long)]
     procedure_dir: Option<String>,
+    #[clap(long)]
+    user_provider: Option<String>,
 }
 
 impl StartCommand {
     async fn build(self) -> Result<Instance> {
         logging::info!("Datanode start command: {:#?}", self);
 
-        let opts: DatanodeOptions = self.try_into()?;
+        let plugins = Arc::new(load_frontend_plugins(&self.user_provider)?);
+        let opts: DatanodeOptions = self.clone().try_into()?;
 
         logging::info!("Datanode options: {:#?}", opts);
 
-        let datanode = Datanode::new(opts).await.context(StartDatanodeSnafu)?;
+        let datanode = Datanode::with_plugins(opts, plugins)
+            .await
+            .context(StartDatanodeSnafu)?;
 
-        Ok(Instance { datanode })
+        Ok(Instance {
+            datanode,
+            cmd: self,
+        })
     }
 }
 
@@ -123,6 +185,14 @@ impl TryFrom<StartCommand> for DatanodeOptions {
             opts.mysql_addr = addr;
         }
 
+        if cmd.influxdb_addr.is_some() {
+            opts.influxdb_addr = cmd.influxdb_addr;
+        }
+
+        if cmd.opentsdb_http_addr.is_some() {
+            opts.opentsdb_http_addr = cmd.opentsdb_http_addr;
+        }
+
         if let Some(node_id) = cmd.node_id {
             opts.node_id = Some(node_id);
         }
@@ -146,11 +216,11 @@ impl TryFrom<StartCommand> for DatanodeOptions {
         }
 
         if let Some(data_dir) = cmd.data_dir {
-            opts.storage = ObjectStoreConfig::File(FileConfig { data_dir });
+            opts.storage.store = ObjectStoreConfig::File(FileConfig { data_dir });
         }
 
         if let Some(wal_dir) = cmd.wal_dir {
-            opts.wal.dir = wal_dir;
+            opts.wal.dir = wal_dir.into();
         }
         if let Some(procedure_dir) = cmd.procedure_dir {
             opts.procedure = Some(ProcedureConfig::from_file_path(procedure_dir));
@@ -166,8 +236,11 @@ mod tests {
     use std::io::Write;
     use std::time::Duration;
 
+    use common_base::readable_size::ReadableSize;
     use common_test_util::temp_dir::create_named_temp_file;
-    use datanode::datanode::{CompactionConfig, ObjectStoreConfig};
+    use datanode::datanode::{
+        CompactionConfig, CompactionFairness, FlushConfig, ObjectStoreConfig,
+    };
     use servers::Mode;
 
     use super::*;
@@ -184,6 +257,8 @@ mod tests {
             rpc_runtime_size = 8
             mysql_addr = "127.0.0.1:4406"
             mysql_runtime_size = 2
+            influxdb_addr = "127.0.0.1:4001"
+            opentsdb_http_addr = "127.0.0.1:4242"
 
             [meta_client_options]
             metasrv_addrs = ["127.0.0.1:3002"]
@@ -207,6 +282,12 @@ mod tests {
             max_inflight_tasks = 4
             max_files_in_level0 = 8
             max_purge_tasks = 32
+            fairness = "aging"
+            aging_step = 2
+
+            [flush]
+            memtable_flush_size = "16MB"
+            total_memtable_budget = "1GB"
         "#;
         write!(file, "{}", toml_str).unwrap();
 
@@ -218,8 +299,14 @@ mod tests {
         assert_eq!("127.0.0.1:3001".to_string(), options.rpc_addr);
         assert_eq!("127.0.0.1:4406".to_string(), options.mysql_addr);
         assert_eq!(2, options.mysql_runtime_size);
+        assert_eq!(Some("127.0.0.1:4001".to_string()), options.influxdb_addr);
+        assert_eq!(
+            Some("127.0.0.1:4242".to_string()),
+            options.opentsdb_http_addr
+        );
         assert_eq!(Some(42), options.node_id);
 
+        assert_eq!(&["/tmp/greptimedb/wal".to_string()], options.wal.dir.dirs());
         assert_eq!(Duration::from_secs(600), options.wal.purge_interval);
         assert_eq!(1024 * 1024 * 1024, options.wal.file_size.0);
         assert_eq!(1024 * 1024 * 1024 * 50, options.wal.purge_threshold.0);
@@ -237,12 +324,13 @@ mod tests {
         assert_eq!(3000, timeout_millis);
         assert!(tcp_nodelay);
 
-        match options.storage {
+        match options.storage.store {
             ObjectStoreConfig::File(FileConfig { data_dir }) => {
                 assert_eq!("/tmp/greptimedb/data/".to_string(), data_dir)
             }
             ObjectStoreConfig::S3 { .. } => unreachable!(),
             ObjectStoreConfig::Oss { .. } => unreachable!(),
+            ObjectStoreConfig::Azblob { .. } => unreachable!(),
         };
 
         assert_eq!(
@@ -250,9 +338,22 @@ mod tests {
                 max_inflight_tasks: 4,
                 max_files_in_level0: 8,
                 max_purge_tasks: 32,
+                max_level: 1,
+                fairness: CompactionFairness::Aging,
+                aging_step: 2,
+                bloom_filter_fpp: None,
+                maintenance_window: None,
             },
             options.compaction
         );
+        assert_eq!(
+            FlushConfig {
+                memtable_flush_size: ReadableSize::mb(16),
+                total_memtable_budget: Some(ReadableSize::gb(1)),
+                ..Default::default()
+            },
+            options.flush
+        );
     }
 
     #[test]
@@ -26,6 +26,12 @@ pub enum Error {
         source: datanode::error::Error,
     },
 
+    #[snafu(display("Failed to reload datanode options, source: {}", source))]
+    ReloadDatanode {
+        #[snafu(backtrace)]
+        source: datanode::error::Error,
+    },
+
     #[snafu(display("Failed to shutdown datanode, source: {}", source))]
     ShutdownDatanode {
         #[snafu(backtrace)]
@@ -62,6 +68,26 @@ pub enum Error {
         source: meta_srv::error::Error,
     },
 
+    #[snafu(display("Failed to take or restore metadata snapshot, source: {}", source))]
+    MetaSnapshot {
+        #[snafu(backtrace)]
+        source: meta_srv::error::Error,
+    },
+
+    #[snafu(display("Failed to read file: {}, source: {}", path, source))]
+    ReadFile {
+        path: String,
+        source: std::io::Error,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to write file: {}, source: {}", path, source))]
+    WriteFile {
+        path: String,
+        source: std::io::Error,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Failed to read config file: {}, source: {}", path, source))]
     ReadConfig {
         path: String,
@@ -152,6 +178,32 @@ pub enum Error {
         #[snafu(backtrace)]
         source: substrait::error::Error,
     },
+
+    #[snafu(display("Invalid URI: {}, source: {}", url, source))]
+    InvalidUri {
+        url: String,
+        source: hyper::http::uri::InvalidUri,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to request {}, source: {}", url, source))]
+    RequestHttp {
+        url: String,
+        source: hyper::Error,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to verify storage: {}", reason))]
+    VerifyStorage { reason: String, backtrace: Backtrace },
+
+    #[snafu(display("Failed to parse storage verify report, source: {}", source))]
+    ParseVerifyReport {
+        source: serde_json::Error,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Storage problems found in table {}", table))]
+    StorageProblemsFound { table: String, backtrace: Backtrace },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -160,12 +212,15 @@ impl ErrorExt for Error {
     fn status_code(&self) -> StatusCode {
         match self {
             Error::StartDatanode { source } => source.status_code(),
+            Error::ReloadDatanode { source } => source.status_code(),
             Error::StartFrontend { source } => source.status_code(),
             Error::ShutdownDatanode { source } => source.status_code(),
             Error::ShutdownFrontend { source } => source.status_code(),
             Error::StartMetaServer { source } => source.status_code(),
             Error::ShutdownMetaServer { source } => source.status_code(),
             Error::BuildMetaServer { source } => source.status_code(),
+            Error::MetaSnapshot { source } => source.status_code(),
+            Error::ReadFile { .. } | Error::WriteFile { .. } => StatusCode::StorageUnavailable,
             Error::UnsupportedSelectorType { source, .. } => source.status_code(),
             Error::ReadConfig { .. } | Error::ParseConfig { .. } | Error::MissingConfig { .. } => {
                 StatusCode::InvalidArguments
@@ -184,6 +239,11 @@ impl ErrorExt for Error {
                 source.status_code()
             }
             Error::SubstraitEncodeLogicalPlan { source } => source.status_code(),
+            Error::InvalidUri { .. } => StatusCode::InvalidArguments,
+            Error::RequestHttp { .. }
+            | Error::VerifyStorage { .. }
+            | Error::ParseVerifyReport { .. } => StatusCode::Internal,
+            Error::StorageProblemsFound { .. } => StatusCode::StorageUnavailable,
         }
     }
 
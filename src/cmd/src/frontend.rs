@@ -169,7 +169,10 @@ impl TryFrom<StartCommand> for FrontendOptions {
             });
         }
         if let Some(addr) = cmd.prom_addr {
-            opts.prom_options = Some(PromOptions { addr });
+            opts.prom_options = Some(PromOptions {
+                addr,
+                ..Default::default()
+            });
         }
         if let Some(addr) = cmd.postgres_addr {
             opts.postgres_options = Some(PostgresOptions {
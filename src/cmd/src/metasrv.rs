@@ -16,28 +16,36 @@ use clap::Parser;
 use common_telemetry::{info, logging, warn};
 use meta_srv::bootstrap::MetaSrvInstance;
 use meta_srv::metasrv::MetaSrvOptions;
-use snafu::ResultExt;
+use meta_srv::service::store::etcd::EtcdStore;
+use meta_srv::snapshot::Snapshot;
+use snafu::{ensure, ResultExt};
 
 use crate::error::{Error, Result};
 use crate::{error, toml_loader};
 
-pub struct Instance {
-    instance: MetaSrvInstance,
+pub enum Instance {
+    Server(Box<MetaSrvInstance>),
+    Snapshot(SnapshotCommand),
 }
 
 impl Instance {
     pub async fn run(&mut self) -> Result<()> {
-        self.instance
-            .start()
-            .await
-            .context(error::StartMetaServerSnafu)
+        match self {
+            Instance::Server(instance) => {
+                instance.start().await.context(error::StartMetaServerSnafu)
+            }
+            Instance::Snapshot(cmd) => cmd.run().await,
+        }
     }
 
     pub async fn stop(&self) -> Result<()> {
-        self.instance
-            .shutdown()
-            .await
-            .context(error::ShutdownMetaServerSnafu)
+        match self {
+            Instance::Server(instance) => instance
+                .shutdown()
+                .await
+                .context(error::ShutdownMetaServerSnafu),
+            Instance::Snapshot(_) => Ok(()),
+        }
     }
 }
 
@@ -56,12 +64,14 @@ impl Command {
 #[derive(Parser)]
 enum SubCommand {
     Start(StartCommand),
+    Snapshot(SnapshotCommand),
 }
 
 impl SubCommand {
     async fn build(self) -> Result<Instance> {
         match self {
             SubCommand::Start(cmd) => cmd.build().await,
+            SubCommand::Snapshot(cmd) => cmd.build(),
         }
     }
 }
@@ -80,6 +90,8 @@ struct StartCommand {
     selector: Option<String>,
     #[clap(long)]
     use_memory_store: bool,
+    #[clap(long)]
+    deterministic_placement: bool,
 }
 
 impl StartCommand {
@@ -93,7 +105,62 @@ impl StartCommand {
             .await
             .context(error::BuildMetaServerSnafu)?;
 
-        Ok(Instance { instance })
+        Ok(Instance::Server(Box::new(instance)))
+    }
+}
+
+/// Takes a snapshot of the metadata in etcd into a file, or restores it from a file.
+#[derive(Debug, Parser)]
+pub struct SnapshotCommand {
+    #[clap(long, default_value = "127.0.0.1:2379")]
+    store_addr: String,
+    /// File to write the snapshot to.
+    #[clap(short, long)]
+    output: Option<String>,
+    /// File to restore the snapshot from.
+    #[clap(long)]
+    restore: Option<String>,
+    /// Restores even if the store is not empty.
+    #[clap(long)]
+    force: bool,
+}
+
+impl SnapshotCommand {
+    fn build(self) -> Result<Instance> {
+        ensure!(
+            self.output.is_some() != self.restore.is_some(),
+            error::IllegalConfigSnafu {
+                msg: "exactly one of --output and --restore must be specified",
+            }
+        );
+        Ok(Instance::Snapshot(self))
+    }
+
+    async fn run(&self) -> Result<()> {
+        let kv_store = EtcdStore::with_endpoints([&self.store_addr])
+            .await
+            .context(error::BuildMetaServerSnafu)?;
+
+        if let Some(path) = &self.output {
+            let snapshot = Snapshot::take(&kv_store)
+                .await
+                .context(error::MetaSnapshotSnafu)?;
+            std::fs::write(path, snapshot.encode()).context(error::WriteFileSnafu { path })?;
+            info!("Wrote {} keys to snapshot {}", snapshot.kvs.len(), path);
+        } else if let Some(path) = &self.restore {
+            let data = std::fs::read(path).context(error::ReadFileSnafu { path })?;
+            let snapshot = Snapshot::decode(&data).context(error::MetaSnapshotSnafu)?;
+            snapshot
+                .restore(&kv_store, self.force)
+                .await
+                .context(error::MetaSnapshotSnafu)?;
+            info!(
+                "Restored {} keys from snapshot {}",
+                snapshot.kvs.len(),
+                path
+            );
+        }
+        Ok(())
     }
 }
 
@@ -128,6 +195,10 @@ impl TryFrom<StartCommand> for MetaSrvOptions {
             opts.use_memory_store = true;
         }
 
+        if cmd.deterministic_placement {
+            opts.deterministic_placement = true;
+        }
+
         Ok(opts)
     }
 }
@@ -150,6 +221,7 @@ mod tests {
             config_file: None,
             selector: Some("LoadBased".to_string()),
             use_memory_store: false,
+            deterministic_placement: false,
         };
         let options: MetaSrvOptions = cmd.try_into().unwrap();
         assert_eq!("127.0.0.1:3002".to_string(), options.bind_addr);
@@ -158,6 +230,31 @@ mod tests {
         assert_eq!(SelectorType::LoadBased, options.selector);
     }
 
+    #[test]
+    fn test_snapshot_command() {
+        let cmd = SnapshotCommand::try_parse_from(["snapshot", "--output", "/tmp/meta.snap"]);
+        assert!(cmd.unwrap().build().is_ok());
+
+        let cmd =
+            SnapshotCommand::try_parse_from(["snapshot", "--restore", "/tmp/meta.snap", "--force"])
+                .unwrap();
+        assert!(cmd.force);
+        assert!(cmd.build().is_ok());
+
+        let cmd = SnapshotCommand::try_parse_from(["snapshot"]).unwrap();
+        assert!(cmd.build().is_err());
+
+        let cmd = SnapshotCommand::try_parse_from([
+            "snapshot",
+            "--output",
+            "/tmp/meta.snap",
+            "--restore",
+            "/tmp/meta.snap",
+        ])
+        .unwrap();
+        assert!(cmd.build().is_err());
+    }
+
     #[test]
     fn test_read_from_config_file() {
         let mut file = create_named_temp_file();
@@ -178,6 +275,7 @@ mod tests {
             selector: None,
             config_file: Some(file.path().to_str().unwrap().to_string()),
             use_memory_store: false,
+            deterministic_placement: false,
         };
         let options: MetaSrvOptions = cmd.try_into().unwrap();
         assert_eq!("127.0.0.1:3002".to_string(), options.bind_addr);
@@ -186,4 +284,60 @@ mod tests {
         assert_eq!(15, options.datanode_lease_secs);
         assert_eq!(SelectorType::LeaseBased, options.selector);
     }
+
+    #[test]
+    fn test_read_etcd_options_from_config_file() {
+        let mut file = create_named_temp_file();
+        let toml_str = r#"
+            store_addr = "127.0.0.1:2379"
+
+            [etcd]
+            ca_cert_path = "/etc/etcd/ca.pem"
+            client_cert_path = "/etc/etcd/client.pem"
+            client_key_path = "/etc/etcd/client-key.pem"
+            username = "root"
+            password = "secret"
+            connect_timeout_secs = 3
+            keepalive_interval_secs = 30
+
+            [election]
+            lease_ttl_secs = 10
+            renew_interval_secs = 4
+        "#;
+        write!(file, "{}", toml_str).unwrap();
+
+        let cmd = StartCommand {
+            bind_addr: None,
+            server_addr: None,
+            store_addr: None,
+            selector: None,
+            config_file: Some(file.path().to_str().unwrap().to_string()),
+            use_memory_store: false,
+            deterministic_placement: false,
+        };
+        let options: MetaSrvOptions = cmd.try_into().unwrap();
+        let etcd = &options.etcd;
+        assert_eq!(Some("/etc/etcd/ca.pem"), etcd.ca_cert_path.as_deref());
+        assert_eq!(
+            Some("/etc/etcd/client.pem"),
+            etcd.client_cert_path.as_deref()
+        );
+        assert_eq!(
+            Some("/etc/etcd/client-key.pem"),
+            etcd.client_key_path.as_deref()
+        );
+        assert_eq!(Some("root"), etcd.username.as_deref());
+        assert_eq!(Some("secret"), etcd.password.as_deref());
+        assert_eq!(3, etcd.connect_timeout_secs);
+        assert_eq!(30, etcd.keepalive_interval_secs);
+        // Not set in the file.
+        assert_eq!(10, etcd.keepalive_timeout_secs);
+        assert_eq!(10, options.election.lease_ttl_secs);
+        assert_eq!(4, options.election.renew_interval_secs);
+        assert_eq!(500, options.election.lease_safety_margin_millis);
+
+        let toml_str = toml::to_string(&options).unwrap();
+        let decoded: MetaSrvOptions = toml::from_str(&toml_str).unwrap();
+        assert_eq!(options, decoded);
+    }
 }
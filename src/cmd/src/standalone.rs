@@ -16,9 +16,11 @@ use std::sync::Arc;
 
 use clap::Parser;
 use common_base::Plugins;
+use common_grpc::compression::GrpcCompression;
 use common_telemetry::info;
 use datanode::datanode::{
-    CompactionConfig, Datanode, DatanodeOptions, ObjectStoreConfig, ProcedureConfig, WalConfig,
+    CompactionConfig, Datanode, DatanodeOptions, FlushConfig, ProcedureConfig, StorageConfig,
+    WalConfig,
 };
 use datanode::instance::InstanceRef;
 use frontend::frontend::FrontendOptions;
@@ -30,6 +32,7 @@ use frontend::opentsdb::OpentsdbOptions;
 use frontend::postgres::PostgresOptions;
 use frontend::prom::PromOptions;
 use frontend::prometheus::PrometheusOptions;
+use frontend::query_cache::QueryCacheOptions;
 use serde::{Deserialize, Serialize};
 use servers::http::HttpOptions;
 use servers::tls::{TlsMode, TlsOption};
@@ -73,6 +76,7 @@ impl SubCommand {
 pub struct StandaloneOptions {
     pub mode: Mode,
     pub enable_memory_catalog: bool,
+    pub grpc_compression: GrpcCompression,
     pub http_options: Option<HttpOptions>,
     pub grpc_options: Option<GrpcOptions>,
     pub mysql_options: Option<MysqlOptions>,
@@ -81,9 +85,11 @@ pub struct StandaloneOptions {
     pub influxdb_options: Option<InfluxdbOptions>,
     pub prometheus_options: Option<PrometheusOptions>,
     pub prom_options: Option<PromOptions>,
+    pub query_cache: QueryCacheOptions,
     pub wal: WalConfig,
-    pub storage: ObjectStoreConfig,
+    pub storage: StorageConfig,
     pub compaction: CompactionConfig,
+    pub flush: FlushConfig,
     pub procedure: Option<ProcedureConfig>,
 }
 
@@ -92,6 +98,7 @@ impl Default for StandaloneOptions {
         Self {
             mode: Mode::Standalone,
             enable_memory_catalog: false,
+            grpc_compression: GrpcCompression::None,
             http_options: Some(HttpOptions::default()),
             grpc_options: Some(GrpcOptions::default()),
             mysql_options: Some(MysqlOptions::default()),
@@ -100,9 +107,11 @@ impl Default for StandaloneOptions {
             influxdb_options: Some(InfluxdbOptions::default()),
             prometheus_options: Some(PrometheusOptions::default()),
             prom_options: Some(PromOptions::default()),
+            query_cache: QueryCacheOptions::default(),
             wal: WalConfig::default(),
-            storage: ObjectStoreConfig::default(),
+            storage: StorageConfig::default(),
             compaction: CompactionConfig::default(),
+            flush: FlushConfig::default(),
             procedure: None,
         }
     }
@@ -112,6 +121,7 @@ impl StandaloneOptions {
     fn frontend_options(self) -> FrontendOptions {
         FrontendOptions {
             mode: self.mode,
+            grpc_compression: self.grpc_compression,
             http_options: self.http_options,
             grpc_options: self.grpc_options,
             mysql_options: self.mysql_options,
@@ -121,6 +131,7 @@ impl StandaloneOptions {
             prometheus_options: self.prometheus_options,
             prom_options: self.prom_options,
             meta_client_options: None,
+            query_cache: self.query_cache,
         }
     }
 
@@ -130,6 +141,7 @@ impl StandaloneOptions {
             wal: self.wal,
             storage: self.storage,
             compaction: self.compaction,
+            flush: self.flush,
             procedure: self.procedure,
             ..Default::default()
         }
@@ -226,6 +238,7 @@ impl StartCommand {
             .context(StartDatanodeSnafu)?;
 
         let mut frontend = build_frontend(plugins.clone(), datanode.get_instance()).await?;
+        frontend.set_query_cache(&fe_opts.query_cache);
 
         frontend
             .build_servers(&fe_opts, plugins)
@@ -292,7 +305,10 @@ impl TryFrom<StartCommand> for FrontendOptions {
         }
 
         if let Some(addr) = cmd.prom_addr {
-            opts.prom_options = Some(PromOptions { addr })
+            opts.prom_options = Some(PromOptions {
+                addr,
+                ..Default::default()
+            })
         }
 
         if let Some(addr) = cmd.postgres_addr {
@@ -382,6 +398,7 @@ mod tests {
             fe_opts.mysql_options.as_ref().unwrap().reject_no_database
         );
         assert!(fe_opts.influxdb_options.as_ref().unwrap().enable);
+        assert_eq!(QueryCacheOptions::default(), fe_opts.query_cache);
     }
 
     #[tokio::test]
@@ -0,0 +1,373 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use arrow::array::new_null_array;
+use arrow::compute::{can_cast_types, cast_with_options, CastOptions};
+use arrow::datatypes::{DataType, Schema, SchemaRef, TimeUnit};
+use arrow::record_batch::RecordBatch;
+use snafu::{ensure, ResultExt};
+
+use crate::error::{self, Result};
+
+/// Where a column of the table is read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ColumnSource {
+    /// The column at `index` of the file, which is cast to the type of the table
+    /// column if `cast` is set.
+    File { index: usize, cast: bool },
+    /// The column is missing in the file, so it's filled with nulls.
+    Null,
+}
+
+/// Maps the columns of a file to the columns of a table by name.
+///
+/// The columns only in the file are ignored, and the nullable columns only in the table
+/// are filled with nulls. A column of a different type is coerced if the coercion is
+/// lossless, e.g. from int32 to int64, or from seconds to milliseconds. The types of text
+/// files (CSV and JSON) are inferred, so their columns can be coerced to any type their
+/// values fit in, which is checked while mapping the batches.
+#[derive(Debug)]
+pub struct ColumnMapping {
+    file: String,
+    table_schema: SchemaRef,
+    sources: Vec<ColumnSource>,
+    ignored_columns: Vec<String>,
+}
+
+impl ColumnMapping {
+    pub fn try_new(
+        file: &str,
+        file_schema: &Schema,
+        table_schema: SchemaRef,
+        case_insensitive: bool,
+        is_text: bool,
+    ) -> Result<Self> {
+        let mut sources = Vec::with_capacity(table_schema.fields().len());
+        let mut matched = vec![false; file_schema.fields().len()];
+        for field in table_schema.fields() {
+            let Some(index) = find_column(file_schema, field.name(), case_insensitive) else {
+                ensure!(
+                    field.is_nullable(),
+                    error::MissingColumnSnafu {
+                        column: field.name(),
+                        file,
+                    }
+                );
+                sources.push(ColumnSource::Null);
+                continue;
+            };
+            matched[index] = true;
+
+            let from = file_schema.field(index).data_type();
+            let to = field.data_type();
+            let cast = from != to;
+            ensure!(
+                !cast || is_lossless_coercion(from, to) || (is_text && can_cast_types(from, to)),
+                error::IncompatibleColumnTypeSnafu {
+                    column: field.name(),
+                    from: from.to_string(),
+                    to: to.to_string(),
+                    file,
+                }
+            );
+            sources.push(ColumnSource::File { index, cast });
+        }
+
+        let ignored_columns = file_schema
+            .fields()
+            .iter()
+            .zip(matched)
+            .filter(|(_, matched)| !matched)
+            .map(|(field, _)| field.name().clone())
+            .collect();
+
+        Ok(Self {
+            file: file.to_string(),
+            table_schema,
+            sources,
+            ignored_columns,
+        })
+    }
+
+    /// Returns the names of the columns only in the file.
+    pub fn ignored_columns(&self) -> &[String] {
+        &self.ignored_columns
+    }
+
+    /// Returns the number of columns coerced to the types of the table.
+    pub fn coerced_columns(&self) -> usize {
+        self.sources
+            .iter()
+            .filter(|source| matches!(source, ColumnSource::File { cast: true, .. }))
+            .count()
+    }
+
+    /// Maps a batch read from the file to a batch of the table schema.
+    pub fn map(&self, batch: &RecordBatch) -> Result<RecordBatch> {
+        // Fails instead of turning the values that don't fit into nulls.
+        let options = CastOptions { safe: false };
+        let columns =
+            self.table_schema
+                .fields()
+                .iter()
+                .zip(&self.sources)
+                .map(|(field, source)| match source {
+                    ColumnSource::File { index, cast: false } => Ok(batch.column(*index).clone()),
+                    ColumnSource::File { index, cast: true } => {
+                        cast_with_options(batch.column(*index), field.data_type(), &options)
+                            .context(error::CoerceColumnSnafu {
+                                column: field.name(),
+                                file: &self.file,
+                            })
+                    }
+                    ColumnSource::Null => Ok(new_null_array(field.data_type(), batch.num_rows())),
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+        RecordBatch::try_new(self.table_schema.clone(), columns)
+            .context(error::MapColumnsSnafu { file: &self.file })
+    }
+}
+
+/// Returns the index of the column `name`, an exact match is preferred over a case
+/// insensitive one.
+fn find_column(schema: &Schema, name: &str, case_insensitive: bool) -> Option<usize> {
+    schema.index_of(name).ok().or_else(|| {
+        case_insensitive
+            .then(|| {
+                schema
+                    .fields()
+                    .iter()
+                    .position(|field| field.name().eq_ignore_ascii_case(name))
+            })
+            .flatten()
+    })
+}
+
+fn is_lossless_coercion(from: &DataType, to: &DataType) -> bool {
+    use DataType::*;
+
+    match (from, to) {
+        (Null, _)
+        | (Int8, Int16 | Int32 | Int64 | Float32 | Float64)
+        | (Int16, Int32 | Int64 | Float32 | Float64)
+        | (Int32, Int64 | Float64)
+        | (UInt8, UInt16 | UInt32 | UInt64 | Int16 | Int32 | Int64 | Float32 | Float64)
+        | (UInt16, UInt32 | UInt64 | Int32 | Int64 | Float32 | Float64)
+        | (UInt32, UInt64 | Int64 | Float64)
+        | (Float32, Float64)
+        | (Utf8, LargeUtf8)
+        | (LargeUtf8, Utf8)
+        | (Binary, LargeBinary)
+        | (LargeBinary, Binary) => true,
+        // Only converting to a finer unit keeps the precision.
+        (Timestamp(from, _), Timestamp(to, _)) => unit_rank(to) >= unit_rank(from),
+        _ => false,
+    }
+}
+
+fn unit_rank(unit: &TimeUnit) -> u8 {
+    match unit {
+        TimeUnit::Second => 0,
+        TimeUnit::Millisecond => 1,
+        TimeUnit::Microsecond => 2,
+        TimeUnit::Nanosecond => 3,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::array::{
+        Array, Float64Array, Int32Array, Int64Array, StringArray, TimestampMillisecondArray,
+        TimestampSecondArray,
+    };
+    use arrow::datatypes::Field;
+
+    use super::*;
+
+    fn table_schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("host", DataType::Utf8, true),
+            Field::new("cpu", DataType::Float64, true),
+            Field::new("count", DataType::Int64, true),
+            Field::new(
+                "ts",
+                DataType::Timestamp(TimeUnit::Millisecond, None),
+                false,
+            ),
+        ]))
+    }
+
+    #[test]
+    fn test_map_by_name() {
+        // The columns are reordered, `cpu` is missing and `extra` is not in the table.
+        let file_schema = Arc::new(Schema::new(vec![
+            Field::new("ts", DataType::Timestamp(TimeUnit::Second, None), false),
+            Field::new("extra", DataType::Utf8, true),
+            Field::new("count", DataType::Int32, true),
+            Field::new("host", DataType::Utf8, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            file_schema.clone(),
+            vec![
+                Arc::new(TimestampSecondArray::from(vec![1, 2])),
+                Arc::new(StringArray::from(vec!["a", "b"])),
+                Arc::new(Int32Array::from(vec![Some(10), None])),
+                Arc::new(StringArray::from(vec!["host1", "host2"])),
+            ],
+        )
+        .unwrap();
+
+        let mapping =
+            ColumnMapping::try_new("a.parquet", &file_schema, table_schema(), false, false)
+                .unwrap();
+        assert_eq!(&["extra".to_string()], mapping.ignored_columns());
+        assert_eq!(2, mapping.coerced_columns());
+
+        let mapped = mapping.map(&batch).unwrap();
+        assert_eq!(table_schema(), mapped.schema());
+        let hosts = mapped
+            .column(0)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!("host2", hosts.value(1));
+        let cpus = mapped
+            .column(1)
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .unwrap();
+        assert_eq!(2, cpus.null_count());
+        let counts = mapped
+            .column(2)
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap();
+        assert_eq!(10, counts.value(0));
+        assert!(counts.is_null(1));
+        let ts = mapped
+            .column(3)
+            .as_any()
+            .downcast_ref::<TimestampMillisecondArray>()
+            .unwrap();
+        assert_eq!(2000, ts.value(1));
+    }
+
+    #[test]
+    fn test_map_case_insensitive() {
+        let file_schema = Schema::new(vec![
+            Field::new("HOST", DataType::Utf8, true),
+            Field::new(
+                "Ts",
+                DataType::Timestamp(TimeUnit::Millisecond, None),
+                false,
+            ),
+        ]);
+
+        let err = ColumnMapping::try_new("a.parquet", &file_schema, table_schema(), false, false)
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Column ts is not nullable but missing in file a.parquet"),
+            "{err}"
+        );
+
+        let mapping =
+            ColumnMapping::try_new("a.parquet", &file_schema, table_schema(), true, false).unwrap();
+        assert!(mapping.ignored_columns().is_empty());
+        assert_eq!(0, mapping.coerced_columns());
+    }
+
+    #[test]
+    fn test_reject_lossy_coercion() {
+        let lossy = [
+            ("count", DataType::Float64),
+            ("ts", DataType::Timestamp(TimeUnit::Nanosecond, None)),
+        ];
+        for (column, data_type) in lossy {
+            let file_schema = Schema::new(vec![
+                Field::new("count", DataType::Int64, true),
+                Field::new(
+                    "ts",
+                    DataType::Timestamp(TimeUnit::Millisecond, None),
+                    false,
+                ),
+            ]);
+            let mut fields = file_schema.fields().clone();
+            let index = file_schema.index_of(column).unwrap();
+            fields[index] = Field::new(column, data_type.clone(), true);
+            let file_schema = Schema::new(fields);
+
+            let err =
+                ColumnMapping::try_new("a.parquet", &file_schema, table_schema(), false, false)
+                    .unwrap_err();
+            assert!(
+                err.to_string().contains(&format!(
+                    "Cannot coerce column {column} from type {data_type}"
+                )),
+                "{err}"
+            );
+            assert!(err.to_string().contains("in file a.parquet"), "{err}");
+        }
+    }
+
+    #[test]
+    fn test_coerce_text_columns() {
+        // The types inferred from a text file may be wider than the table types.
+        let table_schema = Arc::new(Schema::new(vec![Field::new(
+            "count",
+            DataType::Int32,
+            true,
+        )]));
+        let file_schema = Arc::new(Schema::new(vec![Field::new(
+            "count",
+            DataType::Int64,
+            true,
+        )]));
+        assert!(
+            ColumnMapping::try_new("a.csv", &file_schema, table_schema.clone(), false, false)
+                .is_err()
+        );
+        let mapping =
+            ColumnMapping::try_new("a.csv", &file_schema, table_schema, false, true).unwrap();
+
+        let batch = RecordBatch::try_new(
+            file_schema.clone(),
+            vec![Arc::new(Int64Array::from(vec![1, 2]))],
+        )
+        .unwrap();
+        let mapped = mapping.map(&batch).unwrap();
+        let counts = mapped
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int32Array>()
+            .unwrap();
+        assert_eq!(2, counts.value(1));
+
+        // The values that don't fit are rejected.
+        let batch = RecordBatch::try_new(
+            file_schema,
+            vec![Arc::new(Int64Array::from(vec![1, i64::MAX]))],
+        )
+        .unwrap();
+        let err = mapping.map(&batch).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Failed to coerce column count in file a.csv"),
+            "{err}"
+        );
+    }
+}
@@ -0,0 +1,68 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+/// Compression algorithms recognized from file extensions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    Gzip,
+    Bzip2,
+    Xz,
+    Zstd,
+    Snappy,
+}
+
+impl Compression {
+    /// Classifies the compression of a file by the extension of its name,
+    /// e.g. `data.csv.gz` is [Compression::Gzip]. Returns `None` if the
+    /// extension is not a recognized compression.
+    pub fn from_filename(filename: &str) -> Option<Self> {
+        let (_, extension) = filename.rsplit_once('.')?;
+        match extension.to_ascii_lowercase().as_str() {
+            "gz" | "gzip" => Some(Compression::Gzip),
+            "bz2" => Some(Compression::Bzip2),
+            "xz" => Some(Compression::Xz),
+            "zst" | "zstd" => Some(Compression::Zstd),
+            "snappy" | "sz" => Some(Compression::Snappy),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_filename() {
+        assert_eq!(
+            Some(Compression::Gzip),
+            Compression::from_filename("a.csv.gz")
+        );
+        assert_eq!(
+            Some(Compression::Zstd),
+            Compression::from_filename("a.parquet.zst")
+        );
+        assert_eq!(
+            Some(Compression::Bzip2),
+            Compression::from_filename("a.json.BZ2")
+        );
+        assert_eq!(
+            Some(Compression::Snappy),
+            Compression::from_filename("a.parquet.snappy")
+        );
+        assert_eq!(None, Compression::from_filename("a.csv"));
+        assert_eq!(None, Compression::from_filename("gz"));
+        assert_eq!(None, Compression::from_filename(""));
+    }
+}
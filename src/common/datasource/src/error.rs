@@ -45,8 +45,109 @@ pub enum Error {
         source: object_store::Error,
     },
 
+    #[snafu(display("Invalid page size: {}, it should be greater than 0", page_size))]
+    InvalidPageSize { page_size: usize },
+
     #[snafu(display("Invalid connection: {}", msg))]
     InvalidConnection { msg: String },
+
+    #[snafu(display("Failed to build regex: {}, source: {}", regex, source))]
+    BuildRegex {
+        regex: String,
+        source: regex::Error,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Unsupported format: {}", format))]
+    UnsupportedFormat { format: String },
+
+    #[snafu(display("Invalid format option, {}: {}", key, value))]
+    InvalidFormatOption { key: String, value: String },
+
+    #[snafu(display(
+        "Unsupported column {} of type {} in {} format",
+        column,
+        data_type,
+        format
+    ))]
+    UnsupportedColumnType {
+        format: String,
+        column: String,
+        data_type: String,
+    },
+
+    #[snafu(display(
+        "Failed to encode record batch in {} format, source: {}",
+        format,
+        source
+    ))]
+    EncodeRecordBatch {
+        format: String,
+        source: arrow::error::ArrowError,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display(
+        "Failed to decode record batch in {} format, source: {}",
+        format,
+        source
+    ))]
+    DecodeRecordBatch {
+        format: String,
+        source: arrow::error::ArrowError,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to infer schema in {} format, source: {}", format, source))]
+    InferSchema {
+        format: String,
+        source: arrow::error::ArrowError,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Column {} is not nullable but missing in file {}", column, file))]
+    MissingColumn { column: String, file: String },
+
+    #[snafu(display(
+        "Cannot coerce column {} from type {} to {} in file {}",
+        column,
+        from,
+        to,
+        file
+    ))]
+    IncompatibleColumnType {
+        column: String,
+        from: String,
+        to: String,
+        file: String,
+    },
+
+    #[snafu(display(
+        "Failed to coerce column {} in file {} without loss, source: {}",
+        column,
+        file,
+        source
+    ))]
+    CoerceColumn {
+        column: String,
+        file: String,
+        source: arrow::error::ArrowError,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to map columns of file {}, source: {}", file, source))]
+    MapColumns {
+        file: String,
+        source: arrow::error::ArrowError,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to write object into path: {}, source: {}", path, source))]
+    WriteObject {
+        path: String,
+        source: object_store::Error,
+        backtrace: Backtrace,
+    },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -55,13 +156,28 @@ impl ErrorExt for Error {
     fn status_code(&self) -> StatusCode {
         use Error::*;
         match self {
-            BuildBackend { .. } | ListObjects { .. } => StatusCode::StorageUnavailable,
+            BuildBackend { .. } | ListObjects { .. } | WriteObject { .. } => {
+                StatusCode::StorageUnavailable
+            }
+
+            EncodeRecordBatch { .. } | DecodeRecordBatch { .. } | MapColumns { .. } => {
+                StatusCode::Unexpected
+            }
 
             UnsupportedBackendProtocol { .. }
             | InvalidConnection { .. }
             | InvalidUrl { .. }
             | EmptyHostPath { .. }
-            | InvalidPath { .. } => StatusCode::InvalidArguments,
+            | InvalidPath { .. }
+            | BuildRegex { .. }
+            | InvalidPageSize { .. }
+            | UnsupportedFormat { .. }
+            | InvalidFormatOption { .. }
+            | UnsupportedColumnType { .. }
+            | InferSchema { .. }
+            | MissingColumn { .. }
+            | IncompatibleColumnType { .. }
+            | CoerceColumn { .. } => StatusCode::InvalidArguments,
         }
     }
 
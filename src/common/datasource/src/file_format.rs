@@ -0,0 +1,665 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::io::{BufReader, Cursor};
+
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use arrow::record_batch::RecordBatch;
+use arrow::{csv, json};
+use object_store::ObjectStore;
+use snafu::{ensure, OptionExt, ResultExt};
+
+use crate::error::{self, Result};
+
+pub const FORMAT_TYPE: &str = "format";
+pub const FORMAT_HEADER: &str = "header";
+pub const FORMAT_DELIMITER: &str = "delimiter";
+pub const FORMAT_TIMESTAMP_FORMAT: &str = "timestamp_format";
+pub const MAX_FILE_SIZE: &str = "max_file_size";
+pub const SCHEMA_INFER_MAX_RECORDS: &str = "schema_infer_max_records";
+pub const CASE_INSENSITIVE_COLUMNS: &str = "case_insensitive_columns";
+
+/// Default max size of an exported file, 256MiB.
+pub const DEFAULT_MAX_FILE_SIZE: usize = 256 * 1024 * 1024;
+/// Default max number of records read to infer the schema of a CSV or JSON file.
+pub const DEFAULT_SCHEMA_INFER_MAX_RECORDS: usize = 1000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Format {
+    Parquet,
+    Csv(CsvFormat),
+    /// Newline delimited JSON.
+    Json,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvFormat {
+    pub has_header: bool,
+    pub delimiter: u8,
+    pub timestamp_format: Option<String>,
+}
+
+impl Default for CsvFormat {
+    fn default() -> Self {
+        Self {
+            has_header: true,
+            delimiter: b',',
+            timestamp_format: None,
+        }
+    }
+}
+
+impl Format {
+    /// Extension of files in this format.
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            Format::Parquet => "parquet",
+            Format::Csv(_) => "csv",
+            Format::Json => "json",
+        }
+    }
+}
+
+impl TryFrom<&HashMap<String, String>> for Format {
+    type Error = error::Error;
+
+    /// Builds the format from `WITH` options of COPY, keys are in lowercase.
+    /// Defaults to parquet if no format is specified.
+    fn try_from(options: &HashMap<String, String>) -> Result<Self> {
+        let Some(format) = options.get(FORMAT_TYPE) else {
+            return Ok(Format::Parquet);
+        };
+
+        match format.to_ascii_lowercase().as_str() {
+            "parquet" => Ok(Format::Parquet),
+            "json" => Ok(Format::Json),
+            "csv" => {
+                let mut csv = CsvFormat::default();
+                if let Some(header) = options.get(FORMAT_HEADER) {
+                    csv.has_header =
+                        header
+                            .parse()
+                            .ok()
+                            .context(error::InvalidFormatOptionSnafu {
+                                key: FORMAT_HEADER,
+                                value: header,
+                            })?;
+                }
+                if let Some(delimiter) = options.get(FORMAT_DELIMITER) {
+                    ensure!(
+                        delimiter.len() == 1,
+                        error::InvalidFormatOptionSnafu {
+                            key: FORMAT_DELIMITER,
+                            value: delimiter,
+                        }
+                    );
+                    csv.delimiter = delimiter.as_bytes()[0];
+                }
+                csv.timestamp_format = options.get(FORMAT_TIMESTAMP_FORMAT).cloned();
+                Ok(Format::Csv(csv))
+            }
+            _ => error::UnsupportedFormatSnafu { format }.fail(),
+        }
+    }
+}
+
+/// Returns the max size of an exported file in `WITH` options of COPY.
+pub fn max_file_size(options: &HashMap<String, String>) -> Result<usize> {
+    let Some(value) = options.get(MAX_FILE_SIZE) else {
+        return Ok(DEFAULT_MAX_FILE_SIZE);
+    };
+    value
+        .parse::<usize>()
+        .ok()
+        .filter(|size| *size > 0)
+        .context(error::InvalidFormatOptionSnafu {
+            key: MAX_FILE_SIZE,
+            value,
+        })
+}
+
+/// Returns the max number of records to infer the schema of a CSV or JSON file in `WITH`
+/// options of COPY.
+pub fn schema_infer_max_records(options: &HashMap<String, String>) -> Result<usize> {
+    let Some(value) = options.get(SCHEMA_INFER_MAX_RECORDS) else {
+        return Ok(DEFAULT_SCHEMA_INFER_MAX_RECORDS);
+    };
+    value
+        .parse::<usize>()
+        .ok()
+        .filter(|records| *records > 0)
+        .context(error::InvalidFormatOptionSnafu {
+            key: SCHEMA_INFER_MAX_RECORDS,
+            value,
+        })
+}
+
+/// Returns whether the columns of the imported files are matched to the table columns
+/// case insensitively in `WITH` options of COPY, defaults to false.
+pub fn case_insensitive_columns(options: &HashMap<String, String>) -> Result<bool> {
+    let Some(value) = options.get(CASE_INSENSITIVE_COLUMNS) else {
+        return Ok(false);
+    };
+    value.parse().ok().context(error::InvalidFormatOptionSnafu {
+        key: CASE_INSENSITIVE_COLUMNS,
+        value,
+    })
+}
+
+/// Infers the schema of a CSV or JSON file from its first `max_records` records.
+///
+/// The columns of a CSV file without header are named after the columns of
+/// `table_schema` in order, so they are mapped to the table by position.
+pub fn infer_schema(
+    format: &Format,
+    data: &[u8],
+    max_records: usize,
+    table_schema: &Schema,
+) -> Result<Schema> {
+    match format {
+        Format::Csv(csv) => {
+            let (schema, _) = csv::reader::infer_reader_schema(
+                Cursor::new(data),
+                csv.delimiter,
+                Some(max_records),
+                csv.has_header,
+            )
+            .context(error::InferSchemaSnafu { format: "csv" })?;
+            if csv.has_header {
+                return Ok(schema);
+            }
+
+            let fields = schema
+                .fields()
+                .iter()
+                .enumerate()
+                .map(|(i, field)| match table_schema.fields().get(i) {
+                    Some(table_field) => Field::new(
+                        table_field.name(),
+                        field.data_type().clone(),
+                        field.is_nullable(),
+                    ),
+                    None => field.clone(),
+                })
+                .collect();
+            Ok(Schema::new(fields))
+        }
+        Format::Json => {
+            json::reader::infer_json_schema(&mut BufReader::new(data), Some(max_records))
+                .context(error::InferSchemaSnafu { format: "json" })
+        }
+        Format::Parquet => error::UnsupportedFormatSnafu { format: "parquet" }.fail(),
+    }
+}
+
+/// Returns the path of the `index`-th file exported to `path`.
+///
+/// Files are named `part-000N.<suffix>` if `path` is a directory, otherwise `.part-000N` is
+/// inserted before the extension of the file name.
+pub fn part_file_path(path: &str, index: usize, suffix: &str) -> String {
+    if path.is_empty() || path.ends_with('/') {
+        return format!("{path}part-{index:04}.{suffix}");
+    }
+
+    let file_start = path.rfind('/').map(|i| i + 1).unwrap_or(0);
+    match path[file_start..].rfind('.') {
+        Some(dot) => {
+            let (stem, ext) = path.split_at(file_start + dot);
+            format!("{stem}.part-{index:04}{ext}")
+        }
+        None => format!("{path}.part-{index:04}"),
+    }
+}
+
+fn check_schema(format: &Format, schema: &SchemaRef) -> Result<()> {
+    if let Format::Csv(_) = format {
+        for field in schema.fields() {
+            ensure!(
+                !matches!(
+                    field.data_type(),
+                    DataType::Binary | DataType::LargeBinary | DataType::FixedSizeBinary(_)
+                ),
+                error::UnsupportedColumnTypeSnafu {
+                    format: "csv",
+                    column: field.name(),
+                    data_type: field.data_type().to_string(),
+                }
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Writes record batches in CSV or JSON format into files under `path`, a new file is
+/// started once the current file reaches `max_file_size`.
+///
+/// The size is checked after each batch, so a file may exceed the limit by at most one
+/// batch.
+pub struct RollingFileWriter {
+    object_store: ObjectStore,
+    path: String,
+    format: Format,
+    max_file_size: usize,
+    buf: Vec<u8>,
+    rows_in_file: usize,
+    files: Vec<String>,
+}
+
+impl RollingFileWriter {
+    pub fn try_new(
+        object_store: ObjectStore,
+        path: String,
+        format: Format,
+        schema: SchemaRef,
+        max_file_size: usize,
+    ) -> Result<Self> {
+        ensure!(
+            format != Format::Parquet,
+            error::UnsupportedFormatSnafu { format: "parquet" }
+        );
+        check_schema(&format, &schema)?;
+
+        Ok(Self {
+            object_store,
+            path,
+            format,
+            max_file_size,
+            buf: Vec::new(),
+            rows_in_file: 0,
+            files: Vec::new(),
+        })
+    }
+
+    pub async fn write(&mut self, batch: &RecordBatch) -> Result<()> {
+        match &self.format {
+            Format::Csv(csv) => {
+                let mut builder = csv::WriterBuilder::new()
+                    .has_headers(csv.has_header && self.buf.is_empty())
+                    .with_delimiter(csv.delimiter);
+                if let Some(timestamp_format) = &csv.timestamp_format {
+                    builder = builder.with_timestamp_format(timestamp_format.clone());
+                }
+                let mut writer = builder.build(&mut self.buf);
+                writer
+                    .write(batch)
+                    .context(error::EncodeRecordBatchSnafu { format: "csv" })?;
+            }
+            Format::Json => {
+                let mut writer = json::LineDelimitedWriter::new(&mut self.buf);
+                writer
+                    .write_batches(std::slice::from_ref(batch))
+                    .context(error::EncodeRecordBatchSnafu { format: "json" })?;
+                writer
+                    .finish()
+                    .context(error::EncodeRecordBatchSnafu { format: "json" })?;
+            }
+            Format::Parquet => unreachable!(),
+        }
+        self.rows_in_file += batch.num_rows();
+
+        if self.buf.len() >= self.max_file_size {
+            self.finish_file().await?;
+        }
+        Ok(())
+    }
+
+    /// Finishes the current file, following batches are written into a new file.
+    pub async fn finish_file(&mut self) -> Result<()> {
+        if self.rows_in_file == 0 {
+            return Ok(());
+        }
+
+        let path = part_file_path(&self.path, self.files.len(), self.format.suffix());
+        let object = self.object_store.object(&path);
+        object
+            .write(std::mem::take(&mut self.buf))
+            .await
+            .context(error::WriteObjectSnafu { path: &path })?;
+
+        self.rows_in_file = 0;
+        self.files.push(path);
+        Ok(())
+    }
+
+    /// Finishes the current file and returns paths of all written files.
+    pub async fn close(mut self) -> Result<Vec<String>> {
+        self.finish_file().await?;
+        Ok(self.files)
+    }
+}
+
+/// Decodes record batches of `schema` from a CSV or JSON file.
+pub fn read_batches(format: &Format, schema: SchemaRef, data: Vec<u8>) -> Result<Vec<RecordBatch>> {
+    check_schema(format, &schema)?;
+
+    match format {
+        Format::Csv(csv) => {
+            let reader = csv::ReaderBuilder::new()
+                .with_schema(schema)
+                .has_header(csv.has_header)
+                .with_delimiter(csv.delimiter)
+                .build(Cursor::new(data))
+                .context(error::DecodeRecordBatchSnafu { format: "csv" })?;
+            reader
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .context(error::DecodeRecordBatchSnafu { format: "csv" })
+        }
+        Format::Json => {
+            let mut reader = json::ReaderBuilder::new()
+                .with_schema(schema)
+                .build(Cursor::new(data))
+                .context(error::DecodeRecordBatchSnafu { format: "json" })?;
+            let mut batches = Vec::new();
+            while let Some(batch) = reader
+                .next()
+                .context(error::DecodeRecordBatchSnafu { format: "json" })?
+            {
+                batches.push(batch);
+            }
+            Ok(batches)
+        }
+        Format::Parquet => error::UnsupportedFormatSnafu { format: "parquet" }.fail(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::array::{Array, Float64Array, StringArray, TimestampMillisecondArray};
+    use arrow::datatypes::{Field, Schema, TimeUnit};
+    use common_test_util::temp_dir::create_temp_dir;
+
+    use super::*;
+    use crate::object_store::fs::build_fs_backend;
+
+    fn test_schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("host", DataType::Utf8, true),
+            Field::new("cpu", DataType::Float64, true),
+            Field::new(
+                "ts",
+                DataType::Timestamp(TimeUnit::Millisecond, None),
+                false,
+            ),
+        ]))
+    }
+
+    fn test_batch(start: i64, rows: usize) -> RecordBatch {
+        let hosts = (0..rows)
+            .map(|i| Some(format!("host{}", start + i as i64)))
+            .collect::<StringArray>();
+        // Every other cpu is null.
+        let cpus = (0..rows)
+            .map(|i| (i % 2 == 0).then_some(i as f64 + 0.5))
+            .collect::<Float64Array>();
+        let ts = (0..rows)
+            .map(|i| 1655276557000 + start + i as i64)
+            .collect::<Vec<_>>();
+        RecordBatch::try_new(
+            test_schema(),
+            vec![
+                Arc::new(hosts),
+                Arc::new(cpus),
+                Arc::new(TimestampMillisecondArray::from(ts)),
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_format_from_options() {
+        let options = |kvs: &[(&str, &str)]| {
+            kvs.iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<HashMap<_, _>>()
+        };
+
+        assert_eq!(Format::Parquet, Format::try_from(&options(&[])).unwrap());
+        assert_eq!(
+            Format::Json,
+            Format::try_from(&options(&[("format", "JSON")])).unwrap()
+        );
+        assert_eq!(
+            Format::Csv(CsvFormat {
+                has_header: false,
+                delimiter: b'|',
+                timestamp_format: Some("%Y-%m-%d %H:%M:%S".to_string()),
+            }),
+            Format::try_from(&options(&[
+                ("format", "csv"),
+                ("header", "false"),
+                ("delimiter", "|"),
+                ("timestamp_format", "%Y-%m-%d %H:%M:%S"),
+            ]))
+            .unwrap()
+        );
+
+        assert!(Format::try_from(&options(&[("format", "orc")])).is_err());
+        assert!(Format::try_from(&options(&[("format", "csv"), ("delimiter", "||")])).is_err());
+        assert!(Format::try_from(&options(&[("format", "csv"), ("header", "yes")])).is_err());
+
+        assert_eq!(DEFAULT_MAX_FILE_SIZE, max_file_size(&options(&[])).unwrap());
+        assert_eq!(
+            1024,
+            max_file_size(&options(&[("max_file_size", "1024")])).unwrap()
+        );
+        assert!(max_file_size(&options(&[("max_file_size", "0")])).is_err());
+
+        assert_eq!(
+            DEFAULT_SCHEMA_INFER_MAX_RECORDS,
+            schema_infer_max_records(&options(&[])).unwrap()
+        );
+        assert_eq!(
+            10,
+            schema_infer_max_records(&options(&[("schema_infer_max_records", "10")])).unwrap()
+        );
+        assert!(schema_infer_max_records(&options(&[("schema_infer_max_records", "0")])).is_err());
+
+        assert!(!case_insensitive_columns(&options(&[])).unwrap());
+        assert!(
+            case_insensitive_columns(&options(&[("case_insensitive_columns", "true")])).unwrap()
+        );
+        assert!(case_insensitive_columns(&options(&[("case_insensitive_columns", "1")])).is_err());
+    }
+
+    #[test]
+    fn test_infer_schema() {
+        let data = b"cpu|host\n0.5|host1\n1|host2\n";
+        let format = Format::Csv(CsvFormat {
+            delimiter: b'|',
+            ..Default::default()
+        });
+        let schema = infer_schema(&format, data, 10, &test_schema()).unwrap();
+        assert_eq!(
+            vec![
+                Field::new("cpu", DataType::Float64, true),
+                Field::new("host", DataType::Utf8, true)
+            ],
+            schema.fields().clone()
+        );
+
+        // Only the first record is read, so the type of cpu is inferred by 0.5.
+        let data = b"cpu\n0.5\nhost1\n";
+        let schema =
+            infer_schema(&Format::Csv(CsvFormat::default()), data, 1, &test_schema()).unwrap();
+        assert_eq!(&DataType::Float64, schema.field(0).data_type());
+        let schema =
+            infer_schema(&Format::Csv(CsvFormat::default()), data, 2, &test_schema()).unwrap();
+        assert_eq!(&DataType::Utf8, schema.field(0).data_type());
+
+        // The columns without header are named after the table columns.
+        let format = Format::Csv(CsvFormat {
+            has_header: false,
+            ..Default::default()
+        });
+        let schema = infer_schema(&format, b"host1,0.5\n", 10, &test_schema()).unwrap();
+        assert_eq!("host", schema.field(0).name());
+        assert_eq!("cpu", schema.field(1).name());
+
+        let data = br#"{"host": "host1", "cpu": 1}"#;
+        let schema = infer_schema(&Format::Json, data, 10, &test_schema()).unwrap();
+        assert_eq!(
+            &DataType::Int64,
+            schema.field_with_name("cpu").unwrap().data_type()
+        );
+    }
+
+    #[test]
+    fn test_part_file_path() {
+        assert_eq!("/a/b/part-0001.csv", part_file_path("/a/b/", 1, "csv"));
+        assert_eq!(
+            "/a/b/demo.part-0000.csv",
+            part_file_path("/a/b/demo.csv", 0, "csv")
+        );
+        assert_eq!(
+            "/a/b/demo.part-0012",
+            part_file_path("/a/b/demo", 12, "csv")
+        );
+        assert_eq!(
+            "/a.b/demo.part-0002",
+            part_file_path("/a.b/demo", 2, "json")
+        );
+    }
+
+    async fn check_round_trip(format: Format) {
+        let dir = create_temp_dir("test_file_format_round_trip");
+        let path = format!("{}/", dir.path().display());
+        let object_store = build_fs_backend("/").unwrap();
+
+        // Each batch is larger than the max file size, so every batch is written into a file.
+        let mut writer = RollingFileWriter::try_new(
+            object_store.clone(),
+            path.clone(),
+            format.clone(),
+            test_schema(),
+            16,
+        )
+        .unwrap();
+        writer.write(&test_batch(0, 10)).await.unwrap();
+        writer.write(&test_batch(10, 10)).await.unwrap();
+        writer.write(&test_batch(20, 5)).await.unwrap();
+        let files = writer.close().await.unwrap();
+        assert_eq!(3, files.len());
+        assert_eq!(part_file_path(&path, 2, format.suffix()), files[2]);
+
+        let mut batches = Vec::new();
+        for file in &files {
+            let data = object_store.object(file).read().await.unwrap();
+            batches.extend(read_batches(&format, test_schema(), data).unwrap());
+        }
+        let rows = batches.iter().map(|b| b.num_rows()).sum::<usize>();
+        assert_eq!(25, rows);
+
+        let batch = &batches[0];
+        let hosts = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!("host0", hosts.value(0));
+        let cpus = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .unwrap();
+        assert_eq!(0.5, cpus.value(0));
+        assert!(cpus.is_null(1));
+        let ts = batch
+            .column(2)
+            .as_any()
+            .downcast_ref::<TimestampMillisecondArray>()
+            .unwrap();
+        assert_eq!(1655276557001, ts.value(1));
+    }
+
+    #[tokio::test]
+    async fn test_csv_round_trip() {
+        check_round_trip(Format::Csv(CsvFormat {
+            delimiter: b'|',
+            ..Default::default()
+        }))
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_json_round_trip() {
+        check_round_trip(Format::Json).await;
+    }
+
+    #[tokio::test]
+    async fn test_rolling_by_size() {
+        let dir = create_temp_dir("test_rolling_by_size");
+        let path = format!("{}/demo.json", dir.path().display());
+        let object_store = build_fs_backend("/").unwrap();
+
+        let mut writer = RollingFileWriter::try_new(
+            object_store,
+            path.clone(),
+            Format::Json,
+            test_schema(),
+            DEFAULT_MAX_FILE_SIZE,
+        )
+        .unwrap();
+        writer.write(&test_batch(0, 10)).await.unwrap();
+        writer.write(&test_batch(10, 10)).await.unwrap();
+        // A new file is started for each partition.
+        writer.finish_file().await.unwrap();
+        writer.write(&test_batch(20, 10)).await.unwrap();
+        // Empty files are not written.
+        writer.finish_file().await.unwrap();
+        let files = writer.close().await.unwrap();
+        assert_eq!(
+            vec![
+                part_file_path(&path, 0, "json"),
+                part_file_path(&path, 1, "json")
+            ],
+            files
+        );
+    }
+
+    #[test]
+    fn test_csv_rejects_binary() {
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            "data",
+            DataType::Binary,
+            true,
+        )]));
+        let object_store = build_fs_backend("/").unwrap();
+        let err = RollingFileWriter::try_new(
+            object_store.clone(),
+            "/tmp/".to_string(),
+            Format::Csv(CsvFormat::default()),
+            schema.clone(),
+            DEFAULT_MAX_FILE_SIZE,
+        )
+        .err()
+        .unwrap();
+        assert!(
+            err.to_string().contains("of type Binary in csv format"),
+            "{err}"
+        );
+
+        // Binary columns are allowed in JSON.
+        assert!(RollingFileWriter::try_new(
+            object_store,
+            "/tmp/".to_string(),
+            Format::Json,
+            schema,
+            DEFAULT_MAX_FILE_SIZE,
+        )
+        .is_ok());
+    }
+}
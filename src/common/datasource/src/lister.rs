@@ -12,11 +12,20 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use futures::{future, TryStreamExt};
-use object_store::{Object, ObjectStore};
-use regex::Regex;
-use snafu::ResultExt;
+use std::collections::{HashSet, VecDeque};
+use std::fs;
+#[cfg(unix)]
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
+use futures::{future, StreamExt, TryStreamExt};
+use object_store::util::{join_path, normalize_dir};
+use object_store::{Object, ObjectStore, Scheme};
+use regex::{Regex, RegexBuilder};
+use snafu::{ensure, ResultExt};
+
+use crate::compression::Compression;
 use crate::error::{self, Result};
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Source {
@@ -24,11 +33,40 @@ pub enum Source {
     Dir,
 }
 
+/// How the symlinks in a local directory are listed by a [Source::Dir]. It only applies
+/// to the file backend, other backends don't have symlinks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SymlinkPolicy {
+    /// Includes the symlinks like other objects without resolving them, as the object
+    /// store lists them. A symlink that can't be resolved only fails when it's read.
+    #[default]
+    Keep,
+    /// Excludes all the symlinks.
+    Skip,
+    /// Includes the symlinks to files. A symlink that can't be resolved, e.g. a dangling
+    /// or cyclic one, fails the listing.
+    Follow,
+    /// Includes the symlinks to files, while the symlinks that can't be resolved are
+    /// excluded. The link chain is walked with cycle detection, so a self-referential
+    /// link doesn't fail the listing.
+    FollowNoCycle,
+}
+
 pub struct Lister {
     object_store: ObjectStore,
     source: Source,
     path: String,
     regex: Option<Regex>,
+    max_results: Option<usize>,
+    symlink_policy: SymlinkPolicy,
+    /// The rest of the listing after the last page returned by [Lister::list_page].
+    page_cache: Mutex<Option<PageCache>>,
+}
+
+/// The sorted objects after the page whose token is `token`.
+struct PageCache {
+    token: String,
+    objects: VecDeque<Object>,
 }
 
 impl Lister {
@@ -41,12 +79,47 @@ impl Lister {
         Lister {
             object_store,
             source,
-            path,
+            // The directory is listed, or joined with the filename, so `dir` and `dir/`
+            // are the same.
+            path: normalize_dir(&path),
             regex,
+            max_results: None,
+            symlink_policy: SymlinkPolicy::default(),
+            page_cache: Mutex::new(None),
+        }
+    }
+
+    /// Makes the regex ignore case, e.g. `\.csv$` matches `FILE.CSV`. The regex is
+    /// case-sensitive by default.
+    pub fn with_case_insensitive(mut self, case_insensitive: bool) -> Result<Self> {
+        if let Some(regex) = self.regex.as_ref().filter(|_| case_insensitive) {
+            let regex = RegexBuilder::new(regex.as_str())
+                .case_insensitive(true)
+                .build()
+                .context(error::BuildRegexSnafu {
+                    regex: regex.as_str(),
+                })?;
+            self.regex = Some(regex);
         }
+        Ok(self)
+    }
+
+    /// Limits the number of objects listed from a [Source::Dir], the listing stops
+    /// once `max_results` objects are collected.
+    pub fn with_max_results(mut self, max_results: Option<usize>) -> Self {
+        self.max_results = max_results;
+        self
     }
 
-    pub async fn list(&self) -> Result<Vec<Object>> {
+    /// Sets how the symlinks are listed from a [Source::Dir] of the file backend.
+    pub fn with_symlink_policy(mut self, symlink_policy: SymlinkPolicy) -> Self {
+        self.symlink_policy = symlink_policy;
+        self
+    }
+
+    /// Lists the objects, returns them along with whether the result is truncated
+    /// by `max_results`.
+    pub async fn list(&self) -> Result<(Vec<Object>, bool)> {
         match &self.source {
             Source::Dir => {
                 let streamer = self
@@ -54,9 +127,7 @@ impl Lister {
                     .object(&self.path)
                     .list()
                     .await
-                    .context(error::ListObjectsSnafu { path: &self.path })?;
-
-                streamer
+                    .context(error::ListObjectsSnafu { path: &self.path })?
                     .try_filter(|f| {
                         let res = self
                             .regex
@@ -65,17 +136,504 @@ impl Lister {
                             .unwrap_or(true);
                         future::ready(res)
                     })
-                    .try_collect::<Vec<_>>()
-                    .await
-                    .context(error::ListObjectsSnafu { path: &self.path })
+                    .try_filter_map(|f| future::ready(self.apply_symlink_policy(f)));
+
+                match self.max_results {
+                    Some(max_results) => {
+                        // Takes one more object to find out whether there are more objects
+                        // than the limit.
+                        let mut objects = streamer
+                            .take(max_results.saturating_add(1))
+                            .try_collect::<Vec<_>>()
+                            .await
+                            .context(error::ListObjectsSnafu { path: &self.path })?;
+                        let truncated = objects.len() > max_results;
+                        objects.truncate(max_results);
+
+                        Ok((objects, truncated))
+                    }
+                    None => {
+                        let objects = streamer
+                            .try_collect::<Vec<_>>()
+                            .await
+                            .context(error::ListObjectsSnafu { path: &self.path })?;
+
+                        Ok((objects, false))
+                    }
+                }
             }
             Source::Filename(filename) => {
-                let obj = self
-                    .object_store
-                    .object(&format!("{}{}", self.path, filename));
+                let obj = self.object_store.object(&join_path(&self.path, filename));
+
+                Ok((vec![obj], false))
+            }
+        }
+    }
+
+    /// Lists a page of at most `page_size` objects, in lexicographic order of their names,
+    /// after the position of `token`. Returns the objects and the token of the next page,
+    /// which is `None` once all the objects are listed.
+    ///
+    /// The filter is applied before paging, so a page only holds the matched objects. The
+    /// token is the name of the last object in the page, so it can be persisted to resume
+    /// the listing after a restart, and objects added before the token are skipped.
+    ///
+    /// The object store doesn't support listing from a position, so the directory is
+    /// listed once and the rest of the listing is kept for the next page. Paging with the
+    /// token of the last page returns the kept objects, which doesn't see the objects added
+    /// since the first page. Any other token lists the directory again.
+    pub async fn list_page(
+        &self,
+        token: Option<String>,
+        page_size: usize,
+    ) -> Result<(Vec<Object>, Option<String>)> {
+        ensure!(page_size > 0, error::InvalidPageSizeSnafu { page_size });
+
+        match &self.source {
+            Source::Dir => {
+                let cache = self
+                    .page_cache
+                    .lock()
+                    .unwrap()
+                    .take()
+                    .filter(|cache| token.as_ref() == Some(&cache.token));
+                let mut objects = match cache {
+                    Some(cache) => cache.objects,
+                    None => self.list_sorted_after(token.as_deref()).await?,
+                };
+                if objects.len() <= page_size {
+                    return Ok((objects.into(), None));
+                }
+
+                let page = objects.drain(..page_size).collect::<Vec<_>>();
+                let next_token = page.last().map(|obj| obj.name().to_string());
+                if let Some(token) = &next_token {
+                    *self.page_cache.lock().unwrap() = Some(PageCache {
+                        token: token.clone(),
+                        objects,
+                    });
+                }
+                Ok((page, next_token))
+            }
+            Source::Filename(_) if token.is_some() => Ok((vec![], None)),
+            Source::Filename(_) => {
+                let (objects, _) = self.list().await?;
+                Ok((objects, None))
+            }
+        }
+    }
+
+    /// Lists the matched objects of a [Source::Dir] after `token`, sorted by their names.
+    async fn list_sorted_after(&self, token: Option<&str>) -> Result<VecDeque<Object>> {
+        let mut objects = self
+            .object_store
+            .object(&self.path)
+            .list()
+            .await
+            .context(error::ListObjectsSnafu { path: &self.path })?
+            .try_filter(|f| {
+                let after_token = token.map(|t| f.name() > t).unwrap_or(true);
+                let matched = self
+                    .regex
+                    .as_ref()
+                    .map(|x| x.is_match(f.name()))
+                    .unwrap_or(true);
+                future::ready(after_token && matched)
+            })
+            .try_filter_map(|f| future::ready(self.apply_symlink_policy(f)))
+            .try_collect::<Vec<_>>()
+            .await
+            .context(error::ListObjectsSnafu { path: &self.path })?;
+        objects.sort_unstable_by(|a, b| a.name().cmp(b.name()));
+        Ok(objects.into())
+    }
+
+    /// Returns the object if it's kept by the symlink policy.
+    fn apply_symlink_policy(&self, obj: Object) -> object_store::Result<Option<Object>> {
+        let metadata = self.object_store.metadata();
+        if self.symlink_policy == SymlinkPolicy::Keep || metadata.scheme() != Scheme::Fs {
+            return Ok(Some(obj));
+        }
+
+        let path = Path::new(metadata.root()).join(obj.path());
+        let is_symlink = fs::symlink_metadata(&path)
+            .map(|m| m.file_type().is_symlink())
+            .unwrap_or(false);
+        if !is_symlink {
+            return Ok(Some(obj));
+        }
 
-                Ok(vec![obj])
+        let is_file = match self.symlink_policy {
+            SymlinkPolicy::Keep => true,
+            SymlinkPolicy::Skip => false,
+            SymlinkPolicy::Follow => fs::metadata(&path)
+                .map_err(|e| {
+                    object_store::Error::new(
+                        object_store::ErrorKind::Unexpected,
+                        &format!("failed to follow symlink {}", path.display()),
+                    )
+                    .set_source(e)
+                })?
+                .is_file(),
+            SymlinkPolicy::FollowNoCycle => {
+                resolve_symlink(path).map(|m| m.is_file()).unwrap_or(false)
             }
+        };
+        Ok(is_file.then_some(obj))
+    }
+
+    /// Lists the objects like [Lister::list], along with their compression
+    /// classified by file extension.
+    pub async fn list_with_compression(
+        &self,
+    ) -> Result<(Vec<(Object, Option<Compression>)>, bool)> {
+        let (objects, truncated) = self.list().await?;
+
+        let objects = objects
+            .into_iter()
+            .map(|obj| {
+                let compression = Compression::from_filename(obj.name());
+                (obj, compression)
+            })
+            .collect();
+        Ok((objects, truncated))
+    }
+}
+
+/// Max number of links walked from a path, the same as the limit of Linux.
+const MAX_SYMLINK_HOPS: usize = 40;
+
+/// Walks the link chain from `path`, and returns the metadata of its target. Returns
+/// `None` if the chain is dangling or cyclic.
+fn resolve_symlink(mut path: PathBuf) -> Option<fs::Metadata> {
+    // The walk ends once a link is visited twice, or too many links are walked in case
+    // the same link is reached by different paths.
+    let mut visited = HashSet::new();
+    loop {
+        let metadata = fs::symlink_metadata(&path).ok()?;
+        if !metadata.file_type().is_symlink() {
+            return Some(metadata);
+        }
+        if !visited.insert(link_id(&path, &metadata)) || visited.len() > MAX_SYMLINK_HOPS {
+            return None;
         }
+
+        let target = fs::read_link(&path).ok()?;
+        path = match path.parent() {
+            // A relative target is relative to the directory of the link.
+            Some(parent) => parent.join(target),
+            None => target,
+        };
+    }
+}
+
+/// Identifies a link by its inode, as the same link can be reached by different paths.
+#[cfg(unix)]
+fn link_id(_path: &Path, metadata: &fs::Metadata) -> (u64, u64) {
+    (metadata.dev(), metadata.ino())
+}
+
+#[cfg(not(unix))]
+fn link_id(path: &Path, _metadata: &fs::Metadata) -> PathBuf {
+    path.to_path_buf()
+}
+
+#[cfg(test)]
+mod tests {
+    use common_test_util::temp_dir::{create_temp_dir, TempDir};
+
+    use super::*;
+    use crate::object_store::fs::build_fs_backend;
+
+    async fn new_store_with_files(names: &[&str]) -> (ObjectStore, TempDir) {
+        let dir = create_temp_dir("test_lister");
+        let store = build_fs_backend(dir.path().to_str().unwrap()).unwrap();
+        for name in names {
+            store.object(name).write("data").await.unwrap();
+        }
+        (store, dir)
+    }
+
+    #[tokio::test]
+    async fn test_list_with_compression() {
+        let (store, _dir) =
+            new_store_with_files(&["a.csv.gz", "b.parquet.zst", "c.csv", "d.txt"]).await;
+        let regex = Regex::new(r"\.(csv|parquet)(\.(gz|zst))?$").unwrap();
+        let lister = Lister::new(store, Source::Dir, "/".to_string(), Some(regex));
+
+        let (objects, truncated) = lister.list_with_compression().await.unwrap();
+        assert!(!truncated);
+        let mut objects = objects
+            .into_iter()
+            .map(|(obj, compression)| (obj.name().to_string(), compression))
+            .collect::<Vec<_>>();
+        objects.sort_by(|a, b| a.0.cmp(&b.0));
+
+        assert_eq!(
+            vec![
+                ("a.csv.gz".to_string(), Some(Compression::Gzip)),
+                ("b.parquet.zst".to_string(), Some(Compression::Zstd)),
+                ("c.csv".to_string(), None),
+            ],
+            objects
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_filename_with_compression() {
+        let (store, _dir) = new_store_with_files(&["a.csv.gz"]).await;
+        let lister = Lister::new(
+            store,
+            Source::Filename("a.csv.gz".to_string()),
+            "/".to_string(),
+            None,
+        );
+
+        let (objects, truncated) = lister.list_with_compression().await.unwrap();
+        assert!(!truncated);
+        assert_eq!(1, objects.len());
+        assert_eq!(Some(Compression::Gzip), objects[0].1);
+    }
+
+    async fn list_with_max_results(num_files: usize, max_results: usize) -> (usize, bool) {
+        let names = (0..num_files)
+            .map(|i| format!("{i}.csv"))
+            .collect::<Vec<_>>();
+        let names = names.iter().map(|x| x.as_str()).collect::<Vec<_>>();
+        let (store, _dir) = new_store_with_files(&names).await;
+        let lister = Lister::new(store, Source::Dir, "/".to_string(), None)
+            .with_max_results(Some(max_results));
+
+        let (objects, truncated) = lister.list().await.unwrap();
+        (objects.len(), truncated)
+    }
+
+    #[tokio::test]
+    async fn test_list_with_max_results() {
+        // Under the limit.
+        assert_eq!((2, false), list_with_max_results(2, 3).await);
+        // Exactly at the limit.
+        assert_eq!((3, false), list_with_max_results(3, 3).await);
+        // Over the limit.
+        assert_eq!((3, true), list_with_max_results(5, 3).await);
+    }
+
+    #[tokio::test]
+    async fn test_list_filename_with_max_results() {
+        let (store, _dir) = new_store_with_files(&["a.csv", "b.csv"]).await;
+        let lister = Lister::new(
+            store,
+            Source::Filename("a.csv".to_string()),
+            "/".to_string(),
+            None,
+        )
+        .with_max_results(Some(0));
+
+        let (objects, truncated) = lister.list().await.unwrap();
+        assert!(!truncated);
+        assert_eq!(1, objects.len());
+    }
+
+    async fn list_names(regex: &str, case_insensitive: bool) -> Vec<String> {
+        let (store, _dir) = new_store_with_files(&["FILE.CSV", "file.csv", "file.txt"]).await;
+        let regex = Regex::new(regex).unwrap();
+        let lister = Lister::new(store, Source::Dir, "/".to_string(), Some(regex))
+            .with_case_insensitive(case_insensitive)
+            .unwrap();
+
+        let (objects, _) = lister.list().await.unwrap();
+        let mut names = objects
+            .iter()
+            .map(|x| x.name().to_string())
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    #[tokio::test]
+    async fn test_list_case_insensitive() {
+        assert_eq!(vec!["file.csv"], list_names(r"\.csv$", false).await);
+        assert_eq!(
+            vec!["FILE.CSV", "file.csv"],
+            list_names(r"\.csv$", true).await
+        );
+    }
+
+    async fn list_all_pages(lister: &Lister, page_size: usize) -> Vec<Vec<String>> {
+        let mut pages = vec![];
+        let mut token = None;
+        loop {
+            let (objects, next_token) = lister.list_page(token, page_size).await.unwrap();
+            pages.push(
+                objects
+                    .iter()
+                    .map(|x| x.name().to_string())
+                    .collect::<Vec<_>>(),
+            );
+            if next_token.is_none() {
+                return pages;
+            }
+            token = next_token;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_list_page() {
+        let (store, _dir) =
+            new_store_with_files(&["e.csv", "a.csv", "d.txt", "c.csv", "b.csv"]).await;
+        let regex = Regex::new(r"\.csv$").unwrap();
+        let lister = Lister::new(store, Source::Dir, "/".to_string(), Some(regex));
+
+        let (objects, _) = lister.list().await.unwrap();
+        let mut all = objects
+            .iter()
+            .map(|x| x.name().to_string())
+            .collect::<Vec<_>>();
+        all.sort();
+
+        // Two pages, the filter applies to each page.
+        let pages = list_all_pages(&lister, 3).await;
+        assert_eq!(vec![vec!["a.csv", "b.csv", "c.csv"], vec!["e.csv"]], pages);
+        assert_eq!(all, pages.concat());
+
+        // A page size equal to the number of objects doesn't produce an empty page.
+        assert_eq!(vec![all.clone()], list_all_pages(&lister, 4).await);
+
+        // The token of a previous listing resumes the listing.
+        let (_, token) = lister.list_page(None, 2).await.unwrap();
+        assert_eq!(Some("b.csv".to_string()), token);
+        let (objects, token) = lister.list_page(token, 10).await.unwrap();
+        assert_eq!(
+            vec!["c.csv", "e.csv"],
+            objects.iter().map(|x| x.name()).collect::<Vec<_>>()
+        );
+        assert_eq!(None, token);
+
+        assert!(lister.list_page(None, 0).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_list_page_cache() {
+        let (store, dir) = new_store_with_files(&["a.csv", "b.csv", "c.csv"]).await;
+        let lister = Lister::new(store.clone(), Source::Dir, "/".to_string(), None);
+
+        let (_, token) = lister.list_page(None, 1).await.unwrap();
+        assert_eq!(Some("a.csv".to_string()), token);
+        // The next page comes from the first listing, so it misses the new object.
+        store.object("d.csv").write("data").await.unwrap();
+        std::fs::remove_file(dir.path().join("c.csv")).unwrap();
+        let (objects, next_token) = lister.list_page(token.clone(), 10).await.unwrap();
+        assert_eq!(
+            vec!["b.csv", "c.csv"],
+            objects.iter().map(|x| x.name()).collect::<Vec<_>>()
+        );
+        assert_eq!(None, next_token);
+
+        // The kept listing is consumed, so the directory is listed again.
+        let (objects, _) = lister.list_page(token, 10).await.unwrap();
+        assert_eq!(
+            vec!["b.csv", "d.csv"],
+            objects.iter().map(|x| x.name()).collect::<Vec<_>>()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_page_filename() {
+        let (store, _dir) = new_store_with_files(&["a.csv", "b.csv"]).await;
+        let lister = Lister::new(
+            store,
+            Source::Filename("a.csv".to_string()),
+            "/".to_string(),
+            None,
+        );
+
+        let (objects, token) = lister.list_page(None, 1).await.unwrap();
+        assert_eq!(1, objects.len());
+        assert_eq!(None, token);
+        let (objects, _) = lister
+            .list_page(Some("a.csv".to_string()), 1)
+            .await
+            .unwrap();
+        assert!(objects.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_list_path_slashes() {
+        let (store, _dir) = new_store_with_files(&["dir/a.csv", "dir/b.csv"]).await;
+
+        for path in ["dir", "dir/", "/dir", "/dir/"] {
+            for filename in ["a.csv", "/a.csv"] {
+                let lister = Lister::new(
+                    store.clone(),
+                    Source::Filename(filename.to_string()),
+                    path.to_string(),
+                    None,
+                );
+                let (objects, _) = lister.list().await.unwrap();
+                assert_eq!(1, objects.len());
+                assert_eq!("a.csv", objects[0].name());
+                assert!(objects[0].is_exist().await.unwrap(), "{path} {filename}");
+            }
+
+            let lister = Lister::new(store.clone(), Source::Dir, path.to_string(), None);
+            let (objects, _) = lister.list().await.unwrap();
+            let mut names = objects.iter().map(|x| x.name()).collect::<Vec<_>>();
+            names.sort();
+            assert_eq!(vec!["a.csv", "b.csv"], names, "{path}");
+        }
+    }
+
+    #[cfg(unix)]
+    async fn new_lister_with_symlinks(policy: SymlinkPolicy) -> (Lister, TempDir) {
+        let (store, dir) = new_store_with_files(&["a.csv"]).await;
+        std::os::unix::fs::symlink("a.csv", dir.path().join("b.csv")).unwrap();
+        std::os::unix::fs::symlink("./c.csv", dir.path().join("c.csv")).unwrap();
+        let lister =
+            Lister::new(store, Source::Dir, "/".to_string(), None).with_symlink_policy(policy);
+        (lister, dir)
+    }
+
+    #[cfg(unix)]
+    async fn list_symlinks(policy: SymlinkPolicy) -> Result<Vec<String>> {
+        let (lister, _dir) = new_lister_with_symlinks(policy).await;
+        let (objects, _) = lister.list().await?;
+        let mut names = objects
+            .iter()
+            .map(|x| x.name().to_string())
+            .collect::<Vec<_>>();
+        names.sort();
+        Ok(names)
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_list_symlinks() {
+        // The symlinks are listed as is by default.
+        assert_eq!(
+            vec!["a.csv", "b.csv", "c.csv"],
+            list_symlinks(SymlinkPolicy::default()).await.unwrap()
+        );
+        assert_eq!(
+            vec!["a.csv"],
+            list_symlinks(SymlinkPolicy::Skip).await.unwrap()
+        );
+        // The cyclic symlink can't be followed.
+        assert!(list_symlinks(SymlinkPolicy::Follow).await.is_err());
+        assert_eq!(
+            vec!["a.csv", "b.csv"],
+            list_symlinks(SymlinkPolicy::FollowNoCycle).await.unwrap()
+        );
+
+        // The symlinks to a symlink are resolved, and a cycle of several links ends.
+        let (lister, dir) = new_lister_with_symlinks(SymlinkPolicy::FollowNoCycle).await;
+        std::os::unix::fs::symlink("b.csv", dir.path().join("d.csv")).unwrap();
+        std::os::unix::fs::symlink("f.csv", dir.path().join("e.csv")).unwrap();
+        std::os::unix::fs::symlink("e.csv", dir.path().join("f.csv")).unwrap();
+        let (objects, token) = lister.list_page(None, 10).await.unwrap();
+        assert_eq!(
+            vec!["a.csv", "b.csv", "d.csv"],
+            objects.iter().map(|x| x.name()).collect::<Vec<_>>()
+        );
+        assert_eq!(None, token);
     }
 }
@@ -114,6 +114,43 @@ impl StatusCode {
             | StatusCode::AccessDenied => false,
         }
     }
+
+    /// Converts a code back to [StatusCode], returns `None` for unknown codes.
+    pub fn from_u32(value: u32) -> Option<Self> {
+        match value {
+            v if v == StatusCode::Success as u32 => Some(StatusCode::Success),
+            v if v == StatusCode::Unknown as u32 => Some(StatusCode::Unknown),
+            v if v == StatusCode::Unsupported as u32 => Some(StatusCode::Unsupported),
+            v if v == StatusCode::Unexpected as u32 => Some(StatusCode::Unexpected),
+            v if v == StatusCode::Internal as u32 => Some(StatusCode::Internal),
+            v if v == StatusCode::InvalidArguments as u32 => Some(StatusCode::InvalidArguments),
+            v if v == StatusCode::InvalidSyntax as u32 => Some(StatusCode::InvalidSyntax),
+            v if v == StatusCode::PlanQuery as u32 => Some(StatusCode::PlanQuery),
+            v if v == StatusCode::EngineExecuteQuery as u32 => Some(StatusCode::EngineExecuteQuery),
+            v if v == StatusCode::TableAlreadyExists as u32 => Some(StatusCode::TableAlreadyExists),
+            v if v == StatusCode::TableNotFound as u32 => Some(StatusCode::TableNotFound),
+            v if v == StatusCode::TableColumnNotFound as u32 => {
+                Some(StatusCode::TableColumnNotFound)
+            }
+            v if v == StatusCode::TableColumnExists as u32 => Some(StatusCode::TableColumnExists),
+            v if v == StatusCode::DatabaseNotFound as u32 => Some(StatusCode::DatabaseNotFound),
+            v if v == StatusCode::StorageUnavailable as u32 => Some(StatusCode::StorageUnavailable),
+            v if v == StatusCode::RuntimeResourcesExhausted as u32 => {
+                Some(StatusCode::RuntimeResourcesExhausted)
+            }
+            v if v == StatusCode::UserNotFound as u32 => Some(StatusCode::UserNotFound),
+            v if v == StatusCode::UnsupportedPasswordType as u32 => {
+                Some(StatusCode::UnsupportedPasswordType)
+            }
+            v if v == StatusCode::UserPasswordMismatch as u32 => {
+                Some(StatusCode::UserPasswordMismatch)
+            }
+            v if v == StatusCode::AuthHeaderNotFound as u32 => Some(StatusCode::AuthHeaderNotFound),
+            v if v == StatusCode::InvalidAuthHeader as u32 => Some(StatusCode::InvalidAuthHeader),
+            v if v == StatusCode::AccessDenied as u32 => Some(StatusCode::AccessDenied),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for StatusCode {
@@ -138,6 +175,20 @@ mod tests {
         assert_status_code_display(StatusCode::TableAlreadyExists, "TableAlreadyExists");
     }
 
+    #[test]
+    fn test_from_u32() {
+        assert_eq!(Some(StatusCode::Success), StatusCode::from_u32(0));
+        assert_eq!(
+            Some(StatusCode::DatabaseNotFound),
+            StatusCode::from_u32(StatusCode::DatabaseNotFound as u32)
+        );
+        assert_eq!(
+            Some(StatusCode::AccessDenied),
+            StatusCode::from_u32(StatusCode::AccessDenied as u32)
+        );
+        assert_eq!(None, StatusCode::from_u32(999));
+    }
+
     #[test]
     fn test_is_success() {
         assert!(StatusCode::is_success(0));
@@ -0,0 +1,57 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use proc_macro::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{Data, DataStruct, DeriveInput, Fields};
+
+pub(crate) fn impl_from_row(ast: &DeriveInput) -> TokenStream {
+    let name = &ast.ident;
+    let fields = match &ast.data {
+        Data::Struct(DataStruct {
+            fields: Fields::Named(fields),
+            ..
+        }) => &fields.named,
+        _ => {
+            return quote_spanned!(
+                ast.span() => compile_error!("FromRow can only be derived for structs with named fields.")
+            )
+            .into()
+        }
+    };
+
+    let getters = fields.iter().map(|field| {
+        let ident = field.ident.as_ref().unwrap();
+        // Columns are named as the fields, without the prefix of raw identifiers.
+        let column = ident.to_string().trim_start_matches("r#").to_string();
+        let ty = &field.ty;
+        // Spans the field so unsupported field types are reported at the field.
+        quote_spanned! {field.span() =>
+            #ident: row.get::<#ty>(#column)?
+        }
+    });
+
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+    let gen = quote! {
+        impl #impl_generics client::FromRow for #name #ty_generics #where_clause {
+            fn from_row(row: &client::Row) -> client::Result<Self> {
+                Ok(Self {
+                    #(#getters,)*
+                })
+            }
+        }
+    };
+    gen.into()
+}
@@ -12,8 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod from_row;
 mod range_fn;
 
+use from_row::impl_from_row;
 use proc_macro::TokenStream;
 use quote::{quote, quote_spanned};
 use range_fn::process_range_fn;
@@ -58,6 +60,29 @@ fn impl_aggr_func_type_store(ast: &DeriveInput) -> TokenStream {
     gen.into()
 }
 
+/// Implements `client::FromRow` for a struct with named fields, so rows of query results can
+/// be collected as the struct by `client::rows_as`. Each field is read from the column of the
+/// same name, and its type must implement `client::FromValue`. Nullable columns should be read
+/// into `Option` fields. So the crate using this macro should depend on `client`.
+///
+/// # Example
+/// ```rust, ignore
+/// #[derive(FromRow)]
+/// struct Cpu {
+///     host: String,
+///     ts: chrono::DateTime<chrono::Utc>,
+///     usage: Option<f64>,
+/// }
+///
+/// let output = database.sql("SELECT host, ts, usage FROM cpu").await?;
+/// let rows: Vec<Cpu> = client::rows_as(output).await?;
+/// ```
+#[proc_macro_derive(FromRow)]
+pub fn from_row_derive(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    impl_from_row(&ast)
+}
+
 /// A struct can be used as a creator for aggregate function if it has been annotated with this
 /// attribute first. This attribute add a necessary field which is intended to store the input
 /// data's types to the struct.
@@ -90,7 +115,8 @@ pub fn as_aggr_func_creator(_args: TokenStream, input: TokenStream) -> TokenStre
 /// Attribute macro to convert an arithimetic function to a range function. The annotated function
 /// should accept servaral arrays as input and return a single value as output. This procedure
 /// macro can works on any number of input parameters. Return type can be either primitive type
-/// or wrapped in `Option`.
+/// or wrapped in `Option`. Input arrays can be `TimestampMillisecondArray`, `Float64Array`,
+/// `Int64Array`, `UInt64Array`, `BooleanArray` or `StringArray`.
 ///
 /// # Example
 /// Take `count_over_time()` in PromQL as an example:
@@ -108,8 +134,25 @@ pub fn as_aggr_func_creator(_args: TokenStream, input: TokenStream) -> TokenStre
 ///
 /// # Arguments
 /// - `name`: The name of the generated [ScalarUDF] struct.
-/// - `ret`: The return type of the generated UDF function.
+/// - `ret`: The return type of the generated UDF function, one of `Float64Array`, `Int64Array`,
+///   `UInt64Array` and `BooleanArray`. Functions returning floats can't use an integer `ret`.
+///   Predicates returning `bool` or `Option<bool>` should use `BooleanArray`, where `None`
+///   becomes null.
 /// - `display_name`: The display name of the generated UDF function.
+/// - `volatility`: Optional, the [Volatility] of the generated UDF function, one of `immutable`,
+///   `stable` and `volatile`. Default to `immutable`. Time-dependent functions should be `stable`
+///   and random ones `volatile`, so the planner won't fold or cache their results.
+/// - `checked`: Optional, default to `false`. Additions, subtractions and multiplications in the
+///   annotated function wrap around on overflow. If `checked = true`, the generated UDF returns
+///   an error on overflow instead.
+/// - `coerce`: Optional, default to `false`. The generated UDF returns an error if an input
+///   array isn't of the declared type. If `coerce = true`, integer arrays are also accepted
+///   where `Float64Array` is declared, and casted to floats before calling the function.
+///
+/// # Registration
+/// The generated UDF registers itself by `display_name`, all of them can be collected by
+/// `common_query::range_fn_registry::collect_range_fns()`. So the crate using this macro
+/// should depend on `common-query`.
 #[proc_macro_attribute]
 pub fn range_fn(args: TokenStream, input: TokenStream) -> TokenStream {
     process_range_fn(args, input)
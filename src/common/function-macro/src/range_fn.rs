@@ -20,9 +20,11 @@ use quote::quote;
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
 use syn::token::Comma;
+use syn::visit_mut::VisitMut;
 use syn::{
-    parse_macro_input, Attribute, AttributeArgs, FnArg, Ident, ItemFn, Meta, MetaNameValue,
-    NestedMeta, Signature, Type, TypeReference, Visibility,
+    parse_macro_input, parse_quote, Attribute, AttributeArgs, BinOp, Block, Expr, ExprAssignOp,
+    ExprBinary, FnArg, Ident, Item, ItemFn, Meta, MetaNameValue, NestedMeta, PathArguments,
+    ReturnType, Signature, Type, TypeReference, Visibility,
 };
 
 /// Internal util macro to early return on error.
@@ -54,9 +56,22 @@ pub(crate) fn process_range_fn(args: TokenStream, input: TokenStream) -> TokenSt
     let Signature {
         inputs,
         ident: fn_name,
+        output,
         ..
     } = &sig;
+    let volatility = ok!(get_volatility(&arg_map));
     let arg_types = ok!(extract_input_types(inputs));
+    let input_data_types = ok!(arg_types
+        .iter()
+        .map(|ty| array_data_type(&unref_type(ty), ty.span()))
+        .collect::<Result<Vec<_>, _>>());
+    let ret_type = ok!(get_ident(&arg_map, "ret", arg_span));
+    let ret_data_type = ok!(array_data_type_of_ident(&ret_type));
+    ok!(check_return_type(output, &ret_type));
+    let checked = ok!(get_bool(&arg_map, "checked"));
+    let coerce = ok!(get_bool(&arg_map, "coerce"));
+    // `BooleanArray` can only be collected from `Option<bool>`s
+    let wrap_some = ret_type == "BooleanArray" && !returns_option(output);
 
     // build the struct and its impl block
     let struct_code = build_struct(
@@ -64,16 +79,29 @@ pub(crate) fn process_range_fn(args: TokenStream, input: TokenStream) -> TokenSt
         vis,
         ok!(get_ident(&arg_map, "name", arg_span)),
         ok!(get_ident(&arg_map, "display_name", arg_span)),
+        volatility,
+        input_data_types.clone(),
+        ret_data_type,
+        coerce,
     );
     let calc_fn_code = build_calc_fn(
         ok!(get_ident(&arg_map, "name", arg_span)),
         arg_types,
+        input_data_types,
         fn_name.clone(),
-        ok!(get_ident(&arg_map, "ret", arg_span)),
+        ret_type,
+        checked,
+        coerce,
+        wrap_some,
     );
     // preserve this fn, but remove its `pub` modifier
+    let mut wrapping_block = block.clone();
+    ArithmeticRewriter { checked: false }.visit_block_mut(&mut wrapping_block);
+    // the checked variant is called instead if `checked` is set
+    let allow_dead_code = checked.then(|| quote!(#[allow(dead_code)]));
     let input_fn_code: TokenStream = quote! {
-        #sig { #block }
+        #allow_dead_code
+        #sig { #wrapping_block }
     }
     .into();
 
@@ -81,6 +109,9 @@ pub(crate) fn process_range_fn(args: TokenStream, input: TokenStream) -> TokenSt
     result.extend(struct_code);
     result.extend(calc_fn_code);
     result.extend(input_fn_code);
+    if checked {
+        result.extend(build_checked_fn(&sig, *block));
+    }
     result
 }
 
@@ -92,6 +123,10 @@ fn extract_arg_map(args: Vec<NestedMeta>) -> Result<HashMap<String, Ident>, syn:
                 let name = path.get_ident().unwrap().to_string();
                 let ident = match lit {
                     syn::Lit::Str(lit_str) => lit_str.parse::<Ident>(),
+                    syn::Lit::Bool(lit_bool) => Ok(Ident::new(
+                        if lit_bool.value { "true" } else { "false" },
+                        lit_bool.span,
+                    )),
                     _ => Err(syn::Error::new(
                         lit.span(),
                         "Unexpected attribute format. Expected `name = \"value\"`",
@@ -115,6 +150,40 @@ fn get_ident(map: &HashMap<String, Ident>, key: &str, span: Span) -> Result<Iden
         .ok_or_else(|| syn::Error::new(span, format!("Expect attribute {key} but not found")))
 }
 
+/// Get the volatility of the generated UDF from the previous arg map, default to `Immutable`.
+fn get_volatility(map: &HashMap<String, Ident>) -> Result<proc_macro2::TokenStream, syn::Error> {
+    let Some(volatility) = map.get("volatility") else {
+        return Ok(quote!(Volatility::Immutable));
+    };
+    match volatility.to_string().as_str() {
+        "immutable" => Ok(quote!(Volatility::Immutable)),
+        "stable" => Ok(quote!(Volatility::Stable)),
+        "volatile" => Ok(quote!(Volatility::Volatile)),
+        other => Err(syn::Error::new(
+            volatility.span(),
+            format!(
+                "Unknown volatility `{other}`, expected one of `immutable`, `stable` or `volatile`"
+            ),
+        )),
+    }
+}
+
+/// Get a boolean flag like `checked` or `coerce` from the previous arg map, default to
+/// `false`.
+fn get_bool(map: &HashMap<String, Ident>, key: &str) -> Result<bool, syn::Error> {
+    let Some(value) = map.get(key) else {
+        return Ok(false);
+    };
+    match value.to_string().as_str() {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        other => Err(syn::Error::new(
+            value.span(),
+            format!("Unknown {key} `{other}`, expected `true` or `false`"),
+        )),
+    }
+}
+
 /// Extract the argument list from the annotated function.
 fn extract_input_types(inputs: &Punctuated<FnArg, Comma>) -> Result<Vec<Type>, syn::Error> {
     inputs
@@ -126,18 +195,133 @@ fn extract_input_types(inputs: &Punctuated<FnArg, Comma>) -> Result<Vec<Type>, s
         .collect()
 }
 
+/// Get the arrow [DataType] of a supported input array type. Besides the types that can
+/// be returned, `StringArray` is accepted as input.
+fn array_data_type(ty: &Type, span: Span) -> Result<proc_macro2::TokenStream, syn::Error> {
+    match ty {
+        Type::Path(type_path) => match type_path.path.segments.last() {
+            Some(segment) if segment.ident == "StringArray" => Ok(quote!(DataType::Utf8)),
+            Some(segment) => array_data_type_of_ident(&segment.ident),
+            None => Err(syn::Error::new(span, "Expect an array type")),
+        },
+        _ => Err(syn::Error::new(span, "Expect an array type")),
+    }
+}
+
+fn array_data_type_of_ident(ident: &Ident) -> Result<proc_macro2::TokenStream, syn::Error> {
+    match ident.to_string().as_str() {
+        "Float64Array" => Ok(quote!(DataType::Float64)),
+        "Int64Array" => Ok(quote!(DataType::Int64)),
+        "UInt64Array" => Ok(quote!(DataType::UInt64)),
+        "BooleanArray" => Ok(quote!(DataType::Boolean)),
+        "TimestampMillisecondArray" => Ok(quote!(DataType::Timestamp(TimeUnit::Millisecond, None))),
+        other => Err(syn::Error::new(
+            ident.span(),
+            format!(
+                "Unsupported array type `{other}`, expected one of `Float64Array`, `Int64Array`, \
+                `UInt64Array`, `BooleanArray` or `TimestampMillisecondArray`"
+            ),
+        )),
+    }
+}
+
+/// Reject functions returning floats when `ret` is an integer array, the results would
+/// be truncated silently otherwise. `bool` results and `ret = "BooleanArray"` must come
+/// together.
+fn check_return_type(output: &ReturnType, ret_type: &Ident) -> Result<(), syn::Error> {
+    let ret_type = ret_type.to_string();
+    let ReturnType::Type(_, ty) = output else {
+        return Ok(());
+    };
+    let Some(ident) = last_type_ident(ty) else {
+        return Ok(());
+    };
+    let mismatch = match ret_type.as_str() {
+        "Int64Array" | "UInt64Array" => ident == "f32" || ident == "f64" || ident == "bool",
+        "BooleanArray" => ident != "bool",
+        _ => ident == "bool",
+    };
+    if mismatch {
+        return Err(syn::Error::new(
+            ty.span(),
+            format!("Function returning `{ident}` can't be used with `ret = \"{ret_type}\"`"),
+        ));
+    }
+    Ok(())
+}
+
+/// Whether the annotated function returns an `Option<T>`.
+fn returns_option(output: &ReturnType) -> bool {
+    let ReturnType::Type(_, ty) = output else {
+        return false;
+    };
+    let Type::Path(type_path) = ty.as_ref() else {
+        return false;
+    };
+    type_path
+        .path
+        .segments
+        .last()
+        .map(|segment| segment.ident == "Option")
+        .unwrap_or(false)
+}
+
+/// Get the ident of a type path, looking through `Option<T>`.
+fn last_type_ident(ty: &Type) -> Option<&Ident> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if segment.ident == "Option" {
+        if let PathArguments::AngleBracketed(args) = &segment.arguments {
+            if let Some(syn::GenericArgument::Type(inner)) = args.args.first() {
+                return last_type_ident(inner);
+            }
+        }
+        return None;
+    }
+    Some(&segment.ident)
+}
+
+fn unref_type(ty: &Type) -> Type {
+    if let Type::Reference(TypeReference { elem, .. }) = ty {
+        elem.as_ref().clone()
+    } else {
+        ty.clone()
+    }
+}
+
 fn build_struct(
     attrs: Vec<Attribute>,
     vis: Visibility,
     name: Ident,
     display_name_ident: Ident,
+    volatility: proc_macro2::TokenStream,
+    input_data_types: Vec<proc_macro2::TokenStream>,
+    ret_data_type: proc_macro2::TokenStream,
+    coerce: bool,
 ) -> TokenStream {
     let display_name = display_name_ident.to_string();
+    // inputs coercible to the declared types are accepted as well, and casted in `calc`
+    let type_signature = if coerce {
+        quote! {
+            TypeSignature::OneOf(common_query::coercion::coercible_signatures(&Self::input_type()))
+        }
+    } else {
+        quote!(TypeSignature::Exact(Self::input_type()))
+    };
     quote! {
         #(#attrs)*
         #[derive(Debug)]
         #vis struct #name {}
 
+        common_query::range_fn_registry::inventory::submit! {
+            common_query::range_fn_registry::RangeFnRegistration::new(
+                #display_name,
+                #name::scalar_udf,
+            )
+        }
+
         impl #name {
             pub const fn name() -> &'static str {
                 #display_name
@@ -146,70 +330,85 @@ fn build_struct(
             pub fn scalar_udf() -> ScalarUDF {
                 ScalarUDF {
                     name: Self::name().to_string(),
-                    signature: Signature::new(
-                        TypeSignature::Exact(Self::input_type()),
-                        Volatility::Immutable,
-                    ),
+                    signature: Signature::new(#type_signature, #volatility),
                     return_type: Arc::new(|_| Ok(Arc::new(Self::return_type()))),
                     fun: Arc::new(Self::calc),
                 }
             }
 
-            // TODO(ruihang): this should be parameterized
-            // time index column and value column
             fn input_type() -> Vec<DataType> {
                 vec![
-                    RangeArray::convert_data_type(DataType::Timestamp(TimeUnit::Millisecond, None)),
-                    RangeArray::convert_data_type(DataType::Float64),
+                    #( RangeArray::convert_data_type(#input_data_types), )*
                 ]
             }
 
-            // TODO(ruihang): this should be parameterized
             fn return_type() -> DataType {
-                DataType::Float64
+                #ret_data_type
             }
         }
     }
     .into()
 }
 
+#[allow(clippy::too_many_arguments)]
 fn build_calc_fn(
     name: Ident,
     param_types: Vec<Type>,
+    input_data_types: Vec<proc_macro2::TokenStream>,
     fn_name: Ident,
     ret_type: Ident,
+    checked: bool,
+    coerce: bool,
+    wrap_some: bool,
 ) -> TokenStream {
     let param_names = param_types
         .iter()
         .enumerate()
         .map(|(i, ty)| Ident::new(&format!("param_{}", i), ty.span()))
         .collect::<Vec<_>>();
-    let unref_param_types = param_types
-        .iter()
-        .map(|ty| {
-            if let Type::Reference(TypeReference { elem, .. }) = ty {
-                elem.as_ref().clone()
-            } else {
-                ty.clone()
-            }
-        })
-        .collect::<Vec<_>>();
+    let unref_param_types = param_types.iter().map(unref_type).collect::<Vec<_>>();
     let num_params = param_types.len();
-    let param_numbers = (0..num_params).collect::<Vec<_>>();
     let range_array_names = param_names
         .iter()
         .map(|name| Ident::new(&format!("{}_range_array", name), name.span()))
         .collect::<Vec<_>>();
     let first_range_array_name = range_array_names.first().unwrap().clone();
+    let extract_range_arrays = range_array_names
+        .iter()
+        .zip(&unref_param_types)
+        .zip(&input_data_types)
+        .enumerate()
+        .map(|(index, ((range_array_name, param_type), data_type))| {
+            build_extract_range_array(index, range_array_name, param_type, data_type, coerce)
+        })
+        .collect::<Vec<_>>();
+    let call_fn = if checked {
+        let checked_fn_name = checked_fn_name(&fn_name);
+        quote! {
+            match #checked_fn_name(#( &#param_names, )*) {
+                Some(result) => result,
+                None => {
+                    return common_query::error::ArithmeticOverflowSnafu { function: Self::name() }
+                        .fail()
+                        .map_err(DataFusionError::from);
+                }
+            }
+        }
+    } else {
+        quote!(#fn_name(#( &#param_names, )*))
+    };
+    let push_result = if wrap_some {
+        quote!(result_array.push(Some(result)))
+    } else {
+        quote!(result_array.push(result))
+    };
 
     quote! {
         impl #name {
             fn calc(input: &[ColumnarValue]) -> Result<ColumnarValue, DataFusionError> {
                 assert_eq!(input.len(), #num_params);
 
-                #( let #range_array_names = RangeArray::try_new(extract_array(&input[#param_numbers])?.data().clone().into())?; )*
-
-                // TODO(ruihang): add ensure!() 
+                #( #extract_range_arrays )*
 
                 let mut result_array = Vec::new();
                 for index in 0..#first_range_array_name.len(){
@@ -217,8 +416,8 @@ fn build_calc_fn(
 
                     // TODO(ruihang): add ensure!() to check length
 
-                    let result = #fn_name(#( &#param_names, )*);
-                    result_array.push(result);
+                    let result = #call_fn;
+                    #push_result;
                 }
 
                 let result = ColumnarValue::Array(Arc::new(#ret_type::from_iter(result_array)));
@@ -228,3 +427,136 @@ fn build_calc_fn(
     }
     .into()
 }
+
+/// Build the code extracting the `index`-th input to a [RangeArray] named `range_array_name`,
+/// whose values are checked to be `param_type`. Values of other types are casted to
+/// `data_type` if `coerce` is set and the cast never fails, otherwise an error is returned.
+fn build_extract_range_array(
+    index: usize,
+    range_array_name: &Ident,
+    param_type: &Type,
+    data_type: &proc_macro2::TokenStream,
+    coerce: bool,
+) -> proc_macro2::TokenStream {
+    let coerce_arm = coerce.then(|| {
+        quote! {
+            range_array if common_query::coercion::can_coerce(&range_array.value_type(), &#data_type) => {
+                let values = common_query::coercion::cast_values(range_array.values(), &#data_type)?;
+                let ranges = range_array.ranges().flatten().collect::<Vec<_>>();
+                RangeArray::from_ranges(values, ranges)?
+            }
+        }
+    });
+
+    quote! {
+        let #range_array_name = match RangeArray::try_new(extract_array(&input[#index])?.data().clone().into())? {
+            range_array if range_array.values().as_any().is::<#param_type>() => range_array,
+            #coerce_arm
+            range_array => {
+                return common_query::error::RangeFnTypeMismatchSnafu {
+                    function: Self::name(),
+                    index: #index,
+                    expected: #data_type.to_string(),
+                    actual: range_array.value_type().to_string(),
+                }
+                .fail()
+                .map_err(DataFusionError::from);
+            }
+        };
+    }
+}
+
+fn checked_fn_name(fn_name: &Ident) -> Ident {
+    Ident::new(&format!("{fn_name}_checked"), fn_name.span())
+}
+
+/// Build a variant of the annotated function that returns `None` on arithmetic overflow.
+fn build_checked_fn(sig: &Signature, mut block: Block) -> TokenStream {
+    ArithmeticRewriter { checked: true }.visit_block_mut(&mut block);
+
+    let mut checked_sig = sig.clone();
+    checked_sig.ident = checked_fn_name(&sig.ident);
+    let output = &sig.output;
+    let ret = match output {
+        ReturnType::Default => quote!(()),
+        ReturnType::Type(_, ty) => quote!(#ty),
+    };
+    checked_sig.output = parse_quote!(-> Option<#ret>);
+
+    quote! {
+        #[allow(clippy::redundant_closure_call)]
+        #checked_sig {
+            let __range_fn_overflow = std::cell::Cell::new(false);
+            let result = (|| #output #block)();
+            if __range_fn_overflow.get() {
+                None
+            } else {
+                Some(result)
+            }
+        }
+    }
+    .into()
+}
+
+/// Rewrite `+`, `-` and `*` (and their assignment forms) in the annotated function to
+/// wrapping arithmetic, or to arithmetic that records overflow to `__range_fn_overflow` if
+/// `checked` is set. So overflow behaves the same in debug and release builds.
+struct ArithmeticRewriter {
+    checked: bool,
+}
+
+impl ArithmeticRewriter {
+    fn rewrite(&self, op: &BinOp, left: &Expr, right: &Expr) -> Option<Expr> {
+        let method = match op {
+            BinOp::Add(_) | BinOp::AddEq(_) => "add",
+            BinOp::Sub(_) | BinOp::SubEq(_) => "sub",
+            BinOp::Mul(_) | BinOp::MulEq(_) => "mul",
+            _ => return None,
+        };
+        if self.checked {
+            let method = Ident::new(&format!("overflowing_{method}"), op.span());
+            Some(parse_quote! {
+                {
+                    let (value, overflow) =
+                        common_query::arithmetic::OverflowingArithmetic::#method(#left, #right);
+                    if overflow {
+                        __range_fn_overflow.set(true);
+                    }
+                    value
+                }
+            })
+        } else {
+            let method = Ident::new(&format!("wrapping_{method}"), op.span());
+            Some(parse_quote! {
+                common_query::arithmetic::OverflowingArithmetic::#method(#left, #right)
+            })
+        }
+    }
+}
+
+impl VisitMut for ArithmeticRewriter {
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        syn::visit_mut::visit_expr_mut(self, expr);
+
+        match expr {
+            Expr::Binary(ExprBinary {
+                left, op, right, ..
+            }) => {
+                if let Some(rewritten) = self.rewrite(op, left, right) {
+                    *expr = rewritten;
+                }
+            }
+            Expr::AssignOp(ExprAssignOp {
+                left, op, right, ..
+            }) => {
+                if let Some(rewritten) = self.rewrite(op, left, right) {
+                    *expr = parse_quote!(#left = #rewritten);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // nested items are not part of the annotated function
+    fn visit_item_mut(&mut self, _: &mut Item) {}
+}
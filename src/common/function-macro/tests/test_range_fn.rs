@@ -0,0 +1,21 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[test]
+fn test_range_fn_compile_fail() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/range_fn_invalid_volatility.rs");
+    t.compile_fail("tests/ui/range_fn_float_ret_mismatch.rs");
+    t.compile_fail("tests/ui/range_fn_bool_ret_mismatch.rs");
+}
@@ -0,0 +1,167 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Compares the rows/sec of converting gRPC insert requests and Arrow record batches, both
+//! encoded to and decoded from protobuf bytes, to table insert requests.
+
+use std::sync::Arc;
+
+use api::v1::column::{SemanticType, Values};
+use api::v1::{Column, ColumnDataType, InsertRequest as GrpcInsertRequest};
+use arrow_flight::FlightData;
+use common_grpc::flight::{FlightDecoder, FlightEncoder, FlightMessage};
+use common_grpc_expr::insert::{record_batch_to_table_insert_request, to_table_insert_request};
+use common_recordbatch::RecordBatch;
+use criterion::{criterion_group, BenchmarkId, Criterion, Throughput};
+use datatypes::data_type::ConcreteDataType;
+use datatypes::schema::{ColumnSchema, Schema, SchemaRef};
+use datatypes::vectors::{Float64Vector, StringVector, TimestampMillisecondVector};
+use prost::Message;
+
+fn new_grpc_request(rows: usize) -> Vec<u8> {
+    let column = |name: &str, semantic_type: SemanticType, datatype, values| Column {
+        column_name: name.to_string(),
+        semantic_type: semantic_type as i32,
+        values: Some(values),
+        datatype: datatype as i32,
+        ..Default::default()
+    };
+    let request = GrpcInsertRequest {
+        table_name: "demo".to_string(),
+        columns: vec![
+            column(
+                "host",
+                SemanticType::Tag,
+                ColumnDataType::String,
+                Values {
+                    string_values: (0..rows).map(|i| format!("host{}", i % 100)).collect(),
+                    ..Default::default()
+                },
+            ),
+            column(
+                "cpu",
+                SemanticType::Field,
+                ColumnDataType::Float64,
+                Values {
+                    f64_values: (0..rows).map(|i| i as f64).collect(),
+                    ..Default::default()
+                },
+            ),
+            column(
+                "ts",
+                SemanticType::Timestamp,
+                ColumnDataType::TimestampMillisecond,
+                Values {
+                    ts_millisecond_values: (0..rows as i64).collect(),
+                    ..Default::default()
+                },
+            ),
+        ],
+        row_count: rows as u32,
+        region_number: 0,
+    };
+    request.encode_to_vec()
+}
+
+fn new_table_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        ColumnSchema::new("host", ConcreteDataType::string_datatype(), true),
+        ColumnSchema::new("cpu", ConcreteDataType::float64_datatype(), true),
+        ColumnSchema::new(
+            "ts",
+            ConcreteDataType::timestamp_millisecond_datatype(),
+            false,
+        ),
+    ]))
+}
+
+fn new_flight_data(schema: &SchemaRef, rows: usize) -> Vec<Vec<u8>> {
+    let batch = RecordBatch::new(
+        schema.clone(),
+        vec![
+            Arc::new(StringVector::from(
+                (0..rows)
+                    .map(|i| format!("host{}", i % 100))
+                    .collect::<Vec<_>>(),
+            )) as _,
+            Arc::new(Float64Vector::from_vec(
+                (0..rows).map(|i| i as f64).collect(),
+            )) as _,
+            Arc::new(TimestampMillisecondVector::from_vec(
+                (0..rows as i64).collect(),
+            )) as _,
+        ],
+    )
+    .unwrap();
+    let mut encoder = FlightEncoder::default();
+    vec![
+        encoder.encode(FlightMessage::Schema(schema.clone())),
+        encoder.encode(FlightMessage::Recordbatch(batch)),
+    ]
+    .into_iter()
+    .map(|flight_data| flight_data.encode_to_vec())
+    .collect()
+}
+
+fn grpc_insert(bytes: &[u8]) -> usize {
+    let request = GrpcInsertRequest::decode(bytes).unwrap();
+    let request = to_table_insert_request("greptime", "public", request).unwrap();
+    request.columns_values.len()
+}
+
+fn record_batch_insert(table_schema: &SchemaRef, messages: &[Vec<u8>]) -> usize {
+    let mut decoder = FlightDecoder::default();
+    let mut columns = 0;
+    for bytes in messages {
+        let flight_data = FlightData::decode(bytes.as_slice()).unwrap();
+        if let FlightMessage::Recordbatch(batch) = decoder.try_decode(flight_data).unwrap() {
+            let request = record_batch_to_table_insert_request(
+                "greptime",
+                "public",
+                "demo",
+                0,
+                table_schema,
+                &batch,
+            )
+            .unwrap();
+            columns += request.columns_values.len();
+        }
+    }
+    columns
+}
+
+fn bench_insert(c: &mut Criterion) {
+    let mut group = c.benchmark_group("insert");
+    let table_schema = new_table_schema();
+    for rows in [1024, 16384] {
+        group.throughput(Throughput::Elements(rows as u64));
+
+        let grpc_request = new_grpc_request(rows);
+        group.bench_with_input(
+            BenchmarkId::new("grpc_insert_request", rows),
+            &grpc_request,
+            |b, bytes| b.iter(|| grpc_insert(bytes)),
+        );
+
+        let flight_data = new_flight_data(&table_schema, rows);
+        group.bench_with_input(
+            BenchmarkId::new("record_batch", rows),
+            &flight_data,
+            |b, messages| b.iter(|| record_batch_insert(&table_schema, messages)),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_insert);
@@ -12,14 +12,16 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+
 use api::v1::alter_expr::Kind;
-use api::v1::{column_def, AlterExpr, CreateTableExpr, DropColumns, RenameTable};
+use api::v1::{column_def, AlterExpr, CreateTableExpr, DropColumn, DropColumns, RenameTable};
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
 use datatypes::schema::{ColumnSchema, RawSchema};
 use snafu::{ensure, OptionExt, ResultExt};
 use table::metadata::TableId;
 use table::requests::{
-    AddColumnRequest, AlterKind, AlterTableRequest, CreateTableRequest, TableOptions,
+    AddColumnRequest, AlterKind, AlterTableRequest, CreateTableRequest, TableOptions, TTL_KEY,
 };
 
 use crate::error::{
@@ -27,6 +29,38 @@ use crate::error::{
     Result, UnrecognizedTableOptionSnafu,
 };
 
+/// Name of the pseudo column dropped by an [`AlterExpr`] to set the TTL of the table.
+///
+/// [`AlterExpr`] has no kind to alter table options, so `SET TTL` is encoded as dropping the
+/// only column `__set_ttl=<ttl>`, and `UNSET TTL` as dropping the only column `__set_ttl`.
+/// So columns of these names can't be dropped alone.
+const SET_TTL_COLUMN: &str = "__set_ttl";
+
+/// Returns the kind of an [`AlterExpr`] setting the TTL of the table to `ttl`, or unsetting
+/// it if `ttl` is `None`.
+pub fn set_ttl_kind(ttl: Option<&str>) -> Kind {
+    let name = match ttl {
+        Some(ttl) => format!("{SET_TTL_COLUMN}={ttl}"),
+        None => SET_TTL_COLUMN.to_string(),
+    };
+    Kind::DropColumns(DropColumns {
+        drop_columns: vec![DropColumn { name }],
+    })
+}
+
+/// Decodes the TTL encoded by [`set_ttl_kind`], returns `None` if the columns are really
+/// dropped.
+fn decode_set_ttl(drop_columns: &[DropColumn]) -> Option<Option<&str>> {
+    let [column] = drop_columns else {
+        return None;
+    };
+    let rest = column.name.strip_prefix(SET_TTL_COLUMN)?;
+    if rest.is_empty() {
+        return Some(None);
+    }
+    rest.strip_prefix('=').map(Some)
+}
+
 /// Convert an [`AlterExpr`] to an [`AlterTableRequest`]
 pub fn alter_expr_to_request(expr: AlterExpr) -> Result<AlterTableRequest> {
     let catalog_name = expr.catalog_name;
@@ -67,8 +101,17 @@ pub fn alter_expr_to_request(expr: AlterExpr) -> Result<AlterTableRequest> {
             Ok(request)
         }
         Kind::DropColumns(DropColumns { drop_columns }) => {
-            let alter_kind = AlterKind::DropColumns {
-                names: drop_columns.into_iter().map(|c| c.name).collect(),
+            let alter_kind = match decode_set_ttl(&drop_columns) {
+                Some(Some(ttl)) => {
+                    let options = HashMap::from([(TTL_KEY.to_string(), ttl.to_string())]);
+                    let options =
+                        TableOptions::try_from(&options).context(UnrecognizedTableOptionSnafu)?;
+                    AlterKind::SetTtl { ttl: options.ttl }
+                }
+                Some(None) => AlterKind::SetTtl { ttl: None },
+                None => AlterKind::DropColumns {
+                    names: drop_columns.into_iter().map(|c| c.name).collect(),
+                },
             };
 
             let request = AlterTableRequest {
@@ -183,7 +226,9 @@ pub fn create_expr_to_request(
 
 #[cfg(test)]
 mod tests {
-    use api::v1::{AddColumn, AddColumns, ColumnDataType, ColumnDef, DropColumn};
+    use std::time::Duration;
+
+    use api::v1::{AddColumn, AddColumns, ColumnDataType, ColumnDef};
     use datatypes::prelude::ConcreteDataType;
 
     use super::*;
@@ -251,4 +296,46 @@ mod tests {
         assert_eq!(1, drop_names.len());
         assert_eq!("mem_usage".to_string(), drop_names.pop().unwrap());
     }
+
+    #[test]
+    fn test_set_ttl_expr() {
+        let expr = |kind| AlterExpr {
+            catalog_name: "test_catalog".to_string(),
+            schema_name: "test_schema".to_string(),
+            table_name: "monitor".to_string(),
+            kind: Some(kind),
+        };
+
+        let alter_request = alter_expr_to_request(expr(set_ttl_kind(Some("1h")))).unwrap();
+        assert_eq!("monitor", alter_request.table_name);
+        match alter_request.alter_kind {
+            AlterKind::SetTtl { ttl } => assert_eq!(Some(Duration::from_secs(3600)), ttl),
+            _ => unreachable!(),
+        }
+
+        let alter_request = alter_expr_to_request(expr(set_ttl_kind(None))).unwrap();
+        assert!(matches!(
+            alter_request.alter_kind,
+            AlterKind::SetTtl { ttl: None }
+        ));
+
+        assert!(alter_expr_to_request(expr(set_ttl_kind(Some("foo")))).is_err());
+
+        // Columns with names like the pseudo column are dropped with other columns.
+        let kind = Kind::DropColumns(DropColumns {
+            drop_columns: vec![
+                DropColumn {
+                    name: "__set_ttl".to_string(),
+                },
+                DropColumn {
+                    name: "mem_usage".to_string(),
+                },
+            ],
+        });
+        let alter_request = alter_expr_to_request(expr(kind)).unwrap();
+        assert!(matches!(
+            alter_request.alter_kind,
+            AlterKind::DropColumns { names } if names.len() == 2
+        ));
+    }
 }
@@ -17,6 +17,8 @@ use std::any::Any;
 use api::DecodeError;
 use common_error::ext::ErrorExt;
 use common_error::prelude::{Snafu, StatusCode};
+use datatypes::arrow::error::ArrowError;
+use datatypes::data_type::ConcreteDataType;
 use snafu::{Backtrace, ErrorCompat};
 
 #[derive(Debug, Snafu)]
@@ -85,6 +87,32 @@ pub enum Error {
         source: api::error::Error,
     },
 
+    #[snafu(display(
+        "Column `{}` of type {:?} is incompatible with table column of type {:?}",
+        column,
+        actual,
+        expected
+    ))]
+    IncompatibleColumnType {
+        column: String,
+        expected: ConcreteDataType,
+        actual: ConcreteDataType,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display(
+        "Failed to cast column `{}` to {:?}, source: {}",
+        column,
+        expected,
+        source
+    ))]
+    CastColumn {
+        column: String,
+        expected: ConcreteDataType,
+        source: ArrowError,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Unrecognized table option: {}", source))]
     UnrecognizedTableOption {
         #[snafu(backtrace)]
@@ -111,6 +139,9 @@ impl ErrorExt for Error {
             Error::ColumnDefaultConstraint { source, .. } => source.status_code(),
             Error::InvalidColumnDef { source, .. } => source.status_code(),
             Error::UnrecognizedTableOption { .. } => StatusCode::InvalidArguments,
+            Error::IncompatibleColumnType { .. } | Error::CastColumn { .. } => {
+                StatusCode::InvalidArguments
+            }
         }
     }
     fn backtrace_opt(&self) -> Option<&Backtrace> {
@@ -21,20 +21,23 @@ use api::v1::{
     InsertRequest as GrpcInsertRequest,
 };
 use common_base::BitVec;
+use common_recordbatch::RecordBatch;
 use common_time::timestamp::Timestamp;
 use common_time::{Date, DateTime};
+use datatypes::arrow::compute::{cast_with_options, CastOptions};
 use datatypes::data_type::{ConcreteDataType, DataType};
 use datatypes::prelude::{ValueRef, VectorRef};
 use datatypes::schema::SchemaRef;
 use datatypes::types::TimestampType;
 use datatypes::value::Value;
-use datatypes::vectors::MutableVector;
+use datatypes::vectors::{Helper, MutableVector};
 use snafu::{ensure, OptionExt, ResultExt};
 use table::metadata::TableId;
 use table::requests::InsertRequest;
 
 use crate::error::{
-    ColumnDataTypeSnafu, CreateVectorSnafu, DuplicatedTimestampColumnSnafu, IllegalInsertDataSnafu,
+    CastColumnSnafu, ColumnDataTypeSnafu, ColumnNotFoundSnafu, CreateVectorSnafu,
+    DuplicatedTimestampColumnSnafu, IllegalInsertDataSnafu, IncompatibleColumnTypeSnafu,
     InvalidColumnProtoSnafu, MissingTimestampColumnSnafu, Result,
 };
 const TAG_SEMANTIC_TYPE: i32 = SemanticType::Tag as i32;
@@ -305,6 +308,78 @@ pub fn to_table_insert_request(
     })
 }
 
+/// Converts a record batch to an [InsertRequest] of the table with `table_schema`.
+///
+/// Columns are moved into the request as is, without building rows. A column whose type
+/// differs from the table column is cast to the table column's type if both are numeric
+/// or timestamp types, and the cast fails if any value overflows.
+pub fn record_batch_to_table_insert_request(
+    catalog_name: &str,
+    schema_name: &str,
+    table_name: &str,
+    region_number: u32,
+    table_schema: &SchemaRef,
+    batch: &RecordBatch,
+) -> Result<InsertRequest> {
+    let mut columns_values = HashMap::with_capacity(batch.num_columns());
+    for (column_schema, vector) in batch.schema.column_schemas().iter().zip(batch.columns()) {
+        let column_name = &column_schema.name;
+        let expected = &table_schema
+            .column_schema_by_name(column_name)
+            .context(ColumnNotFoundSnafu {
+                column_name,
+                table_name,
+            })?
+            .data_type;
+        let vector = cast_column(column_name, vector, expected)?;
+
+        ensure!(
+            columns_values.insert(column_name.clone(), vector).is_none(),
+            IllegalInsertDataSnafu
+        );
+    }
+
+    Ok(InsertRequest {
+        catalog_name: catalog_name.to_string(),
+        schema_name: schema_name.to_string(),
+        table_name: table_name.to_string(),
+        columns_values,
+        region_number,
+    })
+}
+
+fn cast_column(column: &str, vector: &VectorRef, expected: &ConcreteDataType) -> Result<VectorRef> {
+    let actual = vector.data_type();
+    if actual == *expected {
+        return Ok(vector.clone());
+    }
+
+    let numerics = ConcreteDataType::numerics();
+    let castable = (numerics.contains(&actual) && numerics.contains(expected))
+        || (actual.is_timestamp_compatible() && expected.is_timestamp_compatible());
+    ensure!(
+        castable,
+        IncompatibleColumnTypeSnafu {
+            column,
+            expected: expected.clone(),
+            actual,
+        }
+    );
+
+    // Not safe, so values overflowing the target type are errors instead of nulls.
+    let options = CastOptions { safe: false };
+    let array = cast_with_options(
+        &vector.to_arrow_array(),
+        &expected.as_arrow_type(),
+        &options,
+    )
+    .context(CastColumnSnafu {
+        column,
+        expected: expected.clone(),
+    })?;
+    Helper::try_into_vector(array).context(CreateVectorSnafu)
+}
+
 fn add_values_to_builder(
     builder: &mut Box<dyn MutableVector>,
     values: Values,
@@ -462,6 +537,7 @@ mod tests {
     use datatypes::schema::{ColumnSchema, SchemaBuilder, SchemaRef};
     use datatypes::types::{TimestampMillisecondType, TimestampSecondType, TimestampType};
     use datatypes::value::Value;
+    use datatypes::vectors::{Int32Vector, Int64Vector, StringVector, TimestampSecondVector};
     use snafu::ResultExt;
     use table::error::Result as TableResult;
     use table::metadata::TableInfoRef;
@@ -644,6 +720,109 @@ mod tests {
         assert_eq!(Value::Timestamp(Timestamp::new_millisecond(101)), ts.get(1));
     }
 
+    fn new_record_batch(columns: Vec<(&str, VectorRef)>) -> RecordBatch {
+        let column_schemas = columns
+            .iter()
+            .map(|(name, vector)| ColumnSchema::new(*name, vector.data_type(), true))
+            .collect();
+        let schema = Arc::new(
+            SchemaBuilder::try_from(column_schemas)
+                .unwrap()
+                .build()
+                .unwrap(),
+        );
+        RecordBatch::new(schema, columns.into_iter().map(|(_, vector)| vector)).unwrap()
+    }
+
+    fn new_table_schema() -> SchemaRef {
+        let columns = vec![
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), true),
+            ColumnSchema::new("cpu", ConcreteDataType::int32_datatype(), true),
+            ColumnSchema::new(
+                "ts",
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                false,
+            )
+            .with_time_index(true),
+        ];
+        Arc::new(SchemaBuilder::try_from(columns).unwrap().build().unwrap())
+    }
+
+    #[test]
+    fn test_record_batch_to_table_insert_request() {
+        let batch = new_record_batch(vec![
+            (
+                "host",
+                Arc::new(StringVector::from(vec!["host1", "host2"])) as _,
+            ),
+            ("cpu", Arc::new(Int64Vector::from_slice([1, 2])) as _),
+            (
+                "ts",
+                Arc::new(TimestampSecondVector::from_slice([100, 101])) as _,
+            ),
+        ]);
+        let table_schema = new_table_schema();
+        let request = record_batch_to_table_insert_request(
+            "greptime",
+            "public",
+            "demo",
+            1,
+            &table_schema,
+            &batch,
+        )
+        .unwrap();
+
+        assert_eq!("demo", request.table_name);
+        assert_eq!(1, request.region_number);
+        let host = request.columns_values.get("host").unwrap();
+        assert_eq!(Value::String("host2".into()), host.get(1));
+        let cpu = request.columns_values.get("cpu").unwrap();
+        assert_eq!(ConcreteDataType::int32_datatype(), cpu.data_type());
+        assert_eq!(Value::Int32(2), cpu.get(1));
+        let ts = request.columns_values.get("ts").unwrap();
+        assert_eq!(
+            Value::Timestamp(Timestamp::new_millisecond(100_000)),
+            ts.get(0)
+        );
+    }
+
+    #[test]
+    fn test_record_batch_to_table_insert_request_error() {
+        let table_schema = new_table_schema();
+        let convert = |batch: RecordBatch| {
+            record_batch_to_table_insert_request(
+                "greptime",
+                "public",
+                "demo",
+                0,
+                &table_schema,
+                &batch,
+            )
+            .unwrap_err()
+        };
+
+        let batch = new_record_batch(vec![(
+            "cpu",
+            Arc::new(Int64Vector::from_slice([1, i64::MAX])) as _,
+        )]);
+        assert!(matches!(convert(batch), error::Error::CastColumn { .. }));
+
+        let batch = new_record_batch(vec![("cpu", Arc::new(StringVector::from(vec!["1"])) as _)]);
+        assert!(matches!(
+            convert(batch),
+            error::Error::IncompatibleColumnType { .. }
+        ));
+
+        let batch = new_record_batch(vec![(
+            "memory",
+            Arc::new(Int32Vector::from_slice([1])) as _,
+        )]);
+        assert!(matches!(
+            convert(batch),
+            error::Error::ColumnNotFound { .. }
+        ));
+    }
+
     #[test]
     fn test_convert_values() {
         let data_type = ConcreteDataType::float64_datatype();
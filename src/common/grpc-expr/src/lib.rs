@@ -16,5 +16,5 @@ mod alter;
 pub mod error;
 pub mod insert;
 
-pub use alter::{alter_expr_to_request, create_expr_to_request, create_table_schema};
+pub use alter::{alter_expr_to_request, create_expr_to_request, create_table_schema, set_ttl_kind};
 pub use insert::{build_create_expr_from_insertion, column_to_vector, find_new_columns};
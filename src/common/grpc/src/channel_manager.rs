@@ -22,8 +22,10 @@ use snafu::{OptionExt, ResultExt};
 use tonic::transport::{
     Certificate, Channel as InnerChannel, ClientTlsConfig, Endpoint, Identity, Uri,
 };
+use tower::discover::Change;
 use tower::make::MakeConnection;
 
+use crate::compression::GrpcCompression;
 use crate::error::{CreateChannelSnafu, InvalidConfigFilePathSnafu, InvalidTlsConfigSnafu, Result};
 
 const RECYCLE_CHANNEL_INTERVAL_SECS: u64 = 60;
@@ -106,7 +108,7 @@ impl ChannelManager {
             }
             Entry::Vacant(entry) => {
                 let endpoint = self.build_endpoint(addr)?;
-                let inner_channel = endpoint.connect_lazy();
+                let inner_channel = self.connect_lazy(endpoint);
 
                 let channel = Channel {
                     channel: inner_channel,
@@ -150,6 +152,22 @@ impl ChannelManager {
         self.pool.retain_channel(f);
     }
 
+    /// Connects to the endpoint with `pool_size` connections, the requests are
+    /// balanced across them.
+    fn connect_lazy(&self, endpoint: Endpoint) -> InnerChannel {
+        if self.config.pool_size <= 1 {
+            return endpoint.connect_lazy();
+        }
+
+        let (channel, sender) = InnerChannel::balance_channel(self.config.pool_size);
+        // The endpoints are keyed by index, so the same endpoint is connected once per key.
+        for key in 0..self.config.pool_size {
+            // Never fails, the buffer of the sender is as large as the pool.
+            let _ = sender.try_send(Change::Insert(key, endpoint.clone()));
+        }
+        channel
+    }
+
     fn build_endpoint(&self, addr: &str) -> Result<Endpoint> {
         let mut endpoint = Endpoint::new(format!("http://{addr}")).context(CreateChannelSnafu)?;
 
@@ -219,6 +237,8 @@ pub struct ChannelConfig {
     pub tcp_keepalive: Option<Duration>,
     pub tcp_nodelay: bool,
     pub client_tls: Option<ClientTlsOption>,
+    pub pool_size: usize,
+    pub compression: GrpcCompression,
 }
 
 impl Default for ChannelConfig {
@@ -237,6 +257,8 @@ impl Default for ChannelConfig {
             tcp_keepalive: None,
             tcp_nodelay: true,
             client_tls: None,
+            pool_size: 1,
+            compression: GrpcCompression::None,
         }
     }
 }
@@ -354,6 +376,27 @@ impl ChannelConfig {
         }
     }
 
+    /// The number of connections to each address, the requests to an address are
+    /// balanced across its connections.
+    ///
+    /// Default to 1.
+    pub fn pool_size(self, size: usize) -> Self {
+        Self {
+            pool_size: size,
+            ..self
+        }
+    }
+
+    /// How the clients built on the channels compress the requests.
+    ///
+    /// Default to no compression.
+    pub fn compression(self, compression: GrpcCompression) -> Self {
+        Self {
+            compression,
+            ..self
+        }
+    }
+
     /// Set the value of tls client auth.
     ///
     /// Disabled by default.
@@ -504,6 +547,8 @@ mod tests {
                 tcp_keepalive: None,
                 tcp_nodelay: true,
                 client_tls: None,
+                pool_size: 1,
+                compression: GrpcCompression::None,
             },
             default_cfg
         );
@@ -521,6 +566,8 @@ mod tests {
             .http2_adaptive_window(true)
             .tcp_keepalive(Duration::from_secs(2))
             .tcp_nodelay(false)
+            .pool_size(4)
+            .compression(GrpcCompression::Gzip)
             .client_tls_config(ClientTlsOption {
                 server_ca_cert_path: "some_server_path".to_string(),
                 client_cert_path: "some_cert_path".to_string(),
@@ -546,6 +593,8 @@ mod tests {
                     client_cert_path: "some_cert_path".to_string(),
                     client_key_path: "some_key_path".to_string(),
                 }),
+                pool_size: 4,
+                compression: GrpcCompression::Gzip,
             },
             cfg
         );
@@ -0,0 +1,91 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Compression of gRPC messages.
+//!
+//! Peers always accept every encoding we support, the setting only decides how the
+//! messages we send are compressed. So clients and servers with different settings can
+//! still talk to each other.
+
+use std::sync::Once;
+
+use common_telemetry::logging;
+use serde::{Deserialize, Serialize};
+use tonic::codec::CompressionEncoding;
+
+/// Encodings of compressed messages accepted from peers.
+pub const ACCEPTED_ENCODINGS: [CompressionEncoding; 1] = [CompressionEncoding::Gzip];
+
+/// How to compress the gRPC messages sent to peers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GrpcCompression {
+    #[default]
+    None,
+    Gzip,
+    /// Not supported by the gRPC library yet, messages are compressed with gzip instead.
+    Zstd,
+}
+
+impl GrpcCompression {
+    /// Returns the encoding to compress sent messages, or `None` if they are sent
+    /// uncompressed.
+    pub fn encoding(&self) -> Option<CompressionEncoding> {
+        match self {
+            GrpcCompression::None => None,
+            GrpcCompression::Gzip => Some(CompressionEncoding::Gzip),
+            GrpcCompression::Zstd => {
+                static WARN_ZSTD: Once = Once::new();
+                WARN_ZSTD.call_once(|| {
+                    logging::warn!("zstd gRPC compression is not supported, fall back to gzip")
+                });
+                Some(CompressionEncoding::Gzip)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Deserialize)]
+    struct Options {
+        grpc_compression: GrpcCompression,
+    }
+
+    #[test]
+    fn test_grpc_compression() {
+        for (value, compression, encoding) in [
+            ("none", GrpcCompression::None, None),
+            (
+                "gzip",
+                GrpcCompression::Gzip,
+                Some(CompressionEncoding::Gzip),
+            ),
+            (
+                "zstd",
+                GrpcCompression::Zstd,
+                Some(CompressionEncoding::Gzip),
+            ),
+        ] {
+            let options: Options =
+                serde_json::from_str(&format!(r#"{{"grpc_compression": "{value}"}}"#)).unwrap();
+            assert_eq!(compression, options.grpc_compression);
+            assert_eq!(encoding, compression.encoding());
+        }
+
+        assert!(serde_json::from_str::<Options>(r#"{"grpc_compression": "lz4"}"#).is_err());
+    }
+}
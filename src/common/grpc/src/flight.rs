@@ -15,10 +15,11 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use api::v1::{AffectedRows, FlightMetadata};
+use api::v1::{AffectedRows, FlightMetadata, RequestHeader};
 use arrow_flight::utils::flight_data_to_arrow_batch;
-use arrow_flight::{FlightData, IpcMessage, SchemaAsIpc};
+use arrow_flight::{FlightData, IpcMessage, PutResult, SchemaAsIpc};
 use common_base::bytes::Bytes;
+use common_recordbatch::scan_stats::ScanStats;
 use common_recordbatch::{RecordBatch, RecordBatches};
 use datatypes::arrow;
 use datatypes::arrow::datatypes::Schema as ArrowSchema;
@@ -33,11 +34,45 @@ use crate::error::{
     Result,
 };
 
+/// The gRPC request metadata key a `DoGet` client sets to "true" to have the [ScanStats] of
+/// the query sent after its record batches.
+pub const SCAN_STATS_HEADER: &str = "x-greptime-scan-stats";
+
+/// The gRPC request metadata key a `DoGet` client sets to "true" to drop a table with `SYNC`,
+/// i.e. wait for the data of the table to be deleted. [DropTableExpr] has no field for it.
+///
+/// [DropTableExpr]: api::v1::DropTableExpr
+pub const DROP_TABLE_SYNC_HEADER: &str = "x-greptime-drop-table-sync";
+
+/// The gRPC request metadata key a `DoGet` client sets to execute all statements of a SQL
+/// query in order. The value is what to do once a statement fails, [ON_ERROR_STOP] or
+/// [ON_ERROR_CONTINUE]. Outputs of each statement are sent in full, then a
+/// [FlightMessage::StatementEnd] of the statement.
+pub const MULTI_STATEMENTS_HEADER: &str = "x-greptime-multi-statements";
+/// Skips the following statements once a statement fails.
+pub const ON_ERROR_STOP: &str = "stop";
+/// Executes the following statements even if a statement fails.
+pub const ON_ERROR_CONTINUE: &str = "continue";
+
+/// The gRPC request metadata key a `DoPut` client sets to the id of a bulk load, so the record
+/// batches are written to the bulk load instead of the table.
+pub const BULK_LOAD_HEADER: &str = "x-greptime-bulk-load";
+/// Type of the Flight action beginning a bulk load of a table, see [BulkLoadRequest].
+pub const BEGIN_BULK_LOAD: &str = "begin_bulk_load";
+/// Type of the Flight action committing a bulk load.
+pub const COMMIT_BULK_LOAD: &str = "commit_bulk_load";
+/// Type of the Flight action aborting a bulk load.
+pub const ABORT_BULK_LOAD: &str = "abort_bulk_load";
+
 #[derive(Debug, Clone)]
 pub enum FlightMessage {
     Schema(SchemaRef),
     Recordbatch(RecordBatch),
     AffectedRows(usize),
+    /// Stats of the scans executed by the query, sent after all record batches.
+    ScanStats(ScanStats),
+    /// End of the outputs of a statement of a multi-statement query.
+    StatementEnd(StatementResult),
 }
 
 pub struct FlightEncoder {
@@ -93,6 +128,30 @@ impl FlightEncoder {
                     vec![],
                 )
             }
+            FlightMessage::ScanStats(stats) => {
+                let metadata = ScanStatsMetadata {
+                    scan_stats: Some(stats.into()),
+                }
+                .encode_to_vec();
+                FlightData::new(
+                    None,
+                    IpcMessage(build_none_flight_msg().into()),
+                    metadata,
+                    vec![],
+                )
+            }
+            FlightMessage::StatementEnd(result) => {
+                let metadata = StatementEndMetadata {
+                    statement_end: Some(result),
+                }
+                .encode_to_vec();
+                FlightData::new(
+                    None,
+                    IpcMessage(build_none_flight_msg().into()),
+                    metadata,
+                    vec![],
+                )
+            }
         }
     }
 }
@@ -113,11 +172,21 @@ impl FlightDecoder {
         })?;
         match message.header_type() {
             MessageHeader::NONE => {
-                let metadata = FlightMetadata::decode(flight_data.app_metadata)
+                let metadata = FlightMetadata::decode(flight_data.app_metadata.clone())
                     .context(DecodeFlightDataSnafu)?;
                 if let Some(AffectedRows { value }) = metadata.affected_rows {
                     return Ok(FlightMessage::AffectedRows(value as _));
                 }
+                let metadata = ScanStatsMetadata::decode(flight_data.app_metadata.clone())
+                    .context(DecodeFlightDataSnafu)?;
+                if let Some(stats) = metadata.scan_stats {
+                    return Ok(FlightMessage::ScanStats(stats.into()));
+                }
+                let metadata = StatementEndMetadata::decode(flight_data.app_metadata)
+                    .context(DecodeFlightDataSnafu)?;
+                if let Some(result) = metadata.statement_end {
+                    return Ok(FlightMessage::StatementEnd(result));
+                }
                 InvalidFlightDataSnafu {
                     reason: "Expecting FlightMetadata have some meaningful content.",
                 }
@@ -166,6 +235,136 @@ impl FlightDecoder {
     }
 }
 
+/// Metadata of the [FlightData] carrying [FlightMessage::ScanStats]. Its tag is disjoint from
+/// the fields of [FlightMetadata], so it can be told apart from other metadata.
+#[derive(Clone, PartialEq, prost::Message)]
+struct ScanStatsMetadata {
+    #[prost(message, optional, tag = "16")]
+    scan_stats: Option<FlightScanStats>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct FlightScanStats {
+    #[prost(uint64, tag = "1")]
+    files_scanned: u64,
+    #[prost(uint64, tag = "2")]
+    files_pruned: u64,
+    #[prost(uint64, tag = "3")]
+    rows_decoded: u64,
+    #[prost(uint64, tag = "4")]
+    bytes_read: u64,
+}
+
+impl From<ScanStats> for FlightScanStats {
+    fn from(stats: ScanStats) -> Self {
+        FlightScanStats {
+            files_scanned: stats.files_scanned,
+            files_pruned: stats.files_pruned,
+            rows_decoded: stats.rows_decoded,
+            bytes_read: stats.bytes_read,
+        }
+    }
+}
+
+impl From<FlightScanStats> for ScanStats {
+    fn from(stats: FlightScanStats) -> Self {
+        ScanStats {
+            files_scanned: stats.files_scanned,
+            files_pruned: stats.files_pruned,
+            rows_decoded: stats.rows_decoded,
+            bytes_read: stats.bytes_read,
+        }
+    }
+}
+
+/// Metadata of the [FlightData] carrying [FlightMessage::StatementEnd], with a tag disjoint
+/// from other metadata like [ScanStatsMetadata].
+#[derive(Clone, PartialEq, prost::Message)]
+struct StatementEndMetadata {
+    #[prost(message, optional, tag = "17")]
+    statement_end: Option<StatementResult>,
+}
+
+/// Result of a statement of a multi-statement query.
+#[derive(Clone, PartialEq, Eq, prost::Message)]
+pub struct StatementResult {
+    /// Index of the statement in the query, starting from 0.
+    #[prost(uint64, tag = "1")]
+    pub statement_index: u64,
+    /// Name of the status code of the error, empty if the statement succeeds.
+    #[prost(string, tag = "2")]
+    pub err_code: String,
+    #[prost(string, tag = "3")]
+    pub err_msg: String,
+}
+
+/// Result of writing one record batch of a `DoPut` stream, carried in the `app_metadata` of
+/// the [PutResult] returned for the batch.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct PutBatchResult {
+    /// Index of the record batch in the stream, starting from 0.
+    #[prost(uint64, tag = "1")]
+    pub batch_index: u64,
+    #[prost(uint64, tag = "2")]
+    pub affected_rows: u64,
+    /// Name of the status code of the error, empty if the batch is written.
+    #[prost(string, tag = "3")]
+    pub err_code: String,
+    #[prost(string, tag = "4")]
+    pub err_msg: String,
+}
+
+impl From<PutBatchResult> for PutResult {
+    fn from(result: PutBatchResult) -> Self {
+        PutResult {
+            app_metadata: result.encode_to_vec().into(),
+        }
+    }
+}
+
+impl TryFrom<PutResult> for PutBatchResult {
+    type Error = crate::error::Error;
+
+    fn try_from(result: PutResult) -> Result<Self> {
+        PutBatchResult::decode(result.app_metadata).context(DecodeFlightDataSnafu)
+    }
+}
+
+/// Body of the bulk load actions. [BEGIN_BULK_LOAD] only needs the table name, while
+/// [COMMIT_BULK_LOAD] and [ABORT_BULK_LOAD] only need the load id.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct BulkLoadRequest {
+    #[prost(message, optional, tag = "1")]
+    pub header: Option<RequestHeader>,
+    #[prost(string, tag = "2")]
+    pub table_name: String,
+    #[prost(string, tag = "3")]
+    pub load_id: String,
+}
+
+/// Result of the bulk load actions, carried in the body of the action result.
+#[derive(Clone, PartialEq, Eq, prost::Message)]
+pub struct BulkLoadResult {
+    #[prost(string, tag = "1")]
+    pub load_id: String,
+}
+
+impl From<BulkLoadResult> for arrow_flight::Result {
+    fn from(result: BulkLoadResult) -> Self {
+        arrow_flight::Result {
+            body: result.encode_to_vec().into(),
+        }
+    }
+}
+
+impl TryFrom<arrow_flight::Result> for BulkLoadResult {
+    type Error = crate::error::Error;
+
+    fn try_from(result: arrow_flight::Result) -> Result<Self> {
+        BulkLoadResult::decode(result.body).context(DecodeFlightDataSnafu)
+    }
+}
+
 pub fn flight_messages_to_recordbatches(messages: Vec<FlightMessage>) -> Result<RecordBatches> {
     if messages.is_empty() {
         Ok(RecordBatches::empty())
@@ -278,6 +477,49 @@ mod test {
         assert_eq!(actual_batch, batch2);
     }
 
+    #[test]
+    fn test_encode_decode_metadata() {
+        let mut encoder = FlightEncoder::default();
+        let mut decoder = FlightDecoder::default();
+
+        let flight_data = encoder.encode(FlightMessage::AffectedRows(42));
+        let message = decoder.try_decode(flight_data).unwrap();
+        assert!(matches!(message, FlightMessage::AffectedRows(42)));
+
+        let stats = ScanStats {
+            files_scanned: 3,
+            files_pruned: 1,
+            rows_decoded: 100,
+            bytes_read: 4096,
+        };
+        let flight_data = encoder.encode(FlightMessage::ScanStats(stats));
+        let message = decoder.try_decode(flight_data).unwrap();
+        let FlightMessage::ScanStats(decoded) = message else { unreachable!() };
+        assert_eq!(stats, decoded);
+
+        // empty stats are still decoded as stats
+        let flight_data = encoder.encode(FlightMessage::ScanStats(ScanStats::default()));
+        let message = decoder.try_decode(flight_data).unwrap();
+        let FlightMessage::ScanStats(decoded) = message else { unreachable!() };
+        assert_eq!(ScanStats::default(), decoded);
+
+        let result = StatementResult {
+            statement_index: 2,
+            err_code: "TableNotFound".to_string(),
+            err_msg: "Table not found: demo".to_string(),
+        };
+        let flight_data = encoder.encode(FlightMessage::StatementEnd(result.clone()));
+        let message = decoder.try_decode(flight_data).unwrap();
+        let FlightMessage::StatementEnd(decoded) = message else { unreachable!() };
+        assert_eq!(result, decoded);
+
+        // the end of the first successful statement is still decoded as an end
+        let flight_data = encoder.encode(FlightMessage::StatementEnd(StatementResult::default()));
+        let message = decoder.try_decode(flight_data).unwrap();
+        let FlightMessage::StatementEnd(decoded) = message else { unreachable!() };
+        assert_eq!(StatementResult::default(), decoded);
+    }
+
     #[test]
     fn test_flight_messages_to_recordbatches() {
         let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 pub mod channel_manager;
+pub mod compression;
 pub mod error;
 pub mod flight;
 pub mod select;
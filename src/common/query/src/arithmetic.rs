@@ -0,0 +1,122 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Arithmetic of numbers aggregated by functions generated by `range_fn`.
+
+/// Addition, subtraction and multiplication that either wrap around or report overflow.
+///
+/// Floating point numbers never overflow, they saturate to infinity instead.
+pub trait OverflowingArithmetic: Sized {
+    fn wrapping_add(self, rhs: Self) -> Self;
+
+    fn wrapping_sub(self, rhs: Self) -> Self;
+
+    fn wrapping_mul(self, rhs: Self) -> Self;
+
+    /// Returns the wrapped result and whether an overflow happened.
+    fn overflowing_add(self, rhs: Self) -> (Self, bool);
+
+    /// Returns the wrapped result and whether an overflow happened.
+    fn overflowing_sub(self, rhs: Self) -> (Self, bool);
+
+    /// Returns the wrapped result and whether an overflow happened.
+    fn overflowing_mul(self, rhs: Self) -> (Self, bool);
+}
+
+macro_rules! impl_integer_arithmetic {
+    ($($t: ty),*) => {
+        $(
+            impl OverflowingArithmetic for $t {
+                fn wrapping_add(self, rhs: Self) -> Self {
+                    <$t>::wrapping_add(self, rhs)
+                }
+
+                fn wrapping_sub(self, rhs: Self) -> Self {
+                    <$t>::wrapping_sub(self, rhs)
+                }
+
+                fn wrapping_mul(self, rhs: Self) -> Self {
+                    <$t>::wrapping_mul(self, rhs)
+                }
+
+                fn overflowing_add(self, rhs: Self) -> (Self, bool) {
+                    <$t>::overflowing_add(self, rhs)
+                }
+
+                fn overflowing_sub(self, rhs: Self) -> (Self, bool) {
+                    <$t>::overflowing_sub(self, rhs)
+                }
+
+                fn overflowing_mul(self, rhs: Self) -> (Self, bool) {
+                    <$t>::overflowing_mul(self, rhs)
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_float_arithmetic {
+    ($($t: ty),*) => {
+        $(
+            impl OverflowingArithmetic for $t {
+                fn wrapping_add(self, rhs: Self) -> Self {
+                    self + rhs
+                }
+
+                fn wrapping_sub(self, rhs: Self) -> Self {
+                    self - rhs
+                }
+
+                fn wrapping_mul(self, rhs: Self) -> Self {
+                    self * rhs
+                }
+
+                fn overflowing_add(self, rhs: Self) -> (Self, bool) {
+                    (self + rhs, false)
+                }
+
+                fn overflowing_sub(self, rhs: Self) -> (Self, bool) {
+                    (self - rhs, false)
+                }
+
+                fn overflowing_mul(self, rhs: Self) -> (Self, bool) {
+                    (self * rhs, false)
+                }
+            }
+        )*
+    };
+}
+
+impl_integer_arithmetic!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+impl_float_arithmetic!(f32, f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overflowing_arithmetic() {
+        assert_eq!(i64::MIN, OverflowingArithmetic::wrapping_add(i64::MAX, 1));
+        assert_eq!(u64::MAX, OverflowingArithmetic::wrapping_sub(0u64, 1));
+        assert_eq!(
+            (i64::MIN, true),
+            OverflowingArithmetic::overflowing_add(i64::MAX, 1)
+        );
+        assert_eq!((6, false), OverflowingArithmetic::overflowing_mul(2u64, 3));
+        assert_eq!(
+            (f64::INFINITY, false),
+            OverflowingArithmetic::overflowing_mul(f64::MAX, 2.0)
+        );
+    }
+}
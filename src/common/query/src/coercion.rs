@@ -0,0 +1,122 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Coercion of inputs of functions generated by `range_fn` with `coerce = true`.
+//!
+//! Only conversions that never fail are supported, i.e. integers to floats.
+
+use datafusion_common::DataFusionError;
+use datafusion_expr::TypeSignature;
+use datatypes::arrow::array::ArrayRef;
+use datatypes::arrow::compute;
+use datatypes::arrow::datatypes::DataType;
+
+/// Types whose values can be coerced to `Float64`, besides `Float64` itself.
+const COERCIBLE_TO_FLOAT64: [DataType; 4] = [
+    DataType::Int64,
+    DataType::UInt64,
+    DataType::Int32,
+    DataType::UInt32,
+];
+
+/// Returns whether values of type `from` can be coerced to type `to`.
+pub fn can_coerce(from: &DataType, to: &DataType) -> bool {
+    match to {
+        DataType::Float64 => COERCIBLE_TO_FLOAT64.contains(from),
+        _ => false,
+    }
+}
+
+/// Casts `values` to type `to`, the caller should check the cast by [can_coerce] first.
+pub fn cast_values(values: &ArrayRef, to: &DataType) -> Result<ArrayRef, DataFusionError> {
+    compute::cast(values, to).map_err(DataFusionError::from)
+}
+
+/// Returns the signatures accepting `input_types` and all types coercible to them, value
+/// types of dictionaries are coerced as well.
+pub fn coercible_signatures(input_types: &[DataType]) -> Vec<TypeSignature> {
+    let mut signatures = vec![vec![]];
+    for input_type in input_types {
+        let candidates = coercible_from(input_type);
+        signatures = signatures
+            .into_iter()
+            .flat_map(|prefix: Vec<DataType>| {
+                candidates.iter().map(move |candidate| {
+                    let mut signature = prefix.clone();
+                    signature.push(candidate.clone());
+                    signature
+                })
+            })
+            .collect();
+    }
+    signatures.into_iter().map(TypeSignature::Exact).collect()
+}
+
+/// Returns `data_type` itself followed by the types coercible to it.
+fn coercible_from(data_type: &DataType) -> Vec<DataType> {
+    match data_type {
+        DataType::Dictionary(key_type, value_type) => coercible_from(value_type)
+            .into_iter()
+            .map(|value_type| DataType::Dictionary(key_type.clone(), Box::new(value_type)))
+            .collect(),
+        DataType::Float64 => std::iter::once(DataType::Float64)
+            .chain(COERCIBLE_TO_FLOAT64)
+            .collect(),
+        other => vec![other.clone()],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datatypes::arrow::array::{Array, Float64Array, Int64Array};
+
+    use super::*;
+
+    #[test]
+    fn test_can_coerce() {
+        assert!(can_coerce(&DataType::Int64, &DataType::Float64));
+        assert!(can_coerce(&DataType::UInt32, &DataType::Float64));
+        assert!(!can_coerce(&DataType::Utf8, &DataType::Float64));
+        assert!(!can_coerce(&DataType::Float64, &DataType::Int64));
+    }
+
+    #[test]
+    fn test_cast_values() {
+        let values: ArrayRef = Arc::new(Int64Array::from(vec![Some(1), None, Some(3)]));
+        let casted = cast_values(&values, &DataType::Float64).unwrap();
+        let casted = casted.as_any().downcast_ref::<Float64Array>().unwrap();
+        assert_eq!(
+            vec![Some(1.0), None, Some(3.0)],
+            casted.iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_coercible_signatures() {
+        let dict =
+            |value_type| DataType::Dictionary(Box::new(DataType::Int64), Box::new(value_type));
+        let signatures = coercible_signatures(&[DataType::Utf8, dict(DataType::Float64)]);
+        assert_eq!(1 + COERCIBLE_TO_FLOAT64.len(), signatures.len());
+        assert_eq!(
+            TypeSignature::Exact(vec![DataType::Utf8, dict(DataType::Float64)]),
+            signatures[0]
+        );
+        assert!(signatures.contains(&TypeSignature::Exact(vec![
+            DataType::Utf8,
+            dict(DataType::Int64)
+        ])));
+    }
+}
@@ -177,6 +177,36 @@ pub enum Error {
         err_msg: String,
         backtrace: Backtrace,
     },
+
+    #[snafu(display("Arithmetic overflow in function {}", function))]
+    ArithmeticOverflow {
+        function: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display(
+        "Range functions are registered with duplicate display names: {:?}",
+        names
+    ))]
+    DuplicateRangeFn {
+        names: Vec<String>,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display(
+        "Function {} expects argument {} of type {}, actual: {}",
+        function,
+        index,
+        expected,
+        actual
+    ))]
+    RangeFnTypeMismatch {
+        function: String,
+        index: usize,
+        expected: String,
+        actual: String,
+        backtrace: Backtrace,
+    },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -195,7 +225,12 @@ impl ErrorExt for Error {
             | Error::BadAccumulatorImpl { .. }
             | Error::ToScalarValue { .. }
             | Error::GetScalarVector { .. }
-            | Error::ArrowCompute { .. } => StatusCode::EngineExecuteQuery,
+            | Error::ArrowCompute { .. }
+            | Error::ArithmeticOverflow { .. } => StatusCode::EngineExecuteQuery,
+
+            Error::DuplicateRangeFn { .. } => StatusCode::Internal,
+
+            Error::RangeFnTypeMismatch { .. } => StatusCode::InvalidArguments,
 
             Error::InvalidInputType { source, .. }
             | Error::IntoVector { source, .. }
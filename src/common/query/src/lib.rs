@@ -16,12 +16,15 @@ use std::fmt::{Debug, Formatter};
 
 use common_recordbatch::{RecordBatches, SendableRecordBatchStream};
 
+pub mod arithmetic;
+pub mod coercion;
 pub mod columnar_value;
 pub mod error;
 mod function;
 pub mod logical_plan;
 pub mod physical_plan;
 pub mod prelude;
+pub mod range_fn_registry;
 mod signature;
 
 // sql output
@@ -13,7 +13,7 @@
 // limitations under the License.
 
 use std::any::Any;
-use std::fmt::Debug;
+use std::fmt::{self, Debug};
 use std::sync::Arc;
 
 use common_recordbatch::adapter::{DfRecordBatchStreamAdapter, RecordBatchStreamAdapter};
@@ -22,8 +22,9 @@ use datafusion::arrow::datatypes::SchemaRef as DfSchemaRef;
 use datafusion::error::Result as DfResult;
 pub use datafusion::execution::context::{SessionContext, TaskContext};
 use datafusion::physical_plan::expressions::PhysicalSortExpr;
+use datafusion::physical_plan::metrics::MetricsSet;
 pub use datafusion::physical_plan::Partitioning;
-use datafusion::physical_plan::Statistics;
+use datafusion::physical_plan::{DisplayFormatType, Statistics};
 use datatypes::schema::SchemaRef;
 use snafu::ResultExt;
 
@@ -64,6 +65,16 @@ pub trait PhysicalPlan: Debug + Send + Sync {
         partition: usize,
         context: Arc<TaskContext>,
     ) -> Result<SendableRecordBatchStream>;
+
+    /// Formats the plan in `EXPLAIN`.
+    fn fmt_as(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ExecutionPlan(PlaceHolder)")
+    }
+
+    /// Returns the metrics of this plan, `None` if the plan has no metrics.
+    fn metrics(&self) -> Option<MetricsSet> {
+        None
+    }
 }
 
 #[derive(Debug)]
@@ -130,6 +141,14 @@ impl PhysicalPlan for PhysicalPlanAdapter {
 
         Ok(Box::pin(adapter))
     }
+
+    fn fmt_as(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.df_plan.fmt_as(DisplayFormatType::Default, f)
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        self.df_plan.metrics()
+    }
 }
 
 #[derive(Debug)]
@@ -191,6 +210,14 @@ impl DfPhysicalPlan for DfPhysicalPlanAdapter {
         // TODO(LFC): impl statistics
         Statistics::default()
     }
+
+    fn fmt_as(&self, _t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt_as(f)
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        self.0.metrics()
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,127 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Registry of the functions generated by `range_fn`.
+//!
+//! Every generated function submits a [RangeFnRegistration] at compile time, so it
+//! doesn't need to be registered manually. [collect_range_fns] collects all of them.
+
+use std::collections::{BTreeSet, HashMap};
+
+use datafusion_expr::ScalarUDF;
+pub use inventory;
+use snafu::ensure;
+
+use crate::error::{DuplicateRangeFnSnafu, Result};
+
+/// Registration of a function generated by `range_fn`.
+pub struct RangeFnRegistration {
+    /// Display name of the function.
+    pub name: &'static str,
+    /// Builds the [ScalarUDF] of the function.
+    pub scalar_udf: fn() -> ScalarUDF,
+}
+
+impl RangeFnRegistration {
+    pub const fn new(name: &'static str, scalar_udf: fn() -> ScalarUDF) -> Self {
+        Self { name, scalar_udf }
+    }
+}
+
+inventory::collect!(RangeFnRegistration);
+
+/// Collects all registered functions, keyed by their display names.
+///
+/// Returns error listing all the conflicting names if more than one function is
+/// registered with the same name.
+pub fn collect_range_fns() -> Result<HashMap<&'static str, ScalarUDF>> {
+    build_registry(inventory::iter::<RangeFnRegistration>)
+}
+
+fn build_registry<'a>(
+    registrations: impl IntoIterator<Item = &'a RangeFnRegistration>,
+) -> Result<HashMap<&'static str, ScalarUDF>> {
+    let mut functions = HashMap::new();
+    let mut duplicates = BTreeSet::new();
+    for registration in registrations {
+        if functions.contains_key(registration.name) {
+            let _ = duplicates.insert(registration.name.to_string());
+        } else {
+            let _ = functions.insert(registration.name, (registration.scalar_udf)());
+        }
+    }
+    ensure!(
+        duplicates.is_empty(),
+        DuplicateRangeFnSnafu {
+            names: duplicates.into_iter().collect::<Vec<_>>(),
+        }
+    );
+    Ok(functions)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datafusion_common::Result as DfResult;
+    use datafusion_expr::{ColumnarValue, Signature, Volatility};
+    use datatypes::arrow::datatypes::DataType;
+
+    use super::*;
+
+    fn identity(args: &[ColumnarValue]) -> DfResult<ColumnarValue> {
+        Ok(args[0].clone())
+    }
+
+    fn udf(name: &str) -> ScalarUDF {
+        ScalarUDF {
+            name: name.to_string(),
+            signature: Signature::exact(vec![DataType::Float64], Volatility::Immutable),
+            return_type: Arc::new(|_| Ok(Arc::new(DataType::Float64))),
+            fun: Arc::new(identity),
+        }
+    }
+
+    fn foo() -> ScalarUDF {
+        udf("foo")
+    }
+
+    fn bar() -> ScalarUDF {
+        udf("bar")
+    }
+
+    #[test]
+    fn test_build_registry() {
+        let registrations = [
+            RangeFnRegistration::new("foo", foo),
+            RangeFnRegistration::new("bar", bar),
+        ];
+        let functions = build_registry(&registrations).unwrap();
+        assert_eq!(2, functions.len());
+        assert_eq!("foo", functions["foo"].name);
+        assert_eq!("bar", functions["bar"].name);
+
+        let registrations = [
+            RangeFnRegistration::new("foo", foo),
+            RangeFnRegistration::new("bar", bar),
+            RangeFnRegistration::new("foo", bar),
+            RangeFnRegistration::new("bar", foo),
+        ];
+        let err = build_registry(&registrations).unwrap_err();
+        let crate::error::Error::DuplicateRangeFn { names, .. } = err else {
+            panic!("unexpected error {err}");
+        };
+        assert_eq!(vec!["bar", "foo"], names);
+    }
+}
@@ -15,6 +15,7 @@
 pub mod adapter;
 pub mod error;
 mod recordbatch;
+pub mod scan_stats;
 pub mod util;
 
 use std::pin::Pin;
@@ -30,10 +31,17 @@ use error::Result;
 use futures::task::{Context, Poll};
 use futures::{Stream, TryStreamExt};
 pub use recordbatch::RecordBatch;
+use scan_stats::ScanStats;
 use snafu::{ensure, ResultExt};
 
 pub trait RecordBatchStream: Stream<Item = Result<RecordBatch>> {
     fn schema(&self) -> SchemaRef;
+
+    /// Returns the stats of the scans producing this stream, `None` if unknown. Only
+    /// complete once the stream is exhausted.
+    fn scan_stats(&self) -> Option<ScanStats> {
+        None
+    }
 }
 
 pub type SendableRecordBatchStream = Pin<Box<dyn RecordBatchStream + Send>>;
@@ -66,7 +74,7 @@ impl Stream for EmptyRecordBatchStream {
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct RecordBatches {
     schema: SchemaRef,
     batches: Vec<RecordBatch>,
@@ -181,6 +189,55 @@ impl Stream for SimpleRecordBatchStream {
     }
 }
 
+/// A stream that yields at most `limit` rows of the inner stream, then ends.
+pub struct LimitedRecordBatchStream {
+    inner: SendableRecordBatchStream,
+    remaining: usize,
+}
+
+impl LimitedRecordBatchStream {
+    pub fn new(inner: SendableRecordBatchStream, limit: usize) -> Self {
+        Self {
+            inner,
+            remaining: limit,
+        }
+    }
+}
+
+impl RecordBatchStream for LimitedRecordBatchStream {
+    fn schema(&self) -> SchemaRef {
+        self.inner.schema()
+    }
+
+    fn scan_stats(&self) -> Option<ScanStats> {
+        self.inner.scan_stats()
+    }
+}
+
+impl Stream for LimitedRecordBatchStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.remaining == 0 {
+            return Poll::Ready(None);
+        }
+        match self.inner.as_mut().poll_next(cx) {
+            Poll::Ready(Some(Ok(batch))) => {
+                let rows = batch.num_rows();
+                if rows <= self.remaining {
+                    self.remaining -= rows;
+                    return Poll::Ready(Some(Ok(batch)));
+                }
+                let limit = self.remaining;
+                self.remaining = 0;
+                let columns = batch.columns().iter().map(|c| c.slice(0, limit));
+                Poll::Ready(Some(RecordBatch::new(batch.schema.clone(), columns)))
+            }
+            other => other,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
@@ -271,4 +328,38 @@ mod tests {
         assert_eq!(collected[0], batch1);
         assert_eq!(collected[1], batch2);
     }
+
+    #[tokio::test]
+    async fn test_limited_recordbatch_stream() {
+        let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
+            "a",
+            ConcreteDataType::int32_datatype(),
+            false,
+        )]));
+        let batch1 = RecordBatch::new(
+            schema.clone(),
+            vec![Arc::new(Int32Vector::from_slice([1, 2])) as VectorRef],
+        )
+        .unwrap();
+        let batch2 = RecordBatch::new(
+            schema.clone(),
+            vec![Arc::new(Int32Vector::from_slice([3, 4, 5])) as VectorRef],
+        )
+        .unwrap();
+        let recordbatches =
+            RecordBatches::try_new(schema.clone(), vec![batch1.clone(), batch2]).unwrap();
+
+        let stream = LimitedRecordBatchStream::new(recordbatches.as_stream(), 3);
+        let collected = util::collect(Box::pin(stream)).await.unwrap();
+        let expected = RecordBatch::new(
+            schema.clone(),
+            vec![Arc::new(Int32Vector::from_slice([3])) as VectorRef],
+        )
+        .unwrap();
+        assert_eq!(vec![batch1.clone(), expected], collected);
+
+        let stream = LimitedRecordBatchStream::new(recordbatches.as_stream(), 2);
+        let collected = util::collect(Box::pin(stream)).await.unwrap();
+        assert_eq!(vec![batch1], collected);
+    }
 }
@@ -0,0 +1,238 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Statistics of the data read by table scans.
+//!
+//! Storage records the [ScanStats] of a scan in a [ScanStatsRecorder]. Scan plans expose them
+//! as DataFusion metrics labeled by the table name, so the stats of a query can be collected
+//! from its physical plan by [collect_scan_stats].
+
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use datafusion::physical_plan::metrics::{
+    ExecutionPlanMetricsSet, Label, MetricBuilder, MetricValue, MetricsSet,
+};
+use datafusion::physical_plan::ExecutionPlan;
+
+pub const FILES_SCANNED: &str = "scan_files_scanned";
+pub const FILES_PRUNED: &str = "scan_files_pruned";
+pub const ROWS_DECODED: &str = "scan_rows_decoded";
+pub const BYTES_READ: &str = "scan_bytes_read";
+/// Label of the scan metrics, the full name of the table scanned.
+pub const TABLE_LABEL: &str = "table";
+
+/// Counters of the data read by scans.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ScanStats {
+    /// SST files read.
+    pub files_scanned: u64,
+    /// SST files skipped as their time ranges don't match the query.
+    pub files_pruned: u64,
+    /// Rows decoded from SST files, before deduplication and filtering.
+    pub rows_decoded: u64,
+    /// Bytes read from the object store.
+    pub bytes_read: u64,
+}
+
+impl ScanStats {
+    pub fn merge(&mut self, other: &ScanStats) {
+        self.files_scanned += other.files_scanned;
+        self.files_pruned += other.files_pruned;
+        self.rows_decoded += other.rows_decoded;
+        self.bytes_read += other.bytes_read;
+    }
+
+    fn fields(&self) -> [(&'static str, u64); 4] {
+        [
+            (FILES_SCANNED, self.files_scanned),
+            (FILES_PRUNED, self.files_pruned),
+            (ROWS_DECODED, self.rows_decoded),
+            (BYTES_READ, self.bytes_read),
+        ]
+    }
+
+    fn field_mut(&mut self, name: &str) -> Option<&mut u64> {
+        match name {
+            FILES_SCANNED => Some(&mut self.files_scanned),
+            FILES_PRUNED => Some(&mut self.files_pruned),
+            ROWS_DECODED => Some(&mut self.rows_decoded),
+            BYTES_READ => Some(&mut self.bytes_read),
+            _ => None,
+        }
+    }
+
+    /// Converts the stats of `table` to DataFusion metrics.
+    pub fn to_metrics(&self, table: &str) -> MetricsSet {
+        let metrics = ExecutionPlanMetricsSet::new();
+        for (name, value) in self.fields() {
+            MetricBuilder::new(&metrics)
+                .with_label(Label::new(TABLE_LABEL, table.to_string()))
+                .counter(name, 0)
+                .add(value as usize);
+        }
+        metrics.clone_inner()
+    }
+}
+
+impl Display for ScanStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "files_scanned: {}, files_pruned: {}, rows_decoded: {}, bytes_read: {}",
+            self.files_scanned, self.files_pruned, self.rows_decoded, self.bytes_read
+        )
+    }
+}
+
+/// Records [ScanStats] updated concurrently by the readers of a scan.
+#[derive(Debug, Default)]
+pub struct ScanStatsRecorder {
+    files_scanned: AtomicU64,
+    files_pruned: AtomicU64,
+    rows_decoded: AtomicU64,
+    bytes_read: AtomicU64,
+}
+
+pub type ScanStatsRecorderRef = Arc<ScanStatsRecorder>;
+
+impl ScanStatsRecorder {
+    pub fn add_files_scanned(&self, n: u64) {
+        self.files_scanned.fetch_add(n, Ordering::Relaxed);
+    }
+
+    pub fn add_files_pruned(&self, n: u64) {
+        self.files_pruned.fetch_add(n, Ordering::Relaxed);
+    }
+
+    pub fn add_rows_decoded(&self, n: u64) {
+        self.rows_decoded.fetch_add(n, Ordering::Relaxed);
+    }
+
+    pub fn add_bytes_read(&self, n: u64) {
+        self.bytes_read.fetch_add(n, Ordering::Relaxed);
+    }
+
+    pub fn add(&self, stats: &ScanStats) {
+        self.add_files_scanned(stats.files_scanned);
+        self.add_files_pruned(stats.files_pruned);
+        self.add_rows_decoded(stats.rows_decoded);
+        self.add_bytes_read(stats.bytes_read);
+    }
+
+    pub fn stats(&self) -> ScanStats {
+        ScanStats {
+            files_scanned: self.files_scanned.load(Ordering::Relaxed),
+            files_pruned: self.files_pruned.load(Ordering::Relaxed),
+            rows_decoded: self.rows_decoded.load(Ordering::Relaxed),
+            bytes_read: self.bytes_read.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Collects the [ScanStats] of all scans in the plan, grouped by table.
+pub fn collect_scan_stats(plan: &dyn ExecutionPlan) -> HashMap<String, ScanStats> {
+    let mut stats = HashMap::new();
+    collect_plan_scan_stats(plan, &mut stats);
+    stats
+}
+
+fn collect_plan_scan_stats(plan: &dyn ExecutionPlan, stats: &mut HashMap<String, ScanStats>) {
+    if let Some(metrics) = plan.metrics() {
+        add_metrics(&metrics, stats);
+    }
+    for child in plan.children() {
+        collect_plan_scan_stats(child.as_ref(), stats);
+    }
+}
+
+/// Adds scan metrics in `metrics` to the stats of their tables, other metrics are ignored.
+fn add_metrics(metrics: &MetricsSet, stats: &mut HashMap<String, ScanStats>) {
+    for metric in metrics.iter() {
+        let MetricValue::Count { name, count } = metric.value() else {
+            continue;
+        };
+        let Some(table) = metric
+            .labels()
+            .iter()
+            .find(|label| label.name() == TABLE_LABEL)
+        else {
+            continue;
+        };
+        let table_stats = stats.entry(table.value().to_string()).or_default();
+        if let Some(field) = table_stats.field_mut(name) {
+            *field += count.value() as u64;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_stats_recorder() {
+        let recorder = ScanStatsRecorder::default();
+        recorder.add_files_scanned(2);
+        recorder.add_files_pruned(1);
+        recorder.add_rows_decoded(100);
+        recorder.add_bytes_read(4096);
+        recorder.add(&ScanStats {
+            files_scanned: 1,
+            ..Default::default()
+        });
+        let stats = recorder.stats();
+        assert_eq!(
+            ScanStats {
+                files_scanned: 3,
+                files_pruned: 1,
+                rows_decoded: 100,
+                bytes_read: 4096,
+            },
+            stats
+        );
+        assert_eq!(
+            "files_scanned: 3, files_pruned: 1, rows_decoded: 100, bytes_read: 4096",
+            stats.to_string()
+        );
+    }
+
+    #[test]
+    fn test_scan_stats_metrics() {
+        let stats = ScanStats {
+            files_scanned: 3,
+            files_pruned: 1,
+            rows_decoded: 100,
+            bytes_read: 4096,
+        };
+
+        let mut collected = HashMap::new();
+        add_metrics(&stats.to_metrics("greptime.public.foo"), &mut collected);
+        add_metrics(&stats.to_metrics("greptime.public.foo"), &mut collected);
+        add_metrics(&stats.to_metrics("greptime.public.bar"), &mut collected);
+        // metrics without the table label are not scan metrics
+        let others = ExecutionPlanMetricsSet::new();
+        MetricBuilder::new(&others).counter(FILES_SCANNED, 0).add(1);
+        MetricBuilder::new(&others).output_rows(0).add(10);
+        add_metrics(&others.clone_inner(), &mut collected);
+
+        let mut expected = stats;
+        expected.merge(&stats);
+        assert_eq!(2, collected.len());
+        assert_eq!(expected, collected["greptime.public.foo"]);
+        assert_eq!(stats, collected["greptime.public.bar"]);
+    }
+}
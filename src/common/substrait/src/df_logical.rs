@@ -25,17 +25,28 @@ use datafusion::arrow::datatypes::SchemaRef as ArrowSchemaRef;
 use datafusion::common::{DFField, DFSchema, OwnedTableReference};
 use datafusion::datasource::DefaultTableSource;
 use datafusion::physical_plan::project_schema;
-use datafusion_expr::{Filter, LogicalPlan, TableScan};
+use datafusion_expr::expr::{AggregateFunction, Sort};
+use datafusion_expr::{
+    AggregateFunction as AggregateFunctionEnum, Expr, Filter, LogicalPlan, LogicalPlanBuilder,
+    TableScan,
+};
+use datatypes::schema::Schema;
 use prost::Message;
 use session::context::QueryContext;
 use snafu::{ensure, OptionExt, ResultExt};
+use substrait_proto::proto::aggregate_rel::Measure;
 use substrait_proto::proto::expression::mask_expression::{StructItem, StructSelect};
 use substrait_proto::proto::expression::MaskExpression;
 use substrait_proto::proto::extensions::simple_extension_declaration::MappingType;
+use substrait_proto::proto::function_argument::ArgType;
 use substrait_proto::proto::plan_rel::RelType as PlanRelType;
 use substrait_proto::proto::read_rel::{NamedTable, ReadType};
 use substrait_proto::proto::rel::RelType;
-use substrait_proto::proto::{FilterRel, Plan, PlanRel, ReadRel, Rel};
+use substrait_proto::proto::sort_field::{SortDirection, SortKind};
+use substrait_proto::proto::{
+    AggregateFunction as SubstraitAggregateFunction, AggregateRel, FetchRel, FilterRel,
+    FunctionArgument, Plan, PlanRel, ReadRel, Rel, SortField, SortRel,
+};
 use table::table::adapter::DfTableProviderAdapter;
 
 use crate::context::ConvertorContext;
@@ -156,18 +167,78 @@ impl DFLogicalSubstraitConvertor {
 
                 LogicalPlan::Filter(Filter::try_new(predicate, input).context(DFInternalSnafu)?)
             }
-            RelType::Fetch(_fetch_rel) => UnsupportedPlanSnafu {
-                name: "Fetch Relation",
+            RelType::Fetch(fetch_rel) => {
+                let FetchRel {
+                    input,
+                    offset,
+                    count,
+                    ..
+                } = *fetch_rel;
+
+                let input = input.context(MissingFieldSnafu {
+                    field: "input",
+                    plan: "Fetch",
+                })?;
+                let input = self.rel_to_logical_plan(ctx, input, table_provider).await?;
+
+                // A negative count means no limit.
+                let fetch = (count >= 0).then_some(count as usize);
+                LogicalPlanBuilder::from(input)
+                    .limit(offset as usize, fetch)
+                    .and_then(|builder| builder.build())
+                    .context(DFInternalSnafu)?
             }
-            .fail()?,
-            RelType::Aggregate(_aggr_rel) => UnsupportedPlanSnafu {
-                name: "Fetch Relation",
+            RelType::Aggregate(aggr_rel) => {
+                let AggregateRel {
+                    input,
+                    groupings,
+                    measures,
+                    ..
+                } = *aggr_rel;
+                ensure!(
+                    groupings
+                        .iter()
+                        .all(|grouping| grouping.grouping_expressions.is_empty()),
+                    UnsupportedPlanSnafu {
+                        name: "Aggregate Relation with groupings",
+                    }
+                );
+
+                let input = input.context(MissingFieldSnafu {
+                    field: "input",
+                    plan: "Aggregate",
+                })?;
+                let input = self.rel_to_logical_plan(ctx, input, table_provider).await?;
+
+                let schema = input_schema(ctx)?;
+                let aggr_exprs = measures
+                    .into_iter()
+                    .map(|measure| to_df_aggregate_expr(ctx, measure, &schema))
+                    .collect::<Result<Vec<_>, Error>>()?;
+                LogicalPlanBuilder::from(input)
+                    .aggregate(Vec::<Expr>::new(), aggr_exprs)
+                    .and_then(|builder| builder.build())
+                    .context(DFInternalSnafu)?
             }
-            .fail()?,
-            RelType::Sort(_sort_rel) => UnsupportedPlanSnafu {
-                name: "Sort Relation",
+            RelType::Sort(sort_rel) => {
+                let SortRel { input, sorts, .. } = *sort_rel;
+
+                let input = input.context(MissingFieldSnafu {
+                    field: "input",
+                    plan: "Sort",
+                })?;
+                let input = self.rel_to_logical_plan(ctx, input, table_provider).await?;
+
+                let schema = input_schema(ctx)?;
+                let sort_exprs = sorts
+                    .into_iter()
+                    .map(|sort| to_df_sort_expr(ctx, sort, &schema))
+                    .collect::<Result<Vec<_>, Error>>()?;
+                LogicalPlanBuilder::from(input)
+                    .sort(sort_exprs)
+                    .and_then(|builder| builder.build())
+                    .context(DFInternalSnafu)?
             }
-            .fail()?,
             RelType::Join(_join_rel) => UnsupportedPlanSnafu {
                 name: "Join Relation",
             }
@@ -352,14 +423,62 @@ impl DFLogicalSubstraitConvertor {
                 name: "DataFusion Logical Window",
             }
             .fail()?,
-            LogicalPlan::Aggregate(_) => UnsupportedPlanSnafu {
-                name: "DataFusion Logical Aggregate",
+            LogicalPlan::Aggregate(aggregate) => {
+                ensure!(
+                    aggregate.group_expr.is_empty(),
+                    UnsupportedPlanSnafu {
+                        name: "DataFusion Logical Aggregate with GROUP BY",
+                    }
+                );
+                let input = Some(Box::new(
+                    self.logical_plan_to_rel(ctx, aggregate.input.clone())?,
+                ));
+
+                let schema = aggregate
+                    .input
+                    .schema()
+                    .clone()
+                    .try_into()
+                    .context(error::ConvertDfSchemaSnafu)?;
+                let measures = aggregate
+                    .aggr_expr
+                    .iter()
+                    .map(|expr| from_df_aggregate_expr(ctx, expr, &schema))
+                    .collect::<Result<Vec<_>, Error>>()?;
+
+                let rel = AggregateRel {
+                    input,
+                    measures,
+                    ..Default::default()
+                };
+                Rel {
+                    rel_type: Some(RelType::Aggregate(Box::new(rel))),
+                }
             }
-            .fail()?,
-            LogicalPlan::Sort(_) => UnsupportedPlanSnafu {
-                name: "DataFusion Logical Sort",
+            LogicalPlan::Sort(sort) => {
+                let input = Some(Box::new(self.logical_plan_to_rel(ctx, sort.input.clone())?));
+
+                let schema = sort
+                    .input
+                    .schema()
+                    .clone()
+                    .try_into()
+                    .context(error::ConvertDfSchemaSnafu)?;
+                let sorts = sort
+                    .expr
+                    .iter()
+                    .map(|expr| from_df_sort_expr(ctx, expr, &schema))
+                    .collect::<Result<Vec<_>, Error>>()?;
+
+                let rel = SortRel {
+                    input,
+                    sorts,
+                    ..Default::default()
+                };
+                Rel {
+                    rel_type: Some(RelType::Sort(Box::new(rel))),
+                }
             }
-            .fail()?,
             LogicalPlan::Join(_) => UnsupportedPlanSnafu {
                 name: "DataFusion Logical Join",
             }
@@ -386,10 +505,21 @@ impl DFLogicalSubstraitConvertor {
                 name: "DataFusion Logical EmptyRelation",
             }
             .fail()?,
-            LogicalPlan::Limit(_) => UnsupportedPlanSnafu {
-                name: "DataFusion Logical Limit",
+            LogicalPlan::Limit(limit) => {
+                let input = Some(Box::new(
+                    self.logical_plan_to_rel(ctx, limit.input.clone())?,
+                ));
+
+                let rel = FetchRel {
+                    input,
+                    offset: limit.skip as i64,
+                    count: limit.fetch.map(|fetch| fetch as i64).unwrap_or(-1),
+                    ..Default::default()
+                };
+                Rel {
+                    rel_type: Some(RelType::Fetch(Box::new(rel))),
+                }
             }
-            .fail()?,
 
             LogicalPlan::Subquery(_)
             | LogicalPlan::SubqueryAlias(_)
@@ -522,6 +652,166 @@ impl DFLogicalSubstraitConvertor {
     }
 }
 
+/// Returns the schema of the underlying table scan, which the expressions of the plans
+/// above it refer to.
+fn input_schema(ctx: &ConvertorContext) -> Result<Schema, Error> {
+    let schema = ctx.df_schema().context(InvalidParametersSnafu {
+        reason: "the underlying TableScan plan should have included a table schema",
+    })?;
+    schema
+        .clone()
+        .try_into()
+        .context(error::ConvertDfSchemaSnafu)
+}
+
+fn from_df_sort_expr(
+    ctx: &mut ConvertorContext,
+    expr: &Expr,
+    schema: &Schema,
+) -> Result<SortField, Error> {
+    let Expr::Sort(Sort { expr, asc, nulls_first }) = expr else {
+        return UnsupportedExprSnafu {
+            name: expr.to_string(),
+        }
+        .fail();
+    };
+    let direction = match (*asc, *nulls_first) {
+        (true, true) => SortDirection::AscNullsFirst,
+        (true, false) => SortDirection::AscNullsLast,
+        (false, true) => SortDirection::DescNullsFirst,
+        (false, false) => SortDirection::DescNullsLast,
+    };
+    Ok(SortField {
+        expr: Some(expression_from_df_expr(ctx, expr, schema)?),
+        sort_kind: Some(SortKind::Direction(direction as i32)),
+    })
+}
+
+fn to_df_sort_expr(
+    ctx: &ConvertorContext,
+    sort: SortField,
+    schema: &Schema,
+) -> Result<Expr, Error> {
+    let expr = sort.expr.context(MissingFieldSnafu {
+        field: "expr",
+        plan: "Sort",
+    })?;
+    let (asc, nulls_first) = match sort.sort_kind {
+        Some(SortKind::Direction(direction))
+            if direction == SortDirection::AscNullsFirst as i32 =>
+        {
+            (true, true)
+        }
+        Some(SortKind::Direction(direction)) if direction == SortDirection::AscNullsLast as i32 => {
+            (true, false)
+        }
+        Some(SortKind::Direction(direction))
+            if direction == SortDirection::DescNullsFirst as i32 =>
+        {
+            (false, true)
+        }
+        Some(SortKind::Direction(direction))
+            if direction == SortDirection::DescNullsLast as i32 =>
+        {
+            (false, false)
+        }
+        sort_kind => {
+            return UnsupportedExprSnafu {
+                name: format!("sort kind {sort_kind:?}"),
+            }
+            .fail()
+        }
+    };
+    Ok(to_df_expr(ctx, expr, schema)?.sort(asc, nulls_first))
+}
+
+/// Only `min`, `max` and `count` without `DISTINCT` or `FILTER` are supported.
+fn from_df_aggregate_expr(
+    ctx: &mut ConvertorContext,
+    expr: &Expr,
+    schema: &Schema,
+) -> Result<Measure, Error> {
+    let unsupported = || {
+        UnsupportedExprSnafu {
+            name: expr.to_string(),
+        }
+        .fail()
+    };
+    let Expr::AggregateFunction(AggregateFunction { fun, args, distinct: false, filter: None }) = expr else {
+        return unsupported();
+    };
+    let name = match fun {
+        AggregateFunctionEnum::Min => "min",
+        AggregateFunctionEnum::Max => "max",
+        AggregateFunctionEnum::Count => "count",
+        _ => return unsupported(),
+    };
+
+    let arguments = args
+        .iter()
+        .map(|arg| {
+            Ok(FunctionArgument {
+                arg_type: Some(ArgType::Value(expression_from_df_expr(ctx, arg, schema)?)),
+            })
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    let function_reference = ctx.register_scalar_fn(name);
+    Ok(Measure {
+        measure: Some(SubstraitAggregateFunction {
+            function_reference,
+            arguments,
+            ..Default::default()
+        }),
+        filter: None,
+    })
+}
+
+fn to_df_aggregate_expr(
+    ctx: &ConvertorContext,
+    measure: Measure,
+    schema: &Schema,
+) -> Result<Expr, Error> {
+    let function = measure.measure.context(MissingFieldSnafu {
+        field: "measure",
+        plan: "Aggregate",
+    })?;
+    let anchor = function.function_reference;
+    let name = ctx
+        .find_scalar_fn(anchor)
+        .with_context(|| InvalidParametersSnafu {
+            reason: format!("Unregistered aggregate function reference: {anchor}"),
+        })?;
+    let fun = match name {
+        "min" => AggregateFunctionEnum::Min,
+        "max" => AggregateFunctionEnum::Max,
+        "count" => AggregateFunctionEnum::Count,
+        _ => {
+            return UnsupportedExprSnafu {
+                name: name.to_string(),
+            }
+            .fail()
+        }
+    };
+
+    let args = function
+        .arguments
+        .into_iter()
+        .map(|arg| match arg.arg_type {
+            Some(ArgType::Value(expr)) => to_df_expr(ctx, expr, schema),
+            _ => InvalidParametersSnafu {
+                reason: "Only value expression arg is supported to be function argument",
+            }
+            .fail(),
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    Ok(Expr::AggregateFunction(AggregateFunction {
+        fun,
+        args,
+        distinct: false,
+        filter: None,
+    }))
+}
+
 fn same_schema_without_metadata(lhs: &ArrowSchemaRef, rhs: &ArrowSchemaRef) -> bool {
     lhs.fields.len() == rhs.fields.len()
         && lhs.fields.iter().zip(rhs.fields.iter()).all(|(x, y)| {
@@ -537,7 +827,7 @@ mod test {
     use catalog::{CatalogList, CatalogProvider, RegisterTableRequest};
     use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
     use datafusion::common::{DFSchema, ToDFSchema};
-    use datafusion_expr::TableSource;
+    use datafusion_expr::{col, count, max, min, TableSource};
     use datatypes::schema::RawSchema;
     use table::requests::CreateTableRequest;
     use table::test_util::{EmptyTable, MockTableEngine};
@@ -633,4 +923,70 @@ mod test {
 
         logical_plan_round_trip(table_scan_plan, catalog_manager).await;
     }
+
+    async fn build_table_scan(catalog_manager: &CatalogManagerRef) -> LogicalPlanBuilder {
+        let table_ref = Arc::new(EmptyTable::new(build_create_table_request(
+            DEFAULT_TABLE_NAME,
+        )));
+        catalog_manager
+            .register_table(RegisterTableRequest {
+                catalog: DEFAULT_CATALOG_NAME.to_string(),
+                schema: DEFAULT_SCHEMA_NAME.to_string(),
+                table_name: DEFAULT_TABLE_NAME.to_string(),
+                table_id: 1,
+                table: table_ref.clone(),
+            })
+            .await
+            .unwrap();
+        let adapter = Arc::new(DefaultTableSource::new(Arc::new(
+            DfTableProviderAdapter::new(table_ref),
+        )));
+
+        LogicalPlanBuilder::scan(
+            format!("{DEFAULT_CATALOG_NAME}.{DEFAULT_SCHEMA_NAME}.{DEFAULT_TABLE_NAME}"),
+            adapter,
+            None,
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_sort_and_limit() {
+        let catalog_manager = build_mock_catalog_manager().await;
+        let plan = build_table_scan(&catalog_manager)
+            .await
+            .sort(vec![col("Int64").sort(false, true)])
+            .unwrap()
+            .limit(1, Some(10))
+            .unwrap()
+            .build()
+            .unwrap();
+
+        logical_plan_round_trip(plan, catalog_manager).await;
+    }
+
+    #[tokio::test]
+    async fn test_aggregate() {
+        let catalog_manager = build_mock_catalog_manager().await;
+        let scan = build_table_scan(&catalog_manager).await;
+        let plan = scan
+            .clone()
+            .aggregate(
+                Vec::<Expr>::new(),
+                vec![min(col("Int64")), max(col("Int64")), count(col("Int64"))],
+            )
+            .unwrap()
+            .build()
+            .unwrap();
+
+        logical_plan_round_trip(plan, catalog_manager).await;
+
+        // Aggregates with GROUP BY are not supported.
+        let plan = scan
+            .aggregate(vec![col("String")], vec![min(col("Int64"))])
+            .unwrap()
+            .build()
+            .unwrap();
+        assert!(DFLogicalSubstraitConvertor.encode(plan).is_err());
+    }
 }
@@ -29,6 +29,9 @@ pub enum Error {
     #[snafu(display("Failed to parse a string into Timestamp, raw string: {}", raw))]
     ParseTimestamp { raw: String, backtrace: Backtrace },
 
+    #[snafu(display("Invalid time zone: {}", raw))]
+    ParseTimeZone { raw: String, backtrace: Backtrace },
+
     #[snafu(display("Current timestamp overflow, source: {}", source))]
     TimestampOverflow {
         source: TryFromIntError,
@@ -42,9 +45,9 @@ pub enum Error {
 impl ErrorExt for Error {
     fn status_code(&self) -> StatusCode {
         match self {
-            Error::ParseDateStr { .. } | Error::ParseTimestamp { .. } => {
-                StatusCode::InvalidArguments
-            }
+            Error::ParseDateStr { .. }
+            | Error::ParseTimestamp { .. }
+            | Error::ParseTimeZone { .. } => StatusCode::InvalidArguments,
             Error::TimestampOverflow { .. } => StatusCode::Internal,
             Error::ArithmeticOverflow { .. } => StatusCode::InvalidArguments,
         }
@@ -18,6 +18,7 @@ pub mod error;
 pub mod range;
 pub mod timestamp;
 pub mod timestamp_millis;
+pub mod timezone;
 pub mod util;
 
 pub use date::Date;
@@ -25,3 +26,4 @@ pub use datetime::DateTime;
 pub use range::RangeMillis;
 pub use timestamp::Timestamp;
 pub use timestamp_millis::TimestampMillis;
+pub use timezone::TimeZone;
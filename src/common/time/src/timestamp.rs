@@ -26,6 +26,7 @@ use snafu::{OptionExt, ResultExt};
 
 use crate::error;
 use crate::error::{ArithmeticOverflowSnafu, Error, ParseTimestampSnafu, TimestampOverflowSnafu};
+use crate::timezone::TimeZone as SessionTimeZone;
 use crate::util::div_ceil;
 
 #[derive(Debug, Clone, Default, Copy, Serialize, Deserialize)]
@@ -171,6 +172,52 @@ impl Timestamp {
         }
     }
 
+    /// Format timestamp to a string in the given time zone, without the offset suffix.
+    /// Falls back to [Timestamp::to_iso8601_string] if the timestamp is out of range.
+    pub fn to_timezone_aware_string(&self, tz: &SessionTimeZone) -> String {
+        if let LocalResult::Single(datetime) = self.to_chrono_datetime() {
+            let utc = datetime.naive_utc();
+            let local = utc + tz.offset_from_utc(&utc);
+            format!("{}", local.format("%Y-%m-%d %H:%M:%S%.f"))
+        } else {
+            self.to_iso8601_string()
+        }
+    }
+
+    /// Parses a timestamp string like [FromStr] does, but strings without an explicit offset
+    /// are interpreted in the time zone `tz` instead of the local time zone of the server.
+    pub fn from_str_with_timezone(s: &str, tz: Option<&SessionTimeZone>) -> error::Result<Self> {
+        // RFC3339 timestamp (with a T)
+        if let Ok(ts) = DateTime::parse_from_rfc3339(s) {
+            return Ok(Timestamp::new(ts.timestamp_nanos(), TimeUnit::Nanosecond));
+        }
+        if let Ok(ts) = DateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f%:z") {
+            return Ok(Timestamp::new(ts.timestamp_nanos(), TimeUnit::Nanosecond));
+        }
+        if let Ok(ts) = Utc.datetime_from_str(s, "%Y-%m-%d %H:%M:%S%.fZ") {
+            return Ok(Timestamp::new(ts.timestamp_nanos(), TimeUnit::Nanosecond));
+        }
+
+        for format in [
+            "%Y-%m-%dT%H:%M:%S",
+            "%Y-%m-%dT%H:%M:%S%.f",
+            "%Y-%m-%d %H:%M:%S",
+            "%Y-%m-%d %H:%M:%S%.f",
+        ] {
+            if let Ok(ts) = NaiveDateTime::parse_from_str(s, format) {
+                return match tz {
+                    Some(tz) => tz
+                        .to_utc(&ts)
+                        .map(|utc| Timestamp::new(utc.timestamp_nanos(), TimeUnit::Nanosecond))
+                        .context(ParseTimestampSnafu { raw: s }),
+                    None => naive_datetime_to_timestamp(s, ts),
+                };
+            }
+        }
+
+        ParseTimestampSnafu { raw: s }.fail()
+    }
+
     pub fn to_chrono_datetime(&self) -> LocalResult<DateTime<Utc>> {
         let (sec, nsec) = self.split();
         Utc.timestamp_opt(sec, nsec)
@@ -192,34 +239,7 @@ impl FromStr for Timestamp {
     /// - `2022-09-20 14:16:43` (local timezone, without T)
     /// - `2022-09-20 14:16:43.012345` (local timezone, without T)
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        // RFC3339 timestamp (with a T)
-        if let Ok(ts) = DateTime::parse_from_rfc3339(s) {
-            return Ok(Timestamp::new(ts.timestamp_nanos(), TimeUnit::Nanosecond));
-        }
-        if let Ok(ts) = DateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f%:z") {
-            return Ok(Timestamp::new(ts.timestamp_nanos(), TimeUnit::Nanosecond));
-        }
-        if let Ok(ts) = Utc.datetime_from_str(s, "%Y-%m-%d %H:%M:%S%.fZ") {
-            return Ok(Timestamp::new(ts.timestamp_nanos(), TimeUnit::Nanosecond));
-        }
-
-        if let Ok(ts) = NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S") {
-            return naive_datetime_to_timestamp(s, ts);
-        }
-
-        if let Ok(ts) = NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f") {
-            return naive_datetime_to_timestamp(s, ts);
-        }
-
-        if let Ok(ts) = NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S") {
-            return naive_datetime_to_timestamp(s, ts);
-        }
-
-        if let Ok(ts) = NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f") {
-            return naive_datetime_to_timestamp(s, ts);
-        }
-
-        ParseTimestampSnafu { raw: s }.fail()
+        Self::from_str_with_timezone(s, None)
     }
 }
 
@@ -657,6 +677,32 @@ mod tests {
         assert_eq!("1969-12-31 23:59:58.999+0000", ts.to_iso8601_string());
     }
 
+    #[test]
+    fn test_timezone_aware_round_trip() {
+        let tz = SessionTimeZone::from_str("Asia/Shanghai").unwrap();
+        let ts = Timestamp::from_str_with_timezone("2023-03-01 08:00:00", Some(&tz)).unwrap();
+        assert_eq!(
+            Timestamp::from_str("2023-03-01 00:00:00Z").unwrap(),
+            ts.convert_to(TimeUnit::Nanosecond).unwrap()
+        );
+        assert_eq!("2023-03-01 08:00:00", ts.to_timezone_aware_string(&tz));
+
+        // Explicit offsets take precedence over the session time zone.
+        let ts = Timestamp::from_str_with_timezone("2023-03-01 08:00:00+00:00", Some(&tz)).unwrap();
+        assert_eq!("2023-03-01 16:00:00", ts.to_timezone_aware_string(&tz));
+
+        let tz = SessionTimeZone::from_str("-05:00").unwrap();
+        let ts = Timestamp::new_millisecond(1668070237001);
+        assert_eq!("2022-11-10 03:50:37.001", ts.to_timezone_aware_string(&tz));
+        let parsed =
+            Timestamp::from_str_with_timezone("2022-11-10 03:50:37.001", Some(&tz)).unwrap();
+        assert_eq!(ts, parsed);
+
+        // Nonexistent local time on the day daylight saving time starts.
+        let tz = SessionTimeZone::from_str("America/New_York").unwrap();
+        assert!(Timestamp::from_str_with_timezone("2023-03-12 02:30:00", Some(&tz)).is_err());
+    }
+
     #[test]
     fn test_serialize_to_json_value() {
         assert_eq!(
@@ -0,0 +1,162 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use chrono::{FixedOffset, LocalResult, NaiveDateTime, Offset, TimeZone as _};
+use chrono_tz::Tz;
+use snafu::OptionExt;
+
+use crate::error::{Error, ParseTimeZoneSnafu, Result};
+
+/// A time zone to render and parse timestamps, either a fixed offset from UTC such as
+/// `+08:00`, or a named zone in the IANA database such as `Asia/Shanghai`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeZone {
+    Offset(FixedOffset),
+    Named(Tz),
+}
+
+impl TimeZone {
+    pub fn utc() -> Self {
+        TimeZone::Offset(FixedOffset::east_opt(0).unwrap())
+    }
+
+    /// Returns the offset from UTC at the UTC datetime `utc`.
+    pub fn offset_from_utc(&self, utc: &NaiveDateTime) -> FixedOffset {
+        match self {
+            TimeZone::Offset(offset) => *offset,
+            TimeZone::Named(tz) => tz.offset_from_utc_datetime(utc).fix(),
+        }
+    }
+
+    /// Converts the `local` datetime in this time zone to UTC, returns None if the
+    /// datetime doesn't exist in this time zone. The earlier one is chosen if the
+    /// datetime is ambiguous.
+    pub fn to_utc(&self, local: &NaiveDateTime) -> Option<NaiveDateTime> {
+        let offset = match self {
+            TimeZone::Offset(offset) => *offset,
+            TimeZone::Named(tz) => match tz.offset_from_local_datetime(local) {
+                LocalResult::None => return None,
+                LocalResult::Single(offset) | LocalResult::Ambiguous(offset, _) => offset.fix(),
+            },
+        };
+        Some(*local - offset)
+    }
+}
+
+impl FromStr for TimeZone {
+    type Err = Error;
+
+    /// Accepts `UTC`, an offset like `+08:00`, or a named zone like `Asia/Shanghai`.
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("UTC") || s == "Z" {
+            return Ok(TimeZone::utc());
+        }
+        if s.starts_with('+') || s.starts_with('-') {
+            return parse_offset(s).map(TimeZone::Offset);
+        }
+        Tz::from_str(s)
+            .ok()
+            .map(TimeZone::Named)
+            .context(ParseTimeZoneSnafu { raw: s })
+    }
+}
+
+/// Parses an offset like `+08:00` or `-0530`.
+fn parse_offset(s: &str) -> Result<FixedOffset> {
+    let (sign, rest) = s.split_at(1);
+    let sign = if sign == "-" { -1 } else { 1 };
+    let rest = rest.replace(':', "");
+    let (hours, minutes) = match rest.len() {
+        1 | 2 => (rest.as_str(), "0"),
+        4 => rest.split_at(2),
+        _ => return ParseTimeZoneSnafu { raw: s }.fail(),
+    };
+    let hours = hours
+        .parse::<i32>()
+        .ok()
+        .context(ParseTimeZoneSnafu { raw: s })?;
+    let minutes = minutes
+        .parse::<i32>()
+        .ok()
+        .context(ParseTimeZoneSnafu { raw: s })?;
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+        .filter(|_| minutes < 60)
+        .context(ParseTimeZoneSnafu { raw: s })
+}
+
+impl Display for TimeZone {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimeZone::Offset(offset) => write!(f, "{offset}"),
+            TimeZone::Named(tz) => write!(f, "{}", tz.name()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_time_zone() {
+        assert_eq!(TimeZone::utc(), TimeZone::from_str("UTC").unwrap());
+        assert_eq!(TimeZone::utc(), TimeZone::from_str("utc").unwrap());
+        assert_eq!(
+            TimeZone::Offset(FixedOffset::east_opt(8 * 3600).unwrap()),
+            TimeZone::from_str("+08:00").unwrap()
+        );
+        assert_eq!(
+            TimeZone::Offset(FixedOffset::west_opt(5 * 3600 + 30 * 60).unwrap()),
+            TimeZone::from_str("-0530").unwrap()
+        );
+        assert_eq!(
+            TimeZone::Named(Tz::Asia__Shanghai),
+            TimeZone::from_str("Asia/Shanghai").unwrap()
+        );
+
+        assert!(TimeZone::from_str("Mars/Olympus").is_err());
+        assert!(TimeZone::from_str("+08:61").is_err());
+        assert!(TimeZone::from_str("+123").is_err());
+    }
+
+    #[test]
+    fn test_display_time_zone() {
+        assert_eq!("+08:00", TimeZone::from_str("+08:00").unwrap().to_string());
+        assert_eq!(
+            "Asia/Shanghai",
+            TimeZone::from_str("Asia/Shanghai").unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn test_to_utc() {
+        let local =
+            NaiveDateTime::parse_from_str("2023-03-01 08:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let utc =
+            NaiveDateTime::parse_from_str("2023-03-01 00:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let tz = TimeZone::from_str("Asia/Shanghai").unwrap();
+        assert_eq!(Some(utc), tz.to_utc(&local));
+        assert_eq!(8 * 3600, tz.offset_from_utc(&utc).local_minus_utc());
+
+        // 02:30 doesn't exist in New York on the day daylight saving time starts.
+        let tz = TimeZone::from_str("America/New_York").unwrap();
+        let local =
+            NaiveDateTime::parse_from_str("2023-03-12 02:30:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        assert_eq!(None, tz.to_utc(&local));
+    }
+}
@@ -16,18 +16,33 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use common_base::readable_size::ReadableSize;
+use common_base::Plugins;
+use common_grpc::compression::GrpcCompression;
 use common_telemetry::info;
+use log_store::RecoveryMode;
 use meta_client::MetaClientOptions;
+use mito::config::default_region_open_parallelism;
+use object_store::retry::RetryOptions;
 use serde::{Deserialize, Serialize};
 use servers::Mode;
-use storage::config::EngineConfig as StorageEngineConfig;
-use storage::scheduler::SchedulerConfig;
+use snafu::{ensure, ResultExt};
+use storage::backup::BackupOptions;
+use storage::config::{EngineConfig as StorageEngineConfig, MultipartConfig, WriteStallConfig};
+use storage::gc::GcOptions;
+use storage::scheduler::{SchedulePolicy, SchedulerConfig};
 
-use crate::error::Result;
-use crate::instance::{Instance, InstanceRef};
+use crate::error::{self, Result};
+use crate::instance::{new_object_store, Instance, InstanceRef};
+use crate::reload::ConfigReloader;
 use crate::server::Services;
 
-pub const DEFAULT_OBJECT_STORE_CACHE_SIZE: ReadableSize = ReadableSize(1024);
+/// Default capacity of the object store read cache.
+pub const DEFAULT_OBJECT_STORE_CACHE_SIZE: ReadableSize = ReadableSize::gb(1);
+pub const DEFAULT_MULTIPART_CHUNK_SIZE: ReadableSize = ReadableSize::mb(8);
+/// Minimum part size of S3 multipart uploads, except the last part.
+const S3_MIN_MULTIPART_CHUNK_SIZE: ReadableSize = ReadableSize::mb(5);
+/// Minimum part size of OSS multipart uploads, except the last part.
+const OSS_MIN_MULTIPART_CHUNK_SIZE: ReadableSize = ReadableSize::kb(100);
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
@@ -35,6 +50,7 @@ pub enum ObjectStoreConfig {
     File(FileConfig),
     S3(S3Config),
     Oss(OssConfig),
+    Azblob(AzblobConfig),
 }
 
 #[derive(Debug, Clone, Serialize, Default, Deserialize)]
@@ -50,10 +66,18 @@ pub struct S3Config {
     pub root: String,
     pub access_key_id: String,
     pub secret_access_key: String,
+    /// Accesses the bucket without credentials, the keys are ignored and the object store
+    /// only allows reads.
+    pub anonymous: bool,
     pub endpoint: Option<String>,
     pub region: Option<String>,
     pub cache_path: Option<String>,
+    /// Max total size of the files under `cache_path`.
     pub cache_capacity: Option<ReadableSize>,
+    /// SST files of at least this size are uploaded in parts, disabled if absent.
+    pub multipart_threshold: Option<ReadableSize>,
+    /// Size of each part of a multipart upload.
+    pub multipart_chunk_size: Option<ReadableSize>,
 }
 
 #[derive(Debug, Clone, Serialize, Default, Deserialize)]
@@ -63,9 +87,69 @@ pub struct OssConfig {
     pub root: String,
     pub access_key_id: String,
     pub access_key_secret: String,
+    /// Accesses the bucket without credentials, the keys are ignored and the object store
+    /// only allows reads.
+    pub anonymous: bool,
     pub endpoint: String,
     pub cache_path: Option<String>,
+    /// Max total size of the files under `cache_path`.
     pub cache_capacity: Option<ReadableSize>,
+    /// SST files of at least this size are uploaded in parts, disabled if absent.
+    pub multipart_threshold: Option<ReadableSize>,
+    /// Size of each part of a multipart upload.
+    pub multipart_chunk_size: Option<ReadableSize>,
+}
+
+#[derive(Debug, Clone, Serialize, Default, Deserialize)]
+#[serde(default)]
+pub struct AzblobConfig {
+    pub container: String,
+    pub root: String,
+    pub account_name: String,
+    pub account_key: Option<String>,
+    /// Shared access signature of the container, preferred over `account_key` if both
+    /// are set.
+    pub sas_token: Option<String>,
+    /// Accesses the container without credentials, the credentials are ignored and the
+    /// object store only allows reads.
+    pub anonymous: bool,
+    pub endpoint: String,
+    pub cache_path: Option<String>,
+    /// Max total size of the files under `cache_path`.
+    pub cache_capacity: Option<ReadableSize>,
+}
+
+/// The credential used to access an Azure Blob container.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AzblobCredential<'a> {
+    AccountKey(&'a str),
+    SasToken(&'a str),
+    Anonymous,
+}
+
+impl AzblobConfig {
+    /// Returns the credential to access the container, the SAS token is preferred over
+    /// the account key. Fails if neither is set and the access is not anonymous.
+    pub fn credential(&self) -> Result<AzblobCredential<'_>> {
+        if self.anonymous {
+            return Ok(AzblobCredential::Anonymous);
+        }
+        let non_empty = |s: &Option<String>| s.as_deref().filter(|s| !s.is_empty());
+        if let Some(sas_token) = non_empty(&self.sas_token) {
+            return Ok(AzblobCredential::SasToken(sas_token));
+        }
+        if let Some(account_key) = non_empty(&self.account_key) {
+            return Ok(AzblobCredential::AccountKey(account_key));
+        }
+        error::InvalidStorageConfigSnafu {
+            msg: format!(
+                "either account_key or sas_token is required to access azblob container {}, \
+                 or set anonymous to true",
+                self.container
+            ),
+        }
+        .fail()
+    }
 }
 
 impl Default for ObjectStoreConfig {
@@ -76,11 +160,280 @@ impl Default for ObjectStoreConfig {
     }
 }
 
+impl ObjectStoreConfig {
+    /// Checks the object store is accessible by writing, reading and deleting a probe
+    /// object under the root, so misconfigured credentials are reported before the
+    /// first write. An anonymous object store is checked by listing the root.
+    pub async fn check_connectivity(&self) -> Result<()> {
+        let object_store = new_object_store(self, &RetryOptions::default()).await?;
+        let location = self.location();
+        if self.is_anonymous() {
+            // Only reads are allowed, lists the root instead.
+            let mut lister =
+                object_store
+                    .object("/")
+                    .list()
+                    .await
+                    .context(error::CheckObjectStoreSnafu {
+                        op: "list",
+                        path: "/",
+                        location: &location,
+                    })?;
+            let _ = lister
+                .next_page()
+                .await
+                .context(error::CheckObjectStoreSnafu {
+                    op: "list",
+                    path: "/",
+                    location: &location,
+                })?;
+            info!("Object store {} is accessible for reads", location);
+            return Ok(());
+        }
+        let path = format!(".probe-{}", uuid::Uuid::new_v4());
+        let object = object_store.object(&path);
+
+        object
+            .write(PROBE_CONTENT)
+            .await
+            .context(error::CheckObjectStoreSnafu {
+                op: "write",
+                path: &path,
+                location: &location,
+            })?;
+        let content = object.read().await.context(error::CheckObjectStoreSnafu {
+            op: "read",
+            path: &path,
+            location: &location,
+        })?;
+        ensure!(
+            content == PROBE_CONTENT,
+            error::ProbeObjectMismatchSnafu {
+                path: &path,
+                location: &location,
+            }
+        );
+        object
+            .delete()
+            .await
+            .context(error::CheckObjectStoreSnafu {
+                op: "delete",
+                path: &path,
+                location: &location,
+            })?;
+
+        info!("Object store {} is accessible", location);
+        Ok(())
+    }
+
+    /// Returns the multipart upload options of the backend, validates the chunk size against
+    /// the minimum part size of the backend.
+    pub fn multipart_config(&self) -> Result<Option<MultipartConfig>> {
+        let (threshold, chunk_size, min_chunk_size) = match self {
+            // Azblob doesn't support multipart uploads.
+            ObjectStoreConfig::File(_) | ObjectStoreConfig::Azblob(_) => return Ok(None),
+            ObjectStoreConfig::S3(config) => (
+                config.multipart_threshold,
+                config.multipart_chunk_size,
+                S3_MIN_MULTIPART_CHUNK_SIZE,
+            ),
+            ObjectStoreConfig::Oss(config) => (
+                config.multipart_threshold,
+                config.multipart_chunk_size,
+                OSS_MIN_MULTIPART_CHUNK_SIZE,
+            ),
+        };
+        let Some(threshold) = threshold else { return Ok(None) };
+        let chunk_size = chunk_size.unwrap_or(DEFAULT_MULTIPART_CHUNK_SIZE);
+        ensure!(
+            chunk_size.0 >= min_chunk_size.0,
+            error::InvalidMultipartConfigSnafu {
+                msg: format!(
+                    "multipart_chunk_size of {} should be at least {}, actual: {}",
+                    self.location(),
+                    min_chunk_size,
+                    chunk_size
+                ),
+            }
+        );
+        Ok(Some(MultipartConfig {
+            threshold: threshold.0 as usize,
+            chunk_size: chunk_size.0 as usize,
+        }))
+    }
+
+    /// Returns true if the object store is accessed without credentials, so it is read only.
+    pub fn is_anonymous(&self) -> bool {
+        match self {
+            ObjectStoreConfig::File(_) => false,
+            ObjectStoreConfig::S3(config) => config.anonymous,
+            ObjectStoreConfig::Oss(config) => config.anonymous,
+            ObjectStoreConfig::Azblob(config) => config.anonymous,
+        }
+    }
+
+    /// Returns where the objects are stored, without credentials.
+    fn location(&self) -> String {
+        match self {
+            ObjectStoreConfig::File(config) => config.data_dir.clone(),
+            ObjectStoreConfig::S3(config) => format!("s3://{}/{}", config.bucket, config.root),
+            ObjectStoreConfig::Oss(config) => format!("oss://{}/{}", config.bucket, config.root),
+            ObjectStoreConfig::Azblob(config) => {
+                format!("azblob://{}/{}", config.container, config.root)
+            }
+        }
+    }
+}
+
+const PROBE_CONTENT: &[u8] = b"greptimedb";
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct StorageConfig {
+    /// Checks the object store is accessible on startup.
+    pub validate_on_start: bool,
+    /// Max number of in-flight requests to the object store, shared by all reads and
+    /// writes of the storage engine. Unbounded if not set.
+    pub max_concurrent_requests: Option<usize>,
+    /// Records request counts, bytes and errors of the object store, which are exported
+    /// by the `/metrics` endpoint.
+    pub enable_metrics: bool,
+    /// Retries of requests failed by throttling, 5xx responses or timeouts.
+    pub retry: RetryConfig,
+    /// Copies SST files to another object store in background if set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup: Option<BackupConfig>,
+    /// Deletes SST files never referenced by the manifest of their regions periodically
+    /// if set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gc: Option<GcConfig>,
+    #[serde(flatten)]
+    pub store: ObjectStoreConfig,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryConfig {
+    /// Max attempts of a request, including the first one.
+    pub max_attempts: usize,
+    /// Initial retry delay of a request, increases exponentially.
+    #[serde(with = "humantime_serde")]
+    pub retry_delay: Duration,
+    /// Max retry delay of a request.
+    #[serde(with = "humantime_serde")]
+    pub max_retry_delay: Duration,
+    /// Max time a flush or compaction may spend on its requests before it stops
+    /// retrying. Unbounded if not set.
+    #[serde(with = "humantime_serde")]
+    pub task_budget: Option<Duration>,
+}
+
+impl Default for RetryConfig {
+    fn default() -> RetryConfig {
+        let options = RetryOptions::default();
+        RetryConfig {
+            max_attempts: options.max_attempts,
+            retry_delay: options.retry_delay,
+            max_retry_delay: options.max_retry_delay,
+            task_budget: options.task_budget,
+        }
+    }
+}
+
+impl From<&RetryConfig> for RetryOptions {
+    fn from(config: &RetryConfig) -> RetryOptions {
+        RetryOptions {
+            max_attempts: config.max_attempts,
+            retry_delay: config.retry_delay,
+            max_retry_delay: config.max_retry_delay,
+            task_budget: config.task_budget,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BackupConfig {
+    /// Don't delete local SST files by TTL or compaction until they are backed up.
+    pub strict: bool,
+    /// Max retry times of copying a file.
+    pub max_retry_times: usize,
+    /// Initial retry delay of copying a file, increases exponentially.
+    #[serde(with = "humantime_serde")]
+    pub retry_delay: Duration,
+    /// Max retry delay of copying a file.
+    #[serde(with = "humantime_serde")]
+    pub max_retry_delay: Duration,
+    /// Max number of files copied concurrently.
+    pub max_concurrent_copies: usize,
+    /// Storage config of the backup.
+    pub store: ObjectStoreConfig,
+}
+
+impl Default for BackupConfig {
+    fn default() -> BackupConfig {
+        let options = BackupOptions::default();
+        BackupConfig {
+            strict: options.strict,
+            max_retry_times: options.max_retries,
+            retry_delay: options.retry_delay,
+            max_retry_delay: options.max_retry_delay,
+            max_concurrent_copies: options.max_concurrent_copies,
+            store: ObjectStoreConfig::File(FileConfig {
+                data_dir: "/tmp/greptimedb/backup/".to_string(),
+            }),
+        }
+    }
+}
+
+impl From<&BackupConfig> for BackupOptions {
+    fn from(config: &BackupConfig) -> BackupOptions {
+        BackupOptions {
+            strict: config.strict,
+            max_retries: config.max_retry_times,
+            retry_delay: config.retry_delay,
+            max_retry_delay: config.max_retry_delay,
+            max_concurrent_copies: config.max_concurrent_copies,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GcConfig {
+    /// Interval between two collections of all regions.
+    #[serde(with = "humantime_serde")]
+    pub interval: Duration,
+    /// Orphan files modified within this period are kept, should be longer than the
+    /// longest flush or compaction.
+    #[serde(with = "humantime_serde")]
+    pub grace_period: Duration,
+}
+
+impl Default for GcConfig {
+    fn default() -> GcConfig {
+        let options = GcOptions::default();
+        GcConfig {
+            interval: options.interval,
+            grace_period: options.grace_period,
+        }
+    }
+}
+
+impl From<&GcConfig> for GcOptions {
+    fn from(config: &GcConfig) -> GcOptions {
+        GcOptions {
+            interval: config.interval,
+            grace_period: config.grace_period,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct WalConfig {
-    // wal directory
-    pub dir: String,
+    // wal directory, or a list of directories to stripe region wals across
+    pub dir: WalDirs,
     // wal file size in bytes
     pub file_size: ReadableSize,
     // wal purge threshold in bytes
@@ -88,27 +441,63 @@ pub struct WalConfig {
     // purge interval in seconds
     #[serde(with = "humantime_serde")]
     pub purge_interval: Duration,
+    // max number of wal directories purged concurrently
+    pub max_concurrent_purges: usize,
     // read batch size
     pub read_batch_size: usize,
+    // max number of batches read ahead while replaying, 0 to read batches on demand
+    pub prefetch_batches: usize,
     // whether to sync log file after every write
     pub sync_write: bool,
+    // how to treat corrupted entries on replay
+    pub recovery_mode: RecoveryMode,
 }
 
 impl Default for WalConfig {
     fn default() -> Self {
         Self {
-            dir: "/tmp/greptimedb/wal".to_string(),
+            dir: "/tmp/greptimedb/wal".to_string().into(),
             file_size: ReadableSize::gb(1),        // log file size 1G
             purge_threshold: ReadableSize::gb(50), // purge threshold 50G
             purge_interval: Duration::from_secs(600),
+            max_concurrent_purges: 2,
             read_batch_size: 128,
+            prefetch_batches: 0,
             sync_write: false,
+            recovery_mode: RecoveryMode::TolerateTailCorruption,
         }
     }
 }
 
+/// WAL directories, either a single directory or a list of directories.
+///
+/// Wals of regions are striped across the directories by region id. Directories
+/// can be appended to the list, which only affects newly created regions, but a
+/// directory still holding wals can't be removed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum WalDirs {
+    Single(String),
+    Multiple(Vec<String>),
+}
+
+impl WalDirs {
+    pub fn dirs(&self) -> &[String] {
+        match self {
+            WalDirs::Single(dir) => std::slice::from_ref(dir),
+            WalDirs::Multiple(dirs) => dirs,
+        }
+    }
+}
+
+impl From<String> for WalDirs {
+    fn from(dir: String) -> Self {
+        WalDirs::Single(dir)
+    }
+}
+
 /// Options for table compaction
-#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(default)]
 pub struct CompactionConfig {
     /// Max task number that can concurrently run.
@@ -117,6 +506,19 @@ pub struct CompactionConfig {
     pub max_files_in_level0: usize,
     /// Max task number for SST purge task after compaction.
     pub max_purge_tasks: usize,
+    /// Max level of compaction output, must be in `[1, MAX_LEVEL)`. Files in a level below it
+    /// that share a time window are compacted to the next level.
+    pub max_level: u8,
+    /// How to pick the next region to compact when all tasks are busy.
+    pub fairness: CompactionFairness,
+    /// Priority gained by a waiting region for each other region compacted, under
+    /// [CompactionFairness::Aging].
+    pub aging_step: usize,
+    /// Target false positive probability of bloom filters of row key columns in
+    /// compaction outputs, must be in (0, 1). No filter is written if not set.
+    pub bloom_filter_fpp: Option<f64>,
+    /// Routine compactions only run in this window if set.
+    pub maintenance_window: Option<MaintenanceWindowConfig>,
 }
 
 impl Default for CompactionConfig {
@@ -125,14 +527,86 @@ impl Default for CompactionConfig {
             max_inflight_tasks: 4,
             max_files_in_level0: 8,
             max_purge_tasks: 32,
+            max_level: 1,
+            fairness: CompactionFairness::RoundRobin,
+            aging_step: 1,
+            bloom_filter_fpp: None,
+            maintenance_window: None,
+        }
+    }
+}
+
+/// Window for routine compactions in the timezone of the node, either a daily window set
+/// by `start` and `end`, or a window opened by `cron` and lasting `duration`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MaintenanceWindowConfig {
+    /// Start time of the daily window in `HH:MM`.
+    pub start: Option<String>,
+    /// End time of the daily window in `HH:MM`, the window spans midnight if it's not after
+    /// `start`.
+    pub end: Option<String>,
+    /// Cron expression of times the window opens, starting with seconds, e.g.
+    /// `0 0 1 * * Sat,Sun`.
+    pub cron: Option<String>,
+    /// How long the window opened by `cron` lasts.
+    #[serde(default, with = "humantime_serde")]
+    pub duration: Option<Duration>,
+    /// Regions with more files in level 0 are compacted outside the window, must be greater
+    /// than `max_files_in_level0`.
+    pub emergency_files_in_level0: usize,
+}
+
+/// Policies to share compaction tasks among regions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompactionFairness {
+    /// Regions are compacted in the order they become eligible.
+    #[default]
+    RoundRobin,
+    /// Regions with more files in level 0 are compacted first, while waiting regions gain
+    /// priority over time.
+    Aging,
+}
+
+/// Options for memtable flush
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(default)]
+pub struct FlushConfig {
+    /// Memtable size of a region to trigger a flush.
+    pub memtable_flush_size: ReadableSize,
+    /// Max memtable size of all regions, the region with the largest memtable is
+    /// flushed once it's exceeded. No limit if not set.
+    pub total_memtable_budget: Option<ReadableSize>,
+    /// Writes to a region are delayed once its unflushed memtables exceed this multiple
+    /// of `memtable_flush_size`.
+    pub write_stall_soft_factor: usize,
+    /// Writes to a region are rejected once its unflushed memtables exceed this multiple
+    /// of `memtable_flush_size`.
+    pub write_stall_hard_factor: usize,
+}
+
+impl Default for FlushConfig {
+    fn default() -> Self {
+        Self {
+            memtable_flush_size: ReadableSize::mb(32),
+            total_memtable_budget: None,
+            write_stall_soft_factor: 2,
+            write_stall_hard_factor: 4,
         }
     }
 }
 
 impl From<&DatanodeOptions> for SchedulerConfig {
     fn from(value: &DatanodeOptions) -> Self {
+        let policy = match value.compaction.fairness {
+            CompactionFairness::RoundRobin => SchedulePolicy::RoundRobin,
+            CompactionFairness::Aging => SchedulePolicy::Aging {
+                step: value.compaction.aging_step,
+            },
+        };
         Self {
             max_inflight_tasks: value.compaction.max_inflight_tasks,
+            policy,
         }
     }
 }
@@ -142,6 +616,19 @@ impl From<&DatanodeOptions> for StorageEngineConfig {
         Self {
             max_files_in_l0: value.compaction.max_files_in_level0,
             max_purge_tasks: value.compaction.max_purge_tasks,
+            memtable_flush_size: value.flush.memtable_flush_size.0 as usize,
+            total_memtable_budget: value
+                .flush
+                .total_memtable_budget
+                .map(|size| size.0 as usize),
+            multipart: None,
+            gc: value.storage.gc.as_ref().map(GcOptions::from),
+            write_stall: WriteStallConfig {
+                soft_factor: value.flush.write_stall_soft_factor,
+                hard_factor: value.flush.write_stall_hard_factor,
+                ..Default::default()
+            },
+            wal_prefetch_batches: value.wal.prefetch_batches,
         }
     }
 }
@@ -188,13 +675,29 @@ pub struct DatanodeOptions {
     pub rpc_addr: String,
     pub rpc_hostname: Option<String>,
     pub rpc_runtime_size: usize,
+    /// Compression of gRPC responses, compressed requests are always accepted.
+    pub grpc_compression: GrpcCompression,
     pub mysql_addr: String,
     pub mysql_runtime_size: usize,
+    /// Address of the HTTP server for writes in the InfluxDB line protocol, disabled if
+    /// absent.
+    pub influxdb_addr: Option<String>,
+    /// Address of the HTTP server for writes through the OpenTSDB `/api/put` API, disabled
+    /// if absent.
+    pub opentsdb_http_addr: Option<String>,
     pub meta_client_options: Option<MetaClientOptions>,
     pub wal: WalConfig,
-    pub storage: ObjectStoreConfig,
+    pub storage: StorageConfig,
     pub compaction: CompactionConfig,
+    pub flush: FlushConfig,
     pub procedure: Option<ProcedureConfig>,
+    /// Max number of regions opened concurrently on startup.
+    pub region_open_parallelism: usize,
+    /// Max number of tables opened concurrently on request of other nodes, e.g. when the
+    /// regions of a failed datanode are moved here.
+    pub max_concurrent_region_opens: usize,
+    /// Max number of regions with the highest write rates reported in a heartbeat.
+    pub heartbeat_max_hot_regions: usize,
 }
 
 impl Default for DatanodeOptions {
@@ -206,13 +709,20 @@ impl Default for DatanodeOptions {
             rpc_addr: "127.0.0.1:3001".to_string(),
             rpc_hostname: None,
             rpc_runtime_size: 8,
+            grpc_compression: GrpcCompression::None,
             mysql_addr: "127.0.0.1:4406".to_string(),
             mysql_runtime_size: 2,
+            influxdb_addr: None,
+            opentsdb_http_addr: None,
             meta_client_options: None,
             wal: WalConfig::default(),
-            storage: ObjectStoreConfig::default(),
+            storage: StorageConfig::default(),
             compaction: CompactionConfig::default(),
+            flush: FlushConfig::default(),
             procedure: None,
+            region_open_parallelism: default_region_open_parallelism(),
+            max_concurrent_region_opens: 4,
+            heartbeat_max_hot_regions: 32,
         }
     }
 }
@@ -222,16 +732,25 @@ pub struct Datanode {
     opts: DatanodeOptions,
     services: Services,
     instance: InstanceRef,
+    reloader: Arc<ConfigReloader>,
 }
 
 impl Datanode {
     pub async fn new(opts: DatanodeOptions) -> Result<Datanode> {
+        Self::with_plugins(opts, Arc::new(Plugins::new())).await
+    }
+
+    /// Creates the datanode with `plugins`, e.g. the user provider to authenticate the
+    /// requests of the InfluxDB line protocol and the OpenTSDB put API.
+    pub async fn with_plugins(opts: DatanodeOptions, plugins: Arc<Plugins>) -> Result<Datanode> {
         let instance = Arc::new(Instance::new(&opts).await?);
-        let services = Services::try_new(instance.clone(), &opts).await?;
+        let services = Services::try_new(instance.clone(), &opts, plugins).await?;
+        let reloader = Arc::new(ConfigReloader::new(instance.clone(), opts.clone()));
         Ok(Self {
             opts,
             services,
             instance,
+            reloader,
         })
     }
 
@@ -255,6 +774,11 @@ impl Datanode {
         self.instance.clone()
     }
 
+    /// Returns the reloader to apply reloaded options to the running datanode.
+    pub fn config_reloader(&self) -> Arc<ConfigReloader> {
+        self.reloader.clone()
+    }
+
     pub async fn shutdown_instance(&self) -> Result<()> {
         self.instance.shutdown().await
     }
@@ -272,6 +796,8 @@ impl Datanode {
 
 #[cfg(test)]
 mod tests {
+    use common_test_util::temp_dir::create_temp_dir;
+
     use super::*;
 
     #[test]
@@ -280,4 +806,356 @@ mod tests {
         let toml_string = toml::to_string(&opts).unwrap();
         let _parsed: DatanodeOptions = toml::from_str(&toml_string).unwrap();
     }
+
+    #[test]
+    fn test_wal_dirs_toml() {
+        let opts: DatanodeOptions = toml::from_str(
+            r#"
+            [wal]
+            dir = "/tmp/greptimedb/wal"
+        "#,
+        )
+        .unwrap();
+        assert_eq!(&["/tmp/greptimedb/wal".to_string()], opts.wal.dir.dirs());
+
+        let opts: DatanodeOptions = toml::from_str(
+            r#"
+            [wal]
+            dir = ["/disk0/wal", "/disk1/wal"]
+        "#,
+        )
+        .unwrap();
+        assert_eq!(
+            &["/disk0/wal".to_string(), "/disk1/wal".to_string()],
+            opts.wal.dir.dirs()
+        );
+        let toml_string = toml::to_string(&opts).unwrap();
+        let parsed: DatanodeOptions = toml::from_str(&toml_string).unwrap();
+        assert_eq!(opts.wal.dir, parsed.wal.dir);
+    }
+
+    #[test]
+    fn test_storage_config_toml() {
+        let toml_str = r#"
+            [storage]
+            type = "File"
+            data_dir = "/tmp/greptimedb/test_data/"
+            validate_on_start = true
+            max_concurrent_requests = 64
+            enable_metrics = true
+        "#;
+        let opts: DatanodeOptions = toml::from_str(toml_str).unwrap();
+        assert!(opts.storage.validate_on_start);
+        assert_eq!(Some(64), opts.storage.max_concurrent_requests);
+        assert!(opts.storage.enable_metrics);
+        match opts.storage.store {
+            ObjectStoreConfig::File(FileConfig { data_dir }) => {
+                assert_eq!("/tmp/greptimedb/test_data/", data_dir)
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_backup_config_toml() {
+        let toml_str = r#"
+            [storage]
+            type = "File"
+            data_dir = "/tmp/greptimedb/test_data/"
+
+            [storage.backup]
+            strict = true
+            retry_delay = "1s"
+
+            [storage.backup.store]
+            type = "S3"
+            bucket = "greptimedb-backup"
+            root = "data"
+        "#;
+        let opts: DatanodeOptions = toml::from_str(toml_str).unwrap();
+        let backup = opts.storage.backup.unwrap();
+        let options = BackupOptions::from(&backup);
+        assert!(options.strict);
+        assert_eq!(Duration::from_secs(1), options.retry_delay);
+        assert_eq!(BackupOptions::default().max_retries, options.max_retries);
+        let ObjectStoreConfig::S3(s3_config) = &backup.store else { unreachable!() };
+        assert_eq!("greptimedb-backup", s3_config.bucket);
+        assert_eq!("data", s3_config.root);
+        assert!(matches!(opts.storage.store, ObjectStoreConfig::File(_)));
+    }
+
+    #[test]
+    fn test_gc_config_toml() {
+        let opts = DatanodeOptions::default();
+        assert!(opts.storage.gc.is_none());
+        assert!(StorageEngineConfig::from(&opts).gc.is_none());
+
+        let toml_str = r#"
+            [storage]
+            type = "File"
+            data_dir = "/tmp/greptimedb/test_data/"
+
+            [storage.gc]
+            grace_period = "2h"
+        "#;
+        let opts: DatanodeOptions = toml::from_str(toml_str).unwrap();
+        let config = StorageEngineConfig::from(&opts);
+        assert_eq!(
+            Some(GcOptions {
+                interval: GcOptions::default().interval,
+                grace_period: Duration::from_secs(2 * 60 * 60),
+            }),
+            config.gc
+        );
+    }
+
+    #[test]
+    fn test_maintenance_window_config_toml() {
+        let toml_str = r#"
+            [compaction.maintenance_window]
+            cron = "0 0 1 * * Sat,Sun"
+            duration = "4h"
+            emergency_files_in_level0 = 32
+        "#;
+        let opts: DatanodeOptions = toml::from_str(toml_str).unwrap();
+        let window = opts.compaction.maintenance_window.unwrap();
+        assert_eq!(None, window.start);
+        assert_eq!(Some("0 0 1 * * Sat,Sun"), window.cron.as_deref());
+        assert_eq!(Some(Duration::from_secs(4 * 60 * 60)), window.duration);
+
+        let toml_str = r#"
+            [compaction.maintenance_window]
+            start = "01:00"
+            end = "05:00"
+            emergency_files_in_level0 = 32
+        "#;
+        let opts: DatanodeOptions = toml::from_str(toml_str).unwrap();
+        let window = opts.compaction.maintenance_window.unwrap();
+        assert_eq!(Some("01:00"), window.start.as_deref());
+        assert_eq!(None, window.cron);
+        assert_eq!(None, window.duration);
+    }
+
+    #[test]
+    fn test_multipart_config_toml() {
+        let toml_str = r#"
+            [storage]
+            type = "S3"
+            bucket = "greptimedb"
+            root = "data"
+            multipart_threshold = "64MiB"
+            multipart_chunk_size = "16MiB"
+        "#;
+        let opts: DatanodeOptions = toml::from_str(toml_str).unwrap();
+        let ObjectStoreConfig::S3(s3_config) = &opts.storage.store else { unreachable!() };
+        assert_eq!(Some(ReadableSize::mb(64)), s3_config.multipart_threshold);
+        assert_eq!(Some(ReadableSize::mb(16)), s3_config.multipart_chunk_size);
+
+        let toml_string = toml::to_string(&opts).unwrap();
+        let parsed: DatanodeOptions = toml::from_str(&toml_string).unwrap();
+        let ObjectStoreConfig::S3(parsed) = parsed.storage.store else { unreachable!() };
+        assert_eq!(s3_config.multipart_threshold, parsed.multipart_threshold);
+        assert_eq!(s3_config.multipart_chunk_size, parsed.multipart_chunk_size);
+
+        assert_eq!(
+            Some(MultipartConfig {
+                threshold: ReadableSize::mb(64).0 as usize,
+                chunk_size: ReadableSize::mb(16).0 as usize,
+            }),
+            opts.storage.store.multipart_config().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_multipart_config() {
+        let file_config = ObjectStoreConfig::File(FileConfig::default());
+        assert_eq!(None, file_config.multipart_config().unwrap());
+
+        let s3_config = ObjectStoreConfig::S3(S3Config::default());
+        assert_eq!(None, s3_config.multipart_config().unwrap());
+
+        // The chunk size defaults to 8MiB.
+        let s3_config = ObjectStoreConfig::S3(S3Config {
+            multipart_threshold: Some(ReadableSize::mb(100)),
+            ..Default::default()
+        });
+        assert_eq!(
+            Some(MultipartConfig {
+                threshold: ReadableSize::mb(100).0 as usize,
+                chunk_size: DEFAULT_MULTIPART_CHUNK_SIZE.0 as usize,
+            }),
+            s3_config.multipart_config().unwrap()
+        );
+
+        // S3 requires parts of at least 5MiB, while OSS accepts 100KiB.
+        let s3_config = ObjectStoreConfig::S3(S3Config {
+            multipart_threshold: Some(ReadableSize::mb(100)),
+            multipart_chunk_size: Some(ReadableSize::mb(1)),
+            ..Default::default()
+        });
+        assert!(matches!(
+            s3_config.multipart_config(),
+            Err(error::Error::InvalidMultipartConfig { .. })
+        ));
+        let oss_config = ObjectStoreConfig::Oss(OssConfig {
+            multipart_threshold: Some(ReadableSize::mb(100)),
+            multipart_chunk_size: Some(ReadableSize::mb(1)),
+            ..Default::default()
+        });
+        assert!(oss_config.multipart_config().unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_check_connectivity() {
+        let dir = create_temp_dir("test_check_connectivity");
+        let config = ObjectStoreConfig::File(FileConfig {
+            data_dir: dir.path().to_str().unwrap().to_string(),
+        });
+        config.check_connectivity().await.unwrap();
+        // The probe object is deleted.
+        let entries = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .filter(|name| name.starts_with(".probe-"))
+            .collect::<Vec<_>>();
+        assert!(entries.is_empty(), "{entries:?}");
+    }
+
+    #[tokio::test]
+    async fn test_check_connectivity_bogus_root() {
+        let dir = create_temp_dir("test_check_connectivity_bogus_root");
+        // The root is under a regular file, so it can't be created.
+        let file = dir.path().join("file");
+        std::fs::write(&file, b"").unwrap();
+        let config = ObjectStoreConfig::File(FileConfig {
+            data_dir: file.join("data").to_str().unwrap().to_string(),
+        });
+        assert!(config.check_connectivity().await.is_err());
+    }
+
+    #[test]
+    fn test_anonymous_config_toml() {
+        let toml_str = r#"
+            [storage]
+            type = "S3"
+            bucket = "public-data"
+            root = "data"
+            anonymous = true
+        "#;
+        let opts: DatanodeOptions = toml::from_str(toml_str).unwrap();
+        let ObjectStoreConfig::S3(s3_config) = &opts.storage.store else { unreachable!() };
+        assert!(s3_config.anonymous);
+        assert!(s3_config.access_key_id.is_empty());
+        assert!(opts.storage.store.is_anonymous());
+
+        let toml_str = r#"
+            [storage]
+            type = "Oss"
+            bucket = "public-data"
+            root = "data"
+            endpoint = "https://oss-cn-hangzhou.aliyuncs.com"
+            anonymous = true
+        "#;
+        let opts: DatanodeOptions = toml::from_str(toml_str).unwrap();
+        assert!(opts.storage.store.is_anonymous());
+
+        // Credentials are required by default.
+        assert!(!ObjectStoreConfig::S3(S3Config::default()).is_anonymous());
+        assert!(!ObjectStoreConfig::Oss(OssConfig::default()).is_anonymous());
+        assert!(!ObjectStoreConfig::default().is_anonymous());
+    }
+
+    #[tokio::test]
+    async fn test_anonymous_object_store_rejects_writes() {
+        let config = ObjectStoreConfig::S3(S3Config {
+            bucket: "public-data".to_string(),
+            root: "data".to_string(),
+            region: Some("us-east-1".to_string()),
+            endpoint: Some("http://127.0.0.1:9".to_string()),
+            anonymous: true,
+            ..Default::default()
+        });
+        let object_store = new_object_store(&config, &RetryOptions::default())
+            .await
+            .unwrap();
+
+        // Writes are rejected before sending any request.
+        let err = object_store
+            .object("test_file")
+            .write("Hello, World!")
+            .await
+            .unwrap_err();
+        assert_eq!(object_store::ErrorKind::ObjectPermissionDenied, err.kind());
+        assert!(err.to_string().contains("anonymous"), "{err}");
+        let err = object_store.object("test_file").delete().await.unwrap_err();
+        assert_eq!(object_store::ErrorKind::ObjectPermissionDenied, err.kind());
+    }
+
+    #[test]
+    fn test_azblob_config_toml() {
+        let toml_str = r#"
+            [storage]
+            type = "Azblob"
+            container = "greptimedb"
+            root = "data"
+            account_name = "greptime"
+            sas_token = "sv=2021-06-08&sig=secret"
+            endpoint = "https://greptime.blob.core.windows.net"
+        "#;
+        let opts: DatanodeOptions = toml::from_str(toml_str).unwrap();
+        let ObjectStoreConfig::Azblob(azblob_config) = &opts.storage.store else { unreachable!() };
+        assert_eq!("greptimedb", azblob_config.container);
+        assert_eq!(None, azblob_config.account_key);
+        assert_eq!(
+            AzblobCredential::SasToken("sv=2021-06-08&sig=secret"),
+            azblob_config.credential().unwrap()
+        );
+        assert_eq!(None, opts.storage.store.multipart_config().unwrap());
+
+        let toml_string = toml::to_string(&opts).unwrap();
+        let parsed: DatanodeOptions = toml::from_str(&toml_string).unwrap();
+        let ObjectStoreConfig::Azblob(parsed) = parsed.storage.store else { unreachable!() };
+        assert_eq!(azblob_config.container, parsed.container);
+        assert_eq!(azblob_config.root, parsed.root);
+        assert_eq!(azblob_config.account_name, parsed.account_name);
+        assert_eq!(azblob_config.sas_token, parsed.sas_token);
+        assert_eq!(azblob_config.endpoint, parsed.endpoint);
+    }
+
+    #[test]
+    fn test_azblob_credential() {
+        // The SAS token takes precedence over the account key.
+        let config = AzblobConfig {
+            account_key: Some("key".to_string()),
+            sas_token: Some("token".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            AzblobCredential::SasToken("token"),
+            config.credential().unwrap()
+        );
+
+        let config = AzblobConfig {
+            account_key: Some("key".to_string()),
+            sas_token: Some(String::new()),
+            ..Default::default()
+        };
+        assert_eq!(
+            AzblobCredential::AccountKey("key"),
+            config.credential().unwrap()
+        );
+
+        // At least one credential is required unless the access is anonymous.
+        let config = AzblobConfig::default();
+        assert!(matches!(
+            config.credential(),
+            Err(error::Error::InvalidStorageConfig { .. })
+        ));
+        let config = AzblobConfig {
+            account_key: Some("key".to_string()),
+            anonymous: true,
+            ..Default::default()
+        };
+        assert_eq!(AzblobCredential::Anonymous, config.credential().unwrap());
+    }
 }
@@ -82,6 +82,20 @@ pub enum Error {
         source: TableError,
     },
 
+    #[snafu(display("Failed to open table: {}, source: {}", table_name, source))]
+    OpenTable {
+        table_name: String,
+        #[snafu(backtrace)]
+        source: TableError,
+    },
+
+    #[snafu(display("Failed to close regions of table: {}, source: {}", table_name, source))]
+    CloseRegions {
+        table_name: String,
+        #[snafu(backtrace)]
+        source: TableError,
+    },
+
     #[snafu(display("Failed to get table: {}, source: {}", table_name, source))]
     GetTable {
         table_name: String,
@@ -109,6 +123,12 @@ pub enum Error {
         backtrace: Backtrace,
     },
 
+    #[snafu(display("Table already exists: {}", table_name))]
+    TableExists {
+        table_name: String,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Column {} not found in table {}", column_name, table_name))]
     ColumnNotFound {
         column_name: String,
@@ -217,12 +237,44 @@ pub enum Error {
         backtrace: Backtrace,
     },
 
+    #[snafu(display(
+        "Failed to {} probe object {} in object store {}, source: {}",
+        op,
+        path,
+        location,
+        source
+    ))]
+    CheckObjectStore {
+        op: String,
+        path: String,
+        location: String,
+        source: object_store::Error,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display(
+        "Probe object {} read from object store {} is not the one written",
+        path,
+        location
+    ))]
+    ProbeObjectMismatch {
+        path: String,
+        location: String,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Failed to build backend, source: {}", source))]
     BuildBackend {
         #[snafu(backtrace)]
         source: DataSourceError,
     },
 
+    #[snafu(display("Failed to copy table in the given format, source: {}", source))]
+    CopyFormat {
+        #[snafu(backtrace)]
+        source: DataSourceError,
+    },
+
     #[snafu(display("Failed to parse url, source: {}", source))]
     ParseUrl {
         source: DataSourceError,
@@ -250,6 +302,24 @@ pub enum Error {
     #[snafu(display("Unsupported backend protocol: {}", protocol))]
     UnsupportedBackendProtocol { protocol: String },
 
+    #[snafu(display("Invalid compaction config: {}", msg))]
+    InvalidCompactionConfig { msg: String, backtrace: Backtrace },
+
+    #[snafu(display("Failed to serialize options, source: {}", source))]
+    SerializeOptions {
+        source: serde_json::Error,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Invalid multipart upload config: {}", msg))]
+    InvalidMultipartConfig { msg: String, backtrace: Backtrace },
+
+    #[snafu(display("Invalid storage config: {}", msg))]
+    InvalidStorageConfig { msg: String, backtrace: Backtrace },
+
+    #[snafu(display("Invalid WAL config: {}", msg))]
+    InvalidWalConfig { msg: String, backtrace: Backtrace },
+
     #[snafu(display("Failed to regex, source: {}", source))]
     BuildRegex {
         backtrace: Backtrace,
@@ -366,12 +436,37 @@ pub enum Error {
         source: meta_client::error::Error,
     },
 
+    #[snafu(display("Failed to reply instruction {}, source: {}", id, source))]
+    ReplyInstruction {
+        id: u64,
+        #[snafu(backtrace)]
+        source: meta_client::error::Error,
+    },
+
+    #[snafu(display("Failed to report failed regions, source: {}", source))]
+    ReportFailedRegions {
+        #[snafu(backtrace)]
+        source: meta_client::error::Error,
+    },
+
     #[snafu(display("Failed to insert data, source: {}", source))]
     InsertData {
         #[snafu(backtrace)]
         source: common_grpc_expr::error::Error,
     },
 
+    #[snafu(display("Failed to build CreateExpr on insertion: {}", source))]
+    BuildCreateExprOnInsertion {
+        #[snafu(backtrace)]
+        source: common_grpc_expr::error::Error,
+    },
+
+    #[snafu(display("Failed to find new columns on insertion: {}", source))]
+    FindNewColumnsOnInsertion {
+        #[snafu(backtrace)]
+        source: common_grpc_expr::error::Error,
+    },
+
     #[snafu(display(
         "Table id provider not found, cannot execute SQL directly on datanode in distributed mode"
     ))]
@@ -440,16 +535,6 @@ pub enum Error {
         source: common_query::error::Error,
     },
 
-    #[snafu(display(
-        "File Schema mismatch, expected table schema: {} but found :{}",
-        table_schema,
-        file_schema
-    ))]
-    InvalidSchema {
-        table_schema: String,
-        file_schema: String,
-    },
-
     #[snafu(display("Failed to read parquet file, source: {}", source))]
     ReadParquet {
         source: parquet::errors::ParquetError,
@@ -531,6 +616,9 @@ pub enum Error {
         #[snafu(backtrace)]
         source: BoxedError,
     },
+
+    #[snafu(display("Datanode is starting up and not ready to serve requests yet"))]
+    NotReady { backtrace: Backtrace },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -548,9 +636,11 @@ impl ErrorExt for Error {
             DecodeLogicalPlan { source } => source.status_code(),
             NewCatalog { source } | RegisterSchema { source } => source.status_code(),
             FindTable { source, .. } => source.status_code(),
-            CreateTable { source, .. } | GetTable { source, .. } | AlterTable { source, .. } => {
-                source.status_code()
-            }
+            CreateTable { source, .. }
+            | OpenTable { source, .. }
+            | CloseRegions { source, .. }
+            | GetTable { source, .. }
+            | AlterTable { source, .. } => source.status_code(),
             DropTable { source, .. } => source.status_code(),
             FlushTable { source, .. } => source.status_code(),
 
@@ -559,13 +649,16 @@ impl ErrorExt for Error {
             CollectRecords { source, .. } => source.status_code(),
 
             TableNotFound { .. } => StatusCode::TableNotFound,
+            TableExists { .. } => StatusCode::TableAlreadyExists,
             ColumnNotFound { .. } => StatusCode::TableColumnNotFound,
 
             ParseSqlValue { source, .. } | ParseSql { source, .. } => source.status_code(),
 
             AlterExprToRequest { source, .. }
             | CreateExprToRequest { source }
-            | InsertData { source } => source.status_code(),
+            | InsertData { source }
+            | BuildCreateExprOnInsertion { source }
+            | FindNewColumnsOnInsertion { source } => source.status_code(),
 
             ConvertSchema { source, .. } | VectorComputation { source } => source.status_code(),
 
@@ -576,6 +669,10 @@ impl ErrorExt for Error {
             | InvalidPath { .. }
             | InvalidConnection { .. }
             | UnsupportedBackendProtocol { .. }
+            | InvalidCompactionConfig { .. }
+            | InvalidMultipartConfig { .. }
+            | InvalidStorageConfig { .. }
+            | InvalidWalConfig { .. }
             | BuildRegex { .. }
             | NotSupportSql { .. }
             | KeyColumnNotFound { .. }
@@ -604,15 +701,17 @@ impl ErrorExt for Error {
             | Catalog { .. }
             | MissingRequiredField { .. }
             | BuildParquetRecordBatchStream { .. }
-            | InvalidSchema { .. }
             | ParseDataTypes { .. }
             | IncorrectInternalState { .. }
             | ShutdownServer { .. }
             | ShutdownInstance { .. }
-            | CloseTableEngine { .. } => StatusCode::Internal,
+            | CloseTableEngine { .. }
+            | SerializeOptions { .. } => StatusCode::Internal,
 
             BuildBackend { .. }
             | InitBackend { .. }
+            | CheckObjectStore { .. }
+            | ProbeObjectMismatch { .. }
             | ReadParquet { .. }
             | WriteParquet { .. }
             | PollStream { .. }
@@ -623,11 +722,16 @@ impl ErrorExt for Error {
             StartScriptManager { source } => source.status_code(),
             OpenStorageEngine { source } => source.status_code(),
             RuntimeResource { .. } => StatusCode::RuntimeResourcesExhausted,
-            MetaClientInit { source, .. } => source.status_code(),
+            // Retryable, the datanode becomes ready once it has recovered its tables.
+            NotReady { .. } => StatusCode::StorageUnavailable,
+            MetaClientInit { source, .. }
+            | ReplyInstruction { source, .. }
+            | ReportFailedRegions { source, .. } => source.status_code(),
             TableIdProviderNotFound { .. } => StatusCode::Unsupported,
             BumpTableId { source, .. } => source.status_code(),
             ColumnDefaultValue { source, .. } => source.status_code(),
             CopyTable { source, .. } => source.status_code(),
+            CopyFormat { source } => source.status_code(),
             TableScanExec { source, .. } => source.status_code(),
             UnrecognizedTableOption { .. } => StatusCode::InvalidArguments,
             RecoverProcedure { source, .. } | SubmitProcedure { source, .. } => {
@@ -12,17 +12,29 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use api::v1::meta::{HeartbeatRequest, HeartbeatResponse, NodeStat, Peer};
-use catalog::{datanode_stat, CatalogManagerRef};
-use common_telemetry::{error, info, warn};
+use api::v1::meta::{HeartbeatRequest, HeartbeatResponse, NodeStat, Peer, RegionStat};
+use catalog::{datanode_stat, CatalogManagerRef, DatanodeRegionStat};
+use common_telemetry::{debug, error, info, warn};
+use common_time::util as time_util;
 use meta_client::client::{HeartbeatSender, MetaClient};
+use meta_client::rpc::PutRequest;
+use meta_srv::handler::instruction::InstructionMessage;
+use meta_srv::handler::node_stat::{rows_per_sec_to_wcus, OTHER_REGIONS_ID};
+use meta_srv::keys::{FailedRegionKey, FailedRegionValue};
+use mito::engine::FailedRegion;
 use snafu::ResultExt;
 
-use crate::error::{MetaClientInitSnafu, Result};
+use crate::error::{MetaClientInitSnafu, ReportFailedRegionsSnafu, Result};
+use crate::heartbeat::instruction::InstructionExecutor;
+use crate::instance::DefaultEngine;
+use crate::region_open::RegionOpenLimiter;
+
+mod instruction;
 
 pub struct HeartbeatTask {
     node_id: u64,
@@ -31,7 +43,10 @@ pub struct HeartbeatTask {
     running: Arc<AtomicBool>,
     meta_client: Arc<MetaClient>,
     catalog_manager: CatalogManagerRef,
+    table_engine: Arc<DefaultEngine>,
+    instruction_executor: InstructionExecutor,
     interval: u64,
+    max_hot_regions: usize,
 }
 
 impl Drop for HeartbeatTask {
@@ -48,7 +63,16 @@ impl HeartbeatTask {
         server_hostname: Option<String>,
         meta_client: Arc<MetaClient>,
         catalog_manager: CatalogManagerRef,
+        table_engine: Arc<DefaultEngine>,
+        region_open_limiter: Arc<RegionOpenLimiter>,
+        max_hot_regions: usize,
     ) -> Self {
+        let instruction_executor = InstructionExecutor::new(
+            meta_client.clone(),
+            catalog_manager.clone(),
+            table_engine.clone(),
+            region_open_limiter,
+        );
         Self {
             node_id,
             server_addr,
@@ -56,13 +80,17 @@ impl HeartbeatTask {
             running: Arc::new(AtomicBool::new(false)),
             meta_client,
             catalog_manager,
+            table_engine,
+            instruction_executor,
             interval: 5_000, // default interval is set to 5 secs
+            max_hot_regions,
         }
     }
 
-    pub async fn create_streams(
+    async fn create_streams(
         meta_client: &MetaClient,
         running: Arc<AtomicBool>,
+        instruction_executor: InstructionExecutor,
     ) -> Result<HeartbeatSender> {
         let (tx, mut rx) = meta_client.heartbeat().await.context(MetaClientInitSnafu)?;
         common_runtime::spawn_bg(async move {
@@ -73,7 +101,7 @@ impl HeartbeatTask {
                     None
                 }
             } {
-                Self::handle_response(res).await;
+                Self::handle_response(res, &instruction_executor);
                 if !running.load(Ordering::Acquire) {
                     info!("Heartbeat task shutdown");
                 }
@@ -83,8 +111,47 @@ impl HeartbeatTask {
         Ok(tx)
     }
 
-    async fn handle_response(resp: HeartbeatResponse) {
-        info!("heartbeat response: {:?}", resp);
+    fn handle_response(resp: HeartbeatResponse, instruction_executor: &InstructionExecutor) {
+        debug!("heartbeat response: {:?}", resp);
+        for payload in &resp.payload {
+            match serde_json::from_slice::<InstructionMessage>(payload) {
+                Ok(message) => instruction_executor.submit(message),
+                Err(e) => error!("Failed to decode instruction, error: {}", e),
+            }
+        }
+    }
+
+    /// Puts the regions failed to open to the metasrv, so they can be listed by its admin API.
+    async fn report_failed_regions(
+        meta_client: &MetaClient,
+        regions: &[FailedRegion],
+    ) -> Result<()> {
+        let (cluster_id, node_id) = meta_client.id();
+        let key = FailedRegionKey {
+            cluster_id,
+            node_id,
+        };
+        let value = FailedRegionValue {
+            timestamp_millis: time_util::current_time_millis(),
+            regions: regions
+                .iter()
+                .map(|region| meta_srv::keys::FailedRegion {
+                    table_name: region.table_name.clone(),
+                    region_number: region.region_number,
+                    error: region.error.clone(),
+                    retries: region.retries,
+                })
+                .collect(),
+        };
+        // Safety: the value is a plain struct that can always be serialized.
+        let value: Vec<u8> = value.try_into().unwrap();
+        let req = PutRequest::new().with_key(key).with_value(value);
+        let _ = meta_client
+            .put(req)
+            .await
+            .context(ReportFailedRegionsSnafu)?;
+
+        Ok(())
     }
 
     /// Start heartbeat task, spawn background task.
@@ -101,13 +168,23 @@ impl HeartbeatTask {
         let node_id = self.node_id;
         let addr = resolve_addr(&self.server_addr, &self.server_hostname);
         let meta_client = self.meta_client.clone();
+        let mut hot_regions = HotRegionTracker::new(self.max_hot_regions);
+        let table_engine = self.table_engine.clone();
+        // Failed regions reported last time, they are reported again once changed.
+        let mut reported_failed_regions = None;
 
         let catalog_manager_clone = self.catalog_manager.clone();
-        let mut tx = Self::create_streams(&meta_client, running.clone()).await?;
+        let instruction_executor = self.instruction_executor.clone();
+        let mut tx =
+            Self::create_streams(&meta_client, running.clone(), instruction_executor.clone())
+                .await?;
         common_runtime::spawn_bg(async move {
             while running.load(Ordering::Acquire) {
                 let (region_num, region_stats) = match datanode_stat(&catalog_manager_clone).await {
-                    Ok(datanode_stat) => (datanode_stat.0 as i64, datanode_stat.1),
+                    Ok((region_num, region_stats)) => (
+                        region_num as i64,
+                        hot_regions.hot_regions(region_stats, Instant::now()),
+                    ),
                     Err(e) => {
                         error!("failed to get region status, err: {e:?}");
                         (-1, vec![])
@@ -127,9 +204,23 @@ impl HeartbeatTask {
                     ..Default::default()
                 };
 
+                let failed_regions = table_engine.failed_regions();
+                if reported_failed_regions.as_ref() != Some(&failed_regions) {
+                    match Self::report_failed_regions(&meta_client, &failed_regions).await {
+                        Ok(()) => reported_failed_regions = Some(failed_regions),
+                        Err(e) => error!(e; "Failed to report failed regions to metasrv"),
+                    }
+                }
+
                 if let Err(e) = tx.send(req).await {
                     error!("Failed to send heartbeat to metasrv, error: {:?}", e);
-                    match Self::create_streams(&meta_client, running.clone()).await {
+                    match Self::create_streams(
+                        &meta_client,
+                        running.clone(),
+                        instruction_executor.clone(),
+                    )
+                    .await
+                    {
                         Ok(new_tx) => {
                             info!("Reconnected to metasrv");
                             tx = new_tx;
@@ -159,6 +250,86 @@ impl HeartbeatTask {
     }
 }
 
+/// Picks the regions with the highest write rates since the previous heartbeat, so
+/// the size of a heartbeat is bounded regardless of how many regions the datanode hosts.
+/// The other regions are summed up in one stat, so the totals of the datanode such as
+/// its disk usage are still complete.
+struct HotRegionTracker {
+    max_regions: usize,
+    /// Number of rows written to each region when the previous heartbeat is sent.
+    last_written_rows: HashMap<u64, u64>,
+    last_instant: Instant,
+}
+
+impl HotRegionTracker {
+    fn new(max_regions: usize) -> Self {
+        Self {
+            max_regions,
+            last_written_rows: HashMap::new(),
+            last_instant: Instant::now(),
+        }
+    }
+
+    /// Returns at most `max_regions` stats in descending order of the write rates, followed
+    /// by a stat with [OTHER_REGIONS_ID] summing up the others if there are. The write
+    /// rates are converted to the `wcus` of stats by [rows_per_sec_to_wcus].
+    fn hot_regions(&mut self, stats: Vec<DatanodeRegionStat>, now: Instant) -> Vec<RegionStat> {
+        let elapsed_secs = now
+            .saturating_duration_since(self.last_instant)
+            .as_secs_f64();
+        self.last_instant = now;
+        let last_written_rows = std::mem::replace(
+            &mut self.last_written_rows,
+            HashMap::with_capacity(stats.len()),
+        );
+
+        let mut stats = stats
+            .into_iter()
+            .map(
+                |DatanodeRegionStat {
+                     mut stat,
+                     written_rows,
+                     ..
+                 }| {
+                    // The counter starts from zero again if the region is reopened.
+                    let last = last_written_rows
+                        .get(&stat.region_id)
+                        .copied()
+                        .filter(|last| *last <= written_rows)
+                        .unwrap_or(0);
+                    stat.wcus = if elapsed_secs > 0.0 {
+                        rows_per_sec_to_wcus((written_rows - last) as f64 / elapsed_secs)
+                    } else {
+                        0
+                    };
+                    self.last_written_rows.insert(stat.region_id, written_rows);
+                    stat
+                },
+            )
+            .collect::<Vec<_>>();
+        stats.sort_unstable_by(|a, b| {
+            b.wcus
+                .cmp(&a.wcus)
+                .then_with(|| b.approximate_bytes.cmp(&a.approximate_bytes))
+        });
+        if stats.len() > self.max_regions {
+            let others = stats.split_off(self.max_regions);
+            let mut other = RegionStat {
+                region_id: OTHER_REGIONS_ID,
+                ..Default::default()
+            };
+            for stat in others {
+                other.rcus += stat.rcus;
+                other.wcus += stat.wcus;
+                other.approximate_bytes += stat.approximate_bytes;
+                other.approximate_rows += stat.approximate_rows;
+            }
+            stats.push(other);
+        }
+        stats
+    }
+}
+
 /// Resolves hostname:port address for meta registration
 ///
 fn resolve_addr(bind_addr: &str, hostname_addr: &Option<String>) -> String {
@@ -180,6 +351,61 @@ fn resolve_addr(bind_addr: &str, hostname_addr: &Option<String>) -> String {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+
+    fn region_stat(region_id: u64, written_rows: u64) -> DatanodeRegionStat {
+        DatanodeRegionStat {
+            stat: RegionStat {
+                region_id,
+                approximate_bytes: 1024,
+                ..Default::default()
+            },
+            written_rows,
+            max_timestamp: None,
+        }
+    }
+
+    #[test]
+    fn test_hot_regions() {
+        let start = Instant::now();
+        let mut tracker = HotRegionTracker {
+            max_regions: 2,
+            last_written_rows: HashMap::new(),
+            last_instant: start,
+        };
+
+        let stats = vec![
+            region_stat(1, 100),
+            region_stat(2, 300),
+            region_stat(3, 200),
+        ];
+        let hot = tracker.hot_regions(stats, start + Duration::from_secs(10));
+        let rates = hot
+            .iter()
+            .map(|s| (s.region_id, s.wcus))
+            .collect::<Vec<_>>();
+        assert_eq!(vec![(2, 30), (3, 20), (OTHER_REGIONS_ID, 10)], rates);
+        // The disk usage of all regions is reported.
+        let bytes: i64 = hot.iter().map(|s| s.approximate_bytes).sum();
+        assert_eq!(3 * 1024, bytes);
+
+        // Region 2 is reopened, so its counter starts from zero again.
+        let stats = vec![region_stat(1, 600), region_stat(2, 50), region_stat(3, 200)];
+        let hot = tracker.hot_regions(stats, start + Duration::from_secs(20));
+        let rates = hot
+            .iter()
+            .map(|s| (s.region_id, s.wcus))
+            .collect::<Vec<_>>();
+        assert_eq!(vec![(1, 50), (2, 5), (OTHER_REGIONS_ID, 0)], rates);
+
+        // No other regions to sum up.
+        tracker.max_regions = 3;
+        let stats = vec![region_stat(1, 600), region_stat(2, 50), region_stat(3, 200)];
+        let hot = tracker.hot_regions(stats, start + Duration::from_secs(30));
+        assert_eq!(3, hot.len());
+        assert!(hot.iter().all(|s| s.region_id != OTHER_REGIONS_ID));
+    }
+
     #[test]
     fn test_resolve_addr() {
         assert_eq!(
@@ -0,0 +1,221 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+use catalog::CatalogManagerRef;
+use common_error::prelude::BoxedError;
+use common_telemetry::{error, info};
+use meta_client::client::MetaClient;
+use meta_client::rpc::PutRequest;
+use meta_srv::handler::instruction::{
+    Instruction, InstructionMessage, InstructionReply, RegionIdent,
+};
+use meta_srv::keys::InstructionReplyKey;
+use snafu::{OptionExt, ResultExt};
+use table::engine::{EngineContext, TableEngineRef, TableReference};
+use table::requests::{CloseRegionsRequest, DropTableRequest, OpenRegionsRequest};
+
+use crate::error::{self, Result};
+use crate::region_open::RegionOpenLimiter;
+
+/// Executes the instructions sent by the metasrv in heartbeat responses, e.g. to move
+/// regions between datanodes, and replies them through the metasrv's kv store.
+#[derive(Clone)]
+pub(crate) struct InstructionExecutor {
+    meta_client: Arc<MetaClient>,
+    catalog_manager: CatalogManagerRef,
+    table_engine: TableEngineRef,
+    region_open_limiter: Arc<RegionOpenLimiter>,
+    /// Ids of the instructions being executed, an instruction is sent in every heartbeat
+    /// response until it's replied.
+    executing: Arc<Mutex<HashSet<u64>>>,
+}
+
+impl InstructionExecutor {
+    pub(crate) fn new(
+        meta_client: Arc<MetaClient>,
+        catalog_manager: CatalogManagerRef,
+        table_engine: TableEngineRef,
+        region_open_limiter: Arc<RegionOpenLimiter>,
+    ) -> Self {
+        Self {
+            meta_client,
+            catalog_manager,
+            table_engine,
+            region_open_limiter,
+            executing: Arc::default(),
+        }
+    }
+
+    /// Executes the instruction in background unless it's being executed.
+    pub(crate) fn submit(&self, message: InstructionMessage) {
+        if !self.executing.lock().unwrap().insert(message.id) {
+            return;
+        }
+
+        let executor = self.clone();
+        common_runtime::spawn_bg(async move {
+            let id = message.id;
+            info!("Executing instruction {}: {:?}", id, message.instruction);
+            let error = executor
+                .execute(message.instruction)
+                .await
+                .err()
+                .map(|e| e.to_string());
+            if let Err(e) = executor.reply(id, InstructionReply { error }).await {
+                error!(e; "Failed to reply instruction {}", id);
+            }
+            let _ = executor.executing.lock().unwrap().remove(&id);
+        });
+    }
+
+    async fn execute(&self, instruction: Instruction) -> Result<()> {
+        match instruction {
+            Instruction::OpenRegions(region) => self.open_regions(region).await,
+            Instruction::CloseRegions(region) => self.close_regions(region).await,
+            Instruction::DropTable(region) => self.drop_table(region).await,
+        }
+    }
+
+    async fn open_regions(&self, region: RegionIdent) -> Result<()> {
+        let table_name = region.table_name();
+        let request = OpenRegionsRequest {
+            catalog_name: region.catalog.clone(),
+            schema_name: region.schema.clone(),
+            table_name: region.table.clone(),
+            table_id: region.table_id,
+            region_numbers: region.region_numbers,
+        };
+        let table = self
+            .region_open_limiter
+            .run(
+                self.table_engine
+                    .open_regions(&EngineContext::default(), request),
+            )
+            .await
+            .context(error::OpenTableSnafu {
+                table_name: &table_name,
+            })?
+            .context(error::TableNotFoundSnafu {
+                table_name: &table_name,
+            })?;
+
+        // Registers the table again so its regions in the catalog are updated.
+        let schema = self.schema(&region.catalog, &region.schema)?;
+        let _ = schema
+            .register_table(region.table, table)
+            .context(error::CatalogSnafu)?;
+        info!("Opened regions of table {} on request", table_name);
+
+        Ok(())
+    }
+
+    async fn close_regions(&self, region: RegionIdent) -> Result<()> {
+        let table_name = region.table_name();
+        let request = CloseRegionsRequest {
+            catalog_name: region.catalog.clone(),
+            schema_name: region.schema.clone(),
+            table_name: region.table.clone(),
+            region_numbers: region.region_numbers,
+        };
+        let ctx = EngineContext::default();
+        self.table_engine
+            .close_regions(&ctx, request)
+            .await
+            .context(error::CloseRegionsSnafu {
+                table_name: &table_name,
+            })?;
+
+        let table_ref = TableReference {
+            catalog: &region.catalog,
+            schema: &region.schema,
+            table: &region.table,
+        };
+        let table =
+            self.table_engine
+                .get_table(&ctx, &table_ref)
+                .context(error::GetTableSnafu {
+                    table_name: &table_name,
+                })?;
+        let schema = self.schema(&region.catalog, &region.schema)?;
+        let _ = match table {
+            Some(table) => schema.register_table(region.table, table),
+            // The table is closed with its last region.
+            None => schema.deregister_table(&region.table),
+        }
+        .context(error::CatalogSnafu)?;
+        info!("Closed regions of table {} on request", table_name);
+
+        Ok(())
+    }
+
+    async fn drop_table(&self, region: RegionIdent) -> Result<()> {
+        let table_name = region.table_name();
+        let schema = self.schema(&region.catalog, &region.schema)?;
+        let _ = schema
+            .deregister_table(&region.table)
+            .context(error::CatalogSnafu)?;
+
+        let request = DropTableRequest {
+            catalog_name: region.catalog,
+            schema_name: region.schema,
+            table_name: region.table,
+            sync: false,
+        };
+        let dropped = self
+            .table_engine
+            .drop_table(&EngineContext::default(), request)
+            .await
+            .map_err(BoxedError::new)
+            .context(error::DropTableSnafu {
+                table_name: &table_name,
+            })?;
+        info!(
+            "Dropped table {} on request, existed: {}",
+            table_name, dropped
+        );
+
+        Ok(())
+    }
+
+    fn schema(&self, catalog: &str, schema: &str) -> Result<catalog::SchemaProviderRef> {
+        self.catalog_manager
+            .schema(catalog, schema)
+            .context(error::CatalogSnafu)?
+            .context(error::SchemaNotFoundSnafu {
+                name: format!("{catalog}.{schema}"),
+            })
+    }
+
+    async fn reply(&self, id: u64, reply: InstructionReply) -> Result<()> {
+        let (cluster_id, node_id) = self.meta_client.id();
+        let key = InstructionReplyKey {
+            cluster_id,
+            node_id,
+            id,
+        };
+        // Safety: the reply is a plain struct that can always be serialized.
+        let value = serde_json::to_vec(&reply).unwrap();
+        let req = PutRequest::new().with_key(key).with_value(value);
+        let _ = self
+            .meta_client
+            .put(req)
+            .await
+            .context(error::ReplyInstructionSnafu { id })?;
+
+        Ok(())
+    }
+}
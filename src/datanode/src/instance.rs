@@ -12,55 +12,80 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use std::{fs, path};
 
+use async_trait::async_trait;
 use catalog::remote::MetaKvBackend;
 use catalog::{CatalogManager, CatalogManagerRef, RegisterTableRequest};
+use chrono::NaiveTime;
 use common_base::readable_size::ReadableSize;
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, MIN_USER_TABLE_ID};
+use common_catalog::format_full_table_name;
 use common_error::prelude::BoxedError;
 use common_grpc::channel_manager::{ChannelConfig, ChannelManager};
 use common_procedure::local::{LocalManager, ManagerConfig};
 use common_procedure::ProcedureManagerRef;
 use common_telemetry::logging::info;
+use common_time::timestamp::TimeUnit;
 use log_store::raft_engine::log_store::RaftEngineLogStore;
 use log_store::LogConfig;
 use meta_client::client::{MetaClient, MetaClientBuilder};
 use meta_client::MetaClientOptions;
 use mito::config::EngineConfig as TableEngineConfig;
-use mito::engine::MitoEngine;
+use mito::engine::{FailedRegion, MitoEngine};
 use object_store::cache_policy::LruCacheLayer;
-use object_store::layers::{LoggingLayer, MetricsLayer, RetryLayer, TracingLayer};
-use object_store::services::{Fs as FsBuilder, Oss as OSSBuilder, S3 as S3Builder};
+use object_store::layers::{ConcurrentLimitLayer, LoggingLayer, MetricsLayer, TracingLayer};
+use object_store::metrics::ObjectStoreMetricsLayer;
+use object_store::read_only::ReadOnlyLayer;
+use object_store::retry::{ObjectStoreRetryLayer, RetryOptions};
+use object_store::services::{
+    Azblob as AzblobBuilder, Fs as FsBuilder, Oss as OSSBuilder, S3 as S3Builder,
+};
 use object_store::{util, ObjectStore, ObjectStoreBuilder};
 use query::query_engine::{QueryEngineFactory, QueryEngineRef};
+use servers::query_handler::{
+    BackupHandler, CompactionStatus, CompactionStatusHandler, ReadinessHandler, RegionBackupLag,
+    RegionDataStats, RegionGcResult, RegionVerifyResult, SstFileSize, SstGcHandler,
+    StorageVerifyHandler, StorageVerifyResult, TableStats, TableStatsHandler,
+};
 use servers::Mode;
 use session::context::QueryContext;
 use snafu::prelude::*;
-use storage::compaction::{CompactionHandler, CompactionSchedulerRef, SimplePicker};
-use storage::config::EngineConfig as StorageEngineConfig;
+use storage::backup::{BackupOptions, SstBackup, SstBackupRef};
+use storage::compaction::{CompactionHandler, CompactionSchedulerRef, SimplePicker, MAX_LEVEL};
+use storage::config::{BloomFilterConfig, EngineConfig as StorageEngineConfig};
+use storage::scheduler::window::MaintenanceWindow;
 use storage::scheduler::{LocalScheduler, SchedulerConfig};
+use storage::verify::{RegionVerifyReport, DEFAULT_VERIFY_PARALLELISM};
 use storage::EngineImpl;
 use store_api::logstore::LogStore;
-use table::requests::FlushTableRequest;
+use table::engine::{EngineContext, TableEngine};
+use table::requests::{FlushTableRequest, OpenTableRequest};
 use table::table::numbers::NumbersTable;
 use table::table::TableIdProviderRef;
-use table::Table;
+use table::{Table, TableRef};
 
 use crate::datanode::{
-    DatanodeOptions, ObjectStoreConfig, ProcedureConfig, WalConfig, DEFAULT_OBJECT_STORE_CACHE_SIZE,
+    AzblobCredential, DatanodeOptions, MaintenanceWindowConfig, ObjectStoreConfig, ProcedureConfig,
+    WalConfig, DEFAULT_OBJECT_STORE_CACHE_SIZE,
 };
 use crate::error::{
-    self, CatalogSnafu, MetaClientInitSnafu, MissingMetasrvOptsSnafu, MissingNodeIdSnafu,
-    NewCatalogSnafu, OpenLogStoreSnafu, RecoverProcedureSnafu, Result, ShutdownInstanceSnafu,
+    self, CatalogSnafu, InvalidCompactionConfigSnafu, InvalidStorageConfigSnafu,
+    MetaClientInitSnafu, MissingMetasrvOptsSnafu, MissingNodeIdSnafu, NewCatalogSnafu,
+    OpenLogStoreSnafu, RecoverProcedureSnafu, Result, ShutdownInstanceSnafu,
 };
 use crate::heartbeat::HeartbeatTask;
+use crate::region_open::RegionOpenLimiter;
 use crate::script::ScriptExecutor;
 use crate::sql::{SqlHandler, SqlRequest};
 
 mod grpc;
+mod influxdb;
+mod opentsdb;
 mod script;
 pub mod sql;
 
@@ -74,6 +99,14 @@ pub struct Instance {
     pub(crate) script_executor: ScriptExecutor,
     pub(crate) table_id_provider: Option<TableIdProviderRef>,
     pub(crate) heartbeat_task: Option<HeartbeatTask>,
+    pub(crate) table_engine: Arc<DefaultEngine>,
+    pub(crate) backup: Option<SstBackupRef>,
+    pub(crate) storage_engine: EngineImpl<RaftEngineLogStore>,
+    pub(crate) compaction_scheduler: CompactionSchedulerRef<RaftEngineLogStore>,
+    pub(crate) region_open_limiter: Arc<RegionOpenLimiter>,
+    /// Set once the instance has started, i.e. the tables are recovered from the WAL and
+    /// manifests. Requests are rejected before that.
+    ready: AtomicBool,
 }
 
 pub type InstanceRef = Arc<Instance>;
@@ -94,7 +127,7 @@ impl Instance {
             }
         };
 
-        let compaction_scheduler = create_compaction_scheduler(opts);
+        let compaction_scheduler = create_compaction_scheduler(opts)?;
 
         Self::new_with(opts, meta_client, compaction_scheduler).await
     }
@@ -104,17 +137,54 @@ impl Instance {
         meta_client: Option<Arc<MetaClient>>,
         compaction_scheduler: CompactionSchedulerRef<RaftEngineLogStore>,
     ) -> Result<Self> {
-        let object_store = new_object_store(&opts.storage).await?;
+        if opts.storage.validate_on_start {
+            opts.storage.store.check_connectivity().await?;
+        }
+        let retry_options = RetryOptions::from(&opts.storage.retry);
+        let mut object_store = with_concurrent_limit(
+            new_object_store(&opts.storage.store, &retry_options).await?,
+            opts.storage.max_concurrent_requests,
+        )?;
+        if opts.storage.enable_metrics {
+            object_store = object_store.layer(ObjectStoreMetricsLayer);
+        }
         let log_store = Arc::new(create_log_store(&opts.wal).await?);
+        let storage_config = StorageEngineConfig {
+            multipart: opts.storage.store.multipart_config()?,
+            ..StorageEngineConfig::from(opts)
+        };
 
-        let table_engine = Arc::new(DefaultEngine::new(
-            TableEngineConfig::default(),
-            EngineImpl::new(
-                StorageEngineConfig::from(opts),
+        let backup = match &opts.storage.backup {
+            Some(config) => Some(SstBackup::new(
+                object_store.clone(),
+                new_object_store(&config.store, &retry_options).await?,
+                BackupOptions::from(config),
+            )),
+            None => None,
+        };
+        let storage_engine = match &backup {
+            Some(backup) => EngineImpl::with_backup(
+                storage_config,
                 log_store.clone(),
                 object_store.clone(),
-                compaction_scheduler,
+                compaction_scheduler.clone(),
+                backup.clone(),
+            )
+            .context(error::OpenStorageEngineSnafu)?,
+            None => EngineImpl::new(
+                storage_config,
+                log_store.clone(),
+                object_store.clone(),
+                compaction_scheduler.clone(),
             ),
+        };
+
+        let table_engine = Arc::new(DefaultEngine::new(
+            TableEngineConfig {
+                region_open_parallelism: opts.region_open_parallelism,
+                ..Default::default()
+            },
+            storage_engine.clone(),
             object_store,
         ));
 
@@ -171,6 +241,8 @@ impl Instance {
         let script_executor =
             ScriptExecutor::new(catalog_manager.clone(), query_engine.clone()).await?;
 
+        let region_open_limiter =
+            Arc::new(RegionOpenLimiter::new(opts.max_concurrent_region_opens));
         let heartbeat_task = match opts.mode {
             Mode::Standalone => None,
             Mode::Distributed => Some(HeartbeatTask::new(
@@ -179,6 +251,9 @@ impl Instance {
                 opts.rpc_hostname.clone(),
                 meta_client.as_ref().unwrap().clone(),
                 catalog_manager.clone(),
+                table_engine.clone(),
+                region_open_limiter.clone(),
+                opts.heartbeat_max_hot_regions,
             )),
         };
 
@@ -205,16 +280,65 @@ impl Instance {
                 table_engine.clone(),
                 catalog_manager.clone(),
                 query_engine.clone(),
-                table_engine,
+                table_engine.clone(),
                 procedure_manager,
             ),
             catalog_manager,
             script_executor,
             heartbeat_task,
             table_id_provider,
+            table_engine,
+            backup,
+            storage_engine,
+            compaction_scheduler,
+            region_open_limiter,
+            ready: AtomicBool::new(false),
         })
     }
 
+    /// Opens the table on request of other nodes and registers it to the catalog, returns
+    /// None if the table doesn't exist. The opens are limited by `max_concurrent_region_opens`.
+    pub async fn open_table(&self, request: OpenTableRequest) -> Result<Option<TableRef>> {
+        let table_name = format_full_table_name(
+            &request.catalog_name,
+            &request.schema_name,
+            &request.table_name,
+        );
+        let table = self
+            .region_open_limiter
+            .run(
+                self.table_engine
+                    .open_table(&EngineContext::default(), request.clone()),
+            )
+            .await
+            .context(error::OpenTableSnafu {
+                table_name: &table_name,
+            })?;
+        let Some(table) = table else {
+            return Ok(None);
+        };
+
+        let register_req = RegisterTableRequest {
+            catalog: request.catalog_name,
+            schema: request.schema_name,
+            table_name: request.table_name,
+            table_id: request.table_id,
+            table: table.clone(),
+        };
+        self.catalog_manager
+            .register_table(register_req)
+            .await
+            .context(error::InsertSystemCatalogSnafu)?;
+        info!("Opened table on request: {}", table_name);
+
+        Ok(Some(table))
+    }
+
+    /// Returns regions failed to open, which are retried in background.
+    pub fn failed_regions(&self) -> Vec<FailedRegion> {
+        self.table_engine.failed_regions()
+    }
+
     pub async fn start(&self) -> Result<()> {
         self.catalog_manager
             .start()
@@ -223,6 +347,18 @@ impl Instance {
         if let Some(task) = &self.heartbeat_task {
             task.start().await?;
         }
+        self.ready.store(true, Ordering::Release);
+        info!("Datanode instance is ready");
+        Ok(())
+    }
+
+    /// Returns whether the instance has finished starting up and is able to serve requests.
+    pub fn is_ready(&self) -> bool {
+        self.ready.load(Ordering::Acquire)
+    }
+
+    pub(crate) fn ensure_ready(&self) -> Result<()> {
+        ensure!(self.is_ready(), error::NotReadySnafu);
         Ok(())
     }
 
@@ -294,37 +430,345 @@ impl Instance {
     }
 }
 
-fn create_compaction_scheduler<S: LogStore>(opts: &DatanodeOptions) -> CompactionSchedulerRef<S> {
-    let picker = SimplePicker::default();
-    let config = SchedulerConfig::from(opts);
+impl BackupHandler for Instance {
+    fn backup_status(&self) -> servers::error::Result<Vec<RegionBackupLag>> {
+        let backup = self
+            .backup
+            .as_ref()
+            .context(servers::error::NotSupportedSnafu {
+                feat: "SST backup without [storage.backup] config",
+            })?;
+        Ok(backup
+            .status()
+            .into_iter()
+            .map(|status| RegionBackupLag {
+                region_id: status.region_id,
+                high_water_mark: status.high_water_mark,
+                pending_files: status.pending_files,
+                pending_bytes: status.pending_bytes,
+                failed_files: status.failed_files,
+                lag_ms: status.lag.as_millis() as u64,
+            })
+            .collect())
+    }
+}
+
+impl CompactionStatusHandler for Instance {
+    fn compaction_status(&self) -> servers::error::Result<CompactionStatus> {
+        let status = self.compaction_scheduler.status();
+        Ok(CompactionStatus {
+            queued_regions: status.queued,
+            deferred_regions: status.deferred,
+            window_open: status.window_open,
+        })
+    }
+}
+
+impl ReadinessHandler for Instance {
+    fn is_ready(&self) -> bool {
+        Instance::is_ready(self)
+    }
+}
+
+#[async_trait]
+impl TableStatsHandler for Instance {
+    async fn table_stats(&self) -> servers::error::Result<Vec<TableStats>> {
+        let (_, region_stats) = catalog::datanode_stat(&self.catalog_manager)
+            .await
+            .map_err(|e| {
+                servers::error::InternalSnafu {
+                    err_msg: format!("Failed to get region stats, source: {e}"),
+                }
+                .build()
+            })?;
+
+        let mut tables: BTreeMap<(String, String, String), TableStats> = BTreeMap::new();
+        for region_stat in region_stats {
+            let Some(table_name) = region_stat.stat.table_name else {
+                continue;
+            };
+            let max_timestamp_millis = region_stat
+                .max_timestamp
+                .and_then(|ts| ts.convert_to(TimeUnit::Millisecond))
+                .map(|ts| ts.value());
+            let approximate_rows = region_stat.stat.approximate_rows.max(0) as u64;
+
+            let key = (
+                table_name.catalog_name,
+                table_name.schema_name,
+                table_name.table_name,
+            );
+            let table = tables.entry(key.clone()).or_insert_with(|| TableStats {
+                catalog_name: key.0,
+                schema_name: key.1,
+                table_name: key.2,
+                max_timestamp_millis: None,
+                approximate_rows: 0,
+                regions: Vec::new(),
+            });
+            table.max_timestamp_millis = table.max_timestamp_millis.max(max_timestamp_millis);
+            table.approximate_rows += approximate_rows;
+            table.regions.push(RegionDataStats {
+                region_id: region_stat.stat.region_id,
+                max_timestamp_millis,
+                approximate_rows,
+            });
+        }
+        Ok(tables.into_values().collect())
+    }
+}
+
+#[async_trait]
+impl SstGcHandler for Instance {
+    async fn collect_garbage(&self, dry_run: bool) -> servers::error::Result<Vec<RegionGcResult>> {
+        Ok(self
+            .storage_engine
+            .collect_garbage(dry_run)
+            .await
+            .into_iter()
+            .map(|report| RegionGcResult {
+                region_id: report.region_id,
+                region_name: report.region_name,
+                orphan_files: report
+                    .orphans
+                    .iter()
+                    .map(|orphan| orphan.file_id.as_parquet())
+                    .collect(),
+                orphan_bytes: report.orphans.iter().map(|orphan| orphan.file_size).sum(),
+                failed_files: report.failed_files,
+                skipped: report.skipped,
+            })
+            .collect())
+    }
+}
+
+#[async_trait]
+impl StorageVerifyHandler for Instance {
+    async fn verify_storage(
+        &self,
+        catalog_name: &str,
+        schema_name: &str,
+        table_name: &str,
+    ) -> servers::error::Result<StorageVerifyResult> {
+        let table = self
+            .catalog_manager
+            .table(catalog_name, schema_name, table_name)
+            .await
+            .context(servers::error::CatalogSnafu)?
+            .with_context(|| servers::error::InvalidQuerySnafu {
+                reason: format!(
+                    "Table not found: {}",
+                    format_full_table_name(catalog_name, schema_name, table_name)
+                ),
+            })?;
+        let table_info = table.table_info();
+        let table_id = table_info.ident.table_id;
+        let region_numbers = &table_info.meta.region_numbers;
+
+        let region_names = region_numbers
+            .iter()
+            .map(|n| table::engine::region_name(table_id, *n))
+            .collect::<Vec<_>>();
+        let reports = self
+            .storage_engine
+            .verify_regions(&region_names, DEFAULT_VERIFY_PARALLELISM)
+            .await;
+
+        let has_problems = reports.iter().any(RegionVerifyReport::has_problems);
+        let regions = region_numbers
+            .iter()
+            .zip(reports)
+            .map(|(n, report)| region_verify_result(table::engine::region_id(table_id, *n), report))
+            .collect();
+        Ok(StorageVerifyResult {
+            catalog_name: catalog_name.to_string(),
+            schema_name: schema_name.to_string(),
+            table_name: table_name.to_string(),
+            has_problems,
+            regions,
+        })
+    }
+}
+
+/// Converts the `report` of region `region_id`, the id is not known by the storage engine
+/// if the region is not opened.
+fn region_verify_result(region_id: u64, report: RegionVerifyReport) -> RegionVerifyResult {
+    RegionVerifyResult {
+        region_id,
+        region_name: report.region_name,
+        checked_files: report.checked_files,
+        missing_files: report
+            .missing
+            .into_iter()
+            .map(|file| SstFileSize {
+                file_name: file.file_id.as_parquet(),
+                expected_size: Some(file.expected_size),
+                actual_size: None,
+            })
+            .collect(),
+        size_mismatches: report
+            .size_mismatches
+            .into_iter()
+            .map(|file| SstFileSize {
+                file_name: file.file_id.as_parquet(),
+                expected_size: Some(file.expected_size),
+                actual_size: Some(file.actual_size),
+            })
+            .collect(),
+        orphan_files: report
+            .orphans
+            .into_iter()
+            .map(|file| SstFileSize {
+                file_name: file.file_id.as_parquet(),
+                expected_size: None,
+                actual_size: Some(file.file_size),
+            })
+            .collect(),
+        skipped: report.skipped,
+    }
+}
+
+fn create_compaction_scheduler<S: LogStore>(
+    opts: &DatanodeOptions,
+) -> Result<CompactionSchedulerRef<S>> {
+    let max_level = opts.compaction.max_level;
+    ensure!(
+        max_level >= 1 && max_level < MAX_LEVEL,
+        InvalidCompactionConfigSnafu {
+            msg: format!("max_level should be in [1, {MAX_LEVEL}), actual: {max_level}"),
+        }
+    );
+
+    let bloom_filter = match opts.compaction.bloom_filter_fpp {
+        Some(fpp) => {
+            ensure!(
+                fpp > 0.0 && fpp < 1.0,
+                InvalidCompactionConfigSnafu {
+                    msg: format!("bloom_filter_fpp should be in (0, 1), actual: {fpp}"),
+                }
+            );
+            Some(BloomFilterConfig { fpp })
+        }
+        None => None,
+    };
+
+    let window = opts
+        .compaction
+        .maintenance_window
+        .as_ref()
+        .map(|window| maintenance_window(window, opts.compaction.max_files_in_level0))
+        .transpose()?;
+
+    let picker = SimplePicker::default()
+        .with_max_level(max_level)
+        .with_bloom_filter(bloom_filter);
+    let config = SchedulerConfig {
+        window,
+        ..SchedulerConfig::from(opts)
+    };
     let handler = CompactionHandler::new(picker);
     let scheduler = LocalScheduler::new(config, handler);
-    Arc::new(scheduler)
+    Ok(Arc::new(scheduler))
 }
 
-pub(crate) async fn new_object_store(store_config: &ObjectStoreConfig) -> Result<ObjectStore> {
+fn maintenance_window(
+    config: &MaintenanceWindowConfig,
+    max_files_in_level0: usize,
+) -> Result<MaintenanceWindow> {
+    let parse_time = |time: &str| {
+        NaiveTime::parse_from_str(time, "%H:%M").map_err(|_| {
+            InvalidCompactionConfigSnafu {
+                msg: format!("maintenance window time should be in HH:MM, actual: {time}"),
+            }
+            .build()
+        })
+    };
+    let emergency_files = config.emergency_files_in_level0;
+    ensure!(
+        emergency_files > max_files_in_level0,
+        InvalidCompactionConfigSnafu {
+            msg: format!(
+                "emergency_files_in_level0 should be greater than max_files_in_level0 {max_files_in_level0}, actual: {emergency_files}"
+            ),
+        }
+    );
+    match (&config.start, &config.end, &config.cron) {
+        (Some(start), Some(end), None) => Ok(MaintenanceWindow::new(
+            parse_time(start)?,
+            parse_time(end)?,
+            emergency_files,
+        )),
+        (None, None, Some(cron)) => {
+            let duration = config
+                .duration
+                .and_then(|duration| chrono::Duration::from_std(duration).ok())
+                .filter(|duration| *duration > chrono::Duration::zero())
+                .context(InvalidCompactionConfigSnafu {
+                    msg: "maintenance window with cron requires a positive duration",
+                })?;
+            MaintenanceWindow::with_cron(cron, duration, emergency_files).map_err(|e| {
+                InvalidCompactionConfigSnafu {
+                    msg: format!("invalid maintenance window cron {cron}: {e}"),
+                }
+                .build()
+            })
+        }
+        _ => InvalidCompactionConfigSnafu {
+            msg: "maintenance window should have either start and end, or cron",
+        }
+        .fail(),
+    }
+}
+
+pub(crate) async fn new_object_store(
+    store_config: &ObjectStoreConfig,
+    retry_options: &RetryOptions,
+) -> Result<ObjectStore> {
     let object_store = match store_config {
         ObjectStoreConfig::File { .. } => new_fs_object_store(store_config).await,
         ObjectStoreConfig::S3 { .. } => new_s3_object_store(store_config).await,
         ObjectStoreConfig::Oss { .. } => new_oss_object_store(store_config).await,
+        ObjectStoreConfig::Azblob { .. } => new_azblob_object_store(store_config).await,
     };
 
     object_store.map(|object_store| {
+        let object_store = if store_config.is_anonymous() {
+            info!("The object store is accessed anonymously, only reads are allowed");
+            object_store.layer(ReadOnlyLayer)
+        } else {
+            object_store
+        };
         object_store
-            .layer(RetryLayer::new().with_jitter())
+            .layer(ObjectStoreRetryLayer::new(retry_options.clone()))
             .layer(MetricsLayer)
             .layer(LoggingLayer::default())
             .layer(TracingLayer)
     })
 }
 
+/// Bounds the number of in-flight requests to the `object_store` by `limit`, if any.
+fn with_concurrent_limit(object_store: ObjectStore, limit: Option<usize>) -> Result<ObjectStore> {
+    let Some(limit) = limit else {
+        return Ok(object_store);
+    };
+    ensure!(
+        limit > 0,
+        InvalidStorageConfigSnafu {
+            msg: "max_concurrent_requests should be greater than 0",
+        }
+    );
+    info!("Limit concurrent object store requests to {}", limit);
+
+    Ok(object_store.layer(ConcurrentLimitLayer::new(limit)))
+}
+
 pub(crate) async fn new_oss_object_store(store_config: &ObjectStoreConfig) -> Result<ObjectStore> {
     let oss_config = match store_config {
         ObjectStoreConfig::Oss(config) => config,
         _ => unreachable!(),
     };
 
-    let root = util::normalize_dir(&oss_config.root);
+    let root = util::normalize_root(&oss_config.root);
     info!(
         "The oss storage bucket is: {}, root is: {}",
         oss_config.bucket, &root
@@ -334,18 +778,59 @@ pub(crate) async fn new_oss_object_store(store_config: &ObjectStoreConfig) -> Re
     let builder = builder
         .root(&root)
         .bucket(&oss_config.bucket)
-        .endpoint(&oss_config.endpoint)
-        .access_key_id(&oss_config.access_key_id)
-        .access_key_secret(&oss_config.access_key_secret);
+        .endpoint(&oss_config.endpoint);
+    if !oss_config.anonymous {
+        builder
+            .access_key_id(&oss_config.access_key_id)
+            .access_key_secret(&oss_config.access_key_secret);
+    }
 
     let accessor = builder.build().with_context(|_| error::InitBackendSnafu {
         config: store_config.clone(),
     })?;
 
-    create_object_store_with_cache(ObjectStore::new(accessor).finish(), store_config)
+    create_object_store_with_cache(ObjectStore::new(accessor).finish(), store_config).await
 }
 
-fn create_object_store_with_cache(
+pub(crate) async fn new_azblob_object_store(
+    store_config: &ObjectStoreConfig,
+) -> Result<ObjectStore> {
+    let azblob_config = match store_config {
+        ObjectStoreConfig::Azblob(config) => config,
+        _ => unreachable!(),
+    };
+    let credential = azblob_config.credential()?;
+
+    let root = util::normalize_root(&azblob_config.root);
+    info!(
+        "The azblob storage container is: {}, root is: {}",
+        azblob_config.container, &root
+    );
+
+    let mut builder = AzblobBuilder::default();
+    let builder = builder
+        .root(&root)
+        .container(&azblob_config.container)
+        .endpoint(&azblob_config.endpoint)
+        .account_name(&azblob_config.account_name);
+    match credential {
+        AzblobCredential::SasToken(sas_token) => {
+            builder.sas_token(sas_token);
+        }
+        AzblobCredential::AccountKey(account_key) => {
+            builder.account_key(account_key);
+        }
+        AzblobCredential::Anonymous => {}
+    }
+
+    let accessor = builder.build().with_context(|_| error::InitBackendSnafu {
+        config: store_config.clone(),
+    })?;
+
+    create_object_store_with_cache(ObjectStore::new(accessor).finish(), store_config).await
+}
+
+async fn create_object_store_with_cache(
     object_store: ObjectStore,
     store_config: &ObjectStoreConfig,
 ) -> Result<ObjectStore> {
@@ -364,6 +849,13 @@ fn create_object_store_with_cache(
                 .unwrap_or(DEFAULT_OBJECT_STORE_CACHE_SIZE);
             (path, capacity)
         }
+        ObjectStoreConfig::Azblob(azblob_config) => {
+            let path = azblob_config.cache_path.as_ref();
+            let capacity = azblob_config
+                .cache_capacity
+                .unwrap_or(DEFAULT_OBJECT_STORE_CACHE_SIZE);
+            (path, capacity)
+        }
         _ => (None, ReadableSize(0)),
     };
 
@@ -375,7 +867,11 @@ fn create_object_store_with_cache(
                 .with_context(|_| error::InitBackendSnafu {
                     config: store_config.clone(),
                 })?;
-        let cache_layer = LruCacheLayer::new(Arc::new(cache_store), cache_capacity.0 as usize);
+        let cache_layer = LruCacheLayer::new(Arc::new(cache_store), cache_capacity.0)
+            .await
+            .with_context(|_| error::InitBackendSnafu {
+                config: store_config.clone(),
+            })?;
         Ok(object_store.layer(cache_layer))
     } else {
         Ok(object_store)
@@ -388,18 +884,22 @@ pub(crate) async fn new_s3_object_store(store_config: &ObjectStoreConfig) -> Res
         _ => unreachable!(),
     };
 
-    let root = util::normalize_dir(&s3_config.root);
+    let root = util::normalize_root(&s3_config.root);
     info!(
         "The s3 storage bucket is: {}, root is: {}",
         s3_config.bucket, &root
     );
 
     let mut builder = S3Builder::default();
-    let mut builder = builder
-        .root(&root)
-        .bucket(&s3_config.bucket)
-        .access_key_id(&s3_config.access_key_id)
-        .secret_access_key(&s3_config.secret_access_key);
+    let mut builder = builder.root(&root).bucket(&s3_config.bucket);
+    if s3_config.anonymous {
+        // Sends unsigned requests instead of loading credentials from the environment.
+        builder = builder.disable_credential_loader();
+    } else {
+        builder = builder
+            .access_key_id(&s3_config.access_key_id)
+            .secret_access_key(&s3_config.secret_access_key);
+    }
 
     if s3_config.endpoint.is_some() {
         builder = builder.endpoint(s3_config.endpoint.as_ref().unwrap());
@@ -412,7 +912,7 @@ pub(crate) async fn new_s3_object_store(store_config: &ObjectStoreConfig) -> Res
         config: store_config.clone(),
     })?;
 
-    create_object_store_with_cache(ObjectStore::new(accessor).finish(), store_config)
+    create_object_store_with_cache(ObjectStore::new(accessor).finish(), store_config).await
 }
 
 pub(crate) async fn new_fs_object_store(store_config: &ObjectStoreConfig) -> Result<ObjectStore> {
@@ -468,18 +968,28 @@ async fn new_metasrv_client(node_id: u64, meta_config: &MetaClientOptions) -> Re
 }
 
 pub(crate) async fn create_log_store(wal_config: &WalConfig) -> Result<RaftEngineLogStore> {
-    // create WAL directory
-    fs::create_dir_all(path::Path::new(&wal_config.dir)).context(error::CreateDirSnafu {
-        dir: &wal_config.dir,
-    })?;
+    let dirs = wal_config.dir.dirs();
+    ensure!(
+        !dirs.is_empty(),
+        error::InvalidWalConfigSnafu {
+            msg: "at least one WAL directory is required",
+        }
+    );
+    // create WAL directories
+    for dir in dirs {
+        fs::create_dir_all(path::Path::new(dir)).context(error::CreateDirSnafu { dir })?;
+    }
     info!("Creating logstore with config: {:?}", wal_config);
     let log_config = LogConfig {
         file_size: wal_config.file_size.0,
-        log_file_dir: wal_config.dir.clone(),
+        log_file_dir: dirs[0].clone(),
+        extra_log_file_dirs: dirs[1..].to_vec(),
         purge_interval: wal_config.purge_interval,
         purge_threshold: wal_config.purge_threshold.0,
+        max_concurrent_purges: wal_config.max_concurrent_purges,
         read_batch_size: wal_config.read_batch_size,
         sync_write: wal_config.sync_write,
+        recovery_mode: wal_config.recovery_mode,
     };
 
     let logstore = RaftEngineLogStore::try_new(log_config)
@@ -500,7 +1010,7 @@ pub(crate) async fn create_procedure_manager(
         procedure_config
     );
 
-    let object_store = new_object_store(&procedure_config.store).await?;
+    let object_store = new_object_store(&procedure_config.store, &RetryOptions::default()).await?;
     let manager_config = ManagerConfig {
         object_store,
         max_retry_times: procedure_config.max_retry_times,
@@ -87,6 +87,7 @@ impl Instance {
                     start: promql.start,
                     end: promql.end,
                     step: promql.step,
+                    lookback: None,
                 };
                 self.execute_promql(&prom_query, ctx).await
             }
@@ -126,7 +127,7 @@ impl Instance {
             DdlExpr::CreateTable(expr) => self.handle_create(expr).await,
             DdlExpr::Alter(expr) => self.handle_alter(expr).await,
             DdlExpr::CreateDatabase(expr) => self.handle_create_database(expr, query_ctx).await,
-            DdlExpr::DropTable(expr) => self.handle_drop_table(expr).await,
+            DdlExpr::DropTable(expr) => self.handle_drop_table(expr, query_ctx).await,
             DdlExpr::FlushTable(expr) => self.handle_flush_table(expr).await,
         }
     }
@@ -137,6 +138,7 @@ impl GrpcQueryHandler for Instance {
     type Error = error::Error;
 
     async fn do_query(&self, request: GrpcRequest, ctx: QueryContextRef) -> Result<Output> {
+        self.ensure_ready()?;
         match request {
             GrpcRequest::Insert(request) => self.handle_insert(request, ctx).await,
             GrpcRequest::Query(query_request) => {
@@ -159,6 +161,7 @@ mod test {
         alter_expr, AddColumn, AddColumns, AlterExpr, Column, ColumnDataType, ColumnDef,
         CreateDatabaseExpr, CreateTableExpr, QueryRequest,
     };
+    use common_error::prelude::{ErrorExt, StatusCode};
     use common_recordbatch::RecordBatches;
     use datatypes::prelude::*;
     use query::parser::QueryLanguageParser;
@@ -367,4 +370,29 @@ mod test {
         let actual = recordbatch.pretty_print().unwrap();
         assert_eq!(actual, expected);
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_reject_query_before_ready() {
+        let (opts, _guard) = test_util::create_tmp_dir_and_datanode_opts("test_not_ready");
+        let instance = Instance::with_mock_meta_client(&opts).await.unwrap();
+        assert!(!instance.is_ready());
+
+        let query = || {
+            GrpcRequest::Query(QueryRequest {
+                query: Some(Query::Sql("SELECT 1".to_string())),
+            })
+        };
+        let err = instance
+            .do_query(query(), QueryContext::arc())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, error::Error::NotReady { .. }));
+        assert_eq!(StatusCode::StorageUnavailable, err.status_code());
+        assert!(err.status_code().is_retryable());
+
+        instance.start().await.unwrap();
+        assert!(instance.is_ready());
+        let output = instance.do_query(query(), QueryContext::arc()).await.unwrap();
+        assert!(matches!(output, Output::Stream(_)));
+    }
 }
@@ -0,0 +1,218 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use api::v1::alter_expr::Kind;
+use api::v1::{AlterExpr, InsertRequest};
+use async_trait::async_trait;
+use common_error::prelude::BoxedError;
+use common_telemetry::info;
+use servers::influxdb::InfluxdbRequest;
+use servers::query_handler::InfluxdbLineProtocolHandler;
+use session::context::QueryContextRef;
+use snafu::ResultExt;
+
+use crate::error::{self, Result};
+use crate::instance::Instance;
+
+#[async_trait]
+impl InfluxdbLineProtocolHandler for Instance {
+    async fn exec(
+        &self,
+        request: &InfluxdbRequest,
+        ctx: QueryContextRef,
+    ) -> servers::error::Result<()> {
+        let requests: Vec<InsertRequest> = request.try_into()?;
+        self.handle_inserts_on_demand(requests, ctx)
+            .await
+            .map_err(BoxedError::new)
+            .context(servers::error::ExecuteGrpcQuerySnafu)
+    }
+}
+
+impl Instance {
+    /// Inserts the requests, tables that don't exist and columns not in the tables are
+    /// created from the data like what the frontend does.
+    ///
+    /// Tables can only be created in standalone mode, where table ids are allocated by
+    /// the datanode.
+    pub(crate) async fn handle_inserts_on_demand(
+        &self,
+        requests: Vec<InsertRequest>,
+        ctx: QueryContextRef,
+    ) -> Result<()> {
+        self.ensure_ready()?;
+        for request in requests {
+            self.create_or_alter_table_on_demand(&request, &ctx).await?;
+            self.handle_insert(request, ctx.clone()).await?;
+        }
+        Ok(())
+    }
+
+    async fn create_or_alter_table_on_demand(
+        &self,
+        request: &InsertRequest,
+        ctx: &QueryContextRef,
+    ) -> Result<()> {
+        let catalog_name = ctx.current_catalog();
+        let schema_name = ctx.current_schema();
+        let table_name = &request.table_name;
+
+        let table = self
+            .catalog_manager
+            .table(&catalog_name, &schema_name, table_name)
+            .await
+            .context(error::CatalogSnafu)?;
+        match table {
+            None => {
+                let expr = common_grpc_expr::build_create_expr_from_insertion(
+                    &catalog_name,
+                    &schema_name,
+                    None,
+                    table_name,
+                    &request.columns,
+                )
+                .context(error::BuildCreateExprOnInsertionSnafu)?;
+                info!(
+                    "Table {}.{}.{} does not exist, create it on insertion",
+                    catalog_name, schema_name, table_name
+                );
+                self.handle_create(expr).await?;
+            }
+            Some(table) => {
+                if let Some(add_columns) =
+                    common_grpc_expr::find_new_columns(&table.schema(), &request.columns)
+                        .context(error::FindNewColumnsOnInsertionSnafu)?
+                {
+                    info!(
+                        "Find new columns {:?} on insertion, alter table {}.{}.{}",
+                        add_columns, catalog_name, schema_name, table_name
+                    );
+                    let expr = AlterExpr {
+                        catalog_name,
+                        schema_name,
+                        table_name: table_name.clone(),
+                        kind: Some(Kind::AddColumns(add_columns)),
+                    };
+                    self.handle_alter(expr).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use common_grpc::writer::Precision;
+    use session::context::QueryContext;
+
+    use super::*;
+    use crate::tests::test_util::{check_output_stream, MockInstance};
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_put_influxdb_lines() {
+        let instance = MockInstance::new("test_put_influxdb_lines").await;
+
+        let lines = r"
+monitor1,host=host1 cpu=66.6,memory=1024 1663840496100023100
+monitor1,host=host2 memory=1027 1663840496400340001";
+        let request = InfluxdbRequest {
+            precision: None,
+            lines: lines.to_string(),
+        };
+        instance
+            .inner()
+            .exec(&request, QueryContext::arc())
+            .await
+            .unwrap();
+
+        // New fields are added to the table.
+        let request = InfluxdbRequest {
+            precision: None,
+            lines: "monitor1,host=host3 memory=1028,disk=0.5 1663840496500000000".to_string(),
+        };
+        instance
+            .inner()
+            .exec(&request, QueryContext::arc())
+            .await
+            .unwrap();
+
+        let output = instance
+            .execute_sql("SELECT ts, host, cpu, memory, disk FROM monitor1 ORDER BY ts")
+            .await;
+        let expected = "\
++-------------------------+-------+------+--------+------+
+| ts                      | host  | cpu  | memory | disk |
++-------------------------+-------+------+--------+------+
+| 2022-09-22T09:54:56.100 | host1 | 66.6 | 1024.0 |      |
+| 2022-09-22T09:54:56.400 | host2 |      | 1027.0 |      |
+| 2022-09-22T09:54:56.500 | host3 |      | 1028.0 | 0.5  |
++-------------------------+-------+------+--------+------+";
+        check_output_stream(output, expected.to_string()).await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_put_influxdb_lines_with_precision() {
+        let instance = MockInstance::new("test_put_influxdb_lines_with_precision").await;
+
+        for (precision, ts) in [
+            (Precision::Second, 1663840496),
+            (Precision::Millisecond, 1663840497000),
+            (Precision::Microsecond, 1663840498000000),
+        ] {
+            let request = InfluxdbRequest {
+                precision: Some(precision),
+                lines: format!("monitor2,host=host1 cpu=1.0 {ts}"),
+            };
+            instance
+                .inner()
+                .exec(&request, QueryContext::arc())
+                .await
+                .unwrap();
+        }
+
+        let output = instance
+            .execute_sql("SELECT ts, host, cpu FROM monitor2 ORDER BY ts")
+            .await;
+        let expected = "\
++---------------------+-------+-----+
+| ts                  | host  | cpu |
++---------------------+-------+-----+
+| 2022-09-22T09:54:56 | host1 | 1.0 |
+| 2022-09-22T09:54:57 | host1 | 1.0 |
+| 2022-09-22T09:54:58 | host1 | 1.0 |
++---------------------+-------+-----+";
+        check_output_stream(output, expected.to_string()).await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_put_malformed_influxdb_lines() {
+        let instance = MockInstance::new("test_put_malformed_influxdb_lines").await;
+
+        let request = InfluxdbRequest {
+            precision: None,
+            lines: "monitor3,   host=host1 cpu=1.2 1663840496100023100".to_string(),
+        };
+        let result = instance.inner().exec(&request, QueryContext::arc()).await;
+        assert!(result.is_err());
+        // Nothing is written.
+        assert!(instance
+            .inner()
+            .catalog_manager
+            .table("greptime", "public", "monitor3")
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
@@ -0,0 +1,144 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use async_trait::async_trait;
+use common_error::prelude::BoxedError;
+use servers::opentsdb::codec::DataPoint;
+use servers::query_handler::OpentsdbProtocolHandler;
+use session::context::QueryContextRef;
+use snafu::ResultExt;
+
+use crate::instance::Instance;
+
+/// Data points are written to the tables named after their metrics, which are created on
+/// demand like what the frontend does.
+#[async_trait]
+impl OpentsdbProtocolHandler for Instance {
+    async fn exec(
+        &self,
+        data_point: &DataPoint,
+        ctx: QueryContextRef,
+    ) -> servers::error::Result<()> {
+        let request = data_point.as_grpc_insert();
+        self.handle_inserts_on_demand(vec![request], ctx)
+            .await
+            .map_err(BoxedError::new)
+            .with_context(|_| servers::error::ExecuteQuerySnafu {
+                query: format!("{data_point:?}"),
+            })
+    }
+
+    async fn exec_batch(
+        &self,
+        data_points: &[DataPoint],
+        ctx: QueryContextRef,
+    ) -> servers::error::Result<()> {
+        let requests = DataPoint::to_grpc_inserts(data_points)?;
+        self.handle_inserts_on_demand(requests, ctx)
+            .await
+            .map_err(BoxedError::new)
+            .context(servers::error::ExecuteGrpcQuerySnafu)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use session::context::QueryContext;
+
+    use super::*;
+    use crate::tests::test_util::{check_output_stream, MockInstance};
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_put_opentsdb_data_point() {
+        let instance = MockInstance::new("test_put_opentsdb_data_point").await;
+
+        let data_point = DataPoint::new(
+            "metric1".to_string(),
+            1000,
+            1.0,
+            vec![("host".to_string(), "host1".to_string())],
+        );
+        instance
+            .inner()
+            .exec(&data_point, QueryContext::arc())
+            .await
+            .unwrap();
+
+        // New tags are added to the table.
+        let data_point = DataPoint::new(
+            "metric1".to_string(),
+            2000,
+            2.0,
+            vec![
+                ("host".to_string(), "host2".to_string()),
+                ("dc".to_string(), "dc1".to_string()),
+            ],
+        );
+        instance
+            .inner()
+            .exec(&data_point, QueryContext::arc())
+            .await
+            .unwrap();
+
+        let output = instance
+            .execute_sql("SELECT * FROM metric1 ORDER BY greptime_timestamp")
+            .await;
+        let expected = "\
++---------------------+----------------+-------+-----+
+| greptime_timestamp  | greptime_value | host  | dc  |
++---------------------+----------------+-------+-----+
+| 1970-01-01T00:00:01 | 1.0            | host1 |     |
+| 1970-01-01T00:00:02 | 2.0            | host2 | dc1 |
++---------------------+----------------+-------+-----+";
+        check_output_stream(output, expected.to_string()).await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_put_opentsdb_data_points() {
+        let instance = MockInstance::new("test_put_opentsdb_data_points").await;
+
+        let tags = vec![("host".to_string(), "host1".to_string())];
+        let data_points = vec![
+            DataPoint::new("metric2".to_string(), 1000, 1.0, tags.clone()),
+            DataPoint::new("metric3".to_string(), 1000, 10.0, tags.clone()),
+            DataPoint::new("metric2".to_string(), 2000, 2.0, tags),
+        ];
+        instance
+            .inner()
+            .exec_batch(&data_points, QueryContext::arc())
+            .await
+            .unwrap();
+
+        let output = instance
+            .execute_sql("SELECT * FROM metric2 ORDER BY greptime_timestamp")
+            .await;
+        let expected = "\
++---------------------+----------------+-------+
+| greptime_timestamp  | greptime_value | host  |
++---------------------+----------------+-------+
+| 1970-01-01T00:00:01 | 1.0            | host1 |
+| 1970-01-01T00:00:02 | 2.0            | host1 |
++---------------------+----------------+-------+";
+        check_output_stream(output, expected.to_string()).await;
+
+        let output = instance.execute_sql("SELECT * FROM metric3").await;
+        let expected = "\
++---------------------+----------------+-------+
+| greptime_timestamp  | greptime_value | host  |
++---------------------+----------------+-------+
+| 1970-01-01T00:00:01 | 10.0           | host1 |
++---------------------+----------------+-------+";
+        check_output_stream(output, expected.to_string()).await;
+    }
+}
@@ -131,10 +131,21 @@ impl Instance {
             QueryStatement::Sql(Statement::DropTable(drop_table)) => {
                 let (catalog_name, schema_name, table_name) =
                     table_idents_to_full_name(drop_table.table_name(), query_ctx.clone())?;
+                if drop_table.drop_if_exists()
+                    && self
+                        .catalog_manager
+                        .table(&catalog_name, &schema_name, &table_name)
+                        .await
+                        .context(error::CatalogSnafu)?
+                        .is_none()
+                {
+                    return Ok(Output::AffectedRows(0));
+                }
                 let req = DropTableRequest {
                     catalog_name,
                     schema_name,
                     table_name,
+                    sync: drop_table.sync() || query_ctx.variables().drop_table_sync,
                 };
                 self.sql_handler
                     .execute(SqlRequest::DropTable(req), query_ctx)
@@ -155,8 +166,10 @@ impl Instance {
                     .execute(SqlRequest::DescribeTable(describe_table), query_ctx)
                     .await
             }
-            QueryStatement::Sql(Statement::ShowCreateTable(_show_create_table)) => {
-                unimplemented!("SHOW CREATE TABLE is unimplemented yet");
+            QueryStatement::Sql(Statement::ShowCreateTable(show_create_table)) => {
+                self.sql_handler
+                    .execute(SqlRequest::ShowCreateTable(show_create_table), query_ctx)
+                    .await
             }
             QueryStatement::Sql(Statement::Copy(copy_table)) => {
                 let req = match copy_table {
@@ -164,6 +177,7 @@ impl Instance {
                         let CopyTableArgument {
                             location,
                             connection,
+                            with,
                             pattern,
                             table_name,
                             ..
@@ -176,6 +190,7 @@ impl Instance {
                             table_name,
                             location,
                             connection,
+                            with,
                             pattern,
                             direction: CopyDirection::Export,
                         }
@@ -184,6 +199,7 @@ impl Instance {
                         let CopyTableArgument {
                             location,
                             connection,
+                            with,
                             pattern,
                             table_name,
                             ..
@@ -196,6 +212,7 @@ impl Instance {
                             table_name,
                             location,
                             connection,
+                            with,
                             pattern,
                             direction: CopyDirection::Import,
                         }
@@ -210,6 +227,9 @@ impl Instance {
             | QueryStatement::Sql(Statement::Explain(_))
             | QueryStatement::Sql(Statement::Use(_))
             | QueryStatement::Sql(Statement::Tql(_))
+            | QueryStatement::Sql(Statement::SetVariables(_))
+            | QueryStatement::Sql(Statement::ShowProcesslist(_))
+            | QueryStatement::Sql(Statement::Kill(_))
             | QueryStatement::Promql(_) => unreachable!(),
         }
     }
@@ -247,6 +267,7 @@ impl Instance {
             start: "0".to_string(),
             end: "0".to_string(),
             step: "5m".to_string(),
+            lookback: None,
         };
         let mut stmt = QueryLanguageParser::parse_promql(&query).context(ExecuteSqlSnafu)?;
         match &mut stmt {
@@ -307,6 +328,9 @@ impl StatementHandler for Instance {
         stmt: QueryStatement,
         query_ctx: QueryContextRef,
     ) -> query::error::Result<Output> {
+        self.ensure_ready()
+            .map_err(BoxedError::new)
+            .context(QueryExecutionSnafu)?;
         self.execute_stmt(stmt, query_ctx)
             .await
             .map_err(BoxedError::new)
@@ -21,6 +21,8 @@ mod heartbeat;
 pub mod instance;
 pub mod metric;
 mod mock;
+pub mod region_open;
+pub mod reload;
 mod script;
 pub mod server;
 pub mod sql;
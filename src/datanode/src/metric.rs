@@ -18,3 +18,6 @@ pub const METRIC_HANDLE_SQL_ELAPSED: &str = "datanode.handle_sql_elapsed";
 pub const METRIC_HANDLE_SCRIPTS_ELAPSED: &str = "datanode.handle_scripts_elapsed";
 pub const METRIC_RUN_SCRIPT_ELAPSED: &str = "datanode.run_script_elapsed";
 pub const METRIC_HANDLE_PROMQL_ELAPSED: &str = "datanode.handle_promql_elapsed";
+pub const METRIC_COPY_FROM_IGNORED_COLUMNS: &str = "datanode.copy_from.ignored_columns";
+pub const METRIC_REGION_OPEN_QUEUE_DEPTH: &str = "datanode.region_open.queue_depth";
+pub const METRIC_REGION_OPEN_ELAPSED: &str = "datanode.region_open.elapsed";
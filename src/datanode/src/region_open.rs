@@ -0,0 +1,107 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use metrics::{gauge, histogram};
+use tokio::sync::Semaphore;
+
+use crate::metric::{METRIC_REGION_OPEN_ELAPSED, METRIC_REGION_OPEN_QUEUE_DEPTH};
+
+/// Limits the number of tables opened concurrently on request of other nodes, e.g. when
+/// the regions of a failed datanode are moved here, so a burst of opens doesn't starve the
+/// live queries of this datanode. The opens on startup are limited by the table engine's
+/// `region_open_parallelism` instead.
+pub struct RegionOpenLimiter {
+    permits: Semaphore,
+    /// Number of opens waiting for a permit.
+    queued: AtomicUsize,
+}
+
+impl RegionOpenLimiter {
+    pub fn new(max_concurrent_opens: usize) -> Self {
+        Self {
+            permits: Semaphore::new(max_concurrent_opens.max(1)),
+            queued: AtomicUsize::new(0),
+        }
+    }
+
+    /// Runs the `open` once a permit is acquired, the opens beyond the limit are queued.
+    pub async fn run<F: Future>(&self, open: F) -> F::Output {
+        let queued = self.queued.fetch_add(1, Ordering::Relaxed) + 1;
+        gauge!(METRIC_REGION_OPEN_QUEUE_DEPTH, queued as f64);
+        // The semaphore is never closed.
+        let _permit = self.permits.acquire().await.unwrap();
+        let queued = self.queued.fetch_sub(1, Ordering::Relaxed) - 1;
+        gauge!(METRIC_REGION_OPEN_QUEUE_DEPTH, queued as f64);
+
+        let start = Instant::now();
+        let output = open.await;
+        histogram!(METRIC_REGION_OPEN_ELAPSED, start.elapsed());
+        output
+    }
+
+    /// Returns the number of opens waiting for a permit.
+    pub fn queue_depth(&self) -> usize {
+        self.queued.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_region_open_limiter() {
+        let limiter = Arc::new(RegionOpenLimiter::new(2));
+        let running = Arc::new(AtomicUsize::new(0));
+        let max_running = Arc::new(AtomicUsize::new(0));
+
+        let handles = (0..8)
+            .map(|_| {
+                let limiter = limiter.clone();
+                let running = running.clone();
+                let max_running = max_running.clone();
+                tokio::spawn(async move {
+                    limiter
+                        .run(async {
+                            let n = running.fetch_add(1, Ordering::SeqCst) + 1;
+                            let _ = max_running.fetch_max(n, Ordering::SeqCst);
+                            tokio::time::sleep(Duration::from_millis(20)).await;
+                            let _ = running.fetch_sub(1, Ordering::SeqCst);
+                        })
+                        .await
+                })
+            })
+            .collect::<Vec<_>>();
+        for handle in handles {
+            handle.await.unwrap();
+        }
+
+        assert_eq!(2, max_running.load(Ordering::SeqCst));
+        assert_eq!(0, limiter.queue_depth());
+    }
+
+    #[tokio::test]
+    async fn test_region_open_limiter_zero() {
+        // A limit of 0 still allows one open at a time.
+        let limiter = RegionOpenLimiter::new(0);
+        assert_eq!(42, limiter.run(async { 42 }).await);
+    }
+}
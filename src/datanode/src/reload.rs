@@ -0,0 +1,164 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Reloads datanode options at runtime.
+
+use std::sync::Mutex;
+
+use common_telemetry::{info, warn};
+use serde_json::Value;
+use snafu::{ensure, ResultExt};
+
+use crate::datanode::DatanodeOptions;
+use crate::error::{self, Result};
+use crate::instance::InstanceRef;
+
+/// Options that take effect without restarting the datanode.
+const RELOADABLE_OPTIONS: &[&str] = &["compaction.max_inflight_tasks"];
+
+/// Result of a reload.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ReloadReport {
+    /// Changed options that are applied.
+    pub applied: Vec<String>,
+    /// Changed options that only take effect after restart.
+    pub ignored: Vec<String>,
+}
+
+/// Applies the subset of options that are safe to change at runtime.
+pub struct ConfigReloader {
+    instance: InstanceRef,
+    /// Options the datanode is running with.
+    opts: Mutex<DatanodeOptions>,
+}
+
+impl ConfigReloader {
+    pub fn new(instance: InstanceRef, opts: DatanodeOptions) -> ConfigReloader {
+        ConfigReloader {
+            instance,
+            opts: Mutex::new(opts),
+        }
+    }
+
+    /// Applies reloadable options in `new_opts`, other changed options are reported as
+    /// ignored. Rejects `new_opts` without changing anything if it's invalid.
+    pub fn reload(&self, new_opts: DatanodeOptions) -> Result<ReloadReport> {
+        let max_inflight_tasks = new_opts.compaction.max_inflight_tasks;
+        ensure!(
+            max_inflight_tasks > 0,
+            error::InvalidCompactionConfigSnafu {
+                msg: "max_inflight_tasks should be greater than 0",
+            }
+        );
+
+        let mut opts = self.opts.lock().unwrap();
+        let mut changed = Vec::new();
+        diff_options("", &to_value(&opts)?, &to_value(&new_opts)?, &mut changed);
+        let (applied, ignored): (Vec<_>, Vec<_>) = changed
+            .into_iter()
+            .partition(|option| RELOADABLE_OPTIONS.contains(&option.as_str()));
+
+        if !applied.is_empty() {
+            self.instance
+                .compaction_scheduler
+                .set_max_inflight_tasks(max_inflight_tasks);
+            opts.compaction.max_inflight_tasks = max_inflight_tasks;
+        }
+
+        let report = ReloadReport { applied, ignored };
+        info!("Reloaded datanode options, applied: {:?}", report.applied);
+        if !report.ignored.is_empty() {
+            warn!(
+                "Changed options {:?} only take effect after restart",
+                report.ignored
+            );
+        }
+        Ok(report)
+    }
+}
+
+fn to_value(opts: &DatanodeOptions) -> Result<Value> {
+    serde_json::to_value(opts).context(error::SerializeOptionsSnafu)
+}
+
+/// Collects dotted paths of leaf options that differ between `old` and `new`.
+fn diff_options(prefix: &str, old: &Value, new: &Value, changed: &mut Vec<String>) {
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            let mut keys: Vec<_> = old.keys().chain(new.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{prefix}.{key}")
+                };
+                match (old.get(key), new.get(key)) {
+                    (Some(old), Some(new)) => diff_options(&path, old, new, changed),
+                    _ => changed.push(path),
+                }
+            }
+        }
+        (old, new) if old != new => changed.push(prefix.to_string()),
+        _ => (),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::instance::Instance;
+    use crate::tests::test_util::create_tmp_dir_and_datanode_opts;
+
+    #[tokio::test]
+    async fn test_reload_compaction_limit() {
+        let (opts, _guard) = create_tmp_dir_and_datanode_opts("reload_compaction_limit");
+        let instance = Arc::new(Instance::new(&opts).await.unwrap());
+        let reloader = ConfigReloader::new(instance.clone(), opts.clone());
+        assert_eq!(
+            opts.compaction.max_inflight_tasks,
+            instance.compaction_scheduler.max_inflight_tasks()
+        );
+
+        let mut new_opts = opts.clone();
+        new_opts.compaction.max_inflight_tasks = 16;
+        new_opts.compaction.max_files_in_level0 = 32;
+        new_opts.rpc_addr = "127.0.0.1:5001".to_string();
+        let report = reloader.reload(new_opts.clone()).unwrap();
+        assert_eq!(
+            ReloadReport {
+                applied: vec!["compaction.max_inflight_tasks".to_string()],
+                ignored: vec![
+                    "compaction.max_files_in_level0".to_string(),
+                    "rpc_addr".to_string()
+                ],
+            },
+            report
+        );
+        assert_eq!(16, instance.compaction_scheduler.max_inflight_tasks());
+
+        // Restart-only options are still reported as changed.
+        let report = reloader.reload(new_opts.clone()).unwrap();
+        assert!(report.applied.is_empty());
+        assert_eq!(2, report.ignored.len());
+
+        // Invalid options are rejected.
+        new_opts.compaction.max_inflight_tasks = 0;
+        assert!(reloader.reload(new_opts).is_err());
+        assert_eq!(16, instance.compaction_scheduler.max_inflight_tasks());
+    }
+}
@@ -16,8 +16,12 @@ use std::default::Default;
 use std::net::SocketAddr;
 use std::sync::Arc;
 
+use common_base::Plugins;
 use common_runtime::Builder as RuntimeBuilder;
+use servers::auth::UserProviderRef;
 use servers::grpc::GrpcServer;
+use servers::influxdb::server::InfluxdbServer;
+use servers::opentsdb::http_server::OpentsdbHttpServer;
 use servers::query_handler::grpc::ServerGrpcQueryHandlerAdaptor;
 use servers::server::Server;
 use snafu::ResultExt;
@@ -33,10 +37,16 @@ pub mod grpc;
 /// All rpc services.
 pub struct Services {
     grpc_server: GrpcServer,
+    influxdb_server: Option<(Box<InfluxdbServer>, SocketAddr)>,
+    opentsdb_server: Option<(Box<OpentsdbHttpServer>, SocketAddr)>,
 }
 
 impl Services {
-    pub async fn try_new(instance: InstanceRef, opts: &DatanodeOptions) -> Result<Self> {
+    pub async fn try_new(
+        instance: InstanceRef,
+        opts: &DatanodeOptions,
+        plugins: Arc<Plugins>,
+    ) -> Result<Self> {
         let grpc_runtime = Arc::new(
             RuntimeBuilder::default()
                 .worker_threads(opts.rpc_runtime_size)
@@ -45,12 +55,42 @@ impl Services {
                 .context(RuntimeResourceSnafu)?,
         );
 
+        let user_provider = plugins.get::<UserProviderRef>();
+        let influxdb_server = match &opts.influxdb_addr {
+            Some(addr) => {
+                let addr: SocketAddr = addr.parse().context(ParseAddrSnafu { addr })?;
+                let mut server = InfluxdbServer::create_server(instance.clone());
+                if let Some(user_provider) = user_provider {
+                    server.set_user_provider(user_provider.clone());
+                }
+                Some((server, addr))
+            }
+            None => None,
+        };
+
+        let opentsdb_server = match &opts.opentsdb_http_addr {
+            Some(addr) => {
+                let addr: SocketAddr = addr.parse().context(ParseAddrSnafu { addr })?;
+                let mut server = OpentsdbHttpServer::create_server(instance.clone());
+                if let Some(user_provider) = user_provider {
+                    server.set_user_provider(user_provider.clone());
+                }
+                Some((server, addr))
+            }
+            None => None,
+        };
+
+        let mut grpc_server = GrpcServer::new(
+            ServerGrpcQueryHandlerAdaptor::arc(instance),
+            None,
+            grpc_runtime,
+        );
+        grpc_server.set_compression(opts.grpc_compression);
+
         Ok(Self {
-            grpc_server: GrpcServer::new(
-                ServerGrpcQueryHandlerAdaptor::arc(instance),
-                None,
-                grpc_runtime,
-            ),
+            grpc_server,
+            influxdb_server,
+            opentsdb_server,
         })
     }
 
@@ -58,8 +98,14 @@ impl Services {
         let grpc_addr: SocketAddr = opts.rpc_addr.parse().context(ParseAddrSnafu {
             addr: &opts.rpc_addr,
         })?;
-        self.grpc_server
-            .start(grpc_addr)
+        let mut servers = vec![self.grpc_server.start(grpc_addr)];
+        if let Some((influxdb_server, influxdb_addr)) = &self.influxdb_server {
+            servers.push(influxdb_server.start(*influxdb_addr));
+        }
+        if let Some((opentsdb_server, opentsdb_addr)) = &self.opentsdb_server {
+            servers.push(opentsdb_server.start(*opentsdb_addr));
+        }
+        let _ = futures::future::try_join_all(servers)
             .await
             .context(StartServerSnafu)?;
         Ok(())
@@ -69,6 +115,19 @@ impl Services {
         self.grpc_server
             .shutdown()
             .await
-            .context(ShutdownServerSnafu)
+            .context(ShutdownServerSnafu)?;
+        if let Some((influxdb_server, _)) = &self.influxdb_server {
+            influxdb_server
+                .shutdown()
+                .await
+                .context(ShutdownServerSnafu)?;
+        }
+        if let Some((opentsdb_server, _)) = &self.opentsdb_server {
+            opentsdb_server
+                .shutdown()
+                .await
+                .context(ShutdownServerSnafu)?;
+        }
+        Ok(())
     }
 }
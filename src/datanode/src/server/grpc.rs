@@ -16,7 +16,7 @@ use api::v1::{AlterExpr, CreateTableExpr, DropTableExpr, FlushTableExpr};
 use common_grpc_expr::{alter_expr_to_request, create_expr_to_request};
 use common_query::Output;
 use common_telemetry::info;
-use session::context::QueryContext;
+use session::context::{QueryContext, QueryContextRef};
 use snafu::prelude::*;
 use table::requests::{DropTableRequest, FlushTableRequest};
 
@@ -72,14 +72,21 @@ impl Instance {
             .await
     }
 
-    pub(crate) async fn handle_drop_table(&self, expr: DropTableExpr) -> Result<Output> {
+    /// Handle gRPC drop table requests, the table is dropped with `SYNC` if the session
+    /// variable `drop_table_sync` of `query_ctx` is on.
+    pub(crate) async fn handle_drop_table(
+        &self,
+        expr: DropTableExpr,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
         let req = DropTableRequest {
             catalog_name: expr.catalog_name,
             schema_name: expr.schema_name,
             table_name: expr.table_name,
+            sync: query_ctx.variables().drop_table_sync,
         };
         self.sql_handler()
-            .execute(SqlRequest::DropTable(req), QueryContext::arc())
+            .execute(SqlRequest::DropTable(req), query_ctx)
             .await
     }
 
@@ -18,12 +18,12 @@ use common_procedure::ProcedureManagerRef;
 use common_query::Output;
 use common_telemetry::error;
 use query::query_engine::QueryEngineRef;
-use query::sql::{describe_table, show_databases, show_tables};
+use query::sql::{describe_table, show_create_table, show_databases, show_tables};
 use session::context::QueryContextRef;
 use snafu::{OptionExt, ResultExt};
 use sql::statements::delete::Delete;
 use sql::statements::describe::DescribeTable;
-use sql::statements::show::{ShowDatabases, ShowTables};
+use sql::statements::show::{ShowCreateTable, ShowDatabases, ShowTables};
 use table::engine::{EngineContext, TableEngineProcedureRef, TableEngineRef, TableReference};
 use table::requests::*;
 use table::TableRef;
@@ -53,6 +53,7 @@ pub enum SqlRequest {
     ShowDatabases(ShowDatabases),
     ShowTables(ShowTables),
     DescribeTable(DescribeTable),
+    ShowCreateTable(ShowCreateTable),
     Delete(Delete),
     CopyTable(CopyTableRequest),
 }
@@ -119,6 +120,19 @@ impl SqlHandler {
                     })?;
                 describe_table(table).context(ExecuteSqlSnafu)
             }
+            SqlRequest::ShowCreateTable(req) => {
+                let (catalog, schema, table) =
+                    table_idents_to_full_name(&req.table_name, query_ctx.clone())?;
+                let table = self
+                    .catalog_manager
+                    .table(&catalog, &schema, &table)
+                    .await
+                    .context(error::CatalogSnafu)?
+                    .with_context(|| TableNotFoundSnafu {
+                        table_name: req.table_name.to_string(),
+                    })?;
+                show_create_table(table, None).context(ExecuteSqlSnafu)
+            }
             SqlRequest::FlushTable(req) => self.flush_table(req).await,
         };
         if let Err(e) = &result {
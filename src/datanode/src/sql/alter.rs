@@ -12,19 +12,37 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+
 use catalog::RenameTableRequest;
+use common_procedure::{watcher, ProcedureManagerRef, ProcedureWithId};
 use common_query::Output;
+use common_telemetry::tracing::info;
 use snafu::prelude::*;
 use sql::statements::alter::{AlterTable, AlterTableOperation};
 use sql::statements::column_def_to_schema;
 use table::engine::{EngineContext, TableReference};
-use table::requests::{AddColumnRequest, AlterKind, AlterTableRequest};
+use table::requests::{
+    is_compaction_option, AddColumnRequest, AlterKind, AlterTableRequest, TableOptions, TTL_KEY,
+};
+use table_procedure::AlterTableProcedure;
 
 use crate::error::{self, Result};
+use crate::sql::create::stmt_options_to_table_options;
 use crate::sql::SqlHandler;
 
 impl SqlHandler {
     pub(crate) async fn alter(&self, req: AlterTableRequest) -> Result<Output> {
+        if let AlterKind::RenameTable { new_table_name } = &req.alter_kind {
+            // The frontend sends the rename again when it resumes an interrupted rename.
+            if self.is_renamed(&req, new_table_name).await? {
+                return Ok(Output::AffectedRows(0));
+            }
+        }
+        if let Some(procedure_manager) = &self.procedure_manager {
+            return self.alter_table_by_procedure(procedure_manager, req).await;
+        }
+
         let ctx = EngineContext {};
         let table_name = req.table_name.clone();
         let table_ref = TableReference {
@@ -41,6 +59,23 @@ impl SqlHandler {
                 table_name: &full_table_name,
             }
         );
+        if let AlterKind::RenameTable { new_table_name } = &req.alter_kind {
+            let new_table = self
+                .catalog_manager
+                .table(&req.catalog_name, &req.schema_name, new_table_name)
+                .await
+                .context(error::CatalogSnafu)?;
+            ensure!(
+                new_table.is_none(),
+                error::TableExistsSnafu {
+                    table_name: TableReference {
+                        table: new_table_name,
+                        ..table_ref
+                    }
+                    .to_string(),
+                }
+            );
+        }
         let is_rename = req.is_rename_table();
         let table =
             self.table_engine
@@ -67,6 +102,50 @@ impl SqlHandler {
         Ok(Output::AffectedRows(0))
     }
 
+    pub(crate) async fn alter_table_by_procedure(
+        &self,
+        procedure_manager: &ProcedureManagerRef,
+        req: AlterTableRequest,
+    ) -> Result<Output> {
+        let table_name = req.table_name.clone();
+        let procedure =
+            AlterTableProcedure::new(req, self.catalog_manager.clone(), self.table_engine.clone());
+        let procedure_with_id = ProcedureWithId::with_random_id(Box::new(procedure));
+        let procedure_id = procedure_with_id.id;
+
+        info!("Alter table {} by procedure {}", table_name, procedure_id);
+
+        let mut watcher = procedure_manager
+            .submit(procedure_with_id)
+            .await
+            .context(error::SubmitProcedureSnafu { procedure_id })?;
+
+        watcher::wait(&mut watcher)
+            .await
+            .context(error::WaitProcedureSnafu { procedure_id })?;
+
+        Ok(Output::AffectedRows(0))
+    }
+
+    /// Returns whether the table of `req` is already renamed to `new_table_name`, i.e. only
+    /// the new name exists.
+    async fn is_renamed(&self, req: &AlterTableRequest, new_table_name: &str) -> Result<bool> {
+        let old_table = self
+            .catalog_manager
+            .table(&req.catalog_name, &req.schema_name, &req.table_name)
+            .await
+            .context(error::CatalogSnafu)?;
+        if old_table.is_some() {
+            return Ok(false);
+        }
+        let new_table = self
+            .catalog_manager
+            .table(&req.catalog_name, &req.schema_name, new_table_name)
+            .await
+            .context(error::CatalogSnafu)?;
+        Ok(new_table.is_some())
+    }
+
     pub(crate) fn alter_to_request(
         &self,
         alter_table: AlterTable,
@@ -93,6 +172,26 @@ impl SqlHandler {
             AlterTableOperation::RenameTable { new_table_name } => AlterKind::RenameTable {
                 new_table_name: new_table_name.clone(),
             },
+            AlterTableOperation::SetTtl { ttl } => {
+                let options = HashMap::from([(TTL_KEY.to_string(), ttl.clone())]);
+                let options = TableOptions::try_from(&options)
+                    .context(error::UnrecognizedTableOptionSnafu)?;
+                AlterKind::SetTtl { ttl: options.ttl }
+            }
+            AlterTableOperation::UnsetTtl => AlterKind::SetTtl { ttl: None },
+            AlterTableOperation::SetTableOptions { options } => {
+                if let Some(option) = options.iter().find(|o| !is_compaction_option(&o.name.value))
+                {
+                    return error::InvalidSqlSnafu {
+                        msg: format!("table option {} could not be altered", option.name),
+                    }
+                    .fail();
+                }
+                let options = stmt_options_to_table_options(options)?;
+                AlterKind::SetCompactionOptions {
+                    options: options.compaction,
+                }
+            }
         };
         Ok(AlterTableRequest {
             catalog_name: table_ref.catalog.to_string(),
@@ -106,6 +205,7 @@ impl SqlHandler {
 #[cfg(test)]
 mod tests {
     use std::assert_matches::assert_matches;
+    use std::time::Duration;
 
     use datatypes::prelude::ConcreteDataType;
     use sql::dialect::GenericDialect;
@@ -178,4 +278,62 @@ mod tests {
             _ => unreachable!(),
         }
     }
+
+    #[tokio::test]
+    async fn test_alter_to_request_with_ttl() {
+        let handler = create_mock_sql_handler().await;
+        let alter_table = parse_sql("ALTER TABLE test_table SET TTL = '30d';");
+        let req = handler
+            .alter_to_request(
+                alter_table,
+                TableReference::full("greptime", "public", "test_table"),
+            )
+            .unwrap();
+        match req.alter_kind {
+            AlterKind::SetTtl { ttl } => {
+                assert_eq!(Some(Duration::from_secs(30 * 24 * 3600)), ttl);
+            }
+            _ => unreachable!(),
+        }
+
+        let alter_table = parse_sql("ALTER TABLE test_table UNSET TTL;");
+        let req = handler
+            .alter_to_request(
+                alter_table,
+                TableReference::full("greptime", "public", "test_table"),
+            )
+            .unwrap();
+        assert_matches!(req.alter_kind, AlterKind::SetTtl { ttl: None });
+
+        let alter_table = parse_sql("ALTER TABLE test_table SET TTL = 'foo';");
+        let result = handler.alter_to_request(
+            alter_table,
+            TableReference::full("greptime", "public", "test_table"),
+        );
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_alter_to_request_with_compaction_options() {
+        let handler = create_mock_sql_handler().await;
+        let table_ref = || TableReference::full("greptime", "public", "test_table");
+        let alter_table =
+            parse_sql("ALTER TABLE test_table SET (compaction_max_files_in_level0 = 4);");
+        let req = handler.alter_to_request(alter_table, table_ref()).unwrap();
+        match req.alter_kind {
+            AlterKind::SetCompactionOptions { options } => {
+                assert_eq!(Some(4), options.max_files_in_l0);
+                assert_eq!(None, options.max_concurrent_outputs);
+            }
+            _ => unreachable!(),
+        }
+
+        let alter_table = parse_sql("ALTER TABLE test_table SET (compaction_max_inflight = 0);");
+        assert!(handler.alter_to_request(alter_table, table_ref()).is_err());
+
+        // Only compaction options could be altered.
+        let alter_table = parse_sql("ALTER TABLE test_table SET (write_buffer_size = '1MB');");
+        let err = handler.alter_to_request(alter_table, table_ref()).unwrap_err();
+        assert!(err.to_string().contains("write_buffer_size"), "{err}");
+    }
 }
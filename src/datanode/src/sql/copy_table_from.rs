@@ -13,26 +13,42 @@
 // limitations under the License.
 
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use async_compat::CompatExt;
+use common_datasource::column_mapping::ColumnMapping;
+use common_datasource::file_format::{
+    case_insensitive_columns, infer_schema, read_batches, schema_infer_max_records, Format,
+};
 use common_datasource::lister::{Lister, Source};
 use common_datasource::object_store::{build_backend, parse_url};
 use common_datasource::util::find_dir_and_filename;
 use common_query::Output;
 use common_recordbatch::error::DataTypesSnafu;
+use common_telemetry::{info, warn};
 use datafusion::parquet::arrow::ParquetRecordBatchStreamBuilder;
 use datatypes::arrow::record_batch::RecordBatch;
 use datatypes::vectors::{Helper, VectorRef};
 use futures_util::TryStreamExt;
+use metrics::counter;
 use regex::Regex;
-use snafu::{ensure, ResultExt};
+use snafu::ResultExt;
 use table::engine::TableReference;
 use table::requests::{CopyTableRequest, InsertRequest};
 use tokio::io::BufReader;
 
 use crate::error::{self, Result};
+use crate::metric::METRIC_COPY_FROM_IGNORED_COLUMNS;
 use crate::sql::SqlHandler;
 
+/// Rows imported from a file and how its columns are mapped to the table.
+struct FileReport {
+    path: String,
+    rows: usize,
+    coerced_columns: usize,
+    ignored_columns: usize,
+}
+
 impl SqlHandler {
     pub(crate) async fn copy_table_from(&self, req: CopyTableRequest) -> Result<Output> {
         let table_ref = TableReference {
@@ -41,6 +57,7 @@ impl SqlHandler {
             table: &req.table_name,
         };
         let table = self.get_table(&table_ref)?;
+        let format = Format::try_from(&req.with).context(error::CopyFormatSnafu)?;
 
         let (_schema, _host, path) = parse_url(&req.location).context(error::ParseUrlSnafu)?;
 
@@ -64,39 +81,104 @@ impl SqlHandler {
 
         let lister = Lister::new(object_store, source, dir, regex);
 
-        let objects = lister.list().await.context(error::ListObjectsSnafu)?;
+        // The lister is not limited, so the result is never truncated.
+        let (objects, _) = lister.list().await.context(error::ListObjectsSnafu)?;
+
+        let table_schema = table.schema().arrow_schema().clone();
+        let case_insensitive =
+            case_insensitive_columns(&req.with).context(error::CopyFormatSnafu)?;
+        let max_records = schema_infer_max_records(&req.with).context(error::CopyFormatSnafu)?;
 
         let mut buf: Vec<RecordBatch> = Vec::new();
+        let mut reports = Vec::with_capacity(objects.len());
 
         for obj in objects.iter() {
-            let reader = obj.reader().await.context(error::ReadObjectSnafu {
-                path: &obj.path().to_string(),
-            })?;
-
-            let buf_reader = BufReader::new(reader.compat());
-
-            let builder = ParquetRecordBatchStreamBuilder::new(buf_reader)
-                .await
-                .context(error::ReadParquetSnafu)?;
+            let path = obj.path().to_string();
+            let (batches, mapping) = if format != Format::Parquet {
+                let data = obj
+                    .read()
+                    .await
+                    .context(error::ReadObjectSnafu { path: &path })?;
+                let file_schema = infer_schema(&format, &data, max_records, &table_schema)
+                    .context(error::CopyFormatSnafu)?;
+                let mapping = ColumnMapping::try_new(
+                    &path,
+                    &file_schema,
+                    table_schema.clone(),
+                    case_insensitive,
+                    true,
+                )
+                .context(error::CopyFormatSnafu)?;
+                let batches = read_batches(&format, Arc::new(file_schema), data)
+                    .context(error::CopyFormatSnafu)?;
+                (batches, mapping)
+            } else {
+                let reader = obj
+                    .reader()
+                    .await
+                    .context(error::ReadObjectSnafu { path: &path })?;
+
+                let buf_reader = BufReader::new(reader.compat());
+
+                let builder = ParquetRecordBatchStreamBuilder::new(buf_reader)
+                    .await
+                    .context(error::ReadParquetSnafu)?;
+
+                let mapping = ColumnMapping::try_new(
+                    &path,
+                    builder.schema(),
+                    table_schema.clone(),
+                    case_insensitive,
+                    false,
+                )
+                .context(error::CopyFormatSnafu)?;
+
+                let stream = builder
+                    .build()
+                    .context(error::BuildParquetRecordBatchStreamSnafu)?;
+
+                let batches = stream
+                    .try_collect::<Vec<_>>()
+                    .await
+                    .context(error::ReadParquetSnafu)?;
+                (batches, mapping)
+            };
+
+            let ignored_columns = mapping.ignored_columns();
+            if !ignored_columns.is_empty() {
+                warn!(
+                    "Ignored columns {:?} of file {} not in table {}",
+                    ignored_columns, path, req.table_name
+                );
+                counter!(
+                    METRIC_COPY_FROM_IGNORED_COLUMNS,
+                    ignored_columns.len() as u64
+                );
+            }
+
+            let mut rows = 0;
+            for batch in batches.iter() {
+                let batch = mapping.map(batch).context(error::CopyFormatSnafu)?;
+                rows += batch.num_rows();
+                buf.push(batch);
+            }
+            reports.push(FileReport {
+                path,
+                rows,
+                coerced_columns: mapping.coerced_columns(),
+                ignored_columns: ignored_columns.len(),
+            });
+        }
 
-            ensure!(
-                builder.schema() == table.schema().arrow_schema(),
-                error::InvalidSchemaSnafu {
-                    table_schema: table.schema().arrow_schema().to_string(),
-                    file_schema: (*(builder.schema())).to_string()
-                }
+        for report in &reports {
+            info!(
+                "Copy {} rows from file {} into table {}, {} columns coerced, {} columns ignored",
+                report.rows,
+                report.path,
+                req.table_name,
+                report.coerced_columns,
+                report.ignored_columns
             );
-
-            let stream = builder
-                .build()
-                .context(error::BuildParquetRecordBatchStreamSnafu)?;
-
-            let chunk = stream
-                .try_collect::<Vec<_>>()
-                .await
-                .context(error::ReadParquetSnafu)?;
-
-            buf.extend(chunk.into_iter());
         }
 
         let fields = table
@@ -14,7 +14,7 @@
 
 use std::pin::Pin;
 
-use common_datasource;
+use common_datasource::file_format::{max_file_size, Format, RollingFileWriter};
 use common_datasource::object_store::{build_backend, parse_url};
 use common_query::physical_plan::SessionContext;
 use common_query::Output;
@@ -40,23 +40,54 @@ impl SqlHandler {
             table: &req.table_name,
         };
         let table = self.get_table(&table_ref)?;
+        let format = Format::try_from(&req.with).context(error::CopyFormatSnafu)?;
 
-        let stream = table
+        let plan = table
             .scan(None, &[], None)
             .await
             .with_context(|_| error::CopyTableSnafu {
                 table_name: table_ref.to_string(),
             })?;
 
-        let stream = stream
-            .execute(0, SessionContext::default().task_ctx())
-            .context(error::TableScanExecSnafu)?;
-        let stream = Box::pin(DfRecordBatchStreamAdapter::new(stream));
-
         let (_schema, _host, path) = parse_url(&req.location).context(error::ParseUrlSnafu)?;
         let object_store =
             build_backend(&req.location, req.connection).context(error::BuildBackendSnafu)?;
 
+        if format != Format::Parquet {
+            let max_file_size = max_file_size(&req.with).context(error::CopyFormatSnafu)?;
+            let mut writer = RollingFileWriter::try_new(
+                object_store,
+                path,
+                format,
+                plan.schema().arrow_schema().clone(),
+                max_file_size,
+            )
+            .context(error::CopyFormatSnafu)?;
+
+            let task_ctx = SessionContext::default().task_ctx();
+            let mut rows = 0;
+            for partition in 0..plan.output_partitioning().partition_count() {
+                let stream = plan
+                    .execute(partition, task_ctx.clone())
+                    .context(error::TableScanExecSnafu)?;
+                let mut stream = DfRecordBatchStreamAdapter::new(stream);
+                while let Some(batch) = stream.try_next().await.context(error::PollStreamSnafu)? {
+                    writer.write(&batch).await.context(error::CopyFormatSnafu)?;
+                    rows += batch.num_rows();
+                }
+                // Each partition is written into its own files.
+                writer.finish_file().await.context(error::CopyFormatSnafu)?;
+            }
+            writer.close().await.context(error::CopyFormatSnafu)?;
+
+            return Ok(Output::AffectedRows(rows));
+        }
+
+        let stream = plan
+            .execute(0, SessionContext::default().task_ctx())
+            .context(error::TableScanExecSnafu)?;
+        let stream = Box::pin(DfRecordBatchStreamAdapter::new(stream));
+
         let mut parquet_writer = ParquetWriter::new(path.to_string(), stream, object_store);
         // TODO(jiachun):
         // For now, COPY is implemented synchronously.
@@ -291,7 +291,7 @@ impl SqlHandler {
     }
 }
 
-fn stmt_options_to_table_options(opts: &[SqlOption]) -> error::Result<TableOptions> {
+pub(crate) fn stmt_options_to_table_options(opts: &[SqlOption]) -> error::Result<TableOptions> {
     let mut map = HashMap::with_capacity(opts.len());
     for SqlOption { name, value } in opts {
         let value_str = match value {
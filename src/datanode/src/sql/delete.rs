@@ -16,6 +16,7 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use common_query::Output;
+use common_time::TimeZone;
 use datatypes::data_type::DataType;
 use datatypes::prelude::VectorRef;
 use datatypes::vectors::StringVector;
@@ -34,6 +35,7 @@ use crate::sql::SqlHandler;
 
 impl SqlHandler {
     pub(crate) async fn delete(&self, query_ctx: QueryContextRef, stmt: Delete) -> Result<Output> {
+        let timezone = query_ctx.time_zone();
         let (catalog_name, schema_name, table_name) =
             table_idents_to_full_name(stmt.table_name(), query_ctx)?;
         let table_ref = TableReference {
@@ -45,7 +47,7 @@ impl SqlHandler {
         let table = self.get_table(&table_ref)?;
 
         let req = DeleteRequest {
-            key_column_values: parse_selection(stmt.selection(), &table)?,
+            key_column_values: parse_selection(stmt.selection(), &table, timezone.as_ref())?,
         };
 
         let affected_rows = table.delete(req).await.with_context(|_| DeleteSnafu {
@@ -61,10 +63,11 @@ impl SqlHandler {
 fn parse_selection(
     selection: &Option<Expr>,
     table: &TableRef,
+    timezone: Option<&TimeZone>,
 ) -> Result<HashMap<String, VectorRef>> {
     let mut key_column_values = HashMap::new();
     if let Some(expr) = selection {
-        parse_expr(expr, &mut key_column_values, table)?;
+        parse_expr(expr, &mut key_column_values, table, timezone)?;
     }
     Ok(key_column_values)
 }
@@ -73,21 +76,22 @@ fn parse_expr(
     expr: &Expr,
     key_column_values: &mut HashMap<String, VectorRef>,
     table: &TableRef,
+    timezone: Option<&TimeZone>,
 ) -> Result<()> {
     // match BinaryOp
     if let Expr::BinaryOp { left, op, right } = expr {
         match (&**left, op, &**right) {
             // match And operator
             (Expr::BinaryOp { .. }, BinaryOperator::And, Expr::BinaryOp { .. }) => {
-                parse_expr(left, key_column_values, table)?;
-                parse_expr(right, key_column_values, table)?;
+                parse_expr(left, key_column_values, table, timezone)?;
+                parse_expr(right, key_column_values, table, timezone)?;
                 return Ok(());
             }
             // match Eq operator
             (Expr::Identifier(column_name), BinaryOperator::Eq, Expr::Value(value)) => {
                 key_column_values.insert(
                     column_name.to_string(),
-                    value_to_vector(&column_name.to_string(), value, table)?,
+                    value_to_vector(&column_name.to_string(), value, table, timezone)?,
                 );
                 return Ok(());
             }
@@ -110,7 +114,12 @@ fn parse_expr(
 }
 
 /// parse value to vector
-fn value_to_vector(column_name: &String, sql_value: &Value, table: &TableRef) -> Result<VectorRef> {
+fn value_to_vector(
+    column_name: &String,
+    sql_value: &Value,
+    table: &TableRef,
+    timezone: Option<&TimeZone>,
+) -> Result<VectorRef> {
     let schema = table.schema();
     let column_schema =
         schema
@@ -120,7 +129,7 @@ fn value_to_vector(column_name: &String, sql_value: &Value, table: &TableRef) ->
                 column_name: column_name.to_string(),
             })?;
     let data_type = &column_schema.data_type;
-    let value = sql_value_to_value(column_name, data_type, sql_value);
+    let value = sql_value_to_value(column_name, data_type, sql_value, timezone);
     match value {
         Ok(value) => {
             let mut vec = data_type.create_mutable_vector(1);
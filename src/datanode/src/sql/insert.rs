@@ -18,6 +18,7 @@ use catalog::CatalogManagerRef;
 use common_catalog::format_full_table_name;
 use common_query::Output;
 use common_recordbatch::RecordBatch;
+use common_time::TimeZone;
 use datafusion_expr::type_coercion::binary::coerce_types;
 use datafusion_expr::Operator;
 use datatypes::data_type::DataType;
@@ -77,6 +78,7 @@ impl SqlHandler {
         table_ref: TableReference,
         table: &TableRef,
         stmt: Insert,
+        timezone: Option<&TimeZone>,
     ) -> Result<SqlRequest> {
         let values = stmt
             .values_body()
@@ -125,7 +127,7 @@ impl SqlHandler {
             );
 
             for (sql_val, (column_schema, builder)) in row.iter().zip(columns_builders.iter_mut()) {
-                add_row_to_vector(column_schema, sql_val, builder)?;
+                add_row_to_vector(column_schema, sql_val, builder, timezone)?;
             }
         }
 
@@ -300,7 +302,10 @@ impl SqlHandler {
         } else {
             let table_ref = TableReference::full(&catalog_name, &schema_name, &table_name);
             Ok(InsertRequests::Request(Self::build_request_from_values(
-                table_ref, &table, stmt,
+                table_ref,
+                &table,
+                stmt,
+                query_ctx.time_zone().as_ref(),
             )?))
         }
     }
@@ -310,6 +315,7 @@ fn add_row_to_vector(
     column_schema: &ColumnSchema,
     sql_val: &SqlValue,
     builder: &mut Box<dyn MutableVector>,
+    timezone: Option<&TimeZone>,
 ) -> Result<()> {
     let value = if replace_default(sql_val) {
         column_schema
@@ -321,8 +327,13 @@ fn add_row_to_vector(
                 column: column_schema.name.to_string(),
             })?
     } else {
-        statements::sql_value_to_value(&column_schema.name, &column_schema.data_type, sql_val)
-            .context(ParseSqlSnafu)?
+        statements::sql_value_to_value(
+            &column_schema.name,
+            &column_schema.data_type,
+            sql_val,
+            timezone,
+        )
+        .context(ParseSqlSnafu)?
     };
     builder.push_value_ref(value.as_value_ref());
     Ok(())
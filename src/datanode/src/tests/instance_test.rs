@@ -19,12 +19,14 @@ use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
 use common_query::Output;
 use common_recordbatch::util;
 use common_telemetry::logging;
+use common_test_util::temp_dir::create_temp_dir;
 use datatypes::data_type::ConcreteDataType;
 use datatypes::vectors::{Int64Vector, StringVector, UInt64Vector, VectorRef};
 use query::parser::{QueryLanguageParser, QueryStatement};
 use session::context::QueryContext;
 use snafu::ResultExt;
 use sql::statements::statement::Statement;
+use table::requests::OpenTableRequest;
 
 use crate::error::{Error, ExecuteLogicalPlanSnafu, PlanStatementSnafu};
 use crate::tests::test_util::{self, check_output_stream, setup_test_instance, MockInstance};
@@ -908,6 +910,156 @@ async fn test_execute_copy_from_s3() {
     }
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_execute_copy_csv_and_json_round_trip() {
+    let instance = setup_test_instance("test_execute_copy_csv_and_json_round_trip").await;
+
+    execute_sql(
+        &instance,
+        "create table demo(host string, cpu double, memory double, ts timestamp time index);",
+    )
+    .await;
+    let output = execute_sql(
+        &instance,
+        r#"insert into demo(host, cpu, memory, ts) values
+                    ('host1', 66.6, 1024, 1655276557000),
+                    ('host2', 88.8, null, 1655276558000),
+                    ('host3', null, 333.3, 1655276559000)
+                    "#,
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(3)));
+
+    let expected = "\
++-------+------+--------+---------------------+
+| host  | cpu  | memory | ts                  |
++-------+------+--------+---------------------+
+| host1 | 66.6 | 1024.0 | 2022-06-15T07:02:37 |
+| host2 | 88.8 |        | 2022-06-15T07:02:38 |
+| host3 |      | 333.3  | 2022-06-15T07:02:39 |
++-------+------+--------+---------------------+";
+
+    let tests = [
+        ("csv", "FORMAT = 'csv', HEADER = true, DELIMITER = '|'"),
+        ("json", "FORMAT = 'json', MAX_FILE_SIZE = 64"),
+    ];
+    for (name, options) in tests {
+        let dir = create_temp_dir(&format!("test_copy_{name}"));
+        let location = format!("{}/", dir.path().display());
+
+        let output = execute_sql(
+            &instance,
+            &format!("Copy demo TO '{location}' WITH ({options})"),
+        )
+        .await;
+        assert!(matches!(output, Output::AffectedRows(3)));
+
+        let table_name = format!("demo_from_{name}");
+        execute_sql(
+            &instance,
+            &format!("create table {table_name}(host string, cpu double, memory double, ts timestamp time index);"),
+        )
+        .await;
+        let output = execute_sql(
+            &instance,
+            &format!("Copy {table_name} FROM '{location}' WITH ({options})"),
+        )
+        .await;
+        assert!(matches!(output, Output::AffectedRows(3)));
+
+        let output = execute_sql(
+            &instance,
+            &format!("select * from {table_name} order by ts"),
+        )
+        .await;
+        check_output_stream(output, expected.to_string()).await;
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_execute_copy_from_mapped_columns() {
+    let instance = setup_test_instance("test_execute_copy_from_mapped_columns").await;
+
+    execute_sql(
+        &instance,
+        "create table demo(host string, cpu double, memory double, ts timestamp time index);",
+    )
+    .await;
+    execute_sql(
+        &instance,
+        r#"insert into demo(host, cpu, memory, ts) values
+                    ('host1', 66.6, 1024, 1655276557000),
+                    ('host2', 88.8, 333.3, 1655276558000)
+                    "#,
+    )
+    .await;
+    let dir = create_temp_dir("test_copy_from_mapped_columns");
+    let location = format!("{}/demo.parquet", dir.path().display());
+    execute_sql(&instance, &format!("Copy demo TO '{location}'")).await;
+
+    // The columns are reordered, `memory` is not in the table and `disk` is not in the file.
+    execute_sql(
+        &instance,
+        "create table mapped(ts timestamp time index, host string, disk double, cpu double);",
+    )
+    .await;
+    let output = execute_sql(
+        &instance,
+        &format!("Copy mapped FROM '{location}' WITH (CASE_INSENSITIVE_COLUMNS = true)"),
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(2)));
+
+    let output = execute_sql(&instance, "select * from mapped order by ts").await;
+    let expected = "\
++---------------------+-------+------+------+
+| ts                  | host  | disk | cpu  |
++---------------------+-------+------+------+
+| 2022-06-15T07:02:37 | host1 |      | 66.6 |
+| 2022-06-15T07:02:38 | host2 |      | 88.8 |
++---------------------+-------+------+------+";
+    check_output_stream(output, expected.to_string()).await;
+
+    // A lossy coercion is rejected.
+    execute_sql(
+        &instance,
+        "create table lossy(host string, cpu int, ts timestamp time index);",
+    )
+    .await;
+    let err = try_execute_sql(&instance, &format!("Copy lossy FROM '{location}'"))
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Cannot coerce column cpu from type Float64 to Int32"),
+        "{err}"
+    );
+    assert!(err.to_string().contains("demo.parquet"), "{err}");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_execute_copy_binary_to_csv() {
+    let instance = setup_test_instance("test_execute_copy_binary_to_csv").await;
+
+    execute_sql(
+        &instance,
+        "create table demo(host string, data varbinary, ts timestamp time index);",
+    )
+    .await;
+
+    let dir = create_temp_dir("test_copy_binary_to_csv");
+    let sql = format!(
+        "Copy demo TO '{}/' WITH (FORMAT = 'csv')",
+        dir.path().display()
+    );
+    let err = try_execute_sql(&instance, &sql).await.unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Unsupported column data of type LargeBinary in csv format"),
+        "{err}"
+    );
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_create_by_procedure() {
     common_telemetry::init_default_ut_logging();
@@ -944,6 +1096,50 @@ async fn test_create_by_procedure() {
     assert!(matches!(output, Output::AffectedRows(0)));
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_open_table() {
+    let instance = MockInstance::new("open_table").await;
+
+    let output = execute_sql(
+        &instance,
+        "create table demo(host string, ts timestamp, TIME INDEX (ts))",
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+    let table_id = instance
+        .inner()
+        .catalog_manager
+        .table(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, "demo")
+        .await
+        .unwrap()
+        .unwrap()
+        .table_info()
+        .ident
+        .table_id;
+
+    let open_request = |table_name: &str, table_id| OpenTableRequest {
+        catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+        schema_name: DEFAULT_SCHEMA_NAME.to_string(),
+        table_name: table_name.to_string(),
+        table_id,
+    };
+    let table = instance
+        .inner()
+        .open_table(open_request("demo", table_id))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(table_id, table.table_info().ident.table_id);
+    assert_eq!(0, instance.inner().region_open_limiter.queue_depth());
+
+    assert!(instance
+        .inner()
+        .open_table(open_request("missing", table_id + 1))
+        .await
+        .unwrap()
+        .is_none());
+}
+
 async fn execute_sql(instance: &MockInstance, sql: &str) -> Output {
     execute_sql_in_db(instance, sql, DEFAULT_SCHEMA_NAME).await
 }
@@ -33,7 +33,9 @@ use sql::statements::tql::Tql;
 use table::engine::{EngineContext, TableEngineRef};
 use table::requests::{CreateTableRequest, TableOptions};
 
-use crate::datanode::{DatanodeOptions, FileConfig, ObjectStoreConfig, ProcedureConfig, WalConfig};
+use crate::datanode::{
+    DatanodeOptions, FileConfig, ObjectStoreConfig, ProcedureConfig, StorageConfig, WalConfig,
+};
 use crate::error::{CreateTableSnafu, Result};
 use crate::instance::Instance;
 use crate::sql::SqlHandler;
@@ -97,6 +99,7 @@ impl MockInstance {
                             end: eval.end,
                             step: eval.step,
                             query: eval.query,
+                            lookback: None,
                         };
                         let stmt = QueryLanguageParser::parse_promql(&promql).unwrap();
                         planner.plan(stmt, QueryContext::arc()).await.unwrap()
@@ -118,22 +121,25 @@ impl MockInstance {
     }
 }
 
-struct TestGuard {
+pub(crate) struct TestGuard {
     _wal_tmp_dir: TempDir,
     _data_tmp_dir: TempDir,
 }
 
-fn create_tmp_dir_and_datanode_opts(name: &str) -> (DatanodeOptions, TestGuard) {
+pub(crate) fn create_tmp_dir_and_datanode_opts(name: &str) -> (DatanodeOptions, TestGuard) {
     let wal_tmp_dir = create_temp_dir(&format!("gt_wal_{name}"));
     let data_tmp_dir = create_temp_dir(&format!("gt_data_{name}"));
     let opts = DatanodeOptions {
         wal: WalConfig {
-            dir: wal_tmp_dir.path().to_str().unwrap().to_string(),
+            dir: wal_tmp_dir.path().to_str().unwrap().to_string().into(),
+            ..Default::default()
+        },
+        storage: StorageConfig {
+            store: ObjectStoreConfig::File(FileConfig {
+                data_dir: data_tmp_dir.path().to_str().unwrap().to_string(),
+            }),
             ..Default::default()
         },
-        storage: ObjectStoreConfig::File(FileConfig {
-            data_dir: data_tmp_dir.path().to_str().unwrap().to_string(),
-        }),
         mode: Mode::Standalone,
         ..Default::default()
     };
@@ -33,6 +33,7 @@ use futures::StreamExt;
 use meta_client::rpc::TableName;
 use partition::manager::PartitionRuleManagerRef;
 use snafu::prelude::*;
+use table::metadata::TableInfoRef;
 use table::TableRef;
 
 use crate::datanode::DatanodeClients;
@@ -295,13 +296,20 @@ impl SchemaProvider for FrontendSchemaProvider {
         };
         let Some(kv) = self.backend.get(table_global_key.to_string().as_bytes()).await? else { return Ok(None) };
         let v = TableGlobalValue::from_bytes(kv.1).context(InvalidCatalogValueSnafu)?;
-        let table_info = Arc::new(
+        let table_info: TableInfoRef = Arc::new(
             v.table_info
                 .try_into()
                 .context(catalog_err::InvalidTableInfoInCatalogSnafu)?,
         );
+        let table_name = TableName::new(&self.catalog_name, &self.schema_name, name);
+        // Routes are cached by table name, the cached one may belong to a table renamed or
+        // dropped by other frontends.
+        self.partition_manager
+            .table_routes()
+            .invalidate_stale_route(&table_name, table_info.ident.table_id as u64)
+            .await;
         let table = Arc::new(DistTable::new(
-            TableName::new(&self.catalog_name, &self.schema_name, name),
+            table_name,
             table_info,
             self.partition_manager.clone(),
             self.datanode_clients.clone(),
@@ -318,7 +326,7 @@ impl SchemaProvider for FrontendSchemaProvider {
         unimplemented!("Frontend schema provider does not support register table")
     }
 
-    fn rename_table(&self, _name: &str, _new_name: String) -> catalog_err::Result<TableRef> {
+    async fn rename_table(&self, _name: &str, _new_name: String) -> catalog_err::Result<TableRef> {
         unimplemented!("Frontend schema provider does not support rename table")
     }
 
@@ -15,7 +15,7 @@
 use std::time::Duration;
 
 use client::Client;
-use common_grpc::channel_manager::ChannelManager;
+use common_grpc::channel_manager::{ChannelConfig, ChannelManager};
 use meta_client::rpc::Peer;
 use moka::future::{Cache, CacheBuilder};
 
@@ -26,17 +26,21 @@ pub struct DatanodeClients {
 
 impl Default for DatanodeClients {
     fn default() -> Self {
+        Self::with_config(ChannelConfig::default())
+    }
+}
+
+impl DatanodeClients {
+    pub(crate) fn with_config(config: ChannelConfig) -> Self {
         Self {
-            channel_manager: ChannelManager::new(),
+            channel_manager: ChannelManager::with_config(config),
             clients: CacheBuilder::new(1024)
                 .time_to_live(Duration::from_secs(30 * 60))
                 .time_to_idle(Duration::from_secs(5 * 60))
                 .build(),
         }
     }
-}
 
-impl DatanodeClients {
     pub(crate) async fn get_client(&self, datanode: &Peer) -> Client {
         self.clients
             .get_with_by_ref(datanode, async move {
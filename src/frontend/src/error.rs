@@ -129,6 +129,9 @@ pub enum Error {
         backtrace: Backtrace,
     },
 
+    #[snafu(display("Bulk load {} not found", load_id))]
+    BulkLoadNotFound { load_id: String, backtrace: Backtrace },
+
     #[snafu(display("Column {} not found in table {}", column_name, table_name))]
     ColumnNotFound {
         column_name: String,
@@ -190,6 +193,31 @@ pub enum Error {
         source: partition::error::Error,
     },
 
+    #[snafu(display(
+        "Failed to rename table route of table {}, source: {}",
+        table_name,
+        source
+    ))]
+    RenameTableRoute {
+        table_name: String,
+        #[snafu(backtrace)]
+        source: partition::error::Error,
+    },
+
+    #[snafu(display("Table {} is being renamed to {}", table_name, new_table_name))]
+    TableRenaming {
+        table_name: String,
+        new_table_name: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Invalid table rename of key {}, source: {}", key, source))]
+    InvalidTableRename {
+        key: String,
+        source: serde_json::error::Error,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Failed to create table info, source: {}", source))]
     CreateTableInfo {
         #[snafu(backtrace)]
@@ -211,6 +239,15 @@ pub enum Error {
         source: common_grpc_expr::error::Error,
     },
 
+    #[snafu(display(
+        "Failed to convert record batch to table InsertRequest, source: {}",
+        source
+    ))]
+    RecordBatchToInsertRequest {
+        #[snafu(backtrace)]
+        source: common_grpc_expr::error::Error,
+    },
+
     #[snafu(display("Failed to find catalog by name: {}", catalog_name))]
     CatalogNotFound {
         catalog_name: String,
@@ -265,6 +302,40 @@ pub enum Error {
         source: query::error::Error,
     },
 
+    #[snafu(display("Failed to set session variable, source: {}", source))]
+    SetVariable {
+        #[snafu(backtrace)]
+        source: session::error::Error,
+    },
+
+    #[snafu(display("Statement timed out after {:?}", timeout))]
+    StatementTimeout {
+        timeout: std::time::Duration,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Query {} is killed", id))]
+    QueryKilled { id: u64, backtrace: Backtrace },
+
+    #[snafu(display("User {} is not allowed to kill query {}", user, id))]
+    KillQueryDenied {
+        id: u64,
+        user: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to build processlist, source: {}", source))]
+    BuildProcesslist {
+        #[snafu(backtrace)]
+        source: common_recordbatch::error::Error,
+    },
+
+    #[snafu(display("Failed to collect record batches, source: {}", source))]
+    CollectRecordbatch {
+        #[snafu(backtrace)]
+        source: common_recordbatch::error::Error,
+    },
+
     #[snafu(display("Failed to build DataFusion logical plan, source: {}", source))]
     BuildDfLogicalPlan {
         source: datafusion_common::DataFusionError,
@@ -388,6 +459,7 @@ impl ErrorExt for Error {
             Error::ParseAddr { .. }
             | Error::InvalidSql { .. }
             | Error::InvalidInsertRequest { .. }
+            | Error::BulkLoadNotFound { .. }
             | Error::ColumnValuesNumberMismatch { .. }
             | Error::IllegalPrimaryKeysDef { .. }
             | Error::CatalogNotFound { .. }
@@ -442,6 +514,7 @@ impl ErrorExt for Error {
             }
             Error::BuildCreateExprOnInsertion { source }
             | Error::ToTableInsertRequest { source }
+            | Error::RecordBatchToInsertRequest { source }
             | Error::FindNewColumnsOnInsertion { source } => source.status_code(),
 
             Error::ExecuteStatement { source, .. }
@@ -450,17 +523,28 @@ impl ErrorExt for Error {
             | Error::ExecLogicalPlan { source }
             | Error::DescribeStatement { source } => source.status_code(),
 
+            Error::SetVariable { source } => source.status_code(),
+            Error::StatementTimeout { .. } | Error::QueryKilled { .. } => {
+                StatusCode::EngineExecuteQuery
+            }
+            Error::KillQueryDenied { .. } => StatusCode::AccessDenied,
+            Error::BuildProcesslist { source } | Error::CollectRecordbatch { source } => {
+                source.status_code()
+            }
+
             Error::AlterExprToRequest { source, .. } => source.status_code(),
             Error::LeaderNotFound { .. } => StatusCode::StorageUnavailable,
             Error::TableAlreadyExist { .. } => StatusCode::TableAlreadyExists,
+            Error::TableRenaming { .. } => StatusCode::InvalidArguments,
+            Error::InvalidTableRename { .. } => StatusCode::Unexpected,
             Error::EncodeSubstraitLogicalPlan { source } => source.status_code(),
             Error::InvokeDatanode { source } => source.status_code(),
             Error::ColumnDefaultValue { source, .. } => source.status_code(),
 
             Error::External { source } => source.status_code(),
-            Error::DeserializePartition { source, .. } | Error::FindTableRoute { source, .. } => {
-                source.status_code()
-            }
+            Error::DeserializePartition { source, .. }
+            | Error::FindTableRoute { source, .. }
+            | Error::RenameTableRoute { source, .. } => source.status_code(),
             Error::UnrecognizedTableOption { .. } => StatusCode::InvalidArguments,
         }
     }
@@ -185,7 +185,7 @@ fn columns_to_expr(
 ) -> crate::error::Result<Vec<api::v1::ColumnDef>> {
     let column_schemas = column_defs
         .iter()
-        .map(|c| column_def_to_schema(c, c.name.to_string() == time_index).context(ParseSqlSnafu))
+        .map(|c| column_def_to_schema(c, c.name.value == time_index).context(ParseSqlSnafu))
         .collect::<Result<Vec<ColumnSchema>>>()?;
 
     let column_datatypes = column_schemas
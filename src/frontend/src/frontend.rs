@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use common_grpc::compression::GrpcCompression;
 use meta_client::MetaClientOptions;
 use serde::{Deserialize, Serialize};
 use servers::http::HttpOptions;
@@ -24,11 +25,15 @@ use crate::opentsdb::OpentsdbOptions;
 use crate::postgres::PostgresOptions;
 use crate::prom::PromOptions;
 use crate::prometheus::PrometheusOptions;
+use crate::query_cache::QueryCacheOptions;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(default)]
 pub struct FrontendOptions {
     pub mode: Mode,
+    /// Compression of gRPC responses to clients and requests to datanodes, compressed
+    /// messages are always accepted.
+    pub grpc_compression: GrpcCompression,
     pub http_options: Option<HttpOptions>,
     pub grpc_options: Option<GrpcOptions>,
     pub mysql_options: Option<MysqlOptions>,
@@ -38,12 +43,14 @@ pub struct FrontendOptions {
     pub prometheus_options: Option<PrometheusOptions>,
     pub prom_options: Option<PromOptions>,
     pub meta_client_options: Option<MetaClientOptions>,
+    pub query_cache: QueryCacheOptions,
 }
 
 impl Default for FrontendOptions {
     fn default() -> Self {
         Self {
             mode: Mode::Standalone,
+            grpc_compression: GrpcCompression::None,
             http_options: Some(HttpOptions::default()),
             grpc_options: Some(GrpcOptions::default()),
             mysql_options: Some(MysqlOptions::default()),
@@ -53,6 +60,7 @@ impl Default for FrontendOptions {
             prometheus_options: Some(PrometheusOptions::default()),
             prom_options: Some(PromOptions::default()),
             meta_client_options: None,
+            query_cache: QueryCacheOptions::default(),
         }
     }
 }
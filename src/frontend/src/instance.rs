@@ -12,16 +12,19 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod bulk_load;
 pub(crate) mod distributed;
 mod grpc;
 mod influxdb;
 mod opentsdb;
 mod prometheus;
+mod record_batch;
 mod standalone;
 
 use std::collections::HashMap;
+use std::future::Future;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use api::v1::alter_expr::Kind;
 use api::v1::ddl_request::Expr as DdlExpr;
@@ -34,20 +37,22 @@ use common_base::Plugins;
 use common_error::ext::BoxedError;
 use common_grpc::channel_manager::{ChannelConfig, ChannelManager};
 use common_query::Output;
-use common_recordbatch::RecordBatches;
-use common_telemetry::logging::{debug, info};
+use common_recordbatch::{LimitedRecordBatchStream, RecordBatches};
+use common_telemetry::logging::{debug, error, info, warn};
 use common_telemetry::timer;
 use datafusion::sql::sqlparser::ast::ObjectName;
 use datanode::instance::sql::table_idents_to_full_name;
 use datanode::instance::InstanceRef as DnInstanceRef;
 use datanode::metric;
 use datatypes::schema::Schema;
+use datatypes::vectors::{StringVector, TimestampMillisecondVector, UInt64Vector, VectorRef};
 use distributed::DistInstance;
 use meta_client::client::{MetaClient, MetaClientBuilder};
 use meta_client::MetaClientOptions;
 use partition::manager::PartitionRuleManager;
 use partition::route::TableRoutes;
 use query::parser::{PromQuery, QueryLanguageParser, QueryStatement};
+use query::plan::LogicalPlan;
 use query::query_engine::options::{validate_catalog_and_schema, QueryOptions};
 use query::query_engine::StatementHandlerRef;
 use query::{QueryEngineFactory, QueryEngineRef};
@@ -57,14 +62,22 @@ use servers::prom::{PromHandler, PromHandlerRef};
 use servers::query_handler::grpc::{GrpcQueryHandler, GrpcQueryHandlerRef};
 use servers::query_handler::sql::SqlQueryHandler;
 use servers::query_handler::{
-    InfluxdbLineProtocolHandler, OpentsdbProtocolHandler, PrometheusProtocolHandler, ScriptHandler,
-    ScriptHandlerRef,
+    BackupHandler, BackupHandlerRef, BulkLoadHandler, CompactionStatus, CompactionStatusHandler,
+    CompactionStatusHandlerRef, InfluxdbLineProtocolHandler, OpentsdbProtocolHandler,
+    PrometheusProtocolHandler, ReadinessHandler, ReadinessHandlerRef, RecordBatchInsertHandler,
+    RegionBackupLag, RegionGcResult, ScriptHandler, ScriptHandlerRef, SstGcHandler,
+    SstGcHandlerRef, StorageVerifyHandler, StorageVerifyHandlerRef, StorageVerifyResult,
+    TableStats, TableStatsHandler, TableStatsHandlerRef,
 };
 use session::context::QueryContextRef;
+use session::variables::SessionVariables;
 use snafu::prelude::*;
 use sql::dialect::GenericDialect;
 use sql::parser::ParserContext;
 use sql::statements::copy::CopyTable;
+use sql::statements::kill::Kill;
+use sql::statements::set_variables::SetVariables;
+use sql::statements::show::ShowProcesslist;
 use sql::statements::statement::Statement;
 use sql::statements::tql::Tql;
 
@@ -73,12 +86,17 @@ use crate::datanode::DatanodeClients;
 use crate::error::{
     self, Error, ExecLogicalPlanSnafu, ExecutePromqlSnafu, ExecuteStatementSnafu, ExternalSnafu,
     InvalidInsertRequestSnafu, MissingMetasrvOptsSnafu, NotSupportedSnafu, ParseQuerySnafu,
-    ParseSqlSnafu, PlanStatementSnafu, Result, SqlExecInterceptedSnafu,
+    ParseSqlSnafu, PlanStatementSnafu, Result, SetVariableSnafu, SqlExecInterceptedSnafu,
+    StatementTimeoutSnafu,
 };
 use crate::expr_factory::{CreateExprFactoryRef, DefaultCreateExprFactory};
 use crate::frontend::FrontendOptions;
+use crate::instance::bulk_load::BulkLoads;
 use crate::instance::standalone::StandaloneGrpcQueryHandler;
+use crate::process::{self, Process, ProcessGuard, ProcessManagerRef, ProcessStream};
+use crate::query_cache::{Lookup, QueryCache, QueryCacheOptions, QueryCacheRef, Writes};
 use crate::server::{start_server, ServerHandlers, Services};
+use crate::table::rename;
 
 #[async_trait]
 pub trait FrontendInstance:
@@ -87,6 +105,8 @@ pub trait FrontendInstance:
     + OpentsdbProtocolHandler
     + InfluxdbLineProtocolHandler
     + PrometheusProtocolHandler
+    + RecordBatchInsertHandler
+    + BulkLoadHandler
     + ScriptHandler
     + PromHandler
     + Send
@@ -108,6 +128,18 @@ pub struct Instance {
     query_engine: QueryEngineRef,
     grpc_query_handler: GrpcQueryHandlerRef<Error>,
     promql_handler: Option<PromHandlerRef>,
+    /// Backup handler is None in distributed mode, only works on standalone mode.
+    backup_handler: Option<BackupHandlerRef>,
+    /// SST GC handler is None in distributed mode, only works on standalone mode.
+    gc_handler: Option<SstGcHandlerRef>,
+    /// Compaction status handler is None in distributed mode, only works on standalone mode.
+    compaction_handler: Option<CompactionStatusHandlerRef>,
+    /// Table stats handler is None in distributed mode, only works on standalone mode.
+    table_stats_handler: Option<TableStatsHandlerRef>,
+    /// Storage verify handler is None in distributed mode, only works on standalone mode.
+    verify_handler: Option<StorageVerifyHandlerRef>,
+    /// Readiness of the datanode, None in distributed mode where the frontend is always ready.
+    readiness_handler: Option<ReadinessHandlerRef>,
 
     create_expr_factory: CreateExprFactoryRef,
 
@@ -116,6 +148,15 @@ pub struct Instance {
     plugins: Arc<Plugins>,
 
     servers: Arc<ServerHandlers>,
+
+    /// Statements being executed.
+    process_manager: ProcessManagerRef,
+
+    /// Results of queries, None if the cache is disabled.
+    query_cache: Option<QueryCacheRef>,
+
+    /// Uncommitted bulk loads.
+    bulk_loads: Arc<BulkLoads>,
 }
 
 impl Instance {
@@ -130,13 +171,21 @@ impl Instance {
         });
         let table_routes = Arc::new(TableRoutes::new(meta_client.clone()));
         let partition_manager = Arc::new(PartitionRuleManager::new(table_routes));
-        let datanode_clients = Arc::new(DatanodeClients::default());
+        let datanode_clients = Arc::new(DatanodeClients::with_config(
+            ChannelConfig::new().compression(opts.grpc_compression),
+        ));
 
         let catalog_manager = Arc::new(FrontendCatalogManager::new(
             meta_backend,
             partition_manager,
             datanode_clients.clone(),
         ));
+        let resume_catalog_manager = catalog_manager.clone();
+        common_runtime::spawn_bg(async move {
+            if let Err(e) = rename::resume_all(&resume_catalog_manager).await {
+                error!(e; "Failed to resume table renames");
+            }
+        });
 
         let dist_instance =
             DistInstance::new(meta_client, catalog_manager.clone(), datanode_clients);
@@ -146,7 +195,7 @@ impl Instance {
             QueryEngineFactory::new_with_plugins(catalog_manager.clone(), plugins.clone())
                 .query_engine();
 
-        Ok(Instance {
+        let mut instance = Instance {
             catalog_manager,
             script_handler: None,
             create_expr_factory: Arc::new(DefaultCreateExprFactory),
@@ -154,9 +203,22 @@ impl Instance {
             query_engine,
             grpc_query_handler: dist_instance,
             promql_handler: None,
+            backup_handler: None,
+            gc_handler: None,
+            compaction_handler: None,
+            table_stats_handler: None,
+            verify_handler: None,
+            readiness_handler: None,
             plugins: plugins.clone(),
             servers: Arc::new(HashMap::new()),
-        })
+            process_manager: Default::default(),
+            query_cache: None,
+            bulk_loads: Default::default(),
+        };
+        // The writes through other frontends are not observed, so the results are only
+        // cached for a short time.
+        instance.set_query_cache(&opts.query_cache.distributed());
+        Ok(instance)
     }
 
     async fn create_meta_client(opts: &FrontendOptions) -> Result<Arc<MetaClient>> {
@@ -198,8 +260,17 @@ impl Instance {
             query_engine: dn_instance.query_engine(),
             grpc_query_handler: StandaloneGrpcQueryHandler::arc(dn_instance.clone()),
             promql_handler: Some(dn_instance.clone()),
+            backup_handler: Some(dn_instance.clone()),
+            gc_handler: Some(dn_instance.clone()),
+            compaction_handler: Some(dn_instance.clone()),
+            table_stats_handler: Some(dn_instance.clone()),
+            verify_handler: Some(dn_instance.clone()),
+            readiness_handler: Some(dn_instance.clone()),
             plugins: Default::default(),
             servers: Arc::new(HashMap::new()),
+            process_manager: Default::default(),
+            query_cache: None,
+            bulk_loads: Default::default(),
         }
     }
 
@@ -226,8 +297,17 @@ impl Instance {
             create_expr_factory: Arc::new(DefaultCreateExprFactory),
             grpc_query_handler: dist_instance,
             promql_handler: None,
+            backup_handler: None,
+            gc_handler: None,
+            compaction_handler: None,
+            table_stats_handler: None,
+            verify_handler: None,
+            readiness_handler: None,
             plugins: Default::default(),
             servers: Arc::new(HashMap::new()),
+            process_manager: Default::default(),
+            query_cache: None,
+            bulk_loads: Default::default(),
         }
     }
 
@@ -235,6 +315,26 @@ impl Instance {
         &self.catalog_manager
     }
 
+    pub fn set_query_cache(&mut self, options: &QueryCacheOptions) {
+        self.query_cache = options.enable.then(|| {
+            info!("Enable query cache with options: {:?}", options);
+            Arc::new(QueryCache::new(options.clone()))
+        });
+    }
+
+    pub fn query_cache(&self) -> Option<&QueryCacheRef> {
+        self.query_cache.as_ref()
+    }
+
+    /// Records the writes to the query cache, if it's enabled.
+    pub(crate) fn record_writes(&self, writes: impl FnOnce() -> Option<Writes>) {
+        if let Some(query_cache) = &self.query_cache {
+            if let Some(writes) = writes() {
+                query_cache.record_writes(writes);
+            }
+        }
+    }
+
     pub fn set_script_handler(&mut self, handler: ScriptHandlerRef) {
         debug_assert!(
             self.script_handler.is_none(),
@@ -263,8 +363,15 @@ impl Instance {
         self.create_or_alter_table_on_demand(ctx.clone(), &request)
             .await?;
 
+        let writes = Writes::Table {
+            catalog: ctx.current_catalog(),
+            schema: ctx.current_schema(),
+            table: request.table_name.clone(),
+        };
         let query = Request::Insert(request);
-        GrpcQueryHandler::do_query(&*self.grpc_query_handler, query, ctx).await
+        let output = GrpcQueryHandler::do_query(&*self.grpc_query_handler, query, ctx).await;
+        self.record_writes(|| Some(writes));
+        output
     }
 
     // check if table already exist:
@@ -395,6 +502,71 @@ impl Instance {
         Ok(Output::RecordBatches(RecordBatches::empty()))
     }
 
+    fn handle_set_variables(
+        &self,
+        set: SetVariables,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        let name = set.variable();
+        let value = set.value_string();
+        if !query_ctx
+            .set_variable(&name, &value)
+            .context(SetVariableSnafu)?
+        {
+            warn!("Ignore unknown session variable {} = {}", name, value);
+        }
+        Ok(Output::RecordBatches(RecordBatches::empty()))
+    }
+
+    fn handle_show_processlist(
+        &self,
+        show: ShowProcesslist,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        let processes = self.process_manager.processes(&query_ctx.current_user());
+        let schema = Arc::new(Schema::new(process::processlist_column_schemas()));
+        let strings = |f: fn(&Process) -> &str| -> VectorRef {
+            Arc::new(StringVector::from(
+                processes.iter().map(|p| f(p)).collect::<Vec<_>>(),
+            ))
+        };
+        let columns: Vec<VectorRef> = vec![
+            Arc::new(UInt64Vector::from_values(processes.iter().map(|p| p.id()))),
+            strings(Process::user),
+            strings(Process::catalog),
+            strings(Process::schema),
+            strings(Process::protocol),
+            Arc::new(TimestampMillisecondVector::from_values(
+                processes.iter().map(|p| p.start_time()),
+            )),
+            Arc::new(UInt64Vector::from_values(
+                processes.iter().map(|p| p.elapsed_millis()),
+            )),
+            Arc::new(StringVector::from(
+                processes
+                    .iter()
+                    .map(|p| p.datanodes().join(","))
+                    .collect::<Vec<_>>(),
+            )),
+            if show.full {
+                strings(Process::query)
+            } else {
+                strings(Process::truncated_query)
+            },
+        ];
+        let batches = RecordBatches::try_from_columns(schema, columns)
+            .context(error::BuildProcesslistSnafu)?;
+        Ok(Output::RecordBatches(batches))
+    }
+
+    /// Kills the query, which is a no-op if the query has finished.
+    fn handle_kill(&self, kill: Kill, query_ctx: QueryContextRef) -> Result<Output> {
+        let killed = self
+            .process_manager
+            .kill(kill.id, &query_ctx.current_user())?;
+        Ok(Output::AffectedRows(killed as usize))
+    }
+
     pub fn set_plugins(&mut self, map: Arc<Plugins>) {
         self.plugins = map;
     }
@@ -428,21 +600,79 @@ fn parse_stmt(sql: &str) -> Result<Vec<Statement>> {
 }
 
 impl Instance {
-    async fn query_statement(&self, stmt: Statement, query_ctx: QueryContextRef) -> Result<Output> {
+    /// Executes the statement, subject to the `statement_timeout` and `max_execution_rows`
+    /// variables of the session.
+    ///
+    /// The statement is cancelled once the `process` is killed. If the output is a stream,
+    /// the process stays registered until the stream is dropped, and the timeout also
+    /// applies to reading the stream.
+    async fn query_statement(
+        &self,
+        stmt: Statement,
+        query_ctx: QueryContextRef,
+        process: ProcessGuard,
+    ) -> Result<Output> {
+        let variables = query_ctx.variables();
+        Self::execute_in_process(self.execute_statement(stmt, query_ctx), &variables, process).await
+    }
+
+    /// Runs `execution` as the `process`, see [Instance::query_statement].
+    async fn execute_in_process(
+        execution: impl Future<Output = Result<Output>>,
+        variables: &SessionVariables,
+        process: ProcessGuard,
+    ) -> Result<Output> {
+        let started = Instant::now();
+        let execution = async {
+            tokio::select! {
+                output = process.scope(execution) => output,
+                _ = process.process().killed() => error::QueryKilledSnafu {
+                    id: process.process().id(),
+                }
+                .fail(),
+            }
+        };
+        let output = match variables.statement_timeout {
+            Some(timeout) => tokio::time::timeout(timeout, execution)
+                .await
+                .ok()
+                .context(StatementTimeoutSnafu { timeout })??,
+            None => execution.await?,
+        };
+        let output = match output {
+            Output::Stream(stream) => {
+                let stream = ProcessStream::new(stream, process);
+                let stream = match variables.statement_timeout {
+                    Some(timeout) => stream.with_deadline(started + timeout, timeout),
+                    None => stream,
+                };
+                Output::Stream(Box::pin(stream))
+            }
+            output => output,
+        };
+        Ok(match (variables.max_execution_rows, output) {
+            (Some(limit), Output::Stream(stream)) => {
+                Output::Stream(Box::pin(LimitedRecordBatchStream::new(stream, limit)))
+            }
+            (Some(limit), Output::RecordBatches(batches)) => Output::Stream(Box::pin(
+                LimitedRecordBatchStream::new(batches.as_stream(), limit),
+            )),
+            (_, output) => output,
+        })
+    }
+
+    async fn execute_statement(
+        &self,
+        stmt: Statement,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
         check_permission(self.plugins.clone(), &stmt, &query_ctx)?;
 
         let planner = self.query_engine.planner();
 
         match stmt {
             Statement::Query(_) | Statement::Explain(_) => {
-                let plan = planner
-                    .plan(QueryStatement::Sql(stmt), query_ctx)
-                    .await
-                    .context(PlanStatementSnafu)?;
-                self.query_engine
-                    .execute(&plan)
-                    .await
-                    .context(ExecLogicalPlanSnafu)
+                self.execute_query(stmt, query_ctx).await
             }
             Statement::Tql(tql) => {
                 let plan = match tql {
@@ -452,6 +682,7 @@ impl Instance {
                             end: eval.end,
                             step: eval.step,
                             query: eval.query,
+                            lookback: None,
                         };
                         let stmt =
                             QueryLanguageParser::parse_promql(&promql).context(ParseQuerySnafu)?;
@@ -472,21 +703,69 @@ impl Instance {
             | Statement::CreateTable(_)
             | Statement::ShowTables(_)
             | Statement::DescribeTable(_)
+            | Statement::ShowCreateTable(_)
             | Statement::Insert(_)
             | Statement::Delete(_)
             | Statement::Alter(_)
             | Statement::DropTable(_)
-            | Statement::Copy(_) => self
-                .statement_handler
-                .handle_statement(QueryStatement::Sql(stmt), query_ctx)
-                .await
-                .context(ExecuteStatementSnafu),
+            | Statement::Copy(_) => {
+                let writes = self
+                    .query_cache
+                    .as_ref()
+                    .and_then(|_| Writes::of_statement(&stmt, &query_ctx));
+                let output = self
+                    .statement_handler
+                    .handle_statement(QueryStatement::Sql(stmt), query_ctx)
+                    .await
+                    .context(ExecuteStatementSnafu);
+                // Records the writes even if the statement fails, which may have written
+                // some of the rows.
+                self.record_writes(|| writes);
+                output
+            }
             Statement::Use(db) => self.handle_use(db, query_ctx),
-            Statement::ShowCreateTable(_) => NotSupportedSnafu {
-                feat: format!("{stmt:?}"),
+            Statement::SetVariables(set) => self.handle_set_variables(set, query_ctx),
+            Statement::ShowProcesslist(show) => self.handle_show_processlist(show, query_ctx),
+            Statement::Kill(kill) => self.handle_kill(kill, query_ctx),
+        }
+    }
+}
+
+impl Instance {
+    /// Executes a query, returns the cached result if any, unless the session skips the
+    /// query cache.
+    async fn execute_query(&self, stmt: Statement, query_ctx: QueryContextRef) -> Result<Output> {
+        let query = match (&stmt, &self.query_cache) {
+            (Statement::Query(query), Some(_)) if !query_ctx.variables().skip_query_cache => {
+                Some(query.clone())
             }
-            .fail(),
+            _ => None,
+        };
+
+        let plan = self
+            .query_engine
+            .planner()
+            .plan(QueryStatement::Sql(stmt), query_ctx.clone())
+            .await
+            .context(PlanStatementSnafu)?;
+
+        let lookup = match (&self.query_cache, query) {
+            (Some(query_cache), Some(query)) => query_cache.lookup(&query, &plan, &query_ctx),
+            _ => Lookup::Uncacheable,
+        };
+        if let Lookup::Hit(batches) = lookup {
+            return Ok(Output::RecordBatches(batches));
         }
+
+        let output = self
+            .query_engine
+            .execute(&plan)
+            .await
+            .context(ExecLogicalPlanSnafu)?;
+        Ok(match (&self.query_cache, lookup) {
+            (Some(query_cache), Lookup::Miss(query)) => query_cache.cache_output(query, output),
+            _ => output,
+        })
     }
 }
 
@@ -507,25 +786,42 @@ impl SqlQueryHandler for Instance {
             .and_then(|stmts| query_interceptor.post_parsing(stmts, query_ctx.clone()))
         {
             Ok(stmts) => {
+                let multi_statements = stmts.len() > 1;
                 let mut results = Vec::with_capacity(stmts.len());
                 for stmt in stmts {
                     // TODO(sunng87): figure out at which stage we can call
                     // this hook after ArrowFlight adoption. We need to provide
                     // LogicalPlan as to this hook.
-                    if let Err(e) = query_interceptor.pre_execute(&stmt, None, query_ctx.clone()) {
-                        results.push(Err(e));
-                        break;
-                    }
-                    match self.query_statement(stmt, query_ctx.clone()).await {
-                        Ok(output) => {
-                            let output_result =
-                                query_interceptor.post_execute(output, query_ctx.clone());
-                            results.push(output_result);
+                    let result = match query_interceptor.pre_execute(
+                        &stmt,
+                        None,
+                        query_ctx.clone(),
+                    ) {
+                        Ok(()) => {
+                            let process = self.process_manager.register(&query, &query_ctx);
+                            self.query_statement(stmt, query_ctx.clone(), process)
+                                .await
+                                .and_then(|output| {
+                                    query_interceptor.post_execute(output, query_ctx.clone())
+                                })
                         }
-                        Err(e) => {
-                            results.push(Err(e));
-                            break;
+                        Err(e) => Err(e),
+                    };
+                    // Results of a statement are collected before the following statements
+                    // are executed, so a query doesn't see the writes after it.
+                    let result = match result {
+                        Ok(Output::Stream(stream)) if multi_statements => {
+                            RecordBatches::try_collect(stream)
+                                .await
+                                .map(Output::RecordBatches)
+                                .context(error::CollectRecordbatchSnafu)
                         }
+                        result => result,
+                    };
+                    let failed = result.is_err();
+                    results.push(result);
+                    if failed && !query_ctx.variables().continue_on_error {
+                        break;
                     }
                 }
                 results
@@ -575,6 +871,47 @@ impl SqlQueryHandler for Instance {
         }
     }
 
+    async fn do_prepare(
+        &self,
+        stmt: Statement,
+        query_ctx: QueryContextRef,
+    ) -> Result<Option<LogicalPlan>> {
+        if !matches!(stmt, Statement::Query(_)) {
+            return Ok(None);
+        }
+        check_permission(self.plugins.clone(), &stmt, &query_ctx)?;
+
+        let plan = self
+            .query_engine
+            .planner()
+            .plan(QueryStatement::Sql(stmt.clone()), query_ctx.clone())
+            .await
+            .context(PlanStatementSnafu)?;
+        let query_interceptor = self.plugins.get::<SqlQueryInterceptorRef<Error>>();
+        query_interceptor.pre_execute(&stmt, Some(&plan), query_ctx)?;
+        Ok(Some(plan))
+    }
+
+    async fn do_exec_plan(
+        &self,
+        query: &str,
+        plan: LogicalPlan,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        let _timer = timer!(metric::METRIC_HANDLE_SQL_ELAPSED);
+
+        let process = self.process_manager.register(query, &query_ctx);
+        let execution = async {
+            self.query_engine
+                .execute(&plan)
+                .await
+                .context(ExecLogicalPlanSnafu)
+        };
+        let output = Self::execute_in_process(execution, &query_ctx.variables(), process).await?;
+        let query_interceptor = self.plugins.get::<SqlQueryInterceptorRef<Error>>();
+        query_interceptor.post_execute(output, query_ctx)
+    }
+
     fn is_valid_schema(&self, catalog: &str, schema: &str) -> Result<bool> {
         self.catalog_manager
             .schema(catalog, schema)
@@ -618,6 +955,90 @@ impl ScriptHandler for Instance {
     }
 }
 
+impl BackupHandler for Instance {
+    fn backup_status(&self) -> server_error::Result<Vec<RegionBackupLag>> {
+        if let Some(handler) = &self.backup_handler {
+            handler.backup_status()
+        } else {
+            server_error::NotSupportedSnafu {
+                feat: "SST backup in Frontend",
+            }
+            .fail()
+        }
+    }
+}
+
+impl CompactionStatusHandler for Instance {
+    fn compaction_status(&self) -> server_error::Result<CompactionStatus> {
+        if let Some(handler) = &self.compaction_handler {
+            handler.compaction_status()
+        } else {
+            server_error::NotSupportedSnafu {
+                feat: "Compaction status in Frontend",
+            }
+            .fail()
+        }
+    }
+}
+
+#[async_trait]
+impl TableStatsHandler for Instance {
+    async fn table_stats(&self) -> server_error::Result<Vec<TableStats>> {
+        if let Some(handler) = &self.table_stats_handler {
+            handler.table_stats().await
+        } else {
+            server_error::NotSupportedSnafu {
+                feat: "Table stats in Frontend",
+            }
+            .fail()
+        }
+    }
+}
+
+impl ReadinessHandler for Instance {
+    fn is_ready(&self) -> bool {
+        self.readiness_handler
+            .as_ref()
+            .map(|handler| handler.is_ready())
+            .unwrap_or(true)
+    }
+}
+
+#[async_trait]
+impl SstGcHandler for Instance {
+    async fn collect_garbage(&self, dry_run: bool) -> server_error::Result<Vec<RegionGcResult>> {
+        if let Some(handler) = &self.gc_handler {
+            handler.collect_garbage(dry_run).await
+        } else {
+            server_error::NotSupportedSnafu {
+                feat: "SST GC in Frontend",
+            }
+            .fail()
+        }
+    }
+}
+
+#[async_trait]
+impl StorageVerifyHandler for Instance {
+    async fn verify_storage(
+        &self,
+        catalog_name: &str,
+        schema_name: &str,
+        table_name: &str,
+    ) -> server_error::Result<StorageVerifyResult> {
+        if let Some(handler) = &self.verify_handler {
+            handler
+                .verify_storage(catalog_name, schema_name, table_name)
+                .await
+        } else {
+            server_error::NotSupportedSnafu {
+                feat: "Storage verify in Frontend",
+            }
+            .fail()
+        }
+    }
+}
+
 #[async_trait]
 impl PromHandler for Instance {
     async fn do_query(&self, query: &PromQuery) -> server_error::Result<Output> {
@@ -651,8 +1072,12 @@ pub fn check_permission(
         Statement::Query(_) | Statement::Explain(_) | Statement::Tql(_) => {}
         // database ops won't be checked
         Statement::CreateDatabase(_) | Statement::ShowDatabases(_) | Statement::Use(_) => {}
-        // show create table and alter are not supported yet
-        Statement::ShowCreateTable(_) | Statement::Alter(_) => {}
+        // session variables only affect the current session
+        Statement::SetVariables(_) => {}
+        // permissions of processes are checked by the process manager
+        Statement::ShowProcesslist(_) | Statement::Kill(_) => {}
+        // alter is not supported yet
+        Statement::Alter(_) => {}
 
         Statement::Insert(insert) => {
             validate_param(insert.table_name(), query_ctx)?;
@@ -673,6 +1098,9 @@ pub fn check_permission(
         Statement::DescribeTable(stmt) => {
             validate_param(stmt.name(), query_ctx)?;
         }
+        Statement::ShowCreateTable(stmt) => {
+            validate_param(&stmt.table_name, query_ctx)?;
+        }
         Statement::Delete(delete) => {
             validate_param(delete.table_name(), query_ctx)?;
         }
@@ -727,10 +1155,11 @@ mod tests {
 
     use api::v1::column::Values;
     use catalog::helper::{TableGlobalKey, TableGlobalValue};
+    use common_error::prelude::{ErrorExt, StatusCode};
     use datatypes::prelude::{ConcreteDataType, Value};
     use datatypes::schema::{ColumnDefaultConstraint, ColumnSchema};
     use query::query_engine::options::QueryOptions;
-    use session::context::QueryContext;
+    use session::context::{Channel, QueryContext, UserInfo, DEFAULT_USERNAME};
     use strfmt::Format;
 
     use super::*;
@@ -826,11 +1255,10 @@ mod tests {
         }
 
         let sql = r#"
-        SHOW CREATE TABLE demo;
         ALTER TABLE demo ADD COLUMN new_col INT;
         "#;
         let stmts = parse_stmt(sql).unwrap();
-        assert_eq!(stmts.len(), 2);
+        assert_eq!(stmts.len(), 1);
         for stmt in stmts {
             let re = check_permission(plugins.clone(), &stmt, &query_ctx);
             assert!(re.is_ok());
@@ -910,6 +1338,10 @@ mod tests {
         // test describe table
         let sql = "DESC TABLE {catalog}{schema}demo;";
         replace_test(sql, plugins.clone(), &query_ctx);
+
+        // test show create table
+        let sql = "SHOW CREATE TABLE {catalog}{schema}demo;";
+        replace_test(sql, plugins.clone(), &query_ctx);
     }
 
     #[tokio::test(flavor = "multi_thread")]
@@ -934,6 +1366,166 @@ mod tests {
         drop_table(instance).await;
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_execute_multi_statements() {
+        let standalone = tests::create_standalone_instance("test_execute_multi_statements").await;
+        let instance = standalone.instance.as_ref();
+        let sql = "CREATE TABLE IF NOT EXISTS demo(host STRING, ts TIMESTAMP TIME INDEX);
+            INSERT INTO demo VALUES ('host1', 1000);
+            SELECT host FROM demo ORDER BY host;
+            INSERT INTO demo VALUES ('host2', 2000);
+            INSERT INTO missing VALUES ('host3', 3000);
+            DROP TABLE IF EXISTS missing;";
+        let pretty_print = |output: &Output| {
+            let Output::RecordBatches(batches) = output else { unreachable!() };
+            batches.pretty_print().unwrap()
+        };
+
+        // Stops at the failed statement by default.
+        let results = SqlQueryHandler::do_query(instance, sql, QueryContext::arc()).await;
+        assert_eq!(5, results.len());
+        assert!(matches!(results[0], Ok(Output::AffectedRows(0))));
+        assert!(matches!(results[1], Ok(Output::AffectedRows(1))));
+        // The query doesn't see the rows inserted after it.
+        let expected = "\
++-------+
+| host  |
++-------+
+| host1 |
++-------+";
+        assert_eq!(expected, pretty_print(results[2].as_ref().unwrap()));
+        assert!(matches!(results[3], Ok(Output::AffectedRows(1))));
+        assert_eq!(
+            StatusCode::TableNotFound,
+            results[4].as_ref().unwrap_err().status_code()
+        );
+
+        // Reruns all statements, which are idempotent except the failed one.
+        let ctx = QueryContext::arc();
+        assert!(ctx.set_variable("continue_on_error", "ON").unwrap());
+        let results = SqlQueryHandler::do_query(instance, sql, ctx).await;
+        assert_eq!(6, results.len());
+        let expected = "\
++-------+
+| host  |
++-------+
+| host1 |
+| host2 |
++-------+";
+        assert_eq!(expected, pretty_print(results[2].as_ref().unwrap()));
+        assert!(results[4].is_err());
+        assert!(matches!(results[5], Ok(Output::AffectedRows(0))));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_show_processlist_and_kill_query() {
+        let standalone = tests::create_standalone_instance("test_kill_query").await;
+        let instance = standalone.instance.as_ref();
+        create_table(
+            instance,
+            "CREATE TABLE demo(host STRING, ts TIMESTAMP, TIME INDEX (ts)) engine=mito",
+        )
+        .await;
+        let output = query(instance, "INSERT INTO demo VALUES ('host1', 1000)").await;
+        assert!(matches!(output, Output::AffectedRows(1)));
+
+        // Each connection has its own context.
+        let connect = |user: &str| {
+            let ctx = Arc::new(QueryContext::new().with_channel(Channel::Mysql));
+            ctx.set_current_user(UserInfo::new(user));
+            ctx
+        };
+        let (alice, bob, admin) = (connect("alice"), connect("bob"), connect(DEFAULT_USERNAME));
+        let execute = |sql: &str, ctx: &QueryContextRef| {
+            let ctx = ctx.clone();
+            let sql = sql.to_string();
+            async move {
+                SqlQueryHandler::do_query(instance, &sql, ctx)
+                    .await
+                    .remove(0)
+            }
+        };
+        let processlist_rows = |output: Output| {
+            let Output::RecordBatches(batches) = output else { unreachable!() };
+            batches.iter().map(|b| b.num_rows()).sum::<usize>()
+        };
+
+        // The query is running until its output is consumed.
+        let Output::Stream(stream) = execute("SELECT * FROM demo", &alice).await.unwrap() else {
+            unreachable!()
+        };
+        let processes = instance.process_manager.processes(&alice.current_user());
+        assert_eq!(1, processes.len());
+        let process = &processes[0];
+        assert_eq!("alice", process.user());
+        assert_eq!("mysql", process.protocol());
+        assert_eq!("SELECT * FROM demo", process.query());
+        let id = process.id();
+
+        // Others can only see their own queries, except the administrator.
+        let output = execute("SHOW PROCESSLIST", &bob).await.unwrap();
+        assert_eq!(1, processlist_rows(output));
+        let output = execute("SHOW FULL PROCESSLIST", &admin).await.unwrap();
+        assert_eq!(2, processlist_rows(output));
+
+        let kill = format!("KILL QUERY {id}");
+        let err = execute(&kill, &bob).await.unwrap_err();
+        assert_eq!(StatusCode::AccessDenied, err.status_code());
+
+        let output = execute(&kill, &admin).await.unwrap();
+        assert!(matches!(output, Output::AffectedRows(1)));
+        let err = common_recordbatch::util::collect(stream).await.unwrap_err();
+        assert!(err.to_string().contains(&format!("Query {id} is killed")));
+
+        // Killing a finished query is a no-op.
+        assert!(instance
+            .process_manager
+            .processes(&admin.current_user())
+            .is_empty());
+        let output = execute(&kill, &alice).await.unwrap();
+        assert!(matches!(output, Output::AffectedRows(0)));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_time_zone_of_filter_literals() {
+        let standalone = tests::create_standalone_instance("test_time_zone_of_filter").await;
+        let instance = standalone.instance.as_ref();
+        create_table(
+            instance,
+            "CREATE TABLE demo(host STRING, ts TIMESTAMP, TIME INDEX (ts)) engine=mito",
+        )
+        .await;
+        // 2023-01-01 00:00:00 UTC.
+        let output = query(instance, "INSERT INTO demo VALUES ('host1', 1672531200000)").await;
+        assert!(matches!(output, Output::AffectedRows(1)));
+
+        let count_rows = |time_zone: &str, sql: &str| {
+            let query_ctx = QueryContext::arc();
+            assert!(query_ctx.set_variable("time_zone", time_zone).unwrap());
+            let sql = sql.to_string();
+            async move {
+                let output = SqlQueryHandler::do_query(instance, &sql, query_ctx)
+                    .await
+                    .remove(0)
+                    .unwrap();
+                let Output::Stream(stream) = output else { unreachable!() };
+                let batches = common_recordbatch::util::collect(stream).await.unwrap();
+                batches.iter().map(|b| b.num_rows()).sum::<usize>()
+            }
+        };
+
+        let sql = "SELECT * FROM demo WHERE ts = '2023-01-01 08:00:00'";
+        assert_eq!(1, count_rows("+08:00", sql).await);
+        assert_eq!(0, count_rows("UTC", sql).await);
+        let sql =
+            "SELECT * FROM demo WHERE ts >= '2023-01-01 00:00:00' AND ts < '2023-01-01 00:00:01'";
+        assert_eq!(0, count_rows("+08:00", sql).await);
+        assert_eq!(1, count_rows("UTC", sql).await);
+        // Strings with an explicit offset are not affected.
+        let sql = "SELECT * FROM demo WHERE ts = '2023-01-01 00:00:00Z'";
+        assert_eq!(1, count_rows("+08:00", sql).await);
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_distributed_exec_sql() {
         let distributed = tests::create_distributed_instance("test_distributed_exec_sql").await;
@@ -1008,6 +1600,164 @@ mod tests {
         verify_table_is_dropped(&distributed).await;
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_distributed_scan_pushdown() {
+        let distributed =
+            tests::create_distributed_instance("test_distributed_scan_pushdown").await;
+        let instance = distributed.frontend.as_ref();
+
+        let sql = r#"
+            CREATE TABLE metrics(
+                host STRING,
+                ts TIMESTAMP,
+                cpu DOUBLE NULL,
+                TIME INDEX (ts),
+                PRIMARY KEY(host)
+            )
+            PARTITION BY RANGE COLUMNS (host) (
+                PARTITION r0 VALUES LESS THAN ('b'),
+                PARTITION r1 VALUES LESS THAN ('c'),
+                PARTITION r2 VALUES LESS THAN (MAXVALUE),
+            )
+            engine=mito"#;
+        create_table(instance, sql).await;
+
+        // The time ranges of the regions are interleaved.
+        let sql = r#"INSERT INTO metrics(host, ts, cpu) VALUES
+                                ('a', 1000, 0.1),
+                                ('b', 2000, 0.2),
+                                ('c', 3000, 0.3),
+                                ('a', 4000, 0.4),
+                                ('b', 5000, 0.5),
+                                ('c', 6000, 0.6)
+                                "#;
+        let Output::AffectedRows(x) = query(instance, sql).await else { unreachable!() };
+        assert_eq!(x, 6);
+
+        async fn query_and_print(instance: &Instance, sql: &str) -> String {
+            match query(instance, sql).await {
+                Output::Stream(s) => common_recordbatch::util::collect_batches(s)
+                    .await
+                    .unwrap()
+                    .pretty_print()
+                    .unwrap(),
+                Output::RecordBatches(batches) => batches.pretty_print().unwrap(),
+                Output::AffectedRows(_) => unreachable!(),
+            }
+        }
+
+        let sql = "SELECT host, ts FROM metrics ORDER BY ts DESC LIMIT 2";
+        let expected = "\
++------+---------------------+
+| host | ts                  |
++------+---------------------+
+| c    | 1970-01-01T00:00:06 |
+| b    | 1970-01-01T00:00:05 |
++------+---------------------+";
+        assert_eq!(query_and_print(instance, sql).await, expected);
+        let explain = query_and_print(instance, &format!("EXPLAIN {sql}")).await;
+        assert!(explain.contains("pushdown=TopN(limit=2, order=desc)"));
+
+        let sql = "SELECT host, ts FROM metrics WHERE cpu > 0.15 ORDER BY ts LIMIT 2";
+        let expected = "\
++------+---------------------+
+| host | ts                  |
++------+---------------------+
+| b    | 1970-01-01T00:00:02 |
+| c    | 1970-01-01T00:00:03 |
++------+---------------------+";
+        assert_eq!(query_and_print(instance, sql).await, expected);
+
+        let sql = "SELECT min(ts) AS min_ts, max(ts) AS max_ts, count(ts) AS c, count(*) AS c2 FROM metrics";
+        let expected = "\
++---------------------+---------------------+---+----+
+| min_ts              | max_ts              | c | c2 |
++---------------------+---------------------+---+----+
+| 1970-01-01T00:00:01 | 1970-01-01T00:00:06 | 6 | 6  |
++---------------------+---------------------+---+----+";
+        assert_eq!(query_and_print(instance, sql).await, expected);
+        let explain = query_and_print(instance, &format!("EXPLAIN {sql}")).await;
+        assert!(explain.contains("pushdown=TimeIndexAggregate(min, max, count)"));
+
+        let sql = "SELECT min(ts) AS min_ts, count(*) AS c FROM metrics WHERE cpu > 1";
+        let expected = "\
++--------+---+
+| min_ts | c |
++--------+---+
+|        | 0 |
++--------+---+";
+        assert_eq!(query_and_print(instance, sql).await, expected);
+
+        // Not pushed down through joins.
+        let sql =
+            "SELECT a.ts FROM metrics a, metrics b WHERE a.ts = b.ts ORDER BY a.ts DESC LIMIT 2";
+        let expected = "\
++---------------------+
+| ts                  |
++---------------------+
+| 1970-01-01T00:00:06 |
+| 1970-01-01T00:00:05 |
++---------------------+";
+        assert_eq!(query_and_print(instance, sql).await, expected);
+        let explain = query_and_print(instance, &format!("EXPLAIN {sql}")).await;
+        assert!(!explain.contains("pushdown="));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_query_cache() {
+        let (opts, _guard) = tests::create_tmp_dir_and_datanode_opts("test_query_cache");
+        let datanode = datanode::instance::Instance::new(&opts).await.unwrap();
+        datanode.start().await.unwrap();
+        let mut instance = Instance::new_standalone(Arc::new(datanode));
+        instance.set_query_cache(&QueryCacheOptions {
+            enable: true,
+            ..Default::default()
+        });
+        let instance = &instance;
+        let cache = instance.query_cache().unwrap();
+
+        create_table(
+            instance,
+            "CREATE TABLE demo(host STRING, ts TIMESTAMP, TIME INDEX (ts)) engine=mito",
+        )
+        .await;
+        let output = query(instance, "INSERT INTO demo VALUES ('host1', 1000)").await;
+        assert!(matches!(output, Output::AffectedRows(1)));
+
+        async fn count_rows(instance: &Instance, query_ctx: QueryContextRef) -> usize {
+            let sql = "SELECT * FROM demo";
+            let output = SqlQueryHandler::do_query(instance, sql, query_ctx)
+                .await
+                .remove(0)
+                .unwrap();
+            let batches = match output {
+                Output::Stream(s) => common_recordbatch::util::collect_batches(s).await.unwrap(),
+                Output::RecordBatches(batches) => batches,
+                Output::AffectedRows(_) => unreachable!(),
+            };
+            batches.iter().map(|b| b.num_rows()).sum()
+        }
+
+        assert_eq!(1, count_rows(instance, QueryContext::arc()).await);
+        assert_eq!((0, 1), (cache.hits(), cache.misses()));
+        assert_eq!(1, count_rows(instance, QueryContext::arc()).await);
+        assert_eq!((1, 1), (cache.hits(), cache.misses()));
+
+        // A write to the table invalidates the cached result.
+        let output = query(instance, "INSERT INTO demo VALUES ('host2', 2000)").await;
+        assert!(matches!(output, Output::AffectedRows(1)));
+        assert_eq!(2, count_rows(instance, QueryContext::arc()).await);
+        assert_eq!((1, 2), (cache.hits(), cache.misses()));
+        assert_eq!(2, count_rows(instance, QueryContext::arc()).await);
+        assert_eq!((2, 2), (cache.hits(), cache.misses()));
+
+        // The session can bypass the cache.
+        let query_ctx = QueryContext::arc();
+        assert!(query_ctx.set_variable("skip_query_cache", "ON").unwrap());
+        assert_eq!(2, count_rows(instance, query_ctx).await);
+        assert_eq!((2, 2), (cache.hits(), cache.misses()));
+    }
+
     async fn query(instance: &Instance, sql: &str) -> Output {
         SqlQueryHandler::do_query(instance, sql, QueryContext::arc())
             .await
@@ -1101,6 +1851,15 @@ mod tests {
         let output = query(instance, sql).await;
         let Output::AffectedRows(x) = output else { unreachable!() };
         assert_eq!(x, 1);
+
+        // Dropping a dropped table only succeeds with `IF EXISTS`.
+        let output = query(instance, "DROP TABLE IF EXISTS demo").await;
+        assert!(matches!(output, Output::AffectedRows(0)));
+        let err = SqlQueryHandler::do_query(instance, sql, QueryContext::arc())
+            .await
+            .remove(0)
+            .unwrap_err();
+        assert_eq!(StatusCode::TableNotFound, err.status_code());
     }
 
     async fn verify_table_is_dropped(instance: &MockDistributedInstance) {
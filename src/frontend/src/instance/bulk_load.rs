@@ -0,0 +1,304 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use common_error::prelude::BoxedError;
+use common_grpc_expr::insert::record_batch_to_table_insert_request;
+use common_recordbatch::RecordBatch;
+use common_telemetry::info;
+use servers::error as server_error;
+use servers::query_handler::BulkLoadHandler;
+use session::context::QueryContextRef;
+use snafu::prelude::*;
+use table::TableRef;
+use uuid::Uuid;
+
+use crate::error::{self, Result};
+use crate::instance::Instance;
+use crate::query_cache::Writes;
+
+/// An uncommitted bulk load of a table.
+#[derive(Clone)]
+struct BulkLoad {
+    catalog: String,
+    schema: String,
+    table_name: String,
+    table: TableRef,
+}
+
+/// Uncommitted bulk loads begun through the frontend, by their ids.
+#[derive(Default)]
+pub(crate) struct BulkLoads {
+    loads: Mutex<HashMap<String, BulkLoad>>,
+}
+
+impl BulkLoads {
+    fn get(&self, load_id: &str) -> Result<BulkLoad> {
+        self.loads
+            .lock()
+            .unwrap()
+            .get(load_id)
+            .cloned()
+            .context(error::BulkLoadNotFoundSnafu { load_id })
+    }
+
+    fn remove(&self, load_id: &str) -> Result<BulkLoad> {
+        self.loads
+            .lock()
+            .unwrap()
+            .remove(load_id)
+            .context(error::BulkLoadNotFoundSnafu { load_id })
+    }
+}
+
+impl Instance {
+    async fn handle_begin_bulk_load(
+        &self,
+        table_name: &str,
+        ctx: QueryContextRef,
+    ) -> Result<String> {
+        let catalog_name = ctx.current_catalog();
+        let schema_name = ctx.current_schema();
+        let table = self
+            .catalog_manager
+            .table(&catalog_name, &schema_name, table_name)
+            .await
+            .context(error::CatalogSnafu)?
+            .with_context(|| error::TableNotFoundSnafu {
+                table_name: format!("{catalog_name}.{schema_name}.{table_name}"),
+            })?;
+
+        let load_id = Uuid::new_v4().to_string();
+        table
+            .begin_bulk_load(&load_id)
+            .await
+            .context(error::TableSnafu)?;
+        info!(
+            "Begin bulk load {} of table {}.{}.{}",
+            load_id, catalog_name, schema_name, table_name
+        );
+
+        let load = BulkLoad {
+            catalog: catalog_name,
+            schema: schema_name,
+            table_name: table_name.to_string(),
+            table,
+        };
+        self.bulk_loads
+            .loads
+            .lock()
+            .unwrap()
+            .insert(load_id.clone(), load);
+        Ok(load_id)
+    }
+
+    async fn handle_write_bulk_load(
+        &self,
+        load_id: &str,
+        region_number: u32,
+        batch: RecordBatch,
+    ) -> Result<usize> {
+        let load = self.bulk_loads.get(load_id)?;
+        let request = record_batch_to_table_insert_request(
+            &load.catalog,
+            &load.schema,
+            &load.table_name,
+            region_number,
+            &load.table.schema(),
+            &batch,
+        )
+        .context(error::RecordBatchToInsertRequestSnafu)?;
+        load
+            .table
+            .insert_bulk_load(load_id, request)
+            .await
+            .context(error::TableSnafu)
+    }
+
+    async fn handle_commit_bulk_load(&self, load_id: &str) -> Result<()> {
+        let load = self.bulk_loads.remove(load_id)?;
+        let result = load
+            .table
+            .commit_bulk_load(load_id)
+            .await
+            .context(error::TableSnafu);
+        self.record_writes(|| {
+            Some(Writes::Table {
+                catalog: load.catalog,
+                schema: load.schema,
+                table: load.table_name,
+            })
+        });
+        result
+    }
+
+    async fn handle_abort_bulk_load(&self, load_id: &str) -> Result<()> {
+        let load = self.bulk_loads.remove(load_id)?;
+        load
+            .table
+            .abort_bulk_load(load_id)
+            .await
+            .context(error::TableSnafu)
+    }
+}
+
+#[async_trait]
+impl BulkLoadHandler for Instance {
+    async fn begin_bulk_load(
+        &self,
+        table_name: &str,
+        ctx: QueryContextRef,
+    ) -> server_error::Result<String> {
+        self.handle_begin_bulk_load(table_name, ctx)
+            .await
+            .map_err(BoxedError::new)
+            .context(server_error::ExecuteGrpcQuerySnafu)
+    }
+
+    async fn write_bulk_load(
+        &self,
+        load_id: &str,
+        region_number: u32,
+        batch: RecordBatch,
+    ) -> server_error::Result<usize> {
+        self.handle_write_bulk_load(load_id, region_number, batch)
+            .await
+            .map_err(BoxedError::new)
+            .context(server_error::ExecuteGrpcQuerySnafu)
+    }
+
+    async fn commit_bulk_load(&self, load_id: &str) -> server_error::Result<()> {
+        self.handle_commit_bulk_load(load_id)
+            .await
+            .map_err(BoxedError::new)
+            .context(server_error::ExecuteGrpcQuerySnafu)
+    }
+
+    async fn abort_bulk_load(&self, load_id: &str) -> server_error::Result<()> {
+        self.handle_abort_bulk_load(load_id)
+            .await
+            .map_err(BoxedError::new)
+            .context(server_error::ExecuteGrpcQuerySnafu)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use common_query::Output;
+    use common_recordbatch::RecordBatches;
+    use datatypes::prelude::ConcreteDataType;
+    use datatypes::schema::{ColumnSchema, Schema};
+    use datatypes::vectors::{StringVector, TimestampMillisecondVector, VectorRef};
+    use servers::query_handler::sql::SqlQueryHandler;
+    use session::context::QueryContext;
+
+    use super::*;
+    use crate::tests;
+
+    fn new_record_batch(hosts: Vec<&str>, ts: &[i64]) -> RecordBatch {
+        let schema = Arc::new(Schema::new(vec![
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), true),
+            ColumnSchema::new(
+                "ts",
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                true,
+            ),
+        ]));
+        let columns: Vec<VectorRef> = vec![
+            Arc::new(StringVector::from(hosts)),
+            Arc::new(TimestampMillisecondVector::from_slice(ts)),
+        ];
+        RecordBatch::new(schema, columns).unwrap()
+    }
+
+    async fn do_query(instance: &Instance, sql: &str) -> Output {
+        SqlQueryHandler::do_query(instance, sql, QueryContext::arc())
+            .await
+            .remove(0)
+            .unwrap()
+    }
+
+    async fn query_hosts(instance: &Instance) -> String {
+        let output = do_query(instance, "SELECT host FROM demo ORDER BY ts").await;
+        let Output::Stream(stream) = output else { unreachable!() };
+        let recordbatches = RecordBatches::try_collect(stream).await.unwrap();
+        recordbatches.pretty_print().unwrap()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_standalone_bulk_load() {
+        let standalone = tests::create_standalone_instance("test_standalone_bulk_load").await;
+        let instance = standalone.instance.as_ref();
+
+        let sql = "CREATE TABLE demo(host STRING, ts TIMESTAMP TIME INDEX, PRIMARY KEY(host))";
+        let output = do_query(instance, sql).await;
+        assert!(matches!(output, Output::AffectedRows(0)));
+
+        let load_id = instance
+            .begin_bulk_load("demo", QueryContext::arc())
+            .await
+            .unwrap();
+        let batch = new_record_batch(vec!["host2", "host3"], &[2000, 3000]);
+        let rows = instance.write_bulk_load(&load_id, 0, batch).await.unwrap();
+        assert_eq!(2, rows);
+        // Normal inserts are visible at once, while the bulk load is invisible until committed.
+        let output = do_query(instance, "INSERT INTO demo VALUES ('host1', 1000)").await;
+        assert!(matches!(output, Output::AffectedRows(1)));
+        let expected = "\
++-------+
+| host  |
++-------+
+| host1 |
++-------+";
+        assert_eq!(expected, query_hosts(instance).await);
+
+        instance.commit_bulk_load(&load_id).await.unwrap();
+        let expected = "\
++-------+
+| host  |
++-------+
+| host1 |
+| host2 |
+| host3 |
++-------+";
+        assert_eq!(expected, query_hosts(instance).await);
+
+        // Committed bulk loads are gone.
+        let batch = new_record_batch(vec!["host4"], &[4000]);
+        let err = instance.write_bulk_load(&load_id, 0, batch).await.unwrap_err();
+        assert!(err.to_string().contains("not found"), "{err}");
+
+        // Aborted bulk loads are discarded.
+        let load_id = instance
+            .begin_bulk_load("demo", QueryContext::arc())
+            .await
+            .unwrap();
+        let batch = new_record_batch(vec!["host4"], &[4000]);
+        let _ = instance.write_bulk_load(&load_id, 0, batch).await.unwrap();
+        instance.abort_bulk_load(&load_id).await.unwrap();
+        assert_eq!(expected, query_hosts(instance).await);
+        assert!(instance.commit_bulk_load(&load_id).await.is_err());
+
+        let err = instance
+            .begin_bulk_load("not_exist", QueryContext::arc())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("not exist"), "{err}");
+    }
+}
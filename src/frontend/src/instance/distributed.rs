@@ -45,25 +45,25 @@ use partition::partition::{PartitionBound, PartitionDef};
 use query::error::QueryExecutionSnafu;
 use query::parser::QueryStatement;
 use query::query_engine::StatementHandler;
-use query::sql::{describe_table, show_databases, show_tables};
+use query::sql::{describe_table, show_create_table, show_databases, show_tables};
 use session::context::QueryContextRef;
 use snafu::{ensure, OptionExt, ResultExt};
-use sql::ast::Value as SqlValue;
-use sql::statements::create::Partitions;
-use sql::statements::sql_value_to_value;
+use sql::ast::{Ident, Value as SqlValue};
+use sql::statements::create::{PartitionEntry, Partitions};
 use sql::statements::statement::Statement;
+use sql::statements::{sql_value_to_value, value_to_sql_value};
 use table::metadata::{RawTableInfo, RawTableMeta, TableIdent, TableType};
-use table::requests::TableOptions;
+use table::requests::{AlterKind, TableOptions};
 use table::table::AlterContext;
 
 use crate::catalog::FrontendCatalogManager;
 use crate::datanode::DatanodeClients;
 use crate::error::{
     self, AlterExprToRequestSnafu, CatalogEntrySerdeSnafu, CatalogSnafu, ColumnDataTypeSnafu,
-    DeserializePartitionSnafu, NotSupportedSnafu, ParseSqlSnafu, PrimaryKeyNotFoundSnafu,
-    RequestDatanodeSnafu, RequestMetaSnafu, Result, SchemaExistsSnafu, StartMetaClientSnafu,
-    TableAlreadyExistSnafu, TableNotFoundSnafu, TableSnafu, ToTableInsertRequestSnafu,
-    UnrecognizedTableOptionSnafu,
+    DeserializePartitionSnafu, FindTableRouteSnafu, NotSupportedSnafu, ParseSqlSnafu,
+    PrimaryKeyNotFoundSnafu, RequestDatanodeSnafu, RequestMetaSnafu, Result, SchemaExistsSnafu,
+    StartMetaClientSnafu, TableAlreadyExistSnafu, TableNotFoundSnafu, TableSnafu,
+    ToTableInsertRequestSnafu, UnrecognizedTableOptionSnafu,
 };
 use crate::expr_factory;
 use crate::sql::insert_to_request;
@@ -201,8 +201,16 @@ impl DistInstance {
         Ok(Output::AffectedRows(0))
     }
 
-    async fn drop_table(&self, table_name: TableName) -> Result<Output> {
-        let _ = self
+    /// Drops the table on the metasrv and all datanodes, a missing table is not an error
+    /// if `drop_if_exists` is true. If `sync` is true, returns after the datanodes delete
+    /// the data of the table.
+    async fn drop_table(
+        &self,
+        table_name: TableName,
+        drop_if_exists: bool,
+        sync: bool,
+    ) -> Result<Output> {
+        let table = self
             .catalog_manager
             .table(
                 &table_name.catalog_name,
@@ -210,10 +218,16 @@ impl DistInstance {
                 &table_name.table_name,
             )
             .await
-            .context(CatalogSnafu)?
-            .with_context(|| TableNotFoundSnafu {
+            .context(CatalogSnafu)?;
+        if table.is_none() && drop_if_exists {
+            return Ok(Output::AffectedRows(0));
+        }
+        ensure!(
+            table.is_some(),
+            TableNotFoundSnafu {
                 table_name: table_name.to_string(),
-            })?;
+            }
+        );
 
         let route_response = self
             .meta_client
@@ -249,10 +263,12 @@ impl DistInstance {
 
                 let client = self.datanode_clients.get_client(&datanode).await;
                 let client = Database::new(&expr.catalog_name, &expr.schema_name, client);
-                client
-                    .drop_table(expr.clone())
-                    .await
-                    .context(RequestDatanodeSnafu)?;
+                let result = if sync {
+                    client.drop_table_sync(expr.clone()).await
+                } else {
+                    client.drop_table(expr.clone()).await
+                };
+                result.context(RequestDatanodeSnafu)?;
             }
         }
 
@@ -336,12 +352,15 @@ impl DistInstance {
                 return self.handle_alter_table(expr).await;
             }
             Statement::DropTable(stmt) => {
+                let sync = stmt.sync() || query_ctx.variables().drop_table_sync;
                 let (catalog, schema, table) =
                     table_idents_to_full_name(stmt.table_name(), query_ctx)
                         .map_err(BoxedError::new)
                         .context(error::ExternalSnafu)?;
                 let table_name = TableName::new(catalog, schema, table);
-                return self.drop_table(table_name).await;
+                return self
+                    .drop_table(table_name, stmt.drop_if_exists(), sync)
+                    .await;
             }
             Statement::ShowDatabases(stmt) => show_databases(stmt, self.catalog_manager.clone()),
             Statement::ShowTables(stmt) => {
@@ -361,6 +380,27 @@ impl DistInstance {
                     })?;
                 describe_table(table)
             }
+            Statement::ShowCreateTable(stmt) => {
+                let (catalog, schema, table) =
+                    table_idents_to_full_name(&stmt.table_name, query_ctx)
+                        .map_err(BoxedError::new)
+                        .context(error::ExternalSnafu)?;
+                let table_name = TableName::new(catalog, schema, table);
+                let table = self
+                    .catalog_manager
+                    .table(
+                        &table_name.catalog_name,
+                        &table_name.schema_name,
+                        &table_name.table_name,
+                    )
+                    .await
+                    .context(CatalogSnafu)?
+                    .with_context(|| TableNotFoundSnafu {
+                        table_name: stmt.table_name.to_string(),
+                    })?;
+                let partitions = self.table_partitions(&table_name).await?;
+                show_create_table(table, partitions)
+            }
             Statement::Insert(insert) => {
                 let (catalog, schema, table) =
                     table_idents_to_full_name(insert.table_name(), query_ctx.clone())
@@ -390,6 +430,66 @@ impl DistInstance {
         .context(error::ExecuteStatementSnafu)
     }
 
+    /// Returns the partitions of the table in the form of the `PARTITION BY` clause, or `None`
+    /// if the table is not partitioned by the user (it has only one unbounded partition).
+    async fn table_partitions(&self, table_name: &TableName) -> Result<Option<Partitions>> {
+        let route = self
+            .catalog_manager
+            .partition_manager()
+            .find_table_route(table_name)
+            .await
+            .with_context(|_| FindTableRouteSnafu {
+                table_name: table_name.to_string(),
+            })?;
+
+        let mut partitions = route
+            .region_routes
+            .iter()
+            .filter_map(|r| r.region.partition.clone())
+            .map(PartitionDef::try_from)
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .context(DeserializePartitionSnafu)?;
+        partitions.sort_by(|a, b| a.partition_bounds().cmp(b.partition_bounds()));
+
+        let Some(first) = partitions.first() else {
+            return Ok(None);
+        };
+        if partitions.len() == 1
+            && first
+                .partition_bounds()
+                .iter()
+                .all(|b| *b == PartitionBound::MaxValue)
+        {
+            return Ok(None);
+        }
+
+        let column_list = first
+            .partition_columns()
+            .iter()
+            .map(|c| Ident::new(c.as_str()))
+            .collect();
+        let entries = partitions
+            .iter()
+            .enumerate()
+            .map(|(i, p)| PartitionEntry {
+                name: Ident::new(format!("r{i}")),
+                value_list: p
+                    .partition_bounds()
+                    .iter()
+                    .map(|b| match b {
+                        PartitionBound::Value(v) => value_to_sql_value(v)
+                            .unwrap_or_else(|| SqlValue::SingleQuotedString(v.to_string())),
+                        PartitionBound::MaxValue => SqlValue::Number("MAXVALUE".to_string(), false),
+                    })
+                    .collect(),
+            })
+            .collect();
+        Ok(Some(Partitions {
+            column_list,
+            entries,
+        }))
+    }
+
     /// Handles distributed database creation
     async fn handle_create_database(
         &self,
@@ -464,6 +564,21 @@ impl DistInstance {
         let request = common_grpc_expr::alter_expr_to_request(expr.clone())
             .context(AlterExprToRequestSnafu)?;
 
+        if let AlterKind::RenameTable { new_table_name } = &request.alter_kind {
+            let exists = self
+                .catalog_manager
+                .table(catalog_name, schema_name, new_table_name)
+                .await
+                .context(CatalogSnafu)?
+                .is_some();
+            ensure!(
+                !exists,
+                TableAlreadyExistSnafu {
+                    table: format_full_table_name(catalog_name, schema_name, new_table_name),
+                }
+            );
+        }
+
         let mut context = AlterContext::with_capacity(1);
         context.insert(expr);
 
@@ -673,7 +788,8 @@ fn find_partition_entries(
                 let v = match v {
                     SqlValue::Number(n, _) if n == "MAXVALUE" => PartitionBound::MaxValue,
                     _ => PartitionBound::Value(
-                        sql_value_to_value(column_name, data_type, v).context(ParseSqlSnafu)?,
+                        sql_value_to_value(column_name, data_type, v, None)
+                            .context(ParseSqlSnafu)?,
                     ),
                 };
                 values.push(v);
@@ -859,4 +975,80 @@ ENGINE=mito",
             assert_show_tables(x.clone()).await
         }
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_show_create_table_round_trip() {
+        let instance =
+            crate::tests::create_distributed_instance("test_show_create_table_round_trip").await;
+        let dist_instance = &instance.dist_instance;
+
+        let sql = r#"
+            CREATE TABLE "Monitor" (
+                host STRING DEFAULT 'it''s',
+                "select" DOUBLE DEFAULT 0.5,
+                n INT,
+                ts TIMESTAMP(6) DEFAULT CURRENT_TIMESTAMP(),
+                TIME INDEX (ts),
+                PRIMARY KEY (n, host),
+            )
+            PARTITION BY RANGE COLUMNS (n) (
+                PARTITION r0 VALUES LESS THAN (10),
+                PARTITION r1 VALUES LESS THAN (MAXVALUE),
+            )
+            ENGINE=mito
+            WITH (ttl='7days', write_buffer_size='1024KB')"#;
+        handle_sql(dist_instance, sql).await;
+
+        async fn show_create_table(
+            instance: &Arc<DistInstance>,
+            query_ctx: QueryContextRef,
+        ) -> String {
+            let stmt = parse_stmt(r#"SHOW CREATE TABLE "Monitor""#)
+                .unwrap()
+                .remove(0);
+            let Output::RecordBatches(batches) =
+                instance.handle_statement(stmt, query_ctx).await.unwrap() else {
+                unreachable!()
+            };
+            let batch = batches.take().remove(0);
+            batch.column(1).get(0).to_string()
+        }
+        let create_sql = show_create_table(dist_instance, QueryContext::arc()).await;
+        assert!(
+            create_sql.contains("PARTITION BY RANGE COLUMNS (n)"),
+            "{create_sql}"
+        );
+
+        // Executes the shown statement in another database, which creates an identical table.
+        handle_sql(dist_instance, "CREATE DATABASE round_trip").await;
+        let query_ctx = Arc::new(QueryContext::with(DEFAULT_CATALOG_NAME, "round_trip"));
+        let stmt = parse_stmt(&create_sql).unwrap().remove(0);
+        dist_instance
+            .handle_statement(stmt, query_ctx.clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            create_sql,
+            show_create_table(dist_instance, query_ctx).await
+        );
+
+        let table = |schema: &'static str| async move {
+            dist_instance
+                .catalog_manager
+                .table(DEFAULT_CATALOG_NAME, schema, "Monitor")
+                .await
+                .unwrap()
+                .unwrap()
+                .table_info()
+        };
+        let expected = table(DEFAULT_SCHEMA_NAME).await;
+        let actual = table("round_trip").await;
+        assert_eq!(expected.meta.schema, actual.meta.schema);
+        assert_eq!(
+            expected.meta.primary_key_indices,
+            actual.meta.primary_key_indices
+        );
+        assert_eq!(expected.meta.options, actual.meta.options);
+        assert_eq!(expected.meta.engine, actual.meta.engine);
+    }
 }
@@ -55,7 +55,8 @@ impl GrpcQueryHandler for DistInstance {
                     DdlExpr::DropTable(expr) => {
                         let table_name =
                             TableName::new(&expr.catalog_name, &expr.schema_name, &expr.table_name);
-                        self.drop_table(table_name).await
+                        let sync = ctx.variables().drop_table_sync;
+                        self.drop_table(table_name, false, sync).await
                     }
                     DdlExpr::FlushTable(expr) => {
                         let table_name =
@@ -102,6 +103,14 @@ pub(crate) fn to_alter_expr(
         AlterTableOperation::RenameTable { new_table_name } => Kind::RenameTable(RenameTable {
             new_table_name: new_table_name.to_string(),
         }),
+        AlterTableOperation::SetTtl { ttl } => common_grpc_expr::set_ttl_kind(Some(ttl)),
+        AlterTableOperation::UnsetTtl => common_grpc_expr::set_ttl_kind(None),
+        AlterTableOperation::SetTableOptions { .. } => {
+            return error::NotSupportedSnafu {
+                feat: "ALTER TABLE SET table options",
+            }
+            .fail();
+        }
     };
 
     Ok(AlterExpr {
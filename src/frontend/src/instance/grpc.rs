@@ -24,6 +24,7 @@ use snafu::{ensure, OptionExt};
 
 use crate::error::{self, Result};
 use crate::instance::Instance;
+use crate::query_cache::Writes;
 
 #[async_trait]
 impl GrpcQueryHandler for Instance {
@@ -61,6 +62,7 @@ impl GrpcQueryHandler for Instance {
                             start: promql.start,
                             end: promql.end,
                             step: promql.step,
+                            lookback: None,
                         };
                         let mut result =
                             SqlQueryHandler::do_promql_query(self, &prom_query, ctx).await;
@@ -76,7 +78,10 @@ impl GrpcQueryHandler for Instance {
             }
             Request::Ddl(request) => {
                 let query = Request::Ddl(request);
-                GrpcQueryHandler::do_query(&*self.grpc_query_handler, query, ctx).await?
+                let output =
+                    GrpcQueryHandler::do_query(&*self.grpc_query_handler, query, ctx).await;
+                self.record_writes(|| Some(Writes::All));
+                output?
             }
         };
         Ok(output)
@@ -86,6 +91,7 @@ impl GrpcQueryHandler for Instance {
 #[cfg(test)]
 mod test {
     use std::collections::HashMap;
+    use std::sync::Arc;
 
     use api::v1::column::{SemanticType, Values};
     use api::v1::ddl_request::Expr as DdlExpr;
@@ -95,8 +101,12 @@ mod test {
         InsertRequest, QueryRequest,
     };
     use catalog::helper::{TableGlobalKey, TableGlobalValue};
+    use client::Database;
+    use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
+    use common_grpc::compression::GrpcCompression;
     use common_query::Output;
     use common_recordbatch::RecordBatches;
+    use datanode::instance::Instance as DatanodeInstance;
     use query::parser::QueryLanguageParser;
     use session::context::QueryContext;
     use tests::{has_parquet_file, test_region_dir};
@@ -784,4 +794,85 @@ CREATE TABLE {table_name} (
 +---+------+---------------------+";
         assert_eq!(recordbatches.pretty_print().unwrap(), expected);
     }
+
+    async fn collect_sql(database: &Database, sql: &str) -> RecordBatches {
+        match database.sql(sql).await.unwrap() {
+            Output::RecordBatches(recordbatches) => recordbatches,
+            Output::Stream(stream) => RecordBatches::try_collect(stream).await.unwrap(),
+            Output::AffectedRows(_) => unreachable!(),
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_grpc_compression() {
+        let (opts, _guard) = tests::create_tmp_dir_and_datanode_opts("test_grpc_compression");
+        let datanode = Arc::new(DatanodeInstance::new(&opts).await.unwrap());
+        datanode.start().await.unwrap();
+
+        // About 4MiB of highly compressible data.
+        let rows = 50_000;
+        let hosts: Vec<String> = (0..rows).map(|i| format!("{:0>64}", i % 100)).collect();
+        let compressions = [
+            GrpcCompression::None,
+            GrpcCompression::Gzip,
+            GrpcCompression::Zstd,
+        ];
+        // Every pair of settings works, including mismatched ones.
+        for server_compression in compressions {
+            for client_compression in compressions {
+                let (_, client) = tests::create_datanode_client_with_compression(
+                    datanode.clone(),
+                    server_compression,
+                    client_compression,
+                )
+                .await;
+                let database = Database::new(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, client);
+                let table_name =
+                    format!("compressed_{server_compression:?}_{client_compression:?}")
+                        .to_lowercase();
+                let output = database
+                    .sql(&format!(
+                        "CREATE TABLE {table_name} (host STRING, ts TIMESTAMP TIME INDEX, PRIMARY KEY (host))"
+                    ))
+                    .await
+                    .unwrap();
+                assert!(matches!(output, Output::AffectedRows(0)));
+
+                let insert = InsertRequest {
+                    table_name: table_name.clone(),
+                    columns: vec![
+                        Column {
+                            column_name: "host".to_string(),
+                            values: Some(Values {
+                                string_values: hosts.clone(),
+                                ..Default::default()
+                            }),
+                            semantic_type: SemanticType::Tag as i32,
+                            datatype: ColumnDataType::String as i32,
+                            ..Default::default()
+                        },
+                        Column {
+                            column_name: "ts".to_string(),
+                            values: Some(Values {
+                                ts_millisecond_values: (0..rows as i64).collect(),
+                                ..Default::default()
+                            }),
+                            semantic_type: SemanticType::Timestamp as i32,
+                            datatype: ColumnDataType::TimestampMillisecond as i32,
+                            ..Default::default()
+                        },
+                    ],
+                    row_count: rows as u32,
+                    ..Default::default()
+                };
+                assert_eq!(rows as u32, database.insert(insert).await.unwrap());
+
+                // Reads all rows back so the response is large as well.
+                let recordbatches =
+                    collect_sql(&database, &format!("SELECT host, ts FROM {table_name}")).await;
+                let num_rows: usize = recordbatches.iter().map(|b| b.num_rows()).sum();
+                assert_eq!(rows, num_rows);
+            }
+        }
+    }
 }
@@ -34,6 +34,19 @@ impl OpentsdbProtocolHandler for Instance {
             })?;
         Ok(())
     }
+
+    async fn exec_batch(
+        &self,
+        data_points: &[DataPoint],
+        ctx: QueryContextRef,
+    ) -> server_error::Result<()> {
+        let requests = DataPoint::to_grpc_inserts(data_points)?;
+        self.handle_inserts(requests, ctx)
+            .await
+            .map_err(BoxedError::new)
+            .context(server_error::ExecuteGrpcQuerySnafu)?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -125,5 +138,45 @@ mod tests {
             }
             _ => unreachable!(),
         };
+
+        let data_points = vec![
+            DataPoint::new(
+                "my_metric_2".to_string(),
+                1000,
+                1.0,
+                vec![("cpu.core".to_string(), "0".to_string())],
+            ),
+            DataPoint::new(
+                "my_metric_2".to_string(),
+                2000,
+                2.0,
+                vec![("cpu.core".to_string(), "1".to_string())],
+            ),
+        ];
+        // should escape the tag key and insert all data points in one request
+        let result = instance.exec_batch(&data_points, ctx.clone()).await;
+        assert!(result.is_ok());
+
+        let output = instance
+            .do_query(
+                "select * from my_metric_2 order by greptime_timestamp",
+                Arc::new(QueryContext::new()),
+            )
+            .await
+            .remove(0)
+            .unwrap();
+        let Output::Stream(stream) = output else { unreachable!() };
+        let recordbatches = RecordBatches::try_collect(stream).await.unwrap();
+        let expected = vec![
+            "+---------------------+----------------+--------------+",
+            "| greptime_timestamp  | greptime_value | cpu_x2e_core |",
+            "+---------------------+----------------+--------------+",
+            "| 1970-01-01T00:00:01 | 1.0            | 0            |",
+            "| 1970-01-01T00:00:02 | 2.0            | 1            |",
+            "+---------------------+----------------+--------------+",
+        ]
+        .into_iter()
+        .join("\n");
+        assert_eq!(recordbatches.pretty_print().unwrap(), expected);
     }
 }
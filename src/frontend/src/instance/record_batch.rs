@@ -0,0 +1,180 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use async_trait::async_trait;
+use common_error::prelude::BoxedError;
+use common_grpc_expr::insert::record_batch_to_table_insert_request;
+use common_recordbatch::RecordBatch;
+use servers::error as server_error;
+use servers::query_handler::RecordBatchInsertHandler;
+use session::context::QueryContextRef;
+use snafu::prelude::*;
+
+use crate::error::{self, Result};
+use crate::instance::Instance;
+use crate::query_cache::Writes;
+
+impl Instance {
+    async fn handle_record_batch_insert(
+        &self,
+        table_name: &str,
+        region_number: u32,
+        batch: RecordBatch,
+        ctx: QueryContextRef,
+    ) -> Result<usize> {
+        let catalog_name = &ctx.current_catalog();
+        let schema_name = &ctx.current_schema();
+        let table = self
+            .catalog_manager
+            .table(catalog_name, schema_name, table_name)
+            .await
+            .context(error::CatalogSnafu)?
+            .with_context(|| error::TableNotFoundSnafu {
+                table_name: format!("{catalog_name}.{schema_name}.{table_name}"),
+            })?;
+
+        let request = record_batch_to_table_insert_request(
+            catalog_name,
+            schema_name,
+            table_name,
+            region_number,
+            &table.schema(),
+            &batch,
+        )
+        .context(error::RecordBatchToInsertRequestSnafu)?;
+        let output = table.insert(request).await.context(error::TableSnafu);
+        self.record_writes(|| {
+            Some(Writes::Table {
+                catalog: catalog_name.clone(),
+                schema: schema_name.clone(),
+                table: table_name.to_string(),
+            })
+        });
+        output
+    }
+}
+
+#[async_trait]
+impl RecordBatchInsertHandler for Instance {
+    async fn insert_record_batch(
+        &self,
+        table_name: &str,
+        region_number: u32,
+        batch: RecordBatch,
+        ctx: QueryContextRef,
+    ) -> server_error::Result<usize> {
+        self.handle_record_batch_insert(table_name, region_number, batch, ctx)
+            .await
+            .map_err(BoxedError::new)
+            .context(server_error::ExecuteGrpcQuerySnafu)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use common_query::Output;
+    use common_recordbatch::RecordBatches;
+    use datatypes::schema::{ColumnSchema, Schema};
+    use datatypes::vectors::{Int64Vector, StringVector, TimestampMillisecondVector, VectorRef};
+    use servers::query_handler::sql::SqlQueryHandler;
+    use session::context::QueryContext;
+
+    use super::*;
+    use crate::tests;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_standalone_insert_record_batch() {
+        let standalone =
+            tests::create_standalone_instance("test_standalone_insert_record_batch").await;
+        let instance = &standalone.instance;
+
+        test_insert_record_batch(instance).await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_distributed_insert_record_batch() {
+        let instance =
+            tests::create_distributed_instance("test_distributed_insert_record_batch").await;
+        let instance = &instance.frontend;
+
+        test_insert_record_batch(instance).await;
+    }
+
+    fn new_record_batch(columns: Vec<(&str, VectorRef)>) -> RecordBatch {
+        let column_schemas = columns
+            .iter()
+            .map(|(name, vector)| ColumnSchema::new(*name, vector.data_type(), true))
+            .collect();
+        let schema = Arc::new(Schema::new(column_schemas));
+        RecordBatch::new(schema, columns.into_iter().map(|(_, vector)| vector)).unwrap()
+    }
+
+    async fn test_insert_record_batch(instance: &Arc<Instance>) {
+        let sql =
+            "CREATE TABLE demo(host STRING, cpu INT, ts TIMESTAMP TIME INDEX, PRIMARY KEY(host))";
+        let output = SqlQueryHandler::do_query(instance.as_ref(), sql, QueryContext::arc())
+            .await
+            .remove(0)
+            .unwrap();
+        assert!(matches!(output, Output::AffectedRows(0)));
+
+        // Int64 cpu values are cast to the Int32 column.
+        let batch = new_record_batch(vec![
+            (
+                "host",
+                Arc::new(StringVector::from(vec!["host1", "host2"])) as _,
+            ),
+            ("cpu", Arc::new(Int64Vector::from_slice([1, 2])) as _),
+            (
+                "ts",
+                Arc::new(TimestampMillisecondVector::from_slice([1000, 2000])) as _,
+            ),
+        ]);
+        let rows = instance
+            .insert_record_batch("demo", 0, batch, QueryContext::arc())
+            .await
+            .unwrap();
+        assert_eq!(2, rows);
+
+        let batch = new_record_batch(vec![("cpu", Arc::new(StringVector::from(vec!["3"])) as _)]);
+        let err = instance
+            .insert_record_batch("demo", 0, batch, QueryContext::arc())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("incompatible"), "{err}");
+
+        let output = SqlQueryHandler::do_query(
+            instance.as_ref(),
+            "SELECT ts, host, cpu FROM demo ORDER BY ts",
+            QueryContext::arc(),
+        )
+        .await
+        .remove(0)
+        .unwrap();
+        let Output::Stream(stream) = output else { unreachable!() };
+        let recordbatches = RecordBatches::try_collect(stream).await.unwrap();
+        assert_eq!(
+            recordbatches.pretty_print().unwrap(),
+            "\
++---------------------+-------+-----+
+| ts                  | host  | cpu |
++---------------------+-------+-----+
+| 1970-01-01T00:00:01 | host1 | 1   |
+| 1970-01-01T00:00:02 | host2 | 2   |
++---------------------+-------+-----+"
+        );
+    }
+}
@@ -22,11 +22,14 @@ pub mod frontend;
 pub mod grpc;
 pub mod influxdb;
 pub mod instance;
+mod metric;
 pub mod mysql;
 pub mod opentsdb;
 pub mod postgres;
+pub mod process;
 pub mod prom;
 pub mod prometheus;
+pub mod query_cache;
 mod server;
 mod sql;
 mod table;
@@ -0,0 +1,386 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Registry of the statements being executed by the frontend, which backs
+//! `SHOW PROCESSLIST` and `KILL QUERY`.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use common_error::ext::BoxedError;
+use common_recordbatch::error::ExternalSnafu;
+use common_recordbatch::scan_stats::ScanStats;
+use common_recordbatch::{RecordBatch, RecordBatchStream, SendableRecordBatchStream};
+use common_telemetry::info;
+use common_time::util::current_time_millis;
+use datatypes::prelude::ConcreteDataType;
+use datatypes::schema::{ColumnSchema, SchemaRef};
+use futures::Stream;
+use session::context::{QueryContextRef, UserInfo};
+use snafu::{ensure, ResultExt};
+use tokio::time::Sleep;
+use tokio_util::sync::CancellationToken;
+
+use crate::error::{self, Result};
+
+/// Max number of characters of a query shown by `SHOW PROCESSLIST` without `FULL`.
+const TRUNCATED_QUERY_LEN: usize = 100;
+
+tokio::task_local! {
+    /// The process of the statement being executed by the current task.
+    static CURRENT_PROCESS: ProcessRef;
+}
+
+pub type ProcessRef = Arc<Process>;
+pub type ProcessManagerRef = Arc<ProcessManager>;
+
+/// A statement being executed.
+#[derive(Debug)]
+pub struct Process {
+    id: u64,
+    user: String,
+    catalog: String,
+    schema: String,
+    query: String,
+    protocol: String,
+    /// Start time in milliseconds since the Unix epoch.
+    start_time: i64,
+    started: Instant,
+    /// Addresses of the datanodes scanned by the statement.
+    datanodes: Mutex<BTreeSet<String>>,
+    cancel: CancellationToken,
+}
+
+impl Process {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn user(&self) -> &str {
+        &self.user
+    }
+
+    pub fn catalog(&self) -> &str {
+        &self.catalog
+    }
+
+    pub fn schema(&self) -> &str {
+        &self.schema
+    }
+
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// The query truncated to at most [TRUNCATED_QUERY_LEN] characters.
+    pub fn truncated_query(&self) -> &str {
+        match self.query.char_indices().nth(TRUNCATED_QUERY_LEN) {
+            Some((end, _)) => &self.query[..end],
+            None => &self.query,
+        }
+    }
+
+    pub fn protocol(&self) -> &str {
+        &self.protocol
+    }
+
+    pub fn start_time(&self) -> i64 {
+        self.start_time
+    }
+
+    pub fn elapsed_millis(&self) -> u64 {
+        self.started.elapsed().as_millis() as u64
+    }
+
+    pub fn datanodes(&self) -> Vec<String> {
+        self.datanodes.lock().unwrap().iter().cloned().collect()
+    }
+
+    pub fn is_killed(&self) -> bool {
+        self.cancel.is_cancelled()
+    }
+
+    /// Resolves once the process is killed.
+    pub async fn killed(&self) {
+        self.cancel.cancelled().await
+    }
+}
+
+/// Registry of the processes of a frontend, ids are unique within the frontend.
+#[derive(Debug, Default)]
+pub struct ProcessManager {
+    next_id: AtomicU64,
+    processes: RwLock<BTreeMap<u64, ProcessRef>>,
+}
+
+impl ProcessManager {
+    /// Registers the execution of `query`, which is deregistered once the returned guard
+    /// is dropped.
+    pub fn register(self: &Arc<Self>, query: &str, query_ctx: &QueryContextRef) -> ProcessGuard {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let process = Arc::new(Process {
+            id,
+            user: query_ctx.current_user().username().to_string(),
+            catalog: query_ctx.current_catalog(),
+            schema: query_ctx.current_schema(),
+            query: query.to_string(),
+            protocol: query_ctx
+                .channel()
+                .map(|channel| channel.to_string())
+                .unwrap_or_default(),
+            start_time: current_time_millis(),
+            started: Instant::now(),
+            datanodes: Mutex::default(),
+            cancel: CancellationToken::new(),
+        });
+        let _ = self.processes.write().unwrap().insert(id, process.clone());
+        ProcessGuard {
+            manager: self.clone(),
+            process,
+        }
+    }
+
+    /// Returns the processes visible to `user` ordered by id. Only the administrator
+    /// can see the processes of other users.
+    pub fn processes(&self, user: &UserInfo) -> Vec<ProcessRef> {
+        self.processes
+            .read()
+            .unwrap()
+            .values()
+            .filter(|p| user.is_admin() || p.user == user.username())
+            .cloned()
+            .collect()
+    }
+
+    /// Kills the process `id` on behalf of `user`, returns false if there's no such
+    /// process, e.g. it has just finished.
+    ///
+    /// Only the administrator can kill the processes of other users.
+    pub fn kill(&self, id: u64, user: &UserInfo) -> Result<bool> {
+        let Some(process) = self.processes.read().unwrap().get(&id).cloned() else {
+            return Ok(false);
+        };
+        ensure!(
+            user.is_admin() || process.user == user.username(),
+            error::KillQueryDeniedSnafu {
+                id,
+                user: user.username(),
+            }
+        );
+        info!(
+            "Kill query {}, user: {}, query: {}",
+            id,
+            user.username(),
+            process.truncated_query()
+        );
+        process.cancel.cancel();
+        Ok(true)
+    }
+
+    fn deregister(&self, id: u64) {
+        let _ = self.processes.write().unwrap().remove(&id);
+    }
+}
+
+/// Deregisters the process on drop.
+#[derive(Debug)]
+pub struct ProcessGuard {
+    manager: ProcessManagerRef,
+    process: ProcessRef,
+}
+
+impl ProcessGuard {
+    pub fn process(&self) -> &ProcessRef {
+        &self.process
+    }
+
+    /// Runs `fut` in the scope of the process, so the datanodes it scans are recorded.
+    pub async fn scope<F: Future>(&self, fut: F) -> F::Output {
+        CURRENT_PROCESS.scope(self.process.clone(), fut).await
+    }
+}
+
+impl Drop for ProcessGuard {
+    fn drop(&mut self) {
+        self.manager.deregister(self.process.id);
+    }
+}
+
+/// Records the datanodes scanned by the process of the current task, if any.
+pub(crate) fn record_datanodes<'a>(addrs: impl IntoIterator<Item = &'a str>) {
+    let _ = CURRENT_PROCESS.try_with(|process| {
+        process
+            .datanodes
+            .lock()
+            .unwrap()
+            .extend(addrs.into_iter().map(ToString::to_string));
+    });
+}
+
+/// Returns the column schemas of `SHOW PROCESSLIST`.
+pub(crate) fn processlist_column_schemas() -> Vec<ColumnSchema> {
+    vec![
+        ColumnSchema::new("Id", ConcreteDataType::uint64_datatype(), false),
+        ColumnSchema::new("User", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new("Catalog", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new("Schema", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new("Protocol", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new(
+            "Start_time",
+            ConcreteDataType::timestamp_millisecond_datatype(),
+            false,
+        ),
+        ColumnSchema::new("Elapsed_ms", ConcreteDataType::uint64_datatype(), false),
+        ColumnSchema::new("Datanodes", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new("Info", ConcreteDataType::string_datatype(), false),
+    ]
+}
+
+/// Stream of the output of a process, which keeps the process registered until the stream
+/// is dropped and ends with an error once the process is killed or its deadline elapses.
+///
+/// Dropping the inner stream also cancels the scans on the datanodes.
+pub(crate) struct ProcessStream {
+    schema: SchemaRef,
+    inner: Option<SendableRecordBatchStream>,
+    guard: ProcessGuard,
+    killed: Pin<Box<dyn Future<Output = ()> + Send>>,
+    /// Deadline of the statement and its timeout.
+    deadline: Option<(Pin<Box<Sleep>>, Duration)>,
+}
+
+impl ProcessStream {
+    pub(crate) fn new(inner: SendableRecordBatchStream, guard: ProcessGuard) -> Self {
+        let process = guard.process.clone();
+        Self {
+            schema: inner.schema(),
+            inner: Some(inner),
+            guard,
+            killed: Box::pin(async move { process.killed().await }),
+            deadline: None,
+        }
+    }
+
+    /// Ends the stream with a timeout error once `deadline` elapses, which is `timeout`
+    /// after the statement started.
+    pub(crate) fn with_deadline(mut self, deadline: Instant, timeout: Duration) -> Self {
+        self.deadline = Some((Box::pin(tokio::time::sleep_until(deadline.into())), timeout));
+        self
+    }
+}
+
+impl RecordBatchStream for ProcessStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn scan_stats(&self) -> Option<ScanStats> {
+        self.inner.as_ref().and_then(|inner| inner.scan_stats())
+    }
+}
+
+impl Stream for ProcessStream {
+    type Item = common_recordbatch::error::Result<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.inner.is_none() {
+            return Poll::Ready(None);
+        }
+        if let Some((deadline, timeout)) = &mut self.deadline {
+            if deadline.as_mut().poll(cx).is_ready() {
+                let timeout = *timeout;
+                self.inner = None;
+                return Poll::Ready(Some(
+                    Err(BoxedError::new(
+                        error::StatementTimeoutSnafu { timeout }.build(),
+                    ))
+                    .context(ExternalSnafu),
+                ));
+            }
+        }
+        if !self.guard.process.is_killed() {
+            if let Poll::Ready(item) = self.inner.as_mut().unwrap().as_mut().poll_next(cx) {
+                return Poll::Ready(item);
+            }
+            if self.killed.as_mut().poll(cx).is_pending() {
+                return Poll::Pending;
+            }
+        }
+
+        // Drops the inner stream to stop the execution as soon as possible.
+        self.inner = None;
+        let id = self.guard.process.id;
+        Poll::Ready(Some(
+            Err(BoxedError::new(error::QueryKilledSnafu { id }.build())).context(ExternalSnafu),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_error::prelude::{ErrorExt, StatusCode};
+    use common_recordbatch::util;
+    use datatypes::schema::Schema;
+    use session::context::QueryContext;
+
+    use super::*;
+
+    /// Stream that never yields a batch, like a scan waiting on a slow datanode.
+    struct SlowStream {
+        schema: SchemaRef,
+    }
+
+    impl RecordBatchStream for SlowStream {
+        fn schema(&self) -> SchemaRef {
+            self.schema.clone()
+        }
+    }
+
+    impl Stream for SlowStream {
+        type Item = common_recordbatch::error::Result<RecordBatch>;
+
+        fn poll_next(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            Poll::Pending
+        }
+    }
+
+    #[tokio::test]
+    async fn test_process_stream_deadline() {
+        let manager = Arc::new(ProcessManager::default());
+        let query_ctx = QueryContext::arc();
+        let guard = manager.register("SELECT * FROM demo", &query_ctx);
+        let timeout = Duration::from_millis(100);
+        let stream = ProcessStream::new(
+            Box::pin(SlowStream {
+                schema: Arc::new(Schema::new(vec![])),
+            }),
+            guard,
+        )
+        .with_deadline(Instant::now() + timeout, timeout);
+
+        let err = tokio::time::timeout(Duration::from_secs(10), util::collect(Box::pin(stream)))
+            .await
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(StatusCode::EngineExecuteQuery, err.status_code());
+        assert!(err.to_string().contains("Statement timed out"), "{err}");
+        // The process is deregistered once the stream is dropped.
+        assert!(manager.processes(&query_ctx.current_user()).is_empty());
+    }
+}
@@ -12,28 +12,67 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::time::Duration;
+
+use query::parser::DEFAULT_LOOKBACK_DELTA;
 use serde::{Deserialize, Serialize};
+use servers::prom::{PromQueryOptions, DEFAULT_MAX_STEPS};
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct PromOptions {
     pub addr: String,
+    /// Lookback delta of queries without the `lookback_delta` parameter.
+    #[serde(with = "humantime_serde")]
+    pub lookback_delta: Duration,
+    /// Max number of steps of a range query.
+    pub max_steps: u64,
 }
 
 impl Default for PromOptions {
     fn default() -> Self {
         Self {
             addr: "127.0.0.1:4004".to_string(),
+            lookback_delta: DEFAULT_LOOKBACK_DELTA,
+            max_steps: DEFAULT_MAX_STEPS,
+        }
+    }
+}
+
+impl PromOptions {
+    pub fn query_options(&self) -> PromQueryOptions {
+        PromQueryOptions {
+            lookback_delta: self.lookback_delta,
+            max_steps: self.max_steps,
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
     use super::PromOptions;
 
     #[test]
     fn test_prometheus_options() {
         let default = PromOptions::default();
         assert_eq!(default.addr, "127.0.0.1:4004".to_string());
+        assert_eq!(default.lookback_delta, Duration::from_secs(300));
+        assert_eq!(default.max_steps, 11_000);
+    }
+
+    #[test]
+    fn test_prometheus_options_toml() {
+        let opts: PromOptions = toml::from_str(
+            r#"
+            addr = "127.0.0.1:4005"
+            lookback_delta = "1m"
+        "#,
+        )
+        .unwrap();
+        assert_eq!(opts.addr, "127.0.0.1:4005");
+        assert_eq!(opts.lookback_delta, Duration::from_secs(60));
+        assert_eq!(opts.max_steps, 11_000);
     }
 }
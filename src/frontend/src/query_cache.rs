@@ -0,0 +1,568 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A cache of query results, for dashboards that run the same queries on every refresh.
+//!
+//! Results are keyed by the normalized SQL, the catalog, schema and time zone of the session,
+//! and the bucket of the current time, so a query relative to `now()` is cached for at most
+//! one bucket. Invalidation is coarse: the frontend records a sequence number of the last
+//! write to each table, and a cached result is only returned if none of the tables it reads
+//! were written since its query started.
+//!
+//! Only the writes through the frontend are observed. In distributed mode, writes through
+//! other frontends are not, so a result is cached for at most
+//! [QueryCacheOptions::distributed_ttl] there, which bounds how stale it could be.
+
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use common_base::readable_size::ReadableSize;
+use common_catalog::consts::INFORMATION_SCHEMA_NAME;
+use common_catalog::format_full_table_name;
+use common_query::Output;
+use common_recordbatch::error::Result as RecordBatchResult;
+use common_recordbatch::scan_stats::ScanStats;
+use common_recordbatch::{
+    RecordBatch, RecordBatchStream, RecordBatches, SendableRecordBatchStream,
+};
+use common_telemetry::logging::debug;
+use common_time::util::current_time_millis;
+use datafusion::datasource::DefaultTableSource;
+use datafusion_expr::expr_rewriter::{ExprRewritable, ExprRewriter};
+use datafusion_expr::{Expr, LogicalPlan as DfLogicalPlan};
+use datanode::instance::sql::table_idents_to_full_name;
+use datatypes::schema::SchemaRef;
+use futures::Stream;
+use metrics::increment_counter;
+use moka::sync::Cache;
+use query::plan::LogicalPlan;
+use serde::{Deserialize, Serialize};
+use session::context::QueryContextRef;
+use sql::statements::query::Query;
+use sql::statements::statement::Statement;
+use table::table::adapter::DfTableProviderAdapter;
+
+use crate::metric::{METRIC_QUERY_CACHE_HITS_TOTAL, METRIC_QUERY_CACHE_MISSES_TOTAL};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QueryCacheOptions {
+    pub enable: bool,
+    /// Max time a result is cached.
+    #[serde(with = "humantime_serde")]
+    pub ttl: Duration,
+    /// Max time a result is cached in distributed mode, which is also the max delay before
+    /// the writes through other frontends are visible in the cached results.
+    #[serde(with = "humantime_serde")]
+    pub distributed_ttl: Duration,
+    /// Max total size of the cached results, larger results are not cached.
+    pub max_memory: ReadableSize,
+    /// Width of the buckets of the current time. Results computed in different buckets are
+    /// cached separately, so queries relative to `now()` are refreshed on each bucket.
+    #[serde(with = "humantime_serde")]
+    pub time_bucket: Duration,
+}
+
+impl Default for QueryCacheOptions {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            ttl: Duration::from_secs(60),
+            distributed_ttl: Duration::from_secs(5),
+            max_memory: ReadableSize::mb(256),
+            time_bucket: Duration::from_secs(10),
+        }
+    }
+}
+
+impl QueryCacheOptions {
+    /// Returns the options of the cache in distributed mode, whose TTL is capped by
+    /// `distributed_ttl`.
+    pub fn distributed(&self) -> Self {
+        Self {
+            ttl: self.ttl.min(self.distributed_ttl),
+            ..self.clone()
+        }
+    }
+}
+
+pub type QueryCacheRef = Arc<QueryCache>;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct CacheKey {
+    sql: String,
+    catalog: String,
+    schema: String,
+    time_zone: Option<String>,
+    time_bucket: i64,
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    batches: RecordBatches,
+    /// Full names of the tables read by the query.
+    tables: Vec<String>,
+    /// Write sequence when the query started.
+    high_water_mark: u64,
+    size: usize,
+}
+
+/// Result of [QueryCache::lookup].
+pub(crate) enum Lookup {
+    /// The cached result, which is still valid.
+    Hit(RecordBatches),
+    /// The result is not cached, pass the query to [QueryCache::cache_stream] to cache it.
+    Miss(CacheableQuery),
+    /// The result of the query can't be cached.
+    Uncacheable,
+}
+
+pub(crate) struct CacheableQuery {
+    key: CacheKey,
+    tables: Vec<String>,
+    high_water_mark: u64,
+}
+
+/// Tables written by a statement.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Writes {
+    Table {
+        catalog: String,
+        schema: String,
+        table: String,
+    },
+    /// The statement may write any table.
+    All,
+}
+
+impl Writes {
+    /// Returns the tables the statement writes to, or None if it's read only or only creates
+    /// objects, which no cached result could read.
+    pub(crate) fn of_statement(stmt: &Statement, query_ctx: &QueryContextRef) -> Option<Self> {
+        let table_name = match stmt {
+            Statement::Insert(insert) => insert.table_name(),
+            Statement::Delete(delete) => delete.table_name(),
+            Statement::Alter(alter) => alter.table_name(),
+            Statement::DropTable(drop) => drop.table_name(),
+            Statement::Copy(_) => return Some(Writes::All),
+            _ => return None,
+        };
+        let writes = match table_idents_to_full_name(table_name, query_ctx.clone()) {
+            Ok((catalog, schema, table)) => Writes::Table {
+                catalog,
+                schema,
+                table,
+            },
+            Err(_) => Writes::All,
+        };
+        Some(writes)
+    }
+}
+
+pub struct QueryCache {
+    options: QueryCacheOptions,
+    entries: Cache<CacheKey, Arc<CacheEntry>>,
+    writes: WriteTracker,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl QueryCache {
+    pub fn new(options: QueryCacheOptions) -> Self {
+        let entries = Cache::builder()
+            .max_capacity(options.max_memory.0)
+            .weigher(|_, entry: &Arc<CacheEntry>| entry.size.try_into().unwrap_or(u32::MAX))
+            .time_to_live(options.ttl)
+            .build();
+        Self {
+            options,
+            entries,
+            writes: WriteTracker::default(),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Records a write to the table, which invalidates the results that read it.
+    pub fn record_write(&self, catalog: &str, schema: &str, table: &str) {
+        self.writes
+            .record(Some(&format_full_table_name(catalog, schema, table)));
+    }
+
+    /// Records a write that may change any table, such as a DDL, which invalidates all the
+    /// results.
+    pub fn record_write_all(&self) {
+        self.writes.record(None);
+    }
+
+    /// Looks up the result of the `query` planned as `plan`.
+    pub(crate) fn lookup(
+        &self,
+        query: &Query,
+        plan: &LogicalPlan,
+        query_ctx: &QueryContextRef,
+    ) -> Lookup {
+        // The parameters of a prepared statement are not part of the SQL.
+        if !query.param_types().is_empty() {
+            return Lookup::Uncacheable;
+        }
+        let LogicalPlan::DfPlan(plan) = plan;
+        let Some(tables) = scanned_tables(plan) else { return Lookup::Uncacheable };
+
+        let bucket_millis = self.options.time_bucket.as_millis().max(1) as i64;
+        let key = CacheKey {
+            sql: query.inner.to_string(),
+            catalog: query_ctx.current_catalog(),
+            schema: query_ctx.current_schema(),
+            time_zone: query_ctx.time_zone().map(|tz| tz.to_string()),
+            time_bucket: current_time_millis() / bucket_millis,
+        };
+        // Takes the high-water mark before checking the entry, so a write racing with the
+        // query invalidates its result.
+        let high_water_mark = self.writes.high_water_mark();
+
+        if let Some(entry) = self.entries.get(&key) {
+            if self.writes.last_write(&entry.tables) <= entry.high_water_mark {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                increment_counter!(METRIC_QUERY_CACHE_HITS_TOTAL);
+                return Lookup::Hit(entry.batches.clone());
+            }
+            self.entries.invalidate(&key);
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        increment_counter!(METRIC_QUERY_CACHE_MISSES_TOTAL);
+
+        Lookup::Miss(CacheableQuery {
+            key,
+            tables,
+            high_water_mark,
+        })
+    }
+
+    /// Caches the `output` of the `query`. A stream is cached once it's fully consumed,
+    /// unless it fails or exceeds the max memory of the cache.
+    pub(crate) fn cache_output(self: &Arc<Self>, query: CacheableQuery, output: Output) -> Output {
+        match output {
+            Output::Stream(stream) => Output::Stream(Box::pin(CachingStream {
+                inner: stream,
+                cache: self.clone(),
+                query: Some(query),
+                batches: vec![],
+                size: 0,
+            })),
+            Output::RecordBatches(batches) => {
+                self.insert(query, batches.schema(), batches.iter().cloned().collect());
+                Output::RecordBatches(batches)
+            }
+            output => output,
+        }
+    }
+
+    /// Records the writes of a statement.
+    pub(crate) fn record_writes(&self, writes: Writes) {
+        match writes {
+            Writes::Table {
+                catalog,
+                schema,
+                table,
+            } => self.record_write(&catalog, &schema, &table),
+            Writes::All => self.record_write_all(),
+        }
+    }
+
+    fn insert(&self, query: CacheableQuery, schema: SchemaRef, batches: Vec<RecordBatch>) {
+        let size: usize = batches.iter().map(batch_size).sum();
+        if size as u64 > self.options.max_memory.0 {
+            return;
+        }
+        let Ok(batches) = RecordBatches::try_new(schema, batches) else { return };
+        debug!(
+            "Cache the result of query {}, size: {}",
+            query.key.sql, size
+        );
+        let entry = CacheEntry {
+            batches,
+            tables: query.tables,
+            high_water_mark: query.high_water_mark,
+            size,
+        };
+        self.entries.insert(query.key, Arc::new(entry));
+    }
+
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+}
+
+/// Tracks the sequence of the last write to each table.
+///
+/// Sequences are used instead of timestamps, so a write in the same millisecond as a query
+/// still invalidates its result.
+#[derive(Debug, Default)]
+struct WriteTracker {
+    sequence: AtomicU64,
+    /// Sequence of the last write of any table.
+    all: AtomicU64,
+    /// Sequence of the last write of each table, by the lowercase full table name.
+    tables: RwLock<HashMap<String, u64>>,
+}
+
+impl WriteTracker {
+    fn high_water_mark(&self) -> u64 {
+        self.sequence.load(Ordering::SeqCst)
+    }
+
+    /// Records a write to `table`, or to all the tables if it's None.
+    fn record(&self, table: Option<&str>) {
+        let mut tables = self.tables.write().unwrap();
+        let sequence = self.sequence.fetch_add(1, Ordering::SeqCst) + 1;
+        match table {
+            Some(table) => {
+                let _ = tables.insert(table.to_lowercase(), sequence);
+            }
+            None => self.all.store(sequence, Ordering::SeqCst),
+        }
+    }
+
+    /// Returns the sequence of the last write to any of the `tables`.
+    fn last_write(&self, tables: &[String]) -> u64 {
+        let last_writes = self.tables.read().unwrap();
+        tables
+            .iter()
+            .filter_map(|table| last_writes.get(table).copied())
+            .chain(std::iter::once(self.all.load(Ordering::SeqCst)))
+            .max()
+            .unwrap_or_default()
+    }
+}
+
+/// Returns the lowercase full names of the tables scanned by the `plan`, or None if the
+/// result of the plan can't be cached: it has subqueries, which are not visited, or it
+/// reads the information schema, which changes without writes.
+fn scanned_tables(plan: &DfLogicalPlan) -> Option<Vec<String>> {
+    let mut tables = vec![];
+    collect_scanned_tables(plan, &mut tables)?;
+    tables.sort();
+    tables.dedup();
+    Some(tables)
+}
+
+fn collect_scanned_tables(plan: &DfLogicalPlan, tables: &mut Vec<String>) -> Option<()> {
+    if let DfLogicalPlan::TableScan(scan) = plan {
+        let table = scan
+            .source
+            .as_any()
+            .downcast_ref::<DefaultTableSource>()?
+            .table_provider
+            .as_any()
+            .downcast_ref::<DfTableProviderAdapter>()?
+            .table();
+        let info = table.table_info();
+        if info
+            .schema_name
+            .eq_ignore_ascii_case(INFORMATION_SCHEMA_NAME)
+        {
+            return None;
+        }
+        tables.push(
+            format_full_table_name(&info.catalog_name, &info.schema_name, &info.name)
+                .to_lowercase(),
+        );
+    }
+
+    for expr in plan.expressions() {
+        let mut finder = SubqueryFinder::default();
+        let _ = expr.rewrite(&mut finder).ok()?;
+        if finder.found {
+            return None;
+        }
+    }
+    for input in plan.inputs() {
+        collect_scanned_tables(input, tables)?;
+    }
+    Some(())
+}
+
+#[derive(Default)]
+struct SubqueryFinder {
+    found: bool,
+}
+
+impl ExprRewriter for SubqueryFinder {
+    fn mutate(&mut self, expr: Expr) -> datafusion_common::Result<Expr> {
+        if matches!(
+            expr,
+            Expr::ScalarSubquery(_) | Expr::InSubquery { .. } | Expr::Exists { .. }
+        ) {
+            self.found = true;
+        }
+        Ok(expr)
+    }
+}
+
+fn batch_size(batch: &RecordBatch) -> usize {
+    batch.columns().iter().map(|c| c.memory_size()).sum()
+}
+
+/// Passes through the batches of the inner stream, and caches them once the inner stream
+/// ends.
+struct CachingStream {
+    inner: SendableRecordBatchStream,
+    cache: QueryCacheRef,
+    /// None if the result won't be cached.
+    query: Option<CacheableQuery>,
+    batches: Vec<RecordBatch>,
+    size: usize,
+}
+
+impl RecordBatchStream for CachingStream {
+    fn schema(&self) -> SchemaRef {
+        self.inner.schema()
+    }
+
+    fn scan_stats(&self) -> Option<ScanStats> {
+        self.inner.scan_stats()
+    }
+}
+
+impl Stream for CachingStream {
+    type Item = RecordBatchResult<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        let poll = this.inner.as_mut().poll_next(cx);
+        match &poll {
+            Poll::Ready(Some(Ok(batch))) if this.query.is_some() => {
+                this.size += batch_size(batch);
+                if this.size as u64 > this.cache.options.max_memory.0 {
+                    this.query = None;
+                    this.batches = vec![];
+                } else {
+                    this.batches.push(batch.clone());
+                }
+            }
+            Poll::Ready(Some(Err(_))) => {
+                this.query = None;
+                this.batches = vec![];
+            }
+            Poll::Ready(None) => {
+                if let Some(query) = this.query.take() {
+                    let batches = std::mem::take(&mut this.batches);
+                    this.cache.insert(query, this.inner.schema(), batches);
+                }
+            }
+            _ => {}
+        }
+        poll
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use session::context::QueryContext;
+    use sql::dialect::GenericDialect;
+    use sql::parser::ParserContext;
+
+    use super::*;
+
+    fn parse(sql: &str) -> Statement {
+        ParserContext::create_with_dialect(sql, &GenericDialect {})
+            .unwrap()
+            .remove(0)
+    }
+
+    #[test]
+    fn test_query_cache_options_toml() {
+        let options: QueryCacheOptions = toml::from_str(
+            r#"
+            enable = true
+            ttl = "5m"
+            max_memory = "64MB"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            QueryCacheOptions {
+                enable: true,
+                ttl: Duration::from_secs(300),
+                distributed_ttl: Duration::from_secs(5),
+                max_memory: ReadableSize::mb(64),
+                time_bucket: Duration::from_secs(10),
+            },
+            options
+        );
+        assert_eq!(Duration::from_secs(5), options.distributed().ttl);
+
+        let options = QueryCacheOptions {
+            ttl: Duration::from_secs(1),
+            ..Default::default()
+        };
+        assert_eq!(Duration::from_secs(1), options.distributed().ttl);
+    }
+
+    #[test]
+    fn test_writes_of_statement() {
+        let query_ctx = QueryContext::arc();
+        let table = |table: &str| {
+            Some(Writes::Table {
+                catalog: "greptime".to_string(),
+                schema: "public".to_string(),
+                table: table.to_string(),
+            })
+        };
+
+        let stmt = parse("INSERT INTO demo VALUES ('host1', 1000)");
+        assert_eq!(table("demo"), Writes::of_statement(&stmt, &query_ctx));
+        let stmt = parse("DELETE FROM demo WHERE host = 'host1'");
+        assert_eq!(table("demo"), Writes::of_statement(&stmt, &query_ctx));
+        let stmt = parse("DROP TABLE demo");
+        assert_eq!(table("demo"), Writes::of_statement(&stmt, &query_ctx));
+        let stmt = parse("COPY demo FROM '/tmp/demo.parquet'");
+        assert_eq!(Some(Writes::All), Writes::of_statement(&stmt, &query_ctx));
+
+        let stmt = parse("SELECT * FROM demo");
+        assert_eq!(None, Writes::of_statement(&stmt, &query_ctx));
+        let stmt = parse("CREATE DATABASE test");
+        assert_eq!(None, Writes::of_statement(&stmt, &query_ctx));
+    }
+
+    #[test]
+    fn test_write_tracker() {
+        let tracker = WriteTracker::default();
+        let (foo, bar) = (
+            "greptime.public.foo".to_string(),
+            "greptime.public.bar".to_string(),
+        );
+
+        let before = tracker.high_water_mark();
+        assert!(tracker.last_write(&[foo.clone(), bar.clone()]) <= before);
+
+        tracker.record(Some("greptime.public.FOO"));
+        assert!(tracker.last_write(&[foo.clone()]) > before);
+        assert!(tracker.last_write(&[bar.clone()]) <= before);
+
+        let before = tracker.high_water_mark();
+        tracker.record(None);
+        assert!(tracker.last_write(&[bar]) > before);
+        assert!(tracker.last_write(&[foo]) > before);
+    }
+}
@@ -57,6 +57,7 @@ impl Services {
         let mut result = Vec::<ServerHandler>::with_capacity(plugins.len());
         let user_provider = plugins.get::<UserProviderRef>().cloned();
 
+        let grpc_compression = opts.grpc_compression;
         if let Some(opts) = &opts.grpc_options {
             let grpc_addr = parse_addr(&opts.addr)?;
 
@@ -68,11 +69,15 @@ impl Services {
                     .context(error::RuntimeResourceSnafu)?,
             );
 
-            let grpc_server = GrpcServer::new(
+            let mut grpc_server = GrpcServer::new(
                 ServerGrpcQueryHandlerAdaptor::arc(instance.clone()),
                 user_provider.clone(),
                 grpc_runtime,
             );
+            grpc_server.set_record_batch_insert_handler(instance.clone());
+            grpc_server.set_bulk_load_handler(instance.clone());
+            grpc_server.set_sql_query_handler(ServerSqlQueryHandlerAdaptor::arc(instance.clone()));
+            grpc_server.set_compression(grpc_compression);
 
             result.push((Box::new(grpc_server), grpc_addr));
         };
@@ -176,6 +181,12 @@ impl Services {
                 http_server.set_prom_handler(instance.clone());
             }
             http_server.set_script_handler(instance.clone());
+            http_server.set_backup_handler(instance.clone());
+            http_server.set_gc_handler(instance.clone());
+            http_server.set_compaction_handler(instance.clone());
+            http_server.set_table_stats_handler(instance.clone());
+            http_server.set_verify_handler(instance.clone());
+            http_server.set_readiness_handler(instance.clone());
 
             result.push((Box::new(http_server), http_addr));
         }
@@ -184,6 +195,7 @@ impl Services {
             let prom_addr = parse_addr(&prom_options.addr)?;
 
             let mut prom_server = PromServer::create_server(instance);
+            prom_server.set_query_options(prom_options.query_options());
             if let Some(user_provider) = user_provider {
                 prom_server.set_user_provider(user_provider);
             }
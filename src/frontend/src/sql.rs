@@ -14,6 +14,7 @@
 
 use common_error::ext::BoxedError;
 use common_error::snafu::ensure;
+use common_time::TimeZone;
 use datanode::instance::sql::table_idents_to_full_name;
 use datatypes::data_type::DataType;
 use datatypes::prelude::MutableVector;
@@ -43,6 +44,7 @@ pub(crate) fn insert_to_request(
         .context(error::ParseSqlSnafu)?
         .context(error::MissingInsertValuesSnafu)?;
 
+    let timezone = query_ctx.time_zone();
     let (catalog_name, schema_name, table_name) =
         table_idents_to_full_name(stmt.table_name(), query_ctx)
             .map_err(BoxedError::new)
@@ -87,7 +89,7 @@ pub(crate) fn insert_to_request(
         );
 
         for (sql_val, (column_schema, builder)) in row.iter().zip(columns_builders.iter_mut()) {
-            add_row_to_vector(column_schema, sql_val, builder)?;
+            add_row_to_vector(column_schema, sql_val, builder, timezone.as_ref())?;
         }
     }
 
@@ -107,6 +109,7 @@ fn add_row_to_vector(
     column_schema: &ColumnSchema,
     sql_val: &SqlValue,
     builder: &mut Box<dyn MutableVector>,
+    timezone: Option<&TimeZone>,
 ) -> Result<()> {
     let value = if replace_default(sql_val) {
         column_schema
@@ -118,8 +121,13 @@ fn add_row_to_vector(
                 column: column_schema.name.to_string(),
             })?
     } else {
-        statements::sql_value_to_value(&column_schema.name, &column_schema.data_type, sql_val)
-            .context(error::ParseSqlSnafu)?
+        statements::sql_value_to_value(
+            &column_schema.name,
+            &column_schema.data_type,
+            sql_val,
+            timezone,
+        )
+        .context(error::ParseSqlSnafu)?
     };
     builder.push_value_ref(value.as_value_ref());
     Ok(())
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::any::Any;
+use std::fmt;
 use std::sync::Arc;
 
 use api::v1::AlterExpr;
@@ -26,9 +27,11 @@ use common_query::logical_plan::Expr;
 use common_query::physical_plan::{PhysicalPlan, PhysicalPlanRef};
 use common_query::Output;
 use common_recordbatch::adapter::AsyncRecordBatchStreamAdapter;
+use common_recordbatch::scan_stats::{ScanStatsRecorder, ScanStatsRecorderRef};
 use common_recordbatch::{RecordBatches, SendableRecordBatchStream};
 use common_telemetry::debug;
 use datafusion::execution::context::TaskContext;
+use datafusion::physical_plan::metrics::MetricsSet;
 use datafusion::physical_plan::{
     Partitioning, SendableRecordBatchStream as DfSendableRecordBatchStream,
 };
@@ -37,18 +40,21 @@ use datatypes::schema::{ColumnSchema, Schema, SchemaRef};
 use meta_client::rpc::TableName;
 use partition::manager::PartitionRuleManagerRef;
 use snafu::prelude::*;
-use table::error::TableOperationSnafu;
-use table::metadata::{FilterPushDownType, TableInfo, TableInfoRef};
-use table::requests::{AlterTableRequest, InsertRequest};
+use table::error::{TableOperationSnafu, UnsupportedSnafu};
+use table::metadata::{FilterPushDownType, ScanPushdown, TableInfo, TableInfoRef};
+use table::requests::{AlterKind, AlterTableRequest, InsertRequest};
 use table::table::AlterContext;
 use table::Table;
 use tokio::sync::RwLock;
 
 use crate::datanode::DatanodeClients;
 use crate::error::{self, Result};
+use crate::process;
+use crate::table::rename::TableRename;
 use crate::table::scan::{DatanodeInstance, TableScanPlan};
 
 pub mod insert;
+pub(crate) mod rename;
 pub(crate) mod scan;
 
 #[derive(Clone)]
@@ -96,6 +102,62 @@ impl Table for DistTable {
         projection: Option<&Vec<usize>>,
         filters: &[Expr],
         limit: Option<usize>,
+    ) -> table::Result<PhysicalPlanRef> {
+        self.dist_scan(projection, filters, limit, None).await
+    }
+
+    fn supports_pushdown(&self, _pushdown: &ScanPushdown) -> bool {
+        true
+    }
+
+    async fn scan_with_pushdown(
+        &self,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        pushdown: &ScanPushdown,
+    ) -> table::Result<PhysicalPlanRef> {
+        self.dist_scan(projection, filters, None, Some(pushdown.clone()))
+            .await
+    }
+
+    fn supports_filters_pushdown(
+        &self,
+        filters: &[&Expr],
+    ) -> table::Result<Vec<FilterPushDownType>> {
+        Ok(vec![FilterPushDownType::Inexact; filters.len()])
+    }
+
+    async fn alter(&self, context: AlterContext, request: &AlterTableRequest) -> table::Result<()> {
+        self.handle_alter(context, request)
+            .await
+            .map_err(BoxedError::new)
+            .context(TableOperationSnafu)
+    }
+}
+
+impl DistTable {
+    pub(crate) fn new(
+        table_name: TableName,
+        table_info: TableInfoRef,
+        partition_manager: PartitionRuleManagerRef,
+        datanode_clients: Arc<DatanodeClients>,
+        backend: KvBackendRef,
+    ) -> Self {
+        Self {
+            table_name,
+            table_info,
+            partition_manager,
+            datanode_clients,
+            backend,
+        }
+    }
+
+    async fn dist_scan(
+        &self,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        limit: Option<usize>,
+        pushdown: Option<ScanPushdown>,
     ) -> table::Result<PhysicalPlanRef> {
         let partition_rule = self
             .partition_manager
@@ -115,8 +177,33 @@ impl Table for DistTable {
             .await
             .map_err(BoxedError::new)
             .context(TableOperationSnafu)?;
+        process::record_datanodes(datanodes.keys().map(|peer| peer.addr.as_str()));
+
+        // The aggregates pushed down are projected here, the datanodes then return
+        // exactly the projected columns.
+        let (schema, projection, pushdown) = match pushdown {
+            Some(ScanPushdown::TimeIndexAggregate { aggregates }) => {
+                let aggregates = match projection {
+                    Some(projection) => projection.iter().map(|i| aggregates[*i]).collect(),
+                    None => aggregates,
+                };
+                let pushdown = ScanPushdown::TimeIndexAggregate { aggregates };
+                let schema = pushdown
+                    .output_schema(&self.schema())
+                    .context(UnsupportedSnafu {
+                        operation: "SCAN WITH PUSHDOWN without time index",
+                    })?;
+                (schema, None, Some(pushdown))
+            }
+            pushdown => (
+                project_schema(self.schema(), projection),
+                projection.cloned(),
+                pushdown,
+            ),
+        };
 
         let table_name = &self.table_name;
+        let scan_stats = Arc::new(ScanStatsRecorder::default());
         let mut partition_execs = Vec::with_capacity(datanodes.len());
         for (datanode, _regions) in datanodes.iter() {
             let client = self.datanode_clients.get_client(datanode).await;
@@ -126,52 +213,25 @@ impl Table for DistTable {
             partition_execs.push(Arc::new(PartitionExec {
                 table_name: table_name.clone(),
                 datanode_instance,
-                projection: projection.cloned(),
+                projection: projection.clone(),
                 filters: filters.to_vec(),
                 limit,
+                pushdown: pushdown.clone(),
                 batches: Arc::new(RwLock::new(None)),
+                scan_stats: scan_stats.clone(),
             }));
         }
 
         let dist_scan = DistTableScan {
-            schema: project_schema(self.schema(), projection),
+            table_name: table_name.to_string(),
+            schema,
             partition_execs,
+            pushdown,
+            scan_stats,
         };
         Ok(Arc::new(dist_scan))
     }
 
-    fn supports_filters_pushdown(
-        &self,
-        filters: &[&Expr],
-    ) -> table::Result<Vec<FilterPushDownType>> {
-        Ok(vec![FilterPushDownType::Inexact; filters.len()])
-    }
-
-    async fn alter(&self, context: AlterContext, request: &AlterTableRequest) -> table::Result<()> {
-        self.handle_alter(context, request)
-            .await
-            .map_err(BoxedError::new)
-            .context(TableOperationSnafu)
-    }
-}
-
-impl DistTable {
-    pub(crate) fn new(
-        table_name: TableName,
-        table_info: TableInfoRef,
-        partition_manager: PartitionRuleManagerRef,
-        datanode_clients: Arc<DatanodeClients>,
-        backend: KvBackendRef,
-    ) -> Self {
-        Self {
-            table_name,
-            table_info,
-            partition_manager,
-            datanode_clients,
-            backend,
-        }
-    }
-
     pub(crate) async fn table_global_value(
         &self,
         key: &TableGlobalKey,
@@ -205,9 +265,21 @@ impl DistTable {
             .get::<AlterExpr>()
             .context(error::ContextValueNotFoundSnafu { key: "AlterExpr" })?;
 
+        let table_info = self.table_info();
+        if let AlterKind::RenameTable { new_table_name } = &request.alter_kind {
+            let rename = TableRename::new(
+                self.table_name.clone(),
+                self.backend.clone(),
+                self.partition_manager.clone(),
+                self.datanode_clients.clone(),
+            );
+            return rename
+                .rename(table_info.ident.table_id as u64, new_table_name)
+                .await;
+        }
+
         self.alter_by_expr(alter_expr).await?;
 
-        let table_info = self.table_info();
         let table_name = &table_info.name;
         let new_meta = table_info
             .meta
@@ -242,36 +314,51 @@ impl DistTable {
     /// Define a `alter_by_expr` instead of impl [`Table::alter`] to avoid redundant conversion between
     /// [`table::requests::AlterTableRequest`] and [`AlterExpr`].
     async fn alter_by_expr(&self, expr: &AlterExpr) -> Result<()> {
-        let table_routes = self
-            .partition_manager
-            .find_table_route(&self.table_name)
-            .await
-            .with_context(|_| error::FindTableRouteSnafu {
-                table_name: self.table_name.to_string(),
-            })?;
-        let leaders = table_routes.find_leaders();
-        ensure!(
-            !leaders.is_empty(),
-            error::LeaderNotFoundSnafu {
-                table: format!(
-                    "{:?}.{:?}.{}",
-                    expr.catalog_name, expr.schema_name, expr.table_name
-                )
-            }
-        );
-        for datanode in leaders {
-            let client = self.datanode_clients.get_client(&datanode).await;
-            let db = Database::new(&expr.catalog_name, &expr.schema_name, client);
-            debug!("Sending {:?} to {:?}", expr, db);
-            let result = db
-                .alter(expr.clone())
-                .await
-                .context(error::RequestDatanodeSnafu)?;
-            debug!("Alter table result: {:?}", result);
-            // TODO(hl): We should further check and track alter result in some global DDL task tracker
+        alter_on_datanodes(
+            &self.partition_manager,
+            &self.datanode_clients,
+            &self.table_name,
+            expr,
+        )
+        .await
+    }
+}
+
+/// Sends the alter `expr` of the table `table_name` to the datanodes leading its regions.
+pub(crate) async fn alter_on_datanodes(
+    partition_manager: &PartitionRuleManagerRef,
+    datanode_clients: &DatanodeClients,
+    table_name: &TableName,
+    expr: &AlterExpr,
+) -> Result<()> {
+    let table_routes = partition_manager
+        .find_table_route(table_name)
+        .await
+        .with_context(|_| error::FindTableRouteSnafu {
+            table_name: table_name.to_string(),
+        })?;
+    let leaders = table_routes.find_leaders();
+    ensure!(
+        !leaders.is_empty(),
+        error::LeaderNotFoundSnafu {
+            table: format!(
+                "{:?}.{:?}.{}",
+                expr.catalog_name, expr.schema_name, expr.table_name
+            )
         }
-        Ok(())
+    );
+    for datanode in leaders {
+        let client = datanode_clients.get_client(&datanode).await;
+        let db = Database::new(&expr.catalog_name, &expr.schema_name, client);
+        debug!("Sending {:?} to {:?}", expr, db);
+        let result = db
+            .alter(expr.clone())
+            .await
+            .context(error::RequestDatanodeSnafu)?;
+        debug!("Alter table result: {:?}", result);
+        // TODO(hl): We should further check and track alter result in some global DDL task tracker
     }
+    Ok(())
 }
 
 fn project_schema(table_schema: SchemaRef, projection: Option<&Vec<usize>>) -> SchemaRef {
@@ -289,8 +376,12 @@ fn project_schema(table_schema: SchemaRef, projection: Option<&Vec<usize>>) -> S
 
 #[derive(Debug)]
 struct DistTableScan {
+    table_name: String,
     schema: SchemaRef,
     partition_execs: Vec<Arc<PartitionExec>>,
+    pushdown: Option<ScanPushdown>,
+    /// Stats of the scans on all datanodes.
+    scan_stats: ScanStatsRecorderRef,
 }
 
 impl PhysicalPlan for DistTableScan {
@@ -329,6 +420,22 @@ impl PhysicalPlan for DistTableScan {
         let stream = AsyncRecordBatchStreamAdapter::new(self.schema(), stream);
         Ok(Box::pin(stream))
     }
+
+    fn fmt_as(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "DistTableScan: partitions={}",
+            self.partition_execs.len()
+        )?;
+        if let Some(pushdown) = &self.pushdown {
+            write!(f, ", pushdown={pushdown}")?;
+        }
+        Ok(())
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.scan_stats.stats().to_metrics(&self.table_name))
+    }
 }
 
 #[derive(Debug)]
@@ -338,7 +445,9 @@ struct PartitionExec {
     projection: Option<Vec<usize>>,
     filters: Vec<Expr>,
     limit: Option<usize>,
+    pushdown: Option<ScanPushdown>,
     batches: Arc<RwLock<Option<RecordBatches>>>,
+    scan_stats: ScanStatsRecorderRef,
 }
 
 impl PartitionExec {
@@ -357,8 +466,10 @@ impl PartitionExec {
             projection: self.projection.clone(),
             filters: self.filters.clone(),
             limit: self.limit,
+            pushdown: self.pushdown.clone(),
         };
-        let result = self.datanode_instance.grpc_table_scan(plan).await?;
+        let (result, scan_stats) = self.datanode_instance.grpc_table_scan(plan).await?;
+        self.scan_stats.add(&scan_stats);
         let _ = batches.insert(result);
         Ok(())
     }
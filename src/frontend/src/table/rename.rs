@@ -0,0 +1,351 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use api::v1::alter_expr::Kind;
+use api::v1::{AlterExpr, RenameTable};
+use catalog::helper::{TableGlobalKey, TableGlobalValue};
+use catalog::remote::{Kv, KvBackendRef};
+use common_telemetry::{error, info};
+use futures::StreamExt;
+use meta_client::rpc::{RenameRouteRequest, TableName};
+use partition::manager::PartitionRuleManagerRef;
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, ResultExt};
+
+use crate::catalog::FrontendCatalogManager;
+use crate::datanode::DatanodeClients;
+use crate::error::{self, Result};
+use crate::table::alter_on_datanodes;
+
+const TABLE_RENAME_KEY_PREFIX: &str = "__table_rename";
+
+/// Step of a table rename, see [TableRename].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+enum RenameStep {
+    /// Renames the table on the datanodes leading its regions.
+    Datanodes,
+    /// Moves the route of the table to the new name.
+    Route,
+    /// Moves the catalog entry of the table to the new name, which makes the new name
+    /// visible.
+    Catalog,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct TableRenameValue {
+    table_id: u64,
+    new_table_name: String,
+    step: RenameStep,
+}
+
+/// Renames a table on the datanodes, then its route and its catalog entry in metasrv.
+///
+/// Like the decommission of metasrv, the rename is persisted in the kv backend with its
+/// next step before the step is executed, and every step can be executed again. So a
+/// rename interrupted by a crash or an error is resumed by retrying it, or by
+/// [resume_all] when a frontend starts.
+pub(crate) struct TableRename {
+    table_name: TableName,
+    backend: KvBackendRef,
+    partition_manager: PartitionRuleManagerRef,
+    datanode_clients: Arc<DatanodeClients>,
+}
+
+impl TableRename {
+    pub(crate) fn new(
+        table_name: TableName,
+        backend: KvBackendRef,
+        partition_manager: PartitionRuleManagerRef,
+        datanode_clients: Arc<DatanodeClients>,
+    ) -> Self {
+        Self {
+            table_name,
+            backend,
+            partition_manager,
+            datanode_clients,
+        }
+    }
+
+    /// Renames the table `table_id` to `new_table_name`. A pending rename of the table is
+    /// resumed if it has the same new name, otherwise fails.
+    pub(crate) async fn rename(&self, table_id: u64, new_table_name: &str) -> Result<()> {
+        let value = TableRenameValue {
+            table_id,
+            new_table_name: new_table_name.to_string(),
+            step: RenameStep::Datanodes,
+        };
+        let key = self.key();
+        let raw =
+            serde_json::to_vec(&value).context(error::InvalidTableRenameSnafu { key: &key })?;
+        let value = match self
+            .backend
+            .compare_and_set(key.as_bytes(), &[], &raw)
+            .await
+            .context(error::CatalogSnafu)?
+        {
+            Ok(()) => value,
+            Err(pending) => {
+                let pending = match pending {
+                    Some(pending) => decode_value(&key, &pending)?,
+                    // Finished by others just now.
+                    None => return Ok(()),
+                };
+                ensure!(
+                    pending.table_id == table_id && pending.new_table_name == new_table_name,
+                    error::TableRenamingSnafu {
+                        table_name: self.table_name.to_string(),
+                        new_table_name: pending.new_table_name,
+                    }
+                );
+                info!(
+                    "Resume renaming table {} to {}, step: {:?}",
+                    self.table_name, new_table_name, pending.step
+                );
+                pending
+            }
+        };
+        self.run(value).await
+    }
+
+    async fn run(&self, mut value: TableRenameValue) -> Result<()> {
+        loop {
+            value.step = match value.step {
+                RenameStep::Datanodes => {
+                    self.rename_on_datanodes(&value).await?;
+                    RenameStep::Route
+                }
+                RenameStep::Route => {
+                    self.rename_route(&value).await?;
+                    RenameStep::Catalog
+                }
+                RenameStep::Catalog => {
+                    self.rename_in_catalog(&value).await?;
+                    break;
+                }
+            };
+            self.save(&value).await?;
+        }
+
+        self.backend
+            .delete(self.key().as_bytes())
+            .await
+            .context(error::CatalogSnafu)?;
+        info!(
+            "Table {} is renamed to {}",
+            self.table_name, value.new_table_name
+        );
+        Ok(())
+    }
+
+    async fn rename_on_datanodes(&self, value: &TableRenameValue) -> Result<()> {
+        self.partition_manager
+            .table_routes()
+            .invalidate_stale_route(&self.table_name, value.table_id)
+            .await;
+        let expr = AlterExpr {
+            catalog_name: self.table_name.catalog_name.clone(),
+            schema_name: self.table_name.schema_name.clone(),
+            table_name: self.table_name.table_name.clone(),
+            kind: Some(Kind::RenameTable(RenameTable {
+                new_table_name: value.new_table_name.clone(),
+            })),
+        };
+        alter_on_datanodes(
+            &self.partition_manager,
+            &self.datanode_clients,
+            &self.table_name,
+            &expr,
+        )
+        .await
+    }
+
+    /// Routes are looked up by table name, so they must follow the table before the
+    /// catalog entry makes the new name visible.
+    async fn rename_route(&self, value: &TableRenameValue) -> Result<()> {
+        let req = RenameRouteRequest::new(
+            value.table_id,
+            self.table_name.clone(),
+            value.new_table_name.clone(),
+        );
+        self.partition_manager
+            .table_routes()
+            .rename_table_route(req)
+            .await
+            .context(error::RenameTableRouteSnafu {
+                table_name: self.table_name.to_string(),
+            })
+    }
+
+    async fn rename_in_catalog(&self, value: &TableRenameValue) -> Result<()> {
+        let key = TableGlobalKey {
+            catalog_name: self.table_name.catalog_name.clone(),
+            schema_name: self.table_name.schema_name.clone(),
+            table_name: self.table_name.table_name.clone(),
+        }
+        .to_string();
+        let new_key = TableGlobalKey {
+            catalog_name: self.table_name.catalog_name.clone(),
+            schema_name: self.table_name.schema_name.clone(),
+            table_name: value.new_table_name.clone(),
+        }
+        .to_string();
+
+        let Some(Kv(_, raw)) = self
+            .backend
+            .get(key.as_bytes())
+            .await
+            .context(error::CatalogSnafu)?
+        else {
+            // Moved before the rename is interrupted.
+            return Ok(());
+        };
+        let mut global_value =
+            TableGlobalValue::from_bytes(raw).context(error::CatalogEntrySerdeSnafu)?;
+        global_value.table_info.name = value.new_table_name.clone();
+        global_value.table_info.ident.version += 1;
+        let new_raw = global_value
+            .as_bytes()
+            .context(error::CatalogEntrySerdeSnafu)?;
+
+        // Puts the new entry only if the new name is still free, or it is put before the
+        // rename is interrupted.
+        if let Err(existing) = self
+            .backend
+            .compare_and_set(new_key.as_bytes(), &[], &new_raw)
+            .await
+            .context(error::CatalogSnafu)?
+        {
+            let renamed = match existing {
+                Some(existing) => {
+                    TableGlobalValue::from_bytes(existing)
+                        .context(error::CatalogEntrySerdeSnafu)?
+                        .table_id() as u64
+                        == value.table_id
+                }
+                None => false,
+            };
+            ensure!(
+                renamed,
+                error::TableAlreadyExistSnafu {
+                    table: format!(
+                        "{}.{}.{}",
+                        self.table_name.catalog_name,
+                        self.table_name.schema_name,
+                        value.new_table_name
+                    ),
+                }
+            );
+        }
+        self.backend
+            .delete(key.as_bytes())
+            .await
+            .context(error::CatalogSnafu)
+    }
+
+    async fn save(&self, value: &TableRenameValue) -> Result<()> {
+        let key = self.key();
+        let raw =
+            serde_json::to_vec(value).context(error::InvalidTableRenameSnafu { key: &key })?;
+        self.backend
+            .set(key.as_bytes(), &raw)
+            .await
+            .context(error::CatalogSnafu)
+    }
+
+    fn key(&self) -> String {
+        format!(
+            "{TABLE_RENAME_KEY_PREFIX}-{}-{}-{}",
+            self.table_name.catalog_name, self.table_name.schema_name, self.table_name.table_name
+        )
+    }
+}
+
+fn decode_value(key: &str, raw: &[u8]) -> Result<TableRenameValue> {
+    serde_json::from_slice(raw).context(error::InvalidTableRenameSnafu { key })
+}
+
+/// Resumes the renames interrupted before they finish, a rename failing again is logged
+/// and left to be retried.
+pub(crate) async fn resume_all(catalog_manager: &FrontendCatalogManager) -> Result<()> {
+    let backend = catalog_manager.backend();
+    let prefix = format!("{TABLE_RENAME_KEY_PREFIX}-");
+    let mut pending = Vec::new();
+    let mut iter = backend.range(prefix.as_bytes());
+    while let Some(kv) = iter.next().await {
+        let Kv(key, raw) = kv.context(error::CatalogSnafu)?;
+        let key = String::from_utf8_lossy(&key).to_string();
+        let Some(table_name) = parse_key(&key) else {
+            continue;
+        };
+        pending.push((table_name, decode_value(&key, &raw)?));
+    }
+
+    for (table_name, value) in pending {
+        info!(
+            "Resume renaming table {} to {}, step: {:?}",
+            table_name, value.new_table_name, value.step
+        );
+        let rename = TableRename::new(
+            table_name.clone(),
+            backend.clone(),
+            catalog_manager.partition_manager(),
+            catalog_manager.datanode_clients(),
+        );
+        if let Err(e) = rename.run(value).await {
+            error!(e; "Failed to resume renaming table {}", table_name);
+        }
+    }
+    Ok(())
+}
+
+fn parse_key(key: &str) -> Option<TableName> {
+    let names = key
+        .strip_prefix(TABLE_RENAME_KEY_PREFIX)?
+        .strip_prefix('-')?;
+    let mut names = names.splitn(3, '-');
+    Some(TableName::new(names.next()?, names.next()?, names.next()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_rename_key() {
+        let key = format!("{TABLE_RENAME_KEY_PREFIX}-greptime-public-demo");
+        assert_eq!(
+            Some(TableName::new("greptime", "public", "demo")),
+            parse_key(&key)
+        );
+        assert_eq!(None, parse_key("__tg-greptime-public-demo"));
+        assert_eq!(
+            None,
+            parse_key(&format!("{TABLE_RENAME_KEY_PREFIX}-greptime"))
+        );
+    }
+
+    #[test]
+    fn test_table_rename_value() {
+        let value = TableRenameValue {
+            table_id: 1024,
+            new_table_name: "demo_new".to_string(),
+            step: RenameStep::Route,
+        };
+        let raw = serde_json::to_vec(&value).unwrap();
+        assert_eq!(value, decode_value("key", &raw).unwrap());
+        assert!(decode_value("key", b"invalid").is_err());
+    }
+}
@@ -19,12 +19,16 @@ use api::v1::InsertRequest;
 use client::Database;
 use common_query::prelude::Expr;
 use common_query::Output;
+use common_recordbatch::scan_stats::ScanStats;
 use common_recordbatch::RecordBatches;
 use datafusion::datasource::DefaultTableSource;
-use datafusion_expr::{LogicalPlan, LogicalPlanBuilder};
+use datafusion_common::Column;
+use datafusion_expr::expr::AggregateFunction;
+use datafusion_expr::{Expr as DfExpr, LogicalPlan, LogicalPlanBuilder};
 use meta_client::rpc::TableName;
-use snafu::ResultExt;
+use snafu::{OptionExt, ResultExt};
 use substrait::{DFLogicalSubstraitConvertor, SubstraitPlan};
+use table::metadata::ScanPushdown;
 use table::table::adapter::DfTableProviderAdapter;
 use table::TableRef;
 
@@ -51,20 +55,24 @@ impl DatanodeInstance {
         self.db.insert(request).await
     }
 
-    pub(crate) async fn grpc_table_scan(&self, plan: TableScanPlan) -> Result<RecordBatches> {
+    /// Scans the table on the datanode, returns the results and the stats of the scan.
+    pub(crate) async fn grpc_table_scan(
+        &self,
+        plan: TableScanPlan,
+    ) -> Result<(RecordBatches, ScanStats)> {
         let logical_plan = self.build_logical_plan(&plan)?;
 
         let substrait_plan = DFLogicalSubstraitConvertor
             .encode(logical_plan)
             .context(error::EncodeSubstraitLogicalPlanSnafu)?;
 
-        let result = self
+        let (result, scan_stats) = self
             .db
-            .logical_plan(substrait_plan.to_vec())
+            .logical_plan_with_scan_stats(substrait_plan.to_vec())
             .await
             .context(error::RequestDatanodeSnafu)?;
         let Output::RecordBatches(recordbatches) = result else { unreachable!() };
-        Ok(recordbatches)
+        Ok((recordbatches, scan_stats))
     }
 
     fn build_logical_plan(&self, table_scan: &TableScanPlan) -> Result<LogicalPlan> {
@@ -100,8 +108,49 @@ impl DatanodeInstance {
                 .context(error::BuildDfLogicalPlanSnafu)?;
         }
 
+        if let Some(pushdown) = &table_scan.pushdown {
+            builder = self.push_down(builder, pushdown)?;
+        }
+
         builder.build().context(error::BuildDfLogicalPlanSnafu)
     }
+
+    /// Evaluates the operators pushed down to the scan of the datanode, on top of the
+    /// filters.
+    fn push_down(
+        &self,
+        builder: LogicalPlanBuilder,
+        pushdown: &ScanPushdown,
+    ) -> Result<LogicalPlanBuilder> {
+        let schema = self.table.schema();
+        let time_index = schema
+            .timestamp_column()
+            .context(error::NotSupportedSnafu {
+                feat: "pushdown to the scan of a table without time index",
+            })?;
+        let time_index = DfExpr::Column(Column::from_name(&time_index.name));
+
+        match pushdown {
+            ScanPushdown::TopN { limit, descending } => builder
+                .sort(vec![time_index.sort(!descending, false)])
+                .and_then(|builder| builder.limit(0, Some(*limit))),
+            ScanPushdown::TimeIndexAggregate { aggregates } => {
+                let aggr_exprs = aggregates
+                    .iter()
+                    .map(|aggregate| {
+                        DfExpr::AggregateFunction(AggregateFunction {
+                            fun: aggregate.aggregate_function(),
+                            args: vec![time_index.clone()],
+                            distinct: false,
+                            filter: None,
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                builder.aggregate(Vec::<DfExpr>::new(), aggr_exprs)
+            }
+        }
+        .context(error::BuildDfLogicalPlanSnafu)
+    }
 }
 
 #[derive(Debug)]
@@ -110,4 +159,5 @@ pub(crate) struct TableScanPlan {
     pub projection: Option<Vec<usize>>,
     pub filters: Vec<Expr>,
     pub limit: Option<usize>,
+    pub pushdown: Option<ScanPushdown>,
 }
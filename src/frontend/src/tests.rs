@@ -18,10 +18,13 @@ use std::time::Duration;
 
 use catalog::remote::MetaKvBackend;
 use client::Client;
-use common_grpc::channel_manager::ChannelManager;
+use common_grpc::channel_manager::{ChannelConfig, ChannelManager};
+use common_grpc::compression::GrpcCompression;
 use common_runtime::Builder as RuntimeBuilder;
 use common_test_util::temp_dir::{create_temp_dir, TempDir};
-use datanode::datanode::{DatanodeOptions, FileConfig, ObjectStoreConfig, WalConfig};
+use datanode::datanode::{
+    DatanodeOptions, FileConfig, ObjectStoreConfig, StorageConfig, WalConfig,
+};
 use datanode::instance::Instance as DatanodeInstance;
 use meta_client::client::MetaClientBuilder;
 use meta_client::rpc::Peer;
@@ -87,17 +90,20 @@ pub(crate) async fn create_standalone_instance(test_name: &str) -> MockStandalon
     }
 }
 
-fn create_tmp_dir_and_datanode_opts(name: &str) -> (DatanodeOptions, TestGuard) {
+pub(crate) fn create_tmp_dir_and_datanode_opts(name: &str) -> (DatanodeOptions, TestGuard) {
     let wal_tmp_dir = create_temp_dir(&format!("gt_wal_{name}"));
     let data_tmp_dir = create_temp_dir(&format!("gt_data_{name}"));
     let opts = DatanodeOptions {
         wal: WalConfig {
-            dir: wal_tmp_dir.path().to_str().unwrap().to_string(),
+            dir: wal_tmp_dir.path().to_str().unwrap().to_string().into(),
+            ..Default::default()
+        },
+        storage: StorageConfig {
+            store: ObjectStoreConfig::File(FileConfig {
+                data_dir: data_tmp_dir.path().to_str().unwrap().to_string(),
+            }),
             ..Default::default()
         },
-        storage: ObjectStoreConfig::File(FileConfig {
-            data_dir: data_tmp_dir.path().to_str().unwrap().to_string(),
-        }),
         mode: Mode::Standalone,
         ..Default::default()
     };
@@ -112,6 +118,21 @@ fn create_tmp_dir_and_datanode_opts(name: &str) -> (DatanodeOptions, TestGuard)
 
 pub(crate) async fn create_datanode_client(
     datanode_instance: Arc<DatanodeInstance>,
+) -> (String, Client) {
+    create_datanode_client_with_compression(
+        datanode_instance,
+        GrpcCompression::None,
+        GrpcCompression::None,
+    )
+    .await
+}
+
+/// Creates a client of the datanode, the datanode compresses responses with
+/// `server_compression` and the client compresses requests with `client_compression`.
+pub(crate) async fn create_datanode_client_with_compression(
+    datanode_instance: Arc<DatanodeInstance>,
+    server_compression: GrpcCompression,
+    client_compression: GrpcCompression,
 ) -> (String, Client) {
     let (client, server) = tokio::io::duplex(1024);
 
@@ -125,11 +146,12 @@ pub(crate) async fn create_datanode_client(
 
     // create a mock datanode grpc service, see example here:
     // https://github.com/hyperium/tonic/blob/master/examples/src/mock/mock.rs
-    let grpc_server = GrpcServer::new(
+    let mut grpc_server = GrpcServer::new(
         ServerGrpcQueryHandlerAdaptor::arc(datanode_instance),
         None,
         runtime,
     );
+    grpc_server.set_compression(server_compression);
     tokio::spawn(async move {
         Server::builder()
             .add_service(grpc_server.create_flight_service())
@@ -143,7 +165,8 @@ pub(crate) async fn create_datanode_client(
     let mut client = Some(client);
     // "127.0.0.1:3001" is just a placeholder, does not actually connect to it.
     let addr = "127.0.0.1:3001";
-    let channel_manager = ChannelManager::new();
+    let channel_manager =
+        ChannelManager::with_config(ChannelConfig::new().compression(client_compression));
     channel_manager
         .reset_with_connector(
             addr,
@@ -179,12 +202,15 @@ async fn create_distributed_datanode(
     let opts = DatanodeOptions {
         node_id: Some(datanode_id),
         wal: WalConfig {
-            dir: wal_tmp_dir.path().to_str().unwrap().to_string(),
+            dir: wal_tmp_dir.path().to_str().unwrap().to_string().into(),
+            ..Default::default()
+        },
+        storage: StorageConfig {
+            store: ObjectStoreConfig::File(FileConfig {
+                data_dir: data_tmp_dir.path().to_str().unwrap().to_string(),
+            }),
             ..Default::default()
         },
-        storage: ObjectStoreConfig::File(FileConfig {
-            data_dir: data_tmp_dir.path().to_str().unwrap().to_string(),
-        }),
         mode: Mode::Distributed,
         ..Default::default()
     };
@@ -14,14 +14,33 @@
 
 use std::time::Duration;
 
+use serde::{Deserialize, Serialize};
+
+/// How to treat corrupted entries found while replaying the log.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecoveryMode {
+    /// A corrupted last entry is regarded as a torn write of a crash and is dropped,
+    /// corruption elsewhere fails the replay.
+    #[default]
+    TolerateTailCorruption,
+    /// Any corruption fails the replay.
+    AbsoluteConsistency,
+}
+
 #[derive(Debug, Clone)]
 pub struct LogConfig {
     pub file_size: u64,
     pub log_file_dir: String,
+    /// Additional directories to stripe the logs of namespaces across, usually on other disks.
+    pub extra_log_file_dirs: Vec<String>,
     pub purge_interval: Duration,
     pub purge_threshold: u64,
+    /// Max number of log directories purged concurrently, at least 1.
+    pub max_concurrent_purges: usize,
     pub read_batch_size: usize,
     pub sync_write: bool,
+    pub recovery_mode: RecoveryMode,
 }
 
 impl Default for LogConfig {
@@ -31,14 +50,26 @@ impl Default for LogConfig {
         Self {
             file_size: 1024 * 1024 * 1024,
             log_file_dir: "/tmp/greptimedb".to_string(),
+            extra_log_file_dirs: vec![],
             purge_interval: Duration::from_secs(10 * 60),
             purge_threshold: 1024 * 1024 * 1024 * 50,
+            max_concurrent_purges: 2,
             read_batch_size: 128,
             sync_write: false,
+            recovery_mode: RecoveryMode::TolerateTailCorruption,
         }
     }
 }
 
+impl LogConfig {
+    /// Returns all directories of the log store, the first one is [LogConfig::log_file_dir].
+    pub fn log_file_dirs(&self) -> Vec<&str> {
+        std::iter::once(self.log_file_dir.as_str())
+            .chain(self.extra_log_file_dirs.iter().map(String::as_str))
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use common_telemetry::info;
@@ -53,7 +84,10 @@ mod tests {
         assert_eq!(1024 * 1024 * 1024, default.file_size);
         assert_eq!(Duration::from_secs(600), default.purge_interval);
         assert_eq!(1024 * 1024 * 1024 * 50, default.purge_threshold);
+        assert_eq!(2, default.max_concurrent_purges);
         assert_eq!(128, default.read_batch_size);
         assert!(!default.sync_write);
+        assert_eq!(RecoveryMode::TolerateTailCorruption, default.recovery_mode);
+        assert_eq!(vec!["/tmp/greptimedb"], default.log_file_dirs());
     }
 }
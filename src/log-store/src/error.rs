@@ -27,6 +27,12 @@ pub enum Error {
         backtrace: Backtrace,
     },
 
+    #[snafu(display("Failed to join the task purging log files, source: {}", source))]
+    JoinPurgeTask {
+        source: JoinError,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Failed to add entry to LogBatch, source: {}", source))]
     AddEntryLogBatch {
         source: raft_engine::Error,
@@ -61,6 +67,60 @@ pub enum Error {
         source: raft_engine::Error,
         backtrace: Backtrace,
     },
+
+    #[snafu(display(
+        "Corrupted entry {} in namespace {}, file: {}, offset: {}, expected checksum: {}, actual: {}",
+        id,
+        ns,
+        file,
+        offset,
+        expected,
+        actual
+    ))]
+    CorruptedEntry {
+        ns: u64,
+        id: u64,
+        file: String,
+        offset: u64,
+        expected: u32,
+        actual: u32,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Corrupted log files in {}, source: {}", dir, source))]
+    CorruptedLogFile {
+        dir: String,
+        source: raft_engine::Error,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Duplicate log file directory: {}", dir))]
+    DuplicateLogFileDir { dir: String, backtrace: Backtrace },
+
+    #[snafu(display("Failed to access WAL directory list {}, source: {}", path, source))]
+    WalDirsFile {
+        path: String,
+        source: std::io::Error,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display(
+        "WAL directory {} is removed from config but still has logs of regions {:?}, add it back to replay them",
+        dir,
+        regions
+    ))]
+    OrphanedWalDir {
+        dir: String,
+        regions: Vec<u64>,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Namespace {} has logs in multiple directories: {:?}", ns, dirs))]
+    NamespaceInMultipleDirs {
+        ns: u64,
+        dirs: Vec<String>,
+        backtrace: Backtrace,
+    },
 }
 
 impl ErrorExt for Error {
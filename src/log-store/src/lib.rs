@@ -15,8 +15,9 @@
 mod config;
 pub mod error;
 mod noop;
+mod purge;
 pub mod raft_engine;
 pub mod test_util;
 
-pub use config::LogConfig;
+pub use config::{LogConfig, RecoveryMode};
 pub use noop::NoopLogStore;
@@ -111,6 +111,11 @@ impl LogStore for NoopLogStore {
         let _ = id;
         Ok(())
     }
+
+    async fn purge(&self, namespace: &Self::Namespace) -> std::result::Result<(), Self::Error> {
+        let _ = namespace;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,151 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Scheduling of log file purges.
+
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use common_telemetry::info;
+use rand::Rng;
+use tokio::sync::Semaphore;
+use tokio_util::sync::CancellationToken;
+
+/// Returns the delay before next purge, which is picked from `[interval / 2, interval * 3 / 2)`
+/// randomly.
+///
+/// Log directories sharing the same `purge_interval` would be purged at the same time as
+/// they start together, jitter spreads purges within the interval window while keeping the
+/// average interval unchanged.
+pub(crate) fn jittered_interval<R: Rng>(interval: Duration, rng: &mut R) -> Duration {
+    if interval.is_zero() {
+        return interval;
+    }
+    interval / 2 + rng.gen_range(Duration::ZERO..interval)
+}
+
+/// Runs `purge` every jittered `interval` until `cancel` is cancelled, purges are
+/// bounded by `permits`.
+pub(crate) async fn run_purge_loop<F, Fut>(
+    interval: Duration,
+    permits: Arc<Semaphore>,
+    cancel: CancellationToken,
+    mut purge: F,
+) where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = ()>,
+{
+    loop {
+        let delay = jittered_interval(interval, &mut rand::thread_rng());
+        tokio::select! {
+            _ = tokio::time::sleep(delay) => {}
+            _ = cancel.cancelled() => {
+                info!("LogStore gc task has been cancelled");
+                return;
+            }
+        }
+
+        let _permit = tokio::select! {
+            // Safety: the semaphore is never closed.
+            permit = permits.acquire() => permit.unwrap(),
+            _ = cancel.cancelled() => {
+                info!("LogStore gc task has been cancelled");
+                return;
+            }
+        };
+        purge().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use tokio::time::Instant;
+
+    use super::*;
+
+    #[test]
+    fn test_jittered_interval() {
+        let interval = Duration::from_secs(600);
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..100 {
+            let delay = jittered_interval(interval, &mut rng);
+            assert!(delay >= interval / 2);
+            assert!(delay < interval * 3 / 2);
+        }
+
+        assert_eq!(Duration::ZERO, jittered_interval(Duration::ZERO, &mut rng));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_purge_loop_jittered_and_bounded() {
+        let interval = Duration::from_secs(600);
+        let num_loops = 16;
+        let max_permits = 2;
+        let permits = Arc::new(Semaphore::new(max_permits));
+        let cancel = CancellationToken::new();
+
+        let start = Instant::now();
+        let purge_times = Arc::new(Mutex::new(Vec::new()));
+        let running = Arc::new(AtomicUsize::new(0));
+        let max_running = Arc::new(AtomicUsize::new(0));
+
+        let mut handles = Vec::with_capacity(num_loops);
+        for _ in 0..num_loops {
+            let purge_times = purge_times.clone();
+            let running = running.clone();
+            let max_running = max_running.clone();
+            handles.push(tokio::spawn(run_purge_loop(
+                interval,
+                permits.clone(),
+                cancel.clone(),
+                move || {
+                    let purge_times = purge_times.clone();
+                    let running = running.clone();
+                    let max_running = max_running.clone();
+                    async move {
+                        purge_times.lock().unwrap().push(Instant::now() - start);
+                        let current = running.fetch_add(1, Ordering::Relaxed) + 1;
+                        max_running.fetch_max(current, Ordering::Relaxed);
+                        // Purge takes a while so purges would overlap without permits.
+                        tokio::time::sleep(Duration::from_secs(60)).await;
+                        running.fetch_sub(1, Ordering::Relaxed);
+                    }
+                },
+            )));
+        }
+
+        // The paused clock advances automatically when all tasks are idle.
+        tokio::time::sleep(interval * 3).await;
+        cancel.cancel();
+        for handle in handles {
+            handle.await.unwrap();
+        }
+
+        let mut purge_times = purge_times.lock().unwrap().clone();
+        assert!(purge_times.len() >= num_loops);
+        // Purges are spread instead of happening at the same time.
+        purge_times.sort();
+        purge_times.dedup();
+        assert!(purge_times.len() >= num_loops);
+        assert!(purge_times[0] >= interval / 2);
+        // Purges contend for permits but never exceed the bound.
+        assert_eq!(max_permits, max_running.load(Ordering::Relaxed));
+    }
+}
@@ -14,14 +14,20 @@
 
 use std::hash::{Hash, Hasher};
 
+use crc::{Crc, CRC_32_ISCSI};
 use store_api::logstore::entry::{Entry, Id};
 use store_api::logstore::namespace::Namespace;
 
 use crate::error::Error;
 use crate::raft_engine::protos::logstore::{EntryImpl, NamespaceImpl};
 
+mod file_system;
 pub mod log_store;
 
+const CRC32: Crc<u32> = Crc::<u32>::new(&CRC_32_ISCSI);
+/// Version of entries with checksum, entries written before have version 0.
+const ENTRY_VERSION: u32 = 1;
+
 pub mod protos {
     include!(concat!(env!("OUT_DIR"), concat!("/", "protos/", "mod.rs")));
 }
@@ -35,6 +41,32 @@ impl EntryImpl {
             ..Default::default()
         }
     }
+
+    /// Computes the checksum of entry's version, id, namespace and data.
+    pub fn checksum(&self) -> u32 {
+        let mut digest = CRC32.digest();
+        digest.update(&self.version.to_le_bytes());
+        digest.update(&self.id.to_le_bytes());
+        digest.update(&self.namespace_id.to_le_bytes());
+        digest.update(&self.data);
+        digest.finalize()
+    }
+
+    /// Sets the version and the checksum of the entry before it's written.
+    pub fn seal(&mut self) {
+        self.version = ENTRY_VERSION;
+        self.crc = self.checksum();
+    }
+
+    /// Returns false if the entry is corrupted. Entries of version 0 are written without
+    /// checksum, so they are valid only if they don't have a checksum either.
+    pub fn is_valid(&self) -> bool {
+        if self.version == 0 {
+            self.crc == 0
+        } else {
+            self.crc == self.checksum()
+        }
+    }
 }
 impl NamespaceImpl {
     pub fn with_id(id: Id) -> Self {
@@ -77,3 +109,30 @@ impl Entry for EntryImpl {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entry_checksum() {
+        let mut entry = EntryImpl::create(1, 2, b"hello".to_vec());
+        // Entries written without checksum.
+        assert!(entry.is_valid());
+
+        entry.seal();
+        assert_eq!(ENTRY_VERSION, entry.version);
+        assert!(entry.is_valid());
+
+        let mut torn = entry.clone();
+        torn.crc = 0;
+        assert!(!torn.is_valid());
+        let mut torn = entry.clone();
+        torn.data.truncate(2);
+        assert!(!torn.is_valid());
+        // A checksum without version is not from a legacy entry.
+        let mut torn = entry;
+        torn.version = 0;
+        assert!(!torn.is_valid());
+    }
+}
@@ -0,0 +1,130 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::cell::RefCell;
+use std::io::{Read, Result, Seek, SeekFrom};
+use std::path::Path;
+use std::sync::Arc;
+
+use raft_engine::env::{DefaultFileSystem, FileSystem, Handle};
+
+type DefaultHandle = <DefaultFileSystem as FileSystem>::Handle;
+type DefaultReader = <DefaultFileSystem as FileSystem>::Reader;
+
+thread_local! {
+    /// The file and the offset of the last block read by the thread.
+    static LAST_READ: RefCell<Option<(Arc<Path>, u64)>> = RefCell::new(None);
+}
+
+/// Takes the file and the offset of the last block read by the current thread.
+pub(crate) fn take_last_read() -> Option<(Arc<Path>, u64)> {
+    LAST_READ.with(|last_read| last_read.take())
+}
+
+/// The default file system of raft-engine, which also records the position of the last
+/// block read by each thread.
+///
+/// Raft-engine doesn't expose where an entry lives in the log files, reading the entry
+/// with this file system is the way to locate it.
+#[derive(Debug, Default)]
+pub(crate) struct LocatingFileSystem;
+
+pub(crate) struct LocatingHandle {
+    path: Arc<Path>,
+    inner: Arc<DefaultHandle>,
+}
+
+impl Handle for LocatingHandle {
+    fn truncate(&self, offset: usize) -> Result<()> {
+        self.inner.truncate(offset)
+    }
+
+    fn file_size(&self) -> Result<usize> {
+        self.inner.file_size()
+    }
+
+    fn sync(&self) -> Result<()> {
+        self.inner.sync()
+    }
+}
+
+pub(crate) struct LocatingReader {
+    path: Arc<Path>,
+    inner: DefaultReader,
+    offset: u64,
+    /// Whether the block starting at `offset` is recorded, a block is read by several reads.
+    recorded: bool,
+}
+
+impl Read for LocatingReader {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        if !self.recorded {
+            let last_read = (self.path.clone(), self.offset);
+            LAST_READ.with(|r| *r.borrow_mut() = Some(last_read));
+            self.recorded = true;
+        }
+        let len = self.inner.read(buf)?;
+        self.offset += len as u64;
+        Ok(len)
+    }
+}
+
+impl Seek for LocatingReader {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        self.offset = self.inner.seek(pos)?;
+        self.recorded = false;
+        Ok(self.offset)
+    }
+}
+
+impl FileSystem for LocatingFileSystem {
+    type Handle = LocatingHandle;
+    type Reader = LocatingReader;
+    type Writer = <DefaultFileSystem as FileSystem>::Writer;
+
+    fn create<P: AsRef<Path>>(&self, path: P) -> Result<Self::Handle> {
+        Ok(LocatingHandle {
+            path: path.as_ref().into(),
+            inner: Arc::new(DefaultFileSystem.create(path)?),
+        })
+    }
+
+    fn open<P: AsRef<Path>>(&self, path: P) -> Result<Self::Handle> {
+        Ok(LocatingHandle {
+            path: path.as_ref().into(),
+            inner: Arc::new(DefaultFileSystem.open(path)?),
+        })
+    }
+
+    fn delete<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        DefaultFileSystem.delete(path)
+    }
+
+    fn rename<P: AsRef<Path>>(&self, src_path: P, dst_path: P) -> Result<()> {
+        DefaultFileSystem.rename(src_path, dst_path)
+    }
+
+    fn new_reader(&self, handle: Arc<Self::Handle>) -> Result<Self::Reader> {
+        Ok(LocatingReader {
+            path: handle.path.clone(),
+            inner: DefaultFileSystem.new_reader(handle.inner.clone())?,
+            offset: 0,
+            recorded: false,
+        })
+    }
+
+    fn new_writer(&self, handle: Arc<Self::Handle>) -> Result<Self::Writer> {
+        DefaultFileSystem.new_writer(handle.inner.clone())
+    }
+}
@@ -12,105 +12,155 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::{BTreeSet, HashMap};
 use std::fmt::{Debug, Formatter};
+use std::path::Path;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
 use async_stream::stream;
-use common_telemetry::{error, info};
-use raft_engine::{Config, Engine, LogBatch, MessageExt, ReadableSize, RecoveryMode};
+use common_telemetry::{error, info, warn};
+use raft_engine::{Config, LogBatch, MessageExt, ReadableSize};
 use snafu::{ensure, OptionExt, ResultExt};
 use store_api::logstore::entry::Id;
 use store_api::logstore::entry_stream::SendableEntryStream;
 use store_api::logstore::namespace::Namespace as NamespaceTrait;
 use store_api::logstore::{AppendResponse, LogStore};
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, Semaphore};
 use tokio::task::JoinHandle;
 use tokio_util::sync::CancellationToken;
 
-use crate::config::LogConfig;
+use crate::config::{LogConfig, RecoveryMode};
 use crate::error::{
-    AddEntryLogBatchSnafu, Error, FetchEntrySnafu, IllegalNamespaceSnafu, IllegalStateSnafu,
-    RaftEngineSnafu, WaitGcTaskStopSnafu,
+    AddEntryLogBatchSnafu, CorruptedEntrySnafu, CorruptedLogFileSnafu, DuplicateLogFileDirSnafu,
+    Error, FetchEntrySnafu, IllegalNamespaceSnafu, IllegalStateSnafu, JoinPurgeTaskSnafu,
+    NamespaceInMultipleDirsSnafu, OrphanedWalDirSnafu, RaftEngineSnafu, WaitGcTaskStopSnafu,
+    WalDirsFileSnafu,
 };
+use crate::purge::run_purge_loop;
+use crate::raft_engine::file_system::{self, LocatingFileSystem};
 use crate::raft_engine::protos::logstore::{EntryImpl as Entry, NamespaceImpl as Namespace};
 
 const NAMESPACE_PREFIX: &str = "__sys_namespace_";
 const SYSTEM_NAMESPACE: u64 = 0;
+/// File in every log directory recording all the configured directories, so a directory
+/// removed from the config can still be found on the next startup.
+const WAL_DIRS_FILE: &str = "WAL_DIRS";
+
+type Engine = raft_engine::Engine<LocatingFileSystem>;
 
 pub struct RaftEngineLogStore {
     config: LogConfig,
-    engine: Arc<Engine>,
+    /// Engines of the log directories, in the order of [LogConfig::log_file_dirs].
+    engines: Vec<Arc<Engine>>,
+    /// Index of the engine holding each namespace found on startup. Other namespaces are
+    /// striped across engines by their ids.
+    namespace_engines: HashMap<u64, usize>,
     cancel_token: Mutex<Option<CancellationToken>>,
-    gc_task_handle: Mutex<Option<JoinHandle<()>>>,
+    /// Purge tasks of the engines.
+    gc_task_handles: Mutex<Vec<JoinHandle<()>>>,
     started: AtomicBool,
 }
 
 impl RaftEngineLogStore {
     pub async fn try_new(config: LogConfig) -> Result<Self, Error> {
-        // TODO(hl): set according to available disk space
-        let raft_engine_config = Config {
-            dir: config.log_file_dir.clone(),
-            purge_threshold: ReadableSize(config.purge_threshold),
-            recovery_mode: RecoveryMode::TolerateTailCorruption,
-            batch_compression_threshold: ReadableSize::kb(8),
-            target_file_size: ReadableSize(config.file_size),
-            ..Default::default()
-        };
-        let engine = Arc::new(Engine::open(raft_engine_config).context(RaftEngineSnafu)?);
+        let dirs = config.log_file_dirs();
+        for (i, dir) in dirs.iter().enumerate() {
+            ensure!(
+                !dirs[..i].iter().any(|d| same_dir(d, dir)),
+                DuplicateLogFileDirSnafu { dir: *dir }
+            );
+        }
+        check_removed_dirs(&config, &dirs)?;
+
+        let engines = dirs
+            .iter()
+            .map(|dir| open_engine(&config, dir))
+            .collect::<Result<Vec<_>, _>>()?;
+        let namespace_engines = locate_namespaces(&engines, &dirs)?;
+        for dir in &dirs {
+            write_dirs_file(dir, &dirs)?;
+        }
+
         let log_store = Self {
             config,
-            engine,
+            engines,
+            namespace_engines,
             cancel_token: Mutex::new(None),
-            gc_task_handle: Mutex::new(None),
+            gc_task_handles: Mutex::new(Vec::new()),
             started: AtomicBool::new(false),
         };
         log_store.start().await?;
         Ok(log_store)
     }
 
+    /// Returns the engine storing the logs of given namespace.
+    fn engine(&self, ns: u64) -> &Arc<Engine> {
+        &self.engines[self.engine_index(ns)]
+    }
+
+    /// Returns the index of the engine storing the logs of given namespace.
+    fn engine_index(&self, ns: u64) -> usize {
+        self.namespace_engines
+            .get(&ns)
+            .copied()
+            .unwrap_or_else(|| stripe_index(ns, self.engines.len()))
+    }
+
     pub fn started(&self) -> bool {
         self.started.load(Ordering::Relaxed)
     }
 
+    /// Starts a purge task for each engine, at most [LogConfig::max_concurrent_purges]
+    /// engines are purged at the same time.
     async fn start(&self) -> Result<(), Error> {
-        let engine_clone = self.engine.clone();
         let interval = self.config.purge_interval;
+        let permits = Arc::new(Semaphore::new(self.config.max_concurrent_purges.max(1)));
         let token = CancellationToken::new();
-        let child = token.child_token();
-        // TODO(hl): Maybe spawn to a blocking runtime.
-        let handle = common_runtime::spawn_bg(async move {
-            loop {
-                tokio::select! {
-                    _ = tokio::time::sleep(interval) => {}
-                    _ = child.cancelled() => {
-                        info!("LogStore gc task has been cancelled");
-                        return;
-                    }
-                }
-                match engine_clone.purge_expired_files().context(RaftEngineSnafu) {
-                    Ok(res) => {
-                        // TODO(hl): the retval of purge_expired_files indicates the namespaces need to be compact,
-                        // which is useful when monitoring regions failed to flush it's memtable to SSTs.
-                        info!(
-                            "Successfully purged logstore files, namespaces need compaction: {:?}",
-                            res
-                        );
-                    }
-                    Err(e) => {
-                        error!(e; "Failed to purge files in logstore");
-                    }
-                }
-            }
-        });
+        let mut handles = Vec::with_capacity(self.engines.len());
+        for engine in &self.engines {
+            let engine = engine.clone();
+            let handle = common_runtime::spawn_bg(run_purge_loop(
+                interval,
+                permits.clone(),
+                token.child_token(),
+                move || purge_engine(engine.clone()),
+            ));
+            handles.push(handle);
+        }
         *self.cancel_token.lock().await = Some(token);
-        *self.gc_task_handle.lock().await = Some(handle);
+        *self.gc_task_handles.lock().await = handles;
         self.started.store(true, Ordering::Relaxed);
         info!("RaftEngineLogStore started with config: {:?}", self.config);
         Ok(())
     }
 }
 
+/// Purges expired files of the engine.
+async fn purge_engine(engine: Arc<Engine>) {
+    // Purging files is blocking IO.
+    let res = common_runtime::spawn_blocking_bg(move || {
+        engine.purge_expired_files().context(RaftEngineSnafu)
+    })
+    .await;
+    match res {
+        Ok(Ok(res)) => {
+            // TODO(hl): the retval of purge_expired_files indicates the namespaces need to be compact,
+            // which is useful when monitoring regions failed to flush it's memtable to SSTs.
+            info!(
+                "Successfully purged logstore files, namespaces need compaction: {:?}",
+                res
+            );
+        }
+        Ok(Err(e)) => {
+            error!(e; "Failed to purge files in logstore");
+        }
+        Err(e) => {
+            error!("Failed to join purge task in logstore, error: {}", e);
+        }
+    }
+}
+
 impl Debug for RaftEngineLogStore {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("RaftEngineLogsStore")
@@ -133,12 +183,7 @@ impl LogStore for RaftEngineLogStore {
                 .is_ok(),
             IllegalStateSnafu
         );
-        let handle = self
-            .gc_task_handle
-            .lock()
-            .await
-            .take()
-            .context(IllegalStateSnafu)?;
+        let handles = std::mem::take(&mut *self.gc_task_handles.lock().await);
         let token = self
             .cancel_token
             .lock()
@@ -146,21 +191,25 @@ impl LogStore for RaftEngineLogStore {
             .take()
             .context(IllegalStateSnafu)?;
         token.cancel();
-        handle.await.context(WaitGcTaskStopSnafu)?;
+        for handle in handles {
+            handle.await.context(WaitGcTaskStopSnafu)?;
+        }
         info!("RaftEngineLogStore stopped");
         Ok(())
     }
 
     /// Append an entry to logstore. Currently of existence of entry's namespace is not checked.
-    async fn append(&self, e: Self::Entry) -> Result<AppendResponse, Self::Error> {
+    async fn append(&self, mut e: Self::Entry) -> Result<AppendResponse, Self::Error> {
         ensure!(self.started(), IllegalStateSnafu);
         let entry_id = e.id;
+        let ns_id = e.namespace_id;
+        e.seal();
         let mut batch = LogBatch::with_capacity(1);
         batch
-            .add_entries::<MessageType>(e.namespace_id, &[e])
+            .add_entries::<MessageType>(ns_id, &[e])
             .context(AddEntryLogBatchSnafu)?;
 
-        self.engine
+        self.engine(ns_id)
             .write(&mut batch, self.config.sync_write)
             .context(RaftEngineSnafu)?;
         Ok(AppendResponse { entry_id })
@@ -171,15 +220,18 @@ impl LogStore for RaftEngineLogStore {
     async fn append_batch(
         &self,
         ns: &Self::Namespace,
-        entries: Vec<Self::Entry>,
+        mut entries: Vec<Self::Entry>,
     ) -> Result<Vec<Id>, Self::Error> {
         ensure!(self.started(), IllegalStateSnafu);
         let entry_ids = entries.iter().map(Entry::get_id).collect::<Vec<_>>();
+        for e in &mut entries {
+            e.seal();
+        }
         let mut batch = LogBatch::with_capacity(entries.len());
         batch
             .add_entries::<MessageType>(ns.id, &entries)
             .context(AddEntryLogBatchSnafu)?;
-        self.engine
+        self.engine(ns.id)
             .write(&mut batch, self.config.sync_write)
             .context(RaftEngineSnafu)?;
         Ok(entry_ids)
@@ -187,18 +239,25 @@ impl LogStore for RaftEngineLogStore {
 
     /// Create a stream of entries from logstore in the given namespace. The end of stream is
     /// determined by the current "last index" of the namespace.
+    ///
+    /// A corrupted last entry is regarded as a torn write and dropped with a warning, unless
+    /// the recovery mode is [RecoveryMode::AbsoluteConsistency]. Any other corrupted entry
+    /// fails the stream.
     async fn read(
         &self,
         ns: &Self::Namespace,
         id: Id,
     ) -> Result<SendableEntryStream<'_, Self::Entry, Self::Error>, Self::Error> {
         ensure!(self.started(), IllegalStateSnafu);
-        let engine = self.engine.clone();
+        let index = self.engine_index(ns.id);
+        let engine = self.engines[index].clone();
+        let dir = self.config.log_file_dirs()[index].to_string();
 
         let last_index = engine.last_index(ns.id).unwrap_or(0);
         let mut start_index = id.max(engine.first_index(ns.id).unwrap_or(last_index + 1));
 
         let max_batch_size = self.config.read_batch_size;
+        let recovery_mode = self.config.recovery_mode;
         let (tx, mut rx) = tokio::sync::mpsc::channel(max_batch_size);
         let ns = ns.clone();
         common_runtime::spawn_read(async move {
@@ -219,6 +278,32 @@ impl LogStore for RaftEngineLogStore {
                         max_size: max_batch_size,
                     }) {
                     Ok(_) => {
+                        if let Some(pos) = vec.iter().position(|e| !e.is_valid()) {
+                            let entry = &vec[pos];
+                            if entry.id == last_index
+                                && recovery_mode == RecoveryMode::TolerateTailCorruption
+                            {
+                                warn!(
+                                    "Dropping torn entry {} at the end of namespace {}",
+                                    entry.id, ns.id
+                                );
+                                vec.truncate(pos);
+                                let _ = tx.send(Ok(vec)).await;
+                            } else {
+                                let (file, offset) = locate_entry(&engine, &dir, ns.id, entry.id);
+                                let e = CorruptedEntrySnafu {
+                                    ns: ns.id,
+                                    id: entry.id,
+                                    file,
+                                    offset,
+                                    expected: entry.crc,
+                                    actual: entry.checksum(),
+                                }
+                                .build();
+                                let _ = tx.send(Err(e)).await;
+                            }
+                            break;
+                        }
                         if let Some(last_entry) = vec.last() {
                             start_index = last_entry.id + 1;
                         }
@@ -254,7 +339,7 @@ impl LogStore for RaftEngineLogStore {
         batch
             .put_message::<Namespace>(SYSTEM_NAMESPACE, key, ns)
             .context(RaftEngineSnafu)?;
-        self.engine
+        self.engine(ns.id)
             .write(&mut batch, true)
             .context(RaftEngineSnafu)?;
         Ok(())
@@ -269,7 +354,7 @@ impl LogStore for RaftEngineLogStore {
         let key = format!("{}{}", NAMESPACE_PREFIX, ns.id).as_bytes().to_vec();
         let mut batch = LogBatch::with_capacity(1);
         batch.delete(SYSTEM_NAMESPACE, key);
-        self.engine
+        self.engine(ns.id)
             .write(&mut batch, true)
             .context(RaftEngineSnafu)?;
         Ok(())
@@ -278,18 +363,20 @@ impl LogStore for RaftEngineLogStore {
     async fn list_namespaces(&self) -> Result<Vec<Self::Namespace>, Self::Error> {
         ensure!(self.started(), IllegalStateSnafu);
         let mut namespaces: Vec<Namespace> = vec![];
-        self.engine
-            .scan_messages::<Namespace, _>(
-                SYSTEM_NAMESPACE,
-                Some(NAMESPACE_PREFIX.as_bytes()),
-                None,
-                false,
-                |_, v| {
-                    namespaces.push(v);
-                    true
-                },
-            )
-            .context(RaftEngineSnafu)?;
+        for engine in &self.engines {
+            engine
+                .scan_messages::<Namespace, _>(
+                    SYSTEM_NAMESPACE,
+                    Some(NAMESPACE_PREFIX.as_bytes()),
+                    None,
+                    false,
+                    |_, v| {
+                        namespaces.push(v);
+                        true
+                    },
+                )
+                .context(RaftEngineSnafu)?;
+        }
         Ok(namespaces)
     }
 
@@ -311,7 +398,9 @@ impl LogStore for RaftEngineLogStore {
 
     async fn obsolete(&self, namespace: Self::Namespace, id: Id) -> Result<(), Self::Error> {
         ensure!(self.started(), IllegalStateSnafu);
-        let obsoleted = self.engine.compact_to(namespace.id(), id + 1);
+        let obsoleted = self
+            .engine(namespace.id())
+            .compact_to(namespace.id(), id + 1);
         info!(
             "Namespace {} obsoleted {} entries",
             namespace.id(),
@@ -319,6 +408,151 @@ impl LogStore for RaftEngineLogStore {
         );
         Ok(())
     }
+
+    async fn purge(&self, namespace: &Self::Namespace) -> Result<(), Self::Error> {
+        ensure!(self.started(), IllegalStateSnafu);
+        let engine = self.engine(namespace.id()).clone();
+        // Both syncing and purging files are blocking IO.
+        common_runtime::spawn_blocking_bg(move || {
+            engine.sync()?;
+            engine.purge_expired_files()
+        })
+        .await
+        .context(JoinPurgeTaskSnafu)?
+        .context(RaftEngineSnafu)?;
+        info!("Namespace {} purged", namespace.id());
+        Ok(())
+    }
+}
+
+fn open_engine(config: &LogConfig, dir: &str) -> Result<Arc<Engine>, Error> {
+    // TODO(hl): set according to available disk space
+    let raft_engine_config = Config {
+        dir: dir.to_string(),
+        purge_threshold: ReadableSize(config.purge_threshold),
+        recovery_mode: match config.recovery_mode {
+            RecoveryMode::TolerateTailCorruption => {
+                raft_engine::RecoveryMode::TolerateTailCorruption
+            }
+            RecoveryMode::AbsoluteConsistency => raft_engine::RecoveryMode::AbsoluteConsistency,
+        },
+        batch_compression_threshold: ReadableSize::kb(8),
+        target_file_size: ReadableSize(config.file_size),
+        ..Default::default()
+    };
+    match Engine::open_with_file_system(raft_engine_config, Arc::new(LocatingFileSystem)) {
+        Ok(engine) => Ok(Arc::new(engine)),
+        Err(e @ raft_engine::Error::Corruption(_)) => Err(e).context(CorruptedLogFileSnafu { dir }),
+        Err(e) => Err(e).context(RaftEngineSnafu),
+    }
+}
+
+/// Returns the log file and the offset of the block holding the entry. The directory of the
+/// log files and offset 0 are returned if the entry is purged.
+fn locate_entry(engine: &Engine, dir: &str, ns: u64, id: u64) -> (String, u64) {
+    // Reads the entry on a new thread, as raft-engine caches the last block read by each
+    // thread.
+    let last_read = std::thread::scope(|s| {
+        s.spawn(|| {
+            // The block is read even if the entry fails to decode.
+            let _ = engine.get_entry::<MessageType>(ns, id);
+            file_system::take_last_read()
+        })
+        .join()
+        .ok()
+        .flatten()
+    });
+    match last_read {
+        Some((path, offset)) => (path.display().to_string(), offset),
+        None => (dir.to_string(), 0),
+    }
+}
+
+/// Picks the directory of a namespace not found on startup. The result only depends on the
+/// namespace id and the number of directories.
+fn stripe_index(ns: u64, num_dirs: usize) -> usize {
+    // Fibonacci hashing, so region ids of the same table spread across directories.
+    ((ns.wrapping_mul(0x9E37_79B9_7F4A_7C15) >> 32) % num_dirs as u64) as usize
+}
+
+fn same_dir(a: &str, b: &str) -> bool {
+    a.trim_end_matches('/') == b.trim_end_matches('/')
+}
+
+/// Returns namespaces with logs in the engine.
+fn namespaces_with_logs(engine: &Engine) -> Vec<u64> {
+    let mut namespaces = engine
+        .raft_groups()
+        .into_iter()
+        .filter(|ns| *ns != SYSTEM_NAMESPACE && engine.first_index(*ns).is_some())
+        .collect::<Vec<_>>();
+    namespaces.sort_unstable();
+    namespaces
+}
+
+/// Maps each namespace with logs to the index of the engine holding it.
+fn locate_namespaces(engines: &[Arc<Engine>], dirs: &[&str]) -> Result<HashMap<u64, usize>, Error> {
+    let mut namespace_engines = HashMap::new();
+    for (i, engine) in engines.iter().enumerate() {
+        for ns in namespaces_with_logs(engine) {
+            if let Some(prev) = namespace_engines.insert(ns, i) {
+                return NamespaceInMultipleDirsSnafu {
+                    ns,
+                    dirs: vec![dirs[prev].to_string(), dirs[i].to_string()],
+                }
+                .fail();
+            }
+        }
+    }
+    Ok(namespace_engines)
+}
+
+/// Ensures directories recorded by previous startups but removed from the config hold no
+/// logs, otherwise those logs would be silently lost.
+fn check_removed_dirs(config: &LogConfig, dirs: &[&str]) -> Result<(), Error> {
+    let mut recorded = BTreeSet::new();
+    for dir in dirs {
+        recorded.extend(read_dirs_file(dir)?);
+    }
+    for removed in recorded
+        .iter()
+        .filter(|r| !dirs.iter().any(|d| same_dir(d, r)))
+    {
+        if !Path::new(removed).exists() {
+            continue;
+        }
+        let regions = namespaces_with_logs(&*open_engine(config, removed)?);
+        ensure!(
+            regions.is_empty(),
+            OrphanedWalDirSnafu {
+                dir: removed,
+                regions,
+            }
+        );
+    }
+    Ok(())
+}
+
+fn read_dirs_file(dir: &str) -> Result<Vec<String>, Error> {
+    let path = Path::new(dir).join(WAL_DIRS_FILE);
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let content = std::fs::read_to_string(&path).context(WalDirsFileSnafu {
+        path: path.to_string_lossy(),
+    })?;
+    Ok(content
+        .lines()
+        .filter(|l| !l.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+fn write_dirs_file(dir: &str, dirs: &[&str]) -> Result<(), Error> {
+    let path = Path::new(dir).join(WAL_DIRS_FILE);
+    std::fs::write(&path, dirs.join("\n")).context(WalDirsFileSnafu {
+        path: path.to_string_lossy(),
+    })
 }
 
 #[derive(Debug, Clone)]
@@ -340,14 +574,14 @@ mod tests {
     use common_telemetry::debug;
     use common_test_util::temp_dir::create_temp_dir;
     use futures_util::StreamExt;
-    use raft_engine::ReadableSize;
+    use raft_engine::{LogBatch, ReadableSize};
     use store_api::logstore::entry_stream::SendableEntryStream;
     use store_api::logstore::namespace::Namespace as NamespaceTrait;
     use store_api::logstore::LogStore;
 
-    use crate::config::LogConfig;
+    use crate::config::{LogConfig, RecoveryMode};
     use crate::error::Error;
-    use crate::raft_engine::log_store::RaftEngineLogStore;
+    use crate::raft_engine::log_store::{namespaces_with_logs, MessageType, RaftEngineLogStore};
     use crate::raft_engine::protos::logstore::{EntryImpl as Entry, NamespaceImpl as Namespace};
 
     #[tokio::test]
@@ -521,6 +755,38 @@ mod tests {
         assert!(before_purge > after_purge);
     }
 
+    #[tokio::test]
+    async fn test_purge() {
+        common_telemetry::init_default_ut_logging();
+        let dir = create_temp_dir("raft-engine-logstore-test");
+
+        let config = LogConfig {
+            log_file_dir: dir.path().to_str().unwrap().to_string(),
+            file_size: ReadableSize::mb(2).0,
+            purge_threshold: ReadableSize::mb(4).0,
+            // The background purge never runs in this test.
+            purge_interval: Duration::from_secs(3600),
+            ..Default::default()
+        };
+
+        let logstore = RaftEngineLogStore::try_new(config).await.unwrap();
+        let namespace = Namespace::with_id(42);
+        for id in 0..4096 {
+            let entry = Entry::create(id, namespace.id(), [b'x'; 4096].to_vec());
+            logstore.append(entry).await.unwrap();
+        }
+
+        let before_purge = wal_dir_usage(dir.path().to_str().unwrap()).await;
+        logstore.obsolete(namespace.clone(), 4095).await.unwrap();
+        logstore.purge(&namespace).await.unwrap();
+        let after_purge = wal_dir_usage(dir.path().to_str().unwrap()).await;
+        assert!(before_purge > after_purge);
+        assert_eq!(
+            None,
+            logstore.engine(namespace.id).first_index(namespace.id)
+        );
+    }
+
     #[tokio::test]
     async fn test_obsolete() {
         common_telemetry::init_default_ut_logging();
@@ -543,11 +809,314 @@ mod tests {
         }
 
         logstore.obsolete(namespace.clone(), 100).await.unwrap();
-        assert_eq!(101, logstore.engine.first_index(namespace.id).unwrap());
+        assert_eq!(
+            101,
+            logstore
+                .engine(namespace.id)
+                .first_index(namespace.id)
+                .unwrap()
+        );
 
         let res = logstore.read(&namespace, 100).await.unwrap();
         let mut vec = collect_entries(res).await;
         vec.sort_by(|a, b| a.id.partial_cmp(&b.id).unwrap());
         assert_eq!(101, vec.first().unwrap().id);
     }
+
+    /// Writes an entry with a wrong checksum, bypassing the checksum computation in append.
+    fn write_corrupted_entry(logstore: &RaftEngineLogStore, id: u64, ns: u64) {
+        let mut entry = Entry::create(id, ns, id.to_string().as_bytes().to_vec());
+        entry.seal();
+        entry.crc = entry.crc.wrapping_add(1);
+        write_raw_entry(logstore, entry);
+    }
+
+    /// Writes the entry as is, bypassing the checksum computation in append.
+    fn write_raw_entry(logstore: &RaftEngineLogStore, entry: Entry) {
+        let ns = entry.namespace_id;
+        let mut batch = LogBatch::with_capacity(1);
+        batch.add_entries::<MessageType>(ns, &[entry]).unwrap();
+        logstore.engine(ns).write(&mut batch, true).unwrap();
+    }
+
+    async fn new_logstore_with_entries(
+        dir: &str,
+        recovery_mode: RecoveryMode,
+        ids: std::ops::Range<u64>,
+    ) -> RaftEngineLogStore {
+        let logstore = RaftEngineLogStore::try_new(LogConfig {
+            log_file_dir: dir.to_string(),
+            recovery_mode,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        for id in ids {
+            logstore
+                .append(Entry::create(id, 1, id.to_string().as_bytes().to_vec()))
+                .await
+                .unwrap();
+        }
+        logstore
+    }
+
+    #[tokio::test]
+    async fn test_read_with_checksum() {
+        let dir = create_temp_dir("raft-engine-logstore-checksum-test");
+        let logstore = new_logstore_with_entries(
+            dir.path().to_str().unwrap(),
+            RecoveryMode::TolerateTailCorruption,
+            0..10,
+        )
+        .await;
+
+        let entries =
+            collect_entries(logstore.read(&Namespace::with_id(1), 0).await.unwrap()).await;
+        assert_eq!(10, entries.len());
+        assert!(entries.iter().all(|e| e.crc != 0 && e.is_valid()));
+    }
+
+    #[tokio::test]
+    async fn test_read_entries_without_checksum() {
+        let dir = create_temp_dir("raft-engine-logstore-legacy-test");
+        let logstore = RaftEngineLogStore::try_new(LogConfig {
+            log_file_dir: dir.path().to_str().unwrap().to_string(),
+            recovery_mode: RecoveryMode::AbsoluteConsistency,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        // Entries written before the checksum is added.
+        for id in 0..5 {
+            write_raw_entry(
+                &logstore,
+                Entry::create(id, 1, id.to_string().as_bytes().to_vec()),
+            );
+        }
+        logstore
+            .append(Entry::create(5, 1, "5".as_bytes().to_vec()))
+            .await
+            .unwrap();
+
+        let entries =
+            collect_entries(logstore.read(&Namespace::with_id(1), 0).await.unwrap()).await;
+        assert_eq!(6, entries.len());
+        assert!(entries[..5].iter().all(|e| e.version == 0 && e.crc == 0));
+        assert_ne!(0, entries[5].version);
+    }
+
+    #[tokio::test]
+    async fn test_read_torn_last_entry() {
+        common_telemetry::init_default_ut_logging();
+        let dir = create_temp_dir("raft-engine-logstore-torn-test");
+        let logstore = new_logstore_with_entries(
+            dir.path().to_str().unwrap(),
+            RecoveryMode::TolerateTailCorruption,
+            0..10,
+        )
+        .await;
+        write_corrupted_entry(&logstore, 10, 1);
+
+        let entries =
+            collect_entries(logstore.read(&Namespace::with_id(1), 0).await.unwrap()).await;
+        assert_eq!(
+            (0..10).collect::<Vec<_>>(),
+            entries.iter().map(|e| e.id).collect::<Vec<_>>()
+        );
+
+        // the torn entry is overwritten by later appends
+        logstore
+            .append(Entry::create(10, 1, "10".as_bytes().to_vec()))
+            .await
+            .unwrap();
+        let entries =
+            collect_entries(logstore.read(&Namespace::with_id(1), 0).await.unwrap()).await;
+        assert_eq!(11, entries.len());
+    }
+
+    #[tokio::test]
+    async fn test_read_torn_last_entry_absolute_consistency() {
+        let dir = create_temp_dir("raft-engine-logstore-torn-test");
+        let logstore = new_logstore_with_entries(
+            dir.path().to_str().unwrap(),
+            RecoveryMode::AbsoluteConsistency,
+            0..10,
+        )
+        .await;
+        write_corrupted_entry(&logstore, 10, 1);
+
+        let mut s = logstore.read(&Namespace::with_id(1), 0).await.unwrap();
+        let mut err = None;
+        while let Some(r) = s.next().await {
+            if let Err(e) = r {
+                err = Some(e);
+            }
+        }
+        assert!(matches!(err, Some(Error::CorruptedEntry { id: 10, .. })));
+    }
+
+    #[tokio::test]
+    async fn test_read_corrupted_middle_entry() {
+        let dir = create_temp_dir("raft-engine-logstore-corrupted-test");
+        let logstore = new_logstore_with_entries(
+            dir.path().to_str().unwrap(),
+            RecoveryMode::TolerateTailCorruption,
+            0..5,
+        )
+        .await;
+        write_corrupted_entry(&logstore, 5, 1);
+        for id in 6..10 {
+            logstore
+                .append(Entry::create(id, 1, id.to_string().as_bytes().to_vec()))
+                .await
+                .unwrap();
+        }
+
+        let mut s = logstore.read(&Namespace::with_id(1), 0).await.unwrap();
+        let mut ids = vec![];
+        let mut err = None;
+        while let Some(r) = s.next().await {
+            match r {
+                Ok(entries) => ids.extend(entries.into_iter().map(|e| e.id)),
+                Err(e) => err = Some(e),
+            }
+        }
+        assert!(ids.iter().all(|id| *id < 5));
+        match err {
+            Some(Error::CorruptedEntry {
+                ns: 1,
+                id: 5,
+                file,
+                offset,
+                ..
+            }) => {
+                assert!(file.ends_with(".raftlog"), "{file}");
+                assert!(file.starts_with(dir.path().to_str().unwrap()), "{file}");
+                assert!(offset > 0);
+            }
+            _ => panic!("unexpected error: {err:?}"),
+        }
+    }
+
+    fn multi_dir_config(dirs: &[&str]) -> LogConfig {
+        LogConfig {
+            log_file_dir: dirs[0].to_string(),
+            extra_log_file_dirs: dirs[1..].iter().map(|d| d.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    async fn append_to_namespaces(logstore: &RaftEngineLogStore, namespaces: std::ops::Range<u64>) {
+        for ns in namespaces {
+            for id in 0..3 {
+                logstore
+                    .append(Entry::create(id, ns, id.to_string().as_bytes().to_vec()))
+                    .await
+                    .unwrap();
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reopen_multiple_dirs() {
+        let dir0 = create_temp_dir("raft-engine-logstore-multi-dir-0");
+        let dir1 = create_temp_dir("raft-engine-logstore-multi-dir-1");
+        let dirs = [dir0.path().to_str().unwrap(), dir1.path().to_str().unwrap()];
+        {
+            let logstore = RaftEngineLogStore::try_new(multi_dir_config(&dirs))
+                .await
+                .unwrap();
+            append_to_namespaces(&logstore, 1..17).await;
+            // logs are striped across both directories
+            assert!(logstore
+                .engines
+                .iter()
+                .all(|e| !namespaces_with_logs(e).is_empty()));
+            logstore.stop().await.unwrap();
+        }
+
+        let logstore = RaftEngineLogStore::try_new(multi_dir_config(&dirs))
+            .await
+            .unwrap();
+        for ns in 1..17 {
+            let entries =
+                collect_entries(logstore.read(&Namespace::with_id(ns), 0).await.unwrap()).await;
+            assert_eq!(
+                vec![0, 1, 2],
+                entries.iter().map(|e| e.id).collect::<Vec<_>>()
+            );
+            assert!(entries.iter().all(|e| e.namespace_id == ns));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_remove_dir_with_logs() {
+        let dir0 = create_temp_dir("raft-engine-logstore-remove-dir-0");
+        let dir1 = create_temp_dir("raft-engine-logstore-remove-dir-1");
+        let dirs = [dir0.path().to_str().unwrap(), dir1.path().to_str().unwrap()];
+        let expected = {
+            let logstore = RaftEngineLogStore::try_new(multi_dir_config(&dirs))
+                .await
+                .unwrap();
+            append_to_namespaces(&logstore, 1..17).await;
+            logstore.stop().await.unwrap();
+            namespaces_with_logs(&logstore.engines[1])
+        };
+        assert!(!expected.is_empty());
+
+        let err = RaftEngineLogStore::try_new(multi_dir_config(&dirs[..1]))
+            .await
+            .unwrap_err();
+        match err {
+            Error::OrphanedWalDir { dir, regions, .. } => {
+                assert_eq!(dirs[1], dir);
+                assert_eq!(expected, regions);
+            }
+            e => panic!("unexpected error: {e}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_add_dir() {
+        let dir0 = create_temp_dir("raft-engine-logstore-add-dir-0");
+        let dir1 = create_temp_dir("raft-engine-logstore-add-dir-1");
+        let dirs = [dir0.path().to_str().unwrap(), dir1.path().to_str().unwrap()];
+        {
+            let logstore = RaftEngineLogStore::try_new(multi_dir_config(&dirs[..1]))
+                .await
+                .unwrap();
+            append_to_namespaces(&logstore, 1..9).await;
+            logstore.stop().await.unwrap();
+        }
+
+        let logstore = RaftEngineLogStore::try_new(multi_dir_config(&dirs))
+            .await
+            .unwrap();
+        // existing namespaces stay in the old directory
+        assert_eq!(
+            (1..9).collect::<Vec<_>>(),
+            namespaces_with_logs(&logstore.engines[0])
+        );
+        for ns in 1..9 {
+            let entries =
+                collect_entries(logstore.read(&Namespace::with_id(ns), 0).await.unwrap()).await;
+            assert_eq!(3, entries.len());
+        }
+
+        // only new namespaces are striped to the new directory
+        append_to_namespaces(&logstore, 9..25).await;
+        let in_new_dir = namespaces_with_logs(&logstore.engines[1]);
+        assert!(!in_new_dir.is_empty());
+        assert!(in_new_dir.iter().all(|ns| *ns >= 9));
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_dirs() {
+        let dir = create_temp_dir("raft-engine-logstore-duplicate-dir");
+        let path = dir.path().to_str().unwrap();
+        let err = RaftEngineLogStore::try_new(multi_dir_config(&[path, &format!("{path}/")]))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::DuplicateLogFileDir { .. }));
+    }
 }
@@ -23,7 +23,7 @@ use common_telemetry::info;
 use heartbeat::Client as HeartbeatClient;
 use lock::Client as LockClient;
 use router::Client as RouterClient;
-use snafu::OptionExt;
+use snafu::{ensure, OptionExt};
 use store::Client as StoreClient;
 
 pub use self::heartbeat::{HeartbeatSender, HeartbeatStream};
@@ -34,8 +34,9 @@ use crate::rpc::router::DeleteRequest;
 use crate::rpc::{
     BatchGetRequest, BatchGetResponse, BatchPutRequest, BatchPutResponse, CompareAndPutRequest,
     CompareAndPutResponse, CreateRequest, DeleteRangeRequest, DeleteRangeResponse,
-    MoveValueRequest, MoveValueResponse, PutRequest, PutResponse, RangeRequest, RangeResponse,
-    RouteRequest, RouteResponse,
+    ListTableRoutesRequest, ListTableRoutesResponse, MoveValueRequest, MoveValueResponse,
+    PutRequest, PutResponse, RangeRequest, RangeResponse, RenameRouteRequest, RouteRequest,
+    RouteResponse,
 };
 
 pub type Id = (u64, u64);
@@ -236,6 +237,58 @@ impl MetaClient {
         self.router_client()?.delete(req.into()).await?.try_into()
     }
 
+    /// Lists routes of all tables in pages, optionally filtered by catalog and schema,
+    /// without walking the tables one by one.
+    ///
+    /// Pass the `next_page_token` of a page to the request of the next page, see
+    /// [ListTableRoutesResponse] for the consistency of the pages.
+    pub async fn list_table_routes(
+        &self,
+        req: ListTableRoutesRequest,
+    ) -> Result<ListTableRoutesResponse> {
+        let res = self.range(req.to_range_request()).await?;
+        ListTableRoutesResponse::try_new(&req, res)
+    }
+
+    /// Moves the route of a table to its new name, fails if a route of the new name exists.
+    ///
+    /// Renaming a route again is a no-op, so it's safe to retry after failures.
+    pub async fn rename_route(&self, req: RenameRouteRequest) -> Result<()> {
+        let key = req.route_key();
+        let new_key = req.new_route_key();
+        let mut res = self
+            .range(RangeRequest::new().with_key(key.clone()))
+            .await?;
+        let Some(kv) = res.take_kvs().pop() else {
+            let mut res = self.range(RangeRequest::new().with_key(new_key)).await?;
+            ensure!(
+                !res.take_kvs().is_empty(),
+                error::TableRouteNotFoundSnafu {
+                    table_name: req.table_name.to_string(),
+                }
+            );
+            return Ok(());
+        };
+
+        let value = req.rename_value(kv.value())?;
+        let res = self
+            .compare_and_put(
+                CompareAndPutRequest::new()
+                    .with_key(new_key)
+                    .with_value(value),
+            )
+            .await?;
+        ensure!(
+            res.is_success(),
+            error::TableRouteExistsSnafu {
+                table_name: req.renamed_table_name().to_string(),
+            }
+        );
+        self.delete_range(DeleteRangeRequest::new().with_key(key))
+            .await?;
+        Ok(())
+    }
+
     /// Range gets the keys in the range from the key-value store.
     pub async fn range(&self, req: RangeRequest) -> Result<RangeResponse> {
         self.store_client()?.range(req.into()).await?.try_into()
@@ -556,21 +609,19 @@ mod tests {
         type Context = Context;
         type Output = Vec<Peer>;
 
-        async fn select(&self, _ns: Namespace, _ctx: &Self::Context) -> MetaResult<Self::Output> {
-            Ok(vec![
-                Peer {
-                    id: 0,
-                    addr: "peer0".to_string(),
-                },
-                Peer {
-                    id: 1,
-                    addr: "peer1".to_string(),
-                },
-                Peer {
-                    id: 2,
-                    addr: "peer2".to_string(),
-                },
-            ])
+        async fn select_n(
+            &self,
+            _ns: Namespace,
+            _ctx: &Self::Context,
+            count: usize,
+        ) -> MetaResult<Self::Output> {
+            Ok((0..3)
+                .take(count)
+                .map(|id| Peer {
+                    id,
+                    addr: format!("peer{id}"),
+                })
+                .collect())
         }
     }
 
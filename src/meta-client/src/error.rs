@@ -66,6 +66,18 @@ pub enum Error {
         backtrace: Backtrace,
     },
 
+    #[snafu(display("Route of table {} not found", table_name))]
+    TableRouteNotFound {
+        table_name: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Route of table {} already exists", table_name))]
+    TableRouteExists {
+        table_name: String,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Illegal state from server, code: {}, error: {}", code, err_msg))]
     IllegalServerState {
         code: i32,
@@ -106,6 +118,8 @@ impl ErrorExt for Error {
             | Error::IllegalServerState { .. }
             | Error::SerdeJson { .. } => StatusCode::Internal,
             Error::RouteInfoCorrupted { .. } => StatusCode::Unexpected,
+            Error::TableRouteNotFound { .. } => StatusCode::TableNotFound,
+            Error::TableRouteExists { .. } => StatusCode::TableAlreadyExists,
         }
     }
 }
@@ -24,7 +24,8 @@ use api::v1::meta::{
     TableName as PbTableName,
 };
 pub use router::{
-    CreateRequest, Partition, Region, RouteRequest, RouteResponse, Table, TableRoute,
+    CreateRequest, ListTableRoutesRequest, ListTableRoutesResponse, Partition, Region,
+    RenameRouteRequest, RouteRequest, RouteResponse, Table, TableRoute,
 };
 use serde::{Deserialize, Serialize};
 pub use store::{
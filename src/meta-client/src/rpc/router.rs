@@ -17,7 +17,7 @@ use std::collections::{HashMap, HashSet};
 use api::v1::meta::{
     CreateRequest as PbCreateRequest, DeleteRequest as PbDeleteRequest, Partition as PbPartition,
     Region as PbRegion, RouteRequest as PbRouteRequest, RouteResponse as PbRouteResponse,
-    Table as PbTable,
+    Table as PbTable, TableRouteValue as PbTableRouteValue,
 };
 use serde::{Deserialize, Serialize, Serializer};
 use snafu::{OptionExt, ResultExt};
@@ -25,7 +25,10 @@ use table::metadata::RawTableInfo;
 
 use crate::error;
 use crate::error::Result;
-use crate::rpc::{util, Peer, TableName};
+use crate::rpc::{util, Peer, RangeRequest, RangeResponse, TableName};
+
+/// Key prefix of table routes in the meta store, the same as the metasrv.
+const TABLE_ROUTE_PREFIX: &str = "__meta_table_route";
 
 #[derive(Debug, Clone)]
 pub struct CreateRequest<'a> {
@@ -114,6 +117,192 @@ impl DeleteRequest {
     }
 }
 
+/// Renames the route of a table, the id and the regions of the table are unchanged.
+#[derive(Debug, Clone)]
+pub struct RenameRouteRequest {
+    pub table_id: u64,
+    pub table_name: TableName,
+    pub new_table_name: String,
+}
+
+impl RenameRouteRequest {
+    #[inline]
+    pub fn new(table_id: u64, table_name: TableName, new_table_name: impl Into<String>) -> Self {
+        Self {
+            table_id,
+            table_name,
+            new_table_name: new_table_name.into(),
+        }
+    }
+
+    pub(crate) fn renamed_table_name(&self) -> TableName {
+        TableName {
+            table_name: self.new_table_name.clone(),
+            ..self.table_name.clone()
+        }
+    }
+
+    /// Route key of the table before renaming.
+    pub(crate) fn route_key(&self) -> String {
+        route_key(self.table_id, &self.table_name)
+    }
+
+    /// Route key of the table after renaming.
+    pub(crate) fn new_route_key(&self) -> String {
+        route_key(self.table_id, &self.renamed_table_name())
+    }
+
+    /// Returns the route `value` with the table name replaced by the new name.
+    pub(crate) fn rename_value(&self, value: &[u8]) -> Result<Vec<u8>> {
+        let mut value = PbTableRouteValue::try_from(value).map_err(|e| {
+            error::RouteInfoCorruptedSnafu {
+                err_msg: format!("failed to decode table route, {e}"),
+            }
+            .build()
+        })?;
+        let table = value
+            .table_route
+            .as_mut()
+            .and_then(|route| route.table.as_mut())
+            .context(error::RouteInfoCorruptedSnafu {
+                err_msg: "table required",
+            })?;
+        table.table_name = Some(self.renamed_table_name().into());
+        Ok(value.into())
+    }
+}
+
+fn route_key(table_id: u64, table_name: &TableName) -> String {
+    format!(
+        "{TABLE_ROUTE_PREFIX}-{}-{}-{}-{table_id}",
+        table_name.catalog_name, table_name.schema_name, table_name.table_name
+    )
+}
+
+/// Lists routes of tables in pages, in the order of their route keys.
+#[derive(Debug, Clone, Default)]
+pub struct ListTableRoutesRequest {
+    pub catalog_name: Option<String>,
+    /// Only takes effect with `catalog_name`.
+    pub schema_name: Option<String>,
+    /// Max number of routes in a page, no limit if it's 0.
+    pub limit: usize,
+    /// `next_page_token` of the previous page.
+    pub page_token: Option<String>,
+}
+
+impl ListTableRoutesRequest {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn with_catalog(mut self, catalog_name: impl Into<String>) -> Self {
+        self.catalog_name = Some(catalog_name.into());
+        self
+    }
+
+    #[inline]
+    pub fn with_schema(mut self, schema_name: impl Into<String>) -> Self {
+        self.schema_name = Some(schema_name.into());
+        self
+    }
+
+    #[inline]
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    #[inline]
+    pub fn with_page_token(mut self, page_token: impl Into<String>) -> Self {
+        self.page_token = Some(page_token.into());
+        self
+    }
+
+    fn prefix(&self) -> String {
+        match (&self.catalog_name, &self.schema_name) {
+            (Some(catalog), Some(schema)) => format!("{TABLE_ROUTE_PREFIX}-{catalog}-{schema}-"),
+            (Some(catalog), None) => format!("{TABLE_ROUTE_PREFIX}-{catalog}-"),
+            (None, _) => format!("{TABLE_ROUTE_PREFIX}-"),
+        }
+    }
+
+    /// Range of route keys in the page.
+    pub(crate) fn to_range_request(&self) -> RangeRequest {
+        let prefix = self.prefix();
+        let range_end = util::get_prefix_end_key(prefix.as_bytes());
+        let key = match &self.page_token {
+            // Starts from the key right after the token.
+            Some(token) if token.starts_with(&prefix) => format!("{token}\0"),
+            _ => prefix,
+        };
+        RangeRequest::new()
+            .with_range(key, range_end)
+            .with_limit(self.limit as i64)
+    }
+
+    fn matches(&self, table_name: &TableName) -> bool {
+        // Names may contain '-', so the key prefix may match tables of other schemas.
+        self.catalog_name
+            .as_ref()
+            .map_or(true, |catalog| &table_name.catalog_name == catalog)
+            && self
+                .schema_name
+                .as_ref()
+                .map_or(true, |schema| &table_name.schema_name == schema)
+    }
+}
+
+/// A page of table routes. `table_schema` of the tables are not filled.
+///
+/// Each page is read from a single revision of the meta store, but different pages may be
+/// read from different revisions: tables created or dropped while listing may be missing
+/// from the result. The page token only depends on the data in the meta store, so it's
+/// still valid after the leader of the metasrv changes.
+#[derive(Debug, Clone)]
+pub struct ListTableRoutesResponse {
+    pub table_routes: Vec<TableRoute>,
+    /// Token to get the next page, `None` if this is the last page.
+    pub next_page_token: Option<String>,
+}
+
+impl ListTableRoutesResponse {
+    pub(crate) fn try_new(req: &ListTableRoutesRequest, mut res: RangeResponse) -> Result<Self> {
+        let kvs = res.take_kvs();
+        let next_page_token = match kvs.last() {
+            Some(kv) if res.more() => Some(String::from_utf8_lossy(kv.key()).to_string()),
+            _ => None,
+        };
+
+        let mut table_routes = Vec::with_capacity(kvs.len());
+        for kv in &kvs {
+            let value = PbTableRouteValue::try_from(kv.value()).map_err(|e| {
+                error::RouteInfoCorruptedSnafu {
+                    err_msg: format!("failed to decode table route, {e}"),
+                }
+                .build()
+            })?;
+            let res = RouteResponse::try_from(PbRouteResponse {
+                header: None,
+                peers: value.peers,
+                table_routes: value.table_route.into_iter().collect(),
+            })?;
+            table_routes.extend(
+                res.table_routes
+                    .into_iter()
+                    .filter(|route| req.matches(&route.table.table_name)),
+            );
+        }
+
+        Ok(Self {
+            table_routes,
+            next_page_token,
+        })
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct RouteResponse {
     pub table_routes: Vec<TableRoute>,
@@ -303,8 +492,9 @@ impl From<PbPartition> for Partition {
 #[cfg(test)]
 mod tests {
     use api::v1::meta::{
-        DeleteRequest as PbDeleteRequest, Partition as PbPartition, Peer as PbPeer,
-        Region as PbRegion, RegionRoute as PbRegionRoute, RouteRequest as PbRouteRequest,
+        DeleteRequest as PbDeleteRequest, KeyValue as PbKeyValue, Partition as PbPartition,
+        Peer as PbPeer, RangeResponse as PbRangeResponse, Region as PbRegion,
+        RegionRoute as PbRegionRoute, RouteRequest as PbRouteRequest,
         RouteResponse as PbRouteResponse, Table as PbTable, TableName as PbTableName,
         TableRoute as PbTableRoute,
     };
@@ -394,6 +584,130 @@ mod tests {
         );
     }
 
+    fn new_table_route_kv(table_id: u64, schema: &str, table: &str) -> PbKeyValue {
+        let value = PbTableRouteValue {
+            peers: vec![PbPeer {
+                id: 1,
+                addr: "peer1".to_string(),
+            }],
+            table_route: Some(PbTableRoute {
+                table: Some(PbTable {
+                    id: table_id,
+                    table_name: Some(PbTableName {
+                        catalog_name: "c1".to_string(),
+                        schema_name: schema.to_string(),
+                        table_name: table.to_string(),
+                    }),
+                    table_schema: vec![],
+                }),
+                region_routes: vec![PbRegionRoute {
+                    region: Some(PbRegion {
+                        id: 1,
+                        ..Default::default()
+                    }),
+                    leader_peer_index: 0,
+                    follower_peer_indexes: vec![],
+                }],
+            }),
+        };
+        PbKeyValue {
+            key: format!("{TABLE_ROUTE_PREFIX}-c1-{schema}-{table}-{table_id}").into_bytes(),
+            value: value.into(),
+        }
+    }
+
+    #[test]
+    fn test_list_table_routes_request() {
+        let req = ListTableRoutesRequest::new().with_limit(10);
+        let range = req.to_range_request();
+        assert_eq!(b"__meta_table_route-".to_vec(), range.key);
+        assert_eq!(b"__meta_table_route.".to_vec(), range.range_end);
+        assert_eq!(10, range.limit);
+
+        let req = ListTableRoutesRequest::new()
+            .with_catalog("c1")
+            .with_schema("s1")
+            .with_page_token("__meta_table_route-c1-s1-t1-1024");
+        let range = req.to_range_request();
+        assert_eq!(b"__meta_table_route-c1-s1-t1-1024\0".to_vec(), range.key);
+        assert_eq!(b"__meta_table_route-c1-s1.".to_vec(), range.range_end);
+
+        // Tokens out of the range are ignored.
+        let req = req.with_page_token("__meta_table_route-c2-s1-t1-1024");
+        assert_eq!(
+            b"__meta_table_route-c1-s1-".to_vec(),
+            req.to_range_request().key
+        );
+    }
+
+    #[test]
+    fn test_list_table_routes_response() {
+        let req = ListTableRoutesRequest::new()
+            .with_catalog("c1")
+            .with_schema("s1")
+            .with_limit(3);
+        let res = RangeResponse::new(PbRangeResponse {
+            header: None,
+            kvs: vec![
+                new_table_route_kv(1024, "s1", "t1"),
+                new_table_route_kv(1025, "s1-x", "t2"),
+                new_table_route_kv(1026, "s1", "t3"),
+            ],
+            more: true,
+        });
+        let res = ListTableRoutesResponse::try_new(&req, res).unwrap();
+
+        let tables: Vec<_> = res
+            .table_routes
+            .iter()
+            .map(|route| (route.table.id, route.table.table_name.table_name.as_str()))
+            .collect();
+        assert_eq!(vec![(1024, "t1"), (1026, "t3")], tables);
+        let leader = res.table_routes[0].region_routes[0].leader_peer.as_ref();
+        assert_eq!("peer1", leader.unwrap().addr);
+        assert_eq!(
+            Some("__meta_table_route-c1-s1-t3-1026"),
+            res.next_page_token.as_deref()
+        );
+
+        let res = RangeResponse::new(PbRangeResponse {
+            header: None,
+            kvs: vec![new_table_route_kv(1027, "s1", "t4")],
+            more: false,
+        });
+        let res = ListTableRoutesResponse::try_new(&req, res).unwrap();
+        assert_eq!(1, res.table_routes.len());
+        assert!(res.next_page_token.is_none());
+    }
+
+    #[test]
+    fn test_rename_route_request() {
+        let req = RenameRouteRequest::new(1024, TableName::new("c1", "s1", "t1"), "t2");
+        assert_eq!("__meta_table_route-c1-s1-t1-1024", req.route_key());
+        assert_eq!("__meta_table_route-c1-s1-t2-1024", req.new_route_key());
+
+        let kv = new_table_route_kv(1024, "s1", "t1");
+        let value = req.rename_value(&kv.value).unwrap();
+        let res = RangeResponse::new(PbRangeResponse {
+            header: None,
+            kvs: vec![PbKeyValue {
+                key: req.new_route_key().into_bytes(),
+                value,
+            }],
+            more: false,
+        });
+        let res = ListTableRoutesResponse::try_new(&ListTableRoutesRequest::new(), res).unwrap();
+        let route = &res.table_routes[0];
+        assert_eq!(1024, route.table.id);
+        assert_eq!(TableName::new("c1", "s1", "t2"), route.table.table_name);
+        assert_eq!(
+            "peer1",
+            route.region_routes[0].leader_peer.as_ref().unwrap().addr
+        );
+
+        assert!(req.rename_value(b"invalid").is_err());
+    }
+
     #[test]
     fn test_route_request_trans() {
         let req = RouteRequest {
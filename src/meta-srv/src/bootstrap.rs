@@ -13,32 +13,37 @@
 // limitations under the License.
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use api::v1::meta::cluster_server::ClusterServer;
 use api::v1::meta::heartbeat_server::HeartbeatServer;
 use api::v1::meta::lock_server::LockServer;
 use api::v1::meta::router_server::RouterServer;
 use api::v1::meta::store_server::StoreServer;
-use etcd_client::Client;
-use snafu::ResultExt;
+use common_telemetry::{info, warn};
+use etcd_client::{Certificate, Client, ConnectOptions, Identity, TlsOptions};
+use snafu::{ensure, ResultExt};
 use tokio::net::TcpListener;
 use tokio::sync::mpsc::{self, Receiver, Sender};
 use tokio_stream::wrappers::TcpListenerStream;
 use tonic::transport::server::Router;
+use tower::layer::util::{Identity, Stack};
 
 use crate::cluster::MetaPeerClientBuilder;
 use crate::election::etcd::EtcdElection;
 use crate::lock::etcd::EtcdLock;
 use crate::metasrv::builder::MetaSrvBuilder;
-use crate::metasrv::{MetaSrv, MetaSrvOptions, SelectorRef};
+use crate::metasrv::{EtcdOptions, MetaSrv, MetaSrvOptions, SelectorRef};
 use crate::selector::lease_based::LeaseBasedSelector;
 use crate::selector::load_based::LoadBasedSelector;
+use crate::selector::weighted_load_based::WeightedLoadBasedSelector;
 use crate::selector::SelectorType;
 use crate::service::admin;
+use crate::service::inflight::{InflightLayer, InflightRequests};
 use crate::service::store::etcd::EtcdStore;
 use crate::service::store::kv::ResettableKvStoreRef;
 use crate::service::store::memory::MemStore;
-use crate::{error, Result};
+use crate::{error, snapshot, Result};
 
 #[derive(Clone)]
 pub struct MetaSrvInstance {
@@ -47,8 +52,12 @@ pub struct MetaSrvInstance {
     opts: MetaSrvOptions,
 
     signal_sender: Option<Sender<()>>,
+
+    inflight: InflightRequests,
 }
 
+pub type MetaSrvRouter = Router<Stack<InflightLayer, Identity>>;
+
 impl MetaSrvInstance {
     pub async fn new(opts: MetaSrvOptions) -> Result<MetaSrvInstance> {
         let meta_srv = build_meta_srv(&opts).await?;
@@ -57,6 +66,7 @@ impl MetaSrvInstance {
             meta_srv,
             opts,
             signal_sender: None,
+            inflight: InflightRequests::default(),
         })
     }
 
@@ -66,22 +76,37 @@ impl MetaSrvInstance {
 
         self.signal_sender = Some(tx);
 
-        bootstrap_meta_srv_with_router(
-            &self.opts.bind_addr,
-            router(self.meta_srv.clone()),
-            &mut rx,
-        )
-        .await?;
+        let bind_addr = self.opts.bind_addr.clone();
+        let router = router(self.meta_srv.clone(), self.inflight.clone());
+        // Serves in a separate task, so in-flight requests can still be drained after
+        // this future is dropped.
+        let server = tokio::spawn(async move {
+            bootstrap_meta_srv_with_router(&bind_addr, router, &mut rx).await
+        });
+        server.await.context(error::JoinServerSnafu)??;
 
         Ok(())
     }
 
+    /// Stops accepting new connections and waits for in-flight requests to finish, for
+    /// at most `drain_timeout_secs`.
     pub async fn shutdown(&self) -> Result<()> {
         if let Some(signal) = &self.signal_sender {
             signal
                 .send(())
                 .await
                 .context(error::SendShutdownSignalSnafu)?;
+
+            let timeout = Duration::from_secs(self.opts.drain_timeout_secs);
+            let pending = self.inflight.wait_idle(timeout).await;
+            if pending > 0 {
+                warn!(
+                    "MetaSrv drain timeout after {:?}, {} requests are still pending",
+                    timeout, pending
+                );
+            } else {
+                info!("MetaSrv drained all in-flight requests");
+            }
         }
 
         self.meta_srv.shutdown();
@@ -92,7 +117,7 @@ impl MetaSrvInstance {
 
 pub async fn bootstrap_meta_srv_with_router(
     bind_addr: &str,
-    router: Router,
+    router: MetaSrvRouter,
     signal: &mut Receiver<()>,
 ) -> Result<()> {
     let listener = TcpListener::bind(bind_addr)
@@ -110,9 +135,10 @@ pub async fn bootstrap_meta_srv_with_router(
     Ok(())
 }
 
-pub fn router(meta_srv: MetaSrv) -> Router {
+pub fn router(meta_srv: MetaSrv, inflight: InflightRequests) -> MetaSrvRouter {
     tonic::transport::Server::builder()
         .accept_http1(true) // for admin services
+        .layer(InflightLayer::new(inflight))
         .add_service(HeartbeatServer::new(meta_srv.clone()))
         .add_service(RouterServer::new(meta_srv.clone()))
         .add_service(StoreServer::new(meta_srv.clone()))
@@ -125,16 +151,20 @@ pub async fn build_meta_srv(opts: &MetaSrvOptions) -> Result<MetaSrv> {
     let (kv_store, election, lock) = if opts.use_memory_store {
         (Arc::new(MemStore::new()) as _, None, None)
     } else {
-        let etcd_endpoints = [&opts.store_addr];
-        let etcd_client = Client::connect(etcd_endpoints, None)
-            .await
-            .context(error::ConnectEtcdSnafu)?;
+        let etcd_client = connect_etcd(opts).await?;
+        let election = EtcdElection::with_etcd_client(
+            &opts.server_addr,
+            etcd_client.clone(),
+            opts.election.clone(),
+        )?;
+        let kv_store = EtcdStore::with_fenced_etcd_client(etcd_client.clone(), election.clone())?;
+        ensure!(
+            !snapshot::is_restore_incomplete(&kv_store).await?,
+            error::IncompleteRestoreSnafu
+        );
         (
-            EtcdStore::with_etcd_client(etcd_client.clone())?,
-            Some(EtcdElection::with_etcd_client(
-                &opts.server_addr,
-                etcd_client.clone(),
-            )?),
+            kv_store,
+            Some(election),
             Some(EtcdLock::with_etcd_client(etcd_client)?),
         )
     };
@@ -144,15 +174,24 @@ pub async fn build_meta_srv(opts: &MetaSrvOptions) -> Result<MetaSrv> {
     let meta_peer_client = MetaPeerClientBuilder::default()
         .election(election.clone())
         .in_memory(in_memory.clone())
+        .stat_ttl_secs(opts.stat_ttl_secs)
+        .lease_safety_margin_millis(opts.election.lease_safety_margin_millis)
         .build()
         // Safety: all required fields set at initialization
         .unwrap();
 
+    let deterministic = opts.deterministic_placement;
     let selector = match opts.selector {
         SelectorType::LoadBased => Arc::new(LoadBasedSelector {
             meta_peer_client: meta_peer_client.clone(),
+            deterministic,
+        }) as SelectorRef,
+        SelectorType::LeaseBased => Arc::new(LeaseBasedSelector { deterministic }) as SelectorRef,
+        SelectorType::WeightedLoadBased => Arc::new(WeightedLoadBasedSelector {
+            meta_peer_client: meta_peer_client.clone(),
+            weights: opts.load_weights.clone(),
+            deterministic,
         }) as SelectorRef,
-        SelectorType::LeaseBased => Arc::new(LeaseBasedSelector) as SelectorRef,
     };
 
     let meta_srv = MetaSrvBuilder::new()
@@ -176,3 +215,110 @@ pub async fn make_meta_srv(opts: &MetaSrvOptions) -> Result<MetaSrv> {
 
     Ok(meta_srv)
 }
+
+/// Connects to the etcd at `store_addr` with the TLS, auth and timeout options.
+async fn connect_etcd(opts: &MetaSrvOptions) -> Result<Client> {
+    let connect_options = etcd_connect_options(&opts.etcd)?;
+    let result = Client::connect([&opts.store_addr], Some(connect_options)).await;
+    match (result, &opts.etcd.username) {
+        // The client authenticates while connecting, a status error means the auth
+        // is rejected.
+        (Err(e @ etcd_client::Error::GRpcStatus(_)), Some(username)) => {
+            Err(e).context(error::AuthenticateEtcdSnafu { username })
+        }
+        (result, _) => result.context(error::ConnectEtcdSnafu),
+    }
+}
+
+fn etcd_connect_options(opts: &EtcdOptions) -> Result<ConnectOptions> {
+    let mut options = ConnectOptions::new();
+    if opts.connect_timeout_secs > 0 {
+        options = options.with_connect_timeout(Duration::from_secs(opts.connect_timeout_secs));
+    }
+    if opts.keepalive_interval_secs > 0 {
+        options = options.with_keep_alive(
+            Duration::from_secs(opts.keepalive_interval_secs),
+            Duration::from_secs(opts.keepalive_timeout_secs),
+        );
+    }
+
+    match (&opts.username, &opts.password) {
+        (Some(username), Some(password)) => options = options.with_user(username, password),
+        (None, None) => {}
+        _ => {
+            return error::InvalidArgumentsSnafu {
+                err_msg: "etcd username and password must be set together",
+            }
+            .fail()
+        }
+    }
+
+    if let Some(tls) = etcd_tls_options(opts)? {
+        options = options.with_tls(tls);
+    }
+    Ok(options)
+}
+
+fn etcd_tls_options(opts: &EtcdOptions) -> Result<Option<TlsOptions>> {
+    let identity = match (&opts.client_cert_path, &opts.client_key_path) {
+        (Some(cert_path), Some(key_path)) => Some(Identity::from_pem(
+            read_tls_file(cert_path)?,
+            read_tls_file(key_path)?,
+        )),
+        (None, None) => None,
+        _ => {
+            return error::InvalidArgumentsSnafu {
+                err_msg: "etcd client_cert_path and client_key_path must be set together",
+            }
+            .fail()
+        }
+    };
+    if opts.ca_cert_path.is_none() && identity.is_none() {
+        return Ok(None);
+    }
+
+    let mut tls = TlsOptions::new();
+    if let Some(ca_cert_path) = &opts.ca_cert_path {
+        tls = tls.ca_certificate(Certificate::from_pem(read_tls_file(ca_cert_path)?));
+    }
+    if let Some(identity) = identity {
+        tls = tls.identity(identity);
+    }
+    Ok(Some(tls))
+}
+
+fn read_tls_file(path: &str) -> Result<Vec<u8>> {
+    std::fs::read(path).context(error::ReadEtcdTlsFileSnafu { path })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_etcd_connect_options() {
+        assert!(etcd_connect_options(&EtcdOptions::default()).is_ok());
+
+        let opts = EtcdOptions {
+            ca_cert_path: Some("/not/exist/ca.pem".to_string()),
+            ..Default::default()
+        };
+        let err = etcd_connect_options(&opts).unwrap_err();
+        assert!(matches!(err, error::Error::ReadEtcdTlsFile { .. }));
+        assert!(err.to_string().contains("/not/exist/ca.pem"), "{err}");
+
+        let opts = EtcdOptions {
+            client_cert_path: Some("/not/exist/client.pem".to_string()),
+            ..Default::default()
+        };
+        let err = etcd_connect_options(&opts).unwrap_err();
+        assert!(matches!(err, error::Error::InvalidArguments { .. }));
+
+        let opts = EtcdOptions {
+            username: Some("root".to_string()),
+            ..Default::default()
+        };
+        let err = etcd_connect_options(&opts).unwrap_err();
+        assert!(matches!(err, error::Error::InvalidArguments { .. }));
+    }
+}
@@ -13,23 +13,47 @@
 // limitations under the License.
 
 use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
 use std::time::Duration;
 
 use api::v1::meta::cluster_client::ClusterClient;
 use api::v1::meta::{
-    BatchGetRequest, BatchGetResponse, KeyValue, RangeRequest, RangeResponse, ResponseHeader,
+    BatchGetRequest, BatchGetResponse, DeleteRangeRequest, KeyValue, RangeRequest, RangeResponse,
+    ResponseHeader,
 };
-use common_grpc::channel_manager::ChannelManager;
-use common_telemetry::warn;
+use common_grpc::channel_manager::{ChannelConfig, ChannelManager};
+use common_telemetry::{info, warn};
+use common_time::util as time_util;
 use derive_builder::Builder;
 use snafu::{ensure, OptionExt, ResultExt};
+use tonic::Code;
 
+use crate::election::LEASE_SAFETY_MARGIN_MILLIS;
 use crate::error::{match_for_io_error, Result};
-use crate::keys::{StatKey, StatValue, DN_STAT_PREFIX};
+use crate::keys::{
+    HotRegion, HotRegionValue, StatKey, StatValue, DN_HOT_REGION_PREFIX, DN_STAT_PREFIX,
+};
 use crate::metasrv::ElectionRef;
 use crate::service::store::kv::ResettableKvStoreRef;
 use crate::{error, util};
 
+/// Source of the current time, which is mocked in tests.
+pub trait Clock: Send + Sync {
+    fn now_millis(&self) -> i64;
+}
+
+pub type ClockRef = Arc<dyn Clock>;
+
+#[derive(Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_millis(&self) -> i64 {
+        time_util::current_time_millis()
+    }
+}
+
 #[derive(Builder, Clone)]
 pub struct MetaPeerClient {
     election: Option<ElectionRef>,
@@ -40,67 +64,163 @@ pub struct MetaPeerClient {
     max_retry_count: usize,
     #[builder(default = "1000")]
     retry_interval_ms: u64,
+    /// The stat kvs of a datanode expire if its latest stat is older than this.
+    #[builder(default = "120")]
+    stat_ttl_secs: u64,
+    #[builder(default = "Arc::new(SystemClock)")]
+    clock: ClockRef,
+    /// The leader serves reads from its in_memory store only if its lease expires later
+    /// than this, otherwise the reads are sent to the (possibly new) leader.
+    #[builder(default = "LEASE_SAFETY_MARGIN_MILLIS")]
+    lease_safety_margin_millis: u64,
+}
+
+impl MetaPeerClientBuilder {
+    /// Sets the config of the channels to the leader, e.g. the pool size, connect timeout
+    /// and keepalive. The default is the config of `ChannelManager::default()`.
+    pub fn channel_config(&mut self, config: ChannelConfig) -> &mut Self {
+        self.channel_manager = Some(ChannelManager::with_config(config));
+        self
+    }
 }
 
 impl MetaPeerClient {
     // Get all datanode stat kvs from leader meta.
     pub async fn get_all_dn_stat_kvs(&self) -> Result<HashMap<StatKey, StatValue>> {
         let key = format!("{DN_STAT_PREFIX}-").into_bytes();
-        let range_end = util::get_prefix_end_key(&key);
 
-        let kvs = self.range(key, range_end).await?;
+        let kvs = self.range_prefix(key, 0).await?;
 
-        to_stat_kv_map(kvs)
+        self.remove_expired_stats(to_stat_kv_map(kvs)?).await
     }
 
-    // Get datanode stat kvs from leader meta by input keys.
+    // Get datanode stat kvs from leader meta by input keys. The datanodes without stats,
+    // e.g. dead or never reported, are absent in the result, while failing to get the
+    // stats from the leader is an error.
     pub async fn get_dn_stat_kvs(&self, keys: Vec<StatKey>) -> Result<HashMap<StatKey, StatValue>> {
         let stat_keys = keys.into_iter().map(|key| key.into()).collect();
 
-        let kvs = self.batch_get(stat_keys).await?;
+        let kvs = self
+            .batch_get_map(stat_keys)
+            .await?
+            .into_iter()
+            .filter_map(|(key, value)| value.map(|value| KeyValue { key, value }))
+            .collect();
+
+        self.remove_expired_stats(to_stat_kv_map(kvs)?).await
+    }
+
+    // Remove the stats of the datanodes whose latest stats are older than the ttl, the
+    // expired stat kvs are also deleted if this is the leader.
+    async fn remove_expired_stats(
+        &self,
+        mut stats: HashMap<StatKey, StatValue>,
+    ) -> Result<HashMap<StatKey, StatValue>> {
+        let now = self.clock.now_millis();
+        let ttl_millis = self.stat_ttl_secs.saturating_mul(1000) as i64;
+        let expired = stats
+            .iter()
+            .filter(|(_, value)| {
+                value
+                    .latest_timestamp_millis()
+                    .map_or(true, |ts| now - ts > ttl_millis)
+            })
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+
+        for key in expired {
+            stats.remove(&key);
+            if self.is_leader() {
+                info!(
+                    "Remove the expired stats of datanode {} in cluster {}",
+                    key.node_id, key.cluster_id
+                );
+                let req = DeleteRangeRequest {
+                    key: key.into(),
+                    ..Default::default()
+                };
+                self.in_memory.delete_range(req).await?;
+            }
+        }
+
+        Ok(stats)
+    }
+
+    // Get the `top_n` hottest regions by write rate across all datanodes, from the hot
+    // regions reported in their latest heartbeats.
+    pub async fn get_hot_regions(&self, top_n: usize) -> Result<Vec<HotRegion>> {
+        let key = format!("{DN_HOT_REGION_PREFIX}-").into_bytes();
+
+        let kvs = self.range_prefix(key, 0).await?;
+
+        let mut regions = Vec::new();
+        for kv in kvs {
+            let value: HotRegionValue = kv.value.try_into()?;
+            regions.extend(value.regions);
+        }
+        regions.sort_unstable_by(|a, b| {
+            b.rows_per_sec
+                .cmp(&a.rows_per_sec)
+                .then_with(|| a.region_id.cmp(&b.region_id))
+        });
+        regions.truncate(top_n);
 
-        to_stat_kv_map(kvs)
+        Ok(regions)
     }
 
     // Range kv information from the leader's in_mem kv store
     pub async fn range(&self, key: Vec<u8>, range_end: Vec<u8>) -> Result<Vec<KeyValue>> {
+        self.range_with_limit(key, range_end, 0).await
+    }
+
+    // Range kvs whose key starts with `prefix` from the leader's in_mem kv store,
+    // an empty prefix means all kvs. At most `limit` kvs are returned if `limit` > 0.
+    pub async fn range_prefix(&self, prefix: Vec<u8>, limit: i64) -> Result<Vec<KeyValue>> {
+        let (key, range_end) = if prefix.is_empty() {
+            // "\0" to "\0" covers the whole key space
+            (vec![0], vec![0])
+        } else {
+            let range_end = util::get_prefix_end_key(&prefix);
+            (prefix, range_end)
+        };
+
+        self.range_with_limit(key, range_end, limit).await
+    }
+
+    async fn range_with_limit(
+        &self,
+        key: Vec<u8>,
+        range_end: Vec<u8>,
+        limit: i64,
+    ) -> Result<Vec<KeyValue>> {
         if self.is_leader() {
             let request = RangeRequest {
                 key,
                 range_end,
+                limit,
                 ..Default::default()
             };
 
             return self.in_memory.range(request).await.map(|resp| resp.kvs);
         }
 
-        let max_retry_count = self.max_retry_count;
-        let retry_interval_ms = self.retry_interval_ms;
-
-        for _ in 0..max_retry_count {
-            match self.remote_range(key.clone(), range_end.clone()).await {
-                Ok(kvs) => return Ok(kvs),
-                Err(e) => {
-                    if need_retry(&e) {
-                        warn!("Encountered an error that need to retry, err: {:?}", e);
-                        tokio::time::sleep(Duration::from_millis(retry_interval_ms)).await;
-                    } else {
-                        return Err(e);
-                    }
-                }
-            }
-        }
-
-        error::ExceededRetryLimitSnafu {
-            func_name: "range",
-            retry_num: max_retry_count,
-        }
-        .fail()
+        self.retry("range", || {
+            self.remote_range(key.clone(), range_end.clone(), limit)
+        })
+        .await
     }
 
-    async fn remote_range(&self, key: Vec<u8>, range_end: Vec<u8>) -> Result<Vec<KeyValue>> {
+    async fn remote_range(
+        &self,
+        key: Vec<u8>,
+        range_end: Vec<u8>,
+        limit: i64,
+    ) -> Result<Vec<KeyValue>> {
         // Safety: when self.is_leader() == false, election must not empty.
         let election = self.election.as_ref().unwrap();
+        // The lease of this node is about to expire, wait for it to be renewed or lost
+        // instead of reading from itself.
+        ensure!(!election.is_leader(), error::LeaderLeaseExpiringSnafu);
 
         let leader_addr = election.leader().await?.0;
 
@@ -112,6 +232,7 @@ impl MetaPeerClient {
         let request = tonic::Request::new(RangeRequest {
             key,
             range_end,
+            limit,
             ..Default::default()
         });
 
@@ -137,12 +258,38 @@ impl MetaPeerClient {
             return self.in_memory.batch_get(request).await.map(|resp| resp.kvs);
         }
 
+        self.retry("batch_get", || self.remote_batch_get(keys.clone()))
+            .await
+    }
+
+    /// Gets the values of `keys` from the leader's in_mem kv store. Unlike `batch_get`,
+    /// every requested key is in the returned map, with `None` if it's missing in the
+    /// store.
+    ///
+    /// An error, e.g. the leader is unreachable or responds with an error header, still
+    /// fails the whole call, so a `None` value always means the key does not exist.
+    pub async fn batch_get_map(
+        &self,
+        keys: Vec<Vec<u8>>,
+    ) -> Result<HashMap<Vec<u8>, Option<Vec<u8>>>> {
+        let kvs = self.batch_get(keys.clone()).await?;
+
+        Ok(to_kv_map(keys, kvs))
+    }
+
+    /// Calls `func` until it succeeds, fails with an error that is not retryable, or
+    /// the retry limit is exceeded.
+    async fn retry<T, F, Fut>(&self, func_name: &str, func: F) -> Result<T>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
         let max_retry_count = self.max_retry_count;
         let retry_interval_ms = self.retry_interval_ms;
 
         for _ in 0..max_retry_count {
-            match self.remote_batch_get(keys.clone()).await {
-                Ok(kvs) => return Ok(kvs),
+            match func().await {
+                Ok(res) => return Ok(res),
                 Err(e) => {
                     if need_retry(&e) {
                         warn!("Encountered an error that need to retry, err: {:?}", e);
@@ -155,7 +302,7 @@ impl MetaPeerClient {
         }
 
         error::ExceededRetryLimitSnafu {
-            func_name: "batch_get",
+            func_name,
             retry_num: max_retry_count,
         }
         .fail()
@@ -164,6 +311,9 @@ impl MetaPeerClient {
     async fn remote_batch_get(&self, keys: Vec<Vec<u8>>) -> Result<Vec<KeyValue>> {
         // Safety: when self.is_leader() == false, election must not empty.
         let election = self.election.as_ref().unwrap();
+        // The lease of this node is about to expire, wait for it to be renewed or lost
+        // instead of reading from itself.
+        ensure!(!election.is_leader(), error::LeaderLeaseExpiringSnafu);
 
         let leader_addr = election.leader().await?.0;
 
@@ -188,14 +338,19 @@ impl MetaPeerClient {
         Ok(response.kvs)
     }
 
-    // Check if the meta node is a leader node.
+    // Check if the meta node is a leader node whose lease is not about to expire.
     // Note: when self.election is None, we also consider the meta node is leader
     fn is_leader(&self) -> bool {
         self.election
             .as_ref()
-            .map(|election| election.is_leader())
+            .map(|election| election.is_leader() && !self.is_lease_expiring(election))
             .unwrap_or(true)
     }
+
+    fn is_lease_expiring(&self, election: &ElectionRef) -> bool {
+        let remaining = election.lease_expire_at_millis() - self.clock.now_millis();
+        remaining <= self.lease_safety_margin_millis as i64
+    }
 }
 
 fn to_stat_kv_map(kvs: Vec<KeyValue>) -> Result<HashMap<StatKey, StatValue>> {
@@ -206,6 +361,18 @@ fn to_stat_kv_map(kvs: Vec<KeyValue>) -> Result<HashMap<StatKey, StatValue>> {
     Ok(map)
 }
 
+/// Maps each of the `keys` to its value in `kvs`. Kvs of keys that are not requested
+/// are ignored.
+fn to_kv_map(keys: Vec<Vec<u8>>, kvs: Vec<KeyValue>) -> HashMap<Vec<u8>, Option<Vec<u8>>> {
+    let mut map: HashMap<_, _> = keys.into_iter().map(|key| (key, None)).collect();
+    for kv in kvs {
+        if let Some(value) = map.get_mut(&kv.key) {
+            *value = Some(kv.value);
+        }
+    }
+    map
+}
+
 struct Context<'a> {
     addr: &'a str,
 }
@@ -222,27 +389,76 @@ fn check_resp_header(header: &Option<ResponseHeader>, ctx: Context) -> Result<()
         }
     );
 
+    if let Some(err) = &header.error {
+        return error::ResponseHeaderSnafu {
+            node_addr: ctx.addr,
+            code: err.code,
+            err_msg: &err.err_msg,
+        }
+        .fail();
+    }
+
     Ok(())
 }
 
+/// Returns true if a failed request to the leader may succeed when retried. Every
+/// operation sent to the leader should list its error here.
 fn need_retry(error: &error::Error) -> bool {
     match error {
-        error::Error::IsNotLeader { .. } => true,
+        error::Error::IsNotLeader { .. } | error::Error::LeaderLeaseExpiring { .. } => true,
         error::Error::Range { source, .. } | error::Error::BatchGet { source, .. } => {
-            match_for_io_error(source).is_some()
+            is_transient(source)
         }
         _ => false,
     }
 }
 
+/// The leader is unreachable or too slow for now, or the connection is broken. Errors
+/// caused by the request itself, such as `InvalidArgument` and `PermissionDenied`, are
+/// terminal.
+fn is_transient(status: &tonic::Status) -> bool {
+    match status.code() {
+        Code::Unavailable | Code::DeadlineExceeded => true,
+        Code::InvalidArgument
+        | Code::PermissionDenied
+        | Code::Unauthenticated
+        | Code::NotFound
+        | Code::AlreadyExists
+        | Code::FailedPrecondition
+        | Code::OutOfRange
+        | Code::Unimplemented => false,
+        _ => match_for_io_error(status).is_some(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use api::v1::meta::{Error, ErrorCode, KeyValue, ResponseHeader};
-
-    use super::{check_resp_header, to_stat_kv_map, Context};
-    use crate::error;
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicI64, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use api::v1::meta::{
+        BatchGetRequest, BatchGetResponse, BatchPutRequest, BatchPutResponse, CompareAndPutRequest,
+        CompareAndPutResponse, DeleteRangeRequest, DeleteRangeResponse, Error, ErrorCode, KeyValue,
+        MoveValueRequest, MoveValueResponse, PutRequest, PutResponse, RangeRequest, RangeResponse,
+        ResponseHeader,
+    };
+    use common_grpc::channel_manager::{ChannelConfig, ChannelManager};
+    use snafu::IntoError;
+    use tonic::Status;
+
+    use super::{
+        check_resp_header, need_retry, to_kv_map, to_stat_kv_map, Clock, Context,
+        MetaPeerClientBuilder,
+    };
+    use crate::election::Election;
     use crate::handler::node_stat::Stat;
-    use crate::keys::{StatKey, StatValue};
+    use crate::keys::{HotRegion, HotRegionKey, HotRegionValue, StatKey, StatValue};
+    use crate::metasrv::LeaderValue;
+    use crate::service::store::kv::{KvStore, ResettableKvStore};
+    use crate::service::store::memory::MemStore;
+    use crate::{error, util};
 
     #[test]
     fn test_to_stat_kv_map() {
@@ -307,9 +523,458 @@ mod tests {
             result.err().unwrap(),
             error::Error::IsNotLeader { .. }
         ));
+
+        let header = Some(ResponseHeader {
+            error: Some(Error {
+                code: -1,
+                err_msg: "Unknown error".to_string(),
+            }),
+            ..Default::default()
+        });
+        let result = check_resp_header(&header, mock_ctx());
+        assert!(matches!(
+            result.err().unwrap(),
+            error::Error::ResponseHeader { .. }
+        ));
+    }
+
+    #[test]
+    fn test_to_kv_map() {
+        let kv = |key: &str| KeyValue {
+            key: key.as_bytes().to_vec(),
+            value: format!("{key}-value").into_bytes(),
+        };
+        let keys = vec![b"a".to_vec(), b"b".to_vec()];
+
+        // all present
+        let map = to_kv_map(keys.clone(), vec![kv("a"), kv("b")]);
+        assert_eq!(2, map.len());
+        assert_eq!(Some(b"a-value".to_vec()), map[b"a".as_slice()]);
+        assert_eq!(Some(b"b-value".to_vec()), map[b"b".as_slice()]);
+
+        // some missing, and kvs that are not requested are ignored
+        let map = to_kv_map(keys.clone(), vec![kv("b"), kv("c")]);
+        assert_eq!(2, map.len());
+        assert_eq!(None, map[b"a".as_slice()]);
+        assert_eq!(Some(b"b-value".to_vec()), map[b"b".as_slice()]);
+
+        let map = to_kv_map(keys, vec![]);
+        assert!(map.values().all(Option::is_none));
+    }
+
+    #[tokio::test]
+    async fn test_batch_get_map() {
+        let in_memory = Arc::new(MemStore::new());
+        for key in ["a", "b"] {
+            in_memory
+                .put(PutRequest {
+                    key: key.as_bytes().to_vec(),
+                    value: key.as_bytes().to_vec(),
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+        }
+        let client = MetaPeerClientBuilder::default()
+            .election(None)
+            .in_memory(in_memory)
+            .build()
+            .unwrap();
+
+        let map = client
+            .batch_get_map(vec![b"a".to_vec(), b"b".to_vec()])
+            .await
+            .unwrap();
+        assert_eq!(Some(b"a".to_vec()), map[b"a".as_slice()]);
+        assert_eq!(Some(b"b".to_vec()), map[b"b".as_slice()]);
+
+        let map = client
+            .batch_get_map(vec![b"a".to_vec(), b"c".to_vec()])
+            .await
+            .unwrap();
+        assert_eq!(2, map.len());
+        assert_eq!(Some(b"a".to_vec()), map[b"a".as_slice()]);
+        assert_eq!(None, map[b"c".as_slice()]);
+    }
+
+    struct FailedBatchGetStore;
+
+    #[async_trait::async_trait]
+    impl KvStore for FailedBatchGetStore {
+        async fn range(&self, _: RangeRequest) -> error::Result<RangeResponse> {
+            unreachable!()
+        }
+
+        async fn put(&self, _: PutRequest) -> error::Result<PutResponse> {
+            unreachable!()
+        }
+
+        async fn batch_get(&self, _: BatchGetRequest) -> error::Result<BatchGetResponse> {
+            error::ResponseHeaderNotFoundSnafu.fail()
+        }
+
+        async fn batch_put(&self, _: BatchPutRequest) -> error::Result<BatchPutResponse> {
+            unreachable!()
+        }
+
+        async fn compare_and_put(
+            &self,
+            _: CompareAndPutRequest,
+        ) -> error::Result<CompareAndPutResponse> {
+            unreachable!()
+        }
+
+        async fn delete_range(&self, _: DeleteRangeRequest) -> error::Result<DeleteRangeResponse> {
+            unreachable!()
+        }
+
+        async fn move_value(&self, _: MoveValueRequest) -> error::Result<MoveValueResponse> {
+            unreachable!()
+        }
+    }
+
+    impl ResettableKvStore for FailedBatchGetStore {
+        fn reset(&self) {}
+    }
+
+    #[tokio::test]
+    async fn test_batch_get_map_error() {
+        let client = MetaPeerClientBuilder::default()
+            .election(None)
+            .in_memory(Arc::new(FailedBatchGetStore))
+            .build()
+            .unwrap();
+
+        // The error is not reported as missing keys.
+        let result = client.batch_get_map(vec![b"a".to_vec()]).await;
+        assert!(matches!(
+            result.err().unwrap(),
+            error::Error::ResponseHeaderNotFound { .. }
+        ));
     }
 
     fn mock_ctx<'a>() -> Context<'a> {
         Context { addr: "addr" }
     }
+
+    #[tokio::test]
+    async fn test_range_prefix() {
+        let in_memory = Arc::new(MemStore::new());
+        for key in ["a", "ab", "abc", "b", "ba", "c"] {
+            in_memory
+                .put(PutRequest {
+                    key: key.as_bytes().to_vec(),
+                    value: key.as_bytes().to_vec(),
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+        }
+        let client = MetaPeerClientBuilder::default()
+            .election(None)
+            .in_memory(in_memory)
+            .build()
+            .unwrap();
+
+        for prefix in ["a", "ab", "b", "c"] {
+            let prefix = prefix.as_bytes().to_vec();
+            let range_end = util::get_prefix_end_key(&prefix);
+            let expected = client.range(prefix.clone(), range_end).await.unwrap();
+            let kvs = client.range_prefix(prefix, 0).await.unwrap();
+            assert_eq!(expected, kvs);
+        }
+
+        // a prefix that matches a single key
+        let kvs = client.range_prefix(b"abc".to_vec(), 0).await.unwrap();
+        assert_eq!(1, kvs.len());
+        assert_eq!(b"abc".to_vec(), kvs[0].key);
+
+        let kvs = client.range_prefix(b"d".to_vec(), 0).await.unwrap();
+        assert!(kvs.is_empty());
+
+        // an empty prefix scans all
+        let kvs = client.range_prefix(vec![], 0).await.unwrap();
+        assert_eq!(6, kvs.len());
+
+        let kvs = client.range_prefix(b"a".to_vec(), 2).await.unwrap();
+        let keys = kvs.into_iter().map(|kv| kv.key).collect::<Vec<_>>();
+        assert_eq!(vec![b"a".to_vec(), b"ab".to_vec()], keys);
+
+        let kvs = client.range_prefix(vec![], 4).await.unwrap();
+        assert_eq!(4, kvs.len());
+    }
+
+    #[tokio::test]
+    async fn test_channel_config() {
+        let in_memory = Arc::new(MemStore::new());
+        let client = MetaPeerClientBuilder::default()
+            .election(None)
+            .in_memory(in_memory.clone())
+            .build()
+            .unwrap();
+        assert_eq!(
+            ChannelManager::default().config(),
+            client.channel_manager.config()
+        );
+
+        let config = ChannelConfig::new()
+            .pool_size(4)
+            .connect_timeout(Duration::from_secs(3))
+            .http2_keep_alive_interval(Duration::from_secs(10))
+            .tcp_keepalive(Duration::from_secs(30));
+        let client = MetaPeerClientBuilder::default()
+            .election(None)
+            .in_memory(in_memory)
+            .channel_config(config.clone())
+            .build()
+            .unwrap();
+        assert_eq!(&config, client.channel_manager.config());
+        assert!(client.channel_manager.get("127.0.0.1:3002").is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_get_hot_regions() {
+        let in_memory = Arc::new(MemStore::new());
+        let hot_region = |node_id, region_id, rows_per_sec| HotRegion {
+            node_id,
+            region_id,
+            table_id: 1024,
+            rows_per_sec,
+            approximate_bytes: 0,
+        };
+        let nodes = [
+            (1, vec![hot_region(1, 1, 300), hot_region(1, 2, 10)]),
+            (2, vec![hot_region(2, 3, 200), hot_region(2, 4, 300)]),
+            (3, vec![]),
+        ];
+        for (node_id, regions) in nodes {
+            let key = HotRegionKey {
+                cluster_id: 0,
+                node_id,
+            };
+            let value = HotRegionValue {
+                timestamp_millis: 0,
+                regions,
+            };
+            in_memory
+                .put(PutRequest {
+                    key: key.into(),
+                    value: value.try_into().unwrap(),
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+        }
+        let client = MetaPeerClientBuilder::default()
+            .election(None)
+            .in_memory(in_memory)
+            .build()
+            .unwrap();
+
+        let regions = client.get_hot_regions(3).await.unwrap();
+        assert_eq!(
+            vec![
+                hot_region(1, 1, 300),
+                hot_region(2, 4, 300),
+                hot_region(2, 3, 200)
+            ],
+            regions
+        );
+
+        let regions = client.get_hot_regions(10).await.unwrap();
+        assert_eq!(4, regions.len());
+    }
+
+    struct MockClock(AtomicI64);
+
+    impl Clock for MockClock {
+        fn now_millis(&self) -> i64 {
+            self.0.load(Ordering::Relaxed)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_expire_stats() {
+        let in_memory = Arc::new(MemStore::new());
+        for (node_id, timestamp_millis) in [(1, 10_000), (2, 40_000)] {
+            let stat = Stat {
+                timestamp_millis,
+                cluster_id: 0,
+                id: node_id,
+                ..Default::default()
+            };
+            let key = StatKey {
+                cluster_id: 0,
+                node_id,
+            };
+            in_memory
+                .put(PutRequest {
+                    key: key.into(),
+                    value: StatValue { stats: vec![stat] }.try_into().unwrap(),
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+        }
+        let clock = Arc::new(MockClock(AtomicI64::new(20_000)));
+        let client = MetaPeerClientBuilder::default()
+            .election(None)
+            .in_memory(in_memory.clone())
+            .stat_ttl_secs(30)
+            .clock(clock.clone())
+            .build()
+            .unwrap();
+
+        let node_ids = |stats: HashMap<StatKey, StatValue>| {
+            let mut ids = stats.into_keys().map(|k| k.node_id).collect::<Vec<_>>();
+            ids.sort_unstable();
+            ids
+        };
+        let stats = client.get_all_dn_stat_kvs().await.unwrap();
+        assert_eq!(vec![1, 2], node_ids(stats));
+
+        // The heartbeats of node 1 lapse for longer than the ttl.
+        clock.0.store(50_000, Ordering::Relaxed);
+        let stats = client.get_all_dn_stat_kvs().await.unwrap();
+        assert_eq!(vec![2], node_ids(stats));
+        let key = StatKey {
+            cluster_id: 0,
+            node_id: 1,
+        };
+        let stats = client.get_dn_stat_kvs(vec![key.clone()]).await.unwrap();
+        assert!(stats.is_empty());
+        // The expired kv is removed from the store.
+        let res = in_memory.range(RangeRequest {
+            key: key.into(),
+            ..Default::default()
+        });
+        assert!(res.await.unwrap().kvs.is_empty());
+
+        clock.0.store(80_000, Ordering::Relaxed);
+        let stats = client.get_all_dn_stat_kvs().await.unwrap();
+        assert!(stats.is_empty());
+    }
+
+    struct MockElection {
+        lease_expire_at_millis: AtomicI64,
+    }
+
+    #[async_trait::async_trait]
+    impl Election for MockElection {
+        type Leader = LeaderValue;
+
+        fn is_leader(&self) -> bool {
+            true
+        }
+
+        fn term(&self) -> u64 {
+            1
+        }
+
+        fn lease_expire_at_millis(&self) -> i64 {
+            self.lease_expire_at_millis.load(Ordering::Relaxed)
+        }
+
+        fn in_infancy(&self) -> bool {
+            false
+        }
+
+        async fn campaign(&self) -> error::Result<()> {
+            unreachable!()
+        }
+
+        async fn leader(&self) -> error::Result<LeaderValue> {
+            unreachable!()
+        }
+
+        async fn resign(&self) -> error::Result<()> {
+            unreachable!()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_read_with_lease_expiring() {
+        let in_memory = Arc::new(MemStore::new());
+        in_memory
+            .put(PutRequest {
+                key: b"a".to_vec(),
+                value: b"a".to_vec(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let election = Arc::new(MockElection {
+            lease_expire_at_millis: AtomicI64::new(13_000),
+        });
+        let client = MetaPeerClientBuilder::default()
+            .election(Some(election.clone()))
+            .in_memory(in_memory)
+            .max_retry_count(1)
+            .retry_interval_ms(0)
+            .clock(Arc::new(MockClock(AtomicI64::new(10_000))))
+            .lease_safety_margin_millis(500)
+            .build()
+            .unwrap();
+
+        let kvs = client.range(b"a".to_vec(), vec![]).await.unwrap();
+        assert_eq!(b"a".to_vec(), kvs[0].value);
+
+        // The lease is to expire within the safety margin, the leader neither reads from
+        // its own states nor sends the read to itself.
+        election.lease_expire_at_millis.store(10_500, Ordering::Relaxed);
+        let err = client.range(b"a".to_vec(), vec![]).await.unwrap_err();
+        assert!(matches!(err, error::Error::ExceededRetryLimit { .. }), "{err:?}");
+    }
+
+    #[test]
+    fn test_need_retry() {
+        type NewStatus = fn() -> Status;
+        let cases: [(NewStatus, bool); 8] = [
+            (|| Status::unavailable("leader unavailable"), true),
+            (|| Status::deadline_exceeded("timeout"), true),
+            (
+                || {
+                    Status::from_error(Box::new(std::io::Error::new(
+                        std::io::ErrorKind::BrokenPipe,
+                        "broken pipe",
+                    )))
+                },
+                true,
+            ),
+            (|| Status::unknown("unknown"), false),
+            (|| Status::invalid_argument("bad request"), false),
+            (|| Status::permission_denied("denied"), false),
+            (|| Status::unauthenticated("unauthenticated"), false),
+            (|| Status::unimplemented("unimplemented"), false),
+        ];
+
+        type ToError = fn(Status) -> error::Error;
+        let operations: [(&str, ToError); 2] = [
+            ("range", |status| error::RangeSnafu.into_error(status)),
+            ("batch_get", |status| {
+                error::BatchGetSnafu.into_error(status)
+            }),
+        ];
+        for (operation, to_error) in operations {
+            for (new_status, expected) in cases {
+                let status = new_status();
+                let code = status.code();
+                assert_eq!(
+                    expected,
+                    need_retry(&to_error(status)),
+                    "operation: {operation}, code: {code:?}"
+                );
+            }
+        }
+
+        let not_leader = error::IsNotLeaderSnafu {
+            node_addr: "127.0.0.1:3002",
+        }
+        .build();
+        assert!(need_retry(&not_leader));
+        let lease_expiring = error::LeaderLeaseExpiringSnafu.build();
+        assert!(need_retry(&lease_expiring));
+        let other = error::ResponseHeaderNotFoundSnafu.build();
+        assert!(!need_retry(&other));
+    }
 }
@@ -0,0 +1,1086 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use api::v1::meta::{
+    CompareAndPutRequest, DeleteRangeRequest, Peer, PutRequest, RangeRequest, TableName,
+    TableRouteValue,
+};
+use catalog::helper::{TableGlobalKey, TableGlobalValue};
+use common_telemetry::{error, info, warn};
+use common_time::util as time_util;
+use metrics::histogram;
+use snafu::{ensure, OptionExt, ResultExt};
+
+use crate::error::{self, Result};
+use crate::handler::instruction::{Instruction, RegionIdent};
+use crate::keys::{
+    DecommissionKey, DecommissionState, DecommissionValue, HotRegionKey, LeaseKey, LeaseValue,
+    MigrationStep, RegionMigration, StatKey, TableRouteKey, DN_DECOMMISSION_PREFIX,
+    TABLE_ROUTE_PREFIX,
+};
+use crate::mailbox::MailboxRef;
+use crate::metasrv::Context;
+use crate::metric::{LABEL_NODE_ID, METRIC_META_REGION_OPEN_ELAPSED};
+use crate::service::store::ext::KvStoreExt;
+use crate::service::store::kv::{KvStoreRef, ResettableKvStoreRef};
+use crate::{lease, util};
+
+/// The interval to poll the replies of the instructions sent to datanodes.
+pub(crate) const INSTRUCTION_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Drives a datanode through cordon, region migration and removal.
+///
+/// Every step is persisted in the kv store before it is executed, so a new
+/// leader can pick up an unfinished decommission via [resume_all].
+///
+/// A region is migrated by flushing and closing it on the decommissioned datanode,
+/// then opening it on the target datanode and finally moving its route, with
+/// [Instruction]s sent through the [crate::mailbox::Mailbox]. The target reads the
+/// region from the same object store, so datanodes must share their storage. If the
+/// region fails to be closed or opened, it is reopened on the decommissioned datanode
+/// and the decommission fails.
+///
+/// The opens are paced by the window of pending opens of the [Mailbox](crate::mailbox::Mailbox).
+pub struct DatanodeDecommission {
+    key: DecommissionKey,
+    kv_store: KvStoreRef,
+    in_memory: ResettableKvStoreRef,
+    mailbox: MailboxRef,
+    datanode_lease_secs: i64,
+    poll_interval: Duration,
+}
+
+impl DatanodeDecommission {
+    pub fn new(cluster_id: u64, node_id: u64, ctx: &Context) -> Self {
+        Self {
+            key: DecommissionKey {
+                cluster_id,
+                node_id,
+            },
+            kv_store: ctx.kv_store.clone(),
+            in_memory: ctx.in_memory.clone(),
+            mailbox: ctx.mailbox.clone(),
+            datanode_lease_secs: ctx.datanode_lease_secs,
+            poll_interval: INSTRUCTION_POLL_INTERVAL,
+        }
+    }
+
+    /// Cordons the datanode, or retries a failed decommission. Fails upfront if the
+    /// other alive datanodes cannot absorb all of its regions.
+    ///
+    /// `max_regions_per_datanode` of 0 means unlimited, then the check only makes
+    /// sure there is another alive datanode if the datanode hosts any region.
+    pub async fn start(&self, max_regions_per_datanode: u64) -> Result<DecommissionValue> {
+        if let Some(value) = self.status().await? {
+            if value.state != DecommissionState::Failed {
+                return Ok(value);
+            }
+        }
+
+        let placements = region_placements(&self.kv_store).await?;
+        let loads = region_loads(&placements);
+        let regions = loads.get(&self.key.node_id).copied().unwrap_or(0);
+        let candidates = self.candidates(&loads).await?;
+        let capacity = absorbable_regions(&candidates, max_regions_per_datanode);
+        ensure!(
+            regions <= capacity,
+            error::DecommissionCapacitySnafu {
+                node_id: self.key.node_id,
+                regions,
+                capacity,
+            }
+        );
+
+        let value = DecommissionValue {
+            state: DecommissionState::Cordoned,
+            started_at_millis: time_util::current_time_millis(),
+            regions_remaining: regions,
+            current_migration: None,
+            error: None,
+        };
+        self.save(value.clone()).await?;
+
+        info!(
+            "Datanode {:?} cordoned for decommission, regions: {}",
+            self.key, regions
+        );
+
+        Ok(value)
+    }
+
+    pub async fn status(&self) -> Result<Option<DecommissionValue>> {
+        self.kv_store
+            .get(self.key.clone().into())
+            .await?
+            .map(|kv| kv.value.try_into())
+            .transpose()
+    }
+
+    /// Runs the decommission until it is done or failed. It is safe to call again
+    /// after an interruption, each step re-reads the current routes.
+    pub async fn run(&self) -> Result<()> {
+        loop {
+            let mut value = match self.status().await? {
+                Some(value) => value,
+                None => return Ok(()),
+            };
+
+            match value.state {
+                DecommissionState::Cordoned => {
+                    value.state = DecommissionState::Migrating;
+                    self.save(value).await?;
+                }
+                DecommissionState::Migrating => match value.current_migration.take() {
+                    Some(migration) => self.migrate_region(value, migration).await?,
+                    None => self.next_migration(value).await?,
+                },
+                DecommissionState::Cleaning => {
+                    self.remove_node_keys().await?;
+                    value.state = DecommissionState::Done;
+                    self.save(value).await?;
+                    info!("Datanode {:?} decommissioned", self.key);
+                }
+                DecommissionState::Done | DecommissionState::Failed => return Ok(()),
+            }
+        }
+    }
+
+    /// Picks the next region to move and the least loaded datanode to move it to.
+    async fn next_migration(&self, mut value: DecommissionValue) -> Result<()> {
+        let placements = region_placements(&self.kv_store).await?;
+        let remaining = placements
+            .iter()
+            .filter(|p| p.node_id == self.key.node_id)
+            .collect::<Vec<_>>();
+
+        // The datanode is region-free.
+        if remaining.is_empty() {
+            value.state = DecommissionState::Cleaning;
+            value.regions_remaining = 0;
+            return self.save(value).await;
+        }
+
+        let loads = region_loads(&placements);
+        let (to, _) = self
+            .candidates(&loads)
+            .await?
+            .into_iter()
+            .min_by_key(|(peer, load)| (*load, peer.id))
+            .context(error::DecommissionCapacitySnafu {
+                node_id: self.key.node_id,
+                regions: remaining.len(),
+                capacity: 0_usize,
+            })?;
+
+        value.regions_remaining = remaining.len();
+        value.current_migration = Some(RegionMigration {
+            region: remaining[0].region_ident(),
+            to_node_id: to.id,
+            to_node_addr: to.addr,
+            step: MigrationStep::CloseSource,
+            instruction_id: None,
+            error: None,
+        });
+        self.save(value).await
+    }
+
+    /// Executes the current step of the migration and saves the next one.
+    async fn migrate_region(
+        &self,
+        mut value: DecommissionValue,
+        mut migration: RegionMigration,
+    ) -> Result<()> {
+        let source = self.key.node_id;
+        match migration.step {
+            MigrationStep::CloseSource => {
+                let instruction = Instruction::CloseRegions(migration.region.clone());
+                match self
+                    .execute(&mut value, &mut migration, source, instruction)
+                    .await
+                {
+                    Ok(()) => migration.step = MigrationStep::OpenTarget,
+                    // Nothing is served by a dead datanode, it's safe to open the region
+                    // on the target.
+                    Err(e @ error::Error::DatanodeUnavailable { .. }) => {
+                        warn!(
+                            e; "Skip closing region {:?} on datanode {}",
+                            migration.region,
+                            source
+                        );
+                        migration.step = MigrationStep::OpenTarget;
+                    }
+                    Err(e @ error::Error::ExecuteInstruction { .. }) => {
+                        migration.error = Some(e.to_string());
+                        migration.step = MigrationStep::Rollback;
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+            MigrationStep::OpenTarget => {
+                let target = migration.to_node_id;
+                let instruction = Instruction::OpenRegions(migration.region.clone());
+                match self
+                    .execute(&mut value, &mut migration, target, instruction)
+                    .await
+                {
+                    Ok(()) => migration.step = MigrationStep::UpdateRoute,
+                    Err(
+                        e @ (error::Error::DatanodeUnavailable { .. }
+                        | error::Error::ExecuteInstruction { .. }),
+                    ) => {
+                        migration.error = Some(e.to_string());
+                        migration.step = MigrationStep::Rollback;
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+            MigrationStep::UpdateRoute => {
+                if !self.update_route(&migration).await? {
+                    // The route was changed concurrently, it is re-read in the next round.
+                    value.current_migration = Some(migration);
+                    return self.save(value).await;
+                }
+                info!(
+                    "Region {:?} moved from datanode {} to {}",
+                    migration.region, source, migration.to_node_id
+                );
+                value.regions_remaining = value.regions_remaining.saturating_sub(1);
+                return self.save(value).await;
+            }
+            MigrationStep::Rollback => {
+                let instruction = Instruction::OpenRegions(migration.region.clone());
+                let mut error = migration.error.clone().unwrap_or_default();
+                if let Err(e) = self
+                    .execute(&mut value, &mut migration, source, instruction)
+                    .await
+                {
+                    error = format!("{error}, failed to reopen the region: {e}");
+                }
+                error!(
+                    "Failed to move region {:?} off datanode {}: {}",
+                    migration.region, source, error
+                );
+
+                value.state = DecommissionState::Failed;
+                value.error = Some(error.clone());
+                self.save(value).await?;
+                return error::MigrateRegionSnafu {
+                    node_id: source,
+                    error,
+                }
+                .fail();
+            }
+        }
+
+        value.current_migration = Some(migration);
+        self.save(value).await
+    }
+
+    /// Sends the instruction of the current step unless it has been sent, then waits
+    /// for the datanode to reply.
+    async fn execute(
+        &self,
+        value: &mut DecommissionValue,
+        migration: &mut RegionMigration,
+        node_id: u64,
+        instruction: Instruction,
+    ) -> Result<()> {
+        let cluster_id = self.key.cluster_id;
+        let is_open = matches!(instruction, Instruction::OpenRegions(_));
+        let id = match migration.instruction_id {
+            Some(id) => id,
+            None => {
+                let id = self.mailbox.send(cluster_id, node_id, instruction).await?;
+                migration.instruction_id = Some(id);
+                value.current_migration = Some(migration.clone());
+                self.save(value.clone()).await?;
+                id
+            }
+        };
+
+        let reply = loop {
+            if let Some(reply) = self.mailbox.reply(cluster_id, node_id, id).await? {
+                break Some(reply);
+            }
+            if !self.is_alive(node_id).await? {
+                break None;
+            }
+            tokio::time::sleep(self.poll_interval).await;
+        };
+        if is_open && reply.is_some() {
+            self.record_open_elapsed(node_id, id).await?;
+        }
+        // The instruction is removed even if the datanode is dead, so it won't be
+        // executed after the datanode is back.
+        self.mailbox.remove(cluster_id, node_id, id).await?;
+        migration.instruction_id = None;
+
+        let reply = reply.context(error::DatanodeUnavailableSnafu { node_id, id })?;
+        match reply.error {
+            Some(error) => error::ExecuteInstructionSnafu { node_id, id, error }.fail(),
+            None => Ok(()),
+        }
+    }
+
+    /// Records the time from sending the open instruction to observing its reply. The
+    /// instruction keeps its sending time, so it's measured even across leader changes.
+    async fn record_open_elapsed(&self, node_id: u64, id: u64) -> Result<()> {
+        if let Some(value) = self
+            .mailbox
+            .instruction(self.key.cluster_id, node_id, id)
+            .await?
+        {
+            let elapsed = time_util::current_time_millis() - value.created_at_millis;
+            histogram!(
+                METRIC_META_REGION_OPEN_ELAPSED,
+                Duration::from_millis(elapsed.max(0) as u64),
+                LABEL_NODE_ID => node_id.to_string()
+            );
+        }
+        Ok(())
+    }
+
+    /// Alive datanodes that can take over regions, with their region loads.
+    async fn candidates(&self, loads: &HashMap<u64, usize>) -> Result<Vec<(Peer, usize)>> {
+        let cordoned = cordoned_nodes(self.key.cluster_id, &self.kv_store).await?;
+        let node_id = self.key.node_id;
+        let lease_secs = self.datanode_lease_secs;
+        let lease_filter = |k: &LeaseKey, v: &LeaseValue| {
+            k.node_id != node_id
+                && !cordoned.contains(&k.node_id)
+                && time_util::current_time_millis() - v.timestamp_millis < lease_secs * 1000
+        };
+        let lease_kvs =
+            lease::alive_datanodes(self.key.cluster_id, &self.kv_store, lease_filter).await?;
+
+        Ok(lease_kvs
+            .into_iter()
+            .map(|(k, v)| {
+                let load = loads.get(&k.node_id).copied().unwrap_or(0);
+                let peer = Peer {
+                    id: k.node_id,
+                    addr: v.node_addr,
+                };
+                (peer, load)
+            })
+            .collect())
+    }
+
+    async fn is_alive(&self, node_id: u64) -> Result<bool> {
+        let lease_secs = self.datanode_lease_secs;
+        let lease_filter = |k: &LeaseKey, v: &LeaseValue| {
+            k.node_id == node_id
+                && time_util::current_time_millis() - v.timestamp_millis < lease_secs * 1000
+        };
+        let lease_kvs =
+            lease::alive_datanodes(self.key.cluster_id, &self.kv_store, lease_filter).await?;
+
+        Ok(!lease_kvs.is_empty())
+    }
+
+    /// Moves the route of the region to the target datanode, returns false if the route
+    /// was changed concurrently.
+    async fn update_route(&self, migration: &RegionMigration) -> Result<bool> {
+        let region = &migration.region;
+        let region_ids = region
+            .region_numbers
+            .iter()
+            .map(|n| *n as u64)
+            .collect::<HashSet<_>>();
+        let table_name = TableName {
+            catalog_name: region.catalog.clone(),
+            schema_name: region.schema.clone(),
+            table_name: region.table.clone(),
+        };
+        let route_key = TableRouteKey::with_table_name(region.table_id as u64, &table_name)
+            .key()
+            .into_bytes();
+        let kv = self
+            .kv_store
+            .get(route_key.clone())
+            .await?
+            .with_context(|| error::TableRouteNotFoundSnafu {
+                key: String::from_utf8_lossy(&route_key),
+            })?;
+        let mut trv: TableRouteValue = kv
+            .value
+            .as_slice()
+            .try_into()
+            .context(error::DecodeTableRouteSnafu)?;
+
+        let peer_index = match trv.peers.iter().position(|p| p.id == migration.to_node_id) {
+            Some(index) => index,
+            None => {
+                trv.peers.push(Peer {
+                    id: migration.to_node_id,
+                    addr: migration.to_node_addr.clone(),
+                });
+                trv.peers.len() - 1
+            }
+        };
+        if let Some(table_route) = &mut trv.table_route {
+            for rr in &mut table_route.region_routes {
+                if let Some(r) = &rr.region {
+                    if region_ids.contains(&r.id) {
+                        rr.leader_peer_index = peer_index as u64;
+                    }
+                }
+            }
+        }
+
+        let req = CompareAndPutRequest {
+            key: route_key,
+            expect: kv.value,
+            value: trv.into(),
+            ..Default::default()
+        };
+        if !self.kv_store.compare_and_put(req).await?.success {
+            warn!(
+                "Table route of {} changed while migrating regions {:?}, retrying",
+                region.table_name(),
+                region.region_numbers
+            );
+            return Ok(false);
+        }
+
+        self.move_region_ids(&table_name, &region.region_numbers, migration.to_node_id)
+            .await?;
+
+        Ok(true)
+    }
+
+    async fn move_region_ids(
+        &self,
+        table_name: &TableName,
+        region_numbers: &[u32],
+        to: u64,
+    ) -> Result<()> {
+        let tgk = TableGlobalKey {
+            catalog_name: table_name.catalog_name.clone(),
+            schema_name: table_name.schema_name.clone(),
+            table_name: table_name.table_name.clone(),
+        };
+        let kv = match self.kv_store.get(tgk.to_string().into_bytes()).await? {
+            Some(kv) => kv,
+            None => {
+                warn!("Table global value is absent: {}", tgk);
+                return Ok(());
+            }
+        };
+        let mut tgv =
+            TableGlobalValue::from_bytes(kv.value).context(error::InvalidCatalogValueSnafu)?;
+
+        if let Some(regions) = tgv.regions_id_map.get_mut(&self.key.node_id) {
+            regions.retain(|id| !region_numbers.contains(id));
+            if regions.is_empty() {
+                tgv.regions_id_map.remove(&self.key.node_id);
+            }
+        }
+        let regions = tgv.regions_id_map.entry(to).or_default();
+        for region_number in region_numbers {
+            if !regions.contains(region_number) {
+                regions.push(*region_number);
+            }
+        }
+
+        let req = PutRequest {
+            key: kv.key,
+            value: tgv.as_bytes().context(error::InvalidCatalogValueSnafu)?,
+            ..Default::default()
+        };
+        self.kv_store.put(req).await?;
+
+        Ok(())
+    }
+
+    async fn remove_node_keys(&self) -> Result<()> {
+        let lease_key = LeaseKey {
+            cluster_id: self.key.cluster_id,
+            node_id: self.key.node_id,
+        };
+        let req = DeleteRangeRequest {
+            key: lease_key.try_into()?,
+            ..Default::default()
+        };
+        self.kv_store.delete_range(req).await?;
+
+        let stat_key = StatKey {
+            cluster_id: self.key.cluster_id,
+            node_id: self.key.node_id,
+        };
+        let req = DeleteRangeRequest {
+            key: stat_key.into(),
+            ..Default::default()
+        };
+        self.in_memory.delete_range(req).await?;
+
+        let hot_region_key = HotRegionKey {
+            cluster_id: self.key.cluster_id,
+            node_id: self.key.node_id,
+        };
+        let req = DeleteRangeRequest {
+            key: hot_region_key.into(),
+            ..Default::default()
+        };
+        self.in_memory.delete_range(req).await?;
+
+        Ok(())
+    }
+
+    async fn save(&self, value: DecommissionValue) -> Result<()> {
+        let req = PutRequest {
+            key: self.key.clone().into(),
+            value: value.try_into()?,
+            ..Default::default()
+        };
+        self.kv_store.put(req).await?;
+
+        Ok(())
+    }
+}
+
+/// Resumes all the unfinished decommissions, used when a new leader is elected.
+pub async fn resume_all(ctx: Context) -> Result<()> {
+    let key = format!("{DN_DECOMMISSION_PREFIX}-").into_bytes();
+    let range_end = util::get_prefix_end_key(&key);
+    let req = RangeRequest {
+        key,
+        range_end,
+        ..Default::default()
+    };
+    let kvs = ctx.kv_store.range(req).await?.kvs;
+
+    for kv in kvs {
+        let key: DecommissionKey = kv.key.try_into()?;
+        let value: DecommissionValue = kv.value.try_into()?;
+        if matches!(
+            value.state,
+            DecommissionState::Done | DecommissionState::Failed
+        ) {
+            continue;
+        }
+
+        info!("Resuming decommission of datanode {:?}", key);
+        let decommission = DatanodeDecommission::new(key.cluster_id, key.node_id, &ctx);
+        common_runtime::spawn_bg(async move {
+            if let Err(e) = decommission.run().await {
+                error!(e; "Failed to decommission datanode {:?}", decommission.key);
+            }
+        });
+    }
+
+    Ok(())
+}
+
+/// Datanodes being (or already) decommissioned, they must not receive new regions.
+pub async fn cordoned_nodes(cluster_id: u64, kv_store: &KvStoreRef) -> Result<HashSet<u64>> {
+    let key = format!("{DN_DECOMMISSION_PREFIX}-{cluster_id}-").into_bytes();
+    let range_end = util::get_prefix_end_key(&key);
+    let req = RangeRequest {
+        key,
+        range_end,
+        keys_only: true,
+        ..Default::default()
+    };
+    let kvs = kv_store.range(req).await?.kvs;
+
+    kvs.into_iter()
+        .map(|kv| DecommissionKey::try_from(kv.key).map(|k| k.node_id))
+        .collect()
+}
+
+/// Where a region is currently placed according to its table route.
+struct RegionPlacement {
+    table_id: u32,
+    table_name: TableName,
+    region_id: u64,
+    node_id: u64,
+}
+
+impl RegionPlacement {
+    fn region_ident(&self) -> RegionIdent {
+        RegionIdent {
+            catalog: self.table_name.catalog_name.clone(),
+            schema: self.table_name.schema_name.clone(),
+            table: self.table_name.table_name.clone(),
+            table_id: self.table_id,
+            region_numbers: vec![self.region_id as u32],
+        }
+    }
+}
+
+async fn region_placements(kv_store: &KvStoreRef) -> Result<Vec<RegionPlacement>> {
+    let key = format!("{TABLE_ROUTE_PREFIX}-").into_bytes();
+    let range_end = util::get_prefix_end_key(&key);
+    let req = RangeRequest {
+        key,
+        range_end,
+        ..Default::default()
+    };
+    let kvs = kv_store.range(req).await?.kvs;
+
+    let mut placements = vec![];
+    for kv in kvs {
+        let TableRouteValue { peers, table_route }: TableRouteValue = kv
+            .value
+            .as_slice()
+            .try_into()
+            .context(error::DecodeTableRouteSnafu)?;
+        let table_route = match table_route {
+            Some(table_route) => table_route,
+            None => continue,
+        };
+        let (table_id, table_name) = match table_route.table {
+            Some(table) => (table.id as u32, table.table_name.unwrap_or_default()),
+            None => continue,
+        };
+
+        for rr in table_route.region_routes {
+            let peer = peers.get(rr.leader_peer_index as usize);
+            if let (Some(region), Some(peer)) = (rr.region, peer) {
+                placements.push(RegionPlacement {
+                    table_id,
+                    table_name: table_name.clone(),
+                    region_id: region.id,
+                    node_id: peer.id,
+                });
+            }
+        }
+    }
+
+    Ok(placements)
+}
+
+fn region_loads(placements: &[RegionPlacement]) -> HashMap<u64, usize> {
+    let mut loads = HashMap::new();
+    for placement in placements {
+        *loads.entry(placement.node_id).or_insert(0) += 1;
+    }
+    loads
+}
+
+fn absorbable_regions(candidates: &[(Peer, usize)], max_regions_per_datanode: u64) -> usize {
+    if candidates.is_empty() {
+        return 0;
+    }
+    if max_regions_per_datanode == 0 {
+        return usize::MAX;
+    }
+    let max = max_regions_per_datanode as usize;
+    candidates
+        .iter()
+        .map(|(_, load)| max.saturating_sub(*load))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicBool;
+    use std::sync::{Arc, Mutex};
+
+    use api::v1::meta::{Region, RegionRoute, Table, TableRoute};
+    use tokio::task::JoinHandle;
+
+    use super::*;
+    use crate::handler::instruction::InstructionReply;
+    use crate::keys::InstructionReplyKey;
+    use crate::mailbox::Mailbox;
+    use crate::service::store::memory::MemStore;
+
+    fn new_ctx() -> Context {
+        let kv_store: KvStoreRef = Arc::new(MemStore::new());
+        Context {
+            datanode_lease_secs: 30,
+            server_addr: "127.0.0.1:0000".to_string(),
+            in_memory: Arc::new(MemStore::new()),
+            kv_store: kv_store.clone(),
+            mailbox: Arc::new(Mailbox::new(kv_store)),
+            election: None,
+            skip_all: Arc::new(AtomicBool::new(false)),
+            catalog: None,
+            schema: None,
+            table: None,
+        }
+    }
+
+    fn peer(id: u64) -> Peer {
+        Peer {
+            id,
+            addr: format!("127.0.0.1:300{id}"),
+        }
+    }
+
+    async fn put_lease(ctx: &Context, node_id: u64) {
+        let key = LeaseKey {
+            cluster_id: 0,
+            node_id,
+        };
+        let value = LeaseValue {
+            timestamp_millis: time_util::current_time_millis(),
+            node_addr: peer(node_id).addr,
+        };
+        let req = PutRequest {
+            key: key.try_into().unwrap(),
+            value: value.try_into().unwrap(),
+            ..Default::default()
+        };
+        ctx.kv_store.put(req).await.unwrap();
+    }
+
+    /// Puts a table whose i-th region is led by `leaders[i]`.
+    async fn put_table_route(ctx: &Context, leaders: &[u64]) {
+        let table_name = TableName {
+            catalog_name: "greptime".to_string(),
+            schema_name: "public".to_string(),
+            table_name: "demo".to_string(),
+        };
+        let peers = leaders.iter().map(|id| peer(*id)).collect::<Vec<_>>();
+        let region_routes = (0..leaders.len())
+            .map(|i| RegionRoute {
+                region: Some(Region {
+                    id: i as u64,
+                    ..Default::default()
+                }),
+                leader_peer_index: i as u64,
+                follower_peer_indexes: vec![],
+            })
+            .collect();
+        let trv = TableRouteValue {
+            peers,
+            table_route: Some(TableRoute {
+                table: Some(Table {
+                    id: 1024,
+                    table_name: Some(table_name.clone()),
+                    ..Default::default()
+                }),
+                region_routes,
+            }),
+        };
+        let req = PutRequest {
+            key: TableRouteKey::with_table_name(1024, &table_name)
+                .key()
+                .into_bytes(),
+            value: trv.into(),
+            ..Default::default()
+        };
+        ctx.kv_store.put(req).await.unwrap();
+
+        let value = r#"{"node_id":1,"regions_id_map":{},"table_info":{"ident":{"table_id":1024,"version":1},"name":"demo","desc":null,"catalog_name":"greptime","schema_name":"public","meta":{"schema":{"column_schemas":[],"timestamp_index":null,"version":0},"primary_key_indices":[],"value_indices":[],"engine":"mito","next_column_id":0,"region_numbers":[],"engine_options":{},"options":{},"created_on":"1970-01-01T00:00:00Z"},"table_type":"Base"}}"#;
+        let mut tgv = TableGlobalValue::parse(value).unwrap();
+        for (i, leader) in leaders.iter().enumerate() {
+            tgv.regions_id_map
+                .entry(*leader)
+                .or_default()
+                .push(i as u32);
+        }
+        let tgk = TableGlobalKey {
+            catalog_name: "greptime".to_string(),
+            schema_name: "public".to_string(),
+            table_name: "demo".to_string(),
+        };
+        let req = PutRequest {
+            key: tgk.to_string().into_bytes(),
+            value: tgv.as_bytes().unwrap(),
+            ..Default::default()
+        };
+        ctx.kv_store.put(req).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_decommission_capacity() {
+        let ctx = new_ctx();
+        put_lease(&ctx, 1).await;
+        put_table_route(&ctx, &[1, 1, 2]).await;
+
+        // no other alive datanode
+        let decommission = DatanodeDecommission::new(0, 1, &ctx);
+        let err = decommission.start(0).await.unwrap_err();
+        assert!(matches!(
+            err,
+            error::Error::DecommissionCapacity {
+                regions: 2,
+                capacity: 0,
+                ..
+            }
+        ));
+        assert!(decommission.status().await.unwrap().is_none());
+
+        // datanode 3 can only take 1 more region
+        put_lease(&ctx, 3).await;
+        let err = decommission.start(1).await.unwrap_err();
+        assert!(matches!(
+            err,
+            error::Error::DecommissionCapacity {
+                regions: 2,
+                capacity: 1,
+                ..
+            }
+        ));
+
+        let value = decommission.start(2).await.unwrap();
+        assert_eq!(DecommissionState::Cordoned, value.state);
+        assert_eq!(2, value.regions_remaining);
+    }
+
+    fn new_decommission(ctx: &Context, node_id: u64) -> DatanodeDecommission {
+        let mut decommission = DatanodeDecommission::new(0, node_id, ctx);
+        decommission.poll_interval = Duration::from_millis(10);
+        decommission
+    }
+
+    type Executed = Arc<Mutex<Vec<(u64, Instruction)>>>;
+
+    /// Replies the instructions sent to the datanodes `node_ids` like datanodes do, the
+    /// instructions to open regions on `failing_node` fail. Returns the executed
+    /// instructions with the datanodes executing them.
+    fn start_datanodes(
+        ctx: &Context,
+        node_ids: Vec<u64>,
+        failing_node: Option<u64>,
+    ) -> (Executed, JoinHandle<()>) {
+        let executed = Executed::default();
+        let mailbox = ctx.mailbox.clone();
+        let kv_store = ctx.kv_store.clone();
+        let executed_clone = executed.clone();
+        let handle = tokio::spawn(async move {
+            loop {
+                for node_id in &node_ids {
+                    for (message, _) in mailbox.pending(0, *node_id).await.unwrap() {
+                        let error = match message.instruction {
+                            Instruction::OpenRegions(_) if failing_node == Some(*node_id) => {
+                                Some("mocked error".to_string())
+                            }
+                            _ => None,
+                        };
+                        executed_clone
+                            .lock()
+                            .unwrap()
+                            .push((*node_id, message.instruction));
+
+                        let key = InstructionReplyKey {
+                            cluster_id: 0,
+                            node_id: *node_id,
+                            id: message.id,
+                        };
+                        let req = PutRequest {
+                            key: key.into(),
+                            value: InstructionReply { error }.try_into().unwrap(),
+                            ..Default::default()
+                        };
+                        kv_store.put(req).await.unwrap();
+                    }
+                }
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        });
+        (executed, handle)
+    }
+
+    fn region(region_number: u32) -> RegionIdent {
+        RegionIdent {
+            catalog: "greptime".to_string(),
+            schema: "public".to_string(),
+            table: "demo".to_string(),
+            table_id: 1024,
+            region_numbers: vec![region_number],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_decommission_run() {
+        let ctx = new_ctx();
+        put_lease(&ctx, 1).await;
+        put_lease(&ctx, 2).await;
+        put_lease(&ctx, 3).await;
+        put_table_route(&ctx, &[1, 1, 2]).await;
+        let (executed, handle) = start_datanodes(&ctx, vec![1, 2, 3], None);
+
+        let decommission = new_decommission(&ctx, 1);
+        let value = decommission.start(0).await.unwrap();
+        assert_eq!(2, value.regions_remaining);
+
+        let cordoned = cordoned_nodes(0, &ctx.kv_store).await.unwrap();
+        assert!(cordoned.contains(&1));
+
+        decommission.run().await.unwrap();
+        handle.abort();
+
+        let value = decommission.status().await.unwrap().unwrap();
+        assert_eq!(DecommissionState::Done, value.state);
+        assert_eq!(0, value.regions_remaining);
+
+        let placements = region_placements(&ctx.kv_store).await.unwrap();
+        assert_eq!(3, placements.len());
+        assert!(placements.iter().all(|p| p.node_id != 1));
+        // the regions are spread over the least loaded datanodes
+        let loads = region_loads(&placements);
+        assert_eq!(Some(&2), loads.get(&2));
+        assert_eq!(Some(&1), loads.get(&3));
+
+        // each region is closed on the decommissioned datanode before it's opened
+        // on the target datanode
+        let expected = vec![
+            (1, Instruction::CloseRegions(region(0))),
+            (3, Instruction::OpenRegions(region(0))),
+            (1, Instruction::CloseRegions(region(1))),
+            (2, Instruction::OpenRegions(region(1))),
+        ];
+        assert_eq!(expected, *executed.lock().unwrap());
+        assert!(ctx.mailbox.pending(0, 1).await.unwrap().is_empty());
+
+        let tgk = TableGlobalKey {
+            catalog_name: "greptime".to_string(),
+            schema_name: "public".to_string(),
+            table_name: "demo".to_string(),
+        };
+        let kv = ctx.kv_store.get(tgk.to_string().into_bytes()).await;
+        let tgv = TableGlobalValue::from_bytes(kv.unwrap().unwrap().value).unwrap();
+        assert!(!tgv.regions_id_map.contains_key(&1));
+        assert_eq!(Some(&vec![2, 1]), tgv.regions_id_map.get(&2));
+        assert_eq!(Some(&vec![0]), tgv.regions_id_map.get(&3));
+
+        let alive = lease::alive_datanodes(0, &ctx.kv_store, |_, _| true)
+            .await
+            .unwrap();
+        assert!(alive.iter().all(|(k, _)| k.node_id != 1));
+    }
+
+    #[tokio::test]
+    async fn test_decommission_resume() {
+        let ctx = new_ctx();
+        put_lease(&ctx, 1).await;
+        put_lease(&ctx, 2).await;
+        put_table_route(&ctx, &[1, 2]).await;
+
+        let decommission = new_decommission(&ctx, 1);
+        decommission.start(0).await.unwrap();
+        decommission
+            .next_migration(decommission.status().await.unwrap().unwrap())
+            .await
+            .unwrap();
+        // the instruction to close the region was sent by the old leader
+        let mut value = decommission.status().await.unwrap().unwrap();
+        value.state = DecommissionState::Migrating;
+        let mut migration = value.current_migration.take().unwrap();
+        let instruction = Instruction::CloseRegions(region(0));
+        let id = ctx.mailbox.send(0, 1, instruction).await.unwrap();
+        migration.instruction_id = Some(id);
+        value.current_migration = Some(migration);
+        decommission.save(value).await.unwrap();
+
+        // a new instance, e.g. on another leader, continues from the persisted state
+        let (executed, handle) = start_datanodes(&ctx, vec![1, 2], None);
+        let decommission = new_decommission(&ctx, 1);
+        decommission.run().await.unwrap();
+        handle.abort();
+
+        let value = decommission.status().await.unwrap().unwrap();
+        assert_eq!(DecommissionState::Done, value.state);
+
+        let placements = region_placements(&ctx.kv_store).await.unwrap();
+        assert!(placements.iter().all(|p| p.node_id == 2));
+        let expected = vec![
+            (1, Instruction::CloseRegions(region(0))),
+            (2, Instruction::OpenRegions(region(0))),
+        ];
+        assert_eq!(expected, *executed.lock().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_decommission_rollback() {
+        let ctx = new_ctx();
+        put_lease(&ctx, 1).await;
+        put_lease(&ctx, 2).await;
+        put_table_route(&ctx, &[1, 2]).await;
+        let (executed, handle) = start_datanodes(&ctx, vec![1, 2], Some(2));
+
+        let decommission = new_decommission(&ctx, 1);
+        decommission.start(0).await.unwrap();
+        let err = decommission.run().await.unwrap_err();
+        assert!(err.to_string().contains("mocked error"), "{err}");
+        handle.abort();
+
+        // the region is reopened on the decommissioned datanode
+        let expected = vec![
+            (1, Instruction::CloseRegions(region(0))),
+            (2, Instruction::OpenRegions(region(0))),
+            (1, Instruction::OpenRegions(region(0))),
+        ];
+        assert_eq!(expected, *executed.lock().unwrap());
+
+        let value = decommission.status().await.unwrap().unwrap();
+        assert_eq!(DecommissionState::Failed, value.state);
+        assert!(value.error.unwrap().contains("mocked error"));
+        let placements = region_placements(&ctx.kv_store).await.unwrap();
+        assert_eq!(Some(&1), region_loads(&placements).get(&1));
+        // the datanode stays cordoned
+        let cordoned = cordoned_nodes(0, &ctx.kv_store).await.unwrap();
+        assert!(cordoned.contains(&1));
+
+        // a failed decommission can be started again
+        let value = decommission.start(0).await.unwrap();
+        assert_eq!(DecommissionState::Cordoned, value.state);
+        assert_eq!(None, value.error);
+    }
+
+    #[tokio::test]
+    async fn test_decommission_dead_datanode() {
+        let ctx = new_ctx();
+        put_lease(&ctx, 1).await;
+        put_lease(&ctx, 2).await;
+        put_table_route(&ctx, &[1, 2]).await;
+        // only datanode 2 is running
+        let (executed, handle) = start_datanodes(&ctx, vec![2], None);
+
+        let decommission = new_decommission(&ctx, 1);
+        decommission.start(0).await.unwrap();
+        // the lease of datanode 1 expires
+        let key = LeaseKey {
+            cluster_id: 0,
+            node_id: 1,
+        };
+        let value = LeaseValue {
+            timestamp_millis: 0,
+            node_addr: peer(1).addr,
+        };
+        let req = PutRequest {
+            key: key.try_into().unwrap(),
+            value: value.try_into().unwrap(),
+            ..Default::default()
+        };
+        ctx.kv_store.put(req).await.unwrap();
+
+        decommission.run().await.unwrap();
+        handle.abort();
+
+        let value = decommission.status().await.unwrap().unwrap();
+        assert_eq!(DecommissionState::Done, value.state);
+        assert_eq!(
+            vec![(2, Instruction::OpenRegions(region(0)))],
+            *executed.lock().unwrap()
+        );
+        // the unreplied instruction isn't delivered when the datanode is back
+        assert!(ctx.mailbox.pending(0, 1).await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_absorbable_regions() {
+        assert_eq!(0, absorbable_regions(&[], 0));
+        assert_eq!(usize::MAX, absorbable_regions(&[(peer(1), 10)], 0));
+        assert_eq!(3, absorbable_regions(&[(peer(1), 10), (peer(2), 2)], 5));
+    }
+}
@@ -14,11 +14,72 @@
 
 pub mod etcd;
 
-use crate::error::Result;
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, OptionExt};
 
-pub const LEASE_SECS: i64 = 3;
-pub const KEEP_ALIVE_PERIOD_SECS: u64 = LEASE_SECS as u64 * 2 / 3;
+use crate::error::{self, Result};
+
+pub const LEASE_SECS: u64 = 3;
+pub const KEEP_ALIVE_PERIOD_SECS: u64 = LEASE_SECS * 2 / 3;
 pub const ELECTION_KEY: &str = "__meta_srv_election";
+/// Key of the term of the latest leader, increased by every new leader. Writes to the store
+/// are fenced by the term, so an old leader can't write after a new one is elected.
+pub const LEADER_TERM_KEY: &str = "__meta_srv_leader_term";
+pub const LEASE_SAFETY_MARGIN_MILLIS: u64 = 500;
+
+/// Options of the leader election. A shorter lease detects a dead leader faster, at the
+/// cost of more renewals to etcd.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ElectionOptions {
+    /// Seconds the leadership lasts without being renewed.
+    pub lease_ttl_secs: u64,
+    /// Seconds between the renewals of the leader lease, it must be shorter than the lease.
+    pub renew_interval_secs: u64,
+    /// The leader stops serving reads from its local states once its lease is to expire
+    /// within this, as a new leader may have been elected if the lease can't be renewed.
+    pub lease_safety_margin_millis: u64,
+}
+
+impl Default for ElectionOptions {
+    fn default() -> Self {
+        Self {
+            lease_ttl_secs: LEASE_SECS,
+            renew_interval_secs: KEEP_ALIVE_PERIOD_SECS,
+            lease_safety_margin_millis: LEASE_SAFETY_MARGIN_MILLIS,
+        }
+    }
+}
+
+impl ElectionOptions {
+    pub fn validate(&self) -> Result<()> {
+        ensure!(
+            self.renew_interval_secs > 0,
+            error::InvalidArgumentsSnafu {
+                err_msg: "election renew_interval_secs must be greater than 0",
+            }
+        );
+        ensure!(
+            self.renew_interval_secs < self.lease_ttl_secs,
+            error::InvalidArgumentsSnafu {
+                err_msg: format!(
+                    "election renew_interval_secs ({}) must be less than lease_ttl_secs ({})",
+                    self.renew_interval_secs, self.lease_ttl_secs
+                ),
+            }
+        );
+        ensure!(
+            self.lease_safety_margin_millis < self.lease_ttl_secs * 1000,
+            error::InvalidArgumentsSnafu {
+                err_msg: format!(
+                    "election lease_safety_margin_millis ({}) must be less than the lease ({}s)",
+                    self.lease_safety_margin_millis, self.lease_ttl_secs
+                ),
+            }
+        );
+        Ok(())
+    }
+}
 
 #[async_trait::async_trait]
 pub trait Election: Send + Sync {
@@ -27,6 +88,14 @@ pub trait Election: Send + Sync {
     /// Returns `true` if current node is the leader.
     fn is_leader(&self) -> bool;
 
+    /// Returns the term of the latest leadership of current node, 0 if it has never been
+    /// the leader.
+    fn term(&self) -> u64;
+
+    /// Returns the time in milliseconds since the epoch when the lease of current node as
+    /// the leader expires unless renewed, 0 if it's not the leader.
+    fn lease_expire_at_millis(&self) -> i64;
+
     /// When a new leader is born, it may need some initialization
     /// operations (asynchronous), this method tells us when these
     /// initialization operations can be performed.
@@ -47,3 +116,61 @@ pub trait Election: Send + Sync {
     /// acquire leadership on the election.
     async fn resign(&self) -> Result<()>;
 }
+
+pub(crate) fn encode_term(term: u64) -> Vec<u8> {
+    term.to_string().into_bytes()
+}
+
+pub(crate) fn decode_term(value: &[u8]) -> Result<u64> {
+    std::str::from_utf8(value)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .context(error::InvalidLeaderTermSnafu {
+            value: String::from_utf8_lossy(value),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_election_options() {
+        assert!(ElectionOptions::default().validate().is_ok());
+
+        let opts = ElectionOptions {
+            lease_ttl_secs: 10,
+            renew_interval_secs: 3,
+            lease_safety_margin_millis: 1000,
+        };
+        assert!(opts.validate().is_ok());
+
+        for (lease_ttl_secs, renew_interval_secs, lease_safety_margin_millis) in [
+            (3, 3, 500),
+            (3, 5, 500),
+            (3, 0, 500),
+            (0, 0, 0),
+            (3, 2, 3000),
+        ] {
+            let opts = ElectionOptions {
+                lease_ttl_secs,
+                renew_interval_secs,
+                lease_safety_margin_millis,
+            };
+            let err = opts.validate().unwrap_err();
+            assert!(
+                matches!(err, error::Error::InvalidArguments { .. }),
+                "{opts:?}: {err}"
+            );
+        }
+    }
+    #[test]
+    fn test_encode_decode_term() {
+        assert_eq!(b"42".to_vec(), encode_term(42));
+        assert_eq!(42, decode_term(&encode_term(42)).unwrap());
+        assert!(matches!(
+            decode_term(b"not a term").unwrap_err(),
+            error::Error::InvalidLeaderTerm { .. }
+        ));
+    }
+}
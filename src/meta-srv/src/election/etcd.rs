@@ -12,15 +12,18 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 
 use common_telemetry::{info, warn};
-use etcd_client::Client;
+use common_time::util as time_util;
+use etcd_client::{Client, Compare, CompareOp, Txn, TxnOp};
 use snafu::{OptionExt, ResultExt};
 
-use crate::election::{Election, ELECTION_KEY, KEEP_ALIVE_PERIOD_SECS, LEASE_SECS};
+use crate::election::{
+    decode_term, encode_term, Election, ElectionOptions, ELECTION_KEY, LEADER_TERM_KEY,
+};
 use crate::error;
 use crate::error::Result;
 use crate::metasrv::{ElectionRef, LeaderValue};
@@ -30,10 +33,17 @@ pub struct EtcdElection {
     client: Client,
     is_leader: AtomicBool,
     infancy: AtomicBool,
+    term: AtomicU64,
+    lease_expire_at_millis: AtomicI64,
+    options: ElectionOptions,
 }
 
 impl EtcdElection {
-    pub async fn with_endpoints<E, S>(leader_value: E, endpoints: S) -> Result<ElectionRef>
+    pub async fn with_endpoints<E, S>(
+        leader_value: E,
+        endpoints: S,
+        options: ElectionOptions,
+    ) -> Result<ElectionRef>
     where
         E: AsRef<str>,
         S: AsRef<[E]>,
@@ -42,21 +52,68 @@ impl EtcdElection {
             .await
             .context(error::ConnectEtcdSnafu)?;
 
-        Self::with_etcd_client(leader_value, client)
+        Self::with_etcd_client(leader_value, client, options)
     }
 
-    pub fn with_etcd_client<E>(leader_value: E, client: Client) -> Result<ElectionRef>
+    pub fn with_etcd_client<E>(
+        leader_value: E,
+        client: Client,
+        options: ElectionOptions,
+    ) -> Result<ElectionRef>
     where
         E: AsRef<str>,
     {
+        Ok(Arc::new(Self::new(leader_value, client, options)?))
+    }
+
+    fn new<E>(leader_value: E, client: Client, options: ElectionOptions) -> Result<Self>
+    where
+        E: AsRef<str>,
+    {
+        options.validate()?;
         let leader_value = leader_value.as_ref().into();
 
-        Ok(Arc::new(Self {
+        Ok(Self {
             leader_value,
             client,
             is_leader: AtomicBool::new(false),
             infancy: AtomicBool::new(false),
-        }))
+            term: AtomicU64::new(0),
+            lease_expire_at_millis: AtomicI64::new(0),
+            options,
+        })
+    }
+
+    pub fn options(&self) -> &ElectionOptions {
+        &self.options
+    }
+}
+
+/// Increases the leader term in etcd by 1, returns the increased term.
+pub(crate) async fn increase_term(client: &Client) -> Result<u64> {
+    let mut kv_client = client.kv_client();
+    // Retries only if another node increases the term at the same time, which is rare.
+    loop {
+        let res = kv_client
+            .get(LEADER_TERM_KEY, None)
+            .await
+            .context(error::EtcdFailedSnafu)?;
+        let (term, compare) = match res.kvs().first() {
+            Some(kv) => (
+                decode_term(kv.value())?,
+                Compare::value(LEADER_TERM_KEY, CompareOp::Equal, kv.value()),
+            ),
+            // revision 0 means key was not exist
+            None => (0, Compare::create_revision(LEADER_TERM_KEY, CompareOp::Equal, 0)),
+        };
+
+        let new_term = term + 1;
+        let put = TxnOp::put(LEADER_TERM_KEY, encode_term(new_term), None);
+        let txn = Txn::new().when(vec![compare]).and_then(vec![put]);
+        let txn_res = kv_client.txn(txn).await.context(error::EtcdFailedSnafu)?;
+        if txn_res.succeeded() {
+            return Ok(new_term);
+        }
     }
 }
 
@@ -68,6 +125,14 @@ impl Election for EtcdElection {
         self.is_leader.load(Ordering::Relaxed)
     }
 
+    fn term(&self) -> u64 {
+        self.term.load(Ordering::Relaxed)
+    }
+
+    fn lease_expire_at_millis(&self) -> i64 {
+        self.lease_expire_at_millis.load(Ordering::Relaxed)
+    }
+
     fn in_infancy(&self) -> bool {
         self.infancy
             .compare_exchange(true, false, Ordering::Relaxed, Ordering::Relaxed)
@@ -78,7 +143,7 @@ impl Election for EtcdElection {
         let mut lease_client = self.client.lease_client();
         let mut election_client = self.client.election_client();
         let res = lease_client
-            .grant(LEASE_SECS, None)
+            .grant(self.options.lease_ttl_secs as i64, None)
             .await
             .context(error::EtcdFailedSnafu)?;
         let lease_id = res.id();
@@ -98,6 +163,11 @@ impl Election for EtcdElection {
             .context(error::EtcdFailedSnafu)?;
 
         if let Some(leader) = res.leader() {
+            // Fences the writes of previous leaders before serving as the leader.
+            let term = increase_term(&self.client).await?;
+            self.term.store(term, Ordering::Relaxed);
+            info!("[{}] leader term: {}", &self.leader_value, term);
+
             let (mut keeper, mut receiver) = self
                 .client
                 .lease_client()
@@ -106,13 +176,17 @@ impl Election for EtcdElection {
                 .context(error::EtcdFailedSnafu)?;
 
             let mut keep_alive_interval =
-                tokio::time::interval(Duration::from_secs(KEEP_ALIVE_PERIOD_SECS));
+                tokio::time::interval(Duration::from_secs(self.options.renew_interval_secs));
             loop {
                 keep_alive_interval.tick().await;
+                // The lease is renewed for ttl seconds since no earlier than now.
+                let sent_at = time_util::current_time_millis();
                 keeper.keep_alive().await.context(error::EtcdFailedSnafu)?;
 
                 if let Some(res) = receiver.message().await.context(error::EtcdFailedSnafu)? {
                     if res.ttl() > 0 {
+                        self.lease_expire_at_millis
+                            .store(sent_at + res.ttl() * 1000, Ordering::Relaxed);
                         // Only after a successful `keep_alive` is the leader considered official.
                         if self
                             .is_leader
@@ -138,6 +212,7 @@ impl Election for EtcdElection {
             }
 
             self.is_leader.store(false, Ordering::Relaxed);
+            self.lease_expire_at_millis.store(0, Ordering::Relaxed);
         }
 
         Ok(())
@@ -163,3 +238,35 @@ impl Election for EtcdElection {
         todo!()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_election_options() {
+        // Requires an etcd server, e.g. GT_ETCD_ENDPOINTS=127.0.0.1:2379.
+        let Ok(endpoints) = std::env::var("GT_ETCD_ENDPOINTS") else {
+            return;
+        };
+        let client = Client::connect(endpoints.split(',').collect::<Vec<_>>(), None)
+            .await
+            .unwrap();
+
+        let options = ElectionOptions {
+            lease_ttl_secs: 10,
+            renew_interval_secs: 4,
+            ..Default::default()
+        };
+        let election =
+            EtcdElection::new("127.0.0.1:3002", client.clone(), options.clone()).unwrap();
+        assert_eq!(&options, election.options());
+
+        let options = ElectionOptions {
+            lease_ttl_secs: 2,
+            renew_interval_secs: 2,
+            ..Default::default()
+        };
+        assert!(EtcdElection::new("127.0.0.1:3002", client, options).is_err());
+    }
+}
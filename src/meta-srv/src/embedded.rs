@@ -0,0 +1,206 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use api::v1::meta::{
+    BatchGetRequest, BatchGetResponse, BatchPutRequest, BatchPutResponse, CompareAndPutRequest,
+    CompareAndPutResponse, CreateRequest, DeleteRangeRequest, DeleteRangeResponse, DeleteRequest,
+    MoveValueRequest, MoveValueResponse, PutRequest, PutResponse, RangeRequest, RangeResponse,
+    RouteRequest, RouteResponse,
+};
+use snafu::OptionExt;
+
+use crate::bootstrap::build_meta_srv;
+use crate::error::{self, Result};
+use crate::metasrv::{MetaSrv, MetaSrvOptions};
+use crate::service::router::{handle_create, handle_delete, handle_route};
+
+/// A [MetaSrv] running in the same process as its caller.
+///
+/// Requests are served by calling the [MetaSrv] directly, without the gRPC router and
+/// network round trips.
+#[derive(Clone)]
+pub struct EmbeddedMetaSrv {
+    meta_srv: MetaSrv,
+}
+
+/// Builds and starts a [MetaSrv] backed by the memory store, without binding any port.
+pub async fn build_embedded_meta_srv(opts: &MetaSrvOptions) -> Result<EmbeddedMetaSrv> {
+    let opts = MetaSrvOptions {
+        use_memory_store: true,
+        ..opts.clone()
+    };
+    let meta_srv = build_meta_srv(&opts).await?;
+    meta_srv.start().await;
+
+    Ok(EmbeddedMetaSrv { meta_srv })
+}
+
+impl EmbeddedMetaSrv {
+    #[inline]
+    pub fn meta_srv(&self) -> &MetaSrv {
+        &self.meta_srv
+    }
+
+    pub fn shutdown(&self) {
+        self.meta_srv.shutdown();
+    }
+
+    pub async fn range(&self, req: RangeRequest) -> Result<RangeResponse> {
+        self.meta_srv.kv_store().range(req).await
+    }
+
+    pub async fn put(&self, req: PutRequest) -> Result<PutResponse> {
+        self.meta_srv.kv_store().put(req).await
+    }
+
+    pub async fn batch_get(&self, req: BatchGetRequest) -> Result<BatchGetResponse> {
+        self.meta_srv.kv_store().batch_get(req).await
+    }
+
+    pub async fn batch_put(&self, req: BatchPutRequest) -> Result<BatchPutResponse> {
+        self.meta_srv.kv_store().batch_put(req).await
+    }
+
+    pub async fn compare_and_put(
+        &self,
+        req: CompareAndPutRequest,
+    ) -> Result<CompareAndPutResponse> {
+        self.meta_srv.kv_store().compare_and_put(req).await
+    }
+
+    pub async fn delete_range(&self, req: DeleteRangeRequest) -> Result<DeleteRangeResponse> {
+        self.meta_srv.kv_store().delete_range(req).await
+    }
+
+    pub async fn move_value(&self, req: MoveValueRequest) -> Result<MoveValueResponse> {
+        self.meta_srv.kv_store().move_value(req).await
+    }
+
+    pub async fn create_route(&self, req: CreateRequest) -> Result<RouteResponse> {
+        let table_name = req.table_name.clone().context(error::EmptyTableNameSnafu)?;
+        let ctx = self.meta_srv.create_ctx(table_name);
+
+        handle_create(
+            req,
+            ctx,
+            self.meta_srv.selector(),
+            self.meta_srv.table_id_sequence(),
+        )
+        .await
+    }
+
+    pub async fn route(&self, req: RouteRequest) -> Result<RouteResponse> {
+        handle_route(req, self.meta_srv.new_ctx()).await
+    }
+
+    pub async fn delete_route(&self, req: DeleteRequest) -> Result<RouteResponse> {
+        handle_delete(req, self.meta_srv.new_ctx()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use api::v1::meta::{KeyValue, TableName};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_embedded_kv() {
+        // The bind address is never bound.
+        let opts = MetaSrvOptions {
+            bind_addr: "127.0.0.1:1".to_string(),
+            ..Default::default()
+        };
+        let meta_srv = build_embedded_meta_srv(&opts).await.unwrap();
+        assert!(meta_srv.meta_srv().options().use_memory_store);
+
+        meta_srv
+            .put(PutRequest {
+                key: b"key1".to_vec(),
+                value: b"value1".to_vec(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        meta_srv
+            .batch_put(BatchPutRequest {
+                kvs: vec![KeyValue {
+                    key: b"key2".to_vec(),
+                    value: b"value2".to_vec(),
+                }],
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let res = meta_srv
+            .range(RangeRequest {
+                key: b"key".to_vec(),
+                range_end: b"kez".to_vec(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let keys = res.kvs.into_iter().map(|kv| kv.key).collect::<Vec<_>>();
+        assert_eq!(vec![b"key1".to_vec(), b"key2".to_vec()], keys);
+
+        let res = meta_srv
+            .compare_and_put(CompareAndPutRequest {
+                key: b"key1".to_vec(),
+                expect: b"value1".to_vec(),
+                value: b"value3".to_vec(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert!(res.success);
+
+        let res = meta_srv
+            .batch_get(BatchGetRequest {
+                keys: vec![b"key1".to_vec()],
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(b"value3".to_vec(), res.kvs[0].value);
+
+        meta_srv.shutdown();
+    }
+
+    #[tokio::test]
+    async fn test_embedded_route_not_found() {
+        let meta_srv = build_embedded_meta_srv(&MetaSrvOptions::default())
+            .await
+            .unwrap();
+
+        let res = meta_srv
+            .route(RouteRequest {
+                table_names: vec![TableName {
+                    catalog_name: "greptime".to_string(),
+                    schema_name: "public".to_string(),
+                    table_name: "no_such_table".to_string(),
+                }],
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert!(res.table_routes.is_empty());
+
+        let err = meta_srv
+            .create_route(CreateRequest::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, error::Error::EmptyTableName { .. }));
+    }
+}
@@ -43,6 +43,24 @@ pub enum Error {
         backtrace: Backtrace,
     },
 
+    #[snafu(display("Failed to read Etcd TLS file: {}, source: {}", path, source))]
+    ReadEtcdTlsFile {
+        path: String,
+        source: std::io::Error,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display(
+        "Failed to authenticate to Etcd as user: {}, source: {}",
+        username,
+        source
+    ))]
+    AuthenticateEtcd {
+        username: String,
+        source: etcd_client::Error,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Failed to bind address {}, source: {}", addr, source))]
     TcpBind {
         addr: String,
@@ -50,6 +68,12 @@ pub enum Error {
         backtrace: Backtrace,
     },
 
+    #[snafu(display("Failed to join gRPC server task, source: {}", source))]
+    JoinServer {
+        source: tokio::task::JoinError,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Failed to start gRPC server, source: {}", source))]
     StartGrpc {
         source: tonic::transport::Error,
@@ -155,6 +179,9 @@ pub enum Error {
     #[snafu(display("Table {} not found", name))]
     TableNotFound { name: String, backtrace: Backtrace },
 
+    #[snafu(display("Schema not found: {}", name))]
+    SchemaNotFound { name: String, backtrace: Backtrace },
+
     #[snafu(display(
         "Failed to move the value of {} because other clients caused a race condition",
         key
@@ -213,12 +240,34 @@ pub enum Error {
     #[snafu(display("Response header not found"))]
     ResponseHeaderNotFound { backtrace: Backtrace },
 
+    #[snafu(display(
+        "The requested meta node responds error, node addr: {}, code: {}, message: {}",
+        node_addr,
+        code,
+        err_msg
+    ))]
+    ResponseHeader {
+        node_addr: String,
+        code: i32,
+        err_msg: String,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("The requested meta node is not leader, node addr: {}", node_addr))]
     IsNotLeader {
         node_addr: String,
         backtrace: Backtrace,
     },
 
+    #[snafu(display("Leader term {} is stale, a newer leader has been elected", term))]
+    StaleLeaderTerm { term: u64, backtrace: Backtrace },
+
+    #[snafu(display("Invalid leader term: {}", value))]
+    InvalidLeaderTerm { value: String, backtrace: Backtrace },
+
+    #[snafu(display("The leader lease of this meta node is about to expire"))]
+    LeaderLeaseExpiring { backtrace: Backtrace },
+
     #[snafu(display("MetaSrv has no meta peer client"))]
     NoMetaPeerClient { backtrace: Backtrace },
 
@@ -274,6 +323,68 @@ pub enum Error {
 
     #[snafu(display("Missing required parameter, param: {:?}", param))]
     MissingRequiredParameter { param: String },
+
+    #[snafu(display("Invalid datanode decommission key: {}", key))]
+    InvalidDecommissionKey { key: String, backtrace: Backtrace },
+
+    #[snafu(display("Invalid datanode instruction key: {}", key))]
+    InvalidInstructionKey { key: String, backtrace: Backtrace },
+
+    #[snafu(display(
+        "Datanode {} failed to execute instruction {}, error: {}",
+        node_id,
+        id,
+        error
+    ))]
+    ExecuteInstruction {
+        node_id: u64,
+        id: u64,
+        error: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display(
+        "Datanode {} is unavailable, instruction {} is not replied",
+        node_id,
+        id
+    ))]
+    DatanodeUnavailable {
+        node_id: u64,
+        id: u64,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to move regions off datanode {}, error: {}", node_id, error))]
+    MigrateRegion {
+        node_id: u64,
+        error: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display(
+        "Cannot decommission datanode {}: it hosts {} regions but the rest of the cluster can only absorb {}",
+        node_id,
+        regions,
+        capacity
+    ))]
+    DecommissionCapacity {
+        node_id: u64,
+        regions: usize,
+        capacity: usize,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Invalid metadata snapshot: {}", reason))]
+    InvalidSnapshot {
+        reason: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Cannot restore snapshot into a non-empty store"))]
+    RestoreTargetNotEmpty { backtrace: Backtrace },
+
+    #[snafu(display("Store contains a half-restored snapshot, restore it again with force"))]
+    IncompleteRestore { backtrace: Backtrace },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -298,6 +409,8 @@ impl ErrorExt for Error {
             Error::StreamNone { .. }
             | Error::EtcdFailed { .. }
             | Error::ConnectEtcd { .. }
+            | Error::ReadEtcdTlsFile { .. }
+            | Error::AuthenticateEtcd { .. }
             | Error::TcpBind { .. }
             | Error::SerializeToJson { .. }
             | Error::DeserializeFromJson { .. }
@@ -308,7 +421,11 @@ impl ErrorExt for Error {
             | Error::BatchGet { .. }
             | Error::Range { .. }
             | Error::ResponseHeaderNotFound { .. }
+            | Error::ResponseHeader { .. }
             | Error::IsNotLeader { .. }
+            | Error::StaleLeaderTerm { .. }
+            | Error::InvalidLeaderTerm { .. }
+            | Error::LeaderLeaseExpiring { .. }
             | Error::NoMetaPeerClient { .. }
             | Error::InvalidHttpBody { .. }
             | Error::Lock { .. }
@@ -317,12 +434,21 @@ impl ErrorExt for Error {
             | Error::LockNotConfig { .. }
             | Error::ExceededRetryLimit { .. }
             | Error::SendShutdownSignal { .. }
+            | Error::JoinServer { .. }
+            | Error::ExecuteInstruction { .. }
+            | Error::DatanodeUnavailable { .. }
+            | Error::MigrateRegion { .. }
             | Error::StartGrpc { .. } => StatusCode::Internal,
             Error::EmptyKey { .. }
             | Error::MissingRequiredParameter { .. }
             | Error::EmptyTableName { .. }
             | Error::InvalidLeaseKey { .. }
             | Error::InvalidStatKey { .. }
+            | Error::InvalidDecommissionKey { .. }
+            | Error::InvalidInstructionKey { .. }
+            | Error::DecommissionCapacity { .. }
+            | Error::InvalidSnapshot { .. }
+            | Error::RestoreTargetNotEmpty { .. }
             | Error::ParseNum { .. }
             | Error::UnsupportedSelectorType { .. }
             | Error::InvalidArguments { .. } => StatusCode::InvalidArguments,
@@ -337,8 +463,10 @@ impl ErrorExt for Error {
             | Error::InvalidKvsLength { .. }
             | Error::InvalidTxnResult { .. }
             | Error::InvalidUtf8Value { .. }
+            | Error::IncompleteRestore { .. }
             | Error::Unexpected { .. } => StatusCode::Unexpected,
             Error::TableNotFound { .. } => StatusCode::TableNotFound,
+            Error::SchemaNotFound { .. } => StatusCode::DatabaseNotFound,
             Error::InvalidCatalogValue { source, .. } => source.status_code(),
             Error::MetaInternal { source } => source.status_code(),
         }
@@ -15,16 +15,20 @@
 pub use check_leader_handler::CheckLeaderHandler;
 pub use collect_stats_handler::CollectStatsHandler;
 pub use keep_lease_handler::KeepLeaseHandler;
+pub use mailbox_handler::MailboxHandler;
 pub use on_leader_start::OnLeaderStartHandler;
+pub use persist_hot_regions_handler::PersistHotRegionsHandler;
 pub use persist_stats_handler::PersistStatsHandler;
 pub use response_header_handler::ResponseHeaderHandler;
 
 mod check_leader_handler;
 mod collect_stats_handler;
-mod instruction;
+pub mod instruction;
 mod keep_lease_handler;
+mod mailbox_handler;
 pub mod node_stat;
 mod on_leader_start;
+mod persist_hot_regions_handler;
 mod persist_stats_handler;
 mod response_header_handler;
 
@@ -36,7 +40,7 @@ use common_telemetry::info;
 use tokio::sync::mpsc::Sender;
 use tokio::sync::RwLock;
 
-use self::instruction::Instruction;
+use self::instruction::InstructionMessage;
 use self::node_stat::Stat;
 use crate::error::Result;
 use crate::metasrv::Context;
@@ -55,13 +59,17 @@ pub trait HeartbeatHandler: Send + Sync {
 pub struct HeartbeatAccumulator {
     pub header: Option<ResponseHeader>,
     pub stats: Vec<Stat>,
-    pub instructions: Vec<Instruction>,
+    pub instructions: Vec<InstructionMessage>,
 }
 
 impl HeartbeatAccumulator {
+    /// Encodes the instructions as json, one instruction per payload.
     pub fn into_payload(self) -> Vec<Vec<u8>> {
-        // TODO(jiachun): to HeartbeatResponse payload
-        vec![]
+        self.instructions
+            .iter()
+            // Safety: instructions are plain structs that can always be serialized.
+            .map(|instruction| serde_json::to_vec(instruction).unwrap())
+            .collect()
     }
 }
 
@@ -12,5 +12,47 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-#[derive(Debug)]
-pub enum Instruction {}
+use serde::{Deserialize, Serialize};
+
+/// Regions of a table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegionIdent {
+    pub catalog: String,
+    pub schema: String,
+    pub table: String,
+    pub table_id: u32,
+    pub region_numbers: Vec<u32>,
+}
+
+impl RegionIdent {
+    pub fn table_name(&self) -> String {
+        format!("{}.{}.{}", self.catalog, self.schema, self.table)
+    }
+}
+
+/// Instructions to a datanode, executed idempotently by the datanode as an instruction
+/// may be delivered more than once.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Instruction {
+    /// Opens the regions, and the table if it isn't opened on the datanode.
+    OpenRegions(RegionIdent),
+    /// Flushes and closes the regions, the table is closed with its last region.
+    CloseRegions(RegionIdent),
+    /// Drops the table with all its regions on the datanode like `DROP TABLE` does,
+    /// dropping a missing table succeeds.
+    DropTable(RegionIdent),
+}
+
+/// An instruction in the payload of a heartbeat response, the datanode acknowledges it by
+/// putting an [InstructionReply] under [crate::keys::InstructionReplyKey] with the same id.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstructionMessage {
+    pub id: u64,
+    pub instruction: Instruction,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstructionReply {
+    /// Error of executing the instruction, `None` if it succeeded.
+    pub error: Option<String>,
+}
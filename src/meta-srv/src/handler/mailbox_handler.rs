@@ -0,0 +1,107 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use api::v1::meta::HeartbeatRequest;
+
+use crate::error::Result;
+use crate::handler::{HeartbeatAccumulator, HeartbeatHandler};
+use crate::metasrv::Context;
+
+/// Sends the pending instructions of the datanode in the heartbeat response.
+#[derive(Default)]
+pub struct MailboxHandler;
+
+#[async_trait::async_trait]
+impl HeartbeatHandler for MailboxHandler {
+    async fn handle(
+        &self,
+        req: &HeartbeatRequest,
+        ctx: &mut Context,
+        acc: &mut HeartbeatAccumulator,
+    ) -> Result<()> {
+        if ctx.is_skip_all() {
+            return Ok(());
+        }
+        let (Some(header), Some(peer)) = (&req.header, &req.peer) else { return Ok(()) };
+
+        let pending = ctx.mailbox.pending(header.cluster_id, peer.id).await?;
+        acc.instructions
+            .extend(pending.into_iter().map(|(message, _)| message));
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicBool;
+    use std::sync::Arc;
+
+    use api::v1::meta::{Peer, RequestHeader};
+
+    use super::*;
+    use crate::handler::instruction::{Instruction, InstructionMessage, RegionIdent};
+    use crate::mailbox::Mailbox;
+    use crate::service::store::memory::MemStore;
+
+    #[tokio::test]
+    async fn test_handle_mailbox() {
+        let kv_store = Arc::new(MemStore::new());
+        let mut ctx = Context {
+            datanode_lease_secs: 30,
+            server_addr: "127.0.0.1:0000".to_string(),
+            in_memory: Arc::new(MemStore::new()),
+            kv_store: kv_store.clone(),
+            mailbox: Arc::new(Mailbox::new(kv_store)),
+            election: None,
+            skip_all: Arc::new(AtomicBool::new(false)),
+            catalog: None,
+            schema: None,
+            table: None,
+        };
+        let instruction = Instruction::CloseRegions(RegionIdent {
+            catalog: "greptime".to_string(),
+            schema: "public".to_string(),
+            table: "demo".to_string(),
+            table_id: 1024,
+            region_numbers: vec![1],
+        });
+        let id = ctx.mailbox.send(3, 101, instruction.clone()).await.unwrap();
+
+        let req = HeartbeatRequest {
+            header: Some(RequestHeader {
+                cluster_id: 3,
+                ..Default::default()
+            }),
+            peer: Some(Peer {
+                id: 101,
+                addr: "127.0.0.1:3001".to_string(),
+            }),
+            ..Default::default()
+        };
+        let mut acc = HeartbeatAccumulator::default();
+        MailboxHandler
+            .handle(&req, &mut ctx, &mut acc)
+            .await
+            .unwrap();
+
+        let expected = InstructionMessage { id, instruction };
+        assert_eq!(vec![expected.clone()], acc.instructions);
+        let payload = acc.into_payload();
+        assert_eq!(
+            expected,
+            serde_json::from_slice::<InstructionMessage>(&payload[0]).unwrap()
+        );
+    }
+}
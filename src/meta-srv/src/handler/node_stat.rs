@@ -18,6 +18,23 @@ use serde::{Deserialize, Serialize};
 
 use crate::keys::StatKey;
 
+/// Region id of the region stat summing up the regions of a datanode that are not reported
+/// one by one in its heartbeat, as only the hottest regions are. No table has the max id,
+/// so no region has this id.
+pub const OTHER_REGIONS_ID: u64 = u64::MAX;
+
+/// Converts the rows written to a region per second to the write capacity units in its
+/// stat, a unit is a row written per second.
+pub fn rows_per_sec_to_wcus(rows_per_sec: f64) -> i64 {
+    rows_per_sec as i64
+}
+
+/// Converts the write capacity units in a region stat to rows written per second, the
+/// inverse of [rows_per_sec_to_wcus].
+pub fn wcus_to_rows_per_sec(wcus: i64) -> u64 {
+    wcus.max(0) as u64
+}
+
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Stat {
     pub timestamp_millis: i64,
@@ -40,7 +57,8 @@ pub struct Stat {
     pub read_io_rate: f64,
     /// Write disk IO on this node
     pub write_io_rate: f64,
-    /// Region stats on this node
+    /// Region stats on this node, the hottest ones and one with [OTHER_REGIONS_ID] for
+    /// the others
     pub region_stats: Vec<RegionStat>,
 }
 
@@ -52,7 +70,7 @@ pub struct RegionStat {
     pub table: String,
     /// The read capacity units during this period
     pub rcus: i64,
-    /// The write capacity units during this period
+    /// The write capacity units during this period, see [rows_per_sec_to_wcus]
     pub wcus: i64,
     /// Approximate bytes of this region
     pub approximate_bytes: i64,
@@ -13,7 +13,9 @@
 // limitations under the License.
 
 use api::v1::meta::HeartbeatRequest;
+use common_telemetry::error;
 
+use crate::decommission;
 use crate::error::Result;
 use crate::handler::{HeartbeatAccumulator, HeartbeatHandler};
 use crate::metasrv::Context;
@@ -32,6 +34,13 @@ impl HeartbeatHandler for OnLeaderStartHandler {
         if let Some(election) = &ctx.election {
             if election.in_infancy() {
                 ctx.reset_in_memory();
+
+                let ctx = ctx.clone();
+                common_runtime::spawn_bg(async move {
+                    if let Err(e) = decommission::resume_all(ctx).await {
+                        error!(e; "Failed to resume datanode decommissions");
+                    }
+                });
             }
         }
         Ok(())
@@ -0,0 +1,159 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use api::v1::meta::{HeartbeatRequest, PutRequest};
+use common_time::util as time_util;
+
+use crate::error::Result;
+use crate::handler::node_stat::{wcus_to_rows_per_sec, OTHER_REGIONS_ID};
+use crate::handler::{HeartbeatAccumulator, HeartbeatHandler};
+use crate::keys::{HotRegion, HotRegionKey, HotRegionValue};
+use crate::metasrv::Context;
+
+/// Persists the hottest regions reported in the latest heartbeat of a datanode,
+/// replacing the ones of its previous heartbeat.
+#[derive(Default)]
+pub struct PersistHotRegionsHandler;
+
+#[async_trait::async_trait]
+impl HeartbeatHandler for PersistHotRegionsHandler {
+    async fn handle(
+        &self,
+        req: &HeartbeatRequest,
+        ctx: &mut Context,
+        _acc: &mut HeartbeatAccumulator,
+    ) -> Result<()> {
+        if ctx.is_skip_all() || req.node_stat.is_none() {
+            return Ok(());
+        }
+        let (Some(header), Some(peer)) = (&req.header, &req.peer) else {
+            return Ok(());
+        };
+
+        let regions = req
+            .region_stats
+            .iter()
+            .filter(|stat| stat.region_id != OTHER_REGIONS_ID)
+            .map(|stat| HotRegion {
+                node_id: peer.id,
+                region_id: stat.region_id,
+                table_id: (stat.region_id >> 32) as u32,
+                rows_per_sec: wcus_to_rows_per_sec(stat.wcus),
+                approximate_bytes: stat.approximate_bytes.max(0) as u64,
+            })
+            .collect();
+        let key = HotRegionKey {
+            cluster_id: header.cluster_id,
+            node_id: peer.id,
+        };
+        let value = HotRegionValue {
+            timestamp_millis: time_util::current_time_millis(),
+            regions,
+        };
+
+        let put = PutRequest {
+            key: key.into(),
+            value: value.try_into()?,
+            ..Default::default()
+        };
+        ctx.in_memory.put(put).await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicBool;
+    use std::sync::Arc;
+
+    use api::v1::meta::{NodeStat, Peer, RangeRequest, RegionStat, RequestHeader};
+
+    use super::*;
+    use crate::mailbox::Mailbox;
+    use crate::service::store::memory::MemStore;
+
+    #[tokio::test]
+    async fn test_handle_hot_regions() {
+        let in_memory = Arc::new(MemStore::new());
+        let kv_store = Arc::new(MemStore::new());
+        let mailbox = Arc::new(Mailbox::new(kv_store.clone()));
+        let mut ctx = Context {
+            datanode_lease_secs: 30,
+            server_addr: "127.0.0.1:0000".to_string(),
+            in_memory,
+            kv_store,
+            mailbox,
+            election: None,
+            skip_all: Arc::new(AtomicBool::new(false)),
+            catalog: None,
+            schema: None,
+            table: None,
+        };
+
+        let req = HeartbeatRequest {
+            header: Some(RequestHeader {
+                cluster_id: 3,
+                ..Default::default()
+            }),
+            peer: Some(Peer {
+                id: 101,
+                addr: "127.0.0.1:3001".to_string(),
+            }),
+            node_stat: Some(NodeStat::default()),
+            region_stats: vec![
+                RegionStat {
+                    region_id: (1024 << 32) | 1,
+                    wcus: 100,
+                    approximate_bytes: 4096,
+                    ..Default::default()
+                },
+                // The other regions are not hot regions.
+                RegionStat {
+                    region_id: OTHER_REGIONS_ID,
+                    approximate_bytes: 1 << 20,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        PersistHotRegionsHandler
+            .handle(&req, &mut ctx, &mut HeartbeatAccumulator::default())
+            .await
+            .unwrap();
+
+        let key = HotRegionKey {
+            cluster_id: 3,
+            node_id: 101,
+        };
+        let req = RangeRequest {
+            key: key.into(),
+            ..Default::default()
+        };
+        let res = ctx.in_memory.range(req).await.unwrap();
+        assert_eq!(1, res.kvs.len());
+
+        let value: HotRegionValue = res.kvs[0].value.clone().try_into().unwrap();
+        assert_eq!(
+            vec![HotRegion {
+                node_id: 101,
+                region_id: (1024 << 32) | 1,
+                table_id: 1024,
+                rows_per_sec: 100,
+                approximate_bytes: 4096,
+            }],
+            value.regions
+        );
+    }
+}
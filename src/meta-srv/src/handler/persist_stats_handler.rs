@@ -67,17 +67,20 @@ mod tests {
     use super::*;
     use crate::handler::node_stat::Stat;
     use crate::keys::StatKey;
+    use crate::mailbox::Mailbox;
     use crate::service::store::memory::MemStore;
 
     #[tokio::test]
     async fn test_handle_datanode_stats() {
         let in_memory = Arc::new(MemStore::new());
         let kv_store = Arc::new(MemStore::new());
+        let mailbox = Arc::new(Mailbox::new(kv_store.clone()));
         let mut ctx = Context {
             datanode_lease_secs: 30,
             server_addr: "127.0.0.1:0000".to_string(),
             in_memory,
             kv_store,
+            mailbox,
             election: None,
             skip_all: Arc::new(AtomicBool::new(false)),
             catalog: None,
@@ -23,20 +23,32 @@ use snafu::{ensure, OptionExt, ResultExt};
 
 use crate::error;
 use crate::error::Result;
+use crate::handler::instruction::{Instruction, InstructionReply, RegionIdent};
 use crate::handler::node_stat::Stat;
 
 pub(crate) const REMOVED_PREFIX: &str = "__removed";
 pub(crate) const DN_LEASE_PREFIX: &str = "__meta_dnlease";
+pub(crate) const DN_DECOMMISSION_PREFIX: &str = "__meta_dndecommission";
+pub(crate) const DN_INSTRUCTION_PREFIX: &str = "__meta_dninstruction";
 pub(crate) const SEQ_PREFIX: &str = "__meta_seq";
 pub(crate) const TABLE_ROUTE_PREFIX: &str = "__meta_table_route";
 
 pub const DN_STAT_PREFIX: &str = "__meta_dnstat";
+pub const DN_HOT_REGION_PREFIX: &str = "__meta_dnhotregion";
+pub const DN_FAILED_REGION_PREFIX: &str = "__meta_dnfailedregion";
+pub const DN_INSTRUCTION_REPLY_PREFIX: &str = "__meta_dnreply";
 
 lazy_static! {
     static ref DATANODE_LEASE_KEY_PATTERN: Regex =
         Regex::new(&format!("^{DN_LEASE_PREFIX}-([0-9]+)-([0-9]+)$")).unwrap();
     static ref DATANODE_STAT_KEY_PATTERN: Regex =
         Regex::new(&format!("^{DN_STAT_PREFIX}-([0-9]+)-([0-9]+)$")).unwrap();
+    static ref DATANODE_DECOMMISSION_KEY_PATTERN: Regex =
+        Regex::new(&format!("^{DN_DECOMMISSION_PREFIX}-([0-9]+)-([0-9]+)$")).unwrap();
+    static ref DATANODE_INSTRUCTION_KEY_PATTERN: Regex = Regex::new(&format!(
+        "^(?:{DN_INSTRUCTION_PREFIX}|{DN_INSTRUCTION_REPLY_PREFIX})-([0-9]+)-([0-9]+)-([0-9]+)$"
+    ))
+    .unwrap();
 }
 #[derive(Debug, Clone, Eq, Hash, PartialEq)]
 pub struct LeaseKey {
@@ -172,6 +184,16 @@ impl<'a> TableRouteKey<'a> {
     pub fn removed_key(&self) -> String {
         to_removed_key(&self.key())
     }
+
+    /// Prefix of the route keys of tables in `catalog` and `schema`, all tables if
+    /// `catalog` is absent. `schema` is ignored without `catalog`.
+    pub fn list_prefix(catalog: Option<&str>, schema: Option<&str>) -> String {
+        match (catalog, schema) {
+            (Some(catalog), Some(schema)) => format!("{TABLE_ROUTE_PREFIX}-{catalog}-{schema}-"),
+            (Some(catalog), None) => format!("{TABLE_ROUTE_PREFIX}-{catalog}-"),
+            (None, _) => format!("{TABLE_ROUTE_PREFIX}-"),
+        }
+    }
 }
 
 pub(crate) fn to_removed_key(key: &str) -> String {
@@ -233,6 +255,11 @@ pub struct StatValue {
 }
 
 impl StatValue {
+    /// Get the timestamp of the latest stat.
+    pub fn latest_timestamp_millis(&self) -> Option<i64> {
+        self.stats.iter().map(|stat| stat.timestamp_millis).max()
+    }
+
     /// Get the region number from stat value.
     pub fn region_num(&self) -> Option<u64> {
         for stat in self.stats.iter() {
@@ -275,10 +302,440 @@ impl TryFrom<Vec<u8>> for StatValue {
     }
 }
 
+#[derive(Eq, PartialEq, Debug, Clone, Hash)]
+pub struct HotRegionKey {
+    pub cluster_id: u64,
+    pub node_id: u64,
+}
+
+impl From<HotRegionKey> for Vec<u8> {
+    fn from(value: HotRegionKey) -> Self {
+        format!(
+            "{}-{}-{}",
+            DN_HOT_REGION_PREFIX, value.cluster_id, value.node_id
+        )
+        .into_bytes()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HotRegion {
+    pub node_id: u64,
+    pub region_id: u64,
+    pub table_id: u32,
+    /// Rows written per second since the previous heartbeat.
+    pub rows_per_sec: u64,
+    /// Approximate bytes of this region on disk.
+    pub approximate_bytes: u64,
+}
+
+/// The hottest regions of a datanode reported in its latest heartbeat.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HotRegionValue {
+    pub timestamp_millis: i64,
+    pub regions: Vec<HotRegion>,
+}
+
+impl FromStr for HotRegionValue {
+    type Err = error::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        serde_json::from_str(value).context(error::DeserializeFromJsonSnafu { input: value })
+    }
+}
+
+impl TryFrom<Vec<u8>> for HotRegionValue {
+    type Error = error::Error;
+
+    fn try_from(bytes: Vec<u8>) -> Result<Self> {
+        String::from_utf8(bytes)
+            .context(error::InvalidUtf8ValueSnafu)
+            .map(|x| x.parse())?
+    }
+}
+
+impl TryFrom<HotRegionValue> for Vec<u8> {
+    type Error = error::Error;
+
+    fn try_from(value: HotRegionValue) -> Result<Self> {
+        Ok(serde_json::to_string(&value)
+            .context(error::SerializeToJsonSnafu {
+                input: format!("{value:?}"),
+            })?
+            .into_bytes())
+    }
+}
+
+/// The regions a datanode failed to open, put by the datanode whenever they change.
+#[derive(Eq, PartialEq, Debug, Clone, Hash)]
+pub struct FailedRegionKey {
+    pub cluster_id: u64,
+    pub node_id: u64,
+}
+
+impl From<FailedRegionKey> for Vec<u8> {
+    fn from(value: FailedRegionKey) -> Self {
+        format!(
+            "{}-{}-{}",
+            DN_FAILED_REGION_PREFIX, value.cluster_id, value.node_id
+        )
+        .into_bytes()
+    }
+}
+
+/// A region failed to open, the datanode keeps retrying it in background.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FailedRegion {
+    /// Full name of the table the region belongs to.
+    pub table_name: String,
+    pub region_number: u32,
+    /// Error of the last attempt to open the region.
+    pub error: String,
+    /// Number of retries since the region failed to open.
+    pub retries: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FailedRegionValue {
+    pub timestamp_millis: i64,
+    pub regions: Vec<FailedRegion>,
+}
+
+impl FromStr for FailedRegionValue {
+    type Err = error::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        serde_json::from_str(value).context(error::DeserializeFromJsonSnafu { input: value })
+    }
+}
+
+impl TryFrom<Vec<u8>> for FailedRegionValue {
+    type Error = error::Error;
+
+    fn try_from(bytes: Vec<u8>) -> Result<Self> {
+        String::from_utf8(bytes)
+            .context(error::InvalidUtf8ValueSnafu)
+            .map(|x| x.parse())?
+    }
+}
+
+impl TryFrom<FailedRegionValue> for Vec<u8> {
+    type Error = error::Error;
+
+    fn try_from(value: FailedRegionValue) -> Result<Self> {
+        Ok(serde_json::to_string(&value)
+            .context(error::SerializeToJsonSnafu {
+                input: format!("{value:?}"),
+            })?
+            .into_bytes())
+    }
+}
+
+#[derive(Eq, PartialEq, Debug, Clone, Hash)]
+pub struct DecommissionKey {
+    pub cluster_id: u64,
+    pub node_id: u64,
+}
+
+impl From<DecommissionKey> for Vec<u8> {
+    fn from(value: DecommissionKey) -> Self {
+        format!(
+            "{}-{}-{}",
+            DN_DECOMMISSION_PREFIX, value.cluster_id, value.node_id
+        )
+        .into_bytes()
+    }
+}
+
+impl FromStr for DecommissionKey {
+    type Err = error::Error;
+
+    fn from_str(key: &str) -> Result<Self> {
+        let caps = DATANODE_DECOMMISSION_KEY_PATTERN
+            .captures(key)
+            .context(error::InvalidDecommissionKeySnafu { key })?;
+
+        ensure!(caps.len() == 3, error::InvalidDecommissionKeySnafu { key });
+
+        let cluster_id = caps[1].to_string();
+        let node_id = caps[2].to_string();
+        let cluster_id: u64 = cluster_id.parse().context(error::ParseNumSnafu {
+            err_msg: format!("invalid cluster_id: {cluster_id}"),
+        })?;
+        let node_id: u64 = node_id.parse().context(error::ParseNumSnafu {
+            err_msg: format!("invalid node_id: {node_id}"),
+        })?;
+
+        Ok(Self {
+            cluster_id,
+            node_id,
+        })
+    }
+}
+
+impl TryFrom<Vec<u8>> for DecommissionKey {
+    type Error = error::Error;
+
+    fn try_from(bytes: Vec<u8>) -> Result<Self> {
+        String::from_utf8(bytes)
+            .context(error::InvalidUtf8ValueSnafu)
+            .map(|x| x.parse())?
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub enum DecommissionState {
+    /// The datanode no longer receives new regions.
+    Cordoned,
+    /// Regions are being moved off the datanode.
+    Migrating,
+    /// The datanode is region-free, its stat and lease keys are being removed.
+    Cleaning,
+    Done,
+    /// A region couldn't be moved off the datanode and was reopened on it, the datanode
+    /// stays cordoned. Starting the decommission again retries it.
+    Failed,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub enum MigrationStep {
+    /// The region is being flushed and closed on the decommissioned datanode.
+    CloseSource,
+    /// The region is being opened on the target datanode.
+    OpenTarget,
+    /// The region is served by the target datanode, its route is being updated.
+    UpdateRoute,
+    /// The region failed to be closed or opened, it is being reopened on the
+    /// decommissioned datanode.
+    Rollback,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct RegionMigration {
+    pub region: RegionIdent,
+    pub to_node_id: u64,
+    pub to_node_addr: String,
+    pub step: MigrationStep,
+    /// The instruction of the current step, waited for by a new leader.
+    pub instruction_id: Option<u64>,
+    /// Why the region is rolled back.
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct DecommissionValue {
+    pub state: DecommissionState,
+    pub started_at_millis: i64,
+    pub regions_remaining: usize,
+    pub current_migration: Option<RegionMigration>,
+    /// Why the decommission failed.
+    pub error: Option<String>,
+}
+
+impl FromStr for DecommissionValue {
+    type Err = error::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        serde_json::from_str(value).context(error::DeserializeFromJsonSnafu { input: value })
+    }
+}
+
+impl TryFrom<Vec<u8>> for DecommissionValue {
+    type Error = error::Error;
+
+    fn try_from(bytes: Vec<u8>) -> Result<Self> {
+        String::from_utf8(bytes)
+            .context(error::InvalidUtf8ValueSnafu)
+            .map(|x| x.parse())?
+    }
+}
+
+impl TryFrom<DecommissionValue> for Vec<u8> {
+    type Error = error::Error;
+
+    fn try_from(value: DecommissionValue) -> Result<Self> {
+        Ok(serde_json::to_string(&value)
+            .context(error::SerializeToJsonSnafu {
+                input: format!("{value:?}"),
+            })?
+            .into_bytes())
+    }
+}
+
+/// An instruction to a datanode, see [crate::mailbox::Mailbox].
+#[derive(Eq, PartialEq, Debug, Clone, Hash)]
+pub struct InstructionKey {
+    pub cluster_id: u64,
+    pub node_id: u64,
+    pub id: u64,
+}
+
+impl From<InstructionKey> for Vec<u8> {
+    fn from(value: InstructionKey) -> Self {
+        format!(
+            "{}-{}-{}-{}",
+            DN_INSTRUCTION_PREFIX, value.cluster_id, value.node_id, value.id
+        )
+        .into_bytes()
+    }
+}
+
+impl TryFrom<Vec<u8>> for InstructionKey {
+    type Error = error::Error;
+
+    fn try_from(bytes: Vec<u8>) -> Result<Self> {
+        let key = String::from_utf8(bytes).context(error::InvalidUtf8ValueSnafu)?;
+        let (cluster_id, node_id, id) = parse_instruction_key(&key)?;
+        Ok(Self {
+            cluster_id,
+            node_id,
+            id,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstructionValue {
+    pub created_at_millis: i64,
+    pub instruction: Instruction,
+}
+
+impl FromStr for InstructionValue {
+    type Err = error::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        serde_json::from_str(value).context(error::DeserializeFromJsonSnafu { input: value })
+    }
+}
+
+impl TryFrom<Vec<u8>> for InstructionValue {
+    type Error = error::Error;
+
+    fn try_from(bytes: Vec<u8>) -> Result<Self> {
+        String::from_utf8(bytes)
+            .context(error::InvalidUtf8ValueSnafu)
+            .map(|x| x.parse())?
+    }
+}
+
+impl TryFrom<InstructionValue> for Vec<u8> {
+    type Error = error::Error;
+
+    fn try_from(value: InstructionValue) -> Result<Self> {
+        Ok(serde_json::to_string(&value)
+            .context(error::SerializeToJsonSnafu {
+                input: format!("{value:?}"),
+            })?
+            .into_bytes())
+    }
+}
+
+/// The reply of a datanode to an [InstructionKey] with the same id, put by the datanode.
+#[derive(Eq, PartialEq, Debug, Clone, Hash)]
+pub struct InstructionReplyKey {
+    pub cluster_id: u64,
+    pub node_id: u64,
+    pub id: u64,
+}
+
+impl From<InstructionReplyKey> for Vec<u8> {
+    fn from(value: InstructionReplyKey) -> Self {
+        format!(
+            "{}-{}-{}-{}",
+            DN_INSTRUCTION_REPLY_PREFIX, value.cluster_id, value.node_id, value.id
+        )
+        .into_bytes()
+    }
+}
+
+impl TryFrom<Vec<u8>> for InstructionReplyKey {
+    type Error = error::Error;
+
+    fn try_from(bytes: Vec<u8>) -> Result<Self> {
+        let key = String::from_utf8(bytes).context(error::InvalidUtf8ValueSnafu)?;
+        let (cluster_id, node_id, id) = parse_instruction_key(&key)?;
+        Ok(Self {
+            cluster_id,
+            node_id,
+            id,
+        })
+    }
+}
+
+fn parse_instruction_key(key: &str) -> Result<(u64, u64, u64)> {
+    let caps = DATANODE_INSTRUCTION_KEY_PATTERN
+        .captures(key)
+        .context(error::InvalidInstructionKeySnafu { key })?;
+
+    ensure!(caps.len() == 4, error::InvalidInstructionKeySnafu { key });
+
+    let mut ids = [0; 3];
+    for (i, id) in ids.iter_mut().enumerate() {
+        let s = &caps[i + 1];
+        *id = s.parse().context(error::ParseNumSnafu {
+            err_msg: format!("invalid number in instruction key: {s}"),
+        })?;
+    }
+    Ok((ids[0], ids[1], ids[2]))
+}
+
+impl FromStr for InstructionReply {
+    type Err = error::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        serde_json::from_str(value).context(error::DeserializeFromJsonSnafu { input: value })
+    }
+}
+
+impl TryFrom<Vec<u8>> for InstructionReply {
+    type Error = error::Error;
+
+    fn try_from(bytes: Vec<u8>) -> Result<Self> {
+        String::from_utf8(bytes)
+            .context(error::InvalidUtf8ValueSnafu)
+            .map(|x| x.parse())?
+    }
+}
+
+impl TryFrom<InstructionReply> for Vec<u8> {
+    type Error = error::Error;
+
+    fn try_from(value: InstructionReply) -> Result<Self> {
+        Ok(serde_json::to_string(&value)
+            .context(error::SerializeToJsonSnafu {
+                input: format!("{value:?}"),
+            })?
+            .into_bytes())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_instruction_key_round_trip() {
+        let key = InstructionKey {
+            cluster_id: 1,
+            node_id: 2,
+            id: 30,
+        };
+        let bytes: Vec<u8> = key.clone().into();
+        assert_eq!(b"__meta_dninstruction-1-2-30".to_vec(), bytes);
+        assert_eq!(key, bytes.try_into().unwrap());
+
+        let key = InstructionReplyKey {
+            cluster_id: 1,
+            node_id: 2,
+            id: 30,
+        };
+        let bytes: Vec<u8> = key.clone().into();
+        assert_eq!(b"__meta_dnreply-1-2-30".to_vec(), bytes);
+        assert_eq!(key, bytes.try_into().unwrap());
+
+        assert!(InstructionKey::try_from(b"__meta_dninstruction-1-2".to_vec()).is_err());
+    }
+
     #[test]
     fn test_stat_key_round_trip() {
         let key = StatKey {
@@ -318,6 +775,43 @@ mod tests {
         assert_eq!(Some(100), stat.region_num);
     }
 
+    #[test]
+    fn test_hot_region_value_round_trip() {
+        let value = HotRegionValue {
+            timestamp_millis: 111,
+            regions: vec![HotRegion {
+                node_id: 1,
+                region_id: (1024 << 32) | 2,
+                table_id: 1024,
+                rows_per_sec: 500,
+                approximate_bytes: 4096,
+            }],
+        };
+
+        let value_bytes: Vec<u8> = value.clone().try_into().unwrap();
+        let new_value: HotRegionValue = value_bytes.try_into().unwrap();
+
+        assert_eq!(new_value, value);
+    }
+
+    #[test]
+    fn test_failed_region_value_round_trip() {
+        let value = FailedRegionValue {
+            timestamp_millis: 111,
+            regions: vec![FailedRegion {
+                table_name: "greptime.public.demo".to_string(),
+                region_number: 2,
+                error: "corrupted manifest".to_string(),
+                retries: 3,
+            }],
+        };
+
+        let value_bytes: Vec<u8> = value.clone().try_into().unwrap();
+        let new_value: FailedRegionValue = value_bytes.try_into().unwrap();
+
+        assert_eq!(new_value, value);
+    }
+
     #[test]
     fn test_lease_key_round_trip() {
         let key = LeaseKey {
@@ -344,6 +838,48 @@ mod tests {
         assert_eq!(new_value, value);
     }
 
+    #[test]
+    fn test_decommission_key_round_trip() {
+        let key = DecommissionKey {
+            cluster_id: 0,
+            node_id: 1,
+        };
+
+        let key_bytes: Vec<u8> = key.clone().into();
+        let new_key: DecommissionKey = key_bytes.try_into().unwrap();
+
+        assert_eq!(new_key, key);
+    }
+
+    #[test]
+    fn test_decommission_value_round_trip() {
+        let value = DecommissionValue {
+            state: DecommissionState::Migrating,
+            started_at_millis: 111,
+            regions_remaining: 2,
+            current_migration: Some(RegionMigration {
+                region: RegionIdent {
+                    catalog: "greptime".to_string(),
+                    schema: "public".to_string(),
+                    table: "demo".to_string(),
+                    table_id: 1024,
+                    region_numbers: vec![1],
+                },
+                to_node_id: 2,
+                to_node_addr: "127.0.0.1:3002".to_string(),
+                step: MigrationStep::OpenTarget,
+                instruction_id: Some(10),
+                error: None,
+            }),
+            error: None,
+        };
+
+        let value_bytes: Vec<u8> = value.clone().try_into().unwrap();
+        let new_value: DecommissionValue = value_bytes.try_into().unwrap();
+
+        assert_eq!(new_value, value);
+    }
+
     #[test]
     fn test_get_region_num_from_stat_val() {
         let empty = StatValue { stats: vec![] };
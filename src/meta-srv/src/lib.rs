@@ -15,7 +15,9 @@
 #![feature(btree_drain_filter)]
 pub mod bootstrap;
 pub mod cluster;
+pub mod decommission;
 pub mod election;
+pub mod embedded;
 pub mod error;
 // TODO(LFC): TBC
 #[allow(dead_code)]
@@ -24,12 +26,15 @@ pub mod handler;
 pub mod keys;
 pub mod lease;
 pub mod lock;
+pub mod mailbox;
 pub mod metasrv;
+pub mod metric;
 #[cfg(feature = "mock")]
 pub mod mocks;
 pub mod selector;
 mod sequence;
 pub mod service;
+pub mod snapshot;
 pub mod util;
 
 pub use crate::error::Result;
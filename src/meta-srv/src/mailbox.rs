@@ -0,0 +1,406 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use api::v1::meta::{DeleteRangeRequest, PutRequest, RangeRequest};
+use common_time::util as time_util;
+use metrics::gauge;
+use tokio::sync::Mutex as AsyncMutex;
+
+use crate::error::Result;
+use crate::handler::instruction::{Instruction, InstructionMessage, InstructionReply};
+use crate::keys::{
+    InstructionKey, InstructionReplyKey, InstructionValue, LeaseKey, LeaseValue,
+    DN_INSTRUCTION_PREFIX, DN_INSTRUCTION_REPLY_PREFIX,
+};
+use crate::metric::{LABEL_NODE_ID, METRIC_META_REGION_OPEN_PENDING};
+use crate::sequence::Sequence;
+use crate::service::store::ext::KvStoreExt;
+use crate::service::store::kv::KvStoreRef;
+use crate::{lease, util};
+
+pub const INSTRUCTION_ID_SEQ: &str = "instruction_id";
+const OPEN_WINDOW_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+pub type MailboxRef = Arc<Mailbox>;
+
+/// Delivers instructions to datanodes in the responses of their heartbeats.
+///
+/// Instructions and their replies are kept in the kv store: a pending instruction is sent
+/// in every heartbeat response to the datanode until the datanode replies, so it survives
+/// restarts of the datanode and changes of the leader.
+///
+/// Region opens are paced by a sliding window per datanode: an open is only sent to a
+/// datanode if fewer than `max_pending_opens` opens are pending on it, counted from the
+/// instructions in the kv store, so the window holds for every sender and across leaders.
+pub struct Mailbox {
+    kv_store: KvStoreRef,
+    sequence: Sequence,
+    /// Max region opens pending on a datanode, 0 means unlimited.
+    max_pending_opens: usize,
+    datanode_lease_secs: i64,
+    poll_interval: Duration,
+    /// Opens sent to a datanode are serialized, so concurrent senders can't exceed the
+    /// window together.
+    open_locks: Mutex<HashMap<(u64, u64), Arc<AsyncMutex<()>>>>,
+}
+
+impl Mailbox {
+    pub fn new(kv_store: KvStoreRef) -> Self {
+        let sequence = Sequence::new(INSTRUCTION_ID_SEQ, 1, 100, kv_store.clone());
+        Self {
+            kv_store,
+            sequence,
+            max_pending_opens: 0,
+            datanode_lease_secs: 0,
+            poll_interval: OPEN_WINDOW_POLL_INTERVAL,
+            open_locks: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Limits the region opens pending on a datanode to `max_pending_opens`, 0 means
+    /// unlimited. Opens to a datanode without a lease in `datanode_lease_secs` are not
+    /// limited, they would never be replied.
+    pub fn with_open_limit(mut self, max_pending_opens: usize, datanode_lease_secs: i64) -> Self {
+        self.max_pending_opens = max_pending_opens;
+        self.datanode_lease_secs = datanode_lease_secs;
+        self
+    }
+
+    /// Sends the instruction to the datanode, returns the id of the instruction.
+    ///
+    /// A region open waits until the datanode has room in its window of pending opens.
+    pub async fn send(
+        &self,
+        cluster_id: u64,
+        node_id: u64,
+        instruction: Instruction,
+    ) -> Result<u64> {
+        let _open_guard = match instruction {
+            Instruction::OpenRegions(_) if self.max_pending_opens > 0 => {
+                let lock = self.open_lock(cluster_id, node_id);
+                let guard = lock.lock_owned().await;
+                self.wait_open_window(cluster_id, node_id).await?;
+                Some(guard)
+            }
+            _ => None,
+        };
+
+        let id = self.sequence.next().await?;
+        let key = InstructionKey {
+            cluster_id,
+            node_id,
+            id,
+        };
+        let value = InstructionValue {
+            created_at_millis: time_util::current_time_millis(),
+            instruction,
+        };
+        let req = PutRequest {
+            key: key.into(),
+            value: value.try_into()?,
+            ..Default::default()
+        };
+        self.kv_store.put(req).await?;
+
+        Ok(id)
+    }
+
+    /// Instructions sent to the datanode but not replied yet, ordered by their ids.
+    ///
+    /// Also reports the number of pending region opens of the datanode, which is read on
+    /// every heartbeat of the datanode.
+    pub async fn pending(
+        &self,
+        cluster_id: u64,
+        node_id: u64,
+    ) -> Result<Vec<(InstructionMessage, InstructionValue)>> {
+        let prefix = format!("{DN_INSTRUCTION_REPLY_PREFIX}-{cluster_id}-{node_id}-");
+        let replied = self
+            .range(prefix, true)
+            .await?
+            .into_iter()
+            .map(|(key, _)| InstructionReplyKey::try_from(key).map(|k| k.id))
+            .collect::<Result<HashSet<_>>>()?;
+
+        let prefix = format!("{DN_INSTRUCTION_PREFIX}-{cluster_id}-{node_id}-");
+        let mut pending = Vec::new();
+        for (key, value) in self.range(prefix, false).await? {
+            let key = InstructionKey::try_from(key)?;
+            if replied.contains(&key.id) {
+                continue;
+            }
+            let value = InstructionValue::try_from(value)?;
+            let message = InstructionMessage {
+                id: key.id,
+                instruction: value.instruction.clone(),
+            };
+            pending.push((message, value));
+        }
+        pending.sort_unstable_by_key(|(message, _)| message.id);
+
+        let opens = pending
+            .iter()
+            .filter(|(message, _)| matches!(message.instruction, Instruction::OpenRegions(_)))
+            .count();
+        gauge!(
+            METRIC_META_REGION_OPEN_PENDING,
+            opens as f64,
+            LABEL_NODE_ID => node_id.to_string()
+        );
+
+        Ok(pending)
+    }
+
+    /// Returns the reply of the instruction, or `None` if the datanode hasn't replied yet.
+    pub async fn reply(
+        &self,
+        cluster_id: u64,
+        node_id: u64,
+        id: u64,
+    ) -> Result<Option<InstructionReply>> {
+        let key = InstructionReplyKey {
+            cluster_id,
+            node_id,
+            id,
+        };
+        self.kv_store
+            .get(key.into())
+            .await?
+            .map(|kv| kv.value.try_into())
+            .transpose()
+    }
+
+    /// Returns the instruction, or `None` if it's removed.
+    pub async fn instruction(
+        &self,
+        cluster_id: u64,
+        node_id: u64,
+        id: u64,
+    ) -> Result<Option<InstructionValue>> {
+        let key = InstructionKey {
+            cluster_id,
+            node_id,
+            id,
+        };
+        self.kv_store
+            .get(key.into())
+            .await?
+            .map(|kv| kv.value.try_into())
+            .transpose()
+    }
+
+    /// Removes the instruction and its reply.
+    pub async fn remove(&self, cluster_id: u64, node_id: u64, id: u64) -> Result<()> {
+        let key = InstructionKey {
+            cluster_id,
+            node_id,
+            id,
+        };
+        let req = DeleteRangeRequest {
+            key: key.into(),
+            ..Default::default()
+        };
+        self.kv_store.delete_range(req).await?;
+
+        let key = InstructionReplyKey {
+            cluster_id,
+            node_id,
+            id,
+        };
+        let req = DeleteRangeRequest {
+            key: key.into(),
+            ..Default::default()
+        };
+        self.kv_store.delete_range(req).await?;
+
+        Ok(())
+    }
+
+    fn open_lock(&self, cluster_id: u64, node_id: u64) -> Arc<AsyncMutex<()>> {
+        self.open_locks
+            .lock()
+            .unwrap()
+            .entry((cluster_id, node_id))
+            .or_default()
+            .clone()
+    }
+
+    /// Waits until fewer than `max_pending_opens` region opens are pending on the datanode,
+    /// or the datanode is dead.
+    async fn wait_open_window(&self, cluster_id: u64, node_id: u64) -> Result<()> {
+        loop {
+            let pending = self
+                .pending(cluster_id, node_id)
+                .await?
+                .into_iter()
+                .filter(|(message, _)| matches!(message.instruction, Instruction::OpenRegions(_)))
+                .count();
+            if pending < self.max_pending_opens || !self.is_alive(cluster_id, node_id).await? {
+                return Ok(());
+            }
+            tokio::time::sleep(self.poll_interval).await;
+        }
+    }
+
+    async fn is_alive(&self, cluster_id: u64, node_id: u64) -> Result<bool> {
+        let lease_secs = self.datanode_lease_secs;
+        let lease_filter = |k: &LeaseKey, v: &LeaseValue| {
+            k.node_id == node_id
+                && time_util::current_time_millis() - v.timestamp_millis < lease_secs * 1000
+        };
+        let lease_kvs = lease::alive_datanodes(cluster_id, &self.kv_store, lease_filter).await?;
+
+        Ok(!lease_kvs.is_empty())
+    }
+
+    async fn range(&self, prefix: String, keys_only: bool) -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
+        let key = prefix.into_bytes();
+        let range_end = util::get_prefix_end_key(&key);
+        let req = RangeRequest {
+            key,
+            range_end,
+            keys_only,
+            ..Default::default()
+        };
+        let kvs = self.kv_store.range(req).await?.kvs;
+
+        Ok(kvs.into_iter().map(|kv| (kv.key, kv.value)).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handler::instruction::RegionIdent;
+    use crate::service::store::memory::MemStore;
+
+    fn open_regions(table: &str) -> Instruction {
+        Instruction::OpenRegions(RegionIdent {
+            catalog: "greptime".to_string(),
+            schema: "public".to_string(),
+            table: table.to_string(),
+            table_id: 1024,
+            region_numbers: vec![0],
+        })
+    }
+
+    async fn put_reply(kv_store: &KvStoreRef, node_id: u64, id: u64, error: Option<String>) {
+        let key = InstructionReplyKey {
+            cluster_id: 0,
+            node_id,
+            id,
+        };
+        let req = PutRequest {
+            key: key.into(),
+            value: InstructionReply { error }.try_into().unwrap(),
+            ..Default::default()
+        };
+        kv_store.put(req).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_mailbox() {
+        let kv_store: KvStoreRef = Arc::new(MemStore::new());
+        let mailbox = Mailbox::new(kv_store.clone());
+
+        let id1 = mailbox.send(0, 1, open_regions("t1")).await.unwrap();
+        let id2 = mailbox.send(0, 1, open_regions("t2")).await.unwrap();
+        let id3 = mailbox.send(0, 10, open_regions("t3")).await.unwrap();
+        assert!(id1 < id2);
+
+        let pending = mailbox.pending(0, 1).await.unwrap();
+        let ids = pending.iter().map(|(m, _)| m.id).collect::<Vec<_>>();
+        assert_eq!(vec![id1, id2], ids);
+        assert_eq!(open_regions("t1"), pending[0].0.instruction);
+        assert!(mailbox.reply(0, 1, id1).await.unwrap().is_none());
+
+        // Replied instructions are no longer sent.
+        put_reply(&kv_store, 1, id1, Some("error".to_string())).await;
+        let pending = mailbox.pending(0, 1).await.unwrap();
+        assert_eq!(1, pending.len());
+        assert_eq!(id2, pending[0].0.id);
+        let reply = mailbox.reply(0, 1, id1).await.unwrap().unwrap();
+        assert_eq!(Some("error".to_string()), reply.error);
+
+        let value = mailbox.instruction(0, 1, id1).await.unwrap().unwrap();
+        assert_eq!(open_regions("t1"), value.instruction);
+
+        mailbox.remove(0, 1, id1).await.unwrap();
+        assert!(mailbox.reply(0, 1, id1).await.unwrap().is_none());
+        assert!(mailbox.instruction(0, 1, id1).await.unwrap().is_none());
+        assert_eq!(1, mailbox.pending(0, 1).await.unwrap().len());
+        assert_eq!(id3, mailbox.pending(0, 10).await.unwrap()[0].0.id);
+
+        // Instructions survive the restart of the leader.
+        let mailbox = Mailbox::new(kv_store);
+        assert_eq!(1, mailbox.pending(0, 1).await.unwrap().len());
+        assert!(mailbox.send(0, 1, open_regions("t4")).await.unwrap() > id3);
+    }
+
+    async fn put_lease(kv_store: &KvStoreRef, node_id: u64) {
+        let key = LeaseKey {
+            cluster_id: 0,
+            node_id,
+        };
+        let value = LeaseValue {
+            timestamp_millis: time_util::current_time_millis(),
+            node_addr: format!("{node_id}.peer"),
+        };
+        let req = PutRequest {
+            key: key.try_into().unwrap(),
+            value: value.try_into().unwrap(),
+            ..Default::default()
+        };
+        kv_store.put(req).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_open_window() {
+        let kv_store: KvStoreRef = Arc::new(MemStore::new());
+        let mut mailbox = Mailbox::new(kv_store.clone()).with_open_limit(2, 30);
+        mailbox.poll_interval = Duration::from_millis(10);
+        put_lease(&kv_store, 2).await;
+
+        let id1 = mailbox.send(0, 2, open_regions("t1")).await.unwrap();
+        mailbox.send(0, 2, open_regions("t2")).await.unwrap();
+        // Only region opens are limited.
+        let close = Instruction::CloseRegions(RegionIdent {
+            catalog: "greptime".to_string(),
+            schema: "public".to_string(),
+            table: "t1".to_string(),
+            table_id: 1024,
+            region_numbers: vec![0],
+        });
+        mailbox.send(0, 2, close).await.unwrap();
+
+        let send = mailbox.send(0, 2, open_regions("t3"));
+        tokio::pin!(send);
+        assert!(tokio::time::timeout(Duration::from_millis(50), &mut send)
+            .await
+            .is_err());
+
+        put_reply(&kv_store, 2, id1, None).await;
+        send.await.unwrap();
+        assert_eq!(3, mailbox.pending(0, 2).await.unwrap().len());
+
+        // Opens to a dead datanode are never replied, so they are not limited.
+        for table in ["t1", "t2", "t3"] {
+            mailbox.send(0, 3, open_regions(table)).await.unwrap();
+        }
+    }
+}
@@ -22,10 +22,11 @@ use common_telemetry::{info, warn};
 use serde::{Deserialize, Serialize};
 
 use crate::cluster::MetaPeerClient;
-use crate::election::Election;
+use crate::election::{Election, ElectionOptions};
 use crate::handler::HeartbeatHandlerGroup;
 use crate::lock::DistLockRef;
-use crate::selector::{Selector, SelectorType};
+use crate::mailbox::MailboxRef;
+use crate::selector::{LoadWeights, Selector, SelectorType};
 use crate::sequence::SequenceRef;
 use crate::service::store::kv::{KvStoreRef, ResettableKvStoreRef};
 
@@ -39,7 +40,28 @@ pub struct MetaSrvOptions {
     pub store_addr: String,
     pub datanode_lease_secs: i64,
     pub selector: SelectorType,
+    /// Breaks the ties of the selectors by node id instead of randomly, so the region
+    /// placements are reproducible, e.g. in tests.
+    pub deterministic_placement: bool,
     pub use_memory_store: bool,
+    /// The max number of regions a datanode can host, 0 means unlimited. It is
+    /// used to check whether the other datanodes can absorb the regions of a datanode
+    /// being decommissioned, when unlimited it's only checked that there is another
+    /// alive datanode.
+    pub max_regions_per_datanode: u64,
+    /// The max number of region open instructions pending on a datanode, 0 means
+    /// unlimited. Every region open sent to a datanode, e.g. by decommission or failover,
+    /// waits until fewer opens are pending on the datanode.
+    pub max_pending_region_opens: usize,
+    /// Max seconds to wait for in-flight requests to finish on shutdown.
+    pub drain_timeout_secs: u64,
+    /// The stat kvs of a datanode expire if its stats are not persisted for longer than
+    /// this, the stats are persisted every 10 heartbeats.
+    pub stat_ttl_secs: u64,
+    /// The weights of the load score, only used by the weighted load-based selector.
+    pub load_weights: LoadWeights,
+    pub etcd: EtcdOptions,
+    pub election: ElectionOptions,
 }
 
 impl Default for MetaSrvOptions {
@@ -50,7 +72,51 @@ impl Default for MetaSrvOptions {
             store_addr: "127.0.0.1:2379".to_string(),
             datanode_lease_secs: 15,
             selector: SelectorType::default(),
+            deterministic_placement: false,
             use_memory_store: false,
+            max_regions_per_datanode: 0,
+            max_pending_region_opens: 4,
+            drain_timeout_secs: 5,
+            stat_ttl_secs: 120,
+            load_weights: LoadWeights::default(),
+            etcd: EtcdOptions::default(),
+            election: ElectionOptions::default(),
+        }
+    }
+}
+
+/// Options of the connection to the etcd store, shared by the store, election and
+/// lock clients.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EtcdOptions {
+    /// The CA certificate to verify the etcd server, TLS is enabled if it's set.
+    pub ca_cert_path: Option<String>,
+    /// The client certificate and key for mutual TLS, they must be set together.
+    pub client_cert_path: Option<String>,
+    pub client_key_path: Option<String>,
+    /// The user to authenticate to etcd, the password must be set along with it.
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// Seconds to wait for a connection to be established, 0 means no timeout.
+    pub connect_timeout_secs: u64,
+    /// Seconds between the keepalive pings, 0 disables keepalive.
+    pub keepalive_interval_secs: u64,
+    /// Seconds to wait for a keepalive ping to be acknowledged.
+    pub keepalive_timeout_secs: u64,
+}
+
+impl Default for EtcdOptions {
+    fn default() -> Self {
+        Self {
+            ca_cert_path: None,
+            client_cert_path: None,
+            client_key_path: None,
+            username: None,
+            password: None,
+            connect_timeout_secs: 5,
+            keepalive_interval_secs: 0,
+            keepalive_timeout_secs: 10,
         }
     }
 }
@@ -61,6 +127,7 @@ pub struct Context {
     pub server_addr: String,
     pub in_memory: ResettableKvStoreRef,
     pub kv_store: KvStoreRef,
+    pub mailbox: MailboxRef,
     pub election: Option<ElectionRef>,
     pub skip_all: Arc<AtomicBool>,
     pub catalog: Option<String>,
@@ -96,6 +163,7 @@ pub struct MetaSrv {
     in_memory: ResettableKvStoreRef,
     kv_store: KvStoreRef,
     table_id_sequence: SequenceRef,
+    mailbox: MailboxRef,
     selector: SelectorRef,
     handler_group: HeartbeatHandlerGroup,
     election: Option<ElectionRef>,
@@ -156,6 +224,11 @@ impl MetaSrv {
         self.table_id_sequence.clone()
     }
 
+    #[inline]
+    pub fn mailbox(&self) -> MailboxRef {
+        self.mailbox.clone()
+    }
+
     #[inline]
     pub fn selector(&self) -> SelectorRef {
         self.selector.clone()
@@ -187,6 +260,7 @@ impl MetaSrv {
         let server_addr = self.options().server_addr.clone();
         let in_memory = self.in_memory();
         let kv_store = self.kv_store();
+        let mailbox = self.mailbox();
         let election = self.election();
         let skip_all = Arc::new(AtomicBool::new(false));
         Context {
@@ -194,6 +268,7 @@ impl MetaSrv {
             server_addr,
             in_memory,
             kv_store,
+            mailbox,
             election,
             skip_all,
             catalog: None,
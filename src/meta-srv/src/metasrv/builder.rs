@@ -18,9 +18,11 @@ use std::sync::Arc;
 use crate::cluster::MetaPeerClient;
 use crate::handler::{
     CheckLeaderHandler, CollectStatsHandler, HeartbeatHandlerGroup, KeepLeaseHandler,
-    OnLeaderStartHandler, PersistStatsHandler, ResponseHeaderHandler,
+    MailboxHandler, OnLeaderStartHandler, PersistHotRegionsHandler, PersistStatsHandler,
+    ResponseHeaderHandler,
 };
 use crate::lock::DistLockRef;
+use crate::mailbox::Mailbox;
 use crate::metasrv::{ElectionRef, MetaSrv, MetaSrvOptions, SelectorRef, TABLE_ID_SEQ};
 use crate::selector::lease_based::LeaseBasedSelector;
 use crate::sequence::Sequence;
@@ -113,7 +115,11 @@ impl MetaSrvBuilder {
 
         let in_memory = in_memory.unwrap_or_else(|| Arc::new(MemStore::default()));
 
-        let selector = selector.unwrap_or_else(|| Arc::new(LeaseBasedSelector));
+        let selector = selector.unwrap_or_else(|| {
+            Arc::new(LeaseBasedSelector {
+                deterministic: options.deterministic_placement,
+            })
+        });
 
         let handler_group = match handler_group {
             Some(handler_group) => handler_group,
@@ -129,11 +135,17 @@ impl MetaSrvBuilder {
                 group.add_handler(OnLeaderStartHandler::default()).await;
                 group.add_handler(CollectStatsHandler::default()).await;
                 group.add_handler(PersistStatsHandler::default()).await;
+                group.add_handler(PersistHotRegionsHandler::default()).await;
+                group.add_handler(MailboxHandler::default()).await;
                 group
             }
         };
 
         let table_id_sequence = Arc::new(Sequence::new(TABLE_ID_SEQ, 1024, 10, kv_store.clone()));
+        let mailbox = Arc::new(Mailbox::new(kv_store.clone()).with_open_limit(
+            options.max_pending_region_opens,
+            options.datanode_lease_secs,
+        ));
 
         MetaSrv {
             started,
@@ -141,6 +153,7 @@ impl MetaSrvBuilder {
             in_memory,
             kv_store,
             table_id_sequence,
+            mailbox,
             selector,
             handler_group,
             election,
@@ -0,0 +1,22 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! metasrv metrics
+
+/// Number of region open instructions pending on a datanode, labeled by node id.
+pub const METRIC_META_REGION_OPEN_PENDING: &str = "meta.region_open.pending";
+/// Time from sending a region open instruction to the datanode's reply, labeled by node id.
+pub const METRIC_META_REGION_OPEN_ELAPSED: &str = "meta.region_open.elapsed";
+
+pub(crate) const LABEL_NODE_ID: &str = "node_id";
@@ -14,7 +14,14 @@
 
 pub mod lease_based;
 pub mod load_based;
+#[cfg(test)]
+mod test_util;
+pub mod weighted_load_based;
+
+use std::cmp::Ordering;
 
+use api::v1::meta::Peer;
+use rand::seq::SliceRandom;
 use serde::{Deserialize, Serialize};
 
 use crate::error;
@@ -24,10 +31,39 @@ pub type Namespace = u64;
 
 #[async_trait::async_trait]
 pub trait Selector: Send + Sync {
-    type Context;
-    type Output;
+    type Context: Sync;
+    type Output: Send;
+
+    /// Selects the most preferred candidate, the same as `select_n(ns, ctx, 1)`.
+    async fn select(&self, ns: Namespace, ctx: &Self::Context) -> Result<Self::Output> {
+        self.select_n(ns, ctx, 1).await
+    }
 
-    async fn select(&self, ns: Namespace, ctx: &Self::Context) -> Result<Self::Output>;
+    /// Selects up to `count` distinct candidates, in descending order of preference.
+    async fn select_n(
+        &self,
+        ns: Namespace,
+        ctx: &Self::Context,
+        count: usize,
+    ) -> Result<Self::Output>;
+}
+
+/// Sorts the peers in ascending order of their keys. The peers with equal keys are
+/// ordered by node id if `deterministic`, otherwise they are shuffled, so that the
+/// placements don't all go to the same node.
+pub(crate) fn sort_peers<K: PartialOrd>(
+    mut peers: Vec<(K, Peer)>,
+    deterministic: bool,
+) -> Vec<Peer> {
+    let cmp_key = |a: &(K, Peer), b: &(K, Peer)| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal);
+    if deterministic {
+        peers.sort_by(|a, b| cmp_key(a, b).then(a.1.id.cmp(&b.1.id)));
+    } else {
+        peers.shuffle(&mut rand::thread_rng());
+        // the sort is stable, so the shuffled order is kept among equal keys
+        peers.sort_by(cmp_key);
+    }
+    peers.into_iter().map(|(_, peer)| peer).collect()
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
@@ -35,6 +71,27 @@ pub enum SelectorType {
     LoadBased,
     #[default]
     LeaseBased,
+    WeightedLoadBased,
+}
+
+/// The weights of the load score used by the weighted load-based selector.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LoadWeights {
+    /// The weight of each region on a datanode.
+    pub region_num: u64,
+    /// The weight of each GiB of the regions on a datanode. Datanodes don't report
+    /// their disk capacity, so the used bytes stand in for the free capacity.
+    pub disk_usage_gb: u64,
+}
+
+impl Default for LoadWeights {
+    fn default() -> Self {
+        Self {
+            region_num: 1,
+            disk_usage_gb: 1,
+        }
+    }
 }
 
 impl TryFrom<&str> for SelectorType {
@@ -44,6 +101,7 @@ impl TryFrom<&str> for SelectorType {
         match value {
             "LoadBased" => Ok(SelectorType::LoadBased),
             "LeaseBased" => Ok(SelectorType::LeaseBased),
+            "WeightedLoadBased" => Ok(SelectorType::WeightedLoadBased),
             other => error::UnsupportedSelectorTypeSnafu {
                 selector_type: other,
             }
@@ -72,6 +130,10 @@ mod tests {
         let selector_type = loadbased.try_into().unwrap();
         assert_eq!(SelectorType::LoadBased, selector_type);
 
+        let weighted = "WeightedLoadBased";
+        let selector_type = weighted.try_into().unwrap();
+        assert_eq!(SelectorType::WeightedLoadBased, selector_type);
+
         let unknown = "unknown";
         let selector_type: Result<SelectorType> = unknown.try_into();
         assert!(selector_type.is_err());
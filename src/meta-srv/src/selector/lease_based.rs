@@ -17,29 +17,47 @@ use common_time::util as time_util;
 
 use crate::error::Result;
 use crate::keys::{LeaseKey, LeaseValue};
-use crate::lease;
 use crate::metasrv::Context;
 use crate::selector::{Namespace, Selector};
+use crate::{decommission, lease};
 
-pub struct LeaseBasedSelector;
+#[derive(Default)]
+pub struct LeaseBasedSelector {
+    /// Orders the datanodes by node id instead of their last activity, so the
+    /// placements are reproducible.
+    pub deterministic: bool,
+}
 
 #[async_trait::async_trait]
 impl Selector for LeaseBasedSelector {
     type Context = Context;
     type Output = Vec<Peer>;
 
-    async fn select(&self, ns: Namespace, ctx: &Self::Context) -> Result<Self::Output> {
-        // filter out the nodes out lease
-        let lease_filter = |_: &LeaseKey, v: &LeaseValue| {
-            time_util::current_time_millis() - v.timestamp_millis < ctx.datanode_lease_secs * 1000
+    async fn select_n(
+        &self,
+        ns: Namespace,
+        ctx: &Self::Context,
+        count: usize,
+    ) -> Result<Self::Output> {
+        // filter out the nodes out lease or being decommissioned
+        let cordoned = decommission::cordoned_nodes(ns, &ctx.kv_store).await?;
+        let lease_filter = |k: &LeaseKey, v: &LeaseValue| {
+            !cordoned.contains(&k.node_id)
+                && time_util::current_time_millis() - v.timestamp_millis
+                    < ctx.datanode_lease_secs * 1000
         };
         let mut lease_kvs = lease::alive_datanodes(ns, &ctx.kv_store, lease_filter).await?;
-        // TODO(jiachun): At the moment we are just pushing the latest to the forefront,
-        // and it is better to use load-based strategies in the future.
-        lease_kvs.sort_by(|a, b| b.1.timestamp_millis.cmp(&a.1.timestamp_millis));
+        if self.deterministic {
+            lease_kvs.sort_by_key(|(k, _)| k.node_id);
+        } else {
+            // TODO(jiachun): At the moment we are just pushing the latest to the forefront,
+            // and it is better to use load-based strategies in the future.
+            lease_kvs.sort_by(|a, b| b.1.timestamp_millis.cmp(&a.1.timestamp_millis));
+        }
 
         let peers = lease_kvs
             .into_iter()
+            .take(count)
             .map(|(k, v)| Peer {
                 id: k.node_id,
                 addr: v.node_addr,
@@ -49,3 +67,36 @@ impl Selector for LeaseBasedSelector {
         Ok(peers)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::selector::test_util::{new_ctx, put_node, select_n_ids};
+
+    #[tokio::test]
+    async fn test_select_n() {
+        let ctx = new_ctx();
+        let selector = LeaseBasedSelector {
+            deterministic: true,
+        };
+        put_node(&ctx, 1, 0, 0, 0).await;
+        put_node(&ctx, 2, 0, 0, 0).await;
+        assert_eq!(
+            vec![1],
+            selector
+                .select(0, &ctx)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|p| p.id)
+                .collect::<Vec<_>>()
+        );
+        // Fewer nodes than requested.
+        assert_eq!(vec![1, 2], select_n_ids(&selector, &ctx, 3).await);
+
+        put_node(&ctx, 3, 0, 0, 0).await;
+        put_node(&ctx, 4, 0, 0, 0).await;
+        assert_eq!(vec![1, 2, 3], select_n_ids(&selector, &ctx, 3).await);
+        assert!(select_n_ids(&selector, &ctx, 0).await.is_empty());
+    }
+}
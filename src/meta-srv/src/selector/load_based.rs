@@ -20,12 +20,15 @@ use common_time::util as time_util;
 use crate::cluster::MetaPeerClient;
 use crate::error::Result;
 use crate::keys::{LeaseKey, LeaseValue, StatKey};
-use crate::lease;
 use crate::metasrv::Context;
-use crate::selector::{Namespace, Selector};
+use crate::selector::{sort_peers, Namespace, Selector};
+use crate::{decommission, lease};
 
 pub struct LoadBasedSelector {
     pub meta_peer_client: MetaPeerClient,
+    /// Orders the datanodes with the same number of regions by node id, otherwise
+    /// they are shuffled.
+    pub deterministic: bool,
 }
 
 #[async_trait::async_trait]
@@ -33,10 +36,18 @@ impl Selector for LoadBasedSelector {
     type Context = Context;
     type Output = Vec<Peer>;
 
-    async fn select(&self, ns: Namespace, ctx: &Self::Context) -> Result<Self::Output> {
-        // get alive datanodes
-        let lease_filter = |_: &LeaseKey, v: &LeaseValue| {
-            time_util::current_time_millis() - v.timestamp_millis < ctx.datanode_lease_secs * 1000
+    async fn select_n(
+        &self,
+        ns: Namespace,
+        ctx: &Self::Context,
+        count: usize,
+    ) -> Result<Self::Output> {
+        // get alive datanodes, except for those being decommissioned
+        let cordoned = decommission::cordoned_nodes(ns, &ctx.kv_store).await?;
+        let lease_filter = |k: &LeaseKey, v: &LeaseValue| {
+            !cordoned.contains(&k.node_id)
+                && time_util::current_time_millis() - v.timestamp_millis
+                    < ctx.datanode_lease_secs * 1000
         };
         let lease_kvs: HashMap<LeaseKey, LeaseValue> =
             lease::alive_datanodes(ns, &ctx.kv_store, lease_filter)
@@ -55,13 +66,17 @@ impl Selector for LoadBasedSelector {
         let stat_kvs = self.meta_peer_client.get_dn_stat_kvs(stat_keys).await?;
 
         // aggregate lease and stat information
-        let mut tuples: Vec<(LeaseKey, LeaseValue, u64)> = stat_kvs
+        let peers: Vec<(u64, Peer)> = stat_kvs
             .into_iter()
             .filter_map(|(stat_key, stat_val)| {
                 let lease_key = to_lease_key(&stat_key);
                 match (lease_kvs.get(&lease_key), stat_val.region_num()) {
                     (Some(lease_val), Some(region_num)) => {
-                        Some((lease_key, lease_val.clone(), region_num))
+                        let peer = Peer {
+                            id: lease_key.node_id,
+                            addr: lease_val.node_addr.clone(),
+                        };
+                        Some((region_num, peer))
                     }
                     _ => None,
                 }
@@ -69,15 +84,9 @@ impl Selector for LoadBasedSelector {
             .collect();
 
         // sort the datanodes according to the number of regions
-        tuples.sort_by(|a, b| a.2.cmp(&b.2));
-
-        Ok(tuples
-            .into_iter()
-            .map(|(stat_key, lease_val, _)| Peer {
-                id: stat_key.node_id,
-                addr: lease_val.node_addr,
-            })
-            .collect())
+        let mut peers = sort_peers(peers, self.deterministic);
+        peers.truncate(count);
+        Ok(peers)
     }
 }
 
@@ -90,8 +99,10 @@ fn to_lease_key(k: &StatKey) -> LeaseKey {
 
 #[cfg(test)]
 mod tests {
-    use super::to_lease_key;
-    use crate::keys::StatKey;
+    use super::*;
+    use crate::selector::test_util::{
+        new_ctx, new_meta_peer_client, put_node, select_ids, select_n_ids,
+    };
 
     #[test]
     fn test_to_lease_key() {
@@ -103,4 +114,41 @@ mod tests {
         assert_eq!(1, lease_key.cluster_id);
         assert_eq!(101, lease_key.node_id);
     }
+
+    #[tokio::test]
+    async fn test_deterministic_select() {
+        let ctx = new_ctx();
+        for node_id in 1..=4 {
+            put_node(&ctx, node_id, 2, 1, 0).await;
+        }
+        put_node(&ctx, 5, 1, 1, 0).await;
+
+        let selector = LoadBasedSelector {
+            meta_peer_client: new_meta_peer_client(&ctx),
+            deterministic: true,
+        };
+        for _ in 0..10 {
+            assert_eq!(vec![5, 1, 2, 3, 4], select_ids(&selector, &ctx).await);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_select_n_distinct() {
+        let ctx = new_ctx();
+        for node_id in 1..=4 {
+            put_node(&ctx, node_id, 1, 1, 0).await;
+        }
+        // The ties are shuffled, but the candidates are still distinct.
+        let selector = LoadBasedSelector {
+            meta_peer_client: new_meta_peer_client(&ctx),
+            deterministic: false,
+        };
+        for _ in 0..10 {
+            let mut ids = select_n_ids(&selector, &ctx, 3).await;
+            ids.sort();
+            ids.dedup();
+            assert_eq!(3, ids.len());
+        }
+        assert_eq!(4, select_n_ids(&selector, &ctx, 5).await.len());
+    }
 }
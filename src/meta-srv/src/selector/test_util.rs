@@ -0,0 +1,133 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+use api::v1::meta::{Peer, PutRequest};
+use common_time::util as time_util;
+
+use crate::cluster::{MetaPeerClient, MetaPeerClientBuilder};
+use crate::handler::node_stat::{RegionStat, Stat};
+use crate::keys::{LeaseKey, LeaseValue, StatValue};
+use crate::mailbox::Mailbox;
+use crate::metasrv::Context;
+use crate::selector::Selector;
+use crate::service::store::kv::KvStoreRef;
+use crate::service::store::memory::MemStore;
+
+pub(crate) fn new_ctx() -> Context {
+    let kv_store: KvStoreRef = Arc::new(MemStore::new());
+    Context {
+        datanode_lease_secs: 30,
+        server_addr: "127.0.0.1:0000".to_string(),
+        in_memory: Arc::new(MemStore::new()),
+        kv_store: kv_store.clone(),
+        mailbox: Arc::new(Mailbox::new(kv_store)),
+        election: None,
+        skip_all: Arc::new(AtomicBool::new(false)),
+        catalog: None,
+        schema: None,
+        table: None,
+    }
+}
+
+/// Puts the lease and the stat of a datanode with `region_num` regions of
+/// `region_gb` GiB each, reported `age_millis` ago.
+pub(crate) async fn put_node(
+    ctx: &Context,
+    node_id: u64,
+    region_num: u64,
+    region_gb: i64,
+    age_millis: i64,
+) {
+    let now = time_util::current_time_millis();
+    let addr = format!("127.0.0.1:300{node_id}");
+    let lease_key = LeaseKey {
+        cluster_id: 0,
+        node_id,
+    };
+    let lease_value = LeaseValue {
+        timestamp_millis: now,
+        node_addr: addr.clone(),
+    };
+    ctx.kv_store
+        .put(PutRequest {
+            key: lease_key.try_into().unwrap(),
+            value: lease_value.try_into().unwrap(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    let region_stats = (0..region_num)
+        .map(|id| RegionStat {
+            id,
+            catalog: "greptime".to_string(),
+            schema: "public".to_string(),
+            table: "demo".to_string(),
+            rcus: 0,
+            wcus: 0,
+            approximate_bytes: region_gb << 30,
+            approximate_rows: 0,
+        })
+        .collect();
+    let stat = Stat {
+        timestamp_millis: now - age_millis,
+        id: node_id,
+        addr,
+        region_num: Some(region_num),
+        region_stats,
+        ..Default::default()
+    };
+    ctx.in_memory
+        .put(PutRequest {
+            key: stat.stat_key().into(),
+            value: StatValue { stats: vec![stat] }.try_into().unwrap(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+}
+
+/// Returns the ids of at most `count` selected datanodes in order.
+pub(crate) async fn select_n_ids<S>(selector: &S, ctx: &Context, count: usize) -> Vec<u64>
+where
+    S: Selector<Context = Context, Output = Vec<Peer>>,
+{
+    selector
+        .select_n(0, ctx, count)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|peer| peer.id)
+        .collect()
+}
+
+/// Returns the ids of all the selected datanodes in order.
+pub(crate) async fn select_ids<S>(selector: &S, ctx: &Context) -> Vec<u64>
+where
+    S: Selector<Context = Context, Output = Vec<Peer>>,
+{
+    select_n_ids(selector, ctx, usize::MAX).await
+}
+
+/// Returns a client reading the stats from the in-memory store of `ctx`.
+pub(crate) fn new_meta_peer_client(ctx: &Context) -> MetaPeerClient {
+    MetaPeerClientBuilder::default()
+        .election(None)
+        .in_memory(ctx.in_memory.clone())
+        .build()
+        .unwrap()
+}
@@ -0,0 +1,203 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use api::v1::meta::Peer;
+use common_time::util as time_util;
+
+use crate::cluster::MetaPeerClient;
+use crate::error::Result;
+use crate::handler::node_stat::Stat;
+use crate::keys::{LeaseKey, LeaseValue, StatKey};
+use crate::metasrv::Context;
+use crate::selector::{sort_peers, LoadWeights, Namespace, Selector};
+use crate::{decommission, lease};
+
+const GIB: f64 = (1u64 << 30) as f64;
+
+/// Selects the datanodes in ascending order of their load scores, the load score
+/// is a weighted sum of the region number and the disk usage reported in the latest
+/// stat of a datanode. The disk usage is summed from the hottest regions reported in
+/// the stat and the stat of all the other regions.
+pub struct WeightedLoadBasedSelector {
+    pub meta_peer_client: MetaPeerClient,
+    pub weights: LoadWeights,
+    /// Orders the datanodes with the same score by node id, otherwise they are
+    /// shuffled.
+    pub deterministic: bool,
+}
+
+#[async_trait::async_trait]
+impl Selector for WeightedLoadBasedSelector {
+    type Context = Context;
+    type Output = Vec<Peer>;
+
+    async fn select_n(
+        &self,
+        ns: Namespace,
+        ctx: &Self::Context,
+        count: usize,
+    ) -> Result<Self::Output> {
+        let now = time_util::current_time_millis();
+        let lease_millis = ctx.datanode_lease_secs * 1000;
+
+        // get alive datanodes, except for those being decommissioned
+        let cordoned = decommission::cordoned_nodes(ns, &ctx.kv_store).await?;
+        let lease_filter = |k: &LeaseKey, v: &LeaseValue| {
+            !cordoned.contains(&k.node_id) && now - v.timestamp_millis < lease_millis
+        };
+        let lease_kvs: HashMap<LeaseKey, LeaseValue> =
+            lease::alive_datanodes(ns, &ctx.kv_store, lease_filter)
+                .await?
+                .into_iter()
+                .collect();
+
+        let stat_keys: Vec<StatKey> = lease_kvs
+            .keys()
+            .map(|k| StatKey {
+                cluster_id: k.cluster_id,
+                node_id: k.node_id,
+            })
+            .collect();
+        let stat_kvs = self.meta_peer_client.get_dn_stat_kvs(stat_keys).await?;
+
+        // score the datanodes by their latest stats, the stale ones are excluded
+        let scored: Vec<(f64, Peer)> = stat_kvs
+            .into_iter()
+            .filter_map(|(stat_key, stat_val)| {
+                let stat = stat_val.stats.last()?;
+                if now - stat.timestamp_millis >= lease_millis {
+                    return None;
+                }
+                let lease_val = lease_kvs.get(&LeaseKey {
+                    cluster_id: stat_key.cluster_id,
+                    node_id: stat_key.node_id,
+                })?;
+                let score = self.weights.score(stat)?;
+                let peer = Peer {
+                    id: stat_key.node_id,
+                    addr: lease_val.node_addr.clone(),
+                };
+                Some((score, peer))
+            })
+            .collect();
+
+        let mut peers = sort_peers(scored, self.deterministic);
+        peers.truncate(count);
+        Ok(peers)
+    }
+}
+
+impl LoadWeights {
+    /// Returns the load score of a datanode, or `None` if its region number is not
+    /// reported.
+    fn score(&self, stat: &Stat) -> Option<f64> {
+        let region_num = stat.region_num?;
+        let disk_usage: i64 = stat
+            .region_stats
+            .iter()
+            .map(|r| r.approximate_bytes.max(0))
+            .sum();
+
+        Some(
+            self.region_num as f64 * region_num as f64
+                + self.disk_usage_gb as f64 * disk_usage as f64 / GIB,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handler::node_stat::{RegionStat, OTHER_REGIONS_ID};
+    use crate::selector::test_util::{new_ctx, new_meta_peer_client, put_node, select_ids};
+
+    fn new_selector(ctx: &Context, weights: LoadWeights) -> WeightedLoadBasedSelector {
+        WeightedLoadBasedSelector {
+            meta_peer_client: new_meta_peer_client(ctx),
+            weights,
+            deterministic: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_select_least_loaded() {
+        let ctx = new_ctx();
+        let selector = new_selector(&ctx, LoadWeights::default());
+        put_node(&ctx, 1, 3, 1, 0).await;
+        put_node(&ctx, 2, 1, 1, 0).await;
+        put_node(&ctx, 3, 2, 1, 0).await;
+        assert_eq!(vec![2, 3, 1], select_ids(&selector, &ctx).await);
+
+        // Raising the load of the least loaded node shifts the choice.
+        put_node(&ctx, 2, 5, 1, 0).await;
+        assert_eq!(vec![3, 1, 2], select_ids(&selector, &ctx).await);
+
+        // The node with a stale stat is excluded.
+        put_node(&ctx, 3, 2, 1, 60_000).await;
+        assert_eq!(vec![1, 2], select_ids(&selector, &ctx).await);
+    }
+
+    #[tokio::test]
+    async fn test_select_by_weights() {
+        let ctx = new_ctx();
+        // Node 1 has fewer but larger regions than node 2.
+        put_node(&ctx, 1, 1, 10, 0).await;
+        put_node(&ctx, 2, 4, 1, 0).await;
+
+        let selector = new_selector(
+            &ctx,
+            LoadWeights {
+                region_num: 1,
+                disk_usage_gb: 0,
+            },
+        );
+        assert_eq!(vec![1, 2], select_ids(&selector, &ctx).await);
+
+        let selector = new_selector(
+            &ctx,
+            LoadWeights {
+                region_num: 1,
+                disk_usage_gb: 1,
+            },
+        );
+        assert_eq!(vec![2, 1], select_ids(&selector, &ctx).await);
+    }
+
+    #[test]
+    fn test_score_with_other_regions() {
+        let region_stat = |id, gb: i64| RegionStat {
+            id,
+            catalog: String::new(),
+            schema: String::new(),
+            table: String::new(),
+            rcus: 0,
+            wcus: 0,
+            approximate_bytes: gb << 30,
+            approximate_rows: 0,
+        };
+        // Only one of the 3 regions is hot, the other 2 regions are summed up.
+        let stat = Stat {
+            region_num: Some(3),
+            region_stats: vec![region_stat(1, 1), region_stat(OTHER_REGIONS_ID, 4)],
+            ..Default::default()
+        };
+        let weights = LoadWeights {
+            region_num: 1,
+            disk_usage_gb: 1,
+        };
+        assert_eq!(Some(8.0), weights.score(&stat));
+    }
+}
@@ -20,6 +20,7 @@ use tonic::{Response, Status};
 pub mod admin;
 pub mod cluster;
 mod heartbeat;
+pub mod inflight;
 pub mod lock;
 pub mod router;
 pub mod store;
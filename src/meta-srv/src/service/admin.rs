@@ -12,10 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod decommission;
 mod health;
 mod heartbeat;
 mod leader;
 mod meta;
+mod region;
+mod schema;
 
 use std::collections::HashMap;
 use std::convert::Infallible;
@@ -52,6 +55,22 @@ pub fn make_admin_service(meta_srv: MetaSrv) -> Admin {
         },
     );
 
+    let router = router
+        .route_method(
+            http::Method::POST,
+            "/schemas",
+            schema::CreateSchemaHandler {
+                kv_store: meta_srv.kv_store(),
+            },
+        )
+        .route_method(
+            http::Method::DELETE,
+            "/schemas/{catalog_name}/{schema_name}",
+            schema::DropSchemaHandler {
+                ctx: meta_srv.new_ctx(),
+            },
+        );
+
     let router = router.route(
         "/tables",
         meta::TablesHandler {
@@ -66,6 +85,13 @@ pub fn make_admin_service(meta_srv: MetaSrv) -> Admin {
         },
     );
 
+    let router = router.route(
+        "/table-routes",
+        meta::TableRoutesHandler {
+            kv_store: meta_srv.kv_store(),
+        },
+    );
+
     let router = router.route(
         "/leader",
         leader::LeaderHandler {
@@ -73,6 +99,30 @@ pub fn make_admin_service(meta_srv: MetaSrv) -> Admin {
         },
     );
 
+    let router = router
+        .route_method(
+            http::Method::POST,
+            "/datanodes/{node_id}/decommission",
+            decommission::DecommissionHandler {
+                ctx: meta_srv.new_ctx(),
+                max_regions_per_datanode: meta_srv.options().max_regions_per_datanode,
+            },
+        )
+        .route_method(
+            http::Method::GET,
+            "/datanodes/{node_id}/decommission",
+            decommission::DecommissionStatusHandler {
+                ctx: meta_srv.new_ctx(),
+            },
+        )
+        .route_method(
+            http::Method::GET,
+            "/datanodes/{node_id}/failed-regions",
+            region::FailedRegionsHandler {
+                kv_store: meta_srv.kv_store(),
+            },
+        );
+
     let router = Router::nest("/admin", router);
 
     Admin::new(router)
@@ -131,20 +181,23 @@ where
             })
             .unwrap_or_else(HashMap::new);
         let path = req.uri().path().to_owned();
-        Box::pin(async move { router.call(&path, query_params).await })
+        let method = req.method().clone();
+        Box::pin(async move { router.call(&method, &path, query_params).await })
     }
 }
 
 #[derive(Default)]
 pub struct Router {
     handlers: HashMap<String, Box<dyn HttpHandler>>,
+    /// Handlers serving only the requests of a method, they are matched before `handlers`.
+    /// A `{name}` segment in their paths matches any segment, which is passed to the
+    /// handler as param `name`.
+    method_handlers: Vec<(http::Method, String, Box<dyn HttpHandler>)>,
 }
 
 impl Router {
     pub fn new() -> Self {
-        Self {
-            handlers: HashMap::default(),
-        }
+        Self::default()
     }
 
     pub fn nest(path: &str, router: Router) -> Self {
@@ -155,8 +208,16 @@ impl Router {
             .into_iter()
             .map(|(url, handler)| (format!("{path}{url}"), handler))
             .collect();
+        let method_handlers = router
+            .method_handlers
+            .into_iter()
+            .map(|(method, url, handler)| (method, format!("{path}{url}"), handler))
+            .collect();
 
-        Self { handlers }
+        Self {
+            handlers,
+            method_handlers,
+        }
     }
 
     pub fn route(mut self, path: &str, handler: impl HttpHandler + 'static) -> Self {
@@ -167,12 +228,39 @@ impl Router {
         self
     }
 
+    pub fn route_method(
+        mut self,
+        method: http::Method,
+        path: &str,
+        handler: impl HttpHandler + 'static,
+    ) -> Self {
+        check_path(path);
+
+        self.method_handlers
+            .push((method, path.to_owned(), Box::new(handler)));
+
+        self
+    }
+
     pub async fn call(
         &self,
+        method: &http::Method,
         path: &str,
-        params: HashMap<String, String>,
+        mut params: HashMap<String, String>,
     ) -> Result<http::Response<BoxBody>, Infallible> {
-        let handler = match self.handlers.get(path) {
+        let mut handler = None;
+        for (handler_method, pattern, method_handler) in &self.method_handlers {
+            if handler_method != method {
+                continue;
+            }
+            if let Some(path_params) = match_path(pattern, path) {
+                params.extend(path_params);
+                handler = Some(method_handler);
+                break;
+            }
+        }
+
+        let handler = match handler.or_else(|| self.handlers.get(path)) {
             Some(handler) => handler,
             None => {
                 return Ok(http::Response::builder()
@@ -194,6 +282,32 @@ impl Router {
     }
 }
 
+/// Matches `path` with `pattern`, returns the params bound to the `{name}` segments
+/// of the pattern if matched.
+fn match_path(pattern: &str, path: &str) -> Option<Vec<(String, String)>> {
+    let pattern_segments = pattern.split('/').collect::<Vec<_>>();
+    let path_segments = path.split('/').collect::<Vec<_>>();
+    if pattern_segments.len() != path_segments.len() {
+        return None;
+    }
+
+    let mut params = Vec::new();
+    for (pattern_segment, path_segment) in pattern_segments.into_iter().zip(path_segments) {
+        match pattern_segment
+            .strip_prefix('{')
+            .and_then(|s| s.strip_suffix('}'))
+        {
+            Some(name) if !path_segment.is_empty() => {
+                params.push((name.to_string(), path_segment.to_string()))
+            }
+            Some(_) => return None,
+            None if pattern_segment != path_segment => return None,
+            None => {}
+        }
+    }
+    Some(params)
+}
+
 fn check_path(path: &str) {
     if path.is_empty() || !path.starts_with('/') {
         panic!("paths must start with a `/`")
@@ -208,6 +322,8 @@ fn boxed(body: String) -> BoxBody {
 
 #[cfg(test)]
 mod tests {
+    use http_body::Body;
+
     use super::*;
     use crate::error;
 
@@ -268,7 +384,11 @@ mod tests {
         let router = Router::nest("/test_root", router);
 
         let res = router
-            .call("/test_root/test_node", HashMap::default())
+            .call(
+                &http::Method::GET,
+                "/test_root/test_node",
+                HashMap::default(),
+            )
             .await
             .unwrap();
 
@@ -280,10 +400,98 @@ mod tests {
         let router = Router::new();
 
         let res = router
-            .call("/test_root/test_node", HashMap::default())
+            .call(
+                &http::Method::GET,
+                "/test_root/test_node",
+                HashMap::default(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(http::StatusCode::NOT_FOUND, res.status());
+    }
+
+    struct MockParamsHandler;
+
+    #[async_trait::async_trait]
+    impl HttpHandler for MockParamsHandler {
+        async fn handle(
+            &self,
+            _: &str,
+            params: &HashMap<String, String>,
+        ) -> crate::Result<http::Response<String>> {
+            Ok(http::Response::builder()
+                .status(http::StatusCode::OK)
+                .body(format!("{}.{}", params["catalog"], params["schema"]))
+                .unwrap())
+        }
+    }
+
+    #[test]
+    fn test_match_path() {
+        assert_eq!(Some(vec![]), match_path("/a/b", "/a/b"));
+        assert_eq!(None, match_path("/a/b", "/a/c"));
+        assert_eq!(None, match_path("/a/b", "/a/b/c"));
+        assert_eq!(
+            Some(vec![
+                ("catalog".to_string(), "c".to_string()),
+                ("schema".to_string(), "s".to_string())
+            ]),
+            match_path("/a/{catalog}/{schema}", "/a/c/s")
+        );
+        assert_eq!(None, match_path("/a/{catalog}/{schema}", "/a/c/"));
+        assert_eq!(None, match_path("/a/{catalog}/{schema}", "/a/c"));
+    }
+
+    #[tokio::test]
+    async fn test_route_call_method() {
+        let router = Router::new()
+            .route("/schemas", MockEmptyKeyErrorHandler {})
+            .route_method(http::Method::POST, "/schemas", MockOkHandler {})
+            .route_method(
+                http::Method::DELETE,
+                "/schemas/{catalog}/{schema}",
+                MockParamsHandler {},
+            );
+        let router = Router::nest("/test_root", router);
+
+        // Handlers of other methods fall back to the method agnostic handler.
+        let res = router
+            .call(&http::Method::GET, "/test_root/schemas", HashMap::default())
+            .await
+            .unwrap();
+        assert_eq!(http::StatusCode::INTERNAL_SERVER_ERROR, res.status());
+
+        let res = router
+            .call(
+                &http::Method::POST,
+                "/test_root/schemas",
+                HashMap::default(),
+            )
             .await
             .unwrap();
+        assert!(res.status().is_success());
 
+        let res = router
+            .call(
+                &http::Method::DELETE,
+                "/test_root/schemas/greptime/public",
+                HashMap::default(),
+            )
+            .await
+            .unwrap();
+        assert!(res.status().is_success());
+        let body = res.into_body().data().await.unwrap().unwrap();
+        assert_eq!(b"greptime.public", body.as_ref());
+
+        let res = router
+            .call(
+                &http::Method::DELETE,
+                "/test_root/schemas/greptime",
+                HashMap::default(),
+            )
+            .await
+            .unwrap();
         assert_eq!(http::StatusCode::NOT_FOUND, res.status());
     }
 
@@ -294,7 +502,11 @@ mod tests {
         let router = Router::nest("/test_root", router);
 
         let res = router
-            .call("/test_root/test_node", HashMap::default())
+            .call(
+                &http::Method::GET,
+                "/test_root/test_node",
+                HashMap::default(),
+            )
             .await
             .unwrap();
 
@@ -0,0 +1,124 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use common_telemetry::error;
+use snafu::{OptionExt, ResultExt};
+use tonic::codegen::http;
+
+use crate::decommission::DatanodeDecommission;
+use crate::error::{self, Result};
+use crate::keys::DecommissionValue;
+use crate::metasrv::Context;
+use crate::service::admin::HttpHandler;
+
+/// Starts decommissioning the datanode, or retries a failed decommission of it.
+pub struct DecommissionHandler {
+    pub ctx: Context,
+    pub max_regions_per_datanode: u64,
+}
+
+/// Returns the progress of the decommission of the datanode.
+pub struct DecommissionStatusHandler {
+    pub ctx: Context,
+}
+
+#[async_trait::async_trait]
+impl HttpHandler for DecommissionHandler {
+    async fn handle(
+        &self,
+        _: &str,
+        params: &HashMap<String, String>,
+    ) -> Result<http::Response<String>> {
+        let (cluster_id, node_id) = parse_node(params)?;
+        let decommission = DatanodeDecommission::new(cluster_id, node_id, &self.ctx);
+        let value = decommission.start(self.max_regions_per_datanode).await?;
+
+        common_runtime::spawn_bg(async move {
+            if let Err(e) = decommission.run().await {
+                error!(e; "Failed to decommission datanode {}", node_id);
+            }
+        });
+
+        to_http_response(&value)
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpHandler for DecommissionStatusHandler {
+    async fn handle(
+        &self,
+        _: &str,
+        params: &HashMap<String, String>,
+    ) -> Result<http::Response<String>> {
+        let (cluster_id, node_id) = parse_node(params)?;
+        let decommission = DatanodeDecommission::new(cluster_id, node_id, &self.ctx);
+        match decommission.status().await? {
+            Some(value) => to_http_response(&value),
+            None => http::Response::builder()
+                .status(http::StatusCode::NOT_FOUND)
+                .body(format!("Datanode {node_id} is not being decommissioned"))
+                .context(error::InvalidHttpBodySnafu),
+        }
+    }
+}
+
+pub(super) fn parse_node(params: &HashMap<String, String>) -> Result<(u64, u64)> {
+    let cluster_id = match params.get("cluster_id") {
+        Some(cluster_id) => cluster_id.parse().context(error::ParseNumSnafu {
+            err_msg: format!("invalid cluster_id: {cluster_id}"),
+        })?,
+        None => 0,
+    };
+    let node_id = params
+        .get("node_id")
+        .context(error::MissingRequiredParameterSnafu { param: "node_id" })?;
+    let node_id = node_id.parse().context(error::ParseNumSnafu {
+        err_msg: format!("invalid node_id: {node_id}"),
+    })?;
+
+    Ok((cluster_id, node_id))
+}
+
+fn to_http_response(value: &DecommissionValue) -> Result<http::Response<String>> {
+    let body = serde_json::to_string(value).context(error::SerializeToJsonSnafu {
+        input: format!("{value:?}"),
+    })?;
+
+    http::Response::builder()
+        .status(http::StatusCode::OK)
+        .body(body)
+        .context(error::InvalidHttpBodySnafu)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_node() {
+        let mut params = HashMap::new();
+        assert!(parse_node(&params).is_err());
+
+        params.insert("node_id".to_string(), "3".to_string());
+        assert_eq!((0, 3), parse_node(&params).unwrap());
+
+        params.insert("cluster_id".to_string(), "1".to_string());
+        assert_eq!((1, 3), parse_node(&params).unwrap());
+
+        params.insert("node_id".to_string(), "x".to_string());
+        assert!(parse_node(&params).is_err());
+    }
+}
@@ -14,13 +14,15 @@
 
 use std::collections::HashMap;
 
-use api::v1::meta::{RangeRequest, RangeResponse};
+use api::v1::meta::{Peer, RangeRequest, RangeResponse, TableRouteValue};
 use catalog::helper::{CATALOG_KEY_PREFIX, SCHEMA_KEY_PREFIX, TABLE_GLOBAL_KEY_PREFIX};
+use serde::Serialize;
 use snafu::{OptionExt, ResultExt};
 use tonic::codegen::http;
 
 use crate::error::Result;
 use crate::service::admin::HttpHandler;
+use crate::service::router::list_table_routes;
 use crate::service::store::ext::KvStoreExt;
 use crate::service::store::kv::KvStoreRef;
 use crate::{error, util};
@@ -41,6 +43,13 @@ pub struct TableHandler {
     pub kv_store: KvStoreRef,
 }
 
+pub struct TableRoutesHandler {
+    pub kv_store: KvStoreRef,
+}
+
+/// Default number of table routes in a page.
+const DEFAULT_TABLE_ROUTES_LIMIT: usize = 1000;
+
 #[async_trait::async_trait]
 impl HttpHandler for CatalogsHandler {
     async fn handle(&self, _: &str, _: &HashMap<String, String>) -> Result<http::Response<String>> {
@@ -115,6 +124,113 @@ impl HttpHandler for TableHandler {
     }
 }
 
+/// Lists routes of tables in pages, filtered by optional `catalog_name` and `schema_name`.
+/// Pass the `next_page_token` of the response as `page_token` to get the next page.
+#[async_trait::async_trait]
+impl HttpHandler for TableRoutesHandler {
+    async fn handle(
+        &self,
+        _: &str,
+        params: &HashMap<String, String>,
+    ) -> Result<http::Response<String>> {
+        let limit = match params.get("limit") {
+            Some(limit) => limit.parse().context(error::ParseNumSnafu {
+                err_msg: format!("invalid limit: {limit}"),
+            })?,
+            None => DEFAULT_TABLE_ROUTES_LIMIT,
+        };
+        let page = list_table_routes(
+            &self.kv_store,
+            params.get("catalog_name").map(String::as_str),
+            params.get("schema_name").map(String::as_str),
+            params.get("page_token").map(String::as_str),
+            limit,
+        )
+        .await?;
+
+        let page = TableRoutesPage {
+            table_routes: page
+                .table_routes
+                .iter()
+                .filter_map(to_table_placement)
+                .collect(),
+            next_page_token: page.next_page_token,
+        };
+        let body = serde_json::to_string(&page).context(error::SerializeToJsonSnafu {
+            input: format!("{page:?}"),
+        })?;
+
+        http::Response::builder()
+            .status(http::StatusCode::OK)
+            .body(body)
+            .context(error::InvalidHttpBodySnafu)
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct TableRoutesPage {
+    table_routes: Vec<TablePlacement>,
+    next_page_token: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct TablePlacement {
+    catalog_name: String,
+    schema_name: String,
+    table_name: String,
+    table_id: u64,
+    regions: Vec<RegionPlacement>,
+}
+
+#[derive(Debug, Serialize)]
+struct RegionPlacement {
+    region_id: u64,
+    leader: Option<PeerPlacement>,
+    followers: Vec<PeerPlacement>,
+}
+
+#[derive(Debug, Serialize)]
+struct PeerPlacement {
+    id: u64,
+    addr: String,
+}
+
+fn to_table_placement(value: &TableRouteValue) -> Option<TablePlacement> {
+    let route = value.table_route.as_ref()?;
+    let table = route.table.as_ref()?;
+    let name = table.table_name.as_ref()?;
+    let peer = |index: u64| {
+        value
+            .peers
+            .get(index as usize)
+            .map(|Peer { id, addr }| PeerPlacement {
+                id: *id,
+                addr: addr.clone(),
+            })
+    };
+    let regions = route
+        .region_routes
+        .iter()
+        .map(|region_route| RegionPlacement {
+            region_id: region_route.region.as_ref().map_or(0, |region| region.id),
+            leader: peer(region_route.leader_peer_index),
+            followers: region_route
+                .follower_peer_indexes
+                .iter()
+                .filter_map(|index| peer(*index))
+                .collect(),
+        })
+        .collect();
+
+    Some(TablePlacement {
+        catalog_name: name.catalog_name.clone(),
+        schema_name: name.schema_name.clone(),
+        table_name: name.table_name.clone(),
+        table_id: table.id,
+        regions,
+    })
+}
+
 /// Get kv_store's key list with http response format by prefix key
 async fn get_http_response_by_prefix(
     key_prefix: String,
@@ -157,11 +273,16 @@ async fn get_keys_by_prefix(key_prefix: String, kv_store: &KvStoreRef) -> Result
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
     use std::sync::Arc;
 
-    use api::v1::meta::PutRequest;
+    use api::v1::meta::{
+        Peer, PutRequest, Region, RegionRoute, Table, TableName, TableRoute, TableRouteValue,
+    };
 
-    use crate::service::admin::meta::get_keys_by_prefix;
+    use crate::keys::TableRouteKey;
+    use crate::service::admin::meta::{get_keys_by_prefix, TableRoutesHandler};
+    use crate::service::admin::HttpHandler;
     use crate::service::store::kv::KvStoreRef;
     use crate::service::store::memory::MemStore;
 
@@ -194,4 +315,91 @@ mod tests {
         assert_eq!("1", keys[0]);
         assert_eq!("2", keys[1]);
     }
+
+    async fn put_table_route(kv_store: &KvStoreRef, table_id: u64, schema: &str, table: &str) {
+        let table_name = TableName {
+            catalog_name: "greptime".to_string(),
+            schema_name: schema.to_string(),
+            table_name: table.to_string(),
+        };
+        let value = TableRouteValue {
+            peers: vec![Peer {
+                id: 1,
+                addr: "127.0.0.1:3001".to_string(),
+            }],
+            table_route: Some(TableRoute {
+                table: Some(Table {
+                    id: table_id,
+                    table_name: Some(table_name.clone()),
+                    ..Default::default()
+                }),
+                region_routes: vec![RegionRoute {
+                    region: Some(Region {
+                        id: 0,
+                        ..Default::default()
+                    }),
+                    leader_peer_index: 0,
+                    follower_peer_indexes: vec![],
+                }],
+            }),
+        };
+        kv_store
+            .put(PutRequest {
+                key: TableRouteKey::with_table_name(table_id, &table_name)
+                    .key()
+                    .into_bytes(),
+                value: value.into(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+    }
+
+    async fn list(handler: &TableRoutesHandler, params: &[(&str, &str)]) -> serde_json::Value {
+        let params: HashMap<_, _> = params
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let res = handler.handle("", &params).await.unwrap();
+        serde_json::from_str(res.body()).unwrap()
+    }
+
+    fn table_names(page: &serde_json::Value) -> Vec<&str> {
+        page["table_routes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|route| route["table_name"].as_str().unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_list_table_routes() {
+        let kv_store = Arc::new(MemStore::new()) as KvStoreRef;
+        put_table_route(&kv_store, 1024, "public", "a").await;
+        put_table_route(&kv_store, 1025, "public", "b").await;
+        put_table_route(&kv_store, 1026, "public", "c").await;
+        // Its route key also starts with the prefix of schema "public".
+        put_table_route(&kv_store, 1027, "public-x", "d").await;
+        let handler = TableRoutesHandler { kv_store };
+
+        let page = list(&handler, &[("limit", "2")]).await;
+        assert_eq!(vec!["a", "b"], table_names(&page));
+        let region = &page["table_routes"][0]["regions"][0];
+        assert_eq!(1024, page["table_routes"][0]["table_id"]);
+        assert_eq!("127.0.0.1:3001", region["leader"]["addr"]);
+
+        let token = page["next_page_token"].as_str().unwrap();
+        let page = list(&handler, &[("limit", "2"), ("page_token", token)]).await;
+        assert_eq!(vec!["c", "d"], table_names(&page));
+        assert!(page["next_page_token"].is_null());
+
+        let page = list(
+            &handler,
+            &[("catalog_name", "greptime"), ("schema_name", "public")],
+        )
+        .await;
+        assert_eq!(vec!["a", "b", "c"], table_names(&page));
+        assert!(page["next_page_token"].is_null());
+    }
 }
@@ -0,0 +1,113 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use snafu::ResultExt;
+use tonic::codegen::http;
+
+use crate::error::{self, Result};
+use crate::keys::{FailedRegionKey, FailedRegionValue};
+use crate::service::admin::decommission::parse_node;
+use crate::service::admin::HttpHandler;
+use crate::service::store::ext::KvStoreExt;
+use crate::service::store::kv::KvStoreRef;
+
+/// Returns the regions the datanode failed to open, as it reported last time.
+pub struct FailedRegionsHandler {
+    pub kv_store: KvStoreRef,
+}
+
+#[async_trait::async_trait]
+impl HttpHandler for FailedRegionsHandler {
+    async fn handle(
+        &self,
+        _: &str,
+        params: &HashMap<String, String>,
+    ) -> Result<http::Response<String>> {
+        let (cluster_id, node_id) = parse_node(params)?;
+        let key = FailedRegionKey {
+            cluster_id,
+            node_id,
+        };
+        let kv = match self.kv_store.get(key.into()).await? {
+            Some(kv) => kv,
+            None => {
+                return http::Response::builder()
+                    .status(http::StatusCode::NOT_FOUND)
+                    .body(format!("Datanode {node_id} hasn't reported failed regions"))
+                    .context(error::InvalidHttpBodySnafu)
+            }
+        };
+
+        // Validates the value before returning it.
+        let value = FailedRegionValue::try_from(kv.value)?;
+        let body = serde_json::to_string(&value).context(error::SerializeToJsonSnafu {
+            input: format!("{value:?}"),
+        })?;
+        http::Response::builder()
+            .status(http::StatusCode::OK)
+            .body(body)
+            .context(error::InvalidHttpBodySnafu)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use api::v1::meta::PutRequest;
+
+    use super::*;
+    use crate::keys::FailedRegion;
+    use crate::service::store::kv::KvStore;
+    use crate::service::store::memory::MemStore;
+
+    #[tokio::test]
+    async fn test_failed_regions() {
+        let kv_store: KvStoreRef = Arc::new(MemStore::new());
+        let handler = FailedRegionsHandler {
+            kv_store: kv_store.clone(),
+        };
+        let params = HashMap::from([("node_id".to_string(), "3".to_string())]);
+
+        let resp = handler.handle("", &params).await.unwrap();
+        assert_eq!(http::StatusCode::NOT_FOUND, resp.status());
+
+        let value = FailedRegionValue {
+            timestamp_millis: 100,
+            regions: vec![FailedRegion {
+                table_name: "greptime.public.demo".to_string(),
+                region_number: 1,
+                error: "corrupted manifest".to_string(),
+                retries: 2,
+            }],
+        };
+        let req = PutRequest {
+            key: FailedRegionKey {
+                cluster_id: 0,
+                node_id: 3,
+            }
+            .into(),
+            value: value.clone().try_into().unwrap(),
+            ..Default::default()
+        };
+        let _ = kv_store.put(req).await.unwrap();
+
+        let resp = handler.handle("", &params).await.unwrap();
+        assert_eq!(http::StatusCode::OK, resp.status());
+        let reported: FailedRegionValue = resp.body().parse().unwrap();
+        assert_eq!(value, reported);
+    }
+}
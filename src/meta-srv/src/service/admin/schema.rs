@@ -0,0 +1,680 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{BTreeMap, HashMap};
+
+use api::v1::meta::{
+    CompareAndPutRequest, DeleteRangeRequest, DeleteRequest, RangeRequest, TableName,
+    TableRouteValue,
+};
+use catalog::helper::{build_table_global_prefix, SchemaKey, SchemaValue, TableGlobalKey};
+use common_error::prelude::{ErrorExt, StatusCode};
+use common_telemetry::info;
+use common_time::util as time_util;
+use serde::Serialize;
+use snafu::{OptionExt, ResultExt};
+use tonic::codegen::http;
+
+use crate::decommission::INSTRUCTION_POLL_INTERVAL;
+use crate::error::{self, Result};
+use crate::handler::instruction::{Instruction, RegionIdent};
+use crate::keys::{LeaseKey, LeaseValue, TableRouteKey};
+use crate::metasrv::Context;
+use crate::service::admin::HttpHandler;
+use crate::service::router::{get_table_global_value, get_table_route_value, handle_delete};
+use crate::service::store::ext::KvStoreExt;
+use crate::service::store::kv::KvStoreRef;
+use crate::{lease, util};
+
+/// Creates a schema, `POST /admin/schemas?catalog_name=..&schema_name=..`.
+///
+/// The schema is created the same way as `CREATE DATABASE`. Creating an existing schema
+/// succeeds with `created` false, so concurrent creations of a schema all succeed.
+pub struct CreateSchemaHandler {
+    pub kv_store: KvStoreRef,
+}
+
+/// Drops a schema, `DELETE /admin/schemas/{catalog_name}/{schema_name}?cascade=false`.
+///
+/// A schema with tables can only be dropped with `cascade=true`, which drops its tables
+/// first. Like `DROP TABLE`, a table is dropped on the datanodes hosting its regions, with
+/// [Instruction::DropTable] sent to the datanodes of the cluster `cluster_id` (0 by
+/// default), before its metadata is removed. The drop fails if any of these datanodes is
+/// dead or fails to drop the table, the tables not dropped yet are kept and the drop can
+/// be retried.
+pub struct DropSchemaHandler {
+    pub ctx: Context,
+}
+
+#[derive(Debug, Serialize)]
+struct CreateSchemaResponse {
+    catalog_name: String,
+    schema_name: String,
+    /// False if the schema already exists.
+    created: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct DropSchemaResponse {
+    catalog_name: String,
+    schema_name: String,
+    dropped_tables: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ErrorResponse {
+    code: u32,
+    error: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tables: Vec<String>,
+}
+
+#[async_trait::async_trait]
+impl HttpHandler for CreateSchemaHandler {
+    async fn handle(
+        &self,
+        _: &str,
+        params: &HashMap<String, String>,
+    ) -> Result<http::Response<String>> {
+        let result = async {
+            let key = parse_schema_key(params)?;
+            let created = create_schema(&self.kv_store, &key).await?;
+            Ok(CreateSchemaResponse {
+                catalog_name: key.catalog_name,
+                schema_name: key.schema_name,
+                created,
+            })
+        }
+        .await;
+
+        match result {
+            Ok(resp) if resp.created => to_json_response(http::StatusCode::CREATED, &resp),
+            Ok(resp) => to_json_response(http::StatusCode::OK, &resp),
+            Err(e) => to_error_response(&e),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpHandler for DropSchemaHandler {
+    async fn handle(
+        &self,
+        _: &str,
+        params: &HashMap<String, String>,
+    ) -> Result<http::Response<String>> {
+        let result = async {
+            let key = parse_schema_key(params)?;
+            let cascade = match params.get("cascade") {
+                Some(cascade) => cascade.parse().ok().context(error::InvalidArgumentsSnafu {
+                    err_msg: format!("invalid cascade: {cascade}"),
+                })?,
+                None => false,
+            };
+            let cluster_id = match params.get("cluster_id") {
+                Some(cluster_id) => cluster_id.parse().context(error::ParseNumSnafu {
+                    err_msg: format!("invalid cluster_id: {cluster_id}"),
+                })?,
+                None => 0,
+            };
+            drop_schema(&self.ctx, cluster_id, key, cascade).await
+        }
+        .await;
+
+        match result {
+            Ok(DropSchemaOutcome::Dropped(resp)) => to_json_response(http::StatusCode::OK, &resp),
+            Ok(DropSchemaOutcome::NotEmpty { key, tables }) => to_json_response(
+                http::StatusCode::CONFLICT,
+                &ErrorResponse {
+                    code: StatusCode::InvalidArguments as u32,
+                    error: format!(
+                        "Schema {}.{} is not empty, drop it with cascade=true to drop its tables",
+                        key.catalog_name, key.schema_name
+                    ),
+                    tables,
+                },
+            ),
+            Err(e) => to_error_response(&e),
+        }
+    }
+}
+
+fn parse_schema_key(params: &HashMap<String, String>) -> Result<SchemaKey> {
+    let catalog_name =
+        params
+            .get("catalog_name")
+            .context(error::MissingRequiredParameterSnafu {
+                param: "catalog_name",
+            })?;
+    let schema_name = params
+        .get("schema_name")
+        .context(error::MissingRequiredParameterSnafu {
+            param: "schema_name",
+        })?;
+    let key = SchemaKey {
+        catalog_name: catalog_name.clone(),
+        schema_name: schema_name.clone(),
+    };
+    // Rejects the names that the catalog can't parse back.
+    SchemaKey::parse(key.to_string())
+        .ok()
+        .context(error::InvalidArgumentsSnafu {
+            err_msg: format!("invalid schema name: {catalog_name}.{schema_name}"),
+        })
+}
+
+/// Puts the schema key if absent, returns false if the schema already exists.
+async fn create_schema(kv_store: &KvStoreRef, key: &SchemaKey) -> Result<bool> {
+    let value = SchemaValue
+        .as_bytes()
+        .context(error::InvalidCatalogValueSnafu)?;
+    let req = CompareAndPutRequest {
+        key: key.to_string().into_bytes(),
+        expect: vec![],
+        value,
+        ..Default::default()
+    };
+    let created = kv_store.compare_and_put(req).await?.success;
+    if created {
+        info!("Created schema {}.{}", key.catalog_name, key.schema_name);
+    }
+
+    Ok(created)
+}
+
+enum DropSchemaOutcome {
+    Dropped(DropSchemaResponse),
+    NotEmpty { key: SchemaKey, tables: Vec<String> },
+}
+
+async fn drop_schema(
+    ctx: &Context,
+    cluster_id: u64,
+    key: SchemaKey,
+    cascade: bool,
+) -> Result<DropSchemaOutcome> {
+    let kv_store = &ctx.kv_store;
+    let schema_key = key.to_string();
+    let _ = kv_store
+        .get(schema_key.clone().into_bytes())
+        .await?
+        .with_context(|| error::SchemaNotFoundSnafu {
+            name: format!("{}.{}", key.catalog_name, key.schema_name),
+        })?;
+
+    let tables = list_tables(kv_store, &key).await?;
+    if !tables.is_empty() && !cascade {
+        return Ok(DropSchemaOutcome::NotEmpty { key, tables });
+    }
+
+    let mut dropped_tables = Vec::with_capacity(tables.len());
+    for table in tables {
+        let table_name = TableName {
+            catalog_name: key.catalog_name.clone(),
+            schema_name: key.schema_name.clone(),
+            table_name: table.clone(),
+        };
+        drop_table_on_datanodes(ctx, cluster_id, &table_name).await?;
+
+        let req = DeleteRequest {
+            table_name: Some(table_name),
+            ..Default::default()
+        };
+        match handle_delete(req, ctx.clone()).await {
+            Ok(_) => dropped_tables.push(table),
+            // Dropped by others.
+            Err(error::Error::TableNotFound { .. }) => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    let req = DeleteRangeRequest {
+        key: schema_key.into_bytes(),
+        ..Default::default()
+    };
+    let _ = kv_store.delete_range(req).await?;
+    info!(
+        "Dropped schema {}.{}, dropped tables: {:?}",
+        key.catalog_name, key.schema_name, dropped_tables
+    );
+
+    Ok(DropSchemaOutcome::Dropped(DropSchemaResponse {
+        catalog_name: key.catalog_name,
+        schema_name: key.schema_name,
+        dropped_tables,
+    }))
+}
+
+/// Drops the table on the datanodes hosting its regions, one datanode at a time.
+async fn drop_table_on_datanodes(
+    ctx: &Context,
+    cluster_id: u64,
+    table_name: &TableName,
+) -> Result<()> {
+    let tgk = TableGlobalKey {
+        catalog_name: table_name.catalog_name.clone(),
+        schema_name: table_name.schema_name.clone(),
+        table_name: table_name.table_name.clone(),
+    };
+    let table_id = match get_table_global_value(&ctx.kv_store, &tgk).await? {
+        Some(tgv) => tgv.table_id(),
+        // Dropped by others.
+        None => return Ok(()),
+    };
+    let trk = TableRouteKey::with_table_name(table_id as u64, table_name);
+    let TableRouteValue { peers, table_route } =
+        match get_table_route_value(&ctx.kv_store, &trk).await {
+            Ok(trv) => trv,
+            Err(error::Error::TableRouteNotFound { .. }) => return Ok(()),
+            Err(e) => return Err(e),
+        };
+
+    let mut regions = BTreeMap::<u64, Vec<u32>>::new();
+    for rr in table_route.map(|t| t.region_routes).unwrap_or_default() {
+        let peer = peers.get(rr.leader_peer_index as usize);
+        if let (Some(region), Some(peer)) = (rr.region, peer) {
+            regions.entry(peer.id).or_default().push(region.id as u32);
+        }
+    }
+
+    for (node_id, region_numbers) in regions {
+        let instruction = Instruction::DropTable(RegionIdent {
+            catalog: table_name.catalog_name.clone(),
+            schema: table_name.schema_name.clone(),
+            table: table_name.table_name.clone(),
+            table_id,
+            region_numbers,
+        });
+        execute(ctx, cluster_id, node_id, instruction).await?;
+    }
+    Ok(())
+}
+
+/// Sends the instruction to the datanode and waits for its reply, fails if the datanode
+/// dies before replying.
+async fn execute(
+    ctx: &Context,
+    cluster_id: u64,
+    node_id: u64,
+    instruction: Instruction,
+) -> Result<()> {
+    let id = ctx.mailbox.send(cluster_id, node_id, instruction).await?;
+    let lease_secs = ctx.datanode_lease_secs;
+    let lease_filter = |k: &LeaseKey, v: &LeaseValue| {
+        k.node_id == node_id
+            && time_util::current_time_millis() - v.timestamp_millis < lease_secs * 1000
+    };
+    let reply = loop {
+        if let Some(reply) = ctx.mailbox.reply(cluster_id, node_id, id).await? {
+            break Some(reply);
+        }
+        if lease::alive_datanodes(cluster_id, &ctx.kv_store, lease_filter)
+            .await?
+            .is_empty()
+        {
+            break None;
+        }
+        tokio::time::sleep(INSTRUCTION_POLL_INTERVAL).await;
+    };
+    // The instruction is removed even if the datanode is dead, so it won't be executed
+    // after the datanode is back.
+    ctx.mailbox.remove(cluster_id, node_id, id).await?;
+
+    let reply = reply.context(error::DatanodeUnavailableSnafu { node_id, id })?;
+    match reply.error {
+        Some(error) => error::ExecuteInstructionSnafu { node_id, id, error }.fail(),
+        None => Ok(()),
+    }
+}
+
+/// Lists the names of tables in the schema.
+async fn list_tables(kv_store: &KvStoreRef, key: &SchemaKey) -> Result<Vec<String>> {
+    let prefix = build_table_global_prefix(&key.catalog_name, &key.schema_name);
+    let req = RangeRequest {
+        range_end: util::get_prefix_end_key(prefix.as_bytes()),
+        key: prefix.into_bytes(),
+        keys_only: true,
+        ..Default::default()
+    };
+    let res = kv_store.range(req).await?;
+
+    let mut tables = Vec::with_capacity(res.kvs.len());
+    for kv in res.kvs {
+        let key = String::from_utf8(kv.key).context(error::InvalidUtf8ValueSnafu)?;
+        let key = TableGlobalKey::parse(key).context(error::InvalidCatalogValueSnafu)?;
+        tables.push(key.table_name);
+    }
+    Ok(tables)
+}
+
+fn to_json_response<T: Serialize + std::fmt::Debug>(
+    status: http::StatusCode,
+    value: &T,
+) -> Result<http::Response<String>> {
+    let body = serde_json::to_string(value).context(error::SerializeToJsonSnafu {
+        input: format!("{value:?}"),
+    })?;
+    http::Response::builder()
+        .status(status)
+        .header(http::header::CONTENT_TYPE, "application/json")
+        .body(body)
+        .context(error::InvalidHttpBodySnafu)
+}
+
+fn to_error_response(e: &error::Error) -> Result<http::Response<String>> {
+    let code = e.status_code();
+    let status = match code {
+        StatusCode::InvalidArguments => http::StatusCode::BAD_REQUEST,
+        StatusCode::DatabaseNotFound => http::StatusCode::NOT_FOUND,
+        _ => http::StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    to_json_response(
+        status,
+        &ErrorResponse {
+            code: code as u32,
+            error: e.to_string(),
+            tables: vec![],
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicBool;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use api::v1::meta::{Peer, PutRequest, Region, RegionRoute, Table, TableRoute};
+    use catalog::helper::TableGlobalValue;
+    use tokio::task::JoinHandle;
+
+    use super::*;
+    use crate::handler::instruction::InstructionReply;
+    use crate::keys::InstructionReplyKey;
+    use crate::mailbox::Mailbox;
+    use crate::service::store::kv::KvStore;
+    use crate::service::store::memory::MemStore;
+
+    fn new_ctx() -> Context {
+        let kv_store: KvStoreRef = Arc::new(MemStore::new());
+        Context {
+            datanode_lease_secs: 30,
+            server_addr: "127.0.0.1:0000".to_string(),
+            in_memory: Arc::new(MemStore::new()),
+            kv_store: kv_store.clone(),
+            mailbox: Arc::new(Mailbox::new(kv_store)),
+            election: None,
+            skip_all: Arc::new(AtomicBool::new(false)),
+            catalog: None,
+            schema: None,
+            table: None,
+        }
+    }
+
+    fn schema_params(catalog: &str, schema: &str) -> HashMap<String, String> {
+        HashMap::from([
+            ("catalog_name".to_string(), catalog.to_string()),
+            ("schema_name".to_string(), schema.to_string()),
+        ])
+    }
+
+    async fn put_table(kv_store: &KvStoreRef, table_id: u32, schema: &str, table: &str) {
+        let value = format!(
+            r#"{{"node_id":1,"regions_id_map":{{"1":[0]}},"table_info":{{"ident":{{"table_id":{table_id},"version":1}},"name":"{table}","desc":null,"catalog_name":"greptime","schema_name":"{schema}","meta":{{"schema":{{"column_schemas":[],"timestamp_index":null,"version":0}},"primary_key_indices":[],"value_indices":[],"engine":"mito","next_column_id":0,"region_numbers":[0],"engine_options":{{}},"options":{{}},"created_on":"1970-01-01T00:00:00Z"}},"table_type":"Base"}}}}"#
+        );
+        let value = TableGlobalValue::parse(value).unwrap();
+        let key = TableGlobalKey {
+            catalog_name: "greptime".to_string(),
+            schema_name: schema.to_string(),
+            table_name: table.to_string(),
+        };
+        kv_store
+            .put(PutRequest {
+                key: key.to_string().into_bytes(),
+                value: value.as_bytes().unwrap(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let table_name = TableName {
+            catalog_name: "greptime".to_string(),
+            schema_name: schema.to_string(),
+            table_name: table.to_string(),
+        };
+        let route = TableRouteValue {
+            peers: vec![Peer {
+                id: 1,
+                addr: "127.0.0.1:3001".to_string(),
+            }],
+            table_route: Some(TableRoute {
+                table: Some(Table {
+                    id: table_id as u64,
+                    table_name: Some(table_name.clone()),
+                    ..Default::default()
+                }),
+                region_routes: vec![RegionRoute {
+                    region: Some(Region {
+                        id: 0,
+                        ..Default::default()
+                    }),
+                    leader_peer_index: 0,
+                    follower_peer_indexes: vec![],
+                }],
+            }),
+        };
+        kv_store
+            .put(PutRequest {
+                key: TableRouteKey::with_table_name(table_id as u64, &table_name)
+                    .key()
+                    .into_bytes(),
+                value: route.into(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+    }
+
+    async fn put_lease(kv_store: &KvStoreRef, node_id: u64) {
+        let key = LeaseKey {
+            cluster_id: 0,
+            node_id,
+        };
+        let value = LeaseValue {
+            timestamp_millis: time_util::current_time_millis(),
+            node_addr: "127.0.0.1:3001".to_string(),
+        };
+        let req = PutRequest {
+            key: key.try_into().unwrap(),
+            value: value.try_into().unwrap(),
+            ..Default::default()
+        };
+        kv_store.put(req).await.unwrap();
+    }
+
+    /// Replies the instructions sent to datanode 1 like the datanode does, returns the
+    /// tables dropped on it.
+    fn start_datanode(ctx: &Context) -> (Arc<Mutex<Vec<String>>>, JoinHandle<()>) {
+        let dropped = Arc::new(Mutex::new(vec![]));
+        let mailbox = ctx.mailbox.clone();
+        let kv_store = ctx.kv_store.clone();
+        let dropped_clone = dropped.clone();
+        let handle = tokio::spawn(async move {
+            loop {
+                for (message, _) in mailbox.pending(0, 1).await.unwrap() {
+                    if let Instruction::DropTable(region) = message.instruction {
+                        dropped_clone.lock().unwrap().push(region.table);
+                    }
+                    let key = InstructionReplyKey {
+                        cluster_id: 0,
+                        node_id: 1,
+                        id: message.id,
+                    };
+                    let req = PutRequest {
+                        key: key.into(),
+                        value: InstructionReply { error: None }.try_into().unwrap(),
+                        ..Default::default()
+                    };
+                    kv_store.put(req).await.unwrap();
+                }
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        });
+        (dropped, handle)
+    }
+
+    async fn call(
+        handler: &impl HttpHandler,
+        params: HashMap<String, String>,
+    ) -> (http::StatusCode, serde_json::Value) {
+        let res = handler.handle("", &params).await.unwrap();
+        let status = res.status();
+        (status, serde_json::from_str(res.body()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_create_schema() {
+        let ctx = new_ctx();
+        let handler = CreateSchemaHandler {
+            kv_store: ctx.kv_store.clone(),
+        };
+
+        let (status, body) = call(&handler, schema_params("greptime", "test")).await;
+        assert_eq!(http::StatusCode::CREATED, status);
+        assert_eq!(true, body["created"]);
+        let key = SchemaKey {
+            catalog_name: "greptime".to_string(),
+            schema_name: "test".to_string(),
+        };
+        let kv = ctx
+            .kv_store
+            .get(key.to_string().into_bytes())
+            .await
+            .unwrap();
+        assert!(kv.is_some());
+
+        // Creating an existing schema is idempotent.
+        let (status, body) = call(&handler, schema_params("greptime", "test")).await;
+        assert_eq!(http::StatusCode::OK, status);
+        assert_eq!(false, body["created"]);
+
+        let (status, body) = call(&handler, schema_params("greptime", "bad-name")).await;
+        assert_eq!(http::StatusCode::BAD_REQUEST, status);
+        assert!(body["error"].is_string());
+
+        let params = HashMap::from([("catalog_name".to_string(), "greptime".to_string())]);
+        let (status, _) = call(&handler, params).await;
+        assert_eq!(http::StatusCode::BAD_REQUEST, status);
+    }
+
+    #[tokio::test]
+    async fn test_create_schema_concurrently() {
+        let ctx = new_ctx();
+        let handles = (0..8)
+            .map(|_| {
+                let handler = CreateSchemaHandler {
+                    kv_store: ctx.kv_store.clone(),
+                };
+                tokio::spawn(async move { call(&handler, schema_params("greptime", "test")).await })
+            })
+            .collect::<Vec<_>>();
+
+        let mut created = 0;
+        for handle in handles {
+            let (status, body) = handle.await.unwrap();
+            assert!(status.is_success());
+            if body["created"] == true {
+                created += 1;
+            }
+        }
+        assert_eq!(1, created);
+    }
+
+    #[tokio::test]
+    async fn test_drop_schema() {
+        let ctx = new_ctx();
+        let create = CreateSchemaHandler {
+            kv_store: ctx.kv_store.clone(),
+        };
+        let drop = DropSchemaHandler { ctx: ctx.clone() };
+
+        let (status, _) = call(&drop, schema_params("greptime", "test")).await;
+        assert_eq!(http::StatusCode::NOT_FOUND, status);
+
+        let _ = call(&create, schema_params("greptime", "test")).await;
+        let _ = call(&create, schema_params("greptime", "test_other")).await;
+        put_table(&ctx.kv_store, 1024, "test", "t1").await;
+        put_table(&ctx.kv_store, 1025, "test", "t2").await;
+        put_table(&ctx.kv_store, 1026, "test_other", "t3").await;
+
+        // Refuses to drop a non-empty schema.
+        let (status, body) = call(&drop, schema_params("greptime", "test")).await;
+        assert_eq!(http::StatusCode::CONFLICT, status);
+        assert_eq!(serde_json::json!(["t1", "t2"]), body["tables"]);
+        let mut params = schema_params("greptime", "test");
+        params.insert("cascade".to_string(), "false".to_string());
+        let (status, _) = call(&drop, params).await;
+        assert_eq!(http::StatusCode::CONFLICT, status);
+
+        let mut params = schema_params("greptime", "test");
+        params.insert("cascade".to_string(), "maybe".to_string());
+        let (status, _) = call(&drop, params).await;
+        assert_eq!(http::StatusCode::BAD_REQUEST, status);
+
+        let key = SchemaKey {
+            catalog_name: "greptime".to_string(),
+            schema_name: "test".to_string(),
+        };
+        // The tables are kept if their regions can't be dropped as the datanode is dead.
+        let mut params = schema_params("greptime", "test");
+        params.insert("cascade".to_string(), "true".to_string());
+        let (status, _) = call(&drop, params.clone()).await;
+        assert_eq!(http::StatusCode::INTERNAL_SERVER_ERROR, status);
+        assert_eq!(
+            vec!["t1", "t2"],
+            list_tables(&ctx.kv_store, &key).await.unwrap()
+        );
+        assert!(ctx.mailbox.pending(0, 1).await.unwrap().is_empty());
+
+        put_lease(&ctx.kv_store, 1).await;
+        let (dropped, handle) = start_datanode(&ctx);
+        let (status, body) = call(&drop, params).await;
+        handle.abort();
+        assert_eq!(http::StatusCode::OK, status);
+        assert_eq!(serde_json::json!(["t1", "t2"]), body["dropped_tables"]);
+        // The tables are dropped on the datanode before their metadata is removed.
+        assert_eq!(vec!["t1", "t2"], *dropped.lock().unwrap());
+        assert!(list_tables(&ctx.kv_store, &key).await.unwrap().is_empty());
+        let kv = ctx
+            .kv_store
+            .get(key.to_string().into_bytes())
+            .await
+            .unwrap();
+        assert!(kv.is_none());
+        // Tables of other schemas are kept.
+        let other = SchemaKey {
+            catalog_name: "greptime".to_string(),
+            schema_name: "test_other".to_string(),
+        };
+        assert_eq!(
+            vec!["t3"],
+            list_tables(&ctx.kv_store, &other).await.unwrap()
+        );
+
+        // An empty schema is dropped without cascade.
+        let _ = call(&create, schema_params("greptime", "test")).await;
+        let (status, body) = call(&drop, schema_params("greptime", "test")).await;
+        assert_eq!(http::StatusCode::OK, status);
+        assert_eq!(serde_json::json!([]), body["dropped_tables"]);
+    }
+}
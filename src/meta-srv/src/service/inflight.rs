@@ -0,0 +1,195 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures::future::BoxFuture;
+use tokio::sync::Notify;
+use tower::{Layer, Service};
+
+/// Counts requests that are being served, so shutdown can wait for them.
+#[derive(Clone, Default)]
+pub struct InflightRequests {
+    inner: Arc<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    count: AtomicUsize,
+    idle: Notify,
+}
+
+impl InflightRequests {
+    pub fn count(&self) -> usize {
+        self.inner.count.load(Ordering::Relaxed)
+    }
+
+    /// Waits until no request is in flight or `timeout` elapses, returns the number
+    /// of requests still pending.
+    pub async fn wait_idle(&self, timeout: Duration) -> usize {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            // Registers before checking the count, so a notification between them is
+            // not lost.
+            let idle = self.inner.idle.notified();
+            let count = self.count();
+            if count == 0 {
+                return 0;
+            }
+
+            tokio::select! {
+                _ = idle => {}
+                _ = tokio::time::sleep_until(deadline) => return self.count(),
+            }
+        }
+    }
+
+    fn enter(&self) -> InflightGuard {
+        self.inner.count.fetch_add(1, Ordering::Relaxed);
+        InflightGuard {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+struct InflightGuard {
+    inner: Arc<Inner>,
+}
+
+impl Drop for InflightGuard {
+    fn drop(&mut self) {
+        if self.inner.count.fetch_sub(1, Ordering::Relaxed) == 1 {
+            self.inner.idle.notify_waiters();
+        }
+    }
+}
+
+/// Layer that tracks requests of the wrapped service in [InflightRequests].
+///
+/// A request is in flight until its response future completes or is dropped. For
+/// streaming responses, the stream body is not tracked.
+#[derive(Clone)]
+pub struct InflightLayer {
+    inflight: InflightRequests,
+}
+
+impl InflightLayer {
+    pub fn new(inflight: InflightRequests) -> Self {
+        Self { inflight }
+    }
+}
+
+impl<S> Layer<S> for InflightLayer {
+    type Service = InflightService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        InflightService {
+            inner,
+            inflight: self.inflight.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct InflightService<S> {
+    inner: S,
+    inflight: InflightRequests,
+}
+
+impl<S, Req> Service<Req> for InflightService<S>
+where
+    S: Service<Req>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<S::Response, S::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Req) -> Self::Future {
+        let guard = self.inflight.enter();
+        let fut = self.inner.call(req);
+        Box::pin(async move {
+            let _guard = guard;
+            fut.await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use tower::{service_fn, ServiceExt};
+
+    use super::*;
+
+    fn slow_service(
+        inflight: InflightRequests,
+        delay: Duration,
+    ) -> impl Service<(), Response = (), Error = Infallible, Future = impl Send> + Send + 'static
+    {
+        InflightLayer::new(inflight).layer(service_fn(move |_: ()| async move {
+            tokio::time::sleep(delay).await;
+            Ok::<_, Infallible>(())
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_slow_request_completes_within_drain() {
+        let inflight = InflightRequests::default();
+        let service = slow_service(inflight.clone(), Duration::from_millis(200));
+
+        let request = tokio::spawn(service.oneshot(()));
+        // Waits for the request to start.
+        while inflight.count() == 0 {
+            tokio::task::yield_now().await;
+        }
+
+        let pending = inflight.wait_idle(Duration::from_secs(5)).await;
+        assert_eq!(0, pending);
+        request.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_drain_deadline() {
+        let inflight = InflightRequests::default();
+        let service = slow_service(inflight.clone(), Duration::from_secs(60));
+
+        let request = tokio::spawn(service.oneshot(()));
+        while inflight.count() == 0 {
+            tokio::task::yield_now().await;
+        }
+
+        let pending = inflight.wait_idle(Duration::from_millis(50)).await;
+        assert_eq!(1, pending);
+
+        // Dropping the request also leaves the in-flight set.
+        request.abort();
+        let _ = request.await;
+        assert_eq!(0, inflight.count());
+    }
+
+    #[tokio::test]
+    async fn test_wait_idle_without_requests() {
+        let inflight = InflightRequests::default();
+        assert_eq!(0, inflight.wait_idle(Duration::from_secs(5)).await);
+    }
+}
@@ -16,8 +16,8 @@ use std::collections::HashMap;
 
 use api::v1::meta::{
     router_server, BatchPutRequest, CreateRequest, DeleteRequest, Error, KeyValue,
-    MoveValueRequest, Peer, PeerDict, Region, RegionRoute, ResponseHeader, RouteRequest,
-    RouteResponse, Table, TableName, TableRoute, TableRouteValue,
+    MoveValueRequest, Peer, PeerDict, RangeRequest, Region, RegionRoute, ResponseHeader,
+    RouteRequest, RouteResponse, Table, TableName, TableRoute, TableRouteValue,
 };
 use catalog::helper::{TableGlobalKey, TableGlobalValue};
 use common_telemetry::warn;
@@ -25,7 +25,6 @@ use snafu::{OptionExt, ResultExt};
 use table::metadata::RawTableInfo;
 use tonic::{Request, Response};
 
-use crate::error;
 use crate::error::Result;
 use crate::keys::TableRouteKey;
 use crate::metasrv::{Context, MetaSrv, SelectorRef};
@@ -33,6 +32,7 @@ use crate::sequence::SequenceRef;
 use crate::service::store::ext::KvStoreExt;
 use crate::service::store::kv::KvStoreRef;
 use crate::service::GrpcResult;
+use crate::{error, util};
 
 #[async_trait::async_trait]
 impl router_server::Router for MetaSrv {
@@ -69,7 +69,7 @@ impl router_server::Router for MetaSrv {
 }
 
 impl MetaSrv {
-    fn create_ctx(&self, table_name: TableName) -> Context {
+    pub(crate) fn create_ctx(&self, table_name: TableName) -> Context {
         let mut ctx = self.new_ctx();
         let TableName {
             catalog_name,
@@ -83,7 +83,7 @@ impl MetaSrv {
     }
 }
 
-async fn handle_create(
+pub(crate) async fn handle_create(
     req: CreateRequest,
     ctx: Context,
     selector: SelectorRef,
@@ -106,7 +106,10 @@ async fn handle_create(
         })?;
 
     let cluster_id = header.as_ref().map_or(0, |h| h.cluster_id);
-    let peers = selector.select(cluster_id, &ctx).await?;
+    // Each region is placed on a distinct datanode if there are enough datanodes.
+    let peers = selector
+        .select_n(cluster_id, &ctx, partitions.len().max(1))
+        .await?;
     if peers.is_empty() {
         let header = Some(ResponseHeader::failed(
             cluster_id,
@@ -235,7 +238,7 @@ fn create_table_global_value(
     })
 }
 
-async fn handle_route(req: RouteRequest, ctx: Context) -> Result<RouteResponse> {
+pub(crate) async fn handle_route(req: RouteRequest, ctx: Context) -> Result<RouteResponse> {
     let RouteRequest {
         header,
         table_names,
@@ -257,7 +260,7 @@ async fn handle_route(req: RouteRequest, ctx: Context) -> Result<RouteResponse>
     })
 }
 
-async fn handle_delete(req: DeleteRequest, ctx: Context) -> Result<RouteResponse> {
+pub(crate) async fn handle_delete(req: DeleteRequest, ctx: Context) -> Result<RouteResponse> {
     let DeleteRequest { header, table_name } = req;
     let cluster_id = header.as_ref().map_or(0, |h| h.cluster_id);
     let tgk = table_name
@@ -288,6 +291,78 @@ async fn handle_delete(req: DeleteRequest, ctx: Context) -> Result<RouteResponse
     })
 }
 
+/// A page of table routes, see [list_table_routes].
+#[derive(Debug)]
+pub(crate) struct TableRoutePage {
+    pub table_routes: Vec<TableRouteValue>,
+    /// Passed to the next call to get the next page, `None` if this is the last page.
+    pub next_page_token: Option<String>,
+}
+
+/// Lists routes of tables in `catalog` and `schema` in the order of their route keys,
+/// at most `limit` routes a page, no limit if `limit` is 0.
+///
+/// The page token is the last route key of the previous page, so it only depends on the
+/// data in the kv store and is still valid after the leader changes. Each page is read
+/// from a single revision of the kv store, but different pages may be read from different
+/// revisions: tables created or dropped while listing may be missing from the result.
+pub(crate) async fn list_table_routes(
+    kv_store: &KvStoreRef,
+    catalog: Option<&str>,
+    schema: Option<&str>,
+    page_token: Option<&str>,
+    limit: usize,
+) -> Result<TableRoutePage> {
+    let prefix = TableRouteKey::list_prefix(catalog, schema);
+    let range_end = util::get_prefix_end_key(prefix.as_bytes());
+    let key = match page_token {
+        // Starts from the key right after the token.
+        Some(token) if token.starts_with(&prefix) => format!("{token}\0"),
+        _ => prefix,
+    };
+    let req = RangeRequest {
+        key: key.into_bytes(),
+        range_end,
+        limit: limit as i64,
+        ..Default::default()
+    };
+    let res = kv_store.range(req).await?;
+
+    let next_page_token = match res.kvs.last() {
+        Some(kv) if res.more => {
+            Some(String::from_utf8(kv.key.clone()).context(error::InvalidUtf8ValueSnafu)?)
+        }
+        _ => None,
+    };
+    let mut table_routes = Vec::with_capacity(res.kvs.len());
+    for kv in res.kvs {
+        let trv: TableRouteValue = kv
+            .value
+            .as_slice()
+            .try_into()
+            .context(error::DecodeTableRouteSnafu)?;
+        // Names may contain '-', so the prefix may match tables of other schemas.
+        let matched = trv
+            .table_route
+            .as_ref()
+            .and_then(|route| route.table.as_ref())
+            .and_then(|table| table.table_name.as_ref())
+            .map(|name| {
+                catalog.map_or(true, |catalog| name.catalog_name == catalog)
+                    && schema.map_or(true, |schema| name.schema_name == schema)
+            })
+            .unwrap_or(false);
+        if matched {
+            table_routes.push(trv);
+        }
+    }
+
+    Ok(TableRoutePage {
+        table_routes,
+        next_page_token,
+    })
+}
+
 fn fill_table_routes(
     tables: Vec<(TableGlobalValue, TableRouteValue)>,
 ) -> Result<(Vec<Peer>, Vec<TableRoute>)> {
@@ -346,7 +421,7 @@ async fn fetch_tables(
     Ok(tables)
 }
 
-async fn get_table_route_value(
+pub(crate) async fn get_table_route_value(
     kv_store: &KvStoreRef,
     key: &TableRouteKey<'_>,
 ) -> Result<TableRouteValue> {
@@ -394,7 +469,7 @@ async fn remove_table_global_value(
     Ok((kv.0, value))
 }
 
-async fn get_table_global_value(
+pub(crate) async fn get_table_global_value(
     kv_store: &KvStoreRef,
     key: &TableGlobalKey,
 ) -> Result<Option<TableGlobalValue>> {
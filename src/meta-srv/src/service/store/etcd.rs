@@ -23,14 +23,19 @@ use common_error::prelude::*;
 use common_telemetry::warn;
 use etcd_client::{
     Client, Compare, CompareOp, DeleteOptions, GetOptions, PutOptions, Txn, TxnOp, TxnOpResponse,
+    TxnResponse,
 };
 
+use crate::election::{encode_term, LEADER_TERM_KEY};
 use crate::error;
 use crate::error::Result;
+use crate::metasrv::ElectionRef;
 use crate::service::store::kv::{KvStore, KvStoreRef};
 
 pub struct EtcdStore {
     client: Client,
+    /// Writes are rejected unless the term of the election is the latest leader term.
+    fence: Option<ElectionRef>,
 }
 
 impl EtcdStore {
@@ -47,7 +52,41 @@ impl EtcdStore {
     }
 
     pub fn with_etcd_client(client: Client) -> Result<KvStoreRef> {
-        Ok(Arc::new(Self { client }))
+        Ok(Arc::new(Self {
+            client,
+            fence: None,
+        }))
+    }
+
+    /// Creates a store whose writes are fenced by the leader term of `election`, i.e. they
+    /// fail once a newer leader is elected, even if this node still believes it's the leader.
+    pub fn with_fenced_etcd_client(client: Client, election: ElectionRef) -> Result<KvStoreRef> {
+        Ok(Arc::new(Self {
+            client,
+            fence: Some(election),
+        }))
+    }
+
+    /// Runs `txn`, as a part of a txn that checks the leader term first if the store is fenced.
+    async fn txn(&self, txn: Txn) -> Result<TxnResponse> {
+        let mut client = self.client.kv_client();
+        let Some(election) = &self.fence else {
+            return client.txn(txn).await.context(error::EtcdFailedSnafu);
+        };
+
+        let term = election.term();
+        let compare = Compare::value(LEADER_TERM_KEY, CompareOp::Equal, encode_term(term));
+        let fenced = Txn::new().when(vec![compare]).and_then(vec![TxnOp::txn(txn)]);
+        let txn_res = client.txn(fenced).await.context(error::EtcdFailedSnafu)?;
+        ensure!(txn_res.succeeded(), error::StaleLeaderTermSnafu { term });
+
+        match txn_res.op_responses().pop() {
+            Some(TxnOpResponse::Txn(res)) => Ok(res),
+            _ => error::InvalidTxnResultSnafu {
+                err_msg: "expect the response of the fenced txn",
+            }
+            .fail(),
+        }
     }
 }
 
@@ -85,14 +124,13 @@ impl KvStore for EtcdStore {
             options,
         } = req.try_into()?;
 
-        let res = self
-            .client
-            .kv_client()
-            .put(key, value, options)
-            .await
-            .context(error::EtcdFailedSnafu)?;
+        let put = TxnOp::put(key, value, options);
+        let txn_res = self.txn(Txn::new().and_then(vec![put])).await?;
 
-        let prev_kv = res.prev_key().map(KvPair::to_kv);
+        let prev_kv = match txn_res.op_responses().pop() {
+            Some(TxnOpResponse::Put(res)) => res.prev_key().map(KvPair::to_kv),
+            _ => unreachable!(), // never get here
+        };
 
         let header = Some(ResponseHeader::success(cluster_id));
         Ok(PutResponse { header, prev_kv })
@@ -145,12 +183,7 @@ impl KvStore for EtcdStore {
             .collect::<Vec<_>>();
         let txn = Txn::new().and_then(put_ops);
 
-        let txn_res = self
-            .client
-            .kv_client()
-            .txn(txn)
-            .await
-            .context(error::EtcdFailedSnafu)?;
+        let txn_res = self.txn(txn).await?;
 
         let mut prev_kvs = vec![];
         for op_res in txn_res.op_responses() {
@@ -192,12 +225,7 @@ impl KvStore for EtcdStore {
             .and_then(vec![put])
             .or_else(vec![get]);
 
-        let txn_res = self
-            .client
-            .kv_client()
-            .txn(txn)
-            .await
-            .context(error::EtcdFailedSnafu)?;
+        let txn_res = self.txn(txn).await?;
 
         let success = txn_res.succeeded();
         let op_res = txn_res
@@ -228,13 +256,13 @@ impl KvStore for EtcdStore {
             options,
         } = req.try_into()?;
 
-        let res = self
-            .client
-            .kv_client()
-            .delete(key, options)
-            .await
-            .context(error::EtcdFailedSnafu)?;
+        let delete = TxnOp::delete(key, options);
+        let txn_res = self.txn(Txn::new().and_then(vec![delete])).await?;
 
+        let res = match txn_res.op_responses().pop() {
+            Some(TxnOpResponse::Delete(res)) => res,
+            _ => unreachable!(), // never get here
+        };
         let prev_kvs = res.prev_kvs().iter().map(KvPair::to_kv).collect::<Vec<_>>();
 
         let header = Some(ResponseHeader::success(cluster_id));
@@ -285,7 +313,7 @@ impl KvStore for EtcdStore {
                 }
             };
 
-            let txn_res = client.txn(txn).await.context(error::EtcdFailedSnafu)?;
+            let txn_res = self.txn(txn).await?;
 
             if !txn_res.succeeded() {
                 warn!(
@@ -562,6 +590,9 @@ impl<'a> From<KvPair<'a>> for KeyValue {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::election::etcd::increase_term;
+    use crate::election::Election;
+    use crate::metasrv::LeaderValue;
 
     #[test]
     fn test_parse_get() {
@@ -674,4 +705,77 @@ mod tests {
         assert_eq!(b"test_to_key".to_vec(), move_value.to_key);
         assert!(move_value.delete_options.is_some());
     }
+
+    struct TermElection(u64);
+
+    #[async_trait::async_trait]
+    impl Election for TermElection {
+        type Leader = LeaderValue;
+
+        fn is_leader(&self) -> bool {
+            true
+        }
+
+        fn term(&self) -> u64 {
+            self.0
+        }
+
+        fn lease_expire_at_millis(&self) -> i64 {
+            i64::MAX
+        }
+
+        fn in_infancy(&self) -> bool {
+            false
+        }
+
+        async fn campaign(&self) -> Result<()> {
+            unreachable!()
+        }
+
+        async fn leader(&self) -> Result<LeaderValue> {
+            unreachable!()
+        }
+
+        async fn resign(&self) -> Result<()> {
+            unreachable!()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fenced_writes() {
+        // Requires an etcd server, e.g. GT_ETCD_ENDPOINTS=127.0.0.1:2379.
+        let Ok(endpoints) = std::env::var("GT_ETCD_ENDPOINTS") else {
+            return;
+        };
+        let client = Client::connect(endpoints.split(',').collect::<Vec<_>>(), None)
+            .await
+            .unwrap();
+        let put = || PutRequest {
+            key: b"__test_fenced_writes".to_vec(),
+            value: b"v".to_vec(),
+            ..Default::default()
+        };
+
+        let old_term = increase_term(&client).await.unwrap();
+        let old_leader =
+            EtcdStore::with_fenced_etcd_client(client.clone(), Arc::new(TermElection(old_term)))
+                .unwrap();
+        old_leader.put(put()).await.unwrap();
+
+        // A new leader is elected while the old one is paused, e.g. by a long GC.
+        let new_term = increase_term(&client).await.unwrap();
+        let new_leader =
+            EtcdStore::with_fenced_etcd_client(client.clone(), Arc::new(TermElection(new_term)))
+                .unwrap();
+
+        let err = old_leader.put(put()).await.unwrap_err();
+        assert!(matches!(err, error::Error::StaleLeaderTerm { term, .. } if term == old_term));
+        new_leader.put(put()).await.unwrap();
+
+        let req = DeleteRangeRequest {
+            key: b"__test_fenced_writes".to_vec(),
+            ..Default::default()
+        };
+        new_leader.delete_range(req).await.unwrap();
+    }
 }
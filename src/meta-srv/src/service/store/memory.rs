@@ -71,6 +71,15 @@ impl KvStore for MemStore {
                     value: if keys_only { vec![] } else { v.clone() },
                 }]
             })
+        } else if range_end == [0] {
+            // As etcd does, "\0" as range_end means all keys >= key.
+            memory
+                .range(key..)
+                .map(|kv| KeyValue {
+                    key: kv.0.clone(),
+                    value: if keys_only { vec![] } else { kv.1.clone() },
+                })
+                .collect::<Vec<_>>()
         } else {
             let range = Range {
                 start: key,
@@ -85,12 +94,10 @@ impl KvStore for MemStore {
                 .collect::<Vec<_>>()
         };
 
-        let more = if limit > 0 {
+        let more = limit > 0 && kvs.len() > limit as usize;
+        if more {
             kvs.truncate(limit as usize);
-            true
-        } else {
-            false
-        };
+        }
 
         let cluster_id = header.map_or(0, |h| h.cluster_id);
         let header = Some(ResponseHeader::success(cluster_id));
@@ -0,0 +1,351 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Snapshot and restore of the metadata stored in metasrv's kv store.
+//!
+//! A snapshot file is laid out as:
+//!
+//! ```text
+//! +-------+---------+-------+----------------------------------------------+-------+
+//! | magic | version | count | (key_len, key, value_len, value) * count     | crc32 |
+//! | 4B    | u32     | u64   | u32, [u8], u32, [u8]                         | u32   |
+//! +-------+---------+-------+----------------------------------------------+-------+
+//! ```
+//!
+//! All integers are little endian, the checksum covers all bytes before it.
+
+use api::v1::meta::{BatchPutRequest, DeleteRangeRequest, KeyValue, PutRequest, RangeRequest};
+use common_telemetry::info;
+use crc::{Crc, CRC_32_ISCSI};
+use snafu::ensure;
+
+use crate::election::ELECTION_KEY;
+use crate::error::{self, Result};
+use crate::keys::{DN_LEASE_PREFIX, DN_STAT_PREFIX};
+use crate::service::store::kv::KvStoreRef;
+
+const MAGIC: &[u8; 4] = b"GTMS";
+pub const SNAPSHOT_VERSION: u32 = 1;
+const CRC32: Crc<u32> = Crc::<u32>::new(&CRC_32_ISCSI);
+
+/// Key written before restoring and removed once all keys are restored, a store
+/// containing this key is half-restored.
+pub const RESTORE_MARKER_KEY: &str = "__meta_restore_in_progress";
+
+/// Max number of keys written in one batch, etcd limits the number of operations
+/// in a transaction to 128 by default.
+const RESTORE_BATCH_SIZE: usize = 128;
+
+/// Prefixes of the keys that are runtime states and should not be backed up.
+const EXCLUDED_PREFIXES: [&str; 4] = [
+    DN_LEASE_PREFIX,
+    DN_STAT_PREFIX,
+    ELECTION_KEY,
+    RESTORE_MARKER_KEY,
+];
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Snapshot {
+    pub kvs: Vec<KeyValue>,
+}
+
+impl Snapshot {
+    /// Takes a snapshot of all keys in `kv_store` except runtime states.
+    ///
+    /// All keys are read by a single range request, so they are from the same revision.
+    pub async fn take(kv_store: &KvStoreRef) -> Result<Snapshot> {
+        let req = RangeRequest {
+            key: vec![0],
+            range_end: vec![0],
+            ..Default::default()
+        };
+        let kvs = kv_store
+            .range(req)
+            .await?
+            .kvs
+            .into_iter()
+            .filter(|kv| !is_excluded(&kv.key))
+            .collect::<Vec<_>>();
+
+        Ok(Snapshot { kvs })
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(MAGIC);
+        buf.extend_from_slice(&SNAPSHOT_VERSION.to_le_bytes());
+        buf.extend_from_slice(&(self.kvs.len() as u64).to_le_bytes());
+        for kv in &self.kvs {
+            buf.extend_from_slice(&(kv.key.len() as u32).to_le_bytes());
+            buf.extend_from_slice(&kv.key);
+            buf.extend_from_slice(&(kv.value.len() as u32).to_le_bytes());
+            buf.extend_from_slice(&kv.value);
+        }
+        let crc = CRC32.checksum(&buf);
+        buf.extend_from_slice(&crc.to_le_bytes());
+        buf
+    }
+
+    pub fn decode(data: &[u8]) -> Result<Snapshot> {
+        ensure!(
+            data.len() >= MAGIC.len() + 4 + 8 + 4,
+            error::InvalidSnapshotSnafu {
+                reason: format!("snapshot is too short, len: {}", data.len()),
+            }
+        );
+
+        let (body, crc) = data.split_at(data.len() - 4);
+        let expected = u32::from_le_bytes(crc.try_into().unwrap());
+        let actual = CRC32.checksum(body);
+        ensure!(
+            expected == actual,
+            error::InvalidSnapshotSnafu {
+                reason: format!("checksum mismatch, expected: {expected}, actual: {actual}"),
+            }
+        );
+
+        let mut reader = Reader { buf: body };
+        ensure!(
+            reader.read_bytes(MAGIC.len())? == MAGIC,
+            error::InvalidSnapshotSnafu {
+                reason: "bad magic number",
+            }
+        );
+        let version = reader.read_u32()?;
+        ensure!(
+            version == SNAPSHOT_VERSION,
+            error::InvalidSnapshotSnafu {
+                reason: format!("unsupported version {version}"),
+            }
+        );
+
+        let count = reader.read_u64()?;
+        let mut kvs = Vec::new();
+        for _ in 0..count {
+            let key_len = reader.read_u32()? as usize;
+            let key = reader.read_bytes(key_len)?.to_vec();
+            let value_len = reader.read_u32()? as usize;
+            let value = reader.read_bytes(value_len)?.to_vec();
+            kvs.push(KeyValue { key, value });
+        }
+        ensure!(
+            reader.buf.is_empty(),
+            error::InvalidSnapshotSnafu {
+                reason: format!("{} trailing bytes", reader.buf.len()),
+            }
+        );
+
+        Ok(Snapshot { kvs })
+    }
+
+    /// Restores the snapshot into `kv_store`.
+    ///
+    /// The store must be empty unless `force` is set. A marker key is kept in the store
+    /// during restoring, so an interrupted restore can be detected by
+    /// [is_restore_incomplete].
+    pub async fn restore(&self, kv_store: &KvStoreRef, force: bool) -> Result<()> {
+        if !force {
+            ensure!(
+                !is_restore_incomplete(kv_store).await?,
+                error::IncompleteRestoreSnafu
+            );
+            ensure!(is_empty(kv_store).await?, error::RestoreTargetNotEmptySnafu);
+        }
+
+        kv_store
+            .put(PutRequest {
+                key: RESTORE_MARKER_KEY.as_bytes().to_vec(),
+                value: SNAPSHOT_VERSION.to_string().into_bytes(),
+                ..Default::default()
+            })
+            .await?;
+
+        for kvs in self.kvs.chunks(RESTORE_BATCH_SIZE) {
+            kv_store
+                .batch_put(BatchPutRequest {
+                    kvs: kvs.to_vec(),
+                    ..Default::default()
+                })
+                .await?;
+        }
+
+        kv_store
+            .delete_range(DeleteRangeRequest {
+                key: RESTORE_MARKER_KEY.as_bytes().to_vec(),
+                ..Default::default()
+            })
+            .await?;
+
+        info!("Restored {} keys from snapshot", self.kvs.len());
+        Ok(())
+    }
+}
+
+/// Returns true if a restore was started on `kv_store` but not finished.
+pub async fn is_restore_incomplete(kv_store: &KvStoreRef) -> Result<bool> {
+    let req = RangeRequest {
+        key: RESTORE_MARKER_KEY.as_bytes().to_vec(),
+        ..Default::default()
+    };
+    Ok(!kv_store.range(req).await?.kvs.is_empty())
+}
+
+async fn is_empty(kv_store: &KvStoreRef) -> Result<bool> {
+    let req = RangeRequest {
+        key: vec![0],
+        range_end: vec![0],
+        limit: 1,
+        keys_only: true,
+        ..Default::default()
+    };
+    Ok(kv_store.range(req).await?.kvs.is_empty())
+}
+
+fn is_excluded(key: &[u8]) -> bool {
+    EXCLUDED_PREFIXES
+        .iter()
+        .any(|prefix| key.starts_with(prefix.as_bytes()))
+}
+
+struct Reader<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn read_bytes(&mut self, len: usize) -> Result<&'a [u8]> {
+        ensure!(
+            self.buf.len() >= len,
+            error::InvalidSnapshotSnafu {
+                reason: "unexpected end of snapshot",
+            }
+        );
+        let (bytes, rest) = self.buf.split_at(len);
+        self.buf = rest;
+        Ok(bytes)
+    }
+
+    fn read_u32(&mut self) -> Result<u32> {
+        let bytes = self.read_bytes(4)?;
+        Ok(u32::from_le_bytes(bytes.try_into().unwrap()))
+    }
+
+    fn read_u64(&mut self) -> Result<u64> {
+        let bytes = self.read_bytes(8)?;
+        Ok(u64::from_le_bytes(bytes.try_into().unwrap()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::error::Error;
+    use crate::service::store::memory::MemStore;
+
+    async fn put(kv_store: &KvStoreRef, key: &str, value: &str) {
+        kv_store
+            .put(PutRequest {
+                key: key.as_bytes().to_vec(),
+                value: value.as_bytes().to_vec(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+    }
+
+    async fn all_kvs(kv_store: &KvStoreRef) -> Vec<KeyValue> {
+        let req = RangeRequest {
+            key: vec![0],
+            range_end: vec![0],
+            ..Default::default()
+        };
+        kv_store.range(req).await.unwrap().kvs
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_round_trip() {
+        let source = Arc::new(MemStore::new()) as KvStoreRef;
+        put(&source, "__c-greptime", "{}").await;
+        put(&source, "__s-greptime-public", "{}").await;
+        put(&source, "__meta_table_route-greptime-public-demo", "route").await;
+        put(&source, "__meta_seq-table_id", "1024").await;
+        put(&source, &format!("{DN_LEASE_PREFIX}-0-1"), "lease").await;
+        put(&source, &format!("{DN_STAT_PREFIX}-0-1"), "stat").await;
+        put(&source, &format!("{ELECTION_KEY}-leader"), "127.0.0.1:3002").await;
+
+        let snapshot = Snapshot::take(&source).await.unwrap();
+        assert_eq!(4, snapshot.kvs.len());
+
+        let decoded = Snapshot::decode(&snapshot.encode()).unwrap();
+        assert_eq!(snapshot, decoded);
+
+        let target = Arc::new(MemStore::new()) as KvStoreRef;
+        decoded.restore(&target, false).await.unwrap();
+        assert!(!is_restore_incomplete(&target).await.unwrap());
+        assert_eq!(snapshot.kvs, all_kvs(&target).await);
+    }
+
+    #[tokio::test]
+    async fn test_restore_to_non_empty_store() {
+        let snapshot = Snapshot {
+            kvs: vec![KeyValue {
+                key: b"__c-greptime".to_vec(),
+                value: b"{}".to_vec(),
+            }],
+        };
+
+        let target = Arc::new(MemStore::new()) as KvStoreRef;
+        put(&target, "__c-other", "{}").await;
+        let err = snapshot.restore(&target, false).await.unwrap_err();
+        assert!(matches!(err, Error::RestoreTargetNotEmpty { .. }));
+
+        snapshot.restore(&target, true).await.unwrap();
+        assert_eq!(2, all_kvs(&target).await.len());
+    }
+
+    #[tokio::test]
+    async fn test_detect_incomplete_restore() {
+        let target = Arc::new(MemStore::new()) as KvStoreRef;
+        // Simulates a restore interrupted after writing some keys.
+        put(&target, RESTORE_MARKER_KEY, "1").await;
+        put(&target, "__c-greptime", "{}").await;
+        assert!(is_restore_incomplete(&target).await.unwrap());
+
+        let snapshot = Snapshot::default();
+        let err = snapshot.restore(&target, false).await.unwrap_err();
+        assert!(matches!(err, Error::IncompleteRestore { .. }));
+
+        snapshot.restore(&target, true).await.unwrap();
+        assert!(!is_restore_incomplete(&target).await.unwrap());
+    }
+
+    #[test]
+    fn test_decode_corrupted_snapshot() {
+        let snapshot = Snapshot {
+            kvs: vec![KeyValue {
+                key: b"key".to_vec(),
+                value: b"value".to_vec(),
+            }],
+        };
+        let mut data = snapshot.encode();
+
+        let err = Snapshot::decode(&data[..10]).unwrap_err();
+        assert!(matches!(err, Error::InvalidSnapshot { .. }));
+
+        data[MAGIC.len() + 12] ^= 0xff;
+        let err = Snapshot::decode(&data).unwrap_err();
+        assert!(err.to_string().contains("checksum mismatch"), "{err}");
+    }
+}
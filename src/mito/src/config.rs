@@ -14,5 +14,38 @@
 
 //! Table Engine config
 
-#[derive(Debug, Clone, Default)]
-pub struct EngineConfig {}
+use std::time::Duration;
+
+/// Default initial interval to retry opening a region that failed to open.
+const DEFAULT_REGION_OPEN_RETRY_INTERVAL: Duration = Duration::from_secs(1);
+/// Default max time to wait for the files of a dropped table to be deleted.
+const DEFAULT_DROP_SYNC_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Clone)]
+pub struct EngineConfig {
+    /// Max number of regions opened concurrently.
+    pub region_open_parallelism: usize,
+    /// Initial interval to retry opening a region that failed to open, the interval
+    /// is doubled after each failed retry.
+    pub region_open_retry_interval: Duration,
+    /// Max time to wait for the files of a table to be deleted by `DROP TABLE ... SYNC`.
+    pub drop_sync_timeout: Duration,
+}
+
+impl Default for EngineConfig {
+    fn default() -> Self {
+        Self {
+            region_open_parallelism: default_region_open_parallelism(),
+            region_open_retry_interval: DEFAULT_REGION_OPEN_RETRY_INTERVAL,
+            drop_sync_timeout: DEFAULT_DROP_SYNC_TIMEOUT,
+        }
+    }
+}
+
+/// Returns the default number of regions opened concurrently, which is twice the
+/// number of CPUs as opening a region is mostly waiting for IO.
+pub fn default_region_open_parallelism() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get() * 2)
+        .unwrap_or(8)
+}
@@ -12,12 +12,15 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod lock;
 mod procedure;
+mod region_opener;
 #[cfg(test)]
 mod tests;
 
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
+use std::time::Duration;
 
 use async_trait::async_trait;
 use common_catalog::format_full_table_name;
@@ -30,24 +33,30 @@ use object_store::ObjectStore;
 use snafu::{ensure, OptionExt, ResultExt};
 use store_api::storage::{
     ColumnDescriptorBuilder, ColumnFamilyDescriptor, ColumnFamilyDescriptorBuilder, ColumnId,
-    CreateOptions, EngineContext as StorageEngineContext, OpenOptions, Region,
-    RegionDescriptorBuilder, RowKeyDescriptor, RowKeyDescriptorBuilder, StorageEngine,
+    CreateOptions, DropOptions, EngineContext as StorageEngineContext, FlushContext, OpenOptions,
+    Region, RegionDescriptorBuilder, RegionNumber, RowKeyDescriptor, RowKeyDescriptorBuilder,
+    StorageEngine,
 };
 use table::engine::{
     region_id, region_name, table_dir, EngineContext, TableEngine, TableEngineProcedure,
     TableReference,
 };
 use table::error::TableOperationSnafu;
-use table::metadata::{TableInfo, TableInfoBuilder, TableMetaBuilder, TableType, TableVersion};
+use table::metadata::{
+    TableId, TableInfo, TableInfoBuilder, TableMetaBuilder, TableType, TableVersion,
+};
 use table::requests::{
-    AlterKind, AlterTableRequest, CreateTableRequest, DropTableRequest, OpenTableRequest,
+    AlterKind, AlterTableRequest, CloseRegionsRequest, CreateTableRequest, DropTableRequest,
+    OpenRegionsRequest, OpenTableRequest,
 };
 use table::table::{AlterContext, TableRef};
 use table::{error as table_error, Result as TableResult, Table};
-use tokio::sync::Mutex;
 
 use crate::config::EngineConfig;
+use crate::engine::lock::TableMutex;
 use crate::engine::procedure::CreateMitoTable;
+pub use crate::engine::region_opener::FailedRegion;
+use crate::engine::region_opener::RegionOpener;
 use crate::error::{
     self, BuildColumnDescriptorSnafu, BuildColumnFamilyDescriptorSnafu, BuildRegionDescriptorSnafu,
     BuildRowKeyDescriptorSnafu, InvalidPrimaryKeySnafu, InvalidRawSchemaSnafu,
@@ -76,6 +85,11 @@ impl<S: StorageEngine> MitoEngine<S> {
         }
     }
 
+    /// Returns regions failed to open, which are retried in background.
+    pub fn failed_regions(&self) -> Vec<FailedRegion> {
+        self.inner.region_opener.failed_regions()
+    }
+
     /// Register all procedure loaders to the procedure manager.
     ///
     /// # Panics
@@ -115,6 +129,30 @@ impl<S: StorageEngine> TableEngine for MitoEngine<S> {
             .context(table_error::TableOperationSnafu)
     }
 
+    async fn open_regions(
+        &self,
+        _ctx: &EngineContext,
+        request: OpenRegionsRequest,
+    ) -> TableResult<Option<TableRef>> {
+        self.inner
+            .open_regions(request)
+            .await
+            .map_err(BoxedError::new)
+            .context(table_error::TableOperationSnafu)
+    }
+
+    async fn close_regions(
+        &self,
+        _ctx: &EngineContext,
+        request: CloseRegionsRequest,
+    ) -> TableResult<()> {
+        self.inner
+            .close_regions(request)
+            .await
+            .map_err(BoxedError::new)
+            .context(table_error::TableOperationSnafu)
+    }
+
     async fn alter_table(
         &self,
         ctx: &EngineContext,
@@ -178,9 +216,12 @@ pub(crate) struct MitoEngineInner<S: StorageEngine> {
     tables: RwLock<HashMap<String, TableRef>>,
     object_store: ObjectStore,
     storage_engine: S,
+    region_opener: RegionOpener<S>,
     /// Table mutex is used to protect the operations such as creating/opening/closing
     /// a table, to avoid things like opening the same table simultaneously.
-    table_mutex: Mutex<()>,
+    table_mutex: TableMutex,
+    /// Max time to wait for the files of a table to be deleted by `DROP TABLE ... SYNC`.
+    drop_sync_timeout: Duration,
 }
 
 fn build_row_key_desc(
@@ -359,7 +400,7 @@ impl<S: StorageEngine> MitoEngineInner<S> {
         let table_dir = table_dir(catalog_name, schema_name, table_id);
         let mut regions = HashMap::with_capacity(request.region_numbers.len());
 
-        let _lock = self.table_mutex.lock().await;
+        let _lock = self.table_mutex.lock(&table_ref.to_string()).await;
         // Checks again, read lock should be enough since we are guarded by the mutex.
         if let Some(table) = self.get_table(&table_ref) {
             return if request.create_if_not_exists {
@@ -390,6 +431,7 @@ impl<S: StorageEngine> MitoEngineInner<S> {
                     .write_buffer_size
                     .map(|size| size.0 as usize),
                 ttl: request.table_options.ttl,
+                compaction: request.table_options.compaction,
             };
 
             let region = self
@@ -464,14 +506,13 @@ impl<S: StorageEngine> MitoEngineInner<S> {
 
         // Acquires the mutex before opening a new table.
         let table = {
-            let _lock = self.table_mutex.lock().await;
+            let _lock = self.table_mutex.lock(&table_ref.to_string()).await;
             // Checks again, read lock should be enough since we are guarded by the mutex.
             if let Some(table) = self.get_table(&table_ref) {
                 return Ok(Some(table));
             }
 
             let table_id = request.table_id;
-            let engine_ctx = StorageEngineContext::default();
             let table_dir = table_dir(catalog_name, schema_name, table_id);
 
             let Some((manifest, table_info)) = self
@@ -479,43 +520,53 @@ impl<S: StorageEngine> MitoEngineInner<S> {
                 .await.map_err(BoxedError::new)
                 .context(TableOperationSnafu)? else { return Ok(None) };
 
-            let opts = OpenOptions {
-                parent_dir: table_dir.to_string(),
-                write_buffer_size: table_info
-                    .meta
-                    .options
-                    .write_buffer_size
-                    .map(|s| s.0 as usize),
-                ttl: table_info.meta.options.ttl,
-            };
+            let opts = open_options(&table_dir, &table_info);
 
             debug!(
                 "Opening table {}, table info recovered: {:?}",
                 table_id, table_info
             );
 
-            let mut regions = HashMap::with_capacity(table_info.meta.region_numbers.len());
-            for region_number in &table_info.meta.region_numbers {
-                let region_name = region_name(table_id, *region_number);
-                let region = self
-                    .storage_engine
-                    .open_region(&engine_ctx, &region_name, &opts)
-                    .await
-                    .map_err(BoxedError::new)
-                    .context(table_error::TableOperationSnafu)?
-                    .with_context(|| RegionNotFoundSnafu {
-                        table: format!(
-                            "{}.{}.{}",
-                            request.catalog_name, request.schema_name, request.table_name
-                        ),
-                        region: *region_number,
-                    })
-                    .map_err(BoxedError::new)
-                    .context(table_error::TableOperationSnafu)?;
-                regions.insert(*region_number, region);
+            let results = self
+                .region_opener
+                .open_regions(table_id, &table_info.meta.region_numbers, &opts)
+                .await;
+            let mut regions = HashMap::with_capacity(results.len());
+            let mut failed = Vec::new();
+            for (region_number, result) in results {
+                match result {
+                    Ok(Some(region)) => {
+                        regions.insert(region_number, region);
+                    }
+                    Ok(None) => {
+                        return RegionNotFoundSnafu {
+                            table: table_ref.to_string(),
+                            region: region_number,
+                        }
+                        .fail()
+                        .map_err(BoxedError::new)
+                        .context(table_error::TableOperationSnafu);
+                    }
+                    // A region failed to open doesn't fail the whole table, the table serves
+                    // other regions and the region is retried in background.
+                    Err(e) => {
+                        logging::error!(
+                            e; "Failed to open region {} of table {}", region_number, table_ref
+                        );
+                        failed.push((region_number, e.to_string()));
+                    }
+                }
             }
 
             let table = Arc::new(MitoTable::new(table_info, regions, manifest));
+            if !failed.is_empty() {
+                self.region_opener.reopen_in_background(
+                    &table_ref.to_string(),
+                    &table,
+                    failed,
+                    opts,
+                );
+            }
 
             self.tables
                 .write()
@@ -529,6 +580,202 @@ impl<S: StorageEngine> MitoEngineInner<S> {
         Ok(table)
     }
 
+    /// Opens the given regions of the table. Unlike [Self::open_table], regions failed to
+    /// open fail the request instead of being retried in background, as the caller, e.g. a
+    /// region migration, needs to know whether the regions are served.
+    async fn open_regions(&self, request: OpenRegionsRequest) -> Result<Option<TableRef>> {
+        let table_ref = TableReference {
+            catalog: &request.catalog_name,
+            schema: &request.schema_name,
+            table: &request.table_name,
+        };
+        let table_name = table_ref.to_string();
+        let table_dir = table_dir(
+            &request.catalog_name,
+            &request.schema_name,
+            request.table_id,
+        );
+        let mut region_numbers = request.region_numbers.clone();
+        region_numbers.sort_unstable();
+        region_numbers.dedup();
+
+        let _lock = self.table_mutex.lock(&table_name).await;
+        if let Some(table) = self.get_table(&table_ref) {
+            let Some(mito_table) = table.as_any().downcast_ref::<MitoTable<S::Region>>() else {
+                return Ok(Some(table));
+            };
+            let opened = mito_table.regions();
+            region_numbers.retain(|n| !opened.contains_key(n));
+            if region_numbers.is_empty() {
+                return Ok(Some(table));
+            }
+
+            let mut table_info = mito_table.table_info().as_ref().clone();
+            let opts = open_options(&table_dir, &table_info);
+            let regions = self
+                .open_all_regions(&table_name, request.table_id, &region_numbers, &opts)
+                .await?;
+            for (region_number, region) in regions {
+                mito_table.add_region(region_number, region);
+            }
+            let numbers = &mut table_info.meta.region_numbers;
+            numbers.extend(region_numbers);
+            numbers.sort_unstable();
+            numbers.dedup();
+            mito_table.set_table_info(table_info);
+
+            logging::info!(
+                "Mito engine opened regions {:?} of table {}",
+                request.region_numbers,
+                table_name
+            );
+            return Ok(Some(table));
+        }
+
+        let Some((manifest, mut table_info)) = self
+            .recover_table_manifest_and_info(&request.table_name, &table_dir)
+            .await? else { return Ok(None) };
+        let opts = open_options(&table_dir, &table_info);
+        let regions = self
+            .open_all_regions(&table_name, request.table_id, &region_numbers, &opts)
+            .await?;
+        // The table only serves the regions opened, other regions of the table may be
+        // served by other nodes.
+        table_info.meta.region_numbers = region_numbers;
+
+        let table = Arc::new(MitoTable::new(table_info, regions, manifest));
+        self.tables
+            .write()
+            .unwrap()
+            .insert(table_name.clone(), table.clone());
+
+        logging::info!(
+            "Mito engine opened table {} with regions {:?}",
+            table_name,
+            request.region_numbers
+        );
+        Ok(Some(table))
+    }
+
+    /// Opens all the regions, or none of them if any region fails to open.
+    async fn open_all_regions(
+        &self,
+        table_name: &str,
+        table_id: TableId,
+        region_numbers: &[RegionNumber],
+        opts: &OpenOptions,
+    ) -> Result<HashMap<RegionNumber, S::Region>> {
+        for region_number in region_numbers {
+            // The region is opened here, stops retrying it in background.
+            self.region_opener
+                .remove_region(&region_name(table_id, *region_number));
+        }
+
+        let results = self
+            .region_opener
+            .open_regions(table_id, region_numbers, opts)
+            .await;
+        let mut regions = HashMap::with_capacity(results.len());
+        let mut error = None;
+        for (region_number, result) in results {
+            match result {
+                Ok(Some(region)) => {
+                    regions.insert(region_number, region);
+                }
+                Ok(None) => {
+                    let _ = error.get_or_insert_with(|| {
+                        RegionNotFoundSnafu {
+                            table: table_name,
+                            region: region_number,
+                        }
+                        .build()
+                    });
+                }
+                Err(e) => {
+                    let _ = error.get_or_insert(e);
+                }
+            }
+        }
+
+        if let Some(e) = error {
+            for region in regions.into_values() {
+                if let Err(e) = self.close_region(region).await {
+                    logging::error!(e; "Failed to close region of table {}", table_name);
+                }
+            }
+            return Err(e);
+        }
+        Ok(regions)
+    }
+
+    /// Flushes and closes the given regions of the table, the table is removed from the
+    /// engine once all its regions are closed.
+    async fn close_regions(&self, request: CloseRegionsRequest) -> Result<()> {
+        let table_ref = TableReference {
+            catalog: &request.catalog_name,
+            schema: &request.schema_name,
+            table: &request.table_name,
+        };
+        let table_name = table_ref.to_string();
+
+        let _lock = self.table_mutex.lock(&table_name).await;
+        let Some(table) = self.get_table(&table_ref) else { return Ok(()) };
+        let Some(mito_table) = table.as_any().downcast_ref::<MitoTable<S::Region>>() else {
+            return Ok(());
+        };
+
+        let table_id = mito_table.table_info().ident.table_id;
+        for region_number in &request.region_numbers {
+            self.region_opener
+                .remove_region(&region_name(table_id, *region_number));
+            let Some(region) = mito_table.regions().get(region_number).cloned() else {
+                continue;
+            };
+
+            // Flushes the memtables, so the data in the WAL of this node is persisted before
+            // the region is opened elsewhere.
+            region
+                .flush(&FlushContext::default())
+                .await
+                .map_err(BoxedError::new)
+                .context(error::CloseRegionSnafu {
+                    region_name: region.name(),
+                })?;
+            self.close_region(region).await?;
+            let _ = mito_table.remove_region(*region_number);
+        }
+
+        let regions = mito_table.regions();
+        let mut table_info = mito_table.table_info().as_ref().clone();
+        table_info
+            .meta
+            .region_numbers
+            .retain(|n| !request.region_numbers.contains(n));
+        mito_table.set_table_info(table_info);
+
+        if regions.is_empty() {
+            self.region_opener.remove_table(&table_name);
+            let _ = self.tables.write().unwrap().remove(&table_name);
+            logging::info!("Mito engine closed table {}", table_name);
+        } else {
+            logging::info!(
+                "Mito engine closed regions {:?} of table {}",
+                request.region_numbers,
+                table_name
+            );
+        }
+        Ok(())
+    }
+
+    async fn close_region(&self, region: S::Region) -> Result<()> {
+        let region_name = region.name().to_string();
+        self.storage_engine
+            .close_region(&StorageEngineContext::default(), region)
+            .await
+            .map_err(BoxedError::new)
+            .context(error::CloseRegionSnafu { region_name })
+    }
+
     async fn recover_table_manifest_and_info(
         &self,
         table_name: &str,
@@ -598,23 +845,71 @@ impl<S: StorageEngine> MitoEngineInner<S> {
     }
 
     /// Drop table. Returns whether a table is dropped (true) or not exist (false).
+    ///
+    /// The table is only removed from the engine, its data is kept, unless `req.sync` is
+    /// set, in which case waits until the manifests, SST files and WAL of the table are
+    /// deleted.
     async fn drop_table(&self, req: DropTableRequest) -> Result<bool> {
         let table_reference = TableReference {
             catalog: &req.catalog_name,
             schema: &req.schema_name,
             table: &req.table_name,
         };
-        // todo(ruihang): reclaim persisted data
-        Ok(self
-            .tables
-            .write()
-            .unwrap()
-            .remove(&table_reference.to_string())
-            .is_some())
+        let table_name = table_reference.to_string();
+        let _lock = self.table_mutex.lock(&table_name).await;
+
+        self.region_opener.remove_table(&table_name);
+        let Some(table) = self.tables.write().unwrap().remove(&table_name) else {
+            return Ok(false);
+        };
+        if !req.sync {
+            // todo(ruihang): reclaim persisted data
+            return Ok(true);
+        }
+        let Some(table) = table.as_any().downcast_ref::<MitoTable<S::Region>>() else {
+            return Ok(true);
+        };
+
+        table
+            .manifest()
+            .delete_all()
+            .await
+            .context(error::DeleteTableManifestSnafu {
+                table_name: &table_name,
+            })?;
+
+        let opts = DropOptions {
+            purge_timeout: Some(self.drop_sync_timeout),
+        };
+        for region in table.regions().values() {
+            self.storage_engine
+                .drop_region(&StorageEngineContext::default(), region.clone(), &opts)
+                .await
+                .map_err(BoxedError::new)
+                .context(error::DropRegionSnafu {
+                    region_name: region.name(),
+                })?;
+        }
+
+        // Removes the remaining files, e.g. the empty region directories.
+        let table_dir = table_dir(
+            &req.catalog_name,
+            &req.schema_name,
+            table.table_info().ident.table_id,
+        );
+        self.object_store
+            .batch()
+            .remove_all(&table_dir)
+            .await
+            .context(error::DeleteTableDirSnafu { table_dir })?;
+
+        logging::info!("Mito engine dropped table {} and its data", table_name);
+        Ok(true)
     }
 
     async fn close(&self) -> TableResult<()> {
-        let _lock = self.table_mutex.lock().await;
+        let _lock = self.table_mutex.lock_all().await;
+        self.region_opener.clear();
 
         let tables = self.tables.write().unwrap().clone();
 
@@ -627,13 +922,28 @@ impl<S: StorageEngine> MitoEngineInner<S> {
     }
 }
 
+fn open_options(table_dir: &str, table_info: &TableInfo) -> OpenOptions {
+    OpenOptions {
+        parent_dir: table_dir.to_string(),
+        write_buffer_size: table_info
+            .meta
+            .options
+            .write_buffer_size
+            .map(|s| s.0 as usize),
+        ttl: table_info.meta.options.ttl,
+        compaction: table_info.meta.options.compaction,
+    }
+}
+
 impl<S: StorageEngine> MitoEngineInner<S> {
-    fn new(_config: EngineConfig, storage_engine: S, object_store: ObjectStore) -> Self {
+    fn new(config: EngineConfig, storage_engine: S, object_store: ObjectStore) -> Self {
         Self {
             tables: RwLock::new(HashMap::default()),
+            region_opener: RegionOpener::new(storage_engine.clone(), &config),
             storage_engine,
             object_store,
-            table_mutex: Mutex::new(()),
+            table_mutex: TableMutex::default(),
+            drop_sync_timeout: config.drop_sync_timeout,
         }
     }
 }
@@ -0,0 +1,142 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Lock to serialize operations such as creating/opening a table.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex as StdMutex};
+
+use tokio::sync::{Mutex, OwnedMutexGuard, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// Serializes operations on the same table, to avoid things like opening the same table
+/// simultaneously, while operations on different tables could run concurrently.
+#[derive(Default)]
+pub(crate) struct TableMutex {
+    /// Operations on a single table hold the read lock, operations on all tables hold
+    /// the write lock.
+    all: RwLock<()>,
+    /// Mutexes of tables being operated, map key is formatted [TableReference].
+    ///
+    /// [TableReference]: table::engine::TableReference
+    tables: StdMutex<HashMap<String, Arc<Mutex<()>>>>,
+}
+
+impl TableMutex {
+    /// Locks the table with key `table`.
+    pub(crate) async fn lock(&self, table: &str) -> TableGuard<'_> {
+        let all = self.all.read().await;
+        let mutex = self
+            .tables
+            .lock()
+            .unwrap()
+            .entry(table.to_string())
+            .or_default()
+            .clone();
+        let guard = mutex.clone().lock_owned().await;
+
+        TableGuard {
+            _all: all,
+            table: table.to_string(),
+            mutex,
+            guard: Some(guard),
+            tables: &self.tables,
+        }
+    }
+
+    /// Locks all tables.
+    pub(crate) async fn lock_all(&self) -> RwLockWriteGuard<'_, ()> {
+        self.all.write().await
+    }
+
+    #[cfg(test)]
+    fn num_locked(&self) -> usize {
+        self.tables.lock().unwrap().len()
+    }
+}
+
+pub(crate) struct TableGuard<'a> {
+    _all: RwLockReadGuard<'a, ()>,
+    table: String,
+    mutex: Arc<Mutex<()>>,
+    guard: Option<OwnedMutexGuard<()>>,
+    tables: &'a StdMutex<HashMap<String, Arc<Mutex<()>>>>,
+}
+
+impl Drop for TableGuard<'_> {
+    fn drop(&mut self) {
+        // Releases the mutex first.
+        self.guard.take();
+
+        let mut tables = self.tables.lock().unwrap();
+        // Only the map and this guard hold the mutex, no one is waiting for it. New waiters
+        // need to lock the map to get the mutex, so it is safe to remove the mutex.
+        if Arc::strong_count(&self.mutex) == 2 {
+            tables.remove(&self.table);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_lock_different_tables() {
+        let mutex = TableMutex::default();
+        let _guard1 = mutex.lock("greptime.public.t1").await;
+        // Locking another table is not blocked.
+        let guard2 = tokio::time::timeout(Duration::from_secs(5), mutex.lock("greptime.public.t2"))
+            .await
+            .unwrap();
+        assert_eq!(2, mutex.num_locked());
+
+        drop(guard2);
+        assert_eq!(1, mutex.num_locked());
+    }
+
+    #[tokio::test]
+    async fn test_lock_same_table() {
+        let mutex = Arc::new(TableMutex::default());
+        let guard = mutex.lock("greptime.public.t1").await;
+
+        let waiter = {
+            let mutex = mutex.clone();
+            tokio::spawn(async move {
+                let _guard = mutex.lock("greptime.public.t1").await;
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!waiter.is_finished());
+
+        drop(guard);
+        waiter.await.unwrap();
+        assert_eq!(0, mutex.num_locked());
+    }
+
+    #[tokio::test]
+    async fn test_lock_all() {
+        let mutex = TableMutex::default();
+        let guard = mutex.lock("greptime.public.t1").await;
+        assert!(
+            tokio::time::timeout(Duration::from_millis(50), mutex.lock_all())
+                .await
+                .is_err()
+        );
+
+        drop(guard);
+        let _all = mutex.lock_all().await;
+    }
+}
@@ -0,0 +1,216 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Opening regions of tables, with bounded concurrency and background retries for
+//! regions failed to open.
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock, Weak};
+use std::time::Duration;
+
+use common_error::ext::BoxedError;
+use common_telemetry::logging;
+use snafu::ResultExt;
+use store_api::storage::{
+    EngineContext as StorageEngineContext, OpenOptions, RegionNumber, StorageEngine,
+};
+use table::engine::region_name;
+use table::metadata::TableId;
+use tokio::sync::Semaphore;
+
+use crate::config::EngineConfig;
+use crate::error::{OpenRegionSnafu, Result};
+use crate::table::MitoTable;
+
+/// Max interval to retry opening a region.
+const MAX_RETRY_INTERVAL: Duration = Duration::from_secs(300);
+
+/// A region failed to open.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FailedRegion {
+    /// Full name of the table the region belongs to.
+    pub table_name: String,
+    pub region_number: RegionNumber,
+    /// Error of the last attempt to open the region.
+    pub error: String,
+    /// Number of retries since the region failed to open.
+    pub retries: usize,
+}
+
+#[derive(Clone)]
+pub(crate) struct RegionOpener<S: StorageEngine> {
+    storage_engine: S,
+    /// Limits the number of regions opened concurrently.
+    permits: Arc<Semaphore>,
+    retry_interval: Duration,
+    /// Regions failed to open, map key is the region name.
+    failed_regions: Arc<RwLock<HashMap<String, FailedRegion>>>,
+}
+
+impl<S: StorageEngine> RegionOpener<S> {
+    pub(crate) fn new(storage_engine: S, config: &EngineConfig) -> Self {
+        Self {
+            storage_engine,
+            permits: Arc::new(Semaphore::new(config.region_open_parallelism.max(1))),
+            retry_interval: config.region_open_retry_interval,
+            failed_regions: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Opens regions of the table concurrently, returns the result of each region.
+    pub(crate) async fn open_regions(
+        &self,
+        table_id: TableId,
+        region_numbers: &[RegionNumber],
+        opts: &OpenOptions,
+    ) -> Vec<(RegionNumber, Result<Option<S::Region>>)> {
+        futures::future::join_all(region_numbers.iter().map(|region_number| async move {
+            let region_name = region_name(table_id, *region_number);
+            (*region_number, self.open_region(&region_name, opts).await)
+        }))
+        .await
+    }
+
+    async fn open_region(
+        &self,
+        region_name: &str,
+        opts: &OpenOptions,
+    ) -> Result<Option<S::Region>> {
+        // Safety: the semaphore is never closed.
+        let _permit = self.permits.acquire().await.unwrap();
+
+        self.storage_engine
+            .open_region(&StorageEngineContext::default(), region_name, opts)
+            .await
+            .map_err(BoxedError::new)
+            .context(OpenRegionSnafu { region_name })
+    }
+
+    /// Records regions failed to open and retries opening them in background, regions
+    /// opened are added to the `table`.
+    pub(crate) fn reopen_in_background(
+        &self,
+        table_name: &str,
+        table: &Arc<MitoTable<S::Region>>,
+        failed: Vec<(RegionNumber, String)>,
+        opts: OpenOptions,
+    ) {
+        let table_id = table.table_info().ident.table_id;
+        let mut region_numbers = Vec::with_capacity(failed.len());
+        {
+            let mut failed_regions = self.failed_regions.write().unwrap();
+            for (region_number, error) in failed {
+                failed_regions.insert(
+                    region_name(table_id, region_number),
+                    FailedRegion {
+                        table_name: table_name.to_string(),
+                        region_number,
+                        error,
+                        retries: 0,
+                    },
+                );
+                region_numbers.push(region_number);
+            }
+        }
+
+        let opener = self.clone();
+        let table = Arc::downgrade(table);
+        let _handle = tokio::spawn(async move {
+            opener
+                .retry_open(table, table_id, region_numbers, opts)
+                .await
+        });
+    }
+
+    async fn retry_open(
+        &self,
+        table: Weak<MitoTable<S::Region>>,
+        table_id: TableId,
+        mut region_numbers: Vec<RegionNumber>,
+        opts: OpenOptions,
+    ) {
+        let mut interval = self.retry_interval;
+        while !region_numbers.is_empty() {
+            tokio::time::sleep(interval).await;
+            interval = (interval * 2).min(MAX_RETRY_INTERVAL);
+
+            let Some(table) = table.upgrade() else { return };
+            let mut pending = Vec::with_capacity(region_numbers.len());
+            for region_number in region_numbers {
+                let region_name = region_name(table_id, region_number);
+                // The region is removed if the table is dropped or closed.
+                if !self
+                    .failed_regions
+                    .read()
+                    .unwrap()
+                    .contains_key(&region_name)
+                {
+                    continue;
+                }
+
+                let error = match self.open_region(&region_name, &opts).await {
+                    Ok(Some(region)) => {
+                        table.add_region(region_number, region);
+                        let _ = self.failed_regions.write().unwrap().remove(&region_name);
+                        logging::info!("Region {} is reopened", region_name);
+                        continue;
+                    }
+                    Ok(None) => format!("Region {region_name} not found"),
+                    Err(e) => e.to_string(),
+                };
+
+                logging::warn!("Failed to reopen region {}, error: {}", region_name, error);
+                if let Some(failed) = self.failed_regions.write().unwrap().get_mut(&region_name) {
+                    failed.error = error;
+                    failed.retries += 1;
+                }
+                pending.push(region_number);
+            }
+            region_numbers = pending;
+        }
+    }
+
+    /// Returns regions failed to open, ordered by table and region number.
+    pub(crate) fn failed_regions(&self) -> Vec<FailedRegion> {
+        let mut regions = self
+            .failed_regions
+            .read()
+            .unwrap()
+            .values()
+            .cloned()
+            .collect::<Vec<_>>();
+        regions.sort_unstable_by(|a, b| {
+            (&a.table_name, a.region_number).cmp(&(&b.table_name, b.region_number))
+        });
+        regions
+    }
+
+    /// Stops retrying regions of the table.
+    pub(crate) fn remove_table(&self, table_name: &str) {
+        self.failed_regions
+            .write()
+            .unwrap()
+            .retain(|_, region| region.table_name != table_name);
+    }
+
+    /// Stops retrying the region.
+    pub(crate) fn remove_region(&self, region_name: &str) {
+        let _ = self.failed_regions.write().unwrap().remove(region_name);
+    }
+
+    /// Stops retrying all regions.
+    pub(crate) fn clear(&self) {
+        self.failed_regions.write().unwrap().clear();
+    }
+}
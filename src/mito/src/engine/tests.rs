@@ -14,6 +14,8 @@
 
 //! Tests for mito table engine.
 
+use std::time::Duration;
+
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
 use common_query::physical_plan::SessionContext;
 use common_recordbatch::util;
@@ -30,7 +32,7 @@ use storage::config::EngineConfig as StorageEngineConfig;
 use storage::region::RegionImpl;
 use storage::EngineImpl;
 use store_api::manifest::Manifest;
-use store_api::storage::ReadContext;
+use store_api::storage::{CompactionOptions, ReadContext, RegionDescriptor};
 use table::requests::{
     AddColumnRequest, AlterKind, DeleteRequest, FlushTableRequest, TableOptions,
 };
@@ -657,6 +659,128 @@ async fn test_alter_rename_table() {
     assert_eq!(reopened.manifest().last_version(), 2);
 }
 
+async fn count_rows(table: &TableRef) -> usize {
+    let session_ctx = SessionContext::new();
+    let stream = table.scan(None, &[], None).await.unwrap();
+    let stream = stream.execute(0, session_ctx.task_ctx()).unwrap();
+    let batches = util::collect(stream).await.unwrap();
+    batches.iter().map(|batch| batch.num_rows()).sum()
+}
+
+fn new_set_ttl_req(ttl: Option<Duration>) -> AlterTableRequest {
+    AlterTableRequest {
+        catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+        schema_name: DEFAULT_SCHEMA_NAME.to_string(),
+        table_name: TABLE_NAME.to_string(),
+        alter_kind: AlterKind::SetTtl { ttl },
+    }
+}
+
+#[tokio::test]
+async fn test_alter_table_ttl() {
+    let TestEngineComponents {
+        table_engine,
+        storage_engine,
+        table_ref: table,
+        object_store,
+        dir: _dir,
+        ..
+    } = test_util::setup_test_engine_and_table().await;
+    let ctx = EngineContext::default();
+
+    // Rows written by `setup_table()` are all around the unix epoch.
+    setup_table(table.clone()).await;
+    table.flush(None, Some(true)).await.unwrap();
+    assert_eq!(4, count_rows(&table).await);
+
+    let ttl = Duration::from_secs(24 * 3600);
+    let table = table_engine
+        .alter_table(&ctx, new_set_ttl_req(Some(ttl)))
+        .await
+        .unwrap();
+    assert_eq!(Some(ttl), table.table_info().meta.options.ttl);
+    let mito_table = table
+        .as_any()
+        .downcast_ref::<MitoTable<RegionImpl<NoopLogStore>>>()
+        .unwrap();
+    for region in mito_table.regions().values() {
+        assert_eq!(Some(ttl), region.ttl());
+    }
+    // Expired rows are invisible even if they are not purged yet.
+    assert_eq!(0, count_rows(&table).await);
+
+    // The ttl is persisted in the table manifest.
+    let table_engine = MitoEngine::new(EngineConfig::default(), storage_engine, object_store);
+    let open_req = OpenTableRequest {
+        catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+        schema_name: DEFAULT_SCHEMA_NAME.to_string(),
+        table_name: TABLE_NAME.to_string(),
+        table_id: 1,
+    };
+    let reopened = table_engine
+        .open_table(&ctx, open_req)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(Some(ttl), reopened.table_info().meta.options.ttl);
+
+    // Rows become visible again once the ttl is unset, as the compaction scheduler is noop.
+    let table = table_engine
+        .alter_table(&ctx, new_set_ttl_req(None))
+        .await
+        .unwrap();
+    assert_eq!(None, table.table_info().meta.options.ttl);
+    assert_eq!(4, count_rows(&table).await);
+}
+
+#[tokio::test]
+async fn test_alter_table_compaction_options() {
+    let TestEngineComponents {
+        table_engine,
+        storage_engine,
+        table_ref: table,
+        object_store,
+        dir: _dir,
+        ..
+    } = test_util::setup_test_engine_and_table().await;
+    let ctx = EngineContext::default();
+
+    let options = CompactionOptions {
+        max_files_in_l0: Some(4),
+        max_concurrent_outputs: Some(2),
+    };
+    let req = AlterTableRequest {
+        catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+        schema_name: DEFAULT_SCHEMA_NAME.to_string(),
+        table_name: TABLE_NAME.to_string(),
+        alter_kind: AlterKind::SetCompactionOptions { options },
+    };
+    let table = table_engine.alter_table(&ctx, req).await.unwrap();
+    assert_eq!(options, table.table_info().meta.options.compaction);
+    let mito_table = table
+        .as_any()
+        .downcast_ref::<MitoTable<RegionImpl<NoopLogStore>>>()
+        .unwrap();
+    for region in mito_table.regions().values() {
+        assert_eq!(options, region.compaction_options());
+    }
+
+    // The options are persisted in the table manifest.
+    let table_engine = MitoEngine::new(EngineConfig::default(), storage_engine, object_store);
+    let open_req = OpenTableRequest {
+        catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+        schema_name: DEFAULT_SCHEMA_NAME.to_string(),
+        table_name: TABLE_NAME.to_string(),
+        table_id: 1,
+    };
+    let reopened = table_engine
+        .open_table(&ctx, open_req)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(options, reopened.table_info().meta.options.compaction);
+}
+
 #[tokio::test]
 async fn test_drop_table() {
     common_telemetry::init_default_ut_logging();
@@ -697,6 +821,7 @@ async fn test_drop_table() {
         catalog_name: table_reference.catalog.to_string(),
         schema_name: table_reference.schema.to_string(),
         table_name: table_reference.table.to_string(),
+        sync: false,
     };
     let table_dropped = table_engine
         .drop_table(&engine_ctx, drop_table_request)
@@ -722,6 +847,56 @@ async fn test_drop_table() {
     assert!(table_engine.table_exists(&engine_ctx, &table_reference));
 }
 
+#[tokio::test]
+async fn test_drop_table_sync() {
+    common_telemetry::init_default_ut_logging();
+    let TestEngineComponents {
+        table_engine,
+        table_ref: table,
+        object_store,
+        dir,
+        ..
+    } = test_util::setup_test_engine_and_table().await;
+
+    setup_table(table.clone()).await;
+    table.flush(None, None).await.unwrap();
+    // Trigger again, wait for the previous task finished
+    table.flush(None, None).await.unwrap();
+
+    let table_info = table.table_info();
+    let table_dir = table_dir(
+        &table_info.catalog_name,
+        &table_info.schema_name,
+        table_info.ident.table_id,
+    );
+    let region_dir = format!(
+        "{}/{}/{}",
+        dir.path().to_str().unwrap(),
+        table_dir,
+        region_name(table_info.ident.table_id, 0)
+    );
+    assert!(has_parquet_file(&region_dir));
+
+    let drop_table_request = DropTableRequest {
+        catalog_name: table_info.catalog_name.clone(),
+        schema_name: table_info.schema_name.clone(),
+        table_name: table_info.name.clone(),
+        sync: true,
+    };
+    assert!(table_engine
+        .drop_table(&EngineContext::default(), drop_table_request)
+        .await
+        .unwrap());
+
+    let object = object_store.object(&table_dir);
+    if object.is_exist().await.unwrap() {
+        let objects = object_store::util::collect(object.list().await.unwrap())
+            .await
+            .unwrap();
+        assert!(objects.is_empty(), "{objects:?}");
+    }
+}
+
 #[tokio::test]
 async fn test_table_delete_rows() {
     let TestEngineComponents {
@@ -769,6 +944,82 @@ async fn test_table_delete_rows() {
     );
 }
 
+fn new_columns_values(host: &str, ts: i64) -> HashMap<String, VectorRef> {
+    let mut columns_values: HashMap<String, VectorRef> = HashMap::with_capacity(4);
+    let hosts: VectorRef = Arc::new(StringVector::from(vec![host]));
+    let cpus: VectorRef = Arc::new(Float64Vector::from_vec(vec![1.0]));
+    let memories: VectorRef = Arc::new(Float64Vector::from_vec(vec![1.0]));
+    let tss: VectorRef = Arc::new(TimestampMillisecondVector::from_vec(vec![ts]));
+    columns_values.insert("host".to_string(), hosts);
+    columns_values.insert("cpu".to_string(), cpus);
+    columns_values.insert("memory".to_string(), memories);
+    columns_values.insert("ts".to_string(), tss);
+    columns_values
+}
+
+async fn scan_hosts(table: &TableRef) -> String {
+    let session_ctx = SessionContext::new();
+    let stream = table.scan(Some(&vec![0]), &[], None).await.unwrap();
+    let stream = stream.execute(0, session_ctx.task_ctx()).unwrap();
+    let batches = util::collect_batches(stream).await.unwrap();
+    batches.pretty_print().unwrap()
+}
+
+#[tokio::test]
+async fn test_table_bulk_load() {
+    let TestEngineComponents {
+        table_ref: table,
+        dir: _dir,
+        ..
+    } = test_util::setup_test_engine_and_table().await;
+
+    let insert_req = new_insert_request("demo".to_string(), new_columns_values("host1", 1));
+    assert_eq!(1, table.insert(insert_req).await.unwrap());
+
+    table.begin_bulk_load("load-0").await.unwrap();
+    let insert_req = new_insert_request("demo".to_string(), new_columns_values("host2", 2));
+    assert_eq!(
+        1,
+        table.insert_bulk_load("load-0", insert_req).await.unwrap()
+    );
+    let insert_req = new_insert_request("demo".to_string(), new_columns_values("host3", 3));
+    assert_eq!(
+        1,
+        table.insert_bulk_load("load-0", insert_req).await.unwrap()
+    );
+
+    // Rows of the bulk load are invisible until it's committed.
+    let expect = "\
++-------+
+| host  |
++-------+
+| host1 |
++-------+";
+    assert_eq!(expect, scan_hosts(&table).await);
+
+    // Rows of an aborted bulk load are discarded.
+    table.begin_bulk_load("load-1").await.unwrap();
+    let insert_req = new_insert_request("demo".to_string(), new_columns_values("host4", 4));
+    assert_eq!(
+        1,
+        table.insert_bulk_load("load-1", insert_req).await.unwrap()
+    );
+    table.abort_bulk_load("load-1").await.unwrap();
+    let insert_req = new_insert_request("demo".to_string(), new_columns_values("host4", 4));
+    assert!(table.insert_bulk_load("load-1", insert_req).await.is_err());
+
+    table.commit_bulk_load("load-0").await.unwrap();
+    let expect = "\
++-------+
+| host  |
++-------+
+| host1 |
+| host2 |
+| host3 |
++-------+";
+    assert_eq!(expect, scan_hosts(&table).await);
+}
+
 #[tokio::test]
 async fn test_flush_table_all_regions() {
     let TestEngineComponents {
@@ -841,3 +1092,340 @@ async fn test_flush_table_with_region_id() {
 
     assert!(has_parquet_file(&region_dir));
 }
+
+#[tokio::test]
+async fn test_open_table_with_failed_region() {
+    common_telemetry::init_default_ut_logging();
+
+    let (dir, object_store) =
+        test_util::new_test_object_store("test_open_table_with_failed_region").await;
+    let new_storage_engine = || {
+        EngineImpl::new(
+            StorageEngineConfig::default(),
+            Arc::new(NoopLogStore::default()),
+            object_store.clone(),
+            Arc::new(NoopCompactionScheduler::default()),
+        )
+    };
+    let config = EngineConfig {
+        region_open_parallelism: 2,
+        region_open_retry_interval: Duration::from_millis(10),
+        ..Default::default()
+    };
+    let ctx = EngineContext::default();
+
+    let table_engine = MitoEngine::new(config.clone(), new_storage_engine(), object_store.clone());
+    let mut request = test_util::new_create_request(Arc::new(schema_for_test()));
+    request.region_numbers = vec![0, 1, 2];
+    let table = table_engine.create_table(&ctx, request).await.unwrap();
+
+    // Corrupts the manifest of region 1.
+    let table_info = table.table_info();
+    let table_dir = table_dir(&table_info.catalog_name, &table_info.schema_name, 1);
+    let manifest_dir = dir
+        .path()
+        .join(table_dir)
+        .join(region_name(1, 1))
+        .join("manifest");
+    let mut manifest_files = Vec::new();
+    for entry in std::fs::read_dir(&manifest_dir).unwrap() {
+        let path = entry.unwrap().path();
+        manifest_files.push((path.clone(), std::fs::read(&path).unwrap()));
+        std::fs::write(&path, b"corrupted").unwrap();
+    }
+
+    // Opens the table by a new engine, the table is opened without region 1.
+    let table_engine = MitoEngine::new(config, new_storage_engine(), object_store.clone());
+    let open_req = OpenTableRequest {
+        catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+        schema_name: DEFAULT_SCHEMA_NAME.to_string(),
+        table_name: TABLE_NAME.to_string(),
+        table_id: 1,
+    };
+    let table = table_engine
+        .open_table(&ctx, open_req)
+        .await
+        .unwrap()
+        .unwrap();
+    let mito_table = table
+        .as_any()
+        .downcast_ref::<MitoTable<RegionImpl<NoopLogStore>>>()
+        .unwrap();
+    let mut region_numbers = mito_table.regions().keys().copied().collect::<Vec<_>>();
+    region_numbers.sort_unstable();
+    assert_eq!(vec![0, 2], region_numbers);
+
+    let failed = table_engine.failed_regions();
+    assert_eq!(1, failed.len());
+    assert_eq!(
+        format_full_table_name(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, TABLE_NAME),
+        failed[0].table_name
+    );
+    assert_eq!(1, failed[0].region_number);
+
+    // Repairs the manifest, then the region is reopened in background.
+    for (path, data) in manifest_files {
+        std::fs::write(path, data).unwrap();
+    }
+    tokio::time::timeout(Duration::from_secs(10), async {
+        while !table_engine.failed_regions().is_empty() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .unwrap();
+    assert_eq!(3, mito_table.regions().len());
+}
+
+/// Storage engine that takes a while to open each region, like opening a region with a
+/// large manifest or WAL.
+#[derive(Clone)]
+struct SlowOpenEngine<S> {
+    inner: S,
+    delay: Duration,
+}
+
+#[async_trait]
+impl<S: StorageEngine> StorageEngine for SlowOpenEngine<S> {
+    type Error = S::Error;
+    type Region = S::Region;
+
+    async fn open_region(
+        &self,
+        ctx: &StorageEngineContext,
+        name: &str,
+        opts: &OpenOptions,
+    ) -> std::result::Result<Option<Self::Region>, Self::Error> {
+        tokio::time::sleep(self.delay).await;
+        self.inner.open_region(ctx, name, opts).await
+    }
+
+    async fn close_region(
+        &self,
+        ctx: &StorageEngineContext,
+        region: Self::Region,
+    ) -> std::result::Result<(), Self::Error> {
+        self.inner.close_region(ctx, region).await
+    }
+
+    async fn create_region(
+        &self,
+        ctx: &StorageEngineContext,
+        descriptor: RegionDescriptor,
+        opts: &CreateOptions,
+    ) -> std::result::Result<Self::Region, Self::Error> {
+        self.inner.create_region(ctx, descriptor, opts).await
+    }
+
+    async fn drop_region(
+        &self,
+        ctx: &StorageEngineContext,
+        region: Self::Region,
+        opts: &DropOptions,
+    ) -> std::result::Result<(), Self::Error> {
+        self.inner.drop_region(ctx, region, opts).await
+    }
+
+    fn get_region(
+        &self,
+        ctx: &StorageEngineContext,
+        name: &str,
+    ) -> std::result::Result<Option<Self::Region>, Self::Error> {
+        self.inner.get_region(ctx, name)
+    }
+}
+
+#[tokio::test]
+async fn test_open_many_regions_in_parallel() {
+    common_telemetry::init_default_ut_logging();
+
+    let (_dir, object_store) =
+        test_util::new_test_object_store("test_open_many_regions_in_parallel").await;
+    let new_storage_engine = || {
+        EngineImpl::new(
+            StorageEngineConfig::default(),
+            Arc::new(NoopLogStore::default()),
+            object_store.clone(),
+            Arc::new(NoopCompactionScheduler::default()),
+        )
+    };
+    let ctx = EngineContext::default();
+    let num_regions = 32;
+    let delay = Duration::from_millis(20);
+
+    let table_engine = MitoEngine::new(
+        EngineConfig::default(),
+        new_storage_engine(),
+        object_store.clone(),
+    );
+    let mut request = test_util::new_create_request(Arc::new(schema_for_test()));
+    request.region_numbers = (0..num_regions).collect();
+    let _ = table_engine.create_table(&ctx, request).await.unwrap();
+
+    // Opens the table by new engines with different parallelism, returns the elapsed time.
+    let open_table = |parallelism| {
+        let storage_engine = SlowOpenEngine {
+            inner: new_storage_engine(),
+            delay,
+        };
+        let config = EngineConfig {
+            region_open_parallelism: parallelism,
+            ..Default::default()
+        };
+        let table_engine = MitoEngine::new(config, storage_engine, object_store.clone());
+        async move {
+            let open_req = OpenTableRequest {
+                catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+                schema_name: DEFAULT_SCHEMA_NAME.to_string(),
+                table_name: TABLE_NAME.to_string(),
+                table_id: 1,
+            };
+            let start = std::time::Instant::now();
+            let table = table_engine
+                .open_table(&EngineContext::default(), open_req)
+                .await
+                .unwrap()
+                .unwrap();
+            let elapsed = start.elapsed();
+
+            let mito_table = table
+                .as_any()
+                .downcast_ref::<MitoTable<RegionImpl<NoopLogStore>>>()
+                .unwrap();
+            assert_eq!(num_regions as usize, mito_table.regions().len());
+            assert!(table_engine.failed_regions().is_empty());
+            elapsed
+        }
+    };
+
+    let sequential = open_table(1).await;
+    let parallel = open_table(8).await;
+    logging::info!(
+        "Opened {} regions sequentially in {:?}, in parallel in {:?}",
+        num_regions,
+        sequential,
+        parallel
+    );
+    // Regions are opened one by one without parallelism.
+    assert!(sequential >= delay * num_regions);
+    assert!(parallel * 2 < sequential);
+}
+
+#[tokio::test]
+async fn test_move_regions_between_engines() {
+    common_telemetry::init_default_ut_logging();
+
+    let (_dir, object_store) =
+        test_util::new_test_object_store("test_move_regions_between_engines").await;
+    let new_table_engine = || {
+        let storage_engine = EngineImpl::new(
+            StorageEngineConfig::default(),
+            Arc::new(NoopLogStore::default()),
+            object_store.clone(),
+            Arc::new(NoopCompactionScheduler::default()),
+        );
+        MitoEngine::new(
+            EngineConfig::default(),
+            storage_engine,
+            object_store.clone(),
+        )
+    };
+    let ctx = EngineContext::default();
+    let table_ref = TableReference::full(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, TABLE_NAME);
+    let open_req = OpenRegionsRequest {
+        catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+        schema_name: DEFAULT_SCHEMA_NAME.to_string(),
+        table_name: TABLE_NAME.to_string(),
+        table_id: 1,
+        region_numbers: vec![0],
+    };
+    let close_req = |region_numbers| CloseRegionsRequest {
+        catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+        schema_name: DEFAULT_SCHEMA_NAME.to_string(),
+        table_name: TABLE_NAME.to_string(),
+        region_numbers,
+    };
+
+    // Both engines share the object store, like datanodes sharing the same storage.
+    let source = new_table_engine();
+    let target = new_table_engine();
+    let mut request = test_util::new_create_request(Arc::new(schema_for_test()));
+    request.region_numbers = vec![0, 1];
+    let table = source.create_table(&ctx, request).await.unwrap();
+    let insert_req = new_insert_request("demo".to_string(), new_columns_values("host1", 1));
+    assert_eq!(1, table.insert(insert_req).await.unwrap());
+
+    // The rows in the memtable are flushed before the region is closed.
+    source
+        .close_regions(&ctx, close_req(vec![0]))
+        .await
+        .unwrap();
+    assert_eq!(vec![1], table.table_info().meta.region_numbers);
+    assert!(source.table_exists(&ctx, &table_ref));
+
+    let table = target
+        .open_regions(&ctx, open_req.clone())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(vec![0], table.table_info().meta.region_numbers);
+    let expect = "\
++-------+
+| host  |
++-------+
+| host1 |
++-------+";
+    assert_eq!(expect, scan_hosts(&table).await);
+
+    // The table is closed with its last region.
+    source
+        .close_regions(&ctx, close_req(vec![1]))
+        .await
+        .unwrap();
+    assert!(!source.table_exists(&ctx, &table_ref));
+    // Closing again is a no-op.
+    source
+        .close_regions(&ctx, close_req(vec![1]))
+        .await
+        .unwrap();
+
+    // Moves region 0 back, and region 1 to the target.
+    target
+        .close_regions(&ctx, close_req(vec![0]))
+        .await
+        .unwrap();
+    let table = source
+        .open_regions(&ctx, open_req.clone())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(expect, scan_hosts(&table).await);
+    let mut req = open_req.clone();
+    req.region_numbers = vec![1];
+    let table = target.open_regions(&ctx, req).await.unwrap().unwrap();
+    assert_eq!(vec![1], table.table_info().meta.region_numbers);
+
+    // Opening a region not exists fails the request.
+    let mut req = open_req;
+    req.region_numbers = vec![1, 2];
+    let err = source.open_regions(&ctx, req).await.unwrap_err();
+    assert!(err.to_string().contains("Cannot find region"), "{err}");
+    assert_eq!(vec![0], table_regions(&source, &table_ref));
+}
+
+fn table_regions(
+    engine: &MitoEngine<EngineImpl<NoopLogStore>>,
+    table_ref: &TableReference,
+) -> Vec<RegionNumber> {
+    let table = engine
+        .get_table(&EngineContext::default(), table_ref)
+        .unwrap()
+        .unwrap();
+    let table = table
+        .as_any()
+        .downcast_ref::<MitoTable<RegionImpl<NoopLogStore>>>()
+        .unwrap();
+    let mut region_numbers = table.regions().keys().copied().collect::<Vec<_>>();
+    region_numbers.sort_unstable();
+    region_numbers
+}
@@ -179,6 +179,13 @@ pub enum Error {
         backtrace: Backtrace,
     },
 
+    #[snafu(display("Failed to open region {}, source: {}", region_name, source))]
+    OpenRegion {
+        region_name: String,
+        #[snafu(backtrace)]
+        source: BoxedError,
+    },
+
     #[snafu(display("Invalid region name: {}", region_name))]
     InvalidRegionName {
         region_name: String,
@@ -187,6 +194,38 @@ pub enum Error {
 
     #[snafu(display("Invalid schema, source: {}", source))]
     InvalidRawSchema { source: datatypes::error::Error },
+
+    #[snafu(display(
+        "Failed to delete table manifest, table: {}, source: {}",
+        table_name,
+        source
+    ))]
+    DeleteTableManifest {
+        #[snafu(backtrace)]
+        source: storage::error::Error,
+        table_name: String,
+    },
+
+    #[snafu(display("Failed to drop region {}, source: {}", region_name, source))]
+    DropRegion {
+        region_name: String,
+        #[snafu(backtrace)]
+        source: BoxedError,
+    },
+
+    #[snafu(display("Failed to close region {}, source: {}", region_name, source))]
+    CloseRegion {
+        region_name: String,
+        #[snafu(backtrace)]
+        source: BoxedError,
+    },
+
+    #[snafu(display("Failed to delete table dir {}, source: {}", table_dir, source))]
+    DeleteTableDir {
+        table_dir: String,
+        source: object_store::Error,
+        backtrace: Backtrace,
+    },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -196,7 +235,10 @@ impl ErrorExt for Error {
         use Error::*;
 
         match self {
-            CreateRegion { source, .. } => source.status_code(),
+            CreateRegion { source, .. }
+            | OpenRegion { source, .. }
+            | CloseRegion { source, .. }
+            | DropRegion { source, .. } => source.status_code(),
 
             AlterTable { source, .. } => source.status_code(),
 
@@ -215,7 +257,10 @@ impl ErrorExt for Error {
 
             TableInfoNotFound { .. } | ConvertRaw { .. } => StatusCode::Unexpected,
 
-            ScanTableManifest { .. } | UpdateTableManifest { .. } => StatusCode::StorageUnavailable,
+            ScanTableManifest { .. }
+            | UpdateTableManifest { .. }
+            | DeleteTableManifest { .. }
+            | DeleteTableDir { .. } => StatusCode::StorageUnavailable,
             RegionNotFound { .. } => StatusCode::Internal,
             InvalidRegionName { .. } => StatusCode::Internal,
         }
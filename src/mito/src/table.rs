@@ -26,6 +26,7 @@ use common_error::ext::BoxedError;
 use common_query::logical_plan::Expr;
 use common_query::physical_plan::PhysicalPlanRef;
 use common_recordbatch::error::{ExternalSnafu, Result as RecordBatchResult};
+use common_recordbatch::scan_stats::ScanStatsRecorder;
 use common_recordbatch::{RecordBatch, RecordBatchStream};
 use common_telemetry::logging;
 use datatypes::schema::Schema;
@@ -68,7 +69,7 @@ pub struct MitoTable<R: Region> {
     manifest: TableManifest,
     // guarded by `self.alter_lock`
     table_info: ArcSwap<TableInfo>,
-    regions: HashMap<RegionNumber, R>,
+    regions: ArcSwap<HashMap<RegionNumber, R>>,
     alter_lock: Mutex<()>,
 }
 
@@ -87,19 +88,7 @@ impl<R: Region> Table for MitoTable<R> {
             return Ok(0);
         }
 
-        let region = self
-            .regions
-            .get(&request.region_number)
-            .with_context(|| RegionNotFoundSnafu {
-                table: common_catalog::format_full_table_name(
-                    &request.catalog_name,
-                    &request.schema_name,
-                    &request.table_name,
-                ),
-                region: request.region_number,
-            })
-            .map_err(BoxedError::new)
-            .context(table_error::TableOperationSnafu)?;
+        let region = self.region_to_insert(&request)?;
         let mut write_request = region.write_request();
 
         let columns_values = request.columns_values;
@@ -142,14 +131,16 @@ impl<R: Region> Table for MitoTable<R> {
         _limit: Option<usize>,
     ) -> TableResult<PhysicalPlanRef> {
         let read_ctx = ReadContext::default();
-        let mut readers = Vec::with_capacity(self.regions.len());
+        let regions = self.regions();
+        let mut readers = Vec::with_capacity(regions.len());
         let mut first_schema: Option<Arc<Schema>> = None;
 
         let table_info = self.table_info.load();
+        let scan_stats = Arc::new(ScanStatsRecorder::default());
         // TODO(hl): Currently the API between frontend and datanode is under refactoring in
         // https://github.com/GreptimeTeam/greptimedb/issues/597 . Once it's finished, query plan
         // can carry filtered region info to avoid scanning all regions on datanode.
-        for region in self.regions.values() {
+        for region in regions.values() {
             let snapshot = region
                 .snapshot(&read_ctx)
                 .map_err(BoxedError::new)
@@ -162,6 +153,7 @@ impl<R: Region> Table for MitoTable<R> {
             let scan_request = ScanRequest {
                 projection,
                 filters,
+                scan_stats: Some(scan_stats.clone()),
                 ..Default::default()
             };
             let reader = snapshot
@@ -205,7 +197,14 @@ impl<R: Region> Table for MitoTable<R> {
         });
 
         let stream = Box::pin(ChunkStream { schema, stream });
-        Ok(Arc::new(SimpleTableScan::new(stream)))
+        let table_name = common_catalog::format_full_table_name(
+            &table_info.catalog_name,
+            &table_info.schema_name,
+            &table_info.name,
+        );
+        Ok(Arc::new(
+            SimpleTableScan::new(stream).with_scan_stats(table_name, scan_stats),
+        ))
     }
 
     fn supports_filters_pushdown(&self, filters: &[&Expr]) -> TableResult<Vec<FilterPushDownType>> {
@@ -225,7 +224,10 @@ impl<R: Region> Table for MitoTable<R> {
             AlterKind::RenameTable { new_table_name } => {
                 new_info.name = new_table_name.clone();
             }
-            AlterKind::AddColumns { .. } | AlterKind::DropColumns { .. } => {
+            AlterKind::AddColumns { .. }
+            | AlterKind::DropColumns { .. }
+            | AlterKind::SetTtl { .. }
+            | AlterKind::SetCompactionOptions { .. } => {
                 let table_meta = &table_info.meta;
                 let new_meta = table_meta
                     .builder_with_alter_kind(table_name, &req.alter_kind)?
@@ -284,6 +286,23 @@ impl<R: Region> Table for MitoTable<R> {
                     .context(TableOperationSnafu)?;
             }
         }
+        match &req.alter_kind {
+            AlterKind::SetTtl { ttl } => {
+                // The ttl has been persisted in the table manifest, so regions could pick it up
+                // when they are reopened.
+                for region in self.regions().values() {
+                    region.set_ttl(*ttl);
+                }
+            }
+            AlterKind::SetCompactionOptions { .. } => {
+                // Like the ttl, options of regions are loaded from the table manifest when
+                // they are reopened.
+                for region in self.regions().values() {
+                    region.set_compaction_options(new_info.meta.options.compaction);
+                }
+            }
+            _ => {}
+        }
         // Update in memory metadata of the table.
         self.set_table_info(new_info);
 
@@ -297,7 +316,7 @@ impl<R: Region> Table for MitoTable<R> {
         let mut rows_deleted = 0;
         // TODO(hl): Should be tracked by procedure.
         // TODO(hl): Parse delete request into region->keys instead of delete in each region
-        for region in self.regions.values() {
+        for region in self.regions().values() {
             let mut write_request = region.write_request();
             let key_column_values = request.key_column_values.clone();
             // Safety: key_column_values isn't empty.
@@ -323,6 +342,88 @@ impl<R: Region> Table for MitoTable<R> {
         Ok(rows_deleted)
     }
 
+    async fn begin_bulk_load(&self, load_id: &str) -> TableResult<()> {
+        let regions = self.regions();
+        let begins = regions
+            .values()
+            .map(|region| region.begin_bulk_load(load_id));
+        if let Err(e) = futures::future::try_join_all(begins).await {
+            // Aborts the bulk load in regions where it has begun.
+            for region in regions.values() {
+                let _ = region.abort_bulk_load(load_id).await;
+            }
+            return Err(e)
+                .map_err(BoxedError::new)
+                .context(table_error::TableOperationSnafu);
+        }
+
+        logging::info!(
+            "Begin bulk load {} of table {}",
+            load_id,
+            self.table_info().name
+        );
+
+        Ok(())
+    }
+
+    async fn insert_bulk_load(&self, load_id: &str, request: InsertRequest) -> TableResult<usize> {
+        if request.columns_values.is_empty() {
+            return Ok(0);
+        }
+
+        let region = self.region_to_insert(&request)?;
+        let mut write_request = region.write_request();
+
+        let columns_values = request.columns_values;
+        // columns_values is not empty, it's safe to unwrap
+        let rows_num = columns_values.values().next().unwrap().len();
+
+        write_request
+            .put(columns_values)
+            .map_err(BoxedError::new)
+            .context(table_error::TableOperationSnafu)?;
+
+        let _resp = region
+            .write_bulk_load(load_id, write_request)
+            .await
+            .map_err(BoxedError::new)
+            .context(table_error::TableOperationSnafu)?;
+
+        Ok(rows_num)
+    }
+
+    async fn commit_bulk_load(&self, load_id: &str) -> TableResult<()> {
+        futures::future::try_join_all(
+            self.regions()
+                .values()
+                .map(|region| region.commit_bulk_load(load_id)),
+        )
+        .await
+        .map_err(BoxedError::new)
+        .context(table_error::TableOperationSnafu)?;
+
+        logging::info!(
+            "Committed bulk load {} of table {}",
+            load_id,
+            self.table_info().name
+        );
+
+        Ok(())
+    }
+
+    async fn abort_bulk_load(&self, load_id: &str) -> TableResult<()> {
+        futures::future::try_join_all(
+            self.regions()
+                .values()
+                .map(|region| region.abort_bulk_load(load_id)),
+        )
+        .await
+        .map_err(BoxedError::new)
+        .context(table_error::TableOperationSnafu)?;
+
+        Ok(())
+    }
+
     async fn flush(
         &self,
         region_number: Option<RegionNumber>,
@@ -330,7 +431,7 @@ impl<R: Region> Table for MitoTable<R> {
     ) -> TableResult<()> {
         let flush_ctx = wait.map(|wait| FlushContext { wait }).unwrap_or_default();
         if let Some(region_number) = region_number {
-            if let Some(region) = self.regions.get(&region_number) {
+            if let Some(region) = self.regions().get(&region_number) {
                 region
                     .flush(&flush_ctx)
                     .await
@@ -339,7 +440,9 @@ impl<R: Region> Table for MitoTable<R> {
             }
         } else {
             futures::future::try_join_all(
-                self.regions.values().map(|region| region.flush(&flush_ctx)),
+                self.regions()
+                    .values()
+                    .map(|region| region.flush(&flush_ctx)),
             )
             .await
             .map_err(BoxedError::new)
@@ -350,7 +453,7 @@ impl<R: Region> Table for MitoTable<R> {
     }
 
     async fn close(&self) -> TableResult<()> {
-        futures::future::try_join_all(self.regions.values().map(|region| region.close()))
+        futures::future::try_join_all(self.regions().values().map(|region| region.close()))
             .await
             .map_err(BoxedError::new)
             .context(table_error::TableOperationSnafu)?;
@@ -360,11 +463,16 @@ impl<R: Region> Table for MitoTable<R> {
 
     fn region_stats(&self) -> TableResult<Vec<RegionStat>> {
         Ok(self
-            .regions
+            .regions()
             .values()
             .map(|region| RegionStat {
                 region_id: region.id(),
+                memtable_usage_bytes: region.memtable_usage_bytes(),
                 disk_usage_bytes: region.disk_usage_bytes(),
+                written_rows: region.written_rows(),
+                write_stalled: region.is_write_stalled(),
+                max_timestamp: region.max_timestamp(),
+                approximate_rows: region.approximate_rows(),
             })
             .collect())
     }
@@ -402,12 +510,29 @@ impl<R: Region> MitoTable<R> {
     ) -> Self {
         Self {
             table_info: ArcSwap::new(Arc::new(table_info)),
-            regions,
+            regions: ArcSwap::new(Arc::new(regions)),
             manifest,
             alter_lock: Mutex::new(()),
         }
     }
 
+    /// Returns the region to insert rows of the `request`.
+    fn region_to_insert(&self, request: &InsertRequest) -> TableResult<R> {
+        self.regions()
+            .get(&request.region_number)
+            .cloned()
+            .with_context(|| RegionNotFoundSnafu {
+                table: common_catalog::format_full_table_name(
+                    &request.catalog_name,
+                    &request.schema_name,
+                    &request.table_name,
+                ),
+                region: request.region_number,
+            })
+            .map_err(BoxedError::new)
+            .context(table_error::TableOperationSnafu)
+    }
+
     /// Transform projection which is based on table schema
     /// into projection based on region schema.
     fn transform_projection(
@@ -538,8 +663,29 @@ impl<R: Region> MitoTable<R> {
     }
 
     #[inline]
-    pub fn regions(&self) -> &HashMap<RegionNumber, R> {
-        &self.regions
+    pub fn regions(&self) -> Arc<HashMap<RegionNumber, R>> {
+        self.regions.load_full()
+    }
+
+    /// Adds a region that is opened after the table, e.g. a region failed to open
+    /// when opening the table.
+    pub(crate) fn add_region(&self, region_number: RegionNumber, region: R) {
+        self.regions.rcu(|regions| {
+            let mut regions = HashMap::clone(regions);
+            regions.insert(region_number, region.clone());
+            regions
+        });
+    }
+
+    /// Removes a closed region from the table.
+    pub(crate) fn remove_region(&self, region_number: RegionNumber) -> Option<R> {
+        let mut removed = None;
+        self.regions.rcu(|regions| {
+            let mut regions = HashMap::clone(regions);
+            removed = regions.remove(&region_number);
+            regions
+        });
+        removed
     }
 
     pub fn set_table_info(&self, table_info: TableInfo) {
@@ -572,6 +718,10 @@ fn create_alter_operation(
         })),
         // No need to build alter operation when reaming tables.
         AlterKind::RenameTable { .. } => Ok(None),
+        // Ttl is not a part of region metadata, regions update it via `Region::set_ttl()`.
+        AlterKind::SetTtl { .. } => Ok(None),
+        // Regions update compaction options via `Region::set_compaction_options()`.
+        AlterKind::SetCompactionOptions { .. } => Ok(None),
     }
 }
 
@@ -16,19 +16,23 @@
 
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
 
 use arc_swap::ArcSwap;
 use async_trait::async_trait;
 use common_error::mock::MockError;
+use common_error::prelude::StatusCode;
 use common_telemetry::logging;
+use common_time::Timestamp;
 use datatypes::prelude::{DataType, Value, VectorRef};
 use datatypes::schema::{ColumnSchema, Schema};
 use storage::metadata::{RegionMetaImpl, RegionMetadata};
 use storage::write_batch::WriteBatch;
 use store_api::storage::{
-    AlterRequest, Chunk, ChunkReader, CreateOptions, EngineContext, FlushContext, GetRequest,
-    GetResponse, OpenOptions, ReadContext, Region, RegionDescriptor, RegionId, ScanRequest,
-    ScanResponse, SchemaRef, Snapshot, StorageEngine, WriteContext, WriteResponse,
+    AlterRequest, Chunk, ChunkReader, CompactionOptions, CreateOptions, DropOptions,
+    EngineContext, FlushContext, GetRequest, GetResponse, OpenOptions, ReadContext, Region,
+    RegionDescriptor, RegionId, ScanRequest, ScanResponse, SchemaRef, Snapshot, StorageEngine,
+    WriteContext, WriteResponse,
 };
 
 pub type Result<T> = std::result::Result<T, MockError>;
@@ -140,6 +144,10 @@ pub struct MockRegionInner {
     name: String,
     pub metadata: ArcSwap<RegionMetadata>,
     memtable: Arc<RwLock<MockMemtable>>,
+    ttl: RwLock<Option<Duration>>,
+    compaction_options: RwLock<CompactionOptions>,
+    /// Requests of uncommitted bulk loads.
+    bulk_loads: Mutex<HashMap<String, Vec<WriteBatch>>>,
 }
 
 /// A columnar memtable, maps column name to data of that column in each row.
@@ -197,13 +205,82 @@ impl Region for MockRegion {
         Ok(())
     }
 
+    fn memtable_usage_bytes(&self) -> u64 {
+        0
+    }
+
+    fn written_rows(&self) -> u64 {
+        0
+    }
+
+    fn is_write_stalled(&self) -> bool {
+        false
+    }
+
     fn disk_usage_bytes(&self) -> u64 {
         0
     }
 
+    fn max_timestamp(&self) -> Option<Timestamp> {
+        None
+    }
+
+    fn approximate_rows(&self) -> u64 {
+        0
+    }
+
     async fn flush(&self, _ctx: &FlushContext) -> Result<()> {
         unimplemented!()
     }
+
+    fn ttl(&self) -> Option<Duration> {
+        *self.inner.ttl.read().unwrap()
+    }
+
+    fn set_ttl(&self, ttl: Option<Duration>) {
+        *self.inner.ttl.write().unwrap() = ttl;
+    }
+
+    fn compaction_options(&self) -> CompactionOptions {
+        *self.inner.compaction_options.read().unwrap()
+    }
+
+    fn set_compaction_options(&self, options: CompactionOptions) {
+        *self.inner.compaction_options.write().unwrap() = options;
+    }
+
+    async fn begin_bulk_load(&self, load_id: &str) -> Result<()> {
+        let mut bulk_loads = self.inner.bulk_loads.lock().unwrap();
+        if bulk_loads.contains_key(load_id) {
+            return Err(MockError::new(StatusCode::InvalidArguments));
+        }
+        bulk_loads.insert(load_id.to_string(), Vec::new());
+        Ok(())
+    }
+
+    async fn write_bulk_load(&self, load_id: &str, request: WriteBatch) -> Result<WriteResponse> {
+        self.inner
+            .bulk_loads
+            .lock()
+            .unwrap()
+            .get_mut(load_id)
+            .ok_or_else(|| MockError::new(StatusCode::InvalidArguments))?
+            .push(request);
+        Ok(WriteResponse {})
+    }
+
+    async fn commit_bulk_load(&self, load_id: &str) -> Result<()> {
+        let requests = self.inner.remove_bulk_load(load_id)?;
+        for request in requests {
+            self.inner.write(request);
+        }
+        Ok(())
+    }
+
+    async fn abort_bulk_load(&self, load_id: &str) -> Result<()> {
+        let _ = self.inner.remove_bulk_load(load_id)?;
+        Ok(())
+    }
 }
 
 impl MockRegionInner {
@@ -216,6 +293,9 @@ impl MockRegionInner {
             name: metadata.name().to_string(),
             metadata: ArcSwap::new(Arc::new(metadata)),
             memtable: Arc::new(RwLock::new(memtable)),
+            ttl: RwLock::new(None),
+            compaction_options: RwLock::new(CompactionOptions::default()),
+            bulk_loads: Mutex::new(HashMap::new()),
         }
     }
 
@@ -234,6 +314,14 @@ impl MockRegionInner {
         self.metadata.swap(Arc::new(metadata));
     }
 
+    fn remove_bulk_load(&self, load_id: &str) -> Result<Vec<WriteBatch>> {
+        self.bulk_loads
+            .lock()
+            .unwrap()
+            .remove(load_id)
+            .ok_or_else(|| MockError::new(StatusCode::InvalidArguments))
+    }
+
     fn write(&self, request: WriteBatch) {
         let metadata = self.metadata.load();
 
@@ -318,8 +406,16 @@ impl StorageEngine for MockEngine {
         Ok(region)
     }
 
-    async fn drop_region(&self, _ctx: &EngineContext, _region: Self::Region) -> Result<()> {
-        unimplemented!()
+    async fn drop_region(
+        &self,
+        _ctx: &EngineContext,
+        region: Self::Region,
+        _opts: &DropOptions,
+    ) -> Result<()> {
+        let mut regions = self.regions.lock().unwrap();
+        regions.opened_regions.remove(region.name());
+        regions.closed_regions.remove(region.name());
+        Ok(())
     }
 
     fn get_region(&self, _ctx: &EngineContext, name: &str) -> Result<Option<MockRegion>> {
@@ -12,34 +12,79 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::io;
-use std::num::NonZeroUsize;
-use std::ops::DerefMut;
 use std::pin::Pin;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::task::{Context, Poll};
 
 use async_trait::async_trait;
+use bytes::Bytes;
 use futures::AsyncRead;
 use lru::LruCache;
+use metrics::{counter, gauge, increment_counter};
 use opendal::ops::*;
+use opendal::raw::output::Page;
 use opendal::raw::*;
-use opendal::{ErrorKind, Result};
-use tokio::sync::Mutex;
+use opendal::{ErrorKind, ObjectMode, Result};
 
+use crate::metrics::{
+    METRIC_OBJECT_STORE_CACHE_ADMISSIONS_TOTAL, METRIC_OBJECT_STORE_CACHE_BYTES,
+    METRIC_OBJECT_STORE_CACHE_EVICTIONS_TOTAL,
+};
+
+/// Caches the objects read from the inner accessor in the `cache` accessor, keeping the
+/// total size of the cache files within `capacity` bytes by evicting the least recently
+/// used ones.
 pub struct LruCacheLayer<C> {
     cache: Arc<C>,
-    lru_cache: Arc<Mutex<LruCache<String, ()>>>,
+    index: Arc<Mutex<CacheIndex>>,
 }
 
 impl<C: Accessor> LruCacheLayer<C> {
-    pub fn new(cache: Arc<C>, capacity: usize) -> Self {
-        Self {
-            cache,
-            lru_cache: Arc::new(Mutex::new(LruCache::new(
-                NonZeroUsize::new(capacity).unwrap(),
-            ))),
+    /// Creates the layer, rebuilding the index from the files already in `cache` so the
+    /// capacity still holds after a restart.
+    pub async fn new(cache: Arc<C>, capacity: u64) -> Result<Self> {
+        let mut files = Vec::new();
+        let mut dirs = vec!["/".to_string()];
+        while let Some(dir) = dirs.pop() {
+            let mut pager = match cache.list(&dir, OpList::new()).await {
+                Ok((_, pager)) => pager,
+                Err(err) if err.kind() == ErrorKind::ObjectNotFound => continue,
+                Err(err) => return Err(err),
+            };
+            while let Some(entries) = pager.next_page().await? {
+                for entry in entries {
+                    match entry.mode() {
+                        ObjectMode::DIR => dirs.push(entry.path().to_string()),
+                        ObjectMode::FILE => {
+                            let meta = cache.stat(entry.path(), OpStat::new()).await?;
+                            let meta = meta.into_metadata();
+                            files.push((
+                                entry.path().to_string(),
+                                meta.content_length(),
+                                meta.last_modified(),
+                            ));
+                        }
+                        ObjectMode::Unknown => {}
+                    }
+                }
+            }
         }
+        // The most recently modified files are the most recently used ones.
+        files.sort_by_key(|(_, _, last_modified)| *last_modified);
+
+        let mut index = CacheIndex::new(capacity);
+        for (path, size, _) in files {
+            index.insert(path, size);
+        }
+        let evicted = index.shrink_to(capacity);
+        delete_cache_files(&*cache, evicted).await;
+
+        Ok(Self {
+            cache,
+            index: Arc::new(Mutex::new(index)),
+        })
     }
 }
 
@@ -50,22 +95,157 @@ impl<I: Accessor, C: Accessor> Layer<I> for LruCacheLayer<C> {
         LruCacheAccessor {
             inner: Arc::new(inner),
             cache: self.cache.clone(),
-            lru_cache: self.lru_cache.clone(),
+            index: self.index.clone(),
         }
     }
 }
 
+/// Sizes of the cache files in LRU order, and the files being read.
+#[derive(Debug)]
+struct CacheIndex {
+    /// Cache file path to its size in bytes.
+    lru: LruCache<String, u64>,
+    /// Number of ongoing reads of each cache file. These files are never evicted.
+    readers: HashMap<String, usize>,
+    /// Total size of the files in `lru`.
+    bytes: u64,
+    capacity: u64,
+}
+
+impl CacheIndex {
+    fn new(capacity: u64) -> Self {
+        Self {
+            lru: LruCache::unbounded(),
+            readers: HashMap::new(),
+            bytes: 0,
+            capacity,
+        }
+    }
+
+    fn insert(&mut self, path: String, size: u64) {
+        if let Some(old) = self.lru.push(path, size) {
+            self.bytes -= old.1;
+        }
+        self.bytes += size;
+        self.update_gauge();
+    }
+
+    fn remove(&mut self, path: &str) {
+        if let Some(size) = self.lru.pop(path) {
+            self.bytes -= size;
+            self.update_gauge();
+        }
+    }
+
+    /// Makes room for a new file of `size` bytes at `path` and reserves it, the reserved
+    /// file is pinned as if it's being read.
+    ///
+    /// Returns the evicted files, or `None` if the file can't be admitted even if all the
+    /// files not being read are evicted.
+    fn admit(&mut self, path: &str, size: u64) -> Option<Vec<String>> {
+        if self.lru.contains(path) || size > self.capacity {
+            return None;
+        }
+        let evictable: u64 = self
+            .lru
+            .iter()
+            .filter(|(k, _)| !self.readers.contains_key(*k))
+            .map(|(_, size)| size)
+            .sum();
+        if self.bytes - evictable + size > self.capacity {
+            return None;
+        }
+
+        let evicted = self.shrink_to(self.capacity - size);
+        self.insert(path.to_string(), size);
+        self.pin(path);
+        increment_counter!(METRIC_OBJECT_STORE_CACHE_ADMISSIONS_TOTAL);
+        Some(evicted)
+    }
+
+    /// Evicts the least recently used files not being read until the total size is at most
+    /// `bytes`, returns the evicted files.
+    fn shrink_to(&mut self, bytes: u64) -> Vec<String> {
+        let mut evicted = Vec::new();
+        while self.bytes > bytes {
+            let victim = self
+                .lru
+                .iter()
+                .rev()
+                .map(|(k, _)| k)
+                .find(|k| !self.readers.contains_key(*k))
+                .cloned();
+            let Some(victim) = victim else {
+                break;
+            };
+            self.remove(&victim);
+            evicted.push(victim);
+        }
+        counter!(
+            METRIC_OBJECT_STORE_CACHE_EVICTIONS_TOTAL,
+            evicted.len() as u64
+        );
+        evicted
+    }
+
+    /// Marks the file at `path` as being read and moves it to the front of the LRU, returns
+    /// false if the file isn't in the cache.
+    fn pin(&mut self, path: &str) -> bool {
+        if self.lru.get(path).is_none() {
+            return false;
+        }
+        *self.readers.entry(path.to_string()).or_default() += 1;
+        true
+    }
+
+    fn unpin(&mut self, path: &str) {
+        if let Some(readers) = self.readers.get_mut(path) {
+            *readers -= 1;
+            if *readers == 0 {
+                self.readers.remove(path);
+            }
+        }
+    }
+
+    fn update_gauge(&self) {
+        gauge!(METRIC_OBJECT_STORE_CACHE_BYTES, self.bytes as f64);
+    }
+}
+
+/// Unpins the cache file on drop.
+struct PinGuard {
+    index: Arc<Mutex<CacheIndex>>,
+    path: String,
+}
+
+impl Drop for PinGuard {
+    fn drop(&mut self) {
+        self.index.lock().unwrap().unpin(&self.path);
+    }
+}
+
 #[derive(Debug)]
 pub struct LruCacheAccessor<I, C> {
     inner: Arc<I>,
     cache: Arc<C>,
-    lru_cache: Arc<Mutex<LruCache<String, ()>>>,
+    index: Arc<Mutex<CacheIndex>>,
 }
 
 impl<I, C> LruCacheAccessor<I, C> {
     fn cache_path(&self, path: &str, args: &OpRead) -> String {
         format!("{}.cache-{}", path, args.range().to_header())
     }
+
+    fn pin(&self, cache_path: &str) -> Option<PinGuard> {
+        self.index
+            .lock()
+            .unwrap()
+            .pin(cache_path)
+            .then(|| PinGuard {
+                index: self.index.clone(),
+                path: cache_path.to_string(),
+            })
+    }
 }
 
 #[async_trait]
@@ -83,44 +263,50 @@ impl<I: Accessor, C: Accessor> LayeredAccessor for LruCacheAccessor<I, C> {
     async fn read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::Reader)> {
         let path = path.to_string();
         let cache_path = self.cache_path(&path, &args);
-        let lru_cache = self.lru_cache.clone();
-
-        match self.cache.read(&cache_path, OpRead::default()).await {
-            Ok((rp, r)) => {
-                // update lru when cache hit
-                let mut lru_cache = lru_cache.lock().await;
-                lru_cache.get_or_insert(cache_path.clone(), || ());
-                Ok(to_output_reader((rp, r)))
-            }
-            Err(err) if err.kind() == ErrorKind::ObjectNotFound => {
-                let (rp, reader) = self.inner.read(&path, args.clone()).await?;
-                let size = rp.clone().into_metadata().content_length();
-                let _ = self
-                    .cache
-                    .write(
-                        &cache_path,
-                        OpWrite::new(size),
-                        Box::new(ReadWrapper(reader)),
-                    )
-                    .await?;
-                match self.cache.read(&cache_path, OpRead::default()).await {
-                    Ok((rp, reader)) => {
-                        let r = {
-                            // push new cache file name to lru
-                            let mut lru_cache = lru_cache.lock().await;
-                            lru_cache.push(cache_path.clone(), ())
-                        };
-                        // delete the evicted cache file
-                        if let Some((k, _v)) = r {
-                            let _ = self.cache.delete(&k, OpDelete::new()).await;
-                        }
-                        return Ok(to_output_reader((rp, reader)));
-                    }
-                    Err(_) => return self.inner.read(&path, args).await.map(to_output_reader),
+
+        // Cache hit, the file is pinned until the reader is dropped.
+        if let Some(guard) = self.pin(&cache_path) {
+            match self.cache.read(&cache_path, OpRead::default()).await {
+                Ok((rp, reader)) => return Ok(to_pinned_reader((rp, reader), guard)),
+                Err(err) if err.kind() == ErrorKind::ObjectNotFound => {
+                    // The cache file was removed externally.
+                    drop(guard);
+                    self.index.lock().unwrap().remove(&cache_path);
                 }
+                Err(_) => return self.inner.read(&path, args).await.map(to_output_reader),
+            }
+        }
+
+        let (rp, reader) = self.inner.read(&path, args.clone()).await?;
+        let size = rp.clone().into_metadata().content_length();
+        let Some(evicted) = self.index.lock().unwrap().admit(&cache_path, size) else {
+            return Ok(to_output_reader((rp, reader)));
+        };
+        delete_cache_files(&*self.cache, evicted).await;
+        // The admitted file is already pinned.
+        let guard = PinGuard {
+            index: self.index.clone(),
+            path: cache_path.clone(),
+        };
+
+        let written = self
+            .cache
+            .write(
+                &cache_path,
+                OpWrite::new(size),
+                Box::new(ReadWrapper(reader)),
+            )
+            .await;
+        if written.is_ok() {
+            if let Ok((rp, reader)) = self.cache.read(&cache_path, OpRead::default()).await {
+                return Ok(to_pinned_reader((rp, reader), guard));
             }
-            Err(_) => return self.inner.read(&path, args).await.map(to_output_reader),
         }
+
+        drop(guard);
+        self.index.lock().unwrap().remove(&cache_path);
+        delete_cache_files(&*self.cache, vec![cache_path]).await;
+        self.inner.read(&path, args).await.map(to_output_reader)
     }
 
     fn blocking_read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::BlockingReader)> {
@@ -129,24 +315,22 @@ impl<I: Accessor, C: Accessor> LayeredAccessor for LruCacheAccessor<I, C> {
 
     async fn delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
         let path = path.to_string();
-        let lru_cache = self.lru_cache.clone();
+        let prefix = format!("{path}.cache-");
 
         let cache_files: Vec<String> = {
-            let mut guard = lru_cache.lock().await;
-            let lru = guard.deref_mut();
-            let cache_files = lru
+            let mut index = self.index.lock().unwrap();
+            let cache_files = index
+                .lru
                 .iter()
-                .filter(|(k, _v)| k.starts_with(format!("{path}.cache-").as_str()))
+                .filter(|(k, _v)| k.starts_with(&prefix))
                 .map(|(k, _v)| k.clone())
                 .collect::<Vec<_>>();
             for k in &cache_files {
-                lru.pop(k);
+                index.remove(k);
             }
             cache_files
         };
-        for file in cache_files {
-            let _ = self.cache.delete(&file, OpDelete::new()).await;
-        }
+        delete_cache_files(&*self.cache, cache_files).await;
         return self.inner.delete(&path, args).await;
     }
 
@@ -167,6 +351,12 @@ impl<I: Accessor, C: Accessor> LayeredAccessor for LruCacheAccessor<I, C> {
     }
 }
 
+async fn delete_cache_files<C: Accessor>(cache: &C, files: Vec<String>) {
+    for file in files {
+        let _ = cache.delete(&file, OpDelete::new()).await;
+    }
+}
+
 /// TODO: Workaround for output::Read doesn't implement input::Read
 ///
 /// Should be remove after opendal fixed it.
@@ -182,7 +372,41 @@ impl<R: output::Read> AsyncRead for ReadWrapper<R> {
     }
 }
 
+/// Reader of a cache file, which keeps the file pinned until it's dropped.
+struct PinnedReader<R> {
+    reader: R,
+    _guard: PinGuard,
+}
+
+impl<R: output::Read> output::Read for PinnedReader<R> {
+    fn poll_read(&mut self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        self.reader.poll_read(cx, buf)
+    }
+
+    fn poll_seek(&mut self, cx: &mut Context<'_>, pos: io::SeekFrom) -> Poll<io::Result<u64>> {
+        self.reader.poll_seek(cx, pos)
+    }
+
+    fn poll_next(&mut self, cx: &mut Context<'_>) -> Poll<Option<io::Result<Bytes>>> {
+        self.reader.poll_next(cx)
+    }
+}
+
 #[inline]
 fn to_output_reader<R: output::Read + 'static>(input: (RpRead, R)) -> (RpRead, output::Reader) {
     (input.0, Box::new(input.1))
 }
+
+#[inline]
+fn to_pinned_reader<R: output::Read + 'static>(
+    input: (RpRead, R),
+    guard: PinGuard,
+) -> (RpRead, output::Reader) {
+    (
+        input.0,
+        Box::new(PinnedReader {
+            reader: input.1,
+            _guard: guard,
+        }),
+    )
+}
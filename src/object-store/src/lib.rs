@@ -14,8 +14,11 @@
 
 pub use opendal::{
     layers, services, Builder as ObjectStoreBuilder, Error, ErrorKind, Object, ObjectLister,
-    ObjectMetadata, ObjectMode, Operator as ObjectStore, Result,
+    ObjectMetadata, ObjectMode, Operator as ObjectStore, Result, Scheme,
 };
 pub mod cache_policy;
+pub mod metrics;
+pub mod read_only;
+pub mod retry;
 pub mod test_util;
 pub mod util;
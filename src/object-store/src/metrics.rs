@@ -0,0 +1,146 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Metrics of the object store, and a layer that records the number of requests and bytes
+//! transferred by each operation.
+
+use async_trait::async_trait;
+use metrics::{counter, increment_counter};
+use opendal::ops::*;
+use opendal::raw::*;
+use opendal::Result;
+
+/// Number of requests, labeled by operation.
+pub const METRIC_OBJECT_STORE_REQUESTS_TOTAL: &str = "object_store.requests_total";
+/// Number of bytes read or written, labeled by operation.
+pub const METRIC_OBJECT_STORE_BYTES_TOTAL: &str = "object_store.bytes_total";
+/// Number of failed requests, labeled by operation.
+pub const METRIC_OBJECT_STORE_ERRORS_TOTAL: &str = "object_store.errors_total";
+/// Total size of the files in the read cache.
+pub const METRIC_OBJECT_STORE_CACHE_BYTES: &str = "object_store.cache_bytes";
+/// Number of files admitted to the read cache.
+pub const METRIC_OBJECT_STORE_CACHE_ADMISSIONS_TOTAL: &str = "object_store.cache_admissions_total";
+/// Number of files evicted from the read cache.
+pub const METRIC_OBJECT_STORE_CACHE_EVICTIONS_TOTAL: &str = "object_store.cache_evictions_total";
+
+const LABEL_OP: &str = "op";
+
+/// Records requests, bytes and errors of the inner accessor to the global metrics recorder.
+///
+/// Bytes of a read are the content length of the response, and bytes of a write are the
+/// size of the request, so the readers are not wrapped.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ObjectStoreMetricsLayer;
+
+impl<A: Accessor> Layer<A> for ObjectStoreMetricsLayer {
+    type LayeredAccessor = ObjectStoreMetricsAccessor<A>;
+
+    fn layer(&self, inner: A) -> Self::LayeredAccessor {
+        ObjectStoreMetricsAccessor { inner }
+    }
+}
+
+#[derive(Debug)]
+pub struct ObjectStoreMetricsAccessor<A> {
+    inner: A,
+}
+
+/// Records a request of `op`, and its error if any.
+fn record<T>(op: &'static str, result: Result<T>) -> Result<T> {
+    increment_counter!(METRIC_OBJECT_STORE_REQUESTS_TOTAL, LABEL_OP => op);
+    if result.is_err() {
+        increment_counter!(METRIC_OBJECT_STORE_ERRORS_TOTAL, LABEL_OP => op);
+    }
+    result
+}
+
+fn record_bytes(op: &'static str, bytes: u64) {
+    counter!(METRIC_OBJECT_STORE_BYTES_TOTAL, bytes, LABEL_OP => op);
+}
+
+#[async_trait]
+impl<A: Accessor> LayeredAccessor for ObjectStoreMetricsAccessor<A> {
+    type Inner = A;
+    type Reader = A::Reader;
+    type BlockingReader = A::BlockingReader;
+    type Pager = A::Pager;
+    type BlockingPager = A::BlockingPager;
+
+    fn inner(&self) -> &Self::Inner {
+        &self.inner
+    }
+
+    async fn read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::Reader)> {
+        let result = record("read", self.inner.read(path, args).await);
+        if let Ok((rp, _)) = &result {
+            record_bytes("read", rp.clone().into_metadata().content_length());
+        }
+        result
+    }
+
+    fn blocking_read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::BlockingReader)> {
+        let result = record("read", self.inner.blocking_read(path, args));
+        if let Ok((rp, _)) = &result {
+            record_bytes("read", rp.clone().into_metadata().content_length());
+        }
+        result
+    }
+
+    async fn write(&self, path: &str, args: OpWrite, r: input::Reader) -> Result<RpWrite> {
+        let size = args.size();
+        let result = record("write", self.inner.write(path, args, r).await);
+        if result.is_ok() {
+            record_bytes("write", size);
+        }
+        result
+    }
+
+    async fn write_multipart(
+        &self,
+        path: &str,
+        args: OpWriteMultipart,
+        r: input::Reader,
+    ) -> Result<RpWriteMultipart> {
+        let size = args.size();
+        let result = record("write", self.inner.write_multipart(path, args, r).await);
+        if result.is_ok() {
+            record_bytes("write", size);
+        }
+        result
+    }
+
+    async fn stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
+        record("stat", self.inner.stat(path, args).await)
+    }
+
+    async fn delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
+        record("delete", self.inner.delete(path, args).await)
+    }
+
+    async fn list(&self, path: &str, args: OpList) -> Result<(RpList, Self::Pager)> {
+        record("list", self.inner.list(path, args).await)
+    }
+
+    async fn scan(&self, path: &str, args: OpScan) -> Result<(RpScan, Self::Pager)> {
+        record("list", self.inner.scan(path, args).await)
+    }
+
+    fn blocking_list(&self, path: &str, args: OpList) -> Result<(RpList, Self::BlockingPager)> {
+        record("list", self.inner.blocking_list(path, args))
+    }
+
+    fn blocking_scan(&self, path: &str, args: OpScan) -> Result<(RpScan, Self::BlockingPager)> {
+        record("list", self.inner.blocking_scan(path, args))
+    }
+}
@@ -0,0 +1,110 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A layer that rejects all the operations that modify the object store, used for the
+//! object stores accessed anonymously.
+
+use async_trait::async_trait;
+use opendal::ops::*;
+use opendal::raw::*;
+use opendal::{Error, ErrorKind, Result};
+
+/// Rejects writes, creations and deletions with [ErrorKind::ObjectPermissionDenied],
+/// reads, stats and lists are passed to the inner accessor.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ReadOnlyLayer;
+
+impl<A: Accessor> Layer<A> for ReadOnlyLayer {
+    type LayeredAccessor = ReadOnlyAccessor<A>;
+
+    fn layer(&self, inner: A) -> Self::LayeredAccessor {
+        ReadOnlyAccessor { inner }
+    }
+}
+
+#[derive(Debug)]
+pub struct ReadOnlyAccessor<A> {
+    inner: A,
+}
+
+fn read_only<T>(op: &'static str, path: &str) -> Result<T> {
+    Err(Error::new(
+        ErrorKind::ObjectPermissionDenied,
+        "the object store is read only with anonymous access",
+    )
+    .with_operation(op)
+    .with_context("path", path))
+}
+
+#[async_trait]
+impl<A: Accessor> LayeredAccessor for ReadOnlyAccessor<A> {
+    type Inner = A;
+    type Reader = A::Reader;
+    type BlockingReader = A::BlockingReader;
+    type Pager = A::Pager;
+    type BlockingPager = A::BlockingPager;
+
+    fn inner(&self) -> &Self::Inner {
+        &self.inner
+    }
+
+    async fn create(&self, path: &str, _: OpCreate) -> Result<RpCreate> {
+        read_only("create", path)
+    }
+
+    async fn write(&self, path: &str, _: OpWrite, _: input::Reader) -> Result<RpWrite> {
+        read_only("write", path)
+    }
+
+    async fn delete(&self, path: &str, _: OpDelete) -> Result<RpDelete> {
+        read_only("delete", path)
+    }
+
+    async fn create_multipart(
+        &self,
+        path: &str,
+        _: OpCreateMultipart,
+    ) -> Result<RpCreateMultipart> {
+        read_only("create_multipart", path)
+    }
+
+    async fn write_multipart(
+        &self,
+        path: &str,
+        _: OpWriteMultipart,
+        _: input::Reader,
+    ) -> Result<RpWriteMultipart> {
+        read_only("write_multipart", path)
+    }
+
+    async fn complete_multipart(
+        &self,
+        path: &str,
+        _: OpCompleteMultipart,
+    ) -> Result<RpCompleteMultipart> {
+        read_only("complete_multipart", path)
+    }
+
+    fn blocking_create(&self, path: &str, _: OpCreate) -> Result<RpCreate> {
+        read_only("blocking_create", path)
+    }
+
+    fn blocking_write(&self, path: &str, _: OpWrite, _: input::BlockingReader) -> Result<RpWrite> {
+        read_only("blocking_write", path)
+    }
+
+    fn blocking_delete(&self, path: &str, _: OpDelete) -> Result<RpDelete> {
+        read_only("blocking_delete", path)
+    }
+}
@@ -0,0 +1,313 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A layer that retries requests failed by temporary errors, such as throttling, 5xx
+//! responses and timeouts, with exponential backoff and jitter.
+//!
+//! Each request is retried at most [RetryOptions::max_attempts] times. Requests made inside
+//! [scope_task] also share the time budget [RetryOptions::task_budget] of the task, so a
+//! flush or compaction fails in bounded time when the object store is unavailable.
+
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::io::Cursor;
+use futures::AsyncReadExt;
+use metrics::increment_counter;
+use opendal::ops::*;
+use opendal::raw::*;
+use opendal::{Error, ErrorKind, Result};
+use rand::Rng;
+
+/// Number of retried requests, labeled by operation.
+pub const METRIC_OBJECT_STORE_RETRIES_TOTAL: &str = "object_store.retries_total";
+/// Number of requests that failed after running out of retry budget, labeled by operation.
+pub const METRIC_OBJECT_STORE_RETRIES_EXHAUSTED_TOTAL: &str =
+    "object_store.retries_exhausted_total";
+
+const LABEL_OP: &str = "op";
+
+tokio::task_local! {
+    /// Start time of the task scoped by [scope_task].
+    static TASK_START: Instant;
+}
+
+/// Runs `fut` as a task whose requests share the [RetryOptions::task_budget].
+pub async fn scope_task<F: Future>(fut: F) -> F::Output {
+    TASK_START.scope(Instant::now(), fut).await
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryOptions {
+    /// Max attempts of a request, including the first one.
+    pub max_attempts: usize,
+    /// Delay before the first retry, doubled on each retry.
+    pub retry_delay: Duration,
+    /// Max delay between two attempts.
+    pub max_retry_delay: Duration,
+    /// Max time a task scoped by [scope_task] may spend on its requests before it stops
+    /// retrying. Unbounded if not set.
+    pub task_budget: Option<Duration>,
+}
+
+impl Default for RetryOptions {
+    fn default() -> Self {
+        Self {
+            max_attempts: 4,
+            retry_delay: Duration::from_millis(200),
+            max_retry_delay: Duration::from_secs(10),
+            task_budget: Some(Duration::from_secs(600)),
+        }
+    }
+}
+
+impl RetryOptions {
+    /// Delay after the `attempt`-th attempt, picked at random from the upper half of the
+    /// exponential delay so concurrent requests don't retry in lockstep.
+    fn backoff(&self, attempt: usize) -> Duration {
+        let exp = self
+            .retry_delay
+            .saturating_mul(1 << (attempt - 1).min(16))
+            .min(self.max_retry_delay);
+        exp / 2 + exp.mul_f64(rand::thread_rng().gen_range(0.0..0.5))
+    }
+}
+
+/// Only temporary errors are retried, objects that are missing or not permitted fail
+/// immediately.
+fn is_retryable(err: &Error) -> bool {
+    err.is_temporary()
+        && !matches!(
+            err.kind(),
+            ErrorKind::ObjectNotFound | ErrorKind::ObjectPermissionDenied
+        )
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ObjectStoreRetryLayer {
+    options: RetryOptions,
+}
+
+impl ObjectStoreRetryLayer {
+    pub fn new(options: RetryOptions) -> Self {
+        Self { options }
+    }
+}
+
+impl<A: Accessor> Layer<A> for ObjectStoreRetryLayer {
+    type LayeredAccessor = ObjectStoreRetryAccessor<A>;
+
+    fn layer(&self, inner: A) -> Self::LayeredAccessor {
+        ObjectStoreRetryAccessor {
+            inner,
+            options: self.options.clone(),
+        }
+    }
+}
+
+/// Retries the requests to the inner accessor.
+///
+/// Bodies of writes are buffered so they can be sent again. Reads are retried until the
+/// response arrives, errors while reading the body are returned to the caller.
+#[derive(Debug)]
+pub struct ObjectStoreRetryAccessor<A> {
+    inner: A,
+    options: RetryOptions,
+}
+
+impl<A> ObjectStoreRetryAccessor<A> {
+    async fn retry<T, F, Fut>(&self, op: &'static str, mut f: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let start = Instant::now();
+        let deadline = match (
+            TASK_START.try_with(|start| *start),
+            self.options.task_budget,
+        ) {
+            (Ok(task_start), Some(budget)) => Some(task_start + budget),
+            _ => None,
+        };
+        let mut attempt = 1;
+        loop {
+            let err = match f().await {
+                Ok(v) => return Ok(v),
+                Err(err) if is_retryable(&err) => err,
+                Err(err) => return Err(err),
+            };
+
+            let delay = self.options.backoff(attempt);
+            let exhausted = if attempt >= self.options.max_attempts {
+                Some("attempts")
+            } else if deadline
+                .map(|d| Instant::now() + delay > d)
+                .unwrap_or(false)
+            {
+                Some("task time")
+            } else {
+                None
+            };
+            if let Some(budget) = exhausted {
+                increment_counter!(METRIC_OBJECT_STORE_RETRIES_EXHAUSTED_TOTAL, LABEL_OP => op);
+                return Err(err
+                    .with_context("retry_budget_exhausted", budget)
+                    .with_context("attempts", attempt.to_string())
+                    .with_context("elapsed", format!("{:?}", start.elapsed())));
+            }
+
+            increment_counter!(METRIC_OBJECT_STORE_RETRIES_TOTAL, LABEL_OP => op);
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+}
+
+/// Reads the body of a write into memory so it can be sent again.
+async fn buffer(mut r: input::Reader, size: u64) -> Result<Bytes> {
+    let mut buf = Vec::with_capacity(size as usize);
+    r.read_to_end(&mut buf).await.map_err(|e| {
+        Error::new(ErrorKind::Unexpected, "failed to read the body of a write").set_source(e)
+    })?;
+    Ok(buf.into())
+}
+
+fn to_reader(bytes: &Bytes) -> input::Reader {
+    Box::new(Cursor::new(bytes.clone()))
+}
+
+#[async_trait]
+impl<A: Accessor> LayeredAccessor for ObjectStoreRetryAccessor<A> {
+    type Inner = A;
+    type Reader = A::Reader;
+    type BlockingReader = A::BlockingReader;
+    type Pager = A::Pager;
+    type BlockingPager = A::BlockingPager;
+
+    fn inner(&self) -> &Self::Inner {
+        &self.inner
+    }
+
+    async fn read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::Reader)> {
+        self.retry("read", || self.inner.read(path, args.clone()))
+            .await
+    }
+
+    async fn write(&self, path: &str, args: OpWrite, r: input::Reader) -> Result<RpWrite> {
+        let body = buffer(r, args.size()).await?;
+        self.retry("write", || {
+            self.inner.write(path, args.clone(), to_reader(&body))
+        })
+        .await
+    }
+
+    async fn stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
+        self.retry("stat", || self.inner.stat(path, args.clone()))
+            .await
+    }
+
+    async fn delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
+        self.retry("delete", || self.inner.delete(path, args.clone()))
+            .await
+    }
+
+    async fn list(&self, path: &str, args: OpList) -> Result<(RpList, Self::Pager)> {
+        self.retry("list", || self.inner.list(path, args.clone()))
+            .await
+    }
+
+    async fn scan(&self, path: &str, args: OpScan) -> Result<(RpScan, Self::Pager)> {
+        self.retry("list", || self.inner.scan(path, args.clone()))
+            .await
+    }
+
+    async fn create_multipart(
+        &self,
+        path: &str,
+        args: OpCreateMultipart,
+    ) -> Result<RpCreateMultipart> {
+        self.retry("create_multipart", || {
+            self.inner.create_multipart(path, args.clone())
+        })
+        .await
+    }
+
+    async fn write_multipart(
+        &self,
+        path: &str,
+        args: OpWriteMultipart,
+        r: input::Reader,
+    ) -> Result<RpWriteMultipart> {
+        let body = buffer(r, args.size()).await?;
+        self.retry("write", || {
+            self.inner
+                .write_multipart(path, args.clone(), to_reader(&body))
+        })
+        .await
+    }
+
+    async fn complete_multipart(
+        &self,
+        path: &str,
+        args: OpCompleteMultipart,
+    ) -> Result<RpCompleteMultipart> {
+        self.retry("complete_multipart", || {
+            self.inner.complete_multipart(path, args.clone())
+        })
+        .await
+    }
+
+    async fn abort_multipart(
+        &self,
+        path: &str,
+        args: OpAbortMultipart,
+    ) -> Result<RpAbortMultipart> {
+        self.retry("abort_multipart", || {
+            self.inner.abort_multipart(path, args.clone())
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff() {
+        let options = RetryOptions {
+            retry_delay: Duration::from_millis(100),
+            max_retry_delay: Duration::from_millis(500),
+            ..Default::default()
+        };
+        for (attempt, max) in [(1, 100), (2, 200), (3, 400), (4, 500), (30, 500)] {
+            let delay = options.backoff(attempt);
+            let max = Duration::from_millis(max);
+            assert!(delay >= max / 2 && delay <= max, "{attempt}: {delay:?}");
+        }
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        let err = Error::new(ErrorKind::Unexpected, "slow down").set_temporary();
+        assert!(is_retryable(&err));
+        assert!(!is_retryable(&Error::new(ErrorKind::Unexpected, "bad")));
+        let err = Error::new(ErrorKind::ObjectNotFound, "missing").set_temporary();
+        assert!(!is_retryable(&err));
+        let err = Error::new(ErrorKind::ObjectPermissionDenied, "denied");
+        assert!(!is_retryable(&err));
+    }
+}
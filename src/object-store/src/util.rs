@@ -30,6 +30,31 @@ pub fn normalize_dir(dir: &str) -> String {
     dir
 }
 
+/// Normalize the root of an object store, ensure it starts and ends with exactly one '/',
+/// and the segments are separated by exactly one '/'.
+pub fn normalize_root(root: &str) -> String {
+    let segments = root
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>();
+    if segments.is_empty() {
+        "/".to_string()
+    } else {
+        format!("/{}/", segments.join("/"))
+    }
+}
+
+/// Join a directory and a file name with exactly one '/' between them, the directory
+/// may or may not end with '/', and the file name may or may not start with '/'.
+pub fn join_path(dir: &str, name: &str) -> String {
+    let name = name.trim_start_matches('/');
+    if dir.is_empty() {
+        return name.to_string();
+    }
+
+    format!("{}/{}", dir.trim_end_matches('/'), name)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -40,4 +65,28 @@ mod tests {
         assert_eq!("/", normalize_dir(""));
         assert_eq!("/test/", normalize_dir("/test"));
     }
+
+    #[test]
+    fn test_normalize_root() {
+        assert_eq!("/", normalize_root(""));
+        assert_eq!("/", normalize_root("/"));
+        assert_eq!("/", normalize_root("//"));
+        assert_eq!("/data/", normalize_root("data"));
+        assert_eq!("/data/", normalize_root("data/"));
+        assert_eq!("/data/", normalize_root("/data"));
+        assert_eq!("/data/", normalize_root("//data//"));
+        assert_eq!("/data/greptime/", normalize_root("data//greptime"));
+    }
+
+    #[test]
+    fn test_join_path() {
+        assert_eq!("dir/a.csv", join_path("dir", "a.csv"));
+        assert_eq!("dir/a.csv", join_path("dir/", "a.csv"));
+        assert_eq!("dir/a.csv", join_path("dir", "/a.csv"));
+        assert_eq!("dir/a.csv", join_path("dir/", "/a.csv"));
+        assert_eq!("dir/a.csv", join_path("dir//", "//a.csv"));
+        assert_eq!("/a.csv", join_path("/", "a.csv"));
+        assert_eq!("/a.csv", join_path("/", "/a.csv"));
+        assert_eq!("a.csv", join_path("", "/a.csv"));
+    }
 }
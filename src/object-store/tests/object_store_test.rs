@@ -13,15 +13,33 @@
 // limitations under the License.
 
 use std::env;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::Result;
+use async_trait::async_trait;
 use common_telemetry::logging;
 use common_test_util::temp_dir::create_temp_dir;
 use object_store::cache_policy::LruCacheLayer;
+use object_store::layers::ConcurrentLimitLayer;
+use object_store::metrics::{
+    ObjectStoreMetricsLayer, METRIC_OBJECT_STORE_BYTES_TOTAL, METRIC_OBJECT_STORE_ERRORS_TOTAL,
+    METRIC_OBJECT_STORE_REQUESTS_TOTAL,
+};
+use object_store::read_only::ReadOnlyLayer;
+use object_store::retry::{
+    scope_task, ObjectStoreRetryLayer, RetryOptions, METRIC_OBJECT_STORE_RETRIES_EXHAUSTED_TOTAL,
+    METRIC_OBJECT_STORE_RETRIES_TOTAL,
+};
 use object_store::services::{Fs, S3};
 use object_store::test_util::TempFolder;
-use object_store::{util, Object, ObjectLister, ObjectMode, ObjectStore, ObjectStoreBuilder};
+use object_store::{
+    util, ErrorKind, Object, ObjectLister, ObjectMode, ObjectStore, ObjectStoreBuilder,
+};
+use opendal::ops::*;
+use opendal::raw::*;
 use opendal::services::Oss;
 use opendal::Operator;
 
@@ -212,7 +230,7 @@ async fn test_object_store_cache_policy() -> Result<()> {
     let cache_store = ObjectStore::new(cache_acc.clone()).finish();
     // create operator for cache dir to verify cache file
     let store = store
-        .layer(LruCacheLayer::new(Arc::new(cache_acc), 3))
+        .layer(LruCacheLayer::new(Arc::new(cache_acc), 38).await?)
         .finish();
 
     // create several object handler.
@@ -268,3 +286,422 @@ async fn test_object_store_cache_policy() -> Result<()> {
 
     Ok(())
 }
+
+fn dir_size(dir: &Path) -> u64 {
+    std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| {
+            let entry = entry.unwrap();
+            let meta = entry.metadata().unwrap();
+            if meta.is_dir() {
+                dir_size(&entry.path())
+            } else {
+                meta.len()
+            }
+        })
+        .sum()
+}
+
+#[tokio::test]
+async fn test_object_store_cache_capacity() -> Result<()> {
+    let root_dir = create_temp_dir("test_cache_capacity");
+    let root_acc = Fs::default()
+        .root(&root_dir.path().to_string_lossy())
+        .atomic_write_dir(&root_dir.path().to_string_lossy())
+        .build()?;
+    let cache_dir = create_temp_dir("test_cache_capacity_cache");
+    let cache_acc = Fs::default()
+        .root(&cache_dir.path().to_string_lossy())
+        .atomic_write_dir(&cache_dir.path().to_string_lossy())
+        .build()?;
+    // Each object has 20 bytes, so at most 3 of them are cached.
+    let capacity = 64;
+    let cached_store = ObjectStore::new(root_acc.clone())
+        .layer(LruCacheLayer::new(Arc::new(cache_acc.clone()), capacity).await?)
+        .finish();
+
+    for i in 0..10 {
+        let o = cached_store.object(&format!("test_file{i}"));
+        o.write(format!("Hello, object{i:06}!")).await?;
+        assert_eq!(format!("Hello, object{i:06}!").as_bytes(), o.read().await?);
+        assert!(dir_size(cache_dir.path()) <= capacity);
+    }
+    assert_eq!(60, dir_size(cache_dir.path()));
+
+    // The file being read is not evicted, even if it's the least recently used one.
+    let cache_file = cache_dir.path().join("test_file9.cache-bytes=0-");
+    let reader = cached_store.object("test_file9").reader().await?;
+    for i in 0..3 {
+        cached_store.object(&format!("test_file{i}")).read().await?;
+        assert!(cache_file.exists());
+    }
+    drop(reader);
+    cached_store.object("test_file3").read().await?;
+    assert!(!cache_file.exists());
+    assert_eq!(60, dir_size(cache_dir.path()));
+
+    // The index is rebuilt from the cache directory after restart.
+    let cached_store = ObjectStore::new(root_acc)
+        .layer(LruCacheLayer::new(Arc::new(cache_acc), capacity).await?)
+        .finish();
+    for i in 4..7 {
+        cached_store.object(&format!("test_file{i}")).read().await?;
+        assert!(dir_size(cache_dir.path()) <= capacity);
+    }
+    assert_eq!(60, dir_size(cache_dir.path()));
+
+    Ok(())
+}
+
+/// Records the max number of in-flight `stat` requests to the inner accessor.
+#[derive(Debug, Default)]
+struct InflightCounter {
+    current: AtomicUsize,
+    max: AtomicUsize,
+}
+
+struct InflightCountLayer(Arc<InflightCounter>);
+
+impl<A: Accessor> Layer<A> for InflightCountLayer {
+    type LayeredAccessor = InflightCountAccessor<A>;
+
+    fn layer(&self, inner: A) -> Self::LayeredAccessor {
+        InflightCountAccessor {
+            inner,
+            counter: self.0.clone(),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct InflightCountAccessor<A> {
+    inner: A,
+    counter: Arc<InflightCounter>,
+}
+
+#[async_trait]
+impl<A: Accessor> LayeredAccessor for InflightCountAccessor<A> {
+    type Inner = A;
+    type Reader = A::Reader;
+    type BlockingReader = A::BlockingReader;
+    type Pager = A::Pager;
+    type BlockingPager = A::BlockingPager;
+
+    fn inner(&self) -> &Self::Inner {
+        &self.inner
+    }
+
+    async fn read(&self, path: &str, args: OpRead) -> opendal::Result<(RpRead, Self::Reader)> {
+        self.inner.read(path, args).await
+    }
+
+    fn blocking_read(
+        &self,
+        path: &str,
+        args: OpRead,
+    ) -> opendal::Result<(RpRead, Self::BlockingReader)> {
+        self.inner.blocking_read(path, args)
+    }
+
+    async fn stat(&self, path: &str, args: OpStat) -> opendal::Result<RpStat> {
+        let current = self.counter.current.fetch_add(1, Ordering::SeqCst) + 1;
+        self.counter.max.fetch_max(current, Ordering::SeqCst);
+        // Holds the request for a while so other requests pile up.
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        let result = self.inner.stat(path, args).await;
+        self.counter.current.fetch_sub(1, Ordering::SeqCst);
+        result
+    }
+
+    async fn list(&self, path: &str, args: OpList) -> opendal::Result<(RpList, Self::Pager)> {
+        self.inner.list(path, args).await
+    }
+
+    async fn scan(&self, path: &str, args: OpScan) -> opendal::Result<(RpScan, Self::Pager)> {
+        self.inner.scan(path, args).await
+    }
+
+    fn blocking_list(
+        &self,
+        path: &str,
+        args: OpList,
+    ) -> opendal::Result<(RpList, Self::BlockingPager)> {
+        self.inner.blocking_list(path, args)
+    }
+
+    fn blocking_scan(
+        &self,
+        path: &str,
+        args: OpScan,
+    ) -> opendal::Result<(RpScan, Self::BlockingPager)> {
+        self.inner.blocking_scan(path, args)
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_concurrent_limit() -> Result<()> {
+    let limit = 4;
+    let root_dir = create_temp_dir("test_concurrent_limit");
+    let counter = Arc::new(InflightCounter::default());
+    let store = ObjectStore::new(
+        Fs::default()
+            .root(&root_dir.path().to_string_lossy())
+            .atomic_write_dir(&root_dir.path().to_string_lossy())
+            .build()?,
+    )
+    .layer(InflightCountLayer(counter.clone()))
+    .layer(ConcurrentLimitLayer::new(limit))
+    .finish();
+
+    store.object("test_file").write("Hello, World!").await?;
+
+    let tasks = (0..limit * 8)
+        .map(|_| {
+            let store = store.clone();
+            tokio::spawn(async move { store.object("test_file").metadata().await })
+        })
+        .collect::<Vec<_>>();
+    for task in futures::future::join_all(tasks).await {
+        assert_eq!(13, task??.content_length());
+    }
+
+    let max = counter.max.load(Ordering::SeqCst);
+    assert!(max > 0);
+    assert!(max <= limit, "max in-flight requests {max} exceeds {limit}");
+
+    Ok(())
+}
+
+/// Returns the value of the counter `name` with label `op` in the rendered metrics.
+fn op_counter(name: &str, op: &str) -> u64 {
+    let prefix = format!("{}{{op=\"{op}\"}} ", name.replace('.', "_"));
+    common_telemetry::metric::try_handle()
+        .unwrap()
+        .render()
+        .lines()
+        .find_map(|line| line.strip_prefix(&prefix))
+        .map(|value| value.parse().unwrap())
+        .unwrap_or(0)
+}
+
+#[tokio::test]
+async fn test_metrics_layer() -> Result<()> {
+    common_telemetry::metric::init_default_metrics_recorder();
+    let root_dir = create_temp_dir("test_metrics_layer");
+    let store = ObjectStore::new(
+        Fs::default()
+            .root(&root_dir.path().to_string_lossy())
+            .atomic_write_dir(&root_dir.path().to_string_lossy())
+            .build()?,
+    )
+    .layer(ObjectStoreMetricsLayer)
+    .finish();
+
+    let requests = |op| op_counter(METRIC_OBJECT_STORE_REQUESTS_TOTAL, op);
+    let bytes = |op| op_counter(METRIC_OBJECT_STORE_BYTES_TOTAL, op);
+    let errors = |op| op_counter(METRIC_OBJECT_STORE_ERRORS_TOTAL, op);
+    let (read_requests, read_bytes) = (requests("read"), bytes("read"));
+    let (write_requests, write_bytes) = (requests("write"), bytes("write"));
+    let read_errors = errors("read");
+
+    let object = store.object("test_file");
+    object.write("Hello, World!").await?;
+    assert_eq!(write_requests + 1, requests("write"));
+    assert_eq!(write_bytes + 13, bytes("write"));
+
+    assert_eq!("Hello, World!".as_bytes(), object.read().await?);
+    assert_eq!(read_requests + 1, requests("read"));
+    assert_eq!(read_bytes + 13, bytes("read"));
+    assert_eq!(read_errors, errors("read"));
+
+    assert!(store.object("not_exist").read().await.is_err());
+    assert_eq!(read_requests + 2, requests("read"));
+    assert_eq!(read_errors + 1, errors("read"));
+
+    Ok(())
+}
+
+/// Fails `stat` and `write` requests with temporary errors until `failures` runs out.
+struct FlakyLayer(Arc<AtomicUsize>);
+
+impl<A: Accessor> Layer<A> for FlakyLayer {
+    type LayeredAccessor = FlakyAccessor<A>;
+
+    fn layer(&self, inner: A) -> Self::LayeredAccessor {
+        FlakyAccessor {
+            inner,
+            failures: self.0.clone(),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct FlakyAccessor<A> {
+    inner: A,
+    failures: Arc<AtomicUsize>,
+}
+
+impl<A> FlakyAccessor<A> {
+    fn maybe_fail(&self) -> opendal::Result<()> {
+        let failed = self
+            .failures
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+            .is_ok();
+        if failed {
+            return Err(opendal::Error::new(ErrorKind::Unexpected, "slow down").set_temporary());
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<A: Accessor> LayeredAccessor for FlakyAccessor<A> {
+    type Inner = A;
+    type Reader = A::Reader;
+    type BlockingReader = A::BlockingReader;
+    type Pager = A::Pager;
+    type BlockingPager = A::BlockingPager;
+
+    fn inner(&self) -> &Self::Inner {
+        &self.inner
+    }
+
+    async fn read(&self, path: &str, args: OpRead) -> opendal::Result<(RpRead, Self::Reader)> {
+        self.inner.read(path, args).await
+    }
+
+    fn blocking_read(
+        &self,
+        path: &str,
+        args: OpRead,
+    ) -> opendal::Result<(RpRead, Self::BlockingReader)> {
+        self.inner.blocking_read(path, args)
+    }
+
+    async fn write(&self, path: &str, args: OpWrite, r: input::Reader) -> opendal::Result<RpWrite> {
+        self.maybe_fail()?;
+        self.inner.write(path, args, r).await
+    }
+
+    async fn stat(&self, path: &str, args: OpStat) -> opendal::Result<RpStat> {
+        self.maybe_fail()?;
+        self.inner.stat(path, args).await
+    }
+
+    async fn list(&self, path: &str, args: OpList) -> opendal::Result<(RpList, Self::Pager)> {
+        self.inner.list(path, args).await
+    }
+
+    async fn scan(&self, path: &str, args: OpScan) -> opendal::Result<(RpScan, Self::Pager)> {
+        self.inner.scan(path, args).await
+    }
+
+    fn blocking_list(
+        &self,
+        path: &str,
+        args: OpList,
+    ) -> opendal::Result<(RpList, Self::BlockingPager)> {
+        self.inner.blocking_list(path, args)
+    }
+
+    fn blocking_scan(
+        &self,
+        path: &str,
+        args: OpScan,
+    ) -> opendal::Result<(RpScan, Self::BlockingPager)> {
+        self.inner.blocking_scan(path, args)
+    }
+}
+
+#[tokio::test]
+async fn test_retry_layer() -> Result<()> {
+    common_telemetry::metric::init_default_metrics_recorder();
+    let root_dir = create_temp_dir("test_retry_layer");
+    let failures = Arc::new(AtomicUsize::new(0));
+    let store = ObjectStore::new(
+        Fs::default()
+            .root(&root_dir.path().to_string_lossy())
+            .atomic_write_dir(&root_dir.path().to_string_lossy())
+            .build()?,
+    )
+    .layer(FlakyLayer(failures.clone()))
+    .layer(ObjectStoreRetryLayer::new(RetryOptions {
+        max_attempts: 3,
+        retry_delay: Duration::from_millis(1),
+        max_retry_delay: Duration::from_millis(10),
+        task_budget: Some(Duration::ZERO),
+    }))
+    .finish();
+    let retries = |op| op_counter(METRIC_OBJECT_STORE_RETRIES_TOTAL, op);
+    let exhausted = |op| op_counter(METRIC_OBJECT_STORE_RETRIES_EXHAUSTED_TOTAL, op);
+    let (write_retries, stat_retries, stat_exhausted) =
+        (retries("write"), retries("stat"), exhausted("stat"));
+
+    // The body of the write is sent again.
+    let object = store.object("test_file");
+    failures.store(2, Ordering::SeqCst);
+    object.write("Hello, World!").await?;
+    assert_eq!(write_retries + 2, retries("write"));
+    assert_eq!("Hello, World!".as_bytes(), object.read().await?);
+
+    failures.store(2, Ordering::SeqCst);
+    assert_eq!(13, object.metadata().await?.content_length());
+    assert_eq!(stat_retries + 2, retries("stat"));
+
+    // Out of attempts.
+    failures.store(3, Ordering::SeqCst);
+    let err = object.metadata().await.unwrap_err();
+    assert!(err.to_string().contains("attempts: 3"), "{err}");
+    assert_eq!(stat_exhausted + 1, exhausted("stat"));
+
+    // Out of the time budget of the task.
+    failures.store(1, Ordering::SeqCst);
+    let err = scope_task(object.metadata()).await.unwrap_err();
+    assert!(err.to_string().contains("task time"), "{err}");
+    assert_eq!(stat_exhausted + 2, exhausted("stat"));
+
+    // Missing objects are not retried.
+    let stat_retries = retries("stat");
+    let err = store.object("not_exist").metadata().await.unwrap_err();
+    assert_eq!(ErrorKind::ObjectNotFound, err.kind());
+    assert_eq!(stat_retries, retries("stat"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_read_only_layer() -> Result<()> {
+    let root_dir = create_temp_dir("test_read_only_layer");
+    let store = ObjectStore::new(
+        Fs::default()
+            .root(&root_dir.path().to_string_lossy())
+            .atomic_write_dir(&root_dir.path().to_string_lossy())
+            .build()?,
+    )
+    .finish();
+    store.object("test_file").write("Hello, World!").await?;
+
+    let read_only = store.clone().layer(ReadOnlyLayer);
+    let object = read_only.object("test_file");
+    assert_eq!("Hello, World!".as_bytes(), object.read().await?);
+    assert_eq!(13, object.metadata().await?.content_length());
+
+    let err = object.write("Bye").await.unwrap_err();
+    assert_eq!(ErrorKind::ObjectPermissionDenied, err.kind());
+    assert!(err.to_string().contains("anonymous"), "{err}");
+    let err = read_only.object("new_file").write("Bye").await.unwrap_err();
+    assert_eq!(ErrorKind::ObjectPermissionDenied, err.kind());
+    let err = object.delete().await.unwrap_err();
+    assert_eq!(ErrorKind::ObjectPermissionDenied, err.kind());
+    let err = read_only.object("dir/").create().await.unwrap_err();
+    assert_eq!(ErrorKind::ObjectPermissionDenied, err.kind());
+
+    // Nothing is modified.
+    assert_eq!(
+        "Hello, World!".as_bytes(),
+        store.object("test_file").read().await?
+    );
+    assert!(!store.object("new_file").is_exist().await?);
+
+    Ok(())
+}
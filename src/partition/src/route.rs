@@ -16,7 +16,7 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use meta_client::client::MetaClient;
-use meta_client::rpc::{RouteRequest, TableName, TableRoute};
+use meta_client::rpc::{RenameRouteRequest, RouteRequest, TableName, TableRoute};
 use moka::future::{Cache, CacheBuilder};
 use snafu::{ensure, ResultExt};
 
@@ -77,4 +77,30 @@ impl TableRoutes {
     pub async fn invalidate_table_route(&self, table_name: &TableName) {
         self.cache.invalidate(table_name).await
     }
+
+    /// Drops the cached route of `table_name` if it's a route of another table than
+    /// `table_id`, e.g. the table is renamed, or dropped and created again, by another
+    /// frontend.
+    pub async fn invalidate_stale_route(&self, table_name: &TableName, table_id: u64) {
+        let stale = self
+            .cache
+            .get(table_name)
+            .map(|route| route.table.id != table_id)
+            .unwrap_or(false);
+        if stale {
+            self.invalidate_table_route(table_name).await
+        }
+    }
+
+    /// Moves the route of a table to its new name in metasrv, and drops the cached route of
+    /// the old name.
+    pub async fn rename_table_route(&self, req: RenameRouteRequest) -> Result<()> {
+        let table_name = req.table_name.clone();
+        self.meta_client
+            .rename_route(req)
+            .await
+            .context(error::RequestMetaSnafu)?;
+        self.invalidate_table_route(&table_name).await;
+        Ok(())
+    }
 }
@@ -94,6 +94,12 @@ pub enum Error {
         #[snafu(backtrace)]
         source: catalog::error::Error,
     },
+
+    #[snafu(display("Failed to collect range functions, source: {}", source))]
+    RangeFnRegistry {
+        #[snafu(backtrace)]
+        source: common_query::error::Error,
+    },
 }
 
 impl ErrorExt for Error {
@@ -116,6 +122,7 @@ impl ErrorExt for Error {
             TableNotFound { .. } | TableNameNotFound { .. } => StatusCode::TableNotFound,
 
             Catalog { source } => source.status_code(),
+            RangeFnRegistry { source } => source.status_code(),
         }
     }
     fn backtrace_opt(&self) -> Option<&Backtrace> {
@@ -398,21 +398,22 @@ impl RangeManipulateStream {
         for curr_ts in (self.start..=self.end).step_by(self.interval as _) {
             aligned_ts.push(curr_ts);
             let mut range_start = ts_column.len();
+            // exclusive end of the range, stays 0 if all the samples are after `curr_ts`
             let mut range_end = 0;
             for (index, ts) in ts_column.values().iter().enumerate() {
                 if ts + self.range >= curr_ts {
                     range_start = range_start.min(index);
                 }
                 if *ts <= curr_ts {
-                    range_end = range_end.max(index);
+                    range_end = range_end.max(index + 1);
                 } else {
                     break;
                 }
             }
-            if range_start > range_end {
+            if range_start >= range_end {
                 ranges.push((0, 0));
             } else {
-                ranges.push((range_start as _, (range_end + 1 - range_start) as _));
+                ranges.push((range_start as _, (range_end - range_start) as _));
             }
         }
 
@@ -580,4 +581,25 @@ mod test {
         }");
         do_normalize_test(1, 10_001, 3_000, 1_000, expected).await;
     }
+
+    #[tokio::test]
+    async fn range_before_first_sample() {
+        let expected = String::from(
+        "PrimitiveArray<Timestamp(Millisecond, None)>\n[\n  \
+            1969-12-31T23:59:30,\n  \
+            1970-01-01T00:00:00,\n\
+        ]\nRangeArray { \
+            base array: PrimitiveArray<Float64>\n[\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n], \
+            ranges: [Some(0..0), Some(0..1)] \
+        }\nRangeArray { \
+            base array: PrimitiveArray<Float64>\n[\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n], \
+            ranges: [Some(0..0), Some(0..1)] \
+        }\nStringArray\n[\n  \"foo\",\n  \"foo\",\n]\n\
+        RangeArray { \
+            base array: PrimitiveArray<Timestamp(Millisecond, None)>\n[\n  1970-01-01T00:00:00,\n  1970-01-01T00:00:30,\n  1970-01-01T00:01:00,\n  1970-01-01T00:01:30,\n  1970-01-01T00:02:00,\n  1970-01-01T00:03:00,\n  1970-01-01T00:04:00,\n  1970-01-01T00:04:01,\n  1970-01-01T00:04:31,\n  1970-01-01T00:04:51,\n], \
+            ranges: [Some(0..0), Some(0..1)] \
+        }");
+        // No sample is at or before the first step.
+        do_normalize_test(-30_000, 0, 30_000, 10_000, expected).await;
+    }
 }
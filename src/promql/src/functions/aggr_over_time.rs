@@ -121,6 +121,8 @@ pub fn present_over_time(_: &TimestampMillisecondArray, values: &Float64Array) -
 
 #[cfg(test)]
 mod test {
+    use datafusion::arrow::array::{BooleanArray, Int64Array, StringArray, UInt64Array};
+
     use super::*;
     use crate::functions::test_util::simple_range_udf_runner;
 
@@ -332,4 +334,314 @@ mod test {
             ],
         );
     }
+
+    #[range_fn(
+        name = "ImmutableOverTime",
+        ret = "Float64Array",
+        display_name = "immutable_over_time",
+        volatility = "immutable"
+    )]
+    fn immutable_over_time(_: &TimestampMillisecondArray, values: &Float64Array) -> f64 {
+        values.len() as f64
+    }
+
+    #[range_fn(
+        name = "StableOverTime",
+        ret = "Float64Array",
+        display_name = "stable_over_time",
+        volatility = "stable"
+    )]
+    fn stable_over_time(_: &TimestampMillisecondArray, values: &Float64Array) -> f64 {
+        values.len() as f64
+    }
+
+    #[range_fn(
+        name = "VolatileOverTime",
+        ret = "Float64Array",
+        display_name = "volatile_over_time",
+        volatility = "volatile"
+    )]
+    fn volatile_over_time(_: &TimestampMillisecondArray, values: &Float64Array) -> f64 {
+        values.len() as f64
+    }
+
+    #[test]
+    fn test_range_fn_volatility() {
+        // default to immutable
+        assert_eq!(
+            Volatility::Immutable,
+            AvgOverTime::scalar_udf().signature.volatility
+        );
+        assert_eq!(
+            Volatility::Immutable,
+            ImmutableOverTime::scalar_udf().signature.volatility
+        );
+        assert_eq!(
+            Volatility::Stable,
+            StableOverTime::scalar_udf().signature.volatility
+        );
+        assert_eq!(
+            Volatility::Volatile,
+            VolatileOverTime::scalar_udf().signature.volatility
+        );
+    }
+
+    #[range_fn(
+        name = "IntSumOverTime",
+        ret = "Int64Array",
+        display_name = "int_sum_over_time"
+    )]
+    fn int_sum_over_time(_: &TimestampMillisecondArray, values: &Int64Array) -> Option<i64> {
+        compute::sum(values)
+    }
+
+    #[range_fn(
+        name = "UIntCountOverTime",
+        ret = "UInt64Array",
+        display_name = "uint_count_over_time"
+    )]
+    fn uint_count_over_time(_: &TimestampMillisecondArray, values: &UInt64Array) -> u64 {
+        values.len() as u64
+    }
+
+    #[test]
+    fn test_range_fn_integer_array() {
+        let ts_array = Arc::new(TimestampMillisecondArray::from_iter(
+            [1000i64, 2000, 3000, 4000].into_iter().map(Some),
+        ));
+        let ranges = [(0, 2), (1, 3), (3, 0)];
+        let ts_range_array = RangeArray::from_ranges(ts_array, ranges).unwrap();
+
+        let values = Arc::new(Int64Array::from_iter_values([1, 2, 3, 4]));
+        let value_range_array = RangeArray::from_ranges(values, ranges).unwrap();
+        assert_eq!(
+            vec![
+                RangeArray::convert_data_type(DataType::Timestamp(TimeUnit::Millisecond, None)),
+                RangeArray::convert_data_type(DataType::Int64),
+            ],
+            IntSumOverTime::input_type()
+        );
+        assert_eq!(DataType::Int64, IntSumOverTime::return_type());
+        let input = vec![
+            ColumnarValue::Array(Arc::new(ts_range_array.into_dict())),
+            ColumnarValue::Array(Arc::new(value_range_array.into_dict())),
+        ];
+        let result = extract_array(&(IntSumOverTime::scalar_udf().fun)(&input).unwrap()).unwrap();
+        let result = result.as_any().downcast_ref::<Int64Array>().unwrap();
+        assert_eq!(
+            vec![Some(3), Some(9), None],
+            result.iter().collect::<Vec<_>>()
+        );
+
+        let values = Arc::new(UInt64Array::from_iter_values([1, 2, 3, 4]));
+        let value_range_array = RangeArray::from_ranges(values, ranges).unwrap();
+        assert_eq!(
+            vec![
+                RangeArray::convert_data_type(DataType::Timestamp(TimeUnit::Millisecond, None)),
+                RangeArray::convert_data_type(DataType::UInt64),
+            ],
+            UIntCountOverTime::input_type()
+        );
+        assert_eq!(DataType::UInt64, UIntCountOverTime::return_type());
+        let input = vec![
+            input[0].clone(),
+            ColumnarValue::Array(Arc::new(value_range_array.into_dict())),
+        ];
+        let result =
+            extract_array(&(UIntCountOverTime::scalar_udf().fun)(&input).unwrap()).unwrap();
+        let result = result.as_any().downcast_ref::<UInt64Array>().unwrap();
+        assert_eq!(
+            vec![Some(2), Some(3), Some(0)],
+            result.iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[range_fn(
+        name = "AnyNanOverTime",
+        ret = "BooleanArray",
+        display_name = "any_nan_over_time"
+    )]
+    fn any_nan_over_time(_: &TimestampMillisecondArray, values: &Float64Array) -> bool {
+        values.iter().any(|v| v.map(f64::is_nan).unwrap_or(false))
+    }
+
+    #[range_fn(
+        name = "LastIsNanOverTime",
+        ret = "BooleanArray",
+        display_name = "last_is_nan_over_time"
+    )]
+    fn last_is_nan_over_time(_: &TimestampMillisecondArray, values: &Float64Array) -> Option<bool> {
+        if values.is_empty() {
+            None
+        } else {
+            Some(values.value(values.len() - 1).is_nan())
+        }
+    }
+
+    #[test]
+    fn test_range_fn_boolean_array() {
+        let ts_array = Arc::new(TimestampMillisecondArray::from_iter(
+            [1000i64, 2000, 3000, 4000].into_iter().map(Some),
+        ));
+        let ranges = [(0, 2), (1, 3), (3, 0)];
+        let ts_range_array = RangeArray::from_ranges(ts_array, ranges).unwrap();
+        let values = Arc::new(Float64Array::from_iter_values([1.0, f64::NAN, 3.0, 4.0]));
+        let value_range_array = RangeArray::from_ranges(values, ranges).unwrap();
+        let input = vec![
+            ColumnarValue::Array(Arc::new(ts_range_array.into_dict())),
+            ColumnarValue::Array(Arc::new(value_range_array.into_dict())),
+        ];
+
+        assert_eq!(DataType::Boolean, AnyNanOverTime::return_type());
+        let result = extract_array(&(AnyNanOverTime::scalar_udf().fun)(&input).unwrap()).unwrap();
+        let result = result.as_any().downcast_ref::<BooleanArray>().unwrap();
+        assert_eq!(
+            vec![Some(true), Some(true), Some(false)],
+            result.iter().collect::<Vec<_>>()
+        );
+
+        // `None` is mapped to null
+        assert_eq!(DataType::Boolean, LastIsNanOverTime::return_type());
+        let result =
+            extract_array(&(LastIsNanOverTime::scalar_udf().fun)(&input).unwrap()).unwrap();
+        let result = result.as_any().downcast_ref::<BooleanArray>().unwrap();
+        assert_eq!(
+            vec![Some(true), Some(false), None],
+            result.iter().collect::<Vec<_>>()
+        );
+        assert_eq!(1, result.null_count());
+    }
+
+    #[range_fn(
+        name = "NonEmptyLabelsOverTime",
+        ret = "Float64Array",
+        display_name = "non_empty_labels_over_time"
+    )]
+    fn non_empty_labels_over_time(_: &TimestampMillisecondArray, labels: &StringArray) -> f64 {
+        labels
+            .iter()
+            .filter(|label| label.map(|l| !l.is_empty()).unwrap_or(false))
+            .count() as f64
+    }
+
+    #[test]
+    fn test_range_fn_string_array() {
+        let ts_array = Arc::new(TimestampMillisecondArray::from_iter(
+            [1000i64, 2000, 3000, 4000].into_iter().map(Some),
+        ));
+        let ranges = [(0, 2), (1, 3), (3, 0)];
+        let ts_range_array = RangeArray::from_ranges(ts_array, ranges).unwrap();
+        let labels = Arc::new(StringArray::from(vec![
+            Some("a"),
+            Some(""),
+            None,
+            Some("b"),
+        ]));
+        let label_range_array = RangeArray::from_ranges(labels, ranges).unwrap();
+        assert_eq!(
+            vec![
+                RangeArray::convert_data_type(DataType::Timestamp(TimeUnit::Millisecond, None)),
+                RangeArray::convert_data_type(DataType::Utf8),
+            ],
+            NonEmptyLabelsOverTime::input_type()
+        );
+
+        let input = vec![
+            ColumnarValue::Array(Arc::new(ts_range_array.into_dict())),
+            ColumnarValue::Array(Arc::new(label_range_array.into_dict())),
+        ];
+        let result =
+            extract_array(&(NonEmptyLabelsOverTime::scalar_udf().fun)(&input).unwrap()).unwrap();
+        let result = result.as_any().downcast_ref::<Float64Array>().unwrap();
+        assert_eq!(
+            vec![Some(1.0), Some(1.0), Some(0.0)],
+            result.iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[range_fn(
+        name = "WrappingSumOverTime",
+        ret = "Int64Array",
+        display_name = "wrapping_sum_over_time"
+    )]
+    fn wrapping_sum_over_time(_: &TimestampMillisecondArray, values: &Int64Array) -> i64 {
+        values.iter().flatten().fold(0, |sum, value| sum + value)
+    }
+
+    #[range_fn(
+        name = "CheckedSumOverTime",
+        ret = "Int64Array",
+        display_name = "checked_sum_over_time",
+        checked = true
+    )]
+    fn checked_sum_over_time(_: &TimestampMillisecondArray, values: &Int64Array) -> i64 {
+        let mut sum = 0;
+        for value in values.iter().flatten() {
+            sum += value;
+        }
+        sum
+    }
+
+    #[test]
+    fn test_range_fn_checked() {
+        let ts_array = Arc::new(TimestampMillisecondArray::from_iter(
+            [1000i64, 2000, 3000].into_iter().map(Some),
+        ));
+        let values = Arc::new(Int64Array::from_iter_values([i64::MAX, 1, 2]));
+        let build_input = |ranges: &[(u32, u32)]| {
+            let ts_range_array =
+                RangeArray::from_ranges(ts_array.clone(), ranges.iter().copied()).unwrap();
+            let value_range_array =
+                RangeArray::from_ranges(values.clone(), ranges.iter().copied()).unwrap();
+            vec![
+                ColumnarValue::Array(Arc::new(ts_range_array.into_dict())),
+                ColumnarValue::Array(Arc::new(value_range_array.into_dict())),
+            ]
+        };
+
+        // no overflow
+        let input = build_input(&[(1, 2)]);
+        for fun in [
+            WrappingSumOverTime::scalar_udf().fun,
+            CheckedSumOverTime::scalar_udf().fun,
+        ] {
+            let result = extract_array(&fun(&input).unwrap()).unwrap();
+            let result = result.as_any().downcast_ref::<Int64Array>().unwrap();
+            assert_eq!(vec![Some(3)], result.iter().collect::<Vec<_>>());
+        }
+
+        // overflow wraps without `checked`
+        let input = build_input(&[(0, 2), (1, 2)]);
+        let result =
+            extract_array(&(WrappingSumOverTime::scalar_udf().fun)(&input).unwrap()).unwrap();
+        let result = result.as_any().downcast_ref::<Int64Array>().unwrap();
+        assert_eq!(
+            vec![Some(i64::MIN), Some(3)],
+            result.iter().collect::<Vec<_>>()
+        );
+
+        // and is an error with `checked`
+        let err = (CheckedSumOverTime::scalar_udf().fun)(&input).unwrap_err();
+        let DataFusionError::External(err) = err else {
+            panic!("unexpected error {err}");
+        };
+        let err = err.downcast_ref::<common_query::error::Error>().unwrap();
+        assert!(
+            matches!(err, common_query::error::Error::ArithmeticOverflow { function, .. } if function == "checked_sum_over_time"),
+            "unexpected error {err}"
+        );
+    }
+
+    #[test]
+    fn test_range_fn_registry() {
+        let functions = common_query::range_fn_registry::collect_range_fns().unwrap();
+        for name in [
+            AvgOverTime::name(),
+            SumOverTime::name(),
+            PresentOverTime::name(),
+            IntSumOverTime::name(),
+        ] {
+            assert_eq!(name, functions[name].name);
+        }
+    }
 }
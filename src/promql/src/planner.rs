@@ -19,6 +19,7 @@ use std::time::UNIX_EPOCH;
 
 use async_recursion::async_recursion;
 use catalog::table_source::DfTableSourceProvider;
+use common_query::range_fn_registry::collect_range_fns;
 use datafusion::common::{DFSchemaRef, OwnedTableReference, Result as DfResult};
 use datafusion::datasource::DefaultTableSource;
 use datafusion::logical_expr::expr::AggregateFunction;
@@ -41,23 +42,24 @@ use snafu::{ensure, OptionExt, ResultExt};
 use table::table::adapter::DfTableProviderAdapter;
 
 use crate::error::{
-    CatalogSnafu, DataFusionPlanningSnafu, ExpectExprSnafu, MultipleVectorSnafu, Result,
-    TableNameNotFoundSnafu, TimeIndexNotFoundSnafu, UnexpectedTokenSnafu, UnknownTableSnafu,
-    UnsupportedExprSnafu, ValueNotFoundSnafu,
+    CatalogSnafu, DataFusionPlanningSnafu, ExpectExprSnafu, MultipleVectorSnafu,
+    RangeFnRegistrySnafu, Result, TableNameNotFoundSnafu, TimeIndexNotFoundSnafu,
+    UnexpectedTokenSnafu, UnknownTableSnafu, UnsupportedExprSnafu, ValueNotFoundSnafu,
 };
 use crate::extension_plan::{
     EmptyMetric, InstantManipulate, Millisecond, RangeManipulate, SeriesDivide, SeriesNormalize,
 };
-use crate::functions::{
-    AbsentOverTime, AvgOverTime, CountOverTime, IDelta, Increase, LastOverTime, MaxOverTime,
-    MinOverTime, PresentOverTime, SumOverTime,
-};
+use crate::functions::{IDelta, Increase};
 
 const LEFT_PLAN_JOIN_ALIAS: &str = "lhs";
 
 /// `time()` function in PromQL.
 const SPECIAL_TIME_FUNCTION: &str = "time";
 
+/// Prefix of the display names of the range functions generated by `range_fn`, e.g.
+/// `avg_over_time` is registered as `prom_avg_over_time`.
+const RANGE_FN_PREFIX: &str = "prom_";
+
 /// default value column name for empty metric
 const DEFAULT_VALUE_COLUMN: &str = "value";
 
@@ -291,7 +293,7 @@ impl PromPlanner {
                 let matchers = self.preprocess_label_matchers(matchers)?;
                 self.setup_context().await?;
                 let normalize = self
-                    .selector_to_series_normalize_plan(offset, matchers)
+                    .selector_to_series_normalize_plan(offset, matchers, self.ctx.lookback_delta)
                     .await?;
                 let manipulate = InstantManipulate::new(
                     self.ctx.start,
@@ -317,8 +319,10 @@ impl PromPlanner {
                 } = vector_selector;
                 let matchers = self.preprocess_label_matchers(matchers)?;
                 self.setup_context().await?;
+                // Range functions evaluate the samples in `range` before each step, regardless
+                // of the lookback delta.
                 let normalize = self
-                    .selector_to_series_normalize_plan(offset, matchers)
+                    .selector_to_series_normalize_plan(offset, matchers, range.as_millis() as _)
                     .await?;
                 let manipulate = RangeManipulate::new(
                     self.ctx.start,
@@ -397,10 +401,13 @@ impl PromPlanner {
         Ok(Matchers { matchers })
     }
 
+    /// Scans the series selected by `label_matchers` from `window` before the start of the
+    /// query to its end.
     async fn selector_to_series_normalize_plan(
         &mut self,
         offset: &Option<Offset>,
         label_matchers: Matchers,
+        window: Millisecond,
     ) -> Result<LogicalPlan> {
         let table_name = self.ctx.table_name.clone().unwrap();
 
@@ -413,7 +420,7 @@ impl PromPlanner {
         let mut filters = self.matchers_to_expr(label_matchers)?;
         filters.push(self.create_time_index_column_expr()?.gt_eq(DfExpr::Literal(
             ScalarValue::TimestampMillisecond(
-                Some(self.ctx.start - offset_duration - self.ctx.lookback_delta),
+                Some(self.ctx.start - offset_duration - window),
                 None,
             ),
         )));
@@ -671,22 +678,20 @@ impl PromPlanner {
             "increase" => ScalarFunc::Udf(Increase::scalar_udf()),
             "idelta" => ScalarFunc::Udf(IDelta::<false>::scalar_udf()),
             "irate" => ScalarFunc::Udf(IDelta::<true>::scalar_udf()),
-            "avg_over_time" => ScalarFunc::Udf(AvgOverTime::scalar_udf()),
-            "min_over_time" => ScalarFunc::Udf(MinOverTime::scalar_udf()),
-            "max_over_time" => ScalarFunc::Udf(MaxOverTime::scalar_udf()),
-            "sum_over_time" => ScalarFunc::Udf(SumOverTime::scalar_udf()),
-            "count_over_time" => ScalarFunc::Udf(CountOverTime::scalar_udf()),
-            "last_over_time" => ScalarFunc::Udf(LastOverTime::scalar_udf()),
-            "absent_over_time" => ScalarFunc::Udf(AbsentOverTime::scalar_udf()),
-            "present_over_time" => ScalarFunc::Udf(PresentOverTime::scalar_udf()),
-            _ => ScalarFunc::DataFusionBuiltin(
-                BuiltinScalarFunction::from_str(func.name).map_err(|_| {
-                    UnsupportedExprSnafu {
-                        name: func.name.to_string(),
-                    }
-                    .build()
-                })?,
-            ),
+            name => match collect_range_fns()
+                .context(RangeFnRegistrySnafu)?
+                .remove(format!("{RANGE_FN_PREFIX}{name}").as_str())
+            {
+                Some(udf) => ScalarFunc::Udf(udf),
+                None => ScalarFunc::DataFusionBuiltin(
+                    BuiltinScalarFunction::from_str(name).map_err(|_| {
+                        UnsupportedExprSnafu {
+                            name: name.to_string(),
+                        }
+                        .build()
+                    })?,
+                ),
+            },
         };
 
         // TODO(ruihang): handle those functions doesn't require input
@@ -1607,13 +1612,38 @@ mod test {
             \n      PromSeriesNormalize: offset=[0], time index=[timestamp] [tag_0:Utf8, timestamp:Timestamp(Millisecond, None), field_0:Float64;N]\
             \n        PromSeriesDivide: tags=[\"tag_0\"] [tag_0:Utf8, timestamp:Timestamp(Millisecond, None), field_0:Float64;N]\
             \n          Sort: some_metric.tag_0 DESC NULLS LAST, some_metric.timestamp DESC NULLS LAST [tag_0:Utf8, timestamp:Timestamp(Millisecond, None), field_0:Float64;N]\
-            \n            Filter: some_metric.timestamp >= TimestampMillisecond(-1000, None) AND some_metric.timestamp <= TimestampMillisecond(100000000, None) [tag_0:Utf8, timestamp:Timestamp(Millisecond, None), field_0:Float64;N]\
-            \n              TableScan: some_metric, unsupported_filters=[timestamp >= TimestampMillisecond(-1000, None), timestamp <= TimestampMillisecond(100000000, None)] [tag_0:Utf8, timestamp:Timestamp(Millisecond, None), field_0:Float64;N]"
+            \n            Filter: some_metric.timestamp >= TimestampMillisecond(-300000, None) AND some_metric.timestamp <= TimestampMillisecond(100000000, None) [tag_0:Utf8, timestamp:Timestamp(Millisecond, None), field_0:Float64;N]\
+            \n              TableScan: some_metric, unsupported_filters=[timestamp >= TimestampMillisecond(-300000, None), timestamp <= TimestampMillisecond(100000000, None)] [tag_0:Utf8, timestamp:Timestamp(Millisecond, None), field_0:Float64;N]"
         );
 
         indie_query_plan_compare(query, expected).await;
     }
 
+    #[tokio::test]
+    async fn matrix_selector_scan_range() {
+        let eval_stmt = EvalStmt {
+            expr: parser::parse("some_metric[5m]").unwrap(),
+            start: UNIX_EPOCH,
+            end: UNIX_EPOCH
+                .checked_add(Duration::from_secs(100_000))
+                .unwrap(),
+            interval: Duration::from_secs(5),
+            lookback_delta: Duration::from_secs(1),
+        };
+
+        let table_provider = build_test_table_provider("some_metric".to_string(), 1, 1).await;
+        let plan = PromPlanner::stmt_to_plan(table_provider, eval_stmt)
+            .await
+            .unwrap()
+            .display_indent_schema()
+            .to_string();
+        // The whole range before the start is scanned, though the lookback delta is shorter.
+        assert!(
+            plan.contains("timestamp >= TimestampMillisecond(-300000, None)"),
+            "{plan}"
+        );
+    }
+
     #[tokio::test]
     async fn less_filter_on_value() {
         let query = "some_metric < 1.2345";
@@ -1641,8 +1671,8 @@ mod test {
             \n      PromSeriesNormalize: offset=[0], time index=[timestamp] [tag_0:Utf8, timestamp:Timestamp(Millisecond, None), field_0:Float64;N]\
             \n        PromSeriesDivide: tags=[\"tag_0\"] [tag_0:Utf8, timestamp:Timestamp(Millisecond, None), field_0:Float64;N]\
             \n          Sort: some_metric.tag_0 DESC NULLS LAST, some_metric.timestamp DESC NULLS LAST [tag_0:Utf8, timestamp:Timestamp(Millisecond, None), field_0:Float64;N]\
-            \n            Filter: some_metric.timestamp >= TimestampMillisecond(-1000, None) AND some_metric.timestamp <= TimestampMillisecond(100000000, None) [tag_0:Utf8, timestamp:Timestamp(Millisecond, None), field_0:Float64;N]\
-            \n              TableScan: some_metric, unsupported_filters=[timestamp >= TimestampMillisecond(-1000, None), timestamp <= TimestampMillisecond(100000000, None)] [tag_0:Utf8, timestamp:Timestamp(Millisecond, None), field_0:Float64;N]"
+            \n            Filter: some_metric.timestamp >= TimestampMillisecond(-300000, None) AND some_metric.timestamp <= TimestampMillisecond(100000000, None) [tag_0:Utf8, timestamp:Timestamp(Millisecond, None), field_0:Float64;N]\
+            \n              TableScan: some_metric, unsupported_filters=[timestamp >= TimestampMillisecond(-300000, None), timestamp <= TimestampMillisecond(100000000, None)] [tag_0:Utf8, timestamp:Timestamp(Millisecond, None), field_0:Float64;N]"
         );
 
         indie_query_plan_compare(query, expected).await;
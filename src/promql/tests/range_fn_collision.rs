@@ -0,0 +1,68 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Range functions sharing a display name. They live in their own test binary as the
+//! registry is global.
+
+use std::sync::Arc;
+
+use common_function_macro::range_fn;
+use common_query::error::Error;
+use common_query::range_fn_registry::collect_range_fns;
+use datafusion::arrow::array::{Array, ArrayRef, Float64Array, TimestampMillisecondArray};
+use datafusion::arrow::datatypes::{DataType, TimeUnit};
+use datafusion::common::DataFusionError;
+use datafusion::logical_expr::{ScalarUDF, Signature, TypeSignature, Volatility};
+use datafusion::physical_plan::ColumnarValue;
+use promql::range_array::RangeArray;
+
+fn extract_array(columnar_value: &ColumnarValue) -> Result<ArrayRef, DataFusionError> {
+    if let ColumnarValue::Array(array) = columnar_value {
+        Ok(array.clone())
+    } else {
+        Err(DataFusionError::Execution(
+            "expect array as input, found scalar value".to_string(),
+        ))
+    }
+}
+
+#[range_fn(name = "FirstCount", ret = "Float64Array", display_name = "dup_count")]
+fn first_count(_: &TimestampMillisecondArray, values: &Float64Array) -> f64 {
+    values.len() as f64
+}
+
+#[range_fn(name = "SecondCount", ret = "Float64Array", display_name = "dup_count")]
+fn second_count(_: &TimestampMillisecondArray, values: &Float64Array) -> f64 {
+    values.len() as f64
+}
+
+#[range_fn(
+    name = "UniqueCount",
+    ret = "Float64Array",
+    display_name = "unique_count"
+)]
+fn unique_count(_: &TimestampMillisecondArray, values: &Float64Array) -> f64 {
+    values.len() as f64
+}
+
+#[test]
+fn test_range_fn_display_name_collision() {
+    let err = collect_range_fns().unwrap_err();
+    let Error::DuplicateRangeFn { names, .. } = &err else {
+        panic!("unexpected error {err}");
+    };
+    assert_eq!(&vec!["dup_count".to_string()], names);
+    assert!(err.to_string().contains("dup_count"), "{err}");
+    assert!(!err.to_string().contains("unique_count"), "{err}");
+}
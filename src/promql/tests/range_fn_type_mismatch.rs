@@ -0,0 +1,136 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Range functions fed with inputs of types other than the declared ones.
+
+use std::sync::Arc;
+
+use common_function_macro::range_fn;
+use datafusion::arrow::array::{
+    Array, ArrayRef, Float64Array, Int64Array, StringArray, TimestampMillisecondArray,
+};
+use datafusion::arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::{DataFusionError, ToDFSchema};
+use datafusion::logical_expr::{ScalarUDF, Signature, TypeSignature, Volatility};
+use datafusion::physical_expr::create_physical_expr;
+use datafusion::physical_expr::execution_props::ExecutionProps;
+use datafusion::physical_plan::ColumnarValue;
+use datafusion::prelude::{col, Expr};
+use promql::range_array::RangeArray;
+
+fn extract_array(columnar_value: &ColumnarValue) -> Result<ArrayRef, DataFusionError> {
+    if let ColumnarValue::Array(array) = columnar_value {
+        Ok(array.clone())
+    } else {
+        Err(DataFusionError::Execution(
+            "expect array as input, found scalar value".to_string(),
+        ))
+    }
+}
+
+#[range_fn(
+    name = "StrictSum",
+    ret = "Float64Array",
+    display_name = "test_strict_sum"
+)]
+fn strict_sum(_: &TimestampMillisecondArray, values: &Float64Array) -> f64 {
+    values.iter().flatten().sum()
+}
+
+#[range_fn(
+    name = "CoercedSum",
+    ret = "Float64Array",
+    display_name = "test_coerced_sum",
+    coerce = true
+)]
+fn coerced_sum(_: &TimestampMillisecondArray, values: &Float64Array) -> f64 {
+    values.iter().flatten().sum()
+}
+
+/// Evaluates `udf` on one range of `values` by the physical expression DataFusion builds
+/// for it, which doesn't check the inputs against the signature of the function.
+fn evaluate(udf: ScalarUDF, values: ArrayRef) -> Result<Vec<Option<f64>>, DataFusionError> {
+    let len = values.len() as u32;
+    let timestamps: ArrayRef = Arc::new(TimestampMillisecondArray::from_iter_values(
+        (0..len as i64).map(|i| i * 1000),
+    ));
+    let timestamps = RangeArray::from_ranges(timestamps, [(0, len)])
+        .unwrap()
+        .into_dict();
+    let values = RangeArray::from_ranges(values, [(0, len)])
+        .unwrap()
+        .into_dict();
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("ts", timestamps.data_type().clone(), false),
+        Field::new("value", values.data_type().clone(), true),
+    ]));
+    let batch =
+        RecordBatch::try_new(schema.clone(), vec![Arc::new(timestamps), Arc::new(values)]).unwrap();
+
+    let expr = Expr::ScalarUDF {
+        fun: Arc::new(udf),
+        args: vec![col("ts"), col("value")],
+    };
+    let df_schema = schema.clone().to_dfschema().unwrap();
+    let physical_expr = create_physical_expr(&expr, &df_schema, &schema, &ExecutionProps::new())?;
+    let result = physical_expr.evaluate(&batch)?.into_array(1);
+    Ok(result
+        .as_any()
+        .downcast_ref::<Float64Array>()
+        .unwrap()
+        .iter()
+        .collect())
+}
+
+#[test]
+fn test_range_fn_type_mismatch() {
+    let values: ArrayRef = Arc::new(Float64Array::from(vec![1.0, 2.0, 3.0]));
+    assert_eq!(
+        vec![Some(6.0)],
+        evaluate(StrictSum::scalar_udf(), values).unwrap()
+    );
+
+    let values: ArrayRef = Arc::new(Int64Array::from(vec![1, 2, 3]));
+    let err = evaluate(StrictSum::scalar_udf(), values)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("test_strict_sum"), "{err}");
+    assert!(err.contains("argument 1"), "{err}");
+    assert!(err.contains("type Float64"), "{err}");
+    assert!(err.contains("actual: Int64"), "{err}");
+}
+
+#[test]
+fn test_range_fn_coerce() {
+    let udf = CoercedSum::scalar_udf();
+    let TypeSignature::OneOf(signatures) = &udf.signature.type_signature else {
+        panic!("unexpected signature {:?}", udf.signature);
+    };
+    assert!(signatures.contains(&TypeSignature::Exact(vec![
+        RangeArray::convert_data_type(DataType::Timestamp(TimeUnit::Millisecond, None)),
+        RangeArray::convert_data_type(DataType::Int64),
+    ])));
+
+    let values: ArrayRef = Arc::new(Int64Array::from(vec![Some(1), None, Some(3)]));
+    assert_eq!(vec![Some(4.0)], evaluate(udf.clone(), values).unwrap());
+    let values: ArrayRef = Arc::new(Float64Array::from(vec![1.5, 2.5]));
+    assert_eq!(vec![Some(4.0)], evaluate(udf.clone(), values).unwrap());
+
+    // Strings can't be casted to floats safely.
+    let values: ArrayRef = Arc::new(StringArray::from(vec!["1", "2"]));
+    let err = evaluate(udf, values).unwrap_err().to_string();
+    assert!(err.contains("test_coerced_sum"), "{err}");
+    assert!(err.contains("actual: Utf8"), "{err}");
+}
@@ -17,6 +17,7 @@
 mod catalog_adapter;
 mod error;
 mod planner;
+mod scan_stats;
 
 use std::sync::Arc;
 
@@ -38,6 +39,7 @@ use snafu::{OptionExt, ResultExt};
 
 pub use crate::datafusion::catalog_adapter::DfCatalogListAdapter;
 pub use crate::datafusion::planner::DfContextProviderAdapter;
+use crate::datafusion::scan_stats::ScanStatsStream;
 use crate::error::{DataFusionSnafu, QueryExecutionSnafu, Result};
 use crate::executor::QueryExecutor;
 use crate::logical_optimizer::LogicalOptimizer;
@@ -82,7 +84,11 @@ impl QueryEngine for DatafusionQueryEngine {
         let physical_plan = self.create_physical_plan(&mut ctx, &logical_plan).await?;
         let physical_plan = self.optimize_physical_plan(&mut ctx, physical_plan)?;
 
-        Ok(Output::Stream(self.execute_stream(&ctx, &physical_plan)?))
+        let stream = self.execute_stream(&ctx, &physical_plan)?;
+        Ok(Output::Stream(Box::pin(ScanStatsStream::new(
+            stream,
+            physical_plan,
+        ))))
     }
 
     fn register_udf(&self, udf: ScalarUdf) {
@@ -236,7 +236,11 @@ impl SchemaProvider for SchemaProviderAdapter {
             .map(|_| table))
     }
 
-    fn rename_table(&self, _name: &str, _new_name: String) -> catalog_error::Result<TableRef> {
+    async fn rename_table(
+        &self,
+        _name: &str,
+        _new_name: String,
+    ) -> catalog_error::Result<TableRef> {
         todo!()
     }
 
@@ -0,0 +1,107 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use common_query::physical_plan::{DfPhysicalPlanAdapter, PhysicalPlanRef};
+use common_recordbatch::error::Result as RecordBatchResult;
+use common_recordbatch::scan_stats::{collect_scan_stats, ScanStats, TABLE_LABEL};
+use common_recordbatch::{RecordBatch, RecordBatchStream, SendableRecordBatchStream};
+use datatypes::schema::SchemaRef;
+use futures::Stream;
+use metrics::counter;
+
+use crate::metric;
+
+/// A stream of the query results that reports the [ScanStats] of its plan.
+///
+/// The stats of each table are recorded to the global metrics recorder once the stream ends.
+pub(crate) struct ScanStatsStream {
+    inner: SendableRecordBatchStream,
+    plan: PhysicalPlanRef,
+    recorded: bool,
+}
+
+impl ScanStatsStream {
+    pub(crate) fn new(inner: SendableRecordBatchStream, plan: PhysicalPlanRef) -> Self {
+        Self {
+            inner,
+            plan,
+            recorded: false,
+        }
+    }
+
+    fn record_metrics(&self) {
+        let stats = collect_scan_stats(&DfPhysicalPlanAdapter(self.plan.clone()));
+        for (table, stats) in stats {
+            counter!(
+                metric::METRIC_SCAN_FILES_SCANNED,
+                stats.files_scanned,
+                TABLE_LABEL => table.clone()
+            );
+            counter!(
+                metric::METRIC_SCAN_FILES_PRUNED,
+                stats.files_pruned,
+                TABLE_LABEL => table.clone()
+            );
+            counter!(
+                metric::METRIC_SCAN_ROWS_DECODED,
+                stats.rows_decoded,
+                TABLE_LABEL => table.clone()
+            );
+            counter!(
+                metric::METRIC_SCAN_BYTES_READ,
+                stats.bytes_read,
+                TABLE_LABEL => table
+            );
+        }
+    }
+}
+
+impl RecordBatchStream for ScanStatsStream {
+    fn schema(&self) -> SchemaRef {
+        self.inner.schema()
+    }
+
+    fn scan_stats(&self) -> Option<ScanStats> {
+        let stats = collect_scan_stats(&DfPhysicalPlanAdapter(self.plan.clone()));
+        if stats.is_empty() {
+            return None;
+        }
+        Some(stats.values().fold(ScanStats::default(), |mut acc, stats| {
+            acc.merge(stats);
+            acc
+        }))
+    }
+}
+
+impl Stream for ScanStatsStream {
+    type Item = RecordBatchResult<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let poll = Pin::new(&mut self.inner).poll_next(cx);
+        if let Poll::Ready(None) = poll {
+            if !self.recorded {
+                self.recorded = true;
+                self.record_metrics();
+            }
+        }
+        poll
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
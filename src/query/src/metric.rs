@@ -20,3 +20,11 @@ pub static METRIC_OPTIMIZE_LOGICAL_ELAPSED: &str = "query.optimize_logicalplan_e
 pub static METRIC_OPTIMIZE_PHYSICAL_ELAPSED: &str = "query.optimize_physicalplan_elapsed";
 pub static METRIC_CREATE_PHYSICAL_ELAPSED: &str = "query.create_physicalplan_elapsed";
 pub static METRIC_EXEC_PLAN_ELAPSED: &str = "query.execute_plan_elapsed";
+/// Number of SST files scanned by queries, labeled by table.
+pub static METRIC_SCAN_FILES_SCANNED: &str = "query.scan_files_scanned";
+/// Number of SST files pruned by the time range of queries, labeled by table.
+pub static METRIC_SCAN_FILES_PRUNED: &str = "query.scan_files_pruned";
+/// Number of rows decoded from SST files by queries, labeled by table.
+pub static METRIC_SCAN_ROWS_DECODED: &str = "query.scan_rows_decoded";
+/// Number of bytes read from the object store by queries, labeled by table.
+pub static METRIC_SCAN_BYTES_READ: &str = "query.scan_bytes_read";
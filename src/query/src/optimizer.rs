@@ -12,10 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::str::FromStr;
 use std::sync::Arc;
 
 use common_time::timestamp::{TimeUnit, Timestamp};
+use common_time::timezone::TimeZone;
 use datafusion::optimizer::optimizer::OptimizerRule;
 use datafusion::optimizer::OptimizerConfig;
 use datafusion_common::{DFSchemaRef, DataFusionError, Result, ScalarValue};
@@ -26,29 +26,38 @@ use datafusion_expr::{
 use datatypes::arrow::compute;
 use datatypes::arrow::datatypes::DataType;
 
+pub mod scan_pushdown;
+
 /// TypeConversionRule converts some literal values in logical plan to other types according
 /// to data type of corresponding columns.
 /// Specifically:
 /// - string literal of timestamp is converted to `Expr::Literal(ScalarValue::TimestampMillis)`
 /// - string literal of boolean is converted to `Expr::Literal(ScalarValue::Boolean)`
-pub struct TypeConversionRule;
+///
+/// Timestamp strings without an explicit offset are parsed in `time_zone`, or the local time
+/// zone of the server if it's not set.
+#[derive(Default)]
+pub struct TypeConversionRule {
+    time_zone: Option<TimeZone>,
+}
 
-impl OptimizerRule for TypeConversionRule {
-    // TODO(ruihang): fix this warning
-    #[allow(deprecated)]
-    fn try_optimize(
-        &self,
-        plan: &LogicalPlan,
-        _config: &dyn OptimizerConfig,
-    ) -> Result<Option<LogicalPlan>> {
+impl TypeConversionRule {
+    /// Creates a rule that parses timestamp strings in the session time zone `time_zone`.
+    pub fn with_time_zone(time_zone: Option<TimeZone>) -> Self {
+        Self { time_zone }
+    }
+
+    /// Converts the literals in `plan`, returns `None` if the plan is not supported.
+    pub fn convert(&self, plan: &LogicalPlan) -> Result<Option<LogicalPlan>> {
         let mut converter = TypeConverter {
             schemas: plan.all_schemas(),
+            time_zone: self.time_zone.as_ref(),
         };
 
         match plan {
             LogicalPlan::Filter(filter) => {
                 let rewritten = filter.predicate.clone().rewrite(&mut converter)?;
-                let Some(plan) = self.try_optimize(&filter.input, _config)? else { return Ok(None) };
+                let Some(plan) = self.convert(&filter.input)? else { return Ok(None) };
                 Ok(Some(LogicalPlan::Filter(Filter::try_new(
                     rewritten,
                     Arc::new(plan),
@@ -98,7 +107,7 @@ impl OptimizerRule for TypeConversionRule {
                 let inputs = plan.inputs();
                 let mut new_inputs = Vec::with_capacity(inputs.len());
                 for input in inputs {
-                    let Some(plan) = self.try_optimize(input, _config)? else { return Ok(None) };
+                    let Some(plan) = self.convert(input)? else { return Ok(None) };
                     new_inputs.push(plan);
                 }
 
@@ -123,6 +132,18 @@ impl OptimizerRule for TypeConversionRule {
             | LogicalPlan::Unnest(_) => Ok(Some(plan.clone())),
         }
     }
+}
+
+impl OptimizerRule for TypeConversionRule {
+    // TODO(ruihang): fix this warning
+    #[allow(deprecated)]
+    fn try_optimize(
+        &self,
+        plan: &LogicalPlan,
+        _config: &dyn OptimizerConfig,
+    ) -> Result<Option<LogicalPlan>> {
+        self.convert(plan)
+    }
 
     fn name(&self) -> &str {
         "TypeConversionRule"
@@ -131,6 +152,7 @@ impl OptimizerRule for TypeConversionRule {
 
 struct TypeConverter<'a> {
     schemas: Vec<&'a DFSchemaRef>,
+    time_zone: Option<&'a TimeZone>,
 }
 
 impl<'a> TypeConverter<'a> {
@@ -145,9 +167,15 @@ impl<'a> TypeConverter<'a> {
         None
     }
 
-    fn cast_scalar_value(value: &ScalarValue, target_type: &DataType) -> Result<ScalarValue> {
+    fn cast_scalar_value(
+        &self,
+        value: &ScalarValue,
+        target_type: &DataType,
+    ) -> Result<ScalarValue> {
         match (target_type, value) {
-            (DataType::Timestamp(_, _), ScalarValue::Utf8(Some(v))) => string_to_timestamp_ms(v),
+            (DataType::Timestamp(_, _), ScalarValue::Utf8(Some(v))) => {
+                string_to_timestamp_ms(v, self.time_zone)
+            }
             (DataType::Boolean, ScalarValue::Utf8(Some(v))) => match v.to_lowercase().as_str() {
                 "true" => Ok(ScalarValue::Boolean(Some(true))),
                 "false" => Ok(ScalarValue::Boolean(Some(false))),
@@ -183,7 +211,7 @@ impl<'a> TypeConverter<'a> {
 
         match (left, right) {
             (Expr::Column(col), Expr::Literal(value)) => {
-                let casted_right = Self::cast_scalar_value(value, left_type)?;
+                let casted_right = self.cast_scalar_value(value, left_type)?;
                 if casted_right.is_null() {
                     return Err(DataFusionError::Plan(format!(
                         "column:{col:?} value:{value:?} is invalid",
@@ -283,10 +311,10 @@ fn timestamp_to_timestamp_ms_expr(val: i64, unit: TimeUnit) -> Expr {
     Expr::Literal(ScalarValue::TimestampMillisecond(Some(timestamp), None))
 }
 
-fn string_to_timestamp_ms(string: &str) -> Result<ScalarValue> {
+fn string_to_timestamp_ms(string: &str, time_zone: Option<&TimeZone>) -> Result<ScalarValue> {
     Ok(ScalarValue::TimestampMillisecond(
         Some(
-            Timestamp::from_str(string)
+            Timestamp::from_str_with_timezone(string, time_zone)
                 .map(|t| t.value() / 1_000_000)
                 .map_err(|e| DataFusionError::External(Box::new(e)))?,
         ),
@@ -297,6 +325,7 @@ fn string_to_timestamp_ms(string: &str) -> Result<ScalarValue> {
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
+    use std::str::FromStr;
 
     use datafusion_common::{Column, DFField, DFSchema};
 
@@ -305,11 +334,22 @@ mod tests {
     #[test]
     fn test_string_to_timestamp_ms() {
         assert!(matches!(
-            string_to_timestamp_ms("2022-02-02 19:00:00+08:00").unwrap(),
+            string_to_timestamp_ms("2022-02-02 19:00:00+08:00", None).unwrap(),
+            ScalarValue::TimestampMillisecond(Some(1643799600000), None)
+        ));
+        assert!(matches!(
+            string_to_timestamp_ms("2009-02-13 23:31:30Z", None).unwrap(),
+            ScalarValue::TimestampMillisecond(Some(1234567890000), None)
+        ));
+
+        // Strings without an offset are parsed in the time zone.
+        let tz = TimeZone::from_str("+08:00").unwrap();
+        assert!(matches!(
+            string_to_timestamp_ms("2022-02-02 19:00:00", Some(&tz)).unwrap(),
             ScalarValue::TimestampMillisecond(Some(1643799600000), None)
         ));
         assert!(matches!(
-            string_to_timestamp_ms("2009-02-13 23:31:30Z").unwrap(),
+            string_to_timestamp_ms("2009-02-13 23:31:30Z", Some(&tz)).unwrap(),
             ScalarValue::TimestampMillisecond(Some(1234567890000), None)
         ));
     }
@@ -369,6 +409,7 @@ mod tests {
         );
         let mut converter = TypeConverter {
             schemas: vec![&schema_ref],
+            time_zone: None,
         };
 
         assert_eq!(
@@ -397,6 +438,7 @@ mod tests {
         );
         let mut converter = TypeConverter {
             schemas: vec![&schema_ref],
+            time_zone: None,
         };
 
         assert_eq!(
@@ -0,0 +1,502 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use datafusion::datasource::DefaultTableSource;
+use datafusion::optimizer::optimizer::OptimizerRule;
+use datafusion::optimizer::OptimizerConfig;
+use datafusion_common::{Column, Result};
+use datafusion_expr::expr::{self, AggregateFunction};
+use datafusion_expr::utils::from_plan;
+use datafusion_expr::{
+    coalesce, lit, Aggregate, AggregateFunction as AggregateFunctionEnum, BinaryExpr, Expr,
+    LogicalPlan, LogicalPlanBuilder, Operator, Sort, TableScan,
+};
+use table::metadata::{ScanPushdown, TimeIndexAggregate};
+use table::table::adapter::DfTableProviderAdapter;
+use table::TableRef;
+
+/// ScanPushdownRule pushes the operators above a table scan down to the scan, if the
+/// table supports evaluating them (see [`Table::supports_pushdown`](table::Table::supports_pushdown)):
+/// - a sort on the time index with a limit is pushed down as [`ScanPushdown::TopN`], the sort
+///   itself is kept to merge the rows of the partitions of the table;
+/// - `min`, `max` and `count` over the time index without `GROUP BY` are pushed down as
+///   [`ScanPushdown::TimeIndexAggregate`], the partial results are merged by `min`, `max`
+///   and `sum`.
+///
+/// Only projections and the filters already pushed down to the scan may stand between the
+/// operators and the scan. The rule expects the limits and filters to have been pushed down,
+/// so it should be applied after the other rules.
+pub struct ScanPushdownRule;
+
+impl OptimizerRule for ScanPushdownRule {
+    fn try_optimize(
+        &self,
+        plan: &LogicalPlan,
+        config: &dyn OptimizerConfig,
+    ) -> Result<Option<LogicalPlan>> {
+        let optimized = match plan {
+            LogicalPlan::Sort(sort) => push_down_top_n(sort)?,
+            LogicalPlan::Aggregate(aggregate) => push_down_aggregate(aggregate)?,
+            _ => None,
+        };
+        if optimized.is_some() {
+            return Ok(optimized);
+        }
+
+        let inputs = plan.inputs();
+        let mut new_inputs = Vec::with_capacity(inputs.len());
+        let mut optimized = false;
+        for input in inputs {
+            match self.try_optimize(input, config)? {
+                Some(new_input) => {
+                    optimized = true;
+                    new_inputs.push(new_input);
+                }
+                None => new_inputs.push(input.clone()),
+            }
+        }
+        if !optimized {
+            return Ok(None);
+        }
+        from_plan(plan, &plan.expressions(), &new_inputs).map(Some)
+    }
+
+    fn name(&self) -> &str {
+        "ScanPushdownRule"
+    }
+}
+
+fn push_down_top_n(sort: &Sort) -> Result<Option<LogicalPlan>> {
+    let Some(limit) = sort.fetch else { return Ok(None) };
+    let [Expr::Sort(expr::Sort { expr, asc, .. })] = sort.expr.as_slice() else { return Ok(None) };
+    let Some((scan, table, time_index)) = find_scan(&sort.input) else { return Ok(None) };
+    if !is_time_index(&sort.input, expr, &time_index) {
+        return Ok(None);
+    }
+
+    let pushdown = ScanPushdown::TopN {
+        limit,
+        descending: !asc,
+    };
+    if !table.supports_pushdown(&pushdown) {
+        return Ok(None);
+    }
+    // The filters stay in the scan, which evaluates them before the top-n.
+    let Some(adapter) = DfTableProviderAdapter::with_pushdown(table, pushdown, vec![]) else { return Ok(None) };
+    let new_scan = LogicalPlan::TableScan(TableScan {
+        source: Arc::new(DefaultTableSource::new(Arc::new(adapter))),
+        ..scan.clone()
+    });
+
+    Ok(Some(LogicalPlan::Sort(Sort {
+        expr: sort.expr.clone(),
+        input: Arc::new(replace_scan(&sort.input, new_scan)?),
+        fetch: sort.fetch,
+    })))
+}
+
+fn push_down_aggregate(aggregate: &Aggregate) -> Result<Option<LogicalPlan>> {
+    if !aggregate.group_expr.is_empty() {
+        return Ok(None);
+    }
+    let Some((scan, table, time_index)) = find_scan(&aggregate.input) else { return Ok(None) };
+    let Some(functions) = aggregate
+        .aggr_expr
+        .iter()
+        .map(|expr| time_index_aggregate(&aggregate.input, expr, &time_index))
+        .collect::<Option<Vec<_>>>() else { return Ok(None) };
+
+    let mut aggregates = Vec::with_capacity(functions.len());
+    for function in &functions {
+        if !aggregates.contains(function) {
+            aggregates.push(*function);
+        }
+    }
+    let pushdown = ScanPushdown::TimeIndexAggregate {
+        aggregates: aggregates.clone(),
+    };
+    if !table.supports_pushdown(&pushdown) {
+        return Ok(None);
+    }
+    // The filters are evaluated in the scan before the aggregates, so the filters above
+    // the scan, which are the same ones, are dropped along with the scan.
+    let filters = scan.filters.iter().cloned().map(Into::into).collect();
+    let Some(adapter) = DfTableProviderAdapter::with_pushdown(table, pushdown, filters) else { return Ok(None) };
+
+    let merge_exprs = aggregates
+        .iter()
+        .map(|aggregate| {
+            let fun = match aggregate {
+                TimeIndexAggregate::Min => AggregateFunctionEnum::Min,
+                TimeIndexAggregate::Max => AggregateFunctionEnum::Max,
+                TimeIndexAggregate::Count => AggregateFunctionEnum::Sum,
+            };
+            let partial = Column::from_name(aggregate.column_name(&time_index));
+            Expr::AggregateFunction(AggregateFunction {
+                fun,
+                args: vec![Expr::Column(partial)],
+                distinct: false,
+                filter: None,
+            })
+        })
+        .collect::<Vec<_>>();
+    let builder = LogicalPlanBuilder::scan(
+        scan.table_name.clone(),
+        Arc::new(DefaultTableSource::new(Arc::new(adapter))),
+        None,
+    )?
+    .aggregate(Vec::<Expr>::new(), merge_exprs)?;
+
+    // Restores the output of the original aggregate.
+    let merged = builder.schema().fields().clone();
+    let exprs = functions
+        .iter()
+        .zip(aggregate.schema.fields())
+        .map(|(function, field)| {
+            let index = aggregates.iter().position(|a| a == function).unwrap();
+            let merged = Expr::Column(merged[index].qualified_column());
+            let expr = match function {
+                // The sum of no partial counts is null.
+                TimeIndexAggregate::Count => coalesce(vec![merged, lit(0i64)]),
+                TimeIndexAggregate::Min | TimeIndexAggregate::Max => merged,
+            };
+            expr.alias(field.name())
+        })
+        .collect::<Vec<_>>();
+    builder.project(exprs)?.build().map(Some)
+}
+
+/// Maps `expr` to the aggregate pushed down to the scan under `input`, if it is one
+/// of `min`, `max` and `count` over the time index.
+fn time_index_aggregate(
+    input: &LogicalPlan,
+    expr: &Expr,
+    time_index: &str,
+) -> Option<TimeIndexAggregate> {
+    let Expr::AggregateFunction(AggregateFunction { fun, args, distinct: false, filter: None }) = expr else {
+        return None;
+    };
+    let aggregate = match fun {
+        AggregateFunctionEnum::Min => TimeIndexAggregate::Min,
+        AggregateFunctionEnum::Max => TimeIndexAggregate::Max,
+        AggregateFunctionEnum::Count => TimeIndexAggregate::Count,
+        _ => return None,
+    };
+    match args.as_slice() {
+        [arg] if is_time_index(input, arg, time_index) => Some(aggregate),
+        // `COUNT(*)` is planned as `COUNT(1)`, which equals the count of the time index
+        // since the time index is never null.
+        [Expr::Literal(value)] if aggregate == TimeIndexAggregate::Count && !value.is_null() => {
+            Some(aggregate)
+        }
+        _ => None,
+    }
+}
+
+/// Finds the table scan under `plan` that operators could be pushed down to, along with
+/// the table and its time index. Only projections and filters already pushed down to the
+/// scan are allowed in between.
+fn find_scan(plan: &LogicalPlan) -> Option<(&TableScan, TableRef, String)> {
+    match plan {
+        LogicalPlan::TableScan(scan) => {
+            // The rows are limited before any operators could be applied.
+            if scan.fetch.is_some() {
+                return None;
+            }
+            let adapter = scan
+                .source
+                .as_any()
+                .downcast_ref::<DefaultTableSource>()?
+                .table_provider
+                .as_any()
+                .downcast_ref::<DfTableProviderAdapter>()?;
+            // Already pushed down.
+            if adapter.pushdown().is_some() {
+                return None;
+            }
+            let table = adapter.table();
+            let time_index = table.schema().timestamp_column()?.name.clone();
+            Some((scan, table, time_index))
+        }
+        LogicalPlan::Projection(projection) => find_scan(&projection.input),
+        LogicalPlan::Filter(filter) => {
+            let found = find_scan(&filter.input)?;
+            let mut predicates = vec![];
+            split_conjunction(&filter.predicate, &mut predicates);
+            predicates
+                .into_iter()
+                .all(|predicate| found.0.filters.contains(predicate))
+                .then_some(found)
+        }
+        _ => None,
+    }
+}
+
+/// Tests whether `expr` over the output of `plan` is the time index column of the scan
+/// found by [`find_scan`].
+fn is_time_index(plan: &LogicalPlan, expr: &Expr, time_index: &str) -> bool {
+    let Expr::Column(column) = expr else { return false };
+    match plan {
+        LogicalPlan::TableScan(_) => column.name == time_index,
+        LogicalPlan::Projection(projection) => {
+            let Ok(index) = projection.schema.index_of_column(column) else { return false };
+            match &projection.expr[index] {
+                Expr::Alias(expr, _) => is_time_index(&projection.input, expr, time_index),
+                expr => is_time_index(&projection.input, expr, time_index),
+            }
+        }
+        LogicalPlan::Filter(filter) => is_time_index(&filter.input, expr, time_index),
+        _ => false,
+    }
+}
+
+/// Replaces the table scan under `plan` found by [`find_scan`] with `scan`.
+fn replace_scan(plan: &LogicalPlan, scan: LogicalPlan) -> Result<LogicalPlan> {
+    match plan {
+        LogicalPlan::TableScan(_) => Ok(scan),
+        _ => {
+            let input = replace_scan(plan.inputs()[0], scan)?;
+            from_plan(plan, &plan.expressions(), &[input])
+        }
+    }
+}
+
+fn split_conjunction<'a>(expr: &'a Expr, exprs: &mut Vec<&'a Expr>) {
+    match expr {
+        Expr::BinaryExpr(BinaryExpr {
+            left,
+            op: Operator::And,
+            right,
+        }) => {
+            split_conjunction(left, exprs);
+            split_conjunction(right, exprs);
+        }
+        Expr::Alias(expr, _) => split_conjunction(expr, exprs),
+        other => exprs.push(other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_query::physical_plan::PhysicalPlanRef;
+    use common_query::prelude::Expr as TableExpr;
+    use datafusion_expr::{col, count, max, min};
+    use datafusion_optimizer::OptimizerContext;
+    use datatypes::prelude::ConcreteDataType;
+    use datatypes::schema::{ColumnSchema, RawSchema, SchemaRef};
+    use table::metadata::TableInfoRef;
+    use table::requests::CreateTableRequest;
+    use table::test_util::EmptyTable;
+    use table::Table;
+
+    use super::*;
+
+    struct PushdownTable(EmptyTable);
+
+    #[async_trait::async_trait]
+    impl Table for PushdownTable {
+        fn as_any(&self) -> &dyn std::any::Any {
+            self
+        }
+
+        fn schema(&self) -> SchemaRef {
+            self.0.schema()
+        }
+
+        fn table_info(&self) -> TableInfoRef {
+            self.0.table_info()
+        }
+
+        async fn scan(
+            &self,
+            projection: Option<&Vec<usize>>,
+            filters: &[TableExpr],
+            limit: Option<usize>,
+        ) -> table::Result<PhysicalPlanRef> {
+            self.0.scan(projection, filters, limit).await
+        }
+
+        fn supports_pushdown(&self, _pushdown: &ScanPushdown) -> bool {
+            true
+        }
+    }
+
+    fn table_scan(filters: Vec<Expr>) -> LogicalPlanBuilder {
+        let column_schemas = vec![
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new("cpu", ConcreteDataType::float64_datatype(), true),
+            ColumnSchema::new(
+                "ts",
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                false,
+            )
+            .with_time_index(true),
+        ];
+        let table = PushdownTable(EmptyTable::new(CreateTableRequest {
+            id: 1,
+            catalog_name: "greptime".to_string(),
+            schema_name: "public".to_string(),
+            table_name: "t".to_string(),
+            desc: None,
+            schema: RawSchema::new(column_schemas),
+            region_numbers: vec![0],
+            primary_key_indices: vec![0],
+            create_if_not_exists: true,
+            table_options: Default::default(),
+        }));
+        let adapter = DfTableProviderAdapter::new(Arc::new(table));
+        LogicalPlanBuilder::scan_with_filters(
+            "t",
+            Arc::new(DefaultTableSource::new(Arc::new(adapter))),
+            None,
+            filters,
+        )
+        .unwrap()
+    }
+
+    fn top_n(builder: LogicalPlanBuilder, sort_expr: Expr, fetch: usize) -> LogicalPlan {
+        let sort = builder.sort(vec![sort_expr]).unwrap().build().unwrap();
+        let LogicalPlan::Sort(sort) = sort else { unreachable!() };
+        LogicalPlan::Sort(Sort {
+            fetch: Some(fetch),
+            ..sort
+        })
+    }
+
+    fn optimize(plan: &LogicalPlan) -> Option<LogicalPlan> {
+        ScanPushdownRule
+            .try_optimize(plan, &OptimizerContext::new())
+            .unwrap()
+    }
+
+    fn scan_pushdown(plan: &LogicalPlan) -> Option<ScanPushdown> {
+        match plan {
+            LogicalPlan::TableScan(scan) => scan
+                .source
+                .as_any()
+                .downcast_ref::<DefaultTableSource>()
+                .unwrap()
+                .table_provider
+                .as_any()
+                .downcast_ref::<DfTableProviderAdapter>()
+                .unwrap()
+                .pushdown()
+                .cloned(),
+            plan => scan_pushdown(plan.inputs()[0]),
+        }
+    }
+
+    #[test]
+    fn test_push_down_top_n() {
+        // The filters pushed down to the scan are qualified.
+        let filter = Expr::Column(Column::new(Some("t"), "host")).eq(lit("host1"));
+        let builder = table_scan(vec![filter.clone()])
+            .filter(filter)
+            .unwrap()
+            .project(vec![col("cpu"), col("ts").alias("time")])
+            .unwrap();
+        let plan = top_n(builder, col("time").sort(false, true), 10);
+
+        let optimized = optimize(&plan).unwrap();
+        assert_eq!(plan.schema(), optimized.schema());
+        assert_eq!(
+            Some(ScanPushdown::TopN {
+                limit: 10,
+                descending: true
+            }),
+            scan_pushdown(&optimized)
+        );
+
+        // Already pushed down.
+        assert!(optimize(&optimized).is_none());
+    }
+
+    #[test]
+    fn test_not_push_down_top_n() {
+        // The filter is not evaluated by the scan.
+        let builder = table_scan(vec![])
+            .filter(col("host").eq(lit("host1")))
+            .unwrap();
+        let plan = top_n(builder, col("ts").sort(true, false), 10);
+        assert!(optimize(&plan).is_none());
+
+        // Not sorted by the time index.
+        let plan = top_n(table_scan(vec![]), col("cpu").sort(true, false), 10);
+        assert!(optimize(&plan).is_none());
+
+        // The rows of the scan are joined.
+        let builder = table_scan(vec![])
+            .alias("a")
+            .unwrap()
+            .cross_join(&table_scan(vec![]).alias("b").unwrap().build().unwrap())
+            .unwrap();
+        let plan = top_n(builder, col("a.ts").sort(true, false), 10);
+        assert!(optimize(&plan).is_none());
+    }
+
+    #[test]
+    fn test_push_down_aggregate() {
+        let plan = table_scan(vec![])
+            .aggregate(
+                Vec::<Expr>::new(),
+                vec![
+                    min(col("ts")),
+                    max(col("ts")),
+                    count(col("ts")),
+                    count(lit(1u8)),
+                ],
+            )
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let optimized = optimize(&plan).unwrap();
+        assert_eq!(
+            plan.schema().field_names(),
+            optimized.schema().field_names()
+        );
+        assert_eq!(
+            Some(ScanPushdown::TimeIndexAggregate {
+                aggregates: vec![
+                    TimeIndexAggregate::Min,
+                    TimeIndexAggregate::Max,
+                    TimeIndexAggregate::Count
+                ]
+            }),
+            scan_pushdown(&optimized)
+        );
+        assert!(optimize(&optimized).is_none());
+    }
+
+    #[test]
+    fn test_not_push_down_aggregate() {
+        // With GROUP BY.
+        let plan = table_scan(vec![])
+            .aggregate(vec![col("host")], vec![min(col("ts"))])
+            .unwrap()
+            .build()
+            .unwrap();
+        assert!(optimize(&plan).is_none());
+
+        // Not over the time index.
+        let plan = table_scan(vec![])
+            .aggregate(Vec::<Expr>::new(), vec![min(col("ts")), max(col("cpu"))])
+            .unwrap()
+            .build()
+            .unwrap();
+        assert!(optimize(&plan).is_none());
+    }
+}
@@ -30,7 +30,8 @@ use crate::error::{
 };
 use crate::metric::{METRIC_PARSE_PROMQL_ELAPSED, METRIC_PARSE_SQL_ELAPSED};
 
-const DEFAULT_LOOKBACK: u64 = 5 * 60; // 5m
+/// Lookback delta of PromQL queries that don't specify one, the same as Prometheus.
+pub const DEFAULT_LOOKBACK_DELTA: Duration = Duration::from_secs(5 * 60);
 
 #[derive(Debug, Clone)]
 pub enum QueryStatement {
@@ -44,6 +45,9 @@ pub struct PromQuery {
     pub start: String,
     pub end: String,
     pub step: String,
+    /// How far back to look for the latest sample of a series at each step, in seconds or
+    /// a duration like `5m`. [DEFAULT_LOOKBACK_DELTA] is used if absent.
+    pub lookback: Option<String>,
 }
 
 pub struct QueryLanguageParser {}
@@ -88,11 +92,24 @@ impl QueryLanguageParser {
                 query: &query.query,
             })?;
 
-        let step = query
-            .step
-            .parse::<u64>()
-            .map(Duration::from_secs)
-            .or_else(|_| promql_parser::util::parse_duration(&query.step))
+        let step = Self::parse_promql_duration(&query.step)
+            .map_err(|msg| BoxedError::new(PlainError::new(msg, StatusCode::InvalidArguments)))
+            .context(QueryParseSnafu {
+                query: &query.query,
+            })?;
+
+        let lookback_delta = query
+            .lookback
+            .as_deref()
+            .map(Self::parse_promql_duration)
+            .transpose()
+            .map_err(|msg| BoxedError::new(PlainError::new(msg, StatusCode::InvalidArguments)))
+            .context(QueryParseSnafu {
+                query: &query.query,
+            })?
+            .unwrap_or(DEFAULT_LOOKBACK_DELTA);
+
+        Self::validate_promql_range(start, end, step)
             .map_err(|msg| BoxedError::new(PlainError::new(msg, StatusCode::InvalidArguments)))
             .context(QueryParseSnafu {
                 query: &query.query,
@@ -103,14 +120,38 @@ impl QueryLanguageParser {
             start,
             end,
             interval: step,
-            // TODO(ruihang): provide a way to adjust this parameter.
-            lookback_delta: Duration::from_secs(DEFAULT_LOOKBACK),
+            lookback_delta,
         };
 
         Ok(QueryStatement::Promql(eval_stmt))
     }
 
-    fn parse_promql_timestamp(timestamp: &str) -> Result<SystemTime> {
+    /// Parses a duration in seconds or like `5m`.
+    pub fn parse_promql_duration(duration: &str) -> std::result::Result<Duration, String> {
+        duration
+            .parse::<u64>()
+            .map(Duration::from_secs)
+            .or_else(|_| promql_parser::util::parse_duration(duration))
+    }
+
+    /// Checks the range of a query, returns the error in the words of Prometheus.
+    pub fn validate_promql_range(
+        start: SystemTime,
+        end: SystemTime,
+        step: Duration,
+    ) -> std::result::Result<(), String> {
+        if end < start {
+            return Err("end timestamp must not be before start time".to_string());
+        }
+        if step.is_zero() {
+            return Err("zero or negative query resolution step widths are not accepted. \
+                        Try a positive integer"
+                .to_string());
+        }
+        Ok(())
+    }
+
+    pub fn parse_promql_timestamp(timestamp: &str) -> Result<SystemTime> {
         // try rfc3339 format
         let rfc3339_result = DateTime::parse_from_rfc3339(timestamp)
             .context(ParseTimestampSnafu { raw: timestamp })
@@ -228,6 +269,7 @@ mod test {
             start: "2022-02-13T17:14:00Z".to_string(),
             end: "2023-02-13T17:14:00Z".to_string(),
             step: "1d".to_string(),
+            lookback: None,
         };
 
         let expected = String::from(
@@ -252,4 +294,33 @@ mod test {
         let result = QueryLanguageParser::parse_promql(&promql).unwrap();
         assert_eq!(format!("{result:?}"), expected);
     }
+
+    #[test]
+    fn parse_promql_lookback_and_range() {
+        let mut promql = PromQuery {
+            query: "http_request".to_string(),
+            start: "0".to_string(),
+            end: "100".to_string(),
+            step: "10".to_string(),
+            lookback: Some("30s".to_string()),
+        };
+        let stmt = QueryLanguageParser::parse_promql(&promql).unwrap();
+        let QueryStatement::Promql(stmt) = stmt else {
+            unreachable!()
+        };
+        assert_eq!(Duration::from_secs(30), stmt.lookback_delta);
+
+        promql.lookback = Some("x".to_string());
+        assert!(QueryLanguageParser::parse_promql(&promql).is_err());
+
+        promql.lookback = None;
+        promql.step = "0s".to_string();
+        let err = QueryLanguageParser::parse_promql(&promql).unwrap_err();
+        assert!(err.to_string().contains("zero or negative"), "{err}");
+
+        promql.step = "10".to_string();
+        promql.start = "200".to_string();
+        let err = QueryLanguageParser::parse_promql(&promql).unwrap_err();
+        assert!(err.to_string().contains("must not be before"), "{err}");
+    }
 }
@@ -12,13 +12,16 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::fmt::Debug;
 
+use datafusion_common::ScalarValue;
 use datafusion_expr::LogicalPlan as DfLogicalPlan;
+use datatypes::prelude::ConcreteDataType;
 use datatypes::schema::Schema;
 use snafu::ResultExt;
 
-use crate::error::Result;
+use crate::error::{DataFusionSnafu, DatatypeSnafu, Result};
 
 /// A LogicalPlan represents the different types of relational
 /// operators (such as Projection, Filter, etc) and can be created by
@@ -46,4 +49,33 @@ impl LogicalPlan {
             }
         }
     }
+
+    /// Returns the types of the placeholders (`$1`, `$2`...) in this plan, the type is
+    /// `None` if it can't be inferred from the plan.
+    pub fn get_param_types(&self) -> Result<HashMap<String, Option<ConcreteDataType>>> {
+        match self {
+            Self::DfPlan(plan) => plan
+                .get_parameter_types()
+                .context(DataFusionSnafu)?
+                .into_iter()
+                .map(|(name, data_type)| {
+                    let data_type = data_type
+                        .map(|t| ConcreteDataType::try_from(&t).context(DatatypeSnafu))
+                        .transpose()?;
+                    Ok((name, data_type))
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns a new plan with the placeholders replaced by `values`, the `$n`
+    /// placeholder is replaced by the `n-1`th value.
+    pub fn replace_params_with_values(&self, values: &[ScalarValue]) -> Result<LogicalPlan> {
+        match self {
+            Self::DfPlan(plan) => plan
+                .replace_params_with_values(values)
+                .context(DataFusionSnafu)
+                .map(Self::DfPlan),
+        }
+    }
 }
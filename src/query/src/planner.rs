@@ -26,6 +26,7 @@ use snafu::ResultExt;
 use sql::statements::statement::Statement;
 
 use crate::error::{PlanSqlSnafu, QueryPlanSnafu, Result, SqlSnafu};
+use crate::optimizer::TypeConversionRule;
 use crate::parser::QueryStatement;
 use crate::plan::LogicalPlan;
 use crate::query_engine::QueryEngineState;
@@ -52,6 +53,7 @@ impl DfLogicalPlanner {
 
     async fn plan_sql(&self, stmt: Statement, query_ctx: QueryContextRef) -> Result<LogicalPlan> {
         let df_stmt = (&stmt).try_into().context(SqlSnafu)?;
+        let time_zone = query_ctx.time_zone();
 
         let context_provider = DfContextProviderAdapter::try_new(
             self.engine_state.clone(),
@@ -69,14 +71,24 @@ impl DfLogicalPlanner {
 
         let sql_to_rel = SqlToRel::new_with_options(&context_provider, parser_options);
 
-        let result = sql_to_rel.statement_to_plan(df_stmt).with_context(|_| {
-            let sql = if let Statement::Query(query) = stmt {
-                query.inner.to_string()
-            } else {
-                format!("{stmt:?}")
-            };
-            PlanSqlSnafu { sql }
-        })?;
+        let result = sql_to_rel
+            .statement_to_plan(df_stmt)
+            .and_then(|plan| match time_zone {
+                // Timestamp strings in the plan are parsed in the session time zone before
+                // the optimizer converts the others.
+                Some(time_zone) => Ok(TypeConversionRule::with_time_zone(Some(time_zone))
+                    .convert(&plan)?
+                    .unwrap_or(plan)),
+                None => Ok(plan),
+            })
+            .with_context(|_| {
+                let sql = if let Statement::Query(query) = stmt {
+                    query.inner.to_string()
+                } else {
+                    format!("{stmt:?}")
+                };
+                PlanSqlSnafu { sql }
+            })?;
         Ok(LogicalPlan::DfPlan(result))
     }
 
@@ -24,6 +24,7 @@ use common_base::Plugins;
 use common_function::scalars::aggregate::AggregateFunctionMetaRef;
 use common_function::scalars::{FunctionRef, FUNCTION_REGISTRY};
 use common_query::prelude::ScalarUdf;
+use common_query::range_fn_registry::collect_range_fns;
 use common_query::Output;
 use datatypes::schema::Schema;
 use session::context::QueryContextRef;
@@ -76,6 +77,7 @@ impl QueryEngineFactory {
 
     pub fn new_with_plugins(catalog_list: CatalogListRef, plugins: Arc<Plugins>) -> Self {
         let state = Arc::new(QueryEngineState::new(catalog_list, plugins));
+        register_range_fns(&state);
         let query_engine = Arc::new(DatafusionQueryEngine::new(state));
         register_functions(&query_engine);
         Self { query_engine }
@@ -86,6 +88,15 @@ impl QueryEngineFactory {
     }
 }
 
+fn register_range_fns(state: &QueryEngineState) {
+    // Range functions sharing a display name would shadow each other in queries, so we
+    // fail fast here.
+    let range_fns = collect_range_fns().unwrap_or_else(|e| panic!("{e}"));
+    for udf in range_fns.into_values() {
+        state.register_df_udf(udf);
+    }
+}
+
 fn register_functions(query_engine: &Arc<DatafusionQueryEngine>) {
     for func in FUNCTION_REGISTRY.functions() {
         query_engine.register_function(func);
@@ -27,11 +27,12 @@ use datafusion::execution::context::{QueryPlanner, SessionConfig, SessionState};
 use datafusion::execution::runtime_env::RuntimeEnv;
 use datafusion::physical_plan::planner::DefaultPhysicalPlanner;
 use datafusion::physical_plan::{ExecutionPlan, PhysicalPlanner};
-use datafusion_expr::LogicalPlan as DfLogicalPlan;
+use datafusion_expr::{LogicalPlan as DfLogicalPlan, ScalarUDF as DfScalarUDF};
 use datafusion_optimizer::optimizer::Optimizer;
 use promql::extension_plan::PromExtensionPlanner;
 
 use crate::datafusion::DfCatalogListAdapter;
+use crate::optimizer::scan_pushdown::ScanPushdownRule;
 use crate::optimizer::TypeConversionRule;
 use crate::query_engine::options::QueryOptions;
 
@@ -60,7 +61,11 @@ impl QueryEngineState {
         let session_config = SessionConfig::new().with_create_default_catalog_and_schema(false);
         let mut optimizer = Optimizer::new();
         // Apply the type conversion rule first.
-        optimizer.rules.insert(0, Arc::new(TypeConversionRule {}));
+        optimizer
+            .rules
+            .insert(0, Arc::new(TypeConversionRule::default()));
+        // Apply the scan pushdown rule last, after the limits and filters are pushed down.
+        optimizer.rules.push(Arc::new(ScanPushdownRule));
 
         let session_state = SessionState::with_config_rt_and_catalog_list(
             session_config,
@@ -86,6 +91,11 @@ impl QueryEngineState {
         self.df_context.register_udf(udf.into_df_udf());
     }
 
+    /// Register a DataFusion udf, e.g. the functions generated by `range_fn`.
+    pub(crate) fn register_df_udf(&self, udf: DfScalarUDF) {
+        self.df_context.register_udf(udf);
+    }
+
     pub fn aggregate_function(&self, function_name: &str) -> Option<AggregateFunctionMetaRef> {
         self.aggregate_functions
             .read()
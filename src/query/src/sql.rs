@@ -12,19 +12,25 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 
 use catalog::CatalogManagerRef;
 use common_catalog::consts::DEFAULT_CATALOG_NAME;
 use common_query::Output;
 use common_recordbatch::RecordBatches;
+use common_time::timestamp::TimeUnit;
 use datatypes::prelude::*;
-use datatypes::schema::{ColumnSchema, Schema};
+use datatypes::schema::{ColumnDefaultConstraint, ColumnSchema, Schema};
 use datatypes::vectors::{Helper, StringVector};
 use once_cell::sync::Lazy;
 use session::context::QueryContextRef;
 use snafu::{ensure, OptionExt, ResultExt};
+use sql::ast::ALL_KEYWORDS;
+use sql::statements::create::Partitions;
 use sql::statements::show::{ShowDatabases, ShowKind, ShowTables};
+use sql::statements::value_to_sql_value;
+use table::metadata::TableInfo;
 use table::TableRef;
 
 use crate::error::{self, Result};
@@ -36,6 +42,8 @@ const COLUMN_TYPE_COLUMN: &str = "Type";
 const COLUMN_NULLABLE_COLUMN: &str = "Null";
 const COLUMN_DEFAULT_COLUMN: &str = "Default";
 const COLUMN_SEMANTIC_TYPE_COLUMN: &str = "Semantic Type";
+const TABLE_COLUMN: &str = "Table";
+const CREATE_TABLE_COLUMN: &str = "Create Table";
 
 const SEMANTIC_TYPE_PRIMARY_KEY: &str = "PRIMARY KEY";
 const SEMANTIC_TYPE_VALUE: &str = "VALUE";
@@ -74,6 +82,17 @@ static DESCRIBE_TABLE_OUTPUT_SCHEMA: Lazy<Arc<Schema>> = Lazy::new(|| {
     ]))
 });
 
+static SHOW_CREATE_TABLE_OUTPUT_SCHEMA: Lazy<Arc<Schema>> = Lazy::new(|| {
+    Arc::new(Schema::new(vec![
+        ColumnSchema::new(TABLE_COLUMN, ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new(
+            CREATE_TABLE_COLUMN,
+            ConcreteDataType::string_datatype(),
+            false,
+        ),
+    ]))
+});
+
 pub fn show_databases(stmt: ShowDatabases, catalog_manager: CatalogManagerRef) -> Result<Output> {
     // TODO(LFC): supports WHERE
     ensure!(
@@ -167,6 +186,161 @@ pub fn describe_table(table: TableRef) -> Result<Output> {
     Ok(Output::RecordBatches(records))
 }
 
+/// Shows the `CREATE TABLE` statement of the table, `partitions` is the partition rule of
+/// the table in distributed mode.
+pub fn show_create_table(table: TableRef, partitions: Option<Partitions>) -> Result<Output> {
+    let table_info = table.table_info();
+    let columns = vec![
+        Arc::new(StringVector::from(vec![table_info.name.clone()])) as _,
+        Arc::new(StringVector::from(vec![create_table_sql(
+            &table_info,
+            partitions.as_ref(),
+        )])) as _,
+    ];
+    let records = RecordBatches::try_from_columns(SHOW_CREATE_TABLE_OUTPUT_SCHEMA.clone(), columns)
+        .context(error::CreateRecordBatchSnafu)?;
+    Ok(Output::RecordBatches(records))
+}
+
+/// Builds the `CREATE TABLE` statement of the table from its current metadata, table
+/// options (such as `ttl`) altered after creation are also included. Executing the
+/// statement creates a table with the same metadata.
+fn create_table_sql(table_info: &TableInfo, partitions: Option<&Partitions>) -> String {
+    let meta = &table_info.meta;
+    let schema = &meta.schema;
+
+    let mut lines = Vec::with_capacity(schema.num_columns() + 2);
+    for column_schema in schema.column_schemas() {
+        let mut line = format!(
+            "  {} {}",
+            quote_ident(&column_schema.name),
+            sql_type_name(&column_schema.data_type)
+        );
+        line.push_str(if column_schema.is_nullable() {
+            " NULL"
+        } else {
+            " NOT NULL"
+        });
+        match column_schema.default_constraint() {
+            Some(ColumnDefaultConstraint::Value(v)) => match value_to_sql_value(v) {
+                Some(sql_value) => line.push_str(&format!(" DEFAULT {sql_value}")),
+                // Values that could not be expressed in SQL yet.
+                None => line.push_str(&format!(" DEFAULT {v}")),
+            },
+            Some(constraint) => line.push_str(&format!(" DEFAULT {constraint}")),
+            None => {}
+        }
+        lines.push(line);
+    }
+    if let Some(ts_column) = schema.timestamp_column() {
+        lines.push(format!("  TIME INDEX ({})", quote_ident(&ts_column.name)));
+    }
+    if !meta.primary_key_indices.is_empty() {
+        let keys = meta
+            .primary_key_indices
+            .iter()
+            .map(|idx| quote_ident(schema.column_name_by_index(*idx)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        lines.push(format!("  PRIMARY KEY ({keys})"));
+    }
+
+    let mut sql = format!(
+        "CREATE TABLE {} (\n{}\n)",
+        quote_ident(&table_info.name),
+        lines.join(",\n"),
+    );
+
+    if let Some(partitions) = partitions {
+        let columns = partitions
+            .column_list
+            .iter()
+            .map(|column| quote_ident(&column.value))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let entries = partitions
+            .entries
+            .iter()
+            .map(|entry| {
+                let values = entry
+                    .value_list
+                    .iter()
+                    .map(|v| v.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!(
+                    "  PARTITION {} VALUES LESS THAN ({values})",
+                    quote_ident(&entry.name.value)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",\n");
+        sql.push_str(&format!(
+            "\nPARTITION BY RANGE COLUMNS ({columns}) (\n{entries}\n)"
+        ));
+    }
+
+    sql.push_str(&format!("\nENGINE={}", meta.engine));
+
+    // Sort options to make the output stable.
+    let options: BTreeMap<_, _> = HashMap::from(&meta.options).into_iter().collect();
+    if !options.is_empty() {
+        let options = options
+            .iter()
+            .map(|(k, v)| format!("  {k} = '{}'", v.replace('\'', "''")))
+            .collect::<Vec<_>>()
+            .join(",\n");
+        sql.push_str(&format!("\nWITH(\n{options}\n)"));
+    }
+    sql
+}
+
+/// Quotes the identifier if it's a keyword or contains characters other than
+/// letters, digits and underscores, so it's parsed as is.
+fn quote_ident(ident: &str) -> String {
+    let is_plain = ident
+        .chars()
+        .next()
+        .map_or(false, |c| c.is_ascii_alphabetic() || c == '_')
+        && ident.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && ALL_KEYWORDS
+            .binary_search(&ident.to_ascii_uppercase().as_str())
+            .is_err();
+    if is_plain {
+        ident.to_string()
+    } else {
+        format!("\"{}\"", ident.replace('"', "\"\""))
+    }
+}
+
+fn sql_type_name(data_type: &ConcreteDataType) -> String {
+    match data_type {
+        ConcreteDataType::Boolean(_) => "BOOLEAN".to_string(),
+        ConcreteDataType::Int8(_) => "TINYINT".to_string(),
+        ConcreteDataType::Int16(_) => "SMALLINT".to_string(),
+        ConcreteDataType::Int32(_) => "INT".to_string(),
+        ConcreteDataType::Int64(_) => "BIGINT".to_string(),
+        ConcreteDataType::UInt8(_) => "TINYINT UNSIGNED".to_string(),
+        ConcreteDataType::UInt16(_) => "SMALLINT UNSIGNED".to_string(),
+        ConcreteDataType::UInt32(_) => "INT UNSIGNED".to_string(),
+        ConcreteDataType::UInt64(_) => "BIGINT UNSIGNED".to_string(),
+        ConcreteDataType::Float32(_) => "FLOAT".to_string(),
+        ConcreteDataType::Float64(_) => "DOUBLE".to_string(),
+        ConcreteDataType::String(_) => "STRING".to_string(),
+        ConcreteDataType::Binary(_) => "VARBINARY".to_string(),
+        ConcreteDataType::Date(_) => "DATE".to_string(),
+        ConcreteDataType::DateTime(_) => "DATETIME".to_string(),
+        ConcreteDataType::Timestamp(t) => match t.unit() {
+            TimeUnit::Second => "TIMESTAMP(0)".to_string(),
+            TimeUnit::Millisecond => "TIMESTAMP".to_string(),
+            TimeUnit::Microsecond => "TIMESTAMP(6)".to_string(),
+            TimeUnit::Nanosecond => "TIMESTAMP(9)".to_string(),
+        },
+        // Types that could not be expressed in SQL yet.
+        _ => data_type.name().to_string(),
+    }
+}
+
 fn describe_column_names(columns_schemas: &[ColumnSchema]) -> VectorRef {
     Arc::new(StringVector::from_iterator(
         columns_schemas.iter().map(|cs| cs.name.as_str()),
@@ -226,7 +400,9 @@ fn describe_column_semantic_types(
 
 #[cfg(test)]
 mod test {
+    use std::collections::HashMap;
     use std::sync::Arc;
+    use std::time::Duration;
 
     use common_query::Output;
     use common_recordbatch::{RecordBatch, RecordBatches};
@@ -235,13 +411,19 @@ mod test {
     use datatypes::schema::{ColumnDefaultConstraint, ColumnSchema, Schema, SchemaRef};
     use datatypes::vectors::{StringVector, TimestampMillisecondVector, UInt32Vector, VectorRef};
     use snafu::ResultExt;
+    use sql::ast::{Ident, Value as SqlValue};
+    use sql::statements::create::{PartitionEntry, Partitions};
+    use table::metadata::{TableInfoBuilder, TableMetaBuilder};
+    use table::requests::{
+        TableOptions, COMPACTION_MAX_FILES_IN_LEVEL0_KEY, COMPACTION_MAX_INFLIGHT_KEY,
+    };
     use table::test_util::MemTable;
     use table::TableRef;
 
     use crate::error;
     use crate::error::Result;
     use crate::sql::{
-        describe_table, DESCRIBE_TABLE_OUTPUT_SCHEMA, NULLABLE_NO, NULLABLE_YES,
+        create_table_sql, describe_table, DESCRIBE_TABLE_OUTPUT_SCHEMA, NULLABLE_NO, NULLABLE_YES,
         SEMANTIC_TYPE_TIME_INDEX, SEMANTIC_TYPE_VALUE,
     };
 
@@ -309,4 +491,131 @@ mod test {
         let record_batch = RecordBatch::new(table_schema, data).unwrap();
         Arc::new(MemTable::new(table_name, record_batch))
     }
+
+    #[test]
+    fn test_create_table_sql() {
+        let schema = Arc::new(Schema::new(vec![
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), true)
+                .with_default_constraint(Some(ColumnDefaultConstraint::Value("localhost".into())))
+                .unwrap(),
+            ColumnSchema::new(
+                "ts",
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                false,
+            )
+            .with_time_index(true),
+            ColumnSchema::new("cpu", ConcreteDataType::float64_datatype(), true),
+        ]));
+        let new_table_info = |options| {
+            let meta = TableMetaBuilder::default()
+                .schema(schema.clone())
+                .primary_key_indices(vec![0])
+                .engine("mito")
+                .next_column_id(3)
+                .options(options)
+                .build()
+                .unwrap();
+            TableInfoBuilder::default()
+                .name("monitor")
+                .meta(meta)
+                .build()
+                .unwrap()
+        };
+
+        let table_info = new_table_info(TableOptions::default());
+        assert_eq!(
+            r#"CREATE TABLE monitor (
+  host STRING NULL DEFAULT 'localhost',
+  ts TIMESTAMP NOT NULL,
+  cpu DOUBLE NULL,
+  TIME INDEX (ts),
+  PRIMARY KEY (host)
+)
+ENGINE=mito"#,
+            create_table_sql(&table_info, None)
+        );
+
+        let table_info = new_table_info(TableOptions {
+            ttl: Some(Duration::from_secs(30 * 24 * 3600)),
+            ..Default::default()
+        });
+        assert!(create_table_sql(&table_info, None).ends_with(
+            r#"ENGINE=mito
+WITH(
+  ttl = '30days'
+)"#
+        ));
+
+        // Compaction options altered at runtime are shown.
+        let options = HashMap::from([
+            (COMPACTION_MAX_FILES_IN_LEVEL0_KEY.to_string(), "4".to_string()),
+            (COMPACTION_MAX_INFLIGHT_KEY.to_string(), "2".to_string()),
+        ]);
+        let table_info = new_table_info(TableOptions::try_from(&options).unwrap());
+        assert!(create_table_sql(&table_info, None).ends_with(
+            r#"ENGINE=mito
+WITH(
+  compaction_max_files_in_level0 = '4',
+  compaction_max_inflight = '2'
+)"#
+        ));
+    }
+
+    #[test]
+    fn test_create_table_sql_with_partitions() {
+        let schema = Arc::new(Schema::new(vec![
+            ColumnSchema::new("Host", ConcreteDataType::string_datatype(), true)
+                .with_default_constraint(Some(ColumnDefaultConstraint::Value("it's".into())))
+                .unwrap(),
+            ColumnSchema::new("value", ConcreteDataType::int32_datatype(), true),
+            ColumnSchema::new(
+                "ts",
+                ConcreteDataType::timestamp_datatype(TimeUnit::Nanosecond),
+                false,
+            )
+            .with_time_index(true),
+        ]));
+        let meta = TableMetaBuilder::default()
+            .schema(schema)
+            .primary_key_indices(vec![1, 0])
+            .engine("mito")
+            .next_column_id(3)
+            .options(TableOptions::default())
+            .build()
+            .unwrap();
+        let table_info = TableInfoBuilder::default()
+            .name("my-table")
+            .meta(meta)
+            .build()
+            .unwrap();
+        let partitions = Partitions {
+            column_list: vec![Ident::new("value")],
+            entries: vec![
+                PartitionEntry {
+                    name: Ident::new("r0"),
+                    value_list: vec![SqlValue::Number("10".to_string(), false)],
+                },
+                PartitionEntry {
+                    name: Ident::new("r1"),
+                    value_list: vec![SqlValue::Number("MAXVALUE".to_string(), false)],
+                },
+            ],
+        };
+
+        assert_eq!(
+            r#"CREATE TABLE "my-table" (
+  Host STRING NULL DEFAULT 'it''s',
+  "value" INT NULL,
+  ts TIMESTAMP(9) NOT NULL,
+  TIME INDEX (ts),
+  PRIMARY KEY ("value", Host)
+)
+PARTITION BY RANGE COLUMNS ("value") (
+  PARTITION r0 VALUES LESS THAN (10),
+  PARTITION r1 VALUES LESS THAN (MAXVALUE)
+)
+ENGINE=mito"#,
+            create_table_sql(&table_info, Some(&partitions))
+        );
+    }
 }
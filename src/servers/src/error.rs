@@ -96,6 +96,9 @@ pub enum Error {
     #[snafu(display("Failed to describe statement, source: {}", source))]
     DescribeStatement { source: BoxedError },
 
+    #[snafu(display("Failed to prepare statement, source: {}", source))]
+    PrepareStatement { source: BoxedError },
+
     #[snafu(display("Failed to execute alter: {}, source: {}", query, source))]
     ExecuteAlter {
         query: String,
@@ -138,6 +141,29 @@ pub enum Error {
         source: common_grpc::error::Error,
     },
 
+    #[snafu(display("Failed to write OpenTSDB data points, source: {}", source))]
+    OpentsdbLinesWrite {
+        #[snafu(backtrace)]
+        source: common_grpc::error::Error,
+    },
+
+    #[snafu(display("Invalid InfluxDB lines: {}", errors))]
+    InvalidInfluxdbLines {
+        errors: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display(
+        "Partial write, {} lines written, invalid InfluxDB lines: {}",
+        written,
+        errors
+    ))]
+    InfluxdbPartialWrite {
+        written: usize,
+        errors: String,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Failed to convert time precision, name: {}", name))]
     TimePrecision { name: String, backtrace: Backtrace },
 
@@ -194,6 +220,25 @@ pub enum Error {
         backtrace: Backtrace,
     },
 
+    #[snafu(display("Invalid Flight put request: {}", reason))]
+    InvalidFlightPut {
+        reason: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Invalid Flight action {}, source: {}", action_type, source))]
+    InvalidFlightAction {
+        action_type: String,
+        source: api::DecodeError,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Unknown Flight action: {}", action_type))]
+    UnknownFlightAction {
+        action_type: String,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Failed to start frontend service, source: {}", source))]
     StartFrontend {
         #[snafu(backtrace)]
@@ -315,18 +360,23 @@ impl ErrorExt for Error {
             | DecompressPromRemoteRequest { .. }
             | InvalidPromRemoteRequest { .. }
             | InvalidFlightTicket { .. }
+            | InvalidFlightPut { .. }
+            | InvalidFlightAction { .. }
+            | UnknownFlightAction { .. }
             | InvalidPrepareStatement { .. }
+            | InvalidInfluxdbLines { .. }
+            | InfluxdbPartialWrite { .. }
             | TimePrecision { .. } => StatusCode::InvalidArguments,
 
-            InfluxdbLinesWrite { source, .. } | ConvertFlightMessage { source } => {
-                source.status_code()
-            }
+            InfluxdbLinesWrite { source, .. }
+            | OpentsdbLinesWrite { source, .. }
+            | ConvertFlightMessage { source } => source.status_code(),
 
             Hyper { .. } => StatusCode::Unknown,
             TlsRequired { .. } => StatusCode::Unknown,
             StartFrontend { source, .. } => source.status_code(),
             Auth { source, .. } => source.status_code(),
-            DescribeStatement { source } => source.status_code(),
+            DescribeStatement { source } | PrepareStatement { source } => source.status_code(),
 
             NotFoundAuthHeader { .. } | NotFoundInfluxAuth { .. } => StatusCode::AuthHeaderNotFound,
             InvisibleASCII { .. }
@@ -387,8 +437,11 @@ impl IntoResponse for Error {
         let (status, error_message) = match self {
             Error::InfluxdbLineProtocol { .. }
             | Error::InfluxdbLinesWrite { .. }
+            | Error::InvalidInfluxdbLines { .. }
+            | Error::InfluxdbPartialWrite { .. }
             | Error::InvalidOpentsdbLine { .. }
             | Error::InvalidOpentsdbJsonRequest { .. }
+            | Error::OpentsdbLinesWrite { .. }
             | Error::DecodePromRemoteRequest { .. }
             | Error::DecompressPromRemoteRequest { .. }
             | Error::InvalidPromRemoteRequest { .. }
@@ -22,6 +22,7 @@ use std::sync::Arc;
 use api::v1::greptime_database_server::{GreptimeDatabase, GreptimeDatabaseServer};
 use arrow_flight::flight_service_server::{FlightService, FlightServiceServer};
 use async_trait::async_trait;
+use common_grpc::compression::{GrpcCompression, ACCEPTED_ENCODINGS};
 use common_runtime::Runtime;
 use common_telemetry::logging::info;
 use futures::FutureExt;
@@ -40,6 +41,8 @@ use crate::grpc::database::DatabaseService;
 use crate::grpc::flight::FlightHandler;
 use crate::grpc::handler::GreptimeRequestHandler;
 use crate::query_handler::grpc::ServerGrpcQueryHandlerRef;
+use crate::query_handler::sql::ServerSqlQueryHandlerRef;
+use crate::query_handler::{BulkLoadHandlerRef, RecordBatchInsertHandlerRef};
 use crate::server::Server;
 
 type TonicResult<T> = std::result::Result<T, Status>;
@@ -47,6 +50,10 @@ type TonicResult<T> = std::result::Result<T, Status>;
 pub struct GrpcServer {
     shutdown_tx: Mutex<Option<Sender<()>>>,
     request_handler: Arc<GreptimeRequestHandler>,
+    insert_handler: Option<RecordBatchInsertHandlerRef>,
+    bulk_load_handler: Option<BulkLoadHandlerRef>,
+    sql_handler: Option<ServerSqlQueryHandlerRef>,
+    compression: GrpcCompression,
 }
 
 impl GrpcServer {
@@ -63,15 +70,64 @@ impl GrpcServer {
         Self {
             shutdown_tx: Mutex::new(None),
             request_handler,
+            insert_handler: None,
+            bulk_load_handler: None,
+            sql_handler: None,
+            compression: GrpcCompression::None,
         }
     }
 
+    /// Sets the handler of record batches written through Flight `DoPut`.
+    pub fn set_record_batch_insert_handler(&mut self, handler: RecordBatchInsertHandlerRef) {
+        self.insert_handler = Some(handler);
+    }
+
+    /// Sets the handler of bulk loads through Flight `DoAction` and `DoPut`.
+    pub fn set_bulk_load_handler(&mut self, handler: BulkLoadHandlerRef) {
+        self.bulk_load_handler = Some(handler);
+    }
+
+    /// Sets the handler of multi-statement SQL queries sent through Flight `DoGet`.
+    pub fn set_sql_query_handler(&mut self, handler: ServerSqlQueryHandlerRef) {
+        self.sql_handler = Some(handler);
+    }
+
+    /// Sets how the responses are compressed, compressed requests are always accepted.
+    pub fn set_compression(&mut self, compression: GrpcCompression) {
+        self.compression = compression;
+    }
+
     pub fn create_flight_service(&self) -> FlightServiceServer<impl FlightService> {
-        FlightServiceServer::new(FlightHandler::new(self.request_handler.clone()))
+        let mut handler = FlightHandler::new(self.request_handler.clone());
+        if let Some(insert_handler) = &self.insert_handler {
+            handler = handler.with_insert_handler(insert_handler.clone());
+        }
+        if let Some(bulk_load_handler) = &self.bulk_load_handler {
+            handler = handler.with_bulk_load_handler(bulk_load_handler.clone());
+        }
+        if let Some(sql_handler) = &self.sql_handler {
+            handler = handler.with_sql_handler(sql_handler.clone());
+        }
+        let mut service = FlightServiceServer::new(handler);
+        for encoding in ACCEPTED_ENCODINGS {
+            service = service.accept_compressed(encoding);
+        }
+        if let Some(encoding) = self.compression.encoding() {
+            service = service.send_compressed(encoding);
+        }
+        service
     }
 
     pub fn create_database_service(&self) -> GreptimeDatabaseServer<impl GreptimeDatabase> {
-        GreptimeDatabaseServer::new(DatabaseService::new(self.request_handler.clone()))
+        let mut service =
+            GreptimeDatabaseServer::new(DatabaseService::new(self.request_handler.clone()));
+        for encoding in ACCEPTED_ENCODINGS {
+            service = service.accept_compressed(encoding);
+        }
+        if let Some(encoding) = self.compression.encoding() {
+            service = service.send_compressed(encoding);
+        }
+        service
     }
 }
 
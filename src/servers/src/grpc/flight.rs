@@ -17,34 +17,147 @@ mod stream;
 use std::pin::Pin;
 use std::sync::Arc;
 
-use api::v1::GreptimeRequest;
+use api::v1::greptime_request::Request as GreptimeRequestKind;
+use api::v1::query_request::Query;
+use api::v1::{GreptimeRequest, InsertRequest, QueryRequest, RequestHeader};
 use arrow_flight::flight_service_server::FlightService;
 use arrow_flight::{
     Action, ActionType, Criteria, Empty, FlightData, FlightDescriptor, FlightInfo,
     HandshakeRequest, HandshakeResponse, PutResult, SchemaResult, Ticket,
 };
 use async_trait::async_trait;
-use common_grpc::flight::{FlightEncoder, FlightMessage};
+use common_error::prelude::ErrorExt;
+use common_grpc::flight::{
+    BulkLoadRequest, BulkLoadResult, FlightDecoder, FlightEncoder, FlightMessage, PutBatchResult,
+    StatementResult, ABORT_BULK_LOAD, BEGIN_BULK_LOAD, BULK_LOAD_HEADER, COMMIT_BULK_LOAD,
+    DROP_TABLE_SYNC_HEADER, MULTI_STATEMENTS_HEADER, ON_ERROR_CONTINUE, ON_ERROR_STOP,
+    SCAN_STATS_HEADER,
+};
 use common_query::Output;
+use common_recordbatch::{RecordBatch, RecordBatches};
+use common_telemetry::logging;
 use futures::Stream;
 use prost::Message;
-use snafu::ResultExt;
+use session::context::QueryContextRef;
+use session::variables::{CONTINUE_ON_ERROR, DROP_TABLE_SYNC};
+use snafu::{ensure, ErrorCompat, OptionExt, ResultExt};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
 use tonic::{Request, Response, Status, Streaming};
 
 use crate::error;
 use crate::grpc::flight::stream::FlightRecordBatchStream;
 use crate::grpc::handler::GreptimeRequestHandler;
 use crate::grpc::TonicResult;
+use crate::query_handler::sql::ServerSqlQueryHandlerRef;
+use crate::query_handler::{BulkLoadHandlerRef, RecordBatchInsertHandlerRef};
 
 type TonicStream<T> = Pin<Box<dyn Stream<Item = TonicResult<T>> + Send + Sync + 'static>>;
 
+/// Max number of buffered results of a `DoPut` stream before the client reads them.
+const PUT_RESULT_BUFFER_SIZE: usize = 16;
+
 pub struct FlightHandler {
     handler: Arc<GreptimeRequestHandler>,
+    insert_handler: Option<RecordBatchInsertHandlerRef>,
+    bulk_load_handler: Option<BulkLoadHandlerRef>,
+    sql_handler: Option<ServerSqlQueryHandlerRef>,
 }
 
 impl FlightHandler {
     pub fn new(handler: Arc<GreptimeRequestHandler>) -> Self {
-        Self { handler }
+        Self {
+            handler,
+            insert_handler: None,
+            bulk_load_handler: None,
+            sql_handler: None,
+        }
+    }
+
+    /// Enables writing record batches through `DoPut` with the `insert_handler`.
+    pub fn with_insert_handler(mut self, insert_handler: RecordBatchInsertHandlerRef) -> Self {
+        self.insert_handler = Some(insert_handler);
+        self
+    }
+
+    /// Enables bulk loads through `DoAction` and `DoPut` with the `bulk_load_handler`, see
+    /// [BULK_LOAD_HEADER].
+    pub fn with_bulk_load_handler(mut self, bulk_load_handler: BulkLoadHandlerRef) -> Self {
+        self.bulk_load_handler = Some(bulk_load_handler);
+        self
+    }
+
+    /// Enables executing multi-statement SQL queries through `DoGet` with the `sql_handler`,
+    /// see [MULTI_STATEMENTS_HEADER].
+    pub fn with_sql_handler(mut self, sql_handler: ServerSqlQueryHandlerRef) -> Self {
+        self.sql_handler = Some(sql_handler);
+        self
+    }
+
+    /// Executes the statements of the SQL query of `request` in order, `on_error` is the value
+    /// of the [MULTI_STATEMENTS_HEADER]. The outputs of each statement are collected, then
+    /// sent with the [StatementResult] of the statement.
+    async fn do_get_statements(
+        &self,
+        request: GreptimeRequest,
+        on_error: &str,
+    ) -> TonicResult<TonicStream<FlightData>> {
+        let sql_handler = self
+            .sql_handler
+            .clone()
+            .context(error::NotSupportedSnafu {
+                feat: "multiple statements in Flight DoGet",
+            })?;
+        let continue_on_error = match on_error {
+            ON_ERROR_STOP => false,
+            ON_ERROR_CONTINUE => true,
+            _ => {
+                let reason = format!("Unknown value of {MULTI_STATEMENTS_HEADER}: {on_error}");
+                return Err(error::InvalidQuerySnafu { reason }.build().into());
+            }
+        };
+        let query = match request.request {
+            Some(GreptimeRequestKind::Query(QueryRequest { query })) => query,
+            _ => None,
+        };
+        let Some(Query::Sql(sql)) = query else {
+            let reason = "Expecting a SQL query to execute multiple statements.";
+            return Err(error::InvalidQuerySnafu { reason }.build().into());
+        };
+        let ctx = self.handler.create_context(request.header.as_ref()).await?;
+        if continue_on_error {
+            // Never fails, the variable is known and the value is valid.
+            let _ = ctx.set_variable(CONTINUE_ON_ERROR, "ON");
+        }
+
+        // Like `GreptimeRequestHandler`, executes in the handler's runtime so the statements
+        // are not cancelled halfway when the client goes away.
+        let handle = self.handler.runtime().spawn(async move {
+            let results = sql_handler.do_query(&sql, ctx).await;
+            let mut encoder = FlightEncoder::default();
+            let mut flight_data = Vec::new();
+            for (statement_index, result) in results.into_iter().enumerate() {
+                let statement_index = statement_index as u64;
+                let end = match statement_messages(result).await {
+                    Ok(messages) => {
+                        flight_data.extend(messages.into_iter().map(|m| encoder.encode(m)));
+                        StatementResult {
+                            statement_index,
+                            ..Default::default()
+                        }
+                    }
+                    Err(e) => StatementResult {
+                        statement_index,
+                        err_code: e.status_code().to_string(),
+                        err_msg: e.iter_chain().last().unwrap().to_string(),
+                    },
+                };
+                flight_data.push(encoder.encode(FlightMessage::StatementEnd(end)));
+            }
+            flight_data
+        });
+        let flight_data = handle.await.map_err(|e| Status::internal(e.to_string()))?;
+        Ok(Box::pin(tokio_stream::iter(flight_data.into_iter().map(Ok))) as _)
     }
 }
 
@@ -85,23 +198,171 @@ impl FlightService for FlightHandler {
     type DoGetStream = TonicStream<FlightData>;
 
     async fn do_get(&self, request: Request<Ticket>) -> TonicResult<Response<Self::DoGetStream>> {
+        let send_scan_stats = request
+            .metadata()
+            .get(SCAN_STATS_HEADER)
+            .map(|value| value == "true")
+            .unwrap_or(false);
+        let on_error = request
+            .metadata()
+            .get(MULTI_STATEMENTS_HEADER)
+            .map(|value| value.to_str().unwrap_or_default().to_string());
+        let drop_table_sync = request
+            .metadata()
+            .get(DROP_TABLE_SYNC_HEADER)
+            .map(|value| value == "true")
+            .unwrap_or(false);
         let ticket = request.into_inner().ticket;
         let request =
             GreptimeRequest::decode(ticket.as_ref()).context(error::InvalidFlightTicketSnafu)?;
+        if let Some(on_error) = on_error {
+            let stream = self.do_get_statements(request, &on_error).await?;
+            return Ok(Response::new(stream));
+        }
 
-        let output = self.handler.handle_request(request).await?;
+        let ctx = self.handler.create_context(request.header.as_ref()).await?;
+        if drop_table_sync {
+            // Never fails, the variable is known and the value is valid.
+            let _ = ctx.set_variable(DROP_TABLE_SYNC, "ON");
+        }
+        let output = self
+            .handler
+            .handle_request_with_context(request, ctx)
+            .await?;
 
-        let stream = to_flight_data_stream(output);
+        let stream = to_flight_data_stream(output, send_scan_stats);
         Ok(Response::new(stream))
     }
 
     type DoPutStream = TonicStream<PutResult>;
 
+    /// Writes record batches to a table.
+    ///
+    /// The first [FlightData] carries the schema of the batches, and a [FlightDescriptor]
+    /// whose command is an encoded [GreptimeRequest] with an [InsertRequest] that only
+    /// specifies the table name and the region number. Each following record batch is
+    /// written separately, and a [PutResult] is returned for each batch, so an invalid
+    /// batch does not fail the others.
+    ///
+    /// If the [BULK_LOAD_HEADER] is set, the batches are written to the bulk load instead.
     async fn do_put(
         &self,
-        _: Request<Streaming<FlightData>>,
+        request: Request<Streaming<FlightData>>,
     ) -> TonicResult<Response<Self::DoPutStream>> {
-        Err(Status::unimplemented("Not yet implemented"))
+        let bulk_load = request
+            .metadata()
+            .get(BULK_LOAD_HEADER)
+            .map(|value| value.to_str().unwrap_or_default().to_string());
+        let writer = match bulk_load {
+            Some(load_id) => {
+                let handler = self
+                    .bulk_load_handler
+                    .clone()
+                    .context(error::NotSupportedSnafu {
+                        feat: "bulk load in Flight DoPut",
+                    })?;
+                PutWriter::BulkLoad { handler, load_id }
+            }
+            None => {
+                let handler = self
+                    .insert_handler
+                    .clone()
+                    .context(error::NotSupportedSnafu {
+                        feat: "Flight DoPut",
+                    })?;
+                PutWriter::Table(handler)
+            }
+        };
+
+        let mut stream = request.into_inner();
+        let first = stream
+            .message()
+            .await?
+            .context(error::InvalidFlightPutSnafu {
+                reason: "Expecting non-empty FlightData stream.",
+            })?;
+        let (header, target) = decode_put_target(first.flight_descriptor.as_ref())?;
+        let ctx = self.handler.create_context(header.as_ref()).await?;
+
+        let mut decoder = FlightDecoder::default();
+        let message = decoder
+            .try_decode(first)
+            .context(error::ConvertFlightMessageSnafu)?;
+        ensure!(
+            matches!(message, FlightMessage::Schema(_)),
+            error::InvalidFlightPutSnafu {
+                reason: "Expecting the first FlightData to be schema.",
+            }
+        );
+
+        let (tx, rx) = mpsc::channel(PUT_RESULT_BUFFER_SIZE);
+        // Like `GreptimeRequestHandler`, writes in the handler's runtime so a write is not
+        // cancelled halfway when the client goes away.
+        let _handle = self.handler.runtime().spawn(async move {
+            let mut batch_index = 0;
+            loop {
+                let flight_data = match stream.message().await {
+                    Ok(Some(flight_data)) => flight_data,
+                    Ok(None) => break,
+                    Err(status) => {
+                        let _ = tx.send(Err(status)).await;
+                        break;
+                    }
+                };
+
+                let result = match decoder
+                    .try_decode(flight_data)
+                    .context(error::ConvertFlightMessageSnafu)
+                {
+                    // Following batches are decoded with the new schema.
+                    Ok(FlightMessage::Schema(_)) => continue,
+                    Ok(FlightMessage::Recordbatch(batch)) => {
+                        writer.write(&target, batch, ctx.clone()).await
+                    }
+                    Ok(FlightMessage::AffectedRows(_)) => error::InvalidFlightPutSnafu {
+                        reason: "Unexpected AffectedRows in DoPut stream.",
+                    }
+                    .fail(),
+                    Ok(FlightMessage::ScanStats(_)) => error::InvalidFlightPutSnafu {
+                        reason: "Unexpected ScanStats in DoPut stream.",
+                    }
+                    .fail(),
+                    Ok(FlightMessage::StatementEnd(_)) => error::InvalidFlightPutSnafu {
+                        reason: "Unexpected StatementEnd in DoPut stream.",
+                    }
+                    .fail(),
+                    Err(e) => Err(e),
+                };
+                let result = match result {
+                    Ok(rows) => PutBatchResult {
+                        batch_index,
+                        affected_rows: rows as _,
+                        ..Default::default()
+                    },
+                    Err(e) => {
+                        logging::debug!(
+                            "Failed to write batch {} to table {}, error: {}",
+                            batch_index,
+                            target.table_name,
+                            e
+                        );
+                        PutBatchResult {
+                            batch_index,
+                            affected_rows: 0,
+                            err_code: e.status_code().to_string(),
+                            err_msg: e.to_string(),
+                        }
+                    }
+                };
+                if tx.send(Ok(result.into())).await.is_err() {
+                    // The client has gone.
+                    break;
+                }
+                batch_index += 1;
+            }
+        });
+
+        Ok(Response::new(Box::pin(ReceiverStream::new(rx))))
     }
 
     type DoExchangeStream = TonicStream<FlightData>;
@@ -115,8 +376,50 @@ impl FlightService for FlightHandler {
 
     type DoActionStream = TonicStream<arrow_flight::Result>;
 
-    async fn do_action(&self, _: Request<Action>) -> TonicResult<Response<Self::DoActionStream>> {
-        Err(Status::unimplemented("Not yet implemented"))
+    /// Begins, commits or aborts a bulk load. The body of the [Action] is an encoded
+    /// [BulkLoadRequest], and the only result carries an encoded [BulkLoadResult].
+    async fn do_action(
+        &self,
+        request: Request<Action>,
+    ) -> TonicResult<Response<Self::DoActionStream>> {
+        let bulk_load_handler = self
+            .bulk_load_handler
+            .clone()
+            .context(error::NotSupportedSnafu {
+                feat: "Flight DoAction",
+            })?;
+
+        let action = request.into_inner();
+        let action_type = action.r#type;
+        let request = BulkLoadRequest::decode(action.body.as_ref()).context(
+            error::InvalidFlightActionSnafu {
+                action_type: &action_type,
+            },
+        )?;
+        let load_id = match action_type.as_str() {
+            BEGIN_BULK_LOAD => {
+                let ctx = self.handler.create_context(request.header.as_ref()).await?;
+                bulk_load_handler
+                    .begin_bulk_load(&request.table_name, ctx)
+                    .await?
+            }
+            COMMIT_BULK_LOAD => {
+                let _ = self.handler.create_context(request.header.as_ref()).await?;
+                bulk_load_handler.commit_bulk_load(&request.load_id).await?;
+                request.load_id
+            }
+            ABORT_BULK_LOAD => {
+                let _ = self.handler.create_context(request.header.as_ref()).await?;
+                bulk_load_handler.abort_bulk_load(&request.load_id).await?;
+                request.load_id
+            }
+            action_type => {
+                return Err(error::UnknownFlightActionSnafu { action_type }.build().into());
+            }
+        };
+
+        let result: arrow_flight::Result = BulkLoadResult { load_id }.into();
+        Ok(Response::new(Box::pin(tokio_stream::once(Ok(result)))))
     }
 
     type ListActionsStream = TonicStream<ActionType>;
@@ -125,18 +428,93 @@ impl FlightService for FlightHandler {
         &self,
         _: Request<Empty>,
     ) -> TonicResult<Response<Self::ListActionsStream>> {
-        Err(Status::unimplemented("Not yet implemented"))
+        let actions = if self.bulk_load_handler.is_some() {
+            vec![
+                (BEGIN_BULK_LOAD, "Begins a bulk load of a table."),
+                (COMMIT_BULK_LOAD, "Commits a bulk load."),
+                (ABORT_BULK_LOAD, "Aborts a bulk load."),
+            ]
+        } else {
+            vec![]
+        };
+        let actions = actions.into_iter().map(|(r#type, description)| {
+            Ok(ActionType {
+                r#type: r#type.to_string(),
+                description: description.to_string(),
+            })
+        });
+        Ok(Response::new(Box::pin(tokio_stream::iter(actions))))
     }
 }
 
-fn to_flight_data_stream(output: Output) -> TonicStream<FlightData> {
+/// Writes the record batches of a `DoPut` stream.
+enum PutWriter {
+    Table(RecordBatchInsertHandlerRef),
+    BulkLoad {
+        handler: BulkLoadHandlerRef,
+        load_id: String,
+    },
+}
+
+impl PutWriter {
+    async fn write(
+        &self,
+        target: &InsertRequest,
+        batch: RecordBatch,
+        ctx: QueryContextRef,
+    ) -> error::Result<usize> {
+        match self {
+            PutWriter::Table(handler) => {
+                handler
+                    .insert_record_batch(&target.table_name, target.region_number, batch, ctx)
+                    .await
+            }
+            PutWriter::BulkLoad { handler, load_id } => {
+                handler
+                    .write_bulk_load(load_id, target.region_number, batch)
+                    .await
+            }
+        }
+    }
+}
+
+fn decode_put_target(
+    descriptor: Option<&FlightDescriptor>,
+) -> TonicResult<(Option<RequestHeader>, InsertRequest)> {
+    let descriptor = descriptor.context(error::InvalidFlightPutSnafu {
+        reason: "Missing FlightDescriptor in the first FlightData.",
+    })?;
+    let request = GreptimeRequest::decode(descriptor.cmd.as_ref())
+        .context(error::InvalidFlightTicketSnafu)?;
+    let Some(GreptimeRequestKind::Insert(target)) = request.request else {
+        let reason = "Expecting an InsertRequest in FlightDescriptor.";
+        return Err(error::InvalidFlightPutSnafu { reason }.build().into());
+    };
+    Ok((request.header, target))
+}
+
+/// Returns the messages of the output of a statement, whose record batches are collected.
+async fn statement_messages(output: error::Result<Output>) -> error::Result<Vec<FlightMessage>> {
+    let batches = match output? {
+        Output::AffectedRows(rows) => return Ok(vec![FlightMessage::AffectedRows(rows)]),
+        Output::RecordBatches(batches) => batches,
+        Output::Stream(stream) => RecordBatches::try_collect(stream)
+            .await
+            .context(error::CollectRecordbatchSnafu)?,
+    };
+    let mut messages = vec![FlightMessage::Schema(batches.schema())];
+    messages.extend(batches.take().into_iter().map(FlightMessage::Recordbatch));
+    Ok(messages)
+}
+
+fn to_flight_data_stream(output: Output, send_scan_stats: bool) -> TonicStream<FlightData> {
     match output {
         Output::Stream(stream) => {
-            let stream = FlightRecordBatchStream::new(stream);
+            let stream = FlightRecordBatchStream::new(stream, send_scan_stats);
             Box::pin(stream) as _
         }
         Output::RecordBatches(x) => {
-            let stream = FlightRecordBatchStream::new(x.as_stream());
+            let stream = FlightRecordBatchStream::new(x.as_stream(), send_scan_stats);
             Box::pin(stream) as _
         }
         Output::AffectedRows(rows) => {
@@ -39,12 +39,15 @@ pub(super) struct FlightRecordBatchStream {
 }
 
 impl FlightRecordBatchStream {
-    pub(super) fn new(recordbatches: SendableRecordBatchStream) -> Self {
+    /// Creates a stream of the `recordbatches`, which ends with the [ScanStats] of them if
+    /// `send_scan_stats` is true.
+    ///
+    /// [ScanStats]: common_recordbatch::scan_stats::ScanStats
+    pub(super) fn new(recordbatches: SendableRecordBatchStream, send_scan_stats: bool) -> Self {
         let (tx, rx) = mpsc::channel::<TonicResult<FlightMessage>>(1);
-        let join_handle =
-            common_runtime::spawn_read(
-                async move { Self::flight_data_stream(recordbatches, tx).await },
-            );
+        let join_handle = common_runtime::spawn_read(async move {
+            Self::flight_data_stream(recordbatches, send_scan_stats, tx).await
+        });
         Self {
             rx,
             join_handle,
@@ -55,6 +58,7 @@ impl FlightRecordBatchStream {
 
     async fn flight_data_stream(
         mut recordbatches: SendableRecordBatchStream,
+        send_scan_stats: bool,
         mut tx: Sender<TonicResult<FlightMessage>>,
     ) {
         let schema = recordbatches.schema();
@@ -80,6 +84,13 @@ impl FlightRecordBatchStream {
                 }
             }
         }
+
+        if send_scan_stats {
+            let stats = recordbatches.scan_stats().unwrap_or_default();
+            if let Err(e) = tx.send(Ok(FlightMessage::ScanStats(stats))).await {
+                warn!("stop sending Flight data, err: {e}");
+            }
+        }
     }
 }
 
@@ -124,6 +135,7 @@ mod test {
     use std::sync::Arc;
 
     use common_grpc::flight::{FlightDecoder, FlightMessage};
+    use common_recordbatch::scan_stats::ScanStats;
     use common_recordbatch::{RecordBatch, RecordBatches};
     use datatypes::prelude::*;
     use datatypes::schema::{ColumnSchema, Schema};
@@ -146,7 +158,7 @@ mod test {
         let recordbatches = RecordBatches::try_new(schema.clone(), vec![recordbatch.clone()])
             .unwrap()
             .as_stream();
-        let mut stream = FlightRecordBatchStream::new(recordbatches);
+        let mut stream = FlightRecordBatchStream::new(recordbatches, false);
 
         let mut raw_data = Vec::with_capacity(2);
         raw_data.push(stream.next().await.unwrap().unwrap());
@@ -175,4 +187,29 @@ mod test {
             _ => unreachable!(),
         }
     }
+
+    #[tokio::test]
+    async fn test_flight_record_batch_stream_with_scan_stats() {
+        let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
+            "a",
+            ConcreteDataType::int32_datatype(),
+            false,
+        )]));
+        let recordbatches = RecordBatches::try_new(schema.clone(), vec![])
+            .unwrap()
+            .as_stream();
+        let mut stream = FlightRecordBatchStream::new(recordbatches, true);
+
+        let decoder = &mut FlightDecoder::default();
+        let mut flight_messages = Vec::new();
+        while let Some(flight_data) = stream.next().await {
+            flight_messages.push(decoder.try_decode(flight_data.unwrap()).unwrap());
+        }
+        assert_eq!(flight_messages.len(), 2);
+        let FlightMessage::Schema(actual_schema) = &flight_messages[0] else { unreachable!() };
+        assert_eq!(*actual_schema, schema);
+        // streams without scans end with empty stats
+        let FlightMessage::ScanStats(stats) = &flight_messages[1] else { unreachable!() };
+        assert_eq!(ScanStats::default(), *stats);
+    }
 }
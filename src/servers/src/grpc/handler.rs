@@ -18,7 +18,7 @@ use api::v1::auth_header::AuthScheme;
 use api::v1::{Basic, GreptimeRequest, RequestHeader};
 use common_query::Output;
 use common_runtime::Runtime;
-use session::context::{QueryContext, QueryContextRef};
+use session::context::{Channel, QueryContext, QueryContextRef};
 use snafu::OptionExt;
 use tonic::Status;
 
@@ -48,15 +48,21 @@ impl GreptimeRequestHandler {
     }
 
     pub(crate) async fn handle_request(&self, request: GreptimeRequest) -> TonicResult<Output> {
+        let query_ctx = self.create_context(request.header.as_ref()).await?;
+        self.handle_request_with_context(request, query_ctx).await
+    }
+
+    /// Handles the `request` in `query_ctx`, which is created from the header of the
+    /// `request` by [Self::create_context] and may be adjusted by the caller.
+    pub(crate) async fn handle_request_with_context(
+        &self,
+        request: GreptimeRequest,
+        query_ctx: QueryContextRef,
+    ) -> TonicResult<Output> {
         let query = request.request.context(InvalidQuerySnafu {
             reason: "Expecting non-empty GreptimeRequest.",
         })?;
 
-        let header = request.header.as_ref();
-        let query_ctx = create_query_context(header);
-
-        self.auth(header, &query_ctx).await?;
-
         let handler = self.handler.clone();
 
         // Executes requests in another runtime to
@@ -82,6 +88,20 @@ impl GreptimeRequestHandler {
         Ok(output)
     }
 
+    /// Creates the context of a request with `header`, once the user is authenticated.
+    pub(crate) async fn create_context(
+        &self,
+        header: Option<&RequestHeader>,
+    ) -> TonicResult<QueryContextRef> {
+        let query_ctx = create_query_context(header);
+        self.auth(header, &query_ctx).await?;
+        Ok(query_ctx)
+    }
+
+    pub(crate) fn runtime(&self) -> &Arc<Runtime> {
+        &self.runtime
+    }
+
     async fn auth(
         &self,
         header: Option<&RequestHeader>,
@@ -119,12 +139,14 @@ impl GreptimeRequestHandler {
                 &user_info,
             )
             .await
-            .map_err(|e| Status::permission_denied(e.to_string()))
+            .map_err(|e| Status::permission_denied(e.to_string()))?;
+        query_ctx.set_current_user(user_info);
+        Ok(())
     }
 }
 
 fn create_query_context(header: Option<&RequestHeader>) -> QueryContextRef {
-    let ctx = QueryContext::arc();
+    let ctx = Arc::new(QueryContext::new().with_channel(Channel::Grpc));
     if let Some(header) = header {
         if !header.catalog.is_empty() {
             ctx.set_current_catalog(&header.catalog);
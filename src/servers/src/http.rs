@@ -20,6 +20,7 @@ pub mod prometheus;
 pub mod script;
 
 mod admin;
+mod csv;
 #[cfg(feature = "mem-prof")]
 pub mod mem_prof;
 
@@ -37,14 +38,14 @@ use axum::{routing, BoxError, Extension, Router};
 use common_error::prelude::ErrorExt;
 use common_error::status_code::StatusCode;
 use common_query::Output;
-use common_recordbatch::{util, RecordBatch};
+use common_recordbatch::{util, RecordBatch, SendableRecordBatchStream};
 use common_telemetry::logging::info;
 use datatypes::data_type::DataType;
-use futures::FutureExt;
+use futures::{FutureExt, TryStreamExt};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use session::context::QueryContext;
+use session::context::{Channel, QueryContext};
 use snafu::{ensure, ResultExt};
 use tokio::sync::oneshot::{self, Sender};
 use tokio::sync::Mutex;
@@ -57,12 +58,16 @@ use self::authorize::HttpAuth;
 use self::influxdb::{influxdb_health, influxdb_ping, influxdb_write};
 use crate::auth::UserProviderRef;
 use crate::error::{AlreadyStartedSnafu, Result, StartHttpSnafu};
-use crate::http::admin::flush;
+use crate::http::admin::{
+    backup_status, collect_garbage, compaction_status, flush, kill_query, processlist, table_stats,
+    verify_storage,
+};
 use crate::query_handler::grpc::ServerGrpcQueryHandlerRef;
 use crate::query_handler::sql::ServerSqlQueryHandlerRef;
 use crate::query_handler::{
-    InfluxdbLineProtocolHandlerRef, OpentsdbProtocolHandlerRef, PrometheusProtocolHandlerRef,
-    ScriptHandlerRef,
+    BackupHandlerRef, CompactionStatusHandlerRef, InfluxdbLineProtocolHandlerRef,
+    OpentsdbProtocolHandlerRef, PrometheusProtocolHandlerRef, ReadinessHandlerRef,
+    ScriptHandlerRef, SstGcHandlerRef, StorageVerifyHandlerRef, TableStatsHandlerRef,
 };
 use crate::server::Server;
 
@@ -76,7 +81,9 @@ pub(crate) fn query_context_from_db(
         let (catalog, schema) = super::parse_catalog_and_schema_from_client_database_name(db);
 
         match query_handler.is_valid_schema(catalog, schema) {
-            Ok(true) => Ok(Arc::new(QueryContext::with(catalog, schema))),
+            Ok(true) => Ok(Arc::new(
+                QueryContext::with(catalog, schema).with_channel(Channel::Http),
+            )),
             Ok(false) => Err(JsonResponse::with_error(
                 format!("Database not found: {db}"),
                 StatusCode::DatabaseNotFound,
@@ -87,7 +94,7 @@ pub(crate) fn query_context_from_db(
             )),
         }
     } else {
-        Ok(QueryContext::arc())
+        Ok(Arc::new(QueryContext::new().with_channel(Channel::Http)))
     }
 }
 
@@ -105,6 +112,12 @@ pub struct HttpServer {
     opentsdb_handler: Option<OpentsdbProtocolHandlerRef>,
     prom_handler: Option<PrometheusProtocolHandlerRef>,
     script_handler: Option<ScriptHandlerRef>,
+    backup_handler: Option<BackupHandlerRef>,
+    gc_handler: Option<SstGcHandlerRef>,
+    compaction_handler: Option<CompactionStatusHandlerRef>,
+    table_stats_handler: Option<TableStatsHandlerRef>,
+    verify_handler: Option<StorageVerifyHandlerRef>,
+    readiness_handler: Option<ReadinessHandlerRef>,
     shutdown_tx: Mutex<Option<Sender<()>>>,
     user_provider: Option<UserProviderRef>,
 }
@@ -114,6 +127,8 @@ pub struct HttpOptions {
     pub addr: String,
     #[serde(with = "humantime_serde")]
     pub timeout: Duration,
+    /// Max number of rows returned for each result set of a sql query, unlimited if absent.
+    pub max_result_rows: Option<usize>,
 }
 
 impl Default for HttpOptions {
@@ -121,6 +136,7 @@ impl Default for HttpOptions {
         Self {
             addr: "127.0.0.1:4000".to_string(),
             timeout: Duration::from_secs(30),
+            max_result_rows: None,
         }
     }
 }
@@ -175,6 +191,28 @@ impl HttpRecordsOutput {
     pub fn rows(&self) -> &Vec<Vec<Value>> {
         &self.rows
     }
+
+    fn truncate(&mut self, max_rows: Option<usize>) {
+        if let Some(max_rows) = max_rows {
+            self.rows.truncate(max_rows);
+        }
+    }
+}
+
+/// Collects record batches from the stream until at least `max_rows` rows are
+/// collected, the rest of the stream is dropped without being polled.
+async fn collect_with_limit(
+    mut stream: SendableRecordBatchStream,
+    max_rows: usize,
+) -> common_recordbatch::error::Result<Vec<RecordBatch>> {
+    let mut batches = Vec::new();
+    let mut num_rows = 0;
+    while num_rows < max_rows {
+        let Some(batch) = stream.try_next().await? else { break };
+        num_rows += batch.num_rows();
+        batches.push(batch);
+    }
+    Ok(batches)
 }
 
 impl TryFrom<Vec<RecordBatch>> for HttpRecordsOutput {
@@ -270,6 +308,12 @@ impl JsonResponse {
 
     /// Create a json response from query result
     async fn from_output(outputs: Vec<Result<Output>>) -> Self {
+        Self::from_output_with_limit(outputs, None).await
+    }
+
+    /// Create a json response from query result, keeping at most `max_rows` rows
+    /// of each result set.
+    async fn from_output_with_limit(outputs: Vec<Result<Output>>, max_rows: Option<usize>) -> Self {
         // TODO(sunng87): this api response structure cannot represent error
         // well. It hides successful execution results from error response
         let mut results = Vec::with_capacity(outputs.len());
@@ -280,9 +324,14 @@ impl JsonResponse {
                 }
                 Ok(Output::Stream(stream)) => {
                     // TODO(sunng87): streaming response
-                    match util::collect(stream).await {
+                    let batches = match max_rows {
+                        Some(max_rows) => collect_with_limit(stream, max_rows).await,
+                        None => util::collect(stream).await,
+                    };
+                    match batches {
                         Ok(rows) => match HttpRecordsOutput::try_from(rows) {
-                            Ok(rows) => {
+                            Ok(mut rows) => {
+                                rows.truncate(max_rows);
                                 results.push(JsonOutput::Records(rows));
                             }
                             Err(err) => {
@@ -299,7 +348,8 @@ impl JsonResponse {
                     }
                 }
                 Ok(Output::RecordBatches(rbs)) => match HttpRecordsOutput::try_from(rbs.take()) {
-                    Ok(rows) => {
+                    Ok(mut rows) => {
+                        rows.truncate(max_rows);
                         results.push(JsonOutput::Records(rows));
                     }
                     Err(err) => {
@@ -350,6 +400,7 @@ async fn serve_docs() -> Html<String> {
 pub struct ApiState {
     pub sql_handler: ServerSqlQueryHandlerRef,
     pub script_handler: Option<ScriptHandlerRef>,
+    pub max_result_rows: Option<usize>,
 }
 
 impl HttpServer {
@@ -367,6 +418,12 @@ impl HttpServer {
             prom_handler: None,
             user_provider: None,
             script_handler: None,
+            backup_handler: None,
+            gc_handler: None,
+            compaction_handler: None,
+            table_stats_handler: None,
+            verify_handler: None,
+            readiness_handler: None,
             shutdown_tx: Mutex::new(None),
         }
     }
@@ -403,6 +460,54 @@ impl HttpServer {
         self.prom_handler.get_or_insert(handler);
     }
 
+    pub fn set_backup_handler(&mut self, handler: BackupHandlerRef) {
+        debug_assert!(
+            self.backup_handler.is_none(),
+            "Backup handler can be set only once!"
+        );
+        self.backup_handler.get_or_insert(handler);
+    }
+
+    pub fn set_gc_handler(&mut self, handler: SstGcHandlerRef) {
+        debug_assert!(
+            self.gc_handler.is_none(),
+            "SST GC handler can be set only once!"
+        );
+        self.gc_handler.get_or_insert(handler);
+    }
+
+    pub fn set_compaction_handler(&mut self, handler: CompactionStatusHandlerRef) {
+        debug_assert!(
+            self.compaction_handler.is_none(),
+            "Compaction status handler can be set only once!"
+        );
+        self.compaction_handler.get_or_insert(handler);
+    }
+
+    pub fn set_table_stats_handler(&mut self, handler: TableStatsHandlerRef) {
+        debug_assert!(
+            self.table_stats_handler.is_none(),
+            "Table stats handler can be set only once!"
+        );
+        self.table_stats_handler.get_or_insert(handler);
+    }
+
+    pub fn set_verify_handler(&mut self, handler: StorageVerifyHandlerRef) {
+        debug_assert!(
+            self.verify_handler.is_none(),
+            "Storage verify handler can be set only once!"
+        );
+        self.verify_handler.get_or_insert(handler);
+    }
+
+    pub fn set_readiness_handler(&mut self, handler: ReadinessHandlerRef) {
+        debug_assert!(
+            self.readiness_handler.is_none(),
+            "Readiness handler can be set only once!"
+        );
+        self.readiness_handler.get_or_insert(handler);
+    }
+
     pub fn set_user_provider(&mut self, user_provider: UserProviderRef) {
         debug_assert!(
             self.user_provider.is_none(),
@@ -430,6 +535,7 @@ impl HttpServer {
             .route_sql(ApiState {
                 sql_handler: self.sql_handler.clone(),
                 script_handler: self.script_handler.clone(),
+                max_result_rows: self.options.max_result_rows,
             })
             .finish_api(&mut api)
             .layer(Extension(api));
@@ -437,7 +543,7 @@ impl HttpServer {
         let mut router = Router::new().nest(&format!("/{HTTP_API_VERSION}"), sql_router);
         router = router.nest(
             &format!("/{HTTP_API_VERSION}/admin"),
-            self.route_admin(self.grpc_handler.clone()),
+            self.route_admin(self.grpc_handler.clone(), self.sql_handler.clone()),
         );
 
         if let Some(opentsdb_handler) = self.opentsdb_handler.clone() {
@@ -477,6 +583,11 @@ impl HttpServer {
             routing::get(handler::health).post(handler::health),
         );
 
+        router = router.route(
+            "/health/ready",
+            routing::get(handler::ready).with_state(self.readiness_handler.clone()),
+        );
+
         router
             // middlewares
             .layer(
@@ -531,10 +642,65 @@ impl HttpServer {
             .with_state(opentsdb_handler)
     }
 
-    fn route_admin<S>(&self, grpc_handler: ServerGrpcQueryHandlerRef) -> Router<S> {
-        Router::new()
+    fn route_admin<S>(
+        &self,
+        grpc_handler: ServerGrpcQueryHandlerRef,
+        sql_handler: ServerSqlQueryHandlerRef,
+    ) -> Router<S> {
+        let router = Router::new()
             .route("/flush", routing::post(flush))
             .with_state(grpc_handler)
+            .merge(
+                Router::new()
+                    .route("/processlist", routing::get(processlist))
+                    .route("/kill", routing::post(kill_query))
+                    .with_state(sql_handler),
+            );
+
+        let router = match self.backup_handler.clone() {
+            Some(backup_handler) => router.merge(
+                Router::new()
+                    .route("/backup", routing::get(backup_status))
+                    .with_state(backup_handler),
+            ),
+            None => router,
+        };
+
+        let router = match self.gc_handler.clone() {
+            Some(gc_handler) => router.merge(
+                Router::new()
+                    .route("/gc", routing::post(collect_garbage))
+                    .with_state(gc_handler),
+            ),
+            None => router,
+        };
+
+        let router = match self.compaction_handler.clone() {
+            Some(compaction_handler) => router.merge(
+                Router::new()
+                    .route("/compaction", routing::get(compaction_status))
+                    .with_state(compaction_handler),
+            ),
+            None => router,
+        };
+
+        let router = match self.table_stats_handler.clone() {
+            Some(table_stats_handler) => router.merge(
+                Router::new()
+                    .route("/stats", routing::get(table_stats))
+                    .with_state(table_stats_handler),
+            ),
+            None => router,
+        };
+
+        match self.verify_handler.clone() {
+            Some(verify_handler) => router.merge(
+                Router::new()
+                    .route("/verify", routing::get(verify_storage))
+                    .with_state(verify_handler),
+            ),
+            None => router,
+        }
     }
 }
 
@@ -607,6 +773,7 @@ mod test {
     use datatypes::schema::{ColumnSchema, Schema};
     use datatypes::vectors::{StringVector, UInt32Vector};
     use query::parser::PromQuery;
+    use query::plan::LogicalPlan;
     use session::context::QueryContextRef;
     use tokio::sync::mpsc;
 
@@ -656,6 +823,23 @@ mod test {
             unimplemented!()
         }
 
+        async fn do_prepare(
+            &self,
+            _stmt: sql::statements::statement::Statement,
+            _query_ctx: QueryContextRef,
+        ) -> Result<Option<LogicalPlan>> {
+            unimplemented!()
+        }
+
+        async fn do_exec_plan(
+            &self,
+            _query: &str,
+            _plan: LogicalPlan,
+            _query_ctx: QueryContextRef,
+        ) -> Result<Output> {
+            unimplemented!()
+        }
+
         fn is_valid_schema(&self, _catalog: &str, _schema: &str) -> Result<bool> {
             Ok(true)
         }
@@ -691,7 +875,8 @@ mod test {
     fn test_http_options_default() {
         let default = HttpOptions::default();
         assert_eq!("127.0.0.1:4000".to_string(), default.addr);
-        assert_eq!(Duration::from_secs(30), default.timeout)
+        assert_eq!(Duration::from_secs(30), default.timeout);
+        assert_eq!(None, default.max_result_rows);
     }
 
     #[tokio::test]
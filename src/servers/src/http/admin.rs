@@ -13,19 +13,28 @@
 // limitations under the License.
 
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use api::v1::ddl_request::Expr;
 use api::v1::greptime_request::Request;
 use api::v1::{DdlRequest, FlushTableExpr};
 use axum::extract::{Query, RawBody, State};
 use axum::http::StatusCode as HttpStatusCode;
-use axum::Json;
-use session::context::QueryContext;
+use axum::{Extension, Json};
+use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
+use common_error::status_code::StatusCode;
+use session::context::{Channel, QueryContext, UserInfo};
 use snafu::OptionExt;
 
 use crate::error;
 use crate::error::Result;
+use crate::http::JsonResponse;
 use crate::query_handler::grpc::ServerGrpcQueryHandlerRef;
+use crate::query_handler::sql::ServerSqlQueryHandlerRef;
+use crate::query_handler::{
+    BackupHandlerRef, CompactionStatus, CompactionStatusHandlerRef, RegionBackupLag, RegionGcResult,
+    SstGcHandlerRef, StorageVerifyHandlerRef, StorageVerifyResult, TableStats, TableStatsHandlerRef,
+};
 
 #[axum_macros::debug_handler]
 pub async fn flush(
@@ -67,3 +76,116 @@ pub async fn flush(
     grpc_handler.do_query(request, QueryContext::arc()).await?;
     Ok((HttpStatusCode::OK, Json::from("hello, world".to_string())))
 }
+
+/// Lists the queries being executed, like `SHOW PROCESSLIST`. The whole statements are
+/// shown if `full` is true.
+#[axum_macros::debug_handler]
+pub async fn processlist(
+    State(sql_handler): State<ServerSqlQueryHandlerRef>,
+    Query(params): Query<HashMap<String, String>>,
+    Extension(user_info): Extension<UserInfo>,
+) -> Json<JsonResponse> {
+    let sql = if params.get("full").map(|v| v == "true").unwrap_or(false) {
+        "SHOW FULL PROCESSLIST"
+    } else {
+        "SHOW PROCESSLIST"
+    };
+    Json(execute_sql(sql_handler, sql, user_info).await)
+}
+
+/// Kills the query `id`, like `KILL QUERY`.
+#[axum_macros::debug_handler]
+pub async fn kill_query(
+    State(sql_handler): State<ServerSqlQueryHandlerRef>,
+    Query(params): Query<HashMap<String, String>>,
+    Extension(user_info): Extension<UserInfo>,
+) -> Json<JsonResponse> {
+    let Some(id) = params.get("id").and_then(|v| v.parse::<u64>().ok()) else {
+        return Json(JsonResponse::with_error(
+            "id is not present or not a valid query id".to_string(),
+            StatusCode::InvalidArguments,
+        ));
+    };
+    Json(execute_sql(sql_handler, &format!("KILL QUERY {id}"), user_info).await)
+}
+
+/// Reports the backup progress of regions.
+#[axum_macros::debug_handler]
+pub async fn backup_status(
+    State(backup_handler): State<BackupHandlerRef>,
+) -> Result<Json<Vec<RegionBackupLag>>> {
+    backup_handler.backup_status().map(Json)
+}
+
+/// Reports compactions waiting to be scheduled, including ones deferred to the maintenance
+/// window.
+#[axum_macros::debug_handler]
+pub async fn compaction_status(
+    State(compaction_handler): State<CompactionStatusHandlerRef>,
+) -> Result<Json<CompactionStatus>> {
+    compaction_handler.compaction_status().map(Json)
+}
+
+/// Reports the newest timestamp and approximate row count of tables and their regions,
+/// without scanning the data.
+#[axum_macros::debug_handler]
+pub async fn table_stats(
+    State(stats_handler): State<TableStatsHandlerRef>,
+) -> Result<Json<Vec<TableStats>>> {
+    stats_handler.table_stats().await.map(Json)
+}
+
+/// Collects orphan SST files of regions, files are only reported unless `dry_run=false`.
+#[axum_macros::debug_handler]
+pub async fn collect_garbage(
+    State(gc_handler): State<SstGcHandlerRef>,
+    Query(params): Query<HashMap<String, String>>,
+    RawBody(_): RawBody,
+) -> Result<Json<Vec<RegionGcResult>>> {
+    let dry_run = match params.get("dry_run") {
+        Some(dry_run) => dry_run.parse().map_err(|_| {
+            error::InvalidQuerySnafu {
+                reason: format!("invalid dry_run: {dry_run}"),
+            }
+            .build()
+        })?,
+        None => true,
+    };
+    gc_handler.collect_garbage(dry_run).await.map(Json)
+}
+
+/// Checks SST files of the regions of table `table_name` against their manifests in the
+/// object store, without modifying anything.
+#[axum_macros::debug_handler]
+pub async fn verify_storage(
+    State(verify_handler): State<StorageVerifyHandlerRef>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<StorageVerifyResult>> {
+    let catalog_name = params
+        .get("catalog_name")
+        .map(|v| v.as_str())
+        .unwrap_or(DEFAULT_CATALOG_NAME);
+    let schema_name = params
+        .get("schema_name")
+        .map(|v| v.as_str())
+        .unwrap_or(DEFAULT_SCHEMA_NAME);
+    let table_name = params
+        .get("table_name")
+        .context(error::InvalidQuerySnafu {
+            reason: "table_name is not present",
+        })?;
+    verify_handler
+        .verify_storage(catalog_name, schema_name, table_name)
+        .await
+        .map(Json)
+}
+
+async fn execute_sql(
+    sql_handler: ServerSqlQueryHandlerRef,
+    sql: &str,
+    user_info: UserInfo,
+) -> JsonResponse {
+    let query_ctx = Arc::new(QueryContext::new().with_channel(Channel::Http));
+    query_ctx.set_current_user(user_info);
+    JsonResponse::from_output(sql_handler.do_query(sql, query_ctx).await).await
+}
@@ -14,11 +14,12 @@
 
 use std::marker::PhantomData;
 
+use axum::body::BoxBody;
 use axum::http::{self, Request, StatusCode};
-use axum::response::Response;
+use axum::response::{IntoResponse, Json, Response};
+use common_error::prelude::ErrorExt;
 use common_telemetry::error;
 use futures::future::BoxFuture;
-use http_body::Body;
 use session::context::UserInfo;
 use snafu::{ensure, OptionExt, ResultExt};
 use tower_http::auth::AsyncAuthorizeRequest;
@@ -31,7 +32,7 @@ use crate::error::{
     self, InvalidAuthorizationHeaderSnafu, InvisibleASCIISnafu, NotFoundInfluxAuthSnafu, Result,
     UnsupportedAuthSchemeSnafu,
 };
-use crate::http::HTTP_API_PREFIX;
+use crate::http::{JsonResponse, HTTP_API_PREFIX};
 
 pub struct HttpAuth<RespBody> {
     user_provider: Option<UserProviderRef>,
@@ -56,13 +57,12 @@ impl<RespBody> Clone for HttpAuth<RespBody> {
     }
 }
 
-impl<B, RespBody> AsyncAuthorizeRequest<B> for HttpAuth<RespBody>
+impl<B> AsyncAuthorizeRequest<B> for HttpAuth<BoxBody>
 where
     B: Send + Sync + 'static,
-    RespBody: Body + Default,
 {
     type RequestBody = B;
-    type ResponseBody = RespBody;
+    type ResponseBody = BoxBody;
     type Future = BoxFuture<'static, std::result::Result<Request<B>, Response<Self::ResponseBody>>>;
 
     fn authorize(&mut self, mut request: Request<B>) -> Self::Future {
@@ -84,7 +84,7 @@ where
                 }
                 Err(e) => {
                     error!("authenticate failed: {}", e);
-                    return Err(unauthorized_resp());
+                    return Err(unauthorized_resp(e.to_string(), e.status_code()));
                 }
             }
 
@@ -92,7 +92,7 @@ where
                 Ok(_) => Ok(request),
                 Err(e) => {
                     error!("authorize failed: {}", e);
-                    Err(unauthorized_resp())
+                    Err(unauthorized_resp(e.to_string(), e.status_code()))
                 }
             }
         })
@@ -185,13 +185,13 @@ async fn authenticate<B: Send + Sync + 'static>(
         .await?)
 }
 
-fn unauthorized_resp<RespBody>() -> Response<RespBody>
-where
-    RespBody: Body + Default,
-{
-    let mut res = Response::new(RespBody::default());
-    *res.status_mut() = StatusCode::UNAUTHORIZED;
-    res
+/// Responds 401 with a json body carrying the error code and message.
+fn unauthorized_resp(error: String, code: common_error::status_code::StatusCode) -> Response {
+    (
+        StatusCode::UNAUTHORIZED,
+        Json(JsonResponse::with_error(error, code)),
+    )
+        .into_response()
 }
 
 #[derive(Debug)]
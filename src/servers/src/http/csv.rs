@@ -0,0 +1,191 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Encodes query outputs as CSV for the HTTP sql API.
+
+use std::fmt::Write;
+
+use axum::BoxError;
+use bytes::Bytes;
+use common_query::Output;
+use common_recordbatch::{RecordBatch, SendableRecordBatchStream};
+use datatypes::schema::SchemaRef;
+use futures::future::ready;
+use futures::stream::{self, BoxStream};
+use futures::StreamExt;
+use serde_json::Value;
+
+pub const CSV_CONTENT_TYPE: &str = "text/csv; charset=utf-8";
+
+/// Encodes `outputs` into a stream of CSV chunks, one chunk per record batch.
+///
+/// Each result set starts with a header line of column names, and result sets of
+/// different statements are separated by an empty line. At most `max_rows` rows
+/// of each result set are encoded, the remaining record batches are not polled.
+pub(crate) fn encode_outputs(
+    outputs: Vec<Output>,
+    max_rows: Option<usize>,
+) -> BoxStream<'static, Result<Bytes, BoxError>> {
+    stream::iter(outputs.into_iter().enumerate())
+        .flat_map(move |(i, output)| {
+            let separator = (i > 0).then(|| Ok(Bytes::from_static(b"\n")));
+            stream::iter(separator).chain(encode_output(output, max_rows))
+        })
+        .boxed()
+}
+
+fn encode_output(
+    output: Output,
+    max_rows: Option<usize>,
+) -> BoxStream<'static, Result<Bytes, BoxError>> {
+    match output {
+        Output::AffectedRows(rows) => {
+            stream::once(ready(Ok(Bytes::from(format!("affected_rows\n{rows}\n"))))).boxed()
+        }
+        Output::RecordBatches(batches) => encode_stream(batches.as_stream(), max_rows),
+        Output::Stream(stream) => encode_stream(stream, max_rows),
+    }
+}
+
+fn encode_stream(
+    stream: SendableRecordBatchStream,
+    max_rows: Option<usize>,
+) -> BoxStream<'static, Result<Bytes, BoxError>> {
+    let header = encode_header(&stream.schema());
+    let rows = stream.scan(max_rows.unwrap_or(usize::MAX), |remaining, batch| {
+        if *remaining == 0 {
+            return ready(None);
+        }
+        let chunk = batch.map_err(BoxError::from).and_then(|batch| {
+            let num_rows = batch.num_rows().min(*remaining);
+            *remaining -= num_rows;
+            encode_batch(&batch, num_rows)
+        });
+        ready(Some(chunk))
+    });
+
+    stream::once(ready(Ok(header))).chain(rows).boxed()
+}
+
+fn encode_header(schema: &SchemaRef) -> Bytes {
+    let mut line = String::new();
+    for (i, column_schema) in schema.column_schemas().iter().enumerate() {
+        if i > 0 {
+            line.push(',');
+        }
+        push_field(&mut line, &column_schema.name);
+    }
+    line.push('\n');
+    Bytes::from(line)
+}
+
+fn encode_batch(batch: &RecordBatch, num_rows: usize) -> Result<Bytes, BoxError> {
+    let mut buf = String::new();
+    for row in batch.rows().take(num_rows) {
+        for (i, value) in row.into_iter().enumerate() {
+            if i > 0 {
+                buf.push(',');
+            }
+            match Value::try_from(value).map_err(|e| e.to_string())? {
+                Value::Null => {}
+                Value::String(s) => push_field(&mut buf, &s),
+                // Numbers and booleans never need quoting, nested values are
+                // written as json.
+                value => push_field(&mut buf, &value.to_string()),
+            }
+        }
+        buf.push('\n');
+    }
+    Ok(Bytes::from(buf))
+}
+
+/// Appends `field` to `buf`, quoting it as RFC 4180 requires.
+fn push_field(buf: &mut String, field: &str) {
+    if field.contains([',', '"', '\n', '\r']) {
+        let _ = write!(buf, "\"{}\"", field.replace('"', "\"\""));
+    } else {
+        buf.push_str(field);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use common_recordbatch::RecordBatches;
+    use datatypes::prelude::*;
+    use datatypes::schema::{ColumnSchema, Schema};
+    use datatypes::vectors::{StringVector, UInt32Vector};
+
+    use super::*;
+
+    fn test_batches() -> RecordBatches {
+        let schema = Arc::new(Schema::new(vec![
+            ColumnSchema::new("numbers", ConcreteDataType::uint32_datatype(), false),
+            ColumnSchema::new("strings", ConcreteDataType::string_datatype(), true),
+        ]));
+        let batch1 = RecordBatch::new(
+            schema.clone(),
+            vec![
+                Arc::new(UInt32Vector::from_slice(vec![1, 2])) as VectorRef,
+                Arc::new(StringVector::from(vec![None, Some("a,b")])),
+            ],
+        )
+        .unwrap();
+        let batch2 = RecordBatch::new(
+            schema.clone(),
+            vec![
+                Arc::new(UInt32Vector::from_slice(vec![3])) as VectorRef,
+                Arc::new(StringVector::from(vec![Some("say \"hi\"")])),
+            ],
+        )
+        .unwrap();
+        RecordBatches::try_new(schema, vec![batch1, batch2]).unwrap()
+    }
+
+    async fn encode(outputs: Vec<Output>, max_rows: Option<usize>) -> String {
+        let chunks: Vec<_> = encode_outputs(outputs, max_rows).collect().await;
+        chunks
+            .into_iter()
+            .map(|chunk| String::from_utf8(chunk.unwrap().to_vec()).unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_encode_outputs() {
+        let outputs = vec![
+            Output::AffectedRows(2),
+            Output::RecordBatches(test_batches()),
+        ];
+        assert_eq!(
+            "affected_rows\n2\n\nnumbers,strings\n1,\n2,\"a,b\"\n3,\"say \"\"hi\"\"\"\n",
+            encode(outputs, None).await
+        );
+    }
+
+    #[tokio::test]
+    async fn test_encode_outputs_with_limit() {
+        let outputs = vec![Output::Stream(test_batches().as_stream())];
+        assert_eq!(
+            "numbers,strings\n1,\n2,\"a,b\"\n",
+            encode(outputs, Some(2)).await
+        );
+
+        let outputs = vec![Output::RecordBatches(test_batches())];
+        assert_eq!("numbers,strings\n1,\n", encode(outputs, Some(1)).await);
+
+        let outputs = vec![Output::RecordBatches(test_batches())];
+        assert_eq!("numbers,strings\n", encode(outputs, Some(0)).await);
+    }
+}
@@ -15,9 +15,17 @@
 use std::collections::HashMap;
 use std::time::Instant;
 
+use aide::gen::GenContext;
+use aide::openapi::{Operation, Response as OpenApiResponse};
 use aide::transform::TransformOperation;
-use axum::extract::{Json, Query, State};
+use aide::{OperationInput, OperationOutput};
+use async_trait::async_trait;
+use axum::body::StreamBody;
+use axum::extract::{FromRequestParts, Json, Query, State};
+use axum::http::{header, request, HeaderValue, StatusCode as HttpStatusCode};
+use axum::response::{IntoResponse, Response};
 use axum::{Extension, Form};
+use common_error::prelude::ErrorExt;
 use common_error::status_code::StatusCode;
 use common_telemetry::metric;
 use query::parser::PromQuery;
@@ -25,44 +33,219 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use session::context::UserInfo;
 
+use crate::http::csv::{self, CSV_CONTENT_TYPE};
 use crate::http::{ApiState, JsonResponse};
+use crate::query_handler::ReadinessHandlerRef;
 
 #[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
 pub struct SqlQuery {
     pub db: Option<String>,
     pub sql: Option<String>,
+    /// Output format of query results, "json" or "csv". Takes precedence over
+    /// the `Accept` header.
+    pub format: Option<String>,
+}
+
+/// Format of the results returned by the sql handler.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+impl OutputFormat {
+    fn parse(format: &str) -> Option<Self> {
+        match format.to_lowercase().as_str() {
+            "json" => Some(OutputFormat::Json),
+            "csv" => Some(OutputFormat::Csv),
+            _ => None,
+        }
+    }
+
+    fn from_accept(accept: &str) -> Option<Self> {
+        accept.split(',').find_map(|media_range| {
+            let mime = media_range.split(';').next().unwrap_or_default().trim();
+            match mime.to_lowercase().as_str() {
+                "application/json" => Some(OutputFormat::Json),
+                "text/csv" => Some(OutputFormat::Csv),
+                _ => None,
+            }
+        })
+    }
+}
+
+/// Output format requested by the `Accept` header, if any.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct AcceptFormat(pub Option<OutputFormat>);
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for AcceptFormat {
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(
+        parts: &mut request::Parts,
+        _state: &S,
+    ) -> std::result::Result<Self, Self::Rejection> {
+        let format = parts
+            .headers
+            .get(header::ACCEPT)
+            .and_then(|accept| accept.to_str().ok())
+            .and_then(OutputFormat::from_accept);
+        Ok(AcceptFormat(format))
+    }
+}
+
+impl OperationInput for AcceptFormat {}
+
+/// Response of the sql handler.
+#[derive(Debug)]
+pub enum SqlResponse {
+    /// Json response, errors are reported by the code in the body.
+    Json(JsonResponse),
+    /// Streaming csv response.
+    Csv(Response),
+    /// Error of a non-json request. It's still rendered as json, with the HTTP
+    /// status set from the error code as clients can't tell it from results otherwise.
+    Error(JsonResponse),
+}
+
+impl SqlResponse {
+    fn error(format: OutputFormat, resp: JsonResponse) -> Self {
+        match format {
+            OutputFormat::Json => SqlResponse::Json(resp),
+            OutputFormat::Csv => SqlResponse::Error(resp),
+        }
+    }
+}
+
+impl IntoResponse for SqlResponse {
+    fn into_response(self) -> Response {
+        match self {
+            SqlResponse::Json(resp) => Json(resp).into_response(),
+            SqlResponse::Csv(resp) => resp,
+            SqlResponse::Error(resp) => (http_status_of(resp.code()), Json(resp)).into_response(),
+        }
+    }
+}
+
+impl OperationOutput for SqlResponse {
+    type Inner = JsonResponse;
+
+    fn operation_response(
+        ctx: &mut GenContext,
+        operation: &mut Operation,
+    ) -> Option<OpenApiResponse> {
+        Json::<JsonResponse>::operation_response(ctx, operation)
+    }
+
+    fn inferred_responses(
+        ctx: &mut GenContext,
+        operation: &mut Operation,
+    ) -> Vec<(Option<u16>, OpenApiResponse)> {
+        Json::<JsonResponse>::inferred_responses(ctx, operation)
+    }
+}
+
+/// Maps the error code in a [JsonResponse] to a HTTP status.
+pub(crate) fn http_status_of(code: u32) -> HttpStatusCode {
+    match StatusCode::from_u32(code) {
+        Some(StatusCode::Success) => HttpStatusCode::OK,
+        Some(
+            StatusCode::InvalidArguments
+            | StatusCode::InvalidSyntax
+            | StatusCode::PlanQuery
+            | StatusCode::Unsupported
+            | StatusCode::TableAlreadyExists
+            | StatusCode::TableNotFound
+            | StatusCode::TableColumnNotFound
+            | StatusCode::TableColumnExists
+            | StatusCode::DatabaseNotFound,
+        ) => HttpStatusCode::BAD_REQUEST,
+        Some(
+            StatusCode::UserNotFound
+            | StatusCode::UnsupportedPasswordType
+            | StatusCode::UserPasswordMismatch
+            | StatusCode::AuthHeaderNotFound
+            | StatusCode::InvalidAuthHeader,
+        ) => HttpStatusCode::UNAUTHORIZED,
+        Some(StatusCode::AccessDenied) => HttpStatusCode::FORBIDDEN,
+        _ => HttpStatusCode::INTERNAL_SERVER_ERROR,
+    }
 }
 
 /// Handler to execute sql
+///
+/// Results are returned as json by default, or streamed as csv if requested by
+/// the `format` parameter or the `Accept` header.
 #[axum_macros::debug_handler]
 pub async fn sql(
     State(state): State<ApiState>,
     Query(query_params): Query<SqlQuery>,
-    // TODO(fys): pass _user_info into query context
-    _user_info: Extension<UserInfo>,
+    Extension(user_info): Extension<UserInfo>,
+    AcceptFormat(accept_format): AcceptFormat,
     Form(form_params): Form<SqlQuery>,
-) -> Json<JsonResponse> {
+) -> SqlResponse {
     let sql_handler = &state.sql_handler;
 
     let start = Instant::now();
     let sql = query_params.sql.or(form_params.sql);
     let db = query_params.db.or(form_params.db);
-
-    let resp = if let Some(sql) = &sql {
-        match super::query_context_from_db(sql_handler.clone(), db) {
-            Ok(query_ctx) => {
-                JsonResponse::from_output(sql_handler.do_query(sql, query_ctx).await).await
+    let format = match query_params.format.or(form_params.format) {
+        Some(format) => match OutputFormat::parse(&format) {
+            Some(format) => format,
+            None => {
+                return SqlResponse::Error(JsonResponse::with_error(
+                    format!("Unsupported output format: {format}"),
+                    StatusCode::InvalidArguments,
+                ))
             }
-            Err(resp) => resp,
-        }
-    } else {
-        JsonResponse::with_error(
+        },
+        None => accept_format.unwrap_or_default(),
+    };
+
+    let Some(sql) = sql else {
+        let resp = JsonResponse::with_error(
             "sql parameter is required.".to_string(),
             StatusCode::InvalidArguments,
-        )
+        );
+        return SqlResponse::error(format, resp);
     };
 
-    Json(resp.with_execution_time(start.elapsed().as_millis()))
+    let query_ctx = match super::query_context_from_db(sql_handler.clone(), db) {
+        Ok(query_ctx) => query_ctx,
+        Err(resp) => return SqlResponse::error(format, resp),
+    };
+    query_ctx.set_current_user(user_info);
+    let outputs = sql_handler.do_query(&sql, query_ctx).await;
+
+    match format {
+        OutputFormat::Json => {
+            let resp = JsonResponse::from_output_with_limit(outputs, state.max_result_rows).await;
+            SqlResponse::Json(resp.with_execution_time(start.elapsed().as_millis()))
+        }
+        OutputFormat::Csv => {
+            let outputs = match outputs
+                .into_iter()
+                .collect::<crate::error::Result<Vec<_>>>()
+            {
+                Ok(outputs) => outputs,
+                Err(e) => {
+                    return SqlResponse::Error(JsonResponse::with_error(
+                        format!("Query engine output error: {e}"),
+                        e.status_code(),
+                    ))
+                }
+            };
+            let body = StreamBody::new(csv::encode_outputs(outputs, state.max_result_rows));
+            let mut resp = body.into_response();
+            let _ = resp.headers_mut().insert(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static(CSV_CONTENT_TYPE),
+            );
+            SqlResponse::Csv(resp)
+        }
+    }
 }
 
 #[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
@@ -71,6 +254,8 @@ pub struct PromqlQuery {
     pub start: String,
     pub end: String,
     pub step: String,
+    /// Lookback delta in seconds or like `5m`, 5m by default.
+    pub lookback: Option<String>,
     pub db: Option<String>,
 }
 
@@ -81,6 +266,7 @@ impl From<PromqlQuery> for PromQuery {
             start: query.start,
             end: query.end,
             step: query.step,
+            lookback: query.lookback,
         }
     }
 }
@@ -90,8 +276,7 @@ impl From<PromqlQuery> for PromQuery {
 pub async fn promql(
     State(state): State<ApiState>,
     Query(params): Query<PromqlQuery>,
-    // TODO(fys): pass _user_info into query context
-    _user_info: Extension<UserInfo>,
+    Extension(user_info): Extension<UserInfo>,
 ) -> Json<JsonResponse> {
     let sql_handler = &state.sql_handler;
     let exec_start = Instant::now();
@@ -99,6 +284,7 @@ pub async fn promql(
     let prom_query = params.into();
     let resp = match super::query_context_from_db(sql_handler.clone(), db) {
         Ok(query_ctx) => {
+            query_ctx.set_current_user(user_info);
             JsonResponse::from_output(sql_handler.do_promql_query(&prom_query, query_ctx).await)
                 .await
         }
@@ -135,3 +321,25 @@ pub struct HealthResponse {}
 pub async fn health(Query(_params): Query<HealthQuery>) -> Json<HealthResponse> {
     Json(HealthResponse {})
 }
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct ReadyResponse {
+    pub ready: bool,
+}
+
+/// Handler to export readiness check
+///
+/// Returns "503 Service Unavailable" until the instance has finished starting up, always
+/// ready if the server has no readiness handler.
+#[axum_macros::debug_handler]
+pub async fn ready(
+    State(handler): State<Option<ReadinessHandlerRef>>,
+) -> (HttpStatusCode, Json<ReadyResponse>) {
+    let ready = handler.map(|h| h.is_ready()).unwrap_or(true);
+    let status = if ready {
+        HttpStatusCode::OK
+    } else {
+        HttpStatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(ReadyResponse { ready }))
+}
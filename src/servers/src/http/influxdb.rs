@@ -22,8 +22,10 @@ use common_catalog::consts::DEFAULT_SCHEMA_NAME;
 use common_grpc::writer::Precision;
 use session::context::QueryContext;
 
-use crate::error::{Result, TimePrecisionSnafu};
-use crate::influxdb::InfluxdbRequest;
+use crate::error::{
+    InfluxdbPartialWriteSnafu, InvalidInfluxdbLinesSnafu, Result, TimePrecisionSnafu,
+};
+use crate::influxdb::{split_malformed_lines, InfluxdbRequest, MalformedLine};
 use crate::parse_catalog_and_schema_from_client_database_name;
 use crate::query_handler::InfluxdbLineProtocolHandlerRef;
 
@@ -55,16 +57,44 @@ pub async fn influxdb_write(
         .get("precision")
         .map(|val| parse_time_precision(val))
         .transpose()?;
-    let request = InfluxdbRequest { precision, lines };
+    // Whether to write valid lines if there are malformed lines in the request.
+    let partial_write = params
+        .get("partial_write")
+        .map(|val| val == "true")
+        .unwrap_or(false);
 
-    handler.exec(&request, ctx).await?;
-    Ok((StatusCode::NO_CONTENT, ()))
+    let (lines, malformed_lines) = split_malformed_lines(&lines);
+    if malformed_lines.is_empty() {
+        let request = InfluxdbRequest { precision, lines };
+        handler.exec(&request, ctx).await?;
+        return Ok((StatusCode::NO_CONTENT, ()));
+    }
+
+    let errors = format_malformed_lines(&malformed_lines);
+    if !partial_write {
+        return InvalidInfluxdbLinesSnafu { errors }.fail();
+    }
+
+    let written = lines.lines().count();
+    if written > 0 {
+        let request = InfluxdbRequest { precision, lines };
+        handler.exec(&request, ctx).await?;
+    }
+    InfluxdbPartialWriteSnafu { written, errors }.fail()
+}
+
+fn format_malformed_lines(malformed_lines: &[MalformedLine]) -> String {
+    malformed_lines
+        .iter()
+        .map(|line| format!("line {}: {}", line.line_no, line.error))
+        .collect::<Vec<_>>()
+        .join("; ")
 }
 
 fn parse_time_precision(value: &str) -> Result<Precision> {
     match value {
-        "n" => Ok(Precision::Nanosecond),
-        "u" => Ok(Precision::Microsecond),
+        "n" | "ns" => Ok(Precision::Nanosecond),
+        "u" | "us" => Ok(Precision::Microsecond),
         "ms" => Ok(Precision::Millisecond),
         "s" => Ok(Precision::Second),
         "m" => Ok(Precision::Minute),
@@ -85,7 +115,9 @@ mod tests {
     #[test]
     fn test_parse_time_precision() {
         assert_eq!(Precision::Nanosecond, parse_time_precision("n").unwrap());
+        assert_eq!(Precision::Nanosecond, parse_time_precision("ns").unwrap());
         assert_eq!(Precision::Microsecond, parse_time_precision("u").unwrap());
+        assert_eq!(Precision::Microsecond, parse_time_precision("us").unwrap());
         assert_eq!(Precision::Millisecond, parse_time_precision("ms").unwrap());
         assert_eq!(Precision::Second, parse_time_precision("s").unwrap());
         assert_eq!(Precision::Minute, parse_time_precision("m").unwrap());
@@ -22,7 +22,7 @@ use common_catalog::consts::DEFAULT_SCHEMA_NAME;
 use hyper::Body;
 use serde::{Deserialize, Serialize};
 use session::context::QueryContext;
-use snafu::ResultExt;
+use snafu::{OptionExt, ResultExt};
 
 use crate::error::{self, Error, Result};
 use crate::opentsdb::codec::DataPoint;
@@ -53,9 +53,15 @@ pub struct DataPointRequest {
     tags: HashMap<String, String>,
 }
 
-impl From<DataPointRequest> for DataPoint {
-    fn from(request: DataPointRequest) -> Self {
-        let ts_millis = DataPoint::timestamp_to_millis(request.timestamp);
+impl TryFrom<DataPointRequest> for DataPoint {
+    type Error = Error;
+
+    fn try_from(request: DataPointRequest) -> Result<Self> {
+        let ts_millis = DataPoint::timestamp_to_millis(request.timestamp).with_context(|| {
+            error::InvalidQuerySnafu {
+                reason: format!("invalid timestamp: {}", request.timestamp),
+            }
+        })?;
 
         let tags = request
             .tags
@@ -63,7 +69,7 @@ impl From<DataPointRequest> for DataPoint {
             .map(|(k, v)| (k, v))
             .collect::<Vec<(String, String)>>();
 
-        DataPoint::new(request.metric, ts_millis, request.value, tags)
+        Ok(DataPoint::new(request.metric, ts_millis, request.value, tags))
     }
 }
 
@@ -96,14 +102,16 @@ pub async fn put(
     let data_points = parse_data_points(body).await?;
 
     let response = if !summary && !details {
-        for data_point in data_points.into_iter() {
-            if let Err(e) = opentsdb_handler.exec(&data_point.into(), ctx.clone()).await {
-                // Not debugging purpose, failed fast.
-                return error::InternalSnafu {
-                    err_msg: e.to_string(),
-                }
-                .fail();
+        let data_points = data_points
+            .into_iter()
+            .map(DataPoint::try_from)
+            .collect::<Result<Vec<_>>>()?;
+        if let Err(e) = opentsdb_handler.exec_batch(&data_points, ctx).await {
+            // Not debugging purpose, failed fast.
+            return error::InternalSnafu {
+                err_msg: e.to_string(),
             }
+            .fail();
         }
         (HttpStatusCode::NO_CONTENT, Json(OpentsdbPutResponse::Empty))
     } else {
@@ -117,10 +125,12 @@ pub async fn put(
             },
         };
 
+        // Writes data points one by one to report errors of each.
         for data_point in data_points.into_iter() {
-            let result = opentsdb_handler
-                .exec(&data_point.clone().into(), ctx.clone())
-                .await;
+            let result = match DataPoint::try_from(data_point.clone()) {
+                Ok(converted) => opentsdb_handler.exec(&converted, ctx.clone()).await,
+                Err(e) => Err(e),
+            };
             match result {
                 Ok(()) => response.on_success(),
                 Err(e) => {
@@ -189,7 +199,7 @@ mod test {
             value: 1.0,
             tags: HashMap::from([("foo".to_string(), "a".to_string())]),
         };
-        let data_point: DataPoint = request.into();
+        let data_point: DataPoint = request.try_into().unwrap();
         assert_eq!(data_point.metric(), "hello");
         assert_eq!(data_point.ts_millis(), 1234000);
         assert_eq!(data_point.value(), 1.0);
@@ -197,6 +207,18 @@ mod test {
             data_point.tags(),
             &vec![("foo".to_string(), "a".to_string())]
         );
+
+        let request = DataPointRequest {
+            metric: "hello".to_string(),
+            timestamp: -1,
+            value: 1.0,
+            tags: HashMap::new(),
+        };
+        let result: Result<DataPoint> = request.try_into();
+        assert_eq!(
+            "Invalid query: invalid timestamp: -1",
+            result.unwrap_err().to_string()
+        );
     }
 
     #[tokio::test]
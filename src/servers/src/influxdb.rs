@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod server;
+
 use std::collections::HashMap;
 
 use api::v1::InsertRequest as GrpcInsertRequest;
@@ -32,6 +34,41 @@ pub struct InfluxdbRequest {
 
 type TableName = String;
 
+/// A line that can't be parsed.
+#[derive(Debug, PartialEq, Eq)]
+pub struct MalformedLine {
+    /// Line number, starts from 1.
+    pub line_no: usize,
+    pub error: String,
+}
+
+/// Splits `lines` into valid lines and malformed lines.
+///
+/// Lines are parsed one by one so malformed lines could be reported with their line
+/// numbers. Valid lines are joined by `\n`.
+pub fn split_malformed_lines(lines: &str) -> (String, Vec<MalformedLine>) {
+    let mut valid_lines = String::with_capacity(lines.len());
+    let mut malformed_lines = Vec::new();
+
+    for (idx, line) in lines.lines().enumerate() {
+        match parse_lines(line).collect::<influxdb_line_protocol::Result<Vec<_>>>() {
+            Ok(parsed) => {
+                // Skips empty lines and comments.
+                if !parsed.is_empty() {
+                    valid_lines.push_str(line);
+                    valid_lines.push('\n');
+                }
+            }
+            Err(e) => malformed_lines.push(MalformedLine {
+                line_no: idx + 1,
+                error: e.to_string(),
+            }),
+        }
+    }
+
+    (valid_lines, malformed_lines)
+}
+
 impl TryFrom<&InfluxdbRequest> for Vec<GrpcInsertRequest> {
     type Error = Error;
 
@@ -127,6 +164,32 @@ mod tests {
     use super::*;
     use crate::influxdb::InfluxdbRequest;
 
+    #[test]
+    fn test_split_malformed_lines() {
+        let lines = r"
+monitor1,host=host1 cpu=66.6 1663840496100023100
+# comment
+monitor1,   host=host2 cpu=66.6 1663840496100023100
+monitor2,host=host3 cpu=66.5 1663840496100023102
+monitor2,host=host4
+";
+        let (valid_lines, malformed_lines) = split_malformed_lines(lines);
+        assert_eq!(
+            "monitor1,host=host1 cpu=66.6 1663840496100023100\n\
+             monitor2,host=host3 cpu=66.5 1663840496100023102\n",
+            valid_lines
+        );
+        let line_nos = malformed_lines
+            .iter()
+            .map(|line| line.line_no)
+            .collect::<Vec<_>>();
+        assert_eq!(vec![4, 6], line_nos);
+
+        let (valid_lines, malformed_lines) = split_malformed_lines("");
+        assert!(valid_lines.is_empty());
+        assert!(malformed_lines.is_empty());
+    }
+
     #[test]
     fn test_convert_influxdb_lines() {
         let lines = r"
@@ -0,0 +1,124 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::net::SocketAddr;
+
+use async_trait::async_trait;
+use axum::body::BoxBody;
+use axum::{routing, Router};
+use common_telemetry::info;
+use futures::FutureExt;
+use snafu::{ensure, ResultExt};
+use tokio::sync::oneshot::Sender;
+use tokio::sync::{oneshot, Mutex};
+use tower::ServiceBuilder;
+use tower_http::auth::AsyncRequireAuthorizationLayer;
+use tower_http::trace::TraceLayer;
+
+use crate::auth::UserProviderRef;
+use crate::error::{AlreadyStartedSnafu, Result, StartHttpSnafu};
+use crate::http::authorize::HttpAuth;
+use crate::http::influxdb::{influxdb_health, influxdb_ping, influxdb_write};
+use crate::http::HTTP_API_VERSION;
+use crate::query_handler::InfluxdbLineProtocolHandlerRef;
+use crate::server::Server;
+
+pub const INFLUXDB_SERVER: &str = "INFLUXDB_SERVER";
+
+/// An HTTP server only serving the InfluxDB line protocol API, i.e. `/v1/influxdb/write`,
+/// for nodes without the full HTTP API like datanodes.
+pub struct InfluxdbServer {
+    handler: InfluxdbLineProtocolHandlerRef,
+    shutdown_tx: Mutex<Option<Sender<()>>>,
+    user_provider: Option<UserProviderRef>,
+}
+
+impl InfluxdbServer {
+    pub fn create_server(handler: InfluxdbLineProtocolHandlerRef) -> Box<Self> {
+        Box::new(InfluxdbServer {
+            handler,
+            shutdown_tx: Mutex::new(None),
+            user_provider: None,
+        })
+    }
+
+    pub fn set_user_provider(&mut self, user_provider: UserProviderRef) {
+        debug_assert!(self.user_provider.is_none());
+        self.user_provider = Some(user_provider);
+    }
+
+    pub fn make_app(&self) -> Router {
+        let router = Router::new()
+            .route("/write", routing::post(influxdb_write))
+            .route("/ping", routing::get(influxdb_ping))
+            .route("/health", routing::get(influxdb_health))
+            .with_state(self.handler.clone());
+
+        Router::new()
+            .nest(&format!("/{HTTP_API_VERSION}/influxdb"), router)
+            // middlewares
+            .layer(
+                ServiceBuilder::new()
+                    .layer(TraceLayer::new_for_http())
+                    // custom layer
+                    .layer(AsyncRequireAuthorizationLayer::new(
+                        HttpAuth::<BoxBody>::new(self.user_provider.clone()),
+                    )),
+            )
+    }
+}
+
+#[async_trait]
+impl Server for InfluxdbServer {
+    async fn shutdown(&self) -> Result<()> {
+        let mut shutdown_tx = self.shutdown_tx.lock().await;
+        if let Some(tx) = shutdown_tx.take() {
+            if tx.send(()).is_err() {
+                info!("Receiver dropped, the InfluxDB server has already existed");
+            }
+        }
+        info!("Shutdown InfluxDB server");
+
+        Ok(())
+    }
+
+    async fn start(&self, listening: SocketAddr) -> Result<SocketAddr> {
+        let (tx, rx) = oneshot::channel();
+        let server = {
+            let mut shutdown_tx = self.shutdown_tx.lock().await;
+            ensure!(
+                shutdown_tx.is_none(),
+                AlreadyStartedSnafu { server: "InfluxDB" }
+            );
+
+            let app = self.make_app();
+            let server = axum::Server::bind(&listening).serve(app.into_make_service());
+
+            *shutdown_tx = Some(tx);
+
+            server
+        };
+        let listening = server.local_addr();
+        info!("InfluxDB server is bound to {}", listening);
+
+        let graceful = server.with_graceful_shutdown(rx.map(drop));
+        graceful.await.context(StartHttpSnafu)?;
+
+        Ok(listening)
+    }
+
+    fn name(&self) -> &str {
+        INFLUXDB_SERVER
+    }
+}
@@ -27,6 +27,7 @@ use once_cell::sync::Lazy;
 use regex::bytes::RegexSet;
 use regex::Regex;
 use session::context::QueryContextRef;
+use session::variables::{self, TIME_ZONE};
 
 // TODO(LFC): Include GreptimeDB's version and git commit tag etc.
 const MYSQL_VERSION: &str = "8.0.26";
@@ -40,6 +41,17 @@ static SHOW_COLLATION_PATTERN: Lazy<Regex> =
     Lazy::new(|| Regex::new("(?i)^(show collation where(.*))").unwrap());
 static SHOW_VARIABLES_PATTERN: Lazy<Regex> =
     Lazy::new(|| Regex::new("(?i)^(SHOW VARIABLES(.*))").unwrap());
+static SHOW_VARIABLES_LIKE_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)^SHOW\s+(?:SESSION\s+)?VARIABLES\s+LIKE\s+'([^']*)'").unwrap());
+// Captures the variable name of "SET [SESSION] [@@session.]var = value".
+static SET_VARIABLE_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)^SET\s+(?:SESSION\s+|LOCAL\s+)?((?:@@)?(?:session\.|local\.)?\w+)\s*(?:=|TO\b)",
+    )
+    .unwrap()
+});
+static SET_TIME_ZONE_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)^SET\s+TIME\s+ZONE\b").unwrap());
 
 static SELECT_VERSION_PATTERN: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"(?i)^(SELECT VERSION\(\s*\))").unwrap());
@@ -155,20 +167,37 @@ fn select_function(name: &str, value: &str) -> RecordBatches {
 // | Variable_name | Value |
 // | xx            | yy    |
 fn show_variables(name: &str, value: &str) -> RecordBatches {
+    show_variables_rows(vec![(name.to_string(), value.to_string())])
+}
+
+fn show_variables_rows(rows: Vec<(String, String)>) -> RecordBatches {
     let schema = Arc::new(Schema::new(vec![
         ColumnSchema::new("Variable_name", ConcreteDataType::string_datatype(), true),
         ColumnSchema::new("Value", ConcreteDataType::string_datatype(), true),
     ]));
+    let (names, values): (Vec<_>, Vec<_>) = rows.into_iter().unzip();
     let columns = vec![
-        Arc::new(StringVector::from(vec![name])) as _,
-        Arc::new(StringVector::from(vec![value])) as _,
+        Arc::new(StringVector::from(names)) as _,
+        Arc::new(StringVector::from(values)) as _,
     ];
     RecordBatches::try_from_columns(schema, columns)
         // unwrap is safe because the schema and data are definitely able to form a recordbatch, they are all string type
         .unwrap()
 }
 
-fn select_variable(query: &str) -> Option<Output> {
+/// Returns the session value of variable `name` if it's a session variable that has been set,
+/// otherwise the faked server default.
+fn variable_value(name: &str, query_ctx: &QueryContextRef) -> String {
+    let session_vars = query_ctx.variables();
+    let session_value = match variables::normalize_name(name).as_str() {
+        // an unset time zone falls back to the server default
+        TIME_ZONE if session_vars.time_zone.is_none() => None,
+        _ => session_vars.get(name),
+    };
+    session_value.unwrap_or_else(|| VAR_VALUES.get(name).unwrap_or(&"0").to_string())
+}
+
+fn select_variable(query: &str, query_ctx: &QueryContextRef) -> Option<Output> {
     let mut fields = vec![];
     let mut values = vec![];
 
@@ -194,8 +223,8 @@ fn select_variable(query: &str) -> Option<Output> {
         match var_as.len() {
             1 => {
                 // @@aa
-                let value = VAR_VALUES.get(var_as[0]).unwrap_or(&"0");
-                values.push(Arc::new(StringVector::from(vec![*value])) as _);
+                let value = variable_value(var_as[0], query_ctx);
+                values.push(Arc::new(StringVector::from(vec![value])) as _);
 
                 // field is '@@aa'
                 fields.push(ColumnSchema::new(
@@ -207,8 +236,8 @@ fn select_variable(query: &str) -> Option<Output> {
             2 => {
                 // @@bb as cc:
                 // var is 'bb'.
-                let value = VAR_VALUES.get(var_as[0]).unwrap_or(&"0");
-                values.push(Arc::new(StringVector::from(vec![*value])) as _);
+                let value = variable_value(var_as[0], query_ctx);
+                values.push(Arc::new(StringVector::from(vec![value])) as _);
 
                 // field is 'cc'.
                 fields.push(ColumnSchema::new(
@@ -227,32 +256,72 @@ fn select_variable(query: &str) -> Option<Output> {
     Some(Output::RecordBatches(batches))
 }
 
-fn check_select_variable(query: &str) -> Option<Output> {
+fn check_select_variable(query: &str, query_ctx: &QueryContextRef) -> Option<Output> {
     if vec![&SELECT_VAR_PATTERN, &MYSQL_CONN_JAVA_PATTERN]
         .iter()
         .any(|r| r.is_match(query))
     {
-        select_variable(query)
+        select_variable(query, query_ctx)
     } else {
         None
     }
 }
 
-fn check_show_variables(query: &str) -> Option<Output> {
+/// Session variables with their current values, optionally filtered by a `LIKE` pattern.
+fn session_variables(pattern: Option<&str>, query_ctx: &QueryContextRef) -> Vec<(String, String)> {
+    let matcher = pattern.map(|p| {
+        let p = regex::escape(p).replace('%', ".*").replace('_', ".");
+        // unwrap is safe because all the special characters are escaped
+        Regex::new(&format!("(?i)^{p}$")).unwrap()
+    });
+    query_ctx
+        .variables()
+        .all()
+        .into_iter()
+        .filter(|(name, _)| matcher.as_ref().map_or(true, |m| m.is_match(name)))
+        .map(|(name, _)| (name.to_string(), variable_value(name, query_ctx)))
+        .collect()
+}
+
+fn check_show_variables(query: &str, query_ctx: &QueryContextRef) -> Option<Output> {
     let recordbatches = if SHOW_SQL_MODE_PATTERN.is_match(query) {
         Some(show_variables("sql_mode", "ONLY_FULL_GROUP_BY STRICT_TRANS_TABLES NO_ZERO_IN_DATE NO_ZERO_DATE ERROR_FOR_DIVISION_BY_ZERO NO_ENGINE_SUBSTITUTION"))
     } else if SHOW_LOWER_CASE_PATTERN.is_match(query) {
         Some(show_variables("lower_case_table_names", "0"))
-    } else if SHOW_COLLATION_PATTERN.is_match(query) || SHOW_VARIABLES_PATTERN.is_match(query) {
+    } else if SHOW_COLLATION_PATTERN.is_match(query) {
         Some(show_variables("", ""))
+    } else if SHOW_VARIABLES_PATTERN.is_match(query) {
+        let pattern = SHOW_VARIABLES_LIKE_PATTERN
+            .captures(query)
+            .and_then(|c| c.get(1))
+            .map(|m| m.as_str());
+        let rows = session_variables(pattern, query_ctx);
+        if rows.is_empty() {
+            Some(show_variables("", ""))
+        } else {
+            Some(show_variables_rows(rows))
+        }
     } else {
         None
     };
     recordbatches.map(Output::RecordBatches)
 }
 
+/// Whether the query sets a session variable, which must be executed instead of ignored.
+fn is_set_session_variable(query: &str) -> bool {
+    SET_TIME_ZONE_PATTERN.is_match(query)
+        || SET_VARIABLE_PATTERN
+            .captures(query)
+            .and_then(|c| c.get(1))
+            .map_or(false, |name| variables::is_supported(name.as_str()))
+}
+
 // Check for SET or others query, this is the final check of the federated query.
 fn check_others(query: &str, query_ctx: QueryContextRef) -> Option<Output> {
+    if is_set_session_variable(query) {
+        return None;
+    }
+
     if OTHER_NOT_SUPPORTED_STMT.is_match(query.as_bytes()) {
         return Some(Output::RecordBatches(RecordBatches::empty()));
     }
@@ -277,13 +346,13 @@ fn check_others(query: &str, query_ctx: QueryContextRef) -> Option<Output> {
 // and return some faked results if there are any.
 pub(crate) fn check(query: &str, query_ctx: QueryContextRef) -> Option<Output> {
     // First to check the query is like "select @@variables".
-    let output = check_select_variable(query);
+    let output = check_select_variable(query, &query_ctx);
     if output.is_some() {
         return output;
     }
 
     // Then to check "show variables like ...".
-    let output = check_show_variables(query);
+    let output = check_show_variables(query, &query_ctx);
     if output.is_some() {
         return output;
     }
@@ -358,6 +427,21 @@ mod test {
 
         let query = "show variables";
         let expected = "\
++--------------------+-------+
+| Variable_name      | Value |
++--------------------+-------+
+| continue_on_error  | OFF   |
+| drop_table_sync    | OFF   |
+| max_execution_rows | 0     |
+| scan_stats         | OFF   |
+| skip_query_cache   | OFF   |
+| statement_timeout  | 0     |
+| time_zone          | UTC   |
++--------------------+-------+";
+        test(query, expected);
+
+        let query = "show variables like 'net_buffer_length'";
+        let expected = "\
 +---------------+-------+
 | Variable_name | Value |
 +---------------+-------+
@@ -389,4 +473,66 @@ mod test {
 +----------------------------------+";
         test(query, expected);
     }
+
+    #[test]
+    fn test_session_variables() {
+        let query_ctx = Arc::new(QueryContext::new());
+
+        fn test(query: &str, query_ctx: &QueryContextRef, expected: &str) {
+            match check(query, query_ctx.clone()).unwrap() {
+                Output::RecordBatches(r) => {
+                    assert_eq!(&r.pretty_print().unwrap(), expected)
+                }
+                _ => unreachable!(),
+            }
+        }
+
+        // SETs of session variables are passed to the query engine, others are ignored.
+        for query in [
+            "SET time_zone = '+08:00'",
+            "set @@session.time_zone='Asia/Shanghai'",
+            "SET SESSION statement_timeout = 1000",
+            "SET TIME ZONE 'UTC'",
+            "SET max_execution_rows TO 10",
+        ] {
+            assert!(check(query, query_ctx.clone()).is_none(), "{query}");
+        }
+        assert!(check("SET @@session.autocommit = 1", query_ctx.clone()).is_some());
+        assert!(check("SET NAMES utf8mb4", query_ctx.clone()).is_some());
+
+        assert!(query_ctx.set_variable("time_zone", "+08:00").unwrap());
+        assert!(query_ctx.set_variable("max_execution_rows", "100").unwrap());
+
+        let expected = "\
++-------------+--------------------+
+| @@time_zone | @@system_time_zone |
++-------------+--------------------+
+| +08:00      | UTC                |
++-------------+--------------------+";
+        test(
+            "SELECT @@time_zone, @@system_time_zone",
+            &query_ctx,
+            expected,
+        );
+
+        let expected = "\
++---------------+--------+
+| Variable_name | Value  |
++---------------+--------+
+| time_zone     | +08:00 |
++---------------+--------+";
+        test("SHOW VARIABLES LIKE 'time%'", &query_ctx, expected);
+
+        let expected = "\
++--------------------+-------+
+| Variable_name      | Value |
++--------------------+-------+
+| max_execution_rows | 100   |
++--------------------+-------+";
+        test(
+            "show variables like 'max_execution_rows'",
+            &query_ctx,
+            expected,
+        );
+    }
 }
@@ -12,44 +12,69 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashMap;
 use std::net::SocketAddr;
+use std::num::NonZeroUsize;
 use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
 use chrono::{NaiveDate, NaiveDateTime};
+use common_query::prelude::ScalarValue;
 use common_query::Output;
 use common_telemetry::tracing::log;
 use common_telemetry::{error, trace};
+use datatypes::arrow::compute::{cast_with_options, CastOptions};
+use datatypes::arrow::datatypes::DataType as ArrowDataType;
+use datatypes::prelude::{ConcreteDataType, DataType};
+use datatypes::schema::ColumnSchema;
+use lru::LruCache;
 use opensrv_mysql::{
     AsyncMysqlShim, Column, ColumnFlags, ColumnType, ErrorKind, InitWriter, ParamParser,
     ParamValue, QueryResultWriter, StatementMetaWriter, ValueInner,
 };
-use parking_lot::RwLock;
+use query::plan::LogicalPlan;
 use rand::RngCore;
-use session::context::Channel;
+use session::context::{Channel, QueryContextRef};
 use session::Session;
-use snafu::ensure;
+use snafu::{ensure, OptionExt};
 use sql::dialect::GenericDialect;
 use sql::parser::ParserContext;
 use sql::statements::statement::Statement;
+use sqlparser::tokenizer::{Token, Tokenizer};
 use tokio::io::AsyncWrite;
 
 use crate::auth::{Identity, Password, UserProviderRef};
 use crate::error::{self, InvalidPrepareStatementSnafu, Result};
-use crate::mysql::writer::MysqlResultWriter;
+use crate::mysql::writer::{create_mysql_column, MysqlResultWriter};
 use crate::query_handler::sql::ServerSqlQueryHandlerRef;
 
+/// Max number of prepared statements cached in a connection, the least recently used
+/// statement is evicted once the limit is reached.
+const MAX_PREPARED_STMTS_PER_CONNECTION: usize = 256;
+
+/// A statement prepared by `COM_STMT_PREPARE`, it's planned once and then executed many
+/// times with different parameters.
+///
+/// The parameters are bound into the cached plan as values, so they are never parsed as
+/// SQL. The plan is not updated if the tables it reads are altered after it's prepared.
+struct PreparedStatement {
+    /// The query, parameters are `$1`, `$2`... placeholders.
+    query: String,
+    /// Plan of the query, or `None` if the query is answered by the federated module.
+    plan: Option<LogicalPlan>,
+    /// Types of the parameters inferred from the plan, `None` if the type is unknown.
+    param_types: Vec<Option<ConcreteDataType>>,
+}
+
 // An intermediate shim for executing MySQL queries.
 pub struct MysqlInstanceShim {
     query_handler: ServerSqlQueryHandlerRef,
     salt: [u8; 20],
     session: Arc<Session>,
     user_provider: Option<UserProviderRef>,
-    // TODO(SSebo): use something like moka to achieve TTL or LRU
-    prepared_stmts: Arc<RwLock<HashMap<u32, String>>>,
+    // Statement ids are scoped to the connection, as each connection owns its shim.
+    prepared_stmts: LruCache<u32, PreparedStatement>,
     prepared_stmts_counter: AtomicU32,
 }
 
@@ -77,7 +102,9 @@ impl MysqlInstanceShim {
             salt: scramble,
             session: Arc::new(Session::new(client_addr, Channel::Mysql)),
             user_provider,
-            prepared_stmts: Default::default(),
+            prepared_stmts: LruCache::new(
+                NonZeroUsize::new(MAX_PREPARED_STMTS_PER_CONNECTION).unwrap(),
+            ),
             prepared_stmts_counter: AtomicU32::new(1),
         }
     }
@@ -106,16 +133,52 @@ impl MysqlInstanceShim {
         output
     }
 
-    fn set_query(&self, query: String) -> u32 {
-        let stmt_id = self.prepared_stmts_counter.fetch_add(1, Ordering::SeqCst);
-        let mut guard = self.prepared_stmts.write();
-        guard.insert(stmt_id, query);
-        stmt_id
+    /// Plans the `query` whose parameters are `$1`, `$2`... placeholders.
+    async fn prepare(&self, query: String, param_num: usize) -> Result<PreparedStatement> {
+        if crate::mysql::federated::check(&query, self.session.context()).is_some() {
+            ensure!(
+                param_num == 0,
+                InvalidPrepareStatementSnafu {
+                    err_msg: "federated query doesn't support parameters".to_string(),
+                }
+            );
+            return Ok(PreparedStatement {
+                query,
+                plan: None,
+                param_types: vec![],
+            });
+        }
+
+        let stmt = validate_query(&query).await?;
+        let plan = self
+            .query_handler
+            .do_prepare(stmt, self.session.context())
+            .await?
+            .context(InvalidPrepareStatementSnafu {
+                err_msg: "prepare statement only support SELECT for now",
+            })?;
+        let mut param_types = plan.get_param_types().map_err(|e| {
+            InvalidPrepareStatementSnafu {
+                err_msg: e.to_string(),
+            }
+            .build()
+        })?;
+        let param_types = (1..=param_num)
+            .map(|i| param_types.remove(&format!("${i}")).flatten())
+            .collect();
+        Ok(PreparedStatement {
+            query,
+            plan: Some(plan),
+            param_types,
+        })
     }
 
-    fn query(&self, stmt_id: u32) -> Option<String> {
-        let guard = self.prepared_stmts.read();
-        guard.get(&stmt_id).map(|s| s.to_owned())
+    fn save_stmt(&mut self, stmt: PreparedStatement) -> u32 {
+        let stmt_id = self.prepared_stmts_counter.fetch_add(1, Ordering::SeqCst);
+        if let Some((evicted, _)) = self.prepared_stmts.push(stmt_id, stmt) {
+            log::debug!("evict prepared statement {} from connection cache", evicted);
+        }
+        stmt_id
     }
 }
 
@@ -171,15 +234,26 @@ impl<W: AsyncWrite + Send + Sync + Unpin> AsyncMysqlShim<W> for MysqlInstanceShi
         query: &'a str,
         w: StatementMetaWriter<'a, W>,
     ) -> Result<()> {
-        let (query, param_num) = replace_placeholder(query);
-        if let Err(e) = validate_query(&query).await {
-            w.error(ErrorKind::ER_UNKNOWN_ERROR, e.to_string().as_bytes())
-                .await?;
-            return Ok(());
+        let tokens = match Tokenizer::new(&GenericDialect {}, query).tokenize() {
+            Ok(tokens) => tokens,
+            Err(e) => {
+                w.error(ErrorKind::ER_PARSE_ERROR, e.to_string().as_bytes())
+                    .await?;
+                return Ok(());
+            }
+        };
+        let (query, param_num) = replace_placeholder(&tokens);
+        let stmt = match self.prepare(query, param_num).await {
+            Ok(stmt) => stmt,
+            Err(e) => {
+                w.error(ErrorKind::ER_UNKNOWN_ERROR, e.to_string().as_bytes())
+                    .await?;
+                return Ok(());
+            }
         };
 
-        let stmt_id = self.set_query(query);
-        let params = dummy_params(param_num);
+        let params = param_columns(&stmt.param_types);
+        let stmt_id = self.save_stmt(stmt);
 
         w.reply(stmt_id, &params, &[]).await?;
         return Ok(());
@@ -192,23 +266,45 @@ impl<W: AsyncWrite + Send + Sync + Unpin> AsyncMysqlShim<W> for MysqlInstanceShi
         w: QueryResultWriter<'a, W>,
     ) -> Result<()> {
         let params: Vec<ParamValue> = p.into_iter().collect();
-        let query = match self.query(stmt_id) {
-            None => {
-                w.error(
-                    ErrorKind::ER_UNKNOWN_STMT_HANDLER,
-                    b"prepare statement not exist",
-                )
-                .await?;
+        let Some(stmt) = self.prepared_stmts.get(&stmt_id) else {
+            w.error(
+                ErrorKind::ER_UNKNOWN_STMT_HANDLER,
+                b"prepare statement not exist",
+            )
+            .await?;
+            return Ok(());
+        };
+        let query = stmt.query.clone();
+
+        let Some(plan) = &stmt.plan else {
+            let outputs = self.do_query(&query).await;
+            write_output(w, &query, outputs, self.session.context()).await?;
+            return Ok(());
+        };
+        let plan = match params_to_values(params)
+            .and_then(|values| bind_values(&stmt.param_types, values))
+            .and_then(|values| {
+                plan.replace_params_with_values(&values).map_err(|e| {
+                    InvalidPrepareStatementSnafu {
+                        err_msg: e.to_string(),
+                    }
+                    .build()
+                })
+            }) {
+            Ok(plan) => plan,
+            Err(e) => {
+                w.error(ErrorKind::ER_WRONG_ARGUMENTS, e.to_string().as_bytes())
+                    .await?;
                 return Ok(());
             }
-            Some(query) => query,
         };
+        log::debug!("execute prepared query: {}", query);
 
-        let query = replace_params(params, query);
-        log::debug!("execute replaced query: {}", query);
-
-        let outputs = self.do_query(&query).await;
-        write_output(w, &query, outputs).await?;
+        let output = self
+            .query_handler
+            .do_exec_plan(&query, plan, self.session.context())
+            .await;
+        write_output(w, &query, vec![output], self.session.context()).await?;
 
         Ok(())
     }
@@ -217,8 +313,7 @@ impl<W: AsyncWrite + Send + Sync + Unpin> AsyncMysqlShim<W> for MysqlInstanceShi
     where
         W: 'async_trait,
     {
-        let mut guard = self.prepared_stmts.write();
-        guard.remove(&stmt_id);
+        let _ = self.prepared_stmts.pop(&stmt_id);
     }
 
     async fn on_query<'a>(
@@ -227,7 +322,7 @@ impl<W: AsyncWrite + Send + Sync + Unpin> AsyncMysqlShim<W> for MysqlInstanceShi
         writer: QueryResultWriter<'a, W>,
     ) -> Result<()> {
         let outputs = self.do_query(query).await;
-        let mut writer = MysqlResultWriter::new(writer);
+        let mut writer = MysqlResultWriter::new(writer, self.session.context());
         for output in outputs {
             writer.write(query, output).await?;
         }
@@ -263,24 +358,136 @@ impl<W: AsyncWrite + Send + Sync + Unpin> AsyncMysqlShim<W> for MysqlInstanceShi
     }
 }
 
-fn replace_params(params: Vec<ParamValue>, query: String) -> String {
-    let mut query = query;
-    let mut index = 1;
-    for param in params {
-        let s = match param.value.into_inner() {
-            ValueInner::Int(u) => u.to_string(),
-            ValueInner::UInt(u) => u.to_string(),
-            ValueInner::Double(u) => u.to_string(),
-            ValueInner::NULL => "NULL".to_string(),
-            ValueInner::Bytes(b) => format!("'{}'", &String::from_utf8_lossy(b)),
-            ValueInner::Date(_) => NaiveDate::from(param.value).to_string(),
-            ValueInner::Datetime(_) => NaiveDateTime::from(param.value).to_string(),
-            ValueInner::Time(_) => format_duration(Duration::from(param.value)),
-        };
-        query = query.replace(&format!("${}", index), &s);
-        index += 1;
+/// Converts binary protocol parameters to values, each value is of the type closest to
+/// the parameter.
+fn params_to_values(params: Vec<ParamValue>) -> Result<Vec<ScalarValue>> {
+    params
+        .into_iter()
+        .map(|param| {
+            let value = match param.value.into_inner() {
+                ValueInner::Int(i) => ScalarValue::Int64(Some(i)),
+                ValueInner::UInt(u) => ScalarValue::UInt64(Some(u)),
+                ValueInner::Double(d) => ScalarValue::Float64(Some(d)),
+                ValueInner::NULL => ScalarValue::Null,
+                ValueInner::Bytes(b) => match std::str::from_utf8(b) {
+                    Ok(s) => ScalarValue::Utf8(Some(s.to_string())),
+                    Err(_) => ScalarValue::LargeBinary(Some(b.to_vec())),
+                },
+                ValueInner::Date(_) => {
+                    ScalarValue::Utf8(Some(NaiveDate::from(param.value).to_string()))
+                }
+                ValueInner::Datetime(_) => {
+                    ScalarValue::Utf8(Some(NaiveDateTime::from(param.value).to_string()))
+                }
+                ValueInner::Time(_) => {
+                    ScalarValue::Utf8(Some(format_duration(Duration::from(param.value))))
+                }
+            };
+            Ok(value)
+        })
+        .collect()
+}
+
+/// Checks the values against the parameter types inferred from the plan and casts them to
+/// these types, returns the values to bind.
+fn bind_values(
+    param_types: &[Option<ConcreteDataType>],
+    values: Vec<ScalarValue>,
+) -> Result<Vec<ScalarValue>> {
+    ensure!(
+        values.len() == param_types.len(),
+        InvalidPrepareStatementSnafu {
+            err_msg: format!(
+                "expect {} parameters, actual: {}",
+                param_types.len(),
+                values.len()
+            ),
+        }
+    );
+
+    values
+        .into_iter()
+        .zip(param_types)
+        .enumerate()
+        .map(|(i, (value, param_type))| match param_type {
+            Some(param_type) => cast_param(i + 1, value, param_type),
+            None => Ok(value),
+        })
+        .collect()
+}
+
+/// Casts the value of the `index`th parameter to `param_type`, fails if the type of the
+/// value is not compatible with `param_type`, e.g. a string to a number.
+fn cast_param(
+    index: usize,
+    value: ScalarValue,
+    param_type: &ConcreteDataType,
+) -> Result<ScalarValue> {
+    let arrow_type = param_type.as_arrow_type();
+    let value_type = value.get_datatype();
+    if value_type == arrow_type {
+        return Ok(value);
+    }
+    let mismatch = |reason: Option<String>| {
+        let reason = reason.map(|r| format!(", {r}")).unwrap_or_default();
+        InvalidPrepareStatementSnafu {
+            err_msg: format!(
+                "parameter ${index} expects type {}, actual: {value_type:?}{reason}",
+                param_type.name(),
+            ),
+        }
+        .build()
+    };
+
+    let is_binary = matches!(param_type, ConcreteDataType::Binary(_));
+    match value {
+        ScalarValue::Null => {
+            ScalarValue::try_from(&arrow_type).map_err(|e| mismatch(Some(e.to_string())))
+        }
+        ScalarValue::Utf8(s) if is_binary => {
+            Ok(ScalarValue::LargeBinary(s.map(String::into_bytes)))
+        }
+        ScalarValue::Int64(_) | ScalarValue::UInt64(_) | ScalarValue::Float64(_)
+            if param_type.is_boolean()
+                || param_type.is_signed()
+                || param_type.is_unsigned()
+                || param_type.is_float() =>
+        {
+            cast_scalar_value(&value, &arrow_type).map_err(|e| mismatch(Some(e)))
+        }
+        ScalarValue::Utf8(_) if param_type.is_stringifiable() => {
+            cast_scalar_value(&value, &arrow_type).map_err(|e| mismatch(Some(e)))
+        }
+        _ => Err(mismatch(None)),
     }
-    query
+}
+
+fn cast_scalar_value(
+    value: &ScalarValue,
+    to_type: &ArrowDataType,
+) -> std::result::Result<ScalarValue, String> {
+    let options = CastOptions { safe: false };
+    let array =
+        cast_with_options(&value.to_array(), to_type, &options).map_err(|e| e.to_string())?;
+    ScalarValue::try_from_array(&array, 0).map_err(|e| e.to_string())
+}
+
+fn quote_string(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+/// Writes the token back as SQL. Unlike `Token::to_string()`, the quotes inside string
+/// literals are escaped again.
+fn token_to_sql(token: &Token) -> String {
+    match token {
+        Token::SingleQuotedString(s) => quote_string(s),
+        Token::NationalStringLiteral(s) => format!("N{}", quote_string(s)),
+        _ => token.to_string(),
+    }
+}
+
+fn is_placeholder(token: &Token) -> bool {
+    matches!(token, Token::Placeholder(s) if s == "?")
 }
 
 fn format_duration(duration: Duration) -> String {
@@ -322,37 +529,158 @@ async fn write_output<'a, W: AsyncWrite + Send + Sync + Unpin>(
     w: QueryResultWriter<'a, W>,
     query: &str,
     outputs: Vec<Result<Output>>,
+    query_context: QueryContextRef,
 ) -> Result<()> {
-    let mut writer = MysqlResultWriter::new(w);
+    let mut writer = MysqlResultWriter::new(w, query_context);
     for output in outputs {
         writer.write(query, output).await?;
     }
     Ok(())
 }
 
-// dummy columns to satisfy opensrv_mysql, just the number of params is useful
-// TODO(SSebo): use parameter type inference to return actual types
-fn dummy_params(index: u32) -> Vec<Column> {
-    let mut params = vec![];
-
-    for _ in 1..index {
-        params.push(opensrv_mysql::Column {
-            table: "".to_string(),
-            column: "".to_string(),
-            coltype: ColumnType::MYSQL_TYPE_LONG,
-            colflags: ColumnFlags::NOT_NULL_FLAG,
-        });
-    }
-    params
+/// Creates the columns describing the parameters, a parameter of unknown type is
+/// described as `MYSQL_TYPE_NULL`.
+fn param_columns(param_types: &[Option<ConcreteDataType>]) -> Vec<Column> {
+    param_types
+        .iter()
+        .enumerate()
+        .map(|(i, param_type)| {
+            param_type
+                .as_ref()
+                .and_then(|t| {
+                    create_mysql_column(&ColumnSchema::new(format!("${}", i + 1), t.clone(), true))
+                        .ok()
+                })
+                .unwrap_or_else(|| Column {
+                    table: "".to_string(),
+                    column: format!("${}", i + 1),
+                    coltype: ColumnType::MYSQL_TYPE_NULL,
+                    colflags: ColumnFlags::empty(),
+                })
+        })
+        .collect()
+}
+
+/// Replaces `?` placeholders with `$1`, `$2`... so the query could be parsed, returns
+/// the replaced query and the number of placeholders.
+fn replace_placeholder(tokens: &[Token]) -> (String, usize) {
+    let mut param_num = 0;
+    let query = tokens
+        .iter()
+        .map(|token| {
+            if is_placeholder(token) {
+                param_num += 1;
+                format!("${param_num}")
+            } else {
+                token_to_sql(token)
+            }
+        })
+        .collect::<String>();
+    (query, param_num)
 }
 
-fn replace_placeholder(query: &str) -> (String, u32) {
-    let mut query = query.to_string();
-    let mut index = 1;
-    while let Some(position) = query.find('?') {
-        let place_holder = format!("${}", index);
-        query.replace_range(position..position + 1, &place_holder);
-        index += 1;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokenize(query: &str) -> Vec<Token> {
+        Tokenizer::new(&GenericDialect {}, query)
+            .tokenize()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_replace_placeholder() {
+        let tokens = tokenize("SELECT * FROM t WHERE a = ? AND b = '?' AND c = ?");
+        let (query, param_num) = replace_placeholder(&tokens);
+        assert_eq!(2, param_num);
+        assert_eq!("SELECT * FROM t WHERE a = $1 AND b = '?' AND c = $2", query);
+
+        // Quotes in string literals are kept escaped.
+        let tokens = tokenize("SELECT * FROM t WHERE a = ? AND b = 'it''s' AND c = N'it''s'");
+        let (query, param_num) = replace_placeholder(&tokens);
+        assert_eq!(1, param_num);
+        assert_eq!(
+            "SELECT * FROM t WHERE a = $1 AND b = 'it''s' AND c = N'it''s'",
+            query
+        );
+    }
+
+    #[test]
+    fn test_quote_string() {
+        assert_eq!("'abc'", quote_string("abc"));
+        assert_eq!("'it''s'", quote_string("it's"));
+    }
+
+    #[test]
+    fn test_bind_values() {
+        let param_types = vec![
+            Some(ConcreteDataType::uint32_datatype()),
+            Some(ConcreteDataType::float32_datatype()),
+            Some(ConcreteDataType::binary_datatype()),
+            Some(ConcreteDataType::timestamp_millisecond_datatype()),
+            Some(ConcreteDataType::int8_datatype()),
+            None,
+        ];
+        let values = vec![
+            ScalarValue::UInt64(Some(1)),
+            ScalarValue::Float64(Some(0.5)),
+            ScalarValue::Utf8(Some("abc".to_string())),
+            ScalarValue::Utf8(Some("1970-01-01 00:00:01".to_string())),
+            ScalarValue::Null,
+            ScalarValue::Int64(Some(-1)),
+        ];
+        let values = bind_values(&param_types, values).unwrap();
+        assert_eq!(
+            vec![
+                ScalarValue::UInt32(Some(1)),
+                ScalarValue::Float32(Some(0.5)),
+                ScalarValue::LargeBinary(Some(b"abc".to_vec())),
+                ScalarValue::TimestampMillisecond(Some(1000), None),
+                ScalarValue::Int8(None),
+                ScalarValue::Int64(Some(-1)),
+            ],
+            values
+        );
+    }
+
+    #[test]
+    fn test_bind_values_num_mismatch() {
+        let param_types = vec![None, None];
+        let err = bind_values(&param_types, vec![]).unwrap_err();
+        assert!(err.to_string().contains("expect 2 parameters, actual: 0"));
+    }
+
+    #[test]
+    fn test_bind_values_type_mismatch() {
+        let param_types = vec![
+            Some(ConcreteDataType::string_datatype()),
+            Some(ConcreteDataType::uint32_datatype()),
+        ];
+
+        let values = vec![
+            ScalarValue::Utf8(Some("abc".to_string())),
+            ScalarValue::Utf8(Some("1".to_string())),
+        ];
+        let err = bind_values(&param_types, values).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("parameter $2 expects type UInt32, actual: Utf8"));
+
+        let values = vec![ScalarValue::Int64(Some(1)), ScalarValue::UInt64(Some(1))];
+        let err = bind_values(&param_types, values).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("parameter $1 expects type String, actual: Int64"));
+
+        // The value overflows the parameter type.
+        let values = vec![
+            ScalarValue::Utf8(Some("abc".to_string())),
+            ScalarValue::Int64(Some(-1)),
+        ];
+        let err = bind_values(&param_types, values).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("parameter $2 expects type UInt32, actual: Int64"));
     }
-    (query, index)
 }
@@ -15,15 +15,18 @@
 use std::ops::Deref;
 
 use common_query::Output;
-use common_recordbatch::{util, RecordBatch};
+use common_recordbatch::RecordBatch;
 use common_telemetry::error;
 use common_time::datetime::DateTime;
 use common_time::timestamp::TimeUnit;
+use common_time::TimeZone;
 use datatypes::prelude::{ConcreteDataType, Value};
 use datatypes::schema::{ColumnSchema, SchemaRef};
+use futures::TryStreamExt;
 use opensrv_mysql::{
     Column, ColumnFlags, ColumnType, ErrorKind, OkResponse, QueryResultWriter, RowWriter,
 };
+use session::context::QueryContextRef;
 use snafu::prelude::*;
 use tokio::io::AsyncWrite;
 
@@ -32,42 +35,63 @@ use crate::error::{self, Error, Result};
 struct QueryResult {
     recordbatches: Vec<RecordBatch>,
     schema: SchemaRef,
+    /// Human readable info sent in the OK packet after the rows.
+    info: Option<String>,
 }
 
 pub struct MysqlResultWriter<'a, W: AsyncWrite + Unpin> {
     // `QueryResultWriter` will be consumed when the write completed (see
     // QueryResultWriter::completed), thus we use an option to wrap it.
     inner: Option<QueryResultWriter<'a, W>>,
+    query_context: QueryContextRef,
 }
 
 impl<'a, W: AsyncWrite + Unpin> MysqlResultWriter<'a, W> {
-    pub fn new(inner: QueryResultWriter<'a, W>) -> MysqlResultWriter<'a, W> {
-        MysqlResultWriter::<'a, W> { inner: Some(inner) }
+    pub fn new(
+        inner: QueryResultWriter<'a, W>,
+        query_context: QueryContextRef,
+    ) -> MysqlResultWriter<'a, W> {
+        MysqlResultWriter::<'a, W> {
+            inner: Some(inner),
+            query_context,
+        }
     }
 
     pub async fn write(&mut self, query: &str, output: Result<Output>) -> Result<()> {
         let writer = self.inner.take().context(error::InternalSnafu {
             err_msg: "inner MySQL writer is consumed",
         })?;
+        // Loaded for every output since a preceding `SET time_zone` may have changed it.
+        let time_zone = self.query_context.time_zone();
         match output {
             Ok(output) => match output {
-                Output::Stream(stream) => {
+                Output::Stream(mut stream) => {
                     let schema = stream.schema().clone();
-                    let recordbatches = util::collect(stream)
+                    let recordbatches = stream
+                        .by_ref()
+                        .try_collect::<Vec<_>>()
                         .await
                         .context(error::CollectRecordbatchSnafu)?;
+                    // Stats are complete only after the stream is exhausted.
+                    let info = self
+                        .query_context
+                        .variables()
+                        .scan_stats
+                        .then(|| stream.scan_stats().unwrap_or_default().to_string());
                     let query_result = QueryResult {
                         recordbatches,
                         schema,
+                        info,
                     };
-                    Self::write_query_result(query, query_result, writer).await?
+                    Self::write_query_result(query, query_result, writer, time_zone).await?
                 }
                 Output::RecordBatches(recordbatches) => {
                     let query_result = QueryResult {
                         schema: recordbatches.schema(),
                         recordbatches: recordbatches.take(),
+                        info: None,
                     };
-                    Self::write_query_result(query, query_result, writer).await?
+                    Self::write_query_result(query, query_result, writer, time_zone).await?
                 }
                 Output::AffectedRows(rows) => Self::write_affected_rows(writer, rows).await?,
             },
@@ -89,14 +113,19 @@ impl<'a, W: AsyncWrite + Unpin> MysqlResultWriter<'a, W> {
         query: &str,
         query_result: QueryResult,
         writer: QueryResultWriter<'a, W>,
+        time_zone: Option<TimeZone>,
     ) -> Result<()> {
         match create_mysql_column_def(&query_result.schema) {
             Ok(column_def) => {
                 let mut row_writer = writer.start(&column_def).await?;
                 for recordbatch in &query_result.recordbatches {
-                    Self::write_recordbatch(&mut row_writer, recordbatch).await?;
+                    Self::write_recordbatch(&mut row_writer, recordbatch, time_zone.as_ref())
+                        .await?;
+                }
+                match &query_result.info {
+                    Some(info) => row_writer.finish_with_info(info).await?,
+                    None => row_writer.finish().await?,
                 }
-                row_writer.finish().await?;
                 Ok(())
             }
             Err(error) => Self::write_query_error(query, error, writer).await,
@@ -106,6 +135,7 @@ impl<'a, W: AsyncWrite + Unpin> MysqlResultWriter<'a, W> {
     async fn write_recordbatch(
         row_writer: &mut RowWriter<'_, W>,
         recordbatch: &RecordBatch,
+        time_zone: Option<&TimeZone>,
     ) -> Result<()> {
         for row in recordbatch.rows() {
             for value in row.into_iter() {
@@ -126,10 +156,15 @@ impl<'a, W: AsyncWrite + Unpin> MysqlResultWriter<'a, W> {
                     Value::Binary(v) => row_writer.write_col(v.deref())?,
                     Value::Date(v) => row_writer.write_col(v.val())?,
                     Value::DateTime(v) => row_writer.write_col(v.val())?,
-                    Value::Timestamp(v) => row_writer.write_col(
+                    Value::Timestamp(v) => {
                         // safety: converting timestamp with whatever unit to second will not cause overflow
-                        DateTime::new(v.convert_to(TimeUnit::Second).unwrap().value()).to_string(),
-                    )?,
+                        let v = v.convert_to(TimeUnit::Second).unwrap();
+                        let s = match time_zone {
+                            Some(tz) => v.to_timezone_aware_string(tz),
+                            None => DateTime::new(v.value()).to_string(),
+                        };
+                        row_writer.write_col(s)?
+                    }
                     Value::List(_) => {
                         return Err(Error::Internal {
                             err_msg: format!(
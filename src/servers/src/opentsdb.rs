@@ -15,6 +15,7 @@
 pub mod codec;
 pub mod connection;
 mod handler;
+pub mod http_server;
 
 use std::future::Future;
 use std::net::SocketAddr;
@@ -12,14 +12,24 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+
 use api::v1::column::SemanticType;
 use api::v1::{column, Column, ColumnDataType, InsertRequest as GrpcInsertRequest};
+use common_grpc::writer::{LinesWriter, Precision};
+use snafu::{OptionExt, ResultExt};
 
 use crate::error::{self, Result};
 
 pub const OPENTSDB_TIMESTAMP_COLUMN_NAME: &str = "greptime_timestamp";
 pub const OPENTSDB_VALUE_COLUMN_NAME: &str = "greptime_value";
 
+/// Timestamps up to this (fit in 32 bits) are in seconds, larger ones are in milliseconds,
+/// the same as OpenTSDB.
+const MAX_SECONDS_TIMESTAMP: i64 = u32::MAX as i64;
+/// Max timestamp in milliseconds accepted by OpenTSDB.
+const MAX_MILLIS_TIMESTAMP: i64 = 9_999_999_999_999;
+
 #[derive(Debug)]
 pub struct DataPoint {
     metric: String,
@@ -60,15 +70,10 @@ impl DataPoint {
 
         let metric = tokens[1];
 
-        let ts_millis = match tokens[2].parse::<i64>() {
-            Ok(t) => Self::timestamp_to_millis(t),
-            Err(_) => {
-                return error::InvalidQuerySnafu {
-                    reason: format!("put: invalid timestamp: {}", tokens[2]),
-                }
-                .fail()
-            }
-        };
+        let ts_millis =
+            Self::parse_timestamp(tokens[2]).with_context(|| error::InvalidQuerySnafu {
+                reason: format!("put: invalid timestamp: {}", tokens[2]),
+            })?;
 
         let value = match tokens[3].parse::<f64>() {
             Ok(v) => v,
@@ -153,7 +158,7 @@ impl DataPoint {
 
         for (tagk, tagv) in self.tags.iter() {
             columns.push(Column {
-                column_name: tagk.to_string(),
+                column_name: escape_tag_key(tagk),
                 values: Some(column::Values {
                     string_values: vec![tagv.to_string()],
                     ..Default::default()
@@ -172,17 +177,91 @@ impl DataPoint {
         }
     }
 
-    pub fn timestamp_to_millis(t: i64) -> i64 {
-        // 9999999999999 (13 digits) is of date "Sat Nov 20 2286 17:46:39 UTC",
-        // 999999999999 (12 digits) is "Sun Sep 09 2001 01:46:39 UTC",
-        // so timestamp digits less than 13 means we got seconds here.
-        // (We are not expecting to store data that is 21 years ago, are we?)
-        if t.abs().to_string().len() < 13 {
-            t * 1000
+    /// Converts data points to insert requests, one request of all the data points per metric.
+    pub fn to_grpc_inserts(data_points: &[DataPoint]) -> Result<Vec<GrpcInsertRequest>> {
+        let mut writers: HashMap<&str, LinesWriter> = HashMap::new();
+        for data_point in data_points {
+            let writer = writers
+                .entry(data_point.metric())
+                .or_insert_with(|| LinesWriter::with_lines(data_points.len()));
+
+            writer
+                .write_ts(
+                    OPENTSDB_TIMESTAMP_COLUMN_NAME,
+                    (data_point.ts_millis, Precision::Millisecond),
+                )
+                .context(error::OpentsdbLinesWriteSnafu)?;
+            writer
+                .write_f64(OPENTSDB_VALUE_COLUMN_NAME, data_point.value)
+                .context(error::OpentsdbLinesWriteSnafu)?;
+            for (tagk, tagv) in data_point.tags.iter() {
+                writer
+                    .write_tag(&escape_tag_key(tagk), tagv)
+                    .context(error::OpentsdbLinesWriteSnafu)?;
+            }
+            writer.commit();
+        }
+
+        Ok(writers
+            .into_iter()
+            .map(|(metric, writer)| {
+                let (columns, row_count) = writer.finish();
+                GrpcInsertRequest {
+                    table_name: metric.to_string(),
+                    region_number: 0,
+                    columns,
+                    row_count,
+                }
+            })
+            .collect())
+    }
+
+    /// Converts a timestamp in seconds or milliseconds to milliseconds. Like OpenTSDB, a
+    /// timestamp that fits in 32 bits is in seconds, a larger one of at most 13 digits is in
+    /// milliseconds. Returns [None] for negative or larger timestamps.
+    pub fn timestamp_to_millis(t: i64) -> Option<i64> {
+        if !(0..=MAX_MILLIS_TIMESTAMP).contains(&t) {
+            None
+        } else if t <= MAX_SECONDS_TIMESTAMP {
+            Some(t * 1000)
+        } else {
+            Some(t)
+        }
+    }
+
+    /// Parses the timestamp of the telnet `put` command, which is either an integer (see
+    /// [DataPoint::timestamp_to_millis]) or seconds with 3 digits of milliseconds, like
+    /// "1479496100.123".
+    fn parse_timestamp(token: &str) -> Option<i64> {
+        match token.split_once('.') {
+            Some((secs, millis)) => {
+                if millis.len() != 3 || !millis.bytes().all(|b| b.is_ascii_digit()) {
+                    return None;
+                }
+                let secs = secs.parse::<i64>().ok()?;
+                if !(0..=MAX_SECONDS_TIMESTAMP).contains(&secs) {
+                    return None;
+                }
+                Some(secs * 1000 + millis.parse::<i64>().ok()?)
+            }
+            None => Self::timestamp_to_millis(token.parse().ok()?),
+        }
+    }
+}
+
+/// Escapes an OpenTSDB tag key to a column name. ASCII letters, digits and `_` are kept, any
+/// other character is replaced by `_x`, the lowercase hex of its code point and `_`, e.g.
+/// `cpu.core` is escaped to `cpu_x2e_core`, and `disk-id` to `disk_x2d_id`.
+pub fn escape_tag_key(key: &str) -> String {
+    let mut escaped = String::with_capacity(key.len());
+    for c in key.chars() {
+        if c.is_ascii_alphanumeric() || c == '_' {
+            escaped.push(c);
         } else {
-            t
+            escaped.push_str(&format!("_x{:x}_", c as u32));
         }
     }
+    escaped
 }
 
 #[cfg(test)]
@@ -288,4 +367,136 @@ mod test {
             vec!["tagv2"]
         );
     }
+
+    #[test]
+    fn test_timestamp_to_millis() {
+        assert_eq!(Some(0), DataPoint::timestamp_to_millis(0));
+        assert_eq!(
+            Some(1479496100000),
+            DataPoint::timestamp_to_millis(1479496100)
+        );
+        assert_eq!(
+            Some(4294967295000),
+            DataPoint::timestamp_to_millis(4294967295)
+        );
+        // Doesn't fit in 32 bits, so it's in milliseconds.
+        assert_eq!(
+            Some(4294967296),
+            DataPoint::timestamp_to_millis(4294967296)
+        );
+        assert_eq!(
+            Some(1479496100123),
+            DataPoint::timestamp_to_millis(1479496100123)
+        );
+        assert_eq!(None, DataPoint::timestamp_to_millis(-1));
+        assert_eq!(None, DataPoint::timestamp_to_millis(10000000000000));
+
+        assert_eq!(
+            Some(1479496100123),
+            DataPoint::parse_timestamp("1479496100.123")
+        );
+        assert_eq!(None, DataPoint::parse_timestamp("1479496100.12"));
+        assert_eq!(None, DataPoint::parse_timestamp("1479496100123.123"));
+        assert_eq!(None, DataPoint::parse_timestamp("1479496100.-12"));
+    }
+
+    #[test]
+    fn test_escape_tag_key() {
+        assert_eq!("host", escape_tag_key("host"));
+        assert_eq!("Disk_id_0", escape_tag_key("Disk_id_0"));
+        assert_eq!("cpu_x2e_core", escape_tag_key("cpu.core"));
+        assert_eq!("disk_x2d_id", escape_tag_key("disk-id"));
+        assert_eq!("_x2f_mnt", escape_tag_key("/mnt"));
+        assert_eq!("_x4e3b__x673a_", escape_tag_key("主机"));
+    }
+
+    // Lines sent by tcollector's collectors through the telnet `put` command.
+    const TCOLLECTOR_LINES: &str = "\
+put proc.loadavg.1min 1680151200 0.36 host=web01
+put proc.loadavg.5min 1680151200 0.41 host=web01
+put proc.stat.cpu 1680151200 2846152 host=web01 type=user cpu.core=0
+put proc.stat.cpu 1680151200 1284 host=web01 type=nice cpu.core=0
+put df.bytes.free 1680151215 40870154240 host=web01 mount=/ fstype=ext4
+put iostat.disk.read_requests 1680151215.250 103212 host=web01 dev=sda";
+
+    #[test]
+    fn test_tcollector_lines() {
+        let data_points = TCOLLECTOR_LINES
+            .lines()
+            .map(|line| DataPoint::try_create(line).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(6, data_points.len());
+        assert_eq!(1680151215250, data_points[5].ts_millis());
+
+        let mut requests = DataPoint::to_grpc_inserts(&data_points).unwrap();
+        requests.sort_by(|a, b| a.table_name.cmp(&b.table_name));
+        let tables = requests
+            .iter()
+            .map(|r| (r.table_name.as_str(), r.row_count))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                ("df.bytes.free", 1),
+                ("iostat.disk.read_requests", 1),
+                ("proc.loadavg.1min", 1),
+                ("proc.loadavg.5min", 1),
+                ("proc.stat.cpu", 2),
+            ],
+            tables
+        );
+
+        let cpu = &requests[4];
+        let column_names = cpu
+            .columns
+            .iter()
+            .map(|c| c.column_name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                OPENTSDB_TIMESTAMP_COLUMN_NAME,
+                OPENTSDB_VALUE_COLUMN_NAME,
+                "host",
+                "type",
+                "cpu_x2e_core"
+            ],
+            column_names
+        );
+        assert_eq!(
+            vec![2846152.0, 1284.0],
+            cpu.columns[1].values.as_ref().unwrap().f64_values
+        );
+        assert_eq!(
+            vec!["user", "nice"],
+            cpu.columns[3].values.as_ref().unwrap().string_values
+        );
+    }
+
+    #[test]
+    fn test_to_grpc_inserts_with_different_tags() {
+        let data_points = vec![
+            DataPoint::new(
+                "my_metric".to_string(),
+                1000,
+                1.0,
+                vec![("tagk1".to_string(), "tagv1".to_string())],
+            ),
+            DataPoint::new(
+                "my_metric".to_string(),
+                2000,
+                2.0,
+                vec![("tagk2".to_string(), "tagv2".to_string())],
+            ),
+        ];
+        let requests = DataPoint::to_grpc_inserts(&data_points).unwrap();
+        assert_eq!(1, requests.len());
+        let columns = &requests[0].columns;
+        assert_eq!(2, requests[0].row_count);
+        assert_eq!(4, columns.len());
+
+        // null masks of "tagk1" and "tagk2"
+        assert_eq!(columns[2].column_name, "tagk1");
+        assert_eq!(vec![0b10], columns[2].null_mask);
+        assert_eq!(columns[3].column_name, "tagk2");
+        assert_eq!(vec![0b01], columns[3].null_mask);
+    }
 }
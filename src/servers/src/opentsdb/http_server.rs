@@ -0,0 +1,123 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::net::SocketAddr;
+
+use async_trait::async_trait;
+use axum::body::BoxBody;
+use axum::{routing, Router};
+use common_telemetry::info;
+use futures::FutureExt;
+use snafu::{ensure, ResultExt};
+use tokio::sync::oneshot::Sender;
+use tokio::sync::{oneshot, Mutex};
+use tower::ServiceBuilder;
+use tower_http::auth::AsyncRequireAuthorizationLayer;
+use tower_http::trace::TraceLayer;
+
+use crate::auth::UserProviderRef;
+use crate::error::{AlreadyStartedSnafu, Result, StartHttpSnafu};
+use crate::http::authorize::HttpAuth;
+use crate::http::opentsdb;
+use crate::query_handler::OpentsdbProtocolHandlerRef;
+use crate::server::Server;
+
+pub const OPENTSDB_HTTP_SERVER: &str = "OPENTSDB_HTTP_SERVER";
+
+/// An HTTP server only serving the OpenTSDB put API at `/api/put` like OpenTSDB does, for
+/// nodes without the full HTTP API like datanodes.
+///
+/// The body is a data point or an array of data points. Like OpenTSDB, the errors of each
+/// data point are returned with the `details` query parameter, see [opentsdb::put()].
+pub struct OpentsdbHttpServer {
+    handler: OpentsdbProtocolHandlerRef,
+    shutdown_tx: Mutex<Option<Sender<()>>>,
+    user_provider: Option<UserProviderRef>,
+}
+
+impl OpentsdbHttpServer {
+    pub fn create_server(handler: OpentsdbProtocolHandlerRef) -> Box<Self> {
+        Box::new(OpentsdbHttpServer {
+            handler,
+            shutdown_tx: Mutex::new(None),
+            user_provider: None,
+        })
+    }
+
+    pub fn set_user_provider(&mut self, user_provider: UserProviderRef) {
+        debug_assert!(self.user_provider.is_none());
+        self.user_provider = Some(user_provider);
+    }
+
+    pub fn make_app(&self) -> Router {
+        Router::new()
+            .route("/api/put", routing::post(opentsdb::put))
+            .with_state(self.handler.clone())
+            // middlewares
+            .layer(
+                ServiceBuilder::new()
+                    .layer(TraceLayer::new_for_http())
+                    // custom layer
+                    .layer(AsyncRequireAuthorizationLayer::new(
+                        HttpAuth::<BoxBody>::new(self.user_provider.clone()),
+                    )),
+            )
+    }
+}
+
+#[async_trait]
+impl Server for OpentsdbHttpServer {
+    async fn shutdown(&self) -> Result<()> {
+        let mut shutdown_tx = self.shutdown_tx.lock().await;
+        if let Some(tx) = shutdown_tx.take() {
+            if tx.send(()).is_err() {
+                info!("Receiver dropped, the OpenTSDB HTTP server has already existed");
+            }
+        }
+        info!("Shutdown OpenTSDB HTTP server");
+
+        Ok(())
+    }
+
+    async fn start(&self, listening: SocketAddr) -> Result<SocketAddr> {
+        let (tx, rx) = oneshot::channel();
+        let server = {
+            let mut shutdown_tx = self.shutdown_tx.lock().await;
+            ensure!(
+                shutdown_tx.is_none(),
+                AlreadyStartedSnafu {
+                    server: "OpenTSDB HTTP"
+                }
+            );
+
+            let app = self.make_app();
+            let server = axum::Server::bind(&listening).serve(app.into_make_service());
+
+            *shutdown_tx = Some(tx);
+
+            server
+        };
+        let listening = server.local_addr();
+        info!("OpenTSDB HTTP server is bound to {}", listening);
+
+        let graceful = server.with_graceful_shutdown(rx.map(drop));
+        graceful.await.context(StartHttpSnafu)?;
+
+        Ok(listening)
+    }
+
+    fn name(&self) -> &str {
+        OPENTSDB_HTTP_SERVER
+    }
+}
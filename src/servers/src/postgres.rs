@@ -31,7 +31,7 @@ use pgwire::api::auth::ServerParameterProvider;
 use pgwire::api::store::MemPortalStore;
 use pgwire::api::{ClientInfo, MakeHandler};
 pub use server::PostgresServer;
-use session::context::{QueryContext, QueryContextRef};
+use session::context::{Channel, QueryContext, QueryContextRef};
 use sql::statements::statement::Statement;
 
 use self::auth_handler::PgLoginVerifier;
@@ -99,7 +99,7 @@ impl MakeHandler for MakePostgresServerHandler {
             force_tls: self.force_tls,
             param_provider: self.param_provider.clone(),
 
-            query_ctx: QueryContext::arc(),
+            query_ctx: Arc::new(QueryContext::new().with_channel(Channel::Postgres)),
             portal_store: Arc::new(MemPortalStore::new()),
             query_parser: self.query_parser.clone(),
         })
@@ -207,6 +207,9 @@ impl StartupHandler for PostgresServerHandler {
                     )
                     .await;
                 }
+                if let Some(user) = login_info.user {
+                    self.query_ctx.set_current_user(UserInfo::new(user));
+                }
                 set_query_context_from_client_info(client, self.query_ctx.clone());
                 auth::finish_authentication(client, self.param_provider.as_ref()).await;
             }
@@ -16,6 +16,7 @@
 use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
 use axum::body::BoxBody;
@@ -35,7 +36,7 @@ use promql_parser::parser::{
     AggregateExpr, BinaryExpr, Call, Expr as PromqlExpr, MatrixSelector, ParenExpr, SubqueryExpr,
     UnaryExpr, VectorSelector,
 };
-use query::parser::PromQuery;
+use query::parser::{PromQuery, QueryLanguageParser, DEFAULT_LOOKBACK_DELTA};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use snafu::{ensure, OptionExt, ResultExt};
@@ -54,6 +55,8 @@ use crate::http::authorize::HttpAuth;
 use crate::server::Server;
 
 pub const PROM_API_VERSION: &str = "v1";
+/// Default max number of steps of a range query, the same as Prometheus.
+pub const DEFAULT_MAX_STEPS: u64 = 11_000;
 
 pub type PromHandlerRef = Arc<dyn PromHandler + Send + Sync>;
 
@@ -62,9 +65,34 @@ pub trait PromHandler {
     async fn do_query(&self, query: &PromQuery) -> Result<Output>;
 }
 
+/// Options of the PromQL queries through the Prometheus HTTP API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PromQueryOptions {
+    /// Lookback delta of queries without the `lookback_delta` parameter.
+    pub lookback_delta: Duration,
+    /// Max number of steps of a range query, larger queries are rejected.
+    pub max_steps: u64,
+}
+
+impl Default for PromQueryOptions {
+    fn default() -> Self {
+        Self {
+            lookback_delta: DEFAULT_LOOKBACK_DELTA,
+            max_steps: DEFAULT_MAX_STEPS,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct PromApiState {
+    query_handler: PromHandlerRef,
+    query_options: PromQueryOptions,
+}
+
 /// PromServer represents PrometheusServer which handles the compliance with prometheus HTTP API
 pub struct PromServer {
     query_handler: PromHandlerRef,
+    query_options: PromQueryOptions,
     shutdown_tx: Mutex<Option<Sender<()>>>,
     user_provider: Option<UserProviderRef>,
 }
@@ -73,6 +101,7 @@ impl PromServer {
     pub fn create_server(query_handler: PromHandlerRef) -> Box<Self> {
         Box::new(PromServer {
             query_handler,
+            query_options: PromQueryOptions::default(),
             shutdown_tx: Mutex::new(None),
             user_provider: None,
         })
@@ -83,13 +112,20 @@ impl PromServer {
         self.user_provider = Some(user_provider);
     }
 
+    pub fn set_query_options(&mut self, query_options: PromQueryOptions) {
+        self.query_options = query_options;
+    }
+
     pub fn make_app(&self) -> Router {
         // TODO(ruihang): implement format_query, series, labels, values, query_examplars and targets methods
 
         let router = Router::new()
             .route("/query", routing::post(instant_query).get(instant_query))
             .route("/query_range", routing::post(range_query).get(range_query))
-            .with_state(self.query_handler.clone());
+            .with_state(PromApiState {
+                query_handler: self.query_handler.clone(),
+                query_options: self.query_options,
+            });
 
         Router::new()
             .nest(&format!("/api/{PROM_API_VERSION}"), router)
@@ -356,7 +392,7 @@ pub struct InstantQuery {
 
 #[axum_macros::debug_handler]
 pub async fn instant_query(
-    State(_handler): State<PromHandlerRef>,
+    State(_state): State<PromApiState>,
     Query(_params): Query<InstantQuery>,
 ) -> Json<PromJsonResponse> {
     PromJsonResponse::error(
@@ -371,26 +407,69 @@ pub struct RangeQuery {
     start: Option<String>,
     end: Option<String>,
     step: Option<String>,
+    lookback_delta: Option<String>,
     timeout: Option<String>,
 }
 
 #[axum_macros::debug_handler]
 pub async fn range_query(
-    State(handler): State<PromHandlerRef>,
+    State(state): State<PromApiState>,
     Query(params): Query<RangeQuery>,
     Form(form_params): Form<RangeQuery>,
 ) -> Json<PromJsonResponse> {
+    let lookback = params
+        .lookback_delta
+        .or(form_params.lookback_delta)
+        .unwrap_or_else(|| format!("{}ms", state.query_options.lookback_delta.as_millis()));
     let prom_query = PromQuery {
         query: params.query.or(form_params.query).unwrap_or_default(),
         start: params.start.or(form_params.start).unwrap_or_default(),
         end: params.end.or(form_params.end).unwrap_or_default(),
         step: params.step.or(form_params.step).unwrap_or_default(),
+        lookback: Some(lookback),
     };
-    let result = handler.do_query(&prom_query).await;
+    if let Err(reason) = validate_range_query(&prom_query, state.query_options.max_steps) {
+        return PromJsonResponse::error("bad_data", reason);
+    }
+    let result = state.query_handler.do_query(&prom_query).await;
     let metric_name = retrieve_metric_name(&prom_query.query).unwrap_or_default();
     PromJsonResponse::from_query_result(result, metric_name).await
 }
 
+/// Checks the parameters of a range query in the order and words of Prometheus, so clients
+/// like Grafana show the errors as they do for Prometheus.
+fn validate_range_query(query: &PromQuery, max_steps: u64) -> std::result::Result<(), String> {
+    let start = QueryLanguageParser::parse_promql_timestamp(&query.start).map_err(|_| {
+        format!("invalid parameter \"start\": cannot parse {:?} to a valid timestamp", query.start)
+    })?;
+    let end = QueryLanguageParser::parse_promql_timestamp(&query.end).map_err(|_| {
+        format!("invalid parameter \"end\": cannot parse {:?} to a valid timestamp", query.end)
+    })?;
+    let step = QueryLanguageParser::parse_promql_duration(&query.step).map_err(|_| {
+        format!("invalid parameter \"step\": cannot parse {:?} to a valid duration", query.step)
+    })?;
+    QueryLanguageParser::validate_promql_range(start, end, step)?;
+
+    // Like Prometheus, the number of steps excludes the one at `start`.
+    let steps = end.duration_since(start).unwrap_or_default().as_nanos() / step.as_nanos();
+    if steps > max_steps as u128 {
+        return Err(format!(
+            "exceeded maximum resolution of {max_steps} points per timeseries. \
+             Try decreasing the query resolution (?step=XX)"
+        ));
+    }
+
+    if let Some(lookback) = &query.lookback {
+        QueryLanguageParser::parse_promql_duration(lookback).map_err(|_| {
+            format!(
+                "invalid parameter \"lookback_delta\": cannot parse {lookback:?} to a valid \
+                 duration"
+            )
+        })?;
+    }
+    Ok(())
+}
+
 fn retrieve_metric_name(promql: &str) -> Option<String> {
     let promql_expr = promql_parser::parser::parse(promql).ok()?;
     promql_expr_to_metric_name(promql_expr)
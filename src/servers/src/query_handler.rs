@@ -31,6 +31,8 @@ use std::sync::Arc;
 use api::prometheus::remote::{ReadRequest, WriteRequest};
 use async_trait::async_trait;
 use common_query::Output;
+use common_recordbatch::RecordBatch;
+use serde::{Deserialize, Serialize};
 use session::context::QueryContextRef;
 
 use crate::error::Result;
@@ -42,6 +44,14 @@ pub type OpentsdbProtocolHandlerRef = Arc<dyn OpentsdbProtocolHandler + Send + S
 pub type InfluxdbLineProtocolHandlerRef = Arc<dyn InfluxdbLineProtocolHandler + Send + Sync>;
 pub type PrometheusProtocolHandlerRef = Arc<dyn PrometheusProtocolHandler + Send + Sync>;
 pub type ScriptHandlerRef = Arc<dyn ScriptHandler + Send + Sync>;
+pub type RecordBatchInsertHandlerRef = Arc<dyn RecordBatchInsertHandler + Send + Sync>;
+pub type BulkLoadHandlerRef = Arc<dyn BulkLoadHandler + Send + Sync>;
+pub type BackupHandlerRef = Arc<dyn BackupHandler + Send + Sync>;
+pub type SstGcHandlerRef = Arc<dyn SstGcHandler + Send + Sync>;
+pub type CompactionStatusHandlerRef = Arc<dyn CompactionStatusHandler + Send + Sync>;
+pub type ReadinessHandlerRef = Arc<dyn ReadinessHandler + Send + Sync>;
+pub type TableStatsHandlerRef = Arc<dyn TableStatsHandler + Send + Sync>;
+pub type StorageVerifyHandlerRef = Arc<dyn StorageVerifyHandler + Send + Sync>;
 
 #[async_trait]
 pub trait ScriptHandler {
@@ -66,6 +76,14 @@ pub trait OpentsdbProtocolHandler {
     /// A successful request will not return a response.
     /// Only on error will the socket return a line of data.
     async fn exec(&self, data_point: &DataPoint, ctx: QueryContextRef) -> Result<()>;
+
+    /// Writes `data_points` in as few inserts as possible, fails if any of them fails.
+    async fn exec_batch(&self, data_points: &[DataPoint], ctx: QueryContextRef) -> Result<()> {
+        for data_point in data_points {
+            self.exec(data_point, ctx.clone()).await?;
+        }
+        Ok(())
+    }
 }
 
 pub struct PrometheusResponse {
@@ -83,3 +101,178 @@ pub trait PrometheusProtocolHandler {
     /// Handling push gateway requests
     async fn ingest_metrics(&self, metrics: Metrics) -> Result<()>;
 }
+
+#[async_trait]
+pub trait RecordBatchInsertHandler {
+    /// Writes the columns of `batch` to table `table_name`, returns the number of affected
+    /// rows. Columns must exist in the table and have types compatible with table columns.
+    async fn insert_record_batch(
+        &self,
+        table_name: &str,
+        region_number: u32,
+        batch: RecordBatch,
+        ctx: QueryContextRef,
+    ) -> Result<usize>;
+}
+
+/// Handles bulk loads, whose writes bypass the WAL and are invisible until committed.
+#[async_trait]
+pub trait BulkLoadHandler {
+    /// Begins a bulk load of table `table_name`, returns the id of the bulk load.
+    async fn begin_bulk_load(&self, table_name: &str, ctx: QueryContextRef) -> Result<String>;
+
+    /// Writes the columns of `batch` to the bulk load `load_id`, returns the number of
+    /// written rows. Columns are checked like [RecordBatchInsertHandler::insert_record_batch].
+    async fn write_bulk_load(
+        &self,
+        load_id: &str,
+        region_number: u32,
+        batch: RecordBatch,
+    ) -> Result<usize>;
+
+    /// Commits the bulk load `load_id`, all rows written to it are visible at once.
+    async fn commit_bulk_load(&self, load_id: &str) -> Result<()>;
+
+    /// Discards the bulk load `load_id` and all rows written to it.
+    async fn abort_bulk_load(&self, load_id: &str) -> Result<()>;
+}
+
+/// Backup progress of a region.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RegionBackupLag {
+    pub region_id: u64,
+    /// Number of files of the region backed up in order since it's opened.
+    pub high_water_mark: u64,
+    pub pending_files: usize,
+    pub pending_bytes: u64,
+    pub failed_files: usize,
+    /// Milliseconds the oldest pending file has been waiting for.
+    pub lag_ms: u64,
+}
+
+pub trait BackupHandler {
+    /// Returns the backup progress of all regions.
+    fn backup_status(&self) -> Result<Vec<RegionBackupLag>>;
+}
+
+/// Compaction requests waiting to be scheduled.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CompactionStatus {
+    /// Number of regions waiting for compaction.
+    pub queued_regions: usize,
+    /// Number of queued regions waiting for the maintenance window to open.
+    pub deferred_regions: usize,
+    /// Whether the maintenance window is open, absent if there is no window.
+    pub window_open: Option<bool>,
+}
+
+pub trait CompactionStatusHandler {
+    /// Returns the status of queued compactions.
+    fn compaction_status(&self) -> Result<CompactionStatus>;
+}
+
+/// Orphan SST files collected from a region.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RegionGcResult {
+    pub region_id: u64,
+    pub region_name: String,
+    /// Names of SST files never referenced by the manifest of the region.
+    pub orphan_files: Vec<String>,
+    pub orphan_bytes: u64,
+    /// Number of orphan files failed to delete.
+    pub failed_files: usize,
+    /// Why the region is skipped.
+    pub skipped: Option<String>,
+}
+
+#[async_trait]
+pub trait SstGcHandler {
+    /// Collects orphan SST files of all regions, files are only reported but not
+    /// deleted if `dry_run` is true.
+    async fn collect_garbage(&self, dry_run: bool) -> Result<Vec<RegionGcResult>>;
+}
+
+/// Approximate data stats of a table, rolled up from its regions.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TableStats {
+    pub catalog_name: String,
+    pub schema_name: String,
+    pub table_name: String,
+    /// Max timestamp in milliseconds of rows put into the table, absent if it has no data.
+    pub max_timestamp_millis: Option<i64>,
+    /// Rows in SSTs and memtables, updated or deleted rows are counted more than once
+    /// until compacted.
+    pub approximate_rows: u64,
+    pub regions: Vec<RegionDataStats>,
+}
+
+/// Approximate data stats of a region.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RegionDataStats {
+    pub region_id: u64,
+    pub max_timestamp_millis: Option<i64>,
+    pub approximate_rows: u64,
+}
+
+#[async_trait]
+pub trait TableStatsHandler {
+    /// Returns the data stats of all tables.
+    async fn table_stats(&self) -> Result<Vec<TableStats>>;
+}
+
+/// Sizes of an SST file in the manifest of its region and in the object store.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SstFileSize {
+    pub file_name: String,
+    /// Absent for files not in the manifest.
+    pub expected_size: Option<u64>,
+    /// Absent for files not in the object store.
+    pub actual_size: Option<u64>,
+}
+
+/// Result of checking the SST files of a region against its manifest.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RegionVerifyResult {
+    pub region_id: u64,
+    pub region_name: String,
+    /// Number of SST files in the current version of the region.
+    pub checked_files: usize,
+    /// Files in the manifest but not in the object store.
+    pub missing_files: Vec<SstFileSize>,
+    /// Files whose sizes in the object store differ from the ones in the manifest.
+    pub size_mismatches: Vec<SstFileSize>,
+    /// Files in the object store never referenced by the manifest.
+    pub orphan_files: Vec<SstFileSize>,
+    /// Why the region is skipped.
+    pub skipped: Option<String>,
+}
+
+/// Result of checking the SST files of all regions of a table.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StorageVerifyResult {
+    pub catalog_name: String,
+    pub schema_name: String,
+    pub table_name: String,
+    /// Whether any file is missing, mismatched or orphan, or any region is skipped.
+    pub has_problems: bool,
+    pub regions: Vec<RegionVerifyResult>,
+}
+
+#[async_trait]
+pub trait StorageVerifyHandler {
+    /// Checks that the SST files referenced by the manifests of the regions of a table
+    /// exist in the object store with the expected sizes, and lists orphan files. Nothing
+    /// is modified.
+    async fn verify_storage(
+        &self,
+        catalog_name: &str,
+        schema_name: &str,
+        table_name: &str,
+    ) -> Result<StorageVerifyResult>;
+}
+
+pub trait ReadinessHandler {
+    /// Returns whether the underlying instance has finished starting up, e.g. recovered
+    /// its tables from the WAL, and is able to serve requests.
+    fn is_ready(&self) -> bool;
+}
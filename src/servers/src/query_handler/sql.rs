@@ -19,6 +19,7 @@ use common_error::prelude::*;
 use common_query::Output;
 use datatypes::schema::Schema;
 use query::parser::PromQuery;
+use query::plan::LogicalPlan;
 use session::context::QueryContextRef;
 use sql::statements::statement::Statement;
 
@@ -50,6 +51,23 @@ pub trait SqlQueryHandler {
         query_ctx: QueryContextRef,
     ) -> std::result::Result<Option<Schema>, Self::Error>;
 
+    /// Plans the query of a prepared statement, its parameters are `$1`, `$2`...
+    /// placeholders. Returns `None` if the statement can't be prepared.
+    async fn do_prepare(
+        &self,
+        stmt: Statement,
+        query_ctx: QueryContextRef,
+    ) -> std::result::Result<Option<LogicalPlan>, Self::Error>;
+
+    /// Executes the plan of a prepared statement whose parameters have been bound,
+    /// `query` is the prepared query.
+    async fn do_exec_plan(
+        &self,
+        query: &str,
+        plan: LogicalPlan,
+        query_ctx: QueryContextRef,
+    ) -> std::result::Result<Output, Self::Error>;
+
     fn is_valid_schema(
         &self,
         catalog: &str,
@@ -116,6 +134,31 @@ where
             .context(error::DescribeStatementSnafu)
     }
 
+    async fn do_prepare(
+        &self,
+        stmt: Statement,
+        query_ctx: QueryContextRef,
+    ) -> Result<Option<LogicalPlan>> {
+        self.0
+            .do_prepare(stmt, query_ctx)
+            .await
+            .map_err(BoxedError::new)
+            .context(error::PrepareStatementSnafu)
+    }
+
+    async fn do_exec_plan(
+        &self,
+        query: &str,
+        plan: LogicalPlan,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        self.0
+            .do_exec_plan(query, plan, query_ctx)
+            .await
+            .map_err(BoxedError::new)
+            .context(error::ExecuteQuerySnafu { query })
+    }
+
     fn is_valid_schema(&self, catalog: &str, schema: &str) -> Result<bool> {
         self.0
             .is_valid_schema(catalog, schema)
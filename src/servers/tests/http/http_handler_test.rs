@@ -19,6 +19,7 @@ use axum::extract::{Json, Query, RawBody, State};
 use axum::Form;
 use common_telemetry::metric;
 use metrics::counter;
+use servers::http::handler::{AcceptFormat, SqlResponse};
 use servers::http::{handler as http_handler, script as script_handler, ApiState, JsonOutput};
 use session::context::UserInfo;
 use table::test_util::MemTable;
@@ -31,16 +32,18 @@ use crate::{
 #[tokio::test]
 async fn test_sql_not_provided() {
     let sql_handler = create_testing_sql_query_handler(MemTable::default_numbers_table());
-    let Json(json) = http_handler::sql(
+    let SqlResponse::Json(json) = http_handler::sql(
         State(ApiState {
             sql_handler,
             script_handler: None,
+            max_result_rows: None,
         }),
         Query(http_handler::SqlQuery::default()),
         axum::Extension(UserInfo::default()),
+        AcceptFormat::default(),
         Form(http_handler::SqlQuery::default()),
     )
-    .await;
+    .await else { unreachable!() };
     assert!(!json.success());
     assert_eq!(
         Some(&"sql parameter is required.".to_string()),
@@ -56,16 +59,18 @@ async fn test_sql_output_rows() {
     let query = create_query();
     let sql_handler = create_testing_sql_query_handler(MemTable::default_numbers_table());
 
-    let Json(json) = http_handler::sql(
+    let SqlResponse::Json(json) = http_handler::sql(
         State(ApiState {
             sql_handler,
             script_handler: None,
+            max_result_rows: None,
         }),
         query,
         axum::Extension(UserInfo::default()),
+        AcceptFormat::default(),
         Form(http_handler::SqlQuery::default()),
     )
-    .await;
+    .await else { unreachable!() };
     assert!(json.success(), "{json:?}");
     assert!(json.error().is_none());
     match &json.output().expect("assertion failed")[0] {
@@ -102,16 +107,18 @@ async fn test_sql_form() {
     let form = create_form();
     let sql_handler = create_testing_sql_query_handler(MemTable::default_numbers_table());
 
-    let Json(json) = http_handler::sql(
+    let SqlResponse::Json(json) = http_handler::sql(
         State(ApiState {
             sql_handler,
             script_handler: None,
+            max_result_rows: None,
         }),
         Query(http_handler::SqlQuery::default()),
         axum::Extension(UserInfo::default()),
+        AcceptFormat::default(),
         form,
     )
-    .await;
+    .await else { unreachable!() };
     assert!(json.success(), "{json:?}");
     assert!(json.error().is_none());
     match &json.output().expect("assertion failed")[0] {
@@ -162,6 +169,7 @@ async fn insert_script(
         State(ApiState {
             sql_handler: sql_handler.clone(),
             script_handler: Some(script_handler.clone()),
+            max_result_rows: None,
         }),
         invalid_query,
         body,
@@ -177,6 +185,7 @@ async fn insert_script(
         State(ApiState {
             sql_handler: sql_handler.clone(),
             script_handler: Some(script_handler.clone()),
+            max_result_rows: None,
         }),
         exec,
         body,
@@ -207,6 +216,7 @@ def test(n) -> vector[i64]:
         State(ApiState {
             sql_handler,
             script_handler: Some(script_handler),
+            max_result_rows: None,
         }),
         exec,
     )
@@ -274,6 +284,7 @@ def test(n, **params)  -> vector[i64]:
         State(ApiState {
             sql_handler,
             script_handler: Some(script_handler),
+            max_result_rows: None,
         }),
         exec,
     )
@@ -340,6 +351,7 @@ fn create_query() -> Query<http_handler::SqlQuery> {
     Query(http_handler::SqlQuery {
         sql: Some("select sum(uint32s) from numbers limit 20".to_string()),
         db: None,
+        format: None,
     })
 }
 
@@ -347,6 +359,7 @@ fn create_form() -> Form<http_handler::SqlQuery> {
     Form(http_handler::SqlQuery {
         sql: Some("select sum(uint32s) from numbers limit 20".to_string()),
         db: None,
+        format: None,
     })
 }
 
@@ -22,8 +22,10 @@ use axum_test_helper::TestClient;
 use common_query::Output;
 use datatypes::schema::Schema;
 use query::parser::PromQuery;
+use query::plan::LogicalPlan;
 use servers::error::{Error, Result};
 use servers::http::{HttpOptions, HttpServer};
+use servers::influxdb::server::InfluxdbServer;
 use servers::influxdb::InfluxdbRequest;
 use servers::query_handler::grpc::GrpcQueryHandler;
 use servers::query_handler::sql::SqlQueryHandler;
@@ -87,6 +89,23 @@ impl SqlQueryHandler for DummyInstance {
         unimplemented!()
     }
 
+    async fn do_prepare(
+        &self,
+        _stmt: sql::statements::statement::Statement,
+        _query_ctx: QueryContextRef,
+    ) -> Result<Option<LogicalPlan>> {
+        unimplemented!()
+    }
+
+    async fn do_exec_plan(
+        &self,
+        _query: &str,
+        _plan: LogicalPlan,
+        _query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        unimplemented!()
+    }
+
     fn is_valid_schema(&self, _catalog: &str, _schema: &str) -> Result<bool> {
         Ok(true)
     }
@@ -196,3 +215,46 @@ async fn test_influxdb_write() {
         ]
     );
 }
+
+#[tokio::test]
+async fn test_influxdb_server_write() {
+    let (tx, mut rx) = mpsc::channel(100);
+    let mut server = InfluxdbServer::create_server(Arc::new(DummyInstance { tx: Arc::new(tx) }));
+    server.set_user_provider(Arc::new(MockUserProvider::default()));
+    let client = TestClient::new(server.make_app());
+
+    let result = client.get("/v1/influxdb/ping").send().await;
+    assert_eq!(result.status(), 204);
+
+    let result = client
+        .post("/v1/influxdb/write?db=public&precision=ms")
+        .body("monitor,host=host1 cpu=1.2 1664370459457\nmonitor,host=host2 cpu=1.3 1664370459458")
+        .header(http::header::AUTHORIZATION, "token greptime:greptime")
+        .send()
+        .await;
+    assert_eq!(result.status(), 204);
+
+    // malformed line
+    let result = client
+        .post("/v1/influxdb/write?db=public")
+        .body("monitor,   host=host1 cpu=1.2 1664370459457010101")
+        .header(http::header::AUTHORIZATION, "token greptime:greptime")
+        .send()
+        .await;
+    assert_eq!(result.status(), 400);
+    assert!(result.text().await.contains("line 1"));
+
+    // no auth
+    let result = client
+        .post("/v1/influxdb/write?db=public")
+        .body("monitor,host=host1 cpu=1.2 1664370459457010101")
+        .send()
+        .await;
+    assert_eq!(result.status(), 401);
+
+    let mut metrics = vec![];
+    while let Ok(s) = rx.try_recv() {
+        metrics.push(s);
+    }
+    assert_eq!(metrics, vec![("public".to_string(), "monitor".to_string())]);
+}
@@ -16,20 +16,24 @@ use std::sync::Arc;
 
 use api::v1::greptime_request::Request;
 use async_trait::async_trait;
-use axum::Router;
+use axum::{http, Router};
 use axum_test_helper::TestClient;
 use common_query::Output;
 use datatypes::schema::Schema;
 use query::parser::PromQuery;
+use query::plan::LogicalPlan;
 use servers::error::{self, Result};
 use servers::http::{HttpOptions, HttpServer};
 use servers::opentsdb::codec::DataPoint;
+use servers::opentsdb::http_server::OpentsdbHttpServer;
 use servers::query_handler::grpc::GrpcQueryHandler;
 use servers::query_handler::sql::SqlQueryHandler;
 use servers::query_handler::OpentsdbProtocolHandler;
 use session::context::QueryContextRef;
 use tokio::sync::mpsc;
 
+use crate::auth::MockUserProvider;
+
 struct DummyInstance {
     tx: mpsc::Sender<String>,
 }
@@ -85,6 +89,23 @@ impl SqlQueryHandler for DummyInstance {
         unimplemented!()
     }
 
+    async fn do_prepare(
+        &self,
+        _stmt: sql::statements::statement::Statement,
+        _query_ctx: QueryContextRef,
+    ) -> Result<Option<LogicalPlan>> {
+        unimplemented!()
+    }
+
+    async fn do_exec_plan(
+        &self,
+        _query: &str,
+        _plan: LogicalPlan,
+        _query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        unimplemented!()
+    }
+
     fn is_valid_schema(&self, _catalog: &str, _schema: &str) -> Result<bool> {
         Ok(true)
     }
@@ -227,6 +248,143 @@ async fn test_opentsdb_debug_put() {
     );
 }
 
+// Body sent by tcollector with `--http`, which batches the data points of its collectors.
+const TCOLLECTOR_PAYLOAD: &str = r#"[
+    {"metric": "proc.loadavg.1min", "timestamp": 1680151200, "value": 0.36, "tags": {"host": "web01"}},
+    {"metric": "proc.stat.cpu", "timestamp": 1680151200, "value": 2846152, "tags": {"host": "web01", "type": "user", "cpu.core": "0"}},
+    {"metric": "df.bytes.free", "timestamp": 1680151215, "value": 40870154240, "tags": {"host": "web01", "mount": "/", "fstype": "ext4"}},
+    {"metric": "iostat.disk.read_requests", "timestamp": 1680151215250, "value": 103212, "tags": {"host": "web01", "dev": "sda"}}
+]"#;
+
+#[tokio::test]
+async fn test_opentsdb_put_tcollector() {
+    let (tx, mut rx) = mpsc::channel(100);
+
+    let app = make_test_app(tx);
+    let client = TestClient::new(app);
+
+    let result = client
+        .post("/v1/opentsdb/api/put")
+        .body(TCOLLECTOR_PAYLOAD)
+        .send()
+        .await;
+    assert_eq!(result.status(), 204);
+
+    let result = client
+        .post("/v1/opentsdb/api/put?details")
+        .body(format!(
+            "[{},{}]",
+            create_data_point("m1"),
+            r#"{"metric": "m2", "timestamp": -1, "value": 1, "tags": {"host": "web01"}}"#
+        ))
+        .send()
+        .await;
+    assert_eq!(result.status(), 200);
+    assert_eq!(result.text().await, "{\"success\":1,\"failed\":1,\"errors\":[{\"datapoint\":{\"metric\":\"m2\",\"timestamp\":-1,\"value\":1.0,\"tags\":{\"host\":\"web01\"}},\"error\":\"Invalid query: invalid timestamp: -1\"}]}");
+
+    // invalid timestamps fail the whole request without details
+    let result = client
+        .post("/v1/opentsdb/api/put")
+        .body(r#"{"metric": "m3", "timestamp": 10000000000000, "value": 1, "tags": {}}"#)
+        .send()
+        .await;
+    assert_eq!(result.status(), 400);
+    assert_eq!(
+        result.text().await,
+        "{\"error\":\"Invalid query: invalid timestamp: 10000000000000\"}"
+    );
+
+    let mut metrics = vec![];
+    while let Ok(s) = rx.try_recv() {
+        metrics.push(s);
+    }
+    assert_eq!(
+        metrics,
+        vec![
+            "proc.loadavg.1min".to_string(),
+            "proc.stat.cpu".to_string(),
+            "df.bytes.free".to_string(),
+            "iostat.disk.read_requests".to_string(),
+            "m1".to_string()
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_opentsdb_http_server_put() {
+    let (tx, mut rx) = mpsc::channel(100);
+    let mut server = OpentsdbHttpServer::create_server(Arc::new(DummyInstance { tx }));
+    server.set_user_provider(Arc::new(MockUserProvider::default()));
+    let client = TestClient::new(server.make_app());
+
+    // single data point put
+    let result = client
+        .post("/api/put?db=public")
+        .body(create_data_point("m1"))
+        .header(
+            http::header::AUTHORIZATION,
+            "Basic Z3JlcHRpbWU6Z3JlcHRpbWU=",
+        )
+        .send()
+        .await;
+    assert_eq!(result.status(), 204);
+
+    // multiple data point put
+    let result = client
+        .post("/api/put?db=public")
+        .body(format!(
+            "[{},{}]",
+            create_data_point("m2"),
+            create_data_point("m3")
+        ))
+        .header(
+            http::header::AUTHORIZATION,
+            "Basic Z3JlcHRpbWU6Z3JlcHRpbWU=",
+        )
+        .send()
+        .await;
+    assert_eq!(result.status(), 204);
+
+    // the invalid data point doesn't fail others
+    let result = client
+        .post("/api/put?db=public&details")
+        .body(format!(
+            "[{},{}]",
+            create_data_point("m4"),
+            r#"{"metric": "m5", "timestamp": -1, "value": 1, "tags": {"host": "web01"}}"#
+        ))
+        .header(
+            http::header::AUTHORIZATION,
+            "Basic Z3JlcHRpbWU6Z3JlcHRpbWU=",
+        )
+        .send()
+        .await;
+    assert_eq!(result.status(), 200);
+    assert_eq!(result.text().await, "{\"success\":1,\"failed\":1,\"errors\":[{\"datapoint\":{\"metric\":\"m5\",\"timestamp\":-1,\"value\":1.0,\"tags\":{\"host\":\"web01\"}},\"error\":\"Invalid query: invalid timestamp: -1\"}]}");
+
+    // no auth
+    let result = client
+        .post("/api/put?db=public")
+        .body(create_data_point("m6"))
+        .send()
+        .await;
+    assert_eq!(result.status(), 401);
+
+    let mut metrics = vec![];
+    while let Ok(s) = rx.try_recv() {
+        metrics.push(s);
+    }
+    assert_eq!(
+        metrics,
+        vec![
+            "m1".to_string(),
+            "m2".to_string(),
+            "m3".to_string(),
+            "m4".to_string()
+        ]
+    );
+}
+
 fn create_data_point(metric: &str) -> String {
     format!(
         r#"{{
@@ -0,0 +1,258 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use axum_test_helper::TestClient;
+use common_query::Output;
+use common_recordbatch::RecordBatch;
+use datatypes::prelude::ConcreteDataType;
+use datatypes::schema::{ColumnSchema, Schema};
+use datatypes::vectors::{Float64Vector, StringVector, TimestampMillisecondVector, VectorRef};
+use query::parser::PromQuery;
+use serde_json::Value;
+use servers::error::Result;
+use servers::prom::{PromHandler, PromQueryOptions, PromServer};
+use table::metadata::{TableInfoBuilder, TableMetaBuilder};
+use table::test_util::MemTable;
+use tokio::sync::mpsc;
+
+use crate::create_testing_prom_handler;
+
+struct DummyPromHandler {
+    tx: mpsc::Sender<PromQuery>,
+}
+
+#[async_trait]
+impl PromHandler for DummyPromHandler {
+    async fn do_query(&self, query: &PromQuery) -> Result<Output> {
+        let _ = self.tx.send(query.clone()).await;
+        Ok(Output::AffectedRows(0))
+    }
+}
+
+fn make_test_app(tx: mpsc::Sender<PromQuery>) -> TestClient {
+    let mut server = PromServer::create_server(Arc::new(DummyPromHandler { tx }));
+    server.set_query_options(PromQueryOptions {
+        lookback_delta: Duration::from_secs(30),
+        max_steps: 100,
+    });
+    TestClient::new(server.make_app())
+}
+
+async fn query_range(client: &TestClient, params: &str) -> Value {
+    let result = client
+        .get(&format!("/api/v1/query_range?query=up&{params}"))
+        .send()
+        .await;
+    assert_eq!(result.status(), 200);
+    serde_json::from_str(&result.text().await).unwrap()
+}
+
+#[tokio::test]
+async fn test_range_query_validation() {
+    let (tx, mut rx) = mpsc::channel(10);
+    let client = make_test_app(tx);
+
+    let cases = [
+        (
+            "start=100&end=0&step=10",
+            "end timestamp must not be before start time",
+        ),
+        (
+            "start=0&end=100&step=0",
+            "zero or negative query resolution step widths are not accepted. Try a positive integer",
+        ),
+        (
+            "start=0&end=1010&step=10",
+            "exceeded maximum resolution of 100 points per timeseries. Try decreasing the query resolution (?step=XX)",
+        ),
+        (
+            "start=x&end=100&step=10",
+            "invalid parameter \"start\": cannot parse \"x\" to a valid timestamp",
+        ),
+        (
+            "start=0&end=100&step=10&lookback_delta=x",
+            "invalid parameter \"lookback_delta\": cannot parse \"x\" to a valid duration",
+        ),
+    ];
+    for (params, error) in cases {
+        let body = query_range(&client, params).await;
+        assert_eq!(body["status"], "error", "{params}");
+        assert_eq!(body["errorType"], "bad_data", "{params}");
+        assert_eq!(body["error"], error, "{params}");
+    }
+    // Invalid queries are not executed.
+    assert!(rx.try_recv().is_err());
+
+    // 100 steps after the start are allowed.
+    let _ = query_range(&client, "start=0&end=1000&step=10").await;
+    assert!(rx.recv().await.is_some());
+}
+
+#[tokio::test]
+async fn test_range_query_lookback_delta() {
+    let (tx, mut rx) = mpsc::channel(10);
+    let client = make_test_app(tx);
+
+    // The default of the server.
+    let _ = query_range(&client, "start=0&end=100&step=10").await;
+    let query = rx.recv().await.unwrap();
+    assert_eq!(Some("30000ms".to_string()), query.lookback);
+
+    let _ = query_range(&client, "start=0&end=100&step=10&lookback_delta=1m").await;
+    let query = rx.recv().await.unwrap();
+    assert_eq!(Some("1m".to_string()), query.lookback);
+}
+
+/// A metric of two series with gaps, the samples are never on the boundaries of the windows
+/// at the steps of the queries below.
+fn fixture_table() -> MemTable {
+    let column_schemas = vec![
+        ColumnSchema::new("host", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new(
+            "ts",
+            ConcreteDataType::timestamp_millisecond_datatype(),
+            false,
+        )
+        .with_time_index(true),
+        ColumnSchema::new("val", ConcreteDataType::float64_datatype(), true),
+    ];
+    let schema = Arc::new(Schema::new(column_schemas));
+    let columns: Vec<VectorRef> = vec![
+        Arc::new(StringVector::from(vec!["a", "a", "a", "a", "a", "b", "b"])),
+        Arc::new(TimestampMillisecondVector::from_slice([
+            5000, 15000, 25000, 35000, 95000, 5000, 45000,
+        ])),
+        Arc::new(Float64Vector::from_slice([
+            1.0, 2.0, 3.0, 4.0, 10.0, 5.0, 7.0,
+        ])),
+    ];
+    let recordbatch = RecordBatch::new(schema.clone(), columns).unwrap();
+
+    let meta = TableMetaBuilder::default()
+        .schema(schema)
+        .primary_key_indices(vec![0])
+        .value_indices(vec![2])
+        .next_column_id(3)
+        .build()
+        .unwrap();
+    let info = TableInfoBuilder::default()
+        .name("fixture")
+        .meta(meta)
+        .build()
+        .unwrap();
+    MemTable::from_table_info(Arc::new(info), recordbatch)
+}
+
+/// Samples of the series in the `result` of a matrix by their `host` labels. Other labels
+/// are ignored as Prometheus drops `__name__` from the results of functions.
+fn samples_by_host(result: &Value) -> BTreeMap<String, Vec<(f64, String)>> {
+    result
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|series| {
+            let host = series["metric"]["host"].as_str().unwrap().to_string();
+            let mut samples = series["values"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|sample| {
+                    let ts = sample[0].as_f64().unwrap();
+                    (ts, sample[1].as_str().unwrap().to_string())
+                })
+                .collect::<Vec<_>>();
+            samples.sort_by(|a, b| a.0.total_cmp(&b.0));
+            (host, samples)
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_range_query_fixture() {
+    let mut server = PromServer::create_server(create_testing_prom_handler(fixture_table()));
+    server.set_query_options(PromQueryOptions {
+        lookback_delta: Duration::from_secs(300),
+        max_steps: 100,
+    });
+    let client = TestClient::new(server.make_app());
+
+    // The results of Prometheus for the same samples and queries.
+    let cases = [
+        (
+            "avg_over_time(fixture[30s])",
+            "",
+            r#"[
+                {"metric":{"host":"a"},"values":[[10,"1"],[20,"1.5"],[30,"2"],[40,"3"],[50,"3.5"],[60,"4"],[100,"10"]]},
+                {"metric":{"host":"b"},"values":[[10,"5"],[20,"5"],[30,"5"],[50,"7"],[60,"7"],[70,"7"]]}
+            ]"#,
+        ),
+        // The windows of range functions are the ranges of the selectors, regardless of the
+        // lookback delta.
+        (
+            "avg_over_time(fixture[30s])",
+            "&lookback_delta=1s",
+            r#"[
+                {"metric":{"host":"a"},"values":[[10,"1"],[20,"1.5"],[30,"2"],[40,"3"],[50,"3.5"],[60,"4"],[100,"10"]]},
+                {"metric":{"host":"b"},"values":[[10,"5"],[20,"5"],[30,"5"],[50,"7"],[60,"7"],[70,"7"]]}
+            ]"#,
+        ),
+        (
+            "max_over_time(fixture[30s])",
+            "",
+            r#"[
+                {"metric":{"host":"a"},"values":[[10,"1"],[20,"2"],[30,"3"],[40,"4"],[50,"4"],[60,"4"],[100,"10"]]},
+                {"metric":{"host":"b"},"values":[[10,"5"],[20,"5"],[30,"5"],[50,"7"],[60,"7"],[70,"7"]]}
+            ]"#,
+        ),
+        (
+            "fixture",
+            "",
+            r#"[
+                {"metric":{"__name__":"fixture","host":"a"},"values":[[10,"1"],[20,"2"],[30,"3"],[40,"4"],[50,"4"],[60,"4"],[70,"4"],[80,"4"],[90,"4"],[100,"10"]]},
+                {"metric":{"__name__":"fixture","host":"b"},"values":[[10,"5"],[20,"5"],[30,"5"],[40,"5"],[50,"7"],[60,"7"],[70,"7"],[80,"7"],[90,"7"],[100,"7"]]}
+            ]"#,
+        ),
+        (
+            "fixture",
+            "&lookback_delta=20s",
+            r#"[
+                {"metric":{"__name__":"fixture","host":"a"},"values":[[10,"1"],[20,"2"],[30,"3"],[40,"4"],[50,"4"],[100,"10"]]},
+                {"metric":{"__name__":"fixture","host":"b"},"values":[[10,"5"],[20,"5"],[50,"7"],[60,"7"]]}
+            ]"#,
+        ),
+    ];
+    for (query, params, expected) in cases {
+        let result = client
+            .get(&format!(
+                "/api/v1/query_range?query={query}&start=0&end=100&step=10{params}"
+            ))
+            .send()
+            .await;
+        assert_eq!(result.status(), 200);
+        let body: Value = serde_json::from_str(&result.text().await).unwrap();
+        assert_eq!(body["status"], "success", "{query}{params}: {body}");
+
+        let expected: Value = serde_json::from_str(expected).unwrap();
+        assert_eq!(
+            samples_by_host(&expected),
+            samples_by_host(&body["data"]["result"]),
+            "{query}{params}"
+        );
+    }
+}
@@ -25,6 +25,7 @@ use common_query::Output;
 use datatypes::schema::Schema;
 use prost::Message;
 use query::parser::PromQuery;
+use query::plan::LogicalPlan;
 use servers::error::{Error, Result};
 use servers::http::{HttpOptions, HttpServer};
 use servers::prometheus;
@@ -110,6 +111,23 @@ impl SqlQueryHandler for DummyInstance {
         unimplemented!()
     }
 
+    async fn do_prepare(
+        &self,
+        _stmt: sql::statements::statement::Statement,
+        _query_ctx: QueryContextRef,
+    ) -> Result<Option<LogicalPlan>> {
+        unimplemented!()
+    }
+
+    async fn do_exec_plan(
+        &self,
+        _query: &str,
+        _plan: LogicalPlan,
+        _query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        unimplemented!()
+    }
+
     fn is_valid_schema(&self, _catalog: &str, _schema: &str) -> Result<bool> {
         Ok(true)
     }
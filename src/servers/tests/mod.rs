@@ -24,14 +24,16 @@ use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
 use common_query::Output;
 use datatypes::schema::Schema;
 use query::parser::{PromQuery, QueryLanguageParser, QueryStatement};
+use query::plan::LogicalPlan;
 use query::{QueryEngineFactory, QueryEngineRef};
 use script::engine::{CompileContext, EvalContext, Script, ScriptEngine};
 use script::python::{PyEngine, PyScript};
 use servers::error::{Error, NotSupportedSnafu, Result};
+use servers::prom::{PromHandler, PromHandlerRef};
 use servers::query_handler::grpc::{GrpcQueryHandler, ServerGrpcQueryHandlerRef};
 use servers::query_handler::sql::{ServerSqlQueryHandlerRef, SqlQueryHandler};
 use servers::query_handler::{ScriptHandler, ScriptHandlerRef};
-use session::context::QueryContextRef;
+use session::context::{QueryContext, QueryContextRef};
 use snafu::ensure;
 use sql::statements::statement::Statement;
 use table::test_util::MemTable;
@@ -106,6 +108,33 @@ impl SqlQueryHandler for DummyInstance {
         }
     }
 
+    async fn do_prepare(
+        &self,
+        stmt: Statement,
+        query_ctx: QueryContextRef,
+    ) -> Result<Option<LogicalPlan>> {
+        if let Statement::Query(_) = stmt {
+            let plan = self
+                .query_engine
+                .planner()
+                .plan(QueryStatement::Sql(stmt), query_ctx)
+                .await
+                .unwrap();
+            Ok(Some(plan))
+        } else {
+            Ok(None)
+        }
+    }
+
+    async fn do_exec_plan(
+        &self,
+        _query: &str,
+        plan: LogicalPlan,
+        _query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        Ok(self.query_engine.execute(&plan).await.unwrap())
+    }
+
     fn is_valid_schema(&self, catalog: &str, schema: &str) -> Result<bool> {
         Ok(catalog == DEFAULT_CATALOG_NAME && schema == DEFAULT_SCHEMA_NAME)
     }
@@ -176,6 +205,7 @@ impl GrpcQueryHandler for DummyInstance {
                             start: promql.start,
                             end: promql.end,
                             step: promql.step,
+                            lookback: None,
                         };
                         let mut result =
                             SqlQueryHandler::do_promql_query(self, &prom_query, ctx).await;
@@ -195,6 +225,20 @@ impl GrpcQueryHandler for DummyInstance {
     }
 }
 
+#[async_trait]
+impl PromHandler for DummyInstance {
+    async fn do_query(&self, query: &PromQuery) -> Result<Output> {
+        let stmt = QueryLanguageParser::parse_promql(query).unwrap();
+        let plan = self
+            .query_engine
+            .planner()
+            .plan(stmt, QueryContext::arc())
+            .await
+            .unwrap();
+        Ok(self.query_engine.execute(&plan).await.unwrap())
+    }
+}
+
 fn create_testing_instance(table: MemTable) -> DummyInstance {
     let table_name = table.table_name().to_string();
     let table = Arc::new(table);
@@ -226,3 +270,7 @@ fn create_testing_sql_query_handler(table: MemTable) -> ServerSqlQueryHandlerRef
 fn create_testing_grpc_query_handler(table: MemTable) -> ServerGrpcQueryHandlerRef {
     Arc::new(create_testing_instance(table)) as _
 }
+
+fn create_testing_prom_handler(table: MemTable) -> PromHandlerRef {
+    Arc::new(create_testing_instance(table)) as _
+}
@@ -485,6 +485,61 @@ async fn test_query_prepared() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_query_prepared_with_params() -> Result<()> {
+    common_telemetry::init_default_ut_logging();
+
+    let table = MemTable::default_numbers_table();
+    let mysql_server = create_mysql_server(table, Default::default())?;
+    let listening = "127.0.0.1:0".parse::<SocketAddr>().unwrap();
+    let server_addr = mysql_server.start(listening).await.unwrap();
+
+    let mut connection = create_connection_default_db_name(server_addr.port(), false)
+        .await
+        .unwrap();
+
+    // String parameters are escaped, a placeholder inside a string literal is not a parameter.
+    let statement = connection
+        .prep("SELECT uint32s FROM numbers WHERE uint32s = ? AND ? = 'it''s' AND '?' = '?'")
+        .await
+        .unwrap();
+    assert_eq!(2, statement.num_params());
+    for expected in [1u32, 10] {
+        let rows: Vec<u32> = connection
+            .exec(&statement, (expected, "it's"))
+            .await
+            .unwrap();
+        assert_eq!(vec![expected], rows);
+    }
+    let rows: Vec<u32> = connection
+        .exec(&statement, (1u32, "it' OR '1' = '1"))
+        .await
+        .unwrap();
+    assert!(rows.is_empty());
+
+    // NULL never equals to any value.
+    let rows: Vec<u32> = connection
+        .exec(&statement, (mysql_async::Value::NULL, "it's"))
+        .await
+        .unwrap();
+    assert!(rows.is_empty());
+
+    // Parameters are checked against the types inferred from the query.
+    let result: mysql_async::Result<Vec<u32>> = connection.exec(&statement, ("abc", "it's")).await;
+    let err = result.unwrap_err().to_string();
+    assert!(
+        err.contains("parameter $1 expects type UInt32, actual: Utf8"),
+        "{err}"
+    );
+
+    // The statement is not usable after being closed.
+    connection.close(statement.clone()).await.unwrap();
+    let result: mysql_async::Result<Vec<u32>> = connection.exec(&statement, (1u32, "it's")).await;
+    assert!(result.is_err());
+
+    Ok(())
+}
+
 async fn test_prepare_all_type(
     column_schemas: Vec<ColumnSchema>,
     columns: Vec<VectorRef>,
@@ -19,6 +19,10 @@ use std::sync::Arc;
 use arc_swap::ArcSwap;
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
 use common_telemetry::debug;
+use common_time::TimeZone;
+
+use crate::error::Result;
+use crate::variables::SessionVariables;
 
 pub type QueryContextRef = Arc<QueryContext>;
 pub type ConnInfoRef = Arc<ConnInfo>;
@@ -27,6 +31,10 @@ pub type ConnInfoRef = Arc<ConnInfo>;
 pub struct QueryContext {
     current_catalog: ArcSwap<String>,
     current_schema: ArcSwap<String>,
+    variables: ArcSwap<SessionVariables>,
+    current_user: ArcSwap<UserInfo>,
+    /// Protocol of the connection, `None` for internal queries.
+    channel: Option<Channel>,
 }
 
 impl Default for QueryContext {
@@ -55,6 +63,9 @@ impl QueryContext {
         Self {
             current_catalog: ArcSwap::new(Arc::new(DEFAULT_CATALOG_NAME.to_string())),
             current_schema: ArcSwap::new(Arc::new(DEFAULT_SCHEMA_NAME.to_string())),
+            variables: ArcSwap::default(),
+            current_user: ArcSwap::default(),
+            channel: None,
         }
     }
 
@@ -62,9 +73,17 @@ impl QueryContext {
         Self {
             current_catalog: ArcSwap::new(Arc::new(catalog.to_string())),
             current_schema: ArcSwap::new(Arc::new(schema.to_string())),
+            variables: ArcSwap::default(),
+            current_user: ArcSwap::default(),
+            channel: None,
         }
     }
 
+    pub fn with_channel(mut self, channel: Channel) -> Self {
+        self.channel = Some(channel);
+        self
+    }
+
     pub fn current_schema(&self) -> String {
         self.current_schema.load().as_ref().clone()
     }
@@ -88,6 +107,37 @@ impl QueryContext {
             catalog, last
         )
     }
+
+    pub fn variables(&self) -> Arc<SessionVariables> {
+        self.variables.load_full()
+    }
+
+    /// Sets session variable `name` to `value`, returns false if the variable is unknown.
+    pub fn set_variable(&self, name: &str, value: &str) -> Result<bool> {
+        let mut variables = self.variables().as_ref().clone();
+        let known = variables.set(name, value)?;
+        if known {
+            debug!("set session variable {} to {:?}", name, value);
+            self.variables.store(Arc::new(variables));
+        }
+        Ok(known)
+    }
+
+    pub fn time_zone(&self) -> Option<TimeZone> {
+        self.variables.load().time_zone
+    }
+
+    pub fn current_user(&self) -> Arc<UserInfo> {
+        self.current_user.load_full()
+    }
+
+    pub fn set_current_user(&self, user_info: UserInfo) {
+        self.current_user.store(Arc::new(user_info));
+    }
+
+    pub fn channel(&self) -> Option<Channel> {
+        self.channel
+    }
 }
 
 pub const DEFAULT_USERNAME: &str = "greptime";
@@ -115,6 +165,12 @@ impl UserInfo {
             username: username.into(),
         }
     }
+
+    /// The default user is the administrator, which is also the user of all the
+    /// connections if authentication is disabled.
+    pub fn is_admin(&self) -> bool {
+        self.username == DEFAULT_USERNAME
+    }
 }
 
 pub struct ConnInfo {
@@ -131,7 +187,7 @@ impl ConnInfo {
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Channel {
     Grpc,
     Http,
@@ -142,11 +198,42 @@ pub enum Channel {
     Prometheus,
 }
 
+impl Display for Channel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Channel::Grpc => "grpc",
+            Channel::Http => "http",
+            Channel::Mysql => "mysql",
+            Channel::Postgres => "postgres",
+            Channel::Opentsdb => "opentsdb",
+            Channel::Influxdb => "influxdb",
+            Channel::Prometheus => "prometheus",
+        };
+        write!(f, "{name}")
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::context::{Channel, UserInfo};
     use crate::Session;
 
+    #[test]
+    fn test_session_variables() {
+        let session = Session::new("127.0.0.1:9000".parse().unwrap(), Channel::Mysql);
+        let ctx = session.context();
+        assert_eq!(None, ctx.time_zone());
+        assert!(ctx.set_variable("time_zone", "+08:00").unwrap());
+        assert_eq!("+08:00", ctx.time_zone().unwrap().to_string());
+        assert!(!ctx.set_variable("autocommit", "1").unwrap());
+
+        // Variables are shared by all references to the session context.
+        assert_eq!(
+            "+08:00",
+            session.context().variables().get("time_zone").unwrap()
+        );
+    }
+
     #[test]
     fn test_session() {
         let session = Session::new("127.0.0.1:9000".parse().unwrap(), Channel::Mysql);
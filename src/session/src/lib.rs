@@ -13,25 +13,23 @@
 // limitations under the License.
 
 pub mod context;
+pub mod error;
+pub mod variables;
 
 use std::net::SocketAddr;
 use std::sync::Arc;
 
-use arc_swap::ArcSwap;
-
 use crate::context::{Channel, ConnInfo, ConnInfoRef, QueryContext, QueryContextRef, UserInfo};
 
 pub struct Session {
     query_ctx: QueryContextRef,
-    user_info: ArcSwap<UserInfo>,
     conn_info: ConnInfoRef,
 }
 
 impl Session {
     pub fn new(addr: SocketAddr, channel: Channel) -> Self {
         Session {
-            query_ctx: Arc::new(QueryContext::new()),
-            user_info: ArcSwap::new(Arc::new(UserInfo::default())),
+            query_ctx: Arc::new(QueryContext::new().with_channel(channel)),
             conn_info: Arc::new(ConnInfo::new(addr, channel)),
         }
     }
@@ -43,9 +41,9 @@ impl Session {
         self.conn_info.clone()
     }
     pub fn user_info(&self) -> Arc<UserInfo> {
-        self.user_info.load().clone()
+        self.query_ctx.current_user()
     }
     pub fn set_user_info(&self, user_info: UserInfo) {
-        self.user_info.store(Arc::new(user_info));
+        self.query_ctx.set_current_user(user_info);
     }
 }
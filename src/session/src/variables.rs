@@ -0,0 +1,274 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Per-connection session variables, set by `SET` statements and consulted by query execution.
+
+use std::str::FromStr;
+use std::time::Duration;
+
+use common_time::TimeZone;
+use snafu::{OptionExt, ResultExt};
+
+use crate::error::{InvalidTimeZoneSnafu, InvalidVariableValueSnafu, Result};
+
+pub const TIME_ZONE: &str = "time_zone";
+pub const STATEMENT_TIMEOUT: &str = "statement_timeout";
+pub const MAX_EXECUTION_ROWS: &str = "max_execution_rows";
+pub const SCAN_STATS: &str = "scan_stats";
+pub const SKIP_QUERY_CACHE: &str = "skip_query_cache";
+pub const CONTINUE_ON_ERROR: &str = "continue_on_error";
+pub const DROP_TABLE_SYNC: &str = "drop_table_sync";
+
+/// Session variables supported by the server. A `None` value means the server default.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SessionVariables {
+    /// Time zone to render timestamps and to parse timestamp literals without an explicit
+    /// offset. Defaults to the local time zone of the server.
+    pub time_zone: Option<TimeZone>,
+    /// Cancel a statement if it runs longer than this.
+    pub statement_timeout: Option<Duration>,
+    /// Truncate query results to at most this many rows.
+    pub max_execution_rows: Option<usize>,
+    /// Report the stats of the scans of each query to the client.
+    pub scan_stats: bool,
+    /// Execute queries without reading or filling the query result cache of the frontend.
+    pub skip_query_cache: bool,
+    /// Execute the following statements of a multi-statement query after a statement fails,
+    /// instead of stopping at the failed one.
+    pub continue_on_error: bool,
+    /// Wait for the data of a dropped table to be deleted, as if `DROP TABLE` is always
+    /// followed by `SYNC`.
+    pub drop_table_sync: bool,
+}
+
+/// Normalizes a variable name: strips the `@@`, `SESSION.` and `LOCAL.` prefixes of MySQL
+/// and lowercases it. `timezone` is accepted as an alias of `time_zone`.
+pub fn normalize_name(name: &str) -> String {
+    let name = name.trim().trim_start_matches("@@").to_lowercase();
+    let name = name
+        .strip_prefix("session.")
+        .or_else(|| name.strip_prefix("local."))
+        .unwrap_or(&name);
+    match name {
+        "timezone" => TIME_ZONE.to_string(),
+        _ => name.to_string(),
+    }
+}
+
+/// Returns true if the variable is known to [SessionVariables].
+pub fn is_supported(name: &str) -> bool {
+    matches!(
+        normalize_name(name).as_str(),
+        TIME_ZONE
+            | STATEMENT_TIMEOUT
+            | MAX_EXECUTION_ROWS
+            | SCAN_STATS
+            | SKIP_QUERY_CACHE
+            | CONTINUE_ON_ERROR
+            | DROP_TABLE_SYNC
+    )
+}
+
+/// Values that reset a variable to the server default.
+fn is_default(value: &str) -> bool {
+    value.eq_ignore_ascii_case("DEFAULT") || value.eq_ignore_ascii_case("SYSTEM")
+}
+
+impl SessionVariables {
+    /// Sets the variable `name` to `value`, returns false if the variable is unknown, in which
+    /// case nothing is changed.
+    pub fn set(&mut self, name: &str, value: &str) -> Result<bool> {
+        let name = normalize_name(name);
+        let value = value.trim().trim_matches(|c| c == '\'' || c == '"');
+        match name.as_str() {
+            TIME_ZONE => {
+                self.time_zone = if is_default(value) {
+                    None
+                } else {
+                    Some(TimeZone::from_str(value).context(InvalidTimeZoneSnafu)?)
+                };
+            }
+            STATEMENT_TIMEOUT => {
+                self.statement_timeout = parse_non_zero(&name, value)?.map(Duration::from_millis);
+            }
+            MAX_EXECUTION_ROWS => {
+                self.max_execution_rows = parse_non_zero(&name, value)?.map(|v| v as usize);
+            }
+            SCAN_STATS => {
+                self.scan_stats = parse_bool(&name, value)?;
+            }
+            SKIP_QUERY_CACHE => {
+                self.skip_query_cache = parse_bool(&name, value)?;
+            }
+            CONTINUE_ON_ERROR => {
+                self.continue_on_error = parse_bool(&name, value)?;
+            }
+            DROP_TABLE_SYNC => {
+                self.drop_table_sync = parse_bool(&name, value)?;
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    /// Returns the current value of variable `name` as displayed by `SHOW VARIABLES`, or None
+    /// if the variable is unknown.
+    pub fn get(&self, name: &str) -> Option<String> {
+        let value = match normalize_name(name).as_str() {
+            TIME_ZONE => self
+                .time_zone
+                .map(|tz| tz.to_string())
+                .unwrap_or_else(|| "SYSTEM".to_string()),
+            STATEMENT_TIMEOUT => self
+                .statement_timeout
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or_default()
+                .to_string(),
+            MAX_EXECUTION_ROWS => self.max_execution_rows.unwrap_or_default().to_string(),
+            SCAN_STATS => on_off(self.scan_stats),
+            SKIP_QUERY_CACHE => on_off(self.skip_query_cache),
+            CONTINUE_ON_ERROR => on_off(self.continue_on_error),
+            DROP_TABLE_SYNC => on_off(self.drop_table_sync),
+            _ => return None,
+        };
+        Some(value)
+    }
+
+    /// Returns all variables and their current values, ordered by name.
+    pub fn all(&self) -> Vec<(&'static str, String)> {
+        [
+            CONTINUE_ON_ERROR,
+            DROP_TABLE_SYNC,
+            MAX_EXECUTION_ROWS,
+            SCAN_STATS,
+            SKIP_QUERY_CACHE,
+            STATEMENT_TIMEOUT,
+            TIME_ZONE,
+        ]
+        .into_iter()
+        .map(|name| (name, self.get(name).unwrap()))
+        .collect()
+    }
+}
+
+fn on_off(value: bool) -> String {
+    if value { "ON" } else { "OFF" }.to_string()
+}
+
+/// Parses a non-negative integer, where 0 means unlimited.
+fn parse_non_zero(name: &str, value: &str) -> Result<Option<u64>> {
+    if is_default(value) {
+        return Ok(None);
+    }
+    let v = value
+        .parse::<u64>()
+        .ok()
+        .context(InvalidVariableValueSnafu { name, value })?;
+    Ok((v > 0).then_some(v))
+}
+
+/// Parses a switch, which is off by default.
+fn parse_bool(name: &str, value: &str) -> Result<bool> {
+    if is_default(value) {
+        return Ok(false);
+    }
+    match value.to_lowercase().as_str() {
+        "on" | "true" | "1" => Ok(true),
+        "off" | "false" | "0" => Ok(false),
+        _ => InvalidVariableValueSnafu { name, value }.fail(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_name() {
+        assert_eq!("time_zone", normalize_name("@@session.time_zone"));
+        assert_eq!("time_zone", normalize_name("@@TIME_ZONE"));
+        assert_eq!("time_zone", normalize_name("TimeZone"));
+        assert_eq!(
+            "statement_timeout",
+            normalize_name("LOCAL.statement_timeout")
+        );
+        assert!(is_supported("@@max_execution_rows"));
+        assert!(!is_supported("autocommit"));
+    }
+
+    #[test]
+    fn test_set_variables() {
+        let mut vars = SessionVariables::default();
+        assert!(vars.set("time_zone", "'Asia/Shanghai'").unwrap());
+        assert_eq!("Asia/Shanghai", vars.get("@@time_zone").unwrap());
+        assert!(vars.set("TIMEZONE", "+08:00").unwrap());
+        assert_eq!("+08:00", vars.get("time_zone").unwrap());
+        assert!(vars.set("time_zone", "SYSTEM").unwrap());
+        assert_eq!(None, vars.time_zone);
+
+        assert!(vars.set("statement_timeout", "1500").unwrap());
+        assert_eq!(Some(Duration::from_millis(1500)), vars.statement_timeout);
+        assert!(vars.set("statement_timeout", "0").unwrap());
+        assert_eq!(None, vars.statement_timeout);
+
+        assert!(vars.set("max_execution_rows", "10").unwrap());
+        assert_eq!(Some(10), vars.max_execution_rows);
+
+        assert!(vars.set("scan_stats", "ON").unwrap());
+        assert!(vars.scan_stats);
+        assert!(vars.set("@@scan_stats", "0").unwrap());
+        assert!(!vars.scan_stats);
+        assert!(vars.set("scan_stats", "true").unwrap());
+
+        assert!(vars.set("skip_query_cache", "on").unwrap());
+        assert!(vars.skip_query_cache);
+
+        assert!(vars.set("continue_on_error", "1").unwrap());
+        assert!(vars.continue_on_error);
+        assert!(vars.set("continue_on_error", "DEFAULT").unwrap());
+        assert!(!vars.continue_on_error);
+
+        assert!(vars.set("drop_table_sync", "ON").unwrap());
+        assert!(vars.drop_table_sync);
+
+        assert_eq!(
+            vec![
+                ("continue_on_error", "OFF".to_string()),
+                ("drop_table_sync", "ON".to_string()),
+                ("max_execution_rows", "10".to_string()),
+                ("scan_stats", "ON".to_string()),
+                ("skip_query_cache", "ON".to_string()),
+                ("statement_timeout", "0".to_string()),
+                ("time_zone", "SYSTEM".to_string()),
+            ],
+            vars.all()
+        );
+    }
+
+    #[test]
+    fn test_set_invalid_variables() {
+        let mut vars = SessionVariables::default();
+        assert!(!vars.set("autocommit", "1").unwrap());
+        assert_eq!(None, vars.get("autocommit"));
+        assert_eq!(SessionVariables::default(), vars);
+
+        assert!(vars.set("time_zone", "Mars/Olympus").is_err());
+        assert!(vars.set("statement_timeout", "-1").is_err());
+        assert!(vars.set("max_execution_rows", "many").is_err());
+        assert!(vars.set("scan_stats", "maybe").is_err());
+        assert!(vars.set("skip_query_cache", "maybe").is_err());
+        assert!(vars.set("continue_on_error", "maybe").is_err());
+        assert!(vars.set("drop_table_sync", "maybe").is_err());
+        assert_eq!(SessionVariables::default(), vars);
+    }
+}
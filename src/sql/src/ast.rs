@@ -17,3 +17,4 @@ pub use sqlparser::ast::{
     FunctionArg, FunctionArgExpr, Ident, ObjectName, SqlOption, TableConstraint, TimezoneInfo,
     Value,
 };
+pub use sqlparser::keywords::ALL_KEYWORDS;
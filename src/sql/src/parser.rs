@@ -19,11 +19,13 @@ use sqlparser::parser::{Parser, ParserError};
 use sqlparser::tokenizer::{Token, TokenWithLocation};
 
 use crate::error::{self, InvalidDatabaseNameSnafu, InvalidTableNameSnafu, Result, SyntaxSnafu};
-use crate::parsers::tql_parser;
+use crate::parsers::{kill_parser, tql_parser};
 use crate::statements::describe::DescribeTable;
 use crate::statements::drop::DropTable;
 use crate::statements::explain::Explain;
-use crate::statements::show::{ShowCreateTable, ShowDatabases, ShowKind, ShowTables};
+use crate::statements::show::{
+    ShowCreateTable, ShowDatabases, ShowKind, ShowProcesslist, ShowTables,
+};
 use crate::statements::statement::Statement;
 
 /// GrepTime SQL parser context, a simple wrapper for Datafusion SQL parser.
@@ -115,12 +117,18 @@ impl<'a> ParserContext<'a> {
 
                     Keyword::COPY => self.parse_copy(),
 
+                    Keyword::SET => self.parse_set_variables(),
+
                     Keyword::NoKeyword
                         if w.value.to_uppercase() == tql_parser::TQL && w.quote_style.is_none() =>
                     {
                         self.parse_tql()
                     }
 
+                    _ if w.value.to_uppercase() == kill_parser::KILL && w.quote_style.is_none() => {
+                        self.parse_kill()
+                    }
+
                     // todo(hl) support more statements.
                     _ => self.unsupported(self.peek_token_as_string()),
                 }
@@ -153,6 +161,14 @@ impl<'a> ParserContext<'a> {
             } else {
                 self.unsupported(self.peek_token_as_string())
             }
+        } else if self.consume_token("PROCESSLIST") {
+            Ok(Statement::ShowProcesslist(ShowProcesslist { full: false }))
+        } else if self.consume_token("FULL") {
+            if self.consume_token("PROCESSLIST") {
+                Ok(Statement::ShowProcesslist(ShowProcesslist { full: true }))
+            } else {
+                self.unsupported(self.peek_token_as_string())
+            }
         } else {
             self.unsupported(self.peek_token_as_string())
         }
@@ -174,9 +190,7 @@ impl<'a> ParserContext<'a> {
                 name: table_name.to_string(),
             }
         );
-        Ok(Statement::ShowCreateTable(ShowCreateTable {
-            table_name: table_name.to_string(),
-        }))
+        Ok(Statement::ShowCreateTable(ShowCreateTable { table_name }))
     }
 
     fn parse_show_tables(&mut self) -> Result<Statement> {
@@ -295,6 +309,7 @@ impl<'a> ParserContext<'a> {
         }
         self.parser.next_token();
 
+        let drop_if_exists = self.parser.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
         let table_ident =
             self.parser
                 .parse_object_name()
@@ -310,7 +325,13 @@ impl<'a> ParserContext<'a> {
             }
         );
 
-        Ok(Statement::DropTable(DropTable::new(table_ident)))
+        let sync = self.consume_token("SYNC") || self.consume_token("PURGE");
+
+        Ok(Statement::DropTable(
+            DropTable::new(table_ident)
+                .with_sync(sync)
+                .with_drop_if_exists(drop_if_exists),
+        ))
     }
 
     // Report unexpected token
@@ -606,4 +627,49 @@ mod tests {
             ])))
         )
     }
+
+    #[test]
+    pub fn test_drop_table_sync() {
+        for sql in ["DROP TABLE foo SYNC", "DROP TABLE foo purge"] {
+            let mut stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+            assert_eq!(
+                stmts.pop().unwrap(),
+                Statement::DropTable(
+                    DropTable::new(ObjectName(vec![Ident::new("foo")])).with_sync(true)
+                )
+            );
+        }
+
+        let mut stmts =
+            ParserContext::create_with_dialect("DROP TABLE foo", &GenericDialect {}).unwrap();
+        let Statement::DropTable(drop_table) = stmts.pop().unwrap() else { unreachable!() };
+        assert!(!drop_table.sync());
+
+        let result =
+            ParserContext::create_with_dialect("DROP TABLE foo SYNC bar", &GenericDialect {});
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn test_drop_table_if_exists() {
+        let mut stmts =
+            ParserContext::create_with_dialect("DROP TABLE IF EXISTS foo SYNC", &GenericDialect {})
+                .unwrap();
+        assert_eq!(
+            stmts.pop().unwrap(),
+            Statement::DropTable(
+                DropTable::new(ObjectName(vec![Ident::new("foo")]))
+                    .with_sync(true)
+                    .with_drop_if_exists(true)
+            )
+        );
+
+        let mut stmts =
+            ParserContext::create_with_dialect("DROP TABLE foo", &GenericDialect {}).unwrap();
+        let Statement::DropTable(drop_table) = stmts.pop().unwrap() else { unreachable!() };
+        assert!(!drop_table.drop_if_exists());
+
+        let result = ParserContext::create_with_dialect("DROP TABLE IF foo", &GenericDialect {});
+        assert!(result.is_err());
+    }
 }
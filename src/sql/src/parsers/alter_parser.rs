@@ -13,14 +13,19 @@
 // limitations under the License.
 
 use snafu::ResultExt;
+use sqlparser::ast::Value;
 use sqlparser::keywords::Keyword;
-use sqlparser::parser::ParserError;
+use sqlparser::parser::{Parser, ParserError};
+use sqlparser::tokenizer::Token;
 
 use crate::error::{self, Result};
 use crate::parser::ParserContext;
 use crate::statements::alter::{AlterTable, AlterTableOperation};
 use crate::statements::statement::Statement;
 
+const TTL: &str = "TTL";
+const UNSET: &str = "UNSET";
+
 impl<'a> ParserContext<'a> {
     pub(crate) fn parse_alter(&mut self) -> Result<Statement> {
         let alter_table = self
@@ -64,9 +69,30 @@ impl<'a> ParserContext<'a> {
                 }
             };
             AlterTableOperation::RenameTable { new_table_name }
+        } else if parser.parse_keyword(Keyword::SET) {
+            if parser.consume_token(&Token::LParen) {
+                let options = parser.parse_comma_separated(Parser::parse_sql_option)?;
+                parser.expect_token(&Token::RParen)?;
+                AlterTableOperation::SetTableOptions { options }
+            } else {
+                expect_word(parser, TTL)?;
+                parser.expect_token(&Token::Eq)?;
+                let ttl = match parser.parse_value()? {
+                    Value::SingleQuotedString(s) | Value::DoubleQuotedString(s) => s,
+                    value => {
+                        return Err(ParserError::ParserError(format!(
+                            "expect a quoted duration after SET TTL =, found {value}"
+                        )))
+                    }
+                };
+                AlterTableOperation::SetTtl { ttl }
+            }
+        } else if parse_word(parser, UNSET) {
+            expect_word(parser, TTL)?;
+            AlterTableOperation::UnsetTtl
         } else {
             return Err(ParserError::ParserError(format!(
-                "expect keyword ADD or DROP or RENAME after ALTER TABLE, found {}",
+                "expect keyword ADD or DROP or RENAME or SET or UNSET after ALTER TABLE, found {}",
                 parser.peek_token()
             )));
         };
@@ -74,6 +100,28 @@ impl<'a> ParserContext<'a> {
     }
 }
 
+/// Consumes the next token if it's a word equals to `word`, ignoring case.
+fn parse_word(parser: &mut Parser, word: &str) -> bool {
+    match parser.peek_token().token {
+        Token::Word(w) if w.value.eq_ignore_ascii_case(word) => {
+            let _ = parser.next_token();
+            true
+        }
+        _ => false,
+    }
+}
+
+fn expect_word(parser: &mut Parser, word: &str) -> std::result::Result<(), ParserError> {
+    if parse_word(parser, word) {
+        Ok(())
+    } else {
+        Err(ParserError::ParserError(format!(
+            "expect {word}, found {}",
+            parser.peek_token()
+        )))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::assert_matches::assert_matches;
@@ -150,7 +198,7 @@ mod tests {
         let result = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap_err();
         assert!(result
             .to_string()
-            .contains("expect keyword ADD or DROP or RENAME after ALTER TABLE"));
+            .contains("expect keyword ADD or DROP or RENAME or SET or UNSET after ALTER TABLE"));
 
         let sql = "ALTER TABLE test_table RENAME table_t";
         let mut result = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
@@ -174,4 +222,85 @@ mod tests {
             _ => unreachable!(),
         }
     }
+
+    #[test]
+    fn test_parse_alter_set_ttl() {
+        let sql = "ALTER TABLE test_table SET TTL = '30d'";
+        let mut result = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        assert_eq!(1, result.len());
+
+        let statement = result.remove(0);
+        match statement {
+            Statement::Alter(alter_table) => {
+                assert_eq!("test_table", alter_table.table_name().0[0].value);
+                assert_eq!(
+                    &AlterTableOperation::SetTtl {
+                        ttl: "30d".to_string()
+                    },
+                    alter_table.alter_operation()
+                );
+            }
+            _ => unreachable!(),
+        }
+
+        let sql = "ALTER TABLE test_table SET TTL = 30";
+        let result = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap_err();
+        assert!(result
+            .to_string()
+            .contains("expect a quoted duration after SET TTL ="));
+
+        let sql = "ALTER TABLE test_table SET foo = '30d'";
+        let result = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap_err();
+        assert!(result.to_string().contains("expect TTL"));
+    }
+
+    #[test]
+    fn test_parse_alter_set_table_options() {
+        let sql = "ALTER TABLE test_table SET (compaction_max_files_in_level0 = 4, compaction_max_inflight = '2')";
+        let mut result = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        assert_eq!(1, result.len());
+
+        let statement = result.remove(0);
+        match statement {
+            Statement::Alter(alter_table) => {
+                assert_eq!("test_table", alter_table.table_name().0[0].value);
+                match alter_table.alter_operation() {
+                    AlterTableOperation::SetTableOptions { options } => {
+                        assert_eq!(2, options.len());
+                        assert_eq!("compaction_max_files_in_level0", options[0].name.value);
+                        assert_eq!(Value::Number("4".to_string(), false), options[0].value);
+                        assert_eq!("compaction_max_inflight", options[1].name.value);
+                        assert_eq!(
+                            Value::SingleQuotedString("2".to_string()),
+                            options[1].value
+                        );
+                    }
+                    _ => unreachable!(),
+                }
+            }
+            _ => unreachable!(),
+        }
+
+        let sql = "ALTER TABLE test_table SET (compaction_max_inflight = 2";
+        assert!(ParserContext::create_with_dialect(sql, &GenericDialect {}).is_err());
+    }
+
+    #[test]
+    fn test_parse_alter_unset_ttl() {
+        let sql = "ALTER TABLE test_table unset ttl";
+        let mut result = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        assert_eq!(1, result.len());
+
+        let statement = result.remove(0);
+        match statement {
+            Statement::Alter(alter_table) => {
+                assert_eq!("test_table", alter_table.table_name().0[0].value);
+                assert_eq!(
+                    &AlterTableOperation::UnsetTtl,
+                    alter_table.alter_operation()
+                );
+            }
+            _ => unreachable!(),
+        }
+    }
 }
@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+
 use snafu::ResultExt;
 use sqlparser::ast::{ObjectName, Value};
 use sqlparser::keywords::Keyword;
@@ -67,21 +69,18 @@ impl<'a> ParserContext<'a> {
         // default format is parquet
         let mut format = Format::Parquet;
         let mut pattern = None;
+        let mut with = HashMap::new();
         for option in options {
-            match option.name.value.to_ascii_uppercase().as_str() {
-                "FORMAT" => {
-                    if let Some(fmt_str) = ParserContext::parse_option_string(option.value) {
-                        format = Format::try_from(fmt_str)?;
-                    }
-                }
-                "PATTERN" => {
-                    if let Some(v) = ParserContext::parse_option_string(option.value) {
-                        pattern = Some(v);
-                    }
-                }
-                //TODO: throws warnings?
+            let name = option.name.value.to_ascii_lowercase();
+            let Some(value) = ParserContext::parse_option_value(option.value) else {
+                continue;
+            };
+            match name.as_str() {
+                "format" => format = Format::try_from(value.clone())?,
+                "pattern" => pattern = Some(value.clone()),
                 _ => (),
             }
+            with.insert(name, value);
         }
 
         let connection_options = self
@@ -102,6 +101,7 @@ impl<'a> ParserContext<'a> {
         Ok(CopyTableArgument {
             table_name,
             format,
+            with,
             pattern,
             connection,
             location,
@@ -125,12 +125,16 @@ impl<'a> ParserContext<'a> {
 
         // default format is parquet
         let mut format = Format::Parquet;
+        let mut with = HashMap::new();
         for option in options {
-            if option.name.value.eq_ignore_ascii_case("FORMAT") {
-                if let Some(fmt_str) = ParserContext::parse_option_string(option.value) {
-                    format = Format::try_from(fmt_str)?;
-                }
+            let name = option.name.value.to_ascii_lowercase();
+            let Some(value) = ParserContext::parse_option_value(option.value) else {
+                continue;
+            };
+            if name == "format" {
+                format = Format::try_from(value.clone())?;
             }
+            with.insert(name, value);
         }
 
         let connection_options = self
@@ -152,6 +156,7 @@ impl<'a> ParserContext<'a> {
         Ok(CopyTableArgument {
             table_name,
             format,
+            with,
             connection,
             pattern: None,
             location,
@@ -164,6 +169,15 @@ impl<'a> ParserContext<'a> {
             _ => None,
         }
     }
+
+    /// Parses the value of a `WITH` option, which could also be a number or a boolean.
+    fn parse_option_value(value: Value) -> Option<String> {
+        match value {
+            Value::Number(v, _) => Some(v),
+            Value::Boolean(v) => Some(v.to_string()),
+            v => ParserContext::parse_option_string(v),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -336,6 +350,42 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_copy_table_to_with_format_options() {
+        let sql = "COPY tbl TO '/tmp/export/' WITH (FORMAT = 'csv', HEADER = true, DELIMITER = '|', TIMESTAMP_FORMAT = '%Y-%m-%d', MAX_FILE_SIZE = 1024)";
+        let mut result = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        assert_eq!(1, result.len());
+
+        match result.remove(0) {
+            Statement::Copy(CopyTable::To(copy_table)) => {
+                assert_eq!(Format::Csv, copy_table.format);
+                let expected = [
+                    ("format", "csv"),
+                    ("header", "true"),
+                    ("delimiter", "|"),
+                    ("timestamp_format", "%Y-%m-%d"),
+                    ("max_file_size", "1024"),
+                ]
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<HashMap<_, _>>();
+                assert_eq!(expected, copy_table.with);
+            }
+            _ => unreachable!(),
+        }
+
+        let sql = "COPY tbl FROM '/tmp/export/' WITH (FORMAT = 'json', PATTERN = 'part.*')";
+        let mut result = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        match result.remove(0) {
+            Statement::Copy(CopyTable::From(copy_table)) => {
+                assert_eq!(Format::Json, copy_table.format);
+                assert_eq!(Some("part.*".to_string()), copy_table.pattern);
+                assert_eq!("json", copy_table.with["format"]);
+            }
+            _ => unreachable!(),
+        }
+    }
+
     #[test]
     fn test_parse_copy_table_with_unsupopoted_format() {
         let results = [
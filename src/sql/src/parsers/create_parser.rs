@@ -639,8 +639,8 @@ fn ensure_value_lists_strictly_increased<'a>(
                 (false, false) => {
                     let column_name = &column.name.value;
                     let cdt = sql_data_type_to_concrete_data_type(&column.data_type)?;
-                    let x = sql_value_to_value(column_name, &cdt, x)?;
-                    let y = sql_value_to_value(column_name, &cdt, y)?;
+                    let x = sql_value_to_value(column_name, &cdt, x, None)?;
+                    let y = sql_value_to_value(column_name, &cdt, y, None)?;
                     match x.cmp(&y) {
                         Ordering::Less => break,
                         Ordering::Equal => equal_tuples += 1,
@@ -0,0 +1,67 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snafu::ResultExt;
+
+use crate::error::{self, Result};
+use crate::parser::ParserContext;
+use crate::statements::kill::Kill;
+use crate::statements::statement::Statement;
+
+pub const KILL: &str = "KILL";
+
+/// KILL statement parser implementation
+impl<'a> ParserContext<'a> {
+    /// Parses `KILL QUERY <id>`, killing a connection is not supported.
+    pub(crate) fn parse_kill(&mut self) -> Result<Statement> {
+        self.parser.next_token();
+        if !self.consume_token("QUERY") {
+            return self.unsupported(self.peek_token_as_string());
+        }
+        let id = self
+            .parser
+            .parse_literal_uint()
+            .with_context(|_| error::UnexpectedSnafu {
+                sql: self.sql,
+                expected: "a query id",
+                actual: self.peek_token_as_string(),
+            })?;
+        Ok(Statement::Kill(Kill { id }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlparser::dialect::GenericDialect;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_kill() {
+        let mut stmts =
+            ParserContext::create_with_dialect("KILL QUERY 42", &GenericDialect {}).unwrap();
+        assert_eq!(1, stmts.len());
+        assert_eq!(Statement::Kill(Kill { id: 42 }), stmts.remove(0));
+
+        let stmts =
+            ParserContext::create_with_dialect("kill query 7;", &GenericDialect {}).unwrap();
+        assert_eq!(vec![Statement::Kill(Kill { id: 7 })], stmts);
+
+        assert!(ParserContext::create_with_dialect("KILL 42", &GenericDialect {}).is_err());
+        assert!(
+            ParserContext::create_with_dialect("KILL CONNECTION 42", &GenericDialect {}).is_err()
+        );
+        assert!(ParserContext::create_with_dialect("KILL QUERY abc", &GenericDialect {}).is_err());
+    }
+}
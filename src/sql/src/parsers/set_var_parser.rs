@@ -0,0 +1,44 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use snafu::ResultExt;
+use sqlparser::ast::{Ident, ObjectName, Statement as SpStatement};
+
+use crate::error::{self, Result};
+use crate::parser::ParserContext;
+use crate::statements::set_variables::SetVariables;
+use crate::statements::statement::Statement;
+
+/// SET statement parser implementation
+impl<'a> ParserContext<'a> {
+    pub(crate) fn parse_set_variables(&mut self) -> Result<Statement> {
+        let spstatement = self
+            .parser
+            .parse_statement()
+            .context(error::SyntaxSnafu { sql: self.sql })?;
+
+        match spstatement {
+            SpStatement::SetVariable {
+                variable, value, ..
+            } => Ok(Statement::SetVariables(SetVariables::new(variable, value))),
+            SpStatement::SetTimeZone { value, .. } => Ok(Statement::SetVariables(
+                SetVariables::new(ObjectName(vec![Ident::new("time_zone")]), vec![value]),
+            )),
+            unexp => error::UnsupportedSnafu {
+                sql: self.sql.to_string(),
+                keyword: unexp.to_string(),
+            }
+            .fail(),
+        }
+    }
+}
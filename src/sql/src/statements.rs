@@ -20,7 +20,9 @@ pub mod describe;
 pub mod drop;
 pub mod explain;
 pub mod insert;
+pub mod kill;
 pub mod query;
+pub mod set_variables;
 pub mod show;
 pub mod statement;
 pub mod tql;
@@ -29,7 +31,7 @@ use std::str::FromStr;
 
 use api::helper::ColumnDataTypeWrapper;
 use common_base::bytes::Bytes;
-use common_time::Timestamp;
+use common_time::{TimeZone, Timestamp};
 use datatypes::prelude::ConcreteDataType;
 use datatypes::schema::{ColumnDefaultConstraint, ColumnSchema};
 use datatypes::value::Value;
@@ -48,6 +50,7 @@ fn parse_string_to_value(
     column_name: &str,
     s: String,
     data_type: &ConcreteDataType,
+    timezone: Option<&TimeZone>,
 ) -> Result<Value> {
     ensure!(
         data_type.is_stringifiable(),
@@ -81,7 +84,7 @@ fn parse_string_to_value(
             }
         }
         ConcreteDataType::Timestamp(t) => {
-            if let Ok(ts) = Timestamp::from_str(&s) {
+            if let Ok(ts) = Timestamp::from_str_with_timezone(&s, timezone) {
                 Ok(Value::Timestamp(ts.convert_to(t.unit()).context(
                     TimestampOverflowSnafu {
                         timestamp: ts,
@@ -171,10 +174,13 @@ where
     }
 }
 
+/// Converts a sql value into datatype's value. Timestamp strings without an explicit offset
+/// are interpreted in `timezone`, or the local time zone if it's None.
 pub fn sql_value_to_value(
     column_name: &str,
     data_type: &ConcreteDataType,
     sql_val: &SqlValue,
+    timezone: Option<&TimeZone>,
 ) -> Result<Value> {
     Ok(match sql_val {
         SqlValue::Number(n, _) => sql_number_to_value(data_type, n)?,
@@ -192,7 +198,7 @@ pub fn sql_value_to_value(
             (*b).into()
         }
         SqlValue::DoubleQuotedString(s) | SqlValue::SingleQuotedString(s) => {
-            parse_string_to_value(column_name, s.to_owned(), data_type)?
+            parse_string_to_value(column_name, s.to_owned(), data_type, timezone)?
         }
         SqlValue::HexStringLiteral(s) => parse_hex_string(s)?,
         SqlValue::Placeholder(s) => return InvalidSqlValueSnafu { value: s }.fail(),
@@ -200,6 +206,31 @@ pub fn sql_value_to_value(
     })
 }
 
+/// Converts a value into a sql value that [sql_value_to_value] converts back to the same
+/// value, returns `None` if the value can't be expressed in sql.
+pub fn value_to_sql_value(val: &Value) -> Option<SqlValue> {
+    Some(match val {
+        Value::Null => SqlValue::Null,
+        Value::Boolean(b) => SqlValue::Boolean(*b),
+        Value::UInt8(_)
+        | Value::UInt16(_)
+        | Value::UInt32(_)
+        | Value::UInt64(_)
+        | Value::Int8(_)
+        | Value::Int16(_)
+        | Value::Int32(_)
+        | Value::Int64(_)
+        | Value::Float32(_)
+        | Value::Float64(_) => SqlValue::Number(val.to_string(), false),
+        Value::String(s) => SqlValue::SingleQuotedString(s.as_utf8().to_string()),
+        Value::Binary(b) => SqlValue::HexStringLiteral(hex::encode(&b[..])),
+        Value::Date(_) | Value::DateTime(_) | Value::Timestamp(_) => {
+            SqlValue::SingleQuotedString(val.to_string())
+        }
+        Value::List(_) => return None,
+    })
+}
+
 fn parse_column_default_constraint(
     column_name: &str,
     data_type: &ConcreteDataType,
@@ -211,7 +242,7 @@ fn parse_column_default_constraint(
     {
         let default_constraint = match &opt.option {
             ColumnOption::Default(Expr::Value(v)) => {
-                ColumnDefaultConstraint::Value(sql_value_to_value(column_name, data_type, v)?)
+                ColumnDefaultConstraint::Value(sql_value_to_value(column_name, data_type, v, None)?)
             }
             ColumnOption::Default(Expr::Function(func)) => {
                 // Always use lowercase for function expression
@@ -304,7 +335,16 @@ pub fn sql_data_type_to_concrete_data_type(data_type: &SqlDataType) -> Result<Co
         SqlDataType::Date => Ok(ConcreteDataType::date_datatype()),
         SqlDataType::Varbinary(_) => Ok(ConcreteDataType::binary_datatype()),
         SqlDataType::Datetime(_) => Ok(ConcreteDataType::datetime_datatype()),
-        SqlDataType::Timestamp(_, _) => Ok(ConcreteDataType::timestamp_millisecond_datatype()),
+        SqlDataType::Timestamp(precision, _) => match precision {
+            Some(0) => Ok(ConcreteDataType::timestamp_second_datatype()),
+            None | Some(3) => Ok(ConcreteDataType::timestamp_millisecond_datatype()),
+            Some(6) => Ok(ConcreteDataType::timestamp_microsecond_datatype()),
+            Some(9) => Ok(ConcreteDataType::timestamp_nanosecond_datatype()),
+            _ => error::SqlTypeNotSupportedSnafu {
+                t: data_type.clone(),
+            }
+            .fail(),
+        },
         _ => error::SqlTypeNotSupportedSnafu {
             t: data_type.clone(),
         }
@@ -365,6 +405,19 @@ mod tests {
             SqlDataType::Timestamp(None, TimezoneInfo::None),
             ConcreteDataType::timestamp_millisecond_datatype(),
         );
+        check_type(
+            SqlDataType::Timestamp(Some(0), TimezoneInfo::None),
+            ConcreteDataType::timestamp_second_datatype(),
+        );
+        check_type(
+            SqlDataType::Timestamp(Some(9), TimezoneInfo::None),
+            ConcreteDataType::timestamp_nanosecond_datatype(),
+        );
+        assert!(sql_data_type_to_concrete_data_type(&SqlDataType::Timestamp(
+            Some(2),
+            TimezoneInfo::None
+        ))
+        .is_err());
         check_type(
             SqlDataType::Varbinary(None),
             ConcreteDataType::binary_datatype(),
@@ -408,29 +461,29 @@ mod tests {
         let sql_val = SqlValue::Null;
         assert_eq!(
             Value::Null,
-            sql_value_to_value("a", &ConcreteDataType::float64_datatype(), &sql_val).unwrap()
+            sql_value_to_value("a", &ConcreteDataType::float64_datatype(), &sql_val, None).unwrap()
         );
 
         let sql_val = SqlValue::Boolean(true);
         assert_eq!(
             Value::Boolean(true),
-            sql_value_to_value("a", &ConcreteDataType::boolean_datatype(), &sql_val).unwrap()
+            sql_value_to_value("a", &ConcreteDataType::boolean_datatype(), &sql_val, None).unwrap()
         );
 
         let sql_val = SqlValue::Number("3.0".to_string(), false);
         assert_eq!(
             Value::Float64(OrderedFloat(3.0)),
-            sql_value_to_value("a", &ConcreteDataType::float64_datatype(), &sql_val).unwrap()
+            sql_value_to_value("a", &ConcreteDataType::float64_datatype(), &sql_val, None).unwrap()
         );
 
         let sql_val = SqlValue::Number("3.0".to_string(), false);
-        let v = sql_value_to_value("a", &ConcreteDataType::boolean_datatype(), &sql_val);
+        let v = sql_value_to_value("a", &ConcreteDataType::boolean_datatype(), &sql_val, None);
         assert!(v.is_err());
         assert!(format!("{v:?}")
             .contains("Fail to parse number 3.0, invalid column type: Boolean(BooleanType)"));
 
         let sql_val = SqlValue::Boolean(true);
-        let v = sql_value_to_value("a", &ConcreteDataType::float64_datatype(), &sql_val);
+        let v = sql_value_to_value("a", &ConcreteDataType::float64_datatype(), &sql_val, None);
         assert!(v.is_err());
         assert!(
             format!("{v:?}").contains(
@@ -440,11 +493,12 @@ mod tests {
         );
 
         let sql_val = SqlValue::HexStringLiteral("48656c6c6f20776f726c6421".to_string());
-        let v = sql_value_to_value("a", &ConcreteDataType::binary_datatype(), &sql_val).unwrap();
+        let v =
+            sql_value_to_value("a", &ConcreteDataType::binary_datatype(), &sql_val, None).unwrap();
         assert_eq!(Value::Binary(Bytes::from(b"Hello world!".as_slice())), v);
 
         let sql_val = SqlValue::HexStringLiteral("9AF".to_string());
-        let v = sql_value_to_value("a", &ConcreteDataType::binary_datatype(), &sql_val);
+        let v = sql_value_to_value("a", &ConcreteDataType::binary_datatype(), &sql_val, None);
         assert!(v.is_err());
         assert!(
             format!("{v:?}").contains("odd number of digits"),
@@ -452,17 +506,57 @@ mod tests {
         );
 
         let sql_val = SqlValue::HexStringLiteral("AG".to_string());
-        let v = sql_value_to_value("a", &ConcreteDataType::binary_datatype(), &sql_val);
+        let v = sql_value_to_value("a", &ConcreteDataType::binary_datatype(), &sql_val, None);
         assert!(v.is_err());
         assert!(format!("{v:?}").contains("invalid character"), "v is {v:?}",);
     }
 
+    #[test]
+    fn test_value_to_sql_value() {
+        let values = [
+            (Value::Null, ConcreteDataType::int32_datatype()),
+            (Value::Boolean(true), ConcreteDataType::boolean_datatype()),
+            (Value::UInt8(8), ConcreteDataType::uint8_datatype()),
+            (Value::Int64(-64), ConcreteDataType::int64_datatype()),
+            (
+                Value::Float64(OrderedFloat(1.5)),
+                ConcreteDataType::float64_datatype(),
+            ),
+            (Value::from("it's"), ConcreteDataType::string_datatype()),
+            (
+                Value::Binary(Bytes::from(b"Hello".as_slice())),
+                ConcreteDataType::binary_datatype(),
+            ),
+            (
+                Value::Date(common_time::date::Date::new(19000)),
+                ConcreteDataType::date_datatype(),
+            ),
+            (
+                Value::DateTime(common_time::datetime::DateTime::new(1_600_000_000)),
+                ConcreteDataType::datetime_datatype(),
+            ),
+            (
+                Value::Timestamp(Timestamp::new(1_600_000_000_123, TimeUnit::Millisecond)),
+                ConcreteDataType::timestamp_millisecond_datatype(),
+            ),
+        ];
+        for (value, data_type) in values {
+            let sql_val = value_to_sql_value(&value).unwrap();
+            assert_eq!(
+                value,
+                sql_value_to_value("a", &data_type, &sql_val, None).unwrap(),
+                "sql value is {sql_val}"
+            );
+        }
+    }
+
     #[test]
     pub fn test_parse_date_literal() {
         let value = sql_value_to_value(
             "date",
             &ConcreteDataType::date_datatype(),
             &SqlValue::DoubleQuotedString("2022-02-22".to_string()),
+            None,
         )
         .unwrap();
         assert_eq!(ConcreteDataType::date_datatype(), value.data_type());
@@ -479,6 +573,7 @@ mod tests {
             "datetime_col",
             &ConcreteDataType::datetime_datatype(),
             &SqlValue::DoubleQuotedString("2022-02-22 00:01:03".to_string()),
+            None,
         )
         .unwrap();
         assert_eq!(ConcreteDataType::datetime_datatype(), value.data_type());
@@ -495,6 +590,7 @@ mod tests {
             "datetime_col",
             &ConcreteDataType::datetime_datatype(),
             &SqlValue::DoubleQuotedString("2022-02-22 00:01:61".to_string()),
+            None,
         )
         .is_err());
     }
@@ -505,6 +601,7 @@ mod tests {
             "timestamp_col",
             "2022-02-22T00:01:01+08:00".to_string(),
             &ConcreteDataType::timestamp_millisecond_datatype(),
+            None,
         )
         .unwrap()
         {
@@ -521,6 +618,7 @@ mod tests {
             "timestamp_col",
             "2022-02-22T00:01:01+08:00".to_string(),
             &ConcreteDataType::timestamp_datatype(TimeUnit::Second),
+            None,
         )
         .unwrap()
         {
@@ -537,6 +635,7 @@ mod tests {
             "timestamp_col",
             "2022-02-22T00:01:01+08:00".to_string(),
             &ConcreteDataType::timestamp_datatype(TimeUnit::Microsecond),
+            None,
         )
         .unwrap()
         {
@@ -553,6 +652,7 @@ mod tests {
             "timestamp_col",
             "2022-02-22T00:01:01+08:00".to_string(),
             &ConcreteDataType::timestamp_datatype(TimeUnit::Nanosecond),
+            None,
         )
         .unwrap()
         {
@@ -569,10 +669,40 @@ mod tests {
             "timestamp_col",
             "2022-02-22T00:01:01+08".to_string(),
             &ConcreteDataType::timestamp_datatype(TimeUnit::Nanosecond),
+            None,
         )
         .is_err());
     }
 
+    #[test]
+    fn test_parse_timestamp_literal_with_timezone() {
+        let timezone = TimeZone::from_str("Asia/Shanghai").unwrap();
+        match sql_value_to_value(
+            "timestamp_col",
+            &ConcreteDataType::timestamp_millisecond_datatype(),
+            &SqlValue::SingleQuotedString("2022-02-22 00:01:01".to_string()),
+            Some(&timezone),
+        )
+        .unwrap()
+        {
+            Value::Timestamp(ts) => assert_eq!(1645459261000, ts.value()),
+            _ => unreachable!(),
+        }
+
+        // The explicit offset wins over the time zone.
+        match sql_value_to_value(
+            "timestamp_col",
+            &ConcreteDataType::timestamp_millisecond_datatype(),
+            &SqlValue::SingleQuotedString("2022-02-22T00:01:01Z".to_string()),
+            Some(&timezone),
+        )
+        .unwrap()
+        {
+            Value::Timestamp(ts) => assert_eq!(1645488061000, ts.value()),
+            _ => unreachable!(),
+        }
+    }
+
     #[test]
     pub fn test_parse_column_default_constraint() {
         let bool_value = sqlparser::ast::Value::Boolean(true);
@@ -682,7 +812,8 @@ mod tests {
         assert!(sql_value_to_value(
             "test",
             &ConcreteDataType::string_datatype(),
-            &SqlValue::Placeholder("default".into())
+            &SqlValue::Placeholder("default".into()),
+            None
         )
         .is_err());
     }
@@ -11,7 +11,7 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
-
+use sqlparser::ast::{ColumnDef, Ident, ObjectName, SqlOption, TableConstraint};
 use sqlparser::ast::{ColumnDef, Ident, ObjectName, TableConstraint};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -47,4 +47,10 @@ pub enum AlterTableOperation {
     DropColumn { name: Ident },
     /// `RENAME <new_table_name>`
     RenameTable { new_table_name: String },
+    /// `SET TTL = '<duration>'`
+    SetTtl { ttl: String },
+    /// `UNSET TTL`
+    UnsetTtl,
+    /// `SET ( <name> = <value> [, ...] )`
+    SetTableOptions { options: Vec<SqlOption> },
 }
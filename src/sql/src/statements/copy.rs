@@ -28,6 +28,8 @@ pub enum CopyTable {
 pub struct CopyTableArgument {
     pub table_name: ObjectName,
     pub format: Format,
+    /// Options in `WITH`, keys are in lowercase.
+    pub with: HashMap<String, String>,
     pub connection: HashMap<String, String>,
     pub pattern: Option<String>,
     /// Copy tbl [To|From] 'location'.
@@ -37,15 +39,20 @@ pub struct CopyTableArgument {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Format {
     Parquet,
+    Csv,
+    /// Newline delimited JSON.
+    Json,
 }
 
 impl TryFrom<String> for Format {
     type Error = error::Error;
 
     fn try_from(name: String) -> Result<Self> {
-        if name.eq_ignore_ascii_case("PARQUET") {
-            return Ok(Format::Parquet);
+        match name.to_ascii_uppercase().as_str() {
+            "PARQUET" => Ok(Format::Parquet),
+            "CSV" => Ok(Format::Csv),
+            "JSON" => Ok(Format::Json),
+            _ => error::UnsupportedCopyFormatOptionSnafu { name }.fail(),
         }
-        error::UnsupportedCopyFormatOptionSnafu { name }.fail()
     }
 }
@@ -18,15 +18,42 @@ use sqlparser::ast::ObjectName;
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DropTable {
     table_name: ObjectName,
+    /// Whether to wait until the data of the table is deleted, set by `SYNC` or `PURGE`.
+    sync: bool,
+    /// Whether to succeed without dropping anything if the table doesn't exist, set by
+    /// `IF EXISTS`.
+    drop_if_exists: bool,
 }
 
 impl DropTable {
     /// Creates a statement for `DROP TABLE`
     pub fn new(table_name: ObjectName) -> Self {
-        Self { table_name }
+        Self {
+            table_name,
+            sync: false,
+            drop_if_exists: false,
+        }
+    }
+
+    pub fn with_sync(mut self, sync: bool) -> Self {
+        self.sync = sync;
+        self
+    }
+
+    pub fn with_drop_if_exists(mut self, drop_if_exists: bool) -> Self {
+        self.drop_if_exists = drop_if_exists;
+        self
     }
 
     pub fn table_name(&self) -> &ObjectName {
         &self.table_name
     }
+
+    pub fn sync(&self) -> bool {
+        self.sync
+    }
+
+    pub fn drop_if_exists(&self) -> bool {
+        self.drop_if_exists
+    }
 }
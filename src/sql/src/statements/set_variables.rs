@@ -0,0 +1,86 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use sqlparser::ast::{Expr, ObjectName, Value};
+
+/// SQL structure for `SET variable = value`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SetVariables {
+    variable: ObjectName,
+    value: Vec<Expr>,
+}
+
+impl SetVariables {
+    pub fn new(variable: ObjectName, value: Vec<Expr>) -> Self {
+        Self { variable, value }
+    }
+
+    /// Name of the variable, e.g. `@@session.time_zone`.
+    pub fn variable(&self) -> String {
+        self.variable.to_string()
+    }
+
+    pub fn value(&self) -> &[Expr] {
+        &self.value
+    }
+
+    /// Renders the value as a plain string, with quotes of string literals removed.
+    pub fn value_string(&self) -> String {
+        self.value
+            .iter()
+            .map(|expr| match expr {
+                Expr::Value(Value::SingleQuotedString(s))
+                | Expr::Value(Value::DoubleQuotedString(s)) => s.clone(),
+                Expr::Identifier(ident) => ident.value.clone(),
+                _ => expr.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::assert_matches::assert_matches;
+
+    use sqlparser::dialect::MySqlDialect;
+
+    use crate::parser::ParserContext;
+    use crate::statements::statement::Statement;
+
+    #[test]
+    fn test_parse_set_variables() {
+        let sql = "SET time_zone = 'Asia/Shanghai'";
+        let mut stmts = ParserContext::create_with_dialect(sql, &MySqlDialect {}).unwrap();
+        assert_eq!(1, stmts.len());
+        let Statement::SetVariables(set) = stmts.remove(0) else { unreachable!() };
+        assert_eq!("time_zone", set.variable());
+        assert_eq!("Asia/Shanghai", set.value_string());
+
+        let sql = "SET SESSION statement_timeout = 1000";
+        let mut stmts = ParserContext::create_with_dialect(sql, &MySqlDialect {}).unwrap();
+        let Statement::SetVariables(set) = stmts.remove(0) else { unreachable!() };
+        assert_eq!("statement_timeout", set.variable());
+        assert_eq!("1000", set.value_string());
+
+        let sql = "SET TIME ZONE '+08:00'";
+        let mut stmts = ParserContext::create_with_dialect(sql, &MySqlDialect {}).unwrap();
+        let Statement::SetVariables(set) = stmts.remove(0) else { unreachable!() };
+        assert_eq!("time_zone", set.variable());
+        assert_eq!("+08:00", set.value_string());
+
+        let sql = "SET NAMES utf8mb4";
+        let result = ParserContext::create_with_dialect(sql, &MySqlDialect {});
+        assert_matches!(result, Err(crate::error::Error::Unsupported { .. }));
+    }
+}
@@ -14,7 +14,7 @@
 
 use std::fmt;
 
-use crate::ast::{Expr, Ident};
+use crate::ast::{Expr, Ident, ObjectName};
 
 /// Show kind for SQL expressions like `SHOW DATABASE` or `SHOW TABLE`
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -57,7 +57,14 @@ pub struct ShowTables {
 /// SQL structure for `SHOW CREATE TABLE`.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ShowCreateTable {
-    pub table_name: String,
+    pub table_name: ObjectName,
+}
+
+/// SQL structure for `SHOW [FULL] PROCESSLIST`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShowProcesslist {
+    /// Shows the whole statements instead of the truncated ones.
+    pub full: bool,
 }
 
 #[cfg(test)]
@@ -124,7 +131,7 @@ mod tests {
         assert_matches!(&stmts[0], Statement::ShowCreateTable { .. });
         match &stmts[0] {
             Statement::ShowCreateTable(show) => {
-                let table_name = show.table_name.as_str();
+                let table_name = show.table_name.to_string();
                 assert_eq!(table_name, "test");
             }
             _ => {
@@ -137,4 +144,21 @@ mod tests {
         let sql = "SHOW CREATE TABLE";
         ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap_err();
     }
+
+    #[test]
+    pub fn test_show_processlist() {
+        let stmts =
+            ParserContext::create_with_dialect("SHOW PROCESSLIST", &GenericDialect {}).unwrap();
+        assert_eq!(
+            vec![Statement::ShowProcesslist(ShowProcesslist { full: false })],
+            stmts
+        );
+        let stmts = ParserContext::create_with_dialect("show full processlist", &GenericDialect {})
+            .unwrap();
+        assert_eq!(
+            vec![Statement::ShowProcesslist(ShowProcesslist { full: true })],
+            stmts
+        );
+        ParserContext::create_with_dialect("SHOW FULL TABLES", &GenericDialect {}).unwrap_err();
+    }
 }
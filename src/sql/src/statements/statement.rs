@@ -24,8 +24,10 @@ use crate::statements::describe::DescribeTable;
 use crate::statements::drop::DropTable;
 use crate::statements::explain::Explain;
 use crate::statements::insert::Insert;
+use crate::statements::kill::Kill;
 use crate::statements::query::Query;
-use crate::statements::show::{ShowCreateTable, ShowDatabases, ShowTables};
+use crate::statements::set_variables::SetVariables;
+use crate::statements::show::{ShowCreateTable, ShowDatabases, ShowProcesslist, ShowTables};
 use crate::statements::tql::Tql;
 
 /// Tokens parsed by `DFParser` are converted into these values.
@@ -52,6 +54,8 @@ pub enum Statement {
     ShowTables(ShowTables),
     // SHOW CREATE TABLE
     ShowCreateTable(ShowCreateTable),
+    // SHOW [FULL] PROCESSLIST
+    ShowProcesslist(ShowProcesslist),
     // DESCRIBE TABLE
     DescribeTable(DescribeTable),
     // EXPLAIN QUERY
@@ -60,6 +64,10 @@ pub enum Statement {
     // COPY
     Copy(CopyTable),
     Tql(Tql),
+    // SET variable = value
+    SetVariables(SetVariables),
+    // KILL QUERY <id>
+    Kill(Kill),
 }
 
 /// Comment hints from SQL.
@@ -0,0 +1,498 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Backup of SST files to a secondary object store.
+//!
+//! [SstBackup] listens to flush and compaction events of regions and copies the SST files
+//! they add to the backup store in background, under the same path as in the primary store.
+
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+
+use common_telemetry::logging;
+use object_store::{util, ErrorKind, ObjectStore};
+use snafu::ResultExt;
+use store_api::storage::RegionId;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::sync::{Notify, Semaphore};
+
+use crate::error::{Error, ReadObjectSnafu, Result, WriteObjectSnafu};
+use crate::listener::RegionEventListener;
+use crate::manifest::action::RegionEdit;
+use crate::sst::{FileId, FileMeta};
+
+/// Options of [SstBackup].
+#[derive(Debug, Clone)]
+pub struct BackupOptions {
+    /// Don't delete local SST files until they are copied to the backup store.
+    pub strict: bool,
+    /// Max number of retries of a failed copy before giving up.
+    pub max_retries: usize,
+    /// Delay before the first retry, doubled on each retry.
+    pub retry_delay: Duration,
+    /// Upper bound of the delay between retries.
+    pub max_retry_delay: Duration,
+    /// Max number of files copied concurrently.
+    pub max_concurrent_copies: usize,
+}
+
+impl Default for BackupOptions {
+    fn default() -> Self {
+        Self {
+            strict: false,
+            max_retries: 10,
+            retry_delay: Duration::from_millis(500),
+            max_retry_delay: Duration::from_secs(60),
+            max_concurrent_copies: 4,
+        }
+    }
+}
+
+/// Backup progress of a region.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegionBackupStatus {
+    pub region_id: RegionId,
+    /// Number of files of the region added since it's opened, all of which are backed up.
+    pub high_water_mark: u64,
+    /// Number of files waiting to be copied, including failed files.
+    pub pending_files: usize,
+    /// Total size of pending files.
+    pub pending_bytes: u64,
+    /// Number of files the exporter gave up copying.
+    pub failed_files: usize,
+    /// How long the oldest pending file has been waiting.
+    pub lag: Duration,
+}
+
+#[derive(Debug)]
+struct PendingFile {
+    /// Sequence of the file in the region, in the order the files are added.
+    sequence: u64,
+    file_size: u64,
+    added_at: Instant,
+    failed: bool,
+}
+
+#[derive(Debug, Default)]
+struct RegionState {
+    /// Directory of the region's SST files, unknown until the region is registered.
+    sst_dir: Option<String>,
+    next_sequence: u64,
+    high_water_mark: u64,
+    pending: HashMap<FileId, PendingFile>,
+    /// Sequences of copied files above the high-water mark.
+    copied: BTreeSet<u64>,
+}
+
+impl RegionState {
+    /// Adds `file` to pending files, returns false if it's already pending.
+    fn add_file(&mut self, file: &FileMeta) -> bool {
+        if self.pending.contains_key(&file.file_id) {
+            return false;
+        }
+        self.next_sequence += 1;
+        self.pending.insert(
+            file.file_id,
+            PendingFile {
+                sequence: self.next_sequence,
+                file_size: file.file_size,
+                added_at: Instant::now(),
+                failed: false,
+            },
+        );
+        true
+    }
+
+    fn mark_copied(&mut self, file_id: FileId) {
+        let Some(file) = self.pending.remove(&file_id) else { return };
+        self.copied.insert(file.sequence);
+        while self.copied.remove(&(self.high_water_mark + 1)) {
+            self.high_water_mark += 1;
+        }
+    }
+
+    fn status(&self, region_id: RegionId, now: Instant) -> RegionBackupStatus {
+        RegionBackupStatus {
+            region_id,
+            high_water_mark: self.high_water_mark,
+            pending_files: self.pending.len(),
+            pending_bytes: self.pending.values().map(|f| f.file_size).sum(),
+            failed_files: self.pending.values().filter(|f| f.failed).count(),
+            lag: self
+                .pending
+                .values()
+                .map(|f| now.duration_since(f.added_at))
+                .max()
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct CopyJob {
+    region_id: RegionId,
+    file_id: FileId,
+    path: String,
+}
+
+/// Copies SST files added by flush and compaction to a backup store.
+pub struct SstBackup {
+    source: ObjectStore,
+    target: ObjectStore,
+    options: BackupOptions,
+    regions: Mutex<HashMap<RegionId, RegionState>>,
+    sender: UnboundedSender<CopyJob>,
+    /// Notified each time a copy finishes or fails.
+    copy_notify: Notify,
+}
+
+pub type SstBackupRef = Arc<SstBackup>;
+
+impl SstBackup {
+    /// Creates the exporter copying from `source` to `target` and starts its background
+    /// task. Must be called within a tokio runtime.
+    pub fn new(source: ObjectStore, target: ObjectStore, options: BackupOptions) -> SstBackupRef {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let backup = Arc::new(SstBackup {
+            source,
+            target,
+            options,
+            regions: Mutex::new(HashMap::new()),
+            sender,
+            copy_notify: Notify::new(),
+        });
+        common_runtime::spawn_bg(Self::run(Arc::downgrade(&backup), receiver));
+        backup
+    }
+
+    pub fn options(&self) -> &BackupOptions {
+        &self.options
+    }
+
+    /// Registers the SST directory and current files of a created or opened region, files
+    /// already in the backup store are skipped when copying.
+    pub(crate) fn register_region(&self, region_id: RegionId, sst_dir: &str, files: &[FileMeta]) {
+        let sst_dir = util::normalize_dir(sst_dir);
+        let mut regions = self.regions.lock().unwrap();
+        let state = regions.entry(region_id).or_default();
+        state.sst_dir = Some(sst_dir.clone());
+        for file in files {
+            state.add_file(file);
+        }
+        // Files added before registration and failed files are (re)scheduled as well.
+        for (file_id, file) in state.pending.iter_mut() {
+            file.failed = false;
+            self.schedule(region_id, &sst_dir, *file_id);
+        }
+    }
+
+    /// Returns the backup progress of all regions, ordered by region id.
+    pub fn status(&self) -> Vec<RegionBackupStatus> {
+        let now = Instant::now();
+        let regions = self.regions.lock().unwrap();
+        let mut status: Vec<_> = regions
+            .iter()
+            .map(|(region_id, state)| state.status(*region_id, now))
+            .collect();
+        status.sort_unstable_by_key(|s| s.region_id);
+        status
+    }
+
+    /// Waits until the file is copied to the backup store, returns false if the file
+    /// can't be backed up.
+    pub(crate) async fn wait_backed_up(&self, region_id: RegionId, file_id: FileId) -> bool {
+        loop {
+            let notified = self.copy_notify.notified();
+            tokio::pin!(notified);
+            // Register the waiter before checking the state, so no notification is missed.
+            notified.as_mut().enable();
+
+            match self.file_state(region_id, file_id) {
+                FileState::Pending => notified.await,
+                FileState::Failed => return false,
+                FileState::Untracked(path) => {
+                    // The file may be copied already, or added before the exporter started.
+                    let Some(path) = path else { return false };
+                    return match self.copy(&path).await {
+                        Ok(()) => true,
+                        Err(e) => {
+                            logging::error!(e; "Failed to back up SST file {}", path);
+                            false
+                        }
+                    };
+                }
+            }
+        }
+    }
+
+    fn file_state(&self, region_id: RegionId, file_id: FileId) -> FileState {
+        let regions = self.regions.lock().unwrap();
+        let Some(state) = regions.get(&region_id) else { return FileState::Untracked(None) };
+        match state.pending.get(&file_id) {
+            Some(file) if file.failed => FileState::Failed,
+            Some(_) => FileState::Pending,
+            None => FileState::Untracked(
+                state
+                    .sst_dir
+                    .as_ref()
+                    .map(|sst_dir| format!("{}{}", sst_dir, file_id.as_parquet())),
+            ),
+        }
+    }
+
+    fn add_files(&self, region_id: RegionId, files: &[FileMeta]) {
+        let mut regions = self.regions.lock().unwrap();
+        let state = regions.entry(region_id).or_default();
+        for file in files {
+            if state.add_file(file) {
+                if let Some(sst_dir) = &state.sst_dir {
+                    self.schedule(region_id, sst_dir, file.file_id);
+                }
+            }
+        }
+    }
+
+    fn schedule(&self, region_id: RegionId, sst_dir: &str, file_id: FileId) {
+        let job = CopyJob {
+            region_id,
+            file_id,
+            path: format!("{}{}", sst_dir, file_id.as_parquet()),
+        };
+        // The receiver is dropped only if the exporter is dropped.
+        let _ = self.sender.send(job);
+    }
+
+    async fn run(backup: Weak<SstBackup>, mut receiver: UnboundedReceiver<CopyJob>) {
+        let max_concurrent_copies = match backup.upgrade() {
+            Some(backup) => backup.options.max_concurrent_copies.max(1),
+            None => return,
+        };
+        let semaphore = Arc::new(Semaphore::new(max_concurrent_copies));
+        while let Some(job) = receiver.recv().await {
+            let permit = semaphore.clone().acquire_owned().await.unwrap();
+            let Some(backup) = backup.upgrade() else { return };
+            common_runtime::spawn_bg(async move {
+                backup.copy_with_retry(job).await;
+                drop(permit);
+            });
+        }
+    }
+
+    async fn copy_with_retry(&self, job: CopyJob) {
+        let mut delay = self.options.retry_delay;
+        let mut retries = 0;
+        let result = loop {
+            match self.copy(&job.path).await {
+                Ok(()) => break Ok(()),
+                Err(e) if retries < self.options.max_retries && is_retryable(&e) => {
+                    logging::warn!(
+                        "Failed to back up SST file {}, retry after {:?}, err: {}",
+                        job.path,
+                        delay,
+                        e
+                    );
+                    tokio::time::sleep(delay).await;
+                    delay = (delay * 2).min(self.options.max_retry_delay);
+                    retries += 1;
+                }
+                Err(e) => break Err(e),
+            }
+        };
+
+        {
+            let mut regions = self.regions.lock().unwrap();
+            if let Some(state) = regions.get_mut(&job.region_id) {
+                match &result {
+                    Ok(()) => state.mark_copied(job.file_id),
+                    Err(_) => {
+                        if let Some(file) = state.pending.get_mut(&job.file_id) {
+                            file.failed = true;
+                        }
+                    }
+                }
+            }
+        }
+        if let Err(e) = result {
+            logging::error!(e; "Gave up backing up SST file {} after {} retries", job.path, retries);
+        }
+        self.copy_notify.notify_waiters();
+    }
+
+    /// Copies the object at `path` to the backup store, skips the copy if the backup store
+    /// already has an object of the same size.
+    async fn copy(&self, path: &str) -> Result<()> {
+        let source = self.source.object(path);
+        let target = self.target.object(path);
+        let size = source
+            .metadata()
+            .await
+            .context(ReadObjectSnafu { path })?
+            .content_length();
+        match target.metadata().await {
+            Ok(meta) if meta.content_length() == size => return Ok(()),
+            Ok(_) => (),
+            Err(e) if e.kind() == ErrorKind::ObjectNotFound => (),
+            Err(e) => return Err(e).context(ReadObjectSnafu { path }),
+        }
+
+        let bytes = source.read().await.context(ReadObjectSnafu { path })?;
+        target.write(bytes).await.context(WriteObjectSnafu { path })
+    }
+}
+
+/// State of a file in the exporter.
+enum FileState {
+    Pending,
+    Failed,
+    /// The file isn't pending, contains its path if the region is registered.
+    Untracked(Option<String>),
+}
+
+/// A copy can't succeed if the source file is gone, e.g. already purged.
+fn is_retryable(e: &Error) -> bool {
+    !matches!(e, Error::ReadObject { source, .. } if source.kind() == ErrorKind::ObjectNotFound)
+}
+
+impl RegionEventListener for SstBackup {
+    fn on_flush_completed(&self, region_id: RegionId, file: &FileMeta) {
+        self.add_files(region_id, std::slice::from_ref(file));
+    }
+
+    fn on_compaction_completed(&self, region_id: RegionId, edit: &RegionEdit) {
+        self.add_files(region_id, &edit.files_to_add);
+    }
+}
+
+impl std::fmt::Debug for SstBackup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SstBackup")
+            .field("options", &self.options)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_test_util::temp_dir::{create_temp_dir, TempDir};
+    use object_store::services::Fs;
+    use object_store::ObjectStoreBuilder;
+
+    use super::*;
+
+    fn new_fs_store(name: &str) -> (TempDir, ObjectStore) {
+        let dir = create_temp_dir(name);
+        let accessor = Fs::default()
+            .root(dir.path().to_str().unwrap())
+            .build()
+            .unwrap();
+        (dir, ObjectStore::new(accessor).finish())
+    }
+
+    async fn new_sst_file(store: &ObjectStore, sst_dir: &str, region_id: RegionId) -> FileMeta {
+        let file_id = FileId::random();
+        let content = file_id.to_string().into_bytes();
+        let file_size = content.len() as u64;
+        store
+            .object(&format!("{}{}", sst_dir, file_id.as_parquet()))
+            .write(content)
+            .await
+            .unwrap();
+        FileMeta {
+            region_id,
+            file_id,
+            time_range: None,
+            level: 0,
+            file_size,
+            bloom_filter: false,
+            key_range: vec![],
+            row_groups: vec![],
+            num_rows: 0,
+        }
+    }
+
+    async fn is_backed_up(target: &ObjectStore, sst_dir: &str, file: &FileMeta) -> bool {
+        target
+            .object(&format!("{}{}", sst_dir, file.file_id.as_parquet()))
+            .is_exist()
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_backup_sst_files() {
+        let (_source_dir, source) = new_fs_store("backup-source");
+        let (_target_dir, target) = new_fs_store("backup-target");
+        let backup = SstBackup::new(source.clone(), target.clone(), BackupOptions::default());
+        let sst_dir = "data/region1/";
+
+        let existing = new_sst_file(&source, sst_dir, 1).await;
+        // Flushed before the region is registered.
+        let flushed = new_sst_file(&source, sst_dir, 1).await;
+        backup.on_flush_completed(1, &flushed);
+        assert_eq!(1, backup.status()[0].pending_files);
+
+        backup.register_region(1, "data/region1", &[existing.clone()]);
+        let compacted = new_sst_file(&source, sst_dir, 1).await;
+        backup.on_compaction_completed(
+            1,
+            &RegionEdit {
+                region_version: 0,
+                flushed_sequence: None,
+                files_to_add: vec![compacted.clone()],
+                files_to_remove: vec![flushed.clone()],
+            },
+        );
+
+        for file in [&existing, &flushed, &compacted] {
+            assert!(backup.wait_backed_up(1, file.file_id).await);
+            assert!(is_backed_up(&target, sst_dir, file).await);
+        }
+        let status = backup.status();
+        assert_eq!(1, status.len());
+        assert_eq!(3, status[0].high_water_mark);
+        assert_eq!(0, status[0].pending_files);
+        assert_eq!(0, status[0].pending_bytes);
+        assert_eq!(Duration::ZERO, status[0].lag);
+
+        // Files of unknown regions can't be backed up.
+        assert!(!backup.wait_backed_up(2, existing.file_id).await);
+    }
+
+    #[tokio::test]
+    async fn test_backup_missing_file() {
+        let (_source_dir, source) = new_fs_store("backup-missing-source");
+        let (_target_dir, target) = new_fs_store("backup-missing-target");
+        let backup = SstBackup::new(source.clone(), target, BackupOptions::default());
+        let sst_dir = "data/region1/";
+
+        backup.register_region(1, sst_dir, &[]);
+        let file = new_sst_file(&source, sst_dir, 1).await;
+        let missing = FileMeta {
+            file_id: FileId::random(),
+            ..file.clone()
+        };
+        backup.on_flush_completed(1, &missing);
+        backup.on_flush_completed(1, &file);
+
+        // The missing file is not retried and blocks the high-water mark.
+        assert!(!backup.wait_backed_up(1, missing.file_id).await);
+        assert!(backup.wait_backed_up(1, file.file_id).await);
+        let status = &backup.status()[0];
+        assert_eq!(0, status.high_water_mark);
+        assert_eq!(1, status.pending_files);
+        assert_eq!(1, status.failed_files);
+        assert_eq!(missing.file_size, status.pending_bytes);
+    }
+}
@@ -13,11 +13,19 @@
 // limitations under the License.
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
 use common_query::logical_plan::Expr;
+use common_recordbatch::scan_stats::ScanStatsRecorderRef;
 use common_telemetry::debug;
 use common_time::range::TimestampRange;
+use common_time::Timestamp;
+use datafusion::physical_optimizer::pruning::PruningStatistics;
+use datafusion_common::{Column, ScalarValue};
+use datatypes::arrow::array::ArrayRef;
+use datatypes::data_type::DataType;
+use datatypes::value::Value;
 use snafu::ResultExt;
 use store_api::storage::{Chunk, ChunkReader, SchemaRef, SequenceNumber};
 use table::predicate::{Predicate, TimeRangePredicateBuilder};
@@ -26,7 +34,7 @@ use crate::error::{self, Error, Result};
 use crate::memtable::{IterContext, MemtableRef};
 use crate::read::{Batch, BoxedBatchReader, DedupReader, MergeReaderBuilder};
 use crate::schema::{ProjectedSchema, ProjectedSchemaRef, RegionSchemaRef};
-use crate::sst::{AccessLayerRef, FileHandle, LevelMetas, ReadOptions};
+use crate::sst::{AccessLayerRef, ColumnRange, FileHandle, LevelMetas, ReadOptions};
 
 /// Chunk reader implementation.
 // Now we use async-trait to implement the chunk reader, which is easier to implement than
@@ -84,6 +92,8 @@ pub struct ChunkReaderBuilder {
     iter_ctx: IterContext,
     memtables: Vec<MemtableRef>,
     files_to_read: Vec<FileHandle>,
+    ttl: Option<Duration>,
+    scan_stats: Option<ScanStatsRecorderRef>,
 }
 
 impl ChunkReaderBuilder {
@@ -96,6 +106,8 @@ impl ChunkReaderBuilder {
             iter_ctx: IterContext::default(),
             memtables: Vec::new(),
             files_to_read: Vec::new(),
+            ttl: None,
+            scan_stats: None,
         }
     }
 
@@ -125,6 +137,19 @@ impl ChunkReaderBuilder {
         self
     }
 
+    /// Sets the time-to-live of data to read, SSTs and rows older than `now - ttl`
+    /// are skipped even if they are not yet purged by compaction.
+    pub fn ttl(mut self, ttl: Option<Duration>) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Records the stats of reading SSTs to `scan_stats`.
+    pub fn scan_stats(mut self, scan_stats: Option<ScanStatsRecorderRef>) -> Self {
+        self.scan_stats = scan_stats;
+        self
+    }
+
     pub fn pick_memtables(mut self, memtables: MemtableRef) -> Self {
         self.memtables.push(memtables);
         self
@@ -159,7 +184,7 @@ impl ChunkReaderBuilder {
         );
 
         let schema = Arc::new(
-            ProjectedSchema::new(self.schema, self.projection)
+            ProjectedSchema::new(self.schema.clone(), self.projection)
                 .context(error::InvalidProjectionSnafu)?,
         );
 
@@ -173,24 +198,43 @@ impl ChunkReaderBuilder {
             reader_builder = reader_builder.push_batch_iter(iter);
         }
 
+        let files_in_key_range = if self.filters.is_empty() {
+            vec![true; self.files_to_read.len()]
+        } else {
+            let user_schema = self.schema.user_schema();
+            let stats = KeyRangePruningStatistics::new(&self.files_to_read, user_schema);
+            Predicate::new(self.filters.clone()).prune_with_stats(user_schema, &stats)
+        };
         let read_opts = ReadOptions {
             batch_size: self.iter_ctx.batch_size,
             projected_schema: schema.clone(),
             predicate: Predicate::new(self.filters),
             time_range: time_range_predicate,
+            scan_stats: self.scan_stats.clone(),
         };
-        for file in &self.files_to_read {
+        let mut files_pruned = 0;
+        for (file, in_key_range) in self.files_to_read.iter().zip(files_in_key_range) {
             if !Self::file_in_range(file, time_range_predicate) {
                 debug!(
                     "Skip file {:?}, predicate: {:?}",
                     file, time_range_predicate
                 );
+                files_pruned += 1;
+                continue;
+            }
+            if !in_key_range {
+                debug!("Skip file {:?}, out of key range", file);
+                files_pruned += 1;
                 continue;
             }
             let reader = self.sst_layer.read_sst(file.file_id(), &read_opts).await?;
 
             reader_builder = reader_builder.push_batch_reader(reader);
         }
+        if let Some(scan_stats) = &self.scan_stats {
+            scan_stats.add_files_pruned(files_pruned);
+            scan_stats.add_files_scanned(self.files_to_read.len() as u64 - files_pruned);
+        }
 
         let reader = reader_builder.build();
         let reader = DedupReader::new(schema.clone(), reader);
@@ -198,10 +242,18 @@ impl ChunkReaderBuilder {
         Ok(ChunkReaderImpl::new(schema, Box::new(reader)))
     }
 
-    /// Build time range predicate from schema and filters.
+    /// Build time range predicate from schema, filters and ttl.
     pub fn build_time_range_predicate(&self) -> TimestampRange {
         let Some(ts_col) = self.schema.user_schema().timestamp_column() else { return TimestampRange::min_to_max() };
-        TimeRangePredicateBuilder::new(&ts_col.name, &self.filters).build()
+        let range = TimeRangePredicateBuilder::new(&ts_col.name, &self.filters).build();
+
+        match self
+            .ttl
+            .and_then(|ttl| Timestamp::current_millis().sub(ttl).ok())
+        {
+            Some(expire_time) => range.and(&TimestampRange::from_start(expire_time)),
+            None => range,
+        }
     }
 
     /// Check if SST file's time range matches predicate.
@@ -216,3 +268,53 @@ impl ChunkReaderBuilder {
         file_ts_range.intersects(&predicate)
     }
 }
+
+/// [PruningStatistics] of SST files built from the min/max values of their row key columns.
+struct KeyRangePruningStatistics<'a> {
+    files: &'a [FileHandle],
+    schema: &'a SchemaRef,
+}
+
+impl<'a> KeyRangePruningStatistics<'a> {
+    fn new(files: &'a [FileHandle], schema: &'a SchemaRef) -> Self {
+        Self { files, schema }
+    }
+
+    /// Collects min or max values of `column` in each file, files without the range of the
+    /// column get a null value, which means unknown.
+    fn values_of(
+        &self,
+        column: &Column,
+        value_of: impl Fn(&ColumnRange) -> &Value,
+    ) -> Option<ArrayRef> {
+        let column_schema = self.schema.column_schema_by_name(&column.name)?;
+        let data_type = &column_schema.data_type;
+        let null = ScalarValue::try_from(&data_type.as_arrow_type()).ok()?;
+        let values = self.files.iter().map(|file| {
+            file.key_range()
+                .iter()
+                .find(|range| range.column == column.name)
+                .and_then(|range| value_of(range).try_to_scalar_value(data_type).ok())
+                .unwrap_or_else(|| null.clone())
+        });
+        ScalarValue::iter_to_array(values).ok()
+    }
+}
+
+impl<'a> PruningStatistics for KeyRangePruningStatistics<'a> {
+    fn min_values(&self, column: &Column) -> Option<ArrayRef> {
+        self.values_of(column, |range| &range.min)
+    }
+
+    fn max_values(&self, column: &Column) -> Option<ArrayRef> {
+        self.values_of(column, |range| &range.max)
+    }
+
+    fn num_containers(&self) -> usize {
+        self.files.len()
+    }
+
+    fn null_counts(&self, _column: &Column) -> Option<ArrayRef> {
+        None
+    }
+}
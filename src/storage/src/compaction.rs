@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#[cfg(any(test, feature = "test-util"))]
+pub mod harness;
 pub mod noop;
 mod picker;
 mod scheduler;
@@ -23,9 +25,10 @@ use std::sync::Arc;
 
 pub use picker::{Picker, PickerContext, SimplePicker};
 pub use scheduler::{CompactionHandler, CompactionRequestImpl};
-pub use task::{CompactionTask, CompactionTaskImpl};
+pub use task::{CompactionCallbackRef, CompactionOutput, CompactionTask, CompactionTaskImpl};
 
 use crate::scheduler::Scheduler;
+pub use crate::sst::MAX_LEVEL;
 
 pub type CompactionSchedulerRef<S> =
     Arc<dyn Scheduler<Request = CompactionRequestImpl<S>> + Send + Sync>;
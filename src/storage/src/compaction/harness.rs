@@ -0,0 +1,710 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Harness to check the correctness of compaction with randomly generated SSTs.
+//!
+//! The harness writes SSTs generated by [SstGenerator] to a region backed by an in-memory
+//! object store, compacts the region with a [Picker] and checks invariants of the result
+//! via [CompactionRun::check_invariants]. The generator is seeded so a failure can be
+//! reproduced by the seed in its panic message, e.g. by setting [SEED_ENV] to the seed.
+
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+use common_time::range::TimestampRange;
+use common_time::Timestamp;
+use datatypes::prelude::{ConcreteDataType, ScalarVector};
+use datatypes::vectors::{
+    Int64Vector, TimestampMillisecondVector, UInt64Vector, UInt8Vector, VectorRef,
+};
+pub use log_store::NoopLogStore;
+use object_store::services::Memory;
+use object_store::{ObjectStore, ObjectStoreBuilder};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use store_api::storage::{
+    ColumnDescriptorBuilder, ColumnFamilyDescriptorBuilder, OpType, OpenOptions, Region,
+    RegionDescriptor, RowKeyDescriptorBuilder, SequenceNumber,
+};
+use table::predicate::Predicate;
+
+use crate::background::JobPoolImpl;
+use crate::compaction::noop::NoopCompactionScheduler;
+use crate::compaction::{
+    CompactionRequestImpl, CompactionTask, CompactionTaskImpl, Picker, PickerContext,
+};
+use crate::engine;
+use crate::file_purger::noop::NoopFilePurgeHandler;
+use crate::flush::{FlushSchedulerImpl, SizeBasedStrategy};
+use crate::manifest::region::RegionManifest;
+use crate::memtable::{DefaultMemtableBuilder, IterContext, KeyValues, MemtableBuilder};
+use crate::metadata::RegionMetadata;
+use crate::read::BatchReader;
+use crate::region::{RegionImpl, StoreConfig};
+use crate::scheduler::{LocalScheduler, SchedulerConfig};
+use crate::schema::ProjectedSchema;
+pub use crate::sst::{FileId, FileMeta, Level};
+use crate::sst::{FsAccessLayer, ReadOptions, Source, WriteOptions};
+
+/// Environment variable to set the seed of the harness.
+pub const SEED_ENV: &str = "COMPACTION_HARNESS_SEED";
+
+const REGION_NAME: &str = "compaction-harness";
+/// Timestamps of rows in live files start from 2023-01-01T00:00:00Z.
+const LIVE_START_MILLIS: i64 = 1_672_531_200_000;
+/// Files whose rows are all older than this timestamp are expired. Rows in expired files
+/// start from the epoch.
+pub const EXPIRE_BEFORE_MILLIS: i64 = LIVE_START_MILLIS - 12 * 60 * 60 * 1000;
+
+/// Returns the seed in [SEED_ENV] or a random seed if it's absent.
+pub fn seed_from_env() -> u64 {
+    std::env::var(SEED_ENV)
+        .ok()
+        .and_then(|seed| seed.parse().ok())
+        .unwrap_or_else(rand::random)
+}
+
+/// A row in the region of the harness, whose schema is `(k0, timestamp, v0)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Row {
+    pub key: i64,
+    /// Timestamp in milliseconds.
+    pub ts: i64,
+    pub sequence: SequenceNumber,
+    pub op_type: OpType,
+    pub value: Option<i64>,
+}
+
+/// Level and rows of an SST to write.
+#[derive(Debug, Clone)]
+pub struct SstSpec {
+    pub level: Level,
+    pub rows: Vec<Row>,
+}
+
+/// Options of [SstGenerator].
+#[derive(Debug, Clone)]
+pub struct SstGenOptions {
+    /// Max number of live files, at least one live file is generated.
+    pub max_files: usize,
+    /// Max number of expired files.
+    pub max_expired_files: usize,
+    /// Max number of rows in a file, at least one row is generated.
+    pub max_rows_per_file: usize,
+    /// Keys are chosen from `[0, key_space)`, a smaller space produces more duplicate keys.
+    pub key_space: i64,
+    /// Time range of rows in all live (or expired) files.
+    pub time_window: Duration,
+    /// Max time span of rows in a file.
+    pub max_time_span: Duration,
+    /// Timestamps are multiples of the step, a larger step produces more duplicate rows.
+    pub time_step: Duration,
+    /// Probability of a row to be a deletion.
+    pub delete_ratio: f64,
+}
+
+impl Default for SstGenOptions {
+    fn default() -> Self {
+        Self {
+            max_files: 8,
+            max_expired_files: 2,
+            max_rows_per_file: 64,
+            key_space: 16,
+            time_window: Duration::from_secs(6 * 60 * 60),
+            max_time_span: Duration::from_secs(3 * 60 * 60),
+            time_step: Duration::from_secs(10 * 60),
+            delete_ratio: 0.1,
+        }
+    }
+}
+
+/// Generates level 0 SSTs with random keys and time ranges. Files generated later have
+/// larger sequences, like files flushed later.
+pub struct SstGenerator {
+    rng: StdRng,
+    options: SstGenOptions,
+    next_sequence: SequenceNumber,
+}
+
+impl SstGenerator {
+    pub fn new(seed: u64, options: SstGenOptions) -> SstGenerator {
+        SstGenerator {
+            rng: StdRng::seed_from_u64(seed),
+            options,
+            next_sequence: 1,
+        }
+    }
+
+    /// Generates live files and expired files in random order.
+    pub fn generate(&mut self) -> Vec<SstSpec> {
+        let num_files = self.rng.gen_range(1..=self.options.max_files.max(1));
+        let num_expired = self.rng.gen_range(0..=self.options.max_expired_files);
+        let mut expired = vec![false; num_files];
+        expired.extend(std::iter::repeat(true).take(num_expired));
+        for i in (1..expired.len()).rev() {
+            expired.swap(i, self.rng.gen_range(0..=i));
+        }
+
+        expired
+            .into_iter()
+            .map(|expired| {
+                let start = if expired { 0 } else { LIVE_START_MILLIS };
+                self.gen_sst(start)
+            })
+            .collect()
+    }
+
+    fn gen_sst(&mut self, start_millis: i64) -> SstSpec {
+        let step = (self.options.time_step.as_millis() as i64).max(1);
+        let window_steps = (self.options.time_window.as_millis() as i64 / step).max(1);
+        let span_steps = (self.options.max_time_span.as_millis() as i64 / step).min(window_steps);
+        let first_step = self.rng.gen_range(0..window_steps);
+        let last_step = (first_step + self.rng.gen_range(0..=span_steps)).min(window_steps - 1);
+
+        let num_rows = self
+            .rng
+            .gen_range(1..=self.options.max_rows_per_file.max(1));
+        let rows = (0..num_rows)
+            .map(|_| {
+                let op_type = if self.rng.gen_bool(self.options.delete_ratio) {
+                    OpType::Delete
+                } else {
+                    OpType::Put
+                };
+                let sequence = self.next_sequence;
+                self.next_sequence += 1;
+                Row {
+                    key: self.rng.gen_range(0..self.options.key_space.max(1)),
+                    ts: start_millis + self.rng.gen_range(first_step..=last_step) * step,
+                    sequence,
+                    op_type,
+                    value: self.rng.gen_bool(0.9).then(|| self.rng.gen()),
+                }
+            })
+            .collect();
+
+        SstSpec { level: 0, rows }
+    }
+}
+
+/// An SST in the region and its rows.
+#[derive(Debug, Clone)]
+pub struct SstFile {
+    pub meta: FileMeta,
+    pub rows: Vec<Row>,
+}
+
+/// Region backed by an in-memory object store to run compactions.
+pub struct CompactionHarness {
+    seed: u64,
+    object_store: ObjectStore,
+    region: RegionImpl<NoopLogStore>,
+}
+
+impl CompactionHarness {
+    /// Creates a harness with an empty region, `seed` is reported in failures.
+    pub async fn new(seed: u64) -> CompactionHarness {
+        let accessor = Memory::default().build().unwrap();
+        let object_store = ObjectStore::new(accessor).finish();
+        let metadata: RegionMetadata = region_desc().try_into().unwrap();
+        let region = RegionImpl::create(metadata, new_store_config(&object_store))
+            .await
+            .unwrap();
+
+        CompactionHarness {
+            seed,
+            object_store,
+            region,
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Writes SSTs of `specs` and adds them to the region.
+    pub async fn add_ssts(&self, specs: &[SstSpec]) -> Vec<FileMeta> {
+        let schema = self.region.schema();
+        let sst_layer = self.region.sst_layer();
+        let mut files = Vec::with_capacity(specs.len());
+        for spec in specs {
+            let memtable = DefaultMemtableBuilder::default().build(schema.clone());
+            for row in &spec.rows {
+                memtable.write(&row_to_kvs(row)).unwrap();
+            }
+            let iter = memtable
+                .iter(&IterContext {
+                    for_flush: true,
+                    ..Default::default()
+                })
+                .unwrap();
+
+            let file_id = FileId::random();
+            let sst_info = sst_layer
+                .write_sst(file_id, Source::Iter(iter), &WriteOptions::default())
+                .await
+                .unwrap();
+            files.push(FileMeta {
+                region_id: self.region.id(),
+                file_id,
+                time_range: sst_info.time_range,
+                level: spec.level,
+                file_size: sst_info.file_size,
+                bloom_filter: sst_info.bloom_filter,
+                key_range: sst_info.key_range,
+                row_groups: sst_info.row_groups,
+                num_rows: sst_info.num_rows,
+            });
+        }
+
+        self.region.add_files(files.clone()).await.unwrap();
+        files
+    }
+
+    /// Compacts the region once with `picker`, files older than [EXPIRE_BEFORE_MILLIS]
+    /// are expired.
+    pub async fn compact<P>(&self, picker: &P) -> CompactionRun
+    where
+        P: Picker<
+            Request = CompactionRequestImpl<NoopLogStore>,
+            Task = CompactionTaskImpl<NoopLogStore>,
+        >,
+    {
+        let ttl = Timestamp::current_millis().value() - EXPIRE_BEFORE_MILLIS;
+        self.region.set_ttl(Some(Duration::from_millis(ttl as u64)));
+        let files_before = self.read_ssts().await;
+
+        let request = self.region.compaction_request();
+        let task = picker.pick(&PickerContext {}, &request).unwrap();
+        let (inputs, output_levels) = match &task {
+            Some(task) => (
+                task.outputs
+                    .iter()
+                    .flat_map(|output| output.inputs.iter().map(|file| file.file_id()))
+                    .collect(),
+                task.outputs
+                    .iter()
+                    .map(|output| output.output_level)
+                    .collect(),
+            ),
+            None => (vec![], vec![]),
+        };
+        let picked = task.is_some();
+        if let Some(task) = task {
+            if let Err(e) = task.run().await {
+                panic!(
+                    "Failed to run compaction task with seed {}, err: {:?}",
+                    self.seed, e
+                );
+            }
+        }
+
+        let files_after = self.read_ssts().await;
+        let recovered = RegionImpl::open(
+            REGION_NAME.to_string(),
+            new_store_config(&self.object_store),
+            &OpenOptions::default(),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+
+        CompactionRun {
+            seed: self.seed,
+            picked,
+            inputs,
+            output_levels,
+            files_before,
+            files_after,
+            files_recovered: recovered.file_metas(),
+        }
+    }
+
+    async fn read_ssts(&self) -> Vec<SstFile> {
+        let mut files = Vec::new();
+        for meta in self.region.file_metas() {
+            let rows = self.read_rows(meta.file_id).await;
+            files.push(SstFile { meta, rows });
+        }
+        files
+    }
+
+    /// Reads all rows in the SST, including duplicate rows and deletions.
+    async fn read_rows(&self, file_id: FileId) -> Vec<Row> {
+        let projected_schema = Arc::new(ProjectedSchema::no_projection(self.region.schema()));
+        let store_schema = projected_schema.schema_to_read().clone();
+        let opts = ReadOptions {
+            batch_size: 1024,
+            projected_schema,
+            predicate: Predicate::empty(),
+            time_range: TimestampRange::min_to_max(),
+            scan_stats: None,
+        };
+        let mut reader = self
+            .region
+            .sst_layer()
+            .read_sst(file_id, &opts)
+            .await
+            .unwrap();
+
+        let mut rows = Vec::new();
+        while let Some(batch) = reader.next_batch().await.unwrap() {
+            // Columns to read are (k0, timestamp, v0, sequence, op_type).
+            let keys = downcast::<Int64Vector>(batch.column(0));
+            let timestamps = downcast::<TimestampMillisecondVector>(batch.column(1));
+            let values = downcast::<Int64Vector>(batch.column(2));
+            let sequences = downcast::<UInt64Vector>(batch.column(store_schema.sequence_index()));
+            let op_types = downcast::<UInt8Vector>(batch.column(store_schema.op_type_index()));
+            for i in 0..batch.num_rows() {
+                rows.push(Row {
+                    key: keys.get_data(i).unwrap(),
+                    ts: timestamps.get_data(i).unwrap().0.value(),
+                    sequence: sequences.get_data(i).unwrap(),
+                    op_type: if op_types.get_data(i).unwrap() == OpType::Delete.as_u8() {
+                        OpType::Delete
+                    } else {
+                        OpType::Put
+                    },
+                    value: values.get_data(i),
+                });
+            }
+        }
+        rows
+    }
+}
+
+/// Files of the region around a compaction.
+#[derive(Debug)]
+pub struct CompactionRun {
+    pub seed: u64,
+    /// Whether the picker built a task.
+    pub picked: bool,
+    /// Input files of the task.
+    pub inputs: Vec<FileId>,
+    /// Output levels of the task.
+    pub output_levels: Vec<Level>,
+    pub files_before: Vec<SstFile>,
+    pub files_after: Vec<SstFile>,
+    /// Files recovered from the manifest after compaction.
+    pub files_recovered: Vec<FileMeta>,
+}
+
+impl CompactionRun {
+    /// Panics with the seed if any invariant is violated.
+    pub fn check_invariants(&self) {
+        let checks = [
+            self.check_no_rows_lost(),
+            self.check_no_duplicate_rows(),
+            self.check_output_levels(),
+            self.check_expired_removed(),
+            self.check_manifest_consistent(),
+        ];
+        for check in checks {
+            if let Err(msg) = check {
+                panic!(
+                    "Compaction invariant violated with seed {}: {}",
+                    self.seed, msg
+                );
+            }
+        }
+    }
+
+    /// Rows visible before compaction, except those in expired files, are still visible.
+    pub fn check_no_rows_lost(&self) -> Result<(), String> {
+        let live_before = self.files_before.iter().filter(|file| !is_expired(file));
+        let expect = visible_rows(live_before);
+        let actual = visible_rows(self.files_after.iter());
+        if expect == actual {
+            return Ok(());
+        }
+
+        let lost: Vec<_> = expect
+            .iter()
+            .filter(|(key, row)| actual.get(key) != Some(row))
+            .collect();
+        let unexpected: Vec<_> = actual
+            .iter()
+            .filter(|(key, row)| expect.get(key) != Some(row))
+            .collect();
+        Err(format!(
+            "visible rows changed, lost: {lost:?}, unexpected: {unexpected:?}"
+        ))
+    }
+
+    /// No two rows have the same key, timestamp and sequence after compaction.
+    pub fn check_no_duplicate_rows(&self) -> Result<(), String> {
+        let mut seen = HashSet::new();
+        for file in &self.files_after {
+            for row in &file.rows {
+                if !seen.insert((row.key, row.ts, row.sequence)) {
+                    return Err(format!(
+                        "duplicate row {:?} in file {}",
+                        row, file.meta.file_id
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Inputs are removed and files added by compaction are in output levels.
+    pub fn check_output_levels(&self) -> Result<(), String> {
+        let before: HashSet<_> = self.files_before.iter().map(|f| f.meta.file_id).collect();
+        for file in &self.files_after {
+            if self.inputs.contains(&file.meta.file_id) {
+                return Err(format!("input file {} is not removed", file.meta.file_id));
+            }
+            if !before.contains(&file.meta.file_id)
+                && !self.output_levels.contains(&file.meta.level)
+            {
+                return Err(format!(
+                    "output file {} in level {}, expect levels: {:?}",
+                    file.meta.file_id, file.meta.level, self.output_levels
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Expired files are removed if the picker built a task.
+    pub fn check_expired_removed(&self) -> Result<(), String> {
+        if !self.picked {
+            return Ok(());
+        }
+        match self.files_after.iter().find(|file| is_expired(file)) {
+            Some(file) => Err(format!("expired file {:?} is not removed", file.meta)),
+            None => Ok(()),
+        }
+    }
+
+    /// Files recovered from the manifest are the same as files in the region.
+    pub fn check_manifest_consistent(&self) -> Result<(), String> {
+        let expect: HashSet<_> = self.files_after.iter().map(|f| f.meta.clone()).collect();
+        let actual: HashSet<_> = self.files_recovered.iter().cloned().collect();
+        if expect == actual {
+            Ok(())
+        } else {
+            Err(format!(
+                "files in manifest {actual:?} differ from files in region {expect:?}"
+            ))
+        }
+    }
+}
+
+/// Generates SSTs by `options`, compacts them with `picker` and checks invariants of the
+/// compaction, panics with the seed if any invariant is violated.
+pub async fn check_compaction<P>(seed: u64, options: SstGenOptions, picker: &P) -> CompactionRun
+where
+    P: Picker<
+        Request = CompactionRequestImpl<NoopLogStore>,
+        Task = CompactionTaskImpl<NoopLogStore>,
+    >,
+{
+    let specs = SstGenerator::new(seed, options).generate();
+    let harness = CompactionHarness::new(seed).await;
+    harness.add_ssts(&specs).await;
+    let run = harness.compact(picker).await;
+    run.check_invariants();
+    run
+}
+
+fn is_expired(file: &SstFile) -> bool {
+    file.meta
+        .time_range
+        .map(|(_, end)| end.value() < EXPIRE_BEFORE_MILLIS)
+        .unwrap_or(false)
+}
+
+/// Returns the latest row of each key and timestamp, deleted keys are not visible.
+fn visible_rows<'a>(files: impl Iterator<Item = &'a SstFile>) -> BTreeMap<(i64, i64), Row> {
+    let mut latest: BTreeMap<(i64, i64), Row> = BTreeMap::new();
+    for row in files.flat_map(|file| file.rows.iter()) {
+        let entry = latest
+            .entry((row.key, row.ts))
+            .or_insert_with(|| row.clone());
+        if row.sequence > entry.sequence {
+            *entry = row.clone();
+        }
+    }
+    latest.retain(|_, row| row.op_type == OpType::Put);
+    latest
+}
+
+fn downcast<T: 'static>(vector: &VectorRef) -> &T {
+    vector.as_any().downcast_ref::<T>().unwrap()
+}
+
+fn row_to_kvs(row: &Row) -> KeyValues {
+    KeyValues {
+        sequence: row.sequence,
+        op_type: row.op_type,
+        start_index_in_batch: 0,
+        keys: vec![
+            Arc::new(Int64Vector::from_slice([row.key])),
+            Arc::new(TimestampMillisecondVector::from_vec(vec![row.ts])),
+        ],
+        values: vec![Arc::new(Int64Vector::from(vec![row.value]))],
+    }
+}
+
+/// Creates desc with schema (k0, timestamp, v0).
+fn region_desc() -> RegionDescriptor {
+    let timestamp = ColumnDescriptorBuilder::new(
+        1,
+        "timestamp",
+        ConcreteDataType::timestamp_millisecond_datatype(),
+    )
+    .is_nullable(false)
+    .is_time_index(true)
+    .build()
+    .unwrap();
+    let k0 = ColumnDescriptorBuilder::new(2, "k0", ConcreteDataType::int64_datatype())
+        .is_nullable(false)
+        .build()
+        .unwrap();
+    let v0 = ColumnDescriptorBuilder::new(3, "v0", ConcreteDataType::int64_datatype())
+        .build()
+        .unwrap();
+
+    RegionDescriptor {
+        id: 0,
+        name: REGION_NAME.to_string(),
+        row_key: RowKeyDescriptorBuilder::new(timestamp)
+            .enable_version_column(false)
+            .push_column(k0)
+            .build()
+            .unwrap(),
+        default_cf: ColumnFamilyDescriptorBuilder::default()
+            .push_column(v0)
+            .build()
+            .unwrap(),
+        extra_cfs: Vec::new(),
+    }
+}
+
+fn new_store_config(object_store: &ObjectStore) -> StoreConfig<NoopLogStore> {
+    let sst_dir = engine::region_sst_dir("", REGION_NAME);
+    let manifest_dir = engine::region_manifest_dir("", REGION_NAME);
+    let job_pool = Arc::new(JobPoolImpl {});
+
+    StoreConfig {
+        log_store: Arc::new(NoopLogStore::default()),
+        sst_layer: Arc::new(FsAccessLayer::new(&sst_dir, object_store.clone())),
+        manifest: RegionManifest::new(&manifest_dir, object_store.clone()),
+        memtable_builder: Arc::new(DefaultMemtableBuilder::default()),
+        flush_scheduler: Arc::new(FlushSchedulerImpl::new(job_pool)),
+        flush_strategy: Arc::new(SizeBasedStrategy::default()),
+        compaction_scheduler: Arc::new(NoopCompactionScheduler::default()),
+        engine_config: Default::default(),
+        file_purger: Arc::new(LocalScheduler::new(
+            SchedulerConfig::default(),
+            NoopFilePurgeHandler,
+        )),
+        ttl: None,
+        compaction: Default::default(),
+        memtable_budget: None,
+        event_dispatcher: Default::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compaction::SimplePicker;
+
+    #[tokio::test]
+    async fn test_simple_picker_invariants() {
+        let seed = seed_from_env();
+        let picker = SimplePicker::<NoopLogStore>::default();
+        for i in 0..16 {
+            let run =
+                check_compaction(seed.wrapping_add(i), SstGenOptions::default(), &picker).await;
+            assert!(run.picked);
+        }
+    }
+
+    #[test]
+    fn test_generate_with_seed() {
+        let options = SstGenOptions::default();
+        let specs = SstGenerator::new(1, options.clone()).generate();
+        let rows = |specs: &[SstSpec]| {
+            specs
+                .iter()
+                .map(|spec| spec.rows.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            rows(&specs),
+            rows(&SstGenerator::new(1, options.clone()).generate())
+        );
+
+        assert!(specs
+            .iter()
+            .any(|spec| spec.rows.iter().all(|row| row.ts >= LIVE_START_MILLIS)));
+        let mut sequences: Vec<_> = specs
+            .iter()
+            .flat_map(|spec| spec.rows.iter().map(|row| row.sequence))
+            .collect();
+        let num_rows = sequences.len();
+        sequences.dedup();
+        assert_eq!(num_rows, sequences.len());
+    }
+
+    #[test]
+    fn test_check_rows_lost() {
+        let row = |key, sequence, op_type| Row {
+            key,
+            ts: LIVE_START_MILLIS,
+            sequence,
+            op_type,
+            value: Some(key),
+        };
+        let file = |rows| SstFile {
+            meta: FileMeta {
+                region_id: 0,
+                file_id: FileId::random(),
+                time_range: Some((
+                    Timestamp::new_millisecond(LIVE_START_MILLIS),
+                    Timestamp::new_millisecond(LIVE_START_MILLIS),
+                )),
+                level: 0,
+                file_size: 0,
+                bloom_filter: false,
+                key_range: vec![],
+                row_groups: vec![],
+                num_rows: 0,
+            },
+            rows,
+        };
+        let mut run = CompactionRun {
+            seed: 0,
+            picked: true,
+            inputs: vec![],
+            output_levels: vec![1],
+            files_before: vec![
+                file(vec![row(1, 1, OpType::Put), row(2, 2, OpType::Put)]),
+                file(vec![row(1, 3, OpType::Delete)]),
+            ],
+            files_after: vec![file(vec![row(2, 2, OpType::Put)])],
+            files_recovered: vec![],
+        };
+        // Deleted rows don't need to be kept.
+        run.check_no_rows_lost().unwrap();
+
+        run.files_after = vec![file(vec![row(1, 1, OpType::Put), row(2, 2, OpType::Put)])];
+        let err = run.check_no_rows_lost().unwrap_err();
+        assert!(err.contains("unexpected"), "{err}");
+    }
+}
@@ -18,7 +18,7 @@ use std::marker::PhantomData;
 use store_api::storage::RegionId;
 
 use crate::compaction::{CompactionTask, Picker, PickerContext};
-use crate::scheduler::{Request, Scheduler};
+use crate::scheduler::{Request, Scheduler, SchedulerStatus};
 
 pub struct NoopCompactionScheduler<R> {
     _phantom_data: PhantomData<R>,
@@ -89,4 +89,14 @@ where
     async fn stop(&self, _await_termination: bool) -> crate::error::Result<()> {
         Ok(())
     }
+
+    fn max_inflight_tasks(&self) -> usize {
+        0
+    }
+
+    fn set_max_inflight_tasks(&self, _max_inflight_tasks: usize) {}
+
+    fn status(&self) -> SchedulerStatus {
+        SchedulerStatus::default()
+    }
 }
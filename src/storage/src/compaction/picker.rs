@@ -23,10 +23,13 @@ use store_api::logstore::LogStore;
 
 use crate::compaction::scheduler::CompactionRequestImpl;
 use crate::compaction::strategy::{SimpleTimeWindowStrategy, StrategyRef};
-use crate::compaction::task::{CompactionTask, CompactionTaskImpl};
+use crate::compaction::task::{
+    CompactionCallbackRef, CompactionOutput, CompactionTask, CompactionTaskImpl,
+};
+use crate::config::BloomFilterConfig;
 use crate::error::TtlCalculationSnafu;
 use crate::scheduler::Request;
-use crate::sst::{FileHandle, Level};
+use crate::sst::{FileHandle, Level, MAX_LEVEL};
 use crate::version::LevelMetasRef;
 
 /// Picker picks input SST files and builds the compaction task.
@@ -44,9 +47,17 @@ pub trait Picker: Send + 'static {
 
 pub struct PickerContext {}
 
-/// L0 -> L1 compaction based on time windows.
+/// Compaction based on time windows, from level 0 down to `max_level`.
 pub struct SimplePicker<S> {
     strategy: StrategyRef,
+    /// Max level of compaction output, files in this level are not compacted any more.
+    max_level: Level,
+    /// Max number of outputs of a task built concurrently.
+    max_concurrent_outputs: usize,
+    /// Invoked after each compaction is applied to the region.
+    on_compacted: Option<CompactionCallbackRef>,
+    /// Writes bloom filters of row key columns to outputs if set.
+    bloom_filter: Option<BloomFilterConfig>,
     _phantom_data: PhantomData<S>,
 }
 
@@ -60,10 +71,41 @@ impl<S> SimplePicker<S> {
     pub fn new(strategy: StrategyRef) -> Self {
         Self {
             strategy,
+            max_level: 1,
+            max_concurrent_outputs: usize::MAX,
+            on_compacted: None,
+            bloom_filter: None,
             _phantom_data: Default::default(),
         }
     }
 
+    /// Sets the max level of compaction output, 1 by default so only level 0 is compacted.
+    /// The caller should ensure `max_level` is in `[1, MAX_LEVEL)`.
+    pub fn with_max_level(mut self, max_level: Level) -> Self {
+        debug_assert!(max_level >= 1 && max_level < MAX_LEVEL);
+        self.max_level = max_level;
+        self
+    }
+
+    /// Sets the max number of outputs of a task built concurrently, unbounded by default.
+    /// The compaction options of a region take precedence over it.
+    pub fn with_max_concurrent_outputs(mut self, max_concurrent_outputs: usize) -> Self {
+        self.max_concurrent_outputs = max_concurrent_outputs;
+        self
+    }
+
+    /// Sets the callback invoked with the edit of each compaction after it's applied.
+    pub fn with_on_compacted(mut self, on_compacted: CompactionCallbackRef) -> Self {
+        self.on_compacted = Some(on_compacted);
+        self
+    }
+
+    /// Sets the bloom filter options of compaction outputs, no filter is written by default.
+    pub fn with_bloom_filter(mut self, bloom_filter: Option<BloomFilterConfig>) -> Self {
+        self.bloom_filter = bloom_filter;
+        self
+    }
+
     fn get_expired_ssts(
         &self,
         levels: &LevelMetasRef,
@@ -81,6 +123,27 @@ impl<S> SimplePicker<S> {
         }
         Ok(expired_ssts)
     }
+
+    /// Picks compaction outputs of levels in `[0, max_level)`.
+    fn pick_outputs(&self, ctx: &PickerContext, levels: &LevelMetasRef) -> Vec<CompactionOutput> {
+        let mut outputs = vec![];
+        for level_num in 0..levels.level_num().min(self.max_level as usize) {
+            let level = levels.level(level_num as Level);
+            let level_outputs = self.strategy.pick(ctx, level);
+
+            if level_outputs.is_empty() {
+                debug!("No SST file can be compacted at level {}", level_num);
+                continue;
+            }
+
+            debug!(
+                "Found SST files to compact {:?} on level: {}",
+                level_outputs, level_num
+            );
+            outputs.extend(level_outputs);
+        }
+        outputs
+    }
 }
 
 impl<S: LogStore> Picker for SimplePicker<S> {
@@ -110,31 +173,83 @@ impl<S: LogStore> Picker for SimplePicker<S> {
             expired_ssts.iter().for_each(|f| f.mark_compacting(true));
         }
 
-        for level_num in 0..levels.level_num() {
-            let level = levels.level(level_num as u8);
-            let outputs = self.strategy.pick(ctx, level);
+        let outputs = self.pick_outputs(ctx, levels);
+        if outputs.is_empty() {
+            return Ok(None);
+        }
 
-            if outputs.is_empty() {
-                debug!("No SST file can be compacted at level {}", level_num);
-                continue;
-            }
+        Ok(Some(CompactionTaskImpl {
+            schema: req.schema(),
+            sst_layer: req.sst_layer.clone(),
+            outputs,
+            writer: req.writer.clone(),
+            shared_data: req.shared.clone(),
+            wal: req.wal.clone(),
+            manifest: req.manifest.clone(),
+            expired_ssts,
+            max_concurrent_outputs: req
+                .shared
+                .compaction_options()
+                .max_concurrent_outputs
+                .unwrap_or(self.max_concurrent_outputs),
+            on_compacted: self.on_compacted.clone(),
+            bloom_filter: self.bloom_filter,
+        }))
+    }
+}
 
-            debug!(
-                "Found SST files to compact {:?} on level: {}",
-                outputs, level_num
-            );
-            return Ok(Some(CompactionTaskImpl {
-                schema: req.schema(),
-                sst_layer: req.sst_layer.clone(),
-                outputs,
-                writer: req.writer.clone(),
-                shared_data: req.shared.clone(),
-                wal: req.wal.clone(),
-                manifest: req.manifest.clone(),
-                expired_ssts,
-            }));
+#[cfg(test)]
+mod tests {
+    use common_time::Timestamp;
+    use log_store::NoopLogStore;
+
+    use super::*;
+    use crate::file_purger::noop::new_noop_file_purger;
+    use crate::sst::{FileId, FileMeta, LevelMetas};
+    use crate::test_util::access_layer_util::MockAccessLayer;
+
+    fn new_file(level: Level, start_ts_millis: i64, end_ts_millis: i64) -> FileMeta {
+        FileMeta {
+            region_id: 0,
+            file_id: FileId::random(),
+            time_range: Some((
+                Timestamp::new_millisecond(start_ts_millis),
+                Timestamp::new_millisecond(end_ts_millis),
+            )),
+            level,
+            file_size: 0,
+            bloom_filter: false,
+            key_range: vec![],
+            row_groups: vec![],
+            num_rows: 0,
         }
+    }
 
-        Ok(None)
+    #[test]
+    fn test_pick_with_max_level() {
+        // Each level has two files in the same time bucket.
+        let files =
+            (0..MAX_LEVEL).flat_map(|level| [new_file(level, 0, 10), new_file(level, 5, 20)]);
+        let levels = Arc::new(
+            LevelMetas::new(Arc::new(MockAccessLayer), new_noop_file_purger())
+                .merge(files, std::iter::empty()),
+        );
+        let ctx = PickerContext {};
+
+        let picker = SimplePicker::<NoopLogStore>::default();
+        let outputs = picker.pick_outputs(&ctx, &levels);
+        assert_eq!(1, outputs.len());
+        assert_eq!(1, outputs[0].output_level);
+
+        for max_level in 1..MAX_LEVEL {
+            let picker = SimplePicker::<NoopLogStore>::default().with_max_level(max_level);
+            let mut output_levels: Vec<_> = picker
+                .pick_outputs(&ctx, &levels)
+                .iter()
+                .map(|output| output.output_level)
+                .collect();
+            output_levels.sort_unstable();
+            assert_eq!((1..=max_level).collect::<Vec<_>>(), output_levels);
+        }
     }
 }
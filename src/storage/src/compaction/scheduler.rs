@@ -16,6 +16,7 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use common_telemetry::{debug, error, info};
+use object_store::retry::scope_task;
 use store_api::logstore::LogStore;
 use store_api::storage::RegionId;
 use tokio::sync::Notify;
@@ -39,6 +40,11 @@ impl<S: LogStore> Request for CompactionRequestImpl<S> {
     fn key(&self) -> RegionId {
         self.region_id
     }
+
+    /// Regions with more files in level 0 are compacted first.
+    fn priority(&self) -> usize {
+        self.levels().level(0).file_num()
+    }
 }
 
 /// Region compaction request.
@@ -96,7 +102,8 @@ where
         debug!("Compaction task, region: {:?}, task: {:?}", region_id, task);
         // TODO(hl): we need to keep a track of task handle here to allow task cancellation.
         common_runtime::spawn_bg(async move {
-            if let Err(e) = task.run().await {
+            // Requests of the whole compaction share the retry budget of the task.
+            if let Err(e) = scope_task(task.run()).await {
                 // TODO(hl): maybe resubmit compaction task on failure?
                 error!(e; "Failed to compact region: {:?}", region_id);
             } else {
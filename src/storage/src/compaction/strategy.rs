@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 use common_telemetry::{debug, warn};
@@ -31,17 +31,16 @@ pub trait Strategy {
 
 pub type StrategyRef = Arc<dyn Strategy + Send + Sync>;
 
-/// SimpleTimeWindowStrategy only handles level 0 to level 1 compaction in a time-window tiered
-/// manner. It picks all SSTs in level 0 and writes rows in these SSTs to a new file partitioned
-/// by a inferred time bucket in level 1.
+/// SimpleTimeWindowStrategy compacts SSTs of a level to the next level in a time-window tiered
+/// manner. It picks SSTs in the level and writes rows in these SSTs to new files partitioned
+/// by a inferred time bucket in the next level.
+///
+/// All SSTs in level 0 are picked. SSTs in deeper levels are only picked if they share a time
+/// bucket with other SSTs in the same level, so each level holds fewer and larger files.
 pub struct SimpleTimeWindowStrategy {}
 
 impl Strategy for SimpleTimeWindowStrategy {
     fn pick(&self, _ctx: &PickerContext, level: &LevelMeta) -> Vec<CompactionOutput> {
-        // SimpleTimeWindowStrategy only handles level 0 to level 1 compaction.
-        if level.level() != 0 {
-            return vec![];
-        }
         let files = find_compactable_files(level);
         debug!("Compactable files found: {:?}", files);
         if files.is_empty() {
@@ -49,14 +48,19 @@ impl Strategy for SimpleTimeWindowStrategy {
         }
 
         let time_bucket = infer_time_bucket(&files);
-        let buckets = calculate_time_buckets(time_bucket, &files);
+        let mut buckets = calculate_time_buckets(time_bucket, &files);
+        if level.level() != 0 {
+            retain_overlapping_buckets(&mut buckets);
+        }
         debug!("File bucket:{}, file groups: {:?}", time_bucket, buckets);
         buckets
             .into_iter()
             .map(|(bound, files)| CompactionOutput {
-                output_level: 1,
+                output_level: level.level() + 1,
                 bucket_bound: bound,
                 bucket: time_bucket,
+                // Merging more files reduces more read amplification.
+                priority: files.len(),
                 inputs: files,
             })
             .collect()
@@ -96,6 +100,33 @@ fn calculate_time_buckets(bucket_sec: i64, files: &[FileHandle]) -> HashMap<i64,
     buckets
 }
 
+/// Retains buckets that have more than one file, and all other buckets of these files. Input
+/// files are removed after compaction, so all their rows must be written to the outputs.
+fn retain_overlapping_buckets(buckets: &mut HashMap<i64, Vec<FileHandle>>) {
+    let mut picked: HashSet<_> = buckets
+        .values()
+        .filter(|files| files.len() > 1)
+        .flatten()
+        .map(|file| file.file_id())
+        .collect();
+    let mut retained = HashSet::new();
+    loop {
+        let mut changed = false;
+        for (bound, files) in buckets.iter() {
+            if retained.contains(bound) || !files.iter().any(|f| picked.contains(&f.file_id())) {
+                continue;
+            }
+            retained.insert(*bound);
+            picked.extend(files.iter().map(|f| f.file_id()));
+            changed = true;
+        }
+        if !changed {
+            break;
+        }
+    }
+    buckets.retain(|bound, _| retained.contains(bound));
+}
+
 /// Calculates timestamp span between start and end timestamp.
 fn file_time_bucket_span(start_sec: i64, end_sec: i64, bucket_sec: i64) -> Vec<i64> {
     assert!(start_sec <= end_sec);
@@ -169,7 +200,7 @@ mod tests {
 
     use super::*;
     use crate::file_purger::noop::new_noop_file_purger;
-    use crate::sst::{FileId, FileMeta};
+    use crate::sst::{FileId, FileMeta, Level, LevelMetas};
 
     #[test]
     fn test_time_bucket_span() {
@@ -227,6 +258,79 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_pick_overlapping_files_in_level1() {
+        let week = TIME_BUCKETS[4] * 1000;
+        let (a, b, c, d, e) = (
+            FileId::random(),
+            FileId::random(),
+            FileId::random(),
+            FileId::random(),
+            FileId::random(),
+        );
+        let pick = |files: &[(FileId, i64, i64)]| {
+            let levels = new_level_metas(1, files);
+            let mut outputs = SimpleTimeWindowStrategy {}.pick(&PickerContext {}, levels.level(1));
+            outputs.sort_by_key(|output| output.bucket_bound);
+            outputs
+        };
+
+        // Only a and b share a bucket.
+        let outputs = pick(&[
+            (a, 0, 1000),
+            (b, 500, 1500),
+            (c, 2 * week, 2 * week + 1000),
+            (d, 3 * week, 3 * week + 1000),
+        ]);
+        assert_eq!(1, outputs.len());
+        assert_eq!(2, outputs[0].output_level);
+        assert_eq!(0, outputs[0].bucket_bound);
+        let inputs: HashSet<_> = outputs[0].inputs.iter().map(|f| f.file_id()).collect();
+        assert_eq!(HashSet::from([a, b]), inputs);
+
+        // e also spans the buckets of c, so c is compacted with it.
+        let outputs = pick(&[
+            (a, 0, 1000),
+            (b, 500, 1500),
+            (c, 2 * week, 2 * week + 1000),
+            (d, 3 * week, 3 * week + 1000),
+            (e, 1000, 2 * week + 10),
+        ]);
+        let bounds: Vec<_> = outputs.iter().map(|o| o.bucket_bound).collect();
+        assert_eq!(vec![0, TIME_BUCKETS[4], 2 * TIME_BUCKETS[4]], bounds);
+        assert!(outputs
+            .iter()
+            .flat_map(|output| output.inputs.iter())
+            .all(|f| f.file_id() != d));
+
+        // Files without overlap are not compacted.
+        assert!(pick(&[(a, 0, 1000), (c, 2 * week, 2 * week + 1000)]).is_empty());
+    }
+
+    #[test]
+    fn test_pick_all_files_in_level0() {
+        let week = TIME_BUCKETS[4] * 1000;
+        let levels = new_level_metas(
+            0,
+            &[
+                (FileId::random(), 0, 1000),
+                (FileId::random(), 2 * week, 2 * week + 1000),
+            ],
+        );
+        let outputs = SimpleTimeWindowStrategy {}.pick(&PickerContext {}, levels.level(0));
+        assert_eq!(2, outputs.len());
+        assert!(outputs.iter().all(|output| output.output_level == 1));
+    }
+
+    fn new_level_metas(level: Level, files: &[(FileId, i64, i64)]) -> LevelMetas {
+        let layer = Arc::new(crate::test_util::access_layer_util::MockAccessLayer {});
+        let files = new_file_handles(files).into_iter().map(|file| FileMeta {
+            level,
+            ..file.meta()
+        });
+        LevelMetas::new(layer, new_noop_file_purger()).merge(files, std::iter::empty())
+    }
+
     fn new_file_handle(file_id: FileId, start_ts_millis: i64, end_ts_millis: i64) -> FileHandle {
         let file_purger = new_noop_file_purger();
         let layer = Arc::new(crate::test_util::access_layer_util::MockAccessLayer {});
@@ -240,6 +344,10 @@ mod tests {
                 )),
                 level: 0,
                 file_size: 0,
+                bloom_filter: false,
+                key_range: vec![],
+                row_groups: vec![],
+                num_rows: 0,
             },
             layer,
             file_purger,
@@ -12,15 +12,22 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::cmp::Reverse;
 use std::collections::HashSet;
 use std::fmt::{Debug, Formatter};
+use std::future::Future;
+use std::sync::Arc;
 
+use common_error::prelude::BoxedError;
 use common_telemetry::{error, info};
+use futures::{StreamExt, TryStreamExt};
 use store_api::logstore::LogStore;
 use store_api::storage::RegionId;
 
 use crate::compaction::writer::build_sst_reader;
+use crate::config::BloomFilterConfig;
 use crate::error::Result;
+use crate::listener::RegionEvent;
 use crate::manifest::action::RegionEdit;
 use crate::manifest::region::RegionManifest;
 use crate::region::{RegionWriterRef, SharedDataRef};
@@ -30,6 +37,11 @@ use crate::sst::{
 };
 use crate::wal::Wal;
 
+/// Callback invoked with the edit of a compaction after it is applied to the region, e.g. to
+/// invalidate caches. Errors are logged and don't roll back the compaction.
+pub type CompactionCallbackRef =
+    Arc<dyn Fn(RegionId, &RegionEdit) -> std::result::Result<(), BoxedError> + Send + Sync>;
+
 #[async_trait::async_trait]
 pub trait CompactionTask: Debug + Send + Sync + 'static {
     async fn run(self) -> Result<()>;
@@ -44,6 +56,11 @@ pub struct CompactionTaskImpl<S: LogStore> {
     pub wal: Wal<S>,
     pub manifest: RegionManifest,
     pub expired_ssts: Vec<FileHandle>,
+    /// Max number of outputs built concurrently.
+    pub max_concurrent_outputs: usize,
+    pub on_compacted: Option<CompactionCallbackRef>,
+    /// Writes bloom filters of row key columns to outputs if set.
+    pub bloom_filter: Option<BloomFilterConfig>,
 }
 
 impl<S: LogStore> Debug for CompactionTaskImpl<S> {
@@ -63,29 +80,25 @@ impl<S: LogStore> Drop for CompactionTaskImpl<S> {
 impl<S: LogStore> CompactionTaskImpl<S> {
     /// Compacts inputs SSTs, returns `(output file, compacted input file)`.
     async fn merge_ssts(&mut self) -> Result<(HashSet<FileMeta>, HashSet<FileMeta>)> {
-        let mut futs = Vec::with_capacity(self.outputs.len());
-        let mut compacted_inputs = HashSet::new();
         let region_id = self.shared_data.id();
-        for output in self.outputs.drain(..) {
-            let schema = self.schema.clone();
-            let sst_layer = self.sst_layer.clone();
-            compacted_inputs.extend(output.inputs.iter().map(FileHandle::meta));
-
-            // TODO(hl): Maybe spawn to runtime to exploit in-job parallelism.
-            futs.push(async move {
-                match output.build(region_id, schema, sst_layer).await {
-                    Ok(meta) => Ok(meta),
-                    Err(e) => Err(e),
-                }
-            });
-        }
+        let compacted_inputs = self
+            .outputs
+            .iter()
+            .flat_map(|output| output.inputs.iter().map(FileHandle::meta))
+            .collect();
+        let outputs = self.outputs.drain(..).collect();
+        let schema = &self.schema;
+        let sst_layer = &self.sst_layer;
+        let bloom_filter = self.bloom_filter;
 
-        let outputs = futures::future::join_all(futs)
-            .await
-            .into_iter()
-            .collect::<Result<_>>()?;
-        let inputs = compacted_inputs.into_iter().collect();
-        Ok((outputs, inputs))
+        // TODO(hl): Maybe spawn to runtime to exploit in-job parallelism.
+        let outputs = build_outputs(outputs, self.max_concurrent_outputs, |output| async move {
+            output
+                .build(region_id, schema.clone(), sst_layer.clone(), bloom_filter)
+                .await
+        })
+        .await?;
+        Ok((outputs, compacted_inputs))
     }
 
     /// Writes updated SST info into manifest.
@@ -93,7 +106,7 @@ impl<S: LogStore> CompactionTaskImpl<S> {
         &self,
         output: HashSet<FileMeta>,
         input: HashSet<FileMeta>,
-    ) -> Result<()> {
+    ) -> Result<RegionEdit> {
         let version = &self.shared_data.version_control;
         let region_version = version.metadata().version();
 
@@ -109,8 +122,20 @@ impl<S: LogStore> CompactionTaskImpl<S> {
             edit
         );
         self.writer
-            .write_edit_and_apply(&self.wal, &self.shared_data, &self.manifest, edit, None)
-            .await
+            .write_edit_and_apply(
+                &self.wal,
+                &self.shared_data,
+                &self.manifest,
+                edit.clone(),
+                None,
+            )
+            .await?;
+        self.shared_data
+            .publish_event(RegionEvent::CompactionCompleted {
+                region_id: self.shared_data.id(),
+                edit: edit.clone(),
+            });
+        Ok(edit)
     }
 
     /// Mark files are under compaction.
@@ -126,6 +151,14 @@ impl<S: LogStore> CompactionTaskImpl<S> {
 #[async_trait::async_trait]
 impl<S: LogStore> CompactionTask for CompactionTaskImpl<S> {
     async fn run(mut self) -> Result<()> {
+        if self.shared_data.is_dropped() {
+            info!(
+                "Skip compaction of dropped region: {}",
+                self.shared_data.name()
+            );
+            return Ok(());
+        }
+
         self.mark_files_compacting(true);
 
         let (output, mut compacted) = self.merge_ssts().await.map_err(|e| {
@@ -133,13 +166,58 @@ impl<S: LogStore> CompactionTask for CompactionTaskImpl<S> {
             e
         })?;
         compacted.extend(self.expired_ssts.iter().map(FileHandle::meta));
-        self.write_manifest_and_apply(output, compacted)
-            .await
-            .map_err(|e| {
-                error!(e; "Failed to update region manifest: {}", self.shared_data.name());
-                e
-            })
+        let output_ids: Vec<_> = output.iter().map(|meta| meta.file_id).collect();
+        let region_id = self.shared_data.id();
+        let apply = self.write_manifest_and_apply(output, compacted);
+        if let Err(e) = apply_and_notify(region_id, self.on_compacted.as_ref(), apply).await {
+            error!(e; "Failed to update region manifest: {}", self.shared_data.name());
+            // The output files are not referenced by the version, e.g. the region is
+            // dropped during compaction, so we delete them here.
+            for file_id in output_ids {
+                if let Err(e) = self.sst_layer.delete_sst(file_id).await {
+                    error!(e; "Failed to delete compaction output: {}", file_id);
+                }
+            }
+            return Err(e);
+        }
+        Ok(())
+    }
+}
+
+/// Waits for `apply` of a compaction, invokes `callback` with the edit if it succeeds.
+async fn apply_and_notify(
+    region_id: RegionId,
+    callback: Option<&CompactionCallbackRef>,
+    apply: impl Future<Output = Result<RegionEdit>>,
+) -> Result<()> {
+    let edit = apply.await?;
+    if let Some(callback) = callback {
+        if let Err(e) = callback(region_id, &edit) {
+            error!(e; "Failed to invoke compaction callback of region: {}", region_id);
+        }
     }
+    Ok(())
+}
+
+/// Builds `outputs` with at most `limit` of them in flight, outputs with higher priority
+/// start first.
+async fn build_outputs<T, F, Fut>(
+    mut outputs: Vec<CompactionOutput>,
+    limit: usize,
+    build: F,
+) -> Result<HashSet<T>>
+where
+    T: std::hash::Hash + Eq,
+    F: FnMut(CompactionOutput) -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    // The sort is stable, outputs with the same priority are built in picked order.
+    outputs.sort_by_key(|output| Reverse(output.priority));
+    futures::stream::iter(outputs)
+        .map(build)
+        .buffered(limit.max(1))
+        .try_collect()
+        .await
 }
 
 /// Many-to-many compaction can be decomposed to a many-to-one compaction from level n to level n+1
@@ -154,14 +232,17 @@ pub struct CompactionOutput {
     pub(crate) bucket: i64,
     /// Compaction input files.
     pub(crate) inputs: Vec<FileHandle>,
+    /// Outputs with higher priority are built first, e.g. the ones that merge more files.
+    pub(crate) priority: usize,
 }
 
 impl CompactionOutput {
-    async fn build(
+    pub(crate) async fn build(
         &self,
         region_id: RegionId,
         schema: RegionSchemaRef,
         sst_layer: AccessLayerRef,
+        bloom_filter: Option<BloomFilterConfig>,
     ) -> Result<FileMeta> {
         let reader = build_sst_reader(
             schema,
@@ -173,11 +254,15 @@ impl CompactionOutput {
         .await?;
 
         let output_file_id = FileId::random();
-        let opts = WriteOptions {};
+        let opts = WriteOptions { bloom_filter };
 
         let SstInfo {
             time_range,
             file_size,
+            bloom_filter,
+            key_range,
+            row_groups,
+            num_rows,
         } = sst_layer
             .write_sst(output_file_id, Source::Reader(reader), &opts)
             .await?;
@@ -188,13 +273,20 @@ impl CompactionOutput {
             time_range,
             level: self.output_level,
             file_size,
+            bloom_filter,
+            key_range,
+            row_groups,
+            num_rows,
         })
     }
 }
 
 #[cfg(test)]
 pub mod tests {
-    use std::sync::Arc;
+    use std::sync::Mutex;
+
+    use common_error::mock::MockError;
+    use common_error::prelude::StatusCode;
 
     use super::*;
     use crate::compaction::task::CompactionTask;
@@ -221,4 +313,100 @@ pub mod tests {
             Ok(())
         }
     }
+
+    #[tokio::test]
+    async fn test_build_outputs_by_priority() {
+        let outputs = [1, 3, 0, 3, 2]
+            .into_iter()
+            .enumerate()
+            .map(|(i, priority)| CompactionOutput {
+                output_level: 1,
+                bucket_bound: i as i64,
+                bucket: 1,
+                inputs: vec![],
+                priority,
+            })
+            .collect();
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let built = build_outputs(outputs, 1, |output| {
+            let events = events.clone();
+            async move {
+                events.lock().unwrap().push(("start", output.bucket_bound));
+                tokio::task::yield_now().await;
+                events.lock().unwrap().push(("end", output.bucket_bound));
+                Ok(output.bucket_bound)
+            }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(5, built.len());
+        // Higher priority first, ties are kept in picked order, and one output at a time.
+        let expect: Vec<_> = [1, 3, 4, 0, 2]
+            .into_iter()
+            .flat_map(|bound| [("start", bound), ("end", bound)])
+            .collect();
+        assert_eq!(expect, *events.lock().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_apply_and_notify() {
+        let edit = RegionEdit {
+            region_version: 0,
+            flushed_sequence: None,
+            files_to_add: vec![FileMeta {
+                region_id: 1,
+                file_id: FileId::random(),
+                time_range: None,
+                level: 1,
+                file_size: 0,
+                bloom_filter: false,
+                key_range: vec![],
+                row_groups: vec![],
+                num_rows: 0,
+            }],
+            files_to_remove: vec![FileMeta {
+                region_id: 1,
+                file_id: FileId::random(),
+                time_range: None,
+                level: 0,
+                file_size: 0,
+                bloom_filter: false,
+                key_range: vec![],
+                row_groups: vec![],
+                num_rows: 0,
+            }],
+        };
+        let notified = Arc::new(Mutex::new(Vec::new()));
+        let notified_cloned = notified.clone();
+        let callback: CompactionCallbackRef = Arc::new(move |region_id, edit: &RegionEdit| {
+            let ids = |files: &[FileMeta]| files.iter().map(|f| f.file_id).collect::<Vec<_>>();
+            notified_cloned.lock().unwrap().push((
+                region_id,
+                ids(&edit.files_to_add),
+                ids(&edit.files_to_remove),
+            ));
+            Err(BoxedError::new(MockError::new(StatusCode::Unexpected)))
+        });
+
+        // Errors of the callback don't fail the compaction.
+        apply_and_notify(1, Some(&callback), async { Ok(edit.clone()) })
+            .await
+            .unwrap();
+        assert_eq!(
+            vec![(
+                1,
+                vec![edit.files_to_add[0].file_id],
+                vec![edit.files_to_remove[0].file_id]
+            )],
+            *notified.lock().unwrap()
+        );
+
+        let err = apply_and_notify(1, Some(&callback), async {
+            crate::error::CancelledSnafu.fail()
+        })
+        .await;
+        assert!(err.is_err());
+        assert_eq!(1, notified.lock().unwrap().len());
+    }
 }
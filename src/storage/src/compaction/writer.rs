@@ -94,9 +94,14 @@ mod tests {
     };
     use object_store::services::Fs;
     use object_store::{ObjectStore, ObjectStoreBuilder};
+    use parquet::file::properties::ReaderProperties;
+    use parquet::file::reader::{FileReader, RowGroupReader};
+    use parquet::file::serialized_reader::{ReadOptionsBuilder, SerializedFileReader};
     use store_api::storage::{ChunkReader, OpType, SequenceNumber};
 
     use super::*;
+    use crate::compaction::task::CompactionOutput;
+    use crate::config::BloomFilterConfig;
     use crate::file_purger::noop::new_noop_file_purger;
     use crate::memtable::{
         DefaultMemtableBuilder, IterContext, KeyValues, Memtable, MemtableBuilder,
@@ -224,6 +229,7 @@ mod tests {
         let SstInfo {
             time_range,
             file_size,
+            ..
         } = writer
             .write_sst(&sst::WriteOptions::default())
             .await
@@ -235,6 +241,10 @@ mod tests {
                 time_range,
                 level: 0,
                 file_size,
+                bloom_filter: false,
+                key_range: vec![],
+                row_groups: vec![],
+                num_rows: 0,
             },
             Arc::new(crate::test_util::access_layer_util::MockAccessLayer {}),
             new_noop_file_purger(),
@@ -407,7 +417,7 @@ mod tests {
             .await
             .unwrap();
 
-        let opts = WriteOptions {};
+        let opts = WriteOptions::default();
         let s1 = ParquetWriter::new(
             &output_file_ids[0].as_parquet(),
             Source::Reader(reader1),
@@ -467,6 +477,10 @@ mod tests {
                         level: 1,
                         time_range: None,
                         file_size: 0,
+                        bloom_filter: false,
+                        key_range: vec![],
+                        row_groups: vec![],
+                        num_rows: 0,
                     },
                     Arc::new(crate::test_util::access_layer_util::MockAccessLayer {}),
                     new_noop_file_purger(),
@@ -480,4 +494,83 @@ mod tests {
 
         assert_eq!(timestamps_in_outputs, timestamps_in_inputs);
     }
+
+    #[tokio::test]
+    async fn test_compaction_output_bloom_filter() {
+        let dir = create_temp_dir("compaction_bloom_filter");
+        let path = dir.path().to_str().unwrap();
+        let backend = Fs::default().root(path).build().unwrap();
+        let object_store = ObjectStore::new(backend).finish();
+
+        let schema = schema_for_test();
+        let seq = AtomicU64::new(0);
+        let file1 = write_sst(
+            FileId::random(),
+            schema.clone(),
+            &seq,
+            object_store.clone(),
+            &[1000, 2000, 3000],
+            &[OpType::Put, OpType::Put, OpType::Put],
+        )
+        .await;
+        let file2 = write_sst(
+            FileId::random(),
+            schema.clone(),
+            &seq,
+            object_store.clone(),
+            &[4000, 5000, 6000],
+            &[OpType::Put, OpType::Put, OpType::Put],
+        )
+        .await;
+        let sst_layer = Arc::new(FsAccessLayer::new("./", object_store.clone()));
+        let output = CompactionOutput {
+            output_level: 1,
+            bucket_bound: 0,
+            bucket: 10,
+            inputs: vec![file1, file2],
+            priority: 2,
+        };
+
+        // Reads the bloom filter of the timestamp column in the only row group of the file.
+        let read_filter = |file_id: FileId| {
+            let object_store = object_store.clone();
+            async move {
+                let buf = object_store
+                    .object(&file_id.as_parquet())
+                    .read()
+                    .await
+                    .unwrap();
+                let options = ReadOptionsBuilder::new()
+                    .with_reader_properties(
+                        ReaderProperties::builder()
+                            .set_read_bloom_filter(true)
+                            .build(),
+                    )
+                    .build();
+                let reader =
+                    SerializedFileReader::new_with_options(bytes::Bytes::from(buf), options)
+                        .unwrap();
+                assert_eq!(1, reader.num_row_groups());
+                let row_group = reader.get_row_group(0).unwrap();
+                row_group.get_column_bloom_filter(0).cloned()
+            }
+        };
+
+        let meta = output
+            .build(0, schema.clone(), sst_layer.clone(), None)
+            .await
+            .unwrap();
+        assert!(!meta.bloom_filter);
+        assert!(read_filter(meta.file_id).await.is_none());
+
+        let meta = output
+            .build(0, schema, sst_layer, Some(BloomFilterConfig::default()))
+            .await
+            .unwrap();
+        assert!(meta.bloom_filter);
+        let filter = read_filter(meta.file_id).await.unwrap();
+        for ts in [1000_i64, 2000, 3000, 4000, 5000, 6000] {
+            assert!(filter.check(&ts));
+        }
+    }
 }
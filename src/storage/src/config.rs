@@ -14,10 +14,80 @@
 
 //! storage engine config
 
+use std::time::Duration;
+
+use crate::flush::DEFAULT_WRITE_BUFFER_SIZE;
+use crate::gc::GcOptions;
+
 #[derive(Debug, Clone)]
 pub struct EngineConfig {
     pub max_files_in_l0: usize,
     pub max_purge_tasks: usize,
+    /// Memtable size of a region to trigger a flush.
+    pub memtable_flush_size: usize,
+    /// Max memtable size of all regions in the engine, the region with the largest
+    /// memtable is flushed once it's exceeded. `None` means no limit.
+    pub total_memtable_budget: Option<usize>,
+    /// Uploads large SST files in parts, `None` writes every file with a single request.
+    pub multipart: Option<MultipartConfig>,
+    /// Deletes orphan SST files of regions periodically if set.
+    pub gc: Option<GcOptions>,
+    /// Throttles writes of regions whose memtables grow faster than flushes drain them.
+    pub write_stall: WriteStallConfig,
+    /// Max number of WAL entry batches read ahead while replaying a region, 0 to read
+    /// batches on demand.
+    pub wal_prefetch_batches: usize,
+}
+
+/// Options to delay or reject writes of a region with too many unflushed bytes.
+///
+/// Limits are multiples of [EngineConfig::memtable_flush_size].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WriteStallConfig {
+    /// Writes are delayed once unflushed bytes of a region exceed
+    /// `memtable_flush_size * soft_factor`.
+    pub soft_factor: usize,
+    /// Writes are rejected with a retryable error once unflushed bytes of a region exceed
+    /// `memtable_flush_size * hard_factor`.
+    pub hard_factor: usize,
+    /// Delay of the first stalled write, doubled by each following one.
+    pub initial_delay: Duration,
+    /// Max delay of a single write.
+    pub max_delay: Duration,
+}
+
+impl Default for WriteStallConfig {
+    fn default() -> Self {
+        Self {
+            soft_factor: 2,
+            hard_factor: 4,
+            initial_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(100),
+        }
+    }
+}
+
+/// Options to upload SST files to the object store in parts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MultipartConfig {
+    /// Files of at least this many bytes are uploaded in parts.
+    pub threshold: usize,
+    /// Size of each part except the last one.
+    pub chunk_size: usize,
+}
+
+/// Options of bloom filters of row key columns in SST files.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BloomFilterConfig {
+    /// Target false positive probability of each filter, must be in `(0, 1)`. Filters are
+    /// sized by the rows of a row group, so lower probabilities cost more bits per key.
+    pub fpp: f64,
+}
+
+impl Default for BloomFilterConfig {
+    fn default() -> Self {
+        Self { fpp: 0.01 }
+    }
 }
 
 impl Default for EngineConfig {
@@ -25,6 +95,12 @@ impl Default for EngineConfig {
         Self {
             max_files_in_l0: 8,
             max_purge_tasks: 32,
+            memtable_flush_size: DEFAULT_WRITE_BUFFER_SIZE,
+            total_memtable_budget: None,
+            multipart: None,
+            gc: None,
+            write_stall: WriteStallConfig::default(),
+            wal_prefetch_batches: 0,
         }
     }
 }
@@ -13,7 +13,7 @@
 // limitations under the License.
 
 use std::collections::HashMap;
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, RwLock, Weak};
 use std::time::Duration;
 
 use async_trait::async_trait;
@@ -22,21 +22,29 @@ use object_store::{util, ObjectStore};
 use snafu::ResultExt;
 use store_api::logstore::LogStore;
 use store_api::storage::{
-    CreateOptions, EngineContext, OpenOptions, Region, RegionDescriptor, StorageEngine,
+    CompactionOptions, CreateOptions, DropOptions, EngineContext, OpenOptions, Region,
+    RegionDescriptor, StorageEngine,
 };
 
 use crate::background::JobPoolImpl;
+use crate::backup::SstBackupRef;
 use crate::compaction::CompactionSchedulerRef;
 use crate::config::EngineConfig;
 use crate::error::{self, Error, Result};
 use crate::file_purger::{FilePurgeHandler, FilePurgerRef};
-use crate::flush::{FlushSchedulerImpl, FlushSchedulerRef, FlushStrategyRef, SizeBasedStrategy};
+use crate::flush::{
+    FlushSchedulerImpl, FlushSchedulerRef, FlushStrategyRef, MemtableBudget, MemtableBudgetRef,
+    SizeBasedStrategy,
+};
+use crate::gc::{GcOptions, RegionGcReport};
+use crate::listener::{RegionEventDispatcherRef, RegionEventListenerRef};
 use crate::manifest::region::RegionManifest;
 use crate::memtable::{DefaultMemtableBuilder, MemtableBuilderRef};
 use crate::metadata::RegionMetadata;
 use crate::region::{RegionImpl, StoreConfig};
 use crate::scheduler::{LocalScheduler, SchedulerConfig};
 use crate::sst::FsAccessLayer;
+use crate::verify::RegionVerifyReport;
 
 /// [StorageEngine] implementation.
 pub struct EngineImpl<S: LogStore> {
@@ -66,7 +74,7 @@ impl<S: LogStore> StorageEngine for EngineImpl<S> {
     }
 
     async fn close_region(&self, _ctx: &EngineContext, region: Self::Region) -> Result<()> {
-        region.close().await
+        self.inner.close_region(region).await
     }
 
     async fn create_region(
@@ -78,8 +86,13 @@ impl<S: LogStore> StorageEngine for EngineImpl<S> {
         self.inner.create_region(descriptor, opts).await
     }
 
-    async fn drop_region(&self, _ctx: &EngineContext, _region: Self::Region) -> Result<()> {
-        unimplemented!()
+    async fn drop_region(
+        &self,
+        _ctx: &EngineContext,
+        region: Self::Region,
+        opts: &DropOptions,
+    ) -> Result<()> {
+        self.inner.drop_region(region, opts).await
     }
 
     fn get_region(&self, _ctx: &EngineContext, name: &str) -> Result<Option<Self::Region>> {
@@ -94,14 +107,83 @@ impl<S: LogStore> EngineImpl<S> {
         object_store: ObjectStore,
         compaction_scheduler: CompactionSchedulerRef<S>,
     ) -> Self {
-        Self {
+        let engine = Self {
             inner: Arc::new(EngineInner::new(
                 config,
                 log_store,
                 object_store,
                 compaction_scheduler,
+                None,
             )),
+        };
+        engine.start_gc();
+        engine
+    }
+
+    /// Creates an engine that copies its SST files to the backup store of `backup`.
+    pub fn with_backup(
+        config: EngineConfig,
+        log_store: Arc<S>,
+        object_store: ObjectStore,
+        compaction_scheduler: CompactionSchedulerRef<S>,
+        backup: SstBackupRef,
+    ) -> Result<Self> {
+        let engine = Self {
+            inner: Arc::new(EngineInner::new(
+                config,
+                log_store,
+                object_store,
+                compaction_scheduler,
+                Some(backup.clone()),
+            )),
+        };
+        engine.register_listener(backup)?;
+        engine.start_gc();
+        Ok(engine)
+    }
+
+    /// Registers a listener of flush and compaction events of all regions in this engine.
+    pub fn register_listener(&self, listener: RegionEventListenerRef) -> Result<()> {
+        self.inner.event_dispatcher.register(listener)
+    }
+
+    /// Collects orphan SST files of all opened regions, files are only reported but not
+    /// deleted if `dry_run` is true.
+    pub async fn collect_garbage(&self, dry_run: bool) -> Vec<RegionGcReport> {
+        self.inner.collect_garbage(dry_run).await
+    }
+
+    /// Checks SST files of regions `region_names` against their manifests, at most
+    /// `parallelism` files of a region are checked at a time. Regions not opened are
+    /// reported as skipped.
+    pub async fn verify_regions(
+        &self,
+        region_names: &[String],
+        parallelism: usize,
+    ) -> Vec<RegionVerifyReport> {
+        let mut reports = Vec::with_capacity(region_names.len());
+        for name in region_names {
+            let report = match self.inner.get_region(name) {
+                Some(region) => region.verify(parallelism).await,
+                None => RegionVerifyReport {
+                    region_name: name.clone(),
+                    skipped: Some("region is not opened".to_string()),
+                    ..Default::default()
+                },
+            };
+            reports.push(report);
         }
+        reports
+    }
+
+    /// Starts the periodic garbage collection if it's enabled. The task stops once the
+    /// engine is dropped.
+    fn start_gc(&self) {
+        let Some(options) = self.inner.config.gc else {
+            return;
+        };
+        let inner = Arc::downgrade(&self.inner);
+        common_runtime::spawn_bg(EngineInner::run_gc(inner, options));
     }
 }
 
@@ -224,8 +306,11 @@ struct EngineInner<S: LogStore> {
     memtable_builder: MemtableBuilderRef,
     flush_scheduler: FlushSchedulerRef,
     flush_strategy: FlushStrategyRef,
+    memtable_budget: Option<MemtableBudgetRef>,
     compaction_scheduler: CompactionSchedulerRef<S>,
     file_purger: FilePurgerRef,
+    event_dispatcher: RegionEventDispatcherRef,
+    backup: Option<SstBackupRef>,
     config: Arc<EngineConfig>,
 }
 
@@ -235,6 +320,7 @@ impl<S: LogStore> EngineInner<S> {
         log_store: Arc<S>,
         object_store: ObjectStore,
         compaction_scheduler: CompactionSchedulerRef<S>,
+        backup: Option<SstBackupRef>,
     ) -> Self {
         let job_pool = Arc::new(JobPoolImpl {});
         let flush_scheduler = Arc::new(FlushSchedulerImpl::new(job_pool));
@@ -242,8 +328,9 @@ impl<S: LogStore> EngineInner<S> {
         let file_purger = Arc::new(LocalScheduler::new(
             SchedulerConfig {
                 max_inflight_tasks: config.max_purge_tasks,
+                ..Default::default()
             },
-            FilePurgeHandler,
+            FilePurgeHandler::with_backup(backup.clone()),
         ));
         Self {
             object_store,
@@ -251,9 +338,14 @@ impl<S: LogStore> EngineInner<S> {
             regions: RwLock::new(Default::default()),
             memtable_builder: Arc::new(DefaultMemtableBuilder::default()),
             flush_scheduler,
-            flush_strategy: Arc::new(SizeBasedStrategy::default()),
+            flush_strategy: Arc::new(SizeBasedStrategy::new(config.memtable_flush_size)),
+            memtable_budget: config
+                .total_memtable_budget
+                .map(|budget| Arc::new(MemtableBudget::new(budget))),
             compaction_scheduler,
             file_purger,
+            event_dispatcher: Default::default(),
+            backup,
             config: Arc::new(config),
         }
     }
@@ -290,13 +382,19 @@ impl<S: LogStore> EngineInner<S> {
 
         let mut guard = SlotGuard::new(name, &self.regions);
 
-        let store_config =
-            self.region_store_config(&opts.parent_dir, opts.write_buffer_size, name, opts.ttl);
+        let store_config = self.region_store_config(
+            &opts.parent_dir,
+            opts.write_buffer_size,
+            name,
+            opts.ttl,
+            opts.compaction,
+        );
 
         let region = match RegionImpl::open(name.to_string(), store_config, opts).await? {
             None => return Ok(None),
             Some(v) => v,
         };
+        self.register_to_backup(&region, &opts.parent_dir, name);
         guard.update(RegionSlot::Ready(region.clone()));
         info!("Storage engine open region {}", region.id());
         Ok(Some(region))
@@ -326,9 +424,11 @@ impl<S: LogStore> EngineInner<S> {
             opts.write_buffer_size,
             &region_name,
             opts.ttl,
+            opts.compaction,
         );
 
         let region = RegionImpl::create(metadata, store_config).await?;
+        self.register_to_backup(&region, &opts.parent_dir, &region_name);
 
         guard.update(RegionSlot::Ready(region.clone()));
 
@@ -337,22 +437,97 @@ impl<S: LogStore> EngineInner<S> {
         Ok(region)
     }
 
+    async fn close_region(&self, region: RegionImpl<S>) -> Result<()> {
+        region.close().await?;
+        // Removes the closed region so it could be opened again.
+        self.regions.write().unwrap().remove(region.name());
+        info!("Storage engine close region {}", region.id());
+        Ok(())
+    }
+
+    async fn drop_region(&self, region: RegionImpl<S>, opts: &DropOptions) -> Result<()> {
+        region.drop_region().await?;
+        self.regions.write().unwrap().remove(region.name());
+        info!("Storage engine drop region {}", region.id());
+
+        if let Some(timeout) = opts.purge_timeout {
+            region.wait_purged(timeout).await?;
+            region.purge_wal().await?;
+            info!(
+                "SST and WAL files of dropped region {} are purged",
+                region.id()
+            );
+        }
+        Ok(())
+    }
+
+    async fn collect_garbage(&self, dry_run: bool) -> Vec<RegionGcReport> {
+        let grace_period = self.config.gc.unwrap_or_default().grace_period;
+        let regions: Vec<_> = self
+            .regions
+            .read()
+            .unwrap()
+            .values()
+            .filter_map(|slot| slot.get_ready_region())
+            .collect();
+        let mut reports = Vec::with_capacity(regions.len());
+        for region in regions {
+            reports.push(region.collect_garbage(grace_period, dry_run).await);
+        }
+        reports
+    }
+
+    async fn run_gc(inner: Weak<EngineInner<S>>, options: GcOptions) {
+        let mut interval = tokio::time::interval(options.interval);
+        // The first tick completes immediately, skips it so regions are opened before
+        // the first collection.
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            let Some(inner) = inner.upgrade() else {
+                return;
+            };
+            let reports = inner.collect_garbage(false).await;
+            let deleted: usize = reports
+                .iter()
+                .map(|r| r.orphans.len() - r.failed_files)
+                .sum();
+            info!(
+                "Collected orphan SST files of {} regions, deleted: {}",
+                reports.len(),
+                deleted
+            );
+        }
+    }
+
     fn get_region(&self, name: &str) -> Option<RegionImpl<S>> {
         let slot = self.regions.read().unwrap().get(name).cloned()?;
         slot.get_ready_region()
     }
 
+    /// Registers the region to the backup so its existing and new SST files are backed up.
+    fn register_to_backup(&self, region: &RegionImpl<S>, parent_dir: &str, region_name: &str) {
+        if let Some(backup) = &self.backup {
+            let sst_dir = region_sst_dir(&util::normalize_dir(parent_dir), region_name);
+            backup.register_region(region.id(), &sst_dir, &region.file_metas());
+        }
+    }
+
     fn region_store_config(
         &self,
         parent_dir: &str,
         write_buffer_size: Option<usize>,
         region_name: &str,
         ttl: Option<Duration>,
+        compaction: CompactionOptions,
     ) -> StoreConfig<S> {
         let parent_dir = util::normalize_dir(parent_dir);
 
         let sst_dir = &region_sst_dir(&parent_dir, region_name);
-        let sst_layer = Arc::new(FsAccessLayer::new(sst_dir, self.object_store.clone()));
+        let sst_layer = Arc::new(
+            FsAccessLayer::new(sst_dir, self.object_store.clone())
+                .with_multipart(self.config.multipart),
+        );
         let manifest_dir = region_manifest_dir(&parent_dir, region_name);
         let manifest = RegionManifest::new(&manifest_dir, self.object_store.clone());
 
@@ -371,6 +546,9 @@ impl<S: LogStore> EngineInner<S> {
             engine_config: self.config.clone(),
             file_purger: self.file_purger.clone(),
             ttl,
+            compaction,
+            memtable_budget: self.memtable_budget.clone(),
+            event_dispatcher: self.event_dispatcher.clone(),
         }
     }
 }
@@ -427,5 +605,15 @@ mod tests {
         assert_eq!(region_name, region2.name());
 
         assert!(engine.get_region(&ctx, "no such region").unwrap().is_none());
+
+        // A closed region can be opened again.
+        engine.close_region(&ctx, region).await.unwrap();
+        assert!(engine.get_region(&ctx, region_name).unwrap().is_none());
+        let region = engine
+            .open_region(&ctx, region_name, &OpenOptions::default())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(region_name, region.name());
     }
 }
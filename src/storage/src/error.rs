@@ -15,6 +15,7 @@
 use std::any::Any;
 use std::io::Error as IoError;
 use std::str::Utf8Error;
+use std::time::Duration;
 
 use common_error::prelude::*;
 use datatypes::arrow::error::ArrowError;
@@ -206,6 +207,13 @@ pub enum Error {
         source: BoxedError,
     },
 
+    #[snafu(display("Failed to purge WAL, region id: {}, source: {}", region_id, source))]
+    PurgeWal {
+        region_id: u64,
+        #[snafu(backtrace)]
+        source: BoxedError,
+    },
+
     #[snafu(display("WAL data corrupted, region_id: {}, message: {}", region_id, message))]
     WalDataCorrupted {
         region_id: RegionId,
@@ -440,6 +448,55 @@ pub enum Error {
         #[snafu(backtrace)]
         source: common_time::error::Error,
     },
+
+    #[snafu(display(
+        "Failed to build runtime for region event listeners, source: {}",
+        source
+    ))]
+    BuildListenerRuntime {
+        #[snafu(backtrace)]
+        source: common_runtime::error::Error,
+    },
+
+    #[snafu(display(
+        "{} SST files of dropped region {} are not deleted in {:?}",
+        remaining,
+        region,
+        timeout
+    ))]
+    PurgeTimeout {
+        region: String,
+        remaining: usize,
+        timeout: Duration,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display(
+        "Writes to region {} are stalled, unflushed bytes: {}, limit: {}",
+        region,
+        unflushed_bytes,
+        limit
+    ))]
+    WriteStalled {
+        region: String,
+        unflushed_bytes: usize,
+        limit: usize,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Bulk load {} of region {} not found", load_id, region))]
+    BulkLoadNotFound {
+        region: String,
+        load_id: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Bulk load {} of region {} already exists", load_id, region))]
+    BulkLoadExists {
+        region: String,
+        load_id: String,
+        backtrace: Backtrace,
+    },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -460,7 +517,9 @@ impl ErrorExt for Error {
             | TypeMismatch { .. }
             | HasNull { .. }
             | UnequalLengths { .. }
-            | MoreColumnThanExpected { .. } => StatusCode::InvalidArguments,
+            | MoreColumnThanExpected { .. }
+            | BulkLoadNotFound { .. }
+            | BulkLoadExists { .. } => StatusCode::InvalidArguments,
 
             Utf8 { .. }
             | EncodeJson { .. }
@@ -508,13 +567,15 @@ impl ErrorExt for Error {
             PushBatch { source, .. } => source.status_code(),
             CreateDefault { source, .. } => source.status_code(),
             ConvertChunk { source, .. } => source.status_code(),
-            MarkWalObsolete { source, .. } => source.status_code(),
+            MarkWalObsolete { source, .. } | PurgeWal { source, .. } => source.status_code(),
             DecodeParquetTimeRange { .. } => StatusCode::Unexpected,
             RateLimited { .. } => StatusCode::Internal,
             StopScheduler { .. } => StatusCode::Internal,
             DeleteSst { .. } => StatusCode::StorageUnavailable,
             IllegalSchedulerState { .. } => StatusCode::Unexpected,
             TtlCalculation { source, .. } => source.status_code(),
+            BuildListenerRuntime { source } => source.status_code(),
+            PurgeTimeout { .. } | WriteStalled { .. } => StatusCode::StorageUnavailable,
         }
     }
 
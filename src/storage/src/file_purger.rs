@@ -14,10 +14,11 @@
 
 use std::sync::Arc;
 
-use common_telemetry::{debug, error};
+use common_telemetry::{debug, error, warn};
 use store_api::storage::RegionId;
 use tokio::sync::Notify;
 
+use crate::backup::SstBackupRef;
 use crate::scheduler::rate_limit::{BoxedRateLimitToken, RateLimitToken};
 use crate::scheduler::{Handler, LocalScheduler, Request};
 use crate::sst::{AccessLayerRef, FileId};
@@ -36,7 +37,18 @@ impl Request for FilePurgeRequest {
     }
 }
 
-pub struct FilePurgeHandler;
+#[derive(Default)]
+pub struct FilePurgeHandler {
+    /// Exporter of SST files, the handler waits for files to be backed up before deleting
+    /// them if the backup is strict.
+    backup: Option<SstBackupRef>,
+}
+
+impl FilePurgeHandler {
+    pub fn with_backup(backup: Option<SstBackupRef>) -> Self {
+        Self { backup }
+    }
+}
 
 #[async_trait::async_trait]
 impl Handler for FilePurgeHandler {
@@ -48,6 +60,19 @@ impl Handler for FilePurgeHandler {
         token: BoxedRateLimitToken,
         finish_notifier: Arc<Notify>,
     ) -> crate::error::Result<()> {
+        if let Some(backup) = self.backup.as_ref().filter(|b| b.options().strict) {
+            if !backup.wait_backed_up(req.region_id, req.file_id).await {
+                warn!(
+                    "Keep SST file {} of region {} as it is not backed up",
+                    req.file_id.as_parquet(),
+                    req.region_id
+                );
+                token.try_release();
+                finish_notifier.notify_one();
+                return Ok(());
+            }
+        }
+
         req.sst_layer.delete_sst(req.file_id).await.map_err(|e| {
             error!(e; "Failed to delete SST file, file: {}, region: {}", 
                 req.file_id.as_parquet(), req.region_id);
@@ -66,7 +91,7 @@ impl Handler for FilePurgeHandler {
 
 pub type FilePurgerRef = Arc<LocalScheduler<FilePurgeRequest>>;
 
-#[cfg(test)]
+#[cfg(any(test, feature = "test-util"))]
 pub mod noop {
     use std::sync::Arc;
 
@@ -111,6 +136,7 @@ mod tests {
     use store_api::storage::OpType;
 
     use super::*;
+    use crate::backup::{BackupOptions, SstBackup};
     use crate::file_purger::noop::NoopFilePurgeHandler;
     use crate::memtable::tests::{schema_for_test, write_kvs};
     use crate::memtable::{DefaultMemtableBuilder, IterContext, MemtableBuilder};
@@ -143,7 +169,7 @@ mod tests {
         let sst_path = "table1";
         let layer = Arc::new(FsAccessLayer::new(sst_path, os.clone()));
         let sst_info = layer
-            .write_sst(sst_file_id, Source::Iter(iter), &WriteOptions {})
+            .write_sst(sst_file_id, Source::Iter(iter), &WriteOptions::default())
             .await
             .unwrap();
 
@@ -155,6 +181,10 @@ mod tests {
                     time_range: None,
                     level: 0,
                     file_size: sst_info.file_size,
+                    bloom_filter: false,
+                    key_range: vec![],
+                    row_groups: vec![],
+                    num_rows: 0,
                 },
                 layer.clone(),
                 file_purger,
@@ -189,7 +219,7 @@ mod tests {
             sst_layer: layer,
         };
 
-        let handler = FilePurgeHandler;
+        let handler = FilePurgeHandler::default();
         let notify = Arc::new(Notify::new());
         handler
             .handle_request(request, Box::new(MockRateLimitToken {}), notify.clone())
@@ -216,7 +246,7 @@ mod tests {
         let sst_file_id = FileId::random();
         let scheduler = Arc::new(LocalScheduler::new(
             SchedulerConfig::default(),
-            FilePurgeHandler,
+            FilePurgeHandler::default(),
         ));
         let (handle, path, _layer) =
             create_sst_file(object_store.clone(), sst_file_id, scheduler.clone()).await;
@@ -233,4 +263,74 @@ mod tests {
             .await
             .unwrap());
     }
+
+    #[tokio::test]
+    async fn test_file_purger_strict_backup() {
+        let dir = create_temp_dir("file-purge-strict");
+        let object_store = ObjectStore::new(
+            Fs::default()
+                .root(dir.path().to_str().unwrap())
+                .build()
+                .unwrap(),
+        )
+        .finish();
+        let backup_dir = create_temp_dir("file-purge-backup");
+        let backup_store = ObjectStore::new(
+            Fs::default()
+                .root(backup_dir.path().to_str().unwrap())
+                .build()
+                .unwrap(),
+        )
+        .finish();
+        let backup = SstBackup::new(
+            object_store.clone(),
+            backup_store.clone(),
+            BackupOptions {
+                strict: true,
+                ..Default::default()
+            },
+        );
+        let noop_file_purger = Arc::new(LocalScheduler::new(
+            SchedulerConfig::default(),
+            NoopFilePurgeHandler,
+        ));
+        let handler = FilePurgeHandler::with_backup(Some(backup.clone()));
+
+        // Region 0 is not registered to the backup, so its files are kept.
+        let sst_file_id = FileId::random();
+        let (_file, path, layer) =
+            create_sst_file(object_store.clone(), sst_file_id, noop_file_purger.clone()).await;
+        let file_path = format!("{}/{}", path, sst_file_id.as_parquet());
+        let request = FilePurgeRequest {
+            region_id: 0,
+            file_id: sst_file_id,
+            sst_layer: layer.clone(),
+        };
+        handler
+            .handle_request(
+                request,
+                Box::new(MockRateLimitToken {}),
+                Arc::new(Notify::new()),
+            )
+            .await
+            .unwrap();
+        assert!(object_store.object(&file_path).is_exist().await.unwrap());
+
+        backup.register_region(0, &path, &[]);
+        let request = FilePurgeRequest {
+            region_id: 0,
+            file_id: sst_file_id,
+            sst_layer: layer,
+        };
+        handler
+            .handle_request(
+                request,
+                Box::new(MockRateLimitToken {}),
+                Arc::new(Notify::new()),
+            )
+            .await
+            .unwrap();
+        assert!(!object_store.object(&file_path).is_exist().await.unwrap());
+        assert!(backup_store.object(&file_path).is_exist().await.unwrap());
+    }
 }
@@ -12,27 +12,30 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::future::Future;
 use std::pin::Pin;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, Weak};
 
 use async_trait::async_trait;
 use common_telemetry::logging;
+use object_store::retry::scope_task;
 use store_api::logstore::LogStore;
 use store_api::storage::consts::WRITE_ROW_GROUP_SIZE;
-use store_api::storage::SequenceNumber;
+use store_api::storage::{RegionId, SequenceNumber};
 
 use crate::background::{Context, Job, JobHandle, JobPoolRef};
 use crate::error::{CancelledSnafu, Result};
+use crate::listener::RegionEvent;
 use crate::manifest::action::*;
 use crate::manifest::region::RegionManifest;
 use crate::memtable::{IterContext, MemtableId, MemtableRef};
-use crate::region::{RegionWriterRef, SharedDataRef};
+use crate::region::{FlushingGuard, RegionWriterRef, SharedDataRef};
 use crate::sst::{AccessLayerRef, FileId, FileMeta, Source, SstInfo, WriteOptions};
 use crate::wal::Wal;
 
 /// Default write buffer size (32M).
-const DEFAULT_WRITE_BUFFER_SIZE: usize = 32 * 1024 * 1024;
+pub(crate) const DEFAULT_WRITE_BUFFER_SIZE: usize = 32 * 1024 * 1024;
 
 pub trait FlushStrategy: Send + Sync + std::fmt::Debug {
     fn should_flush(
@@ -126,6 +129,96 @@ impl FlushStrategy for SizeBasedStrategy {
     }
 }
 
+/// A region whose memtables are accounted by the [MemtableBudget].
+pub trait FlushableRegion: Send + Sync {
+    fn id(&self) -> RegionId;
+
+    /// Returns bytes allocated by all memtables of the region.
+    fn memtable_bytes(&self) -> usize;
+
+    /// Returns bytes allocated by the mutable memtable of the region.
+    fn mutable_memtable_bytes(&self) -> usize;
+
+    /// Returns true if the region is flushing or about to flush.
+    fn is_flushing(&self) -> bool;
+
+    /// Schedules a flush of the region in background.
+    fn schedule_flush(self: Arc<Self>);
+}
+
+/// Memtable budget shared by all regions in the engine.
+pub struct MemtableBudget {
+    /// Max bytes of memtables of all regions.
+    budget: usize,
+    regions: Mutex<HashMap<RegionId, Weak<dyn FlushableRegion>>>,
+}
+
+pub type MemtableBudgetRef = Arc<MemtableBudget>;
+
+impl std::fmt::Debug for MemtableBudget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MemtableBudget")
+            .field("budget", &self.budget)
+            .finish()
+    }
+}
+
+impl MemtableBudget {
+    pub fn new(budget: usize) -> MemtableBudget {
+        MemtableBudget {
+            budget,
+            regions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn register(&self, region_id: RegionId, region: Weak<dyn FlushableRegion>) {
+        self.regions.lock().unwrap().insert(region_id, region);
+    }
+
+    pub fn unregister(&self, region_id: RegionId) {
+        self.regions.lock().unwrap().remove(&region_id);
+    }
+
+    /// Returns the region with the largest mutable memtable if memtables of all regions
+    /// exceed the budget.
+    ///
+    /// Regions that are already flushing are skipped, so the next largest region would
+    /// be picked instead of waiting for the flushing one.
+    pub fn pick_region_to_flush(&self) -> Option<Arc<dyn FlushableRegion>> {
+        let regions = {
+            let mut regions = self.regions.lock().unwrap();
+            regions.retain(|_, region| region.strong_count() > 0);
+            regions
+                .values()
+                .filter_map(|region| region.upgrade())
+                .collect::<Vec<_>>()
+        };
+
+        let total_bytes: usize = regions.iter().map(|region| region.memtable_bytes()).sum();
+        if total_bytes <= self.budget {
+            return None;
+        }
+
+        let picked = regions
+            .into_iter()
+            .filter(|region| !region.is_flushing())
+            .map(|region| (region.mutable_memtable_bytes(), region))
+            .filter(|(bytes, _)| *bytes > 0)
+            .max_by_key(|(bytes, _)| *bytes)
+            .map(|(_, region)| region);
+        if let Some(region) = &picked {
+            logging::info!(
+                "Memtables exceed the budget, total_bytes: {}, budget: {}, pick region {} to flush",
+                total_bytes,
+                self.budget,
+                region.id()
+            );
+        }
+
+        picked
+    }
+}
+
 #[async_trait]
 pub trait FlushScheduler: Send + Sync + std::fmt::Debug {
     async fn schedule_flush(&self, flush_job: Box<dyn Job>) -> Result<JobHandle>;
@@ -174,6 +267,8 @@ pub struct FlushJob<S: LogStore> {
     pub manifest: RegionManifest,
     /// Callbacks that get invoked on flush success.
     pub on_success: Option<FlushCallback>,
+    /// Marks the region as flushing until the job is dropped.
+    pub flushing_guard: FlushingGuard,
 }
 
 impl<S: LogStore> FlushJob<S> {
@@ -183,48 +278,17 @@ impl<S: LogStore> FlushJob<S> {
         }
 
         let region_id = self.shared.id();
-        let mut futures = Vec::with_capacity(self.memtables.len());
-        let iter_ctx = IterContext {
-            for_flush: true,
-            // TODO(ruihang): dynamic row group size based on content (#412)
-            batch_size: WRITE_ROW_GROUP_SIZE,
-            ..Default::default()
-        };
-        for m in &self.memtables {
+        let futures = self
+            .memtables
+            .iter()
             // skip empty memtable
-            if m.num_rows() == 0 {
-                continue;
-            }
-
-            let file_id = FileId::random();
-            // TODO(hl): Check if random file name already exists in meta.
-            let iter = m.iter(&iter_ctx)?;
-            let sst_layer = self.sst_layer.clone();
-
-            futures.push(async move {
-                let SstInfo {
-                    time_range,
-                    file_size,
-                } = sst_layer
-                    .write_sst(file_id, Source::Iter(iter), &WriteOptions::default())
-                    .await?;
-
-                Ok(FileMeta {
-                    region_id,
-                    file_id,
-                    time_range,
-                    level: 0,
-                    file_size,
-                })
-            });
-        }
+            .filter(|m| m.num_rows() > 0)
+            .map(|m| write_memtable_to_sst(region_id, m, &self.sst_layer));
 
         let metas = futures_util::future::join_all(futures)
             .await
             .into_iter()
-            .collect::<Result<Vec<_>>>()?
-            .into_iter()
-            .collect();
+            .collect::<Result<Vec<_>>>()?;
 
         logging::info!("Successfully flush memtables to files: {:?}", metas);
         Ok(metas)
@@ -247,16 +311,67 @@ impl<S: LogStore> FlushJob<S> {
                 Some(self.max_memtable_id),
             )
             .await?;
+        // Publish before the success callback so listeners always see the flushed files
+        // before any compaction consuming them.
+        for file in file_metas {
+            self.shared.publish_event(RegionEvent::FlushCompleted {
+                region_id: self.shared.id(),
+                file: file.clone(),
+            });
+        }
         self.wal.obsolete(self.flush_sequence).await
     }
 }
 
+/// Writes rows of the `memtable` to a new SST file at level 0.
+pub(crate) async fn write_memtable_to_sst(
+    region_id: RegionId,
+    memtable: &MemtableRef,
+    sst_layer: &AccessLayerRef,
+) -> Result<FileMeta> {
+    let iter_ctx = IterContext {
+        for_flush: true,
+        // TODO(ruihang): dynamic row group size based on content (#412)
+        batch_size: WRITE_ROW_GROUP_SIZE,
+        ..Default::default()
+    };
+    let file_id = FileId::random();
+    // TODO(hl): Check if random file name already exists in meta.
+    let iter = memtable.iter(&iter_ctx)?;
+    let SstInfo {
+        time_range,
+        file_size,
+        bloom_filter,
+        key_range,
+        row_groups,
+        num_rows,
+    } = sst_layer
+        .write_sst(file_id, Source::Iter(iter), &WriteOptions::default())
+        .await?;
+
+    Ok(FileMeta {
+        region_id,
+        file_id,
+        time_range,
+        level: 0,
+        file_size,
+        bloom_filter,
+        key_range,
+        row_groups,
+        num_rows,
+    })
+}
+
 #[async_trait]
 impl<S: LogStore> Job for FlushJob<S> {
     // TODO(yingwen): [flush] Support in-job parallelism (Flush memtables concurrently)
     async fn run(&mut self, ctx: &Context) -> Result<()> {
-        let file_metas = self.write_memtables_to_layer(ctx).await?;
-        self.write_manifest_and_apply(&file_metas).await?;
+        // Requests of the whole flush share the retry budget of the task.
+        scope_task(async {
+            let file_metas = self.write_memtables_to_layer(ctx).await?;
+            self.write_manifest_and_apply(&file_metas).await
+        })
+        .await?;
 
         if let Some(cb) = self.on_success.take() {
             cb.await;
@@ -267,12 +382,115 @@ impl<S: LogStore> Job for FlushJob<S> {
 
 #[cfg(test)]
 mod tests {
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
     use super::*;
 
+    struct MockRegion {
+        id: RegionId,
+        bytes: usize,
+        flushing: AtomicBool,
+        flush_times: AtomicUsize,
+    }
+
+    impl MockRegion {
+        fn new(id: RegionId, bytes: usize) -> Arc<MockRegion> {
+            Arc::new(MockRegion {
+                id,
+                bytes,
+                flushing: AtomicBool::new(false),
+                flush_times: AtomicUsize::new(0),
+            })
+        }
+    }
+
+    impl FlushableRegion for MockRegion {
+        fn id(&self) -> RegionId {
+            self.id
+        }
+
+        fn memtable_bytes(&self) -> usize {
+            self.bytes
+        }
+
+        fn mutable_memtable_bytes(&self) -> usize {
+            self.bytes
+        }
+
+        fn is_flushing(&self) -> bool {
+            self.flushing.load(Ordering::Relaxed)
+        }
+
+        fn schedule_flush(self: Arc<Self>) {
+            self.flushing.store(true, Ordering::Relaxed);
+            self.flush_times.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn register(budget: &MemtableBudget, region: &Arc<MockRegion>) {
+        let region_ref: Arc<dyn FlushableRegion> = region.clone();
+        budget.register(region.id, Arc::downgrade(&region_ref));
+    }
+
     #[test]
     fn test_get_mutable_limitation() {
         assert_eq!(7, get_mutable_limitation(8));
         assert_eq!(8, get_mutable_limitation(10));
         assert_eq!(56, get_mutable_limitation(64));
     }
+
+    #[test]
+    fn test_memtable_budget_not_exceeded() {
+        let budget = MemtableBudget::new(100);
+        let regions = [MockRegion::new(1, 40), MockRegion::new(2, 60)];
+        for region in &regions {
+            register(&budget, region);
+        }
+
+        assert!(budget.pick_region_to_flush().is_none());
+    }
+
+    #[test]
+    fn test_memtable_budget_flush_cascade() {
+        let budget = MemtableBudget::new(10);
+        let regions = [
+            MockRegion::new(1, 20),
+            MockRegion::new(2, 40),
+            MockRegion::new(3, 30),
+            MockRegion::new(4, 0),
+        ];
+        for region in &regions {
+            register(&budget, region);
+        }
+
+        // Picks the largest region, and the next largest one if it's already flushing.
+        for expect in [2, 3, 1] {
+            let region = budget.pick_region_to_flush().unwrap();
+            assert_eq!(expect, region.id());
+            region.schedule_flush();
+        }
+        // All non-empty regions are flushing.
+        assert!(budget.pick_region_to_flush().is_none());
+        for region in &regions {
+            let expect = usize::from(region.bytes > 0);
+            assert_eq!(expect, region.flush_times.load(Ordering::Relaxed));
+        }
+    }
+
+    #[test]
+    fn test_memtable_budget_unregister() {
+        let budget = MemtableBudget::new(10);
+        let region = MockRegion::new(1, 20);
+        register(&budget, &region);
+        assert!(budget.pick_region_to_flush().is_some());
+
+        budget.unregister(1);
+        assert!(budget.pick_region_to_flush().is_none());
+
+        // Dropped regions are also removed.
+        register(&budget, &region);
+        drop(region);
+        assert!(budget.pick_region_to_flush().is_none());
+        assert!(budget.regions.lock().unwrap().is_empty());
+    }
 }
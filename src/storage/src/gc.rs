@@ -0,0 +1,141 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Garbage collection of orphan SST files.
+//!
+//! A crash between uploading an SST file and applying it to the manifest leaves a file
+//! that no edit of the manifest ever references. Files removed by edits are deleted by the
+//! file purger once they are no longer read, so the collector only deletes files that are
+//! never referenced, and only if they are older than a grace period so files being written
+//! by flushes or compactions are kept.
+
+use std::collections::HashSet;
+use std::time::{Duration, SystemTime};
+
+use metrics::counter;
+use store_api::storage::RegionId;
+
+use crate::sst::{FileId, SstObject};
+
+/// Number of orphan SST files deleted.
+pub const METRIC_GC_DELETED_FILES_TOTAL: &str = "storage.gc.deleted_files_total";
+/// Bytes of orphan SST files deleted.
+pub const METRIC_GC_DELETED_BYTES_TOTAL: &str = "storage.gc.deleted_bytes_total";
+
+/// Options of the periodic garbage collection of orphan SST files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GcOptions {
+    /// Interval between two collections of all regions.
+    pub interval: Duration,
+    /// Orphan files modified within this period are kept.
+    pub grace_period: Duration,
+}
+
+impl Default for GcOptions {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(60 * 60),
+            grace_period: Duration::from_secs(24 * 60 * 60),
+        }
+    }
+}
+
+/// An SST file never referenced by the manifest of its region.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrphanSst {
+    pub file_id: FileId,
+    pub file_size: u64,
+}
+
+/// Result of collecting orphan SST files of a region.
+#[derive(Debug, Clone, Default)]
+pub struct RegionGcReport {
+    pub region_id: RegionId,
+    pub region_name: String,
+    /// Orphan files older than the grace period, which are deleted unless it's a dry run.
+    pub orphans: Vec<OrphanSst>,
+    /// Number of orphan files failed to delete.
+    pub failed_files: usize,
+    /// Why the region is skipped, orphans are only collected if the collector is sure
+    /// about the files the region references.
+    pub skipped: Option<String>,
+}
+
+/// Returns the `objects` not in `referenced` and modified more than `grace_period` before
+/// `now`. Objects whose modified time is unknown or in the future are kept.
+pub(crate) fn find_orphans(
+    objects: &[SstObject],
+    referenced: &HashSet<FileId>,
+    now: SystemTime,
+    grace_period: Duration,
+) -> Vec<OrphanSst> {
+    objects
+        .iter()
+        .filter(|object| !referenced.contains(&object.file_id))
+        .filter(|object| {
+            object
+                .last_modified
+                .and_then(|modified| now.duration_since(modified).ok())
+                .map_or(false, |age| age > grace_period)
+        })
+        .map(|object| OrphanSst {
+            file_id: object.file_id,
+            file_size: object.file_size,
+        })
+        .collect()
+}
+
+pub(crate) fn record_deleted(orphan: &OrphanSst) {
+    counter!(METRIC_GC_DELETED_FILES_TOTAL, 1);
+    counter!(METRIC_GC_DELETED_BYTES_TOTAL, orphan.file_size);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_orphans() {
+        let now = SystemTime::now();
+        let hour = Duration::from_secs(60 * 60);
+        let object = |file_size, last_modified| SstObject {
+            file_id: FileId::random(),
+            file_size,
+            last_modified,
+        };
+        let objects = vec![
+            // Referenced.
+            object(1, Some(now - hour * 3)),
+            // Orphan.
+            object(2, Some(now - hour * 3)),
+            // Within the grace period.
+            object(3, Some(now - hour)),
+            // Unknown modified time.
+            object(4, None),
+            // Modified in the future.
+            object(5, Some(now + hour)),
+        ];
+        let referenced = HashSet::from([objects[0].file_id]);
+
+        let orphans = find_orphans(&objects, &referenced, now, hour * 2);
+        assert_eq!(
+            vec![OrphanSst {
+                file_id: objects[1].file_id,
+                file_size: 2,
+            }],
+            orphans
+        );
+        assert!(find_orphans(&objects, &referenced, now, hour * 4).is_empty());
+    }
+}
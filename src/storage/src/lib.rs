@@ -15,6 +15,7 @@
 //! Storage engine implementation.
 
 mod background;
+pub mod backup;
 mod chunk;
 pub mod codec;
 pub mod compaction;
@@ -22,6 +23,8 @@ pub mod config;
 mod engine;
 pub mod error;
 mod flush;
+pub mod gc;
+pub mod listener;
 pub mod manifest;
 pub mod memtable;
 pub mod metadata;
@@ -35,6 +38,7 @@ mod sst;
 mod sync;
 #[cfg(test)]
 mod test_util;
+pub mod verify;
 mod version;
 mod wal;
 pub mod write_batch;
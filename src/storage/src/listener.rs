@@ -0,0 +1,235 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Listeners of region events, e.g. flush and compaction.
+
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Mutex, RwLock};
+
+use common_runtime::{Builder as RuntimeBuilder, Runtime};
+use common_telemetry::logging;
+use snafu::ResultExt;
+use store_api::storage::RegionId;
+use tokio::sync::mpsc::{self, UnboundedSender};
+
+use crate::error::{BuildListenerRuntimeSnafu, Result};
+use crate::manifest::action::RegionEdit;
+use crate::sst::FileMeta;
+
+/// Listener of region events. Callbacks are invoked in the order the events happen, on a
+/// runtime dedicated to listeners, so they should not block for too long.
+pub trait RegionEventListener: Send + Sync {
+    /// Invoked after the `file` flushed from memtables is applied to the region.
+    fn on_flush_completed(&self, _region_id: RegionId, _file: &FileMeta) {}
+
+    /// Invoked after the `edit` of a compaction is durably applied to the region.
+    fn on_compaction_completed(&self, _region_id: RegionId, _edit: &RegionEdit) {}
+}
+
+pub type RegionEventListenerRef = Arc<dyn RegionEventListener>;
+
+#[derive(Debug)]
+pub(crate) enum RegionEvent {
+    FlushCompleted {
+        region_id: RegionId,
+        file: FileMeta,
+    },
+    CompactionCompleted {
+        region_id: RegionId,
+        edit: RegionEdit,
+    },
+}
+
+impl RegionEvent {
+    fn notify(&self, listener: &dyn RegionEventListener) {
+        match self {
+            RegionEvent::FlushCompleted { region_id, file } => {
+                listener.on_flush_completed(*region_id, file)
+            }
+            RegionEvent::CompactionCompleted { region_id, edit } => {
+                listener.on_compaction_completed(*region_id, edit)
+            }
+        }
+    }
+}
+
+/// Dispatches region events to registered listeners.
+///
+/// Events are queued and delivered by a single task on a dedicated runtime, which is
+/// started when the first listener is registered, so the write path never waits for
+/// listeners.
+#[derive(Default)]
+pub struct RegionEventDispatcher {
+    listeners: Arc<RwLock<Vec<RegionEventListenerRef>>>,
+    sender: Mutex<Option<(UnboundedSender<RegionEvent>, Runtime)>>,
+}
+
+pub type RegionEventDispatcherRef = Arc<RegionEventDispatcher>;
+
+impl fmt::Debug for RegionEventDispatcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RegionEventDispatcher")
+            .field("listeners", &self.listeners.read().unwrap().len())
+            .finish()
+    }
+}
+
+impl RegionEventDispatcher {
+    pub fn register(&self, listener: RegionEventListenerRef) -> Result<()> {
+        let mut sender = self.sender.lock().unwrap();
+        if sender.is_none() {
+            let runtime = RuntimeBuilder::default()
+                .worker_threads(1)
+                .thread_name("region-event-listener")
+                .build()
+                .context(BuildListenerRuntimeSnafu)?;
+            let (tx, mut rx) = mpsc::unbounded_channel::<RegionEvent>();
+            let listeners = self.listeners.clone();
+            runtime.spawn(async move {
+                while let Some(event) = rx.recv().await {
+                    let listeners = listeners.read().unwrap().clone();
+                    for listener in listeners {
+                        let result =
+                            panic::catch_unwind(AssertUnwindSafe(|| event.notify(&*listener)));
+                        if result.is_err() {
+                            logging::error!("Region event listener panicked on {:?}", event);
+                        }
+                    }
+                }
+            });
+            *sender = Some((tx, runtime));
+        }
+
+        self.listeners.write().unwrap().push(listener);
+        Ok(())
+    }
+
+    pub(crate) fn publish(&self, event: RegionEvent) {
+        if let Some((sender, _)) = &*self.sender.lock().unwrap() {
+            // The receiver lives as long as the runtime, which is owned by the dispatcher.
+            let _ = sender.send(event);
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::sync::mpsc as std_mpsc;
+    use std::time::Duration;
+
+    use super::*;
+    use crate::sst::FileId;
+
+    /// Records events it received.
+    #[derive(Default)]
+    pub(crate) struct RecordingListener {
+        pub(crate) events: Mutex<Vec<String>>,
+    }
+
+    impl RegionEventListener for RecordingListener {
+        fn on_flush_completed(&self, region_id: RegionId, file: &FileMeta) {
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("flush {region_id} {}", file.file_id));
+        }
+
+        fn on_compaction_completed(&self, region_id: RegionId, edit: &RegionEdit) {
+            let mut removed = edit
+                .files_to_remove
+                .iter()
+                .map(|f| f.file_id.to_string())
+                .collect::<Vec<_>>();
+            removed.sort();
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("compaction {region_id} {}", removed.join(",")));
+        }
+    }
+
+    struct PanicListener;
+
+    impl RegionEventListener for PanicListener {
+        fn on_flush_completed(&self, _region_id: RegionId, _file: &FileMeta) {
+            panic!("listener panics");
+        }
+    }
+
+    struct NotifyListener(Mutex<std_mpsc::Sender<()>>);
+
+    impl RegionEventListener for NotifyListener {
+        fn on_flush_completed(&self, _region_id: RegionId, _file: &FileMeta) {
+            self.0.lock().unwrap().send(()).unwrap();
+        }
+    }
+
+    fn new_file_meta(region_id: RegionId) -> FileMeta {
+        FileMeta {
+            region_id,
+            file_id: FileId::random(),
+            time_range: None,
+            level: 0,
+            file_size: 0,
+            bloom_filter: false,
+            key_range: vec![],
+            row_groups: vec![],
+            num_rows: 0,
+        }
+    }
+
+    #[test]
+    fn test_dispatch_events_after_panic() {
+        let dispatcher = RegionEventDispatcher::default();
+        // Events without listeners are dropped.
+        dispatcher.publish(RegionEvent::FlushCompleted {
+            region_id: 1,
+            file: new_file_meta(1),
+        });
+
+        let recorder = Arc::new(RecordingListener::default());
+        let (tx, rx) = std_mpsc::channel();
+        dispatcher.register(Arc::new(PanicListener)).unwrap();
+        dispatcher.register(recorder.clone()).unwrap();
+        dispatcher
+            .register(Arc::new(NotifyListener(Mutex::new(tx))))
+            .unwrap();
+
+        let file = new_file_meta(1);
+        dispatcher.publish(RegionEvent::FlushCompleted {
+            region_id: 1,
+            file: file.clone(),
+        });
+        let edit = RegionEdit {
+            region_version: 0,
+            flushed_sequence: None,
+            files_to_add: vec![new_file_meta(1)],
+            files_to_remove: vec![file.clone()],
+        };
+        dispatcher.publish(RegionEvent::CompactionCompleted { region_id: 1, edit });
+        dispatcher.publish(RegionEvent::FlushCompleted {
+            region_id: 1,
+            file: new_file_meta(1),
+        });
+
+        // Listeners after the panicking one still receive both flush events.
+        rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        let events = recorder.events.lock().unwrap();
+        assert_eq!(3, events.len());
+        assert_eq!(format!("flush 1 {}", file.file_id), events[0]);
+        assert_eq!(format!("compaction 1 {}", file.file_id), events[1]);
+    }
+}
@@ -43,6 +43,11 @@ impl<M: MetaAction<Error = Error>> ManifestImpl<M> {
     pub fn update_state(&self, version: ManifestVersion, protocol: Option<ProtocolAction>) {
         self.inner.update_state(version, protocol);
     }
+
+    /// Deletes all files of the manifest, e.g. when its region or table is dropped.
+    pub async fn delete_all(&self) -> Result<()> {
+        self.inner.store.delete_all().await
+    }
 }
 
 #[async_trait]
@@ -98,6 +98,24 @@ impl ManifestObjectStore {
         }
     }
 
+    /// Deletes all files of the manifest.
+    pub async fn delete_all(&self) -> Result<()> {
+        let dir = self.object_store.object(&self.path);
+        let dir_exists = dir
+            .is_exist()
+            .await
+            .context(ReadObjectSnafu { path: &self.path })?;
+        if !dir_exists {
+            return Ok(());
+        }
+
+        self.object_store
+            .batch()
+            .remove_all(&self.path)
+            .await
+            .context(DeleteObjectSnafu { path: &self.path })
+    }
+
     fn delta_file_path(&self, version: ManifestVersion) -> String {
         format!("{}{}", self.path, delta_file(version))
     }
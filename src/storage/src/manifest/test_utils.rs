@@ -48,6 +48,10 @@ pub fn build_region_edit(
                 time_range: None,
                 level: 0,
                 file_size: DEFAULT_TEST_FILE_SIZE,
+                bloom_filter: false,
+                key_range: vec![],
+                row_groups: vec![],
+                num_rows: 0,
             })
             .collect(),
         files_to_remove: files_to_remove
@@ -58,6 +62,10 @@ pub fn build_region_edit(
                 time_range: None,
                 level: 0,
                 file_size: DEFAULT_TEST_FILE_SIZE,
+                bloom_filter: false,
+                key_range: vec![],
+                row_groups: vec![],
+                num_rows: 0,
             })
             .collect(),
     }
@@ -21,6 +21,8 @@ mod version;
 use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
 
+use common_time::Timestamp;
+use datatypes::value::Value;
 use datatypes::vectors::VectorRef;
 use store_api::storage::{consts, OpType, SequenceNumber};
 
@@ -58,6 +60,9 @@ pub trait Memtable: Send + Sync + std::fmt::Debug {
 
     /// Return the number of rows contained in this memtable.
     fn num_rows(&self) -> usize;
+
+    /// Returns the max timestamp of rows put into this memtable, `None` if there is no put.
+    fn max_timestamp(&self) -> Option<Timestamp>;
 }
 
 pub type MemtableRef = Arc<dyn Memtable>;
@@ -154,6 +159,17 @@ impl KeyValues {
         self.len() == 0
     }
 
+    /// Returns the max value of the timestamp column at `timestamp_index` of the keys.
+    pub fn max_timestamp(&self, timestamp_index: usize) -> Option<Timestamp> {
+        let timestamps = self.keys.get(timestamp_index)?;
+        (0..timestamps.len())
+            .filter_map(|i| match timestamps.get(i) {
+                Value::Timestamp(ts) => Some(ts),
+                _ => None,
+            })
+            .max()
+    }
+
     pub fn estimated_memory_size(&self) -> usize {
         self.keys.iter().fold(0, |acc, v| acc + v.memory_size())
             + self.values.iter().fold(0, |acc, v| acc + v.memory_size())
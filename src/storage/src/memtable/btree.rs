@@ -19,6 +19,7 @@ use std::ops::Bound;
 use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
 use std::sync::{Arc, RwLock};
 
+use common_time::Timestamp;
 use datatypes::data_type::DataType;
 use datatypes::prelude::*;
 use datatypes::value::Value;
@@ -43,6 +44,7 @@ pub struct BTreeMemtable {
     schema: RegionSchemaRef,
     map: Arc<RwLockMap>,
     estimated_bytes: AtomicUsize,
+    max_timestamp: RwLock<Option<Timestamp>>,
 }
 
 impl BTreeMemtable {
@@ -52,6 +54,7 @@ impl BTreeMemtable {
             schema,
             map: Arc::new(RwLock::new(BTreeMap::new())),
             estimated_bytes: AtomicUsize::new(0),
+            max_timestamp: RwLock::new(None),
         }
     }
 }
@@ -82,6 +85,11 @@ impl Memtable for BTreeMemtable {
     fn write(&self, kvs: &KeyValues) -> Result<()> {
         self.estimated_bytes
             .fetch_add(kvs.estimated_memory_size(), AtomicOrdering::Relaxed);
+        if kvs.op_type == OpType::Put {
+            let written = kvs.max_timestamp(self.schema.timestamp_key_index());
+            let mut max_timestamp = self.max_timestamp.write().unwrap();
+            *max_timestamp = (*max_timestamp).max(written);
+        }
 
         let mut map = self.map.write().unwrap();
         let iter_row = IterRow::new(kvs);
@@ -107,6 +115,10 @@ impl Memtable for BTreeMemtable {
     fn num_rows(&self) -> usize {
         self.map.read().unwrap().len()
     }
+
+    fn max_timestamp(&self) -> Option<Timestamp> {
+        *self.max_timestamp.read().unwrap()
+    }
 }
 
 struct BTreeIterator {
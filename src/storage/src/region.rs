@@ -12,46 +12,58 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod bulk_load;
 #[cfg(test)]
 mod tests;
 mod writer;
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashSet};
 use std::fmt;
-use std::sync::Arc;
-use std::time::Duration;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant, SystemTime};
 
 use async_trait::async_trait;
 use common_telemetry::logging;
-use snafu::ResultExt;
+use common_time::Timestamp;
+use futures::{stream, StreamExt, TryStreamExt};
+use object_store::retry::scope_task;
+use snafu::{ensure, ResultExt};
 use store_api::logstore::LogStore;
 use store_api::manifest::{self, Manifest, ManifestVersion, MetaActionIterator};
 use store_api::storage::{
-    AlterRequest, FlushContext, OpenOptions, ReadContext, Region, RegionId, SequenceNumber,
-    WriteContext, WriteResponse,
+    AlterRequest, CompactionOptions, FlushContext, OpenOptions, ReadContext, Region, RegionId,
+    SequenceNumber, WriteContext, WriteResponse,
 };
 
 use crate::compaction::CompactionSchedulerRef;
 use crate::config::EngineConfig;
 use crate::error::{self, Error, Result};
 use crate::file_purger::FilePurgerRef;
-use crate::flush::{FlushSchedulerRef, FlushStrategyRef};
+use crate::flush::{self, FlushSchedulerRef, FlushStrategyRef, FlushableRegion, MemtableBudgetRef};
+use crate::gc::{self, RegionGcReport};
+use crate::listener::{RegionEvent, RegionEventDispatcherRef};
 use crate::manifest::action::{
-    RawRegionMetadata, RegionChange, RegionMetaAction, RegionMetaActionList,
+    RawRegionMetadata, RegionChange, RegionEdit, RegionMetaAction, RegionMetaActionList,
 };
 use crate::manifest::region::RegionManifest;
-use crate::memtable::MemtableBuilderRef;
+use crate::memtable::{Inserter, MemtableBuilderRef, MemtableId};
 use crate::metadata::{RegionMetaImpl, RegionMetadata, RegionMetadataRef};
+use crate::region::bulk_load::{BulkLoad, BulkLoads};
 pub use crate::region::writer::{AlterContext, RegionWriter, RegionWriterRef, WriterContext};
 use crate::schema::compat::CompatWrite;
 use crate::snapshot::SnapshotImpl;
-use crate::sst::AccessLayerRef;
+use crate::sst::{AccessLayerRef, FileId, FileMeta};
+use crate::verify::{self, RegionVerifyReport};
 use crate::version::{
     Version, VersionControl, VersionControlRef, VersionEdit, INIT_COMMITTED_SEQUENCE,
 };
 use crate::wal::Wal;
 use crate::write_batch::WriteBatch;
 
+/// Interval to check whether SST files of a dropped region are purged.
+const PURGE_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
 /// [Region] implementation.
 pub struct RegionImpl<S: LogStore> {
     inner: Arc<RegionInner<S>>,
@@ -126,6 +138,20 @@ impl<S: LogStore> Region for RegionImpl<S> {
         self.inner.close().await
     }
 
+    fn memtable_usage_bytes(&self) -> u64 {
+        self.inner.memtable_bytes() as u64
+    }
+
+    fn written_rows(&self) -> u64 {
+        self.inner.shared.written_rows()
+    }
+
+    fn is_write_stalled(&self) -> bool {
+        self.inner
+            .writer
+            .is_write_stalled(&self.inner.shared.version_control)
+    }
+
     fn disk_usage_bytes(&self) -> u64 {
         let version = self.inner.version_control().current();
         version
@@ -136,9 +162,84 @@ impl<S: LogStore> Region for RegionImpl<S> {
             .sum()
     }
 
+    fn max_timestamp(&self) -> Option<Timestamp> {
+        let version = self.inner.version_control().current();
+        let memtables = version.memtables();
+        let in_ssts = version
+            .ssts()
+            .levels()
+            .iter()
+            .flat_map(|level_ssts| level_ssts.files())
+            .filter_map(|sst| sst.time_range().map(|(_, end)| end));
+        let in_memtables = std::iter::once(memtables.mutable_memtable())
+            .chain(memtables.immutable_memtables())
+            .filter_map(|memtable| memtable.max_timestamp());
+        in_ssts.chain(in_memtables).max()
+    }
+
+    fn approximate_rows(&self) -> u64 {
+        let version = self.inner.version_control().current();
+        let memtables = version.memtables();
+        let in_ssts: u64 = version
+            .ssts()
+            .levels()
+            .iter()
+            .map(|level_ssts| level_ssts.files().map(|sst| sst.num_rows()).sum::<u64>())
+            .sum();
+        let in_memtables: usize = std::iter::once(memtables.mutable_memtable())
+            .chain(memtables.immutable_memtables())
+            .map(|memtable| memtable.num_rows())
+            .sum();
+        in_ssts + in_memtables as u64
+    }
+
     async fn flush(&self, ctx: &FlushContext) -> Result<()> {
         self.inner.flush(ctx).await
     }
+
+    fn ttl(&self) -> Option<Duration> {
+        self.inner.shared.ttl()
+    }
+
+    fn set_ttl(&self, ttl: Option<Duration>) {
+        logging::info!(
+            "Update ttl of region {} from {:?} to {:?}",
+            self.inner.shared.name,
+            self.inner.shared.ttl(),
+            ttl
+        );
+        self.inner.shared.set_ttl(ttl);
+    }
+
+    fn compaction_options(&self) -> CompactionOptions {
+        self.inner.shared.compaction_options()
+    }
+
+    fn set_compaction_options(&self, options: CompactionOptions) {
+        logging::info!(
+            "Update compaction options of region {} from {:?} to {:?}",
+            self.inner.shared.name,
+            self.inner.shared.compaction_options(),
+            options
+        );
+        self.inner.shared.set_compaction_options(options);
+    }
+
+    async fn begin_bulk_load(&self, load_id: &str) -> Result<()> {
+        self.inner.begin_bulk_load(load_id).await
+    }
+
+    async fn write_bulk_load(&self, load_id: &str, request: WriteBatch) -> Result<WriteResponse> {
+        self.inner.write_bulk_load(load_id, request).await
+    }
+
+    async fn commit_bulk_load(&self, load_id: &str) -> Result<()> {
+        self.inner.commit_bulk_load(load_id).await
+    }
+
+    async fn abort_bulk_load(&self, load_id: &str) -> Result<()> {
+        self.inner.abort_bulk_load(load_id).await
+    }
 }
 
 /// Storage related config for region.
@@ -156,6 +257,9 @@ pub struct StoreConfig<S: LogStore> {
     pub engine_config: Arc<EngineConfig>,
     pub file_purger: FilePurgerRef,
     pub ttl: Option<Duration>,
+    pub compaction: CompactionOptions,
+    pub memtable_budget: Option<MemtableBudgetRef>,
+    pub event_dispatcher: RegionEventDispatcherRef,
 }
 
 pub type RecoverdMetadata = (SequenceNumber, (ManifestVersion, RawRegionMetadata));
@@ -203,18 +307,24 @@ impl<S: LogStore> RegionImpl<S> {
         let id = metadata.id();
         let name = metadata.name().to_string();
         let version_control = VersionControl::with_version(version);
-        let wal = Wal::new(id, store_config.log_store);
+        let wal = Wal::new(id, store_config.log_store)
+            .with_prefetch_batches(store_config.engine_config.wal_prefetch_batches);
 
         let inner = Arc::new(RegionInner {
             shared: Arc::new(SharedData {
                 id,
                 name,
                 version_control: Arc::new(version_control),
+                ttl: RwLock::new(store_config.ttl),
+                compaction_options: RwLock::new(store_config.compaction),
+                flushing: AtomicUsize::new(0),
+                written_rows: AtomicU64::new(0),
+                event_dispatcher: store_config.event_dispatcher,
+                dropped: AtomicBool::new(false),
             }),
             writer: Arc::new(RegionWriter::new(
-                store_config.memtable_builder,
+                store_config.memtable_builder.clone(),
                 store_config.engine_config.clone(),
-                store_config.ttl,
             )),
             wal,
             flush_strategy: store_config.flush_strategy,
@@ -222,7 +332,11 @@ impl<S: LogStore> RegionImpl<S> {
             compaction_scheduler: store_config.compaction_scheduler,
             sst_layer: store_config.sst_layer,
             manifest: store_config.manifest,
+            memtable_budget: store_config.memtable_budget,
+            memtable_builder: store_config.memtable_builder,
+            bulk_loads: BulkLoads::default(),
         });
+        inner.register_to_budget();
 
         RegionImpl { inner }
     }
@@ -283,18 +397,24 @@ impl<S: LogStore> RegionImpl<S> {
             );
         }
 
-        let wal = Wal::new(metadata.id(), store_config.log_store);
+        let wal = Wal::new(metadata.id(), store_config.log_store)
+            .with_prefetch_batches(store_config.engine_config.wal_prefetch_batches);
         wal.obsolete(flushed_sequence).await?;
         let shared = Arc::new(SharedData {
             id: metadata.id(),
             name,
             version_control,
+            ttl: RwLock::new(store_config.ttl),
+            compaction_options: RwLock::new(store_config.compaction),
+            flushing: AtomicUsize::new(0),
+            written_rows: AtomicU64::new(0),
+            event_dispatcher: store_config.event_dispatcher,
+            dropped: AtomicBool::new(false),
         });
 
         let writer = Arc::new(RegionWriter::new(
-            store_config.memtable_builder,
+            store_config.memtable_builder.clone(),
             store_config.engine_config.clone(),
-            store_config.ttl,
         ));
         let writer_ctx = WriterContext {
             shared: &shared,
@@ -305,6 +425,7 @@ impl<S: LogStore> RegionImpl<S> {
             wal: &wal,
             writer: &writer,
             manifest: &store_config.manifest,
+            memtable_budget: store_config.memtable_budget.as_ref(),
         };
         // Replay all unflushed data.
         writer
@@ -320,7 +441,11 @@ impl<S: LogStore> RegionImpl<S> {
             compaction_scheduler: store_config.compaction_scheduler,
             sst_layer: store_config.sst_layer,
             manifest: store_config.manifest,
+            memtable_budget: store_config.memtable_budget,
+            memtable_builder: store_config.memtable_builder,
+            bulk_loads: BulkLoads::default(),
         });
+        inner.register_to_budget();
 
         Ok(Some(RegionImpl { inner }))
     }
@@ -330,6 +455,250 @@ impl<S: LogStore> RegionImpl<S> {
         self.inner.shared.id()
     }
 
+    /// Returns metas of all SST files in current version of the region.
+    pub(crate) fn file_metas(&self) -> Vec<FileMeta> {
+        let version = self.inner.version_control().current();
+        version
+            .ssts()
+            .levels()
+            .iter()
+            .flat_map(|level| level.files().map(|file| file.meta()))
+            .collect()
+    }
+
+    /// Drops the region: closes it, removes all SST files from its version so they are
+    /// purged once no longer referenced, obsoletes its WAL and deletes its manifest.
+    pub(crate) async fn drop_region(&self) -> Result<()> {
+        self.inner.close().await?;
+        self.inner.writer.mark_dropped(&self.inner.shared).await;
+
+        let committed_sequence = self.inner.version_control().committed_sequence();
+        self.inner.wal.obsolete(committed_sequence).await?;
+        self.inner.manifest.delete_all().await
+    }
+
+    /// Deletes the WAL files of the dropped region, a file also holding logs of other
+    /// regions is kept until they are obsolete.
+    pub(crate) async fn purge_wal(&self) -> Result<()> {
+        self.inner.wal.purge().await
+    }
+
+    /// Waits until all SST files of the dropped region are deleted, SST files still in
+    /// use, e.g. by a compaction task, are deleted after they are released.
+    pub(crate) async fn wait_purged(&self, timeout: Duration) -> Result<()> {
+        let deadline = Instant::now() + timeout;
+        let mut last_remaining = None;
+        loop {
+            let remaining = self.inner.sst_layer.list_ssts().await?.len();
+            if remaining == 0 {
+                return Ok(());
+            }
+            if last_remaining != Some(remaining) {
+                logging::info!(
+                    "Waiting for {} SST files of dropped region {} to be purged",
+                    remaining,
+                    self.name()
+                );
+                last_remaining = Some(remaining);
+            }
+            ensure!(
+                Instant::now() < deadline,
+                error::PurgeTimeoutSnafu {
+                    region: self.name(),
+                    remaining,
+                    timeout,
+                }
+            );
+            tokio::time::sleep(PURGE_CHECK_INTERVAL).await;
+        }
+    }
+
+    /// Deletes SST files of the region that are never referenced by its manifest and were
+    /// modified more than `grace_period` ago, only reports them if `dry_run` is true.
+    ///
+    /// The region is skipped on any uncertainty, e.g. a flush or compaction is running, or
+    /// the files or the manifest can't be read.
+    pub(crate) async fn collect_garbage(
+        &self,
+        grace_period: Duration,
+        dry_run: bool,
+    ) -> RegionGcReport {
+        let mut report = RegionGcReport {
+            region_id: self.id(),
+            region_name: self.name().to_string(),
+            ..Default::default()
+        };
+        match self.find_orphans(grace_period).await {
+            Ok(orphans) => report.orphans = orphans,
+            Err(reason) => {
+                logging::info!(
+                    "Skip collecting orphan SST files of region {}, {}",
+                    self.name(),
+                    reason
+                );
+                report.skipped = Some(reason);
+                return report;
+            }
+        }
+        if dry_run {
+            return report;
+        }
+
+        for orphan in &report.orphans {
+            match self.inner.sst_layer.delete_sst(orphan.file_id).await {
+                Ok(()) => {
+                    logging::info!(
+                        "Deleted orphan SST file {} of region {}, size: {}",
+                        orphan.file_id.as_parquet(),
+                        self.name(),
+                        orphan.file_size
+                    );
+                    gc::record_deleted(orphan);
+                }
+                Err(e) => {
+                    logging::error!(e; "Failed to delete orphan SST file {} of region {}",
+                        orphan.file_id.as_parquet(), self.name());
+                    report.failed_files += 1;
+                }
+            }
+        }
+        report
+    }
+
+    /// Returns orphan files older than `grace_period`, or the reason to skip the region.
+    async fn find_orphans(
+        &self,
+        grace_period: Duration,
+    ) -> std::result::Result<Vec<gc::OrphanSst>, String> {
+        if self.inner.shared.is_dropped() {
+            return Err("region is dropped".to_string());
+        }
+        if self.is_busy() {
+            return Err("flush or compaction is running".to_string());
+        }
+        // Lists files before reading the manifest, so files applied in between are
+        // referenced.
+        let objects = self
+            .inner
+            .sst_layer
+            .list_sst_objects()
+            .await
+            .map_err(|e| format!("failed to list SST files: {e}"))?;
+        let mut referenced = self
+            .referenced_files()
+            .await
+            .map_err(|e| format!("failed to read manifest: {e}"))?;
+        // Committed bulk loads are removed before their files are added to the version, so
+        // staged files are collected before the version.
+        referenced.extend(self.inner.bulk_loads.staged_files().await);
+        referenced.extend(self.file_metas().into_iter().map(|meta| meta.file_id));
+        if self.is_busy() {
+            return Err("flush or compaction is running".to_string());
+        }
+
+        Ok(gc::find_orphans(
+            &objects,
+            &referenced,
+            SystemTime::now(),
+            grace_period,
+        ))
+    }
+
+    /// Checks that SST files of the current version exist in the object store with the
+    /// sizes in the manifest, and lists files never referenced by the manifest. At most
+    /// `parallelism` files are checked at a time. Nothing is modified.
+    pub(crate) async fn verify(&self, parallelism: usize) -> RegionVerifyReport {
+        let mut report = RegionVerifyReport {
+            region_id: self.id(),
+            region_name: self.name().to_string(),
+            ..Default::default()
+        };
+        if let Err(reason) = self.verify_files(parallelism, &mut report).await {
+            logging::info!("Skip verifying SST files of region {}, {}", self.name(), reason);
+            report.skipped = Some(reason);
+        }
+        report
+    }
+
+    /// Fills the problems of files in `report`, or returns the reason to skip the region.
+    async fn verify_files(
+        &self,
+        parallelism: usize,
+        report: &mut RegionVerifyReport,
+    ) -> std::result::Result<(), String> {
+        if self.inner.shared.is_dropped() {
+            return Err("region is dropped".to_string());
+        }
+        if self.is_busy() {
+            return Err("flush or compaction is running".to_string());
+        }
+        // Lists files before reading the manifest like collecting garbage, so files applied
+        // in between are referenced.
+        let objects = self
+            .inner
+            .sst_layer
+            .list_sst_objects()
+            .await
+            .map_err(|e| format!("failed to list SST files: {e}"))?;
+        let mut referenced = self
+            .referenced_files()
+            .await
+            .map_err(|e| format!("failed to read manifest: {e}"))?;
+        referenced.extend(self.inner.bulk_loads.staged_files().await);
+        // Holds the version, so its files are not purged during the check.
+        let version = self.inner.version_control().current();
+        let files: Vec<_> = version
+            .ssts()
+            .levels()
+            .iter()
+            .flat_map(|level| level.files().map(|file| file.meta()))
+            .collect();
+        referenced.extend(files.iter().map(|file| file.file_id));
+        if self.is_busy() {
+            return Err("flush or compaction is running".to_string());
+        }
+
+        let sst_layer = &self.inner.sst_layer;
+        let sizes: Vec<_> = stream::iter(&files)
+            .map(|file| sst_layer.sst_size(file.file_id))
+            .buffered(parallelism.max(1))
+            .try_collect()
+            .await
+            .map_err(|e| format!("failed to read SST files: {e}"))?;
+        let (missing, size_mismatches) = verify::check_files(&files, &sizes);
+        report.checked_files = files.len();
+        report.missing = missing;
+        report.size_mismatches = size_mismatches;
+        report.orphans = verify::find_unreferenced(&objects, &referenced);
+        Ok(())
+    }
+
+    /// Returns true if the region may be writing files not in its manifest yet.
+    fn is_busy(&self) -> bool {
+        let version = self.inner.version_control().current();
+        self.inner.shared.is_flushing()
+            || version
+                .ssts()
+                .levels()
+                .iter()
+                .any(|level| level.files().any(|file| file.compacting()))
+    }
+
+    /// Returns ids of all files ever added to the region by its manifest.
+    async fn referenced_files(&self) -> Result<HashSet<FileId>> {
+        let (start, end) = Self::manifest_scan_range();
+        let mut iter = self.inner.manifest.scan(start, end).await?;
+        let mut files = HashSet::new();
+        while let Some((_, action_list)) = iter.next_action().await? {
+            for action in action_list.actions {
+                if let RegionMetaAction::Edit(edit) = action {
+                    files.extend(edit.files_to_add.iter().map(|file| file.file_id));
+                }
+            }
+        }
+        Ok(files)
+    }
+
     async fn recover_from_manifest(
         manifest: &RegionManifest,
         memtable_builder: &MemtableBuilderRef,
@@ -424,6 +793,51 @@ impl<S: LogStore> RegionImpl<S> {
     }
 }
 
+// Methods for the compaction harness.
+#[cfg(any(test, feature = "test-util"))]
+impl<S: LogStore> RegionImpl<S> {
+    pub(crate) fn schema(&self) -> crate::schema::RegionSchemaRef {
+        self.inner.version_control().current().schema().clone()
+    }
+
+    pub(crate) fn sst_layer(&self) -> &AccessLayerRef {
+        &self.inner.sst_layer
+    }
+
+    /// Builds a request to compact the current version of the region.
+    pub(crate) fn compaction_request(&self) -> crate::compaction::CompactionRequestImpl<S> {
+        crate::compaction::CompactionRequestImpl {
+            region_id: self.id(),
+            sst_layer: self.inner.sst_layer.clone(),
+            writer: self.inner.writer.clone(),
+            shared: self.inner.shared.clone(),
+            manifest: self.inner.manifest.clone(),
+            wal: self.inner.wal.clone(),
+            ttl: self.inner.shared.ttl(),
+        }
+    }
+
+    /// Persists `files` to the manifest and adds them to the version of the region.
+    pub(crate) async fn add_files(&self, files: Vec<FileMeta>) -> Result<()> {
+        let edit = crate::manifest::action::RegionEdit {
+            region_version: self.inner.version_control().metadata().version(),
+            flushed_sequence: None,
+            files_to_add: files,
+            files_to_remove: vec![],
+        };
+        self.inner
+            .writer
+            .write_edit_and_apply(
+                &self.inner.wal,
+                &self.inner.shared,
+                &self.inner.manifest,
+                edit,
+                None,
+            )
+            .await
+    }
+}
+
 // Private methods for tests.
 #[cfg(test)]
 impl<S: LogStore> RegionImpl<S> {
@@ -453,6 +867,7 @@ impl<S: LogStore> RegionImpl<S> {
             wal: &inner.wal,
             writer: &inner.writer,
             manifest: &inner.manifest,
+            memtable_budget: inner.memtable_budget.as_ref(),
         };
 
         inner.writer.replay(recovered_metadata, writer_ctx).await
@@ -468,6 +883,18 @@ pub struct SharedData {
     name: String,
     // TODO(yingwen): Maybe no need to use Arc for version control.
     pub version_control: VersionControlRef,
+    /// Time-to-live of the region's data, could be updated at runtime.
+    ttl: RwLock<Option<Duration>>,
+    /// Compaction options overriding the engine's config, could be updated at runtime.
+    compaction_options: RwLock<CompactionOptions>,
+    /// Number of pending or running flushes of the region.
+    flushing: AtomicUsize,
+    /// Number of rows written to the region since it's opened.
+    written_rows: AtomicU64,
+    /// Dispatcher of the region's flush and compaction events.
+    event_dispatcher: RegionEventDispatcherRef,
+    /// Whether the region is dropped, no edit could be applied to a dropped region.
+    dropped: AtomicBool,
 }
 
 impl SharedData {
@@ -480,10 +907,77 @@ impl SharedData {
     pub fn name(&self) -> &str {
         &self.name
     }
+
+    #[inline]
+    pub fn ttl(&self) -> Option<Duration> {
+        *self.ttl.read().unwrap()
+    }
+
+    #[inline]
+    pub fn set_ttl(&self, ttl: Option<Duration>) {
+        *self.ttl.write().unwrap() = ttl;
+    }
+
+    #[inline]
+    pub fn compaction_options(&self) -> CompactionOptions {
+        *self.compaction_options.read().unwrap()
+    }
+
+    #[inline]
+    pub fn set_compaction_options(&self, options: CompactionOptions) {
+        *self.compaction_options.write().unwrap() = options;
+    }
+
+    #[inline]
+    pub fn written_rows(&self) -> u64 {
+        self.written_rows.load(Ordering::Relaxed)
+    }
+
+    /// Publishes a flush or compaction event of the region to listeners.
+    #[inline]
+    pub(crate) fn publish_event(&self, event: RegionEvent) {
+        self.event_dispatcher.publish(event);
+    }
+
+    /// Returns true if there are pending or running flushes of the region.
+    #[inline]
+    pub fn is_flushing(&self) -> bool {
+        self.flushing.load(Ordering::Relaxed) > 0
+    }
+
+    /// Returns true if the region is dropped.
+    #[inline]
+    pub fn is_dropped(&self) -> bool {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    #[inline]
+    pub(crate) fn mark_dropped(&self) {
+        self.dropped.store(true, Ordering::Relaxed);
+    }
+
+    /// Marks the region as flushing until the returned guard is dropped.
+    pub fn start_flushing(self: &Arc<Self>) -> FlushingGuard {
+        self.flushing.fetch_add(1, Ordering::Relaxed);
+        FlushingGuard {
+            shared: self.clone(),
+        }
+    }
 }
 
 pub type SharedDataRef = Arc<SharedData>;
 
+/// Guard that marks the region as flushing while it's alive.
+pub struct FlushingGuard {
+    shared: SharedDataRef,
+}
+
+impl Drop for FlushingGuard {
+    fn drop(&mut self) {
+        self.shared.flushing.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
 struct RegionInner<S: LogStore> {
     shared: SharedDataRef,
     writer: RegionWriterRef,
@@ -493,6 +987,10 @@ struct RegionInner<S: LogStore> {
     compaction_scheduler: CompactionSchedulerRef<S>,
     sst_layer: AccessLayerRef,
     manifest: RegionManifest,
+    memtable_budget: Option<MemtableBudgetRef>,
+    /// Builds memtables of bulk loads.
+    memtable_builder: MemtableBuilderRef,
+    bulk_loads: BulkLoads,
 }
 
 impl<S: LogStore> RegionInner<S> {
@@ -501,6 +999,14 @@ impl<S: LogStore> RegionInner<S> {
         &self.shared.version_control
     }
 
+    /// Registers the region to the memtable budget if there is one.
+    fn register_to_budget(self: &Arc<Self>) {
+        if let Some(budget) = &self.memtable_budget {
+            let region: Arc<dyn FlushableRegion> = self.clone();
+            budget.register(self.shared.id, Arc::downgrade(&region));
+        }
+    }
+
     fn in_memory_metadata(&self) -> RegionMetaImpl {
         let metadata = self.version_control().metadata();
 
@@ -511,7 +1017,7 @@ impl<S: LogStore> RegionInner<S> {
         let version = self.version_control().current();
         let sequence = self.version_control().committed_sequence();
 
-        SnapshotImpl::new(version, sequence, self.sst_layer.clone())
+        SnapshotImpl::new(version, sequence, self.sst_layer.clone(), self.shared.ttl())
     }
 
     fn compat_write_batch(&self, request: &mut WriteBatch) -> Result<()> {
@@ -534,10 +1040,17 @@ impl<S: LogStore> RegionInner<S> {
             wal: &self.wal,
             writer: &self.writer,
             manifest: &self.manifest,
+            memtable_budget: self.memtable_budget.as_ref(),
         };
+        let num_rows = request.payload().num_rows() as u64;
         // The writer would also try to compat the schema of write batch if it finds out the
         // schema version of request is less than current schema version.
-        self.writer.write(ctx, request, writer_ctx).await
+        let response = self.writer.write(ctx, request, writer_ctx).await?;
+        self.shared
+            .written_rows
+            .fetch_add(num_rows, Ordering::Relaxed);
+
+        Ok(response)
     }
 
     async fn alter(&self, request: AlterRequest) -> Result<()> {
@@ -558,9 +1071,202 @@ impl<S: LogStore> RegionInner<S> {
     }
 
     async fn close(&self) -> Result<()> {
+        if let Some(budget) = &self.memtable_budget {
+            budget.unregister(self.shared.id);
+        }
+        self.bulk_loads.clear();
         self.writer.close().await
     }
 
+    async fn begin_bulk_load(&self, load_id: &str) -> Result<()> {
+        let memtable_schema = self.version_control().current().schema().clone();
+        let memtable = self.memtable_builder.build(memtable_schema);
+        let sequence = self.writer.reserve_sequence(&self.wal, &self.shared).await?;
+        self.bulk_loads.add(
+            &self.shared.name,
+            load_id,
+            BulkLoad::new(sequence, memtable),
+        )?;
+
+        logging::info!(
+            "Begin bulk load {} of region {}, sequence: {}",
+            load_id,
+            self.shared.name,
+            sequence
+        );
+
+        Ok(())
+    }
+
+    async fn write_bulk_load(
+        &self,
+        load_id: &str,
+        mut request: WriteBatch,
+    ) -> Result<WriteResponse> {
+        let load = self.bulk_loads.get(&self.shared.name, load_id)?;
+        let full_memtable = {
+            let state = load.state.read().await;
+            ensure!(
+                !state.finished,
+                error::BulkLoadNotFoundSnafu {
+                    region: &self.shared.name,
+                    load_id,
+                }
+            );
+            // Rows of a bulk load are written with the schema when it begins.
+            request.compat_write(state.memtable.schema().user_schema())?;
+            let mut inserter = Inserter::new(load.sequence);
+            inserter.insert_memtable(request.payload(), &state.memtable)?;
+
+            let bytes = state.memtable.bytes_allocated();
+            load.memtable_bytes.store(bytes, Ordering::Relaxed);
+            self.flush_strategy
+                .should_flush(&self.shared, bytes, bytes)
+                .then(|| state.memtable.id())
+        };
+        if let Some(memtable_id) = full_memtable {
+            self.stage_bulk_load(load_id, &load, Some(memtable_id))
+                .await?;
+        }
+
+        if let Some(budget) = &self.memtable_budget {
+            // No lock of the region is held, so the flush is always scheduled in background,
+            // which also stages memtables of bulk loads if this region is picked.
+            if let Some(region) = budget.pick_region_to_flush() {
+                region.schedule_flush();
+            }
+        }
+
+        Ok(WriteResponse {})
+    }
+
+    /// Writes the memtable of the bulk load to a staged SST file, which is invisible to
+    /// reads until the bulk load commits, and replaces it with an empty one. Writes of the
+    /// bulk load wait until the memtable is staged.
+    ///
+    /// Only stages the memtable `memtable_id` if it's given, as others may have staged it.
+    async fn stage_bulk_load(
+        &self,
+        load_id: &str,
+        load: &BulkLoad,
+        memtable_id: Option<MemtableId>,
+    ) -> Result<()> {
+        let mut state = load.state.write().await;
+        if state.finished
+            || state.memtable.num_rows() == 0
+            || memtable_id.map_or(false, |id| id != state.memtable.id())
+        {
+            return Ok(());
+        }
+
+        // Marks the region as flushing, so the SST file is not reported as an orphan before
+        // it's staged.
+        let _guard = self.shared.start_flushing();
+        let file = scope_task(flush::write_memtable_to_sst(
+            self.shared.id,
+            &state.memtable,
+            &self.sst_layer,
+        ))
+        .await?;
+        logging::info!(
+            "Staged bulk load {} of region {}, rows: {}, file: {}",
+            load_id,
+            self.shared.name,
+            file.num_rows,
+            file.file_id.as_parquet()
+        );
+        state.staged.push(file);
+        state.memtable = self.memtable_builder.build(state.memtable.schema());
+        load.memtable_bytes.store(0, Ordering::Relaxed);
+
+        Ok(())
+    }
+
+    /// Stages memtables of all bulk loads.
+    async fn stage_bulk_loads(&self) -> Result<()> {
+        for (load_id, load) in self.bulk_loads.loads() {
+            self.stage_bulk_load(&load_id, &load, None).await?;
+        }
+        Ok(())
+    }
+
+    async fn commit_bulk_load(&self, load_id: &str) -> Result<()> {
+        // Marks the region as flushing before the bulk load is removed, so its files are not
+        // reported as orphans before they're added to the manifest.
+        let _guard = self.shared.start_flushing();
+        // Removes the bulk load first, so no rows could be written to it during the commit.
+        let load = self.bulk_loads.remove(&self.shared.name, load_id)?;
+        // Waits for the writes and the staging of the bulk load in flight.
+        let mut state = load.state.write().await;
+        state.finished = true;
+        load.memtable_bytes.store(0, Ordering::Relaxed);
+
+        let mut files = std::mem::take(&mut state.staged);
+        if state.memtable.num_rows() > 0 {
+            let file = scope_task(flush::write_memtable_to_sst(
+                self.shared.id,
+                &state.memtable,
+                &self.sst_layer,
+            ))
+            .await?;
+            files.push(file);
+        }
+        if files.is_empty() {
+            return Ok(());
+        }
+
+        let num_rows: u64 = files.iter().map(|file| file.num_rows).sum();
+        let file_names: Vec<_> = files.iter().map(|file| file.file_id.as_parquet()).collect();
+        let edit = RegionEdit {
+            region_version: self.version_control().metadata().version(),
+            flushed_sequence: None,
+            files_to_add: files,
+            files_to_remove: vec![],
+        };
+        self.writer
+            .write_edit_and_apply(&self.wal, &self.shared, &self.manifest, edit, None)
+            .await?;
+        self.shared
+            .written_rows
+            .fetch_add(num_rows, Ordering::Relaxed);
+
+        logging::info!(
+            "Committed bulk load {} of region {}, rows: {}, files: {:?}",
+            load_id,
+            self.shared.name,
+            num_rows,
+            file_names
+        );
+
+        Ok(())
+    }
+
+    async fn abort_bulk_load(&self, load_id: &str) -> Result<()> {
+        let load = self.bulk_loads.remove(&self.shared.name, load_id)?;
+        let mut state = load.state.write().await;
+        state.finished = true;
+        load.memtable_bytes.store(0, Ordering::Relaxed);
+
+        let staged = std::mem::take(&mut state.staged);
+        for file in &staged {
+            if let Err(e) = self.sst_layer.delete_sst(file.file_id).await {
+                // The file is left to be collected as an orphan.
+                logging::error!(e; "Failed to delete staged SST file {} of bulk load {} of region {}",
+                    file.file_id.as_parquet(), load_id, self.shared.name);
+            }
+        }
+        let staged_rows: u64 = staged.iter().map(|file| file.num_rows).sum();
+
+        logging::info!(
+            "Aborted bulk load {} of region {}, discarded rows: {}",
+            load_id,
+            self.shared.name,
+            state.memtable.num_rows() as u64 + staged_rows
+        );
+
+        Ok(())
+    }
+
     async fn flush(&self, ctx: &FlushContext) -> Result<()> {
         let writer_ctx = WriterContext {
             shared: &self.shared,
@@ -571,7 +1277,51 @@ impl<S: LogStore> RegionInner<S> {
             wal: &self.wal,
             writer: &self.writer,
             manifest: &self.manifest,
+            memtable_budget: self.memtable_budget.as_ref(),
         };
         self.writer.flush(writer_ctx, ctx).await
     }
 }
+
+impl<S: LogStore> FlushableRegion for RegionInner<S> {
+    fn id(&self) -> RegionId {
+        self.shared.id
+    }
+
+    fn memtable_bytes(&self) -> usize {
+        self.version_control()
+            .current()
+            .memtables()
+            .total_bytes_allocated()
+            + self.bulk_loads.memtable_bytes()
+    }
+
+    fn mutable_memtable_bytes(&self) -> usize {
+        // Memtables of bulk loads are staged by the flush of the region.
+        self.version_control()
+            .current()
+            .memtables()
+            .mutable_bytes_allocated()
+            + self.bulk_loads.memtable_bytes()
+    }
+
+    fn is_flushing(&self) -> bool {
+        self.shared.is_flushing()
+    }
+
+    fn schedule_flush(self: Arc<Self>) {
+        // Marks the region as flushing before the flush job is scheduled, so the region
+        // won't be picked again in the meantime.
+        let guard = self.shared.start_flushing();
+        common_runtime::spawn_bg(async move {
+            if let Err(e) = self.stage_bulk_loads().await {
+                logging::error!(e; "Failed to stage bulk loads of region {} for memtable budget", self.shared.name);
+            }
+            let ctx = FlushContext { wait: false };
+            if let Err(e) = self.flush(&ctx).await {
+                logging::error!(e; "Failed to flush region {} for memtable budget", self.shared.name);
+            }
+            drop(guard);
+        });
+    }
+}
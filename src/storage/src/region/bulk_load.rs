@@ -0,0 +1,141 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Bulk loads of a region, whose rows bypass the WAL.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use snafu::{ensure, OptionExt};
+use store_api::storage::SequenceNumber;
+use tokio::sync::RwLock;
+
+use crate::error::{self, Result};
+use crate::memtable::MemtableRef;
+use crate::sst::{FileId, FileMeta};
+
+/// An uncommitted bulk load.
+///
+/// Rows of the bulk load are only kept in memory and in staged SST files, which are not in
+/// the manifest. So a bulk load interrupted by a crash is gone with the region: committing
+/// it fails and it should be loaded again, its staged files are collected as orphans.
+pub(crate) struct BulkLoad {
+    /// Sequence of all rows in the bulk load, reserved when the bulk load begins.
+    pub(crate) sequence: SequenceNumber,
+    /// Bytes allocated by the memtable of the bulk load, accounted by the memtable budget.
+    pub(crate) memtable_bytes: AtomicUsize,
+    /// Rows are written with the read lock held, staging and finishing the bulk load take
+    /// the write lock so they wait for the writes in flight.
+    pub(crate) state: RwLock<BulkLoadState>,
+}
+
+impl BulkLoad {
+    pub(crate) fn new(sequence: SequenceNumber, memtable: MemtableRef) -> BulkLoad {
+        BulkLoad {
+            sequence,
+            memtable_bytes: AtomicUsize::new(0),
+            state: RwLock::new(BulkLoadState {
+                memtable,
+                staged: Vec::new(),
+                finished: false,
+            }),
+        }
+    }
+}
+
+pub(crate) struct BulkLoadState {
+    /// Memtable holding rows not staged yet. It's not in the version of the region, so its
+    /// rows are invisible to reads.
+    pub(crate) memtable: MemtableRef,
+    /// SST files written from full memtables of the bulk load, they are added to the
+    /// version of the region when the bulk load commits.
+    pub(crate) staged: Vec<FileMeta>,
+    /// Whether the bulk load is committed or aborted.
+    pub(crate) finished: bool,
+}
+
+/// Uncommitted bulk loads of a region.
+#[derive(Default)]
+pub(crate) struct BulkLoads {
+    loads: Mutex<HashMap<String, Arc<BulkLoad>>>,
+}
+
+impl BulkLoads {
+    /// Adds the bulk load `load_id`, fails if it already exists.
+    pub(crate) fn add(&self, region: &str, load_id: &str, load: BulkLoad) -> Result<()> {
+        let mut loads = self.loads.lock().unwrap();
+        ensure!(
+            !loads.contains_key(load_id),
+            error::BulkLoadExistsSnafu { region, load_id }
+        );
+        loads.insert(load_id.to_string(), Arc::new(load));
+        Ok(())
+    }
+
+    /// Returns the bulk load `load_id`. It may be finished by others once it's returned,
+    /// so writers should check [BulkLoadState::finished] with the state locked.
+    pub(crate) fn get(&self, region: &str, load_id: &str) -> Result<Arc<BulkLoad>> {
+        self.loads
+            .lock()
+            .unwrap()
+            .get(load_id)
+            .cloned()
+            .context(error::BulkLoadNotFoundSnafu { region, load_id })
+    }
+
+    /// Removes the bulk load `load_id`.
+    pub(crate) fn remove(&self, region: &str, load_id: &str) -> Result<Arc<BulkLoad>> {
+        self.loads
+            .lock()
+            .unwrap()
+            .remove(load_id)
+            .context(error::BulkLoadNotFoundSnafu { region, load_id })
+    }
+
+    /// Returns all bulk loads with their ids.
+    pub(crate) fn loads(&self) -> Vec<(String, Arc<BulkLoad>)> {
+        self.loads
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(load_id, load)| (load_id.clone(), load.clone()))
+            .collect()
+    }
+
+    /// Returns bytes allocated by memtables of all bulk loads.
+    pub(crate) fn memtable_bytes(&self) -> usize {
+        self.loads
+            .lock()
+            .unwrap()
+            .values()
+            .map(|load| load.memtable_bytes.load(Ordering::Relaxed))
+            .sum()
+    }
+
+    /// Returns ids of staged SST files of all bulk loads.
+    pub(crate) async fn staged_files(&self) -> Vec<FileId> {
+        let mut files = Vec::new();
+        for (_, load) in self.loads() {
+            let state = load.state.read().await;
+            files.extend(state.staged.iter().map(|file| file.file_id));
+        }
+        files
+    }
+
+    /// Discards all bulk loads, their staged files are left to be collected as orphans.
+    pub(crate) fn clear(&self) {
+        self.loads.lock().unwrap().clear();
+    }
+}
@@ -16,8 +16,10 @@
 
 mod alter;
 mod basic;
+mod bulk_load;
 mod close;
 mod flush;
+mod key_range;
 mod projection;
 
 use std::collections::{HashMap, HashSet};
@@ -96,6 +98,23 @@ impl<S: LogStore> TesterBase<S> {
         self.region.write(&self.write_ctx, batch).await
     }
 
+    /// Put without version specified to the bulk load `load_id`.
+    ///
+    /// Format of data: (timestamp, v0), timestamp is key, v0 is value.
+    pub async fn try_put_bulk_load(
+        &self,
+        load_id: &str,
+        data: &[(i64, Option<i64>)],
+    ) -> Result<WriteResponse> {
+        let data: Vec<(TimestampMillisecond, Option<i64>)> =
+            data.iter().map(|(l, r)| ((*l).into(), *r)).collect();
+        let mut batch = new_write_batch_for_test(false);
+        let put_data = new_put_data(&data);
+        batch.put(put_data).unwrap();
+
+        self.region.write_bulk_load(load_id, batch).await
+    }
+
     /// Put without version specified directly to inner writer.
     pub async fn put_inner(&self, data: &[(i64, Option<i64>)]) -> WriteResponse {
         let data: Vec<(TimestampMillisecond, Option<i64>)> =
@@ -116,13 +135,15 @@ impl<S: LogStore> TesterBase<S> {
 
     /// Scan all data.
     pub async fn full_scan(&self) -> Vec<(i64, Option<i64>)> {
+        self.full_scan_with_request(ScanRequest::default()).await
+    }
+
+    /// Scan all data with the given request.
+    pub async fn full_scan_with_request(&self, request: ScanRequest) -> Vec<(i64, Option<i64>)> {
         logging::info!("Full scan with ctx {:?}", self.read_ctx);
         let snapshot = self.region.snapshot(&self.read_ctx).unwrap();
 
-        let resp = snapshot
-            .scan(&self.read_ctx, ScanRequest::default())
-            .await
-            .unwrap();
+        let resp = snapshot.scan(&self.read_ctx, request).await.unwrap();
         let mut reader = resp.reader;
 
         let metadata = self.region.in_memory_metadata();
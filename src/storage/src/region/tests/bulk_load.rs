@@ -0,0 +1,317 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Region bulk load tests.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use common_error::prelude::{ErrorExt, StatusCode};
+use common_test_util::temp_dir::create_temp_dir;
+use log_store::raft_engine::log_store::RaftEngineLogStore;
+use store_api::storage::{OpenOptions, Region, WriteResponse};
+
+use crate::engine;
+use crate::flush::{FlushStrategyRef, SizeBasedStrategy};
+use crate::region::tests::{self, FileTesterBase};
+use crate::region::RegionImpl;
+use crate::test_util::config_util;
+use crate::test_util::flush_switch::{has_parquet_file, FlushSwitch};
+
+const REGION_NAME: &str = "region-bulk-load-0";
+
+/// Create a new region for bulk load test
+async fn create_region_for_bulk_load(
+    store_dir: &str,
+    flush_strategy: FlushStrategyRef,
+) -> RegionImpl<RaftEngineLogStore> {
+    let metadata = tests::new_metadata(REGION_NAME, false);
+
+    let mut store_config = config_util::new_store_config(REGION_NAME, store_dir).await;
+    store_config.flush_strategy = flush_strategy;
+
+    RegionImpl::create(metadata, store_config).await.unwrap()
+}
+
+/// Tester for region bulk load.
+struct BulkLoadTester {
+    base: Option<FileTesterBase>,
+    store_dir: String,
+}
+
+impl BulkLoadTester {
+    async fn new(store_dir: &str) -> BulkLoadTester {
+        Self::with_flush_strategy(store_dir, Arc::new(SizeBasedStrategy::default())).await
+    }
+
+    async fn with_flush_strategy(
+        store_dir: &str,
+        flush_strategy: FlushStrategyRef,
+    ) -> BulkLoadTester {
+        let region = create_region_for_bulk_load(store_dir, flush_strategy).await;
+
+        BulkLoadTester {
+            base: Some(FileTesterBase::with_region(region)),
+            store_dir: store_dir.to_string(),
+        }
+    }
+
+    async fn reopen(&mut self) {
+        // Close the old region.
+        if let Some(base) = self.base.as_ref() {
+            base.close().await;
+        }
+        self.base = None;
+        // Reopen the region.
+        let store_config = config_util::new_store_config(REGION_NAME, &self.store_dir).await;
+        let opts = OpenOptions::default();
+        let region = RegionImpl::open(REGION_NAME.to_string(), store_config, &opts)
+            .await
+            .unwrap()
+            .unwrap();
+        self.base = Some(FileTesterBase::with_region(region));
+    }
+
+    #[inline]
+    fn base(&self) -> &FileTesterBase {
+        self.base.as_ref().unwrap()
+    }
+
+    fn region(&self) -> &RegionImpl<RaftEngineLogStore> {
+        &self.base().region
+    }
+
+    async fn put(&self, data: &[(i64, Option<i64>)]) -> WriteResponse {
+        self.base().put(data).await
+    }
+
+    async fn put_bulk_load(&self, load_id: &str, data: &[(i64, Option<i64>)]) -> WriteResponse {
+        self.base().try_put_bulk_load(load_id, data).await.unwrap()
+    }
+
+    async fn full_scan(&self) -> Vec<(i64, Option<i64>)> {
+        self.base().full_scan().await
+    }
+
+    fn sst_dir(&self) -> String {
+        format!("{}/{}", self.store_dir, engine::region_sst_dir("", REGION_NAME))
+    }
+}
+
+#[tokio::test]
+async fn test_bulk_load_visible_after_commit() {
+    common_telemetry::init_default_ut_logging();
+
+    let dir = create_temp_dir("bulk-load-commit");
+    let store_dir = dir.path().to_str().unwrap();
+    let mut tester = BulkLoadTester::new(store_dir).await;
+
+    tester.put(&[(1000, Some(100))]).await;
+    tester.region().begin_bulk_load("load-0").await.unwrap();
+    tester
+        .put_bulk_load("load-0", &[(2000, Some(200)), (3000, Some(300))])
+        .await;
+    tester.put_bulk_load("load-0", &[(4000, Some(400))]).await;
+
+    // Rows of the bulk load are invisible until it's committed.
+    assert_eq!(vec![(1000, Some(100))], tester.full_scan().await);
+    assert_eq!(1, tester.region().approximate_rows());
+
+    tester.region().commit_bulk_load("load-0").await.unwrap();
+    // The bulk load is written to a SST file, the normal write is still in the memtable.
+    assert!(has_parquet_file(&tester.sst_dir()));
+    let expect = vec![
+        (1000, Some(100)),
+        (2000, Some(200)),
+        (3000, Some(300)),
+        (4000, Some(400)),
+    ];
+    assert_eq!(expect, tester.full_scan().await);
+
+    // The bulk load is gone after commit.
+    let err = tester.region().commit_bulk_load("load-0").await.unwrap_err();
+    assert_eq!(StatusCode::InvalidArguments, err.status_code());
+
+    // Committed rows survive reopening.
+    tester.reopen().await;
+    assert_eq!(expect, tester.full_scan().await);
+
+    // Rows written after reopening override the bulk load.
+    tester.put(&[(2000, Some(20))]).await;
+    let expect = vec![
+        (1000, Some(100)),
+        (2000, Some(20)),
+        (3000, Some(300)),
+        (4000, Some(400)),
+    ];
+    assert_eq!(expect, tester.full_scan().await);
+}
+
+#[tokio::test]
+async fn test_abort_bulk_load() {
+    common_telemetry::init_default_ut_logging();
+
+    let dir = create_temp_dir("bulk-load-abort");
+    let store_dir = dir.path().to_str().unwrap();
+    let tester = BulkLoadTester::new(store_dir).await;
+
+    tester.region().begin_bulk_load("load-0").await.unwrap();
+    // Bulk loads with the same id can't run at the same time.
+    let err = tester.region().begin_bulk_load("load-0").await.unwrap_err();
+    assert!(
+        err.to_string().contains("already exists"),
+        "unexpected error: {err}"
+    );
+
+    tester.put_bulk_load("load-0", &[(1000, Some(100))]).await;
+    tester.region().abort_bulk_load("load-0").await.unwrap();
+    assert!(tester.full_scan().await.is_empty());
+    assert!(!has_parquet_file(&tester.sst_dir()));
+
+    // Aborted bulk loads can't be written or committed.
+    let err = tester
+        .base()
+        .try_put_bulk_load("load-0", &[(2000, Some(200))])
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("not found"), "unexpected error: {err}");
+    assert!(tester.region().commit_bulk_load("load-0").await.is_err());
+
+    // The id could be reused.
+    tester.region().begin_bulk_load("load-0").await.unwrap();
+    tester.put_bulk_load("load-0", &[(3000, Some(300))]).await;
+    tester.region().commit_bulk_load("load-0").await.unwrap();
+    assert_eq!(vec![(3000, Some(300))], tester.full_scan().await);
+}
+
+#[tokio::test]
+async fn test_bulk_load_discarded_on_reopen() {
+    common_telemetry::init_default_ut_logging();
+
+    let dir = create_temp_dir("bulk-load-reopen");
+    let store_dir = dir.path().to_str().unwrap();
+    let mut tester = BulkLoadTester::new(store_dir).await;
+
+    tester.put(&[(1000, Some(100))]).await;
+    tester.region().begin_bulk_load("load-0").await.unwrap();
+    tester.put_bulk_load("load-0", &[(2000, Some(200))]).await;
+
+    // Rows of the bulk load are not in the WAL, so they are gone with the region.
+    tester.reopen().await;
+    assert_eq!(vec![(1000, Some(100))], tester.full_scan().await);
+    assert!(tester.region().commit_bulk_load("load-0").await.is_err());
+    assert!(!has_parquet_file(&tester.sst_dir()));
+}
+
+#[tokio::test]
+async fn test_bulk_load_with_concurrent_writes() {
+    common_telemetry::init_default_ut_logging();
+
+    let dir = create_temp_dir("bulk-load-concurrent-writes");
+    let store_dir = dir.path().to_str().unwrap();
+    let mut tester = BulkLoadTester::new(store_dir).await;
+
+    tester.put(&[(1000, Some(1)), (2000, Some(2))]).await;
+    tester.region().begin_bulk_load("load-0").await.unwrap();
+    // Normal writes during the bulk load still go to the WAL and are visible at once.
+    tester.put(&[(2000, Some(20)), (4000, Some(40))]).await;
+    assert_eq!(
+        vec![(1000, Some(1)), (2000, Some(20)), (4000, Some(40))],
+        tester.full_scan().await
+    );
+
+    tester
+        .put_bulk_load(
+            "load-0",
+            &[(1000, Some(100)), (2000, Some(200)), (3000, Some(300))],
+        )
+        .await;
+    tester.region().commit_bulk_load("load-0").await.unwrap();
+
+    // Rows of the bulk load override rows written before it begins, and are overridden
+    // by rows written after it begins, even if they are committed later.
+    let expect = vec![
+        (1000, Some(100)),
+        (2000, Some(20)),
+        (3000, Some(300)),
+        (4000, Some(40)),
+    ];
+    assert_eq!(expect, tester.full_scan().await);
+
+    // The order is kept after replaying the WAL.
+    tester.reopen().await;
+    assert_eq!(expect, tester.full_scan().await);
+
+    // Rows written after reopening still override the bulk load.
+    tester.put(&[(3000, Some(30))]).await;
+    let expect = vec![
+        (1000, Some(100)),
+        (2000, Some(20)),
+        (3000, Some(30)),
+        (4000, Some(40)),
+    ];
+    assert_eq!(expect, tester.full_scan().await);
+}
+
+#[tokio::test]
+async fn test_bulk_load_staged_until_commit() {
+    common_telemetry::init_default_ut_logging();
+
+    let dir = create_temp_dir("bulk-load-staged");
+    let store_dir = dir.path().to_str().unwrap();
+    let flush_switch = Arc::new(FlushSwitch::default());
+    let tester = BulkLoadTester::with_flush_strategy(store_dir, flush_switch.clone()).await;
+
+    tester.region().begin_bulk_load("load-0").await.unwrap();
+    // Full memtables of the bulk load are staged to SST files.
+    flush_switch.set_should_flush(true);
+    tester.put_bulk_load("load-0", &[(1000, Some(100))]).await;
+    tester.put_bulk_load("load-0", &[(2000, Some(200))]).await;
+    flush_switch.set_should_flush(false);
+    tester.put_bulk_load("load-0", &[(3000, Some(300))]).await;
+    assert!(has_parquet_file(&tester.sst_dir()));
+
+    // Staged files are invisible to reads, but they are not orphans.
+    assert!(tester.full_scan().await.is_empty());
+    let report = tester
+        .region()
+        .collect_garbage(Duration::from_secs(0), true)
+        .await;
+    assert!(report.skipped.is_none(), "{report:?}");
+    assert!(report.orphans.is_empty(), "{report:?}");
+
+    tester.region().commit_bulk_load("load-0").await.unwrap();
+    let expect = vec![(1000, Some(100)), (2000, Some(200)), (3000, Some(300))];
+    assert_eq!(expect, tester.full_scan().await);
+}
+
+#[tokio::test]
+async fn test_abort_staged_bulk_load() {
+    common_telemetry::init_default_ut_logging();
+
+    let dir = create_temp_dir("bulk-load-abort-staged");
+    let store_dir = dir.path().to_str().unwrap();
+    let flush_switch = Arc::new(FlushSwitch::default());
+    let tester = BulkLoadTester::with_flush_strategy(store_dir, flush_switch.clone()).await;
+
+    tester.region().begin_bulk_load("load-0").await.unwrap();
+    flush_switch.set_should_flush(true);
+    tester.put_bulk_load("load-0", &[(1000, Some(100))]).await;
+    assert!(has_parquet_file(&tester.sst_dir()));
+
+    // Staged files are deleted with the bulk load.
+    tester.region().abort_bulk_load("load-0").await.unwrap();
+    assert!(!has_parquet_file(&tester.sst_dir()));
+    assert!(tester.full_scan().await.is_empty());
+}
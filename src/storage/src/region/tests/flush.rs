@@ -14,17 +14,38 @@
 
 //! Region flush tests.
 
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
+use async_trait::async_trait;
+use common_error::prelude::ErrorExt;
+use common_recordbatch::scan_stats::ScanStatsRecorder;
 use common_test_util::temp_dir::create_temp_dir;
+use common_time::Timestamp;
+use datatypes::type_id::LogicalTypeId;
 use log_store::raft_engine::log_store::RaftEngineLogStore;
-use store_api::storage::{FlushContext, OpenOptions, Region, WriteResponse};
-
+use store_api::storage::{
+    CompactionOptions, FlushContext, OpenOptions, Region, RegionId, ScanRequest, WriteResponse,
+};
+use tokio::sync::RwLock;
+
+use crate::background::{Context, Job, JobHandle};
+use crate::compaction::CompactionRequestImpl;
+use crate::config::{EngineConfig, WriteStallConfig};
 use crate::engine;
-use crate::flush::FlushStrategyRef;
+use crate::error::{Error, Result};
+use crate::flush::{
+    FlushScheduler, FlushSchedulerRef, FlushStrategyRef, MemtableBudget, MemtableBudgetRef,
+    SizeBasedStrategy,
+};
+use crate::listener::tests::RecordingListener;
 use crate::region::tests::{self, FileTesterBase};
 use crate::region::RegionImpl;
+use crate::scheduler::{Scheduler, SchedulerStatus};
+use crate::sst::FileId;
 use crate::test_util::config_util;
+use crate::test_util::descriptor_util::RegionDescBuilder;
 use crate::test_util::flush_switch::{has_parquet_file, FlushSwitch};
 
 const REGION_NAME: &str = "region-flush-0";
@@ -116,13 +137,13 @@ async fn test_flush_and_stall() {
     // Put element to trigger flush.
     tester.put(&data).await;
 
-    // Now put another data to trigger write stall and wait until last flush done to
-    // ensure at least one parquet file is generated.
+    // Now put another data while the last flush may be running, writes don't wait for
+    // the flush unless there are too many unflushed bytes.
     tester.put(&data).await;
 
     // Check parquet files.
     let sst_dir = format!("{}/{}", store_dir, engine::region_sst_dir("", REGION_NAME));
-    assert!(has_parquet_file(&sst_dir));
+    wait_parquet_file(&sst_dir).await;
 }
 
 #[tokio::test]
@@ -148,6 +169,141 @@ async fn test_manual_flush() {
     assert!(has_parquet_file(&sst_dir));
 }
 
+#[tokio::test]
+async fn test_max_timestamp_and_rows() {
+    common_telemetry::init_default_ut_logging();
+    let dir = create_temp_dir("max-timestamp");
+    let store_dir = dir.path().to_str().unwrap();
+
+    let flush_switch = Arc::new(FlushSwitch::default());
+    let mut tester = FlushTester::new(store_dir, flush_switch).await;
+    let stats = |tester: &FlushTester| {
+        let region = &tester.base().region;
+        (region.max_timestamp(), region.approximate_rows())
+    };
+    assert_eq!((None, 0), stats(&tester));
+
+    tester.put(&[(1000, Some(100)), (2000, Some(200))]).await;
+    assert_eq!((Some(Timestamp::new_millisecond(2000)), 2), stats(&tester));
+
+    // Flushed rows are counted from the SST metadata.
+    tester.flush(None).await;
+    assert_eq!((Some(Timestamp::new_millisecond(2000)), 2), stats(&tester));
+
+    // Older rows don't lower the high-water mark.
+    tester.put(&[(3000, Some(300)), (500, Some(50))]).await;
+    assert_eq!((Some(Timestamp::new_millisecond(3000)), 4), stats(&tester));
+
+    // Unflushed rows are replayed from the WAL.
+    tester.reopen().await;
+    assert_eq!((Some(Timestamp::new_millisecond(3000)), 4), stats(&tester));
+
+    tester.flush(None).await;
+    tester.reopen().await;
+    assert_eq!((Some(Timestamp::new_millisecond(3000)), 4), stats(&tester));
+}
+
+#[tokio::test]
+async fn test_verify_after_flush() {
+    common_telemetry::init_default_ut_logging();
+    let dir = create_temp_dir("verify-after-flush");
+    let store_dir = dir.path().to_str().unwrap();
+
+    let flush_switch = Arc::new(FlushSwitch::default());
+    let tester = FlushTester::new(store_dir, flush_switch).await;
+    tester.put(&[(1000, Some(100))]).await;
+    tester.flush(None).await;
+
+    let region = &tester.base().region;
+    let report = region.verify(2).await;
+    assert_eq!(1, report.checked_files);
+    assert!(!report.has_problems(), "{report:?}");
+
+    let sst_dir = format!("{}/{}", store_dir, engine::region_sst_dir("", REGION_NAME));
+    let flushed = region.file_metas().remove(0);
+    let flushed_path = format!("{sst_dir}{}", flushed.file_id.as_parquet());
+    let orphan = FileId::random();
+    std::fs::write(format!("{sst_dir}{}", orphan.as_parquet()), b"orphan").unwrap();
+    std::fs::write(&flushed_path, b"truncated").unwrap();
+    let report = region.verify(2).await;
+    assert_eq!(orphan, report.orphans[0].file_id);
+    assert_eq!(flushed.file_id, report.size_mismatches[0].file_id);
+    assert_eq!(9, report.size_mismatches[0].actual_size);
+    assert!(report.missing.is_empty());
+
+    std::fs::remove_file(&flushed_path).unwrap();
+    let report = region.verify(2).await;
+    assert_eq!(flushed.file_id, report.missing[0].file_id);
+    assert!(report.size_mismatches.is_empty());
+    // Nothing is deleted by the check.
+    assert_eq!(1, report.orphans.len());
+}
+
+/// Compaction scheduler that only counts the scheduled requests.
+#[derive(Debug, Default)]
+struct CountingCompactionScheduler {
+    scheduled: AtomicUsize,
+}
+
+#[async_trait]
+impl Scheduler for CountingCompactionScheduler {
+    type Request = CompactionRequestImpl<RaftEngineLogStore>;
+
+    fn schedule(&self, _request: Self::Request) -> Result<bool> {
+        self.scheduled.fetch_add(1, Ordering::Relaxed);
+        Ok(true)
+    }
+
+    async fn stop(&self, _await_termination: bool) -> Result<()> {
+        Ok(())
+    }
+
+    fn max_inflight_tasks(&self) -> usize {
+        0
+    }
+
+    fn set_max_inflight_tasks(&self, _max_inflight_tasks: usize) {}
+
+    fn status(&self) -> SchedulerStatus {
+        SchedulerStatus::default()
+    }
+}
+
+async fn put_and_flush(tester: &FileTesterBase, ts: i64) {
+    tester.put(&[(ts, Some(ts))]).await;
+    tester.region.flush(&FlushContext::default()).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_alter_compaction_options() {
+    common_telemetry::init_default_ut_logging();
+    let dir = create_temp_dir("compaction-options");
+    let store_dir = dir.path().to_str().unwrap();
+
+    let scheduler = Arc::new(CountingCompactionScheduler::default());
+    let metadata = tests::new_metadata(REGION_NAME, false);
+    let mut store_config = config_util::new_store_config(REGION_NAME, store_dir).await;
+    store_config.flush_strategy = Arc::new(FlushSwitch::default());
+    store_config.compaction_scheduler = scheduler.clone();
+    let region = RegionImpl::create(metadata, store_config).await.unwrap();
+    let tester = FileTesterBase::with_region(region);
+
+    // Regions are compacted once they have more than 8 files in level 0 by default.
+    put_and_flush(&tester, 1000).await;
+    put_and_flush(&tester, 2000).await;
+    assert_eq!(0, scheduler.scheduled.load(Ordering::Relaxed));
+
+    // The new threshold applies to the next flush without reopening the region.
+    let options = CompactionOptions {
+        max_files_in_l0: Some(2),
+        ..Default::default()
+    };
+    tester.region.set_compaction_options(options);
+    assert_eq!(options, tester.region.compaction_options());
+    put_and_flush(&tester, 3000).await;
+    assert_eq!(1, scheduler.scheduled.load(Ordering::Relaxed));
+}
+
 #[tokio::test]
 async fn test_flush_empty() {
     let dir = create_temp_dir("flush-empty");
@@ -251,3 +407,261 @@ async fn test_merge_read_after_flush() {
     let output = tester.full_scan().await;
     assert_eq!(expect, output);
 }
+
+/// Create a new region that shares the memtable `budget` with other regions.
+#[tokio::test]
+async fn test_scan_stats_after_flush() {
+    let dir = create_temp_dir("scan-stats-flush");
+    let store_dir = dir.path().to_str().unwrap();
+
+    let flush_switch = Arc::new(FlushSwitch::default());
+    let tester = FlushTester::new(store_dir, flush_switch.clone()).await;
+
+    tester.put(&[(1000, Some(100))]).await;
+    tester.put(&[(2000, Some(200))]).await;
+    tester.flush(None).await;
+    tester.put(&[(3000, Some(300))]).await;
+
+    let scan_stats = Arc::new(ScanStatsRecorder::default());
+    let request = ScanRequest {
+        scan_stats: Some(scan_stats.clone()),
+        ..Default::default()
+    };
+    let output = tester.base().full_scan_with_request(request).await;
+    assert_eq!(3, output.len());
+
+    let stats = scan_stats.stats();
+    assert_eq!(1, stats.files_scanned);
+    assert_eq!(0, stats.files_pruned);
+    // rows in the memtable are not decoded from SSTs
+    assert_eq!(2, stats.rows_decoded);
+    assert!(stats.bytes_read > 0);
+}
+
+async fn create_region_with_budget(
+    store_dir: &str,
+    region_id: RegionId,
+    region_name: &str,
+    budget: MemtableBudgetRef,
+) -> FileTesterBase {
+    let desc = RegionDescBuilder::new(region_name)
+        .id(region_id)
+        .push_value_column(("v0", LogicalTypeId::Int64, true))
+        .build();
+    let metadata = desc.try_into().unwrap();
+
+    let mut store_config = config_util::new_store_config(region_name, store_dir).await;
+    store_config.flush_strategy = Arc::new(FlushSwitch::default());
+    store_config.memtable_budget = Some(budget);
+
+    let region = RegionImpl::create(metadata, store_config).await.unwrap();
+    FileTesterBase::with_region(region)
+}
+
+/// Waits until a parquet file is flushed to `sst_dir`.
+async fn wait_parquet_file(sst_dir: &str) {
+    for _ in 0..100 {
+        if std::path::Path::new(sst_dir).exists() && has_parquet_file(sst_dir) {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    panic!("No parquet file flushed to {sst_dir}");
+}
+
+#[tokio::test]
+async fn test_flush_by_memtable_budget() {
+    common_telemetry::init_default_ut_logging();
+
+    let dir_a = create_temp_dir("flush-budget-a");
+    let store_dir_a = dir_a.path().to_str().unwrap();
+    let dir_b = create_temp_dir("flush-budget-b");
+    let store_dir_b = dir_b.path().to_str().unwrap();
+
+    // A tiny budget, any data in memtables exceeds it.
+    let budget = Arc::new(MemtableBudget::new(1));
+    let region_a = create_region_with_budget(store_dir_a, 1, "region-a", budget.clone()).await;
+    let region_b = create_region_with_budget(store_dir_b, 2, "region-b", budget.clone()).await;
+    let sst_dir_a = format!("{}/{}", store_dir_a, engine::region_sst_dir("", "region-a"));
+    let sst_dir_b = format!("{}/{}", store_dir_b, engine::region_sst_dir("", "region-b"));
+
+    // Memtables of all regions are empty, nothing to flush.
+    region_a.put(&[(1000, Some(100)), (2000, Some(200))]).await;
+    assert!(region_a.region.memtable_usage_bytes() > 0);
+
+    // Writing to region b finds the budget exceeded by region a, which is the only
+    // region that has data in memtable, so region a is flushed in background.
+    region_b.put(&[(1000, Some(100))]).await;
+    wait_parquet_file(&sst_dir_a).await;
+
+    // Region a is flushed, then region b becomes the largest one and flushes itself.
+    region_b.put(&[(2000, Some(200))]).await;
+    wait_parquet_file(&sst_dir_b).await;
+
+    // Data is still readable after flushes.
+    assert_eq!(
+        vec![(1000, Some(100)), (2000, Some(200))],
+        region_a.full_scan().await
+    );
+    assert_eq!(
+        vec![(1000, Some(100)), (2000, Some(200))],
+        region_b.full_scan().await
+    );
+}
+
+#[tokio::test]
+async fn test_flush_publish_events() {
+    common_telemetry::init_default_ut_logging();
+
+    let dir = create_temp_dir("flush-events");
+    let store_dir = dir.path().to_str().unwrap();
+
+    let metadata = tests::new_metadata(REGION_NAME, false);
+    let mut store_config = config_util::new_store_config(REGION_NAME, store_dir).await;
+    store_config.flush_strategy = Arc::new(FlushSwitch::default());
+    let dispatcher = store_config.event_dispatcher.clone();
+    let listener = Arc::new(RecordingListener::default());
+    dispatcher.register(listener.clone()).unwrap();
+    let region = RegionImpl::create(metadata, store_config).await.unwrap();
+    let tester = FileTesterBase::with_region(region);
+
+    tester.put(&[(1000, Some(100))]).await;
+    let ctx = FlushContext { wait: true };
+    tester.region.flush(&ctx).await.unwrap();
+    tester.put(&[(2000, Some(200))]).await;
+    tester.region.flush(&ctx).await.unwrap();
+
+    let files = tester
+        .region
+        .inner
+        .version_control()
+        .current()
+        .ssts()
+        .level(0)
+        .files()
+        .map(|f| format!("flush {} {}", tester.region.id(), f.file_id()))
+        .collect::<Vec<_>>();
+    assert_eq!(2, files.len());
+    for _ in 0..100 {
+        if listener.events.lock().unwrap().len() == files.len() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    let mut events = listener.events.lock().unwrap().clone();
+    events.sort();
+    let mut expect = files;
+    expect.sort();
+    assert_eq!(expect, events);
+}
+
+/// Flush scheduler that holds flush jobs until the gate is released.
+#[derive(Debug)]
+struct GatedFlushScheduler {
+    inner: FlushSchedulerRef,
+    gate: Arc<RwLock<()>>,
+}
+
+struct GatedJob {
+    job: Box<dyn Job>,
+    gate: Arc<RwLock<()>>,
+}
+
+#[async_trait]
+impl Job for GatedJob {
+    async fn run(&mut self, ctx: &Context) -> Result<()> {
+        // Waits until the gate is released.
+        drop(self.gate.read().await);
+        self.job.run(ctx).await
+    }
+}
+
+#[async_trait]
+impl FlushScheduler for GatedFlushScheduler {
+    async fn schedule_flush(&self, flush_job: Box<dyn Job>) -> Result<JobHandle> {
+        let job = GatedJob {
+            job: flush_job,
+            gate: self.gate.clone(),
+        };
+        self.inner.schedule_flush(Box::new(job)).await
+    }
+}
+
+#[tokio::test]
+async fn test_write_stall_on_slow_flush() {
+    common_telemetry::init_default_ut_logging();
+
+    let dir = create_temp_dir("write-stall");
+    let store_dir = dir.path().to_str().unwrap();
+
+    let flush_size = 8 * 1024;
+    let engine_config = EngineConfig {
+        memtable_flush_size: flush_size,
+        write_stall: WriteStallConfig {
+            soft_factor: 2,
+            hard_factor: 4,
+            initial_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(10),
+        },
+        ..Default::default()
+    };
+    let hard_limit = flush_size * 4;
+
+    // Holds all flushes until the gate is released.
+    let gate = Arc::new(RwLock::new(()));
+    let closed = gate.clone().write_owned().await;
+
+    let metadata = tests::new_metadata(REGION_NAME, false);
+    let mut store_config = config_util::new_store_config(REGION_NAME, store_dir).await;
+    store_config.flush_strategy = Arc::new(SizeBasedStrategy::new(flush_size));
+    store_config.flush_scheduler = Arc::new(GatedFlushScheduler {
+        inner: store_config.flush_scheduler.clone(),
+        gate: gate.clone(),
+    });
+    store_config.engine_config = Arc::new(engine_config);
+    let region = RegionImpl::create(metadata, store_config).await.unwrap();
+    let tester = FileTesterBase::with_region(region);
+    let region = &tester.region;
+
+    let batch =
+        |i: i64| -> Vec<(i64, Option<i64>)> { (0..10).map(|j| (i * 10 + j, Some(j))).collect() };
+    tester.put(&batch(0)).await;
+    let batch_bytes = region.memtable_usage_bytes() as usize;
+    assert!(batch_bytes > 0 && batch_bytes * 8 < flush_size);
+
+    // Burst of writes while the flush can't make progress.
+    let mut rejected = 0;
+    let mut max_bytes = 0;
+    for i in 1..500 {
+        match tester.try_put(&batch(i)).await {
+            Ok(_) => (),
+            Err(e) => {
+                assert!(matches!(e, Error::WriteStalled { .. }), "{e:?}");
+                assert!(e.status_code().is_retryable());
+                rejected += 1;
+            }
+        }
+        max_bytes = max_bytes.max(region.memtable_usage_bytes() as usize);
+    }
+    assert!(rejected > 0);
+    assert!(
+        max_bytes <= hard_limit + batch_bytes,
+        "max_bytes: {max_bytes}, hard_limit: {hard_limit}"
+    );
+    assert!(region.is_write_stalled());
+
+    // Releases the flush, writes recover once flushes drain the memtables.
+    drop(closed);
+    let mut recovered = false;
+    for i in 500..1000 {
+        let _ = tester.try_put(&batch(i)).await;
+        if !region.is_write_stalled() {
+            recovered = true;
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert!(recovered);
+    tester.put(&batch(1000)).await;
+    assert!(region.memtable_usage_bytes() as usize <= hard_limit);
+}
@@ -0,0 +1,226 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests of key ranges and row group statistics of SSTs.
+
+use std::sync::Arc;
+
+use common_query::logical_plan::Expr;
+use common_recordbatch::scan_stats::ScanStatsRecorder;
+use common_test_util::temp_dir::create_temp_dir;
+use datafusion_common::{Column, ScalarValue};
+use datafusion_expr::{BinaryExpr, Expr as DfExpr, Operator};
+use datatypes::prelude::ScalarVector;
+use datatypes::type_id::LogicalTypeId;
+use datatypes::value::Value;
+use datatypes::vectors::{Int64Vector, TimestampMillisecondVector, VectorRef};
+use log_store::raft_engine::log_store::RaftEngineLogStore;
+use store_api::storage::{
+    ChunkReader, FlushContext, OpenOptions, ReadContext, Region, ScanRequest, Snapshot,
+    WriteContext, WriteRequest,
+};
+
+use crate::compaction::CompactionOutput;
+use crate::manifest::action::RegionEdit;
+use crate::region::{RegionImpl, RegionMetadata};
+use crate::sst::{ColumnRange, ColumnStats, RowGroupStats};
+use crate::test_util::{self, config_util, descriptor_util, write_batch_util};
+
+const REGION_NAME: &str = "region-key-range-0";
+
+/// Creates a region with schema (k0, timestamp, v0).
+async fn new_region(store_dir: &str) -> RegionImpl<RaftEngineLogStore> {
+    let desc = descriptor_util::desc_with_value_columns(REGION_NAME, 1);
+    let metadata: RegionMetadata = desc.try_into().unwrap();
+    let store_config = config_util::new_store_config(REGION_NAME, store_dir).await;
+    RegionImpl::create(metadata, store_config).await.unwrap()
+}
+
+/// Puts rows with keys in `keys` and flushes them to a new SST.
+async fn put_and_flush(region: &RegionImpl<RaftEngineLogStore>, keys: &[i64]) {
+    let mut batch = write_batch_util::new_write_batch(
+        &[
+            ("k0", LogicalTypeId::Int64, false),
+            (
+                test_util::TIMESTAMP_NAME,
+                LogicalTypeId::TimestampMillisecond,
+                false,
+            ),
+            ("v0", LogicalTypeId::Int64, true),
+        ],
+        Some(1),
+        2,
+    );
+    let put_data = [
+        (
+            "k0".to_string(),
+            Arc::new(Int64Vector::from_slice(keys)) as VectorRef,
+        ),
+        (
+            test_util::TIMESTAMP_NAME.to_string(),
+            Arc::new(TimestampMillisecondVector::from_values(
+                keys.iter().map(|_| 1000),
+            )) as VectorRef,
+        ),
+        (
+            "v0".to_string(),
+            Arc::new(Int64Vector::from_slice(keys)) as VectorRef,
+        ),
+    ]
+    .into_iter()
+    .collect();
+    batch.put(put_data).unwrap();
+    region.write(&WriteContext::default(), batch).await.unwrap();
+    region.flush(&FlushContext::default()).await.unwrap();
+}
+
+fn k0_eq(key: i64) -> Expr {
+    Expr::from(DfExpr::BinaryExpr(BinaryExpr {
+        left: Box::new(DfExpr::Column(Column::from_name("k0"))),
+        op: Operator::Eq,
+        right: Box::new(DfExpr::Literal(ScalarValue::Int64(Some(key)))),
+    }))
+}
+
+#[tokio::test]
+async fn test_prune_sst_by_key_range() {
+    let dir = create_temp_dir("key-range-prune");
+    let store_dir = dir.path().to_str().unwrap();
+    let region = new_region(store_dir).await;
+
+    put_and_flush(&region, &[1, 2, 3]).await;
+    put_and_flush(&region, &[10, 20, 30]).await;
+
+    let files = region
+        .inner
+        .version_control()
+        .current()
+        .ssts()
+        .level(0)
+        .files()
+        .map(|file| file.key_range().to_vec())
+        .collect::<Vec<_>>();
+    assert_eq!(2, files.len());
+    for expect in [(1, 3), (10, 30)] {
+        assert!(files.contains(&vec![ColumnRange {
+            column: "k0".to_string(),
+            min: Value::Int64(expect.0),
+            max: Value::Int64(expect.1),
+        }]));
+    }
+
+    // Only the SST whose key range contains the key is scanned.
+    let scan_stats = Arc::new(ScanStatsRecorder::default());
+    let request = ScanRequest {
+        filters: vec![k0_eq(20)],
+        scan_stats: Some(scan_stats.clone()),
+        ..Default::default()
+    };
+    let read_ctx = ReadContext::default();
+    let snapshot = region.snapshot(&read_ctx).unwrap();
+    let mut reader = snapshot.scan(&read_ctx, request).await.unwrap().reader;
+    let mut keys = Vec::new();
+    while let Some(chunk) = reader.next_chunk().await.unwrap() {
+        let k0 = chunk.columns[0]
+            .as_any()
+            .downcast_ref::<Int64Vector>()
+            .unwrap();
+        keys.extend(k0.iter_data().flatten());
+    }
+    // Filters are not applied to rows exactly, so all rows in the scanned SST are returned.
+    assert_eq!(vec![10, 20, 30], keys);
+
+    let stats = scan_stats.stats();
+    assert_eq!(1, stats.files_scanned);
+    assert_eq!(1, stats.files_pruned);
+}
+
+#[tokio::test]
+async fn test_row_group_stats_of_compaction_output() {
+    let dir = create_temp_dir("row-group-stats");
+    let store_dir = dir.path().to_str().unwrap();
+    let region = new_region(store_dir).await;
+
+    put_and_flush(&region, &[3, 1, 2]).await;
+    put_and_flush(&region, &[30, 10, 20]).await;
+
+    let inner = &region.inner;
+    let version = inner.version_control().current();
+    let inputs: Vec<_> = version.ssts().level(0).files().cloned().collect();
+    assert_eq!(2, inputs.len());
+    let output = CompactionOutput {
+        output_level: 1,
+        bucket_bound: 0,
+        bucket: 10,
+        inputs: inputs.clone(),
+        priority: 2,
+    };
+    let meta = output
+        .build(
+            region.id(),
+            version.schema().clone(),
+            inner.sst_layer.clone(),
+            None,
+        )
+        .await
+        .unwrap();
+
+    let expect = vec![RowGroupStats {
+        num_rows: 6,
+        columns: vec![ColumnStats {
+            column: "k0".to_string(),
+            min: Value::Int64(1),
+            max: Value::Int64(30),
+            null_count: 0,
+        }],
+    }];
+    assert_eq!(expect, meta.row_groups);
+    assert_eq!(
+        vec![ColumnRange {
+            column: "k0".to_string(),
+            min: Value::Int64(1),
+            max: Value::Int64(30),
+        }],
+        meta.key_range
+    );
+
+    // Applies the compaction output and recovers it from the manifest.
+    let edit = RegionEdit {
+        region_version: version.metadata().version(),
+        flushed_sequence: None,
+        files_to_add: vec![meta],
+        files_to_remove: inputs.iter().map(|file| file.meta()).collect(),
+    };
+    inner
+        .writer
+        .write_edit_and_apply(&inner.wal, &inner.shared, &inner.manifest, edit, None)
+        .await
+        .unwrap();
+    region.close().await.unwrap();
+
+    let store_config = config_util::new_store_config(REGION_NAME, store_dir).await;
+    let region = RegionImpl::open(
+        REGION_NAME.to_string(),
+        store_config,
+        &OpenOptions::default(),
+    )
+    .await
+    .unwrap()
+    .unwrap();
+    let version = region.inner.version_control().current();
+    assert_eq!(0, version.ssts().level(0).file_num());
+    let files: Vec<_> = version.ssts().level(1).files().cloned().collect();
+    assert_eq!(1, files.len());
+    assert_eq!(expect, files[0].row_groups());
+}
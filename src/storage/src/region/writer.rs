@@ -13,23 +13,26 @@
 // limitations under the License.
 
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use common_error::prelude::BoxedError;
 use common_telemetry::tracing::log::info;
 use common_telemetry::{error, logging};
 use futures::TryStreamExt;
+use metrics::{counter, histogram};
 use snafu::{ensure, ResultExt};
 use store_api::logstore::LogStore;
 use store_api::manifest::{Manifest, ManifestVersion, MetaAction};
 use store_api::storage::{AlterRequest, FlushContext, SequenceNumber, WriteContext, WriteResponse};
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, Notify};
 
 use crate::background::JobHandle;
 use crate::compaction::{CompactionRequestImpl, CompactionSchedulerRef};
 use crate::config::EngineConfig;
 use crate::error::{self, Result};
-use crate::flush::{FlushCallback, FlushJob, FlushSchedulerRef, FlushStrategyRef};
+use crate::flush::{
+    FlushCallback, FlushJob, FlushSchedulerRef, FlushStrategyRef, MemtableBudgetRef,
+};
 use crate::manifest::action::{
     RawRegionMetadata, RegionChange, RegionEdit, RegionMetaAction, RegionMetaActionList,
 };
@@ -45,6 +48,11 @@ use crate::write_batch::WriteBatch;
 
 pub type RegionWriterRef = Arc<RegionWriter>;
 
+/// Time writes spent waiting for flushes to drain memtables, in seconds.
+pub const METRIC_WRITE_STALL_ELAPSED: &str = "storage.write.stall_elapsed";
+/// Writes rejected because there are too many unflushed bytes in the region.
+pub const METRIC_WRITE_STALL_REJECTED_TOTAL: &str = "storage.write.stall_rejected_total";
+
 // TODO(yingwen): Add benches for write and support group commit to improve write throughput.
 
 /// Region writer manages all write operations to the region.
@@ -58,20 +66,30 @@ pub struct RegionWriter {
     ///
     /// Increasing committed sequence should be guarded by this lock.
     version_mutex: Mutex<()>,
+    /// Throttles writes while flushes can't keep up with them.
+    stall: WriteStall,
 }
 
 impl RegionWriter {
-    pub fn new(
-        memtable_builder: MemtableBuilderRef,
-        config: Arc<EngineConfig>,
-        ttl: Option<Duration>,
-    ) -> RegionWriter {
+    pub fn new(memtable_builder: MemtableBuilderRef, config: Arc<EngineConfig>) -> RegionWriter {
+        let stall = WriteStall::new(&config);
         RegionWriter {
-            inner: Mutex::new(WriterInner::new(memtable_builder, config, ttl)),
+            inner: Mutex::new(WriterInner::new(memtable_builder, config)),
             version_mutex: Mutex::new(()),
+            stall,
         }
     }
 
+    /// Returns true if unflushed bytes of the region exceed the soft limit, so writes
+    /// are delayed or rejected.
+    pub fn is_write_stalled(&self, version_control: &VersionControlRef) -> bool {
+        let unflushed_bytes = version_control
+            .current()
+            .memtables()
+            .total_bytes_allocated();
+        unflushed_bytes > self.stall.soft_limit
+    }
+
     /// Write to region in the write lock.
     pub async fn write<S: LogStore>(
         &self,
@@ -84,7 +102,7 @@ impl RegionWriter {
         ensure!(!inner.is_closed(), error::ClosedRegionSnafu);
 
         inner
-            .write(&self.version_mutex, ctx, request, writer_ctx)
+            .write(&self.version_mutex, &self.stall, ctx, request, writer_ctx)
             .await
     }
 
@@ -114,6 +132,9 @@ impl RegionWriter {
         // write lock thus we have no chance to get the lock and apply the version edit.
         // So we add a version lock to ensure modification to `VersionControl` is
         // serialized.
+        // Rejects edits of a dropped region, e.g. from an in-flight compaction, so
+        // their output files won't be added to the version again.
+        ensure!(!shared.is_dropped(), error::ClosedRegionSnafu);
         let version_control = &shared.version_control;
         let prev_version = version_control.current_manifest_version();
 
@@ -143,6 +164,10 @@ impl RegionWriter {
         // We could tolerate failure during persisting manifest version to the WAL, since it won't
         // affect how we applying the edit to the version.
         version_control.apply_edit(version_edit);
+        if max_memtable_id.is_some() {
+            // Flushed memtables are removed from the version, wake up stalled writes.
+            self.stall.flushed.notify_waiters();
+        }
         // TODO(yingwen): We should set the flush handle to `None`, but we can't acquire
         // write lock here.
 
@@ -152,6 +177,53 @@ impl RegionWriter {
             .await
     }
 
+    /// Reserves a sequence for rows not written to the WAL, e.g. rows of a bulk load.
+    ///
+    /// The sequence is persisted to the WAL without payload, so sequences allocated after
+    /// reopening the region are still greater than it.
+    pub(crate) async fn reserve_sequence<S: LogStore>(
+        &self,
+        wal: &Wal<S>,
+        shared: &SharedDataRef,
+    ) -> Result<SequenceNumber> {
+        let inner = self.inner.lock().await;
+        ensure!(!inner.is_closed(), error::ClosedRegionSnafu);
+
+        let _lock = self.version_mutex.lock().await;
+        ensure!(!shared.is_dropped(), error::ClosedRegionSnafu);
+        let version_control = &shared.version_control;
+        let manifest_version = version_control.current_manifest_version();
+        self.persist_manifest_version(wal, version_control, manifest_version)
+            .await?;
+
+        Ok(version_control.committed_sequence())
+    }
+
+    /// Marks the region as dropped and removes all SST files from its version, so
+    /// they are purged once they are no longer referenced.
+    ///
+    /// Edits written after this call are rejected.
+    pub(crate) async fn mark_dropped(&self, shared: &SharedDataRef) {
+        let _lock = self.version_mutex.lock().await;
+        shared.mark_dropped();
+
+        let version_control = &shared.version_control;
+        let files_to_remove = version_control
+            .current()
+            .ssts()
+            .levels()
+            .iter()
+            .flat_map(|level| level.files().map(|file| file.meta()))
+            .collect();
+        version_control.apply_edit(VersionEdit {
+            files_to_add: Vec::new(),
+            files_to_remove,
+            flushed_sequence: None,
+            manifest_version: version_control.current_manifest_version(),
+            max_memtable_id: None,
+        });
+    }
+
     /// Alter schema of the region.
     pub async fn alter<S: LogStore>(
         &self,
@@ -305,6 +377,7 @@ pub struct WriterContext<'a, S: LogStore> {
     pub wal: &'a Wal<S>,
     pub writer: &'a RegionWriterRef,
     pub manifest: &'a RegionManifest,
+    pub memtable_budget: Option<&'a MemtableBudgetRef>,
 }
 
 impl<'a, S: LogStore> WriterContext<'a, S> {
@@ -327,31 +400,54 @@ impl<'a, S: LogStore> AlterContext<'a, S> {
     }
 }
 
+/// Limits of unflushed bytes of a region, derived from the memtable flush size.
+#[derive(Debug)]
+struct WriteStall {
+    /// Writes are delayed once unflushed bytes exceed this limit.
+    soft_limit: usize,
+    /// Writes are rejected once unflushed bytes exceed this limit.
+    hard_limit: usize,
+    initial_delay: Duration,
+    max_delay: Duration,
+    /// Notified once a flush is applied to the version.
+    flushed: Notify,
+}
+
+impl WriteStall {
+    fn new(config: &EngineConfig) -> WriteStall {
+        let stall = &config.write_stall;
+        WriteStall {
+            soft_limit: config.memtable_flush_size * stall.soft_factor,
+            hard_limit: config.memtable_flush_size * stall.hard_factor,
+            initial_delay: stall.initial_delay,
+            max_delay: stall.max_delay,
+            flushed: Notify::new(),
+        }
+    }
+}
+
 #[derive(Debug)]
 struct WriterInner {
     memtable_builder: MemtableBuilderRef,
     flush_handle: Option<JobHandle>,
+    /// Delay of the next stalled write.
+    stall_delay: Duration,
 
     /// `WriterInner` will reject any future writing, if the closed flag is set.
     ///
     /// It should protected by upper mutex
     closed: bool,
     engine_config: Arc<EngineConfig>,
-    ttl: Option<Duration>,
 }
 
 impl WriterInner {
-    fn new(
-        memtable_builder: MemtableBuilderRef,
-        engine_config: Arc<EngineConfig>,
-        ttl: Option<Duration>,
-    ) -> WriterInner {
+    fn new(memtable_builder: MemtableBuilderRef, engine_config: Arc<EngineConfig>) -> WriterInner {
         WriterInner {
             memtable_builder,
             flush_handle: None,
+            stall_delay: engine_config.write_stall.initial_delay,
             engine_config,
             closed: false,
-            ttl,
         }
     }
 
@@ -362,11 +458,13 @@ impl WriterInner {
     async fn write<S: LogStore>(
         &mut self,
         version_mutex: &Mutex<()>,
+        stall: &WriteStall,
         _ctx: &WriteContext,
         mut request: WriteBatch,
         writer_ctx: WriterContext<'_, S>,
     ) -> Result<WriteResponse> {
         self.preprocess_write(&writer_ctx).await?;
+        self.stall_write(stall, &writer_ctx).await?;
         let version_control = writer_ctx.version_control();
 
         let _lock = version_mutex.lock().await;
@@ -470,6 +568,10 @@ impl WriterInner {
                     // out of memory during replay, but we need to do it carefully to avoid dead lock.
                     let mut inserter = Inserter::new(last_sequence);
                     inserter.insert_memtable(&payload, version.mutable_memtable())?;
+                } else if req_sequence > last_sequence {
+                    // Entries without payload, e.g. sequences reserved by bulk loads, still
+                    // advance the sequence, so sequences allocated after replay are greater.
+                    last_sequence = req_sequence;
                 }
             }
 
@@ -561,12 +663,80 @@ impl WriterInner {
             version_control,
             writer_ctx.flush_strategy,
         ) {
-            self.trigger_flush(writer_ctx).await?;
+            // Waiting for the running flush would block all writes of the region with the
+            // write lock held, so we keep writing to the mutable memtable and let the write
+            // stall bound its size. The next write after the flush triggers another one.
+            if !writer_ctx.shared.is_flushing() {
+                self.trigger_flush(writer_ctx).await?;
+            }
+        } else if let Some(budget) = writer_ctx.memtable_budget {
+            // Memtables of all regions exceed the budget, flush the largest one. We only
+            // hold the write lock of current region, so flush of other regions must be
+            // scheduled in background to avoid deadlock.
+            if let Some(region) = budget.pick_region_to_flush() {
+                if region.id() == writer_ctx.shared.id() {
+                    self.trigger_flush(writer_ctx).await?;
+                } else {
+                    region.schedule_flush();
+                }
+            }
         }
 
         Ok(())
     }
 
+    /// Delays the write if the region has more unflushed bytes than the soft limit, until
+    /// a flush completes or the delay elapses. The delay doubles for each stalled write.
+    ///
+    /// Rejects the write with a retryable error once the hard limit is exceeded.
+    async fn stall_write<S: LogStore>(
+        &mut self,
+        stall: &WriteStall,
+        writer_ctx: &WriterContext<'_, S>,
+    ) -> Result<()> {
+        // Registers the waiter before checking the size, so a flush completed in between
+        // still wakes us up.
+        let flushed = stall.flushed.notified();
+        tokio::pin!(flushed);
+        flushed.as_mut().enable();
+
+        let unflushed_bytes = writer_ctx
+            .version_control()
+            .current()
+            .memtables()
+            .total_bytes_allocated();
+        if unflushed_bytes <= stall.soft_limit {
+            self.stall_delay = stall.initial_delay;
+            return Ok(());
+        }
+
+        if unflushed_bytes > stall.hard_limit {
+            counter!(METRIC_WRITE_STALL_REJECTED_TOTAL, 1);
+            return error::WriteStalledSnafu {
+                region: writer_ctx.shared.name(),
+                unflushed_bytes,
+                limit: stall.hard_limit,
+            }
+            .fail();
+        }
+
+        let delay = self.stall_delay;
+        self.stall_delay = (delay * 2).min(stall.max_delay);
+        logging::debug!(
+            "Stall write of region {} for at most {:?}, unflushed bytes: {}, soft limit: {}",
+            writer_ctx.shared.name(),
+            delay,
+            unflushed_bytes,
+            stall.soft_limit
+        );
+
+        let start = Instant::now();
+        let _ = tokio::time::timeout(delay, flushed).await;
+        histogram!(METRIC_WRITE_STALL_ELAPSED, start.elapsed().as_secs_f64());
+
+        Ok(())
+    }
+
     /// Create a new mutable memtable.
     fn alloc_memtable(&self, version_control: &VersionControlRef) -> MemtableRef {
         let memtable_schema = version_control.current().schema().clone();
@@ -615,7 +785,7 @@ impl WriterInner {
             return Ok(());
         }
 
-        let cb = Self::build_flush_callback(&current_version, ctx, &self.engine_config, self.ttl);
+        let cb = Self::build_flush_callback(&current_version, ctx, &self.engine_config);
 
         let flush_req = FlushJob {
             max_memtable_id: max_memtable_id.unwrap(),
@@ -628,6 +798,7 @@ impl WriterInner {
             wal: ctx.wal.clone(),
             manifest: ctx.manifest.clone(),
             on_success: cb,
+            flushing_guard: ctx.shared.start_flushing(),
         };
 
         let flush_handle = ctx
@@ -643,7 +814,6 @@ impl WriterInner {
         version: &VersionRef,
         ctx: &WriterContext<S>,
         config: &Arc<EngineConfig>,
-        ttl: Option<Duration>,
     ) -> Option<FlushCallback> {
         let region_id = version.metadata().id();
         let compaction_request = CompactionRequestImpl {
@@ -653,12 +823,18 @@ impl WriterInner {
             shared: ctx.shared.clone(),
             manifest: ctx.manifest.clone(),
             wal: ctx.wal.clone(),
-            ttl,
+            // Always use the latest ttl of the region, which might be altered at runtime.
+            ttl: ctx.shared.ttl(),
         };
         let compaction_scheduler = ctx.compaction_scheduler.clone();
         let shared_data = ctx.shared.clone();
-        let max_files_in_l0 = config.max_files_in_l0;
+        let default_max_files_in_l0 = config.max_files_in_l0;
         let schedule_compaction_cb = Box::pin(async move {
+            // Checks the latest options of the region as they might be altered at runtime.
+            let max_files_in_l0 = shared_data
+                .compaction_options()
+                .max_files_in_l0
+                .unwrap_or(default_max_files_in_l0);
             let level0_file_num = shared_data
                 .version_control
                 .current()
@@ -14,7 +14,7 @@
 
 use std::fmt::{Debug, Formatter};
 use std::hash::Hash;
-use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::atomic::{AtomicU8, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex, RwLock};
 
 use async_trait::async_trait;
@@ -30,9 +30,11 @@ use crate::scheduler::dedup_deque::DedupDeque;
 use crate::scheduler::rate_limit::{
     BoxedRateLimitToken, CascadeRateLimiter, MaxInflightTaskLimiter, RateLimiter,
 };
+use crate::scheduler::window::MaintenanceWindow;
 
 pub mod dedup_deque;
 pub mod rate_limit;
+pub mod window;
 
 /// Request that can be scheduled.
 /// It must contain a key for deduplication.
@@ -41,6 +43,11 @@ pub trait Request: Send + Sync + 'static {
     type Key: Eq + Hash + Clone + Debug + Send + Sync;
 
     fn key(&self) -> Self::Key;
+
+    /// Priority of the request under [SchedulePolicy::Aging], the higher the earlier.
+    fn priority(&self) -> usize {
+        0
+    }
 }
 
 #[async_trait::async_trait]
@@ -68,18 +75,57 @@ pub trait Scheduler: Debug {
     /// Stops scheduler. If `await_termination` is set to true, the scheduler will
     /// wait until all queued requests are processed.
     async fn stop(&self, await_termination: bool) -> error::Result<()>;
+
+    /// Returns the max number of requests handled concurrently.
+    fn max_inflight_tasks(&self) -> usize;
+
+    /// Updates the max number of requests handled concurrently. Running requests are not
+    /// affected, the new limit applies to requests scheduled afterwards.
+    fn set_max_inflight_tasks(&self, max_inflight_tasks: usize);
+
+    /// Returns the status of queued requests.
+    fn status(&self) -> SchedulerStatus;
+}
+
+/// Status of queued requests of a scheduler.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SchedulerStatus {
+    /// Number of requests waiting in the queue.
+    pub queued: usize,
+    /// Number of queued requests waiting for the maintenance window to open.
+    pub deferred: usize,
+    /// Whether the maintenance window is open, [None] if there is no window.
+    pub window_open: Option<bool>,
+}
+
+/// How the scheduler picks the next request from the queued ones.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SchedulePolicy {
+    /// Picks requests in the order they are queued. As a key is queued at most once, keys
+    /// are served in turn.
+    #[default]
+    RoundRobin,
+    /// Picks the request with the max [Request::priority] plus `step` for each other request
+    /// scheduled while it waits, so requests with low priority are not starved.
+    Aging { step: usize },
 }
 
 /// Scheduler config.
 #[derive(Debug)]
 pub struct SchedulerConfig {
     pub max_inflight_tasks: usize,
+    pub policy: SchedulePolicy,
+    /// Requests that aren't urgent are only handled in this window if set. Requests
+    /// handled when the window closes still run to completion.
+    pub window: Option<MaintenanceWindow>,
 }
 
 impl Default for SchedulerConfig {
     fn default() -> Self {
         Self {
             max_inflight_tasks: 4,
+            policy: SchedulePolicy::default(),
+            window: None,
         }
     }
 }
@@ -100,6 +146,9 @@ pub struct LocalScheduler<R: Request> {
     join_handle: Mutex<Option<JoinHandle<()>>>,
     /// State of scheduler.
     state: Arc<AtomicU8>,
+    /// Max number of inflight tasks, shared with the rate limiter of the handler loop.
+    max_inflight_tasks: Arc<AtomicUsize>,
+    window: Option<MaintenanceWindow>,
 }
 
 impl<R> Debug for LocalScheduler<R>
@@ -146,6 +195,34 @@ where
         }
         Ok(())
     }
+
+    fn max_inflight_tasks(&self) -> usize {
+        self.max_inflight_tasks.load(Ordering::Relaxed)
+    }
+
+    fn set_max_inflight_tasks(&self, max_inflight_tasks: usize) {
+        self.max_inflight_tasks
+            .store(max_inflight_tasks, Ordering::Relaxed);
+        // Requests pending on the old limit could be handled now.
+        self.task_notifier.notify_one();
+    }
+
+    fn status(&self) -> SchedulerStatus {
+        let queue = self.request_queue.read().unwrap();
+        let window_open = self.window.as_ref().map(|window| window.is_open());
+        let deferred = match &self.window {
+            Some(window) if window_open == Some(false) => queue
+                .values()
+                .filter(|req| !window.is_urgent(req.priority()))
+                .count(),
+            _ => 0,
+        };
+        SchedulerStatus {
+            queued: queue.len(),
+            deferred,
+            window_open,
+        }
+    }
 }
 
 impl<R> LocalScheduler<R>
@@ -161,15 +238,18 @@ where
         let cancel_token = CancellationToken::new();
         let task_notifier = Arc::new(Notify::new());
         let state = Arc::new(AtomicU8::new(STATE_RUNNING));
+        let max_inflight_tasks = Arc::new(AtomicUsize::new(config.max_inflight_tasks));
         let handle_loop = HandlerLoop {
             task_notifier: task_notifier.clone(),
             req_queue: request_queue.clone(),
             cancel_token: cancel_token.child_token(),
             limiter: Arc::new(CascadeRateLimiter::new(vec![Box::new(
-                MaxInflightTaskLimiter::new(config.max_inflight_tasks),
+                MaxInflightTaskLimiter::with_shared_max(max_inflight_tasks.clone()),
             )])),
             request_handler: handler,
             state: state.clone(),
+            policy: config.policy,
+            window: config.window.clone(),
         };
         let join_handle = common_runtime::spawn_bg(async move {
             debug!("Task handler loop spawned");
@@ -181,6 +261,8 @@ where
             cancel_token,
             task_notifier,
             state,
+            max_inflight_tasks,
+            window: config.window,
         }
     }
 
@@ -203,6 +285,8 @@ pub struct HandlerLoop<R: Request, H: Handler> {
     pub request_handler: H,
     pub limiter: Arc<CascadeRateLimiter<R>>,
     pub state: Arc<AtomicU8>,
+    pub policy: SchedulePolicy,
+    pub window: Option<MaintenanceWindow>,
 }
 
 impl<R, H> HandlerLoop<R, H>
@@ -219,6 +303,10 @@ where
                     debug!("Notified, queue size: {:?}",self.req_queue.read().unwrap().len());
                     self.poll_and_execute(&limiter).await;
                 }
+                _ = self.wait_window_change() => {
+                    debug!("Window changed, queue size: {:?}", self.req_queue.read().unwrap().len());
+                    self.poll_and_execute(&limiter).await;
+                }
                 _ = self.cancel_token.cancelled() => {
                     info!("Task scheduler cancelled.");
                     break;
@@ -236,41 +324,78 @@ where
 
     /// Polls and executes requests as many as possible until rate limited.
     async fn poll_and_execute(&self, limiter: &Arc<CascadeRateLimiter<R>>) {
-        while let Some((task_key, req)) = self.poll_task().await {
-            if let Ok(token) = limiter.acquire_token(&req) {
-                debug!("Executing request: {:?}", task_key);
-                if let Err(e) = self
-                    .handle_request(req, token, self.task_notifier.clone())
-                    .await
-                {
-                    error!(e; "Failed to submit request: {:?}", task_key);
-                } else {
-                    info!("Submitted task: {:?}", task_key);
-                }
+        while let Some((task_key, req, token)) = self.poll_task(limiter) {
+            debug!("Executing request: {:?}", task_key);
+            if let Err(e) = self
+                .handle_request(req, token, self.task_notifier.clone())
+                .await
+            {
+                error!(e; "Failed to submit request: {:?}", task_key);
             } else {
-                // rate limited, put back to req queue to wait for next schedule
-                debug!(
-                    "Put back request {:?}, queue size: {}",
-                    task_key,
-                    self.req_queue.read().unwrap().len()
-                );
-                self.put_back_req(task_key, req).await;
-                break;
+                info!("Submitted task: {:?}", task_key);
             }
         }
     }
 
-    #[inline]
-    async fn poll_task(&self) -> Option<(R::Key, R)> {
+    /// Takes the next request to execute by the policy from the queue with a token acquired.
+    /// Returns [None] if the queue is empty or the request is rate limited, in which case it
+    /// stays in the queue to wait for next schedule.
+    fn poll_task(
+        &self,
+        limiter: &Arc<CascadeRateLimiter<R>>,
+    ) -> Option<(R::Key, R, BoxedRateLimitToken)> {
         let mut queue = self.req_queue.write().unwrap();
-        queue.pop_front()
+        // Only urgent requests are runnable while the window is closed.
+        let closed_window = self.window.as_ref().filter(|window| !window.is_open());
+        let runnable = |req: &R| {
+            closed_window
+                .map(|window| window.is_urgent(req.priority()))
+                .unwrap_or(true)
+        };
+        // Runnable requests are preferred, ties are broken as the policy does.
+        let key = match self.policy {
+            SchedulePolicy::RoundRobin => queue.max_key_by(|req, _| runnable(req)),
+            SchedulePolicy::Aging { step } => queue.max_key_by(|req, waited| {
+                (
+                    runnable(req),
+                    req.priority().saturating_add(waited.saturating_mul(step)),
+                )
+            }),
+        }?
+        .clone();
+        if !runnable(queue.get(&key)?) {
+            debug!(
+                "Request {:?} is deferred to the maintenance window, queue size: {}",
+                key,
+                queue.len()
+            );
+            return None;
+        }
+        let token = match limiter.acquire_token(queue.get(&key)?) {
+            Ok(token) => token,
+            Err(_) => {
+                debug!(
+                    "Request {:?} is rate limited, queue size: {}",
+                    key,
+                    queue.len()
+                );
+                return None;
+            }
+        };
+        let req = queue.remove(&key)?;
+        Some((key, req, token))
     }
 
-    /// Puts request back to the front of request queue.
-    #[inline]
-    async fn put_back_req(&self, key: R::Key, req: R) {
-        let mut queue = self.req_queue.write().unwrap();
-        queue.push_front(key, req);
+    /// Waits until the maintenance window opens or closes, never returns if there is no
+    /// window.
+    async fn wait_window_change(&self) {
+        match &self.window {
+            Some(window) => {
+                let now = chrono::Local::now();
+                tokio::time::sleep(window.until_next_change(&now)).await
+            }
+            None => futures::future::pending().await,
+        }
     }
 
     // Handles request, submit task to bg runtime.
@@ -361,6 +486,8 @@ mod tests {
                 MaxInflightTaskLimiter::new(3),
             )])),
             state: Arc::new(AtomicU8::default()),
+            policy: SchedulePolicy::default(),
+            window: None,
         });
 
         let handler_cloned = handler.clone();
@@ -379,6 +506,7 @@ mod tests {
     #[derive(Default, Debug)]
     struct MockRequest {
         region_id: RegionId,
+        priority: usize,
     }
 
     struct MockHandler<F> {
@@ -411,6 +539,10 @@ mod tests {
         fn key(&self) -> Self::Key {
             self.region_id
         }
+
+        fn priority(&self) -> usize {
+            self.priority
+        }
     }
 
     #[tokio::test]
@@ -426,13 +558,24 @@ mod tests {
         let scheduler: LocalScheduler<MockRequest> = LocalScheduler::new(
             SchedulerConfig {
                 max_inflight_tasks: 3,
+                ..Default::default()
             },
             handler,
         );
 
-        scheduler.schedule(MockRequest { region_id: 1 }).unwrap();
+        scheduler
+            .schedule(MockRequest {
+                region_id: 1,
+                ..Default::default()
+            })
+            .unwrap();
 
-        scheduler.schedule(MockRequest { region_id: 2 }).unwrap();
+        scheduler
+            .schedule(MockRequest {
+                region_id: 2,
+                ..Default::default()
+            })
+            .unwrap();
 
         tokio::time::timeout(Duration::from_secs(1), latch.wait())
             .await
@@ -455,6 +598,7 @@ mod tests {
 
         let config = SchedulerConfig {
             max_inflight_tasks: 3,
+            ..Default::default()
         };
         let scheduler = LocalScheduler::new(config, handler);
 
@@ -462,6 +606,7 @@ mod tests {
             scheduler
                 .schedule(MockRequest {
                     region_id: i as RegionId,
+                    ..Default::default()
                 })
                 .unwrap();
         }
@@ -486,6 +631,7 @@ mod tests {
 
         let config = SchedulerConfig {
             max_inflight_tasks: 3,
+            ..Default::default()
         };
         let scheduler = LocalScheduler::new(config, handler);
 
@@ -493,6 +639,7 @@ mod tests {
             scheduler
                 .schedule(MockRequest {
                     region_id: i as RegionId,
+                    ..Default::default()
                 })
                 .unwrap();
         }
@@ -502,6 +649,7 @@ mod tests {
             scheduler
                 .schedule(MockRequest {
                     region_id: i as RegionId,
+                    ..Default::default()
                 })
                 .unwrap();
         }
@@ -517,12 +665,19 @@ mod tests {
         let handler = MockHandler { cb: || {} };
         let config = SchedulerConfig {
             max_inflight_tasks: 30,
+            ..Default::default()
         };
         let scheduler = LocalScheduler::new(config, handler);
 
         let mut scheduled_task = 0;
         for _ in 0..10 {
-            if scheduler.schedule(MockRequest { region_id: 1 }).unwrap() {
+            if scheduler
+                .schedule(MockRequest {
+                    region_id: 1,
+                    ..Default::default()
+                })
+                .unwrap()
+            {
                 scheduled_task += 1;
             }
         }
@@ -545,6 +700,7 @@ mod tests {
 
         let config = SchedulerConfig {
             max_inflight_tasks: 3,
+            ..Default::default()
         };
         let scheduler = Arc::new(LocalScheduler::new(config, handler));
         let scheduler_cloned = scheduler.clone();
@@ -555,6 +711,7 @@ mod tests {
             for i in 0..10000 {
                 if let Ok(res) = scheduler_cloned.schedule(MockRequest {
                     region_id: i as RegionId,
+                    ..Default::default()
                 }) {
                     if res {
                         task_scheduled_cloned.fetch_add(1, Ordering::Relaxed);
@@ -568,4 +725,154 @@ mod tests {
         let finished = finished.load(Ordering::Relaxed);
         assert_eq!(finished, task_scheduled.load(Ordering::Relaxed));
     }
+
+    /// Schedules regions with one slot, each region is queued again once scheduled as if it
+    /// always has files to compact. Returns how many times each region is scheduled.
+    fn schedule_regions(policy: SchedulePolicy, rounds: usize) -> Vec<usize> {
+        let regions = 4;
+        let handler_loop = HandlerLoop {
+            req_queue: Arc::new(RwLock::new(DedupDeque::default())),
+            cancel_token: Default::default(),
+            task_notifier: Arc::new(Default::default()),
+            request_handler: MockHandler { cb: || {} },
+            limiter: Arc::new(CascadeRateLimiter::new(vec![Box::new(
+                MaxInflightTaskLimiter::new(1),
+            )])),
+            state: Arc::new(AtomicU8::default()),
+            policy,
+            window: None,
+        };
+        // region i has i + 1 files in level 0
+        let request = |i: usize| MockRequest {
+            region_id: i as RegionId,
+            priority: i + 1,
+        };
+        for i in 0..regions {
+            let req = request(i);
+            handler_loop
+                .req_queue
+                .write()
+                .unwrap()
+                .push_back(req.key(), req);
+        }
+
+        let mut scheduled = vec![0; regions];
+        for _ in 0..rounds {
+            let (key, _, token) = handler_loop.poll_task(&handler_loop.limiter).unwrap();
+            // the only slot is taken
+            assert!(handler_loop.poll_task(&handler_loop.limiter).is_none());
+            token.try_release();
+
+            scheduled[key as usize] += 1;
+            let req = request(key as usize);
+            handler_loop
+                .req_queue
+                .write()
+                .unwrap()
+                .push_back(req.key(), req);
+        }
+        scheduled
+    }
+
+    #[test]
+    fn test_schedule_fairness() {
+        // without aging, the region with most files takes the slot forever
+        assert_eq!(
+            vec![0, 0, 0, 20],
+            schedule_regions(SchedulePolicy::Aging { step: 0 }, 20)
+        );
+        assert_eq!(
+            vec![5, 5, 5, 5],
+            schedule_regions(SchedulePolicy::RoundRobin, 20)
+        );
+        let scheduled = schedule_regions(SchedulePolicy::Aging { step: 1 }, 20);
+        assert!(scheduled.iter().all(|n| *n > 0), "{scheduled:?}");
+        // regions with more files are still preferred
+        assert!(scheduled[3] > scheduled[0], "{scheduled:?}");
+    }
+
+    /// Returns a window open now if `open` is true, otherwise closed now.
+    fn window_around_now(open: bool, urgent_priority: usize) -> MaintenanceWindow {
+        let now = chrono::Local::now().time();
+        let (start, end) = if open {
+            (now - chrono::Duration::hours(1), now + chrono::Duration::hours(1))
+        } else {
+            (now + chrono::Duration::hours(1), now + chrono::Duration::hours(2))
+        };
+        MaintenanceWindow::new(start, end, urgent_priority)
+    }
+
+    #[test]
+    fn test_poll_task_with_window() {
+        let handler_loop = |window| HandlerLoop {
+            req_queue: Arc::new(RwLock::new(DedupDeque::default())),
+            cancel_token: Default::default(),
+            task_notifier: Arc::new(Default::default()),
+            request_handler: MockHandler { cb: || {} },
+            limiter: Arc::new(CascadeRateLimiter::new(vec![Box::new(
+                MaxInflightTaskLimiter::new(4),
+            )])),
+            state: Arc::new(AtomicU8::default()),
+            policy: SchedulePolicy::RoundRobin,
+            window: Some(window),
+        };
+        let push = |handler_loop: &HandlerLoop<MockRequest, _>, region_id, priority| {
+            handler_loop.req_queue.write().unwrap().push_back(
+                region_id,
+                MockRequest {
+                    region_id,
+                    priority,
+                },
+            );
+        };
+
+        // Only urgent requests run outside the window.
+        let closed = handler_loop(window_around_now(false, 10));
+        push(&closed, 1, 5);
+        push(&closed, 2, 11);
+        push(&closed, 3, 8);
+        let (key, _, _token) = closed.poll_task(&closed.limiter).unwrap();
+        assert_eq!(2, key);
+        assert!(closed.poll_task(&closed.limiter).is_none());
+        assert_eq!(2, closed.req_queue.read().unwrap().len());
+
+        // All requests run in order in the window.
+        let open = handler_loop(window_around_now(true, 10));
+        push(&open, 1, 5);
+        push(&open, 2, 11);
+        push(&open, 3, 8);
+        let keys: Vec<_> = std::iter::from_fn(|| open.poll_task(&open.limiter))
+            .map(|(key, _, _)| key)
+            .collect();
+        assert_eq!(vec![1, 2, 3], keys);
+    }
+
+    #[tokio::test]
+    async fn test_scheduler_status() {
+        let scheduler: LocalScheduler<MockRequest> = LocalScheduler::new(
+            SchedulerConfig {
+                max_inflight_tasks: 1,
+                window: Some(window_around_now(false, 10)),
+                ..Default::default()
+            },
+            MockHandler { cb: || {} },
+        );
+        for (region_id, priority) in [(1, 5), (2, 6)] {
+            scheduler
+                .schedule(MockRequest {
+                    region_id,
+                    priority,
+                })
+                .unwrap();
+        }
+        assert_eq!(
+            SchedulerStatus {
+                queued: 2,
+                deferred: 2,
+                window_open: Some(false),
+            },
+            scheduler.status()
+        );
+        scheduler.stop(false).await.unwrap();
+    }
 }
@@ -18,9 +18,12 @@ use std::fmt::{Debug, Formatter};
 use std::hash::Hash;
 
 /// Deque with key deduplication.
+///
+/// Each value also counts the values removed from the deque while it waits, see
+/// [DedupDeque::max_key_by].
 pub struct DedupDeque<K, V> {
     deque: VecDeque<K>,
-    existing: HashMap<K, V>,
+    existing: HashMap<K, (V, usize)>,
 }
 
 impl<K, V> Default for DedupDeque<K, V> {
@@ -39,7 +42,7 @@ impl<K: Eq + Hash + Clone, V> DedupDeque<K, V> {
     pub fn push_back(&mut self, key: K, value: V) -> bool {
         debug_assert_eq!(self.deque.len(), self.existing.len());
         if let Entry::Vacant(entry) = self.existing.entry(key.clone()) {
-            entry.insert(value);
+            entry.insert((value, 0));
             self.deque.push_back(key);
             return true;
         }
@@ -51,7 +54,7 @@ impl<K: Eq + Hash + Clone, V> DedupDeque<K, V> {
     /// returns false.
     pub fn push_front(&mut self, key: K, value: V) -> bool {
         if let Entry::Vacant(entry) = self.existing.entry(key.clone()) {
-            entry.insert(value);
+            entry.insert((value, 0));
             self.deque.push_front(key);
             return true;
         }
@@ -61,11 +64,50 @@ impl<K: Eq + Hash + Clone, V> DedupDeque<K, V> {
     /// Pops a pair from the back of deque. Returns [None] if the deque is empty.
     pub fn pop_front(&mut self) -> Option<(K, V)> {
         debug_assert_eq!(self.deque.len(), self.existing.len());
-        let key = self.deque.pop_front()?;
-        let value = self.existing.remove(&key)?;
+        let key = self.deque.front()?.clone();
+        let value = self.remove(&key)?;
         Some((key, value))
     }
 
+    /// Returns the key at the front of deque.
+    pub fn front_key(&self) -> Option<&K> {
+        self.deque.front()
+    }
+
+    /// Returns the key whose value has the max `f(value, waited)`, where `waited` is the
+    /// number of values removed since the value was pushed. The key closest to the front
+    /// wins ties.
+    pub fn max_key_by<P: Ord>(&self, mut f: impl FnMut(&V, usize) -> P) -> Option<&K> {
+        let mut max: Option<(&K, P)> = None;
+        for key in &self.deque {
+            let (value, waited) = &self.existing[key];
+            let p = f(value, *waited);
+            if max.as_ref().map(|(_, max_p)| p > *max_p).unwrap_or(true) {
+                max = Some((key, p));
+            }
+        }
+        max.map(|(key, _)| key)
+    }
+
+    /// Returns an iterator over the values in no particular order.
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.existing.values().map(|(value, _)| value)
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.existing.get(key).map(|(value, _)| value)
+    }
+
+    /// Removes the value of `key`, returns [None] if the deque does not contain the key.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let (value, _) = self.existing.remove(key)?;
+        self.deque.retain(|k| k != key);
+        for (_, waited) in self.existing.values_mut() {
+            *waited += 1;
+        }
+        Some(value)
+    }
+
     #[inline]
     pub fn len(&self) -> usize {
         debug_assert_eq!(self.deque.len(), self.existing.len());
@@ -112,4 +154,28 @@ mod tests {
         assert!(!deque.push_back(1, "world".to_string()));
         assert_eq!((1, "hello".to_string()), deque.pop_front().unwrap());
     }
+
+    #[test]
+    fn test_dedup_deque_max_key_by() {
+        let mut deque = DedupDeque::default();
+        assert!(deque.max_key_by(|v: &usize, _| *v).is_none());
+
+        assert!(deque.push_back(1, 1));
+        assert!(deque.push_back(2, 3));
+        assert!(deque.push_back(3, 3));
+        assert!(deque.push_back(4, 2));
+        assert_eq!(Some(&1), deque.front_key());
+        // the front one wins ties
+        assert_eq!(Some(&2), deque.max_key_by(|v, _| *v));
+        assert_eq!(Some(&3), deque.get(&2));
+
+        assert_eq!(Some(3), deque.remove(&2));
+        assert_eq!(None, deque.remove(&2));
+        assert_eq!(Some(1), deque.pop_front().map(|(_, v)| v));
+        assert!(deque.push_back(5, 4));
+        assert_eq!(Some(&5), deque.max_key_by(|v, _| *v));
+        // 3 has waited for 2 removals
+        assert_eq!(Some(&3), deque.max_key_by(|v, waited| *v + waited));
+        assert_eq!(3, deque.len());
+    }
 }
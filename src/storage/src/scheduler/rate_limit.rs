@@ -45,13 +45,19 @@ pub type BoxedRateLimiter<R> = Box<dyn RateLimiter<Request = R> + Send + Sync>;
 
 /// Limits max inflight tasks number.
 pub struct MaxInflightTaskLimiter<R> {
-    max_inflight_tasks: usize,
+    max_inflight_tasks: Arc<AtomicUsize>,
     inflight_tasks: Arc<AtomicUsize>,
     _phantom_data: PhantomData<R>,
 }
 
 impl<R> MaxInflightTaskLimiter<R> {
     pub fn new(max_inflight_tasks: usize) -> Self {
+        Self::with_shared_max(Arc::new(AtomicUsize::new(max_inflight_tasks)))
+    }
+
+    /// Creates a limiter whose max inflight task num could be updated at runtime via
+    /// `max_inflight_tasks`, tokens already acquired are not affected.
+    pub fn with_shared_max(max_inflight_tasks: Arc<AtomicUsize>) -> Self {
         Self {
             max_inflight_tasks,
             inflight_tasks: Arc::new(AtomicUsize::new(0)),
@@ -64,13 +70,14 @@ impl<R> RateLimiter for MaxInflightTaskLimiter<R> {
     type Request = R;
 
     fn acquire_token(&self, _: &Self::Request) -> Result<BoxedRateLimitToken> {
-        if self.inflight_tasks.fetch_add(1, Ordering::Relaxed) >= self.max_inflight_tasks {
+        let max_inflight_tasks = self.max_inflight_tasks.load(Ordering::Relaxed);
+        if self.inflight_tasks.fetch_add(1, Ordering::Relaxed) >= max_inflight_tasks {
             self.inflight_tasks.fetch_sub(1, Ordering::Relaxed);
             return RateLimitedSnafu {
                 msg: format!(
                     "Max inflight task num exceeds, current: {}, max: {}",
                     self.inflight_tasks.load(Ordering::Relaxed),
-                    self.max_inflight_tasks
+                    max_inflight_tasks
                 ),
             }
             .fail();
@@ -171,6 +178,23 @@ mod tests {
         let _t4 = limiter.acquire_token(&1).unwrap();
     }
 
+    #[test]
+    fn test_update_max_inflight_tasks() {
+        let max = Arc::new(AtomicUsize::new(1));
+        let limiter = MaxInflightTaskLimiter::with_shared_max(max.clone());
+        let t1 = limiter.acquire_token(&1).unwrap();
+        assert!(limiter.acquire_token(&1).is_err());
+
+        max.store(2, Ordering::Relaxed);
+        let _t2 = limiter.acquire_token(&1).unwrap();
+        assert!(limiter.acquire_token(&1).is_err());
+
+        // Shrinking the limit doesn't affect acquired tokens.
+        max.store(1, Ordering::Relaxed);
+        t1.try_release();
+        assert!(limiter.acquire_token(&1).is_err());
+    }
+
     #[test]
     fn test_cascade_limiter() {
         let limiter: CascadeRateLimiter<usize> =
@@ -0,0 +1,225 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::str::FromStr;
+use std::time::Duration;
+
+use chrono::{DateTime, Local, NaiveTime, TimeZone, Timelike};
+use cron::Schedule;
+
+const SECS_PER_DAY: u32 = 24 * 60 * 60;
+
+/// Time window in the local timezone to run requests that can wait, e.g. routine
+/// compactions. Requests with [Request::priority](crate::scheduler::Request::priority)
+/// above `urgent_priority` are also run outside the window.
+#[derive(Debug, Clone)]
+pub struct MaintenanceWindow {
+    schedule: WindowSchedule,
+    urgent_priority: usize,
+}
+
+#[derive(Debug, Clone)]
+enum WindowSchedule {
+    /// Opens at `start` (inclusive) and closes at `end` (exclusive) every day.
+    Daily { start: NaiveTime, end: NaiveTime },
+    /// Opens at each time matching `schedule` and closes `duration` later.
+    Cron {
+        schedule: Schedule,
+        duration: chrono::Duration,
+    },
+}
+
+impl MaintenanceWindow {
+    /// Creates a daily window from `start` (inclusive) to `end` (exclusive), the window
+    /// spans midnight if `end` is not after `start`.
+    pub fn new(start: NaiveTime, end: NaiveTime, urgent_priority: usize) -> Self {
+        Self {
+            schedule: WindowSchedule::Daily { start, end },
+            urgent_priority,
+        }
+    }
+
+    /// Creates a window that opens at each time matching the cron `expression` and lasts
+    /// `duration`. The expression starts with seconds, e.g. `0 0 1 * * Sat,Sun` opens the
+    /// window at 01:00 on weekends.
+    pub fn with_cron(
+        expression: &str,
+        duration: chrono::Duration,
+        urgent_priority: usize,
+    ) -> Result<Self, cron::error::Error> {
+        let schedule = Schedule::from_str(expression)?;
+        Ok(Self {
+            schedule: WindowSchedule::Cron { schedule, duration },
+            urgent_priority,
+        })
+    }
+
+    /// Returns whether the window is open now.
+    pub fn is_open(&self) -> bool {
+        self.contains(&Local::now())
+    }
+
+    /// Returns whether `time` is in the window.
+    pub fn contains<Tz: TimeZone>(&self, time: &DateTime<Tz>) -> bool {
+        match &self.schedule {
+            WindowSchedule::Daily { start, end } => {
+                let time = time.time();
+                if start < end {
+                    *start <= time && time < *end
+                } else {
+                    *start <= time || time < *end
+                }
+            }
+            WindowSchedule::Cron { schedule, duration } => {
+                // The window is open if it opens in the last `duration`.
+                schedule
+                    .after(&(time.clone() - *duration))
+                    .next()
+                    .map_or(false, |open| open <= *time)
+            }
+        }
+    }
+
+    /// Returns whether a request of `priority` can't wait for the window.
+    pub fn is_urgent(&self, priority: usize) -> bool {
+        priority > self.urgent_priority
+    }
+
+    /// Returns the duration from `time` to the next time the window opens or closes.
+    pub fn until_next_change<Tz: TimeZone>(&self, time: &DateTime<Tz>) -> Duration {
+        let secs = match &self.schedule {
+            WindowSchedule::Daily { start, end } => {
+                let next = if self.contains(time) { end } else { start };
+                let time = time.time();
+                (next.num_seconds_from_midnight() + SECS_PER_DAY - time.num_seconds_from_midnight())
+                    % SECS_PER_DAY
+            }
+            WindowSchedule::Cron { schedule, duration } => {
+                let next = if self.contains(time) {
+                    // Closes `duration` after it opens, unless it opens again before that.
+                    schedule
+                        .after(&(time.clone() - *duration))
+                        .next()
+                        .map(|open| open + *duration)
+                } else {
+                    schedule.after(time).next()
+                };
+                // Checks again a day later if the schedule never fires again.
+                next.map_or(SECS_PER_DAY, |next| {
+                    next.signed_duration_since(time.clone())
+                        .num_seconds()
+                        .clamp(0, SECS_PER_DAY as i64) as u32
+                })
+            }
+        };
+        // Wakes up at least once a second in case of a zero-length window.
+        Duration::from_secs(secs.max(1) as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn time(hour: u32, min: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, min, 0).unwrap()
+    }
+
+    /// Returns the time at `hour:min` on 2023-03-01, a Wednesday.
+    fn datetime(hour: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2023, 3, 1, hour, min, 0).unwrap()
+    }
+
+    #[test]
+    fn test_window_contains() {
+        let window = MaintenanceWindow::new(time(1, 0), time(5, 0), 0);
+        assert!(!window.contains(&datetime(0, 59)));
+        assert!(window.contains(&datetime(1, 0)));
+        assert!(window.contains(&datetime(4, 59)));
+        assert!(!window.contains(&datetime(5, 0)));
+
+        // spans midnight
+        let window = MaintenanceWindow::new(time(22, 0), time(2, 0), 0);
+        assert!(window.contains(&datetime(23, 0)));
+        assert!(window.contains(&datetime(0, 0)));
+        assert!(window.contains(&datetime(1, 59)));
+        assert!(!window.contains(&datetime(2, 0)));
+        assert!(!window.contains(&datetime(21, 59)));
+    }
+
+    #[test]
+    fn test_until_next_change() {
+        let window = MaintenanceWindow::new(time(22, 0), time(2, 0), 0);
+        assert_eq!(
+            Duration::from_secs(60 * 60),
+            window.until_next_change(&datetime(21, 0))
+        );
+        assert_eq!(
+            Duration::from_secs(3 * 60 * 60),
+            window.until_next_change(&datetime(23, 0))
+        );
+        assert_eq!(
+            Duration::from_secs(20 * 60 * 60),
+            window.until_next_change(&datetime(2, 0))
+        );
+    }
+
+    #[test]
+    fn test_cron_window() {
+        // Opens at 22:00 on Wednesdays and Thursdays, lasts 4 hours.
+        let window =
+            MaintenanceWindow::with_cron("0 0 22 * * Wed,Thu", chrono::Duration::hours(4), 0)
+                .unwrap();
+        assert!(!window.contains(&datetime(21, 59)));
+        assert!(window.contains(&datetime(22, 0)));
+        assert!(window.contains(&datetime(23, 59)));
+        assert!(!window.contains(&datetime(2, 0)));
+        // The window opened on Tuesday night is still closed.
+        assert!(!window.contains(&datetime(1, 0)));
+
+        assert_eq!(
+            Duration::from_secs(60 * 60),
+            window.until_next_change(&datetime(21, 0))
+        );
+        assert_eq!(
+            Duration::from_secs(3 * 60 * 60),
+            window.until_next_change(&datetime(23, 0))
+        );
+        assert_eq!(
+            Duration::from_secs(20 * 60 * 60),
+            window.until_next_change(&datetime(2, 0))
+        );
+
+        // Checks again a day later if the window opens after that.
+        let window =
+            MaintenanceWindow::with_cron("0 0 22 * * Fri", chrono::Duration::hours(4), 0).unwrap();
+        assert_eq!(
+            Duration::from_secs(24 * 60 * 60),
+            window.until_next_change(&datetime(21, 0))
+        );
+
+        assert!(
+            MaintenanceWindow::with_cron("0 0 25 * * *", chrono::Duration::hours(1), 0).is_err()
+        );
+    }
+
+    #[test]
+    fn test_is_urgent() {
+        let window = MaintenanceWindow::new(time(1, 0), time(5, 0), 32);
+        assert!(!window.is_urgent(32));
+        assert!(window.is_urgent(33));
+    }
+}
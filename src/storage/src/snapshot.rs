@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::cmp;
+use std::time::Duration;
 
 use async_trait::async_trait;
 use store_api::storage::{
@@ -31,6 +32,8 @@ pub struct SnapshotImpl {
     /// Max sequence number (inclusive) visible to user.
     visible_sequence: SequenceNumber,
     sst_layer: AccessLayerRef,
+    /// Time-to-live of the region's data, rows older than it are invisible to user.
+    ttl: Option<Duration>,
 }
 
 #[async_trait]
@@ -60,6 +63,8 @@ impl Snapshot for SnapshotImpl {
                 .filters(request.filters)
                 .batch_size(ctx.batch_size)
                 .visible_sequence(visible_sequence)
+                .ttl(self.ttl)
+                .scan_stats(request.scan_stats)
                 .pick_memtables(mutables.clone());
 
         for memtable in immutables {
@@ -81,11 +86,13 @@ impl SnapshotImpl {
         version: VersionRef,
         visible_sequence: SequenceNumber,
         sst_layer: AccessLayerRef,
+        ttl: Option<Duration>,
     ) -> SnapshotImpl {
         SnapshotImpl {
             version,
             visible_sequence,
             sst_layer,
+            ttl,
         }
     }
 
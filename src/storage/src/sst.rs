@@ -16,15 +16,19 @@ pub(crate) mod parquet;
 
 use std::collections::HashMap;
 use std::fmt;
+use std::hash::{Hash, Hasher};
 use std::str::FromStr;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::SystemTime;
 
 use async_trait::async_trait;
+use common_recordbatch::scan_stats::ScanStatsRecorderRef;
 use common_telemetry::{error, info};
 use common_time::range::TimestampRange;
 use common_time::Timestamp;
-use object_store::{util, ObjectStore};
+use datatypes::value::Value;
+use object_store::{util, ErrorKind, ObjectStore};
 use serde::{Deserialize, Deserializer, Serialize};
 use snafu::{ResultExt, Snafu};
 use store_api::storage::{ChunkReader, RegionId};
@@ -32,7 +36,8 @@ use table::predicate::Predicate;
 use uuid::Uuid;
 
 use crate::chunk::ChunkReaderImpl;
-use crate::error::{DeleteSstSnafu, Result};
+use crate::config::{BloomFilterConfig, MultipartConfig};
+use crate::error::{DeleteSstSnafu, ListObjectsSnafu, ReadObjectSnafu, Result};
 use crate::file_purger::{FilePurgeRequest, FilePurgerRef};
 use crate::memtable::BoxedBatchIterator;
 use crate::read::{Batch, BoxedBatchReader};
@@ -41,7 +46,7 @@ use crate::schema::ProjectedSchemaRef;
 use crate::sst::parquet::{ParquetReader, ParquetWriter};
 
 /// Maximum level of SSTs.
-pub const MAX_LEVEL: u8 = 2;
+pub const MAX_LEVEL: u8 = 4;
 
 pub type Level = u8;
 
@@ -213,6 +218,16 @@ impl FileHandle {
         &self.inner.meta.time_range
     }
 
+    #[inline]
+    pub fn key_range(&self) -> &[ColumnRange] {
+        &self.inner.meta.key_range
+    }
+
+    #[inline]
+    pub fn row_groups(&self) -> &[RowGroupStats] {
+        &self.inner.meta.row_groups
+    }
+
     /// Returns true if current file is under compaction.
     #[inline]
     pub fn compacting(&self) -> bool {
@@ -244,6 +259,18 @@ impl FileHandle {
     pub fn file_size(&self) -> u64 {
         self.inner.meta.file_size
     }
+
+    /// Returns the number of rows in the file, counted from the row groups for files
+    /// written before the number is recorded.
+    #[inline]
+    pub fn num_rows(&self) -> u64 {
+        let meta = &self.inner.meta;
+        if meta.num_rows > 0 {
+            meta.num_rows
+        } else {
+            meta.row_groups.iter().map(|rg| rg.num_rows as u64).sum()
+        }
+    }
 }
 
 /// Actually data of [FileHandle].
@@ -356,6 +383,61 @@ pub struct FileMeta {
     pub level: Level,
     /// Size of the file.
     pub file_size: u64,
+    /// Whether the file has bloom filters of row key columns.
+    pub bloom_filter: bool,
+    /// Min/max values of row key columns (except the timestamp column) in the file.
+    ///
+    /// A column absent from the list has unknown range, e.g. files written by old
+    /// versions don't have this field at all.
+    pub key_range: Vec<ColumnRange>,
+    /// Statistics of row key columns (except the timestamp column) in each row group of
+    /// the file, in the order of row groups. Empty if unknown.
+    pub row_groups: Vec<RowGroupStats>,
+    /// Number of rows in the file, 0 for files written by old versions.
+    pub num_rows: u64,
+}
+
+/// Min/max values of a column in a SST file, both ends are inclusive.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnRange {
+    pub column: String,
+    pub min: Value,
+    pub max: Value,
+}
+
+// `Value` doesn't implement `Hash`, so we only hash the column name, which is consistent
+// with `Eq` since equal ranges always have equal names.
+impl Hash for ColumnRange {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.column.hash(state);
+    }
+}
+
+/// Statistics of a row group in a SST file.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct RowGroupStats {
+    /// Number of rows in the row group.
+    pub num_rows: usize,
+    pub columns: Vec<ColumnStats>,
+}
+
+/// Min/max values and null count of a column in a row group, both ends are inclusive.
+///
+/// Min/max values are [Value::Null] if all values of the column are null.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnStats {
+    pub column: String,
+    pub min: Value,
+    pub max: Value,
+    pub null_count: usize,
+}
+
+// Same as `ColumnRange`, `Value` doesn't implement `Hash`.
+impl Hash for ColumnStats {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.column.hash(state);
+        self.null_count.hash(state);
+    }
 }
 
 fn deserialize_from_string<'de, D>(deserializer: D) -> std::result::Result<FileId, D::Error>
@@ -370,6 +452,8 @@ where
 #[derive(Debug, Default)]
 pub struct WriteOptions {
     // TODO(yingwen): [flush] row group size.
+    /// Writes bloom filters of row key columns if set.
+    pub bloom_filter: Option<BloomFilterConfig>,
 }
 
 pub struct ReadOptions {
@@ -381,12 +465,22 @@ pub struct ReadOptions {
 
     pub predicate: Predicate,
     pub time_range: TimestampRange,
+    /// Records the stats of reading the SST if present.
+    pub scan_stats: Option<ScanStatsRecorderRef>,
 }
 
 #[derive(Debug, PartialEq)]
 pub struct SstInfo {
     pub time_range: Option<(Timestamp, Timestamp)>,
     pub file_size: u64,
+    /// Whether bloom filters are written to the file.
+    pub bloom_filter: bool,
+    /// Min/max values of row key columns in the file.
+    pub key_range: Vec<ColumnRange>,
+    /// Statistics of row key columns in each row group.
+    pub row_groups: Vec<RowGroupStats>,
+    /// Number of rows written to the file.
+    pub num_rows: u64,
 }
 
 /// SST access layer.
@@ -405,6 +499,24 @@ pub trait AccessLayer: Send + Sync + std::fmt::Debug {
 
     /// Deletes a SST file with given name.
     async fn delete_sst(&self, file_id: FileId) -> Result<()>;
+
+    /// Returns ids of all SST files in the access layer.
+    async fn list_ssts(&self) -> Result<Vec<FileId>>;
+
+    /// Returns all SST files in the access layer with their sizes and modified times.
+    async fn list_sst_objects(&self) -> Result<Vec<SstObject>>;
+
+    /// Returns the size of the SST file, or `None` if it doesn't exist.
+    async fn sst_size(&self, file_id: FileId) -> Result<Option<u64>>;
+}
+
+/// An SST file in the object store.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SstObject {
+    pub file_id: FileId,
+    pub file_size: u64,
+    /// `None` if the object store doesn't report it.
+    pub last_modified: Option<SystemTime>,
 }
 
 pub type AccessLayerRef = Arc<dyn AccessLayer>;
@@ -441,6 +553,7 @@ impl Source {
 pub struct FsAccessLayer {
     sst_dir: String,
     object_store: ObjectStore,
+    multipart: Option<MultipartConfig>,
 }
 
 impl FsAccessLayer {
@@ -448,9 +561,16 @@ impl FsAccessLayer {
         FsAccessLayer {
             sst_dir: util::normalize_dir(sst_dir),
             object_store,
+            multipart: None,
         }
     }
 
+    /// Uploads SST files in parts according to `multipart`.
+    pub fn with_multipart(mut self, multipart: Option<MultipartConfig>) -> FsAccessLayer {
+        self.multipart = multipart;
+        self
+    }
+
     #[inline]
     fn sst_file_path(&self, file_name: &str) -> String {
         format!("{}{}", self.sst_dir, file_name)
@@ -468,7 +588,8 @@ impl AccessLayer for FsAccessLayer {
         // Now we only supports parquet format. We may allow caller to specific SST format in
         // WriteOptions in the future.
         let file_path = self.sst_file_path(&file_id.as_parquet());
-        let writer = ParquetWriter::new(&file_path, source, self.object_store.clone());
+        let writer = ParquetWriter::new(&file_path, source, self.object_store.clone())
+            .with_multipart(self.multipart);
         writer.write_sst(opts).await
     }
 
@@ -480,7 +601,8 @@ impl AccessLayer for FsAccessLayer {
             opts.projected_schema.clone(),
             opts.predicate.clone(),
             opts.time_range,
-        );
+        )
+        .with_scan_stats(opts.scan_stats.clone());
 
         let stream = reader.chunk_stream().await?;
         Ok(Box::new(stream))
@@ -491,6 +613,70 @@ impl AccessLayer for FsAccessLayer {
         let object = self.object_store.object(&path);
         object.delete().await.context(DeleteSstSnafu)
     }
+
+    async fn list_ssts(&self) -> Result<Vec<FileId>> {
+        let dir = self.object_store.object(&self.sst_dir);
+        let exists = dir.is_exist().await.context(ReadObjectSnafu {
+            path: &self.sst_dir,
+        })?;
+        if !exists {
+            return Ok(Vec::new());
+        }
+
+        let lister = dir.list().await.context(ListObjectsSnafu {
+            path: &self.sst_dir,
+        })?;
+        let objects = util::collect(lister).await.context(ListObjectsSnafu {
+            path: &self.sst_dir,
+        })?;
+        Ok(objects
+            .iter()
+            .filter_map(|object| object.name().strip_suffix(".parquet"))
+            .filter_map(|id| FileId::parse_str(id).ok())
+            .collect())
+    }
+
+    async fn list_sst_objects(&self) -> Result<Vec<SstObject>> {
+        let dir = self.object_store.object(&self.sst_dir);
+        let exists = dir.is_exist().await.context(ReadObjectSnafu {
+            path: &self.sst_dir,
+        })?;
+        if !exists {
+            return Ok(Vec::new());
+        }
+
+        let lister = dir.list().await.context(ListObjectsSnafu {
+            path: &self.sst_dir,
+        })?;
+        let objects = util::collect(lister).await.context(ListObjectsSnafu {
+            path: &self.sst_dir,
+        })?;
+        let mut ssts = Vec::with_capacity(objects.len());
+        for object in objects {
+            let Some(file_id) = object
+                .name()
+                .strip_suffix(".parquet")
+                .and_then(|id| FileId::parse_str(id).ok()) else { continue; };
+            let meta = object.metadata().await.context(ReadObjectSnafu {
+                path: object.path(),
+            })?;
+            ssts.push(SstObject {
+                file_id,
+                file_size: meta.content_length(),
+                last_modified: meta.last_modified().map(SystemTime::from),
+            });
+        }
+        Ok(ssts)
+    }
+
+    async fn sst_size(&self, file_id: FileId) -> Result<Option<u64>> {
+        let path = self.sst_file_path(&file_id.as_parquet());
+        match self.object_store.object(&path).metadata().await {
+            Ok(meta) => Ok(Some(meta.content_length())),
+            Err(e) if e.kind() == ErrorKind::ObjectNotFound => Ok(None),
+            Err(e) => Err(e).context(ReadObjectSnafu { path }),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -579,6 +765,10 @@ mod tests {
             time_range: None,
             level,
             file_size: 0,
+            bloom_filter: false,
+            key_range: vec![],
+            row_groups: vec![],
+            num_rows: 0,
         }
     }
 
@@ -15,8 +15,10 @@
 //! Parquet sst format.
 
 use std::collections::HashMap;
+use std::io::SeekFrom;
 use std::pin::Pin;
 use std::sync::Arc;
+use std::task::{Context, Poll};
 
 use arrow::datatypes::DataType;
 use arrow_array::types::Int64Type;
@@ -27,6 +29,8 @@ use arrow_array::{
 use async_compat::CompatExt;
 use async_stream::try_stream;
 use async_trait::async_trait;
+use bytes::Bytes;
+use common_recordbatch::scan_stats::ScanStatsRecorderRef;
 use common_telemetry::error;
 use common_time::range::TimestampRange;
 use common_time::timestamp::TimeUnit;
@@ -35,19 +39,22 @@ use datatypes::arrow::array::BooleanArray;
 use datatypes::arrow::error::ArrowError;
 use datatypes::arrow::record_batch::RecordBatch;
 use datatypes::prelude::ConcreteDataType;
+use datatypes::value::Value;
+use datatypes::vectors::VectorRef;
 use futures_util::{Stream, StreamExt, TryStreamExt};
-use object_store::ObjectStore;
+use object_store::{ErrorKind, Object, ObjectStore};
 use parquet::arrow::arrow_reader::{ArrowPredicate, RowFilter};
 use parquet::arrow::{ArrowWriter, ParquetRecordBatchStreamBuilder, ProjectionMask};
 use parquet::basic::{Compression, Encoding};
 use parquet::file::metadata::KeyValue;
 use parquet::file::properties::WriterProperties;
 use parquet::format::FileMetaData;
-use parquet::schema::types::SchemaDescriptor;
+use parquet::schema::types::{ColumnPath, SchemaDescriptor};
 use snafu::{OptionExt, ResultExt};
 use table::predicate::Predicate;
-use tokio::io::BufReader;
+use tokio::io::{AsyncRead, AsyncSeek, BufReader, ReadBuf};
 
+use crate::config::{BloomFilterConfig, MultipartConfig};
 use crate::error::{
     self, DecodeParquetTimeRangeSnafu, NewRecordBatchSnafu, ReadObjectSnafu, ReadParquetSnafu,
     Result, WriteObjectSnafu, WriteParquetSnafu,
@@ -56,13 +63,14 @@ use crate::read::{Batch, BatchReader};
 use crate::schema::compat::ReadAdapter;
 use crate::schema::{ProjectedSchemaRef, StoreSchema, StoreSchemaRef};
 use crate::sst;
-use crate::sst::{Source, SstInfo};
+use crate::sst::{ColumnRange, ColumnStats, RowGroupStats, Source, SstInfo};
 /// Parquet sst writer.
 pub struct ParquetWriter<'a> {
     file_path: &'a str,
     source: Source,
     object_store: ObjectStore,
     max_row_group_size: usize,
+    multipart: Option<MultipartConfig>,
 }
 
 impl<'a> ParquetWriter<'a> {
@@ -72,23 +80,34 @@ impl<'a> ParquetWriter<'a> {
             source,
             object_store,
             max_row_group_size: 4096, // TODO(hl): make this configurable
+            multipart: None,
         }
     }
 
-    pub async fn write_sst(self, _opts: &sst::WriteOptions) -> Result<SstInfo> {
-        self.write_rows(None).await
+    /// Uploads files larger than the threshold of `multipart` in parts.
+    pub fn with_multipart(mut self, multipart: Option<MultipartConfig>) -> ParquetWriter<'a> {
+        self.multipart = multipart;
+        self
+    }
+
+    pub async fn write_sst(self, opts: &sst::WriteOptions) -> Result<SstInfo> {
+        self.write_rows(None, opts.bloom_filter).await
     }
 
     /// Iterates memtable and writes rows to Parquet file.
     /// A chunk of records yielded from each iteration with a size given
     /// in config will be written to a single row group.
-    async fn write_rows(mut self, extra_meta: Option<HashMap<String, String>>) -> Result<SstInfo> {
+    async fn write_rows(
+        mut self,
+        extra_meta: Option<HashMap<String, String>>,
+        bloom_filter: Option<BloomFilterConfig>,
+    ) -> Result<SstInfo> {
         let projected_schema = self.source.projected_schema();
         let store_schema = projected_schema.schema_to_read();
         let schema = store_schema.arrow_schema().clone();
         let object = self.object_store.object(self.file_path);
 
-        let writer_props = WriterProperties::builder()
+        let mut builder = WriterProperties::builder()
             .set_compression(Compression::ZSTD)
             .set_encoding(Encoding::PLAIN)
             .set_max_row_group_size(self.max_row_group_size)
@@ -96,8 +115,19 @@ impl<'a> ParquetWriter<'a> {
                 map.iter()
                     .map(|(k, v)| KeyValue::new(k.clone(), v.clone()))
                     .collect::<Vec<_>>()
-            }))
-            .build();
+            }));
+        if let Some(bloom_filter) = bloom_filter {
+            // A row group has at most `max_row_group_size` distinct keys, filters sized by
+            // it meet the target fpp.
+            for idx in store_schema.row_key_indices() {
+                let column = ColumnPath::from(store_schema.column_name(idx));
+                builder = builder
+                    .set_column_bloom_filter_enabled(column.clone(), true)
+                    .set_column_bloom_filter_fpp(column.clone(), bloom_filter.fpp)
+                    .set_column_bloom_filter_ndv(column, self.max_row_group_size as u64);
+            }
+        }
+        let writer_props = builder.build();
 
         // TODO(hl): Since OpenDAL's writer is async and ArrowWriter requires a `std::io::Write`,
         // here we use a Vec<u8> to buffer all parquet bytes in memory and write to object store
@@ -105,8 +135,10 @@ impl<'a> ParquetWriter<'a> {
         let mut buf = vec![];
         let mut arrow_writer = ArrowWriter::try_new(&mut buf, schema.clone(), Some(writer_props))
             .context(WriteParquetSnafu)?;
+        let mut key_stats = KeyStatsCollector::new(store_schema, self.max_row_group_size);
 
         while let Some(batch) = self.source.next_batch().await? {
+            key_stats.update(&batch);
             let arrow_batch = RecordBatch::try_new(
                 schema.clone(),
                 batch
@@ -126,10 +158,17 @@ impl<'a> ParquetWriter<'a> {
         let time_range = decode_timestamp_range(&file_meta, store_schema)
             .ok()
             .flatten();
+        let (key_range, row_groups) = key_stats.finish(&file_meta);
+        let num_rows = file_meta.num_rows as u64;
 
-        object.write(buf).await.context(WriteObjectSnafu {
-            path: object.path(),
-        })?;
+        match self.multipart {
+            Some(multipart) if buf.len() >= multipart.threshold => {
+                write_multipart(&object, buf, multipart.chunk_size).await?
+            }
+            _ => object.write(buf).await.context(WriteObjectSnafu {
+                path: object.path(),
+            })?,
+        }
         let file_size = object
             .metadata()
             .await
@@ -140,10 +179,228 @@ impl<'a> ParquetWriter<'a> {
         Ok(SstInfo {
             time_range,
             file_size,
+            bloom_filter: bloom_filter.is_some(),
+            key_range,
+            row_groups,
+            num_rows,
         })
     }
 }
 
+/// Collects statistics of row key columns, except the timestamp column whose range
+/// is decoded from the statistics of the file.
+///
+/// Rows are split into row groups the same way as the [ArrowWriter], which flushes a row
+/// group once it buffers `max_row_group_size` rows.
+struct KeyStatsCollector {
+    /// Indices and names of the columns to collect.
+    columns: Vec<(usize, String)>,
+    max_row_group_size: usize,
+    /// Number of rows in the row group being written.
+    num_rows: usize,
+    /// Statistics of each column in the row group being written.
+    current: Vec<ColumnStatsBuilder>,
+    /// Statistics of finished row groups.
+    row_groups: Vec<RowGroupStats>,
+}
+
+impl KeyStatsCollector {
+    fn new(store_schema: &StoreSchema, max_row_group_size: usize) -> KeyStatsCollector {
+        let timestamp_index = store_schema.schema().timestamp_index();
+        let columns: Vec<_> = store_schema
+            .row_key_indices()
+            .filter(|idx| Some(*idx) != timestamp_index)
+            .map(|idx| (idx, store_schema.column_name(idx).to_string()))
+            .collect();
+        let current = columns
+            .iter()
+            .map(|_| ColumnStatsBuilder::default())
+            .collect();
+        KeyStatsCollector {
+            columns,
+            max_row_group_size,
+            num_rows: 0,
+            current,
+            row_groups: Vec::new(),
+        }
+    }
+
+    fn update(&mut self, batch: &Batch) {
+        let total = batch.num_rows();
+        let mut offset = 0;
+        while offset < total {
+            let len = (self.max_row_group_size - self.num_rows).min(total - offset);
+            for ((idx, _), builder) in self.columns.iter().zip(self.current.iter_mut()) {
+                builder.update(&batch.column(*idx).slice(offset, len));
+            }
+            self.num_rows += len;
+            offset += len;
+            if self.num_rows >= self.max_row_group_size {
+                self.finish_row_group();
+            }
+        }
+    }
+
+    fn finish_row_group(&mut self) {
+        let columns = self
+            .columns
+            .iter()
+            .zip(self.current.iter_mut())
+            .map(|((_, column), builder)| std::mem::take(builder).build(column.clone()))
+            .collect();
+        self.row_groups.push(RowGroupStats {
+            num_rows: self.num_rows,
+            columns,
+        });
+        self.num_rows = 0;
+    }
+
+    /// Returns min/max values of columns in the file and statistics of each row group.
+    ///
+    /// Row group statistics are discarded if they don't match the row groups in `file_meta`.
+    fn finish(mut self, file_meta: &FileMetaData) -> (Vec<ColumnRange>, Vec<RowGroupStats>) {
+        if self.num_rows > 0 {
+            self.finish_row_group();
+        }
+
+        let key_range = self
+            .columns
+            .iter()
+            .enumerate()
+            .filter_map(|(i, (_, column))| {
+                let (min, max) = self
+                    .row_groups
+                    .iter()
+                    .map(|row_group| &row_group.columns[i])
+                    .filter(|stats| !stats.min.is_null())
+                    .fold(None, |range, stats| {
+                        Some(match range {
+                            None => (stats.min.clone(), stats.max.clone()),
+                            Some((min, max)) => {
+                                (min.min(stats.min.clone()), max.max(stats.max.clone()))
+                            }
+                        })
+                    })?;
+                Some(ColumnRange {
+                    column: column.clone(),
+                    min,
+                    max,
+                })
+            })
+            .collect();
+
+        let matched = self.row_groups.len() == file_meta.row_groups.len()
+            && self
+                .row_groups
+                .iter()
+                .zip(&file_meta.row_groups)
+                .all(|(stats, row_group)| stats.num_rows as i64 == row_group.num_rows);
+        if !matched {
+            error!(
+                "Discard statistics of {} row groups, the SST file has {} row groups",
+                self.row_groups.len(),
+                file_meta.row_groups.len()
+            );
+            return (key_range, Vec::new());
+        }
+
+        (key_range, self.row_groups)
+    }
+}
+
+/// Builds [ColumnStats] of a column in a row group.
+#[derive(Default)]
+struct ColumnStatsBuilder {
+    /// Min/max values, `None` if all values seen so far are null.
+    range: Option<(Value, Value)>,
+    null_count: usize,
+}
+
+impl ColumnStatsBuilder {
+    fn update(&mut self, vector: &VectorRef) {
+        // Positions of the min/max non-null values in the vector.
+        let mut min_max: Option<(usize, usize)> = None;
+        for i in 0..vector.len() {
+            let value = vector.get_ref(i);
+            if value.is_null() {
+                self.null_count += 1;
+                continue;
+            }
+            min_max = Some(match min_max {
+                None => (i, i),
+                Some((min, max)) => (
+                    if value < vector.get_ref(min) { i } else { min },
+                    if value > vector.get_ref(max) { i } else { max },
+                ),
+            });
+        }
+        let Some((min, max)) = min_max else { return; };
+        let (min, max) = (vector.get(min), vector.get(max));
+        self.range = Some(match self.range.take() {
+            None => (min, max),
+            Some((old_min, old_max)) => (old_min.min(min), old_max.max(max)),
+        });
+    }
+
+    fn build(self, column: String) -> ColumnStats {
+        let (min, max) = self.range.unwrap_or((Value::Null, Value::Null));
+        ColumnStats {
+            column,
+            min,
+            max,
+            null_count: self.null_count,
+        }
+    }
+}
+
+/// Uploads `buf` to `object` in parts of `chunk_size` bytes, falls back to a single write if
+/// the backend doesn't support multipart uploads.
+async fn write_multipart(object: &Object, buf: Vec<u8>, chunk_size: usize) -> Result<()> {
+    let multipart = match object.create_multipart().await {
+        Ok(multipart) => multipart,
+        Err(e) if e.kind() == ErrorKind::Unsupported => {
+            return object.write(buf).await.context(WriteObjectSnafu {
+                path: object.path(),
+            });
+        }
+        Err(e) => {
+            return Err(e).context(WriteObjectSnafu {
+                path: object.path(),
+            })
+        }
+    };
+
+    let buf = Bytes::from(buf);
+    let mut parts = Vec::with_capacity(buf.len() / chunk_size + 1);
+    let mut offset = 0;
+    while offset < buf.len() {
+        let end = (offset + chunk_size).min(buf.len());
+        // OpenDAL numbers parts from 0.
+        match multipart.write(parts.len(), buf.slice(offset..end)).await {
+            Ok(part) => parts.push(part),
+            Err(e) => {
+                if let Err(abort_err) = multipart.abort().await {
+                    error!(abort_err; "Failed to abort multipart upload of {}", object.path());
+                }
+                return Err(e).context(WriteObjectSnafu {
+                    path: object.path(),
+                });
+            }
+        }
+        offset = end;
+    }
+
+    if let Err(e) = multipart.complete(parts).await {
+        if let Err(abort_err) = multipart.abort().await {
+            error!(abort_err; "Failed to abort multipart upload of {}", object.path());
+        }
+        return Err(e).context(WriteObjectSnafu {
+            path: object.path(),
+        });
+    }
+    Ok(())
+}
+
 fn decode_timestamp_range(
     file_meta: &FileMetaData,
     store_schema: &StoreSchemaRef,
@@ -216,12 +473,44 @@ fn decode_timestamp_range_inner(
     )))
 }
 
+/// Reader of an object that records the bytes read to the scan stats.
+struct CountingReader<R> {
+    inner: R,
+    scan_stats: Option<ScanStatsRecorderRef>,
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for CountingReader<R> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let filled = buf.filled().len();
+        let res = Pin::new(&mut self.inner).poll_read(cx, buf);
+        if let (Poll::Ready(Ok(())), Some(scan_stats)) = (&res, &self.scan_stats) {
+            scan_stats.add_bytes_read((buf.filled().len() - filled) as u64);
+        }
+        res
+    }
+}
+
+impl<R: AsyncSeek + Unpin> AsyncSeek for CountingReader<R> {
+    fn start_seek(mut self: Pin<&mut Self>, position: SeekFrom) -> std::io::Result<()> {
+        Pin::new(&mut self.inner).start_seek(position)
+    }
+
+    fn poll_complete(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<u64>> {
+        Pin::new(&mut self.inner).poll_complete(cx)
+    }
+}
+
 pub struct ParquetReader<'a> {
     file_path: &'a str,
     object_store: ObjectStore,
     projected_schema: ProjectedSchemaRef,
     predicate: Predicate,
     time_range: TimestampRange,
+    scan_stats: Option<ScanStatsRecorderRef>,
 }
 
 impl<'a> ParquetReader<'a> {
@@ -238,9 +527,16 @@ impl<'a> ParquetReader<'a> {
             projected_schema,
             predicate,
             time_range,
+            scan_stats: None,
         }
     }
 
+    /// Records the rows decoded and bytes read by this reader to `scan_stats`.
+    pub fn with_scan_stats(mut self, scan_stats: Option<ScanStatsRecorderRef>) -> Self {
+        self.scan_stats = scan_stats;
+        self
+    }
+
     pub async fn chunk_stream(&self) -> Result<ChunkStream> {
         let operator = self.object_store.clone();
         let reader = operator
@@ -251,7 +547,10 @@ impl<'a> ParquetReader<'a> {
                 path: self.file_path,
             })?
             .compat();
-        let buf_reader = BufReader::new(reader);
+        let buf_reader = BufReader::new(CountingReader {
+            inner: reader,
+            scan_stats: self.scan_stats.clone(),
+        });
         let builder = ParquetRecordBatchStreamBuilder::new(buf_reader)
             .await
             .context(ReadParquetSnafu {
@@ -295,9 +594,14 @@ impl<'a> ParquetReader<'a> {
         })?;
 
         let file_name = self.file_path.to_string();
+        let scan_stats = self.scan_stats.clone();
         let chunk_stream = try_stream!({
             while let Some(res) = stream.next().await {
-                yield res.context(ReadParquetSnafu { file: &file_name })?
+                let batch = res.context(ReadParquetSnafu { file: &file_name })?;
+                if let Some(scan_stats) = &scan_stats {
+                    scan_stats.add_rows_decoded(batch.num_rows() as u64);
+                }
+                yield batch
             }
         });
 
@@ -646,6 +950,46 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_parquet_writer_multipart_fallback() {
+        let schema = memtable_tests::schema_for_test();
+        let memtable = DefaultMemtableBuilder::default().build(schema);
+        memtable_tests::write_kvs(
+            &*memtable,
+            10, // sequence
+            OpType::Put,
+            &[(1000, 1), (1000, 2), (2002, 1)], // keys
+            &[
+                (Some(1), Some(1234)),
+                (Some(2), Some(1234)),
+                (Some(7), Some(1234)),
+            ], // values
+        );
+
+        let dir = create_temp_dir("write_parquet_multipart");
+        let path = dir.path().to_str().unwrap();
+        let backend = Fs::default().root(path).build().unwrap();
+        let object_store = ObjectStore::new(backend).finish();
+        let sst_file_name = "test-multipart.parquet";
+        let iter = memtable.iter(&IterContext::default()).unwrap();
+        // The fs backend doesn't support multipart uploads, so the writer falls back to
+        // a single write.
+        let writer = ParquetWriter::new(sst_file_name, Source::Iter(iter), object_store.clone())
+            .with_multipart(Some(MultipartConfig {
+                threshold: 1,
+                chunk_size: 16,
+            }));
+
+        let sst_info = writer
+            .write_sst(&sst::WriteOptions::default())
+            .await
+            .unwrap();
+
+        let metadata = object_store.object(sst_file_name).metadata().await.unwrap();
+        assert_eq!(sst_info.file_size, metadata.content_length());
+        assert!(sst_info.file_size > 16);
+    }
+
     #[tokio::test]
     async fn test_parquet_read_large_batch() {
         common_telemetry::init_default_ut_logging();
@@ -680,6 +1024,7 @@ mod tests {
         let SstInfo {
             time_range,
             file_size,
+            ..
         } = writer
             .write_sst(&sst::WriteOptions::default())
             .await
@@ -757,6 +1102,7 @@ mod tests {
         let SstInfo {
             time_range,
             file_size,
+            ..
         } = writer
             .write_sst(&sst::WriteOptions::default())
             .await
@@ -874,6 +1220,7 @@ mod tests {
         let SstInfo {
             time_range,
             file_size,
+            ..
         } = writer
             .write_sst(&sst::WriteOptions::default())
             .await
@@ -13,7 +13,7 @@
 // limitations under the License.
 
 use crate::read::BoxedBatchReader;
-use crate::sst::{AccessLayer, FileId, ReadOptions, Source, SstInfo, WriteOptions};
+use crate::sst::{AccessLayer, FileId, ReadOptions, Source, SstInfo, SstObject, WriteOptions};
 
 #[derive(Debug)]
 pub struct MockAccessLayer;
@@ -40,4 +40,16 @@ impl AccessLayer for MockAccessLayer {
     async fn delete_sst(&self, _file_id: FileId) -> crate::error::Result<()> {
         Ok(())
     }
+
+    async fn list_ssts(&self) -> crate::error::Result<Vec<FileId>> {
+        Ok(Vec::new())
+    }
+
+    async fn list_sst_objects(&self) -> crate::error::Result<Vec<SstObject>> {
+        Ok(Vec::new())
+    }
+
+    async fn sst_size(&self, _file_id: FileId) -> crate::error::Result<Option<u64>> {
+        Ok(None)
+    }
 }
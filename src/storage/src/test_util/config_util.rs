@@ -70,5 +70,8 @@ pub async fn new_store_config(
         engine_config: Default::default(),
         file_purger,
         ttl: None,
+        compaction: Default::default(),
+        memtable_budget: None,
+        event_dispatcher: Default::default(),
     }
 }
@@ -0,0 +1,171 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Consistency check between the manifests of regions and their SST files in the object
+//! store, e.g. before and after migrating the data. The check only reads, it never deletes
+//! or repairs anything.
+
+use std::collections::HashSet;
+
+use store_api::storage::RegionId;
+
+use crate::gc::OrphanSst;
+use crate::sst::{FileId, FileMeta, SstObject};
+
+/// Default number of SST files checked at a time in a region.
+pub const DEFAULT_VERIFY_PARALLELISM: usize = 8;
+
+/// An SST file of the current version of a region that is missing from the object store.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingSst {
+    pub file_id: FileId,
+    /// Size of the file in the manifest.
+    pub expected_size: u64,
+}
+
+/// An SST file whose size in the object store differs from the size in the manifest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SstSizeMismatch {
+    pub file_id: FileId,
+    pub expected_size: u64,
+    pub actual_size: u64,
+}
+
+/// Result of checking the SST files of a region.
+#[derive(Debug, Clone, Default)]
+pub struct RegionVerifyReport {
+    pub region_id: RegionId,
+    pub region_name: String,
+    /// Number of files of the current version checked.
+    pub checked_files: usize,
+    pub missing: Vec<MissingSst>,
+    pub size_mismatches: Vec<SstSizeMismatch>,
+    /// Files in the object store never referenced by the manifest, regardless of their
+    /// ages. Files removed by the manifest but not purged yet are not orphans.
+    pub orphans: Vec<OrphanSst>,
+    /// Why the region is skipped, e.g. it's not opened or a flush is running.
+    pub skipped: Option<String>,
+}
+
+impl RegionVerifyReport {
+    /// Returns true if any problem is found, a region that can't be checked is also a
+    /// problem.
+    pub fn has_problems(&self) -> bool {
+        !self.missing.is_empty()
+            || !self.size_mismatches.is_empty()
+            || !self.orphans.is_empty()
+            || self.skipped.is_some()
+    }
+}
+
+/// Compares `files` of the current version with their `sizes` in the object store, in the
+/// same order, `None` if the file doesn't exist.
+///
+/// Sizes of files written by old versions are unknown (zero) in the manifest, only their
+/// existence is checked.
+pub(crate) fn check_files(
+    files: &[FileMeta],
+    sizes: &[Option<u64>],
+) -> (Vec<MissingSst>, Vec<SstSizeMismatch>) {
+    let mut missing = Vec::new();
+    let mut size_mismatches = Vec::new();
+    for (file, size) in files.iter().zip(sizes) {
+        match size {
+            None => missing.push(MissingSst {
+                file_id: file.file_id,
+                expected_size: file.file_size,
+            }),
+            Some(size) if file.file_size != 0 && *size != file.file_size => {
+                size_mismatches.push(SstSizeMismatch {
+                    file_id: file.file_id,
+                    expected_size: file.file_size,
+                    actual_size: *size,
+                })
+            }
+            Some(_) => (),
+        }
+    }
+    (missing, size_mismatches)
+}
+
+/// Returns the `objects` not in `referenced`.
+pub(crate) fn find_unreferenced(
+    objects: &[SstObject],
+    referenced: &HashSet<FileId>,
+) -> Vec<OrphanSst> {
+    objects
+        .iter()
+        .filter(|object| !referenced.contains(&object.file_id))
+        .map(|object| OrphanSst {
+            file_id: object.file_id,
+            file_size: object.file_size,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file_meta(file_size: u64) -> FileMeta {
+        FileMeta {
+            file_id: FileId::random(),
+            file_size,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_check_files() {
+        let files = vec![file_meta(10), file_meta(10), file_meta(10), file_meta(0)];
+        let sizes = vec![Some(10), None, Some(20), Some(30)];
+
+        let (missing, size_mismatches) = check_files(&files, &sizes);
+        assert_eq!(
+            vec![MissingSst {
+                file_id: files[1].file_id,
+                expected_size: 10,
+            }],
+            missing
+        );
+        // The size of the last file is unknown.
+        assert_eq!(
+            vec![SstSizeMismatch {
+                file_id: files[2].file_id,
+                expected_size: 10,
+                actual_size: 20,
+            }],
+            size_mismatches
+        );
+    }
+
+    #[test]
+    fn test_find_unreferenced() {
+        let object = |file_size| SstObject {
+            file_id: FileId::random(),
+            file_size,
+            last_modified: None,
+        };
+        let objects = vec![object(1), object(2)];
+        let referenced = HashSet::from([objects[0].file_id]);
+
+        assert_eq!(
+            vec![OrphanSst {
+                file_id: objects[1].file_id,
+                file_size: 2,
+            }],
+            find_unreferenced(&objects, &referenced)
+        );
+    }
+}
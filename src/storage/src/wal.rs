@@ -16,17 +16,18 @@ use std::pin::Pin;
 use std::sync::Arc;
 
 use common_error::prelude::BoxedError;
-use futures::{stream, Stream, TryStreamExt};
+use futures::{stream, Stream, StreamExt, TryStreamExt};
 use prost::Message;
 use snafu::{ensure, ResultExt};
 use store_api::logstore::entry::{Entry, Id};
+use store_api::logstore::entry_stream::SendableEntryStream;
 use store_api::logstore::LogStore;
 use store_api::storage::{RegionId, SequenceNumber};
 
 use crate::codec::{Decoder, Encoder};
 use crate::error::{
-    DecodeWalHeaderSnafu, EncodeWalHeaderSnafu, Error, MarkWalObsoleteSnafu, ReadWalSnafu, Result,
-    WalDataCorruptedSnafu, WriteWalSnafu,
+    DecodeWalHeaderSnafu, EncodeWalHeaderSnafu, Error, MarkWalObsoleteSnafu, PurgeWalSnafu,
+    ReadWalSnafu, Result, WalDataCorruptedSnafu, WriteWalSnafu,
 };
 use crate::proto::wal::{self, WalHeader};
 use crate::write_batch::codec::{PayloadDecoder, PayloadEncoder};
@@ -37,6 +38,8 @@ pub struct Wal<S: LogStore> {
     region_id: RegionId,
     namespace: S::Namespace,
     store: Arc<S>,
+    /// Max number of entry batches read ahead while replaying, 0 to read on demand.
+    prefetch_batches: usize,
 }
 
 pub type PayloadStream<'a> =
@@ -49,6 +52,7 @@ impl<S: LogStore> Clone for Wal<S> {
             region_id: self.region_id,
             namespace: self.namespace.clone(),
             store: self.store.clone(),
+            prefetch_batches: self.prefetch_batches,
         }
     }
 }
@@ -60,9 +64,16 @@ impl<S: LogStore> Wal<S> {
             region_id,
             namespace,
             store,
+            prefetch_batches: 0,
         }
     }
 
+    /// Sets max number of entry batches to read ahead while replaying.
+    pub fn with_prefetch_batches(mut self, prefetch_batches: usize) -> Self {
+        self.prefetch_batches = prefetch_batches;
+        self
+    }
+
     pub async fn obsolete(&self, seq: SequenceNumber) -> Result<()> {
         self.store
             .obsolete(self.namespace.clone(), seq)
@@ -73,6 +84,17 @@ impl<S: LogStore> Wal<S> {
             })
     }
 
+    /// Deletes the log files holding only obsolete entries, returns after they are deleted.
+    pub async fn purge(&self) -> Result<()> {
+        self.store
+            .purge(&self.namespace)
+            .await
+            .map_err(BoxedError::new)
+            .context(PurgeWalSnafu {
+                region_id: self.region_id,
+            })
+    }
+
     #[inline]
     pub fn region_id(&self) -> RegionId {
         self.region_id
@@ -133,14 +155,19 @@ impl<S: LogStore> Wal<S> {
     }
 
     pub async fn read_from_wal(&self, start_seq: SequenceNumber) -> Result<PayloadStream<'_>> {
-        let stream = self
-            .store
-            .read(&self.namespace, start_seq)
-            .await
-            .map_err(BoxedError::new)
-            .context(ReadWalSnafu {
-                region_id: self.region_id(),
-            })?
+        let entries = if self.prefetch_batches == 0 {
+            self.store
+                .read(&self.namespace, start_seq)
+                .await
+                .map_err(BoxedError::new)
+                .context(ReadWalSnafu {
+                    region_id: self.region_id(),
+                })?
+        } else {
+            self.prefetch_entries(start_seq)
+        };
+
+        let stream = entries
             // Handle the error when reading from the stream.
             .map_err(|e| Error::ReadWal {
                 region_id: self.region_id(),
@@ -156,6 +183,35 @@ impl<S: LogStore> Wal<S> {
         Ok(Box::pin(stream))
     }
 
+    /// Reads entries in a background task, which reads ahead at most `prefetch_batches`
+    /// batches while the caller processes the current one. Errors of opening the log are
+    /// returned by the stream.
+    fn prefetch_entries(
+        &self,
+        start_seq: SequenceNumber,
+    ) -> SendableEntryStream<'static, S::Entry, S::Error> {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(self.prefetch_batches);
+        let store = self.store.clone();
+        let namespace = self.namespace.clone();
+        common_runtime::spawn_read(async move {
+            let mut entries = match store.read(&namespace, start_seq).await {
+                Ok(entries) => entries,
+                Err(e) => {
+                    let _ = tx.send(Err(e)).await;
+                    return;
+                }
+            };
+            while let Some(batch) = entries.next().await {
+                // Stops reading once the reader is dropped, e.g. replay fails.
+                if tx.send(batch).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        Box::pin(stream::poll_fn(move |cx| rx.poll_recv(cx)))
+    }
+
     async fn write(&self, seq: SequenceNumber, bytes: &[u8]) -> Result<u64> {
         let e = self.store.entry(bytes, seq, self.namespace.clone());
 
@@ -244,11 +300,147 @@ impl Decoder for WalHeaderDecoder {
 
 #[cfg(test)]
 mod tests {
+    use std::time::{Duration, Instant};
+
     use common_test_util::temp_dir::create_temp_dir;
-    use log_store::test_util;
+    use log_store::raft_engine::log_store::RaftEngineLogStore;
+    use log_store::{test_util, LogConfig};
+    use store_api::logstore::{namespace, AppendResponse};
 
     use super::*;
 
+    /// Log store that delays each batch of entries read from the inner store, like a
+    /// remote log store.
+    #[derive(Debug)]
+    struct DelayedLogStore {
+        inner: RaftEngineLogStore,
+        delay: Duration,
+    }
+
+    type RaftEngineError = <RaftEngineLogStore as LogStore>::Error;
+    type RaftEngineNamespace = <RaftEngineLogStore as LogStore>::Namespace;
+    type RaftEngineEntry = <RaftEngineLogStore as LogStore>::Entry;
+
+    #[async_trait::async_trait]
+    impl LogStore for DelayedLogStore {
+        type Error = RaftEngineError;
+        type Namespace = RaftEngineNamespace;
+        type Entry = RaftEngineEntry;
+
+        async fn stop(&self) -> std::result::Result<(), Self::Error> {
+            self.inner.stop().await
+        }
+
+        async fn append(&self, e: Self::Entry) -> std::result::Result<AppendResponse, Self::Error> {
+            self.inner.append(e).await
+        }
+
+        async fn append_batch(
+            &self,
+            ns: &Self::Namespace,
+            e: Vec<Self::Entry>,
+        ) -> std::result::Result<Vec<Id>, Self::Error> {
+            self.inner.append_batch(ns, e).await
+        }
+
+        async fn read(
+            &self,
+            ns: &Self::Namespace,
+            id: Id,
+        ) -> std::result::Result<SendableEntryStream<Self::Entry, Self::Error>, Self::Error>
+        {
+            let delay = self.delay;
+            let entries = self.inner.read(ns, id).await?;
+            Ok(Box::pin(entries.then(move |batch| async move {
+                tokio::time::sleep(delay).await;
+                batch
+            })))
+        }
+
+        async fn create_namespace(
+            &mut self,
+            ns: &Self::Namespace,
+        ) -> std::result::Result<(), Self::Error> {
+            self.inner.create_namespace(ns).await
+        }
+
+        async fn delete_namespace(
+            &mut self,
+            ns: &Self::Namespace,
+        ) -> std::result::Result<(), Self::Error> {
+            self.inner.delete_namespace(ns).await
+        }
+
+        async fn list_namespaces(&self) -> std::result::Result<Vec<Self::Namespace>, Self::Error> {
+            self.inner.list_namespaces().await
+        }
+
+        fn entry<D: AsRef<[u8]>>(&self, data: D, id: Id, ns: Self::Namespace) -> Self::Entry {
+            self.inner.entry(data, id, ns)
+        }
+
+        fn namespace(&self, id: namespace::Id) -> Self::Namespace {
+            self.inner.namespace(id)
+        }
+
+        async fn obsolete(
+            &self,
+            namespace: Self::Namespace,
+            id: Id,
+        ) -> std::result::Result<(), Self::Error> {
+            self.inner.obsolete(namespace, id).await
+        }
+
+        async fn purge(&self, namespace: &Self::Namespace) -> std::result::Result<(), Self::Error> {
+            self.inner.purge(namespace).await
+        }
+    }
+
+    /// Replays all entries in `wal`, processing an entry takes as long as reading it.
+    async fn replay_slowly(wal: &Wal<DelayedLogStore>) -> (Vec<(u64, u64)>, Duration) {
+        let start = Instant::now();
+        let mut stream = wal.read_from_wal(0).await.unwrap();
+        let mut entries = Vec::new();
+        while let Some((seq, header, _)) = stream.try_next().await.unwrap() {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            entries.push((seq, header.last_manifest_version));
+        }
+        (entries, start.elapsed())
+    }
+
+    #[tokio::test]
+    async fn test_read_wal_with_prefetch() {
+        let log_file_dir = create_temp_dir("wal_prefetch");
+        let config = LogConfig {
+            file_size: 128 * 1024,
+            log_file_dir: log_file_dir.path().to_str().unwrap().to_string(),
+            // Reads one entry in each batch.
+            read_batch_size: 1,
+            ..Default::default()
+        };
+        let store = DelayedLogStore {
+            inner: RaftEngineLogStore::try_new(config).await.unwrap(),
+            delay: Duration::from_millis(20),
+        };
+        let wal = Wal::new(0, Arc::new(store));
+        for seq in 0..10 {
+            let header = WalHeader::with_last_manifest_version(seq);
+            wal.write_to_wal(seq, header, None).await.unwrap();
+        }
+
+        let (expect, on_demand) = replay_slowly(&wal).await;
+        assert_eq!((0..10).map(|seq| (seq, seq)).collect::<Vec<_>>(), expect);
+
+        // Reading overlaps processing with prefetch.
+        let wal = wal.with_prefetch_batches(4);
+        let (actual, prefetched) = replay_slowly(&wal).await;
+        assert_eq!(expect, actual);
+        assert!(
+            prefetched < on_demand,
+            "prefetched: {prefetched:?}, on demand: {on_demand:?}"
+        );
+    }
+
     #[tokio::test]
     pub async fn test_write_wal() {
         let log_file_dir = create_temp_dir("wal_test");
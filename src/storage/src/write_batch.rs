@@ -59,6 +59,14 @@ impl Payload {
     pub fn is_empty(&self) -> bool {
         self.mutations.is_empty()
     }
+
+    /// Returns the number of rows of all mutations in the payload.
+    pub fn num_rows(&self) -> usize {
+        self.mutations
+            .iter()
+            .map(|mutation| mutation.record_batch.num_rows())
+            .sum()
+    }
 }
 
 /// A write operation to the region.
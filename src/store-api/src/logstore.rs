@@ -28,8 +28,8 @@ pub mod namespace;
 #[async_trait::async_trait]
 pub trait LogStore: Send + Sync + 'static + std::fmt::Debug {
     type Error: ErrorExt + Send + Sync + 'static;
-    type Namespace: Namespace;
-    type Entry: Entry;
+    type Namespace: Namespace + 'static;
+    type Entry: Entry + 'static;
 
     /// Stop components of logstore.
     async fn stop(&self) -> Result<(), Self::Error>;
@@ -73,6 +73,11 @@ pub trait LogStore: Send + Sync + 'static + std::fmt::Debug {
     /// the log files if all entries inside are obsolete. This method may not delete log
     /// files immediately.
     async fn obsolete(&self, namespace: Self::Namespace, id: Id) -> Result<(), Self::Error>;
+
+    /// Persists the obsolete marks of given `namespace` and deletes the log files whose entries
+    /// are all obsolete, returns after the files are deleted. A log file still holding entries
+    /// of other namespaces is kept.
+    async fn purge(&self, namespace: &Self::Namespace) -> Result<(), Self::Error>;
 }
 
 #[derive(Debug)]
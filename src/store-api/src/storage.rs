@@ -32,7 +32,9 @@ pub use datatypes::schema::{
 
 pub use self::chunk::{Chunk, ChunkReader};
 pub use self::descriptors::*;
-pub use self::engine::{CreateOptions, EngineContext, OpenOptions, StorageEngine};
+pub use self::engine::{
+    CompactionOptions, CreateOptions, DropOptions, EngineContext, OpenOptions, StorageEngine,
+};
 pub use self::metadata::RegionMeta;
 pub use self::region::{FlushContext, Region, WriteContext};
 pub use self::requests::{
@@ -22,6 +22,7 @@ use std::time::Duration;
 
 use async_trait::async_trait;
 use common_error::ext::ErrorExt;
+use serde::{Deserialize, Serialize};
 
 use crate::storage::descriptors::RegionDescriptor;
 use crate::storage::region::Region;
@@ -65,6 +66,7 @@ pub trait StorageEngine: Send + Sync + Clone + 'static {
         &self,
         ctx: &EngineContext,
         region: Self::Region,
+        opts: &DropOptions,
     ) -> Result<(), Self::Error>;
 
     /// Returns the opened region with given name.
@@ -88,6 +90,8 @@ pub struct CreateOptions {
     pub write_buffer_size: Option<usize>,
     /// Region SST files TTL
     pub ttl: Option<Duration>,
+    /// Region compaction options
+    pub compaction: CompactionOptions,
 }
 
 /// Options to open a region.
@@ -99,4 +103,34 @@ pub struct OpenOptions {
     pub write_buffer_size: Option<usize>,
     /// Region SST files TTL
     pub ttl: Option<Duration>,
+    /// Region compaction options
+    pub compaction: CompactionOptions,
+}
+
+/// Compaction options of a region, `None` fields fall back to the config of the engine.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CompactionOptions {
+    /// The region is compacted after a flush once it has more files in level 0.
+    pub max_files_in_l0: Option<usize>,
+    /// Max number of outputs of a compaction of the region written concurrently.
+    pub max_concurrent_outputs: Option<usize>,
+}
+
+impl CompactionOptions {
+    /// Returns options with fields of `other` that are set replacing those of `self`.
+    pub fn merge(&self, other: &CompactionOptions) -> CompactionOptions {
+        CompactionOptions {
+            max_files_in_l0: other.max_files_in_l0.or(self.max_files_in_l0),
+            max_concurrent_outputs: other.max_concurrent_outputs.or(self.max_concurrent_outputs),
+        }
+    }
+}
+
+/// Options to drop a region.
+#[derive(Debug, Clone, Default)]
+pub struct DropOptions {
+    /// Waits until the SST files of the region are deleted if set, returns error if they
+    /// are still not deleted after the timeout. Then purges the WAL of the region.
+    pub purge_timeout: Option<Duration>,
 }
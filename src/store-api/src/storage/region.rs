@@ -32,10 +32,13 @@
 //! a row key. Note that the implementation may allow multiple rows have same row
 //! key (like ClickHouse), which is useful in analytic scenario.
 
+use std::time::Duration;
+
 use async_trait::async_trait;
 use common_error::ext::ErrorExt;
+use common_time::Timestamp;
 
-use crate::storage::engine::OpenOptions;
+use crate::storage::engine::{CompactionOptions, OpenOptions};
 use crate::storage::metadata::RegionMeta;
 use crate::storage::requests::{AlterRequest, WriteRequest};
 use crate::storage::responses::WriteResponse;
@@ -75,10 +78,69 @@ pub trait Region: Send + Sync + Clone + std::fmt::Debug + 'static {
 
     async fn close(&self) -> Result<(), Self::Error>;
 
+    /// Returns bytes allocated by memtables of the region.
+    fn memtable_usage_bytes(&self) -> u64;
+
+    /// Returns the number of rows written to the region since it's opened.
+    fn written_rows(&self) -> u64;
+
+    /// Returns true if writes to the region are delayed or rejected because flushes
+    /// can't keep up with them.
+    fn is_write_stalled(&self) -> bool;
+
     fn disk_usage_bytes(&self) -> u64;
 
+    /// Returns the max timestamp of rows put into the region, `None` if the region has no
+    /// data. It's derived from the time ranges of SSTs and memtables, so it survives
+    /// restarts but isn't lowered by deletes or expired data that is not compacted yet.
+    fn max_timestamp(&self) -> Option<Timestamp>;
+
+    /// Returns the approximate number of rows in the region, i.e. the rows in SSTs and
+    /// memtables. Updated or deleted rows are counted more than once until compacted.
+    fn approximate_rows(&self) -> u64;
+
     /// Flush memtable of the region to disk.
     async fn flush(&self, ctx: &FlushContext) -> Result<(), Self::Error>;
+
+    /// Returns the time-to-live of data in this region, `None` means data never expires.
+    fn ttl(&self) -> Option<Duration>;
+
+    /// Updates the time-to-live of data in this region.
+    ///
+    /// The new ttl takes effect on subsequent reads, flushes and compactions without
+    /// reopening the region. Callers should persist the ttl themselves.
+    fn set_ttl(&self, ttl: Option<Duration>);
+
+    /// Returns the compaction options of this region that override the engine's config.
+    fn compaction_options(&self) -> CompactionOptions;
+
+    /// Updates the compaction options of this region.
+    ///
+    /// Like [Region::set_ttl()], the new options take effect on subsequent flushes and
+    /// compactions without reopening the region, callers should persist them themselves.
+    fn set_compaction_options(&self, options: CompactionOptions);
+
+    /// Begins a bulk load identified by `load_id`.
+    ///
+    /// Writes to a bulk load bypass the WAL and are invisible to reads until the bulk load
+    /// is committed. Uncommitted bulk loads are discarded if the region is closed or the
+    /// process crashes. Rows of a bulk load override rows written to the region before it
+    /// begins, and are overridden by rows written after it begins.
+    async fn begin_bulk_load(&self, load_id: &str) -> Result<(), Self::Error>;
+
+    /// Writes updates to the bulk load `load_id`.
+    async fn write_bulk_load(
+        &self,
+        load_id: &str,
+        request: Self::WriteRequest,
+    ) -> Result<WriteResponse, Self::Error>;
+
+    /// Flushes rows of the bulk load `load_id` to SST files and adds them to the region
+    /// atomically, so they are visible all at once.
+    async fn commit_bulk_load(&self, load_id: &str) -> Result<(), Self::Error>;
+
+    /// Discards the bulk load `load_id` and rows written to it.
+    async fn abort_bulk_load(&self, load_id: &str) -> Result<(), Self::Error>;
 }
 
 /// Context for write operations.
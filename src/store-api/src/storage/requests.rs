@@ -16,6 +16,7 @@ use std::collections::{HashMap, HashSet};
 
 use common_error::ext::ErrorExt;
 use common_query::logical_plan::Expr;
+use common_recordbatch::scan_stats::ScanStatsRecorderRef;
 use datatypes::vectors::VectorRef;
 
 use crate::storage::{ColumnDescriptor, RegionDescriptor, SequenceNumber};
@@ -49,6 +50,8 @@ pub struct ScanRequest {
     pub projection: Option<Vec<usize>>,
     /// Filters pushed down
     pub filters: Vec<Expr>,
+    /// Records the stats of the scan if present.
+    pub scan_stats: Option<ScanStatsRecorderRef>,
 }
 
 #[derive(Debug)]
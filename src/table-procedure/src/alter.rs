@@ -0,0 +1,438 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Procedure to alter a table.
+
+use async_trait::async_trait;
+use catalog::{CatalogManagerRef, RenameTableRequest};
+use common_procedure::{Context, Error, LockKey, Procedure, ProcedureManager, Result, Status};
+use common_telemetry::logging;
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, OptionExt, ResultExt};
+use table::engine::{EngineContext, TableEngineRef, TableReference};
+use table::metadata::TableId;
+use table::requests::{AlterKind, AlterTableRequest};
+use table::TableRef;
+
+use crate::error::{
+    AccessCatalogSnafu, CatalogNotFoundSnafu, DeserializeProcedureSnafu, SchemaNotFoundSnafu,
+    SerializeProcedureSnafu, TableExistsSnafu, TableNotFoundSnafu,
+};
+
+/// Procedure to alter a table.
+pub struct AlterTableProcedure {
+    data: AlterTableData,
+    catalog_manager: CatalogManagerRef,
+    table_engine: TableEngineRef,
+}
+
+#[async_trait]
+impl Procedure for AlterTableProcedure {
+    fn type_name(&self) -> &str {
+        Self::TYPE_NAME
+    }
+
+    async fn execute(&mut self, _ctx: &Context) -> Result<Status> {
+        match self.data.state {
+            AlterTableState::Prepare => self.on_prepare().await,
+            AlterTableState::EngineAlterTable => self.on_engine_alter_table().await,
+            AlterTableState::RenameInCatalog => self.on_rename_in_catalog().await,
+        }
+    }
+
+    fn dump(&self) -> Result<String> {
+        let json = serde_json::to_string(&self.data).context(SerializeProcedureSnafu)?;
+        Ok(json)
+    }
+
+    fn lock_key(&self) -> LockKey {
+        // We lock the whole table, and also the new name if we are renaming the table so
+        // another procedure can't take the name at the same time.
+        let table_name = self.data.table_ref().to_string();
+        match self.data.new_table_ref() {
+            Some(new_table_ref) => LockKey::new([table_name, new_table_ref.to_string()]),
+            None => LockKey::single(table_name),
+        }
+    }
+}
+
+impl AlterTableProcedure {
+    const TYPE_NAME: &str = "table-procedures::AlterTableProcedure";
+
+    /// Returns a new [AlterTableProcedure].
+    pub fn new(
+        request: AlterTableRequest,
+        catalog_manager: CatalogManagerRef,
+        table_engine: TableEngineRef,
+    ) -> AlterTableProcedure {
+        AlterTableProcedure {
+            data: AlterTableData {
+                state: AlterTableState::Prepare,
+                request,
+                table_id: None,
+            },
+            catalog_manager,
+            table_engine,
+        }
+    }
+
+    /// Register the loader of this procedure to the `procedure_manager`.
+    ///
+    /// # Panics
+    /// Panics on error.
+    pub fn register_loader(
+        catalog_manager: CatalogManagerRef,
+        table_engine: TableEngineRef,
+        procedure_manager: &dyn ProcedureManager,
+    ) {
+        procedure_manager
+            .register_loader(
+                Self::TYPE_NAME,
+                Box::new(move |data| {
+                    Self::from_json(data, catalog_manager.clone(), table_engine.clone())
+                        .map(|p| Box::new(p) as _)
+                }),
+            )
+            .unwrap()
+    }
+
+    /// Recover the procedure from json.
+    fn from_json(
+        json: &str,
+        catalog_manager: CatalogManagerRef,
+        table_engine: TableEngineRef,
+    ) -> Result<Self> {
+        let data: AlterTableData = serde_json::from_str(json).context(DeserializeProcedureSnafu)?;
+
+        Ok(AlterTableProcedure {
+            data,
+            catalog_manager,
+            table_engine,
+        })
+    }
+
+    async fn on_prepare(&mut self) -> Result<Status> {
+        let request = &self.data.request;
+        let catalog = self
+            .catalog_manager
+            .catalog(&request.catalog_name)
+            .context(AccessCatalogSnafu)?
+            .context(CatalogNotFoundSnafu {
+                name: &request.catalog_name,
+            })?;
+        let schema = catalog
+            .schema(&request.schema_name)
+            .context(AccessCatalogSnafu)?
+            .context(SchemaNotFoundSnafu {
+                name: &request.schema_name,
+            })?;
+
+        let table = schema
+            .table(&request.table_name)
+            .await
+            .context(AccessCatalogSnafu)?
+            .with_context(|| TableNotFoundSnafu {
+                name: self.data.table_ref().to_string(),
+            })?;
+        if let AlterKind::RenameTable { new_table_name } = &request.alter_kind {
+            let new_table_exists = schema
+                .table(new_table_name)
+                .await
+                .context(AccessCatalogSnafu)?
+                .is_some();
+            ensure!(
+                !new_table_exists,
+                TableExistsSnafu {
+                    name: new_table_name,
+                }
+            );
+        }
+
+        self.data.table_id = Some(table.table_info().ident.table_id);
+        self.data.state = AlterTableState::EngineAlterTable;
+
+        Ok(Status::executing(true))
+    }
+
+    async fn on_engine_alter_table(&mut self) -> Result<Status> {
+        let engine_ctx = EngineContext::default();
+        let request = &self.data.request;
+        let table = self
+            .table_engine
+            .get_table(&engine_ctx, &self.data.table_ref())
+            .map_err(Error::external)?;
+        let altered = match (&request.alter_kind, table) {
+            // The table is already renamed in the engine if the procedure is recovered
+            // after this step.
+            (AlterKind::RenameTable { .. }, None) => self.new_table(&engine_ctx)?.is_some(),
+            (alter_kind, Some(table)) => is_altered(alter_kind, &table),
+            (_, None) => TableNotFoundSnafu {
+                name: self.data.table_ref().to_string(),
+            }
+            .fail()?,
+        };
+        if !altered {
+            self.table_engine
+                .alter_table(&engine_ctx, request.clone())
+                .await
+                .map_err(Error::external)?;
+        }
+        logging::info!(
+            "On engine alter table {}, done, request: {:?}",
+            self.data.table_ref(),
+            request.alter_kind
+        );
+
+        if !request.is_rename_table() {
+            return Ok(Status::Done);
+        }
+        self.data.state = AlterTableState::RenameInCatalog;
+        Ok(Status::executing(true))
+    }
+
+    async fn on_rename_in_catalog(&mut self) -> Result<Status> {
+        let request = &self.data.request;
+        let AlterKind::RenameTable { new_table_name } = &request.alter_kind else {
+            return Ok(Status::Done);
+        };
+        let schema = self
+            .catalog_manager
+            .schema(&request.catalog_name, &request.schema_name)
+            .context(AccessCatalogSnafu)?
+            .context(SchemaNotFoundSnafu {
+                name: &request.schema_name,
+            })?;
+        let renamed = schema
+            .table(new_table_name)
+            .await
+            .context(AccessCatalogSnafu)?
+            .is_some();
+        if renamed {
+            return Ok(Status::Done);
+        }
+
+        let rename_req = RenameTableRequest {
+            catalog: request.catalog_name.clone(),
+            schema: request.schema_name.clone(),
+            table_name: request.table_name.clone(),
+            new_table_name: new_table_name.clone(),
+            // Safety: The table id is always set after the prepare step.
+            table_id: self.data.table_id.unwrap(),
+        };
+        self.catalog_manager
+            .rename_table(rename_req)
+            .await
+            .map_err(Error::external)?;
+
+        Ok(Status::Done)
+    }
+
+    fn new_table(&self, engine_ctx: &EngineContext) -> Result<Option<TableRef>> {
+        let Some(new_table_ref) = self.data.new_table_ref() else {
+            return Ok(None);
+        };
+        self.table_engine
+            .get_table(engine_ctx, &new_table_ref)
+            .map_err(Error::external)
+    }
+}
+
+/// Returns whether `alter_kind` is already applied to the `table`.
+fn is_altered(alter_kind: &AlterKind, table: &TableRef) -> bool {
+    let schema = table.schema();
+    match alter_kind {
+        AlterKind::AddColumns { columns } => columns
+            .iter()
+            .all(|c| schema.contains_column(&c.column_schema.name)),
+        AlterKind::DropColumns { names } => names.iter().all(|name| !schema.contains_column(name)),
+        // The table still has the old name.
+        AlterKind::RenameTable { .. } => false,
+        // Setting ttl or compaction options is idempotent.
+        AlterKind::SetTtl { .. } | AlterKind::SetCompactionOptions { .. } => false,
+    }
+}
+
+/// Represents each step while altering a table in the datanode.
+#[derive(Debug, Serialize, Deserialize)]
+enum AlterTableState {
+    /// Validate request and prepare to alter table.
+    Prepare,
+    /// Alter table in the table engine.
+    EngineAlterTable,
+    /// Rename the table in the catalog, only for renaming the table.
+    RenameInCatalog,
+}
+
+/// Serializable data of [AlterTableProcedure].
+#[derive(Debug, Serialize, Deserialize)]
+struct AlterTableData {
+    /// Current state.
+    state: AlterTableState,
+    /// Request to alter this table.
+    request: AlterTableRequest,
+    /// Id of the table, which is set in the [AlterTableState::Prepare] state.
+    table_id: Option<TableId>,
+}
+
+impl AlterTableData {
+    fn table_ref(&self) -> TableReference {
+        TableReference {
+            catalog: &self.request.catalog_name,
+            schema: &self.request.schema_name,
+            table: &self.request.table_name,
+        }
+    }
+
+    fn new_table_ref(&self) -> Option<TableReference> {
+        match &self.request.alter_kind {
+            AlterKind::RenameTable { new_table_name } => Some(TableReference {
+                catalog: &self.request.catalog_name,
+                schema: &self.request.schema_name,
+                table: new_table_name,
+            }),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use catalog::RegisterTableRequest;
+    use common_procedure::ProcedureWithId;
+    use datatypes::prelude::ConcreteDataType;
+    use datatypes::schema::{ColumnSchema, RawSchema};
+    use table::engine::TableEngine;
+    use table::requests::CreateTableRequest;
+
+    use super::*;
+    use crate::test_util::TestEnv;
+
+    fn new_create_request(table_id: TableId, table_name: &str) -> CreateTableRequest {
+        let column_schemas = vec![
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new("cpu", ConcreteDataType::float64_datatype(), true),
+            ColumnSchema::new(
+                "ts",
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                true,
+            )
+            .with_time_index(true),
+        ];
+        CreateTableRequest {
+            id: table_id,
+            catalog_name: "greptime".to_string(),
+            schema_name: "public".to_string(),
+            table_name: table_name.to_string(),
+            desc: None,
+            schema: RawSchema::new(column_schemas),
+            region_numbers: vec![0],
+            create_if_not_exists: true,
+            primary_key_indices: vec![0],
+            table_options: Default::default(),
+        }
+    }
+
+    async fn create_table(env: &TestEnv, table_id: TableId, table_name: &str) {
+        let request = new_create_request(table_id, table_name);
+        let table = env
+            .table_engine
+            .create_table(&EngineContext::default(), request.clone())
+            .await
+            .unwrap();
+        env.catalog_manager
+            .register_table(RegisterTableRequest {
+                catalog: request.catalog_name,
+                schema: request.schema_name,
+                table_name: request.table_name,
+                table_id: request.id,
+                table,
+            })
+            .await
+            .unwrap();
+    }
+
+    fn new_rename_request(table_name: &str, new_table_name: &str) -> AlterTableRequest {
+        AlterTableRequest {
+            catalog_name: "greptime".to_string(),
+            schema_name: "public".to_string(),
+            table_name: table_name.to_string(),
+            alter_kind: AlterKind::RenameTable {
+                new_table_name: new_table_name.to_string(),
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rename_table_procedure() {
+        let env = TestEnv::new("rename");
+        create_table(&env, 1, "demo").await;
+
+        let procedure = AlterTableProcedure::new(
+            new_rename_request("demo", "demo_new"),
+            env.catalog_manager.clone(),
+            env.table_engine.clone(),
+        );
+        let procedure_with_id = ProcedureWithId::with_random_id(Box::new(procedure));
+        let mut watcher = env
+            .procedure_manager
+            .submit(procedure_with_id)
+            .await
+            .unwrap();
+        watcher.changed().await.unwrap();
+
+        let schema = env
+            .catalog_manager
+            .schema("greptime", "public")
+            .unwrap()
+            .unwrap();
+        assert!(schema.table("demo").await.unwrap().is_none());
+        let table = schema.table("demo_new").await.unwrap().unwrap();
+        assert_eq!("demo_new", table.table_info().name);
+        assert_eq!(vec!["demo_new".to_string()], schema.table_names().unwrap());
+
+        let engine_ctx = EngineContext::default();
+        let table_ref = TableReference {
+            catalog: "greptime",
+            schema: "public",
+            table: "demo_new",
+        };
+        assert!(env
+            .table_engine
+            .get_table(&engine_ctx, &table_ref)
+            .unwrap()
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn test_rename_table_to_existing_table() {
+        let env = TestEnv::new("rename_existing");
+        create_table(&env, 1, "demo").await;
+        create_table(&env, 2, "demo_other").await;
+
+        let mut procedure = AlterTableProcedure::new(
+            new_rename_request("demo", "demo_other"),
+            env.catalog_manager.clone(),
+            env.table_engine.clone(),
+        );
+        let err = procedure.on_prepare().await.unwrap_err();
+        assert!(err.to_string().contains("demo_other"), "{err}");
+
+        let schema = env
+            .catalog_manager
+            .schema("greptime", "public")
+            .unwrap()
+            .unwrap();
+        assert!(schema.table("demo").await.unwrap().is_some());
+    }
+}
@@ -50,6 +50,12 @@ pub enum Error {
     #[snafu(display("Schema {} not found", name))]
     SchemaNotFound { name: String },
 
+    #[snafu(display("Table {} not found", name))]
+    TableNotFound { name: String },
+
+    #[snafu(display("Table {} already exists", name))]
+    TableExists { name: String },
+
     #[snafu(display("Subprocedure {} failed", subprocedure_id))]
     SubprocedureFailed {
         subprocedure_id: ProcedureId,
@@ -70,6 +76,8 @@ impl ErrorExt for Error {
             InvalidRawSchema { source, .. } => source.status_code(),
             AccessCatalog { source } => source.status_code(),
             CatalogNotFound { .. } | SchemaNotFound { .. } => StatusCode::InvalidArguments,
+            TableNotFound { .. } => StatusCode::TableNotFound,
+            TableExists { .. } => StatusCode::TableAlreadyExists,
         }
     }
 
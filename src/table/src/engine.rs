@@ -18,9 +18,12 @@ use std::sync::Arc;
 use common_procedure::BoxedProcedure;
 use store_api::storage::RegionId;
 
-use crate::error::Result;
+use crate::error::{Result, UnsupportedSnafu};
 use crate::metadata::TableId;
-use crate::requests::{AlterTableRequest, CreateTableRequest, DropTableRequest, OpenTableRequest};
+use crate::requests::{
+    AlterTableRequest, CloseRegionsRequest, CreateTableRequest, DropTableRequest,
+    OpenRegionsRequest, OpenTableRequest,
+};
 use crate::TableRef;
 
 /// Represents a resolved path to a table of the form “catalog.schema.table”
@@ -81,6 +84,38 @@ pub trait TableEngine: Send + Sync {
         request: OpenTableRequest,
     ) -> Result<Option<TableRef>>;
 
+    /// Open the given regions of an existing table, the table is opened first if it isn't opened
+    /// yet, and then only serves the regions opened. Returns the table, or `Ok(None)` if the
+    /// table does not exist.
+    ///
+    /// Engines without regions open the whole table.
+    async fn open_regions(
+        &self,
+        ctx: &EngineContext,
+        request: OpenRegionsRequest,
+    ) -> Result<Option<TableRef>> {
+        let request = OpenTableRequest {
+            catalog_name: request.catalog_name,
+            schema_name: request.schema_name,
+            table_name: request.table_name,
+            table_id: request.table_id,
+        };
+        self.open_table(ctx, request).await
+    }
+
+    /// Flush and close the given regions of a table, the table is closed once all its regions
+    /// are closed. Closing regions of a table that does not exist is a no-op.
+    async fn close_regions(
+        &self,
+        _ctx: &EngineContext,
+        _request: CloseRegionsRequest,
+    ) -> Result<()> {
+        UnsupportedSnafu {
+            operation: "close regions",
+        }
+        .fail()
+    }
+
     /// Alter table schema, options etc. by given request,
     ///
     /// Returns the table after altered.
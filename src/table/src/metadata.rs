@@ -13,17 +13,20 @@
 // limitations under the License.
 
 use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
 use std::sync::Arc;
+use std::time::Duration;
 
 use chrono::{DateTime, Utc};
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
-use datafusion_expr::TableProviderFilterPushDown;
+use datafusion_expr::{AggregateFunction, TableProviderFilterPushDown};
 pub use datatypes::error::{Error as ConvertError, Result as ConvertResult};
+use datatypes::prelude::ConcreteDataType;
 use datatypes::schema::{ColumnSchema, RawSchema, Schema, SchemaBuilder, SchemaRef};
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
 use snafu::{ensure, ResultExt};
-use store_api::storage::{ColumnDescriptor, ColumnDescriptorBuilder, ColumnId};
+use store_api::storage::{ColumnDescriptor, ColumnDescriptorBuilder, ColumnId, CompactionOptions};
 
 use crate::error::{self, Result};
 use crate::requests::{AddColumnRequest, AlterKind, TableOptions};
@@ -68,6 +71,91 @@ impl From<FilterPushDownType> for TableProviderFilterPushDown {
     }
 }
 
+/// Operators above a table scan that the table may evaluate in the scan, so they
+/// are evaluated where the data lives. See [`Table::supports_pushdown`](crate::Table::supports_pushdown).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScanPushdown {
+    /// Returns at most `limit` rows of each partition, ordered by the time index.
+    TopN { limit: usize, descending: bool },
+    /// Returns the partial results of the aggregate functions over the time index,
+    /// at most one row for each partition.
+    TimeIndexAggregate { aggregates: Vec<TimeIndexAggregate> },
+}
+
+impl ScanPushdown {
+    /// Returns the schema of the rows returned by the scan with this pushdown, or
+    /// `None` if the table has no time index.
+    pub fn output_schema(&self, table_schema: &SchemaRef) -> Option<SchemaRef> {
+        let time_index = table_schema.timestamp_column()?;
+        match self {
+            ScanPushdown::TopN { .. } => Some(table_schema.clone()),
+            ScanPushdown::TimeIndexAggregate { aggregates } => {
+                let column_schemas = aggregates
+                    .iter()
+                    .map(|aggregate| {
+                        let data_type = match aggregate {
+                            TimeIndexAggregate::Min | TimeIndexAggregate::Max => {
+                                time_index.data_type.clone()
+                            }
+                            TimeIndexAggregate::Count => ConcreteDataType::int64_datatype(),
+                        };
+                        ColumnSchema::new(aggregate.column_name(&time_index.name), data_type, true)
+                    })
+                    .collect();
+                Some(Arc::new(Schema::new(column_schemas)))
+            }
+        }
+    }
+}
+
+impl Display for ScanPushdown {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScanPushdown::TopN { limit, descending } => {
+                let order = if *descending { "desc" } else { "asc" };
+                write!(f, "TopN(limit={limit}, order={order})")
+            }
+            ScanPushdown::TimeIndexAggregate { aggregates } => {
+                let names = aggregates.iter().map(|a| a.name()).collect::<Vec<_>>();
+                write!(f, "TimeIndexAggregate({})", names.join(", "))
+            }
+        }
+    }
+}
+
+/// Aggregate functions over the time index that can be pushed down, the partial
+/// results are merged by `min`, `max` and `sum` respectively.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TimeIndexAggregate {
+    Min,
+    Max,
+    Count,
+}
+
+impl TimeIndexAggregate {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TimeIndexAggregate::Min => "min",
+            TimeIndexAggregate::Max => "max",
+            TimeIndexAggregate::Count => "count",
+        }
+    }
+
+    pub fn aggregate_function(&self) -> AggregateFunction {
+        match self {
+            TimeIndexAggregate::Min => AggregateFunction::Min,
+            TimeIndexAggregate::Max => AggregateFunction::Max,
+            TimeIndexAggregate::Count => AggregateFunction::Count,
+        }
+    }
+
+    /// Returns the name of the column holding the partial result, which is also the
+    /// name DataFusion gives to the aggregate over the time index column.
+    pub fn column_name(&self, time_index: &str) -> String {
+        format!("{}({})", self.aggregate_function(), time_index)
+    }
+}
+
 /// Indicates the type of this table for metadata/catalog purposes.
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TableType {
@@ -163,6 +251,8 @@ impl TableMeta {
             AlterKind::DropColumns { names } => self.remove_columns(table_name, names),
             // No need to rebuild table meta when renaming tables.
             AlterKind::RenameTable { .. } => Ok(TableMetaBuilder::default()),
+            AlterKind::SetTtl { ttl } => Ok(self.set_ttl(*ttl)),
+            AlterKind::SetCompactionOptions { options } => Ok(self.set_compaction_options(options)),
         }
     }
 
@@ -206,6 +296,32 @@ impl TableMeta {
         builder
     }
 
+    fn set_ttl(&self, ttl: Option<Duration>) -> TableMetaBuilder {
+        let mut meta_builder = self.new_meta_builder();
+        let mut options = self.options.clone();
+        options.ttl = ttl;
+        meta_builder
+            .schema(self.schema.clone())
+            .primary_key_indices(self.primary_key_indices.clone())
+            .value_indices(self.value_indices.clone())
+            .options(options);
+
+        meta_builder
+    }
+
+    fn set_compaction_options(&self, compaction: &CompactionOptions) -> TableMetaBuilder {
+        let mut meta_builder = self.new_meta_builder();
+        let mut options = self.options.clone();
+        options.compaction = options.compaction.merge(compaction);
+        meta_builder
+            .schema(self.schema.clone())
+            .primary_key_indices(self.primary_key_indices.clone())
+            .value_indices(self.value_indices.clone())
+            .options(options);
+
+        meta_builder
+    }
+
     fn add_columns(
         &self,
         table_name: &str,
@@ -589,6 +705,82 @@ mod tests {
         assert_eq!(&[1, 2, 4], &new_meta.value_indices[..]);
     }
 
+    #[test]
+    fn test_set_ttl() {
+        let schema = Arc::new(new_test_schema());
+        let meta = TableMetaBuilder::default()
+            .schema(schema)
+            .primary_key_indices(vec![0])
+            .engine("engine")
+            .next_column_id(3)
+            .build()
+            .unwrap();
+
+        let alter_kind = AlterKind::SetTtl {
+            ttl: Some(Duration::from_secs(3600)),
+        };
+        let new_meta = meta
+            .builder_with_alter_kind("my_table", &alter_kind)
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(Some(Duration::from_secs(3600)), new_meta.options.ttl);
+        assert_eq!(meta.schema, new_meta.schema);
+        assert_eq!(meta.primary_key_indices, new_meta.primary_key_indices);
+        assert_eq!(meta.value_indices, new_meta.value_indices);
+        assert_eq!(meta.next_column_id, new_meta.next_column_id);
+
+        let alter_kind = AlterKind::SetTtl { ttl: None };
+        let new_meta = new_meta
+            .builder_with_alter_kind("my_table", &alter_kind)
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(None, new_meta.options.ttl);
+    }
+
+    #[test]
+    fn test_set_compaction_options() {
+        let schema = Arc::new(new_test_schema());
+        let meta = TableMetaBuilder::default()
+            .schema(schema)
+            .primary_key_indices(vec![0])
+            .engine("engine")
+            .next_column_id(3)
+            .build()
+            .unwrap();
+
+        let alter_kind = AlterKind::SetCompactionOptions {
+            options: CompactionOptions {
+                max_files_in_l0: Some(4),
+                max_concurrent_outputs: Some(2),
+            },
+        };
+        let new_meta = meta
+            .builder_with_alter_kind("my_table", &alter_kind)
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(Some(4), new_meta.options.compaction.max_files_in_l0);
+        assert_eq!(Some(2), new_meta.options.compaction.max_concurrent_outputs);
+        assert_eq!(meta.schema, new_meta.schema);
+
+        // Options that are not set are left unchanged.
+        let alter_kind = AlterKind::SetCompactionOptions {
+            options: CompactionOptions {
+                max_files_in_l0: Some(16),
+                max_concurrent_outputs: None,
+            },
+        };
+        let new_meta = new_meta
+            .builder_with_alter_kind("my_table", &alter_kind)
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(Some(16), new_meta.options.compaction.max_files_in_l0);
+        assert_eq!(Some(2), new_meta.options.compaction.max_concurrent_outputs);
+    }
+
     #[test]
     fn test_remove_columns() {
         let schema = Arc::new(new_test_schema());
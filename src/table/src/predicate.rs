@@ -17,7 +17,7 @@ use common_telemetry::{error, warn};
 use common_time::range::TimestampRange;
 use common_time::Timestamp;
 use datafusion::parquet::file::metadata::RowGroupMetaData;
-use datafusion::physical_optimizer::pruning::PruningPredicate;
+use datafusion::physical_optimizer::pruning::{PruningPredicate, PruningStatistics};
 use datafusion_common::ToDFSchema;
 use datafusion_expr::{Between, BinaryExpr, Operator};
 use datafusion_physical_expr::create_physical_expr;
@@ -48,13 +48,25 @@ impl Predicate {
         schema: SchemaRef,
         row_groups: &[RowGroupMetaData],
     ) -> Vec<bool> {
-        let mut res = vec![true; row_groups.len()];
+        let stats = RowGroupPruningStatistics::new(row_groups, &schema);
+        self.prune_with_stats(&schema, &stats)
+    }
+
+    /// Prunes the containers described by `stats`, returns whether each container may
+    /// contain rows matching the predicate. Columns without statistics never prune
+    /// containers.
+    pub fn prune_with_stats<S: PruningStatistics>(
+        &self,
+        schema: &SchemaRef,
+        stats: &S,
+    ) -> Vec<bool> {
+        let mut res = vec![true; stats.num_containers()];
         let arrow_schema = (*schema.arrow_schema()).clone();
         let df_schema = arrow_schema.clone().to_dfschema_ref();
         let df_schema = match df_schema {
             Ok(x) => x,
             Err(e) => {
-                warn!("Failed to create Datafusion schema when trying to prune row groups, error: {e}");
+                warn!("Failed to create Datafusion schema when trying to prune, error: {e}");
                 return res;
             }
         };
@@ -69,19 +81,16 @@ impl Predicate {
             )
             .and_then(|expr| PruningPredicate::try_new(expr, arrow_schema.clone()))
             {
-                Ok(p) => {
-                    let stat = RowGroupPruningStatistics::new(row_groups, &schema);
-                    match p.prune(&stat) {
-                        Ok(r) => {
-                            for (curr_val, res) in r.into_iter().zip(res.iter_mut()) {
-                                *res &= curr_val
-                            }
-                        }
-                        Err(e) => {
-                            warn!("Failed to prune row groups, error: {:?}", e);
+                Ok(p) => match p.prune(stats) {
+                    Ok(r) => {
+                        for (curr_val, res) in r.into_iter().zip(res.iter_mut()) {
+                            *res &= curr_val
                         }
                     }
-                }
+                    Err(e) => {
+                        warn!("Failed to prune, error: {:?}", e);
+                    }
+                },
                 Err(e) => {
                     error!("Failed to create predicate for expr, error: {:?}", e);
                 }
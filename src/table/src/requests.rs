@@ -21,7 +21,7 @@ use common_base::readable_size::ReadableSize;
 use datatypes::prelude::VectorRef;
 use datatypes::schema::{ColumnSchema, RawSchema};
 use serde::{Deserialize, Serialize};
-use store_api::storage::RegionNumber;
+use store_api::storage::{CompactionOptions, RegionNumber};
 
 use crate::error;
 use crate::error::ParseTableOptionSnafu;
@@ -66,12 +66,33 @@ pub struct TableOptions {
     /// Time-to-live of table. Expired data will be automatically purged.
     #[serde(with = "humantime_serde")]
     pub ttl: Option<Duration>,
+    /// Compaction options of the table that override the config of the storage engine.
+    pub compaction: CompactionOptions,
     /// Extra options that may not applicable to all table engines.
     pub extra_options: HashMap<String, String>,
 }
 
 pub const WRITE_BUFFER_SIZE_KEY: &str = "write_buffer_size";
 pub const TTL_KEY: &str = "ttl";
+pub const COMPACTION_MAX_FILES_IN_LEVEL0_KEY: &str = "compaction_max_files_in_level0";
+pub const COMPACTION_MAX_INFLIGHT_KEY: &str = "compaction_max_inflight";
+
+/// Returns true if `key` is an option of compaction, which could be altered at runtime.
+pub fn is_compaction_option(key: &str) -> bool {
+    key == COMPACTION_MAX_FILES_IN_LEVEL0_KEY || key == COMPACTION_MAX_INFLIGHT_KEY
+}
+
+/// Parses the value of option `key` as a number, which must be positive if `positive` is set.
+fn parse_number_option(
+    key: &'static str,
+    value: &str,
+    positive: bool,
+) -> Result<usize, error::Error> {
+    match value.parse::<usize>() {
+        Ok(n) if n > 0 || !positive => Ok(n),
+        _ => ParseTableOptionSnafu { key, value }.fail(),
+    }
+}
 
 impl TryFrom<&HashMap<String, String>> for TableOptions {
     type Error = error::Error;
@@ -102,8 +123,23 @@ impl TryFrom<&HashMap<String, String>> for TableOptions {
                 .into();
             options.ttl = Some(ttl_value);
         }
+
+        if let Some(max_files) = value.get(COMPACTION_MAX_FILES_IN_LEVEL0_KEY) {
+            options.compaction.max_files_in_l0 = Some(parse_number_option(
+                COMPACTION_MAX_FILES_IN_LEVEL0_KEY,
+                max_files,
+                false,
+            )?);
+        }
+        if let Some(max_inflight) = value.get(COMPACTION_MAX_INFLIGHT_KEY) {
+            options.compaction.max_concurrent_outputs = Some(parse_number_option(
+                COMPACTION_MAX_INFLIGHT_KEY,
+                max_inflight,
+                true,
+            )?);
+        }
         options.extra_options = HashMap::from_iter(value.iter().filter_map(|(k, v)| {
-            if k != WRITE_BUFFER_SIZE_KEY && k != TTL_KEY {
+            if k != WRITE_BUFFER_SIZE_KEY && k != TTL_KEY && !is_compaction_option(k) {
                 Some((k.clone(), v.clone()))
             } else {
                 None
@@ -115,7 +151,7 @@ impl TryFrom<&HashMap<String, String>> for TableOptions {
 
 impl From<&TableOptions> for HashMap<String, String> {
     fn from(opts: &TableOptions) -> Self {
-        let mut res = HashMap::with_capacity(2 + opts.extra_options.len());
+        let mut res = HashMap::with_capacity(4 + opts.extra_options.len());
         if let Some(write_buffer_size) = opts.write_buffer_size {
             res.insert(
                 WRITE_BUFFER_SIZE_KEY.to_string(),
@@ -126,6 +162,18 @@ impl From<&TableOptions> for HashMap<String, String> {
             let ttl_str = humantime::format_duration(ttl).to_string();
             res.insert(TTL_KEY.to_string(), ttl_str);
         }
+        if let Some(max_files) = opts.compaction.max_files_in_l0 {
+            res.insert(
+                COMPACTION_MAX_FILES_IN_LEVEL0_KEY.to_string(),
+                max_files.to_string(),
+            );
+        }
+        if let Some(max_inflight) = opts.compaction.max_concurrent_outputs {
+            res.insert(
+                COMPACTION_MAX_INFLIGHT_KEY.to_string(),
+                max_inflight.to_string(),
+            );
+        }
         res.extend(
             opts.extra_options
                 .iter()
@@ -144,8 +192,27 @@ pub struct OpenTableRequest {
     pub table_id: TableId,
 }
 
+/// Open regions request, opens the given regions of an existing table.
+#[derive(Debug, Clone)]
+pub struct OpenRegionsRequest {
+    pub catalog_name: String,
+    pub schema_name: String,
+    pub table_name: String,
+    pub table_id: TableId,
+    pub region_numbers: Vec<RegionNumber>,
+}
+
+/// Close regions request, flushes and closes the given regions of a table.
+#[derive(Debug, Clone)]
+pub struct CloseRegionsRequest {
+    pub catalog_name: String,
+    pub schema_name: String,
+    pub table_name: String,
+    pub region_numbers: Vec<RegionNumber>,
+}
+
 /// Alter table request
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AlterTableRequest {
     pub catalog_name: String,
     pub schema_name: String,
@@ -160,17 +227,30 @@ impl AlterTableRequest {
 }
 
 /// Add column request
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AddColumnRequest {
     pub column_schema: ColumnSchema,
     pub is_key: bool,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum AlterKind {
-    AddColumns { columns: Vec<AddColumnRequest> },
-    DropColumns { names: Vec<String> },
-    RenameTable { new_table_name: String },
+    AddColumns {
+        columns: Vec<AddColumnRequest>,
+    },
+    DropColumns {
+        names: Vec<String>,
+    },
+    RenameTable {
+        new_table_name: String,
+    },
+    SetTtl {
+        ttl: Option<Duration>,
+    },
+    /// Sets compaction options of the table, options that are `None` are left unchanged.
+    SetCompactionOptions {
+        options: CompactionOptions,
+    },
 }
 
 /// Drop table request
@@ -179,6 +259,8 @@ pub struct DropTableRequest {
     pub catalog_name: String,
     pub schema_name: String,
     pub table_name: String,
+    /// Waits until the data of the table is physically deleted.
+    pub sync: bool,
 }
 
 /// Delete (by primary key) request
@@ -204,6 +286,8 @@ pub struct CopyTableRequest {
     pub table_name: String,
     pub location: String,
     pub connection: HashMap<String, String>,
+    /// Options in `WITH`, keys are in lowercase.
+    pub with: HashMap<String, String>,
     pub pattern: Option<String>,
     pub direction: CopyDirection,
 }
@@ -227,6 +311,10 @@ mod tests {
         let options = TableOptions {
             write_buffer_size: None,
             ttl: Some(Duration::from_secs(1000)),
+            compaction: CompactionOptions {
+                max_files_in_l0: Some(4),
+                max_concurrent_outputs: None,
+            },
             extra_options: HashMap::new(),
         };
         let serialized = serde_json::to_string(&options).unwrap();
@@ -239,6 +327,7 @@ mod tests {
         let options = TableOptions {
             write_buffer_size: Some(ReadableSize::mb(128)),
             ttl: Some(Duration::from_secs(1000)),
+            compaction: CompactionOptions::default(),
             extra_options: HashMap::new(),
         };
         let serialized_map = HashMap::from(&options);
@@ -248,6 +337,7 @@ mod tests {
         let options = TableOptions {
             write_buffer_size: None,
             ttl: None,
+            compaction: CompactionOptions::default(),
             extra_options: HashMap::new(),
         };
         let serialized_map = HashMap::from(&options);
@@ -257,10 +347,38 @@ mod tests {
         let options = TableOptions {
             write_buffer_size: Some(ReadableSize::mb(128)),
             ttl: Some(Duration::from_secs(1000)),
+            compaction: CompactionOptions {
+                max_files_in_l0: Some(4),
+                max_concurrent_outputs: Some(2),
+            },
             extra_options: HashMap::from([("a".to_string(), "A".to_string())]),
         };
         let serialized_map = HashMap::from(&options);
+        assert_eq!("4", serialized_map[COMPACTION_MAX_FILES_IN_LEVEL0_KEY]);
+        assert_eq!("2", serialized_map[COMPACTION_MAX_INFLIGHT_KEY]);
         let serialized = TableOptions::try_from(&serialized_map).unwrap();
         assert_eq!(options, serialized);
     }
+
+    #[test]
+    fn test_parse_compaction_options() {
+        let options = HashMap::from([
+            (
+                COMPACTION_MAX_FILES_IN_LEVEL0_KEY.to_string(),
+                "0".to_string(),
+            ),
+            (COMPACTION_MAX_INFLIGHT_KEY.to_string(), "0".to_string()),
+        ]);
+        let err = TableOptions::try_from(&options).unwrap_err();
+        assert!(
+            err.to_string().contains(COMPACTION_MAX_INFLIGHT_KEY),
+            "{err}"
+        );
+
+        let options = HashMap::from([(
+            COMPACTION_MAX_FILES_IN_LEVEL0_KEY.to_string(),
+            "four".to_string(),
+        )]);
+        assert!(TableOptions::try_from(&options).is_err());
+    }
 }
@@ -22,11 +22,12 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use common_query::logical_plan::Expr;
 use common_query::physical_plan::PhysicalPlanRef;
+use common_time::Timestamp;
 use datatypes::schema::SchemaRef;
 use store_api::storage::RegionNumber;
 
 use crate::error::{Result, UnsupportedSnafu};
-use crate::metadata::{FilterPushDownType, TableId, TableInfoRef, TableType};
+use crate::metadata::{FilterPushDownType, ScanPushdown, TableId, TableInfoRef, TableType};
 use crate::requests::{AlterTableRequest, DeleteRequest, InsertRequest};
 
 pub type AlterContext = anymap::Map<dyn Any + Send + Sync>;
@@ -77,6 +78,29 @@ pub trait Table: Send + Sync {
         Ok(vec![FilterPushDownType::Unsupported; filters.len()])
     }
 
+    /// Tests whether the table can evaluate the operators in `pushdown` in its scan.
+    fn supports_pushdown(&self, _pushdown: &ScanPushdown) -> bool {
+        false
+    }
+
+    /// Scans the table with the operators in `pushdown` evaluated, only called if
+    /// [`Table::supports_pushdown`] returns true. The rows are in the schema returned by
+    /// [`ScanPushdown::output_schema`], which the `projection` applies to. Unlike
+    /// [`Table::scan`], the `filters` must be evaluated exactly, before the pushed down
+    /// operators.
+    async fn scan_with_pushdown(
+        &self,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        pushdown: &ScanPushdown,
+    ) -> Result<PhysicalPlanRef> {
+        let _ = (projection, filters, pushdown);
+        UnsupportedSnafu {
+            operation: "SCAN WITH PUSHDOWN",
+        }
+        .fail()?
+    }
+
     /// Alter table.
     async fn alter(&self, _context: AlterContext, _request: &AlterTableRequest) -> Result<()> {
         UnsupportedSnafu {
@@ -95,6 +119,41 @@ pub trait Table: Send + Sync {
         .fail()?
     }
 
+    /// Begins a bulk load `load_id` of the table. Rows inserted into a bulk load bypass
+    /// the WAL and are invisible until the bulk load is committed.
+    async fn begin_bulk_load(&self, _load_id: &str) -> Result<()> {
+        UnsupportedSnafu {
+            operation: "BULK LOAD",
+        }
+        .fail()?
+    }
+
+    /// Inserts values into the bulk load `load_id`.
+    ///
+    /// Returns number of inserted rows.
+    async fn insert_bulk_load(&self, _load_id: &str, _request: InsertRequest) -> Result<usize> {
+        UnsupportedSnafu {
+            operation: "BULK LOAD",
+        }
+        .fail()?
+    }
+
+    /// Commits the bulk load `load_id`, its rows become visible atomically in each region.
+    async fn commit_bulk_load(&self, _load_id: &str) -> Result<()> {
+        UnsupportedSnafu {
+            operation: "BULK LOAD",
+        }
+        .fail()?
+    }
+
+    /// Aborts the bulk load `load_id` and discards its rows.
+    async fn abort_bulk_load(&self, _load_id: &str) -> Result<()> {
+        UnsupportedSnafu {
+            operation: "BULK LOAD",
+        }
+        .fail()?
+    }
+
     /// Flush table.
     ///
     /// Options:
@@ -117,6 +176,15 @@ pub trait Table: Send + Sync {
         }
         .fail()?
     }
+
+    /// Get the data stats of this table, rolled up from the stats of its regions.
+    fn table_stat(&self) -> Result<TableStat> {
+        let mut stat = TableStat::default();
+        for region in self.region_stats()? {
+            stat.add_region(region.max_timestamp, region.approximate_rows);
+        }
+        Ok(stat)
+    }
 }
 
 pub type TableRef = Arc<dyn Table>;
@@ -131,5 +199,30 @@ pub type TableIdProviderRef = Arc<dyn TableIdProvider + Send + Sync>;
 #[derive(Default, Debug)]
 pub struct RegionStat {
     pub region_id: u64,
+    pub memtable_usage_bytes: u64,
     pub disk_usage_bytes: u64,
+    /// Number of rows written to the region since it's opened.
+    pub written_rows: u64,
+    /// Whether writes to the region are stalled by pending flushes.
+    pub write_stalled: bool,
+    /// Max timestamp of rows put into the region, see [store_api::storage::Region::max_timestamp].
+    pub max_timestamp: Option<Timestamp>,
+    /// Approximate number of rows in the region.
+    pub approximate_rows: u64,
+}
+
+/// Data stats of a table, the values are approximate as those of its regions.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct TableStat {
+    /// Max timestamp of rows put into the table, `None` if the table has no data.
+    pub max_timestamp: Option<Timestamp>,
+    pub approximate_rows: u64,
+}
+
+impl TableStat {
+    /// Adds the stats of a region of the table.
+    pub fn add_region(&mut self, max_timestamp: Option<Timestamp>, approximate_rows: u64) {
+        self.max_timestamp = self.max_timestamp.max(max_timestamp);
+        self.approximate_rows += approximate_rows;
+    }
 }
@@ -30,22 +30,59 @@ use datatypes::schema::{SchemaRef as TableSchemaRef, SchemaRef};
 use snafu::prelude::*;
 
 use crate::error::{self, Result};
-use crate::metadata::TableInfoRef;
+use crate::metadata::{ScanPushdown, TableInfoRef};
 use crate::table::{FilterPushDownType, Table, TableRef, TableType};
 
 /// Greptime Table ->  datafusion TableProvider
 pub struct DfTableProviderAdapter {
     table: TableRef,
+    pushdown: Option<Pushdown>,
+}
+
+/// The operators pushed down to the scan of the table.
+struct Pushdown {
+    pushdown: ScanPushdown,
+    schema: DfSchemaRef,
+    filters: Vec<Expr>,
 }
 
 impl DfTableProviderAdapter {
     pub fn new(table: TableRef) -> Self {
-        Self { table }
+        Self {
+            table,
+            pushdown: None,
+        }
+    }
+
+    /// Creates an adapter that scans the table with the operators in `pushdown`
+    /// evaluated, the `filters` are evaluated before them. Returns `None` if the
+    /// table has no time index.
+    pub fn with_pushdown(
+        table: TableRef,
+        pushdown: ScanPushdown,
+        filters: Vec<Expr>,
+    ) -> Option<Self> {
+        let schema = pushdown
+            .output_schema(&table.schema())?
+            .arrow_schema()
+            .clone();
+        Some(Self {
+            table,
+            pushdown: Some(Pushdown {
+                pushdown,
+                schema,
+                filters,
+            }),
+        })
     }
 
     pub fn table(&self) -> TableRef {
         self.table.clone()
     }
+
+    pub fn pushdown(&self) -> Option<&ScanPushdown> {
+        self.pushdown.as_ref().map(|p| &p.pushdown)
+    }
 }
 
 #[async_trait::async_trait]
@@ -55,7 +92,10 @@ impl TableProvider for DfTableProviderAdapter {
     }
 
     fn schema(&self) -> DfSchemaRef {
-        self.table.schema().arrow_schema().clone()
+        match &self.pushdown {
+            Some(pushdown) => pushdown.schema.clone(),
+            None => self.table.schema().arrow_schema().clone(),
+        }
     }
 
     fn table_type(&self) -> DfTableType {
@@ -73,8 +113,16 @@ impl TableProvider for DfTableProviderAdapter {
         filters: &[DfExpr],
         limit: Option<usize>,
     ) -> DfResult<Arc<dyn DfPhysicalPlan>> {
-        let filters: Vec<Expr> = filters.iter().map(Clone::clone).map(Into::into).collect();
-        let inner = self.table.scan(projection, &filters, limit).await?;
+        let mut filters: Vec<Expr> = filters.iter().map(Clone::clone).map(Into::into).collect();
+        let inner = match &self.pushdown {
+            Some(pushdown) => {
+                filters.extend(pushdown.filters.iter().cloned());
+                self.table
+                    .scan_with_pushdown(projection, &filters, &pushdown.pushdown)
+                    .await?
+            }
+            None => self.table.scan(projection, &filters, limit).await?,
+        };
         Ok(Arc::new(DfPhysicalPlanAdapter(inner)))
     }
 
@@ -19,14 +19,18 @@ use std::sync::{Arc, Mutex};
 use common_query::error as query_error;
 use common_query::error::Result as QueryResult;
 use common_query::physical_plan::{Partitioning, PhysicalPlan, PhysicalPlanRef};
+use common_recordbatch::scan_stats::ScanStatsRecorderRef;
 use common_recordbatch::SendableRecordBatchStream;
 use datafusion::execution::context::TaskContext;
+use datafusion::physical_plan::metrics::MetricsSet;
 use datatypes::schema::SchemaRef;
 use snafu::OptionExt;
 
 pub struct SimpleTableScan {
     stream: Mutex<Option<SendableRecordBatchStream>>,
     schema: SchemaRef,
+    /// Name of the table scanned and the stats of the scan.
+    scan_stats: Option<(String, ScanStatsRecorderRef)>,
 }
 
 impl Debug for SimpleTableScan {
@@ -44,8 +48,15 @@ impl SimpleTableScan {
         Self {
             stream: Mutex::new(Some(stream)),
             schema,
+            scan_stats: None,
         }
     }
+
+    /// Exposes the stats recorded by `recorder` as the metrics of the scan of `table`.
+    pub fn with_scan_stats(mut self, table: String, recorder: ScanStatsRecorderRef) -> Self {
+        self.scan_stats = Some((table, recorder));
+        self
+    }
 }
 
 impl PhysicalPlan for SimpleTableScan {
@@ -77,10 +88,18 @@ impl PhysicalPlan for SimpleTableScan {
         let mut stream = self.stream.lock().unwrap();
         stream.take().context(query_error::ExecuteRepeatedlySnafu)
     }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        self.scan_stats
+            .as_ref()
+            .map(|(table, recorder)| recorder.stats().to_metrics(table))
+    }
 }
 
 #[cfg(test)]
 mod test {
+    use common_query::physical_plan::DfPhysicalPlanAdapter;
+    use common_recordbatch::scan_stats::{collect_scan_stats, ScanStats, ScanStatsRecorder};
     use common_recordbatch::{util, RecordBatch, RecordBatches};
     use datafusion::prelude::SessionContext;
     use datatypes::data_type::ConcreteDataType;
@@ -131,4 +150,33 @@ mod test {
             _ => unreachable!(),
         }
     }
+
+    #[test]
+    fn test_simple_table_scan_stats() {
+        let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
+            "a",
+            ConcreteDataType::int32_datatype(),
+            false,
+        )]));
+        let stream = RecordBatches::try_new(schema, vec![]).unwrap().as_stream();
+        let scan = SimpleTableScan::new(stream);
+        assert!(scan.metrics().is_none());
+
+        let recorder = Arc::new(ScanStatsRecorder::default());
+        let scan = SimpleTableScan::new(scan.execute(0, SessionContext::new().task_ctx()).unwrap())
+            .with_scan_stats("greptime.public.foo".to_string(), recorder.clone());
+        recorder.add_files_scanned(2);
+        recorder.add_rows_decoded(10);
+
+        let plan = DfPhysicalPlanAdapter(Arc::new(scan));
+        let stats = collect_scan_stats(&plan);
+        assert_eq!(
+            ScanStats {
+                files_scanned: 2,
+                rows_decoded: 10,
+                ..Default::default()
+            },
+            stats["greptime.public.foo"]
+        );
+    }
 }
@@ -103,6 +103,12 @@ impl MemTable {
         Self { info, recordbatch }
     }
 
+    /// Creates a table of `info`, e.g. to set its primary key and value columns. The schema
+    /// of `info` should be the schema of `recordbatch`.
+    pub fn from_table_info(info: TableInfoRef, recordbatch: RecordBatch) -> Self {
+        Self { info, recordbatch }
+    }
+
     pub fn table_name(&self) -> &str {
         &self.info.name
     }
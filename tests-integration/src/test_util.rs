@@ -24,7 +24,7 @@ use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, MIN_USER
 use common_runtime::Builder as RuntimeBuilder;
 use common_test_util::temp_dir::{create_temp_dir, TempDir};
 use datanode::datanode::{
-    DatanodeOptions, FileConfig, ObjectStoreConfig, OssConfig, S3Config, WalConfig,
+    DatanodeOptions, FileConfig, ObjectStoreConfig, OssConfig, S3Config, StorageConfig, WalConfig,
 };
 use datanode::error::{CreateTableSnafu, Result};
 use datanode::instance::{Instance, InstanceRef};
@@ -98,10 +98,13 @@ fn get_test_store_config(
                 root: uuid::Uuid::new_v4().to_string(),
                 access_key_id: env::var("GT_OSS_ACCESS_KEY_ID").unwrap(),
                 access_key_secret: env::var("GT_OSS_ACCESS_KEY").unwrap(),
+                anonymous: false,
                 bucket: env::var("GT_OSS_BUCKET").unwrap(),
                 endpoint: env::var("GT_OSS_ENDPOINT").unwrap(),
                 cache_path: None,
                 cache_capacity: None,
+                multipart_threshold: None,
+                multipart_chunk_size: None,
             };
 
             let accessor = Oss::default()
@@ -127,11 +130,14 @@ fn get_test_store_config(
                 root: uuid::Uuid::new_v4().to_string(),
                 access_key_id: env::var("GT_S3_ACCESS_KEY_ID").unwrap(),
                 secret_access_key: env::var("GT_S3_ACCESS_KEY").unwrap(),
+                anonymous: false,
                 bucket: env::var("GT_S3_BUCKET").unwrap(),
                 endpoint: None,
                 region: None,
                 cache_path: None,
                 cache_capacity: None,
+                multipart_threshold: None,
+                multipart_chunk_size: None,
             };
 
             let accessor = S3::default()
@@ -191,14 +197,17 @@ pub fn create_tmp_dir_and_datanode_opts(
 ) -> (DatanodeOptions, TestGuard) {
     let wal_tmp_dir = create_temp_dir(&format!("gt_wal_{name}"));
 
-    let (storage, data_tmp_dir) = get_test_store_config(&store_type, name);
+    let (store, data_tmp_dir) = get_test_store_config(&store_type, name);
 
     let opts = DatanodeOptions {
         wal: WalConfig {
             dir: wal_tmp_dir.path().to_str().unwrap().to_string(),
             ..Default::default()
         },
-        storage,
+        storage: StorageConfig {
+            store,
+            ..Default::default()
+        },
         mode: Mode::Standalone,
         ..Default::default()
     };
@@ -300,10 +309,11 @@ pub async fn setup_test_http_app_with_frontend(
     let frontend_ref = Arc::new(frontend);
     let mut http_server = HttpServer::new(
         ServerSqlQueryHandlerAdaptor::arc(frontend_ref.clone()),
-        ServerGrpcQueryHandlerAdaptor::arc(frontend_ref),
+        ServerGrpcQueryHandlerAdaptor::arc(frontend_ref.clone()),
         HttpOptions::default(),
     );
     http_server.set_script_handler(instance.clone());
+    http_server.set_influxdb_handler(frontend_ref);
     let app = http_server.make_app();
     (app, guard)
 }
@@ -350,11 +360,15 @@ pub async fn setup_grpc_server(
 
     let fe_instance = frontend::instance::Instance::new_standalone(instance.clone());
     let fe_instance_ref = Arc::new(fe_instance);
-    let fe_grpc_server = Arc::new(GrpcServer::new(
-        ServerGrpcQueryHandlerAdaptor::arc(fe_instance_ref),
+    let mut fe_grpc_server = GrpcServer::new(
+        ServerGrpcQueryHandlerAdaptor::arc(fe_instance_ref.clone()),
         None,
         runtime,
-    ));
+    );
+    fe_grpc_server.set_record_batch_insert_handler(fe_instance_ref.clone());
+    fe_grpc_server.set_bulk_load_handler(fe_instance_ref.clone());
+    fe_grpc_server.set_sql_query_handler(ServerSqlQueryHandlerAdaptor::arc(fe_instance_ref));
+    let fe_grpc_server = Arc::new(fe_grpc_server);
     let grpc_server_clone = fe_grpc_server.clone();
 
     let fe_grpc_addr_clone = fe_grpc_addr.clone();
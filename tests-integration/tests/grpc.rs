@@ -11,15 +11,21 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
+use std::sync::Arc;
+
 use api::v1::alter_expr::Kind;
 use api::v1::column::SemanticType;
 use api::v1::{
     column, AddColumn, AddColumns, AlterExpr, Column, ColumnDataType, ColumnDef, CreateTableExpr,
     InsertRequest, TableId,
 };
-use client::{Client, Database, DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
+use client::{Client, Database, OnError, DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
 use common_catalog::consts::MIN_USER_TABLE_ID;
+use common_error::prelude::{ErrorExt, StatusCode};
 use common_query::Output;
+use common_recordbatch::RecordBatch;
+use datatypes::schema::{ColumnSchema, Schema};
+use datatypes::vectors::{Float64Vector, StringVector, TimestampMillisecondVector, VectorRef};
 use servers::server::Server;
 use tests_integration::test_util::{setup_grpc_server, StorageType};
 
@@ -55,6 +61,9 @@ macro_rules! grpc_tests {
 
                 test_auto_create_table,
                 test_insert_and_select,
+                test_write_record_batch,
+                test_bulk_load,
+                test_sql_statements,
             );
         )*
     };
@@ -167,6 +176,209 @@ pub async fn test_insert_and_select(store_type: StorageType) {
     guard.remove_all().await;
 }
 
+fn new_record_batch(columns: Vec<(&str, VectorRef)>) -> RecordBatch {
+    let column_schemas = columns
+        .iter()
+        .map(|(name, vector)| ColumnSchema::new(*name, vector.data_type(), true))
+        .collect();
+    let schema = Arc::new(Schema::new(column_schemas));
+    RecordBatch::new(schema, columns.into_iter().map(|(_, vector)| vector)).unwrap()
+}
+
+pub async fn test_write_record_batch(store_type: StorageType) {
+    common_telemetry::init_default_ut_logging();
+    let (addr, mut guard, fe_grpc_server) =
+        setup_grpc_server(store_type, "write_record_batch").await;
+
+    let grpc_client = Client::with_urls(vec![addr]);
+    let db = Database::new(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, grpc_client);
+
+    let result = db.create(testing_create_expr()).await.unwrap();
+    assert!(matches!(result, Output::AffectedRows(0)));
+
+    let batches = vec![
+        new_record_batch(vec![
+            (
+                "host",
+                Arc::new(StringVector::from(vec!["host1", "host2"])) as _,
+            ),
+            ("cpu", Arc::new(Float64Vector::from_slice([0.1, 0.2])) as _),
+            (
+                "ts",
+                Arc::new(TimestampMillisecondVector::from_slice([100, 101])) as _,
+            ),
+        ]),
+        // Schema mismatch, cpu is not a string column.
+        new_record_batch(vec![
+            ("host", Arc::new(StringVector::from(vec!["host3"])) as _),
+            ("cpu", Arc::new(StringVector::from(vec!["0.3"])) as _),
+            (
+                "ts",
+                Arc::new(TimestampMillisecondVector::from_slice([102])) as _,
+            ),
+        ]),
+        new_record_batch(vec![
+            ("host", Arc::new(StringVector::from(vec!["host4"])) as _),
+            ("memory", Arc::new(Float64Vector::from_slice([1024.0])) as _),
+            (
+                "ts",
+                Arc::new(TimestampMillisecondVector::from_slice([103])) as _,
+            ),
+        ]),
+    ];
+    let results = db.write_record_batch("demo", 0, batches).await.unwrap();
+    assert_eq!(3, results.len());
+    assert_eq!(2, *results[0].as_ref().unwrap());
+    assert!(results[1].is_err());
+    assert_eq!(1, *results[2].as_ref().unwrap());
+
+    let result = db
+        .sql("SELECT host, cpu, memory, ts FROM demo")
+        .await
+        .unwrap();
+    let Output::RecordBatches(recordbatches) = result else { unreachable!() };
+    let expected = "\
++-------+-----+--------+-------------------------+
+| host  | cpu | memory | ts                      |
++-------+-----+--------+-------------------------+
+| host1 | 0.1 |        | 1970-01-01T00:00:00.100 |
+| host2 | 0.2 |        | 1970-01-01T00:00:00.101 |
+| host4 |     | 1024.0 | 1970-01-01T00:00:00.103 |
++-------+-----+--------+-------------------------+";
+    assert_eq!(expected, recordbatches.pretty_print().unwrap());
+
+    let _ = fe_grpc_server.shutdown().await;
+    guard.remove_all().await;
+}
+
+fn new_cpu_batch(hosts: Vec<&str>, cpus: &[f64], ts: &[i64]) -> RecordBatch {
+    new_record_batch(vec![
+        ("host", Arc::new(StringVector::from(hosts)) as _),
+        ("cpu", Arc::new(Float64Vector::from_slice(cpus)) as _),
+        (
+            "ts",
+            Arc::new(TimestampMillisecondVector::from_slice(ts)) as _,
+        ),
+    ])
+}
+
+async fn query_cpu(db: &Database) -> String {
+    let result = db
+        .sql("SELECT host, cpu FROM demo ORDER BY ts")
+        .await
+        .unwrap();
+    let Output::RecordBatches(recordbatches) = result else { unreachable!() };
+    recordbatches.pretty_print().unwrap()
+}
+
+pub async fn test_bulk_load(store_type: StorageType) {
+    common_telemetry::init_default_ut_logging();
+    let (addr, mut guard, fe_grpc_server) = setup_grpc_server(store_type, "bulk_load").await;
+
+    let grpc_client = Client::with_urls(vec![addr]);
+    let db = Database::new(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, grpc_client);
+
+    let result = db.create(testing_create_expr()).await.unwrap();
+    assert!(matches!(result, Output::AffectedRows(0)));
+
+    let batch = new_cpu_batch(vec!["host1"], &[0.1], &[100]);
+    let results = db
+        .write_record_batch("demo", 0, vec![batch])
+        .await
+        .unwrap();
+    assert_eq!(1, *results[0].as_ref().unwrap());
+
+    let bulk_load = db.begin_bulk_load("demo").await.unwrap();
+    let batches = vec![
+        new_cpu_batch(vec!["host1", "host2"], &[1.1, 1.2], &[100, 101]),
+        new_cpu_batch(vec!["host5"], &[1.5], &[104]),
+    ];
+    let results = bulk_load.write_batch(0, batches).await.unwrap();
+    assert_eq!(2, results.len());
+    assert_eq!(2, *results[0].as_ref().unwrap());
+    assert_eq!(1, *results[1].as_ref().unwrap());
+
+    // Writes during the bulk load are visible at once, while the bulk load is not.
+    let batch = new_cpu_batch(vec!["host2", "host3"], &[0.2, 0.3], &[101, 102]);
+    let results = db
+        .write_record_batch("demo", 0, vec![batch])
+        .await
+        .unwrap();
+    assert_eq!(2, *results[0].as_ref().unwrap());
+    let expected = "\
++-------+-----+
+| host  | cpu |
++-------+-----+
+| host1 | 0.1 |
+| host2 | 0.2 |
+| host3 | 0.3 |
++-------+-----+";
+    assert_eq!(expected, query_cpu(&db).await);
+
+    // Rows of the bulk load override rows written before it begins, but not rows written
+    // after it begins.
+    bulk_load.commit().await.unwrap();
+    let expected = "\
++-------+-----+
+| host  | cpu |
++-------+-----+
+| host1 | 1.1 |
+| host2 | 0.2 |
+| host3 | 0.3 |
+| host5 | 1.5 |
++-------+-----+";
+    assert_eq!(expected, query_cpu(&db).await);
+
+    let bulk_load = db.begin_bulk_load("demo").await.unwrap();
+    let batch = new_cpu_batch(vec!["host4"], &[1.4], &[103]);
+    let results = bulk_load.write_batch(0, vec![batch]).await.unwrap();
+    assert_eq!(1, *results[0].as_ref().unwrap());
+    bulk_load.abort().await.unwrap();
+    assert_eq!(expected, query_cpu(&db).await);
+
+    assert!(db.begin_bulk_load("missing").await.is_err());
+
+    let _ = fe_grpc_server.shutdown().await;
+    guard.remove_all().await;
+}
+
+pub async fn test_sql_statements(store_type: StorageType) {
+    common_telemetry::init_default_ut_logging();
+    let (addr, mut guard, fe_grpc_server) = setup_grpc_server(store_type, "sql_statements").await;
+
+    let grpc_client = Client::with_urls(vec![addr]);
+    let db = Database::new(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, grpc_client);
+
+    let sql = "CREATE TABLE IF NOT EXISTS demo(host STRING, ts TIMESTAMP TIME INDEX);
+        INSERT INTO demo VALUES ('host1', 1000);
+        INSERT INTO missing VALUES ('host2', 2000);
+        SELECT host FROM demo;
+        DROP TABLE IF EXISTS missing;";
+    let results = db.sql_statements(sql, OnError::Stop).await.unwrap();
+    assert_eq!(3, results.len());
+    assert!(matches!(results[0], Ok(Output::AffectedRows(0))));
+    assert!(matches!(results[1], Ok(Output::AffectedRows(1))));
+    let err = results[2].as_ref().unwrap_err();
+    assert_eq!(StatusCode::TableNotFound, err.status_code());
+
+    // The statements are idempotent except the failed one, so it's fine to run them again.
+    let results = db.sql_statements(sql, OnError::Continue).await.unwrap();
+    assert_eq!(5, results.len());
+    assert!(results[2].is_err());
+    let Ok(Output::RecordBatches(recordbatches)) = &results[3] else { unreachable!() };
+    let expected = "\
++-------+
+| host  |
++-------+
+| host1 |
++-------+";
+    assert_eq!(expected, recordbatches.pretty_print().unwrap());
+    assert!(matches!(results[4], Ok(Output::AffectedRows(0))));
+
+    let _ = fe_grpc_server.shutdown().await;
+    guard.remove_all().await;
+}
+
 async fn insert_and_assert(db: &Database) {
     // testing data:
     let (expected_host_col, expected_cpu_col, expected_mem_col, expected_ts_col) = expect_data();
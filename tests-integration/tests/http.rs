@@ -58,6 +58,7 @@ macro_rules! http_tests {
                 test_metrics_api,
                 test_scripts_api,
                 test_health_api,
+                test_influxdb_write_api,
             );
         )*
     };
@@ -396,3 +397,96 @@ pub async fn test_health_api(store_type: StorageType) {
     let body = serde_json::from_str::<HealthResponse>(&body_text).unwrap();
     assert_eq!(body, HealthResponse {});
 }
+
+pub async fn test_influxdb_write_api(store_type: StorageType) {
+    common_telemetry::init_default_ut_logging();
+    let (app, mut guard) = setup_test_http_app_with_frontend(store_type, "influxdb_api").await;
+    let client = TestClient::new(app);
+
+    // tables are created on demand, tags, fields and timestamps are mixed in lines
+    let lines = "\
+cpu,host=host1,region=us cpu_usage=66.6,idle=true 1664370459457
+cpu,host=host2 cpu_usage=20.5 1664370459458
+mem,host=host1 used=1024i,status=\"ok\" 1664370459457
+";
+    let res = client
+        .post("/v1/influxdb/write?db=public&precision=ms")
+        .body(lines)
+        .send()
+        .await;
+    assert_eq!(res.status(), StatusCode::NO_CONTENT);
+
+    let res = client
+        .get("/v1/sql?sql=select host, region, cpu_usage, idle, ts from cpu order by ts")
+        .send()
+        .await;
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = serde_json::from_str::<JsonResponse>(&res.text().await).unwrap();
+    assert!(body.success());
+    let output = body.output().unwrap();
+    assert_eq!(
+        output[0],
+        serde_json::from_value::<JsonOutput>(json!({
+            "records":{"schema":{"column_schemas":[{"name":"host","data_type":"String"},{"name":"region","data_type":"String"},{"name":"cpu_usage","data_type":"Float64"},{"name":"idle","data_type":"Boolean"},{"name":"ts","data_type":"TimestampMillisecond"}]},"rows":[["host1","us",66.6,true,1664370459457_i64],["host2",null,20.5,null,1664370459458_i64]]}
+        })).unwrap()
+    );
+
+    let res = client
+        .get("/v1/sql?sql=select host, used, status, ts from mem")
+        .send()
+        .await;
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = serde_json::from_str::<JsonResponse>(&res.text().await).unwrap();
+    let output = body.output().unwrap();
+    assert_eq!(
+        output[0],
+        serde_json::from_value::<JsonOutput>(json!({
+            "records":{"schema":{"column_schemas":[{"name":"host","data_type":"String"},{"name":"used","data_type":"Int64"},{"name":"status","data_type":"String"},{"name":"ts","data_type":"TimestampMillisecond"}]},"rows":[["host1",1024,"ok",1664370459457_i64]]}
+        })).unwrap()
+    );
+
+    // malformed lines are reported with line numbers and nothing is written
+    let lines = "\
+disk,host=host1 free=1.0 1664370459457
+disk,   host=host2 free=2.0 1664370459458
+disk,host=host3 1664370459459
+";
+    let res = client
+        .post("/v1/influxdb/write?db=public&precision=ms")
+        .body(lines)
+        .send()
+        .await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    let body = res.text().await;
+    assert!(body.contains("line 2"), "{body}");
+    assert!(body.contains("line 3"), "{body}");
+    assert!(!body.contains("line 1"), "{body}");
+
+    let res = client.get("/v1/sql?sql=select * from disk").send().await;
+    let body = serde_json::from_str::<JsonResponse>(&res.text().await).unwrap();
+    assert!(!body.success());
+
+    // valid lines are written if partial write is allowed
+    let res = client
+        .post("/v1/influxdb/write?db=public&precision=ms&partial_write=true")
+        .body(lines)
+        .send()
+        .await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    assert!(res.text().await.contains("1 lines written"));
+
+    let res = client
+        .get("/v1/sql?sql=select host, free, ts from disk")
+        .send()
+        .await;
+    let body = serde_json::from_str::<JsonResponse>(&res.text().await).unwrap();
+    let output = body.output().unwrap();
+    assert_eq!(
+        output[0],
+        serde_json::from_value::<JsonOutput>(json!({
+            "records":{"schema":{"column_schemas":[{"name":"host","data_type":"String"},{"name":"free","data_type":"Float64"},{"name":"ts","data_type":"TimestampMillisecond"}]},"rows":[["host1",1.0,1664370459457_i64]]}
+        })).unwrap()
+    );
+
+    guard.remove_all().await;
+}
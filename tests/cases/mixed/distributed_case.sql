@@ -0,0 +1,2 @@
+-- SQLNESS ARG mode=distributed
+SELECT 1;
@@ -0,0 +1,2 @@
+-- SQLNESS ARG mode=standalone
+SELECT 1;
@@ -0,0 +1,11 @@
+-- SQLNESS ARG on_error=stop
+CREATE TABLE IF NOT EXISTS batch_t (host STRING, ts TIMESTAMP TIME INDEX); INSERT INTO batch_t VALUES ('host1', 1000); INSERT INTO missing VALUES ('host2', 2000); SELECT host FROM batch_t;
+
+-- SQLNESS ARG on_error=continue
+CREATE TABLE IF NOT EXISTS batch_t (host STRING, ts TIMESTAMP TIME INDEX); INSERT INTO batch_t VALUES ('host1', 1000); INSERT INTO missing VALUES ('host2', 2000); SELECT host FROM batch_t;
+
+DROP TABLE IF EXISTS missing;
+
+DROP TABLE batch_t;
+
+DROP TABLE IF EXISTS batch_t;
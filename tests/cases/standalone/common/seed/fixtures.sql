@@ -0,0 +1 @@
+SELECT * FROM fixtures.monitor ORDER BY host;
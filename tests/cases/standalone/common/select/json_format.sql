@@ -0,0 +1,14 @@
+CREATE TABLE json_format (host STRING, ts TIMESTAMP TIME INDEX, cpu DOUBLE);
+
+INSERT INTO json_format VALUES ('host1', 1000, 0.5), ('host2', 2000, NULL);
+
+-- SQLNESS ARG format=json
+SELECT * FROM json_format ORDER BY ts;
+
+-- SQLNESS ARG format=json
+SELECT * FROM json_format WHERE host = 'host3';
+
+-- SQLNESS ARG format=json
+SELECT * FROM missing;
+
+DROP TABLE json_format;
@@ -0,0 +1,11 @@
+-- Fixtures shared by the cases, in their own schema to keep `public` as the cases expect.
+CREATE SCHEMA IF NOT EXISTS fixtures;
+
+CREATE TABLE fixtures.monitor (
+    host STRING,
+    ts TIMESTAMP TIME INDEX,
+    cpu DOUBLE,
+    PRIMARY KEY(host)
+);
+
+INSERT INTO fixtures.monitor VALUES ('host1', 1672201025000, 66.6), ('host2', 1672201026000, 77.7);
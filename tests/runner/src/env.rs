@@ -16,11 +16,13 @@ use std::fmt::Display;
 use std::fs::OpenOptions;
 use std::path::{Path, PathBuf};
 use std::process::Stdio;
-use std::time::Duration;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
 use client::{
-    Client, Database as DB, Error as ClientError, DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME,
+    Client, Database as DB, Error as ClientError, OnError, DEFAULT_CATALOG_NAME,
+    DEFAULT_SCHEMA_NAME,
 };
 use common_error::ext::ErrorExt;
 use common_error::snafu::ErrorCompat;
@@ -29,42 +31,157 @@ use serde::Serialize;
 use sqlness::{Database, EnvController, QueryContext};
 use tinytemplate::TinyTemplate;
 use tokio::process::{Child, Command};
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, MutexGuard, OnceCell};
 
+use crate::http::HttpClient;
+use crate::json;
+use crate::seed::{self, EnvConfig};
+use crate::template::TemplateVars;
 use crate::util;
 
 const DATANODE_ADDR: &str = "127.0.0.1:4100";
 const METASRV_ADDR: &str = "127.0.0.1:3002";
 const SERVER_ADDR: &str = "127.0.0.1:4001";
+const SERVER_HTTP_ADDR: &str = "127.0.0.1:4000";
+const FRONTEND_GRPC_PORT: u16 = 4001;
+const FRONTEND_HTTP_PORT: u16 = 4000;
+/// Default ports of the frontend servers, by the names of the flags to override them.
+const FRONTEND_PORTS: [(&str, u16); 6] = [
+    ("http", FRONTEND_HTTP_PORT),
+    ("grpc", FRONTEND_GRPC_PORT),
+    ("mysql", 4002),
+    ("postgres", 4003),
+    ("prom", 4004),
+    ("opentsdb", 4242),
+];
+/// Ports of each extra frontend are shifted by this from the previous one.
+const FRONTEND_PORT_STEP: u16 = 1000;
 const SERVER_LOG_FILE: &str = "/tmp/greptime-sqlness.log";
 const METASRV_LOG_FILE: &str = "/tmp/greptime-sqlness-metasrv.log";
 const FRONTEND_LOG_FILE: &str = "/tmp/greptime-sqlness-frontend.log";
 const DATANODE_LOG_FILE: &str = "/tmp/greptime-sqlness-datanode.log";
+/// Lines of the log file to print when a server isn't ready.
+const LOG_TAIL_LINES: usize = 50;
 
-pub struct Env {}
+/// Envs to use a prebuilt binary instead of building one with cargo, looked up in order.
+/// `GREPTIME_BIN_PATH` is kept for compatibility.
+const BIN_PATH_ENVS: [&str; 2] = ["GREPTIME_BIN", "GREPTIME_BIN_PATH"];
+/// Dir of cases that declare their own modes.
+const MIXED_MODE: &str = "mixed";
+/// Name of the `-- SQLNESS ARG` to declare the mode of a case.
+const MODE_ARG: &str = "mode";
+/// Name of the `-- SQLNESS ARG` to send all statements of the next query in one request,
+/// whose value is `stop` or `continue`, what to do once a statement fails.
+const ON_ERROR_ARG: &str = "on_error";
+/// Name of the `-- SQLNESS ARG` to display the result of the next query in a [ResultFormat].
+const FORMAT_ARG: &str = "format";
+
+/// Topology of the servers that cases run against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Standalone,
+    Distributed,
+}
+
+impl Mode {
+    fn from_name(name: &str) -> Option<Mode> {
+        match name {
+            "standalone" => Some(Mode::Standalone),
+            "distributed" => Some(Mode::Distributed),
+            _ => None,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Mode::Standalone => "standalone",
+            Mode::Distributed => "distributed",
+        }
+    }
+}
+
+/// Protocol to send the queries of cases by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Protocol {
+    Grpc,
+    /// The HTTP SQL API. Values in query results are displayed in their JSON forms, e.g.
+    /// timestamps are numbers.
+    Http,
+}
+
+impl Protocol {
+    pub fn from_name(name: &str) -> Option<Protocol> {
+        match name {
+            "grpc" => Some(Protocol::Grpc),
+            "http" => Some(Protocol::Http),
+            _ => None,
+        }
+    }
+}
+
+/// Format to display query results in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResultFormat {
+    /// Tables pretty-printed by arrow.
+    Table,
+    /// Canonical JSON, see [json::render].
+    Json,
+}
+
+impl ResultFormat {
+    fn from_name(name: &str) -> Option<ResultFormat> {
+        match name {
+            "table" => Some(ResultFormat::Table),
+            "json" => Some(ResultFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Env {
+    /// Path of the `greptime` binary, built on the first start.
+    bin_path: Arc<OnceCell<PathBuf>>,
+    /// Number of frontends to start in distributed mode.
+    frontends: usize,
+    /// Variables to substitute in the queries of cases.
+    template_vars: TemplateVars,
+    /// Whether to append the latency of each query to its result.
+    benchmark: bool,
+    /// Protocol to send queries by.
+    protocol: Protocol,
+}
 
 #[allow(clippy::print_stdout)]
 #[async_trait]
 impl EnvController for Env {
     type DB = GreptimeDB;
 
-    async fn start(&self, mode: &str, _config: Option<&Path>) -> Self::DB {
-        match mode {
-            "standalone" => Self::start_standalone().await,
-            "distributed" => Self::start_distributed().await,
-            _ => panic!("Unexpected mode: {mode}"),
+    /// Starts the servers of `mode`. Servers of the `mixed` dir are started by the first
+    /// query of each case instead, in the mode the case declares.
+    async fn start(&self, mode: &str, config: Option<&Path>) -> Self::DB {
+        let env_mode = if mode == MIXED_MODE {
+            None
+        } else {
+            Some(Mode::from_name(mode).unwrap_or_else(|| panic!("Unexpected mode: {mode}")))
+        };
+        let database = GreptimeDB {
+            env: self.clone(),
+            env_mode,
+            seed_file: EnvConfig::load(config).seed_file(),
+            servers: Mutex::new(None),
+        };
+        if let Some(mode) = env_mode {
+            // Starts the servers eagerly, so the run is aborted before any case if they fail.
+            drop(database.servers(mode).await);
         }
+        database
     }
 
     /// Stop one [`Database`].
-    async fn stop(&self, _mode: &str, mut database: Self::DB) {
-        let mut server = database.server_process;
-        Env::stop_server(&mut server).await;
-        if let Some(mut metasrv) = database.metasrv_process.take() {
-            Env::stop_server(&mut metasrv).await;
-        }
-        if let Some(mut datanode) = database.datanode_process.take() {
-            Env::stop_server(&mut datanode).await;
+    async fn stop(&self, _mode: &str, database: Self::DB) {
+        if let Some(servers) = database.servers.into_inner() {
+            servers.stop().await;
         }
         println!("Stopped DB.");
     }
@@ -72,21 +189,90 @@ impl EnvController for Env {
 
 #[allow(clippy::print_stdout)]
 impl Env {
-    pub async fn start_standalone() -> GreptimeDB {
-        // Build the DB with `cargo build --bin greptime`
-        println!("Going to build the DB...");
-        let cargo_build_result = Command::new("cargo")
+    /// Creates an [Env] that starts `frontends` frontends in distributed mode, the client
+    /// of the [GreptimeDB] sends queries to all of them.
+    pub fn new(frontends: usize) -> Self {
+        Self {
+            bin_path: Arc::new(OnceCell::new()),
+            frontends: frontends.max(1),
+            template_vars: TemplateVars::new(common_time::util::current_time_millis().to_string()),
+            benchmark: false,
+            protocol: Protocol::Grpc,
+        }
+    }
+
+    /// Sets whether to append the latency of each query to its result, so the results
+    /// differ from the expected ones in `.result` files.
+    pub fn with_benchmark(mut self, benchmark: bool) -> Self {
+        self.benchmark = benchmark;
+        self
+    }
+
+    /// Sets the protocol to send queries by. Multi-statement queries are always sent by
+    /// gRPC, as the HTTP API doesn't report the results of statements before a failed one.
+    pub fn with_protocol(mut self, protocol: Protocol) -> Self {
+        self.protocol = protocol;
+        self
+    }
+
+    /// Returns whether the HTTP server at `addr` is ready, if queries are sent over HTTP.
+    async fn wait_http_ready(&self, addr: &str, timeout: Duration) -> bool {
+        self.protocol != Protocol::Http || util::check_port(addr.parse().unwrap(), timeout).await
+    }
+
+    /// Returns the path of the `greptime` binary. The binary in env `GREPTIME_BIN` (or
+    /// `GREPTIME_BIN_PATH`) is used if set, otherwise it's built by
+    /// `cargo build --bin greptime`, at most once per [Env].
+    ///
+    /// # Panics
+    /// Panics if the binary in env is not an executable file.
+    async fn bin_path(&self) -> &Path {
+        self.bin_path
+            .get_or_init(|| async {
+                let bin_path = match prebuilt_bin_path() {
+                    Ok(Some(path)) => path,
+                    Ok(None) => {
+                        println!("Going to build the DB...");
+                        Self::build_db().await
+                    }
+                    Err(e) => panic!("{e}"),
+                };
+                println!("Using GreptimeDB binary {}", bin_path.display());
+                bin_path
+            })
+            .await
+    }
+
+    async fn build_db() -> PathBuf {
+        let output = Command::new("cargo")
             .current_dir(util::get_workspace_root())
             .args(["build", "--bin", "greptime"])
             .stdout(Stdio::null())
             .output()
             .await
-            .expect("Failed to start GreptimeDB")
-            .status;
-        if !cargo_build_result.success() {
-            panic!("Failed to build GreptimeDB (`cargo build` fails)");
+            .expect("Failed to run `cargo build`");
+        if !output.status.success() {
+            panic!(
+                "Failed to build GreptimeDB (`cargo build` fails with {}), stderr:\n{}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+        println!("Build finished.");
+
+        PathBuf::from(util::get_binary_dir("debug")).join("greptime")
+    }
+
+    /// Starts the servers of `mode`.
+    async fn start_servers(&self, mode: Mode) -> Servers {
+        match mode {
+            Mode::Standalone => self.start_standalone().await,
+            Mode::Distributed => self.start_distributed().await,
         }
-        println!("Build finished, starting...");
+    }
+
+    pub async fn start_standalone(&self) -> Servers {
+        let bin_path = self.bin_path().await;
 
         // Open log file (build logs will be truncated).
         let log_file = OpenOptions::new()
@@ -98,27 +284,35 @@ impl Env {
 
         let conf = Self::generate_standalone_config_file();
         // Start the DB
-        let server_process = Command::new("./greptime")
-            .current_dir(util::get_binary_dir("debug"))
+        let mut server_process = Command::new(bin_path)
             .args(["--log-level=debug", "standalone", "start", "-c", &conf])
             .stdout(log_file)
             .spawn()
             .expect("Failed to start the DB");
 
-        let is_up = util::check_port(SERVER_ADDR.parse().unwrap(), Duration::from_secs(10)).await;
-        if !is_up {
-            panic!("Server doesn't up in 10 seconds, quit.")
+        let timeout = util::readiness_timeout();
+        let is_ready = util::wait_ready(|| util::probe_sql(SERVER_ADDR), timeout).await
+            && self.wait_http_ready(SERVER_HTTP_ADDR, timeout).await;
+        if !is_ready {
+            Env::stop_server(&mut server_process).await;
+            panic!(
+                "Server isn't ready in {timeout:?}, quit. Tail of {SERVER_LOG_FILE}:\n{}",
+                util::log_tail(SERVER_LOG_FILE, LOG_TAIL_LINES)
+            )
         }
         println!("Started, going to test. Log will be write to {SERVER_LOG_FILE}");
 
         let client = Client::with_urls(vec![SERVER_ADDR]);
         let db = DB::new(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, client);
 
-        GreptimeDB {
-            server_process,
+        Servers {
+            mode: Mode::Standalone,
+            server_processes: vec![server_process],
             metasrv_process: None,
             datanode_process: None,
-            client: Mutex::new(db),
+            protocol: self.protocol,
+            client: db,
+            http_client: HttpClient::new(vec![SERVER_HTTP_ADDR.to_string()]),
         }
     }
 
@@ -151,44 +345,68 @@ impl Env {
         conf_file
     }
 
-    pub async fn start_distributed() -> GreptimeDB {
-        let cargo_build_result = Command::new("cargo")
-            .current_dir(util::get_workspace_root())
-            .args(["build", "--bin", "greptime"])
-            .stdout(Stdio::null())
-            .output()
-            .await
-            .expect("Failed to start GreptimeDB")
-            .status;
-        if !cargo_build_result.success() {
-            panic!("Failed to build GreptimeDB (`cargo build` fails)");
-        }
+    pub async fn start_distributed(&self) -> Servers {
+        let bin_path = self.bin_path().await;
 
         // start a distributed GreptimeDB
-        let mut meta_server = Env::start_server("metasrv");
+        let mut meta_server = Env::start_server(bin_path, "metasrv");
         // wait for election
         tokio::time::sleep(std::time::Duration::from_secs(1)).await;
-        let mut frontend = Env::start_server("frontend");
-        let mut datanode = Env::start_server("datanode");
-
-        for addr in [DATANODE_ADDR, METASRV_ADDR, SERVER_ADDR].iter() {
-            let is_up = util::check_port(addr.parse().unwrap(), Duration::from_secs(10)).await;
-            if !is_up {
-                Env::stop_server(&mut meta_server).await;
-                Env::stop_server(&mut frontend).await;
-                Env::stop_server(&mut datanode).await;
-                panic!("Server {addr} doesn't up in 10 seconds, quit.")
+        let mut frontends = (0..self.frontends)
+            .map(|index| Env::start_frontend(bin_path, index))
+            .collect::<Vec<_>>();
+        let mut datanode = Env::start_server(bin_path, "datanode");
+
+        let timeout = util::readiness_timeout();
+        let metasrv_addr = METASRV_ADDR.parse().unwrap();
+        let frontend_addrs = Env::frontend_addrs(self.frontends, FRONTEND_GRPC_PORT);
+        let frontend_http_addrs = Env::frontend_addrs(self.frontends, FRONTEND_HTTP_PORT);
+        let mut not_ready =
+            if !util::wait_ready(|| util::probe_http(metasrv_addr, "/admin/health"), timeout).await
+            {
+                Some((METASRV_ADDR.to_string(), METASRV_LOG_FILE.to_string()))
+            } else if !util::check_port(DATANODE_ADDR.parse().unwrap(), timeout).await {
+                Some((DATANODE_ADDR.to_string(), DATANODE_LOG_FILE.to_string()))
+            } else {
+                None
+            };
+        if not_ready.is_none() {
+            for (index, (addr, http_addr)) in
+                frontend_addrs.iter().zip(&frontend_http_addrs).enumerate()
+            {
+                if !util::wait_ready(|| util::probe_sql(addr), timeout).await {
+                    not_ready = Some((addr.clone(), Env::frontend_log_file(index)));
+                    break;
+                }
+                if !self.wait_http_ready(http_addr, timeout).await {
+                    not_ready = Some((http_addr.clone(), Env::frontend_log_file(index)));
+                    break;
+                }
             }
         }
+        if let Some((addr, log_file)) = not_ready {
+            Env::stop_server(&mut meta_server).await;
+            for frontend in &mut frontends {
+                Env::stop_server(frontend).await;
+            }
+            Env::stop_server(&mut datanode).await;
+            panic!(
+                "Server {addr} isn't ready in {timeout:?}, quit. Tail of {log_file}:\n{}",
+                util::log_tail(&log_file, LOG_TAIL_LINES)
+            )
+        }
 
-        let client = Client::with_urls(vec![SERVER_ADDR]);
+        let client = Client::with_urls(frontend_addrs);
         let db = DB::new(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, client);
 
-        GreptimeDB {
-            server_process: frontend,
+        Servers {
+            mode: Mode::Distributed,
+            server_processes: frontends,
             metasrv_process: Some(meta_server),
             datanode_process: Some(datanode),
-            client: Mutex::new(db),
+            protocol: self.protocol,
+            client: db,
+            http_client: HttpClient::new(frontend_http_addrs),
         }
     }
 
@@ -197,37 +415,75 @@ impl Env {
         let _ = process.wait().await;
     }
 
-    fn start_server(subcommand: &str) -> Child {
+    fn start_server(bin_path: &Path, subcommand: &str) -> Child {
         let log_file_name = match subcommand {
             "datanode" => DATANODE_LOG_FILE,
-            "frontend" => FRONTEND_LOG_FILE,
             "metasrv" => METASRV_LOG_FILE,
             _ => panic!("Unexpected subcommand: {subcommand}"),
         };
-        let log_file = OpenOptions::new()
-            .create(true)
-            .write(true)
-            .truncate(true)
-            .open(log_file_name)
-            .unwrap_or_else(|_| panic!("Cannot open log file at {log_file_name}"));
 
         let mut args = vec![subcommand.to_string(), "start".to_string()];
-        if subcommand == "frontend" {
-            args.push("--metasrv-addr=0.0.0.0:3002".to_string())
-        } else if subcommand == "datanode" {
+        if subcommand == "datanode" {
             args.push("-c".to_string());
             args.push(Self::generate_datanode_config_file());
         } else if subcommand == "metasrv" {
             args.push("--use-memory-store".to_string());
+            // Keeps the region placements, and so the EXPLAIN outputs, stable across runs.
+            args.push("--deterministic-placement".to_string());
         };
 
-        let process = Command::new("./greptime")
-            .current_dir(util::get_binary_dir("debug"))
+        Self::spawn_server(bin_path, args, log_file_name)
+    }
+
+    /// Starts the `index`-th frontend, the first one listens on the default ports.
+    fn start_frontend(bin_path: &Path, index: usize) -> Child {
+        let mut args = vec![
+            "frontend".to_string(),
+            "start".to_string(),
+            "--metasrv-addr=0.0.0.0:3002".to_string(),
+        ];
+        if index > 0 {
+            for (server, port) in FRONTEND_PORTS {
+                args.push(format!("--{server}-addr={}", Self::frontend_addr(index, port)));
+            }
+        }
+
+        Self::spawn_server(bin_path, args, &Self::frontend_log_file(index))
+    }
+
+    fn spawn_server(bin_path: &Path, args: Vec<String>, log_file_name: &str) -> Child {
+        let log_file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(log_file_name)
+            .unwrap_or_else(|_| panic!("Cannot open log file at {log_file_name}"));
+
+        Command::new(bin_path)
             .args(args)
             .stdout(log_file)
             .spawn()
-            .expect("Failed to start the DB");
-        process
+            .expect("Failed to start the DB")
+    }
+
+    /// Address of the server on default `port` of the `index`-th frontend.
+    fn frontend_addr(index: usize, port: u16) -> String {
+        format!("127.0.0.1:{}", port + FRONTEND_PORT_STEP * index as u16)
+    }
+
+    /// Addresses of the server on default `port` of the first `frontends` frontends.
+    fn frontend_addrs(frontends: usize, port: u16) -> Vec<String> {
+        (0..frontends)
+            .map(|index| Self::frontend_addr(index, port))
+            .collect()
+    }
+
+    fn frontend_log_file(index: usize) -> String {
+        if index == 0 {
+            FRONTEND_LOG_FILE.to_string()
+        } else {
+            format!("/tmp/greptime-sqlness-frontend-{index}.log")
+        }
     }
 
     fn generate_datanode_config_file() -> String {
@@ -260,62 +516,514 @@ impl Env {
     }
 }
 
+/// Returns the path of the prebuilt binary set by env, fails if it's not an executable file
+/// instead of building one silently.
+fn prebuilt_bin_path() -> Result<Option<PathBuf>, String> {
+    let Some((env, path)) = BIN_PATH_ENVS
+        .iter()
+        .find_map(|env| std::env::var(env).ok().map(|path| (env, path)))
+    else {
+        return Ok(None);
+    };
+    util::check_executable(Path::new(&path)).map_err(|e| format!("Invalid {env} {path}: {e}"))?;
+    Ok(Some(PathBuf::from(path)))
+}
+
 pub struct GreptimeDB {
-    server_process: Child,
-    metasrv_process: Option<Child>,
-    datanode_process: Option<Child>,
-    client: Mutex<DB>,
+    /// To start the servers of other modes.
+    env: Env,
+    /// Mode of the dir of the cases, `None` for the `mixed` dir whose cases declare their
+    /// own modes.
+    env_mode: Option<Mode>,
+    /// Loaded each time servers are started.
+    seed_file: Option<PathBuf>,
+    /// Running servers, `None` before the first case of the `mixed` dir.
+    servers: Mutex<Option<Servers>>,
 }
 
 #[async_trait]
 impl Database for GreptimeDB {
-    async fn query(&self, _ctx: QueryContext, query: String) -> Box<dyn Display> {
-        let mut client = self.client.lock().await;
+    async fn query(&self, ctx: QueryContext, query: String) -> Box<dyn Display> {
+        let running = self.servers.lock().await.as_ref().map(|servers| servers.mode);
+        let mode = match query_mode(self.env_mode, running, ctx.context.get(MODE_ARG)) {
+            Ok(mode) => mode,
+            Err(e) => return Box::new(e) as _,
+        };
+        let query = match self.env.template_vars.render(&query) {
+            Ok(query) => query,
+            Err(e) => return Box::new(format!("Failed to render the query, {e}")) as _,
+        };
+
+        let on_error = match ctx.context.get(ON_ERROR_ARG).map(|v| v.as_str()) {
+            None => None,
+            Some("stop") => Some(OnError::Stop),
+            Some("continue") => Some(OnError::Continue),
+            Some(other) => {
+                let e = format!("Unknown {ON_ERROR_ARG} {other}, expect stop or continue");
+                return Box::new(e) as _;
+            }
+        };
+        let format = match ctx.context.get(FORMAT_ARG) {
+            None => ResultFormat::Table,
+            Some(name) => match ResultFormat::from_name(name) {
+                Some(format) => format,
+                None => {
+                    let e = format!("Unknown {FORMAT_ARG} {name}, expect table or json");
+                    return Box::new(e) as _;
+                }
+            },
+        };
+
+        let mut servers = self.servers(mode).await;
+        let servers = servers.as_mut().unwrap();
+        let start = Instant::now();
+        if let Some(on_error) = on_error {
+            let results = servers.client.sql_statements(&query, on_error).await;
+            let latency = self.env.benchmark.then(|| start.elapsed());
+            return Box::new(StatementsDisplayer {
+                results,
+                format,
+                latency,
+            }) as _;
+        }
+        let result = servers.execute(&query).await;
+        let latency = self.env.benchmark.then(|| start.elapsed());
+        Box::new(ResultDisplayer {
+            result,
+            format,
+            latency,
+        }) as _
+    }
+}
+
+#[allow(clippy::print_stdout)]
+impl GreptimeDB {
+    /// Returns the running servers, which are of `mode`. Running servers of another mode
+    /// are stopped first, so at most one set of servers runs at a time.
+    async fn servers(&self, mode: Mode) -> MutexGuard<'_, Option<Servers>> {
+        let mut servers = self.servers.lock().await;
+        if servers.as_ref().map(|servers| servers.mode) == Some(mode) {
+            return servers;
+        }
+        if let Some(running) = servers.take() {
+            println!("Switching from {} to {} mode.", running.mode.name(), mode.name());
+            running.stop().await;
+        }
+
+        let mut started = self.env.start_servers(mode).await;
+        if let Some(seed_file) = &self.seed_file {
+            if let Err(e) = started.load_seed(seed_file, &self.env.template_vars).await {
+                started.stop().await;
+                panic!("{e}");
+            }
+            println!("Loaded seed file {}", seed_file.display());
+        }
+        *servers = Some(started);
+        servers
+    }
+}
+
+/// Returns the mode to run a query in, `declared` is the mode declared before the query
+/// by `-- SQLNESS ARG mode=<mode>`.
+///
+/// Cases in the `mixed` dir declare their modes before their first queries, the following
+/// queries run in the `running` mode until another mode is declared. Cases in the dir of
+/// a mode could only declare the same mode.
+fn query_mode(
+    env_mode: Option<Mode>,
+    running: Option<Mode>,
+    declared: Option<&String>,
+) -> Result<Mode, String> {
+    let declared = declared
+        .map(|name| {
+            Mode::from_name(name)
+                .ok_or_else(|| format!("Unknown mode {name}, expect standalone or distributed"))
+        })
+        .transpose()?;
+    match (env_mode, declared) {
+        (Some(env_mode), Some(declared)) if env_mode != declared => Err(format!(
+            "Case requires {} mode but is in the dir of {} mode, move it to the {MIXED_MODE} dir",
+            declared.name(),
+            env_mode.name()
+        )),
+        (Some(env_mode), _) => Ok(env_mode),
+        (None, Some(declared)) => Ok(declared),
+        (None, None) => running.ok_or_else(|| {
+            format!("Mode is not declared, add `-- SQLNESS ARG {MODE_ARG}=<mode>` to the case")
+        }),
+    }
+}
+
+/// Processes and client of the servers of a mode.
+pub struct Servers {
+    mode: Mode,
+    /// The standalone server, or the frontends in distributed mode.
+    server_processes: Vec<Child>,
+    metasrv_process: Option<Child>,
+    datanode_process: Option<Child>,
+    /// Protocol to execute queries by.
+    protocol: Protocol,
+    client: DB,
+    http_client: HttpClient,
+}
+
+impl Servers {
+    /// Executes `query` by the client of the protocol, `USE` statements also switch the
+    /// schema of the clients.
+    async fn execute(&mut self, query: &str) -> Result<Output, ClientError> {
         if query.trim().starts_with("USE ") {
             let database = query
                 .split_ascii_whitespace()
                 .nth(1)
                 .expect("Illegal `USE` statement: expecting a database.")
                 .trim_end_matches(';');
-            client.set_schema(database);
+            self.client.set_schema(database);
+            self.http_client.set_schema(database);
         }
 
-        let result = client.sql(&query).await;
-        Box::new(ResultDisplayer { result }) as _
+        match self.protocol {
+            Protocol::Grpc => self.client.sql(query).await,
+            Protocol::Http => self.http_client.sql(query).await,
+        }
+    }
+
+    /// Runs the statements of `seed_file`, and stops at the first failed one. Cases still
+    /// start in the default schema even if the seed file switches to another one.
+    async fn load_seed(
+        &mut self,
+        seed_file: &Path,
+        template_vars: &TemplateVars,
+    ) -> Result<(), String> {
+        let content = std::fs::read_to_string(seed_file)
+            .map_err(|e| format!("Cannot read seed file {}: {e}", seed_file.display()))?;
+        for statement in seed::split_statements(&content) {
+            let statement = template_vars.render(&statement).map_err(|e| {
+                format!(
+                    "Failed to render seed statement `{statement}` in {}, {e}",
+                    seed_file.display()
+                )
+            })?;
+            if let Err(e) = self.execute(&statement).await {
+                let root_cause = e.iter_chain().last().unwrap();
+                return Err(format!(
+                    "Failed to run seed statement `{statement}` in {}, error: {root_cause}",
+                    seed_file.display()
+                ));
+            }
+        }
+        self.client.set_schema(DEFAULT_SCHEMA_NAME);
+        self.http_client.set_schema(DEFAULT_SCHEMA_NAME);
+        Ok(())
+    }
+
+    async fn stop(mut self) {
+        for server in &mut self.server_processes {
+            Env::stop_server(server).await;
+        }
+        if let Some(mut metasrv) = self.metasrv_process.take() {
+            Env::stop_server(&mut metasrv).await;
+        }
+        if let Some(mut datanode) = self.datanode_process.take() {
+            Env::stop_server(&mut datanode).await;
+        }
     }
 }
 
 struct ResultDisplayer {
     result: Result<Output, ClientError>,
+    format: ResultFormat,
+    /// Wall-clock latency of the query, only shown in benchmark mode.
+    latency: Option<Duration>,
 }
 
 impl Display for ResultDisplayer {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match &self.result {
-            Ok(result) => match result {
-                Output::AffectedRows(rows) => {
-                    write!(f, "Affected Rows: {rows}")
-                }
-                Output::RecordBatches(recordbatches) => {
-                    let pretty = recordbatches.pretty_print().map_err(|e| e.to_string());
-                    match pretty {
-                        Ok(s) => write!(f, "{s}"),
-                        Err(e) => {
-                            write!(f, "Failed to pretty format {recordbatches:?}, error: {e}")
-                        }
+        fmt_result(&self.result, self.format, f)?;
+        fmt_latency(self.latency, f)
+    }
+}
+
+/// Displays the results of the statements of a multi-statement query in order, each after
+/// the 1-based index of its statement. Statements skipped after a failed one are not shown.
+struct StatementsDisplayer {
+    results: Result<Vec<Result<Output, ClientError>>, ClientError>,
+    format: ResultFormat,
+    /// Wall-clock latency of all the statements, only shown in benchmark mode.
+    latency: Option<Duration>,
+}
+
+impl Display for StatementsDisplayer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.results {
+            Ok(results) => {
+                for (i, result) in results.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, "\n\n")?;
                     }
+                    writeln!(f, "Statement {}:", i + 1)?;
+                    fmt_result(result, self.format, f)?;
                 }
-                Output::Stream(_) => unreachable!(),
-            },
-            Err(e) => {
-                let status_code = e.status_code();
-                let root_cause = e.iter_chain().last().unwrap();
-                write!(
-                    f,
-                    "Error: {}({status_code}), {root_cause}",
-                    status_code as u32
-                )
+            }
+            Err(e) => fmt_error(e, f)?,
+        }
+        fmt_latency(self.latency, f)
+    }
+}
+
+fn fmt_latency(latency: Option<Duration>, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    if let Some(latency) = latency {
+        write!(f, "\n\nLatency: {}ms", latency.as_millis())?;
+    }
+    Ok(())
+}
+
+fn fmt_error(e: &ClientError, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let status_code = e.status_code();
+    let root_cause = e.iter_chain().last().unwrap();
+    write!(
+        f,
+        "Error: {}({status_code}), {root_cause}",
+        status_code as u32
+    )
+}
+
+fn fmt_result(
+    result: &Result<Output, ClientError>,
+    format: ResultFormat,
+    f: &mut std::fmt::Formatter<'_>,
+) -> std::fmt::Result {
+    if format == ResultFormat::Json {
+        return write!(f, "{}", json::render(result));
+    }
+    match result {
+        Ok(Output::AffectedRows(rows)) => write!(f, "Affected Rows: {rows}"),
+        Ok(Output::RecordBatches(recordbatches)) => {
+            let pretty = recordbatches.pretty_print().map_err(|e| e.to_string());
+            match pretty {
+                Ok(s) => write!(f, "{s}"),
+                Err(e) => write!(f, "Failed to pretty format {recordbatches:?}, error: {e}"),
             }
         }
+        Ok(Output::Stream(_)) => unreachable!(),
+        Err(e) => fmt_error(e, f),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::Permissions;
+    use std::os::unix::fs::PermissionsExt;
+
+    use common_error::status_code::StatusCode;
+
+    use super::*;
+
+    #[test]
+    fn test_display_latency() {
+        let displayer = ResultDisplayer {
+            result: Ok(Output::AffectedRows(1)),
+            format: ResultFormat::Table,
+            latency: None,
+        };
+        assert_eq!("Affected Rows: 1", displayer.to_string());
+
+        let displayer = ResultDisplayer {
+            result: Ok(Output::AffectedRows(1)),
+            format: ResultFormat::Table,
+            latency: Some(Duration::from_micros(12_345)),
+        };
+        assert_eq!("Affected Rows: 1\n\nLatency: 12ms", displayer.to_string());
+    }
+
+    #[test]
+    fn test_display_statements() {
+        let displayer = StatementsDisplayer {
+            results: Ok(vec![
+                Ok(Output::AffectedRows(0)),
+                Err(ClientError::Server {
+                    code: StatusCode::TableNotFound,
+                    msg: "Table not found: greptime.public.missing".to_string(),
+                }),
+            ]),
+            format: ResultFormat::Table,
+            latency: None,
+        };
+        let expected = "\
+Statement 1:
+Affected Rows: 0
+
+Statement 2:
+Error: 4001(TableNotFound), Table not found: greptime.public.missing";
+        assert_eq!(expected, displayer.to_string());
+    }
+
+    #[ignore = "starts a distributed GreptimeDB, run it by `cargo test -p sqlness-runner -- --ignored`"]
+    #[tokio::test]
+    async fn test_query_after_frontend_stopped() {
+        let env = Env::new(2);
+        let mut servers = env.start_distributed().await;
+        // The client of the servers picks a random frontend per query without retrying, so
+        // each frontend is targeted by a client of its own to keep the test deterministic.
+        let peers = Env::frontend_addrs(2, FRONTEND_GRPC_PORT)
+            .into_iter()
+            .map(|addr| {
+                let client = Client::with_urls(vec![addr]);
+                DB::new(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, client)
+            })
+            .collect::<Vec<_>>();
+
+        let _ = peers[0]
+            .sql("CREATE TABLE test_failover(host STRING, ts TIMESTAMP TIME INDEX)")
+            .await
+            .unwrap();
+        let _ = peers[1]
+            .sql("INSERT INTO test_failover VALUES ('host1', 1000)")
+            .await
+            .unwrap();
+
+        let mut frontend = servers.server_processes.remove(0);
+        Env::stop_server(&mut frontend).await;
+        assert!(peers[0].sql("SELECT 1").await.is_err());
+
+        // The surviving frontend still serves the table created through the stopped one.
+        let displayer = ResultDisplayer {
+            result: peers[1].sql("SELECT host FROM test_failover").await,
+            format: ResultFormat::Table,
+            latency: None,
+        };
+        let expected = "\
++-------+
+| host  |
++-------+
+| host1 |
++-------+";
+        assert_eq!(expected, displayer.to_string());
+
+        servers.stop().await;
+    }
+
+    #[ignore = "starts a standalone GreptimeDB, run it by `cargo test -p sqlness-runner -- --ignored`"]
+    #[tokio::test]
+    async fn test_query_over_http() {
+        let env = Env::new(1).with_protocol(Protocol::Http);
+        let mut servers = env.start_standalone().await;
+
+        for query in ["SELECT 1, 'a', NULL", "INSERT INTO missing VALUES (1)"] {
+            let grpc = ResultDisplayer {
+                result: servers.client.sql(query).await,
+                format: ResultFormat::Table,
+                latency: None,
+            };
+            let http = ResultDisplayer {
+                result: servers.execute(query).await,
+                format: ResultFormat::Table,
+                latency: None,
+            };
+            assert_eq!(grpc.to_string(), http.to_string());
+        }
+
+        servers.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_prebuilt_bin_path() {
+        let path = std::env::temp_dir().join(format!(
+            "greptime-sqlness-bin-{}",
+            common_time::util::current_time_millis()
+        ));
+        std::env::set_var(BIN_PATH_ENVS[0], &path);
+        let err = prebuilt_bin_path().unwrap_err();
+        assert!(err.starts_with("Invalid GREPTIME_BIN"), "{err}");
+
+        std::fs::write(&path, "").unwrap();
+        let err = prebuilt_bin_path().unwrap_err();
+        assert!(err.ends_with("not executable"), "{err}");
+
+        std::fs::set_permissions(&path, Permissions::from_mode(0o755)).unwrap();
+        // The binary is used as is, without running `cargo build`.
+        let env = Env::new(1);
+        assert_eq!(path.as_path(), env.bin_path().await);
+
+        std::env::remove_var(BIN_PATH_ENVS[0]);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    async fn running_mode(database: &GreptimeDB) -> Option<Mode> {
+        database.servers.lock().await.as_ref().map(|servers| servers.mode)
+    }
+
+    fn mode_ctx(mode: Option<&str>) -> QueryContext {
+        let mut ctx = QueryContext::default();
+        if let Some(mode) = mode {
+            ctx.context.insert(MODE_ARG.to_string(), mode.to_string());
+        }
+        ctx
+    }
+
+    #[test]
+    fn test_query_mode() {
+        let standalone = "standalone".to_string();
+        let distributed = "distributed".to_string();
+
+        // Cases in the dir of a mode.
+        let env_mode = Some(Mode::Standalone);
+        let running = Some(Mode::Standalone);
+        assert_eq!(Ok(Mode::Standalone), query_mode(env_mode, running, None));
+        assert_eq!(
+            Ok(Mode::Standalone),
+            query_mode(env_mode, running, Some(&standalone))
+        );
+        assert!(query_mode(env_mode, running, Some(&distributed)).is_err());
+
+        // Cases in the mixed dir.
+        assert!(query_mode(None, None, None).is_err());
+        assert_eq!(
+            Ok(Mode::Distributed),
+            query_mode(None, None, Some(&distributed))
+        );
+        assert_eq!(
+            Ok(Mode::Standalone),
+            query_mode(None, Some(Mode::Distributed), Some(&standalone))
+        );
+        // Following queries of a case run in the declared mode.
+        assert_eq!(
+            Ok(Mode::Distributed),
+            query_mode(None, Some(Mode::Distributed), None)
+        );
+        assert!(query_mode(None, None, Some(&"cluster".to_string())).is_err());
+    }
+
+    #[ignore = "starts GreptimeDB of both modes, run it by `cargo test -p sqlness-runner -- --ignored`"]
+    #[tokio::test]
+    async fn test_cases_of_different_modes() {
+        let env = Env::new(1);
+        let database = env.start(MIXED_MODE, None).await;
+        let metasrv_addr = METASRV_ADDR.parse().unwrap();
+        // No servers are started until a case declares its mode.
+        assert_eq!(None, running_mode(&database).await);
+        let result = database.query(mode_ctx(None), "SELECT 1".to_string()).await;
+        assert!(result.to_string().contains("Mode is not declared"), "{result}");
+
+        // The first case runs against a distributed GreptimeDB.
+        let result = database
+            .query(mode_ctx(Some("distributed")), "SELECT 1".to_string())
+            .await;
+        assert!(!result.to_string().starts_with("Error"), "{result}");
+        assert_eq!(Some(Mode::Distributed), running_mode(&database).await);
+        assert!(util::check_port(metasrv_addr, Duration::from_secs(1)).await);
+        // Following queries of the case reuse the running servers.
+        let result = database.query(mode_ctx(None), "SELECT 1".to_string()).await;
+        assert!(!result.to_string().starts_with("Error"), "{result}");
+        assert_eq!(Some(Mode::Distributed), running_mode(&database).await);
+
+        // The second case runs against a standalone GreptimeDB, the distributed one is stopped.
+        let result = database
+            .query(mode_ctx(Some("standalone")), "SELECT 1".to_string())
+            .await;
+        assert!(!result.to_string().starts_with("Error"), "{result}");
+        assert_eq!(Some(Mode::Standalone), running_mode(&database).await);
+        assert!(!util::check_port(metasrv_addr, Duration::from_secs(1)).await);
+
+        env.stop(MIXED_MODE, database).await;
     }
 }
@@ -0,0 +1,249 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Client of the HTTP SQL API, to run cases over HTTP instead of gRPC.
+
+use std::io;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use client::{Error as ClientError, DEFAULT_SCHEMA_NAME};
+use common_error::status_code::StatusCode;
+use common_query::Output;
+use common_recordbatch::RecordBatches;
+use datatypes::prelude::{ConcreteDataType, VectorRef};
+use datatypes::schema::{ColumnSchema, Schema};
+use datatypes::vectors::StringVector;
+use serde::Deserialize;
+use serde_json::Value;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+const SQL_PATH: &str = "/v1/sql";
+/// Prefix of the errors of statements reported by the HTTP SQL API.
+const QUERY_ERROR_PREFIX: &str = "Query engine output error: ";
+/// Separator of an error and its source in the display of errors.
+const SOURCE_SEPARATOR: &str = ", source: ";
+
+/// Response of the HTTP SQL API, see `servers::http::JsonResponse`.
+#[derive(Debug, Deserialize)]
+struct JsonResponse {
+    code: u32,
+    error: Option<String>,
+    output: Option<Vec<JsonOutput>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum JsonOutput {
+    AffectedRows(usize),
+    Records(JsonRecords),
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonRecords {
+    schema: Option<JsonSchema>,
+    rows: Vec<Vec<Value>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonSchema {
+    column_schemas: Vec<JsonColumnSchema>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonColumnSchema {
+    name: String,
+}
+
+/// Sends queries to the HTTP SQL API of the servers in turn.
+pub struct HttpClient {
+    addrs: Vec<String>,
+    next: AtomicUsize,
+    schema: String,
+}
+
+impl HttpClient {
+    pub fn new(addrs: Vec<String>) -> Self {
+        Self {
+            addrs,
+            next: AtomicUsize::new(0),
+            schema: DEFAULT_SCHEMA_NAME.to_string(),
+        }
+    }
+
+    pub fn set_schema(&mut self, schema: impl Into<String>) {
+        self.schema = schema.into();
+    }
+
+    /// Executes `sql`, which should have a single statement, and converts the response to
+    /// an [Output] like the gRPC client returns, so both are displayed the same way.
+    pub async fn sql(&self, sql: &str) -> Result<Output, ClientError> {
+        let addr = &self.addrs[self.next.fetch_add(1, Ordering::Relaxed) % self.addrs.len()];
+        let form = format!("sql={}&db={}", url_encode(sql), url_encode(&self.schema));
+        let body = post_form(addr, SQL_PATH, &form).await.map_err(|e| {
+            ClientError::IllegalDatabaseResponse {
+                err_msg: format!("Failed to request {addr}{SQL_PATH}, {e}"),
+            }
+        })?;
+        let response = serde_json::from_str::<JsonResponse>(&body).map_err(|e| {
+            ClientError::IllegalDatabaseResponse {
+                err_msg: format!("Failed to parse response {body}, {e}"),
+            }
+        })?;
+        to_output(response)
+    }
+}
+
+fn to_output(response: JsonResponse) -> Result<Output, ClientError> {
+    if let Some(error) = response.error {
+        // The API reports the whole chain of the error, only the root cause is kept, as the
+        // servers do for gRPC.
+        let error = error.strip_prefix(QUERY_ERROR_PREFIX).unwrap_or(&error);
+        let root_cause = error.rsplit(SOURCE_SEPARATOR).next().unwrap_or(error);
+        return Err(ClientError::Server {
+            code: StatusCode::from_u32(response.code).unwrap_or(StatusCode::Unknown),
+            msg: root_cause.to_string(),
+        });
+    }
+
+    let mut outputs = response.output.unwrap_or_default();
+    if outputs.len() != 1 {
+        return Err(ClientError::IllegalDatabaseResponse {
+            err_msg: format!("expect 1 output, actual {}", outputs.len()),
+        });
+    }
+    match outputs.remove(0) {
+        JsonOutput::AffectedRows(rows) => Ok(Output::AffectedRows(rows)),
+        JsonOutput::Records(records) => records_to_batches(records).map(Output::RecordBatches),
+    }
+}
+
+/// Converts `records` to batches of string columns, values are kept in their JSON forms
+/// except that strings are unquoted, e.g. timestamps are numbers instead of dates.
+fn records_to_batches(records: JsonRecords) -> Result<RecordBatches, ClientError> {
+    let Some(json_schema) = records.schema else {
+        return Ok(RecordBatches::empty());
+    };
+    let column_schemas = json_schema
+        .column_schemas
+        .into_iter()
+        .map(|column| ColumnSchema::new(column.name, ConcreteDataType::string_datatype(), true))
+        .collect();
+    let schema = Arc::new(Schema::new(column_schemas));
+    let columns = (0..schema.num_columns()).map(|index| {
+        let values = records
+            .rows
+            .iter()
+            .map(|row| row.get(index).and_then(value_to_string))
+            .collect::<Vec<_>>();
+        Arc::new(StringVector::from(values)) as VectorRef
+    });
+    RecordBatches::try_from_columns(schema, columns).map_err(|e| {
+        ClientError::IllegalDatabaseResponse {
+            err_msg: format!("Failed to build record batches, {e}"),
+        }
+    })
+}
+
+fn value_to_string(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::String(s) => Some(s.clone()),
+        value => Some(value.to_string()),
+    }
+}
+
+/// Posts `form` to `path` at `addr`, returns the body of the response.
+async fn post_form(addr: &str, path: &str, form: &str) -> io::Result<String> {
+    let mut stream = TcpStream::connect(addr).await?;
+    let request = format!(
+        "POST {path} HTTP/1.1\r\nHost: {addr}\r\n\
+         Content-Type: application/x-www-form-urlencoded\r\n\
+         Content-Length: {}\r\nConnection: close\r\n\r\n{form}",
+        form.len()
+    );
+    stream.write_all(request.as_bytes()).await?;
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).await?;
+
+    // JSON responses have known lengths, so they are not chunked.
+    let response = String::from_utf8_lossy(&response);
+    let (_, body) = response
+        .split_once("\r\n\r\n")
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "incomplete HTTP response"))?;
+    Ok(body.to_string())
+}
+
+/// Encodes `s` as a value of `application/x-www-form-urlencoded`.
+fn url_encode(s: &str) -> String {
+    let mut encoded = String::with_capacity(s.len());
+    for byte in s.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use datatypes::vectors::Int64Vector;
+
+    use super::*;
+
+    #[test]
+    fn test_url_encode() {
+        assert_eq!("SELECT%20%2A%20FROM%20t", url_encode("SELECT * FROM t"));
+        assert_eq!("a%3D%27%E4%BD%A0%27%3B%26", url_encode("a='\u{4f60}';&"));
+        assert_eq!("public", url_encode("public"));
+    }
+
+    #[test]
+    fn test_to_output() {
+        let response: JsonResponse =
+            serde_json::from_str(r#"{"code":0,"output":[{"affectedrows":2}]}"#).unwrap();
+        assert!(matches!(to_output(response), Ok(Output::AffectedRows(2))));
+
+        // Records are displayed as the gRPC client's.
+        let response: JsonResponse = serde_json::from_str(
+            r#"{"code":0,"output":[{"records":{"schema":{"column_schemas":[{"name":"n","data_type":"Int64"}]},"rows":[[1],[null]]}}]}"#,
+        )
+        .unwrap();
+        let Ok(Output::RecordBatches(batches)) = to_output(response) else {
+            unreachable!()
+        };
+        let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
+            "n",
+            ConcreteDataType::int64_datatype(),
+            true,
+        )]));
+        let column = Arc::new(Int64Vector::from(vec![Some(1), None])) as VectorRef;
+        let expected = RecordBatches::try_from_columns(schema, vec![column]).unwrap();
+        assert_eq!(expected.pretty_print().unwrap(), batches.pretty_print().unwrap());
+
+        let response: JsonResponse = serde_json::from_str(
+            r#"{"code":4001,"error":"Query engine output error: Failed to execute, source: Table not found: t"}"#,
+        )
+        .unwrap();
+        let Err(ClientError::Server { code, msg }) = to_output(response) else {
+            unreachable!()
+        };
+        assert_eq!(StatusCode::TableNotFound, code);
+        assert_eq!("Table not found: t", msg);
+    }
+}
@@ -0,0 +1,138 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Renders query results in canonical JSON, which doesn't change with the pretty-print
+//! format of arrow.
+
+use client::Error as ClientError;
+use common_error::ext::ErrorExt;
+use common_error::snafu::ErrorCompat;
+use common_query::Output;
+use common_recordbatch::RecordBatches;
+use datatypes::prelude::DataType;
+use serde_json::{json, Value};
+
+/// Renders `result` in JSON:
+/// - affected rows as `{"affected_rows": <rows>}`.
+/// - errors as `{"error": {"code": <code>, "message": <root cause>, "status": <status>}}`.
+/// - record batches as `{"columns": [{"name": <name>, "type": <type>}], "rows": [[<value>]]}`,
+///   columns are sorted by names and values keep their types, e.g. timestamps are numbers
+///   in the unit of their columns. Each row is in a line.
+pub fn render(result: &Result<Output, ClientError>) -> String {
+    match result {
+        Ok(Output::AffectedRows(rows)) => json!({ "affected_rows": rows }).to_string(),
+        Ok(Output::RecordBatches(recordbatches)) => render_batches(recordbatches)
+            .unwrap_or_else(|e| format!("Failed to render {recordbatches:?} in JSON, error: {e}")),
+        Ok(Output::Stream(_)) => unreachable!(),
+        Err(e) => {
+            let status_code = e.status_code();
+            let root_cause = e.iter_chain().last().unwrap();
+            json!({
+                "error": {
+                    "code": status_code as u32,
+                    "message": root_cause.to_string(),
+                    "status": status_code.to_string(),
+                }
+            })
+            .to_string()
+        }
+    }
+}
+
+fn render_batches(recordbatches: &RecordBatches) -> serde_json::Result<String> {
+    let schema = recordbatches.schema();
+    let column_schemas = schema.column_schemas();
+    let mut indices = (0..column_schemas.len()).collect::<Vec<_>>();
+    indices.sort_by_key(|i| &column_schemas[*i].name);
+
+    let columns = indices
+        .iter()
+        .map(|i| {
+            let column = &column_schemas[*i];
+            json!({ "name": column.name, "type": column.data_type.name() })
+        })
+        .collect::<Vec<_>>();
+    let mut rows = Vec::new();
+    for batch in recordbatches.iter() {
+        for row in 0..batch.num_rows() {
+            let values = indices
+                .iter()
+                .map(|i| Value::try_from(batch.column(*i).get(row)))
+                .collect::<serde_json::Result<Vec<_>>>()?;
+            rows.push(serde_json::to_string(&values)?);
+        }
+    }
+
+    let rows = if rows.is_empty() {
+        "[]".to_string()
+    } else {
+        format!("[\n    {}\n  ]", rows.join(",\n    "))
+    };
+    Ok(format!(
+        "{{\n  \"columns\": {},\n  \"rows\": {rows}\n}}",
+        serde_json::to_string(&columns)?
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use common_error::status_code::StatusCode;
+    use datatypes::prelude::{ConcreteDataType, VectorRef};
+    use datatypes::schema::{ColumnSchema, Schema};
+    use datatypes::vectors::{Float64Vector, StringVector, TimestampMillisecondVector};
+
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let schema = Arc::new(Schema::new(vec![
+            ColumnSchema::new("ts", ConcreteDataType::timestamp_millisecond_datatype(), false),
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), true),
+            ColumnSchema::new("cpu", ConcreteDataType::float64_datatype(), true),
+        ]));
+        let columns: Vec<VectorRef> = vec![
+            Arc::new(TimestampMillisecondVector::from_vec(vec![1000, 2000])),
+            Arc::new(StringVector::from(vec![Some("a"), None])),
+            Arc::new(Float64Vector::from(vec![Some(0.5), Some(1.0)])),
+        ];
+        let recordbatches = RecordBatches::try_from_columns(schema, columns).unwrap();
+        let expected = r#"{
+  "columns": [{"name":"cpu","type":"Float64"},{"name":"host","type":"String"},{"name":"ts","type":"TimestampMillisecond"}],
+  "rows": [
+    [0.5,"a",1000],
+    [1.0,null,2000]
+  ]
+}"#;
+        assert_eq!(expected, render(&Ok(Output::RecordBatches(recordbatches))));
+
+        let recordbatches = RecordBatches::empty();
+        assert_eq!(
+            "{\n  \"columns\": [],\n  \"rows\": []\n}",
+            render(&Ok(Output::RecordBatches(recordbatches)))
+        );
+
+        assert_eq!(r#"{"affected_rows":2}"#, render(&Ok(Output::AffectedRows(2))));
+
+        let error = ClientError::Server {
+            code: StatusCode::TableNotFound,
+            msg: "Table not found: t".to_string(),
+        };
+        assert_eq!(
+            r#"{"error":{"code":4001,"message":"Table not found: t","status":"TableNotFound"}}"#,
+            render(&Err(error))
+        );
+    }
+}
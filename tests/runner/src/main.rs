@@ -16,6 +16,10 @@ use env::Env;
 use sqlness::{ConfigBuilder, Runner};
 
 mod env;
+mod http;
+mod json;
+mod seed;
+mod template;
 mod util;
 
 #[tokio::main]
@@ -34,6 +38,9 @@ async fn main() {
         .follow_links(true)
         .build()
         .unwrap();
-    let runner = Runner::new_with_config(config, Env {}).await.unwrap();
+    let env = Env::new(util::frontends())
+        .with_benchmark(util::benchmark())
+        .with_protocol(util::protocol());
+    let runner = Runner::new_with_config(config, env).await.unwrap();
     runner.run().await.unwrap();
 }
@@ -0,0 +1,111 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Seed data shared by the cases of a mode, loaded once before the cases run.
+
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+/// Config of a mode, read from `config.toml` in the dir of the mode's cases.
+#[derive(Debug, Default, Deserialize)]
+pub struct EnvConfig {
+    /// SQL file to run after the servers are up, relative to the config file.
+    seed_file: Option<PathBuf>,
+    /// Dir of the config file.
+    #[serde(skip)]
+    dir: PathBuf,
+}
+
+impl EnvConfig {
+    /// Reads the config at `path`, or the default config if the mode has no config file.
+    pub fn load(path: Option<&Path>) -> Self {
+        let Some(path) = path else {
+            return Self::default();
+        };
+        let content = std::fs::read_to_string(path)
+            .unwrap_or_else(|e| panic!("Cannot read config file {}: {e}", path.display()));
+        let mut config: EnvConfig = toml::from_str(&content)
+            .unwrap_or_else(|e| panic!("Invalid config file {}: {e}", path.display()));
+        config.dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        config
+    }
+
+    pub fn seed_file(&self) -> Option<PathBuf> {
+        self.seed_file.as_ref().map(|file| self.dir.join(file))
+    }
+}
+
+/// Splits the content of a seed file into statements. A statement ends with `;` at the end
+/// of a line, like the queries of cases. Empty lines and lines starting with `--` are skipped.
+pub fn split_statements(content: &str) -> Vec<String> {
+    let mut statements = Vec::new();
+    let mut statement = String::new();
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with("--") {
+            continue;
+        }
+        if !statement.is_empty() {
+            statement.push('\n');
+        }
+        statement.push_str(line);
+        if trimmed.ends_with(';') {
+            statements.push(std::mem::take(&mut statement));
+        }
+    }
+    // The last statement without `;`.
+    if !statement.is_empty() {
+        statements.push(statement);
+    }
+    statements
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_statements() {
+        let content = "
+-- fixtures
+CREATE SCHEMA fixtures;
+
+CREATE TABLE fixtures.monitor (
+    host STRING,
+    ts TIMESTAMP TIME INDEX
+);
+INSERT INTO fixtures.monitor VALUES ('host1', 1)";
+        assert_eq!(
+            vec![
+                "CREATE SCHEMA fixtures;",
+                "CREATE TABLE fixtures.monitor (\n    host STRING,\n    ts TIMESTAMP TIME INDEX\n);",
+                "INSERT INTO fixtures.monitor VALUES ('host1', 1)",
+            ],
+            split_statements(content)
+        );
+    }
+
+    #[test]
+    fn test_load_config() {
+        let config = EnvConfig::load(None);
+        assert!(config.seed_file().is_none());
+
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("../cases/standalone/config.toml");
+        let config = EnvConfig::load(Some(&path));
+        let seed_file = config.seed_file().unwrap();
+        assert!(seed_file.is_file(), "{}", seed_file.display());
+    }
+}
@@ -0,0 +1,97 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Variables in cases, written as `{{name}}`, substituted before queries are sent.
+
+const OPEN: &str = "{{";
+const CLOSE: &str = "}}";
+
+/// Variables available to the cases:
+/// - `run_id`: unique per run of the runner, e.g. for table names that don't collide
+///   with previous runs.
+/// - `timestamp_ms`: current time in milliseconds when the query is sent.
+#[derive(Debug, Clone)]
+pub struct TemplateVars {
+    run_id: String,
+}
+
+impl TemplateVars {
+    pub fn new(run_id: String) -> Self {
+        Self { run_id }
+    }
+
+    fn get(&self, name: &str) -> Option<String> {
+        match name {
+            "run_id" => Some(self.run_id.clone()),
+            "timestamp_ms" => Some(common_time::util::current_time_millis().to_string()),
+            _ => None,
+        }
+    }
+
+    /// Substitutes variables in `query`, fails on unknown or unclosed variables instead of
+    /// sending them verbatim.
+    pub fn render(&self, query: &str) -> Result<String, String> {
+        let mut rendered = String::with_capacity(query.len());
+        let mut rest = query;
+        while let Some(start) = rest.find(OPEN) {
+            rendered.push_str(&rest[..start]);
+            let after_open = &rest[start + OPEN.len()..];
+            let end = after_open
+                .find(CLOSE)
+                .ok_or_else(|| format!("unclosed template variable: {}", &rest[start..]))?;
+            let name = after_open[..end].trim();
+            let value = self
+                .get(name)
+                .ok_or_else(|| format!("unknown template variable: {OPEN}{name}{CLOSE}"))?;
+            rendered.push_str(&value);
+            rest = &after_open[end + CLOSE.len()..];
+        }
+        rendered.push_str(rest);
+        Ok(rendered)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let vars = TemplateVars::new("42".to_string());
+
+        let case = "CREATE TABLE t_{{run_id}} (ts TIMESTAMP TIME INDEX);\nSELECT * FROM t_{{ run_id }};";
+        assert_eq!(
+            "CREATE TABLE t_42 (ts TIMESTAMP TIME INDEX);\nSELECT * FROM t_42;",
+            vars.render(case).unwrap()
+        );
+
+        let rendered = vars.render("INSERT INTO t VALUES ({{timestamp_ms}})").unwrap();
+        let ts = rendered
+            .trim_start_matches("INSERT INTO t VALUES (")
+            .trim_end_matches(')');
+        assert!(ts.parse::<i64>().is_ok(), "{rendered}");
+
+        // No variables.
+        assert_eq!("SELECT '{}'", vars.render("SELECT '{}'").unwrap());
+
+        assert_eq!(
+            "unknown template variable: {{table}}",
+            vars.render("SELECT * FROM {{ table }}").unwrap_err()
+        );
+        assert_eq!(
+            "unclosed template variable: {{run_id",
+            vars.render("SELECT * FROM {{run_id").unwrap_err()
+        );
+    }
+}
@@ -13,16 +13,33 @@
 // limitations under the License.
 
 use std::fmt::Display;
+use std::future::Future;
 use std::net::SocketAddr;
-use std::path::PathBuf;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
-use tokio::io::AsyncWriteExt;
-use tokio::net::TcpSocket;
-use tokio::time;
-
-/// Check port every 0.1 second.
-const PORT_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+use client::{Client, Database, DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpSocket, TcpStream};
+use tokio::time::{self, Instant};
+
+use crate::env::Protocol;
+
+/// Check readiness every 0.1 second by default.
+const READINESS_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+/// Wait 10 seconds for servers to be ready by default.
+const READINESS_TIMEOUT: Duration = Duration::from_secs(10);
+/// Env to override the readiness check interval, in milliseconds.
+const READINESS_CHECK_INTERVAL_ENV: &str = "SQLNESS_READINESS_CHECK_INTERVAL_MS";
+/// Env to override the readiness timeout, in seconds.
+const READINESS_TIMEOUT_ENV: &str = "SQLNESS_READINESS_TIMEOUT_SECS";
+/// Env to set the number of frontends in distributed mode.
+const FRONTENDS_ENV: &str = "SQLNESS_FRONTENDS";
+/// Env to enable the benchmark mode.
+const BENCHMARK_ENV: &str = "SQLNESS_BENCHMARK";
+/// Env to set the protocol to send queries by.
+const PROTOCOL_ENV: &str = "SQLNESS_PROTOCOL";
 const NULL_DATA_PLACEHOLDER: &str = "NULL";
 
 /// Helper struct for iterate over column with null_mask
@@ -96,21 +113,135 @@ pub fn get_binary_dir(mode: &str) -> String {
     workspace_root.into_os_string().into_string().unwrap()
 }
 
+/// Interval between readiness checks, can be overridden by env
+/// `SQLNESS_READINESS_CHECK_INTERVAL_MS`.
+pub fn readiness_check_interval() -> Duration {
+    std::env::var(READINESS_CHECK_INTERVAL_ENV)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .map(Duration::from_millis)
+        .unwrap_or(READINESS_CHECK_INTERVAL)
+}
+
+/// How long to wait for servers to be ready, can be overridden by env
+/// `SQLNESS_READINESS_TIMEOUT_SECS`.
+pub fn readiness_timeout() -> Duration {
+    std::env::var(READINESS_TIMEOUT_ENV)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .map(Duration::from_secs)
+        .unwrap_or(READINESS_TIMEOUT)
+}
+
+/// Number of frontends to start in distributed mode, 1 unless set by env
+/// `SQLNESS_FRONTENDS`.
+pub fn frontends() -> usize {
+    std::env::var(FRONTENDS_ENV)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(1)
+}
+
+/// Whether to report the latency of each query, enabled by env `SQLNESS_BENCHMARK=true`.
+pub fn benchmark() -> bool {
+    std::env::var(BENCHMARK_ENV)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(false)
+}
+
+/// Protocol to send queries by, set by env `SQLNESS_PROTOCOL` to `grpc` (the default) or
+/// `http`.
+pub fn protocol() -> Protocol {
+    match std::env::var(PROTOCOL_ENV) {
+        Ok(name) => Protocol::from_name(&name)
+            .unwrap_or_else(|| panic!("Unknown {PROTOCOL_ENV} {name}, expect grpc or http")),
+        Err(_) => Protocol::Grpc,
+    }
+}
+
+/// Checks that `path` is a file with any of the execute permissions.
+pub fn check_executable(path: &Path) -> Result<(), String> {
+    let metadata = std::fs::metadata(path).map_err(|e| e.to_string())?;
+    if !metadata.is_file() {
+        return Err("not a file".to_string());
+    }
+    if metadata.permissions().mode() & 0o111 == 0 {
+        return Err("not executable".to_string());
+    }
+    Ok(())
+}
+
+/// Spin-waiting `probe` reports ready, or timeout.
+/// Returns whether it's ready.
+pub async fn wait_ready<F, Fut>(mut probe: F, timeout: Duration) -> bool
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = bool>,
+{
+    let interval = readiness_check_interval();
+    let deadline = Instant::now() + timeout;
+    loop {
+        // a hanging probe shouldn't exceed the deadline
+        if let Ok(true) = time::timeout_at(deadline, probe()).await {
+            return true;
+        }
+        if Instant::now() + interval >= deadline {
+            return false;
+        }
+        time::sleep(interval).await;
+    }
+}
+
 /// Spin-waiting a socket address is available, or timeout.
 /// Returns whether the addr is up.
 pub async fn check_port(ip_addr: SocketAddr, timeout: Duration) -> bool {
-    let check_task = async {
-        loop {
-            let socket = TcpSocket::new_v4().expect("Cannot create v4 socket");
-            match socket.connect(ip_addr).await {
-                Ok(mut stream) => {
-                    let _ = stream.shutdown().await;
-                    break;
-                }
-                Err(_) => time::sleep(PORT_CHECK_INTERVAL).await,
-            }
+    wait_ready(|| probe_port(ip_addr), timeout).await
+}
+
+/// Returns whether the socket address accepts connections.
+pub async fn probe_port(ip_addr: SocketAddr) -> bool {
+    let socket = TcpSocket::new_v4().expect("Cannot create v4 socket");
+    match socket.connect(ip_addr).await {
+        Ok(mut stream) => {
+            let _ = stream.shutdown().await;
+            true
         }
+        Err(_) => false,
+    }
+}
+
+/// Returns whether the server at `addr` answers `SELECT 1`. Unlike [probe_port], this
+/// ensures the catalog is loaded.
+pub async fn probe_sql(addr: &str) -> bool {
+    let client = Client::with_urls(vec![addr]);
+    let db = Database::new(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, client);
+    db.sql("SELECT 1").await.is_ok()
+}
+
+/// Returns whether a `GET` of the http `path` at `ip_addr` responds `200 OK`.
+pub async fn probe_http(ip_addr: SocketAddr, path: &str) -> bool {
+    let Ok(mut stream) = TcpStream::connect(ip_addr).await else {
+        return false;
     };
+    let request = format!("GET {path} HTTP/1.1\r\nHost: {ip_addr}\r\nConnection: close\r\n\r\n");
+    if stream.write_all(request.as_bytes()).await.is_err() {
+        return false;
+    }
+    let mut response = Vec::new();
+    if stream.read_to_end(&mut response).await.is_err() {
+        return false;
+    }
+    response.starts_with(b"HTTP/1.1 200")
+}
 
-    tokio::time::timeout(timeout, check_task).await.is_ok()
+/// Get the last `lines` lines of the log file, for diagnosing servers that are not ready.
+pub fn log_tail(log_file: &str, lines: usize) -> String {
+    match std::fs::read_to_string(log_file) {
+        Ok(content) => {
+            let all_lines = content.lines().collect::<Vec<_>>();
+            all_lines[all_lines.len().saturating_sub(lines)..].join("\n")
+        }
+        Err(e) => format!("Cannot read log file {log_file}: {e}"),
+    }
 }